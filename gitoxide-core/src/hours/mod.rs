@@ -140,7 +140,10 @@ where
             let mut skipped_merge_commits = 0;
             const CHUNK_SIZE: usize = 50;
             let mut chunk = Vec::with_capacity(CHUNK_SIZE);
-            let mut commit_iter = commit_id.ancestors(&repo.objects);
+            let grafts = repo.grafts()?;
+            let mut commit_iter = commit_id
+                .ancestors(&repo.objects)
+                .parents_provider((!grafts.is_empty()).then(|| Box::new(grafts) as _));
             let mut is_shallow = false;
             while let Some(c) = commit_iter.next() {
                 progress.inc();
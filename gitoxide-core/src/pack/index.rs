@@ -82,6 +82,8 @@ pub fn from_pack(
         iteration_mode: ctx.iteration_mode.into(),
         index_version: pack::index::Version::default(),
         object_hash: ctx.object_hash,
+        sha1_flavor: Default::default(),
+        ..Default::default()
     };
     let out = ctx.out;
     let format = ctx.format;
@@ -426,7 +426,13 @@ pub fn update(
         }
 
         let db = Db::new(&repo.objects, &traverse_progress, 50, tx_tree_ids, &known_commits);
-        let commit_iter = gix::interrupt::Iter::new(commit_id.ancestors(&db), || anyhow!("Cancelled by user"));
+        let grafts = repo.grafts()?;
+        let commit_iter = gix::interrupt::Iter::new(
+            commit_id
+                .ancestors(&db)
+                .parents_provider((!grafts.is_empty()).then(|| Box::new(grafts) as _)),
+            || anyhow!("Cancelled by user"),
+        );
         let mut commits = Vec::new();
         for c in commit_iter {
             match c?.map(|c| c.id) {
@@ -1,7 +1,10 @@
-use std::path::{Path, PathBuf};
+use std::{
+    io::Write,
+    path::{Path, PathBuf},
+};
 
 use anyhow::{anyhow, bail};
-use gix::{worktree::archive, NestedProgress, Progress};
+use gix::{worktree::archive, Count, NestedProgress, Progress};
 
 pub struct Options {
     pub format: Option<archive::Format>,
@@ -10,9 +13,10 @@ pub struct Options {
     pub add_paths: Vec<PathBuf>,
 }
 
+/// Write an archive of the commit or tree at `rev_spec` to `destination_path`, or to `out` if it is `None`.
 pub fn stream(
     repo: gix::Repository,
-    destination_path: &Path,
+    destination_path: Option<&Path>,
     rev_spec: Option<&str>,
     mut progress: impl NestedProgress,
     Options {
@@ -21,13 +25,18 @@ pub fn stream(
         add_paths,
         files,
     }: Options,
+    out: impl std::io::Write,
 ) -> anyhow::Result<()> {
-    let format = format.map_or_else(|| format_from_ext(destination_path), Ok)?;
+    let format = match (format, destination_path) {
+        (Some(format), _) => format,
+        (None, Some(path)) => format_from_ext(path)?,
+        (None, None) => archive::Format::Tar,
+    };
     let object = repo.rev_parse_single(rev_spec.unwrap_or("HEAD"))?.object()?;
-    let (modification_date, tree) = fetch_rev_info(object)?;
+    let (modification_date, commit_or_tree) = fetch_rev_info(object)?;
 
     let start = std::time::Instant::now();
-    let (mut stream, index) = repo.worktree_stream(tree)?;
+    let (mut stream, index) = repo.worktree_stream(commit_or_tree)?;
     if !add_paths.is_empty() {
         let root = gix::path::realpath(
             repo.work_dir()
@@ -51,26 +60,62 @@ pub fn stream(
     let mut bytes = progress.add_child("written");
     bytes.init(None, gix::progress::bytes());
 
-    let mut file = gix::progress::Write {
-        inner: std::io::BufWriter::with_capacity(128 * 1024, std::fs::File::create(destination_path)?),
-        progress: &mut bytes,
+    let options = archive::Options {
+        format,
+        tree_prefix: prefix.map(gix::bstr::BString::from),
+        modification_time: modification_date.unwrap_or_else(|| {
+            std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or_default() as gix::date::SecondsSinceUnixEpoch
+        }),
     };
-    repo.worktree_archive(
-        stream,
-        &mut file,
-        &mut entries,
-        &gix::interrupt::IS_INTERRUPTED,
-        gix::worktree::archive::Options {
-            format,
-            tree_prefix: prefix.map(gix::bstr::BString::from),
-            modification_time: modification_date.unwrap_or_else(|| {
-                std::time::SystemTime::now()
-                    .duration_since(std::time::UNIX_EPOCH)
-                    .map(|d| d.as_secs())
-                    .unwrap_or_default() as gix::date::SecondsSinceUnixEpoch
-            }),
-        },
-    )?;
+    match destination_path {
+        Some(destination_path) => {
+            let mut file = gix::progress::Write {
+                inner: std::io::BufWriter::with_capacity(128 * 1024, std::fs::File::create(destination_path)?),
+                progress: &mut bytes,
+            };
+            repo.worktree_archive(
+                stream,
+                &mut file,
+                &mut entries,
+                &gix::interrupt::IS_INTERRUPTED,
+                options,
+            )?;
+        }
+        None => {
+            let mut out = gix::progress::Write {
+                inner: gix::features::interrupt::Write {
+                    inner: std::io::BufWriter::with_capacity(128 * 1024, out),
+                    should_interrupt: &gix::interrupt::IS_INTERRUPTED,
+                },
+                progress: &mut bytes,
+            };
+            match format {
+                archive::Format::InternalTransientNonPersistable => {
+                    std::io::copy(&mut stream.into_read(), &mut out)?;
+                }
+                // Zip needs `Seek`, which is reported as error.
+                _ => {
+                    archive::write_stream(
+                        &mut stream,
+                        |stream| {
+                            if gix::interrupt::is_triggered() {
+                                return Err(std::io::Error::new(std::io::ErrorKind::Other, "Cancelled by user").into());
+                            }
+                            let res = stream.next_entry();
+                            entries.inc();
+                            res
+                        },
+                        &mut out,
+                        options,
+                    )?;
+                }
+            }
+            out.flush()?;
+        }
+    }
 
     entries.show_throughput(start);
     bytes.show_throughput(start);
@@ -84,7 +129,7 @@ fn fetch_rev_info(
     Ok(match object.kind {
         gix::object::Kind::Commit => {
             let commit = object.into_commit();
-            (Some(commit.committer()?.time.seconds), commit.tree_id()?.detach())
+            (Some(commit.committer()?.time.seconds), commit.id)
         }
         gix::object::Kind::Tree => (None, object.id),
         gix::object::Kind::Tag => fetch_rev_info(object.peel_to_kind(gix::object::Kind::Commit)?)?,
@@ -109,7 +109,7 @@ pub(crate) mod function {
         Ok(())
     }
 
-    fn print_match(
+    pub(crate) fn print_match(
         matches: &gix::attrs::search::Outcome,
         path: &BStr,
         mut out: impl std::io::Write,
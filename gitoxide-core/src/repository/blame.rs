@@ -0,0 +1,239 @@
+use std::collections::HashSet;
+use std::ffi::OsStr;
+
+use anyhow::{bail, Context};
+use gix::bstr::{BStr, ByteSlice};
+
+use crate::OutputFormat;
+
+/// The way blame information is printed.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub enum Format {
+    /// Print each line prefixed with the abbreviated commit, its author, date and line number, similar to `git blame`.
+    #[default]
+    Human,
+    /// Print the machine-readable format of `git blame --porcelain`, with commit information shown once per commit.
+    Porcelain,
+    /// Print the machine-readable format of `git blame --incremental`, which emits entries as soon as they are found.
+    Incremental,
+}
+
+pub struct Options {
+    pub format: Format,
+    /// The revision to start the blame from, or `HEAD` if unset.
+    pub rev: Option<String>,
+}
+
+pub fn blame_file(
+    mut repo: gix::Repository,
+    file: &OsStr,
+    Options { format, rev }: Options,
+    mut out: impl std::io::Write,
+    err: Option<&mut dyn std::io::Write>,
+    output_format: OutputFormat,
+) -> anyhow::Result<()> {
+    if output_format != OutputFormat::Human {
+        bail!("Only 'human' output format is currently supported");
+    }
+    repo.object_cache_size_if_unset(50 * 1024 * 1024);
+
+    let suspect = repo
+        .rev_parse_single(rev.as_deref().unwrap_or("HEAD"))?
+        .object()?
+        .peel_to_commit()
+        .context("Blame can only start at a commit")?
+        .id;
+    let file_path = gix::path::os_str_into_bstr(file)?;
+    let file_path = gix::path::to_unix_separators_on_windows(file_path);
+    let file_path: &BStr = &file_path;
+
+    let grafts = repo.grafts()?;
+    let options = gix::blame::Options {
+        parents: (!grafts.is_empty()).then_some(&grafts as _),
+        ..Default::default()
+    };
+    let mut seen = HashSet::new();
+    let outcome = match format {
+        Format::Incremental => {
+            let mut res = Ok(());
+            let outcome = gix::blame::file_incremental(&repo.objects, suspect, file_path, options, |entry| {
+                if res.is_ok() {
+                    res = write_incremental_entry(&repo, &grafts, entry, file_path, &mut seen, &mut out);
+                }
+            })?;
+            res?;
+            outcome
+        }
+        Format::Human | Format::Porcelain => {
+            let outcome = gix::blame::file(&repo.objects, suspect, file_path, options)?;
+            if format == Format::Human {
+                write_human(&repo, &outcome, &mut out)?;
+            } else {
+                write_porcelain(&repo, &grafts, &outcome, file_path, &mut seen, &mut out)?;
+            }
+            outcome
+        }
+    };
+
+    if let Some(err) = err {
+        writeln!(err, "{:#?}", outcome.statistics)?;
+    }
+    Ok(())
+}
+
+fn write_human(
+    repo: &gix::Repository,
+    outcome: &gix::blame::Outcome,
+    mut out: impl std::io::Write,
+) -> anyhow::Result<()> {
+    let line_number_width = outcome.blob.lines_with_terminator().count().to_string().len();
+    for (entry, lines) in outcome.entries_with_lines() {
+        let commit = repo.find_commit(entry.commit_id)?;
+        let short_id = commit.id().shorten_or_id();
+        let author = commit.author()?;
+        let date = author.time.format(gix::date::time::format::ISO8601);
+        for (offset, line) in lines.into_iter().enumerate() {
+            write!(
+                out,
+                "{short_id} ({name} {date} {line_number:>line_number_width$}) ",
+                name = author.name,
+                line_number = entry.start_in_blamed_file as usize + offset + 1,
+            )?;
+            out.write_all(line)?;
+            if !line.ends_with(b"\n") {
+                writeln!(out)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+fn write_porcelain(
+    repo: &gix::Repository,
+    grafts: &gix::revwalk::parents::Grafts,
+    outcome: &gix::blame::Outcome,
+    file_path: &BStr,
+    seen: &mut HashSet<gix::ObjectId>,
+    mut out: impl std::io::Write,
+) -> anyhow::Result<()> {
+    for (entry, lines) in outcome.entries_with_lines() {
+        for (offset, line) in lines.into_iter().enumerate() {
+            let offset = offset as u32;
+            write!(
+                out,
+                "{id} {source_line} {blamed_line}",
+                id = entry.commit_id,
+                source_line = entry.start_in_source_file + offset + 1,
+                blamed_line = entry.start_in_blamed_file + offset + 1,
+            )?;
+            if offset == 0 {
+                writeln!(out, " {}", entry.len)?;
+                if seen.insert(entry.commit_id) {
+                    write_commit_details(repo, grafts, entry.commit_id, &mut out)?;
+                    write_filename_info(repo, grafts, entry.commit_id, file_path, &mut out)?;
+                }
+            } else {
+                writeln!(out)?;
+            }
+            out.write_all(b"\t")?;
+            out.write_all(line)?;
+            if !line.ends_with(b"\n") {
+                writeln!(out)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+fn write_incremental_entry(
+    repo: &gix::Repository,
+    grafts: &gix::revwalk::parents::Grafts,
+    entry: &gix::blame::BlameEntry,
+    file_path: &BStr,
+    seen: &mut HashSet<gix::ObjectId>,
+    mut out: impl std::io::Write,
+) -> anyhow::Result<()> {
+    writeln!(
+        out,
+        "{id} {source_line} {blamed_line} {len}",
+        id = entry.commit_id,
+        source_line = entry.start_in_source_file + 1,
+        blamed_line = entry.start_in_blamed_file + 1,
+        len = entry.len,
+    )?;
+    if seen.insert(entry.commit_id) {
+        write_commit_details(repo, grafts, entry.commit_id, &mut out)?;
+    }
+    write_filename_info(repo, grafts, entry.commit_id, file_path, &mut out)?;
+    // Flush so consumers see each entry as soon as it's available.
+    out.flush()?;
+    Ok(())
+}
+
+/// Write the commit information that `git` emits the first time a commit is mentioned in porcelain and incremental output.
+///
+/// Like `git`, commits without parents are marked as `boundary`, as lines can't be passed on any further.
+fn write_commit_details(
+    repo: &gix::Repository,
+    grafts: &gix::revwalk::parents::Grafts,
+    id: gix::ObjectId,
+    mut out: impl std::io::Write,
+) -> anyhow::Result<()> {
+    let commit = repo.find_commit(id)?;
+    for (role, signature) in [("author", commit.author()?), ("committer", commit.committer()?)] {
+        let raw_time = signature.time.format(gix::date::time::format::RAW);
+        let (seconds, tz) = raw_time.split_once(' ').unwrap_or((raw_time.as_str(), "+0000"));
+        writeln!(out, "{role} {}", signature.name)?;
+        writeln!(out, "{role}-mail <{}>", signature.email)?;
+        writeln!(out, "{role}-time {seconds}")?;
+        writeln!(out, "{role}-tz {tz}")?;
+    }
+    // Like `git`, only use the first non-blank line of the message.
+    let summary = commit
+        .message_raw_sloppy()
+        .lines()
+        .find(|line| !line.trim().is_empty())
+        .unwrap_or_default();
+    writeln!(out, "summary {}", summary.as_bstr())?;
+    if parent_ids(repo, grafts, id)?.is_empty() {
+        writeln!(out, "boundary")?;
+    }
+    Ok(())
+}
+
+/// Write the file the lines of commit `id` are attributed to, preceded by the first parent of `id` that has the file
+/// as `previous`, to allow following the history of the lines.
+fn write_filename_info(
+    repo: &gix::Repository,
+    grafts: &gix::revwalk::parents::Grafts,
+    id: gix::ObjectId,
+    file_path: &BStr,
+    mut out: impl std::io::Write,
+) -> anyhow::Result<()> {
+    for parent_id in parent_ids(repo, grafts, id)? {
+        if repo
+            .find_commit(parent_id)?
+            .tree()?
+            .lookup_entry_by_path(gix::path::from_bstr(file_path))?
+            .is_some()
+        {
+            writeln!(out, "previous {parent_id} {file_path}")?;
+            break;
+        }
+    }
+    writeln!(out, "filename {file_path}")?;
+    Ok(())
+}
+
+/// Return the parents of the commit with `id`, as seen by the blame algorithm.
+fn parent_ids(
+    repo: &gix::Repository,
+    grafts: &gix::revwalk::parents::Grafts,
+    id: gix::ObjectId,
+) -> anyhow::Result<Vec<gix::ObjectId>> {
+    use gix::revwalk::Parents;
+    Ok(match grafts.parents_of(&id) {
+        Some(parents) => parents.to_vec(),
+        None => repo.find_commit(id)?.parent_ids().map(gix::Id::detach).collect(),
+    })
+}
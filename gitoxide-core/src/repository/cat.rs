@@ -1,26 +1,45 @@
 use crate::repository::revision::resolve::{BlobFormat, TreeMode};
 use anyhow::{anyhow, Context};
+use gix::bstr::BStr;
 use gix::diff::blob::ResourceKind;
 use gix::filter::plumbing::driver::apply::Delay;
 use gix::revision::Spec;
 
+/// Create the cache needed to display blobs in `blob_format`, or `None` if blobs are displayed as stored in git.
+pub(crate) fn blob_cache(
+    repo: &gix::Repository,
+    blob_format: BlobFormat,
+) -> anyhow::Result<Option<gix::diff::blob::Platform>> {
+    let mode = match blob_format {
+        BlobFormat::Git => return Ok(None),
+        BlobFormat::Worktree | BlobFormat::Diff => gix::diff::blob::pipeline::Mode::ToWorktreeAndBinaryToText,
+        BlobFormat::DiffOrGit => gix::diff::blob::pipeline::Mode::ToGitUnlessBinaryToTextIsPresent,
+    };
+    Ok(Some(repo.diff_resource_cache(mode, Default::default())?))
+}
+
+/// Display the object `spec` resolves to, using `path` to find the attributes of blobs if `spec` doesn't include a path.
 pub fn display_object(
     repo: &gix::Repository,
     spec: Spec<'_>,
+    path: Option<&BStr>,
     tree_mode: TreeMode,
     cache: Option<(BlobFormat, &mut gix::diff::blob::Platform)>,
     mut out: impl std::io::Write,
 ) -> anyhow::Result<()> {
     let id = spec.single().context("rev-spec must resolve to a single object")?;
     let header = id.header()?;
+    let path_and_mode = spec
+        .path_and_mode()
+        .or_else(|| path.map(|path| (path, gix::object::tree::EntryKind::Blob.into())));
     match header.kind() {
         gix::object::Kind::Tree if matches!(tree_mode, TreeMode::Pretty) => {
             for entry in id.object()?.into_tree().iter() {
                 writeln!(out, "{}", entry?)?;
             }
         }
-        gix::object::Kind::Blob if cache.is_some() && spec.path_and_mode().is_some() => {
-            let (path, mode) = spec.path_and_mode().expect("is present");
+        gix::object::Kind::Blob if cache.is_some() && path_and_mode.is_some() => {
+            let (path, mode) = path_and_mode.expect("is present");
             match cache.expect("is some") {
                 (BlobFormat::Git, _) => unreachable!("no need for a cache when querying object db"),
                 (BlobFormat::Worktree, cache) => {
@@ -36,14 +55,14 @@ pub fn display_object(
                     )?;
                     std::io::copy(&mut converted, &mut out)?;
                 }
-                (BlobFormat::Diff | BlobFormat::DiffOrGit, cache) => {
+                (format @ (BlobFormat::Diff | BlobFormat::DiffOrGit), cache) => {
                     cache.set_resource(id.detach(), mode.kind(), path, ResourceKind::OldOrSource, &repo.objects)?;
                     let resource = cache.resource(ResourceKind::OldOrSource).expect("just set");
-                    let data = resource
-                        .data
-                        .as_slice()
-                        .ok_or_else(|| anyhow!("Binary data at {} cannot be diffed", path))?;
-                    out.write_all(data)?;
+                    match resource.data.as_slice() {
+                        Some(data) => out.write_all(data)?,
+                        None if matches!(format, BlobFormat::DiffOrGit) => out.write_all(&id.object()?.data)?,
+                        None => return Err(anyhow!("Binary data at {} cannot be diffed", path)),
+                    }
                 }
             }
         }
@@ -53,10 +72,49 @@ pub fn display_object(
 }
 
 pub(super) mod function {
-    use crate::repository::revision::resolve::TreeMode;
+    use gix::bstr::BStr;
+
+    use crate::repository::revision::resolve::{BlobFormat, TreeMode};
+
+    /// Print the object `revspec` resolves to, with blobs in `blob_format`, which needs `path` to find the attributes
+    /// of blobs unless `revspec` is like `<rev>:<path>`.
+    pub fn cat(
+        repo: gix::Repository,
+        revspec: &str,
+        blob_format: BlobFormat,
+        path: Option<&BStr>,
+        out: impl std::io::Write,
+    ) -> anyhow::Result<()> {
+        let spec = repo.rev_parse(revspec)?;
+        if !matches!(blob_format, BlobFormat::Git) && path.is_none() && spec.path_and_mode().is_none() {
+            anyhow::bail!("<rev>:<path> or --path is needed to find out how to convert blobs")
+        }
+        let mut cache = super::blob_cache(&repo, blob_format)?;
+        super::display_object(
+            &repo,
+            spec,
+            path,
+            TreeMode::Pretty,
+            cache.as_mut().map(|cache| (blob_format, cache)),
+            out,
+        )?;
+        Ok(())
+    }
 
-    pub fn cat(repo: gix::Repository, revspec: &str, out: impl std::io::Write) -> anyhow::Result<()> {
-        super::display_object(&repo, repo.rev_parse(revspec)?, TreeMode::Pretty, None, out)?;
+    pub fn cat_batch(
+        repo: gix::Repository,
+        mode: gix::object::batch::Mode,
+        format: Option<&str>,
+        buffer: bool,
+        out: impl std::io::Write,
+    ) -> anyhow::Result<()> {
+        use gix::bstr::ByteSlice;
+        let format = match format.filter(|format| !format.is_empty()) {
+            Some(format) => gix::object::batch::Format::parse(format.as_bytes().as_bstr())?,
+            None => Default::default(),
+        };
+        let options = gix::object::batch::Options { format, mode, buffer };
+        repo.object_batch(std::io::stdin().lock(), out, &options)?;
         Ok(())
     }
 }
@@ -5,6 +5,9 @@ pub struct Options {
     pub bare: bool,
     pub handshake_info: bool,
     pub no_tags: bool,
+    pub single_branch: bool,
+    pub filter: Option<String>,
+    pub init_submodules: bool,
     pub shallow: gix::remote::fetch::Shallow,
     pub ref_name: Option<gix::refs::PartialName>,
 }
@@ -32,6 +35,9 @@ pub(crate) mod function {
             handshake_info,
             bare,
             no_tags,
+            single_branch,
+            filter,
+            init_submodules,
             ref_name,
             shallow,
         }: Options,
@@ -75,14 +81,19 @@ pub(crate) mod function {
         if no_tags {
             prepare = prepare.configure_remote(|r| Ok(r.with_fetch_tags(gix::remote::fetch::Tags::None)));
         }
-        let (mut checkout, fetch_outcome) = prepare
+        if let Some(filter) = filter {
+            prepare = prepare.with_filter(filter);
+        }
+        let (checkout, fetch_outcome) = prepare
             .with_shallow(shallow)
+            .with_single_branch(single_branch)
             .with_ref_name(ref_name.as_ref())?
             .fetch_then_checkout(&mut progress, &gix::interrupt::IS_INTERRUPTED)?;
 
         let (repo, outcome) = if bare {
             (checkout.persist(), None)
         } else {
+            let mut checkout = checkout.with_submodule_init(init_submodules);
             let (repo, outcome) = checkout.main_worktree(progress, &gix::interrupt::IS_INTERRUPTED)?;
             (repo, Some(outcome))
         };
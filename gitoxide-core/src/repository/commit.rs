@@ -1,40 +1,52 @@
-use std::{io::Write, process::Stdio};
-
 use anyhow::{anyhow, bail, Context, Result};
 
-/// Note that this is a quick implementation of commit signature verification that ignores a lot of what
-/// git does and can do, while focussing on the gist of it.
-/// For this to go into `gix`, one will have to implement many more options and various validation programs.
-pub fn verify(repo: gix::Repository, rev_spec: Option<&str>) -> Result<()> {
+/// Verify the signature of the commit at `rev_spec`, or `HEAD`, with the keyring configured in `repo` and print what
+/// is known about it to `out`.
+///
+/// It fails if the commit isn't signed, if the signature isn't good, or if it isn't trusted as much as `gpg.minTrustLevel` demands.
+pub fn verify(repo: gix::Repository, rev_spec: Option<&str>, out: impl std::io::Write) -> Result<()> {
     let rev_spec = rev_spec.unwrap_or("HEAD");
     let commit = repo
         .rev_parse_single(format!("{rev_spec}^{{commit}}").as_str())?
         .object()?
         .into_commit();
-    let (signature, signed_data) = commit
-        .signature()
-        .context("Could not parse commit to obtain signature")?
+    let keyring = repo.keyring()?;
+    let verification = commit
+        .verify_signature(&keyring)
+        .context("Could not verify commit signature")?
         .ok_or_else(|| anyhow!("Commit at {rev_spec} is not signed"))?;
+    print_verification(&verification, repo.keyring_min_trust_level()?, out)
+}
 
-    let mut signature_storage = tempfile::NamedTempFile::new()?;
-    signature_storage.write_all(signature.as_ref())?;
-    let signed_storage = signature_storage.into_temp_path();
-
-    let mut cmd: std::process::Command = gix::command::prepare("gpg").into();
-    cmd.args(["--keyid-format=long", "--status-fd=1", "--verify"])
-        .arg(&signed_storage)
-        .arg("-")
-        .stdin(Stdio::piped());
-    gix::trace::debug!("About to execute {cmd:?}");
-    let mut child = cmd.spawn()?;
-    child
-        .stdin
-        .take()
-        .expect("configured")
-        .write_all(signed_data.to_bstring().as_ref())?;
+/// Print `verification` to `out` and fail unless it's good and trusted as much as `min_trust`.
+pub(crate) fn print_verification(
+    verification: &gix::keyring::Verification,
+    min_trust: Option<gix::keyring::Trust>,
+    mut out: impl std::io::Write,
+) -> Result<()> {
+    let outcome = &verification.outcome;
+    writeln!(out, "format: {}", verification.format.as_str())?;
+    writeln!(out, "status: {:?}", outcome.status)?;
+    if let Some(signer) = &outcome.signer {
+        writeln!(out, "signer: {signer}")?;
+    }
+    if let Some(key) = &outcome.key {
+        writeln!(out, "key: {key}")?;
+    }
+    writeln!(out, "trust: {:?}", outcome.trust)?;
+    writeln!(out, "payload: {}", verification.payload_digest)?;
+    if !outcome.output.is_empty() {
+        writeln!(out, "\n{}", outcome.output)?;
+    }
 
-    if !child.wait()?.success() {
-        bail!("Command {cmd:?} failed");
+    if outcome.status != gix::keyring::Status::Good {
+        bail!("The signature is not good: {:?}", outcome.status);
+    }
+    if let Some(min_trust) = min_trust.filter(|min_trust| !verification.is_trusted(*min_trust)) {
+        bail!(
+            "The signing key is trusted {:?}, but needs to be trusted at least {min_trust:?}",
+            outcome.trust
+        );
     }
     Ok(())
 }
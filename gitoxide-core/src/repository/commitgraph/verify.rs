@@ -7,35 +7,98 @@ pub struct Context<W1: std::io::Write, W2: std::io::Write> {
     /// A stream to which to output operation results
     pub out: W1,
     pub output_statistics: Option<OutputFormat>,
+    /// If set, drop corrupt layers of a split commit-graph instead of failing.
+    pub repair: bool,
+}
+
+/// An inconsistency between the commit-graph and the object database.
+#[derive(Debug, thiserror::Error)]
+pub enum OdbMismatch {
+    #[error("Commit {id} from the commit-graph could not be found in the object database")]
+    Missing { id: gix::ObjectId },
+    #[error("Commit {id} has root tree {expected} in the object database, but {actual} in the commit-graph")]
+    RootTree {
+        id: gix::ObjectId,
+        expected: gix::ObjectId,
+        actual: gix::ObjectId,
+    },
+    #[error("Commit {id} has parents {expected:?} in the object database, but {actual:?} in the commit-graph")]
+    Parents {
+        id: gix::ObjectId,
+        expected: Vec<gix::ObjectId>,
+        actual: Vec<gix::ObjectId>,
+    },
 }
 
 pub(crate) mod function {
     use std::io;
 
     use anyhow::{Context as AnyhowContext, Result};
+    use gix::commitgraph::{file::Commit, Graph};
 
-    use crate::{repository::commitgraph::verify::Context, OutputFormat};
+    use crate::{
+        repository::commitgraph::verify::{Context, OdbMismatch},
+        OutputFormat,
+    };
 
     pub fn verify<W1, W2>(
         repo: gix::Repository,
         Context {
-            err: _err,
+            mut err,
             mut out,
             output_statistics,
+            repair,
         }: Context<W1, W2>,
     ) -> Result<gix::commitgraph::verify::Outcome>
     where
         W1: io::Write,
         W2: io::Write,
     {
-        let g = repo.commit_graph()?;
-
-        #[allow(clippy::unnecessary_wraps, unknown_lints)]
-        fn noop_processor(_commit: &gix::commitgraph::file::Commit<'_>) -> std::result::Result<(), std::fmt::Error> {
+        let check_against_odb = |graph: &Graph, commit: &Commit<'_>| -> std::result::Result<(), OdbMismatch> {
+            let id = commit.id().to_owned();
+            let odb_commit = repo.find_commit(id).map_err(|_| OdbMismatch::Missing { id })?;
+            let tree = odb_commit.tree_id().map_err(|_| OdbMismatch::Missing { id })?.detach();
+            if tree.as_ref() != commit.root_tree_id() {
+                return Err(OdbMismatch::RootTree {
+                    id,
+                    expected: tree,
+                    actual: commit.root_tree_id().to_owned(),
+                });
+            }
+            let expected_parents: Vec<_> = odb_commit.parent_ids().map(gix::Id::detach).collect();
+            let parents: Vec<_> = commit
+                .iter_parents()
+                .filter_map(Result::ok)
+                .map(|pos| graph.id_at(pos).to_owned())
+                .collect();
+            if expected_parents != parents {
+                return Err(OdbMismatch::Parents {
+                    id,
+                    expected: expected_parents,
+                    actual: parents,
+                });
+            }
             Ok(())
+        };
+
+        if repair {
+            let commit_graphs_dir = repo.objects.store_ref().path().join("info").join("commit-graphs");
+            if commit_graphs_dir.join("commit-graph-chain").is_file() {
+                let outcome = gix::commitgraph::repair::chain(&commit_graphs_dir, check_against_odb)?;
+                if let Some(corruption) = outcome.corruption {
+                    writeln!(
+                        err,
+                        "Dropped {} corrupt commit-graph layer(s), keeping {}: {corruption}",
+                        outcome.removed_layers.len(),
+                        outcome.num_kept_layers
+                    )?;
+                }
+            }
         }
+
+        let g = repo.commit_graph()?;
         let stats = g
-            .verify_integrity(noop_processor)
+            .verify_integrity(|commit| check_against_odb(&g, commit))
             .with_context(|| "Verification failure")?;
 
         #[cfg_attr(not(feature = "serde"), allow(clippy::single_match))]
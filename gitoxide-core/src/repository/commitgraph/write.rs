@@ -0,0 +1,63 @@
+use crate::OutputFormat;
+
+pub struct Options {
+    pub format: OutputFormat,
+    pub thread_limit: Option<usize>,
+    /// If set, compute changed-path Bloom filters for all commits.
+    pub changed_paths: bool,
+    /// Commits that changed more paths than this get a Bloom filter that matches every path.
+    pub max_changed_paths: Option<u32>,
+}
+
+pub(crate) mod function {
+    use anyhow::bail;
+
+    use crate::{repository::commitgraph::write::Options, OutputFormat};
+
+    pub fn write(
+        mut repo: gix::Repository,
+        mut out: impl std::io::Write,
+        Options {
+            format,
+            thread_limit,
+            changed_paths,
+            max_changed_paths,
+        }: Options,
+    ) -> anyhow::Result<()> {
+        if format != OutputFormat::Human {
+            bail!("Only human output is currently supported");
+        }
+        repo.object_cache_size_if_unset(4 * 1024 * 1024);
+        let changed_paths = changed_paths.then(|| {
+            let mut settings = gix::commitgraph::bloom::Settings::default();
+            if let Some(max_changed_paths) = max_changed_paths {
+                settings.max_changed_paths = max_changed_paths;
+            }
+            settings
+        });
+        let Some(outcome) = repo.write_commit_graph(gix::repository::write_commit_graph::Options {
+            changed_paths,
+            thread_limit,
+        })?
+        else {
+            writeln!(
+                out,
+                "skipped as commit-graphs can't be used in shallow or grafted repositories"
+            )?;
+            return Ok(());
+        };
+        writeln!(
+            out,
+            "wrote {} commits, checksum {}",
+            outcome.num_commits, outcome.checksum
+        )?;
+        if changed_paths.is_some() {
+            writeln!(
+                out,
+                "{} commits changed too many paths to be filtered",
+                outcome.num_large_filters
+            )?;
+        }
+        Ok(())
+    }
+}
@@ -3,11 +3,24 @@ use gix::objs::tree::EntryMode;
 use gix::odb::store::RefreshMode;
 use gix::prelude::ObjectIdExt;
 
+/// How to summarize the changes between two trees instead of listing them.
+#[derive(Debug, Copy, Clone)]
+pub enum Summary {
+    /// Like `git diff --stat`, with lines at most as wide as the given width.
+    Stat { width: usize },
+    /// Like `git diff --numstat`.
+    Numstat,
+    /// Like `git diff --shortstat`.
+    Shortstat,
+}
+
 pub fn tree(
     mut repo: gix::Repository,
     out: &mut dyn std::io::Write,
     old_treeish: BString,
     new_treeish: BString,
+    summary: Option<Summary>,
+    files_summary: bool,
 ) -> anyhow::Result<()> {
     repo.object_cache_size_if_unset(repo.compute_object_cache_size_for_tree_diffs(&**repo.index_or_empty()?));
     repo.objects.refresh = RefreshMode::Never;
@@ -20,6 +33,21 @@ pub fn tree(
 
     let changes = repo.diff_tree_to_tree(&old_tree, &new_tree, None)?;
 
+    if summary.is_some() || files_summary {
+        let mut cache = repo.diff_resource_cache_for_tree_diff()?;
+        let stat = gix::object::tree::diff::Summary::from_changes(&repo, &changes, &mut cache)?;
+        match summary {
+            Some(Summary::Stat { width }) => stat.write_stat(&mut *out, width)?,
+            Some(Summary::Numstat) => stat.write_numstat(&mut *out)?,
+            Some(Summary::Shortstat) => stat.write_shortstat(&mut *out)?,
+            None => {}
+        }
+        if files_summary {
+            stat.write_summary(out)?;
+        }
+        return Ok(());
+    }
+
     writeln!(
         out,
         "Diffing trees `{old_treeish}` ({old_tree_id}) -> `{new_treeish}` ({new_tree_id})\n"
@@ -29,7 +57,7 @@ pub fn tree(
     Ok(())
 }
 
-fn write_changes(
+pub(crate) fn write_changes(
     repo: &gix::Repository,
     mut out: impl std::io::Write,
     changes: Vec<gix::diff::tree_with_rewrites::Change>,
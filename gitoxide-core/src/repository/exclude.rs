@@ -113,7 +113,7 @@ pub fn query(
     Ok(())
 }
 
-fn print_match(
+pub(crate) fn print_match(
     m: Option<gix::ignore::search::Match<'_>>,
     path: &BStr,
     mut out: impl std::io::Write,
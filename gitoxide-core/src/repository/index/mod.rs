@@ -1,4 +1,4 @@
-use std::{ffi::OsString, path::PathBuf};
+use std::{ffi::OsString, path::PathBuf, sync::atomic::AtomicBool};
 
 use anyhow::bail;
 
@@ -39,6 +39,73 @@ pub fn from_tree(
     Ok(())
 }
 
+pub fn from_worktree(
+    repo: gix::Repository,
+    index_path: Option<PathBuf>,
+    force: bool,
+    skip_hash: bool,
+    thread_limit: Option<usize>,
+    should_interrupt: &AtomicBool,
+    mut out: impl std::io::Write,
+) -> anyhow::Result<()> {
+    if let Some(index_path) = index_path.as_ref().filter(|p| p.is_file() && !force) {
+        anyhow::bail!(
+            "File at \"{}\" already exists, to overwrite use the '-f' flag",
+            index_path.display()
+        );
+    }
+    let outcome = repo.index_from_worktree(thread_limit, should_interrupt)?;
+    for path in &outcome.skipped_repositories {
+        writeln!(out, "skipped nested repository {path}")?;
+    }
+    writeln!(out, "{}", outcome.tree_id)?;
+
+    let mut index = outcome.index;
+    let options = gix::index::write::Options {
+        skip_hash,
+        ..Default::default()
+    };
+    match index_path {
+        Some(index_path) => {
+            index.set_path(index_path);
+            index.write(options)?;
+        }
+        None => {
+            let mut out = Vec::with_capacity(512 * 1024);
+            index.write_to(&mut out, options)?;
+        }
+    }
+    Ok(())
+}
+
+pub fn add(
+    repo: gix::Repository,
+    pathspecs: Vec<gix::bstr::BString>,
+    thread_limit: Option<usize>,
+    should_interrupt: &AtomicBool,
+    mut out: impl std::io::Write,
+) -> anyhow::Result<()> {
+    let mut index = match repo.try_index()? {
+        Some(index) => (**index).clone(),
+        None => gix::index::File::from_state(gix::index::State::new(repo.object_hash()), repo.index_path()),
+    };
+    let outcome = repo.add_to_index(&mut index, pathspecs, thread_limit, should_interrupt)?;
+    for path in &outcome.skipped_repositories {
+        writeln!(out, "skipped nested repository {path}")?;
+    }
+    for (action, paths) in [
+        ("add", &outcome.added),
+        ("update", &outcome.updated),
+        ("remove", &outcome.removed),
+    ] {
+        for path in paths {
+            writeln!(out, "{action} '{path}'")?;
+        }
+    }
+    index.write(Default::default())?;
+    Ok(())
+}
+
 pub fn from_list(
     entries_file: PathBuf,
     index_path: Option<PathBuf>,
@@ -92,3 +159,5 @@ pub fn from_list(
 
 pub mod entries;
 pub use entries::function::entries;
+#[cfg(all(unix, feature = "index-server"))]
+pub mod serve;
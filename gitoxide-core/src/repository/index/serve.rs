@@ -0,0 +1,312 @@
+//! A long-running server that keeps the index with its untracked cache as well as attribute and ignore stacks in memory
+//! to answer queries about the worktree over a unix domain socket, which avoids paying for loading them on each invocation.
+//!
+//! ### Protocol
+//!
+//! Each request is a single line of the form `<command> [<arguments>]`, and each response consists of any amount of lines
+//! followed by an empty line. If a request fails, the response is a single `error: <message>` line.
+//! A connection can be used for any amount of requests until it's closed by the client, or by the server once
+//! it was idle for longer than the configured timeout. Connections are served one at a time.
+//!
+//! * `status [<pathspec>…]`
+//!     - the changes between the index and the worktree in the simplified format of `gix status`, with pathspecs
+//!       separated by spaces.
+//! * `entry <path>`
+//!     - the entries at `path` as `<mode> <id> <stage>\t<path>`, one for each stage, like `git ls-files --stage`.
+//! * `attributes <path>`
+//!     - the attributes assigned to `path`, like `gix attributes query`.
+//! * `excluded <path>`
+//!     - the exclude pattern matching `path`, like `gix exclude query`.
+//! * `shutdown`
+//!     - stop the server once the response was sent.
+use std::{
+    io::{BufRead, BufReader, BufWriter, Write},
+    os::unix::net::{UnixListener, UnixStream},
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use anyhow::{anyhow, bail, Context};
+use gix::bstr::{BStr, BString, ByteSlice};
+
+use crate::is_dir_to_mode;
+
+pub struct Options {
+    pub thread_limit: Option<usize>,
+    /// If `true`, the index will be written back after a `status` request if its stat information was refreshed,
+    /// which speeds up subsequent requests.
+    /// Otherwise, the refreshed index is kept in memory for use by subsequent requests until the index on disk changes.
+    pub allow_write: bool,
+    /// The time after which a connection that doesn't send a request, or doesn't receive its response, is closed,
+    /// so that a stuck client can't keep others from being served. If `None`, connections are kept open indefinitely.
+    pub connection_timeout: Option<Duration>,
+}
+
+/// Listen at `socket` and answer requests about the worktree of `repo` until a `shutdown` request is received,
+/// or until the process is interrupted.
+///
+/// A stale socket file is removed, but it's an error if another server is still listening at `socket`.
+pub fn serve(repo: gix::Repository, socket: PathBuf, mut err: impl Write, options: Options) -> anyhow::Result<()> {
+    if repo.work_dir().is_none() {
+        bail!("Cannot serve the index of a bare repository");
+    }
+    if socket.exists() {
+        if UnixStream::connect(&socket).is_ok() {
+            bail!("Another server is already listening at \"{}\"", socket.display());
+        }
+        std::fs::remove_file(&socket)?;
+    }
+    let listener =
+        UnixListener::bind(&socket).with_context(|| format!("Could not listen at \"{}\"", socket.display()))?;
+    let _remove_socket = RemoveOnDrop(&socket);
+    // Don't block so interrupts can be noticed.
+    listener.set_nonblocking(true)?;
+
+    let mut state = State::new(&repo)?;
+    writeln!(err, "Listening at \"{}\"", socket.display())?;
+    while !gix::interrupt::is_triggered() {
+        let stream = match listener.accept() {
+            Ok((stream, _addr)) => stream,
+            Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => {
+                std::thread::sleep(Duration::from_millis(50));
+                continue;
+            }
+            Err(e) => return Err(e.into()),
+        };
+        match state.handle_connection(stream, &options) {
+            Ok(Continue::Yes) => {}
+            Ok(Continue::No) => break,
+            Err(e) => writeln!(err, "Connection failed: {e:#}")?,
+        }
+    }
+    Ok(())
+}
+
+/// Connect to the server listening at `socket` and send it `request`, writing the response to `out`.
+pub fn query(socket: &Path, request: &BStr, mut out: impl Write) -> anyhow::Result<()> {
+    if request.contains(&b'\n') {
+        bail!("Requests must not contain newlines");
+    }
+    let stream = UnixStream::connect(socket)
+        .with_context(|| format!("Could not connect to server at \"{}\"", socket.display()))?;
+    let mut writer = BufWriter::new(stream.try_clone()?);
+    writer.write_all(request)?;
+    writer.write_all(b"\n")?;
+    writer.flush()?;
+
+    let mut reader = BufReader::new(stream);
+    let mut line = Vec::new();
+    loop {
+        line.clear();
+        if reader.read_until(b'\n', &mut line)? == 0 {
+            bail!("The server closed the connection before the response was complete");
+        }
+        match line.as_slice() {
+            b"\n" => break,
+            line => match line.strip_prefix(b"error: ") {
+                Some(message) => bail!("{}", message.trim_end().as_bstr()),
+                None => out.write_all(line)?,
+            },
+        }
+    }
+    Ok(())
+}
+
+enum Continue {
+    Yes,
+    No,
+}
+
+struct RemoveOnDrop<'a>(&'a Path);
+
+impl Drop for RemoveOnDrop<'_> {
+    fn drop(&mut self) {
+        std::fs::remove_file(self.0).ok();
+    }
+}
+
+/// Everything that is kept in memory between requests.
+struct State<'repo> {
+    repo: &'repo gix::Repository,
+    /// The index the attribute stack was created for.
+    index: gix::worktree::Index,
+    attributes: gix::AttributeStack<'repo>,
+    matches: gix::attrs::search::Outcome,
+    /// The index with the changes of the last `status` request applied, along with the index on disk it was derived from,
+    /// for use by the next `status` request if the index on disk didn't change in the meantime.
+    status_index: Option<(gix::worktree::Index, gix::index::File)>,
+}
+
+impl<'repo> State<'repo> {
+    fn new(repo: &'repo gix::Repository) -> anyhow::Result<Self> {
+        let index = repo.index_or_empty()?;
+        let attributes = attribute_stack(repo, &index)?;
+        Ok(State {
+            repo,
+            matches: attributes.attribute_matches(),
+            index,
+            attributes,
+            status_index: None,
+        })
+    }
+
+    /// Pick up the latest version of the index, and recreate the attribute stack if it changed as it might
+    /// read attribute and ignore files from it.
+    fn refresh(&mut self) -> anyhow::Result<()> {
+        let index = self.repo.index_or_empty()?;
+        if !gix::features::threading::OwnShared::ptr_eq(&index, &self.index) {
+            self.attributes = attribute_stack(self.repo, &index)?;
+            self.matches = self.attributes.attribute_matches();
+            self.index = index;
+        }
+        Ok(())
+    }
+
+    fn handle_connection(&mut self, stream: UnixStream, options: &Options) -> anyhow::Result<Continue> {
+        stream.set_nonblocking(false)?;
+        stream.set_read_timeout(options.connection_timeout)?;
+        stream.set_write_timeout(options.connection_timeout)?;
+        let mut reader = BufReader::new(stream.try_clone()?);
+        let mut out = BufWriter::new(stream);
+        let mut line = Vec::new();
+        let mut response = Vec::new();
+        loop {
+            line.clear();
+            let bytes_read = reader.read_until(b'\n', &mut line).map_err(|err| match err.kind() {
+                std::io::ErrorKind::WouldBlock | std::io::ErrorKind::TimedOut => anyhow!(
+                    "Closed connection that didn't send a request for {:?}",
+                    options.connection_timeout.expect("only times out if set")
+                ),
+                _ => err.into(),
+            })?;
+            if bytes_read == 0 {
+                return Ok(Continue::Yes);
+            }
+            let request = line.trim_end_with(|c| c == '\n' || c == '\r');
+            let (command, args) = request.split_once_str(" ").unwrap_or((request, b""));
+
+            response.clear();
+            let mut shutdown = false;
+            let res = match command {
+                b"status" => self.status(
+                    args.fields().map(|spec| spec.as_bstr().to_owned()).collect(),
+                    &mut response,
+                    options,
+                ),
+                b"entry" => self.entry(args.as_bstr(), &mut response),
+                b"attributes" => self.attributes(args.as_bstr(), &mut response),
+                b"excluded" => self.excluded(args.as_bstr(), &mut response),
+                b"shutdown" => {
+                    shutdown = true;
+                    Ok(())
+                }
+                _ => Err(anyhow!("Unknown command: \"{}\"", command.as_bstr())),
+            };
+            match res {
+                Ok(()) => out.write_all(&response)?,
+                Err(e) => writeln!(out, "error: {e:#}")?,
+            }
+            writeln!(out)?;
+            out.flush()?;
+            if shutdown {
+                return Ok(Continue::No);
+            }
+        }
+    }
+
+    fn status(&mut self, pathspecs: Vec<BString>, out: &mut dyn Write, options: &Options) -> anyhow::Result<()> {
+        let on_disk = self.repo.index_or_empty()?;
+        let index = match self.status_index.take() {
+            Some((base, changed)) if gix::features::threading::OwnShared::ptr_eq(&base, &on_disk) => changed.into(),
+            _ => on_disk.clone().into(),
+        };
+        let mut iter = self
+            .repo
+            .status(gix::progress::Discard)?
+            .index(index)
+            .should_interrupt_shared(&gix::interrupt::IS_INTERRUPTED)
+            .index_worktree_options_mut(|opts| {
+                opts.thread_limit = options.thread_limit;
+                opts.sorting =
+                    Some(gix::status::plumbing::index_as_worktree_with_renames::Sorting::ByPathCaseSensitive);
+            })
+            .into_index_worktree_iter(pathspecs)?;
+        for item in iter.by_ref() {
+            crate::repository::status::print_item(out, Path::new(""), item?)?;
+        }
+        if gix::interrupt::is_triggered() {
+            bail!("interrupted by user");
+        }
+
+        let mut outcome = iter.into_outcome().expect("successful iteration has outcome");
+        if options.allow_write {
+            outcome.write_changes().transpose()?;
+        } else {
+            let changed = match outcome.changed_index() {
+                Some(changed) => changed,
+                None => match outcome.index {
+                    gix::worktree::IndexPersistedOrInMemory::InMemory(unchanged) => unchanged,
+                    gix::worktree::IndexPersistedOrInMemory::Persisted(_) => return Ok(()),
+                },
+            };
+            self.status_index = Some((on_disk, changed));
+        }
+        Ok(())
+    }
+
+    fn entry(&mut self, path: &BStr, out: &mut dyn Write) -> anyhow::Result<()> {
+        self.refresh()?;
+        let index = &self.index;
+        for entry in index.entry_range(path).map_or(&[][..], |range| &index.entries()[range]) {
+            writeln!(
+                out,
+                "{mode:06o} {id} {stage}\t{path}",
+                mode = entry.mode.bits(),
+                id = entry.id,
+                stage = entry.stage_raw(),
+            )?;
+        }
+        Ok(())
+    }
+
+    fn attributes(&mut self, path: &BStr, out: &mut dyn Write) -> anyhow::Result<()> {
+        self.refresh()?;
+        let mode = self.mode_of(path);
+        let entry = self.attributes.at_entry(path, mode)?;
+        if entry.matching_attributes(&mut self.matches) {
+            crate::repository::attributes::query::function::print_match(&self.matches, path, out)?;
+        }
+        Ok(())
+    }
+
+    fn excluded(&mut self, path: &BStr, out: &mut dyn Write) -> anyhow::Result<()> {
+        self.refresh()?;
+        let mode = self.mode_of(path);
+        let entry = self.attributes.at_entry(path, mode)?;
+        let match_ = entry.matching_exclude_pattern().filter(|m| !m.pattern.is_negative());
+        crate::repository::exclude::print_match(match_, path, out)?;
+        Ok(())
+    }
+
+    /// Determine the mode of `path` by looking at the worktree, or `None` if it doesn't exist there.
+    fn mode_of(&self, path: &BStr) -> Option<gix::index::entry::Mode> {
+        let work_dir = self.repo.work_dir()?;
+        work_dir
+            .join(gix::path::from_bstr(path))
+            .symlink_metadata()
+            .ok()
+            .map(|m| is_dir_to_mode(m.is_dir()))
+    }
+}
+
+fn attribute_stack<'repo>(
+    repo: &'repo gix::Repository,
+    index: &gix::index::State,
+) -> anyhow::Result<gix::AttributeStack<'repo>> {
+    Ok(repo.attributes(
+        index,
+        gix::worktree::stack::state::attributes::Source::WorktreeThenIdMapping,
+        gix::worktree::stack::state::ignore::Source::WorktreeThenIdMappingIfNotSkipped,
+        None,
+    )?)
+}
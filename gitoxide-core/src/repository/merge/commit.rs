@@ -17,6 +17,7 @@ pub fn commit(
     Options {
         format,
         file_favor,
+        conflict_style,
         in_memory,
         debug,
     }: Options,
@@ -31,7 +32,10 @@ pub fn commit(
     let (ours_ref, ours_id) = refname_and_commit(&repo, ours)?;
     let (theirs_ref, theirs_id) = refname_and_commit(&repo, theirs)?;
 
-    let options = repo.tree_merge_options()?.with_file_favor(file_favor);
+    let mut options = repo.tree_merge_options()?.with_file_favor(file_favor);
+    if let Some(style) = conflict_style {
+        options = options.with_conflict_style(style);
+    }
     let ours_id_str = ours_id.to_string();
     let theirs_id_str = theirs_id.to_string();
     let labels = gix::merge::blob::builtin_driver::text::Labels {
@@ -50,11 +54,12 @@ pub fn commit(
         .tree_merge;
     let has_conflicts = res.conflicts.is_empty();
     let has_unresolved_conflicts = res.has_unresolved_conflicts(TreatAsUnresolved::Renames);
-    {
+    let tree_id = {
         let _span = gix::trace::detail!("Writing merged tree");
         let mut written = 0;
         let tree_id = res
             .tree
+            .clone()
             .detach()
             .write(|tree| {
                 written += 1;
@@ -62,6 +67,17 @@ pub fn commit(
             })
             .map_err(|err| anyhow!("{err}"))?;
         writeln!(out, "{tree_id} (wrote {written} trees)")?;
+        tree_id
+    };
+    for entry in res.conflict_entries(&tree_id, TreatAsUnresolved::Renames)? {
+        writeln!(
+            out,
+            "{:06o} {} {}\t{}",
+            entry.mode.bits(),
+            entry.id,
+            entry.stage as u8,
+            entry.path
+        )?;
     }
 
     if debug {
@@ -3,18 +3,35 @@ use anyhow::{anyhow, bail, Context};
 use gix::bstr::BString;
 use gix::bstr::ByteSlice;
 use gix::merge::blob::builtin_driver::binary;
-use gix::merge::blob::builtin_driver::text::Conflict;
+use gix::merge::blob::builtin_driver::text::{Conflict, ConflictStyle};
 use gix::merge::blob::pipeline::WorktreeRoots;
 use gix::merge::blob::{Resolution, ResourceKind};
 use gix::object::tree::EntryKind;
 use gix::Id;
+use std::num::NonZeroU8;
 use std::path::Path;
 
+pub struct Options {
+    /// How to resolve conflicts, or `None` to write conflict markers.
+    pub conflict: Option<Conflict>,
+    /// The style of conflict markers, or `None` to use the configured one.
+    pub style: Option<ConflictStyle>,
+    /// The amount of characters for each conflict marker, or `None` to use the default.
+    pub marker_size: Option<NonZeroU8>,
+    /// Up to three labels for ours, base and theirs, to use instead of their names.
+    pub labels: Vec<BString>,
+}
+
 pub fn file(
     repo: gix::Repository,
     out: &mut dyn std::io::Write,
     format: OutputFormat,
-    conflict: Option<gix::merge::blob::builtin_driver::text::Conflict>,
+    Options {
+        conflict,
+        style,
+        marker_size,
+        labels,
+    }: Options,
     base: BString,
     ours: BString,
     theirs: BString,
@@ -22,6 +39,9 @@ pub fn file(
     if format != OutputFormat::Human {
         bail!("JSON output isn't implemented yet");
     }
+    if labels.len() > 3 {
+        bail!("At most three labels can be provided for ours, base and theirs");
+    }
     let index = &repo.index_or_load_from_head()?;
     let specs = repo.pathspec(
         false,
@@ -76,11 +96,19 @@ pub fn file(
             Conflict::ResolveWithUnion => None,
         };
     }
+    if let Conflict::Keep {
+        style: current_style,
+        marker_size: current_marker_size,
+    } = &mut options.text.conflict
+    {
+        *current_style = style.unwrap_or(*current_style);
+        *current_marker_size = marker_size.unwrap_or(*current_marker_size);
+    }
     let platform = cache.prepare_merge(&repo.objects, options)?;
     let labels = gix::merge::blob::builtin_driver::text::Labels {
-        ancestor: Some(base.as_bstr()),
-        current: Some(ours.as_bstr()),
-        other: Some(theirs.as_bstr()),
+        ancestor: Some(labels.get(1).unwrap_or(&base).as_bstr()),
+        current: Some(labels.first().unwrap_or(&ours).as_bstr()),
+        other: Some(labels.get(2).unwrap_or(&theirs).as_bstr()),
     };
     let mut buf = repo.empty_reusable_buffer();
     let (pick, resolution) = platform.merge(&mut buf, labels, &repo.command_context()?)?;
@@ -1,4 +1,4 @@
-mod file;
+pub mod file;
 pub use file::file;
 
 pub mod tree;
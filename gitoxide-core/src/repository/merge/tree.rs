@@ -3,6 +3,7 @@ use crate::OutputFormat;
 pub struct Options {
     pub format: OutputFormat,
     pub file_favor: Option<gix::merge::tree::FileFavor>,
+    pub conflict_style: Option<gix::merge::blob::builtin_driver::text::ConflictStyle>,
     pub in_memory: bool,
     pub debug: bool,
 }
@@ -29,6 +30,7 @@ pub(super) mod function {
         Options {
             format,
             file_favor,
+            conflict_style,
             in_memory,
             debug,
         }: Options,
@@ -44,7 +46,10 @@ pub(super) mod function {
         let (ours_ref, ours_id) = refname_and_tree(&repo, ours)?;
         let (theirs_ref, theirs_id) = refname_and_tree(&repo, theirs)?;
 
-        let options = repo.tree_merge_options()?.with_file_favor(file_favor);
+        let mut options = repo.tree_merge_options()?.with_file_favor(file_favor);
+        if let Some(style) = conflict_style {
+            options = options.with_conflict_style(style);
+        }
         let base_id_str = base_id.to_string();
         let ours_id_str = ours_id.to_string();
         let theirs_id_str = theirs_id.to_string();
@@ -65,11 +70,12 @@ pub(super) mod function {
         let res = repo.merge_trees(base_id, ours_id, theirs_id, labels, options)?;
         let has_conflicts = res.conflicts.is_empty();
         let has_unresolved_conflicts = res.has_unresolved_conflicts(TreatAsUnresolved::Renames);
-        {
+        let tree_id = {
             let _span = gix::trace::detail!("Writing merged tree");
             let mut written = 0;
             let tree_id = res
                 .tree
+                .clone()
                 .detach()
                 .write(|tree| {
                     written += 1;
@@ -77,6 +83,17 @@ pub(super) mod function {
                 })
                 .map_err(|err| anyhow!("{err}"))?;
             writeln!(out, "{tree_id} (wrote {written} trees)")?;
+            tree_id
+        };
+        for entry in res.conflict_entries(&tree_id, TreatAsUnresolved::Renames)? {
+            writeln!(
+                out,
+                "{:06o} {} {}\t{}",
+                entry.mode.bits(),
+                entry.id,
+                entry.stage as u8,
+                entry.path
+            )?;
         }
 
         if debug {
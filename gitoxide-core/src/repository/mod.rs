@@ -19,8 +19,10 @@ pub enum PathsOrPatterns {
 
 #[cfg(feature = "archive")]
 pub mod archive;
+pub mod blame;
+pub use blame::blame_file;
 pub mod cat;
-pub use cat::function::cat;
+pub use cat::function::{cat, cat_batch};
 pub mod commit;
 pub mod config;
 mod credential;
@@ -51,10 +53,19 @@ mod merge_base;
 pub use merge_base::merge_base;
 pub mod merge;
 pub mod odb;
+pub mod reflog;
+pub mod refs;
 pub mod remote;
+pub mod rev_parse;
+pub use rev_parse::rev_parse;
 pub mod revision;
+pub mod show;
+pub use show::show;
+pub mod stats;
+pub use stats::stats;
 pub mod status;
 pub mod submodule;
+pub mod tag;
 pub mod tree;
 pub mod verify;
 pub mod worktree;
@@ -42,6 +42,78 @@ pub fn info(
     Ok(())
 }
 
+pub mod count_objects {
+    use crate::OutputFormat;
+
+    #[derive(Debug, Copy, Clone)]
+    pub struct Options {
+        pub format: OutputFormat,
+        /// Print the amount and size of packs and garbage files as well, instead of only the loose objects.
+        pub verbose: bool,
+        /// Print sizes in human-readable units instead of KiB.
+        pub human_readable: bool,
+    }
+}
+
+/// Print information about loose objects, packs and garbage files of `repo` like `git count-objects` does.
+pub fn count_objects(
+    repo: gix::Repository,
+    mut out: impl io::Write,
+    mut err: impl io::Write,
+    count_objects::Options {
+        format,
+        verbose,
+        human_readable,
+    }: count_objects::Options,
+) -> anyhow::Result<()> {
+    let stats = repo.objects.store_ref().statistics()?;
+    match format {
+        OutputFormat::Human => {
+            let size = |bytes: u64| {
+                if human_readable {
+                    bytesize::ByteSize(bytes).to_string()
+                } else {
+                    (bytes / 1024).to_string()
+                }
+            };
+            for garbage in &stats.garbage {
+                let reason = match garbage.kind {
+                    gix::odb::store::statistics::GarbageKind::Unknown => "garbage found",
+                    gix::odb::store::statistics::GarbageKind::IncompletePack => "no corresponding .idx or .pack",
+                };
+                writeln!(err, "warning: {reason}: {}", garbage.path.display())?;
+            }
+            if verbose {
+                writeln!(out, "count: {}", stats.num_loose_objects())?;
+                writeln!(out, "size: {}", size(stats.loose_size_on_disk()))?;
+                writeln!(out, "in-pack: {}", stats.num_packed_objects())?;
+                writeln!(out, "packs: {}", stats.packs.len())?;
+                writeln!(out, "size-pack: {}", size(stats.packs_size()))?;
+                writeln!(out, "prune-packable: {}", stats.num_prune_packable)?;
+                writeln!(out, "garbage: {}", stats.garbage.len())?;
+                writeln!(out, "size-garbage: {}", size(stats.garbage_size()))?;
+            } else if human_readable {
+                writeln!(
+                    out,
+                    "{} objects, {}",
+                    stats.num_loose_objects(),
+                    size(stats.loose_size_on_disk())
+                )?;
+            } else {
+                writeln!(
+                    out,
+                    "{} objects, {} kilobytes",
+                    stats.num_loose_objects(),
+                    size(stats.loose_size_on_disk())
+                )?;
+            }
+        }
+        #[cfg(feature = "serde")]
+        OutputFormat::Json => serde_json::to_writer_pretty(out, &stats)?,
+    }
+    Ok(())
+}
+
 pub mod statistics {
     use crate::OutputFormat;
 
@@ -0,0 +1,76 @@
+use std::io;
+
+use anyhow::{bail, Context};
+use gix::bstr::{BString, ByteSlice};
+use gix::reference::log::{Filter, Order, Query};
+
+use crate::OutputFormat;
+
+pub struct Options {
+    pub format: OutputFormat,
+    /// The reference whose log to list, or `HEAD` if unset.
+    pub reference: Option<BString>,
+    pub oldest_first: bool,
+    pub since: Option<String>,
+    pub until: Option<String>,
+    pub message: Option<BString>,
+}
+
+pub fn list(
+    repo: gix::Repository,
+    mut out: impl io::Write,
+    Options {
+        format,
+        reference,
+        oldest_first,
+        since,
+        until,
+        message,
+    }: Options,
+) -> anyhow::Result<()> {
+    if format != OutputFormat::Human {
+        bail!("Only human output is supported right now");
+    }
+    let now = std::time::SystemTime::now();
+    let parse_time = |value: Option<String>| -> anyhow::Result<Option<gix::date::SecondsSinceUnixEpoch>> {
+        value
+            .map(|value| {
+                gix::date::parse(&value, Some(now))
+                    .map(|time| time.seconds)
+                    .with_context(|| format!("Could not parse '{value}' as date"))
+            })
+            .transpose()
+    };
+    let filter = Filter {
+        since: parse_time(since)?,
+        until: parse_time(until)?,
+        message,
+        ..Default::default()
+    };
+    let order = if oldest_first {
+        Order::OldestFirst
+    } else {
+        Order::NewestFirst
+    };
+
+    let reference = match reference {
+        Some(name) => repo.find_reference(name.as_bstr())?,
+        None => repo.find_reference("HEAD")?,
+    };
+    let mut platform = reference.log_iter();
+    let Some(query) = Query::new(&mut platform, &filter, order)? else {
+        bail!("Reference '{}' doesn't have a reflog", reference.name().as_bstr());
+    };
+    for line in query {
+        let line = line?;
+        writeln!(
+            out,
+            "{} {} {} {}",
+            line.new_oid.to_hex_with_len(12),
+            line.signature.time.format(gix::date::time::format::ISO8601),
+            line.previous_oid.to_hex_with_len(12),
+            line.message
+        )?;
+    }
+    Ok(())
+}
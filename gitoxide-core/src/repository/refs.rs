@@ -0,0 +1,36 @@
+use std::io;
+
+use anyhow::bail;
+use gix::refs::store::Format;
+
+use crate::OutputFormat;
+
+pub struct Options {
+    pub format: OutputFormat,
+    /// The format to migrate all references to.
+    pub ref_format: Format,
+    pub dry_run: bool,
+}
+
+pub fn migrate(
+    mut repo: gix::Repository,
+    mut out: impl io::Write,
+    Options {
+        format,
+        ref_format,
+        dry_run,
+    }: Options,
+) -> anyhow::Result<()> {
+    if format != OutputFormat::Human {
+        bail!("Only human output is supported right now");
+    }
+    let outcome = repo.migrate_refs(ref_format, gix::repository::migrate_refs::Options { dry_run })?;
+    writeln!(
+        out,
+        "{action} {refs} references with {entries} reflog entries to {ref_format:?}",
+        action = if dry_run { "Would migrate" } else { "Migrated" },
+        refs = outcome.refs,
+        entries = outcome.reflog_entries,
+    )?;
+    Ok(())
+}
@@ -18,7 +18,11 @@ mod refs_impl {
         pub const PROGRESS_RANGE: std::ops::RangeInclusive<u8> = 1..=2;
 
         pub enum Kind {
-            Remote,
+            Remote {
+                branches: bool,
+                tags: bool,
+                patterns: Vec<BString>,
+            },
             Tracking {
                 ref_specs: Vec<BString>,
                 show_unmapped_remote_refs: bool,
@@ -72,38 +76,57 @@ mod refs_impl {
                 .context("Remote didn't have a URL to connect to")?
                 .to_bstring()
         ));
-        let map = remote
-            .connect(gix::remote::Direction::Fetch)
-            .await?
-            .ref_map(
-                &mut progress,
-                gix::remote::ref_map::Options {
-                    prefix_from_spec_as_filter_on_remote: !matches!(kind, refs::Kind::Remote),
-                    ..Default::default()
-                },
-            )
-            .await?;
-
-        if handshake_info {
-            writeln!(out, "Handshake Information")?;
-            writeln!(out, "\t{:?}", map.handshake)?;
-        }
+        let connection = remote.connect(gix::remote::Direction::Fetch).await?;
         match kind {
-            refs::Kind::Tracking { .. } => print_refmap(
-                &repo,
-                remote.refspecs(gix::remote::Direction::Fetch),
-                map,
-                show_unmapped,
-                out,
-                err,
-            ),
-            refs::Kind::Remote => {
+            refs::Kind::Tracking { .. } => {
+                let map = connection
+                    .ref_map(
+                        &mut progress,
+                        gix::remote::ref_map::Options {
+                            prefix_from_spec_as_filter_on_remote: true,
+                            ..Default::default()
+                        },
+                    )
+                    .await?;
+                if handshake_info {
+                    writeln!(out, "Handshake Information")?;
+                    writeln!(out, "\t{:?}", map.handshake)?;
+                }
+                print_refmap(
+                    &repo,
+                    remote.refspecs(gix::remote::Direction::Fetch),
+                    map,
+                    show_unmapped,
+                    out,
+                    err,
+                )
+            }
+            refs::Kind::Remote {
+                branches,
+                tags,
+                patterns,
+            } => {
+                let list = connection
+                    .list_refs(
+                        &mut progress,
+                        gix::remote::list_refs::Options {
+                            branches,
+                            tags,
+                            patterns,
+                            ..Default::default()
+                        },
+                    )
+                    .await?;
+                if handshake_info {
+                    writeln!(out, "Handshake Information")?;
+                    writeln!(out, "\t{:?}", list.handshake)?;
+                }
                 match format {
-                    OutputFormat::Human => drop(print(out, &map.remote_refs)),
+                    OutputFormat::Human => drop(print(out, &list.refs)),
                     #[cfg(feature = "serde")]
                     OutputFormat::Json => serde_json::to_writer_pretty(
                         out,
-                        &map.remote_refs.into_iter().map(JsonRef::from).collect::<Vec<_>>(),
+                        &list.refs.into_iter().map(JsonRef::from).collect::<Vec<_>>(),
                     )?,
                 };
                 Ok(())
@@ -0,0 +1,161 @@
+use std::{ffi::OsString, path::PathBuf};
+
+use anyhow::{bail, Context};
+use gix::bstr::{BStr, BString, ByteSlice};
+
+use crate::OutputFormat;
+
+/// How to display the objects or references that revision specifications resolve to.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub enum Names {
+    /// Print full object ids.
+    #[default]
+    Full,
+    /// Print object ids abbreviated to `hex_len`, or as configured by `core.abbrev` if `None`.
+    Short { hex_len: Option<usize> },
+    /// Print the full name of the reference that was named, if any.
+    SymbolicFullName,
+    /// Print the shortest unambiguous name of the reference that was named, if any.
+    AbbrevRef,
+}
+
+pub struct Options {
+    pub format: OutputFormat,
+    /// Require exactly one spec that resolves to a single object.
+    pub verify: bool,
+    pub names: Names,
+    pub show_toplevel: bool,
+    pub is_inside_work_tree: bool,
+    pub git_paths: Vec<PathBuf>,
+}
+
+pub fn rev_parse(
+    repo: gix::Repository,
+    specs: Vec<OsString>,
+    mut out: impl std::io::Write,
+    mut err: impl std::io::Write,
+    Options {
+        format,
+        verify,
+        names,
+        show_toplevel,
+        is_inside_work_tree,
+        git_paths,
+    }: Options,
+) -> anyhow::Result<()> {
+    if format != OutputFormat::Human {
+        bail!("Only 'human' format is currently supported");
+    }
+    if is_inside_work_tree {
+        writeln!(out, "{}", repo.is_inside_work_tree()?)?;
+    }
+    if show_toplevel {
+        let work_dir = repo.work_dir().context("this operation must be run in a work tree")?;
+        let work_dir = gix::path::realpath(work_dir)?;
+        writeln!(out, "{}", work_dir.display())?;
+    }
+    for path in git_paths {
+        writeln!(out, "{}", repo.git_path(path).display())?;
+    }
+
+    let verify = verify || matches!(names, Names::Short { .. });
+    if verify && specs.len() != 1 {
+        bail!("Needed a single revision");
+    }
+    for spec in specs {
+        let spec = gix::path::os_str_into_bstr(&spec)?;
+        match names {
+            Names::Full | Names::Short { .. } if verify => {
+                let id = repo
+                    .rev_parse_single(spec)
+                    .with_context(|| format!("Needed a single revision, got '{spec}'"))?;
+                match names {
+                    Names::Short { hex_len: Some(hex_len) } => writeln!(out, "{}", id.shorten_to(hex_len)?)?,
+                    Names::Short { hex_len: None } => writeln!(out, "{}", id.shorten()?)?,
+                    _ => writeln!(out, "{id}")?,
+                }
+            }
+            Names::Full | Names::Short { .. } => writeln!(out, "{}", repo.rev_parse(spec)?.detach())?,
+            Names::SymbolicFullName | Names::AbbrevRef => {
+                if verify {
+                    repo.rev_parse_single(spec)?;
+                }
+                if is_ambiguous(&repo, spec) {
+                    writeln!(err, "error: refname '{spec}' is ambiguous")?;
+                    continue;
+                }
+                let Some(name) = full_ref_name(&repo, spec)? else {
+                    if verify {
+                        bail!("'{spec}' does not name a reference");
+                    }
+                    continue;
+                };
+                let name = match names {
+                    Names::AbbrevRef => abbreviate(&repo, name.as_bstr()),
+                    _ => name,
+                };
+                writeln!(out, "{name}")?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Return the full name of the reference that `spec` names, following symbolic references like `HEAD` to the branch
+/// they point to, or `None` if `spec` isn't naming a reference.
+fn full_ref_name(repo: &gix::Repository, spec: &BStr) -> anyhow::Result<Option<BString>> {
+    let reference = match spec.to_str().ok().map(|name| repo.try_find_reference(name)) {
+        Some(Ok(Some(reference))) => reference.detach(),
+        _ if spec.ends_with(b"}") && spec.contains_str("@{") => match repo.rev_parse(spec)?.first_reference() {
+            Some(reference) => reference.clone(),
+            None => return Ok(None),
+        },
+        _ => return Ok(None),
+    };
+    Ok(Some(match reference.target {
+        gix::refs::Target::Symbolic(target) => target.as_bstr().to_owned(),
+        gix::refs::Target::Object(_) => reference.name.as_bstr().to_owned(),
+    }))
+}
+
+/// Return `true` if `spec` is a partial reference name that matches more than one reference, which `git` refuses to
+/// turn into a full name.
+fn is_ambiguous(repo: &gix::Repository, spec: &BStr) -> bool {
+    let Ok(name) = spec.to_str() else {
+        return false;
+    };
+    let is_pseudo_ref = name.bytes().all(|b| b.is_ascii_uppercase() || b == b'_');
+    let exact = (name.starts_with("refs/") || is_pseudo_ref).then(|| name.to_owned());
+    let candidates = exact.into_iter().chain(
+        ["refs/", "refs/tags/", "refs/heads/", "refs/remotes/"]
+            .iter()
+            .map(|prefix| format!("{prefix}{name}"))
+            .chain(Some(format!("refs/remotes/{name}/HEAD"))),
+    );
+    candidates
+        .filter(|candidate| matches!(repo.try_find_reference(candidate.as_str()), Ok(Some(_))))
+        .count()
+        > 1
+}
+
+/// Shorten `full_name`, but fall back to longer names if the short one would resolve to a different reference.
+fn abbreviate(repo: &gix::Repository, full_name: &BStr) -> BString {
+    let Ok(name) = <&gix::refs::FullNameRef>::try_from(full_name) else {
+        return full_name.to_owned();
+    };
+    let short = name.shorten();
+    let resolves_to_name = |candidate: &BStr| {
+        candidate
+            .to_str()
+            .ok()
+            .and_then(|candidate| repo.try_find_reference(candidate).ok().flatten())
+            .is_some_and(|r| r.name().as_bstr() == full_name)
+    };
+    if resolves_to_name(short) {
+        return short.to_owned();
+    }
+    match full_name.strip_prefix(b"refs/") {
+        Some(without_refs) if resolves_to_name(without_refs.as_bstr()) => without_refs.as_bstr().to_owned(),
+        _ => full_name.to_owned(),
+    }
+}
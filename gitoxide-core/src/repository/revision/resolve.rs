@@ -28,8 +28,7 @@ pub(crate) mod function {
     use gix::revision::Spec;
 
     use super::Options;
-    use crate::repository::cat::display_object;
-    use crate::repository::revision::resolve::BlobFormat;
+    use crate::repository::cat::{blob_cache, display_object};
     use crate::{repository::revision, OutputFormat};
 
     pub fn resolve(
@@ -46,22 +45,7 @@ pub(crate) mod function {
         }: Options,
     ) -> anyhow::Result<()> {
         repo.object_cache_size_if_unset(1024 * 1024);
-        let mut cache = (!matches!(blob_format, BlobFormat::Git))
-            .then(|| {
-                repo.diff_resource_cache(
-                    match blob_format {
-                        BlobFormat::Git => {
-                            unreachable!("checked before")
-                        }
-                        BlobFormat::Worktree | BlobFormat::Diff => {
-                            gix::diff::blob::pipeline::Mode::ToWorktreeAndBinaryToText
-                        }
-                        BlobFormat::DiffOrGit => gix::diff::blob::pipeline::Mode::ToGitUnlessBinaryToTextIsPresent,
-                    },
-                    Default::default(),
-                )
-            })
-            .transpose()?;
+        let mut cache = blob_cache(&repo, blob_format)?;
 
         match format {
             OutputFormat::Human => {
@@ -72,7 +56,14 @@ pub(crate) mod function {
                     let spec = gix::path::os_str_into_bstr(&spec)?;
                     let spec = repo.rev_parse(spec)?;
                     if cat_file {
-                        return display_object(&repo, spec, tree_mode, cache.as_mut().map(|c| (blob_format, c)), out);
+                        return display_object(
+                            &repo,
+                            spec,
+                            None,
+                            tree_mode,
+                            cache.as_mut().map(|c| (blob_format, c)),
+                            out,
+                        );
                     }
                     if let Some(r) = spec.first_reference().filter(|_| show_reference) {
                         writeln!(out, "{}", r.name)?;
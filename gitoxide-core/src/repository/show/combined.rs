@@ -0,0 +1,495 @@
+//! A dense combined diff of merge commits, as shown by `git show --cc`, following `combine-diff.c` closely
+//! so hunks and their headers are the same.
+use std::{io::Write, ops::Range};
+
+use anyhow::{bail, Result};
+use gix::bstr::BStr;
+use gix::commit::diff_to_parents::Changes;
+use gix::diff::blob::{intern::InternedInput, sources::byte_lines_with_terminator};
+use gix::diff::tree_with_rewrites::Change;
+
+use super::{abbreviate, diffable_content, Side, CONTEXT_LINES};
+
+/// `git` stores one bit per parent, which limits the amount of parents it can show.
+const MAX_PARENTS: usize = 63;
+/// The amount of bytes of a line preceding a hunk that are considered for showing in its header.
+const MAX_HUNK_COMMENT_LEN: usize = 40;
+
+/// A line of the merge result, or the end of it.
+#[derive(Default)]
+struct Line {
+    /// A bit for each parent that doesn't have this line.
+    added_in: u64,
+    /// Lines of parents that don't exist in the result, and that would be right before this line.
+    lost: Vec<LostLine>,
+    /// If `true`, the line is shown as part of a hunk.
+    shown: bool,
+    /// If `true`, the line is only shown as context before a hunk, which is why the `lost` lines are not shown.
+    no_pre_delete: bool,
+}
+
+struct LostLine {
+    /// The line without its terminator.
+    line: Vec<u8>,
+    /// A bit for each parent that had this line.
+    parents: u64,
+}
+
+/// Write the combined diff of all paths in `diffs`, the changes of a merge commit against each of its parents,
+/// to `out`, leaving out files whose changes are all the same as the changes of one of the parents.
+pub(super) fn write(
+    repo: &gix::Repository,
+    resource_cache: &mut gix::diff::blob::Platform,
+    out: &mut Vec<u8>,
+    diffs: &[Changes],
+) -> Result<()> {
+    if diffs.len() > MAX_PARENTS {
+        bail!(
+            "Cannot show combined diffs of commits with more than {MAX_PARENTS} parents, got {}",
+            diffs.len()
+        );
+    }
+    for change in &diffs[0].changes {
+        let location = change.location();
+        let result = match change {
+            Change::Deletion { .. } => None,
+            _ => {
+                let (mode, id) = change.entry_mode_and_id();
+                Some(Side { location, id, mode })
+            }
+        };
+        let mut parents = Vec::with_capacity(diffs.len());
+        for diff in diffs {
+            let change = diff
+                .changes
+                .iter()
+                .find(|change| change.location() == location)
+                .expect("only paths that changed against all parents are kept");
+            parents.push(match change {
+                Change::Addition { .. } => None,
+                _ => {
+                    let (mode, id) = change.source_entry_mode_and_id();
+                    Some(Side {
+                        location: change.source_location(),
+                        id,
+                        mode,
+                    })
+                }
+            });
+        }
+        if result
+            .iter()
+            .chain(parents.iter().flatten())
+            .any(|side| side.mode.is_tree())
+        {
+            continue;
+        }
+        write_path(repo, resource_cache, out, location, result, &parents)?;
+    }
+    Ok(())
+}
+
+fn write_path(
+    repo: &gix::Repository,
+    resource_cache: &mut gix::diff::blob::Platform,
+    out: &mut Vec<u8>,
+    location: &BStr,
+    result: Option<Side<'_>>,
+    parents: &[Option<Side<'_>>],
+) -> Result<()> {
+    let mut result_lines = Vec::new();
+    let mut lines = Vec::new();
+    let mut is_binary = false;
+    for (parent_idx, parent) in parents.iter().enumerate() {
+        let Some(([old, new], algorithm)) = diffable_content(repo, resource_cache, *parent, result)? else {
+            is_binary = true;
+            break;
+        };
+        if parent_idx == 0 {
+            result_lines = new
+                .split_inclusive(|b| *b == b'\n')
+                .map(|line| without_terminator(line).to_vec())
+                .collect();
+            lines.resize_with(result_lines.len() + 1, Line::default);
+        }
+
+        let input = InternedInput::new(byte_lines_with_terminator(&old), byte_lines_with_terminator(&new));
+        let mut hunks = Vec::new();
+        gix::diff::blob::diff(algorithm, &input, |before: Range<u32>, after: Range<u32>| {
+            hunks.push((before, after));
+        });
+        let parent = 1 << parent_idx;
+        let mut lost = Vec::new();
+        let mut lost_before = None;
+        for (before, after) in hunks {
+            for line in &mut lines[after.start as usize..after.end as usize] {
+                line.added_in |= parent;
+            }
+            if before.is_empty() {
+                continue;
+            }
+            if lost_before != Some(after.start) {
+                if let Some(idx) = lost_before {
+                    coalesce(&mut lines[idx as usize].lost, std::mem::take(&mut lost), parent);
+                }
+                lost_before = Some(after.start);
+            }
+            lost.extend(
+                input.before[before.start as usize..before.end as usize]
+                    .iter()
+                    .map(|token| without_terminator(input.interner[*token]).to_vec()),
+            );
+        }
+        if let Some(idx) = lost_before {
+            coalesce(&mut lines[idx as usize].lost, lost, parent);
+        }
+    }
+
+    let mode = |side: Option<Side<'_>>| side.map_or(0, |side| side.mode.0);
+    let mode_differs = parents.iter().any(|parent| mode(*parent) != mode(result));
+    let all_parents = (1 << parents.len()) - 1;
+    let show_hunks = !is_binary && make_hunks(&mut lines, all_parents);
+    if !(is_binary || show_hunks || mode_differs) {
+        return Ok(());
+    }
+
+    writeln!(out, "diff --cc {location}")?;
+    let ids: Vec<_> = parents
+        .iter()
+        .chain(Some(&result))
+        .map(|side| side.map(|side| side.id))
+        .collect();
+    let abbreviations = abbreviate(repo, &ids);
+    let (result_abbreviation, parent_abbreviations) = abbreviations.split_last().expect("result is last");
+    writeln!(out, "index {}..{result_abbreviation}", parent_abbreviations.join(","))?;
+    let deleted = result.is_none();
+    let added = mode_differs && !deleted && parents.iter().all(Option::is_none);
+    if mode_differs {
+        if added {
+            write!(out, "new file mode {:06o}", mode(result))?;
+        } else {
+            if deleted {
+                write!(out, "deleted file ")?;
+            }
+            let modes: Vec<_> = parents.iter().map(|parent| format!("{:06o}", mode(*parent))).collect();
+            write!(out, "mode {}", modes.join(","))?;
+            if let Some(result) = result {
+                write!(out, "..{:06o}", result.mode.0)?;
+            }
+        }
+        writeln!(out)?;
+    }
+    if is_binary {
+        writeln!(out, "Binary files differ")?;
+        return Ok(());
+    }
+    if added {
+        writeln!(out, "--- /dev/null")?;
+    } else {
+        writeln!(out, "--- a/{location}")?;
+    }
+    if deleted {
+        writeln!(out, "+++ /dev/null")?;
+    } else {
+        writeln!(out, "+++ b/{location}")?;
+        write_hunks(out, &lines, &result_lines, parents.len())?;
+    }
+    Ok(())
+}
+
+fn without_terminator(line: &[u8]) -> &[u8] {
+    line.strip_suffix(b"\n").unwrap_or(line)
+}
+
+/// Merge the `new` lines lost by `parent` into the `existing` ones lost by other parents at the same position,
+/// so that equal lines are shown only once.
+fn coalesce(existing: &mut Vec<LostLine>, new: Vec<Vec<u8>>, parent: u64) {
+    #[derive(Copy, Clone)]
+    enum Direction {
+        Match,
+        Existing,
+        New,
+    }
+
+    if existing.is_empty() {
+        existing.extend(new.into_iter().map(|line| LostLine { line, parents: parent }));
+        return;
+    }
+    let (num_existing, num_new) = (existing.len(), new.len());
+    let mut lcs = vec![vec![0usize; num_new + 1]; num_existing + 1];
+    let mut directions = vec![vec![Direction::New; num_new + 1]; num_existing + 1];
+    for row in directions.iter_mut().skip(1) {
+        row[0] = Direction::Existing;
+    }
+    for i in 1..=num_existing {
+        for j in 1..=num_new {
+            if existing[i - 1].line == new[j - 1] {
+                lcs[i][j] = lcs[i - 1][j - 1] + 1;
+                directions[i][j] = Direction::Match;
+            } else if lcs[i][j - 1] >= lcs[i - 1][j] {
+                lcs[i][j] = lcs[i][j - 1];
+                directions[i][j] = Direction::New;
+            } else {
+                lcs[i][j] = lcs[i - 1][j];
+                directions[i][j] = Direction::Existing;
+            }
+        }
+    }
+
+    let mut existing_lines = std::mem::take(existing).into_iter().rev();
+    let mut new_lines = new.into_iter().rev();
+    let (mut i, mut j) = (num_existing, num_new);
+    let mut merged = Vec::with_capacity(num_existing + num_new);
+    while i > 0 || j > 0 {
+        match directions[i][j] {
+            Direction::Match => {
+                let mut line = existing_lines.next().expect("in bounds");
+                new_lines.next();
+                line.parents |= parent;
+                merged.push(line);
+                i -= 1;
+                j -= 1;
+            }
+            Direction::New => {
+                merged.push(LostLine {
+                    line: new_lines.next().expect("in bounds"),
+                    parents: parent,
+                });
+                j -= 1;
+            }
+            Direction::Existing => {
+                merged.push(existing_lines.next().expect("in bounds"));
+                i -= 1;
+            }
+        }
+    }
+    merged.reverse();
+    *existing = merged;
+}
+
+fn is_interesting(line: &Line) -> bool {
+    line.added_in != 0 || !line.lost.is_empty()
+}
+
+/// Return the index of the first line at or after `idx` that is `shown` or not, or the amount of lines.
+fn find_next(lines: &[Line], mut idx: usize, shown: bool) -> usize {
+    while idx < lines.len() && lines[idx].shown != shown {
+        idx += 1;
+    }
+    idx
+}
+
+/// `end` is the first line after a hunk starting at `start`. If the last line of the hunk only removed lines,
+/// it's shown as context of the removal already and can be used as trailing context.
+fn adjust_hunk_tail(lines: &[Line], start: usize, end: usize) -> usize {
+    if start < end && lines[end - 1].added_in == 0 {
+        end - 1
+    } else {
+        end
+    }
+}
+
+/// Mark all lines to show, and return `true` if there is anything to show.
+///
+/// Hunks are only interesting if the result differs from all parents, and if they are not the same as the changes
+/// of just one of the parents.
+fn make_hunks(lines: &mut [Line], all_parents: u64) -> bool {
+    for line in lines.iter_mut() {
+        line.shown = is_interesting(line);
+    }
+
+    let last = lines.len() - 1;
+    let mut idx = 0;
+    while idx <= last {
+        while idx <= last && !lines[idx].shown {
+            idx += 1;
+        }
+        if idx > last {
+            break;
+        }
+        let hunk_start = idx;
+        let mut end = idx + 1;
+        while end <= last {
+            if !lines[end].shown {
+                // See if there is another interesting line within reach of the context.
+                let mut lookahead = (adjust_hunk_tail(lines, hunk_start, end) + CONTEXT_LINES as usize).min(last + 1);
+                let mut continues = false;
+                while lookahead > 0 {
+                    lookahead -= 1;
+                    if lookahead < end {
+                        break;
+                    }
+                    if lines[lookahead].shown {
+                        continues = true;
+                        break;
+                    }
+                }
+                if !continues {
+                    break;
+                }
+                end = lookahead;
+            }
+            end += 1;
+        }
+
+        // The hunk is uninteresting if all changes are made against the same parents, unless it's all of them.
+        let mut same_parents = 0;
+        let mut has_interesting = false;
+        'hunk: for line in &lines[idx..end] {
+            for parents in Some(line.added_in)
+                .filter(|parents| *parents != 0)
+                .into_iter()
+                .chain(line.lost.iter().map(|lost| lost.parents))
+            {
+                if same_parents == 0 {
+                    same_parents = parents;
+                } else if same_parents != parents {
+                    has_interesting = true;
+                    break 'hunk;
+                }
+            }
+        }
+        if !has_interesting && same_parents != all_parents {
+            for line in &mut lines[hunk_start..end] {
+                line.shown = false;
+            }
+        }
+        idx = end;
+    }
+    give_context(lines)
+}
+
+/// Show the context lines around all lines that are shown, and merge hunks which are close to each other.
+fn give_context(lines: &mut [Line]) -> bool {
+    let context = CONTEXT_LINES as usize;
+    let last = lines.len() - 1;
+    let mut idx = find_next(lines, 0, true);
+    if idx > last {
+        return false;
+    }
+    while idx <= last {
+        for line in &mut lines[idx.saturating_sub(context)..idx] {
+            if !line.shown {
+                line.no_pre_delete = true;
+            }
+            line.shown = true;
+        }
+        loop {
+            let end = find_next(lines, idx, false);
+            if end > last {
+                return true;
+            }
+            let next = find_next(lines, end, true);
+            let end = adjust_hunk_tail(lines, idx, end);
+            if next < end + context {
+                for line in &mut lines[end..next] {
+                    line.shown = true;
+                }
+                idx = next;
+                continue;
+            }
+            idx = next;
+            for line in &mut lines[end..(end + context).min(last + 1)] {
+                line.shown = true;
+            }
+            break;
+        }
+    }
+    true
+}
+
+/// Write all shown `lines` as hunks, where `result_lines` are the lines of the merge result.
+fn write_hunks(out: &mut Vec<u8>, lines: &[Line], result_lines: &[Vec<u8>], num_parents: usize) -> Result<()> {
+    let last = lines.len() - 1;
+    // The one-based line number in each parent for each line, including one past the last line.
+    let parent_line_numbers: Vec<Vec<usize>> = (0..num_parents)
+        .map(|parent_idx| {
+            let parent = 1 << parent_idx;
+            let mut line_number = 1;
+            let mut numbers = Vec::with_capacity(lines.len() + 1);
+            for (idx, line) in lines.iter().enumerate() {
+                numbers.push(line_number);
+                line_number += line.lost.iter().filter(|lost| lost.parents & parent != 0).count();
+                if idx < last && line.added_in & parent == 0 {
+                    line_number += 1;
+                }
+            }
+            numbers.push(line_number);
+            numbers
+        })
+        .collect();
+
+    let marker = "@".repeat(num_parents + 1);
+    let mut idx = 0;
+    loop {
+        let mut hunk_comment = None;
+        while idx <= last && !lines[idx].shown {
+            if matches!(
+                result_lines.get(idx).and_then(|line| line.first()),
+                Some(b) if b.is_ascii_alphabetic() || *b == b'_' || *b == b'$'
+            ) {
+                hunk_comment = Some(&result_lines[idx]);
+            }
+            idx += 1;
+        }
+        if idx > last {
+            break;
+        }
+        let mut end = idx + 1;
+        while end <= last && lines[end].shown {
+            end += 1;
+        }
+        let mut num_result_lines = end - idx;
+        if end > last {
+            num_result_lines -= 1;
+        }
+
+        write!(out, "{marker}")?;
+        for line_numbers in &parent_line_numbers {
+            let start = line_numbers[idx];
+            write!(out, " -{start},{}", line_numbers[end] - start)?;
+        }
+        write!(out, " +{},{num_result_lines} {marker}", idx + 1)?;
+        if let Some(comment) = hunk_comment {
+            // Like `git`, leave out the last non-whitespace character.
+            let comment = &comment[..comment.len().min(MAX_HUNK_COMMENT_LEN)];
+            let comment_end = comment.iter().rposition(|b| !b.is_ascii_whitespace()).unwrap_or(0);
+            if comment_end > 0 {
+                out.push(b' ');
+                out.extend_from_slice(&comment[..comment_end]);
+            }
+        }
+        writeln!(out)?;
+
+        while idx < end {
+            let line = &lines[idx];
+            idx += 1;
+            if !line.no_pre_delete {
+                for lost in &line.lost {
+                    for parent_idx in 0..num_parents {
+                        out.push(if lost.parents & (1 << parent_idx) != 0 {
+                            b'-'
+                        } else {
+                            b' '
+                        });
+                    }
+                    out.extend_from_slice(&lost.line);
+                    out.push(b'\n');
+                }
+            }
+            if idx > last {
+                break;
+            }
+            for parent_idx in 0..num_parents {
+                out.push(if line.added_in & (1 << parent_idx) != 0 {
+                    b'+'
+                } else {
+                    b' '
+                });
+            }
+            out.extend_from_slice(&result_lines[idx - 1]);
+            out.push(b'\n');
+        }
+    }
+    Ok(())
+}
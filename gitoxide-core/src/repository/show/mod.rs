@@ -0,0 +1,340 @@
+use anyhow::{bail, Result};
+use gix::bstr::{BStr, BString, ByteSlice, ByteVec};
+use gix::commit::diff_to_parents::Merges;
+use gix::diff::blob::{intern::InternedInput, sources::byte_lines_with_terminator, Algorithm, UnifiedDiff};
+use gix::diff::tree_with_rewrites::Change;
+use gix::objs::tree::EntryMode;
+use gix::prelude::ObjectIdExt;
+
+mod combined;
+
+/// The amount of unchanged lines to show around changed lines in a hunk.
+const CONTEXT_LINES: u32 = 3;
+
+/// How to display the metadata of the commit.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub enum Format {
+    /// Show the id, parents of merge commits, author, date and the full message, like `git show --format=medium`.
+    #[default]
+    Medium,
+    /// Show the abbreviated id and the title of the message on a single line, like `git show --oneline`.
+    Oneline,
+}
+
+pub struct Options {
+    pub format: Format,
+    /// How to diff merge commits.
+    pub merges: Merges,
+}
+
+/// Print the commit at `rev_spec` along with a patch of the changes it introduced to `out`, similar to `git show`.
+///
+/// Merge commits are shown with a combined diff like `git show --cc` by default.
+pub fn show(
+    mut repo: gix::Repository,
+    rev_spec: BString,
+    mut out: impl std::io::Write,
+    Options { format, merges }: Options,
+) -> Result<()> {
+    repo.object_cache_size_if_unset(repo.compute_object_cache_size_for_tree_diffs(&**repo.index_or_empty()?));
+    let commit = repo
+        .rev_parse_single(format!("{rev_spec}^{{commit}}").as_str())?
+        .object()?
+        .into_commit();
+    let mailmap = repo.open_mailmap();
+    let mut resource_cache = repo.diff_resource_cache(
+        gix::diff::blob::pipeline::Mode::ToGitUnlessBinaryToTextIsPresent,
+        Default::default(),
+    )?;
+    resource_cache.options.skip_internal_diff_if_external_is_configured = false;
+
+    let mut diffs = commit.diff_to_parents(merges)?;
+    for diff in &mut diffs {
+        // Additions, deletions and rewrites are tracked last, but `git` shows them in order.
+        diff.changes.sort_by(|a, b| a.location().cmp(b.location()));
+    }
+    if merges == Merges::Combined && diffs.len() > 1 {
+        write_header(&mut out, &commit, format, &mailmap, None)?;
+        let mut patch = Vec::new();
+        combined::write(&repo, &mut resource_cache, &mut patch, &diffs)?;
+        if !patch.is_empty() {
+            // `git` separates the combined diff from the commit even if it's shown on a single line.
+            writeln!(out)?;
+            out.write_all(&patch)?;
+        }
+        return Ok(());
+    }
+
+    let show_parent = diffs.len() > 1;
+    let mut shown = false;
+    for diff in diffs.into_iter().filter(|diff| !diff.changes.is_empty()) {
+        if shown && format == Format::Medium {
+            writeln!(out)?;
+        }
+        write_header(
+            &mut out,
+            &commit,
+            format,
+            &mailmap,
+            diff.parent_id.filter(|_| show_parent).map(|id| id.attach(&repo)),
+        )?;
+        if format == Format::Medium {
+            writeln!(out)?;
+        }
+        for change in &diff.changes {
+            write_patch(&repo, &mut resource_cache, &mut out, change)?;
+        }
+        shown = true;
+    }
+    if !shown {
+        write_header(&mut out, &commit, format, &mailmap, None)?;
+    }
+    Ok(())
+}
+
+/// Write the information about `commit` in `format`, and mention `from_parent` if the following patch is against it.
+fn write_header(
+    out: &mut dyn std::io::Write,
+    commit: &gix::Commit<'_>,
+    format: Format,
+    mailmap: &gix::mailmap::Snapshot,
+    from_parent: Option<gix::Id<'_>>,
+) -> Result<()> {
+    match format {
+        Format::Medium => {
+            write!(out, "commit {}", commit.id)?;
+            if let Some(parent_id) = from_parent {
+                write!(out, " (from {parent_id})")?;
+            }
+            writeln!(out)?;
+            let parent_ids: Vec<_> = commit.parent_ids().collect();
+            if parent_ids.len() > 1 {
+                write!(out, "Merge:")?;
+                for parent_id in parent_ids {
+                    write!(out, " {}", parent_id.shorten_or_id())?;
+                }
+                writeln!(out)?;
+            }
+            let author = mailmap.resolve(commit.author()?);
+            writeln!(out, "Author: {} <{}>", author.name, author.email)?;
+            writeln!(
+                out,
+                "Date:   {}\n",
+                author.time.format(gix::date::time::format::DEFAULT)
+            )?;
+
+            let mut message = BString::default();
+            for line in commit.message_raw()?.lines().skip_while(|line| line.trim().is_empty()) {
+                message.push_str("    ");
+                push_with_expanded_tabs(&mut message, line.as_bstr());
+                message.push(b'\n');
+            }
+            out.write_all(message.trim_end())?;
+            writeln!(out)?;
+        }
+        Format::Oneline => {
+            write!(out, "{}", commit.id().shorten_or_id())?;
+            if let Some(parent_id) = from_parent {
+                write!(out, " (from {})", parent_id.shorten_or_id())?;
+            }
+            writeln!(out, " {}", commit.message()?.summary())?;
+        }
+    }
+    Ok(())
+}
+
+/// Append `line` to `out` with tabs expanded to the next multiple of 8 characters, as `git` does
+/// when indenting commit messages.
+fn push_with_expanded_tabs(out: &mut BString, line: &BStr) {
+    const TAB_WIDTH: usize = 8;
+    let mut column = 0;
+    for (start, end, char) in line.char_indices() {
+        if char == '\t' {
+            let spaces = TAB_WIDTH - column % TAB_WIDTH;
+            out.extend_from_slice(&b"        "[..spaces]);
+            column += spaces;
+        } else {
+            out.extend_from_slice(&line[start..end]);
+            column += 1;
+        }
+    }
+}
+
+/// One side of a file-level diff.
+#[derive(Debug, Copy, Clone)]
+struct Side<'a> {
+    location: &'a BStr,
+    id: &'a gix::oid,
+    mode: EntryMode,
+}
+
+/// Write the patch for `change` to `out` just like `git diff` would, using `resource_cache` to obtain diffable content.
+fn write_patch(
+    repo: &gix::Repository,
+    resource_cache: &mut gix::diff::blob::Platform,
+    out: &mut dyn std::io::Write,
+    change: &Change,
+) -> Result<()> {
+    if change.entry_mode().is_tree() {
+        return Ok(());
+    }
+    let (source_mode, source_id) = change.source_entry_mode_and_id();
+    let (mode, id) = change.entry_mode_and_id();
+    let source = Side {
+        location: change.source_location(),
+        id: source_id,
+        mode: source_mode,
+    };
+    let destination = Side {
+        location: change.location(),
+        id,
+        mode,
+    };
+    match change {
+        Change::Addition { .. } => write_file_patch(repo, resource_cache, out, None, Some(destination), None),
+        Change::Deletion { .. } => write_file_patch(repo, resource_cache, out, Some(source), None, None),
+        Change::Modification { .. } if is_type_change(source_mode, mode) => {
+            write_file_patch(repo, resource_cache, out, Some(source), None, None)?;
+            write_file_patch(repo, resource_cache, out, None, Some(destination), None)
+        }
+        Change::Modification { .. } => {
+            write_file_patch(repo, resource_cache, out, Some(source), Some(destination), None)
+        }
+        Change::Rewrite { diff, copy, .. } => {
+            let similarity = diff.map_or(100, |diff| (diff.similarity * 100.0) as u32);
+            write_file_patch(
+                repo,
+                resource_cache,
+                out,
+                Some(source),
+                Some(destination),
+                Some((similarity, *copy)),
+            )
+        }
+    }
+}
+
+/// Return `true` if `a` and `b` are of a different type, which `git` shows as deletion and addition.
+fn is_type_change(a: EntryMode, b: EntryMode) -> bool {
+    a.is_link() != b.is_link() || a.is_commit() != b.is_commit()
+}
+
+/// Write the patch between `old` and `new` to `out`, where `rewrite` is the similarity in percent and
+/// whether it's a copy if `new` was renamed or copied from `old`.
+fn write_file_patch(
+    repo: &gix::Repository,
+    resource_cache: &mut gix::diff::blob::Platform,
+    out: &mut dyn std::io::Write,
+    old: Option<Side<'_>>,
+    new: Option<Side<'_>>,
+    rewrite: Option<(u32, bool)>,
+) -> Result<()> {
+    let (a, b) = match (old, new) {
+        (Some(old), Some(new)) => (old.location, new.location),
+        (Some(side), None) | (None, Some(side)) => (side.location, side.location),
+        (None, None) => unreachable!("there is always at least one side"),
+    };
+    writeln!(out, "diff --git a/{a} b/{b}")?;
+    match (old, new) {
+        (None, Some(new)) => writeln!(out, "new file mode {:06o}", new.mode.0)?,
+        (Some(old), None) => writeln!(out, "deleted file mode {:06o}", old.mode.0)?,
+        (Some(old), Some(new)) if old.mode != new.mode => {
+            writeln!(out, "old mode {:06o}\nnew mode {:06o}", old.mode.0, new.mode.0)?;
+        }
+        _ => {}
+    }
+    if let Some((similarity, copy)) = rewrite {
+        let kind = if copy { "copy" } else { "rename" };
+        writeln!(out, "similarity index {similarity}%\n{kind} from {a}\n{kind} to {b}")?;
+    }
+    let (old_id, new_id) = (old.map(|side| side.id), new.map(|side| side.id));
+    if old_id == new_id {
+        return Ok(());
+    }
+
+    let abbreviations = abbreviate(repo, &[old_id, new_id]);
+    write!(out, "index {}..{}", abbreviations[0], abbreviations[1])?;
+    match (old, new) {
+        (Some(old), Some(new)) if old.mode == new.mode => writeln!(out, " {:06o}", new.mode.0)?,
+        _ => writeln!(out)?,
+    }
+    let a = old.map_or_else(|| "/dev/null".into(), |_| format!("a/{a}"));
+    let b = new.map_or_else(|| "/dev/null".into(), |_| format!("b/{b}"));
+    match diffable_content(repo, resource_cache, old, new)? {
+        None => {
+            if resource_cache.compare_binary(&repo.objects)?.differs {
+                writeln!(out, "Binary files {a} and {b} differ")?;
+            }
+        }
+        Some(([old, new], algorithm)) => {
+            let input = InternedInput::new(byte_lines_with_terminator(&old), byte_lines_with_terminator(&new));
+            let hunks = gix::diff::blob::diff(algorithm, &input, UnifiedDiff::new(&input, CONTEXT_LINES));
+            if !hunks.is_empty() {
+                writeln!(out, "--- {a}\n+++ {b}")?;
+                out.write_all(&hunks)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Abbreviate all `ids` like `git` does, showing missing ones as zeroes of the same length.
+fn abbreviate(repo: &gix::Repository, ids: &[Option<&gix::oid>]) -> Vec<String> {
+    let abbreviated: Vec<_> = ids
+        .iter()
+        .map(|id| id.map(|id| id.to_owned().attach(repo).shorten_or_id().to_string()))
+        .collect();
+    let len = abbreviated.iter().flatten().map(String::len).next().unwrap_or(7);
+    abbreviated
+        .into_iter()
+        .map(|id| id.unwrap_or_else(|| "0".repeat(len)))
+        .collect()
+}
+
+/// Obtain the content of `old` and `new` as they would be diffed by `git`, along with the algorithm to use for the diff,
+/// or `None` if one of them is binary.
+/// Missing sides are empty, and submodules are represented by the commit they point to.
+fn diffable_content(
+    repo: &gix::Repository,
+    resource_cache: &mut gix::diff::blob::Platform,
+    old: Option<Side<'_>>,
+    new: Option<Side<'_>>,
+) -> Result<Option<([Vec<u8>; 2], Algorithm)>> {
+    use gix::diff::blob::{platform::prepare_diff::Operation, ResourceKind};
+
+    let is_blob = |side: &Side<'_>| !side.mode.is_commit();
+    let blob_mode = old.filter(is_blob).or(new.filter(is_blob)).map(|side| side.mode.kind());
+    let mut content = [Vec::new(), Vec::new()];
+    let algorithm = match blob_mode {
+        None => resource_cache.options.algorithm.unwrap_or_default(),
+        Some(blob_mode) => {
+            for (side, kind) in [(old, ResourceKind::OldOrSource), (new, ResourceKind::NewOrDestination)] {
+                let (id, mode, location) = match side.filter(is_blob) {
+                    Some(side) => (side.id.to_owned(), side.mode.kind(), side.location),
+                    None => (
+                        repo.object_hash().null(),
+                        blob_mode,
+                        old.or(new).expect("one side is set").location,
+                    ),
+                };
+                resource_cache.set_resource(id, mode, location, kind, &repo.objects)?;
+            }
+            let prep = resource_cache.prepare_diff()?;
+            let algorithm = match prep.operation {
+                Operation::InternalDiff { algorithm } => algorithm,
+                Operation::SourceOrDestinationIsBinary => return Ok(None),
+                Operation::ExternalCommand { .. } => bail!("BUG: external diff commands were disabled"),
+            };
+            for (buf, resource) in content.iter_mut().zip([prep.old, prep.new]) {
+                buf.extend_from_slice(resource.data.as_slice().unwrap_or_default());
+            }
+            algorithm
+        }
+    };
+    for (buf, side) in content.iter_mut().zip([old, new]) {
+        if let Some(side) = side.filter(|side| side.mode.is_commit()) {
+            *buf = format!("Subproject commit {}\n", side.id).into_bytes();
+        }
+    }
+    Ok(Some((content, algorithm)))
+}
@@ -0,0 +1,390 @@
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::io;
+use std::path::Path;
+use std::sync::atomic::Ordering;
+
+use gix::{odb::HeaderExt, Count, NestedProgress, Progress};
+
+use crate::OutputFormat;
+
+pub const PROGRESS_RANGE: std::ops::RangeInclusive<u8> = 0..=2;
+
+#[derive(Debug, Copy, Clone)]
+pub struct Options {
+    pub format: OutputFormat,
+    pub thread_limit: Option<usize>,
+    /// The amount of biggest blobs and trees to report.
+    pub top: usize,
+}
+
+/// Counts and sizes of objects of a single kind, as decompressed size.
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[derive(Debug, Default, Clone, Copy)]
+pub struct KindStatistics {
+    pub count: usize,
+    pub size: u64,
+}
+
+/// An object along with its decompressed size.
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct SizedObject {
+    pub size: u64,
+    pub id: gix::ObjectId,
+}
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[derive(Debug, Default, Clone)]
+pub struct Objects {
+    pub commits: KindStatistics,
+    pub trees: KindStatistics,
+    pub blobs: KindStatistics,
+    pub tags: KindStatistics,
+    /// The amount of objects only stored as loose objects.
+    pub loose: usize,
+    /// The amount of objects stored in packs, even if they are also stored as loose objects.
+    pub packed: usize,
+    /// The biggest blobs, biggest first.
+    pub biggest_blobs: Vec<SizedObject>,
+    /// The biggest trees, biggest first.
+    pub biggest_trees: Vec<SizedObject>,
+}
+
+/// The space the object database takes on disk.
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Disk {
+    pub loose_objects_size: u64,
+    pub packs: usize,
+    pub packs_size: u64,
+    pub pack_indices_size: u64,
+}
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[derive(Debug, Default, Clone, Copy)]
+pub struct References {
+    pub local_branches: usize,
+    pub remote_branches: usize,
+    pub tags: usize,
+    pub other: usize,
+}
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Indices {
+    /// `true` if there is a single commit-graph file or a commit-graph chain.
+    pub commit_graph: bool,
+    /// `true` if there is a multi-pack-index.
+    pub multi_pack_index: bool,
+}
+
+/// Statistics about a repository that help to judge its health, similar to what `git-sizer` provides.
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[derive(Debug, Default, Clone)]
+pub struct Statistics {
+    pub objects: Objects,
+    pub disk: Disk,
+    pub references: References,
+    pub indices: Indices,
+}
+
+pub fn stats(
+    repo: gix::Repository,
+    mut progress: impl NestedProgress,
+    out: impl io::Write,
+    Options {
+        format,
+        thread_limit,
+        top,
+    }: Options,
+) -> anyhow::Result<Statistics> {
+    let objects = {
+        let mut progress = progress.add_child("counting objects");
+        progress.init(None, gix::progress::count("objects"));
+        let start = std::time::Instant::now();
+        let objects = count_objects(&repo, &progress, thread_limit, top)?;
+        progress.show_throughput(start);
+        objects
+    };
+    let references = {
+        let mut progress = progress.add_child("counting references");
+        progress.init(None, gix::progress::count("references"));
+        let mut refs = References::default();
+        for reference in repo.references()?.all()? {
+            let reference = reference.map_err(|err| anyhow::anyhow!(err))?;
+            match reference.name().category() {
+                Some(gix::refs::Category::LocalBranch) => refs.local_branches += 1,
+                Some(gix::refs::Category::RemoteBranch) => refs.remote_branches += 1,
+                Some(gix::refs::Category::Tag) => refs.tags += 1,
+                _ => refs.other += 1,
+            }
+            progress.inc();
+        }
+        refs
+    };
+
+    let objects_dir = repo.objects.store_ref().path();
+    let stats = Statistics {
+        objects,
+        disk: disk_usage(objects_dir)?,
+        references,
+        indices: Indices {
+            commit_graph: objects_dir.join("info").join("commit-graph").is_file()
+                || objects_dir
+                    .join("info")
+                    .join("commit-graphs")
+                    .join("commit-graph-chain")
+                    .is_file(),
+            multi_pack_index: objects_dir.join("pack").join("multi-pack-index").is_file(),
+        },
+    };
+
+    match format {
+        OutputFormat::Human => write_human(&stats, out)?,
+        #[cfg(feature = "serde")]
+        OutputFormat::Json => serde_json::to_writer_pretty(out, &stats)?,
+    }
+    Ok(stats)
+}
+
+fn count_objects(
+    repo: &gix::Repository,
+    progress: &impl Progress,
+    thread_limit: Option<usize>,
+    top: usize,
+) -> anyhow::Result<Objects> {
+    #[derive(Default)]
+    struct Tally {
+        objects: Objects,
+        biggest_blobs: BinaryHeap<Reverse<SizedObject>>,
+        biggest_trees: BinaryHeap<Reverse<SizedObject>>,
+    }
+
+    impl Tally {
+        fn consume(&mut self, id: gix::ObjectId, header: gix::odb::find::Header, top: usize) {
+            use gix::object::Kind::*;
+            match header {
+                gix::odb::find::Header::Loose { .. } => self.objects.loose += 1,
+                gix::odb::find::Header::Packed(_) => self.objects.packed += 1,
+            }
+            let size = header.size();
+            let (stats, biggest) = match header.kind() {
+                Commit => (&mut self.objects.commits, None),
+                Tag => (&mut self.objects.tags, None),
+                Tree => (&mut self.objects.trees, Some(&mut self.biggest_trees)),
+                Blob => (&mut self.objects.blobs, Some(&mut self.biggest_blobs)),
+            };
+            stats.count += 1;
+            stats.size += size;
+            if let Some(biggest) = biggest {
+                push_bounded(biggest, SizedObject { size, id }, top);
+            }
+        }
+
+        fn merge(&mut self, other: Tally, top: usize) {
+            for (lhs, rhs) in [
+                (&mut self.objects.commits, other.objects.commits),
+                (&mut self.objects.trees, other.objects.trees),
+                (&mut self.objects.blobs, other.objects.blobs),
+                (&mut self.objects.tags, other.objects.tags),
+            ] {
+                lhs.count += rhs.count;
+                lhs.size += rhs.size;
+            }
+            self.objects.loose += other.objects.loose;
+            self.objects.packed += other.objects.packed;
+            for Reverse(obj) in other.biggest_blobs {
+                push_bounded(&mut self.biggest_blobs, obj, top);
+            }
+            for Reverse(obj) in other.biggest_trees {
+                push_bounded(&mut self.biggest_trees, obj, top);
+            }
+        }
+
+        fn finalize(mut self) -> Objects {
+            fn sorted(heap: BinaryHeap<Reverse<SizedObject>>) -> Vec<SizedObject> {
+                heap.into_sorted_vec().into_iter().map(|Reverse(obj)| obj).collect()
+            }
+            self.objects.biggest_blobs = sorted(self.biggest_blobs);
+            self.objects.biggest_trees = sorted(self.biggest_trees);
+            self.objects
+        }
+    }
+
+    fn push_bounded(heap: &mut BinaryHeap<Reverse<SizedObject>>, obj: SizedObject, top: usize) {
+        if top == 0 {
+            return;
+        }
+        if heap.len() < top {
+            heap.push(Reverse(obj));
+        } else if heap.peek().map_or(false, |Reverse(smallest)| *smallest < obj) {
+            heap.pop();
+            heap.push(Reverse(obj));
+        }
+    }
+
+    struct Reduce {
+        tally: Tally,
+        top: usize,
+    }
+
+    impl gix::parallel::Reduce for Reduce {
+        type Input = anyhow::Result<Tally>;
+        type FeedProduce = ();
+        type Output = Tally;
+        type Error = anyhow::Error;
+
+        fn feed(&mut self, item: Self::Input) -> Result<Self::FeedProduce, Self::Error> {
+            self.tally.merge(item?, self.top);
+            Ok(())
+        }
+
+        fn finalize(self) -> Result<Self::Output, Self::Error> {
+            Ok(self.tally)
+        }
+    }
+
+    let cancelled = || anyhow::anyhow!("Cancelled by user");
+    let counter = progress.counter();
+    // Objects may be stored loose and in packs, or in multiple packs, but each one is only counted once.
+    let mut object_ids = repo.objects.iter()?.collect::<Result<Vec<_>, _>>()?;
+    object_ids.sort_unstable();
+    object_ids.dedup();
+    let tally = gix::parallel::in_parallel(
+        gix::interrupt::Iter::new(
+            gix::features::iter::Chunks {
+                inner: object_ids.into_iter(),
+                size: 1_000,
+            },
+            cancelled,
+        ),
+        thread_limit,
+        {
+            let objects = repo.objects.clone();
+            move |_| (objects.clone().into_inner(), counter.clone())
+        },
+        move |ids, (handle, counter)| -> anyhow::Result<Tally> {
+            let ids = ids?;
+            counter.fetch_add(ids.len(), Ordering::Relaxed);
+            let mut tally = Tally::default();
+            for id in ids {
+                tally.consume(id, handle.header(id)?, top);
+            }
+            Ok(tally)
+        },
+        Reduce {
+            tally: Tally::default(),
+            top,
+        },
+    )?;
+    Ok(tally.finalize())
+}
+
+fn disk_usage(objects_dir: &Path) -> anyhow::Result<Disk> {
+    let mut disk = Disk::default();
+    for entry in std::fs::read_dir(objects_dir)? {
+        let entry = entry?;
+        let name = entry.file_name();
+        let is_fan_out_dir = name.len() == 2
+            && name
+                .to_str()
+                .map_or(false, |n| n.bytes().all(|b| b.is_ascii_hexdigit()));
+        if !is_fan_out_dir || !entry.file_type()?.is_dir() {
+            continue;
+        }
+        for object in std::fs::read_dir(entry.path())? {
+            disk.loose_objects_size += object?.metadata()?.len();
+        }
+    }
+
+    let pack_dir = objects_dir.join("pack");
+    if pack_dir.is_dir() {
+        for entry in std::fs::read_dir(pack_dir)? {
+            let entry = entry?;
+            let path = entry.path();
+            match path.extension().and_then(|ext| ext.to_str()) {
+                Some("pack") => {
+                    disk.packs += 1;
+                    disk.packs_size += entry.metadata()?.len();
+                }
+                Some("idx") => disk.pack_indices_size += entry.metadata()?.len(),
+                _ => {}
+            }
+        }
+    }
+    Ok(disk)
+}
+
+fn write_human(stats: &Statistics, mut out: impl io::Write) -> io::Result<()> {
+    use bytesize::ByteSize;
+
+    let Statistics {
+        objects,
+        disk,
+        references,
+        indices,
+    } = stats;
+    writeln!(out, "objects")?;
+    for (name, kind) in [
+        ("commits", objects.commits),
+        ("trees", objects.trees),
+        ("blobs", objects.blobs),
+        ("tags", objects.tags),
+    ] {
+        writeln!(
+            out,
+            "  {name:<8} {:>10} {:>12}",
+            kind.count,
+            ByteSize(kind.size).to_string()
+        )?;
+    }
+    writeln!(out, "  loose    {:>10}", objects.loose)?;
+    writeln!(out, "  packed   {:>10}", objects.packed)?;
+
+    writeln!(out, "disk")?;
+    writeln!(
+        out,
+        "  loose objects {:>12}",
+        ByteSize(disk.loose_objects_size).to_string()
+    )?;
+    writeln!(
+        out,
+        "  {} pack(s)    {:>12} (+{} in indices)",
+        disk.packs,
+        ByteSize(disk.packs_size).to_string(),
+        ByteSize(disk.pack_indices_size)
+    )?;
+
+    writeln!(out, "references")?;
+    writeln!(out, "  local branches  {:>8}", references.local_branches)?;
+    writeln!(out, "  remote branches {:>8}", references.remote_branches)?;
+    writeln!(out, "  tags            {:>8}", references.tags)?;
+    writeln!(out, "  other           {:>8}", references.other)?;
+
+    writeln!(out, "indices")?;
+    writeln!(out, "  commit-graph     {}", yes_no(indices.commit_graph))?;
+    writeln!(out, "  multi-pack-index {}", yes_no(indices.multi_pack_index))?;
+
+    for (title, biggest) in [
+        ("biggest blobs", &objects.biggest_blobs),
+        ("biggest trees", &objects.biggest_trees),
+    ] {
+        if biggest.is_empty() {
+            continue;
+        }
+        writeln!(out, "{title}")?;
+        for obj in biggest {
+            writeln!(out, "  {} {:>12}", obj.id, ByteSize(obj.size).to_string())?;
+        }
+    }
+    Ok(())
+}
+
+fn yes_no(value: bool) -> &'static str {
+    if value {
+        "yes"
+    } else {
+        "no"
+    }
+}
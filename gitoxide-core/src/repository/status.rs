@@ -111,54 +111,7 @@ pub fn show(
         .into_index_worktree_iter(pathspecs)?;
 
     for item in iter.by_ref() {
-        let item = item?;
-        match item {
-            Item::Modification {
-                entry: _,
-                entry_index: _,
-                rela_path,
-                status,
-            } => print_index_entry_status(&mut out, prefix, rela_path.as_ref(), status)?,
-            Item::DirectoryContents {
-                entry,
-                collapsed_directory_status,
-            } => {
-                if collapsed_directory_status.is_none() {
-                    writeln!(
-                        out,
-                        "{status: >3} {rela_path}{slash}",
-                        status = "?",
-                        rela_path =
-                            gix::path::relativize_with_prefix(&gix::path::from_bstr(entry.rela_path), prefix).display(),
-                        slash = if entry.disk_kind.unwrap_or(gix::dir::entry::Kind::File).is_dir() {
-                            "/"
-                        } else {
-                            ""
-                        }
-                    )?;
-                }
-            }
-            Item::Rewrite {
-                source,
-                dirwalk_entry,
-                copy: _, // TODO: how to visualize copies?
-                ..
-            } => {
-                // TODO: handle multi-status characters, there can also be modifications at the same time as determined by their ID and potentially diffstats.
-                writeln!(
-                    out,
-                    "{status: >3} {source_rela_path} → {dest_rela_path}",
-                    status = "R",
-                    source_rela_path =
-                        gix::path::relativize_with_prefix(&gix::path::from_bstr(source.rela_path()), prefix).display(),
-                    dest_rela_path = gix::path::relativize_with_prefix(
-                        &gix::path::from_bstr(dirwalk_entry.rela_path.as_bstr()),
-                        prefix
-                    )
-                    .display(),
-                )?;
-            }
-        }
+        print_item(&mut out, prefix, item?)?;
     }
     if gix::interrupt::is_triggered() {
         bail!("interrupted by user");
@@ -181,6 +134,56 @@ pub fn show(
     Ok(())
 }
 
+/// Print a single status `item` in the simplified format, with paths relative to `prefix`.
+pub(crate) fn print_item(out: &mut dyn std::io::Write, prefix: &Path, item: Item) -> std::io::Result<()> {
+    match item {
+        Item::Modification {
+            entry: _,
+            entry_index: _,
+            rela_path,
+            status,
+        } => print_index_entry_status(out, prefix, rela_path.as_ref(), status)?,
+        Item::DirectoryContents {
+            entry,
+            collapsed_directory_status,
+        } => {
+            if collapsed_directory_status.is_none() {
+                writeln!(
+                    out,
+                    "{status: >3} {rela_path}{slash}",
+                    status = "?",
+                    rela_path =
+                        gix::path::relativize_with_prefix(&gix::path::from_bstr(entry.rela_path), prefix).display(),
+                    slash = if entry.disk_kind.unwrap_or(gix::dir::entry::Kind::File).is_dir() {
+                        "/"
+                    } else {
+                        ""
+                    }
+                )?;
+            }
+        }
+        Item::Rewrite {
+            source,
+            dirwalk_entry,
+            copy: _, // TODO: how to visualize copies?
+            ..
+        } => {
+            // TODO: handle multi-status characters, there can also be modifications at the same time as determined by their ID and potentially diffstats.
+            writeln!(
+                out,
+                "{status: >3} {source_rela_path} → {dest_rela_path}",
+                status = "R",
+                source_rela_path =
+                    gix::path::relativize_with_prefix(&gix::path::from_bstr(source.rela_path()), prefix).display(),
+                dest_rela_path =
+                    gix::path::relativize_with_prefix(&gix::path::from_bstr(dirwalk_entry.rela_path.as_bstr()), prefix)
+                        .display(),
+            )?;
+        }
+    }
+    Ok(())
+}
+
 fn print_index_entry_status(
     out: &mut dyn std::io::Write,
     prefix: &Path,
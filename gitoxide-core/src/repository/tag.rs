@@ -0,0 +1,19 @@
+use anyhow::{anyhow, Context, Result};
+
+/// Verify the signature of the annotated tag at `rev_spec` with the keyring configured in `repo` and print what
+/// is known about it to `out`.
+///
+/// It fails if the tag isn't signed, if the signature isn't good, or if it isn't trusted as much as `gpg.minTrustLevel` demands.
+pub fn verify(repo: gix::Repository, rev_spec: &str, out: impl std::io::Write) -> Result<()> {
+    let tag = repo
+        .rev_parse_single(rev_spec)?
+        .object()?
+        .try_into_tag()
+        .with_context(|| format!("{rev_spec} does not point to an annotated tag"))?;
+    let keyring = repo.keyring()?;
+    let verification = tag
+        .verify_signature(&keyring)
+        .context("Could not verify tag signature")?
+        .ok_or_else(|| anyhow!("Tag at {rev_spec} is not signed"))?;
+    super::commit::print_verification(&verification, repo.keyring_min_trust_level()?, out)
+}
@@ -26,3 +26,50 @@ pub fn list(repo: gix::Repository, out: &mut dyn std::io::Write, format: OutputF
     }
     Ok(())
 }
+
+pub fn verify(repo: gix::Repository, out: &mut dyn std::io::Write, format: OutputFormat) -> anyhow::Result<()> {
+    use gix::worktree::verify::Divergence;
+    if format != OutputFormat::Human {
+        bail!("JSON output isn't implemented yet");
+    }
+
+    let Some(worktree) = repo.worktree() else {
+        bail!("A worktree is required to verify it");
+    };
+    let outcome = worktree.verify()?;
+    for divergence in &outcome.divergences {
+        match divergence {
+            Divergence::Worktree { rela_path, summary } => {
+                writeln!(out, "worktree: {rela_path}: {summary:?}")?;
+            }
+            Divergence::Conflict { rela_path, conflict } => {
+                writeln!(out, "index: {rela_path}: {conflict:?}")?;
+            }
+            Divergence::Head { rela_path, index, head } => {
+                let display = |e: &Option<(gix::ObjectId, gix::index::entry::Mode)>| {
+                    e.map_or("<missing>".into(), |(id, mode)| format!("{mode:?} {id}"))
+                };
+                writeln!(
+                    out,
+                    "HEAD: {rela_path}: index {index} != HEAD {head}",
+                    index = display(index),
+                    head = display(head)
+                )?;
+            }
+            Divergence::TreeExtensionRoot { extension, head } => {
+                writeln!(out, "TREE extension: root {extension} != HEAD^{{tree}} {head}")?;
+            }
+            Divergence::TreeExtension(err) => {
+                writeln!(out, "TREE extension: {err}")?;
+            }
+        }
+    }
+    if !outcome.is_consistent() {
+        bail!(
+            "Found {} divergences among {} index entries",
+            outcome.divergences.len(),
+            outcome.num_entries
+        );
+    }
+    Ok(())
+}
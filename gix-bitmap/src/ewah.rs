@@ -98,27 +98,105 @@ mod access {
     }
 
     #[inline]
-    fn rlw_running_len(w: &u64) -> u64 {
+    pub(super) fn rlw_running_len(w: &u64) -> u64 {
         (w >> 1) & RLW_LARGEST_RUNNING_COUNT
     }
 
     #[inline]
-    fn rlw_literal_words(w: &u64) -> u64 {
+    pub(super) fn rlw_literal_words(w: &u64) -> u64 {
         w >> (1 + RLW_RUNNING_BITS)
     }
 
     #[inline]
-    fn rlw_runbit_is_set(w: &u64) -> bool {
+    pub(super) fn rlw_runbit_is_set(w: &u64) -> bool {
         w & 1 == 1
     }
 
-    const RLW_RUNNING_BITS: u64 = 4 * 8;
-    const RLW_LARGEST_RUNNING_COUNT: u64 = (1 << RLW_RUNNING_BITS) - 1;
+    pub(super) const RLW_RUNNING_BITS: u64 = 4 * 8;
+    pub(super) const RLW_LARGEST_RUNNING_COUNT: u64 = (1 << RLW_RUNNING_BITS) - 1;
+    pub(super) const RLW_LARGEST_LITERAL_COUNT: u64 = (1 << (64 - 1 - RLW_RUNNING_BITS)) - 1;
+}
+
+mod write {
+    use super::{
+        access::{
+            rlw_literal_words, rlw_runbit_is_set, rlw_running_len, RLW_LARGEST_LITERAL_COUNT,
+            RLW_LARGEST_RUNNING_COUNT, RLW_RUNNING_BITS,
+        },
+        Vec,
+    };
+
+    impl Vec {
+        /// Create a new bitmap with exactly the bits at `indices` set, which must be sorted in ascending order.
+        ///
+        /// The amount of bits is the highest set index plus one, and words are compressed just like `git` would do it
+        /// to allow writing bitmaps that are byte-for-byte identical to the ones it produces.
+        pub fn from_set_bits(indices: impl IntoIterator<Item = usize>) -> Self {
+            let mut words = std::vec::Vec::<u64>::new();
+            let mut num_bits = 0;
+            for index in indices {
+                let word_index = index / 64;
+                if word_index >= words.len() {
+                    words.resize(word_index + 1, 0);
+                }
+                words[word_index] |= 1 << (index % 64);
+                num_bits = index + 1;
+            }
+
+            let mut bits = vec![0u64];
+            let mut rlw = 0;
+            for word in words {
+                if word == 0 || word == u64::MAX {
+                    let run_bit = word == u64::MAX;
+                    let marker = &bits[rlw];
+                    let can_extend_run = rlw_literal_words(marker) == 0
+                        && (rlw_running_len(marker) == 0 || rlw_runbit_is_set(marker) == run_bit)
+                        && rlw_running_len(marker) < RLW_LARGEST_RUNNING_COUNT;
+                    if !can_extend_run {
+                        bits.push(0);
+                        rlw = bits.len() - 1;
+                    }
+                    bits[rlw] = ((rlw_running_len(&bits[rlw]) + 1) << 1) | u64::from(run_bit);
+                } else {
+                    if rlw_literal_words(&bits[rlw]) == RLW_LARGEST_LITERAL_COUNT {
+                        bits.push(0);
+                        rlw = bits.len() - 1;
+                    }
+                    bits[rlw] += 1 << (1 + RLW_RUNNING_BITS);
+                    bits.push(word);
+                }
+            }
+
+            Vec {
+                num_bits: num_bits.try_into().expect("no more than 4 billion bits"),
+                bits,
+                rlw: rlw as u64,
+            }
+        }
+
+        /// Serialize this bitmap to `out` in the format understood by [`decode()`](super::decode()).
+        pub fn write_to(&self, mut out: impl std::io::Write) -> std::io::Result<()> {
+            let len: u32 = self.bits.len().try_into().map_err(|_| {
+                std::io::Error::new(
+                    std::io::ErrorKind::Other,
+                    "EWAH bitmaps can have at most 4 billion words",
+                )
+            })?;
+            let rlw: u32 = self.rlw.try_into().expect("rlw is an index into bits");
+            out.write_all(&self.num_bits.to_be_bytes())?;
+            out.write_all(&len.to_be_bytes())?;
+            for word in &self.bits {
+                out.write_all(&word.to_be_bytes())?;
+            }
+            out.write_all(&rlw.to_be_bytes())
+        }
+    }
 }
 
 /// A growable collection of u64 that are seen as stream of individual bits.
 #[allow(dead_code)]
-#[derive(Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Vec {
     num_bits: u32,
     bits: std::vec::Vec<u64>,
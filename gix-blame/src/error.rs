@@ -0,0 +1,22 @@
+use gix_object::bstr::BString;
+
+/// The error returned by [`file()`](crate::file()) and [`file_incremental()`](crate::file_incremental()).
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("The file to blame at '{file_path}' wasn't found in the first commit at {commit_id}")]
+    FileMissing {
+        /// The file-path to the object to blame.
+        file_path: BString,
+        /// The commit whose tree didn't contain `file_path`.
+        commit_id: gix_hash::ObjectId,
+    },
+    #[error(transparent)]
+    FindObject(#[from] gix_object::find::existing_object::Error),
+    #[error(transparent)]
+    FindCommit(#[from] gix_object::find::existing_iter::Error),
+    #[error(transparent)]
+    FindTreeEntry(#[from] gix_object::find::Error),
+    #[error(transparent)]
+    DecodeCommit(#[from] gix_object::decode::Error),
+}
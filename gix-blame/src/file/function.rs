@@ -0,0 +1,255 @@
+use std::collections::{BinaryHeap, HashMap};
+use std::num::NonZeroU32;
+
+use gix_hash::ObjectId;
+use gix_object::bstr::{BStr, ByteSlice};
+use gix_object::FindExt;
+
+use super::{pass_unchanged_to_parent, UnblamedHunk, UnchangedRange};
+use crate::{BlameEntry, Error, Options, Outcome, Statistics};
+
+/// Annotate all lines of the file at `file_path` as it exists in the commit `suspect` with the commit that
+/// introduced them, by walking the history of `suspect` and looking up objects in `odb`.
+///
+/// The returned [entries](Outcome::entries) are sorted by their position in the blamed file.
+/// Use [`file_incremental()`] to see entries as soon as they are discovered.
+///
+/// ### Performance
+///
+/// Note that `odb` *should* have an object cache to greatly accelerate tree-retrieval.
+///
+/// ### Deviation
+///
+/// * Renames aren't tracked, so lines are attributed to the commit that added the file at `file_path`
+///   even if its content was moved from elsewhere.
+/// * Lines that were moved or copied within the file or across files aren't detected.
+pub fn file(
+    odb: impl gix_object::Find,
+    suspect: ObjectId,
+    file_path: &BStr,
+    options: Options<'_>,
+) -> Result<Outcome, Error> {
+    let mut entries = Vec::new();
+    let (blob, statistics) = blame(&odb, suspect, file_path, options, &mut |entry| entries.push(*entry))?;
+    entries.sort_by_key(|entry| entry.start_in_blamed_file);
+    Ok(Outcome {
+        entries,
+        blob,
+        statistics,
+    })
+}
+
+/// Like [`file()`], but call `on_entry` with each entry as soon as it was attributed to a commit,
+/// which allows showing results before the whole history of the file was traversed.
+///
+/// Entries are delivered in the order in which they are found, which is roughly from the most recent
+/// commit to the oldest one.
+/// The [`entries`](Outcome::entries) of the returned outcome are always empty.
+pub fn file_incremental(
+    odb: impl gix_object::Find,
+    suspect: ObjectId,
+    file_path: &BStr,
+    options: Options<'_>,
+    mut on_entry: impl FnMut(&BlameEntry),
+) -> Result<Outcome, Error> {
+    let (blob, statistics) = blame(&odb, suspect, file_path, options, &mut on_entry)?;
+    Ok(Outcome {
+        entries: Vec::new(),
+        blob,
+        statistics,
+    })
+}
+
+/// A commit that still has hunks to be blamed, ordered by commit time so that children are processed before their parents.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+struct Queued {
+    commit_time: gix_object::date::SecondsSinceUnixEpoch,
+    id: ObjectId,
+}
+
+/// The state of a commit in the queue.
+struct Suspect {
+    /// The id of the blamed file in the commit.
+    blob_id: ObjectId,
+    /// All hunks which still have to be attributed, in the coordinates of the file in this commit.
+    hunks: Vec<UnblamedHunk>,
+}
+
+fn blame(
+    odb: &impl gix_object::Find,
+    suspect: ObjectId,
+    file_path: &BStr,
+    options: Options<'_>,
+    on_entry: &mut dyn FnMut(&BlameEntry),
+) -> Result<(Vec<u8>, Statistics), Error> {
+    let _span = gix_trace::coarse!("gix_blame::file()", file_path = ?file_path, suspect = ?suspect);
+    let mut stats = Statistics::default();
+    let mut buf = Vec::new();
+    let mut buf2 = Vec::new();
+
+    let (commit_time, tree_id) = commit_time_and_tree(odb, &suspect, &mut buf)?;
+    let blob_id =
+        blob_id_at(odb, &tree_id, file_path, &mut buf, &mut buf2, &mut stats)?.ok_or_else(|| Error::FileMissing {
+            file_path: file_path.to_owned(),
+            commit_id: suspect,
+        })?;
+    let blob = odb.find_blob(&blob_id, &mut buf)?.data.to_vec();
+    let num_lines = tokens(&blob).count() as u32;
+
+    let mut queue = BinaryHeap::new();
+    let mut suspects = HashMap::<ObjectId, Suspect>::new();
+    if num_lines != 0 {
+        suspects.insert(
+            suspect,
+            Suspect {
+                blob_id,
+                hunks: vec![UnblamedHunk {
+                    range_in_blamed_file: 0..num_lines,
+                    start_in_suspect: 0,
+                }],
+            },
+        );
+        queue.push(Queued {
+            commit_time,
+            id: suspect,
+        });
+    }
+
+    let mut suspect_blob = Vec::new();
+    let mut parent_blob = Vec::new();
+    while let Some(Queued { id, .. }) = queue.pop() {
+        let Some(Suspect { blob_id, mut hunks }) = suspects.remove(&id) else {
+            continue;
+        };
+        stats.commits_traversed += 1;
+
+        let parent_ids: Vec<_> = match options.parents.and_then(|parents| parents.parents_of(&id)) {
+            Some(ids) => ids.to_vec(),
+            None => odb.find_commit_iter(&id, &mut buf)?.parent_ids().collect(),
+        };
+        let mut parents = Vec::with_capacity(parent_ids.len());
+        for parent_id in parent_ids {
+            let (commit_time, tree_id) = commit_time_and_tree(odb, &parent_id, &mut buf)?;
+            let Some(parent_blob_id) = blob_id_at(odb, &tree_id, file_path, &mut buf, &mut buf2, &mut stats)? else {
+                continue;
+            };
+            if parent_blob_id == blob_id {
+                // The file is unchanged, so everything that is left is passed to this parent.
+                parents.clear();
+                parents.push((parent_id, commit_time, parent_blob_id, true));
+                break;
+            }
+            parents.push((parent_id, commit_time, parent_blob_id, false));
+        }
+
+        for (parent_id, commit_time, parent_blob_id, is_unchanged) in parents {
+            if hunks.is_empty() {
+                break;
+            }
+            let passed = if is_unchanged {
+                std::mem::take(&mut hunks)
+            } else {
+                suspect_blob.clear();
+                suspect_blob.extend_from_slice(odb.find_blob(&blob_id, &mut buf)?.data);
+                parent_blob.clear();
+                parent_blob.extend_from_slice(odb.find_blob(&parent_blob_id, &mut buf)?.data);
+
+                let input = imara_diff::intern::InternedInput::new(tokens(&parent_blob), tokens(&suspect_blob));
+                stats.blobs_diffed += 1;
+                let unchanged = unchanged_ranges(options.diff_algorithm, &input);
+                pass_unchanged_to_parent(&mut hunks, &unchanged)
+            };
+            if passed.is_empty() {
+                continue;
+            }
+            let parent = suspects.entry(parent_id).or_insert_with(|| {
+                queue.push(Queued {
+                    commit_time,
+                    id: parent_id,
+                });
+                Suspect {
+                    blob_id: parent_blob_id,
+                    hunks: Vec::new(),
+                }
+            });
+            parent.hunks.extend(passed);
+        }
+
+        for hunk in hunks {
+            let Some(len) = NonZeroU32::new(hunk.range_in_blamed_file.end - hunk.range_in_blamed_file.start) else {
+                continue;
+            };
+            on_entry(&BlameEntry {
+                start_in_blamed_file: hunk.range_in_blamed_file.start,
+                start_in_source_file: hunk.start_in_suspect,
+                len,
+                commit_id: id,
+            });
+        }
+    }
+    Ok((blob, stats))
+}
+
+/// Diff `input` and return all ranges of lines that are the same in before (the parent) and after (the suspect),
+/// ordered by their position in the suspect.
+fn unchanged_ranges(
+    algorithm: imara_diff::Algorithm,
+    input: &imara_diff::intern::InternedInput<&[u8]>,
+) -> Vec<UnchangedRange> {
+    let mut unchanged = Vec::new();
+    let mut before_end = 0;
+    let mut after_end = 0;
+    imara_diff::diff(
+        algorithm,
+        input,
+        |before: std::ops::Range<u32>, after: std::ops::Range<u32>| {
+            if after_end < after.start {
+                unchanged.push(UnchangedRange {
+                    in_suspect: after_end..after.start,
+                    start_in_parent: before_end,
+                });
+            }
+            before_end = before.end;
+            after_end = after.end;
+        },
+    );
+    let num_lines = input.after.len() as u32;
+    if after_end < num_lines {
+        unchanged.push(UnchangedRange {
+            in_suspect: after_end..num_lines,
+            start_in_parent: before_end,
+        });
+    }
+    unchanged
+}
+
+fn tokens(input: &[u8]) -> imara_diff::sources::ByteLines<'_, true> {
+    imara_diff::sources::byte_lines_with_terminator(input)
+}
+
+fn commit_time_and_tree(
+    odb: &impl gix_object::Find,
+    id: &gix_hash::oid,
+    buf: &mut Vec<u8>,
+) -> Result<(gix_object::date::SecondsSinceUnixEpoch, ObjectId), Error> {
+    let mut commit = odb.find_commit_iter(id, buf)?;
+    let tree_id = commit.tree_id()?;
+    let commit_time = commit.committer()?.time.seconds;
+    Ok((commit_time, tree_id))
+}
+
+fn blob_id_at(
+    odb: &impl gix_object::Find,
+    tree_id: &gix_hash::oid,
+    file_path: &BStr,
+    buf: &mut Vec<u8>,
+    buf2: &mut Vec<u8>,
+    stats: &mut Statistics,
+) -> Result<Option<ObjectId>, Error> {
+    stats.trees_searched += 1;
+    let tree = odb.find_tree_iter(tree_id, buf)?;
+    Ok(tree
+        .lookup_entry(odb, buf2, file_path.split_str("/"))?
+        .filter(|entry| entry.mode.is_blob())
+        .map(|entry| entry.oid))
+}
@@ -0,0 +1,133 @@
+use std::ops::Range;
+
+pub(super) mod function;
+
+/// A range of lines in the blamed file that wasn't yet attributed to a commit,
+/// along with the position of the same lines in the version of the file in the current suspect.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct UnblamedHunk {
+    /// The lines in the blamed file.
+    pub range_in_blamed_file: Range<u32>,
+    /// The first line of `range_in_blamed_file` in the file of the current suspect.
+    pub start_in_suspect: u32,
+}
+
+impl UnblamedHunk {
+    fn len(&self) -> u32 {
+        self.range_in_blamed_file.end - self.range_in_blamed_file.start
+    }
+
+    fn range_in_suspect(&self) -> Range<u32> {
+        self.start_in_suspect..self.start_in_suspect + self.len()
+    }
+
+    /// Return the sub-hunk that covers `range` in the suspect, which must be fully contained in our range in the suspect.
+    fn sub_hunk(&self, range: Range<u32>) -> UnblamedHunk {
+        let offset = range.start - self.start_in_suspect;
+        let start = self.range_in_blamed_file.start + offset;
+        UnblamedHunk {
+            range_in_blamed_file: start..start + (range.end - range.start),
+            start_in_suspect: range.start,
+        }
+    }
+}
+
+/// A range of lines that is the same in the suspect and in one of its parents.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct UnchangedRange {
+    /// The lines in the suspect.
+    pub in_suspect: Range<u32>,
+    /// The first line of `in_suspect` in the parent.
+    pub start_in_parent: u32,
+}
+
+/// Split `hunks` according to `unchanged` ranges, which are sorted by their position in the suspect,
+/// and return all parts that are to be passed on to the parent, in the coordinates of the parent.
+/// All parts that were changed in the suspect remain in `hunks`.
+pub(crate) fn pass_unchanged_to_parent(
+    hunks: &mut Vec<UnblamedHunk>,
+    unchanged: &[UnchangedRange],
+) -> Vec<UnblamedHunk> {
+    let mut passed = Vec::new();
+    let mut remaining = Vec::with_capacity(hunks.len());
+    for hunk in hunks.drain(..) {
+        let suspect_range = hunk.range_in_suspect();
+        let mut cursor = suspect_range.start;
+        let first_candidate = unchanged.partition_point(|u| u.in_suspect.end <= suspect_range.start);
+        for range in unchanged[first_candidate..]
+            .iter()
+            .take_while(|u| u.in_suspect.start < suspect_range.end)
+        {
+            let overlap = range.in_suspect.start.max(suspect_range.start)..range.in_suspect.end.min(suspect_range.end);
+            if overlap.start >= overlap.end {
+                continue;
+            }
+            if cursor < overlap.start {
+                remaining.push(hunk.sub_hunk(cursor..overlap.start));
+            }
+            let mut to_parent = hunk.sub_hunk(overlap.clone());
+            to_parent.start_in_suspect = range.start_in_parent + (overlap.start - range.in_suspect.start);
+            passed.push(to_parent);
+            cursor = overlap.end;
+        }
+        if cursor < suspect_range.end {
+            remaining.push(hunk.sub_hunk(cursor..suspect_range.end));
+        }
+    }
+    *hunks = remaining;
+    passed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{pass_unchanged_to_parent, UnblamedHunk, UnchangedRange};
+
+    fn hunk(blamed: std::ops::Range<u32>, start_in_suspect: u32) -> UnblamedHunk {
+        UnblamedHunk {
+            range_in_blamed_file: blamed,
+            start_in_suspect,
+        }
+    }
+
+    #[test]
+    fn unchanged_ranges_are_passed_on_and_the_rest_remains() {
+        let mut hunks = vec![hunk(0..10, 0)];
+        let passed = pass_unchanged_to_parent(
+            &mut hunks,
+            &[
+                UnchangedRange {
+                    in_suspect: 0..2,
+                    start_in_parent: 0,
+                },
+                UnchangedRange {
+                    in_suspect: 5..10,
+                    start_in_parent: 3,
+                },
+            ],
+        );
+        assert_eq!(passed, vec![hunk(0..2, 0), hunk(5..10, 3)]);
+        assert_eq!(hunks, vec![hunk(2..5, 2)], "the lines that changed remain");
+    }
+
+    #[test]
+    fn hunks_with_offset_into_suspect_are_translated() {
+        let mut hunks = vec![hunk(10..14, 2)];
+        let passed = pass_unchanged_to_parent(
+            &mut hunks,
+            &[UnchangedRange {
+                in_suspect: 3..20,
+                start_in_parent: 0,
+            }],
+        );
+        assert_eq!(passed, vec![hunk(11..14, 0)]);
+        assert_eq!(hunks, vec![hunk(10..11, 2)]);
+    }
+
+    #[test]
+    fn nothing_unchanged_means_nothing_is_passed() {
+        let mut hunks = vec![hunk(0..3, 0)];
+        let passed = pass_unchanged_to_parent(&mut hunks, &[]);
+        assert!(passed.is_empty());
+        assert_eq!(hunks, vec![hunk(0..3, 0)]);
+    }
+}
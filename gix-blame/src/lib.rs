@@ -1,10 +1,15 @@
 //! A crate to implement an algorithm to annotate lines in tracked files with the commits that changed them.
-#![deny(rust_2018_idioms)]
+//!
+//! Use [`file()`] to obtain all [entries](BlameEntry) at once, or [`file_incremental()`] to receive each
+//! entry as soon as it was attributed to a commit.
+#![deny(rust_2018_idioms, missing_docs)]
 #![forbid(unsafe_code)]
 
-#[cfg(test)]
-mod tests {
-    #[test]
-    #[ignore]
-    fn it_works() {}
-}
+mod types;
+pub use types::{BlameEntry, Options, Outcome, Statistics};
+
+mod error;
+pub use error::Error;
+
+mod file;
+pub use file::function::{file, file_incremental};
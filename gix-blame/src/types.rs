@@ -0,0 +1,92 @@
+use std::num::NonZeroU32;
+use std::ops::Range;
+
+/// Options to configure [`file()`](crate::file()) and [`file_incremental()`](crate::file_incremental()).
+#[derive(Copy, Clone)]
+pub struct Options<'a> {
+    /// The algorithm to use when diffing the blamed file between a commit and its parents.
+    pub diff_algorithm: imara_diff::Algorithm,
+    /// If set, the provider of parents that take precedence over the ones recorded in commits,
+    /// which is how grafts and the shallow boundary are applied.
+    pub parents: Option<&'a dyn gix_revwalk::Parents>,
+}
+
+impl Default for Options<'_> {
+    fn default() -> Self {
+        Options {
+            diff_algorithm: imara_diff::Algorithm::Histogram,
+            parents: None,
+        }
+    }
+}
+
+impl std::fmt::Debug for Options<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Options")
+            .field("diff_algorithm", &self.diff_algorithm)
+            .field("parents", &self.parents.map(|_| "<provider>"))
+            .finish()
+    }
+}
+
+/// A single range of lines in the blamed file which was attributed to a commit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Ord, PartialOrd)]
+pub struct BlameEntry {
+    /// The index of the first line of this entry in the blamed file, i.e. the file as seen in the starting commit.
+    pub start_in_blamed_file: u32,
+    /// The index of the first line of this entry in the version of the file as it exists in `commit_id`.
+    pub start_in_source_file: u32,
+    /// The amount of lines this entry spans.
+    pub len: NonZeroU32,
+    /// The commit that introduced the lines of this entry.
+    pub commit_id: gix_hash::ObjectId,
+}
+
+impl BlameEntry {
+    /// Return the range of lines this entry covers in the blamed file.
+    pub fn range_in_blamed_file(&self) -> Range<u32> {
+        self.start_in_blamed_file..self.start_in_blamed_file + self.len.get()
+    }
+
+    /// Return the range of lines this entry covers in the file as seen in [`commit_id`](Self::commit_id).
+    pub fn range_in_source_file(&self) -> Range<u32> {
+        self.start_in_source_file..self.start_in_source_file + self.len.get()
+    }
+}
+
+/// Statistics gathered while blaming a file.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Statistics {
+    /// The amount of commits that were looked at to attribute all lines.
+    pub commits_traversed: usize,
+    /// The amount of trees that were searched for the blamed file.
+    pub trees_searched: usize,
+    /// The amount of blob-diffs that had to be performed to see which lines were passed on to parents.
+    pub blobs_diffed: usize,
+}
+
+/// The result of [`file()`](crate::file()).
+#[derive(Debug, Clone)]
+pub struct Outcome {
+    /// One entry in sequential order, to associate a hunk in the blamed file with the commit that introduced it.
+    pub entries: Vec<BlameEntry>,
+    /// The content of the blamed file as seen in the starting commit.
+    pub blob: Vec<u8>,
+    /// Additional information about the performed operation.
+    pub statistics: Statistics,
+}
+
+impl Outcome {
+    /// Return an iterator over each entry along with the lines in the blamed file it covers, each with its line terminator.
+    pub fn entries_with_lines(&self) -> impl Iterator<Item = (BlameEntry, Vec<&[u8]>)> + '_ {
+        let lines: Vec<&[u8]> = self.blob.split_inclusive(|b| *b == b'\n').collect();
+        self.entries.iter().map(move |entry| {
+            let range = entry.range_in_blamed_file();
+            let lines = lines
+                .get(range.start as usize..range.end as usize)
+                .map(<[_]>::to_vec)
+                .unwrap_or_default();
+            (*entry, lines)
+        })
+    }
+}
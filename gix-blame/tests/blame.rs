@@ -1,4 +1,119 @@
+use gix_hash::ObjectId;
+
+fn fixture() -> gix_testtools::Result<(std::path::PathBuf, gix_odb::Handle)> {
+    let worktree = gix_testtools::scripted_fixture_read_only("make_blame_repo.sh")?;
+    let odb = gix_odb::at(worktree.join(".git/objects"))?;
+    Ok((worktree, odb))
+}
+
+fn tag(worktree: &std::path::Path, name: &str) -> gix_testtools::Result<ObjectId> {
+    let hex = std::fs::read_to_string(worktree.join(".git/refs/tags").join(name))?;
+    Ok(ObjectId::from_hex(hex.trim().as_bytes())?)
+}
+
+#[test]
+fn lines_are_attributed_to_the_commits_that_introduced_them() -> gix_testtools::Result {
+    let (worktree, odb) = fixture()?;
+    let outcome = gix_blame::file(
+        &odb,
+        tag(&worktree, "simple-c4")?,
+        "simple.txt".into(),
+        Default::default(),
+    )?;
+
+    assert_eq!(outcome.blob, b"line 0\nline 1\nline 2\nline 3\n");
+    let actual: Vec<_> = outcome
+        .entries
+        .iter()
+        .map(|e| (e.range_in_blamed_file(), e.start_in_source_file, e.commit_id))
+        .collect();
+    assert_eq!(
+        actual,
+        vec![
+            (0..1, 0, tag(&worktree, "simple-c3")?),
+            (1..2, 0, tag(&worktree, "simple-c1")?),
+            (2..3, 1, tag(&worktree, "simple-c2")?),
+            (3..4, 3, tag(&worktree, "simple-c3")?),
+        ]
+    );
+    assert_eq!(outcome.statistics.commits_traversed, 4);
+    Ok(())
+}
+
+#[test]
+fn incremental_blame_sees_the_same_entries() -> gix_testtools::Result {
+    let (worktree, odb) = fixture()?;
+    let suspect = tag(&worktree, "simple-c4")?;
+    let expected = gix_blame::file(&odb, suspect, "simple.txt".into(), Default::default())?;
+
+    let mut entries = Vec::new();
+    let outcome = gix_blame::file_incremental(&odb, suspect, "simple.txt".into(), Default::default(), |entry| {
+        entries.push(*entry)
+    })?;
+    assert!(outcome.entries.is_empty(), "entries are only passed to the callback");
+    assert_eq!(
+        entries.first().map(|e| e.commit_id),
+        Some(tag(&worktree, "simple-c3")?),
+        "the most recent commits are seen first"
+    );
+    entries.sort_by_key(|e| e.start_in_blamed_file);
+    assert_eq!(entries, expected.entries);
+    Ok(())
+}
+
+#[test]
+fn grafted_parents_are_used_instead_of_the_recorded_ones() -> gix_testtools::Result {
+    let (worktree, odb) = fixture()?;
+    let mut grafts = gix_revwalk::parents::Grafts::default();
+    grafts.insert_shallow(Some(tag(&worktree, "simple-c3")?));
+    let outcome = gix_blame::file(
+        &odb,
+        tag(&worktree, "simple-c4")?,
+        "simple.txt".into(),
+        gix_blame::Options {
+            parents: Some(&grafts),
+            ..Default::default()
+        },
+    )?;
+
+    let actual: Vec<_> = outcome
+        .entries
+        .iter()
+        .map(|e| (e.range_in_blamed_file(), e.start_in_source_file, e.commit_id))
+        .collect();
+    assert_eq!(
+        actual,
+        vec![(0..4, 0, tag(&worktree, "simple-c3")?)],
+        "without parents, all lines are attributed to the boundary commit"
+    );
+    assert_eq!(outcome.statistics.commits_traversed, 2);
+    Ok(())
+}
+
+#[test]
+fn empty_files_have_no_entries() -> gix_testtools::Result {
+    let (worktree, odb) = fixture()?;
+    let head = ObjectId::from_hex(
+        std::fs::read_to_string(worktree.join(".git/refs/heads/main"))?
+            .trim()
+            .as_bytes(),
+    )?;
+    let outcome = gix_blame::file(&odb, head, "empty.txt".into(), Default::default())?;
+    assert!(outcome.entries.is_empty());
+    assert!(outcome.blob.is_empty());
+    Ok(())
+}
+
 #[test]
-fn it_works() {
-    let _worktree = gix_testtools::scripted_fixture_read_only("make_blame_repo.sh").unwrap();
+fn missing_files_are_an_error() -> gix_testtools::Result {
+    let (worktree, odb) = fixture()?;
+    let err = gix_blame::file(
+        &odb,
+        tag(&worktree, "simple-c4")?,
+        "missing.txt".into(),
+        Default::default(),
+    )
+    .unwrap_err();
+    assert!(matches!(err, gix_blame::Error::FileMissing { .. }));
+    Ok(())
 }
@@ -21,3 +21,24 @@ git checkout -q main
 git commit -q --allow-empty -m c5
 git tag at-c5
 git merge branch1 -m m1b1
+
+echo "line 1" > simple.txt
+git add simple.txt
+git commit -q -m "add simple.txt"
+git tag simple-c1
+
+echo "line 2" >> simple.txt
+git commit -q -am "extend simple.txt"
+git tag simple-c2
+
+{ echo "line 0"; cat simple.txt; echo "line 3"; } > simple.txt.new
+mv simple.txt.new simple.txt
+git commit -q -am "surround simple.txt"
+git tag simple-c3
+
+git commit -q --allow-empty -m "unrelated"
+git tag simple-c4
+
+touch empty.txt
+git add empty.txt
+git commit -q -m "add empty.txt"
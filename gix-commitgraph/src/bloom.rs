@@ -0,0 +1,153 @@
+//! Changed-path Bloom filters as stored in the `BIDX` and `BDAT` chunks of a commit-graph file.
+//!
+//! Each commit may have a filter that records all paths that changed compared to its first parent, along with all of
+//! their leading directories, which allows to quickly skip commits that definitely didn't change a path.
+use std::collections::BTreeSet;
+
+use bstr::{BStr, ByteSlice};
+
+const SEED0: u32 = 0x293a_e76f;
+const SEED1: u32 = 0x7e64_6e2c;
+
+/// The filter of commits that changed too many paths, which matches every path.
+const TRUNCATED_LARGE_FILTER: u8 = 0xff;
+
+/// Configure how changed-path Bloom filters are computed and queried, as stored in the header of the `BDAT` chunk.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Settings {
+    /// The version of the murmur3 hash function to use, which is `1` or `2`.
+    ///
+    /// Version `1` is what all versions of `git` can read, but it computes different hashes than version `2` for paths
+    /// with bytes that have the highest bit set, as the bytes are interpreted as signed values.
+    pub hash_version: u32,
+    /// The amount of bits to set for each path in a filter.
+    pub num_hashes: u32,
+    /// The amount of bits to allocate for each path in a filter, which together with [`num_hashes`](Self::num_hashes)
+    /// determines the false-positive rate.
+    pub bits_per_entry: u32,
+    /// If a commit changed more than this amount of paths, including their leading directories, a filter that matches
+    /// every path is stored for it instead.
+    ///
+    /// This value isn't stored in the commit-graph file.
+    pub max_changed_paths: u32,
+}
+
+impl Default for Settings {
+    /// The same settings that `git` uses.
+    fn default() -> Self {
+        Settings {
+            hash_version: 1,
+            num_hashes: 7,
+            bits_per_entry: 10,
+            max_changed_paths: 512,
+        }
+    }
+}
+
+impl Settings {
+    /// Compute the data of a filter for a commit which changed `changed_paths` compared to its first parent, or to the empty
+    /// tree if it has no parent.
+    ///
+    /// `changed_paths` are the slash-separated paths of all changed files, without trees, as their leading directories
+    /// are added automatically.
+    pub fn filter<'a>(&self, changed_paths: impl IntoIterator<Item = &'a BStr>) -> Vec<u8> {
+        let mut num_changes = 0;
+        let mut keys = BTreeSet::new();
+        for path in changed_paths {
+            num_changes += 1;
+            if num_changes > self.max_changed_paths {
+                return vec![TRUNCATED_LARGE_FILTER];
+            }
+            keys.extend(path_and_leading_directories(path));
+        }
+        if keys.len() > self.max_changed_paths as usize {
+            return vec![TRUNCATED_LARGE_FILTER];
+        }
+
+        let len = (keys.len() * self.bits_per_entry as usize + 7) / 8;
+        let mut data = vec![0; len.max(1)];
+        let num_bits = len as u64 * 8;
+        if num_bits == 0 {
+            return data;
+        }
+        for key in keys {
+            for bit in self.bits(key) {
+                let bit = u64::from(bit) % num_bits;
+                data[(bit / 8) as usize] |= 1 << (bit % 8);
+            }
+        }
+        data
+    }
+
+    /// Return `false` if `path` definitely didn't change according to the given `filter`, or `true` if it may have changed.
+    ///
+    /// Note that an empty `filter` means that no filter was computed, so it's unknown if `path` was changed.
+    pub fn maybe_contains(&self, filter: &[u8], path: &BStr) -> bool {
+        let num_bits = filter.len() as u64 * 8;
+        if num_bits == 0 {
+            return true;
+        }
+        path_and_leading_directories(path).all(|key| {
+            self.bits(key).all(|bit| {
+                let bit = u64::from(bit) % num_bits;
+                filter[(bit / 8) as usize] & (1 << (bit % 8)) != 0
+            })
+        })
+    }
+
+    fn bits(&self, key: &BStr) -> impl Iterator<Item = u32> {
+        let hash0 = murmur3(SEED0, key, self.hash_version);
+        let hash1 = murmur3(SEED1, key, self.hash_version);
+        (0..self.num_hashes).map(move |i| hash0.wrapping_add(i.wrapping_mul(hash1)))
+    }
+}
+
+/// Return `path` along with all of its leading directories, like `a/b/c`, `a/b` and `a`.
+fn path_and_leading_directories(path: &BStr) -> impl Iterator<Item = &BStr> {
+    let path = path.trim_end_with(|c| c == '/').as_bstr();
+    std::iter::once(path)
+        .filter(|path| !path.is_empty())
+        .chain(path.rfind_iter("/").map(move |pos| path[..pos].as_bstr()))
+}
+
+/// Compute the 32-bit murmur3 hash of `data` with `seed`, emulating the sign-extension of bytes with their highest bit set
+/// of hash `version` 1.
+pub fn murmur3(seed: u32, data: &[u8], version: u32) -> u32 {
+    const C1: u32 = 0xcc9e_2d51;
+    const C2: u32 = 0x1b87_3593;
+    let byte = |b: u8| -> u32 {
+        if version == 1 {
+            b as i8 as u32
+        } else {
+            u32::from(b)
+        }
+    };
+    let scramble = |mut k: u32| {
+        k = k.wrapping_mul(C1);
+        k = k.rotate_left(15);
+        k.wrapping_mul(C2)
+    };
+
+    let mut hash = seed;
+    let mut blocks = data.chunks_exact(4);
+    for block in &mut blocks {
+        let k = byte(block[0]) | (byte(block[1]) << 8) | (byte(block[2]) << 16) | (byte(block[3]) << 24);
+        hash ^= scramble(k);
+        hash = hash.rotate_left(13).wrapping_mul(5).wrapping_add(0xe654_6b64);
+    }
+    let tail = blocks.remainder();
+    if !tail.is_empty() {
+        let mut k = 0;
+        for (shift, b) in tail.iter().enumerate().rev() {
+            k ^= byte(*b) << (shift * 8);
+        }
+        hash ^= scramble(k);
+    }
+
+    hash ^= data.len() as u32;
+    hash ^= hash >> 16;
+    hash = hash.wrapping_mul(0x85eb_ca6b);
+    hash ^= hash >> 13;
+    hash = hash.wrapping_mul(0xc2b2_ae35);
+    hash ^ (hash >> 16)
+}
@@ -4,6 +4,7 @@ use std::{
 };
 
 use crate::{
+    bloom,
     file::{self, commit::Commit, COMMIT_DATA_ENTRY_SIZE_SANS_HASH},
     File,
 };
@@ -26,6 +27,14 @@ impl File {
         Commit::new(self, pos)
     }
 
+    /// The settings of the changed-path Bloom filters stored in this file, or `None` if it doesn't contain any.
+    ///
+    /// Note that [`max_changed_paths`](bloom::Settings::max_changed_paths) isn't stored, and is always the default.
+    pub fn bloom_filter_settings(&self) -> Option<bloom::Settings> {
+        self.bloom_filter_index_offset?;
+        self.bloom_filter_data.as_ref().map(|(_, settings)| *settings)
+    }
+
     /// The kind of hash used in this File.
     ///
     /// Note that it is always conforming to the hash used in the owning repository.
@@ -131,6 +140,18 @@ impl File {
     pub(crate) fn extra_edges_data(&self) -> Option<&[u8]> {
         Some(&self.data[self.extra_edges_list_range.clone()?])
     }
+
+    /// Return the changed-path Bloom filter of the commit at `pos`, or `None` if there is none or it can't be read.
+    pub(crate) fn bloom_filter_at(&self, pos: file::Position) -> Option<&[u8]> {
+        let index_offset = self.bloom_filter_index_offset?;
+        let (data_range, _) = self.bloom_filter_data.as_ref()?;
+        let pos = pos.0 as usize;
+        let read_end =
+            |pos: usize| u32::from_be_bytes(self.data[index_offset + pos * 4..][..4].try_into().unwrap()) as usize;
+        let start = if pos == 0 { 0 } else { read_end(pos - 1) };
+        let end = read_end(pos);
+        (start <= end && end <= data_range.len()).then(|| &self.data[data_range.start + start..data_range.start + end])
+    }
 }
 
 impl Debug for File {
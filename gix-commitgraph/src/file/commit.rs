@@ -104,6 +104,23 @@ impl<'a> Commit<'a> {
     pub fn root_tree_id(&self) -> &gix_hash::oid {
         self.root_tree_id
     }
+
+    /// Return the data of the changed-path Bloom filter of this commit, or `None` if the owning file doesn't have filters.
+    ///
+    /// An empty filter means that it wasn't computed for this commit. Use it with the
+    /// [settings of the owning file](File::bloom_filter_settings()).
+    pub fn changed_paths_filter(&self) -> Option<&'a [u8]> {
+        self.file.bloom_filter_at(self.pos)
+    }
+
+    /// Return `false` if `path` definitely didn't change in this commit compared to its first parent, or `true` if it may have
+    /// changed, which is also the case if there is no changed-path Bloom filter for this commit.
+    pub fn maybe_changed_path(&self, path: &bstr::BStr) -> bool {
+        match (self.file.bloom_filter_settings(), self.changed_paths_filter()) {
+            (Some(settings), Some(filter)) => settings.maybe_contains(filter, path),
+            _ => true,
+        }
+    }
 }
 
 impl Debug for Commit<'_> {
@@ -3,7 +3,8 @@ use std::path::PathBuf;
 
 use crate::{
     file::{
-        ChunkId, BASE_GRAPHS_LIST_CHUNK_ID, COMMIT_DATA_CHUNK_ID, COMMIT_DATA_ENTRY_SIZE_SANS_HASH,
+        ChunkId, BASE_GRAPHS_LIST_CHUNK_ID, BLOOM_FILTER_DATA_CHUNK_ID, BLOOM_FILTER_DATA_HEADER_LEN,
+        BLOOM_FILTER_INDEX_CHUNK_ID, COMMIT_DATA_CHUNK_ID, COMMIT_DATA_ENTRY_SIZE_SANS_HASH,
         EXTENDED_EDGES_LIST_CHUNK_ID, FAN_LEN, HEADER_LEN, OID_FAN_CHUNK_ID, OID_LOOKUP_CHUNK_ID, SIGNATURE,
     },
     File,
@@ -184,6 +185,49 @@ impl File {
 
         let extra_edges_list_range = chunks.usize_offset_by_id(EXTENDED_EDGES_LIST_CHUNK_ID).ok();
 
+        let bloom_filter_index_offset = chunks
+            .validated_usize_offset_by_id(BLOOM_FILTER_INDEX_CHUNK_ID, |chunk_range| {
+                let chunk_size = chunk_range.len();
+                let expected_size = commit_data_count as usize * 4;
+                if chunk_size != expected_size {
+                    return Err(Error::InvalidChunkSize {
+                        id: BLOOM_FILTER_INDEX_CHUNK_ID,
+                        msg: format!("expected chunk length {expected_size}, got {chunk_size}"),
+                    });
+                }
+                Ok(chunk_range.start)
+            })
+            .ok()
+            .transpose()?;
+        let bloom_filter_data = chunks
+            .validated_usize_offset_by_id(BLOOM_FILTER_DATA_CHUNK_ID, |chunk_range| {
+                if chunk_range.len() < BLOOM_FILTER_DATA_HEADER_LEN {
+                    return Err(Error::InvalidChunkSize {
+                        id: BLOOM_FILTER_DATA_CHUNK_ID,
+                        msg: format!(
+                            "chunk size {} is too small to hold the {BLOOM_FILTER_DATA_HEADER_LEN} bytes header",
+                            chunk_range.len()
+                        ),
+                    });
+                }
+                let header = &data[chunk_range.start..][..BLOOM_FILTER_DATA_HEADER_LEN];
+                let read_u32 = |ofs: usize| u32::from_be_bytes(header[ofs..][..4].try_into().unwrap());
+                let settings = crate::bloom::Settings {
+                    hash_version: read_u32(0),
+                    num_hashes: read_u32(4),
+                    bits_per_entry: read_u32(8),
+                    ..Default::default()
+                };
+                Ok((
+                    chunk_range.start + BLOOM_FILTER_DATA_HEADER_LEN..chunk_range.end,
+                    settings,
+                ))
+            })
+            .ok()
+            .transpose()?
+            // Like git, ignore filters we can't interpret.
+            .filter(|(_, settings)| matches!(settings.hash_version, 1 | 2) && settings.num_hashes > 0);
+
         let trailer = &data[chunks.highest_offset() as usize..];
         if trailer.len() != object_hash.len_in_bytes() {
             return Err(Error::Trailer(format!(
@@ -220,6 +264,8 @@ impl File {
         Ok(File {
             base_graph_count,
             base_graphs_list_offset,
+            bloom_filter_index_offset,
+            bloom_filter_data,
             commit_data_offset,
             data,
             extra_edges_list_range,
@@ -8,6 +8,7 @@ mod access;
 pub mod commit;
 mod init;
 pub mod verify;
+pub mod write;
 
 const COMMIT_DATA_ENTRY_SIZE_SANS_HASH: usize = 16;
 pub(crate) const FAN_LEN: usize = 256;
@@ -17,6 +18,10 @@ const SIGNATURE: &[u8] = b"CGPH";
 
 type ChunkId = gix_chunk::Id;
 const BASE_GRAPHS_LIST_CHUNK_ID: ChunkId = *b"BASE";
+const BLOOM_FILTER_INDEX_CHUNK_ID: ChunkId = *b"BIDX";
+const BLOOM_FILTER_DATA_CHUNK_ID: ChunkId = *b"BDAT";
+/// The size of the header of the `BDAT` chunk, containing the hash version, the number of hashes and the bits per entry.
+const BLOOM_FILTER_DATA_HEADER_LEN: usize = 12;
 const COMMIT_DATA_CHUNK_ID: ChunkId = *b"CDAT";
 const EXTENDED_EDGES_LIST_CHUNK_ID: ChunkId = *b"EDGE";
 const OID_FAN_CHUNK_ID: ChunkId = *b"OIDF";
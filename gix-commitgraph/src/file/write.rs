@@ -0,0 +1,247 @@
+//! Write a single commit-graph file.
+use std::io::Write;
+
+use gix_hash::ObjectId;
+
+use crate::{
+    bloom,
+    file::{
+        BLOOM_FILTER_DATA_CHUNK_ID, BLOOM_FILTER_DATA_HEADER_LEN, BLOOM_FILTER_INDEX_CHUNK_ID, COMMIT_DATA_CHUNK_ID,
+        COMMIT_DATA_ENTRY_SIZE_SANS_HASH, EXTENDED_EDGES_LIST_CHUNK_ID, EXTENDED_EDGES_MASK, FAN_LEN, HEADER_LEN,
+        LAST_EXTENDED_EDGE_MASK, NO_PARENT, OID_FAN_CHUNK_ID, OID_LOOKUP_CHUNK_ID, SIGNATURE,
+    },
+    File, GENERATION_NUMBER_MAX, MAX_COMMITS,
+};
+
+mod error {
+    use gix_hash::ObjectId;
+
+    /// The error returned by [`File::write_to()`](crate::File::write_to()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        Io(#[from] std::io::Error),
+        #[error("A commit-graph can hold at most {max} commits, got {actual}", max = crate::MAX_COMMITS)]
+        TooManyCommits { actual: usize },
+        #[error("Parent {parent_id} of commit {id} is not part of the commit-graph")]
+        MissingParent { id: ObjectId, parent_id: ObjectId },
+    }
+}
+pub use error::Error;
+
+/// A commit to write into a commit-graph file with [`File::write_to()`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Commit {
+    /// The id of the commit.
+    pub id: ObjectId,
+    /// The id of the tree the commit points to.
+    pub tree_id: ObjectId,
+    /// The ids of all parents of the commit, in order, which must all be written into the same file.
+    pub parent_ids: Vec<ObjectId>,
+    /// The time at which the commit was created, in seconds since the unix epoch.
+    pub commit_time: u64,
+    /// The data of the changed-path Bloom filter as computed by [`bloom::Settings::filter()`], or `None` if it wasn't computed
+    /// for this commit.
+    ///
+    /// It's only written if [`Options::changed_paths`] is set.
+    pub changed_paths_filter: Option<Vec<u8>>,
+}
+
+/// Options for use in [`File::write_to()`].
+#[derive(Debug, Clone, Copy)]
+pub struct Options {
+    /// The kind of hash to use for the commit-graph file, which must match the one of all object ids.
+    pub object_hash: gix_hash::Kind,
+    /// If set, write the changed-path Bloom filters of all commits along with the settings they were computed with.
+    pub changed_paths: Option<bloom::Settings>,
+}
+
+/// The result of [`File::write_to()`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Outcome {
+    /// The checksum of the written file, which is also written as its trailer.
+    pub checksum: ObjectId,
+    /// The amount of unique commits that were written.
+    pub num_commits: u32,
+}
+
+/// Writing
+impl File {
+    /// Write a monolithic commit-graph file containing `commits` to `out`, using `options`, in the same way as `git` does.
+    ///
+    /// Commits are sorted by id and deduplicated, and their generation numbers are computed from their parents.
+    /// All parents of all commits must be part of `commits`.
+    pub fn write_to(
+        mut commits: Vec<Commit>,
+        out: &mut dyn std::io::Write,
+        Options {
+            object_hash,
+            changed_paths,
+        }: Options,
+    ) -> Result<Outcome, Error> {
+        commits.sort_by_key(|c| c.id);
+        commits.dedup_by(|a, b| a.id == b.id);
+        let num_commits = u32::try_from(commits.len())
+            .ok()
+            .filter(|num_commits| *num_commits <= MAX_COMMITS)
+            .ok_or(Error::TooManyCommits { actual: commits.len() })?;
+
+        let parents = commits
+            .iter()
+            .map(|commit| {
+                commit
+                    .parent_ids
+                    .iter()
+                    .map(|parent_id| {
+                        commits
+                            .binary_search_by(|c| c.id.cmp(parent_id))
+                            .map(|pos| pos as u32)
+                            .map_err(|_| Error::MissingParent {
+                                id: commit.id,
+                                parent_id: *parent_id,
+                            })
+                    })
+                    .collect::<Result<Vec<_>, _>>()
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let generations = generations(&parents);
+        let num_extra_edges: usize = parents
+            .iter()
+            .filter(|parents| parents.len() > 2)
+            .map(|parents| parents.len() - 1)
+            .sum();
+
+        let hash_len = object_hash.len_in_bytes();
+        let mut cf = gix_chunk::file::Index::for_writing();
+        cf.plan_chunk(OID_FAN_CHUNK_ID, (FAN_LEN * 4) as u64);
+        cf.plan_chunk(OID_LOOKUP_CHUNK_ID, (commits.len() * hash_len) as u64);
+        cf.plan_chunk(
+            COMMIT_DATA_CHUNK_ID,
+            (commits.len() * (hash_len + COMMIT_DATA_ENTRY_SIZE_SANS_HASH)) as u64,
+        );
+        if num_extra_edges > 0 {
+            cf.plan_chunk(EXTENDED_EDGES_LIST_CHUNK_ID, (num_extra_edges * 4) as u64);
+        }
+        fn filter(commit: &Commit) -> &[u8] {
+            commit.changed_paths_filter.as_deref().unwrap_or_default()
+        }
+        if changed_paths.is_some() {
+            cf.plan_chunk(BLOOM_FILTER_INDEX_CHUNK_ID, (commits.len() * 4) as u64);
+            cf.plan_chunk(
+                BLOOM_FILTER_DATA_CHUNK_ID,
+                (BLOOM_FILTER_DATA_HEADER_LEN + commits.iter().map(|c| filter(c).len()).sum::<usize>()) as u64,
+            );
+        }
+
+        let mut out = gix_features::hash::Write::new(out, object_hash);
+        out.write_all(SIGNATURE)?;
+        out.write_all(&[1 /* version */, object_hash as u8])?;
+        out.write_all(&[cf.num_chunks().try_into().expect("BUG: wrote more than 256 chunks")])?;
+        out.write_all(&[0 /* base graphs */])?;
+
+        let mut chunk_write = cf.into_write(&mut out, HEADER_LEN)?;
+        while let Some(chunk_to_write) = chunk_write.next_chunk() {
+            match chunk_to_write {
+                OID_FAN_CHUNK_ID => {
+                    let mut commits = commits.iter().peekable();
+                    let mut count = 0_u32;
+                    for byte in 0..=255_u8 {
+                        while commits.next_if(|c| c.id.first_byte() <= byte).is_some() {
+                            count += 1;
+                        }
+                        chunk_write.write_all(&count.to_be_bytes())?;
+                    }
+                }
+                OID_LOOKUP_CHUNK_ID => {
+                    for commit in &commits {
+                        chunk_write.write_all(commit.id.as_slice())?;
+                    }
+                }
+                COMMIT_DATA_CHUNK_ID => {
+                    let mut extra_edge_index = 0_u32;
+                    for ((commit, parents), generation) in commits.iter().zip(&parents).zip(&generations) {
+                        chunk_write.write_all(commit.tree_id.as_slice())?;
+                        let (parent1, parent2) = match parents.as_slice() {
+                            [] => (NO_PARENT, NO_PARENT),
+                            [parent1] => (*parent1, NO_PARENT),
+                            [parent1, parent2] => (*parent1, *parent2),
+                            [parent1, rest @ ..] => {
+                                let parent2 = EXTENDED_EDGES_MASK | extra_edge_index;
+                                extra_edge_index += rest.len() as u32;
+                                (*parent1, parent2)
+                            }
+                        };
+                        chunk_write.write_all(&parent1.to_be_bytes())?;
+                        chunk_write.write_all(&parent2.to_be_bytes())?;
+                        let generation_and_time_high = (generation << 2) | ((commit.commit_time >> 32) & 0x3) as u32;
+                        chunk_write.write_all(&generation_and_time_high.to_be_bytes())?;
+                        chunk_write.write_all(&(commit.commit_time as u32).to_be_bytes())?;
+                    }
+                }
+                EXTENDED_EDGES_LIST_CHUNK_ID => {
+                    for parents in parents.iter().filter(|parents| parents.len() > 2) {
+                        let (last, extra) = parents[1..].split_last().expect("more than two parents");
+                        for parent in extra {
+                            chunk_write.write_all(&parent.to_be_bytes())?;
+                        }
+                        chunk_write.write_all(&(LAST_EXTENDED_EDGE_MASK | last).to_be_bytes())?;
+                    }
+                }
+                BLOOM_FILTER_INDEX_CHUNK_ID => {
+                    let mut end = 0_u32;
+                    for commit in &commits {
+                        end += filter(commit).len() as u32;
+                        chunk_write.write_all(&end.to_be_bytes())?;
+                    }
+                }
+                BLOOM_FILTER_DATA_CHUNK_ID => {
+                    let settings = changed_paths.expect("set if planned");
+                    for value in [settings.hash_version, settings.num_hashes, settings.bits_per_entry] {
+                        chunk_write.write_all(&value.to_be_bytes())?;
+                    }
+                    for commit in &commits {
+                        chunk_write.write_all(filter(commit))?;
+                    }
+                }
+                unknown => unreachable!("BUG: forgot to implement chunk {:?}", std::str::from_utf8(&unknown)),
+            }
+        }
+
+        let checksum: ObjectId = out.hash.digest().into();
+        out.inner.write_all(checksum.as_slice())?;
+        Ok(Outcome { checksum, num_commits })
+    }
+}
+
+/// Compute the topological level of each commit, given the positions of their `parents`, which is `1` for commits without
+/// parents and one more than the highest level of their parents otherwise.
+fn generations(parents: &[Vec<u32>]) -> Vec<u32> {
+    let mut generations = vec![0_u32; parents.len()];
+    let mut stack = Vec::new();
+    for start in 0..parents.len() {
+        stack.push(start);
+        while let Some(&pos) = stack.last() {
+            if generations[pos] != 0 {
+                stack.pop();
+                continue;
+            }
+            let mut max_parent_generation = 0;
+            let mut all_parents_known = true;
+            for &parent in &parents[pos] {
+                match generations[parent as usize] {
+                    0 => {
+                        all_parents_known = false;
+                        stack.push(parent as usize);
+                    }
+                    generation => max_parent_generation = max_parent_generation.max(generation),
+                }
+            }
+            if all_parents_known {
+                generations[pos] = (max_parent_generation + 1).min(GENERATION_NUMBER_MAX);
+                stack.pop();
+            }
+        }
+    }
+    generations
+}
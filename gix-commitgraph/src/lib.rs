@@ -24,6 +24,8 @@ use std::path::Path;
 pub struct File {
     base_graph_count: u8,
     base_graphs_list_offset: Option<usize>,
+    bloom_filter_index_offset: Option<usize>,
+    bloom_filter_data: Option<(std::ops::Range<usize>, bloom::Settings)>,
     commit_data_offset: usize,
     data: memmap2::Mmap,
     extra_edges_list_range: Option<std::ops::Range<usize>>,
@@ -49,9 +51,11 @@ pub fn at(path: impl AsRef<Path>) -> Result<Graph, init::Error> {
 }
 
 mod access;
+pub mod bloom;
 pub mod file;
 ///
 pub mod init;
+pub mod repair;
 pub mod verify;
 
 /// The number of generations that are considered 'infinite' commit history.
@@ -0,0 +1,202 @@
+//! Repair split commit-graph chains by dropping corrupt layers.
+use std::{
+    io::{BufRead, BufReader, Write},
+    path::{Path, PathBuf},
+};
+
+use crate::{file, init, verify, File, Graph, MAX_COMMITS};
+
+/// The error returned by [`chain()`].
+#[derive(thiserror::Error, Debug)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("Could not read the commit-graph chain file at '{}'", .path.display())]
+    ReadChain {
+        #[source]
+        err: std::io::Error,
+        path: PathBuf,
+    },
+    #[error("Could not rewrite the commit-graph chain file at '{}'", .path.display())]
+    WriteChain {
+        #[source]
+        err: std::io::Error,
+        path: PathBuf,
+    },
+    #[error("Could not remove the corrupt commit-graph layer at '{}'", .path.display())]
+    RemoveLayer {
+        #[source]
+        err: std::io::Error,
+        path: PathBuf,
+    },
+}
+
+/// The reason for a layer to be considered corrupt.
+#[derive(thiserror::Error, Debug)]
+#[allow(missing_docs)]
+pub enum Corruption<E: std::error::Error + 'static> {
+    #[error("Could not open commit-graph layer at '{}'", .path.display())]
+    Open {
+        #[source]
+        err: file::Error,
+        path: PathBuf,
+    },
+    #[error(transparent)]
+    Init(#[from] init::Error),
+    #[error(transparent)]
+    Verify(#[from] verify::Error<E>),
+}
+
+/// The result of [`chain()`].
+#[derive(Debug)]
+pub struct Outcome<E: std::error::Error + 'static> {
+    /// The amount of layers that remain in the chain, all of which passed verification.
+    pub num_kept_layers: usize,
+    /// The paths to the layers that were removed from the chain and deleted, starting at the first corrupt layer.
+    pub removed_layers: Vec<PathBuf>,
+    /// The reason for the first of the [removed layers](Self::removed_layers) to be considered corrupt, or `None` if all layers
+    /// were valid and nothing was changed.
+    pub corruption: Option<Corruption<E>>,
+}
+
+/// Verify all layers of the split commit-graph chain in `commit_graphs_dir`, typically `.git/objects/info/commit-graphs`,
+/// in order while calling `processor(graph, commit)` on each commit to allow checking it against the object database,
+/// with `graph` being useful to look up the ids of parent commits.
+///
+/// If a layer fails to load or to verify, it is dropped from the chain along with all layers that build on it, the chain file is
+/// rewritten to only contain the valid layers before it, and the files of the dropped layers are deleted.
+/// If the very first layer is corrupt, the chain file is removed entirely.
+/// The dropped commits can be added back with the next write of the commit-graph.
+///
+/// Note that the chain file is replaced or removed while holding the same `commit-graph-chain.lock` file that `git` uses.
+pub fn chain<E>(
+    commit_graphs_dir: &Path,
+    mut processor: impl FnMut(&Graph, &file::Commit<'_>) -> Result<(), E>,
+) -> Result<Outcome<E>, Error>
+where
+    E: std::error::Error + 'static,
+{
+    let chain_file_path = commit_graphs_dir.join("commit-graph-chain");
+    let hashes = std::fs::File::open(&chain_file_path)
+        .and_then(|f| BufReader::new(f).lines().collect::<Result<Vec<_>, _>>())
+        .map_err(|err| Error::ReadChain {
+            err,
+            path: chain_file_path.clone(),
+        })?;
+    let layer_path = |hash: &str| commit_graphs_dir.join(format!("graph-{hash}.graph"));
+
+    let (num_kept_layers, corruption) = {
+        let mut files = Vec::with_capacity(hashes.len());
+        let mut corruption = None;
+        for hash in &hashes {
+            let path = layer_path(hash.as_str());
+            match File::at(&path) {
+                Ok(file) => files.push(file),
+                Err(err) => {
+                    corruption = Some(Corruption::Open { err, path });
+                    break;
+                }
+            }
+        }
+        if let Some((num_compatible, err)) = incompatible_layer(&files) {
+            files.truncate(num_compatible);
+            corruption = Some(err.into());
+        }
+        let num_loaded = files.len();
+        if num_loaded == 0 {
+            (0, corruption)
+        } else {
+            match Graph::new(files) {
+                Ok(graph) => match graph.verify_integrity_by_layer(|commit| processor(&graph, commit)) {
+                    Ok(_) => (num_loaded, corruption),
+                    Err((index, err)) => (index.min(num_loaded), Some(err.into())),
+                },
+                Err(err) => (0, Some(err.into())),
+            }
+        }
+    };
+
+    let removed_layers: Vec<_> = hashes[num_kept_layers..]
+        .iter()
+        .map(|hash| layer_path(hash.as_str()))
+        .collect();
+    if removed_layers.is_empty() {
+        return Ok(Outcome {
+            num_kept_layers,
+            removed_layers,
+            corruption: None,
+        });
+    }
+
+    let lock_path = commit_graphs_dir.join("commit-graph-chain.lock");
+    let lock = std::fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(&lock_path)
+        .map_err(|err| Error::WriteChain {
+            err,
+            path: chain_file_path.clone(),
+        })?;
+    let res = if num_kept_layers == 0 {
+        drop(lock);
+        std::fs::remove_file(&chain_file_path).and_then(|_| std::fs::remove_file(&lock_path))
+    } else {
+        let mut lock = lock;
+        hashes[..num_kept_layers]
+            .iter()
+            .try_for_each(|hash| writeln!(lock, "{hash}"))
+            .and_then(|_| lock.sync_all())
+            .and_then(|_| std::fs::rename(&lock_path, &chain_file_path))
+    };
+    res.map_err(|err| {
+        std::fs::remove_file(&lock_path).ok();
+        Error::WriteChain {
+            err,
+            path: chain_file_path.clone(),
+        }
+    })?;
+
+    for path in &removed_layers {
+        match std::fs::remove_file(path) {
+            Ok(()) => {}
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => {}
+            Err(err) => {
+                return Err(Error::RemoveLayer {
+                    err,
+                    path: path.clone(),
+                })
+            }
+        }
+    }
+
+    Ok(Outcome {
+        num_kept_layers,
+        removed_layers,
+        corruption,
+    })
+}
+
+/// Return the amount of leading `files` that can be combined into a [`Graph`] along with the reason the next layer can't be added,
+/// or `None` if all layers are compatible.
+fn incompatible_layer(files: &[File]) -> Option<(usize, init::Error)> {
+    let mut num_commits = 0u64;
+    for (index, file) in files.iter().enumerate() {
+        num_commits += u64::from(file.num_commits());
+        if num_commits > u64::from(MAX_COMMITS) {
+            return Some((index, init::Error::TooManyCommits(num_commits)));
+        }
+        if let Some(previous) = index.checked_sub(1).map(|previous| &files[previous]) {
+            if previous.object_hash() != file.object_hash() {
+                return Some((
+                    index,
+                    init::Error::HashVersionMismatch {
+                        path1: previous.path().to_owned(),
+                        hash1: previous.object_hash(),
+                        path2: file.path().to_owned(),
+                        hash2: file.object_hash(),
+                    },
+                ));
+            }
+        }
+    }
+    None
+}
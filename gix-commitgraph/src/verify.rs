@@ -76,14 +76,27 @@ impl Graph {
     /// When `processor` returns an error, the entire verification is stopped and the error returned.
     pub fn verify_integrity<E>(
         &self,
-        mut processor: impl FnMut(&file::Commit<'_>) -> Result<(), E>,
+        processor: impl FnMut(&file::Commit<'_>) -> Result<(), E>,
     ) -> Result<Outcome, Error<E>>
     where
         E: std::error::Error + 'static,
     {
-        if self.files.len() > 256 {
+        self.verify_integrity_by_layer(processor).map_err(|(_, err)| err)
+    }
+
+    /// Like [`verify_integrity()`][Self::verify_integrity()], but return the index of the file in the chain which failed verification
+    /// along with the error, with all files before it known to be valid.
+    pub(crate) fn verify_integrity_by_layer<E>(
+        &self,
+        mut processor: impl FnMut(&file::Commit<'_>) -> Result<(), E>,
+    ) -> Result<Outcome, (usize, Error<E>)>
+    where
+        E: std::error::Error + 'static,
+    {
+        const MAX_FILES: usize = 256;
+        if self.files.len() > MAX_FILES {
             // A file in a split chain can only have up to 255 base files.
-            return Err(Error::TooManyFiles(self.files.len()));
+            return Err((MAX_FILES, Error::TooManyFiles(self.files.len())));
         }
 
         let mut stats = Outcome {
@@ -99,13 +112,16 @@ impl Graph {
         let mut file_start_pos = Position(0);
         for (file_index, file) in self.files.iter().enumerate() {
             if usize::from(file.base_graph_count()) != file_index {
-                return Err(Error::BaseGraphCount {
-                    actual: file.base_graph_count(),
-                    expected: file_index
-                        .try_into()
-                        .expect("files.len() check to protect against this"),
-                    path: file.path().to_owned(),
-                });
+                return Err((
+                    file_index,
+                    Error::BaseGraphCount {
+                        actual: file.base_graph_count(),
+                        expected: file_index
+                            .try_into()
+                            .expect("files.len() check to protect against this"),
+                        path: file.path().to_owned(),
+                    },
+                ));
             }
 
             for (base_graph_index, (expected, actual)) in self.files[..file_index]
@@ -115,14 +131,17 @@ impl Graph {
                 .enumerate()
             {
                 if actual != expected {
-                    return Err(Error::BaseGraphId {
-                        actual: actual.into(),
-                        expected: expected.into(),
-                        index: base_graph_index
-                            .try_into()
-                            .expect("files.len() check to protect against this"),
-                        path: file.path().to_owned(),
-                    });
+                    return Err((
+                        file_index,
+                        Error::BaseGraphId {
+                            actual: actual.into(),
+                            expected: expected.into(),
+                            index: base_graph_index
+                                .try_into()
+                                .expect("files.len() check to protect against this"),
+                            path: file.path().to_owned(),
+                        },
+                    ));
                 }
             }
 
@@ -184,7 +203,8 @@ impl Graph {
                         },
                     },
                     path: file.path().to_owned(),
-                })?;
+                })
+                .map_err(|err| (file_index, err))?;
 
             max_generation = max(max_generation, file_stats.max_generation);
             stats.num_commits += file_stats.num_commits;
@@ -0,0 +1,62 @@
+use bstr::ByteSlice;
+use gix_commitgraph::bloom::{murmur3, Settings};
+
+#[test]
+fn murmur3_matches_known_values() {
+    for version in [1, 2] {
+        assert_eq!(murmur3(0, b"", version), 0);
+        assert_eq!(murmur3(0, b"Hello world!", version), 0x627b_0c2c);
+        assert_eq!(
+            murmur3(0, b"The quick brown fox jumps over the lazy dog", version),
+            0x2e4f_f723
+        );
+    }
+    assert_ne!(
+        murmur3(0, "ümlaut".as_bytes(), 1),
+        murmur3(0, "ümlaut".as_bytes(), 2),
+        "version 1 sign-extends bytes with the highest bit set"
+    );
+}
+
+#[test]
+fn filters_contain_paths_and_their_leading_directories() {
+    let settings = Settings::default();
+    let filter = settings.filter(Some(b"dir/sub/file".as_bstr()));
+    assert_eq!(filter.len(), 4, "three keys with 10 bits each");
+    for path in ["dir/sub/file", "dir/sub", "dir"] {
+        assert!(settings.maybe_contains(&filter, path.into()), "{path}");
+    }
+}
+
+#[test]
+fn empty_filters() {
+    let settings = Settings::default();
+    let filter = settings.filter(None);
+    assert_eq!(
+        filter,
+        [0],
+        "a commit without changes still has a filter, which matches nothing"
+    );
+    assert!(!settings.maybe_contains(&filter, "a".into()));
+    assert!(
+        settings.maybe_contains(&[], "a".into()),
+        "without filter data, everything may have changed"
+    );
+}
+
+#[test]
+fn filters_with_too_many_changes_match_everything() {
+    let settings = Settings {
+        max_changed_paths: 2,
+        ..Default::default()
+    };
+    let filter = settings.filter(Some(b"a/b".as_bstr()));
+    assert_ne!(filter, [0xff], "two keys are still within the limit");
+
+    let filter = settings.filter(Some(b"a/b/c".as_bstr()));
+    assert_eq!(filter, [0xff], "leading directories count as well");
+    assert!(settings.maybe_contains(&filter, "anything".into()));
+
+    let filter = settings.filter(["a", "b", "c"].iter().map(|p| p.as_bytes().as_bstr()));
+    assert_eq!(filter, [0xff]);
+}
@@ -0,0 +1,106 @@
+use std::convert::Infallible;
+
+use gix_commitgraph::Graph;
+use gix_testtools::scripted_fixture_writable;
+
+fn commit_graphs_dir(repo_dir: &std::path::Path) -> std::path::PathBuf {
+    repo_dir.join(".git").join("objects").join("info").join("commit-graphs")
+}
+
+fn noop(_commit: &gix_commitgraph::file::Commit<'_>) -> Result<(), Infallible> {
+    Ok(())
+}
+
+fn noop_with_graph(_graph: &Graph, _commit: &gix_commitgraph::file::Commit<'_>) -> Result<(), Infallible> {
+    Ok(())
+}
+
+#[test]
+fn valid_chain_is_left_untouched() -> gix_testtools::Result {
+    let tmp = scripted_fixture_writable("split_chain.sh")?;
+    let dir = commit_graphs_dir(tmp.path());
+    let chain_before = std::fs::read(dir.join("commit-graph-chain"))?;
+
+    let outcome = gix_commitgraph::repair::chain(&dir, noop_with_graph)?;
+    assert_eq!(outcome.num_kept_layers, 3);
+    assert!(outcome.removed_layers.is_empty());
+    assert!(outcome.corruption.is_none());
+    assert_eq!(std::fs::read(dir.join("commit-graph-chain"))?, chain_before);
+    Ok(())
+}
+
+#[test]
+fn corrupt_layer_is_dropped_along_with_all_layers_building_on_it() -> gix_testtools::Result {
+    let tmp = scripted_fixture_writable("split_chain.sh")?;
+    let dir = commit_graphs_dir(tmp.path());
+    let chain = std::fs::read_to_string(dir.join("commit-graph-chain"))?;
+    let hashes: Vec<_> = chain.lines().collect();
+    assert_eq!(hashes.len(), 3);
+
+    let middle_layer = dir.join(format!("graph-{}.graph", hashes[1]));
+    let mut data = std::fs::read(&middle_layer)?;
+    let last_byte_before_checksum = data.len() - 21;
+    data[last_byte_before_checksum] ^= 0xff;
+    std::fs::write(&middle_layer, data)?;
+
+    let outcome = gix_commitgraph::repair::chain(&dir, noop_with_graph)?;
+    assert_eq!(outcome.num_kept_layers, 1, "only the first layer is valid");
+    assert_eq!(
+        outcome.removed_layers,
+        [middle_layer.clone(), dir.join(format!("graph-{}.graph", hashes[2]))]
+    );
+    assert!(
+        matches!(outcome.corruption, Some(gix_commitgraph::repair::Corruption::Verify(_))),
+        "the checksum doesn't match anymore"
+    );
+    assert!(outcome.removed_layers.iter().all(|path| !path.exists()));
+    assert!(!dir.join("commit-graph-chain.lock").exists());
+
+    let graph = Graph::from_commit_graphs_dir(&dir)?;
+    assert_eq!(graph.num_commits(), 1);
+    graph.verify_integrity(noop)?;
+    Ok(())
+}
+
+#[test]
+fn chain_is_not_changed_while_locked() -> gix_testtools::Result {
+    let tmp = scripted_fixture_writable("split_chain.sh")?;
+    let dir = commit_graphs_dir(tmp.path());
+    let chain_before = std::fs::read(dir.join("commit-graph-chain"))?;
+    let hashes: Vec<_> = std::str::from_utf8(&chain_before)?
+        .lines()
+        .map(ToOwned::to_owned)
+        .collect();
+    let first_layer = dir.join(format!("graph-{}.graph", hashes[0]));
+    std::fs::write(&first_layer, b"not a commit-graph")?;
+    std::fs::write(dir.join("commit-graph-chain.lock"), b"")?;
+
+    let err = gix_commitgraph::repair::chain(&dir, noop_with_graph).unwrap_err();
+    assert!(matches!(err, gix_commitgraph::repair::Error::WriteChain { .. }));
+    assert_eq!(
+        std::fs::read(dir.join("commit-graph-chain"))?,
+        chain_before,
+        "the chain isn't removed without holding the lock"
+    );
+    assert!(
+        dir.join("commit-graph-chain.lock").is_file(),
+        "the lock of others is kept"
+    );
+    assert!(
+        hashes
+            .iter()
+            .all(|hash| dir.join(format!("graph-{hash}.graph")).is_file()),
+        "no layer is removed either"
+    );
+
+    std::fs::remove_file(dir.join("commit-graph-chain.lock"))?;
+    let outcome = gix_commitgraph::repair::chain(&dir, noop_with_graph)?;
+    assert_eq!(outcome.num_kept_layers, 0);
+    assert!(matches!(
+        outcome.corruption,
+        Some(gix_commitgraph::repair::Corruption::Open { .. })
+    ));
+    assert!(!dir.join("commit-graph-chain").exists());
+    assert!(!dir.join("commit-graph-chain.lock").exists());
+    Ok(())
+}
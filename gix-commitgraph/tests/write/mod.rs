@@ -0,0 +1,150 @@
+use gix_commitgraph::{
+    file::{self, write},
+    File,
+};
+use gix_hash::ObjectId;
+use gix_testtools::scripted_fixture_read_only;
+
+use crate::graph_and_expected;
+
+fn to_write_commit(file: &File, commit: file::Commit<'_>) -> write::Commit {
+    write::Commit {
+        id: commit.id().into(),
+        tree_id: commit.root_tree_id().into(),
+        parent_ids: commit
+            .iter_parents()
+            .map(|pos| file.id_at(file::Position(pos.expect("valid parent").0)).into())
+            .collect(),
+        commit_time: commit.committer_timestamp(),
+        changed_paths_filter: commit.changed_paths_filter().map(ToOwned::to_owned),
+    }
+}
+
+#[test]
+fn rewriting_a_graph_written_by_git_yields_the_same_file() -> gix_testtools::Result {
+    let path = scripted_fixture_read_only("changed_paths.sh")?.join(".git/objects/info/commit-graph");
+    let file = File::at(&path)?;
+    let settings = file.bloom_filter_settings().expect("written with changed paths");
+    assert_eq!(settings, Default::default(), "git uses the same defaults");
+
+    let commits = file.iter_commits().map(|c| to_write_commit(&file, c)).collect();
+    let mut buf = Vec::new();
+    let outcome = File::write_to(
+        commits,
+        &mut buf,
+        write::Options {
+            object_hash: file.object_hash(),
+            changed_paths: Some(settings),
+        },
+    )?;
+    assert_eq!(outcome.num_commits, file.num_commits());
+    assert_eq!(outcome.checksum.as_slice(), &buf[buf.len() - 20..]);
+    assert_eq!(buf, std::fs::read(&path)?, "the files are byte-for-byte identical");
+    Ok(())
+}
+
+#[test]
+fn changed_paths_written_by_git_can_be_queried() {
+    let (cg, refs) = graph_and_expected("changed_paths.sh", &["main~1", "side", "main", "empty", "many"]);
+    let commit = |name: &str| cg.commit_by_id(refs[name].id()).expect("present");
+
+    let root = commit("main~1");
+    for path in ["a", "dir", "dir/b", "dir/sub", "dir/sub/c"] {
+        assert!(root.maybe_changed_path(path.into()), "{path}");
+    }
+
+    let side = commit("side");
+    let settings = gix_commitgraph::bloom::Settings::default();
+    assert_eq!(
+        side.changed_paths_filter(),
+        Some(settings.filter(["dir/sub/c", "dir/ümlaut"].map(Into::into)).as_slice()),
+        "filters are computed exactly like git does, even for paths that aren't ASCII"
+    );
+    for path in ["dir/sub/c", "dir/sub", "dir", "dir/ümlaut"] {
+        assert!(side.maybe_changed_path(path.into()), "{path}");
+    }
+    for path in ["a", "dir/b", "e/f"] {
+        assert!(!side.maybe_changed_path(path.into()), "{path}");
+    }
+
+    let main = commit("main");
+    assert!(main.maybe_changed_path("a".into()), "deletions are changes");
+    assert!(!main.maybe_changed_path("dir/sub/c".into()));
+
+    assert_eq!(commit("empty").changed_paths_filter(), Some(&[0][..]));
+    assert_eq!(
+        commit("many").changed_paths_filter(),
+        Some(&[0xff][..]),
+        "too many changes"
+    );
+}
+
+#[test]
+fn written_graphs_can_be_read() -> gix_testtools::Result {
+    let id = |byte: u8| ObjectId::from([byte; 20]);
+    let commit = |byte: u8, parents: &[u8]| write::Commit {
+        id: id(byte),
+        tree_id: id(0xee),
+        parent_ids: parents.iter().copied().map(id).collect(),
+        commit_time: u64::from(byte) << 32 | 0x2a,
+        changed_paths_filter: None,
+    };
+    let commits = vec![
+        commit(3, &[1, 2, 4, 5]),
+        commit(1, &[]),
+        commit(2, &[1]),
+        commit(4, &[]),
+        commit(5, &[2]),
+        commit(1, &[]),
+    ];
+
+    let tmp = gix_testtools::tempfile::TempDir::new()?;
+    let path = tmp.path().join("commit-graph");
+    let mut out = std::fs::File::create(&path)?;
+    let outcome = File::write_to(
+        commits,
+        &mut out,
+        write::Options {
+            object_hash: gix_hash::Kind::Sha1,
+            changed_paths: None,
+        },
+    )?;
+    drop(out);
+    assert_eq!(outcome.num_commits, 5, "duplicates are removed");
+
+    let file = File::at(&path)?;
+    assert_eq!(file.bloom_filter_settings(), None);
+    let octopus = file.commit_at(file.lookup(id(3)).expect("present"));
+    assert_eq!(octopus.generation(), 4);
+    assert_eq!(octopus.committer_timestamp(), 3 << 32 | 0x2a);
+    assert_eq!(octopus.root_tree_id(), id(0xee));
+    assert_eq!(
+        octopus
+            .iter_parents()
+            .map(|pos| file.id_at(file::Position(pos.expect("valid").0)).to_owned())
+            .collect::<Vec<_>>(),
+        [1, 2, 4, 5].map(id)
+    );
+    assert_eq!(file.commit_at(file.lookup(id(5)).expect("present")).generation(), 3);
+    Ok(())
+}
+
+#[test]
+fn all_parents_must_be_written() {
+    let err = File::write_to(
+        vec![write::Commit {
+            id: ObjectId::from([1; 20]),
+            tree_id: ObjectId::from([2; 20]),
+            parent_ids: vec![ObjectId::from([3; 20])],
+            commit_time: 0,
+            changed_paths_filter: None,
+        }],
+        &mut Vec::new(),
+        write::Options {
+            object_hash: gix_hash::Kind::Sha1,
+            changed_paths: None,
+        },
+    )
+    .unwrap_err();
+    assert!(matches!(err, write::Error::MissingParent { .. }));
+}
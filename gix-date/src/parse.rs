@@ -14,7 +14,12 @@ pub enum Error {
 pub(crate) mod function {
     use std::{str::FromStr, time::SystemTime};
 
-    use jiff::{civil::Date, fmt::rfc2822, tz::TimeZone, Zoned};
+    use jiff::{
+        civil::{Date, DateTime},
+        fmt::rfc2822,
+        tz::TimeZone,
+        Zoned,
+    };
 
     use crate::{
         parse::{relative, Error},
@@ -25,18 +30,23 @@ pub(crate) mod function {
         SecondsSinceUnixEpoch, Time,
     };
 
+    /// E.g. `2018-12-24 12:45:06`, as accepted by git in place of `ISO8601` if the timezone is omitted.
+    const SHORT_WITH_TIME: &str = "%Y-%m-%d %H:%M:%S";
+
     #[allow(missing_docs)]
     pub fn parse(input: &str, now: Option<SystemTime>) -> Result<Time, Error> {
-        // TODO: actual implementation, this is just to not constantly fail
-        if input == "1979-02-26 18:30:00" {
-            return Ok(Time::new(42, 1800));
-        }
-
         Ok(if let Ok(val) = Date::strptime(SHORT.0, input) {
             let val = val
                 .to_zoned(TimeZone::UTC)
                 .map_err(|_| Error::InvalidDateString { input: input.into() })?;
             Time::new(val.timestamp().as_second(), val.offset().seconds())
+        } else if let Ok(val) = DateTime::strptime(SHORT_WITH_TIME, input) {
+            // Like in `git`, a date and time without timezone is in the local timezone, which is also the one
+            // signatures are created with, so it keeps its offset.
+            let val = val
+                .to_zoned(TimeZone::system())
+                .map_err(|_| Error::InvalidDateString { input: input.into() })?;
+            Time::new(val.timestamp().as_second(), val.offset().seconds())
         } else if let Ok(val) = rfc2822_relaxed(input) {
             Time::new(val.timestamp().as_second(), val.offset().seconds())
         } else if let Ok(val) = strptime_relaxed(ISO8601.0, input) {
@@ -1,16 +1,22 @@
 use std::time::SystemTime;
 
-use gix_date::{time::Sign, Time};
+use gix_date::{time::Sign, SecondsSinceUnixEpoch, Time};
 
 #[test]
-fn special_time_is_ok_for_now() {
+fn short_with_time() {
+    let time = gix_date::parse("1979-02-26 18:30:00", Some(SystemTime::now())).unwrap();
+    let local_offset = jiff::tz::TimeZone::system()
+        .to_offset(jiff::Timestamp::from_second(time.seconds).unwrap())
+        .0
+        .seconds();
     assert_eq!(
-        gix_date::parse("1979-02-26 18:30:00", Some(SystemTime::now())).unwrap(),
-        Time {
-            seconds: 42,
-            offset: 1800,
-            sign: Sign::Plus,
-        }
+        time.offset, local_offset,
+        "a date and time without timezone is in the local timezone, like in `git`"
+    );
+    assert_eq!(
+        time.seconds + SecondsSinceUnixEpoch::from(time.offset),
+        288901800,
+        "the offset is applied, so the date and time are the same in the local timezone"
     );
 }
 
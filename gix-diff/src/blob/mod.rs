@@ -11,6 +11,9 @@ pub mod pipeline;
 ///
 pub mod platform;
 
+mod unified_diff;
+pub use unified_diff::UnifiedDiff;
+
 /// Information about the diff performed to detect similarity.
 #[derive(Debug, Default, Clone, Copy, PartialEq, PartialOrd)]
 pub struct DiffLineStats {
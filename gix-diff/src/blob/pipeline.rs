@@ -6,6 +6,7 @@ use std::{
 
 use bstr::{BStr, ByteSlice};
 use gix_filter::{
+    binary::Heuristic,
     driver::apply::{Delay, MaybeDelayed},
     pipeline::convert::{ToGitOutcome, ToWorktreeOutcome},
 };
@@ -258,7 +259,7 @@ impl Pipeline {
                 .is_binary
                 .map(|is_binary| is_binary && driver.binary_to_text_command.is_none())
         } else {
-            attr.assignment.state.is_unset().then_some(true)
+            gix_filter::binary::forced_by_attribute(attr.assignment.state)
         };
         match self.roots.by_kind(kind) {
             Some(root) => {
@@ -365,13 +366,15 @@ impl Pipeline {
                                                 })?;
                                             }
 
-                                            Some(if is_binary.unwrap_or_else(|| is_binary_buf(out)) {
-                                                let size = out.len() as u64;
-                                                out.clear();
-                                                Data::Binary { size }
-                                            } else {
-                                                Data::Buffer
-                                            })
+                                            Some(
+                                                if gix_filter::binary::is_binary(out, is_binary, Heuristic::NullByte) {
+                                                    let size = out.len() as u64;
+                                                    out.clear();
+                                                    Data::Binary { size }
+                                                } else {
+                                                    Data::Buffer
+                                                },
+                                            )
                                         }
                                         None => None,
                                     }
@@ -403,10 +406,15 @@ impl Pipeline {
                             .try_find(id, out)
                             .map_err(gix_object::find::existing_object::Error::Find)?
                             .ok_or_else(|| gix_object::find::existing_object::Error::NotFound { oid: id.to_owned() })?;
-                        if matches!(mode, EntryKind::Blob | EntryKind::BlobExecutable)
-                            && convert == Mode::ToWorktreeAndBinaryToText
-                            || (convert == Mode::ToGitUnlessBinaryToTextIsPresent
-                                && driver.map_or(false, |d| d.binary_to_text_command.is_some()))
+                        // Like `git`, look at the content as stored in `git` to see if it's binary, as after conversion
+                        // to the worktree, text in a `working-tree-encoding` like UTF-16 would appear binary.
+                        let is_binary = driver.map_or(true, |d| d.binary_to_text_command.is_none())
+                            && gix_filter::binary::is_binary(out, is_binary, Heuristic::NullByte);
+                        if !is_binary
+                            && (matches!(mode, EntryKind::Blob | EntryKind::BlobExecutable)
+                                && convert == Mode::ToWorktreeAndBinaryToText
+                                || (convert == Mode::ToGitUnlessBinaryToTextIsPresent
+                                    && driver.map_or(false, |d| d.binary_to_text_command.is_some())))
                         {
                             let res =
                                 self.worktree_filter
@@ -485,9 +493,7 @@ impl Pipeline {
                             }
                         }
 
-                        if driver.map_or(true, |d| d.binary_to_text_command.is_none())
-                            && is_binary.unwrap_or_else(|| is_binary_buf(out))
-                        {
+                        if is_binary {
                             let size = out.len() as u64;
                             out.clear();
                             Data::Binary { size }
@@ -503,11 +509,6 @@ impl Pipeline {
     }
 }
 
-fn is_binary_buf(buf: &[u8]) -> bool {
-    let buf = &buf[..buf.len().min(8000)];
-    buf.contains(&0)
-}
-
 fn none_if_missing<T>(res: std::io::Result<T>) -> std::io::Result<Option<T>> {
     match res {
         Ok(data) => Ok(Some(data)),
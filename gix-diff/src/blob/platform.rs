@@ -1,6 +1,11 @@
 use bstr::{BStr, BString, ByteSlice};
 use std::cmp::Ordering;
-use std::{io::Write, process::Stdio};
+use std::{
+    io::{Read, Write},
+    process::Stdio,
+};
+
+use gix_filter::pipeline::convert::ToGitOutcome;
 
 use super::Algorithm;
 use crate::blob::{pipeline, Pipeline, Platform, ResourceKind};
@@ -244,6 +249,40 @@ pub mod prepare_diff {
     }
 }
 
+///
+pub mod compare_binary {
+    use bstr::BString;
+
+    use crate::blob::ResourceKind;
+
+    /// The outcome of [Platform::compare_binary()](super::Platform::compare_binary()).
+    #[derive(Debug, Copy, Clone, Eq, PartialEq)]
+    pub struct Outcome {
+        /// If `true`, the content of the old and the new resource differs.
+        pub differs: bool,
+        /// The size of the old resource, or `None` if it doesn't exist.
+        pub old_size: Option<u64>,
+        /// The size of the new resource, or `None` if it doesn't exist.
+        pub new_size: Option<u64>,
+    }
+
+    /// The error returned by [Platform::compare_binary()](super::Platform::compare_binary()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("Either the source or the destination of the diff operation were not set")]
+        SourceOrDestinationUnset,
+        #[error("Failed to read {kind} worktree data from '{rela_path}'")]
+        Io {
+            rela_path: BString,
+            kind: ResourceKind,
+            source: std::io::Error,
+        },
+        #[error(transparent)]
+        ConvertToGit(#[from] gix_filter::pipeline::convert::to_git::Error),
+    }
+}
+
 ///
 pub mod prepare_diff_command {
     use std::ops::{Deref, DerefMut};
@@ -538,6 +577,108 @@ impl Platform {
         Ok(out)
     }
 
+    /// Determine if the [previously set](Self::set_resource()) resources differ without holding more than a few blocks of
+    /// their data in memory at a time, which is useful if [`prepare_diff()`](Self::prepare_diff()) returned
+    /// [`SourceOrDestinationIsBinary`](prepare_diff::Operation::SourceOrDestinationIsBinary) as they are binary or
+    /// [too large](pipeline::Options::large_file_threshold_bytes) to be diffed.
+    ///
+    /// Resources with known ids are compared by id, and are compared block by block to other worktree resources.
+    /// Resources in the worktree are converted to what would be stored in `git` using the
+    /// [worktree filters](Pipeline::worktree_filter) and the attributes in `objects`, and hashed to compare them to a known id.
+    /// Note that filters which can't stream, like `eol` conversions, need all data of the file in memory.
+    pub fn compare_binary(
+        &mut self,
+        objects: &impl gix_object::FindObjectOrHeader,
+    ) -> Result<compare_binary::Outcome, compare_binary::Error> {
+        let (old, new) = self
+            .resources()
+            .ok_or(compare_binary::Error::SourceOrDestinationUnset)?;
+        let size = |res: &Resource<'_>| match res.data {
+            resource::Data::Missing => None,
+            resource::Data::Buffer(buf) => Some(buf.len() as u64),
+            resource::Data::Binary { size } => Some(size),
+        };
+        let mut out = compare_binary::Outcome {
+            differs: true,
+            old_size: size(&old),
+            new_size: size(&new),
+        };
+        let old_path = self.worktree_path(&old, ResourceKind::OldOrSource);
+        let new_path = self.worktree_path(&new, ResourceKind::NewOrDestination);
+        // Sizes of worktree files can't be compared to the size of objects as filters may change them.
+        if out.old_size.is_none() != out.new_size.is_none()
+            || (out.old_size != out.new_size && old_path.is_some() == new_path.is_some())
+        {
+            return Ok(out);
+        }
+
+        let (path, rela_path, kind, size, id) = match (old.data, new.data) {
+            (resource::Data::Missing, resource::Data::Missing) => {
+                out.differs = false;
+                return Ok(out);
+            }
+            (resource::Data::Buffer(old), resource::Data::Buffer(new)) => {
+                out.differs = old != new;
+                return Ok(out);
+            }
+            _ if old_path.is_none() && new_path.is_none() => {
+                out.differs = old.id != new.id;
+                return Ok(out);
+            }
+            _ if old_path.is_none() && !old.id.is_null() => (
+                new_path.expect("checked before"),
+                new.rela_path.to_owned(),
+                ResourceKind::NewOrDestination,
+                out.new_size.unwrap_or_default(),
+                old.id.to_owned(),
+            ),
+            _ if new_path.is_none() && !new.id.is_null() => (
+                old_path.expect("checked before"),
+                old.rela_path.to_owned(),
+                ResourceKind::OldOrSource,
+                out.old_size.unwrap_or_default(),
+                new.id.to_owned(),
+            ),
+            _ => {
+                let old_stream = open(&old, old_path.as_deref()).map_err(io_err(&old, ResourceKind::OldOrSource))?;
+                let new_stream =
+                    open(&new, new_path.as_deref()).map_err(io_err(&new, ResourceKind::NewOrDestination))?;
+                out.differs =
+                    streams_differ(old_stream, new_stream).map_err(io_err(&old, ResourceKind::OldOrSource))?;
+                return Ok(out);
+            }
+        };
+
+        let map_io_err = |source| compare_binary::Error::Io {
+            rela_path: rela_path.clone(),
+            kind,
+            source,
+        };
+        let entry = self
+            .attr_stack
+            .at_entry(rela_path.as_bstr(), None, objects)
+            .map_err(map_io_err)?;
+        let file = std::fs::File::open(&path).map_err(map_io_err)?;
+        let actual_id = match self.filter.worktree_filter.convert_to_git(
+            file,
+            gix_path::from_bstr(rela_path.as_bstr()).as_ref(),
+            &mut |_, attrs| {
+                let _ = entry.matching_attributes(attrs);
+            },
+            &mut |buf| objects.try_find(&id, buf).map(|obj| obj.map(|_| ())),
+        )? {
+            ToGitOutcome::Unchanged(file) => hash_stream(file, size, id.kind()).map_err(map_io_err)?,
+            ToGitOutcome::Process(mut stream) => {
+                let mut buf = Vec::new();
+                stream.read_to_end(&mut buf).map_err(map_io_err)?;
+                gix_object::compute_hash(id.kind(), gix_object::Kind::Blob, &buf)
+            }
+            ToGitOutcome::Buffer(buf) => gix_object::compute_hash(id.kind(), gix_object::Kind::Blob, buf),
+        };
+        out.differs = actual_id != id;
+        Ok(out)
+    }
+
     /// Every call to [set_resource()](Self::set_resource()) will keep the diffable data in memory, and that will never be cleared.
     ///
     /// Use this method to clear the cache, releasing memory. Note that this will also lose all information about resources
@@ -583,7 +724,81 @@ impl Platform {
     }
 }
 
+/// The size of the blocks to read when comparing resources that don't fit into memory.
+const BLOCK_SIZE: usize = 64 * 1024;
+
+fn open<'a>(res: &Resource<'a>, path: Option<&std::path::Path>) -> std::io::Result<Box<dyn std::io::Read + 'a>> {
+    Ok(match (res.data, path) {
+        (resource::Data::Buffer(buf), _) => Box::new(buf),
+        (_, Some(path)) => Box::new(std::fs::File::open(path)?),
+        (_, None) => {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::NotFound,
+                "Binary resources without worktree location can only be compared by id",
+            ))
+        }
+    })
+}
+
+fn hash_stream(
+    mut stream: impl std::io::Read,
+    size: u64,
+    hash_kind: gix_hash::Kind,
+) -> std::io::Result<gix_hash::ObjectId> {
+    let mut hasher = gix_features::hash::hasher(hash_kind);
+    hasher.update(&gix_object::encode::loose_header(gix_object::Kind::Blob, size));
+    let mut buf = vec![0; BLOCK_SIZE];
+    loop {
+        let bytes_read = stream.read(&mut buf)?;
+        if bytes_read == 0 {
+            break;
+        }
+        hasher.update(&buf[..bytes_read]);
+    }
+    Ok(gix_hash::ObjectId::from(hasher.digest()))
+}
+
+fn io_err(res: &Resource<'_>, kind: ResourceKind) -> impl FnOnce(std::io::Error) -> compare_binary::Error {
+    let rela_path = res.rela_path.to_owned();
+    move |source| compare_binary::Error::Io {
+        rela_path,
+        kind,
+        source,
+    }
+}
+
+fn streams_differ(mut old: impl std::io::Read, mut new: impl std::io::Read) -> std::io::Result<bool> {
+    fn read_block(stream: &mut impl std::io::Read, buf: &mut [u8]) -> std::io::Result<usize> {
+        let mut filled = 0;
+        while filled < buf.len() {
+            match stream.read(&mut buf[filled..])? {
+                0 => break,
+                n => filled += n,
+            }
+        }
+        Ok(filled)
+    }
+    let (mut old_buf, mut new_buf) = (vec![0; BLOCK_SIZE], vec![0; BLOCK_SIZE]);
+    loop {
+        let old_len = read_block(&mut old, &mut old_buf)?;
+        let new_len = read_block(&mut new, &mut new_buf)?;
+        if old_buf[..old_len] != new_buf[..new_len] {
+            return Ok(true);
+        }
+        if old_len == 0 {
+            return Ok(false);
+        }
+    }
+}
+
 impl Platform {
+    fn worktree_path(&self, res: &Resource<'_>, kind: ResourceKind) -> Option<std::path::PathBuf> {
+        self.filter
+            .roots
+            .by_kind(kind)
+            .map(|root| root.join(gix_path::from_bstr(res.rela_path)))
+    }
+
     fn set_resource_inner(
         &mut self,
         id: gix_hash::ObjectId,
@@ -0,0 +1,189 @@
+use std::{hash::Hash, ops::Range};
+
+use bstr::BString;
+use imara_diff::{
+    intern::{InternedInput, Interner, Token},
+    Sink,
+};
+
+/// The amount of bytes of a line preceding a hunk that are shown in its header, like `git` does.
+const MAX_FUNCTION_LINE_LEN: usize = 80;
+
+/// A [`Sink`] that produces the hunks of a unified diff exactly like `git diff` does.
+///
+/// In contrast to [`UnifiedDiffBuilder`](imara_diff::UnifiedDiffBuilder), hunk headers omit line counts of `1`,
+/// show the line preceding the hunk that looks like the start of a function, and lines without
+/// a terminator at the end of the file are marked with `\ No newline at end of file`.
+///
+/// Tokens are expected to be lines *including* their terminator, as produced by
+/// [`prepare_diff::Outcome::interned_input()`](crate::blob::platform::prepare_diff::Outcome::interned_input()).
+/// File headers like `diff --git` or `---` and `+++` lines are not produced.
+pub struct UnifiedDiff<'a, T>
+where
+    T: Hash + Eq + AsRef<[u8]>,
+{
+    before: &'a [Token],
+    after: &'a [Token],
+    interner: &'a Interner<T>,
+    context_lines: u32,
+
+    /// The position in `before` up to which all lines were printed.
+    pos: u32,
+    hunk: Option<Hunk>,
+    /// The lines of the current hunk.
+    buffer: Vec<u8>,
+    /// The last line that looked like the start of a function, as used in hunk headers.
+    function_line: Vec<u8>,
+    /// The line in `before` at which the last search for a `function_line` started, which is where the next search stops.
+    function_line_search_limit: Option<u32>,
+    out: BString,
+}
+
+struct Hunk {
+    before_start: u32,
+    before_len: u32,
+    after_start: u32,
+    after_len: u32,
+}
+
+impl<'a, T> UnifiedDiff<'a, T>
+where
+    T: Hash + Eq + AsRef<[u8]>,
+{
+    /// Create a new instance to print all hunks of `input` with `context_lines` of unchanged lines around them,
+    /// `git` uses `3` by default.
+    pub fn new(input: &'a InternedInput<T>, context_lines: u32) -> Self {
+        UnifiedDiff {
+            before: &input.before,
+            after: &input.after,
+            interner: &input.interner,
+            context_lines,
+            pos: 0,
+            hunk: None,
+            buffer: Vec::new(),
+            function_line: Vec::new(),
+            function_line_search_limit: None,
+            out: BString::default(),
+        }
+    }
+
+    fn print_line(&mut self, prefix: u8, token: Token) {
+        let line = self.interner[token].as_ref();
+        self.buffer.push(prefix);
+        self.buffer.extend_from_slice(line);
+        if !line.ends_with(b"\n") {
+            self.buffer.extend_from_slice(b"\n\\ No newline at end of file\n");
+        }
+    }
+
+    fn print_context_up_to(&mut self, end: u32) {
+        let hunk = self.hunk.as_mut().expect("only called within a hunk");
+        let len = end - self.pos;
+        hunk.before_len += len;
+        hunk.after_len += len;
+        for idx in self.pos..end {
+            self.print_line(b' ', self.before[idx as usize]);
+        }
+        self.pos = end;
+    }
+
+    /// Find the closest line before `before_start` that looks like the start of a function,
+    /// or keep the previous one if there is none since the last search.
+    fn update_function_line(&mut self, before_start: u32) {
+        let stop = self.function_line_search_limit;
+        self.function_line_search_limit = before_start.checked_sub(1);
+        for idx in (0..before_start).rev() {
+            if stop == Some(idx) {
+                break;
+            }
+            let line = self.interner[self.before[idx as usize]].as_ref();
+            if line
+                .first()
+                .map_or(false, |b| b.is_ascii_alphabetic() || *b == b'_' || *b == b'$')
+            {
+                let line = &line[..line.len().min(MAX_FUNCTION_LINE_LEN)];
+                let end = line
+                    .iter()
+                    .rposition(|b| !b.is_ascii_whitespace())
+                    .map_or(0, |pos| pos + 1);
+                self.function_line.clear();
+                self.function_line.extend_from_slice(&line[..end]);
+                break;
+            }
+        }
+    }
+
+    fn flush(&mut self) {
+        if self.hunk.is_none() {
+            return;
+        }
+        let end = (self.pos + self.context_lines).min(self.before.len() as u32);
+        self.print_context_up_to(end);
+        let hunk = self.hunk.take().expect("checked above");
+
+        self.update_function_line(hunk.before_start);
+        let out = &mut self.out;
+        out.extend_from_slice(b"@@ -");
+        write_range(out, hunk.before_start, hunk.before_len);
+        out.extend_from_slice(b" +");
+        write_range(out, hunk.after_start, hunk.after_len);
+        out.extend_from_slice(b" @@");
+        if !self.function_line.is_empty() {
+            out.push(b' ');
+            out.extend_from_slice(&self.function_line);
+        }
+        out.push(b'\n');
+        out.extend_from_slice(&self.buffer);
+        self.buffer.clear();
+    }
+}
+
+/// Write `start` and `len` the way `git` does in hunk headers, with `start` being zero-based.
+fn write_range(out: &mut BString, start: u32, len: u32) {
+    let start = if len == 0 { start } else { start + 1 };
+    out.extend_from_slice(start.to_string().as_bytes());
+    if len != 1 {
+        out.push(b',');
+        out.extend_from_slice(len.to_string().as_bytes());
+    }
+}
+
+impl<T> Sink for UnifiedDiff<'_, T>
+where
+    T: Hash + Eq + AsRef<[u8]>,
+{
+    type Out = BString;
+
+    fn process_change(&mut self, before: Range<u32>, after: Range<u32>) {
+        if self.hunk.is_some() && before.start - self.pos > 2 * self.context_lines {
+            self.flush();
+        }
+        if self.hunk.is_none() {
+            let before_start = before.start.saturating_sub(self.context_lines);
+            self.hunk = Some(Hunk {
+                before_start,
+                before_len: 0,
+                after_start: after.start - (before.start - before_start),
+                after_len: 0,
+            });
+            self.pos = before_start;
+        }
+        self.print_context_up_to(before.start);
+
+        for idx in before.clone() {
+            self.print_line(b'-', self.before[idx as usize]);
+        }
+        for idx in after.clone() {
+            self.print_line(b'+', self.after[idx as usize]);
+        }
+        let hunk = self.hunk.as_mut().expect("set above");
+        hunk.before_len += before.len() as u32;
+        hunk.after_len += after.len() as u32;
+        self.pos = before.end;
+    }
+
+    fn finish(mut self) -> Self::Out {
+        self.flush();
+        self.out
+    }
+}
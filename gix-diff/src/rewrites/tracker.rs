@@ -145,6 +145,8 @@ pub mod emit {
         SetResource(#[from] crate::blob::platform::set_resource::Error),
         #[error(transparent)]
         PrepareDiff(#[from] crate::blob::platform::prepare_diff::Error),
+        #[error(transparent)]
+        CompareBinary(#[from] crate::blob::platform::compare_binary::Error),
     }
 }
 
@@ -719,7 +721,18 @@ fn find_match<'a, T: Change>(
                     unreachable!("we have disabled this possibility with an option")
                 }
                 Operation::SourceOrDestinationIsBinary => {
-                    // TODO: figure out if git does more here
+                    // Resources without an id, like files in the worktree, can still be identical.
+                    if !diff_cache.compare_binary(objects)?.differs {
+                        return Ok(Some((
+                            can_idx,
+                            src,
+                            DiffLineStats {
+                                similarity: 1.0,
+                                ..Default::default()
+                            }
+                            .into(),
+                        )));
+                    }
                 }
             };
         }
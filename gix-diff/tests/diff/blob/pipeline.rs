@@ -534,6 +534,114 @@ pub(crate) mod convert_to_diffable {
         Ok(())
     }
 
+    #[test]
+    fn binary_by_attributes_and_in_git_representation() -> crate::Result {
+        let tmp = gix_testtools::tempfile::TempDir::new()?;
+        std::fs::write(
+            tmp.path().join(".gitattributes"),
+            "forced-text diff\nforced-binary -diff\nutf16 working-tree-encoding=UTF-16LE\n",
+        )?;
+        let mut attributes = gix_worktree::Stack::new(
+            tmp.path(),
+            gix_worktree::stack::State::AttributesStack(gix_worktree::stack::state::Attributes::new(
+                Default::default(),
+                None,
+                attributes::Source::WorktreeThenIdMapping,
+                Default::default(),
+            )),
+            gix_worktree::glob::pattern::Case::Sensitive,
+            Vec::new(),
+            Vec::new(),
+        );
+        let mut filter = gix_diff::blob::Pipeline::new(
+            WorktreeRoots {
+                old_root: Some(tmp.path().to_owned()),
+                new_root: None,
+            },
+            gix_filter::Pipeline::default(),
+            vec![],
+            default_options(),
+        );
+
+        let does_not_matter = gix_hash::Kind::Sha1.null();
+        let mut buf = Vec::new();
+        let mut db = ObjectDb::default();
+        let id = db.insert("a\0b");
+        for (kind, root_id) in [
+            (ResourceKind::OldOrSource, does_not_matter),
+            (ResourceKind::NewOrDestination, id),
+        ] {
+            std::fs::write(tmp.path().join("forced-text"), "a\0b")?;
+            let out = filter.convert_to_diffable(
+                &root_id,
+                EntryKind::Blob,
+                "forced-text".into(),
+                kind,
+                &mut |path, out| {
+                    let platform = attributes.at_entry(path, None, &gix_object::find::Never).unwrap();
+                    platform.matching_attributes(out);
+                },
+                &db,
+                pipeline::Mode::default(),
+                &mut buf,
+            )?;
+            assert_eq!(
+                out.data,
+                Some(pipeline::Data::Buffer),
+                "the `diff` attribute forces text, like in `git`"
+            );
+            assert_eq!(buf.as_bstr(), "a\0b");
+        }
+
+        let id = db.insert("text");
+        std::fs::write(tmp.path().join("forced-binary"), "text")?;
+        for (kind, root_id) in [
+            (ResourceKind::OldOrSource, does_not_matter),
+            (ResourceKind::NewOrDestination, id),
+        ] {
+            let out = filter.convert_to_diffable(
+                &root_id,
+                EntryKind::Blob,
+                "forced-binary".into(),
+                kind,
+                &mut |path, out| {
+                    let platform = attributes.at_entry(path, None, &gix_object::find::Never).unwrap();
+                    platform.matching_attributes(out);
+                },
+                &db,
+                pipeline::Mode::default(),
+                &mut buf,
+            )?;
+            assert_eq!(
+                out.data,
+                Some(pipeline::Data::Binary { size: 4 }),
+                "`-diff` forces binary"
+            );
+        }
+
+        std::fs::write(tmp.path().join("utf16"), b"h\0i\0\n\0")?;
+        let out = filter.convert_to_diffable(
+            &does_not_matter,
+            EntryKind::Blob,
+            "utf16".into(),
+            ResourceKind::OldOrSource,
+            &mut |path, out| {
+                let platform = attributes.at_entry(path, None, &gix_object::find::Never).unwrap();
+                platform.matching_attributes(out);
+            },
+            &gix_object::find::Never,
+            pipeline::Mode::ToGit,
+            &mut buf,
+        )?;
+        assert_eq!(
+            out.data,
+            Some(pipeline::Data::Buffer),
+            "text in a `working-tree-encoding` is looked at in its UTF-8 representation, so it's not binary"
+        );
+        assert_eq!(buf.as_bstr(), "hi\n");
+        Ok(())
+    }
+
     #[test]
     fn with_driver() -> crate::Result {
         let root = gix_testtools::scripted_fixture_read_only_standalone("make_blob_repo.sh")?;
@@ -214,6 +214,131 @@ fn diff_binary() -> crate::Result {
     Ok(())
 }
 
+#[test]
+fn compare_binary() -> crate::Result {
+    let mut platform = new_platform(
+        Some(gix_diff::blob::Driver {
+            name: "a".into(),
+            is_binary: Some(true),
+            ..Default::default()
+        }),
+        gix_diff::blob::pipeline::Mode::default(),
+    );
+    platform.set_resource(
+        gix_hash::Kind::Sha1.null(),
+        EntryKind::Blob,
+        "a".into(),
+        ResourceKind::OldOrSource,
+        &gix_object::find::Never,
+    )?;
+
+    let mut db = ObjectDb::default();
+    let same_id = db.insert("a\n");
+    platform.set_resource(
+        same_id,
+        EntryKind::Blob,
+        "b".into(),
+        ResourceKind::NewOrDestination,
+        &db,
+    )?;
+    assert_eq!(
+        platform.compare_binary(&db)?,
+        platform::compare_binary::Outcome {
+            differs: false,
+            old_size: Some(2),
+            new_size: Some(2),
+        },
+        "the worktree file is hashed to compare it to the object"
+    );
+
+    let other_id = db.insert("b\n");
+    platform.set_resource(
+        other_id,
+        EntryKind::Blob,
+        "b".into(),
+        ResourceKind::NewOrDestination,
+        &db,
+    )?;
+    assert!(platform.compare_binary(&db)?.differs, "same size, but different content");
+
+    let longer_id = db.insert("longer");
+    platform.set_resource(
+        longer_id,
+        EntryKind::Blob,
+        "b".into(),
+        ResourceKind::NewOrDestination,
+        &db,
+    )?;
+    let out = platform.compare_binary(&db)?;
+    assert!(out.differs);
+    assert_eq!(out.new_size, Some(6));
+
+    platform.filter.roots.new_root = platform.filter.roots.old_root.clone();
+    platform.set_resource(
+        gix_hash::Kind::Sha1.null(),
+        EntryKind::Blob,
+        "b".into(),
+        ResourceKind::NewOrDestination,
+        &gix_object::find::Never,
+    )?;
+    assert!(
+        platform.compare_binary(&db)?.differs,
+        "worktree files are compared block by block"
+    );
+
+    platform.set_resource(
+        gix_hash::Kind::Sha1.null(),
+        EntryKind::Blob,
+        "a".into(),
+        ResourceKind::NewOrDestination,
+        &gix_object::find::Never,
+    )?;
+    assert!(!platform.compare_binary(&db)?.differs);
+    Ok(())
+}
+
+#[test]
+fn compare_binary_converts_worktree_files_with_filters() -> crate::Result {
+    let mut platform = new_platform(
+        Some(gix_diff::blob::Driver {
+            name: "a".into(),
+            is_binary: Some(true),
+            ..Default::default()
+        }),
+        gix_diff::blob::pipeline::Mode::default(),
+    );
+    let tmp = gix_testtools::tempfile::TempDir::new()?;
+    std::fs::write(tmp.path().join("a"), "a\r\n")?;
+    platform.filter.roots.old_root = Some(tmp.path().to_owned());
+
+    let mut db = ObjectDb::default();
+    let id = db.insert("a\n");
+    platform.set_resource(
+        gix_hash::Kind::Sha1.null(),
+        EntryKind::Blob,
+        "a".into(),
+        ResourceKind::OldOrSource,
+        &db,
+    )?;
+    platform.set_resource(id, EntryKind::Blob, "a".into(), ResourceKind::NewOrDestination, &db)?;
+    assert_eq!(
+        platform.compare_binary(&db)?,
+        platform::compare_binary::Outcome {
+            differs: true,
+            old_size: Some(3),
+            new_size: Some(2),
+        },
+        "without eol conversion, the worktree file differs"
+    );
+
+    platform.filter.worktree_filter.options_mut().eol_config.auto_crlf = gix_filter::eol::AutoCrlf::Enabled;
+    assert!(
+        !platform.compare_binary(&db)?.differs,
+        "the worktree file is converted to what would be stored in git before hashing it, despite the different size"
+    );
+    Ok(())
+}
+
 #[test]
 fn diff_performed_despite_external_command() -> crate::Result {
     let mut platform = new_platform(
@@ -0,0 +1,73 @@
+use gix_diff::blob::{intern::InternedInput, sources::byte_lines_with_terminator, Algorithm, UnifiedDiff};
+use gix_object::bstr::BString;
+
+fn diff(before: &str, after: &str, context_lines: u32) -> BString {
+    let input = InternedInput::new(
+        byte_lines_with_terminator(before.as_bytes()),
+        byte_lines_with_terminator(after.as_bytes()),
+    );
+    gix_diff::blob::diff(Algorithm::Myers, &input, UnifiedDiff::new(&input, context_lines))
+}
+
+#[test]
+fn single_lines_omit_their_count() {
+    assert_eq!(diff("a\n", "b\n", 3), "@@ -1 +1 @@\n-a\n+b\n");
+}
+
+#[test]
+fn empty_sides_start_before_the_first_line() {
+    assert_eq!(diff("", "a\nb\n", 3), "@@ -0,0 +1,2 @@\n+a\n+b\n");
+    assert_eq!(diff("a\nb\n", "", 3), "@@ -1,2 +0,0 @@\n-a\n-b\n");
+    assert_eq!(
+        diff("a\nb\n", "a\nx\nb\n", 0),
+        "@@ -1,0 +2 @@ a\n+x\n",
+        "insertions refer to the line they follow"
+    );
+}
+
+#[test]
+fn missing_newlines_at_the_end_of_file_are_marked() {
+    assert_eq!(
+        diff("a\nb", "a\nb\n", 3),
+        "@@ -1,2 +1,2 @@\n a\n-b\n\\ No newline at end of file\n+b\n"
+    );
+}
+
+#[test]
+fn close_changes_share_a_hunk_and_distant_ones_do_not() {
+    let before = "1\n2\n3\n4\n5\n6\n7\n8\n9\n10\n11\n12\n13\n14\n15\n16\n";
+    assert_eq!(
+        diff(
+            before,
+            &before.replacen("2\n", "two\n", 1).replacen("8\n", "eight\n", 1),
+            3
+        ),
+        "@@ -1,11 +1,11 @@\n 1\n-2\n+two\n 3\n 4\n 5\n 6\n 7\n-8\n+eight\n 9\n 10\n 11\n",
+        "up to twice the context lines between changes keep them in one hunk"
+    );
+    assert_eq!(
+        diff(
+            before,
+            &before.replacen("2\n", "two\n", 1).replace("\n10\n", "\nten\n"),
+            3
+        ),
+        "@@ -1,5 +1,5 @@\n 1\n-2\n+two\n 3\n 4\n 5\n@@ -7,7 +7,7 @@\n 7\n 8\n 9\n-10\n+ten\n 11\n 12\n 13\n"
+    );
+}
+
+#[test]
+fn hunk_headers_show_the_preceding_function_line() {
+    let before = "fn first() {\n\t1\n\t2\n\t3\n\t4\n}\n\nfn second() {\n\t1\n\t2\n\t3\n\t4\n\t5\n}\n";
+    let after = before
+        .replace("\t4\n\t5\n", "\t4\n\tfive\n")
+        .replace("\t2\n\t3\n\t4\n}\n\nfn", "\tzwei\n\t3\n\t4\n}\n\nfn");
+    assert_eq!(
+        diff(before, &after, 1),
+        "@@ -2,3 +2,3 @@ fn first() {\n \t1\n-\t2\n+\tzwei\n \t3\n@@ -12,3 +12,3 @@ fn second() {\n \t4\n-\t5\n+\tfive\n }\n"
+    );
+    assert_eq!(
+        diff("fn a() {\n\t1\n\t2\n\t3\n", "fn a() {\n\t1\n\t2\n\tthree\n", 1),
+        "@@ -3,2 +3,2 @@ fn a() {\n \t2\n-\t3\n+\tthree\n",
+        "the function line is the first line that starts with a letter, `_` or `$`"
+    );
+}
@@ -522,6 +522,52 @@ fn rename_by_50_percent_similarity() -> crate::Result {
     Ok(())
 }
 
+#[test]
+fn rename_of_binary_worktree_file_by_similarity() -> crate::Result {
+    let rewrites = Rewrites {
+        copies: None,
+        percentage: Some(0.5),
+        limit: 0,
+    };
+    let mut track = util::new_tracker(rewrites);
+    let data = "binary\0data\n";
+    let mut odb = util::add_retained_blobs(&mut track, [(Change::deletion(), "a", data)]);
+    assert!(
+        track.try_push_change(Change::addition(), "b".into()).is_none(),
+        "the worktree file has no id yet"
+    );
+    let tmp = gix_testtools::tempfile::TempDir::new()?;
+    std::fs::write(tmp.path().join("b"), data)?;
+    let id = odb.insert(data);
+
+    let mut calls = 0;
+    let out = track.emit(
+        |dst, src| {
+            assert_eq!(calls, 0, "only one pair is emitted");
+            calls += 1;
+            let src = src.expect("binary files are compared by content");
+            assert_eq!(src.id, id);
+            assert_eq!(
+                src.diff,
+                Some(DiffLineStats {
+                    similarity: 1.0,
+                    ..Default::default()
+                })
+            );
+            assert_eq!(dst.location, "b");
+            Action::Continue
+        },
+        &mut util::new_platform(gix_diff::blob::pipeline::WorktreeRoots {
+            old_root: None,
+            new_root: Some(tmp.path().to_owned()),
+        }),
+        &odb,
+        |_| -> Result<(), std::io::Error> { unreachable!("no copy detection") },
+    )?;
+    assert_eq!(out.num_similarity_checks, 1);
+    Ok(())
+}
+
 #[test]
 fn directories_without_relation_are_ignored() -> crate::Result {
     let mut track = util::new_tracker(Default::default());
@@ -830,7 +876,7 @@ mod util {
         tracker
             .emit(
                 cb,
-                &mut new_platform_no_worktree(),
+                &mut new_platform(Default::default()),
                 &objects,
                 |cb| -> Result<(), std::io::Error> {
                     let sources = std::mem::take(&mut sources);
@@ -850,7 +896,7 @@ mod util {
         rewrites::Tracker::new(rewrites)
     }
 
-    fn new_platform_no_worktree() -> gix_diff::blob::Platform {
+    pub fn new_platform(roots: gix_diff::blob::pipeline::WorktreeRoots) -> gix_diff::blob::Platform {
         let root = gix_testtools::scripted_fixture_read_only_standalone("make_blob_repo.sh").expect("valid fixture");
         let attributes = gix_worktree::Stack::new(
             root,
@@ -865,7 +911,7 @@ mod util {
             Vec::new(),
         );
         let filter = gix_diff::blob::Pipeline::new(
-            Default::default(),
+            roots,
             gix_filter::Pipeline::default(),
             Vec::new(),
             Default::default(),
@@ -67,21 +67,23 @@ pub(crate) fn git_with_metadata(
 
     {
         // Fast-path: avoid doing the complete search if HEAD is already not there.
-        // TODO(reftable): use a ref-store to lookup HEAD if ref-tables should be supported, or detect ref-tables beforehand.
-        //                 Actually ref-tables still keep a specially marked `HEAD` around, so nothing might be needed here
-        //                 Even though our head-check later would fail without supporting it.
-        if !dot_git.join("HEAD").exists() {
+        let head_path = dot_git.join("HEAD");
+        if !head_path.exists() {
             return Err(crate::is_git::Error::MissingHead);
         }
-        // We expect to be able to parse any ref-hash, so we shouldn't have to know the repos hash here.
-        // With ref-table, the has is probably stored as part of the ref-db itself, so we can handle it from there.
-        // In other words, it's important not to fail on detached heads here because we guessed the hash kind wrongly.
-        let refs = gix_ref::file::Store::at(dot_git.as_ref().into(), Default::default());
-        let head = refs.find_loose("HEAD")?;
-        if head.name.as_bstr() != "HEAD" {
-            return Err(crate::is_git::Error::MisplacedHead {
-                name: head.name.into_inner(),
-            });
+        // With ref-tables, `HEAD` is a specially marked file to keep older versions of git away, the actual `HEAD` is in the table.
+        let is_reftable_head = dot_git.join("reftable").is_dir()
+            && std::fs::read(&head_path).map_or(false, |head| head == b"ref: refs/heads/.invalid\n");
+        if !is_reftable_head {
+            // We expect to be able to parse any ref-hash, so we shouldn't have to know the repos hash here.
+            // It's important not to fail on detached heads here because we guessed the hash kind wrongly.
+            let refs = gix_ref::file::Store::at(dot_git.as_ref().into(), Default::default());
+            let head = refs.find_loose("HEAD")?;
+            if head.name.as_bstr() != "HEAD" {
+                return Err(crate::is_git::Error::MisplacedHead {
+                    name: head.name.into_inner(),
+                });
+            }
         }
     }
 
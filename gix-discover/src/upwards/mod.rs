@@ -1,5 +1,5 @@
 mod types;
-pub use types::{Error, Options};
+pub use types::{parse_ceiling_dirs, Error, Options, Outcome};
 
 mod util;
 
@@ -8,7 +8,7 @@ pub(crate) mod function {
 
     use gix_sec::Trust;
 
-    use super::{Error, Options};
+    use super::{Error, Options, Outcome};
     #[cfg(unix)]
     use crate::upwards::util::device_id;
     use crate::{
@@ -22,9 +22,16 @@ pub(crate) mod function {
     /// an associated Trust level by looking at the git directory's ownership, and control discovery using `options`.
     ///
     /// Fail if no valid-looking git repository could be found.
-    // TODO: tests for trust-based discovery
+    pub fn discover_opts(directory: &Path, options: Options<'_>) -> Result<(crate::repository::Path, Trust), Error> {
+        discover_with_trust(directory, options).map(|outcome| (outcome.path, outcome.trust))
+    }
+
+    /// Like [`discover_opts()`], but return an [`Outcome`] with information about how the trust level of the
+    /// repository was determined, along with the limits that were in effect during discovery.
+    ///
+    /// Fail if no valid-looking git repository could be found.
     #[cfg_attr(not(unix), allow(unused_variables))]
-    pub fn discover_opts(
+    pub fn discover_with_trust(
         directory: &Path,
         Options {
             required_trust,
@@ -34,7 +41,7 @@ pub(crate) mod function {
             current_dir,
             dot_git_only,
         }: Options<'_>,
-    ) -> Result<(crate::repository::Path, Trust), Error> {
+    ) -> Result<Outcome, Error> {
         // Normalize the path so that `Path::parent()` _actually_ gives
         // us the parent directory. (`Path::parent` just strips off the last
         // path component, which means it will not do what you expect when
@@ -67,9 +74,11 @@ pub(crate) mod function {
                 .or_else(|_| dir.as_ref().strip_prefix(cwd.as_ref()))
                 .is_ok();
 
-        let filter_by_trust = |x: &Path| -> Result<Option<Trust>, Error> {
-            let trust = Trust::from_path_ownership(x).map_err(|err| Error::CheckTrust { path: x.into(), err })?;
-            Ok((trust >= required_trust).then_some(trust))
+        let filter_by_trust = |x: &Path| -> Result<(gix_sec::identity::Ownership, Option<Trust>), Error> {
+            let ownership =
+                gix_sec::identity::path_ownership(x).map_err(|err| Error::CheckTrust { path: x.into(), err })?;
+            let trust = Trust::from_ownership(ownership);
+            Ok((ownership, (trust >= required_trust).then_some(trust)))
         };
 
         let max_height = if !ceiling_dirs.is_empty() {
@@ -134,27 +143,31 @@ pub(crate) mod function {
                     None => is_git(&cursor),
                 } {
                     match filter_by_trust(&cursor)? {
-                        Some(trust) => {
+                        (ownership, Some(trust)) => {
                             // TODO: test this more, it definitely doesn't always find the shortest path to a directory
                             let path = if dir_made_absolute {
                                 shorten_path_with_cwd(cursor, cwd.as_ref())
                             } else {
                                 cursor
                             };
-                            break 'outer Ok((
-                                crate::repository::Path::from_dot_git_dir(path, kind, cwd.as_ref()).ok_or_else(
+                            break 'outer Ok(Outcome {
+                                path: crate::repository::Path::from_dot_git_dir(path, kind, cwd.as_ref()).ok_or_else(
                                     || Error::InvalidInput {
                                         directory: directory.into(),
                                     },
                                 )?,
                                 trust,
-                            ));
+                                ownership,
+                                height: current_height - 1,
+                                max_height,
+                            });
                         }
-                        None => {
+                        (ownership, None) => {
                             break 'outer Err(Error::NoTrustedGitRepository {
                                 path: dir.into_owned(),
                                 candidate: cursor,
                                 required: required_trust,
+                                ownership,
                             })
                         }
                     }
@@ -23,6 +23,7 @@ pub enum Error {
         path: PathBuf,
         candidate: PathBuf,
         required: gix_sec::Trust,
+        ownership: gix_sec::identity::Ownership,
     },
     #[error("Could not determine trust level for path '{}'.", .path.display())]
     CheckTrust {
@@ -32,6 +33,23 @@ pub enum Error {
     },
 }
 
+/// The outcome of a [discovery][crate::upwards_with_trust()] of a repository, which explains how its trust level was derived.
+#[derive(Debug, Clone)]
+pub struct Outcome {
+    /// The location of the repository that was found.
+    pub path: crate::repository::Path,
+    /// The trust level of the repository, derived from `ownership`.
+    pub trust: gix_sec::Trust,
+    /// Who owns the git directory of the repository, relative to the user executing the current process.
+    pub ownership: gix_sec::identity::Ownership,
+    /// The amount of parent directories that were traversed from the search directory to find the repository,
+    /// with `0` indicating that it was found in the search directory itself.
+    pub height: usize,
+    /// The maximum amount of parent directories we were allowed to traverse as determined by the ceiling directories,
+    /// or `None` if there was no limit.
+    pub max_height: Option<usize>,
+}
+
 /// Options to help guide the [discovery][crate::upwards()] of repositories, along with their options
 /// when instantiated.
 pub struct Options<'a> {
@@ -105,7 +123,7 @@ impl Options<'_> {
 /// On Windows, paths are separated by `;`.
 /// Non-absolute paths are discarded.
 /// To match git, all paths are normalized, until an empty path is encountered.
-pub(crate) fn parse_ceiling_dirs(ceiling_dirs: &OsStr) -> Vec<PathBuf> {
+pub fn parse_ceiling_dirs(ceiling_dirs: &OsStr) -> Vec<PathBuf> {
     let mut should_normalize = true;
     let mut out = Vec::new();
     for ceiling_dir in std::env::split_paths(ceiling_dirs) {
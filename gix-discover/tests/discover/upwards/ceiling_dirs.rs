@@ -31,6 +31,27 @@ fn git_dir_candidate_within_ceiling_allows_discovery() -> crate::Result {
     Ok(())
 }
 
+#[test]
+fn git_dir_candidate_within_ceiling_reports_heights() -> crate::Result {
+    let work_dir = repo_path()?;
+    let dir = work_dir.join("some/very/deeply/nested/subdir");
+    let outcome = gix_discover::upwards_with_trust(
+        &dir,
+        Options {
+            ceiling_dirs: vec![work_dir.clone()],
+            ..Default::default()
+        },
+    )?;
+    assert_eq!(outcome.height, 5);
+    assert_eq!(
+        outcome.max_height,
+        Some(5),
+        "the ceiling is exactly where the repository was found"
+    );
+    assert_repo_is_current_workdir(outcome.path, &work_dir);
+    Ok(())
+}
+
 #[test]
 fn ceiling_dir_is_ignored_if_we_are_standing_on_the_ceiling_and_no_match_is_required() -> crate::Result {
     let work_dir = repo_path()?;
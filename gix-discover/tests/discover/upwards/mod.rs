@@ -116,6 +116,59 @@ fn from_nested_dir() -> crate::Result {
     Ok(())
 }
 
+#[test]
+fn from_nested_dir_with_trust_outcome() -> crate::Result {
+    let working_dir = repo_path()?;
+    let dir = working_dir.join("some/very/deeply/nested/subdir");
+    let outcome = gix_discover::upwards_with_trust(&dir, Default::default())?;
+    assert_eq!(outcome.path.kind(), Kind::WorkTree { linked_git_dir: None });
+    assert_eq!(outcome.path.as_ref(), working_dir);
+    assert_eq!(outcome.trust, expected_trust());
+    assert_eq!(
+        outcome.ownership.is_current_user(),
+        outcome.trust == gix_sec::Trust::Full,
+        "trust is derived from ownership"
+    );
+    assert_eq!(
+        outcome.height, 5,
+        "the repository is five levels above the search directory"
+    );
+    assert_eq!(outcome.max_height, None, "there are no ceiling directories");
+    Ok(())
+}
+
+#[test]
+fn required_trust_is_enforced() -> crate::Result {
+    let working_dir = repo_path()?;
+    let dir = working_dir.join("some/very/deeply/nested/subdir");
+    let res = gix_discover::upwards_with_trust(
+        &dir,
+        gix_discover::upwards::Options {
+            required_trust: gix_sec::Trust::Full,
+            ..Default::default()
+        },
+    );
+    match expected_trust() {
+        gix_sec::Trust::Full => {
+            let outcome = res?;
+            assert_eq!(outcome.path.as_ref(), working_dir);
+            assert_eq!(outcome.trust, gix_sec::Trust::Full);
+            assert!(outcome.ownership.is_current_user());
+        }
+        gix_sec::Trust::Reduced => {
+            assert!(
+                matches!(
+                    res,
+                    Err(gix_discover::upwards::Error::NoTrustedGitRepository { required: gix_sec::Trust::Full, ownership, .. })
+                        if !ownership.is_current_user()
+                ),
+                "repositories with insufficient trust are rejected, along with the reason"
+            );
+        }
+    }
+    Ok(())
+}
+
 #[test]
 fn from_dir_with_dot_dot() -> crate::Result {
     // This would be neater if we could just change the actual working directory,
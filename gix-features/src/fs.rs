@@ -367,3 +367,55 @@ pub fn open_options_no_follow() -> std::fs::OpenOptions {
     }
     options
 }
+
+/// Determines if and how files are flushed to disk after they were written and before they are moved into place,
+/// trading durability for speed, similar to `core.fsyncMethod` in `git`.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Fsync {
+    /// Leave it to the operating system to flush files eventually, which is fastest but may lose data or leave
+    /// empty or partial files behind on system crashes.
+    #[default]
+    None,
+    /// Ask the operating system to start writing the contents of the file to disk without waiting for it to finish,
+    /// which makes it more likely that the file survives a system crash without making writes much slower.
+    ///
+    /// This uses `sync_file_range()` on Linux like `git` does, and flushes the contents of the file like `fdatasync()` on
+    /// all other platforms, which is as durable as it gets without flushing metadata, but slower.
+    WriteoutOnly,
+    /// Flush the contents of the file along with its metadata, like `fsync()`, which is the most durable choice.
+    PerFile,
+    /// Used when writing many files at once, to only start writing their contents like [`WriteoutOnly`](Self::WriteoutOnly).
+    ///
+    /// Note that `git` flushes to disk once more after the last file of a batch was written, which is not done here
+    /// as writes don't know about batches yet.
+    Batch,
+}
+
+impl Fsync {
+    /// Flush `file` to disk as configured.
+    pub fn sync(self, file: &std::fs::File) -> std::io::Result<()> {
+        match self {
+            Fsync::None => Ok(()),
+            Fsync::WriteoutOnly | Fsync::Batch => writeout(file),
+            Fsync::PerFile => file.sync_all(),
+        }
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn writeout(file: &std::fs::File) -> std::io::Result<()> {
+    use std::os::unix::io::AsRawFd;
+    // SAFETY: the file descriptor is valid for as long as `file` is alive, and no memory is passed.
+    #[allow(unsafe_code)]
+    let res = unsafe { libc::sync_file_range(file.as_raw_fd(), 0, 0, libc::SYNC_FILE_RANGE_WRITE) };
+    if res == 0 {
+        Ok(())
+    } else {
+        Err(std::io::Error::last_os_error())
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+fn writeout(file: &std::fs::File) -> std::io::Result<()> {
+    file.sync_data()
+}
@@ -50,6 +50,93 @@ mod _impl {
 #[cfg(any(feature = "rustsha1", feature = "fast-sha1"))]
 pub use _impl::Sha1 as Hasher;
 
+#[cfg(any(feature = "rustsha1", feature = "fast-sha1"))]
+mod checked {
+    use sha1_checked::{digest::Update, CollisionResult};
+
+    /// A implementation of the Sha1 hash which detects collision attacks, which can be used once.
+    #[derive(Clone)]
+    pub struct Sha1(sha1_checked::Sha1);
+
+    impl Default for Sha1 {
+        fn default() -> Self {
+            Sha1(sha1_checked::Sha1::builder().safe_hash(false).build())
+        }
+    }
+
+    impl Sha1 {
+        /// Digest the given `bytes`.
+        pub fn update(&mut self, bytes: &[u8]) {
+            self.0.update(bytes);
+        }
+        /// Finalize the hash and produce a digest, or fail if the data was found to be part of a collision attack.
+        pub fn try_digest(self) -> Result<super::Digest, gix_hash::hasher::Error> {
+            match self.0.try_finalize() {
+                CollisionResult::Ok(digest) => Ok(digest.into()),
+                CollisionResult::Mitigated(digest) | CollisionResult::Collision(digest) => {
+                    Err(gix_hash::hasher::Error::CollisionAttack {
+                        digest: gix_hash::ObjectId::from(<super::Digest>::from(digest)),
+                    })
+                }
+            }
+        }
+    }
+}
+
+#[cfg(any(feature = "rustsha1", feature = "fast-sha1"))]
+pub use checked::Sha1 as CollisionDetectingHasher;
+
+/// A hasher of a [flavor](gix_hash::hasher::Sha1Flavor) that was chosen for a particular operation.
+///
+/// Obtain it with [`hasher_with_flavor()`].
+#[cfg(any(feature = "rustsha1", feature = "fast-sha1"))]
+#[derive(Clone)]
+pub enum FlavoredHasher {
+    /// The fast implementation.
+    Fast(Hasher),
+    /// The implementation with collision detection.
+    CollisionDetecting(Box<CollisionDetectingHasher>),
+}
+
+#[cfg(any(feature = "rustsha1", feature = "fast-sha1"))]
+impl gix_hash::Hasher for Hasher {
+    fn update(&mut self, bytes: &[u8]) {
+        Hasher::update(self, bytes);
+    }
+
+    fn try_finalize(self) -> Result<gix_hash::ObjectId, gix_hash::hasher::Error> {
+        Ok(self.digest().into())
+    }
+}
+
+#[cfg(any(feature = "rustsha1", feature = "fast-sha1"))]
+impl gix_hash::Hasher for CollisionDetectingHasher {
+    fn update(&mut self, bytes: &[u8]) {
+        CollisionDetectingHasher::update(self, bytes);
+    }
+
+    fn try_finalize(self) -> Result<gix_hash::ObjectId, gix_hash::hasher::Error> {
+        self.try_digest().map(Into::into)
+    }
+}
+
+#[cfg(any(feature = "rustsha1", feature = "fast-sha1"))]
+impl gix_hash::Hasher for FlavoredHasher {
+    fn update(&mut self, bytes: &[u8]) {
+        match self {
+            FlavoredHasher::Fast(hasher) => hasher.update(bytes),
+            FlavoredHasher::CollisionDetecting(hasher) => hasher.update(bytes),
+        }
+    }
+
+    fn try_finalize(self) -> Result<gix_hash::ObjectId, gix_hash::hasher::Error> {
+        match self {
+            FlavoredHasher::Fast(hasher) => gix_hash::Hasher::try_finalize(hasher),
+            FlavoredHasher::CollisionDetecting(hasher) => gix_hash::Hasher::try_finalize(*hasher),
+        }
+    }
+}
+
 /// Compute a CRC32 hash from the given `bytes`, returning the CRC32 hash.
 ///
 /// When calling this function for the first time, `previous_value` should be `0`. Otherwise it
@@ -80,6 +167,19 @@ pub fn hasher(kind: gix_hash::Kind) -> Hasher {
     }
 }
 
+/// Produce a hasher suitable for the given kind of hash, using the implementation of the given `flavor`.
+///
+/// Use [`Sha1Flavor::CollisionDetecting`](gix_hash::hasher::Sha1Flavor::CollisionDetecting) when hashing untrusted data.
+#[cfg(any(feature = "rustsha1", feature = "fast-sha1"))]
+pub fn hasher_with_flavor(kind: gix_hash::Kind, flavor: gix_hash::hasher::Sha1Flavor) -> FlavoredHasher {
+    match (kind, flavor) {
+        (gix_hash::Kind::Sha1, gix_hash::hasher::Sha1Flavor::Fast) => FlavoredHasher::Fast(Hasher::default()),
+        (gix_hash::Kind::Sha1, gix_hash::hasher::Sha1Flavor::CollisionDetecting) => {
+            FlavoredHasher::CollisionDetecting(Box::default())
+        }
+    }
+}
+
 /// Compute the hash of `kind` for the bytes in the file at `path`, hashing only the first `num_bytes_from_start`
 /// while initializing and calling `progress`.
 ///
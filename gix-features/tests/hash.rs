@@ -14,3 +14,40 @@ fn size_of_sha1() {
         if cfg!(target_arch = "x86") { 96 } else { 104 }
     );
 }
+
+mod flavor {
+    use gix_features::hash::hasher_with_flavor;
+    use gix_hash::{hasher::Sha1Flavor, Hasher};
+
+    fn hash(flavor: Sha1Flavor, data: &[u8]) -> Result<gix_hash::ObjectId, gix_hash::hasher::Error> {
+        let mut hasher = hasher_with_flavor(gix_hash::Kind::Sha1, flavor);
+        hasher.update(data);
+        hasher.try_finalize()
+    }
+
+    #[test]
+    fn both_flavors_produce_the_same_digest() {
+        let expected = gix_hash::ObjectId::from_hex(b"2aae6c35c94fcfb415dbe95f408b9ce91ee846ed").expect("valid");
+        for flavor in [Sha1Flavor::Fast, Sha1Flavor::CollisionDetecting] {
+            assert_eq!(hash(flavor, b"hello world").expect("no collision"), expected);
+        }
+    }
+
+    #[test]
+    fn collision_attacks_are_detected_only_if_desired() {
+        // The first 320 bytes of `shattered-1.pdf` from https://shattered.io, which contain the colliding blocks.
+        let data = include_bytes!("fixtures/shattered-1-prefix.bin");
+        let colliding_digest =
+            gix_hash::ObjectId::from_hex(b"f92d74e3874587aaf443d1db961d4e26dde13e9c").expect("valid");
+        assert_eq!(
+            hash(Sha1Flavor::Fast, data).expect("no detection"),
+            colliding_digest,
+            "the fast flavor doesn't notice anything"
+        );
+        let err = hash(Sha1Flavor::CollisionDetecting, data).unwrap_err();
+        assert!(
+            matches!(err, gix_hash::hasher::Error::CollisionAttack { digest } if digest == colliding_digest),
+            "the colliding digest is reported"
+        );
+    }
+}
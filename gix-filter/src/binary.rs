@@ -0,0 +1,51 @@
+use crate::eol::Stats;
+
+/// The amount of bytes at the beginning of a buffer that are checked by [`Heuristic::NullByte`], like `git` does.
+pub const FIRST_FEW_BYTES: usize = 8000;
+
+/// The heuristic to use when determining if a buffer is binary, with each one matching what `git` uses for a particular purpose.
+#[derive(Default, Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum Heuristic {
+    /// The buffer is binary if there is a null-byte in its first [`FIRST_FEW_BYTES`].
+    ///
+    /// This is what `git` uses for diffs and merges, and it's fast as only the beginning of the buffer is looked at.
+    #[default]
+    NullByte,
+    /// The buffer is binary if it contains a null-byte or a lone carriage-return anywhere, or if there are
+    /// less than 128 printable bytes for each non-printable one, as determined by [`Stats::is_binary()`].
+    ///
+    /// This is what `git` uses to decide if end-of-line conversions can be performed with `text=auto`,
+    /// and it looks at the whole buffer.
+    Statistics,
+}
+
+/// Return `true` if `data` is considered binary by `heuristic`, unless `forced` is `Some(is_binary)`, which overrides
+/// the heuristic entirely. `forced` is typically obtained from an attribute with [`forced_by_attribute()`].
+///
+/// Note that `data` is expected to be in its `git` representation, i.e. after the `working-tree-encoding` was
+/// converted to UTF-8 like it's stored in `git`, as otherwise text in encodings like UTF-16 would appear binary.
+pub fn is_binary(data: &[u8], forced: Option<bool>, heuristic: Heuristic) -> bool {
+    forced.unwrap_or_else(|| match heuristic {
+        Heuristic::NullByte => data[..data.len().min(FIRST_FEW_BYTES)].contains(&0),
+        Heuristic::Statistics => Stats::from_bytes(data).is_binary(),
+    })
+}
+
+/// Like [`is_binary()`] with [`Heuristic::Statistics`], but with `stats` already computed for the buffer in question.
+pub fn is_binary_with_stats(stats: &Stats, forced: Option<bool>) -> bool {
+    forced.unwrap_or_else(|| stats.is_binary())
+}
+
+/// Return `Some(true)` if the attribute in `state` forces its buffer to be treated as binary, `Some(false)` if it's
+/// forced to be text, or `None` if the buffer should be checked with a [`Heuristic`].
+///
+/// This is how `git` interprets the `diff`, `merge` and `text` attributes: if *set* the buffer is text, if *unset*
+/// (as with the `binary` macro) it's binary, and if *unspecified* or set to a value like `auto` or the name of a driver,
+/// it's up to the content or the driver.
+pub fn forced_by_attribute(state: gix_attributes::StateRef<'_>) -> Option<bool> {
+    match state {
+        gix_attributes::StateRef::Set => Some(false),
+        gix_attributes::StateRef::Unset => Some(true),
+        gix_attributes::StateRef::Value(_) | gix_attributes::StateRef::Unspecified => None,
+    }
+}
@@ -46,7 +46,7 @@ pub(crate) mod function {
     use bstr::ByteSlice;
 
     use crate::{
-        clear_and_set_capacity,
+        binary, clear_and_set_capacity,
         eol::{
             convert_to_git::{Error, IndexObjectFn, Options, RoundTripCheck},
             AttributesDigest, Stats,
@@ -72,23 +72,23 @@ pub(crate) mod function {
             config,
         }: Options<'_>,
     ) -> Result<bool, Error> {
-        if digest == AttributesDigest::Binary || src.is_empty() {
+        let forced_binary = digest.forced_binary();
+        if forced_binary == Some(true) || src.is_empty() {
             return Ok(false);
         }
 
         let stats = Stats::from_bytes(src);
+        // With `text=auto`, we are supposed to figure out ourselves if we should convert or not.
+        if binary::is_binary_with_stats(&stats, forced_binary) {
+            return Ok(false);
+        }
         let mut convert_crlf_to_lf = stats.crlf > 0;
         if digest.is_auto_text() {
-            // In this mode, we are supposed to figure out ourselves if we should convert or not.
-            if stats.is_binary() {
-                return Ok(false);
-            }
-
             if let Some(()) = index_object(buf).map_err(Error::FetchObjectFromIndex)? {
                 let has_crlf_in_index = buf
                     .find_byte(b'\r')
                     .map(|_| Stats::from_bytes(buf))
-                    .filter(|s| !s.is_binary() && s.crlf > 0)
+                    .filter(|s| !binary::is_binary_with_stats(s, None) && s.crlf > 0)
                     .is_some();
                 if has_crlf_in_index {
                     convert_crlf_to_lf = false;
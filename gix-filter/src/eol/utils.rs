@@ -21,6 +21,16 @@ impl AttributesDigest {
         })
     }
 
+    /// Return `Some(true)` if this digest forces buffers to be treated as binary, `Some(false)` if they are forced to be text,
+    /// or `None` if it's up to the [binary heuristic](crate::binary::is_binary()), like with `text=auto`.
+    pub fn forced_binary(&self) -> Option<bool> {
+        match self {
+            AttributesDigest::Binary => Some(true),
+            digest if digest.is_auto_text() => None,
+            _ => Some(false),
+        }
+    }
+
     /// Return true if this digest allows for auto-determination of CRLF text conversion.
     pub fn is_auto_text(&self) -> bool {
         matches!(
@@ -95,6 +105,8 @@ impl Stats {
     }
 
     /// Returns `true` if these statistics are typical for a binary file.
+    ///
+    /// This is the [`Statistics`](crate::binary::Heuristic::Statistics) heuristic, see [`crate::binary::is_binary()`].
     pub fn is_binary(&self) -> bool {
         self.lone_cr > 0 || self.null > 0 || (self.printable >> 7) < self.non_printable
     }
@@ -110,10 +122,10 @@ impl Stats {
             return false;
         }
 
+        if crate::binary::is_binary_with_stats(self, digest.forced_binary()) {
+            return false;
+        }
         if digest.is_auto_text() {
-            if self.is_binary() {
-                return false;
-            }
             // Lone `\r` or mixed LF and CRLF isn't safe as it won't round-trip, and in auto-mode we don't touch it.
             if self.lone_cr > 0 || self.crlf > 0 {
                 return false;
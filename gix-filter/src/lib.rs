@@ -25,6 +25,9 @@ pub mod eol;
 /// change encodings based on the `working-tree-encoding` attribute.
 pub mod worktree;
 
+/// determine if buffers are binary or text, like `git` does.
+pub mod binary;
+
 /// use filter programs to perform any kind of conversion.
 pub mod driver;
 
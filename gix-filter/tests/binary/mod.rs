@@ -0,0 +1,72 @@
+use gix_filter::binary::{forced_by_attribute, is_binary, is_binary_with_stats, Heuristic, FIRST_FEW_BYTES};
+
+#[test]
+fn null_byte_is_only_detected_in_the_first_few_bytes() {
+    assert!(!is_binary(b"", None, Heuristic::NullByte));
+    assert!(!is_binary(b"text\r\nwith lone\rcr", None, Heuristic::NullByte));
+    assert!(is_binary(b"a\0b", None, Heuristic::NullByte));
+
+    let mut data = vec![b'a'; FIRST_FEW_BYTES];
+    data.push(0);
+    assert!(
+        !is_binary(&data, None, Heuristic::NullByte),
+        "like `git`, null-bytes after the first 8000 bytes aren't seen"
+    );
+    assert!(
+        is_binary(&data, None, Heuristic::Statistics),
+        "statistics look at the whole buffer"
+    );
+}
+
+#[test]
+fn statistics_consider_lone_carriage_returns_and_non_printable_bytes() {
+    assert!(!is_binary(b"", None, Heuristic::Statistics));
+    assert!(!is_binary(b"line\r\nline\n", None, Heuristic::Statistics));
+    assert!(is_binary(b"line\rline\n", None, Heuristic::Statistics));
+
+    let mut data = vec![b'a'; 128];
+    data.push(1);
+    assert!(
+        !is_binary(&data, None, Heuristic::Statistics),
+        "one non-printable byte for 128 printable ones is still text"
+    );
+    data.pop();
+    data.extend_from_slice(&[1, 1]);
+    assert!(is_binary(&data, None, Heuristic::Statistics));
+}
+
+#[test]
+fn forced_decisions_override_the_heuristics() {
+    for heuristic in [Heuristic::NullByte, Heuristic::Statistics] {
+        assert!(is_binary(b"text", Some(true), heuristic));
+        assert!(!is_binary(b"\0binary\r", Some(false), heuristic));
+    }
+}
+
+#[test]
+fn forced_by_attribute_states() {
+    use gix_filter::attributes::StateRef;
+    assert_eq!(forced_by_attribute(StateRef::Set), Some(false), "`diff` or `text`");
+    assert_eq!(forced_by_attribute(StateRef::Unset), Some(true), "`-diff` or `binary`");
+    assert_eq!(forced_by_attribute(StateRef::Unspecified), None);
+    assert_eq!(
+        forced_by_attribute(StateRef::Value("auto".into())),
+        None,
+        "`text=auto` or a driver name"
+    );
+}
+
+#[test]
+fn end_of_line_attributes_are_forced_decisions() {
+    use gix_filter::eol::{AttributesDigest, Stats};
+    let stats = Stats::from_bytes(b"\0binary\r\n");
+    assert!(is_binary_with_stats(&stats, AttributesDigest::Binary.forced_binary()));
+    assert!(
+        !is_binary_with_stats(&stats, AttributesDigest::TextCrlf.forced_binary()),
+        "`text` forces buffers to be text, just like when diffing"
+    );
+    assert!(
+        is_binary_with_stats(&stats, AttributesDigest::TextAuto.forced_binary()),
+        "`text=auto` uses the heuristic"
+    );
+}
@@ -1,3 +1,4 @@
+mod binary;
 pub(crate) mod driver;
 pub(crate) mod eol;
 mod ident;
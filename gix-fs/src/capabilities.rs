@@ -55,6 +55,54 @@ impl Capabilities {
         }
     }
 
+    /// Return `true` if the modification time of directories within `root` changes whenever a file or directory is
+    /// added to or removed from them, which is what an untracked cache relies on to know which directories need
+    /// to be traversed again.
+    ///
+    /// `root` is typically the root of the worktree, and a temporary directory is created and removed within it.
+    ///
+    /// Just like `git update-index --test-untracked-cache`, this waits a second before each change to be sure it can be
+    /// observed even with coarse modification times, so it takes a few seconds to complete and should only be run on
+    /// explicit request. Use [`probe_untracked_cache_with_delay()`](Self::probe_untracked_cache_with_delay()) to
+    /// control how long to wait.
+    pub fn probe_untracked_cache(root: &Path) -> std::io::Result<bool> {
+        Self::probe_untracked_cache_with_delay(root, std::time::Duration::from_secs(1))
+    }
+
+    /// Like [`probe_untracked_cache()`](Self::probe_untracked_cache()), but wait for `delay` before each change
+    /// instead of a second.
+    ///
+    /// A shorter `delay` makes the probe faster, but may report `false` on file systems with coarse modification times
+    /// even though they would support the untracked cache.
+    pub fn probe_untracked_cache_with_delay(root: &Path, delay: std::time::Duration) -> std::io::Result<bool> {
+        let rand = fastrand::usize(..);
+        let dir = root.join(format!("_test_untracked_cache{rand}"));
+        std::fs::create_dir(&dir)?;
+        let res = Self::probe_directory_mtime(&dir, delay);
+        std::fs::remove_dir_all(&dir)?;
+        res
+    }
+
+    fn probe_directory_mtime(dir: &Path, delay: std::time::Duration) -> std::io::Result<bool> {
+        let file = dir.join("file");
+        let subdir = dir.join("dir");
+        let changes: [&dyn Fn() -> std::io::Result<()>; 4] = [
+            &|| std::fs::write(&file, b""),
+            &|| std::fs::create_dir(&subdir),
+            &|| std::fs::remove_file(&file),
+            &|| std::fs::remove_dir(&subdir),
+        ];
+        for change in changes {
+            let before = std::fs::symlink_metadata(dir)?.modified()?;
+            std::thread::sleep(delay);
+            change()?;
+            if std::fs::symlink_metadata(dir)?.modified()? == before {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+
     #[cfg(unix)]
     fn probe_file_mode(root: &Path) -> std::io::Result<bool> {
         use std::os::unix::fs::{MetadataExt, OpenOptionsExt};
@@ -46,3 +46,19 @@ fn parallel_probe() {
         thread.join().expect("no panic");
     }
 }
+
+#[test]
+fn probe_untracked_cache() {
+    let dir = tempfile::tempdir().unwrap();
+    let supported =
+        gix_fs::Capabilities::probe_untracked_cache_with_delay(dir.path(), std::time::Duration::from_millis(10))
+            .unwrap();
+    assert_eq!(
+        std::fs::read_dir(dir.path()).unwrap().count(),
+        0,
+        "there should be no left-over files after probing"
+    );
+    if cfg!(target_os = "linux") {
+        assert!(supported, "directory modification times change reliably");
+    }
+}
@@ -0,0 +1,33 @@
+use crate::ObjectId;
+
+/// The error returned by [`Hasher::try_finalize()`].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("Detected SHA-1 collision attack with digest {digest}")]
+    CollisionAttack { digest: ObjectId },
+}
+
+/// The implementation to use when computing SHA-1 digests, selectable for each operation that produces them.
+#[derive(Default, PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Sha1Flavor {
+    /// A fast implementation which computes digests without any additional checks.
+    #[default]
+    Fast,
+    /// A slower implementation that detects input which is part of a known SHA-1 collision attack, like `sha1dc` used by `git`.
+    ///
+    /// It's useful when hashing untrusted data, like objects in packs received from a remote.
+    CollisionDetecting,
+}
+
+/// A hasher which produces an [`ObjectId`] from data that is fed to it in chunks.
+pub trait Hasher {
+    /// Digest the given `bytes`.
+    fn update(&mut self, bytes: &[u8]);
+
+    /// Finalize the hash and produce an object id, or fail if a collision attack was detected.
+    ///
+    /// Implementations without collision detection never fail.
+    fn try_finalize(self) -> Result<ObjectId, Error>;
+}
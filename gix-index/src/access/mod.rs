@@ -1,6 +1,6 @@
 use std::{cmp::Ordering, ops::Range};
 
-use bstr::{BStr, ByteSlice, ByteVec};
+use bstr::{BStr, BString, ByteSlice, ByteVec};
 use filetime::FileTime;
 
 use crate::entry::{Stage, StageRaw};
@@ -17,6 +17,15 @@ impl State {
         self.version
     }
 
+    /// Set the `version` to use when [writing](State::write_to()) this state.
+    ///
+    /// Note that [`Version::V2`] and [`Version::V3`] are treated the same as the version is upgraded to V3 automatically
+    /// if entries need it, while [`Version::V4`] is always respected to write prefix-compressed paths.
+    /// Use [`write::Options::version`](crate::write::Options::version) to write a specific version instead.
+    pub fn set_version(&mut self, version: Version) {
+        self.version = version;
+    }
+
     /// Returns time at which the state was created, indicating its freshness compared to other files on disk.
     pub fn timestamp(&self) -> FileTime {
         self.timestamp
@@ -360,6 +369,7 @@ impl State {
     /// Returns a boolean value indicating whether the index is sparse or not.
     ///
     /// An index is sparse if it contains at least one [`Mode::DIR`][entry::Mode::DIR] entry.
+    /// Use [`expand()`](State::expand()) to turn it into a regular index.
     pub fn is_sparse(&self) -> bool {
         self.is_sparse
     }
@@ -397,7 +407,7 @@ impl AccelerateLookup<'_> {
             icase_dirs: hashbrown::HashTable::with_capacity(cap / ratio_of_entries_to_dirs_in_webkit),
         }
     }
-    fn icase_hash(data: &BStr) -> u64 {
+    pub(crate) fn icase_hash(data: &BStr) -> u64 {
         use std::hash::Hasher;
         let mut hasher = fnv::FnvHasher::default();
         for b in data.as_bytes() {
@@ -456,6 +466,32 @@ impl State {
         std::mem::take(&mut self.path_backing)
     }
 
+    /// Set the size of all entries that are [racy](Entry::is_racy()) with respect to our [timestamp](Self::timestamp())
+    /// and that `is_modified(entry, rela_path)` to 0, and return the amount of entries that were smudged this way.
+    ///
+    /// Such racily clean entries have stat information that matches the file on disk even though its content changed,
+    /// which would make them appear unmodified once the index is written with a newer timestamp. A size of 0
+    /// forces their content to be checked the next time they are compared to the worktree, just like `git` does.
+    /// `is_modified` is expected to compare the content of the file on disk with the entry's object.
+    pub fn smudge_racily_clean_entries<E>(
+        &mut self,
+        options: entry::stat::Options,
+        mut is_modified: impl FnMut(&Entry, &BStr) -> Result<bool, E>,
+    ) -> Result<usize, E> {
+        let timestamp = self.timestamp;
+        let mut num_smudged = 0;
+        for entry in self.entries.iter_mut() {
+            if entry.stat.size == 0 || !entry.is_racy(timestamp, options) {
+                continue;
+            }
+            if is_modified(entry, entry.path_in(&self.path_backing))? {
+                entry.stat.size = 0;
+                num_smudged += 1;
+            }
+        }
+        Ok(num_smudged)
+    }
+
     /// Like [`entry_index_by_path_and_stage()`][State::entry_index_by_path_and_stage()],
     /// but returns the mutable entry instead of the index.
     pub fn entry_mut_by_path_and_stage(&mut self, path: &BStr, stage: entry::Stage) -> Option<&mut Entry> {
@@ -496,6 +532,374 @@ impl State {
         });
     }
 
+    /// Insert a new entry with `stat`, `id`, `flags`, `mode` and `path` at its sorted position, or update the entry at `path`
+    /// in the stage that is encoded in `flags`, and return its index.
+    ///
+    /// Similar to `git update-index --add --replace`, entries that would conflict with the new entry are removed
+    /// to keep the index valid, which includes:
+    ///
+    /// * entries at a leading directory of `path`, like `a` if `path` is `a/b`,
+    /// * entries within `path` as directory, like `a/b` if `path` is `a`,
+    /// * all conflicting stages of `path` if the new entry is unconflicted, or the unconflicted entry at `path` if it's not.
+    ///
+    /// Conflicting stages that are removed are recorded in the [resolve-undo extension](Self::resolve_undo()) like `git` does.
+    /// The [tree cache](Self::tree()) and [untracked cache](Self::untracked()) are invalidated for `path`, and the entry is marked
+    /// as not known to be valid in the [filesystem monitor extension](Self::fs_monitor()).
+    pub fn upsert_entry(
+        &mut self,
+        stat: entry::Stat,
+        id: gix_hash::ObjectId,
+        flags: entry::Flags,
+        mode: entry::Mode,
+        path: &BStr,
+    ) -> usize {
+        self.keeping_fs_monitor_in_sync(|state| state.upsert_entry_inner(stat, id, flags, mode, path))
+    }
+
+    fn upsert_entry_inner(
+        &mut self,
+        stat: entry::Stat,
+        id: gix_hash::ObjectId,
+        mut flags: entry::Flags,
+        mode: entry::Mode,
+        path: &BStr,
+    ) -> usize {
+        let stage = flags.stage();
+        flags.remove(entry::Flags::FSMONITOR_VALID);
+        invalidate_path(&mut self.tree, &mut self.untracked, path);
+        self.remove_entries_conflicting_with(path, stage);
+
+        let path_backing = &self.path_backing;
+        match self.entries.binary_search_by(|e| {
+            Entry::cmp_filepaths(e.path_in(path_backing), path).then_with(|| e.stage().cmp(&stage))
+        }) {
+            Ok(idx) => {
+                let entry = &mut self.entries[idx];
+                entry.stat = stat;
+                entry.id = id;
+                entry.flags = flags;
+                entry.mode = mode;
+                idx
+            }
+            Err(idx) => {
+                let path = {
+                    let path_start = self.path_backing.len();
+                    self.path_backing.push_str(path);
+                    path_start..self.path_backing.len()
+                };
+                self.entries.insert(
+                    idx,
+                    Entry {
+                        stat,
+                        id,
+                        flags,
+                        mode,
+                        path,
+                    },
+                );
+                idx
+            }
+        }
+    }
+
+    /// Like [`upsert_entry()`](Self::upsert_entry()), but [validate](crate::verify::path()) `path` with `validate` first,
+    /// and fail without changing anything if it's unsafe to check out, for instance because it would write into
+    /// the `.git` directory or outside of the worktree.
+    ///
+    /// Use this if `path` comes from an untrusted source.
+    pub fn try_upsert_entry(
+        &mut self,
+        stat: entry::Stat,
+        id: gix_hash::ObjectId,
+        flags: entry::Flags,
+        mode: entry::Mode,
+        path: &BStr,
+        validate: gix_validate::path::component::Options,
+    ) -> Result<usize, crate::verify::path::Error> {
+        crate::verify::path(path, mode, validate)?;
+        Ok(self.upsert_entry(stat, id, flags, mode, path))
+    }
+
+    /// Remove all entries whose paths don't pass [validation](crate::verify::path()) with `validate`, and return the amount
+    /// of removed entries.
+    ///
+    /// This sanitizes an index from an untrusted source so it can be checked out and [written](crate::write::Options::verify)
+    /// safely, instead of rejecting it as a whole like [`verify_paths()`](Self::verify_paths()) does.
+    /// The [tree cache](Self::tree()) is invalidated for each removed entry.
+    pub fn remove_entries_with_invalid_paths(&mut self, validate: gix_validate::path::component::Options) -> usize {
+        let paths = &self.path_backing;
+        let tree = &mut self.tree;
+        let mut num_removed = 0;
+        self.entries.retain(|e| {
+            let path = e.path_in(paths);
+            let is_valid = crate::verify::path(path, e.mode, validate).is_ok();
+            if !is_valid {
+                num_removed += 1;
+                if let Some(tree) = tree.as_mut() {
+                    tree.invalidate_path(path);
+                }
+            }
+            is_valid
+        });
+        num_removed
+    }
+
+    /// Remove all entries that would conflict with an entry at `path` in `stage`, as described in [`upsert_entry()`](Self::upsert_entry()).
+    fn remove_entries_conflicting_with(&mut self, path: &BStr, stage: entry::Stage) {
+        let is_unconflicted = stage == entry::Stage::Unconflicted;
+        if let Some(range) = self.entry_range(path) {
+            let paths = &self.path_backing;
+            let resolve_undo = &mut self.resolve_undo;
+            let mut idx = 0;
+            self.entries.retain(|e| {
+                let keep = !range.contains(&idx) || (e.stage() == entry::Stage::Unconflicted) == is_unconflicted;
+                idx += 1;
+                if !keep {
+                    record_resolve_undo(resolve_undo, e, e.path_in(paths));
+                }
+                keep
+            });
+        }
+
+        let mut directory = BString::from(path);
+        directory.push(b'/');
+        if let Some(range) = self.prefixed_entries_range(directory.as_ref()) {
+            self.remove_entry_range(range);
+        }
+
+        for pos in path.find_iter("/") {
+            if let Some(range) = self.entry_range(path[..pos].as_bstr()) {
+                self.remove_entry_range(range);
+            }
+        }
+    }
+
+    /// Remove the entries in `range`, and record those with conflicting stages as resolved.
+    fn remove_entry_range(&mut self, range: Range<usize>) {
+        for e in self.entries.drain(range) {
+            record_resolve_undo(&mut self.resolve_undo, &e, e.path_in(&self.path_backing));
+        }
+    }
+
+    /// Call `f` to add or remove entries, and update the bitmap of the [filesystem monitor extension](Self::fs_monitor())
+    /// which refers to entries by their index so it stays valid.
+    ///
+    /// Entries that `f` adds or changes should not have [`FSMONITOR_VALID`](entry::Flags::FSMONITOR_VALID) set.
+    fn keeping_fs_monitor_in_sync<T>(&mut self, f: impl FnOnce(&mut Self) -> T) -> T {
+        let Some(fs_monitor) = self.fs_monitor.as_ref() else {
+            return f(self);
+        };
+        for entry in &mut self.entries {
+            entry.flags.insert(entry::Flags::FSMONITOR_VALID);
+        }
+        let entries = &mut self.entries;
+        fs_monitor.entry_dirty().for_each_set_bit(|idx| {
+            if let Some(entry) = entries.get_mut(idx) {
+                entry.flags.remove(entry::Flags::FSMONITOR_VALID);
+            }
+            Some(())
+        });
+
+        let out = f(self);
+
+        let mut dirty = Vec::new();
+        for (idx, entry) in self.entries.iter_mut().enumerate() {
+            if !entry.flags.contains(entry::Flags::FSMONITOR_VALID) {
+                dirty.push(idx);
+            }
+            entry.flags.remove(entry::Flags::FSMONITOR_VALID);
+        }
+        if let Some(fs_monitor) = self.fs_monitor.as_mut() {
+            fs_monitor.set_entry_dirty(gix_bitmap::ewah::Vec::from_set_bits(dirty));
+        }
+        out
+    }
+
+    /// Remove all stages of the entry at `path` and return `true`, or return `false` if there was no such entry.
+    ///
+    /// Similar to `git update-index --remove`, conflicting stages are recorded in the [resolve-undo extension](Self::resolve_undo()),
+    /// and the [tree cache](Self::tree()) and [untracked cache](Self::untracked()) are invalidated for `path`.
+    pub fn remove_entry(&mut self, path: &BStr) -> bool {
+        let Some(range) = self.entry_range(path) else {
+            return false;
+        };
+        self.keeping_fs_monitor_in_sync(|state| {
+            invalidate_path(&mut state.tree, &mut state.untracked, path);
+            state.remove_entry_range(range);
+        });
+        true
+    }
+
+    /// Remove all entries that match `pathspec` and return the amount of removed entries.
+    ///
+    /// `attributes` is used to look up attributes for pathspecs that need them, as in
+    /// [`Search::pattern_matching_relative_path()`](gix_pathspec::Search::pattern_matching_relative_path()).
+    /// Removed entries with conflicting stages are recorded in the [resolve-undo extension](Self::resolve_undo()),
+    /// and the [tree cache](Self::tree()) and [untracked cache](Self::untracked()) are invalidated for each of them.
+    pub fn remove_entries_by_pathspec(
+        &mut self,
+        pathspec: &mut gix_pathspec::Search,
+        attributes: &mut dyn FnMut(
+            &BStr,
+            gix_pathspec::attributes::glob::pattern::Case,
+            bool,
+            &mut gix_pathspec::attributes::search::Outcome,
+        ) -> bool,
+    ) -> usize {
+        let Some(range) = self.prefixed_entries_range(pathspec.common_prefix()) else {
+            return 0;
+        };
+        self.keeping_fs_monitor_in_sync(|state| state.remove_entries_by_pathspec_inner(range, pathspec, attributes))
+    }
+
+    fn remove_entries_by_pathspec_inner(
+        &mut self,
+        range: Range<usize>,
+        pathspec: &mut gix_pathspec::Search,
+        attributes: &mut dyn FnMut(
+            &BStr,
+            gix_pathspec::attributes::glob::pattern::Case,
+            bool,
+            &mut gix_pathspec::attributes::search::Outcome,
+        ) -> bool,
+    ) -> usize {
+        let paths = &self.path_backing;
+        let tree = &mut self.tree;
+        let untracked = &mut self.untracked;
+        let resolve_undo = &mut self.resolve_undo;
+        let mut idx = 0;
+        let mut num_removed = 0;
+        self.entries.retain(|e| {
+            let is_in_range = range.contains(&idx);
+            idx += 1;
+            if !is_in_range {
+                return true;
+            }
+            let path = e.path_in(paths);
+            let is_match = pathspec
+                .pattern_matching_relative_path(path, Some(e.mode.is_submodule() || e.mode.is_sparse()), attributes)
+                .map_or(false, |m| !m.is_excluded());
+            if is_match {
+                num_removed += 1;
+                invalidate_path(tree, untracked, path);
+                record_resolve_undo(resolve_undo, e, path);
+            }
+            !is_match
+        });
+        num_removed
+    }
+
+    /// Set the [`ASSUME_VALID`](entry::Flags::ASSUME_VALID) flag on all entries that match `pathspec` if `assume_unchanged`
+    /// is `true`, or clear it otherwise, like `git update-index --[no-]assume-unchanged` does. Return the amount of matching entries.
+    ///
+    /// `attributes` is used to look up attributes for pathspecs that need them, as in
+    /// [`remove_entries_by_pathspec()`](Self::remove_entries_by_pathspec()).
+    /// Conflicting entries and [sparse directories](entry::Mode::DIR) are never matched.
+    pub fn set_assume_unchanged_by_pathspec(
+        &mut self,
+        pathspec: &mut gix_pathspec::Search,
+        attributes: &mut dyn FnMut(
+            &BStr,
+            gix_pathspec::attributes::glob::pattern::Case,
+            bool,
+            &mut gix_pathspec::attributes::search::Outcome,
+        ) -> bool,
+        assume_unchanged: bool,
+    ) -> usize {
+        self.set_flags_by_pathspec(pathspec, attributes, entry::Flags::ASSUME_VALID, assume_unchanged)
+    }
+
+    /// Set the [`SKIP_WORKTREE`](entry::Flags::SKIP_WORKTREE) flag on all entries that match `pathspec` if `skip_worktree`
+    /// is `true`, or clear it otherwise, like `git update-index --[no-]skip-worktree` does. Return the amount of matching entries.
+    ///
+    /// As this flag can only be stored in the extended flags of [`Version::V3`], the [version](Self::version()) is raised
+    /// from [`Version::V2`] if needed, or lowered back to it once no entry needs extended flags anymore, just like `git` does
+    /// when writing the index.
+    /// `attributes` is used to look up attributes for pathspecs that need them, as in
+    /// [`remove_entries_by_pathspec()`](Self::remove_entries_by_pathspec()).
+    /// Conflicting entries and [sparse directories](entry::Mode::DIR) are never matched.
+    pub fn set_skip_worktree_by_pathspec(
+        &mut self,
+        pathspec: &mut gix_pathspec::Search,
+        attributes: &mut dyn FnMut(
+            &BStr,
+            gix_pathspec::attributes::glob::pattern::Case,
+            bool,
+            &mut gix_pathspec::attributes::search::Outcome,
+        ) -> bool,
+        skip_worktree: bool,
+    ) -> usize {
+        self.set_flags_by_pathspec(pathspec, attributes, entry::Flags::SKIP_WORKTREE, skip_worktree)
+    }
+
+    fn set_flags_by_pathspec(
+        &mut self,
+        pathspec: &mut gix_pathspec::Search,
+        attributes: &mut dyn FnMut(
+            &BStr,
+            gix_pathspec::attributes::glob::pattern::Case,
+            bool,
+            &mut gix_pathspec::attributes::search::Outcome,
+        ) -> bool,
+        flags: entry::Flags,
+        enable: bool,
+    ) -> usize {
+        let Some(range) = self.prefixed_entries_range(pathspec.common_prefix()) else {
+            return 0;
+        };
+        let mut num_matched = 0;
+        for entry in &mut self.entries[range] {
+            if entry.stage() != entry::Stage::Unconflicted || entry.mode.is_sparse() {
+                continue;
+            }
+            let is_match = pathspec
+                .pattern_matching_relative_path(
+                    entry.path_in(&self.path_backing),
+                    Some(entry.mode.is_submodule()),
+                    attributes,
+                )
+                .map_or(false, |m| !m.is_excluded());
+            if !is_match {
+                continue;
+            }
+            num_matched += 1;
+            entry.flags.set(flags, enable);
+            // Like `git`, only mark entries as extended if they actually have extended flags.
+            entry.flags.remove(entry::Flags::EXTENDED);
+            if entry.flags.needs_extended_storage() {
+                entry.flags.insert(entry::Flags::EXTENDED);
+            }
+        }
+
+        if self.version != Version::V4 {
+            self.version = if self.entries.iter().any(|e| e.flags.needs_extended_storage()) {
+                Version::V3
+            } else {
+                Version::V2
+            };
+        }
+        num_matched
+    }
+
+    /// Rename the entry at `from`, in all of its stages, to `to` and return `true`, or return `false` if there is no entry at `from`.
+    ///
+    /// Existing entries at `to` or those conflicting with it are replaced, as described in [`upsert_entry()`](Self::upsert_entry()).
+    /// The [tree cache](Self::tree()) and [untracked cache](Self::untracked()) are invalidated for both paths, and the
+    /// renamed entries are marked as not known to be valid in the [filesystem monitor extension](Self::fs_monitor()).
+    pub fn rename_entry(&mut self, from: &BStr, to: &BStr) -> bool {
+        let Some(range) = self.entry_range(from) else {
+            return false;
+        };
+        self.keeping_fs_monitor_in_sync(|state| {
+            invalidate_path(&mut state.tree, &mut state.untracked, from);
+            let entries: Vec<_> = state.entries.drain(range).collect();
+            for entry in entries {
+                state.upsert_entry_inner(entry.stat, entry.id, entry.flags, entry.mode, to);
+            }
+        });
+        true
+    }
+
     /// Unconditionally sort entries as needed to perform lookups quickly.
     pub fn sort_entries(&mut self) {
         let path_backing = &self.path_backing;
@@ -542,6 +946,28 @@ impl State {
     pub fn tree(&self) -> Option<&extension::Tree> {
         self.tree.as_ref()
     }
+    /// Remove the `tree` extension and return it, if present.
+    pub fn remove_tree(&mut self) -> Option<extension::Tree> {
+        self.tree.take()
+    }
+    /// Bring the `tree` extension up-to-date with our entries, or create it if it isn't present, writing all trees that
+    /// need it with `objects`, and return the id of the root tree.
+    ///
+    /// See [`extension::Tree::update_from_entries()`] for details.
+    pub fn update_tree(
+        &mut self,
+        objects: impl gix_object::Write,
+    ) -> Result<gix_hash::ObjectId, extension::tree::update::Error> {
+        let mut tree = self.tree.take().unwrap_or_else(|| extension::Tree {
+            name: Default::default(),
+            id: self.object_hash.null(),
+            num_entries: None,
+            children: Vec::new(),
+        });
+        let res = tree.update_from_entries(self, objects);
+        self.tree = Some(tree);
+        res
+    }
     /// Access the `link` extension.
     pub fn link(&self) -> Option<&extension::Link> {
         self.link.as_ref()
@@ -554,10 +980,52 @@ impl State {
     pub fn untracked(&self) -> Option<&extension::UntrackedCache> {
         self.untracked.as_ref()
     }
+    /// Obtain the untracked extension for modification, if present.
+    pub fn untracked_mut(&mut self) -> Option<&mut extension::UntrackedCache> {
+        self.untracked.as_mut()
+    }
+    /// Remove the untracked extension and return it, if present.
+    pub fn remove_untracked(&mut self) -> Option<extension::UntrackedCache> {
+        self.untracked.take()
+    }
+    /// Set the untracked extension to `untracked` and return the previous one, if present.
+    pub fn set_untracked(&mut self, untracked: extension::UntrackedCache) -> Option<extension::UntrackedCache> {
+        self.untracked.replace(untracked)
+    }
     /// Obtain the fsmonitor extension.
     pub fn fs_monitor(&self) -> Option<&extension::FsMonitor> {
         self.fs_monitor.as_ref()
     }
+    /// Remove the fsmonitor extension and return it, if present.
+    ///
+    /// This should be done if entries are changed without consulting the filesystem monitor.
+    pub fn remove_fs_monitor(&mut self) -> Option<extension::FsMonitor> {
+        self.fs_monitor.take()
+    }
+    /// Set the fsmonitor extension to `fs_monitor` and return the previous one, if present.
+    pub fn set_fs_monitor(&mut self, fs_monitor: extension::FsMonitor) -> Option<extension::FsMonitor> {
+        self.fs_monitor.replace(fs_monitor)
+    }
+    /// Obtain the metadata that tools stored alongside the index, if present.
+    pub fn metadata(&self) -> Option<&extension::Metadata> {
+        self.metadata.as_ref()
+    }
+    /// Obtain the metadata that tools stored alongside the index for modification, creating it if needed.
+    pub fn metadata_mut(&mut self) -> &mut extension::Metadata {
+        self.metadata.get_or_insert_with(Default::default)
+    }
+    /// Obtain all optional extensions that aren't understood by this implementation, in the order in which they were read.
+    ///
+    /// They are written back unchanged with [`Extensions::All`](crate::write::Extensions::All).
+    pub fn unknown_extensions(&self) -> &[extension::Unknown] {
+        &self.unknown_extensions
+    }
+    /// Remove all unknown extensions and return them.
+    ///
+    /// This should be done if they are known to depend on entries that were changed, as we can't update them.
+    pub fn remove_unknown_extensions(&mut self) -> Vec<extension::Unknown> {
+        std::mem::take(&mut self.unknown_extensions)
+    }
     /// Return `true` if the end-of-index extension was present when decoding this index.
     pub fn had_end_of_index_marker(&self) -> bool {
         self.end_of_index_at_decode_time
@@ -602,3 +1070,41 @@ mod tests {
         }
     }
 }
+
+/// Invalidate the `tree` and `untracked` cache for `path`, as an entry at it was added, changed or removed.
+fn invalidate_path(tree: &mut Option<extension::Tree>, untracked: &mut Option<extension::UntrackedCache>, path: &BStr) {
+    if let Some(tree) = tree.as_mut() {
+        tree.invalidate_path(path);
+    }
+    if let Some(untracked) = untracked.as_mut() {
+        untracked.invalidate_path(path);
+    }
+}
+
+/// Record `entry` at `path` in `resolve_undo` if it's a conflicting stage that is about to be removed, like `git` does.
+fn record_resolve_undo(resolve_undo: &mut Option<extension::resolve_undo::Paths>, entry: &Entry, path: &BStr) {
+    let stage_idx = match entry.stage() {
+        entry::Stage::Unconflicted => return,
+        entry::Stage::Base => 0,
+        entry::Stage::Ours => 1,
+        entry::Stage::Theirs => 2,
+    };
+    let paths = resolve_undo.get_or_insert_with(Vec::new);
+    let idx = match paths.binary_search_by(|p| p.name.as_bstr().cmp(path)) {
+        Ok(idx) => idx,
+        Err(idx) => {
+            paths.insert(
+                idx,
+                extension::resolve_undo::ResolvePath {
+                    name: path.to_owned(),
+                    stages: [None, None, None],
+                },
+            );
+            idx
+        }
+    };
+    paths[idx].stages[stage_idx] = Some(extension::resolve_undo::Stage {
+        mode: entry.mode.bits(),
+        id: entry.id,
+    });
+}
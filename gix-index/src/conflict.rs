@@ -0,0 +1,168 @@
+use bstr::BStr;
+use gix_hash::ObjectId;
+
+use crate::{entry, extension::resolve_undo, Entry, State};
+
+/// A path with a merge conflict, along with its entries at each of the conflict stages, as returned by [`State::conflicts()`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Conflict<'a> {
+    /// The path relative to the root of the repository.
+    pub path: &'a BStr,
+    /// The entry of the common ancestor at stage 1, or `None` if the path didn't exist there.
+    pub base: Option<&'a Entry>,
+    /// The entry of *our* side at stage 2, or `None` if we deleted the path.
+    pub ours: Option<&'a Entry>,
+    /// The entry of *their* side at stage 3, or `None` if they deleted the path.
+    pub theirs: Option<&'a Entry>,
+}
+
+/// A side of a conflict, to be placed into the index at one of the conflict stages with [`State::set_conflict()`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Side {
+    /// The mode of the entry.
+    pub mode: entry::Mode,
+    /// The id of the object the entry points to.
+    pub id: ObjectId,
+}
+
+/// Define how a conflict should be resolved with [`State::resolve_conflict()`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Resolution {
+    /// Use the entry at the given conflict stage, which must not be [`Unconflicted`](entry::Stage::Unconflicted).
+    Take(entry::Stage),
+    /// Use the given entry, for instance one that contains the result of merging the conflicting versions.
+    Entry(Side),
+    /// Remove the path from the index.
+    Remove,
+}
+
+///
+pub mod resolve {
+    use bstr::BString;
+
+    /// The error returned by [`State::resolve_conflict()`](crate::State::resolve_conflict()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("Path '{path}' has no conflict that could be resolved")]
+        NotConflicted { path: BString },
+        #[error("Path '{path}' has no entry at stage {stage:?} to resolve the conflict with")]
+        MissingStage { path: BString, stage: crate::entry::Stage },
+    }
+}
+
+/// Conflicts
+impl State {
+    /// Return an iterator over all paths with conflicts, along with their entries at each stage, in index order.
+    ///
+    /// This is what `git ls-files --unmerged` lists.
+    pub fn conflicts(&self) -> impl Iterator<Item = Conflict<'_>> + '_ {
+        let mut entries = self.entries.iter().peekable();
+        std::iter::from_fn(move || {
+            let first = entries.find(|e| e.stage() != entry::Stage::Unconflicted)?;
+            let path = first.path(self);
+            let mut conflict = Conflict {
+                path,
+                base: None,
+                ours: None,
+                theirs: None,
+            };
+            let mut next = Some(first);
+            while let Some(entry) = next {
+                match entry.stage() {
+                    entry::Stage::Base => conflict.base = Some(entry),
+                    entry::Stage::Ours => conflict.ours = Some(entry),
+                    entry::Stage::Theirs => conflict.theirs = Some(entry),
+                    entry::Stage::Unconflicted => {}
+                }
+                next = entries.next_if(|e| e.path(self) == path);
+            }
+            Some(conflict)
+        })
+    }
+
+    /// Put `path` into conflict by replacing all of its entries with the given `base`, `ours` and `theirs` sides
+    /// at stage 1, 2 and 3 respectively, each of which may be absent to indicate that the path didn't exist on that side.
+    ///
+    /// Similar to `git update-index --index-info`, entries that would conflict with the new ones are removed as
+    /// described in [`upsert_entry()`](Self::upsert_entry()), and a [resolve-undo](Self::resolve_undo()) record for `path` is
+    /// removed as the path isn't resolved anymore.
+    /// Note that if no side is given, `path` is merely removed.
+    pub fn set_conflict(&mut self, path: &BStr, base: Option<Side>, ours: Option<Side>, theirs: Option<Side>) {
+        self.remove_path(path);
+        for (stage, side) in [
+            (entry::Stage::Base, base),
+            (entry::Stage::Ours, ours),
+            (entry::Stage::Theirs, theirs),
+        ] {
+            if let Some(Side { mode, id }) = side {
+                self.upsert_entry(entry::Stat::default(), id, entry::Flags::from_stage(stage), mode, path);
+            }
+        }
+        if let Some(paths) = self.resolve_undo.as_mut() {
+            paths.retain(|p| p.name() != path);
+        }
+    }
+
+    /// Resolve the conflict at `path` by replacing all of its conflicting stages with a single unconflicted entry,
+    /// or by removing it, as defined by `resolution`.
+    ///
+    /// Like `git` does, the conflicting stages are recorded in the [resolve-undo](Self::resolve_undo()) extension, replacing
+    /// a previous record for `path`, so the conflict can be recreated later.
+    /// Note that the new entry has no [stat](entry::Stat) information, so it needs to be refreshed from the worktree if needed.
+    pub fn resolve_conflict(&mut self, path: &BStr, resolution: Resolution) -> Result<(), resolve::Error> {
+        let mut stages = [None; 3];
+        for entry in self.entry_range(path).map_or(&[][..], |range| &self.entries[range]) {
+            let stage = entry.stage() as usize;
+            if stage != 0 {
+                stages[stage - 1] = Some(Side {
+                    mode: entry.mode,
+                    id: entry.id,
+                });
+            }
+        }
+        if stages.iter().all(Option::is_none) {
+            return Err(resolve::Error::NotConflicted { path: path.to_owned() });
+        }
+        let chosen = match resolution {
+            Resolution::Take(stage) => {
+                let side = match stage {
+                    entry::Stage::Unconflicted => None,
+                    stage => stages[stage as usize - 1],
+                };
+                Some(side.ok_or_else(|| resolve::Error::MissingStage {
+                    path: path.to_owned(),
+                    stage,
+                })?)
+            }
+            Resolution::Entry(side) => Some(side),
+            Resolution::Remove => None,
+        };
+
+        let record = resolve_undo::ResolvePath {
+            name: path.to_owned(),
+            stages: stages.map(|side| side.map(|Side { mode, id }| resolve_undo::Stage { mode: mode.bits(), id })),
+        };
+        let paths = self.resolve_undo.get_or_insert_with(Vec::new);
+        match paths.iter_mut().find(|p| p.name() == path) {
+            Some(existing) => *existing = record,
+            None => paths.push(record),
+        }
+
+        self.remove_path(path);
+        if let Some(Side { mode, id }) = chosen {
+            self.upsert_entry(entry::Stat::default(), id, entry::Flags::empty(), mode, path);
+        }
+        Ok(())
+    }
+
+    /// Remove all entries at `path`, in all of its stages, and invalidate the tree cache accordingly.
+    fn remove_path(&mut self, path: &BStr) {
+        if let Some(range) = self.entry_range(path) {
+            self.entries.drain(range);
+            if let Some(tree) = self.tree.as_mut() {
+                tree.invalidate_path(path);
+            }
+        }
+    }
+}
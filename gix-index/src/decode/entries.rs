@@ -1,8 +1,11 @@
 use std::ops::Range;
 
+use bstr::ByteSlice;
+
 use crate::{
     decode::{self, header},
     entry,
+    mapped::EntryRef,
     util::{read_u32, split_at_byte_exclusive, split_at_pos, var_int},
     Entry, Version,
 };
@@ -84,27 +87,7 @@ fn load_one<'a>(
     prev_path_and_buf: Option<(Range<usize>, &mut Vec<u8>)>,
 ) -> Option<(Entry, &'a [u8])> {
     let first_byte_of_entry = data.as_ptr() as usize;
-    let (ctime_secs, data) = read_u32(data)?;
-    let (ctime_nsecs, data) = read_u32(data)?;
-    let (mtime_secs, data) = read_u32(data)?;
-    let (mtime_nsecs, data) = read_u32(data)?;
-    let (dev, data) = read_u32(data)?;
-    let (ino, data) = read_u32(data)?;
-    let (mode, data) = read_u32(data)?;
-    let (uid, data) = read_u32(data)?;
-    let (gid, data) = read_u32(data)?;
-    let (size, data) = read_u32(data)?;
-    let (hash, data) = split_at_pos(data, hash_len)?;
-    let (flags, data) = read_u16(data)?;
-    let flags = entry::at_rest::Flags::from_bits_retain(flags);
-    let (flags, data) = if flags.contains(entry::at_rest::Flags::EXTENDED) {
-        let (extended_flags, data) = read_u16(data)?;
-        let extended_flags = entry::at_rest::FlagsExtended::from_bits(extended_flags)?;
-        let extended_flags = extended_flags.to_flags()?;
-        (flags.to_memory() | extended_flags, data)
-    } else {
-        (flags.to_memory(), data)
-    };
+    let (Fields { stat, id, flags, mode }, data) = fields(data, hash_len)?;
 
     let start = path_backing.len();
     let data = if has_delta_paths {
@@ -124,14 +107,7 @@ fn load_one<'a>(
 
         data
     } else {
-        let (path, data) = if flags.contains(entry::Flags::PATH_LEN) {
-            split_at_byte_exclusive(data, 0)?
-        } else {
-            let path_len = (flags.bits() & entry::Flags::PATH_LEN.bits()) as usize;
-            let (path, data) = split_at_pos(data, path_len)?;
-            (path, skip_padding(data, first_byte_of_entry))
-        };
-
+        let (path, data) = full_path(data, flags, first_byte_of_entry)?;
         // TODO(perf): for some reason, this causes tremendous `memmove` time even though the backing
         //             has enough capacity most of the time.
         path_backing.extend_from_slice(path);
@@ -141,6 +117,67 @@ fn load_one<'a>(
 
     Some((
         Entry {
+            stat,
+            id: gix_hash::ObjectId::from_bytes_or_panic(id),
+            flags: flags & !entry::Flags::PATH_LEN,
+            mode,
+            path: path_range,
+        },
+        data,
+    ))
+}
+
+/// Decode the entry at the beginning of `data` without copying its path, which only works for indices without delta paths,
+/// i.e. anything but V4.
+pub(crate) fn load_one_ref(data: &[u8], hash_len: usize) -> Option<(EntryRef<'_>, &[u8])> {
+    let first_byte_of_entry = data.as_ptr() as usize;
+    let (Fields { stat, id, flags, mode }, data) = fields(data, hash_len)?;
+    let (path, data) = full_path(data, flags, first_byte_of_entry)?;
+    Some((
+        EntryRef {
+            stat,
+            id: gix_hash::oid::from_bytes_unchecked(id),
+            flags: flags & !entry::Flags::PATH_LEN,
+            mode,
+            path: path.as_bstr(),
+        },
+        data,
+    ))
+}
+
+struct Fields<'a> {
+    stat: entry::Stat,
+    id: &'a [u8],
+    flags: entry::Flags,
+    mode: entry::Mode,
+}
+
+/// Decode all fixed-size fields of an entry, leaving `data` right at the beginning of the path.
+#[inline]
+fn fields(data: &[u8], hash_len: usize) -> Option<(Fields<'_>, &[u8])> {
+    let (ctime_secs, data) = read_u32(data)?;
+    let (ctime_nsecs, data) = read_u32(data)?;
+    let (mtime_secs, data) = read_u32(data)?;
+    let (mtime_nsecs, data) = read_u32(data)?;
+    let (dev, data) = read_u32(data)?;
+    let (ino, data) = read_u32(data)?;
+    let (mode, data) = read_u32(data)?;
+    let (uid, data) = read_u32(data)?;
+    let (gid, data) = read_u32(data)?;
+    let (size, data) = read_u32(data)?;
+    let (id, data) = split_at_pos(data, hash_len)?;
+    let (flags, data) = read_u16(data)?;
+    let flags = entry::at_rest::Flags::from_bits_retain(flags);
+    let (flags, data) = if flags.contains(entry::at_rest::Flags::EXTENDED) {
+        let (extended_flags, data) = read_u16(data)?;
+        let extended_flags = entry::at_rest::FlagsExtended::from_bits(extended_flags)?;
+        let extended_flags = extended_flags.to_flags()?;
+        (flags.to_memory() | extended_flags, data)
+    } else {
+        (flags.to_memory(), data)
+    };
+    Some((
+        Fields {
             stat: entry::Stat {
                 ctime: entry::stat::Time {
                     secs: ctime_secs,
@@ -156,16 +193,27 @@ fn load_one<'a>(
                 gid,
                 size,
             },
-            id: gix_hash::ObjectId::from_bytes_or_panic(hash),
-            flags: flags & !entry::Flags::PATH_LEN,
+            id,
+            flags,
             // This forces us to add the bits we need before being able to use them.
             mode: entry::Mode::from_bits_truncate(mode),
-            path: path_range,
         },
         data,
     ))
 }
 
+/// Split the non-delta path off `data` along with its padding.
+#[inline]
+fn full_path(data: &[u8], flags: entry::Flags, first_byte_of_entry: usize) -> Option<(&[u8], &[u8])> {
+    if flags.contains(entry::Flags::PATH_LEN) {
+        split_at_byte_exclusive(data, 0)
+    } else {
+        let path_len = (flags.bits() & entry::Flags::PATH_LEN.bits()) as usize;
+        let (path, data) = split_at_pos(data, path_len)?;
+        Some((path, skip_padding(data, first_byte_of_entry)))
+    }
+}
+
 #[inline]
 fn skip_padding(data: &[u8], first_byte_of_entry: usize) -> &[u8] {
     let current_offset = data.as_ptr() as usize;
@@ -2,7 +2,7 @@ use filetime::FileTime;
 
 use crate::{entry, extension, Entry, State, Version};
 
-mod entries;
+pub(crate) mod entries;
 ///
 pub mod header;
 
@@ -81,7 +81,9 @@ impl State {
         let (entries, ext, data) = match start_of_extensions {
             Some(offset) if num_threads > 1 => {
                 let extensions_data = &data[offset..];
-                let index_offsets_table = extension::index_entry_offset_table::find(extensions_data, object_hash);
+                // A corrupt offset table would produce wrong entries or out-of-bounds reads, so we rather read sequentially.
+                let index_offsets_table = extension::index_entry_offset_table::find(extensions_data, object_hash)
+                    .filter(|offsets| extension::index_entry_offset_table::is_valid(offsets, num_entries, offset));
                 let (entries_res, ext_res) = gix_features::parallel::threads(|scope| {
                     let extension_loading =
                         (extensions_data.len() > min_extension_block_in_bytes_for_threading).then({
@@ -235,6 +237,8 @@ impl State {
             resolve_undo,
             untracked,
             fs_monitor,
+            metadata,
+            unknown: unknown_extensions,
             is_sparse: is_sparse_from_ext, // a marker is needed in case there are no directories
             end_of_index,
             offset_table,
@@ -257,6 +261,8 @@ impl State {
                 resolve_undo,
                 untracked,
                 fs_monitor,
+                metadata,
+                unknown_extensions,
             },
             checksum,
         ))
@@ -311,13 +317,13 @@ pub(crate) fn stat(data: &[u8]) -> Option<(entry::Stat, &[u8])> {
     Some((
         entry::Stat {
             mtime: entry::stat::Time {
-                secs: ctime_secs,
-                nsecs: ctime_nsecs,
-            },
-            ctime: entry::stat::Time {
                 secs: mtime_secs,
                 nsecs: mtime_nsecs,
             },
+            ctime: entry::stat::Time {
+                secs: ctime_secs,
+                nsecs: ctime_nsecs,
+            },
             dev,
             ino,
             uid,
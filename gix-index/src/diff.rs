@@ -0,0 +1,248 @@
+use bstr::{BStr, BString, ByteSlice, ByteVec};
+use gix_hash::ObjectId;
+use gix_object::FindExt;
+
+use crate::{entry, extension, Entry, State};
+
+/// A change between the entries of a tree and those of an index, as returned by [`State::changes_against_tree()`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Change {
+    /// An entry exists in the index, but not in the tree.
+    Addition {
+        /// The path of the entry relative to the root of the repository.
+        location: BString,
+        /// The position of the entry in the index, for use with [`State::entry()`].
+        index: usize,
+        /// The mode of the entry in the index.
+        entry_mode: entry::Mode,
+        /// The id of the object the entry in the index points to.
+        id: ObjectId,
+    },
+    /// An entry exists in the tree, but not in the index.
+    Deletion {
+        /// The path of the entry relative to the root of the repository.
+        location: BString,
+        /// The mode of the entry in the tree.
+        entry_mode: entry::Mode,
+        /// The id of the object the entry in the tree points to.
+        id: ObjectId,
+    },
+    /// An entry exists in both the tree and the index, but its mode or id differ.
+    Modification {
+        /// The path of the entry relative to the root of the repository.
+        location: BString,
+        /// The position of the entry in the index, for use with [`State::entry()`].
+        index: usize,
+        /// The mode of the entry in the tree.
+        previous_entry_mode: entry::Mode,
+        /// The id of the object the entry in the tree points to.
+        previous_id: ObjectId,
+        /// The mode of the entry in the index.
+        entry_mode: entry::Mode,
+        /// The id of the object the entry in the index points to.
+        id: ObjectId,
+    },
+    /// The index has conflicting entries at this path, so it can't be compared with the tree.
+    Unmerged {
+        /// The path of the entries relative to the root of the repository.
+        location: BString,
+        /// The position of the first conflicting entry in the index, for use with [`State::entry()`].
+        index: usize,
+    },
+}
+
+impl Change {
+    /// Return the location of the entry that changed, relative to the root of the repository.
+    pub fn location(&self) -> &BStr {
+        match self {
+            Change::Addition { location, .. }
+            | Change::Deletion { location, .. }
+            | Change::Modification { location, .. }
+            | Change::Unmerged { location, .. } => location.as_bstr(),
+        }
+    }
+}
+
+///
+pub mod changes_against_tree {
+    /// The error returned by [`State::changes_against_tree()`](crate::State::changes_against_tree()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        FindTree(#[from] gix_object::find::existing_object::Error),
+    }
+}
+
+/// Diffing
+impl State {
+    /// Compare the entries of this index with those in `tree`, recursively, accessing trees with `objects`,
+    /// and return all changes that would turn `tree` into this index, sorted by path.
+    ///
+    /// This is what `git diff-index --cached` does, and is useful to see which changes are staged for commit, for instance
+    /// to implement `git status`.
+    ///
+    /// The [tree cache extension](crate::extension::Tree) is used to skip all directories whose tree is known to be unchanged,
+    /// so no objects have to be read for them. Note that [sparse directory](entry::Mode::DIR) entries are compared
+    /// with their tree as a whole.
+    pub fn changes_against_tree<Find>(
+        &self,
+        tree: &gix_hash::oid,
+        objects: Find,
+    ) -> Result<Vec<Change>, changes_against_tree::Error>
+    where
+        Find: gix_object::Find,
+    {
+        let _span = gix_features::trace::coarse!("gix_index::State::changes_against_tree()");
+        let mut walk = Walk {
+            state: self,
+            objects,
+            cursor: 0,
+            out: Vec::new(),
+        };
+        walk.tree(tree, self.tree.as_ref(), &mut BString::default())?;
+        while walk.cursor < self.entries.len() {
+            walk.addition();
+        }
+        Ok(walk.out)
+    }
+}
+
+struct Walk<'a, Find> {
+    state: &'a State,
+    objects: Find,
+    /// The position of the next index entry to look at.
+    cursor: usize,
+    out: Vec<Change>,
+}
+
+impl<'a, Find> Walk<'a, Find>
+where
+    Find: gix_object::Find,
+{
+    /// Compare all entries of the tree with `id` at `path` to the index entries at the cursor, where `cache` is
+    /// the tree cache for `path`.
+    ///
+    /// Index entries below `path` that remain after the tree was fully traversed aren't handled here.
+    fn tree(
+        &mut self,
+        id: &gix_hash::oid,
+        cache: Option<&extension::Tree>,
+        path: &mut BString,
+    ) -> Result<(), changes_against_tree::Error> {
+        if let Some(num_entries) = cache.filter(|cache| cache.id == id).and_then(|cache| cache.num_entries) {
+            self.cursor += num_entries as usize;
+            return Ok(());
+        }
+
+        let mut buf = Vec::new();
+        let tree = self.objects.find_tree(id, &mut buf)?;
+        let dir_len = path.len();
+        for entry in &tree.entries {
+            if dir_len != 0 {
+                path.push(b'/');
+            }
+            path.push_str(entry.filename);
+            let entry_mode = entry::Mode::from(entry.mode);
+            if entry.mode.is_tree() {
+                path.push(b'/');
+                self.additions_before(path.as_bstr());
+                match self
+                    .current()
+                    .filter(|e| e.mode == entry::Mode::DIR && e.path(self.state) == path.as_bstr())
+                {
+                    Some(sparse_dir) => self.compare(path.as_bstr(), entry_mode, entry.oid, sparse_dir),
+                    None => {
+                        path.pop();
+                        let cache = cache.and_then(|cache| {
+                            cache
+                                .children
+                                .iter()
+                                .find(|child| child.name.as_slice() == entry.filename.as_bytes())
+                        });
+                        self.tree(entry.oid, cache, path)?;
+                        path.push(b'/');
+                        self.additions_within(path.as_bstr());
+                    }
+                }
+            } else {
+                self.additions_before(path.as_bstr());
+                match self.current().filter(|e| e.path(self.state) == path.as_bstr()) {
+                    Some(index_entry) => self.compare(path.as_bstr(), entry_mode, entry.oid, index_entry),
+                    None => self.out.push(Change::Deletion {
+                        location: path.clone(),
+                        entry_mode,
+                        id: entry.oid.to_owned(),
+                    }),
+                }
+            }
+            path.truncate(dir_len);
+        }
+        Ok(())
+    }
+
+    fn current(&self) -> Option<&'a Entry> {
+        self.state.entries.get(self.cursor)
+    }
+
+    /// Compare the tree entry at `location` with the `index_entry` at the cursor, and move past it.
+    fn compare(&mut self, location: &BStr, entry_mode: entry::Mode, id: &gix_hash::oid, index_entry: &Entry) {
+        if index_entry.stage() != entry::Stage::Unconflicted {
+            self.unmerged();
+            return;
+        }
+        if index_entry.mode != entry_mode || index_entry.id != id {
+            self.out.push(Change::Modification {
+                location: location.to_owned(),
+                index: self.cursor,
+                previous_entry_mode: entry_mode,
+                previous_id: id.to_owned(),
+                entry_mode: index_entry.mode,
+                id: index_entry.id,
+            });
+        }
+        self.cursor += 1;
+    }
+
+    /// Record all index entries that sort before `path` as additions.
+    fn additions_before(&mut self, path: &BStr) {
+        while self.current().map_or(false, |e| e.path(self.state) < path) {
+            self.addition();
+        }
+    }
+
+    /// Record all index entries whose path starts with `dir`, which ends with a slash, as additions.
+    fn additions_within(&mut self, dir: &BStr) {
+        while self.current().map_or(false, |e| e.path(self.state).starts_with(dir)) {
+            self.addition();
+        }
+    }
+
+    /// Record the index entry at the cursor as addition, and move past it.
+    fn addition(&mut self) {
+        let entry = &self.state.entries[self.cursor];
+        if entry.stage() != entry::Stage::Unconflicted {
+            self.unmerged();
+            return;
+        }
+        self.out.push(Change::Addition {
+            location: entry.path(self.state).to_owned(),
+            index: self.cursor,
+            entry_mode: entry.mode,
+            id: entry.id,
+        });
+        self.cursor += 1;
+    }
+
+    /// Record the conflicting entries at the cursor as unmerged, and move past all of them.
+    fn unmerged(&mut self) {
+        let location = self.state.entries[self.cursor].path(self.state);
+        self.out.push(Change::Unmerged {
+            location: location.to_owned(),
+            index: self.cursor,
+        });
+        while self.current().map_or(false, |e| e.path(self.state) == location) {
+            self.cursor += 1;
+        }
+    }
+}
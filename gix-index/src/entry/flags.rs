@@ -7,6 +7,7 @@ bitflags! {
     ///
     /// Notably, not all of these will be persisted but can be used to aid all kinds of operations.
     #[derive(Debug, Clone, Copy, Eq, PartialEq)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     pub struct Flags: u32 {
         // TODO: could we use the pathlen ourselves to save 8 bytes? And how to handle longer paths than that? 0 as sentinel maybe?
         /// The mask to obtain the length of the path associated with this entry, up to 4095 characters without extension.
@@ -87,6 +88,14 @@ impl Flags {
         (*self & Flags::STAGE_MASK).bits() >> 12
     }
 
+    /// Return `true` if these flags can only be stored using the extended flags of index version 3 or later.
+    ///
+    /// This is the case if [`EXTENDED`](Self::EXTENDED) is set, or if any flag that is persisted as extended flag is set,
+    /// like [`SKIP_WORKTREE`](Self::SKIP_WORKTREE) or [`INTENT_TO_ADD`](Self::INTENT_TO_ADD).
+    pub fn needs_extended_storage(&self) -> bool {
+        self.intersects(Self::EXTENDED | Self::INTENT_TO_ADD | Self::SKIP_WORKTREE)
+    }
+
     /// Transform ourselves to a storage representation to keep all flags which are to be persisted,
     /// skipping all extended flags. Note that the caller has to check for the `EXTENDED` bit to be present
     /// and write extended flags as well if so.
@@ -35,6 +35,7 @@ use bitflags::bitflags;
 bitflags! {
     /// The kind of file of an entry.
     #[derive(Copy, Clone, Debug, PartialEq, Eq)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     pub struct Mode: u32 {
         /// directory (only used for sparse checkouts), equivalent to a tree, which is _excluded_ from the index via
         /// cone-mode.
@@ -101,6 +102,14 @@ mod access {
         pub fn stage_raw(&self) -> u32 {
             self.flags.stage_raw()
         }
+
+        /// Return `true` if this entry was modified at or after `timestamp`, the time at which the index was written,
+        /// so its stat information can't be trusted to detect changes to the file on disk, see [`entry::Stat::is_racy()`].
+        ///
+        /// Submodules are never racy as their state is determined by looking at the submodule itself.
+        pub fn is_racy(&self, timestamp: filetime::FileTime, options: entry::stat::Options) -> bool {
+            !self.mode.is_submodule() && self.stat.is_racy(timestamp, options)
+        }
     }
 }
 
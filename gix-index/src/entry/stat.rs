@@ -75,6 +75,12 @@ impl Stat {
         }
     }
 
+    /// Compares this stat information with the `metadata` of the file on disk like [`matches()`](Self::matches()),
+    /// returning `false` if the `metadata` can't be represented as stat information.
+    pub fn matches_fs(&self, metadata: &crate::fs::Metadata, options: Options) -> bool {
+        Stat::from_fs(metadata).map_or(false, |stat| self.matches(&stat, options))
+    }
+
     /// Creates stat information from the result of `symlink_metadata`.
     pub fn from_fs(stat: &crate::fs::Metadata) -> Result<Stat, SystemTimeError> {
         let mtime = stat.modified().unwrap_or(std::time::UNIX_EPOCH);
@@ -1,8 +1,50 @@
+use bstr::BStr;
+
 use crate::{entry, Entry, State};
 
 impl Entry {
     /// Serialize ourselves to `out` with path access via `state`, without padding.
     pub fn write_to(&self, mut out: impl std::io::Write, state: &State) -> std::io::Result<()> {
+        let path = self.path(state);
+        self.write_fields_to(&mut out, path)?;
+        out.write_all(path)?;
+        out.write_all(b"\0")
+    }
+
+    /// Serialize ourselves to `out` with path access via `state` like [`write_to()`](Self::write_to()), but with
+    /// the path compressed against `previous_path` as done in index version 4.
+    ///
+    /// `previous_path` is the path of the entry written right before this one, or empty if this is the first entry.
+    pub fn write_to_v4(&self, out: impl std::io::Write, state: &State, previous_path: &BStr) -> std::io::Result<()> {
+        let common_prefix_len = previous_path
+            .iter()
+            .zip(self.path(state).iter())
+            .take_while(|(a, b)| a == b)
+            .count();
+        self.write_to_v4_with_common_prefix(out, state, previous_path.len(), common_prefix_len)
+    }
+
+    /// Like [`write_to_v4()`](Self::write_to_v4()), but with the amount of bytes shared with the previous path given as
+    /// `common_prefix_len`, which allows to write the whole path to start a new block of entries.
+    pub(crate) fn write_to_v4_with_common_prefix(
+        &self,
+        mut out: impl std::io::Write,
+        state: &State,
+        previous_path_len: usize,
+        common_prefix_len: usize,
+    ) -> std::io::Result<()> {
+        let path = self.path(state);
+        self.write_fields_to(&mut out, path)?;
+        let mut buf = [0u8; 10];
+        out.write_all(crate::util::var_int_encode(
+            (previous_path_len - common_prefix_len) as u64,
+            &mut buf,
+        ))?;
+        out.write_all(&path[common_prefix_len..])?;
+        out.write_all(b"\0")
+    }
+
+    fn write_fields_to(&self, out: &mut impl std::io::Write, path: &BStr) -> std::io::Result<()> {
         let stat = self.stat;
         out.write_all(&stat.ctime.secs.to_be_bytes())?;
         out.write_all(&stat.ctime.nsecs.to_be_bytes())?;
@@ -15,7 +57,6 @@ impl Entry {
         out.write_all(&stat.gid.to_be_bytes())?;
         out.write_all(&stat.size.to_be_bytes())?;
         out.write_all(self.id.as_bytes())?;
-        let path = self.path(state);
         let path_len: u16 = if path.len() >= entry::Flags::PATH_LEN.bits() as usize {
             entry::Flags::PATH_LEN.bits() as u16
         } else {
@@ -23,15 +64,15 @@ impl Entry {
                 .try_into()
                 .expect("we just checked that the length is smaller than 0xfff")
         };
-        out.write_all(&(self.flags.to_storage().bits() | path_len).to_be_bytes())?;
-        if self.flags.contains(entry::Flags::EXTENDED) {
-            out.write_all(
-                &entry::at_rest::FlagsExtended::from_flags(self.flags)
-                    .bits()
-                    .to_be_bytes(),
-            )?;
+        let flags = if self.flags.needs_extended_storage() {
+            self.flags | entry::Flags::EXTENDED
+        } else {
+            self.flags
+        };
+        out.write_all(&(flags.to_storage().bits() | path_len).to_be_bytes())?;
+        if flags.contains(entry::Flags::EXTENDED) {
+            out.write_all(&entry::at_rest::FlagsExtended::from_flags(flags).bits().to_be_bytes())?;
         }
-        out.write_all(path)?;
-        out.write_all(b"\0")
+        Ok(())
     }
 }
@@ -0,0 +1,69 @@
+use bstr::BString;
+
+use crate::{entry, State};
+
+/// The error returned by [State::expand()].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("Could not expand the sparse directory entry at '{path}'")]
+    FromTree {
+        path: BString,
+        source: crate::init::from_tree::Error,
+    },
+}
+
+impl State {
+    /// Replace all sparse directory entries with the entries of the trees they point to, looking them up in `objects`,
+    /// to turn a sparse index as written by `git sparse-checkout --sparse-index` into a regular one.
+    ///
+    /// This is needed for operations that can't deal with [sparse directory entries](entry::Mode::DIR).
+    /// All expanded entries are marked with [`SKIP_WORKTREE`](entry::Flags::SKIP_WORKTREE) just like the directory
+    /// they were contained in, and `validate` controls the validation of their path components.
+    /// Nothing happens if this index [isn't sparse](State::is_sparse()).
+    pub fn expand(
+        &mut self,
+        objects: impl gix_object::Find,
+        validate: gix_validate::path::component::Options,
+    ) -> Result<(), Error> {
+        if !self.is_sparse {
+            return Ok(());
+        }
+        let _span = gix_features::trace::detail!("gix_index::State::expand()");
+        let mut entries = Vec::with_capacity(self.entries.len());
+        let mut path_backing = Vec::with_capacity(self.path_backing.len());
+        for entry in &self.entries {
+            let path = entry.path(self);
+            if !entry.mode.is_sparse() {
+                let start = path_backing.len();
+                path_backing.extend_from_slice(path);
+                let mut entry = entry.clone();
+                entry.path = start..path_backing.len();
+                entries.push(entry);
+                continue;
+            }
+
+            let dir = State::from_tree(&entry.id, &objects, validate).map_err(|source| Error::FromTree {
+                path: path.to_owned(),
+                source,
+            })?;
+            // Paths of sparse directories end with a slash, and the entries of their trees keep them in order.
+            for dir_entry in dir.entries() {
+                let start = path_backing.len();
+                path_backing.extend_from_slice(path);
+                if !path.ends_with(b"/") {
+                    path_backing.push(b'/');
+                }
+                path_backing.extend_from_slice(dir_entry.path(&dir));
+                let mut dir_entry = dir_entry.clone();
+                dir_entry.path = start..path_backing.len();
+                dir_entry.flags |= entry::Flags::SKIP_WORKTREE | entry::Flags::EXTENDED;
+                entries.push(dir_entry);
+            }
+        }
+        self.entries = entries;
+        self.path_backing = path_backing;
+        self.is_sparse = false;
+        Ok(())
+    }
+}
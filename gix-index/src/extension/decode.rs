@@ -48,6 +48,9 @@ pub(crate) fn all(
             extension::fs_monitor::SIGNATURE => {
                 ext.fs_monitor = extension::fs_monitor::decode(ext_data);
             }
+            extension::metadata::SIGNATURE => {
+                ext.metadata = extension::metadata::decode(ext_data);
+            }
             extension::end_of_index_entry::SIGNATURE => {
                 ext.end_of_index = true;
             } // skip already done
@@ -65,7 +68,10 @@ pub(crate) fn all(
                 }
                 unknown => return Err(Error::MandatoryUnimplemented { signature: unknown }),
             },
-            _unknown => {} // skip unknown extensions, too
+            unknown => ext.unknown.push(extension::Unknown {
+                signature: unknown,
+                data: ext_data.to_owned(),
+            }),
         }
     }
     Ok((ext, &maybe_beginning_of_extensions[ext_iter.consumed..]))
@@ -78,6 +84,8 @@ pub(crate) struct Outcome {
     pub resolve_undo: Option<extension::resolve_undo::Paths>,
     pub untracked: Option<extension::UntrackedCache>,
     pub fs_monitor: Option<extension::FsMonitor>,
+    pub metadata: Option<extension::Metadata>,
+    pub unknown: Vec<extension::Unknown>,
     pub is_sparse: bool,
     pub offset_table: bool,
     pub end_of_index: bool,
@@ -1,19 +1,85 @@
 use bstr::BString;
 
 use crate::{
-    extension::{FsMonitor, Signature},
+    extension::{self, FsMonitor, Signature},
     util::{read_u32, read_u64, split_at_byte_exclusive},
 };
 
-#[derive(Clone)]
-#[allow(dead_code)]
+/// The token that identifies the point in time at which the filesystem monitor was last queried.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Token {
-    V1 { nanos_since_1970: u64 },
-    V2 { token: BString },
+    /// The token of version 1 of the extension, a timestamp.
+    V1 {
+        /// The amount of nanoseconds since the unix epoch.
+        nanos_since_1970: u64,
+    },
+    /// The token of version 2 of the extension, an opaque string that is interpreted by the filesystem monitor.
+    V2 {
+        /// The token as provided by the filesystem monitor.
+        token: BString,
+    },
 }
 
+/// The signature of the filesystem monitor extension.
 pub const SIGNATURE: Signature = *b"FSMN";
 
+/// Initialization
+impl FsMonitor {
+    /// Create a new instance for the point in time identified by `token`, with each set bit in `entry_dirty` marking the entry
+    /// at the same index as not known to be valid by the filesystem monitor.
+    pub fn new(token: Token, entry_dirty: gix_bitmap::ewah::Vec) -> Self {
+        FsMonitor { token, entry_dirty }
+    }
+}
+
+/// Access
+impl FsMonitor {
+    /// Return the token at which the filesystem monitor was last queried.
+    pub fn token(&self) -> &Token {
+        &self.token
+    }
+
+    /// Return a bitmap in which each set bit marks the entry at the same index as not known to be valid
+    /// by the filesystem monitor.
+    pub fn entry_dirty(&self) -> &gix_bitmap::ewah::Vec {
+        &self.entry_dirty
+    }
+
+    /// Replace the bitmap of entries not known to be valid with `entry_dirty`.
+    pub(crate) fn set_entry_dirty(&mut self, entry_dirty: gix_bitmap::ewah::Vec) {
+        self.entry_dirty = entry_dirty;
+    }
+}
+
+/// Serialization
+impl FsMonitor {
+    /// Serialize this instance to `out`.
+    pub fn write_to(&self, mut out: impl std::io::Write) -> Result<(), std::io::Error> {
+        let mut data = Vec::new();
+        match &self.token {
+            Token::V1 { nanos_since_1970 } => {
+                data.extend_from_slice(&1_u32.to_be_bytes());
+                data.extend_from_slice(&nanos_since_1970.to_be_bytes());
+            }
+            Token::V2 { token } => {
+                data.extend_from_slice(&2_u32.to_be_bytes());
+                data.extend_from_slice(token);
+                data.push(0);
+            }
+        }
+        let mut bitmap = Vec::new();
+        self.entry_dirty.write_to(&mut bitmap)?;
+        data.extend_from_slice(&extension::size_u32(SIGNATURE, bitmap.len())?.to_be_bytes());
+        data.extend_from_slice(&bitmap);
+
+        out.write_all(&SIGNATURE)?;
+        out.write_all(&extension::size_u32(SIGNATURE, data.len())?.to_be_bytes())?;
+        out.write_all(&data)
+    }
+}
+
+/// Decode an instance from `data`, returning `None` if it's malformed.
 pub fn decode(data: &[u8]) -> Option<FsMonitor> {
     let (version, data) = read_u32(data)?;
     let (token, data) = match version {
@@ -29,7 +95,7 @@ pub fn decode(data: &[u8]) -> Option<FsMonitor> {
     };
 
     let (ewah_size, data) = read_u32(data)?;
-    let (entry_dirty, data) = gix_bitmap::ewah::decode(&data[..ewah_size as usize]).ok()?;
+    let (entry_dirty, data) = gix_bitmap::ewah::decode(data.get(..ewah_size as usize)?).ok()?;
 
     if !data.is_empty() {
         return None;
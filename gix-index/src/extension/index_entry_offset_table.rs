@@ -36,6 +36,36 @@ pub fn decode(data: &[u8]) -> Option<Vec<Offset>> {
     out.into()
 }
 
+pub fn write_to(mut out: impl std::io::Write, offsets: &[Offset]) -> Result<(), std::io::Error> {
+    out.write_all(&SIGNATURE)?;
+    let extension_size = 4 + offsets.len() * (4 + 4);
+    out.write_all(&extension::size_u32(SIGNATURE, extension_size)?.to_be_bytes())?;
+    out.write_all(&1_u32.to_be_bytes())?;
+    for offset in offsets {
+        out.write_all(&offset.from_beginning_of_file.to_be_bytes())?;
+        out.write_all(&offset.num_entries.to_be_bytes())?;
+    }
+    Ok(())
+}
+
+/// Return `true` if `offsets` point to blocks in ascending order that start within the entries section, which begins right
+/// after the header and ends at `end_of_entries`, and if they account for exactly `num_entries` entries.
+///
+/// Only then can the offsets be used to decode blocks of entries independently of each other.
+pub fn is_valid(offsets: &[Offset], num_entries: u32, end_of_entries: usize) -> bool {
+    let mut min_offset = crate::decode::header::SIZE;
+    let mut total_entries = 0_u64;
+    for offset in offsets {
+        let start = offset.from_beginning_of_file as usize;
+        if start < min_offset || start >= end_of_entries || offset.num_entries == 0 {
+            return false;
+        }
+        min_offset = start + 1;
+        total_entries += u64::from(offset.num_entries);
+    }
+    total_entries == u64::from(num_entries)
+}
+
 pub fn find(extensions: &[u8], object_hash: gix_hash::Kind) -> Option<Vec<Offset>> {
     extension::Iter::new_without_checksum(extensions, object_hash)?
         .find_map(|(sig, ext_data)| (sig == SIGNATURE).then_some(ext_data))
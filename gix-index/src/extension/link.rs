@@ -1,6 +1,8 @@
 use crate::{
-    extension::{Link, Signature},
+    entry,
+    extension::{self, Link, Signature},
     util::split_at_pos,
+    Entry, PathStorage, State,
 };
 
 /// The signature of the link extension.
@@ -8,6 +10,7 @@ pub const SIGNATURE: Signature = *b"link";
 
 /// Bitmaps to know which entries to delete or replace, even though details are still unknown.
 #[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Bitmaps {
     /// A bitmap to signal which entries to delete, maybe.
     pub delete: gix_bitmap::ewah::Vec,
@@ -15,6 +18,15 @@ pub struct Bitmaps {
     pub replace: gix_bitmap::ewah::Vec,
 }
 
+/// The entries of the shared index that a split index is based on, as they are stored in `sharedindex.<checksum>`.
+#[derive(Clone)]
+pub(crate) struct SharedIndex {
+    /// The checksum of the shared index file, which is also part of its file name.
+    pub checksum: gix_hash::ObjectId,
+    pub entries: Vec<Entry>,
+    pub path_backing: PathStorage,
+}
+
 ///
 pub mod decode {
 
@@ -68,6 +80,19 @@ pub(crate) fn decode(data: &[u8], object_hash: gix_hash::Kind) -> Result<Link, d
 }
 
 impl Link {
+    /// Serialize this instance to `out`.
+    pub(crate) fn write_to(&self, mut out: impl std::io::Write) -> Result<(), std::io::Error> {
+        let mut data = Vec::new();
+        data.extend_from_slice(self.shared_index_checksum.as_bytes());
+        if let Some(bitmaps) = &self.bitmaps {
+            bitmaps.delete.write_to(&mut data)?;
+            bitmaps.replace.write_to(&mut data)?;
+        }
+        out.write_all(&SIGNATURE)?;
+        out.write_all(&extension::size_u32(SIGNATURE, data.len())?.to_be_bytes())?;
+        out.write_all(&data)
+    }
+
     pub(crate) fn dissolve_into(
         self,
         split_index: &mut crate::File,
@@ -89,91 +114,204 @@ impl Link {
                 ..options
             },
         )?;
+        split_index.shared_index = Some(SharedIndex {
+            checksum: self.shared_index_checksum,
+            entries: shared_index.entries.clone(),
+            path_backing: shared_index.path_backing.clone(),
+        });
 
-        if let Some(bitmaps) = self.bitmaps {
-            let mut split_entry_index = 0;
-
-            let mut err = None;
-            bitmaps.replace.for_each_set_bit(|replace_index| {
-                let shared_entry = match shared_index.entries.get_mut(replace_index) {
-                    Some(e) => e,
-                    None => {
-                        err = decode::Error::Corrupt("replace bitmap length exceeds shared index length - more entries in bitmap than found in shared index").into();
-                        return None
-                    }
-                };
+        // Without bitmaps, all entries of the split index are added to the shared index.
+        let bitmaps = self.bitmaps.unwrap_or_else(|| Bitmaps {
+            delete: gix_bitmap::ewah::Vec::from_set_bits(None),
+            replace: gix_bitmap::ewah::Vec::from_set_bits(None),
+        });
+        let mut split_entry_index = 0;
 
-                if shared_entry.flags.contains(crate::entry::Flags::REMOVE) {
-                    err = decode::Error::Corrupt("entry is marked as both replace and delete").into();
+        let mut err = None;
+        bitmaps.replace.for_each_set_bit(|replace_index| {
+            let shared_entry = match shared_index.entries.get_mut(replace_index) {
+                Some(e) => e,
+                None => {
+                    err = decode::Error::Corrupt("replace bitmap length exceeds shared index length - more entries in bitmap than found in shared index").into();
                     return None
                 }
+            };
 
-                let split_entry = match split_index.entries.get(split_entry_index) {
-                    Some(e) => e,
-                    None => {
-                        err = decode::Error::Corrupt("replace bitmap length exceeds split index length - more entries in bitmap than found in split index").into();
-                        return None
-                    }
-                };
-                if !split_entry.path.is_empty() {
-                    err = decode::Error::Corrupt("paths in split index entries that are for replacement should be empty").into();
+            if shared_entry.flags.contains(crate::entry::Flags::REMOVE) {
+                err = decode::Error::Corrupt("entry is marked as both replace and delete").into();
+                return None
+            }
+
+            let split_entry = match split_index.entries.get(split_entry_index) {
+                Some(e) => e,
+                None => {
+                    err = decode::Error::Corrupt("replace bitmap length exceeds split index length - more entries in bitmap than found in split index").into();
                     return None
                 }
-                if shared_entry.path.is_empty() {
-                    err = decode::Error::Corrupt("paths in shared index entries that are replaced should not be empty").into();
+            };
+            if !split_entry.path.is_empty() {
+                err = decode::Error::Corrupt("paths in split index entries that are for replacement should be empty").into();
+                return None
+            }
+            if shared_entry.path.is_empty() {
+                err = decode::Error::Corrupt("paths in shared index entries that are replaced should not be empty").into();
+                return None
+            }
+            shared_entry.stat = split_entry.stat;
+            shared_entry.id = split_entry.id;
+            shared_entry.flags = split_entry.flags;
+            shared_entry.mode = split_entry.mode;
+
+            split_entry_index += 1;
+            Some(())
+        });
+        if let Some(err) = err {
+            return Err(err.into());
+        }
+
+        let split_index_path_backing = std::mem::take(&mut split_index.path_backing);
+        for mut split_entry in split_index.entries.drain(split_entry_index..) {
+            let start = shared_index.path_backing.len();
+            let split_index_path = split_entry.path.clone();
+
+            split_entry.path = start..start + split_entry.path.len();
+            shared_index.entries.push(split_entry);
+
+            shared_index
+                .path_backing
+                .extend_from_slice(&split_index_path_backing[split_index_path]);
+        }
+
+        bitmaps.delete.for_each_set_bit(|delete_index| {
+            let shared_entry = match shared_index.entries.get_mut(delete_index) {
+                Some(e) => e,
+                None => {
+                    err = decode::Error::Corrupt("delete bitmap length exceeds shared index length - more entries in bitmap than found in shared index").into();
                     return None
                 }
-                shared_entry.stat = split_entry.stat;
-                shared_entry.id = split_entry.id;
-                shared_entry.flags = split_entry.flags;
-                shared_entry.mode = split_entry.mode;
+            };
+            shared_entry.flags.insert(crate::entry::Flags::REMOVE);
+            Some(())
+        });
+        if let Some(err) = err {
+            return Err(err.into());
+        }
 
-                split_entry_index += 1;
-                Some(())
-            });
-            if let Some(err) = err {
-                return Err(err.into());
-            }
+        shared_index
+            .entries
+            .retain(|e| !e.flags.contains(crate::entry::Flags::REMOVE));
 
-            let split_index_path_backing = std::mem::take(&mut split_index.path_backing);
-            for mut split_entry in split_index.entries.drain(split_entry_index..) {
-                let start = shared_index.path_backing.len();
-                let split_index_path = split_entry.path.clone();
+        let mut shared_entries = std::mem::take(&mut shared_index.entries);
+        shared_entries.sort_by(|a, b| a.cmp(b, &shared_index.state));
 
-                split_entry.path = start..start + split_entry.path.len();
-                shared_index.entries.push(split_entry);
+        split_index.entries = shared_entries;
+        split_index.path_backing = std::mem::take(&mut shared_index.path_backing);
 
-                shared_index
-                    .path_backing
-                    .extend_from_slice(&split_index_path_backing[split_index_path]);
-            }
+        Ok(())
+    }
+}
 
-            bitmaps.delete.for_each_set_bit(|delete_index| {
-                let shared_entry = match shared_index.entries.get_mut(delete_index) {
-                    Some(e) => e,
-                    None => {
-                        err = decode::Error::Corrupt("delete bitmap length exceeds shared index length - more entries in bitmap than found in shared index").into();
-                        return None
-                    }
-                };
-                shared_entry.flags.insert(crate::entry::Flags::REMOVE);
-                Some(())
-            });
-            if let Some(err) = err {
-                return Err(err.into());
-            }
+impl SharedIndex {
+    /// Create a shared index from all entries in `state` that aren't marked for removal, using `checksum` as its checksum.
+    pub(crate) fn from_state(state: &State, checksum: gix_hash::ObjectId) -> Self {
+        let mut path_backing = PathStorage::with_capacity(state.path_backing.len());
+        let entries = state
+            .entries
+            .iter()
+            .filter(|entry| !entry.flags.contains(entry::Flags::REMOVE))
+            .map(|entry| {
+                let start = path_backing.len();
+                path_backing.extend_from_slice(entry.path(state));
+                Entry {
+                    path: start..path_backing.len(),
+                    ..entry.clone()
+                }
+            })
+            .collect();
+        SharedIndex {
+            checksum,
+            entries,
+            path_backing,
+        }
+    }
 
-            shared_index
-                .entries
-                .retain(|e| !e.flags.contains(crate::entry::Flags::REMOVE));
+    /// Return a state with our entries and no extension, with all other fields taken from `state`.
+    pub(crate) fn to_state(&self, state: &State) -> State {
+        State {
+            object_hash: state.object_hash,
+            timestamp: state.timestamp,
+            version: state.version,
+            entries: self.entries.clone(),
+            path_backing: self.path_backing.clone(),
+            is_sparse: false,
+            end_of_index_at_decode_time: false,
+            offset_table_at_decode_time: false,
+            tree: None,
+            link: None,
+            resolve_undo: None,
+            untracked: None,
+            fs_monitor: None,
+            metadata: None,
+            unknown_extensions: Vec::new(),
+        }
+    }
 
-            let mut shared_entries = std::mem::take(&mut shared_index.entries);
-            shared_entries.sort_by(|a, b| a.cmp(b, &shared_index.state));
+    /// Compute the entries and path backing of the split index that turns our entries into the ones of `state`,
+    /// along with the bitmaps that mark which of our entries are deleted or replaced.
+    ///
+    /// Replaced entries come first and have an empty path, followed by all entries that are new to `state`.
+    pub(crate) fn split(&self, state: &State) -> (Vec<Entry>, PathStorage, Bitmaps) {
+        let persisted = entry::Flags::STAGE_MASK
+            | entry::Flags::ASSUME_VALID
+            | entry::Flags::INTENT_TO_ADD
+            | entry::Flags::SKIP_WORKTREE;
+        let mut is_shared = vec![false; state.entries.len()];
+        let (mut deleted, mut replaced) = (Vec::new(), Vec::new());
+        let mut entries = Vec::new();
+        for (shared_idx, shared) in self.entries.iter().enumerate() {
+            let path = shared.path_in(&self.path_backing);
+            match state
+                .entry_index_by_path_and_stage(path, shared.stage())
+                .filter(|idx| !state.entries[*idx].flags.contains(entry::Flags::REMOVE))
+            {
+                None => deleted.push(shared_idx),
+                Some(idx) => {
+                    is_shared[idx] = true;
+                    let current = &state.entries[idx];
+                    if current.id != shared.id
+                        || current.mode != shared.mode
+                        || current.stat != shared.stat
+                        || (current.flags & persisted) != (shared.flags & persisted)
+                    {
+                        replaced.push(shared_idx);
+                        entries.push(Entry {
+                            path: 0..0,
+                            ..current.clone()
+                        });
+                    }
+                }
+            }
+        }
 
-            split_index.entries = shared_entries;
-            split_index.path_backing = std::mem::take(&mut shared_index.path_backing);
+        let mut path_backing = PathStorage::new();
+        for (entry, _) in state
+            .entries
+            .iter()
+            .zip(is_shared)
+            .filter(|(entry, is_shared)| !*is_shared && !entry.flags.contains(entry::Flags::REMOVE))
+        {
+            let start = path_backing.len();
+            path_backing.extend_from_slice(entry.path(state));
+            entries.push(Entry {
+                path: start..path_backing.len(),
+                ..entry.clone()
+            });
         }
 
-        Ok(())
+        let bitmaps = Bitmaps {
+            delete: gix_bitmap::ewah::Vec::from_set_bits(deleted),
+            replace: gix_bitmap::ewah::Vec::from_set_bits(replaced),
+        };
+        (entries, path_backing, bitmaps)
     }
 }
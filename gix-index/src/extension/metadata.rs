@@ -0,0 +1,108 @@
+use bstr::{BStr, BString, ByteSlice};
+
+use crate::{
+    extension::{self, Metadata, Signature},
+    util::{read_u32, split_at_byte_exclusive, split_at_pos},
+};
+
+/// The signature of the metadata extension.
+///
+/// It starts with an upper-case letter which makes it optional, so `git` ignores it.
+pub const SIGNATURE: Signature = *b"GIXM";
+
+/// Access and mutation
+impl Metadata {
+    /// Return the data stored for `path` in `namespace`, if present.
+    pub fn get(&self, namespace: &BStr, path: &BStr) -> Option<&[u8]> {
+        self.namespaces.get(namespace)?.get(path).map(Vec::as_slice)
+    }
+
+    /// Store `data` for `path` in `namespace` and return the previously stored data, if present.
+    pub fn set(
+        &mut self,
+        namespace: impl Into<BString>,
+        path: impl Into<BString>,
+        data: impl Into<Vec<u8>>,
+    ) -> Option<Vec<u8>> {
+        self.namespaces
+            .entry(namespace.into())
+            .or_default()
+            .insert(path.into(), data.into())
+    }
+
+    /// Remove the data stored for `path` in `namespace` and return it, if present.
+    pub fn remove(&mut self, namespace: &BStr, path: &BStr) -> Option<Vec<u8>> {
+        let paths = self.namespaces.get_mut(namespace)?;
+        let data = paths.remove(path);
+        if paths.is_empty() {
+            self.namespaces.remove(namespace);
+        }
+        data
+    }
+
+    /// Remove all data stored in `namespace`, and return `true` if there was any.
+    pub fn remove_namespace(&mut self, namespace: &BStr) -> bool {
+        self.namespaces.remove(namespace).is_some()
+    }
+
+    /// Return an iterator over all paths and their data in `namespace`, sorted by path.
+    pub fn namespace(&self, namespace: &BStr) -> impl Iterator<Item = (&BStr, &[u8])> + '_ {
+        self.namespaces
+            .get(namespace)
+            .into_iter()
+            .flat_map(|paths| paths.iter().map(|(path, data)| (path.as_bstr(), data.as_slice())))
+    }
+
+    /// Return an iterator over all namespaces that have data, sorted by name.
+    pub fn namespaces(&self) -> impl Iterator<Item = &BStr> + '_ {
+        self.namespaces.keys().map(|name| name.as_bstr())
+    }
+
+    /// Return `true` if no data is stored at all.
+    pub fn is_empty(&self) -> bool {
+        self.namespaces.is_empty()
+    }
+}
+
+/// Serialization
+impl Metadata {
+    /// Serialize this instance to `out`.
+    pub fn write_to(&self, mut out: impl std::io::Write) -> Result<(), std::io::Error> {
+        let len_u32 = |len: usize| extension::size_u32(SIGNATURE, len).map(u32::to_be_bytes);
+        let mut data = Vec::new();
+        for (namespace, paths) in &self.namespaces {
+            data.extend_from_slice(namespace);
+            data.push(0);
+            data.extend_from_slice(&len_u32(paths.len())?);
+            for (path, path_data) in paths {
+                data.extend_from_slice(path);
+                data.push(0);
+                data.extend_from_slice(&len_u32(path_data.len())?);
+                data.extend_from_slice(path_data);
+            }
+        }
+
+        out.write_all(&SIGNATURE)?;
+        out.write_all(&len_u32(data.len())?)?;
+        out.write_all(&data)
+    }
+}
+
+/// Decode the metadata extension from `data`.
+pub fn decode(mut data: &[u8]) -> Option<Metadata> {
+    let mut out = Metadata::default();
+    while !data.is_empty() {
+        let (namespace, rest) = split_at_byte_exclusive(data, 0)?;
+        let (num_paths, rest) = read_u32(rest)?;
+        data = rest;
+        let paths = out.namespaces.entry(namespace.into()).or_default();
+        for _ in 0..num_paths {
+            let (path, rest) = split_at_byte_exclusive(data, 0)?;
+            let (len, rest) = read_u32(rest)?;
+            let (path_data, rest) = split_at_pos(rest, len as usize)?;
+            data = rest;
+            paths.insert(path.into(), path_data.to_owned());
+        }
+    }
+    Some(out)
+}
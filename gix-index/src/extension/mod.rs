@@ -19,8 +19,10 @@ pub struct Iter<'a> {
 /// It allows to more quickly build trees by avoiding as it can quickly reuse portions of the index and its associated tree ids
 /// if there was no change to them. Portions of this tree are invalidated as the index is changed.
 #[derive(PartialEq, Eq, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Tree {
     /// The name of the tree/directory, or empty if it's the root tree.
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_impls::path"))]
     pub name: SmallVec<[u8; 23]>,
     /// The id of the directory tree of the associated tree object.
     pub id: gix_hash::ObjectId,
@@ -34,6 +36,7 @@ pub struct Tree {
 
 /// The link extension to track a shared index.
 #[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Link {
     /// The checksum of the shared index as last seen.
     pub shared_index_checksum: gix_hash::ObjectId,
@@ -42,8 +45,11 @@ pub struct Link {
 }
 
 /// The extension for untracked files.
-#[allow(dead_code)]
-#[derive(Clone)]
+///
+/// It caches the untracked files of each directory along with the stat information of the directory itself,
+/// so directories that didn't change since the cache was written don't have to be traversed again.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct UntrackedCache {
     /// Something identifying the location and machine that this cache is for.
     /// Should the repository be copied to a different machine, the entire cache can immediately be invalidated.
@@ -61,17 +67,60 @@ pub struct UntrackedCache {
 }
 
 /// The extension for keeping state on recent information provided by the filesystem monitor.
-#[allow(dead_code)]
-#[derive(Clone)]
+///
+/// It's written by `git` if `core.fsmonitor` is enabled, and preserved as is when writing the index.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct FsMonitor {
     token: fs_monitor::Token,
     /// if a bit is true, the respective entry is NOT valid as per the fs monitor.
     entry_dirty: gix_bitmap::ewah::Vec,
 }
 
+/// Small blobs of data that tools associate with paths, grouped by namespace to avoid clashes between tools.
+///
+/// Build systems can use it to keep per-file state alongside the index. It's stored in an optional extension that `git`
+/// ignores, but that is preserved when gitoxide writes the index.
+/// Note that the data isn't adjusted as entries are added or removed, so tools have to clean up after themselves.
+#[derive(Default, Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Metadata {
+    namespaces: std::collections::BTreeMap<BString, std::collections::BTreeMap<BString, Vec<u8>>>,
+}
+
+/// An optional extension that isn't understood by this implementation, like one written by a newer version of `git`.
+///
+/// Its data is kept as is so it can be written back unchanged, to avoid losing information.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Unknown {
+    /// The signature of the extension, which starts with an upper-case letter as it's optional.
+    pub signature: Signature,
+    /// The data of the extension, without signature and size.
+    pub data: Vec<u8>,
+}
+
+/// Return `size` as the size of the extension with `signature` as stored on disk, or an error if it exceeds the 4GB
+/// that can be represented.
+pub(crate) fn size_u32(
+    signature: Signature,
+    size: impl TryInto<u32> + Copy + std::fmt::Display,
+) -> std::io::Result<u32> {
+    size.try_into().map_err(|_| {
+        std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            format!(
+                "Extension {} must not be larger than 4GB, but is {size} bytes large",
+                bstr::BStr::new(&signature)
+            ),
+        )
+    })
+}
+
 mod iter;
 
-pub(crate) mod fs_monitor;
+///
+pub mod fs_monitor;
 
 ///
 pub mod decode;
@@ -87,10 +136,14 @@ pub(crate) mod index_entry_offset_table;
 ///
 pub mod link;
 
-pub(crate) mod resolve_undo;
+///
+pub mod resolve_undo;
 
 ///
 pub mod untracked_cache;
 
 ///
 pub mod sparse;
+
+///
+pub mod metadata;
@@ -1,32 +1,64 @@
-use bstr::BString;
+use bstr::{BStr, BString};
 use gix_hash::ObjectId;
 
 use crate::{
-    extension::Signature,
+    extension::{self, Signature},
     util::{split_at_byte_exclusive, split_at_pos},
 };
 
+/// All paths recorded in the resolve-undo extension, in the order they were stored.
 pub type Paths = Vec<ResolvePath>;
 
-#[allow(dead_code)]
-#[derive(Clone)]
+/// A path whose conflict was resolved, along with the stages it had before the resolution.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ResolvePath {
     /// relative to the root of the repository, or what would be stored in the index
-    name: BString,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_impls::path"))]
+    pub(crate) name: BString,
 
     /// 0 = ancestor/common, 1 = ours, 2 = theirs
-    stages: [Option<Stage>; 3],
+    pub(crate) stages: [Option<Stage>; 3],
 }
 
-#[allow(dead_code)]
-#[derive(Clone, Copy)]
+/// An entry at one of the conflict stages as it was before the conflict was resolved.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Stage {
-    mode: u32,
-    id: ObjectId,
+    pub(crate) mode: u32,
+    pub(crate) id: ObjectId,
 }
 
+/// Access
+impl ResolvePath {
+    /// The path relative to the root of the repository.
+    pub fn name(&self) -> &BStr {
+        self.name.as_ref()
+    }
+
+    /// The stages for the common ancestor, ours and theirs, each of which may be absent.
+    pub fn stages(&self) -> &[Option<Stage>; 3] {
+        &self.stages
+    }
+}
+
+/// Access
+impl Stage {
+    /// The mode of the entry at this stage, like `0o100644`.
+    pub fn mode(&self) -> u32 {
+        self.mode
+    }
+
+    /// The id of the object at this stage.
+    pub fn id(&self) -> &gix_hash::oid {
+        &self.id
+    }
+}
+
+/// The signature of the resolve-undo extension.
 pub const SIGNATURE: Signature = *b"REUC";
 
+/// Decode the resolve-undo extension from `data`, or return `None` if it is malformed.
 pub fn decode(mut data: &[u8], object_hash: gix_hash::Kind) -> Option<Paths> {
     let hash_len = object_hash.len_in_bytes();
     let mut out = Vec::new();
@@ -62,3 +94,24 @@ pub fn decode(mut data: &[u8], object_hash: gix_hash::Kind) -> Option<Paths> {
     }
     out.into()
 }
+
+/// Serialize the resolve-undo extension with all `paths` to `out`.
+pub fn write_to(mut out: impl std::io::Write, paths: &Paths) -> Result<(), std::io::Error> {
+    let mut data = Vec::new();
+    for path in paths {
+        data.extend_from_slice(&path.name);
+        data.push(0);
+        for stage in &path.stages {
+            let mode = stage.map_or(0, |stage| stage.mode);
+            data.extend_from_slice(format!("{mode:o}").as_bytes());
+            data.push(0);
+        }
+        for stage in path.stages.iter().flatten() {
+            data.extend_from_slice(stage.id.as_bytes());
+        }
+    }
+
+    out.write_all(&SIGNATURE)?;
+    out.write_all(&extension::size_u32(SIGNATURE, data.len())?.to_be_bytes())?;
+    out.write_all(&data)
+}
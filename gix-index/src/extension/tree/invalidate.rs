@@ -0,0 +1,25 @@
+use bstr::{BStr, ByteSlice};
+
+use crate::extension::Tree;
+
+impl Tree {
+    /// Mark this tree and all trees leading to `path` as invalid as the entry at `path` was added, removed or changed.
+    ///
+    /// If `path` names one of the trees, it's removed entirely as it was replaced by a non-tree entry, or removed.
+    pub fn invalidate_path(&mut self, path: &BStr) {
+        self.num_entries = None;
+        let (name, rest) = match path.split_once_str("/") {
+            Some((name, rest)) => (name, Some(rest)),
+            None => (path.as_bytes(), None),
+        };
+        let Ok(idx) = self.children.binary_search_by(|child| child.name.as_slice().cmp(name)) else {
+            return;
+        };
+        match rest {
+            Some(rest) => self.children[idx].invalidate_path(rest.as_bstr()),
+            None => {
+                self.children.remove(idx);
+            }
+        }
+    }
+}
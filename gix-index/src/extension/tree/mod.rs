@@ -6,9 +6,14 @@ pub const SIGNATURE: Signature = *b"TREE";
 ///
 pub mod verify;
 
+///
+pub mod update;
+
 mod decode;
 pub use decode::decode;
 
+mod invalidate;
+
 mod write;
 
 #[cfg(test)]
@@ -0,0 +1,133 @@
+use bstr::{BString, ByteSlice};
+
+use crate::{entry, extension::Tree, Entry, State};
+
+/// The error returned by [`Tree::update_from_entries()`].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("Cannot write a tree with the conflicting entry at '{path}'")]
+    Conflict { path: BString },
+    #[error("The entry at '{path}' has a mode that can't be represented in a tree: {mode:o}")]
+    InvalidMode { path: BString, mode: u32 },
+    #[error(transparent)]
+    Write(#[from] gix_object::write::Error),
+}
+
+impl Tree {
+    /// Bring this root tree up-to-date with the entries of `state` by recomputing all invalidated trees,
+    /// and return the id of the root tree.
+    ///
+    /// Valid trees are reused, whereas all others are written with `objects` along with the trees leading to them.
+    /// Trees which aren't needed anymore are removed, and trees that are new are added.
+    ///
+    /// Like `git`, entries marked for removal are skipped, and entries that are only intended to be added aren't part
+    /// of the written trees while the trees leading to them remain invalid.
+    /// Conflicting entries make it impossible to write trees and cause an error.
+    pub fn update_from_entries(
+        &mut self,
+        state: &State,
+        objects: impl gix_object::Write,
+    ) -> Result<gix_hash::ObjectId, Error> {
+        let _span = gix_features::trace::coarse!("gix_index::extension::Tree::update_from_entries()");
+        let empty_tree = gix_hash::ObjectId::empty_tree(state.object_hash());
+        update_recursive(self, state.entries(), 0, state.path_backing(), empty_tree, &objects)?;
+        Ok(self.id)
+    }
+}
+
+/// Update `tree` with `entries` and return the amount of entries that were skipped as they are marked for removal,
+/// including those in subdirectories, so parent trees can exclude them from their entry count as well.
+fn update_recursive(
+    tree: &mut Tree,
+    entries: &[Entry],
+    base_len: usize,
+    backing: &crate::PathStorageRef,
+    empty_tree: gix_hash::ObjectId,
+    objects: &dyn gix_object::Write,
+) -> Result<usize, Error> {
+    if tree
+        .num_entries
+        .map_or(false, |num_entries| num_entries as usize == entries.len())
+    {
+        return Ok(0);
+    }
+
+    let mut previous_children = std::mem::take(&mut tree.children);
+    let mut object = gix_object::Tree::empty();
+    let mut num_skipped = 0;
+    let mut is_valid = true;
+    let mut idx = 0;
+    while let Some(entry) = entries.get(idx) {
+        let path = entry.path_in(backing);
+        if entry.stage() != entry::Stage::Unconflicted {
+            return Err(Error::Conflict { path: path.to_owned() });
+        }
+        let rest = path[base_len..].as_bytes();
+        let (filename, mode, id) = match rest.find_byte(b'/') {
+            Some(slash) if !entry.mode.is_sparse() => {
+                let dir_prefix = &path.as_bytes()[..base_len + slash + 1];
+                let num_dir_entries = entries[idx..]
+                    .iter()
+                    .take_while(|entry| entry.path_in(backing).starts_with(dir_prefix))
+                    .count();
+                let name = &rest[..slash];
+                let mut child = match previous_children.binary_search_by(|child| child.name.as_slice().cmp(name)) {
+                    Ok(pos) => previous_children.remove(pos),
+                    Err(_) => Tree {
+                        name: name.into(),
+                        id: gix_hash::ObjectId::null(empty_tree.kind()),
+                        num_entries: None,
+                        children: Vec::new(),
+                    },
+                };
+                num_skipped += update_recursive(
+                    &mut child,
+                    &entries[idx..][..num_dir_entries],
+                    dir_prefix.len(),
+                    backing,
+                    empty_tree,
+                    objects,
+                )?;
+                idx += num_dir_entries;
+
+                let contains_intent_to_add = child.num_entries.is_none();
+                is_valid &= !contains_intent_to_add;
+                let (id, skip) = (child.id, contains_intent_to_add && child.id == empty_tree);
+                tree.children.push(child);
+                if skip {
+                    // The tree only consists of entries that are intended to be added.
+                    continue;
+                }
+                (name, gix_object::tree::EntryKind::Tree.into(), id)
+            }
+            _ => {
+                idx += 1;
+                if entry.flags.contains(entry::Flags::REMOVE) {
+                    num_skipped += 1;
+                    continue;
+                }
+                if entry.flags.contains(entry::Flags::INTENT_TO_ADD) {
+                    is_valid = false;
+                    continue;
+                }
+                let mode = entry.mode.to_tree_entry_mode().ok_or_else(|| Error::InvalidMode {
+                    path: path.to_owned(),
+                    mode: entry.mode.bits(),
+                })?;
+                // Sparse directory entries are stored with a trailing slash.
+                (rest.strip_suffix(b"/").unwrap_or(rest), mode, entry.id)
+            }
+        };
+        object.entries.push(gix_object::tree::Entry {
+            mode,
+            filename: filename.into(),
+            oid: id,
+        });
+    }
+
+    tree.id = objects.write(&object)?;
+    tree.num_entries = is_valid.then(|| (entries.len() - num_skipped) as u32);
+    tree.children.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(num_skipped)
+}
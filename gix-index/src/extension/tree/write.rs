@@ -1,4 +1,4 @@
-use crate::extension::{tree, Tree};
+use crate::extension::{self, tree, Tree};
 
 impl Tree {
     /// Serialize this instance to `out`.
@@ -35,7 +35,7 @@ impl Tree {
         tree_entry(&mut entries, self)?;
 
         out.write_all(&signature)?;
-        out.write_all(&(u32::try_from(entries.len()).expect("less than 4GB tree extension")).to_be_bytes())?;
+        out.write_all(&extension::size_u32(signature, entries.len())?.to_be_bytes())?;
         out.write_all(&entries)?;
 
         Ok(())
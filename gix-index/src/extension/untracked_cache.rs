@@ -1,14 +1,15 @@
-use bstr::BString;
+use bstr::{BStr, BString, ByteSlice};
 use gix_hash::ObjectId;
 
 use crate::{
     entry,
-    extension::{Signature, UntrackedCache},
-    util::{read_u32, split_at_byte_exclusive, split_at_pos, var_int},
+    extension::{self, Signature, UntrackedCache},
+    util::{read_u32, split_at_byte_exclusive, split_at_pos, var_int, var_int_encode},
 };
 
 /// A structure to track filesystem stat information along with an object id, linking a worktree file with what's in our ODB.
-#[derive(Clone)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct OidStat {
     /// The file system stat information
     pub stat: entry::Stat,
@@ -16,28 +17,272 @@ pub struct OidStat {
     pub id: ObjectId,
 }
 
+impl OidStat {
+    /// Return `true` if there is any information, as the file could exist without having been hashed yet.
+    fn is_set(&self) -> bool {
+        !self.id.is_null() || self.stat != entry::Stat::default()
+    }
+}
+
 /// A directory with information about its untracked files, and its sub-directories
-#[derive(Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Directory {
     /// The directories name, or an empty string if this is the root directory.
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_impls::path"))]
     pub name: BString,
     /// Untracked files and directory names
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_impls::paths"))]
     pub untracked_entries: Vec<BString>,
     /// indices for sub-directories similar to this one.
     pub sub_directories: Vec<usize>,
 
-    /// The directories stat data, if available or valid // TODO: or is it the exclude file?
+    /// The directories stat data, if it is valid and can be used to determine if the directory changed.
     pub stat: Option<entry::Stat>,
     /// The oid of a .gitignore file, if it exists
     pub exclude_file_oid: Option<ObjectId>,
-    /// TODO: figure out what this really does
+    /// If `true`, only the existence of untracked files was checked, so `untracked_entries` may be incomplete.
     pub check_only: bool,
 }
 
 /// Only used as an indicator
 pub const SIGNATURE: Signature = *b"UNTR";
 
-// #[allow(unused)]
+/// Initialization
+impl UntrackedCache {
+    /// Create a new cache without any directories for the worktree identified by `identifier`, as produced by
+    /// [`identifier()`], which will be filled in by the next `git status`.
+    ///
+    /// It's configured like `git` does it for `status.showUntrackedFiles=normal`.
+    pub fn new(identifier: BString) -> Self {
+        // DIR_SHOW_OTHER_DIRECTORIES | DIR_HIDE_EMPTY_DIRECTORIES
+        const DIR_FLAGS: u32 = (1 << 1) | (1 << 2);
+        UntrackedCache {
+            identifier,
+            info_exclude: None,
+            excludes_file: None,
+            exclude_filename_per_dir: ".gitignore".into(),
+            dir_flags: DIR_FLAGS,
+            directories: Vec::new(),
+        }
+    }
+}
+
+/// Return the identifier `git` uses for the untracked cache of the worktree at the absolute `work_tree` path on this system.
+///
+/// An untracked cache with a different identifier was created for another location or system, and must not be used.
+pub fn identifier(work_tree: &BStr) -> BString {
+    let system = match std::env::consts::OS {
+        "linux" | "android" => "Linux",
+        "macos" | "ios" => "Darwin",
+        "windows" => "Windows",
+        "freebsd" => "FreeBSD",
+        "netbsd" => "NetBSD",
+        "openbsd" => "OpenBSD",
+        other => other,
+    };
+    let mut out = BString::from("Location ");
+    out.extend_from_slice(work_tree);
+    out.extend_from_slice(b", system ");
+    out.extend_from_slice(system.as_bytes());
+    // Git stores the trailing null byte as well, as the identifier used to be a list of null-separated strings.
+    out.push(0);
+    out
+}
+
+/// Access
+impl UntrackedCache {
+    /// Return the identifier of the location and machine this cache was written for.
+    ///
+    /// If it doesn't match the current one, the whole cache should be considered invalid.
+    pub fn identifier(&self) -> &BStr {
+        self.identifier.as_bstr()
+    }
+    /// Return the stat information and object id of the `.git/info/exclude` file, if it was present.
+    ///
+    /// Note that the id may be null if the file was seen, but its content wasn't hashed.
+    pub fn info_exclude(&self) -> Option<&OidStat> {
+        self.info_exclude.as_ref()
+    }
+    /// Return the stat information and object id of the file configured in `core.excludesFile`, if it was present.
+    pub fn excludes_file(&self) -> Option<&OidStat> {
+        self.excludes_file.as_ref()
+    }
+    /// Return the name of the per-directory exclude file, usually `.gitignore`.
+    pub fn exclude_filename_per_dir(&self) -> &BStr {
+        self.exclude_filename_per_dir.as_bstr()
+    }
+    /// Return the flags of the directory walk that produced the cache, which have to match for the cache to be usable.
+    pub fn dir_flags(&self) -> u32 {
+        self.dir_flags
+    }
+    /// Return all directories, with the root directory at index 0, if there is any.
+    ///
+    /// [`Directory::sub_directories`] are indices into this list.
+    pub fn directories(&self) -> &[Directory] {
+        &self.directories
+    }
+    /// Return all directories for modification, for instance to invalidate their stat information.
+    pub fn directories_mut(&mut self) -> &mut [Directory] {
+        &mut self.directories
+    }
+}
+
+/// Mutation
+impl UntrackedCache {
+    /// Invalidate the directory containing `path` like `git` does when an entry at `path` was added to or removed from the index,
+    /// so its untracked files are determined again.
+    ///
+    /// If untracked directories are listed as such, the directories leading to it are invalidated as well as whether they
+    /// contain untracked files may have changed.
+    pub fn invalidate_path(&mut self, path: &BStr) {
+        // DIR_SHOW_OTHER_DIRECTORIES
+        const SHOW_OTHER_DIRECTORIES: u32 = 1 << 1;
+        if self.directories.is_empty() {
+            return;
+        }
+        let mut leading_dirs = vec![0];
+        let mut components = path.split_str("/").peekable();
+        let mut is_complete = true;
+        while let Some(component) = components.next() {
+            if components.peek().is_none() {
+                break;
+            }
+            let dir = &self.directories[*leading_dirs.last().expect("at least root")];
+            match dir
+                .sub_directories
+                .iter()
+                .find(|idx| self.directories.get(**idx).map_or(false, |sub| sub.name == component))
+            {
+                Some(idx) => leading_dirs.push(*idx),
+                None => {
+                    is_complete = false;
+                    break;
+                }
+            }
+        }
+
+        let num_to_invalidate = if self.dir_flags & SHOW_OTHER_DIRECTORIES != 0 {
+            leading_dirs.len()
+        } else {
+            usize::from(is_complete)
+        };
+        for idx in leading_dirs.into_iter().rev().take(num_to_invalidate) {
+            let dir = &mut self.directories[idx];
+            dir.stat = None;
+            dir.untracked_entries.clear();
+        }
+    }
+}
+
+/// Serialization
+impl UntrackedCache {
+    /// Serialize this instance to `out`, assuming object hashes are of type `object_hash`.
+    ///
+    /// Directories are written depth-first starting at the root, which is the same order as they are decoded in.
+    pub fn write_to(&self, mut out: impl std::io::Write, object_hash: gix_hash::Kind) -> Result<(), std::io::Error> {
+        let mut data = Vec::new();
+        let mut buf = [0u8; 10];
+        data.extend_from_slice(var_int_encode(self.identifier.len() as u64, &mut buf));
+        data.extend_from_slice(&self.identifier);
+        for oid_stat in [&self.info_exclude, &self.excludes_file] {
+            match oid_stat {
+                Some(OidStat { stat, id }) => {
+                    write_stat(&mut data, stat);
+                    data.extend_from_slice(id.as_bytes());
+                }
+                None => {
+                    write_stat(&mut data, &entry::Stat::default());
+                    data.extend_from_slice(object_hash.null().as_bytes());
+                }
+            }
+        }
+        data.extend_from_slice(&self.dir_flags.to_be_bytes());
+        data.extend_from_slice(&self.exclude_filename_per_dir);
+        data.push(0);
+
+        if self.directories.is_empty() {
+            data.extend_from_slice(var_int_encode(0, &mut buf));
+        } else {
+            let mut order = Vec::with_capacity(self.directories.len());
+            let mut blocks = Vec::new();
+            write_directory_block(&self.directories, 0, &mut blocks, &mut order)?;
+            data.extend_from_slice(var_int_encode(order.len() as u64, &mut buf));
+            data.extend_from_slice(&blocks);
+
+            let dirs = || order.iter().map(|index| &self.directories[*index]);
+            let set_bits = |is_set: fn(&Directory) -> bool| {
+                gix_bitmap::ewah::Vec::from_set_bits(dirs().enumerate().filter_map(|(idx, d)| is_set(d).then_some(idx)))
+            };
+            set_bits(|d| d.stat.is_some()).write_to(&mut data)?;
+            set_bits(|d| d.check_only).write_to(&mut data)?;
+            set_bits(|d| d.exclude_file_oid.is_some()).write_to(&mut data)?;
+            for stat in dirs().filter_map(|d| d.stat.as_ref()) {
+                write_stat(&mut data, stat);
+            }
+            for id in dirs().filter_map(|d| d.exclude_file_oid.as_ref()) {
+                data.extend_from_slice(id.as_bytes());
+            }
+            data.push(0);
+        }
+
+        out.write_all(&SIGNATURE)?;
+        out.write_all(&extension::size_u32(SIGNATURE, data.len())?.to_be_bytes())?;
+        out.write_all(&data)
+    }
+}
+
+fn write_directory_block(
+    directories: &[Directory],
+    index: usize,
+    out: &mut Vec<u8>,
+    order: &mut Vec<usize>,
+) -> Result<(), std::io::Error> {
+    let dir = directories.get(index).ok_or_else(|| {
+        std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            format!("Untracked cache sub-directory index {index} is out of bounds"),
+        )
+    })?;
+    if order.len() > directories.len() {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            "Untracked cache directories must form a tree",
+        ));
+    }
+    order.push(index);
+
+    let mut buf = [0u8; 10];
+    out.extend_from_slice(var_int_encode(dir.untracked_entries.len() as u64, &mut buf));
+    out.extend_from_slice(var_int_encode(dir.sub_directories.len() as u64, &mut buf));
+    out.extend_from_slice(&dir.name);
+    out.push(0);
+    for name in &dir.untracked_entries {
+        out.extend_from_slice(name);
+        out.push(0);
+    }
+    for sub_directory in &dir.sub_directories {
+        write_directory_block(directories, *sub_directory, out, order)?;
+    }
+    Ok(())
+}
+
+fn write_stat(out: &mut Vec<u8>, stat: &entry::Stat) {
+    for field in [
+        stat.ctime.secs,
+        stat.ctime.nsecs,
+        stat.mtime.secs,
+        stat.mtime.nsecs,
+        stat.dev,
+        stat.ino,
+        stat.uid,
+        stat.gid,
+        stat.size,
+    ] {
+        out.extend_from_slice(&field.to_be_bytes());
+    }
+}
+
 /// Decode an untracked cache extension from `data`, assuming object hashes are of type `object_hash`.
 pub fn decode(data: &[u8], object_hash: gix_hash::Kind) -> Option<UntrackedCache> {
     if !data.last().map_or(false, |b| *b == 0) {
@@ -56,8 +301,8 @@ pub fn decode(data: &[u8], object_hash: gix_hash::Kind) -> Option<UntrackedCache
 
     let mut res = UntrackedCache {
         identifier: identifier.into(),
-        info_exclude: (!info_exclude.id.is_null()).then_some(info_exclude),
-        excludes_file: (!excludes_file.id.is_null()).then_some(excludes_file),
+        info_exclude: info_exclude.is_set().then_some(info_exclude),
+        excludes_file: excludes_file.is_set().then_some(excludes_file),
         exclude_filename_per_dir: exclude_filename_per_dir.into(),
         dir_flags,
         directories: Vec::new(),
@@ -58,45 +58,15 @@ impl File {
     ) -> Result<Self, Error> {
         let _span = gix_features::trace::detail!("gix_index::File::at()");
         let path = path.into();
-        let (data, mtime) = {
-            let mut file = std::fs::File::open(&path)?;
-            // SAFETY: we have to take the risk of somebody changing the file underneath. Git never writes into the same file.
-            #[allow(unsafe_code)]
-            let data = unsafe { memmap2::MmapOptions::new().map_copy_read_only(&file)? };
-
-            if !skip_hash {
-                // Note that even though it's trivial to offload this into a thread, which is worth it for all but the smallest
-                // index files, we choose more safety here just like git does and don't even try to decode the index if the hashes
-                // don't match.
-                // Thanks to `skip_hash`, we can get performance and it's under caller control, at the cost of some safety.
-                let expected =
-                    gix_hash::ObjectId::from_bytes_or_panic(&data[data.len() - object_hash.len_in_bytes()..]);
-                if !expected.is_null() {
-                    let _span = gix_features::trace::detail!("gix::open_index::hash_index", path = ?path);
-                    let meta = file.metadata()?;
-                    let num_bytes_to_hash = meta.len() - object_hash.len_in_bytes() as u64;
-                    let actual_hash = gix_features::hash::bytes(
-                        &mut file,
-                        num_bytes_to_hash,
-                        object_hash,
-                        &mut gix_features::progress::Discard,
-                        &Default::default(),
-                    )?;
-
-                    if actual_hash != expected {
-                        return Err(Error::Decode(decode::Error::ChecksumMismatch {
-                            actual_checksum: actual_hash,
-                            expected_checksum: expected,
-                        }));
-                    }
-                }
-            }
-
-            (data, filetime::FileTime::from_last_modification_time(&file.metadata()?))
-        };
+        let (data, mtime) = map(&path, object_hash, skip_hash)?;
 
         let (state, checksum) = State::from_bytes(&data, mtime, object_hash, options)?;
-        let mut file = File { state, path, checksum };
+        let mut file = File {
+            state,
+            path,
+            checksum,
+            shared_index: None,
+        };
         if let Some(mut link) = file.link.take() {
             link.dissolve_into(&mut file, object_hash, skip_hash, options)?;
         }
@@ -112,6 +82,49 @@ impl File {
             state,
             path: path.into(),
             checksum: None,
+            shared_index: None,
         }
     }
 }
+
+/// Memory-map the index file at `path` and return it along with its modification time, verifying its checksum
+/// unless `skip_hash` is `true`.
+pub(crate) fn map(
+    path: &Path,
+    object_hash: gix_hash::Kind,
+    skip_hash: bool,
+) -> Result<(memmap2::Mmap, filetime::FileTime), Error> {
+    let mut file = std::fs::File::open(path)?;
+    // SAFETY: we have to take the risk of somebody changing the file underneath. Git never writes into the same file.
+    #[allow(unsafe_code)]
+    let data = unsafe { memmap2::MmapOptions::new().map_copy_read_only(&file)? };
+
+    if !skip_hash {
+        // Note that even though it's trivial to offload this into a thread, which is worth it for all but the smallest
+        // index files, we choose more safety here just like git does and don't even try to decode the index if the hashes
+        // don't match.
+        // Thanks to `skip_hash`, we can get performance and it's under caller control, at the cost of some safety.
+        let expected = gix_hash::ObjectId::from_bytes_or_panic(&data[data.len() - object_hash.len_in_bytes()..]);
+        if !expected.is_null() {
+            let _span = gix_features::trace::detail!("gix::open_index::hash_index", path = ?path);
+            let meta = file.metadata()?;
+            let num_bytes_to_hash = meta.len() - object_hash.len_in_bytes() as u64;
+            let actual_hash = gix_features::hash::bytes(
+                &mut file,
+                num_bytes_to_hash,
+                object_hash,
+                &mut gix_features::progress::Discard,
+                &Default::default(),
+            )?;
+
+            if actual_hash != expected {
+                return Err(Error::Decode(decode::Error::ChecksumMismatch {
+                    actual_checksum: actual_hash,
+                    expected_checksum: expected,
+                }));
+            }
+        }
+    }
+
+    Ok((data, filetime::FileTime::from_last_modification_time(&file.metadata()?)))
+}
@@ -1,6 +1,6 @@
 use gix_features::hash;
 
-use crate::{write, File, Version};
+use crate::{extension, extension::link::SharedIndex, write, File, Version};
 
 /// The error produced by [`File::write()`].
 #[derive(Debug, thiserror::Error)]
@@ -8,6 +8,8 @@ use crate::{write, File, Version};
 pub enum Error {
     #[error(transparent)]
     Io(#[from] std::io::Error),
+    #[error(transparent)]
+    Write(#[from] write::Error),
     #[error("Could not acquire lock for index file")]
     AcquireLock(#[from] gix_lock::acquire::Error),
     #[error("Could not commit lock for index file")]
@@ -21,7 +23,7 @@ impl File {
         &self,
         mut out: impl std::io::Write,
         options: write::Options,
-    ) -> std::io::Result<(Version, gix_hash::ObjectId)> {
+    ) -> Result<(Version, gix_hash::ObjectId), write::Error> {
         let _span = gix_features::trace::detail!("gix_index::File::write_to()", skip_hash = options.skip_hash);
         let (version, hash) = if options.skip_hash {
             let out: &mut dyn std::io::Write = &mut out;
@@ -39,6 +41,10 @@ impl File {
 
     /// Write ourselves to the path we were read from after acquiring a lock, using `options`.
     ///
+    /// The index is written to `index.lock` next to it, which fails if the lock is held by another process,
+    /// and is atomically renamed into place once it was fully written, and [flushed to disk](write::Options::fsync)
+    /// if configured. On error, the lock file is removed and the previous index remains untouched.
+    ///
     /// Note that the hash produced will be stored which is why we need to be mutable.
     pub fn write(&mut self, options: write::Options) -> Result<(), Error> {
         let _span = gix_features::trace::detail!("gix_index::File::write()", path = ?self.path);
@@ -48,11 +54,117 @@ impl File {
         );
         let (version, digest) = self.write_to(&mut lock, options)?;
         match lock.into_inner() {
-            Ok(lock) => lock.commit()?,
+            Ok(mut lock) => {
+                lock.with_mut(|file| options.fsync.sync(file))?;
+                lock.commit()?
+            }
             Err(err) => return Err(err.into_error().into()),
         };
         self.state.version = version;
         self.checksum = Some(digest);
         Ok(())
     }
+
+    /// Write ourselves as split index to the path we were read from, like `git` does if `core.splitIndex` is enabled,
+    /// using `options`.
+    ///
+    /// Only entries that differ from the ones in the shared index are stored in the index file itself, whereas the shared
+    /// index is stored as `sharedindex.<checksum>` right next to it.
+    /// A new shared index is written if there is none yet, or if more than `max_percent_change` percent of all entries
+    /// would have to be stored in the index file, with `0` always and `100` never writing a new one, just like
+    /// `splitIndex.maxPercentChange` which defaults to `20`.
+    ///
+    /// When a new shared index is written, all other `sharedindex.*` files next to the index that were last modified
+    /// before `expire_shared_indices_before` are removed, like `git` does according to `splitIndex.sharedIndexExpire`,
+    /// or none of them if it is `None`. A shared index that is reused is touched so it won't expire while it's in use.
+    pub fn write_split(
+        &mut self,
+        options: write::Options,
+        max_percent_change: u8,
+        expire_shared_indices_before: Option<std::time::SystemTime>,
+    ) -> Result<(), Error> {
+        let _span = gix_features::trace::detail!("gix_index::File::write_split()", path = ?self.path);
+        let dir = self.path.parent().expect("index file is in a directory").to_owned();
+        let shared_index_path = |checksum: &gix_hash::ObjectId| dir.join(format!("sharedindex.{checksum}"));
+        let split = self
+            .shared_index
+            .take()
+            .filter(|shared| shared_index_path(&shared.checksum).is_file())
+            .map(|shared| {
+                let split = shared.split(&self.state);
+                (shared, split)
+            })
+            .filter(|(_, (entries, _, _))| match max_percent_change {
+                0 => false,
+                100.. => true,
+                max => entries.len() * 100 <= self.state.entries.len() * usize::from(max),
+            });
+
+        let mut new_shared_index = None;
+        let (shared, (entries, path_backing, bitmaps)) = match split {
+            Some(split) => {
+                // Like `git`, failing to freshen the shared index isn't fatal, it may just expire sooner.
+                filetime::set_file_mtime(shared_index_path(&split.0.checksum), filetime::FileTime::now()).ok();
+                split
+            }
+            None => {
+                let mut shared = SharedIndex::from_state(&self.state, self.state.object_hash.null());
+                let mut buf = Vec::new();
+                let (_version, checksum) = File::from_state(shared.to_state(&self.state), self.path.clone()).write_to(
+                    &mut buf,
+                    write::Options {
+                        extensions: write::Extensions::None,
+                        ..Default::default()
+                    },
+                )?;
+                shared.checksum = checksum;
+
+                let mut lock = gix_lock::File::acquire_to_update_resource(
+                    shared_index_path(&checksum),
+                    gix_lock::acquire::Fail::Immediately,
+                    None,
+                )?;
+                std::io::Write::write_all(&mut lock, &buf)?;
+                lock.with_mut(|file| options.fsync.sync(file))?;
+                lock.commit()?;
+                new_shared_index = Some(checksum);
+
+                let no_bits = || gix_bitmap::ewah::Vec::from_set_bits(None);
+                let bitmaps = extension::link::Bitmaps {
+                    delete: no_bits(),
+                    replace: no_bits(),
+                };
+                (shared, (Vec::new(), Vec::new(), bitmaps))
+            }
+        };
+
+        let entries = std::mem::replace(&mut self.state.entries, entries);
+        let path_backing = std::mem::replace(&mut self.state.path_backing, path_backing);
+        self.state.link = Some(extension::Link {
+            shared_index_checksum: shared.checksum,
+            bitmaps: Some(bitmaps),
+        });
+        let res = self.write(options);
+        self.state.entries = entries;
+        self.state.path_backing = path_backing;
+        self.state.link = None;
+        self.shared_index = Some(shared);
+        res?;
+
+        if let Some((current, expire_before)) = new_shared_index.zip(expire_shared_indices_before) {
+            let current = format!("sharedindex.{current}");
+            for entry in std::fs::read_dir(&dir)? {
+                let entry = entry?;
+                let name = entry.file_name();
+                let Some(name) = name.to_str() else { continue };
+                if !name.starts_with("sharedindex.") || name == current {
+                    continue;
+                }
+                if entry.metadata()?.modified()? < expire_before {
+                    std::fs::remove_file(entry.path())?;
+                }
+            }
+        }
+        Ok(())
+    }
 }
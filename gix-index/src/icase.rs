@@ -0,0 +1,65 @@
+use bstr::BStr;
+
+use crate::{AccelerateLookup, Entry, State};
+
+/// The way an entry collides with another one on a case-insensitive filesystem.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CollisionKind {
+    /// The paths of both entries only differ in case, like `readme` and `README`, so both would be checked out to the same file.
+    File,
+    /// The path of the entry is equal to a leading directory of the path of the other entry when ignoring case,
+    /// like `a` and `A/b`, so they can't both be checked out.
+    Directory,
+}
+
+/// An entry whose path collides with the one of another entry on a case-insensitive filesystem,
+/// as returned by [`State::icase_collisions()`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Collision<'a> {
+    /// The colliding entry.
+    pub entry: &'a Entry,
+    /// The entry `entry` collides with.
+    pub other: &'a Entry,
+    /// The kind of collision.
+    pub kind: CollisionKind,
+}
+
+impl State {
+    /// Return all entries whose paths collide with the path of another entry when ignoring case, using `lookup`
+    /// for acceleration. It must have been created from this instance with [`prepare_icase_backing()`](Self::prepare_icase_backing()).
+    ///
+    /// Each colliding entry is returned once in the order of entries, along with one of the entries it collides with,
+    /// so entries that only differ in case are all returned.
+    /// Conflicting stages of the same path are treated as a single entry.
+    ///
+    /// This is useful to detect which entries can't be checked out on a case-insensitive filesystem, similar to
+    /// what `git clone` reports as paths that have collided.
+    pub fn icase_collisions<'a>(&'a self, lookup: &AccelerateLookup<'a>) -> Vec<Collision<'a>> {
+        let _span = gix_features::trace::detail!("icase_collisions", entries = self.entries.len());
+        let mut out = Vec::new();
+        let mut previous_path = None::<&BStr>;
+        for entry in &self.entries {
+            let path = entry.path(self);
+            if previous_path == Some(path) {
+                continue;
+            }
+            previous_path = Some(path);
+
+            let same_file = lookup
+                .icase_entries
+                .find(AccelerateLookup::icase_hash(path), |other| {
+                    let other_path = other.path(self);
+                    other_path != path && other_path.eq_ignore_ascii_case(path)
+                })
+                .map(|other| (*other, CollisionKind::File));
+            let collision = same_file.or_else(|| {
+                self.entry_closest_to_directory_icase(path, true, lookup)
+                    .map(|other| (other, CollisionKind::Directory))
+            });
+            if let Some((other, kind)) = collision {
+                out.push(Collision { entry, other, kind });
+            }
+        }
+        out
+    }
+}
@@ -8,7 +8,7 @@ pub mod from_tree {
 
     use crate::{
         entry::{Flags, Mode, Stat},
-        Entry, PathStorage, State, Version,
+        extension, Entry, PathStorage, State, Version,
     };
 
     /// The error returned by [State::from_tree()].
@@ -40,15 +40,18 @@ pub mod from_tree {
                 resolve_undo: None,
                 untracked: None,
                 fs_monitor: None,
+                metadata: None,
+                unknown_extensions: Vec::new(),
                 offset_table_at_decode_time: false,
                 end_of_index_at_decode_time: false,
             }
         }
         /// Create an index [`State`] by traversing `tree` recursively, accessing sub-trees
-        /// with `objects`.
+        /// with `objects`, similar to what `git read-tree` does.
         /// `validate` is used to determine which validations to perform on every path component we see.
         ///
-        /// **No extension data is currently produced**.
+        /// The [tree cache extension](crate::extension::Tree) is populated with all trees we see,
+        /// but no other extension data is produced.
         pub fn from_tree<Find>(
             tree: &gix_hash::oid,
             objects: Find,
@@ -78,6 +81,7 @@ pub mod from_tree {
             let CollectEntries {
                 mut entries,
                 path_backing,
+                trees,
                 path: _,
                 path_deque: _,
                 validate: _,
@@ -85,6 +89,7 @@ pub mod from_tree {
             } = delegate;
 
             entries.sort_by(|a, b| Entry::cmp_filepaths(a.path_in(&path_backing), b.path_in(&path_backing)));
+            let tree_cache = tree_cache(tree.to_owned(), trees, &entries, &path_backing);
 
             Ok(State {
                 object_hash: tree.kind(),
@@ -93,20 +98,75 @@ pub mod from_tree {
                 entries,
                 path_backing,
                 is_sparse: false,
-                tree: None,
+                tree: Some(tree_cache),
                 link: None,
                 resolve_undo: None,
                 untracked: None,
                 fs_monitor: None,
+                metadata: None,
+                unknown_extensions: Vec::new(),
                 offset_table_at_decode_time: false,
                 end_of_index_at_decode_time: false,
             })
         }
     }
 
+    /// Build the tree cache for the tree with `root_id`, given the path and id of all `trees` below it in breadth-first order,
+    /// along with all `entries` in it with their paths in `path_backing`.
+    fn tree_cache(
+        root_id: gix_hash::ObjectId,
+        mut trees: Vec<(BString, gix_hash::ObjectId)>,
+        entries: &[Entry],
+        path_backing: &PathStorage,
+    ) -> extension::Tree {
+        fn new_tree(name: &[u8], id: gix_hash::ObjectId) -> extension::Tree {
+            extension::Tree {
+                name: name.into(),
+                id,
+                num_entries: Some(0),
+                children: Vec::new(),
+            }
+        }
+        fn child_mut<'a>(tree: &'a mut extension::Tree, name: &[u8]) -> &'a mut extension::Tree {
+            let idx = tree
+                .children
+                .binary_search_by(|child| child.name.as_slice().cmp(name))
+                .expect("all directories of all entries are trees");
+            &mut tree.children[idx]
+        }
+
+        // Sorting by path makes each parent appear before its children, and siblings appear in the order of their names.
+        trees.sort_by(|a, b| a.0.cmp(&b.0));
+        let mut root = new_tree(&[], root_id);
+        for (path, id) in trees {
+            let mut components = path.split_str("/").collect::<Vec<_>>();
+            let name = components.pop().expect("at least one component");
+            let parent = components
+                .into_iter()
+                .fold(&mut root, |tree, component| child_mut(tree, component));
+            parent.children.push(new_tree(name, id));
+        }
+
+        for entry in entries {
+            let mut tree = &mut root;
+            *tree.num_entries.as_mut().expect("set") += 1;
+            let path = entry.path_in(path_backing);
+            let Some(dir) = path.rfind_byte(b'/').map(|pos| &path[..pos]) else {
+                continue;
+            };
+            for component in dir.split_str("/") {
+                tree = child_mut(tree, component);
+                *tree.num_entries.as_mut().expect("set") += 1;
+            }
+        }
+        root
+    }
+
     struct CollectEntries {
         entries: Vec<Entry>,
         path_backing: PathStorage,
+        /// The path and id of all trees we have seen.
+        trees: Vec<(BString, gix_hash::ObjectId)>,
         path: BString,
         path_deque: VecDeque<BString>,
         validate: gix_validate::path::component::Options,
@@ -118,6 +178,7 @@ pub mod from_tree {
             CollectEntries {
                 entries: Vec::new(),
                 path_backing: Vec::new(),
+                trees: Vec::new(),
                 path: BString::default(),
                 path_deque: VecDeque::new(),
                 validate,
@@ -206,7 +267,8 @@ pub mod from_tree {
             }
         }
 
-        fn visit_tree(&mut self, _entry: &gix_object::tree::EntryRef<'_>) -> Action {
+        fn visit_tree(&mut self, entry: &gix_object::tree::EntryRef<'_>) -> Action {
+            self.trees.push((self.path.clone(), entry.oid.to_owned()));
             self.determine_action()
         }
 
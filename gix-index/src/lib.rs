@@ -26,12 +26,30 @@ pub mod entry;
 
 mod access;
 
+///
+pub mod icase;
+
+///
+pub mod conflict;
+
+///
+pub mod diff;
+
 ///
 pub mod init;
 
+///
+pub mod expand;
+
 ///
 pub mod decode;
 
+///
+pub mod mapped;
+
+///
+pub mod object_hash;
+
 ///
 pub mod verify;
 
@@ -77,6 +95,8 @@ pub struct File {
     pub(crate) path: PathBuf,
     /// The checksum of all bytes prior to the checksum itself.
     pub(crate) checksum: Option<gix_hash::ObjectId>,
+    /// The shared index this index was split from, if it was read as split index or written as one.
+    pub(crate) shared_index: Option<extension::link::SharedIndex>,
 }
 
 /// The type to use and store paths to all entries.
@@ -126,6 +146,9 @@ pub struct AccelerateLookup<'a> {
 ///
 /// It's recommended to do that using `gix_worktree::Stack` which has it built-in if it's created `for_checkout()`. Alternatively
 /// one can validate component names with `gix_validate::path::component()`.
+///
+/// With the `serde` feature, paths are serialized along with each entry, and deserialized states are [verified](State::verify())
+/// with the default options.
 #[derive(Clone)]
 pub struct State {
     /// The kind of object hash used when storing the underlying file.
@@ -154,8 +177,14 @@ pub struct State {
     resolve_undo: Option<extension::resolve_undo::Paths>,
     untracked: Option<extension::UntrackedCache>,
     fs_monitor: Option<extension::FsMonitor>,
+    metadata: Option<extension::Metadata>,
+    /// Optional extensions we don't understand, in the order in which they were read.
+    unknown_extensions: Vec<extension::Unknown>,
 }
 
+#[cfg(feature = "serde")]
+mod serde_impls;
+
 mod impls {
     use crate::entry::Stage;
     use std::fmt::{Debug, Formatter};
@@ -197,6 +226,21 @@ pub(crate) mod util {
         (num, data).into()
     }
 
+    /// Encode `n` into `buf` in the format understood by [`var_int()`] and return the encoded bytes.
+    #[inline]
+    pub fn var_int_encode(mut n: u64, buf: &mut [u8; 10]) -> &[u8] {
+        let mut pos = buf.len() - 1;
+        buf[pos] = (n & 0x7f) as u8;
+        n >>= 7;
+        while n != 0 {
+            n -= 1;
+            pos -= 1;
+            buf[pos] = 0x80 | (n & 0x7f) as u8;
+            n >>= 7;
+        }
+        &buf[pos..]
+    }
+
     #[inline]
     pub fn read_u32(data: &[u8]) -> Option<(u32, &[u8])> {
         split_at_pos(data, 4).map(|(num, data)| (u32::from_be_bytes(num.try_into().unwrap()), data))
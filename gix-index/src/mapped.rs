@@ -0,0 +1,302 @@
+use std::path::{Path, PathBuf};
+
+use bstr::BStr;
+use filetime::FileTime;
+
+use crate::{decode, decode::entries::load_one_ref, entry, extension, Entry, State, Version};
+
+/// An index entry whose path and id are borrowed from where they are stored, usually a memory-mapped index file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EntryRef<'a> {
+    /// The filesystem stat information for the file on disk.
+    pub stat: entry::Stat,
+    /// The object id for this entry's ODB representation (assuming it's up-to-date with it).
+    pub id: &'a gix_hash::oid,
+    /// Additional flags for use in algorithms and for efficiently storing stage information.
+    pub flags: entry::Flags,
+    /// The kind of item this entry represents.
+    pub mode: entry::Mode,
+    /// The path of the entry, relative to the repository.
+    pub path: &'a BStr,
+}
+
+impl<'a> EntryRef<'a> {
+    /// Create an instance from `entry` that is owned by `state`.
+    pub fn from_entry(entry: &'a Entry, state: &'a State) -> Self {
+        EntryRef {
+            stat: entry.stat,
+            id: &entry.id,
+            flags: entry.flags,
+            mode: entry.mode,
+            path: entry.path(state),
+        }
+    }
+
+    /// Return the stage of this entry.
+    pub fn stage(&self) -> entry::Stage {
+        self.flags.stage()
+    }
+
+    /// Return the stage of this entry as raw number between 0 and 4.
+    pub fn stage_raw(&self) -> u32 {
+        self.flags.stage_raw()
+    }
+}
+
+mod error {
+    /// The error returned by [`File::at()`](super::File::at()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        Open(#[from] crate::file::init::Error),
+        #[error(transparent)]
+        Decode(#[from] crate::decode::Error),
+        #[error(
+            "Index files of version 4 store their paths prefix-compressed and can't be used without decoding them"
+        )]
+        DeltaPaths,
+        #[error("Split indices need to be combined with their shared index and can't be used without decoding them")]
+        SplitIndex,
+    }
+}
+pub use error::Error;
+
+/// A read-only index file whose entries are accessed right where they are stored in a memory map, without
+/// copying their paths.
+///
+/// This makes opening even large indices cheap as only the offsets to each entry are recorded, which is ideal for
+/// read-only operations. Use [`decode()`](File::decode()) to obtain a fully decoded and mutable [`crate::File`], or
+/// [`Index`] to do so only once mutation is needed.
+///
+/// Note that extensions are ignored, and that version 4 indices as well as split indices aren't supported.
+pub struct File {
+    data: memmap2::Mmap,
+    path: PathBuf,
+    object_hash: gix_hash::Kind,
+    version: Version,
+    timestamp: FileTime,
+    /// The byte offsets to the beginning of each entry within `data`.
+    offsets: Vec<usize>,
+    checksum: Option<gix_hash::ObjectId>,
+    /// The options to use when fully decoding the file.
+    options: decode::Options,
+}
+
+/// Initialization
+impl File {
+    /// Open the index file at `path`, assuming `object_hash` is used throughout the file, and keep the `options` for use
+    /// when [decoding](File::decode()) it later.
+    /// If `skip_hash` is `true`, the checksum of the file won't be verified.
+    ///
+    /// All entries are validated, but their paths aren't copied.
+    pub fn at(
+        path: impl Into<PathBuf>,
+        object_hash: gix_hash::Kind,
+        skip_hash: bool,
+        options: decode::Options,
+    ) -> Result<Self, Error> {
+        let _span = gix_features::trace::detail!("gix_index::mapped::File::at()");
+        let path = path.into();
+        let (data, timestamp) = crate::file::init::map(&path, object_hash, skip_hash)?;
+        let (version, num_entries, mut entries) =
+            decode::header::decode(&data, object_hash).map_err(decode::Error::from)?;
+        if version == Version::V4 {
+            return Err(Error::DeltaPaths);
+        }
+
+        let hash_len = object_hash.len_in_bytes();
+        let mut offsets = Vec::with_capacity(num_entries as usize);
+        for index in 0..num_entries {
+            offsets.push(data.len() - entries.len());
+            let (_entry, remaining) = load_one_ref(entries, hash_len).ok_or(decode::Error::Entry { index })?;
+            entries = remaining;
+        }
+        if extension::Iter::new_without_checksum(entries, object_hash).map_or(false, |mut iter| {
+            iter.any(|(signature, _)| signature == extension::link::SIGNATURE)
+        }) {
+            return Err(Error::SplitIndex);
+        }
+
+        let checksum = gix_hash::ObjectId::from_bytes_or_panic(&data[data.len() - hash_len..]);
+        Ok(File {
+            data,
+            path,
+            object_hash,
+            version,
+            timestamp,
+            offsets,
+            checksum: (!checksum.is_null()).then_some(checksum),
+            options,
+        })
+    }
+
+    /// Decode the whole file into a [`crate::File`], copying all entries and decoding all extensions.
+    pub fn decode(&self) -> Result<crate::File, Error> {
+        let (state, checksum) = State::from_bytes(&self.data, self.timestamp, self.object_hash, self.options)?;
+        Ok(crate::File {
+            state,
+            path: self.path.clone(),
+            checksum,
+            shared_index: None,
+        })
+    }
+}
+
+/// Access
+impl File {
+    /// The path from which the index was read.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// The version of the index file.
+    pub fn version(&self) -> Version {
+        self.version
+    }
+
+    /// The kind of hash used in the index file.
+    pub fn object_hash(&self) -> gix_hash::Kind {
+        self.object_hash
+    }
+
+    /// The time at which the file was last modified.
+    pub fn timestamp(&self) -> FileTime {
+        self.timestamp
+    }
+
+    /// The checksum of all bytes prior to the checksum itself, or `None` if it wasn't written.
+    pub fn checksum(&self) -> Option<gix_hash::ObjectId> {
+        self.checksum
+    }
+
+    /// The amount of entries in the index.
+    pub fn len(&self) -> usize {
+        self.offsets.len()
+    }
+
+    /// Return `true` if there is no entry in the index.
+    pub fn is_empty(&self) -> bool {
+        self.offsets.is_empty()
+    }
+
+    /// Return the entry at `idx`, or `None` if it is out of bounds.
+    pub fn entry(&self, idx: usize) -> Option<EntryRef<'_>> {
+        self.offsets.get(idx).map(|offset| self.entry_at(*offset))
+    }
+
+    /// Return an iterator over all entries, in order.
+    pub fn entries(&self) -> impl ExactSizeIterator<Item = EntryRef<'_>> + '_ {
+        self.offsets.iter().map(|offset| self.entry_at(*offset))
+    }
+
+    /// Return the entry at `path` that is either at stage 0, or at stage 2 (ours) in case of a merge conflict.
+    pub fn entry_by_path(&self, path: &BStr) -> Option<EntryRef<'_>> {
+        let idx = self
+            .offsets
+            .binary_search_by(|offset| self.entry_at(*offset).path.cmp(path))
+            .ok()?;
+        // A path has at most 3 stages, so one of them is within reach in either direction.
+        (idx.saturating_sub(2)..(idx + 3).min(self.len()))
+            .filter_map(|idx| self.entry(idx))
+            .find(|entry| entry.path == path && matches!(entry.stage_raw(), 0 | 2))
+    }
+
+    fn entry_at(&self, offset: usize) -> EntryRef<'_> {
+        load_one_ref(&self.data[offset..], self.object_hash.len_in_bytes())
+            .expect("entries were validated when opening")
+            .0
+    }
+}
+
+/// An index which is [memory-mapped](File) until it is mutated for the first time, at which point it will be
+/// [decoded](crate::File) in full.
+///
+/// This is useful if an index is typically only read, but occasionally has to be updated as well.
+pub enum Index {
+    /// The index file is accessed right from its memory map.
+    Mapped(File),
+    /// The index was decoded, either to mutate it or because it can't be memory-mapped.
+    Decoded(Box<crate::File>),
+}
+
+/// Initialization
+impl Index {
+    /// Open the index file at `path` like [`File::at()`], but fall back to decoding it fully if it can't be memory-mapped
+    /// because it's a version 4 index or a split index.
+    pub fn at(
+        path: impl Into<PathBuf>,
+        object_hash: gix_hash::Kind,
+        skip_hash: bool,
+        options: decode::Options,
+    ) -> Result<Self, crate::file::init::Error> {
+        let path = path.into();
+        Ok(match File::at(&path, object_hash, skip_hash, options) {
+            Ok(file) => Index::Mapped(file),
+            Err(Error::DeltaPaths | Error::SplitIndex) => {
+                Index::Decoded(Box::new(crate::File::at(path, object_hash, skip_hash, options)?))
+            }
+            Err(Error::Open(err)) => return Err(err),
+            Err(Error::Decode(err)) => return Err(err.into()),
+        })
+    }
+}
+
+/// Access
+impl Index {
+    /// The amount of entries in the index.
+    pub fn len(&self) -> usize {
+        match self {
+            Index::Mapped(file) => file.len(),
+            Index::Decoded(file) => file.entries().len(),
+        }
+    }
+
+    /// Return `true` if there is no entry in the index.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Return an iterator over all entries, in order.
+    pub fn entries(&self) -> Box<dyn ExactSizeIterator<Item = EntryRef<'_>> + '_> {
+        match self {
+            Index::Mapped(file) => Box::new(file.entries()),
+            Index::Decoded(file) => Box::new(file.entries().iter().map(|entry| EntryRef::from_entry(entry, file))),
+        }
+    }
+
+    /// Return the entry at `path` that is either at stage 0, or at stage 2 (ours) in case of a merge conflict.
+    pub fn entry_by_path(&self, path: &BStr) -> Option<EntryRef<'_>> {
+        match self {
+            Index::Mapped(file) => file.entry_by_path(path),
+            Index::Decoded(file) => file.entry_by_path(path).map(|entry| EntryRef::from_entry(entry, file)),
+        }
+    }
+
+    /// Return `true` if the index wasn't decoded yet.
+    pub fn is_mapped(&self) -> bool {
+        matches!(self, Index::Mapped(_))
+    }
+}
+
+/// Mutation
+impl Index {
+    /// Return the decoded index for mutation, decoding it first if it is still memory-mapped.
+    pub fn to_mut(&mut self) -> Result<&mut crate::File, Error> {
+        if let Index::Mapped(file) = self {
+            *self = Index::Decoded(Box::new(file.decode()?));
+        }
+        match self {
+            Index::Decoded(file) => Ok(&mut **file),
+            Index::Mapped(_) => unreachable!("decoded above"),
+        }
+    }
+
+    /// Turn this instance into a decoded index, decoding it if needed.
+    pub fn into_file(self) -> Result<crate::File, Error> {
+        match self {
+            Index::Mapped(file) => file.decode(),
+            Index::Decoded(file) => Ok(*file),
+        }
+    }
+}
@@ -0,0 +1,114 @@
+use gix_hash::{oid, ObjectId};
+
+use crate::{extension, State};
+
+/// The error returned by [`State::convert_object_hash()`].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("Could not map object id {id} to its {kind} counterpart")]
+    MapId {
+        id: ObjectId,
+        kind: gix_hash::Kind,
+        source: Box<dyn std::error::Error + Send + Sync + 'static>,
+    },
+    #[error("Object id {id} was mapped to {mapped}, which isn't a {kind} id")]
+    KindMismatch {
+        id: ObjectId,
+        mapped: ObjectId,
+        kind: gix_hash::Kind,
+    },
+}
+
+impl State {
+    /// Rewrite all object ids in this instance to be of `kind`, using `map` to obtain the id of the same object in `kind`,
+    /// similar to what's needed when migrating a repository from SHA-1 to SHA-256 or back.
+    ///
+    /// The ids of entries, the tree-cache and the resolve-undo extension are mapped, while null ids, like the ones of invalidated
+    /// trees, are turned into null ids of `kind` without calling `map`.
+    /// Extensions whose ids can't be mapped as they don't refer to objects, i.e. the untracked-cache, the link to a shared index and
+    /// all unknown extensions, are removed. Without link, the index is written as a whole the next time.
+    ///
+    /// Once done, the index is written with `kind` as object hash. On error, this instance remains unchanged.
+    pub fn convert_object_hash<E>(
+        &mut self,
+        kind: gix_hash::Kind,
+        mut map: impl FnMut(&oid) -> Result<ObjectId, E>,
+    ) -> Result<(), Error>
+    where
+        E: std::error::Error + Send + Sync + 'static,
+    {
+        let mut map = |id: &oid| -> Result<ObjectId, Error> {
+            if id.is_null() {
+                return Ok(kind.null());
+            }
+            let mapped = map(id).map_err(|err| Error::MapId {
+                id: id.to_owned(),
+                kind,
+                source: Box::new(err),
+            })?;
+            if mapped.kind() != kind {
+                return Err(Error::KindMismatch {
+                    id: id.to_owned(),
+                    mapped,
+                    kind,
+                });
+            }
+            Ok(mapped)
+        };
+
+        let entry_ids = self
+            .entries
+            .iter()
+            .map(|entry| map(&entry.id))
+            .collect::<Result<Vec<_>, _>>()?;
+        let tree = self
+            .tree
+            .as_ref()
+            .map(|tree| convert_tree(tree, &mut map))
+            .transpose()?;
+        let resolve_undo = self
+            .resolve_undo
+            .as_ref()
+            .map(|paths| {
+                paths
+                    .iter()
+                    .map(|path| {
+                        let mut path = path.clone();
+                        for stage in path.stages.iter_mut().flatten() {
+                            stage.id = map(&stage.id)?;
+                        }
+                        Ok(path)
+                    })
+                    .collect::<Result<extension::resolve_undo::Paths, Error>>()
+            })
+            .transpose()?;
+
+        for (entry, id) in self.entries.iter_mut().zip(entry_ids) {
+            entry.id = id;
+        }
+        self.tree = tree;
+        self.resolve_undo = resolve_undo;
+        self.untracked = None;
+        self.link = None;
+        self.unknown_extensions.clear();
+        self.object_hash = kind;
+        Ok(())
+    }
+}
+
+fn convert_tree(
+    tree: &extension::Tree,
+    map: &mut dyn FnMut(&oid) -> Result<ObjectId, Error>,
+) -> Result<extension::Tree, Error> {
+    Ok(extension::Tree {
+        name: tree.name.clone(),
+        id: map(&tree.id)?,
+        num_entries: tree.num_entries,
+        children: tree
+            .children
+            .iter()
+            .map(|child| convert_tree(child, map))
+            .collect::<Result<_, _>>()?,
+    })
+}
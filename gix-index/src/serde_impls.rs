@@ -0,0 +1,231 @@
+//! `serde` support for [`State`], which stores the paths of its entries in a shared path-backing.
+//!
+//! Paths are serialized as strings if they are valid UTF-8, and as bytes otherwise. Deserialized states are
+//! [verified](State::verify()) so they can't produce a corrupt index when written.
+use bstr::{BStr, BString};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::{entry, extension, State, Version};
+
+/// The serialized form of [`State`], with the paths of all entries inlined.
+#[derive(Serialize)]
+#[serde(rename = "State")]
+struct StateRef<'a> {
+    object_hash: gix_hash::Kind,
+    timestamp: (i64, u32),
+    version: Version,
+    entries: Vec<EntryRef<'a>>,
+    is_sparse: bool,
+    end_of_index_at_decode_time: bool,
+    offset_table_at_decode_time: bool,
+    tree: &'a Option<extension::Tree>,
+    link: &'a Option<extension::Link>,
+    resolve_undo: &'a Option<extension::resolve_undo::Paths>,
+    untracked: &'a Option<extension::UntrackedCache>,
+    fs_monitor: &'a Option<extension::FsMonitor>,
+    metadata: &'a Option<extension::Metadata>,
+    unknown_extensions: &'a [extension::Unknown],
+}
+
+/// The serialized form of an [`Entry`](crate::Entry), with its path inlined.
+#[derive(Serialize)]
+#[serde(rename = "Entry")]
+struct EntryRef<'a> {
+    stat: entry::Stat,
+    id: gix_hash::ObjectId,
+    flags: entry::Flags,
+    mode: entry::Mode,
+    #[serde(with = "path")]
+    path: &'a BStr,
+}
+
+/// The deserialized form of [`StateRef`].
+#[derive(Deserialize)]
+#[serde(rename = "State")]
+struct Repr {
+    object_hash: gix_hash::Kind,
+    timestamp: (i64, u32),
+    version: Version,
+    entries: Vec<Entry>,
+    is_sparse: bool,
+    end_of_index_at_decode_time: bool,
+    offset_table_at_decode_time: bool,
+    tree: Option<extension::Tree>,
+    link: Option<extension::Link>,
+    resolve_undo: Option<extension::resolve_undo::Paths>,
+    untracked: Option<extension::UntrackedCache>,
+    fs_monitor: Option<extension::FsMonitor>,
+    metadata: Option<extension::Metadata>,
+    unknown_extensions: Vec<extension::Unknown>,
+}
+
+/// The deserialized form of [`EntryRef`].
+#[derive(Deserialize)]
+#[serde(rename = "Entry")]
+struct Entry {
+    stat: entry::Stat,
+    id: gix_hash::ObjectId,
+    flags: entry::Flags,
+    mode: entry::Mode,
+    #[serde(with = "path")]
+    path: BString,
+}
+
+impl TryFrom<Repr> for State {
+    type Error = crate::verify::Error;
+
+    fn try_from(repr: Repr) -> Result<Self, Self::Error> {
+        let mut path_backing = Vec::with_capacity(repr.entries.iter().map(|e| e.path.len()).sum());
+        let entries = repr
+            .entries
+            .into_iter()
+            .map(|e| {
+                let start = path_backing.len();
+                path_backing.extend_from_slice(&e.path);
+                crate::Entry {
+                    stat: e.stat,
+                    id: e.id,
+                    flags: e.flags,
+                    mode: e.mode,
+                    path: start..path_backing.len(),
+                }
+            })
+            .collect();
+        let state = State {
+            object_hash: repr.object_hash,
+            timestamp: filetime::FileTime::from_unix_time(repr.timestamp.0, repr.timestamp.1),
+            version: repr.version,
+            entries,
+            path_backing,
+            is_sparse: repr.is_sparse,
+            end_of_index_at_decode_time: repr.end_of_index_at_decode_time,
+            offset_table_at_decode_time: repr.offset_table_at_decode_time,
+            tree: repr.tree,
+            link: repr.link,
+            resolve_undo: repr.resolve_undo,
+            untracked: repr.untracked,
+            fs_monitor: repr.fs_monitor,
+            metadata: repr.metadata,
+            unknown_extensions: repr.unknown_extensions,
+        };
+        state.verify(Default::default())?;
+        Ok(state)
+    }
+}
+
+/// Serialize a path as string if it's valid UTF-8, or as bytes otherwise, and accept both when deserializing.
+pub(crate) mod path {
+    use std::fmt::Formatter;
+
+    use bstr::ByteSlice;
+    use serde::{de::Visitor, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(path: &impl AsRef<[u8]>, serializer: S) -> Result<S::Ok, S::Error> {
+        let path = path.as_ref();
+        match path.to_str() {
+            Ok(path) => serializer.serialize_str(path),
+            Err(_) => serializer.serialize_bytes(path),
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>, T: From<Vec<u8>>>(deserializer: D) -> Result<T, D::Error> {
+        deserializer.deserialize_byte_buf(PathVisitor).map(Into::into)
+    }
+
+    struct PathVisitor;
+
+    impl<'de> Visitor<'de> for PathVisitor {
+        type Value = Vec<u8>;
+
+        fn expecting(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+            f.write_str("a path as string or bytes")
+        }
+
+        fn visit_str<E: serde::de::Error>(self, v: &str) -> Result<Self::Value, E> {
+            Ok(v.as_bytes().to_vec())
+        }
+
+        fn visit_string<E: serde::de::Error>(self, v: String) -> Result<Self::Value, E> {
+            Ok(v.into_bytes())
+        }
+
+        fn visit_bytes<E: serde::de::Error>(self, v: &[u8]) -> Result<Self::Value, E> {
+            Ok(v.to_vec())
+        }
+
+        fn visit_byte_buf<E: serde::de::Error>(self, v: Vec<u8>) -> Result<Self::Value, E> {
+            Ok(v)
+        }
+
+        fn visit_seq<A: serde::de::SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+            let mut out = Vec::with_capacity(seq.size_hint().unwrap_or_default());
+            while let Some(byte) = seq.next_element()? {
+                out.push(byte);
+            }
+            Ok(out)
+        }
+    }
+}
+
+/// Like [`path`], but for a list of paths.
+pub(crate) mod paths {
+    use bstr::BString;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    #[derive(Serialize)]
+    #[serde(transparent)]
+    struct PathRef<'a>(#[serde(with = "super::path")] &'a BString);
+
+    #[derive(Deserialize)]
+    #[serde(transparent)]
+    struct Path(#[serde(with = "super::path")] BString);
+
+    pub fn serialize<S: Serializer>(paths: &[BString], serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(paths.iter().map(PathRef))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<BString>, D::Error> {
+        Ok(Vec::<Path>::deserialize(deserializer)?
+            .into_iter()
+            .map(|p| p.0)
+            .collect())
+    }
+}
+
+impl Serialize for State {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        StateRef {
+            object_hash: self.object_hash,
+            timestamp: (self.timestamp.unix_seconds(), self.timestamp.nanoseconds()),
+            version: self.version,
+            entries: self
+                .entries
+                .iter()
+                .map(|e| EntryRef {
+                    stat: e.stat,
+                    id: e.id,
+                    flags: e.flags,
+                    mode: e.mode,
+                    path: e.path(self),
+                })
+                .collect(),
+            is_sparse: self.is_sparse,
+            end_of_index_at_decode_time: self.end_of_index_at_decode_time,
+            offset_table_at_decode_time: self.offset_table_at_decode_time,
+            tree: &self.tree,
+            link: &self.link,
+            resolve_undo: &self.resolve_undo,
+            untracked: &self.untracked,
+            fs_monitor: &self.fs_monitor,
+            metadata: &self.metadata,
+            unknown_extensions: &self.unknown_extensions,
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for State {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        State::try_from(Repr::deserialize(deserializer)?).map_err(serde::de::Error::custom)
+    }
+}
@@ -1,6 +1,20 @@
 use std::cmp::Ordering;
 
-use crate::State;
+use bstr::{BStr, ByteSlice};
+
+use crate::{entry, State};
+
+/// The error returned by [`State::verify()`].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error(transparent)]
+    Entries(#[from] entries::Error),
+    #[error(transparent)]
+    Paths(#[from] paths::Error),
+    #[error(transparent)]
+    Extensions(#[from] extensions::Error),
+}
 
 ///
 pub mod entries {
@@ -18,6 +32,45 @@ pub mod entries {
             previous_path: BString,
             previous_stage: u8,
         },
+        #[error("Entry '{path}' at index {index} is unmerged, but the path also has a merged entry at stage 0")]
+        MergedAndUnmerged { index: usize, path: BString },
+    }
+}
+
+///
+pub mod paths {
+    use bstr::BString;
+
+    /// The error returned by [`State::verify_paths()`][crate::State::verify_paths()].
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("Entry '{path}' at index {index} has an invalid path")]
+        InvalidComponent {
+            index: usize,
+            path: BString,
+            source: gix_validate::path::component::Error,
+        },
+        #[error("Entry '{path}' at index {index} must not contain '.' or '..' components")]
+        RelativeComponent { index: usize, path: BString },
+    }
+}
+
+///
+pub mod path {
+    use bstr::BString;
+
+    /// The error returned by [`verify::path()`][crate::verify::path()].
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("The path '{path}' has an invalid component")]
+        InvalidComponent {
+            path: BString,
+            source: gix_validate::path::component::Error,
+        },
+        #[error("The path '{path}' must not contain '.' or '..' components")]
+        RelativeComponent { path: BString },
     }
 }
 
@@ -31,11 +84,24 @@ pub mod extensions {
     pub enum Error {
         #[error(transparent)]
         Tree(#[from] extension::tree::verify::Error),
+        #[error("The tree cache claims to contain {tree_entries} entries, but the index only has {entries}")]
+        TreeEntriesCount { tree_entries: u32, entries: usize },
     }
 }
 
 impl State {
-    /// Assure our entries are consistent.
+    /// Assure this state is consistent so it can be written without producing a corrupt index, by
+    /// [verifying entries](Self::verify_entries()), [their paths](Self::verify_paths()) with `validate`
+    /// and [extensions](Self::verify_extensions()) without accessing objects.
+    pub fn verify(&self, validate: gix_validate::path::component::Options) -> Result<(), Error> {
+        self.verify_entries()?;
+        self.verify_paths(validate)?;
+        self.verify_extensions(false, gix_object::find::Never)?;
+        Ok(())
+    }
+
+    /// Assure our entries are consistent, which means they are sorted by path and stage, and that no path has
+    /// a merged entry along with unmerged ones.
     pub fn verify_entries(&self) -> Result<(), entries::Error> {
         let _span = gix_features::trace::coarse!("gix_index::File::verify_entries()");
         let mut previous = None::<&crate::Entry>;
@@ -50,17 +116,84 @@ impl State {
                         previous_stage: prev.flags.stage() as u8,
                     });
                 }
+                if prev.flags.stage() == entry::Stage::Unconflicted
+                    && entry.flags.stage() != entry::Stage::Unconflicted
+                    && prev.path(self) == entry.path(self)
+                {
+                    return Err(entries::Error::MergedAndUnmerged {
+                        index: idx,
+                        path: entry.path(self).into(),
+                    });
+                }
             }
             previous = Some(entry);
         }
         Ok(())
     }
 
+    /// Assure the paths of all entries are [valid](path()), with each of their components passing `validate`, just like
+    /// when they are [created from a tree](State::from_tree()).
+    pub fn verify_paths(&self, validate: gix_validate::path::component::Options) -> Result<(), paths::Error> {
+        let _span = gix_features::trace::coarse!("gix_index::File::verify_paths()");
+        for (index, entry) in self.entries.iter().enumerate() {
+            path(entry.path(self), entry.mode, validate).map_err(|err| match err {
+                path::Error::InvalidComponent { path, source } => {
+                    paths::Error::InvalidComponent { index, path, source }
+                }
+                path::Error::RelativeComponent { path } => paths::Error::RelativeComponent { index, path },
+            })?;
+        }
+        Ok(())
+    }
+
+    /// Assure our extensions are consistent, and that the tree cache doesn't claim more entries than there are.
+    ///
     /// Note: `objects` cannot be `Option<F>` as we can't call it with a closure then due to the indirection through `Some`.
     pub fn verify_extensions(&self, use_find: bool, objects: impl gix_object::Find) -> Result<(), extensions::Error> {
-        self.tree().map(|t| t.verify(use_find, objects)).transpose()?;
+        if let Some(tree) = self.tree() {
+            tree.verify(use_find, objects)?;
+            if let Some(tree_entries) = tree.num_entries.filter(|n| *n as usize > self.entries.len()) {
+                return Err(extensions::Error::TreeEntriesCount {
+                    tree_entries,
+                    entries: self.entries.len(),
+                });
+            }
+        }
         // TODO: verify links by running the whole set of tests on the index
         //       - do that once we load it as well, or maybe that's lazy loaded? Too many questions for now.
         Ok(())
     }
 }
+
+/// Assure `path` of an entry with `mode` is safe to check out, with each of its components passing `validate`, similar to
+/// `verify_path()` in `git`.
+///
+/// This rejects empty, `.` and `..` components along with `.git` and anything that a filesystem could treat as such,
+/// like NTFS alternate data streams or names with codepoints ignored by HFS+, depending on `validate`.
+/// The trailing slash of [sparse directory](entry::Mode::DIR) entries is permitted.
+pub fn path(
+    path: &BStr,
+    mode: entry::Mode,
+    validate: gix_validate::path::component::Options,
+) -> Result<(), path::Error> {
+    let path_to_check = if mode.is_sparse() {
+        path.strip_suffix(b"/").unwrap_or(path).as_bstr()
+    } else {
+        path
+    };
+    let mut components = path_to_check.split_str("/").peekable();
+    while let Some(component) = components.next() {
+        if component == b"." || component == b".." {
+            return Err(path::Error::RelativeComponent { path: path.into() });
+        }
+        let component_mode = (components.peek().is_none() && mode == entry::Mode::SYMLINK)
+            .then_some(gix_validate::path::component::Mode::Symlink);
+        gix_validate::path::component(component.as_bstr(), component_mode, validate).map_err(|source| {
+            path::Error::InvalidComponent {
+                path: path.into(),
+                source,
+            }
+        })?;
+    }
+    Ok(())
+}
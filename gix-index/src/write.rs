@@ -1,15 +1,18 @@
-use std::io::Write;
+use std::{borrow::Cow, io::Write};
 
-use crate::{entry, extension, write::util::CountBytes, State, Version};
+use crate::{entry, extension, write::util::CountBytes, Entry, State};
 
 /// A way to specify which of the optional extensions to write.
 #[derive(Default, Debug, Copy, Clone)]
 pub enum Extensions {
-    /// Writes all available optional extensions to avoid losing any information.
+    /// Writes all available optional extensions to avoid losing any information,
+    /// including [unknown ones](crate::State::unknown_extensions()).
     #[default]
     All,
     /// Only write the given optional extensions, with each extension being marked by a boolean flag.
     ///
+    /// Note that [metadata](crate::extension::Metadata) stored by tools is always written to prevent it from getting lost.
+    ///
     /// # Note: mandatory extensions
     ///
     /// Mandatory extensions, like `sdir` or other lower-case ones, may not be configured here as they need to be present
@@ -19,6 +22,14 @@ pub enum Extensions {
         tree_cache: bool,
         /// Write the end-of-index-entry extension.
         end_of_index_entry: bool,
+        /// Write the index-entry-offset-table extension, if [enabled](Options::offset_table_blocks).
+        offset_table: bool,
+        /// Write the resolve-undo extension, if present.
+        resolve_undo: bool,
+        /// Write the untracked-cache extension, if present.
+        untracked_cache: bool,
+        /// Write the filesystem-monitor extension, if present.
+        fs_monitor: bool,
     },
     /// Write no optional extension at all for what should be the smallest possible index
     None,
@@ -33,9 +44,18 @@ impl Extensions {
             Extensions::Given {
                 tree_cache,
                 end_of_index_entry,
+                offset_table,
+                resolve_undo,
+                untracked_cache,
+                fs_monitor,
             } => match signature {
                 extension::tree::SIGNATURE => tree_cache,
                 extension::end_of_index_entry::SIGNATURE => end_of_index_entry,
+                extension::index_entry_offset_table::SIGNATURE => offset_table,
+                extension::resolve_undo::SIGNATURE => resolve_undo,
+                extension::untracked_cache::SIGNATURE => untracked_cache,
+                extension::fs_monitor::SIGNATURE => fs_monitor,
+                extension::metadata::SIGNATURE => &true,
                 _ => &false,
             }
             .then(|| signature),
@@ -43,58 +63,154 @@ impl Extensions {
     }
 }
 
+/// The version of the index to write.
+#[derive(Default, Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Version {
+    /// Pick the lowest version that can represent all entries, which is [`V3`](crate::Version::V3) if entries have
+    /// extended flags and [`V2`](crate::Version::V2) otherwise, unless the state [is set](State::set_version())
+    /// to [`V4`](crate::Version::V4).
+    #[default]
+    Auto,
+    /// Always write the given version, or fail with [an error](Error::VersionTooLow) if it can't represent all entries.
+    Exactly(crate::Version),
+}
+
+/// The error returned by [`State::write_to()`].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error("Index version {requested:?} can't represent all entries, which need at least {required:?}")]
+    VersionTooLow {
+        requested: crate::Version,
+        required: crate::Version,
+    },
+    #[error("Refusing to write an inconsistent index")]
+    Verify(#[from] crate::verify::Error),
+    #[error("An index can't store more than {max} entries, but {num_entries} were to be written", max = u32::MAX)]
+    TooManyEntries { num_entries: usize },
+}
+
 /// The options for use when [writing an index][State::write_to()].
 ///
-/// Note that default options write either index V2 or V3 depending on the content of the entries.
+/// Note that default options [pick the version](Self::version) automatically.
 #[derive(Debug, Default, Clone, Copy)]
 pub struct Options {
+    /// The version to write, which is [picked automatically](Version::Auto) by default.
+    pub version: Version,
     /// Configures which extensions to write.
     pub extensions: Extensions,
     /// Set the trailing hash of the produced index to all zeroes to save some time.
     ///
     /// This value is typically controlled by `index.skipHash` and is respected when the index is written
     /// via [`File::write()`](crate::File::write()) and [`File::write_to()`](crate::File::write_to()).
-    /// Note that
+    /// Note that [`State::write_to()`] never writes the trailing hash, as it's computed by these methods over
+    /// everything [`State::write_to()`] produces, using the hash kind of the state.
     pub skip_hash: bool,
+    /// The amount of blocks to split entries into, recording where each one starts in the index-entry-offset-table extension.
+    /// This allows readers to decode entries with up to as many threads.
+    ///
+    /// No offset table is written if this is smaller than 2, or if the [extensions](Self::extensions) don't permit it.
+    /// This value is typically derived from `index.threads`.
+    pub offset_table_blocks: usize,
+    /// Determine how to flush the written index to disk before it replaces the previous one when written with
+    /// [`File::write()`](crate::File::write()), so a crash can't leave a truncated index behind.
+    ///
+    /// This value is typically controlled by `core.fsync` and `core.fsyncMethod`.
+    pub fsync: gix_features::fs::Fsync,
+    /// If set, entries that are [racy](crate::Entry::is_racy()) with respect to the [index timestamp](State::timestamp())
+    /// are written with a size of 0, as evaluated with the given stat options.
+    ///
+    /// This assures that they aren't considered unmodified once the index has a newer timestamp, even though their
+    /// content was never checked, at the cost of having to check their content the next time they are compared to the worktree.
+    /// Use [`State::smudge_racily_clean_entries()`] to only smudge entries that are known to be modified.
+    pub smudge_racy_entries: Option<entry::stat::Options>,
+    /// If set, [verify](State::verify()) the state with the given options for validating paths before writing it,
+    /// and fail without writing anything if it's inconsistent, so a corrupt index is never persisted.
+    ///
+    /// To write entries with [unsafe paths](crate::verify::path()) anyway, use
+    /// [`State::remove_entries_with_invalid_paths()`] to remove them first.
+    pub verify: Option<gix_validate::path::component::Options>,
 }
 
 impl State {
-    /// Serialize this instance to `out` with [`options`][Options].
+    /// Serialize this instance to `out` with [`options`][Options], returning the version that was written.
+    ///
+    /// Note that the trailing hash isn't written, so the output is only readable by `git` once it is appended,
+    /// which is why [`File::write_to()`](crate::File::write_to()) should be preferred.
     pub fn write_to(
         &self,
         out: impl std::io::Write,
         Options {
+            version,
             extensions,
             skip_hash: _,
+            offset_table_blocks,
+            fsync: _,
+            smudge_racy_entries,
+            verify,
         }: Options,
-    ) -> std::io::Result<Version> {
+    ) -> Result<crate::Version, Error> {
         let _span = gix_features::trace::detail!("gix_index::State::write()");
-        let version = self.detect_required_version();
+        if let Some(validate) = verify {
+            self.verify(validate)?;
+        }
+        let version = self.version_to_write(version)?;
 
         let mut write = CountBytes::new(out);
-        let num_entries: u32 = self
-            .entries()
-            .len()
-            .try_into()
-            .expect("definitely not 4billion entries");
-        let removed_entries: u32 = self
+        let num_written_entries = self
             .entries()
             .iter()
-            .filter(|e| e.flags.contains(entry::Flags::REMOVE))
-            .count()
-            .try_into()
-            .expect("definitely not too many entries");
-
-        let offset_to_entries = header(&mut write, version, num_entries - removed_entries)?;
-        let offset_to_extensions = entries(&mut write, self, offset_to_entries)?;
-        let (extension_toc, out) = self.write_extensions(write, offset_to_extensions, extensions)?;
-
-        if num_entries > 0
-            && extensions
-                .should_write(extension::end_of_index_entry::SIGNATURE)
-                .is_some()
-            && !extension_toc.is_empty()
-        {
+            .filter(|e| !e.flags.contains(entry::Flags::REMOVE))
+            .count();
+        let num_written_entries_u32 = u32::try_from(num_written_entries).map_err(|_| Error::TooManyEntries {
+            num_entries: num_written_entries,
+        })?;
+
+        let offset_to_entries = header(&mut write, version, num_written_entries_u32)?;
+        let entries_per_block = extensions
+            .should_write(extension::index_entry_offset_table::SIGNATURE)
+            .and_then(|_| {
+                let num_blocks = offset_table_blocks.min(num_written_entries);
+                (num_blocks > 1).then(|| (num_written_entries + num_blocks - 1) / num_blocks)
+            });
+        let (offset_to_extensions, blocks) = if version == crate::Version::V4 {
+            entries_v4(&mut write, self, entries_per_block, smudge_racy_entries)?
+        } else {
+            entries(
+                &mut write,
+                self,
+                offset_to_entries,
+                entries_per_block,
+                smudge_racy_entries,
+            )?
+        };
+        // Both the offset table and the end-of-index-entry extension store offsets as 32 bit numbers, so they can only
+        // be written if all entries end before the 4GB mark. They are optional, so the index remains valid without them.
+        let offset_to_extensions_u32 = u32::try_from(offset_to_extensions).ok();
+        let offsets: Vec<_> = offset_to_extensions_u32
+            .map(|_| {
+                blocks
+                    .into_iter()
+                    .filter_map(|block| {
+                        Some(extension::index_entry_offset_table::Offset {
+                            from_beginning_of_file: block.offset.try_into().ok()?,
+                            num_entries: block.num_entries,
+                        })
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        let (extension_toc, out) = self.write_extensions(write, offset_to_extensions, extensions, &offsets)?;
+
+        if let Some(offset_to_extensions) = offset_to_extensions_u32.filter(|_| {
+            num_written_entries > 0
+                && extensions
+                    .should_write(extension::end_of_index_entry::SIGNATURE)
+                    .is_some()
+                && !extension_toc.is_empty()
+        }) {
             extension::end_of_index_entry::write_to(out, self.object_hash, offset_to_extensions, extension_toc)?;
         }
 
@@ -104,19 +220,61 @@ impl State {
     fn write_extensions<T>(
         &self,
         mut write: CountBytes<T>,
-        offset_to_extensions: u32,
+        offset_to_extensions: u64,
         extensions: Extensions,
+        offsets: &[extension::index_entry_offset_table::Offset],
     ) -> std::io::Result<(Vec<(extension::Signature, u32)>, T)>
     where
         T: std::io::Write,
     {
         type WriteExtFn<'a> = &'a dyn Fn(&mut dyn std::io::Write) -> Option<std::io::Result<extension::Signature>>;
-        let extensions: &[WriteExtFn<'_>] = &[
+        let known_extensions: &[WriteExtFn<'_>] = &[
+            &|write| {
+                (!offsets.is_empty()).then(|| {
+                    extension::index_entry_offset_table::write_to(write, offsets)
+                        .map(|_| extension::index_entry_offset_table::SIGNATURE)
+                })
+            },
+            &|write| {
+                self.link
+                    .as_ref()
+                    .map(|link| link.write_to(write).map(|_| extension::link::SIGNATURE))
+            },
             &|write| {
                 extensions
                     .should_write(extension::tree::SIGNATURE)
                     .and_then(|signature| self.tree().map(|tree| tree.write_to(write).map(|_| signature)))
             },
+            &|write| {
+                extensions
+                    .should_write(extension::resolve_undo::SIGNATURE)
+                    .and_then(|signature| {
+                        self.resolve_undo()
+                            .map(|paths| extension::resolve_undo::write_to(write, paths).map(|_| signature))
+                    })
+            },
+            &|write| {
+                extensions
+                    .should_write(extension::untracked_cache::SIGNATURE)
+                    .and_then(|signature| {
+                        self.untracked()
+                            .map(|untracked| untracked.write_to(write, self.object_hash).map(|_| signature))
+                    })
+            },
+            &|write| {
+                extensions
+                    .should_write(extension::fs_monitor::SIGNATURE)
+                    .and_then(|signature| self.fs_monitor().map(|fsm| fsm.write_to(write).map(|_| signature)))
+            },
+            &|write| {
+                extensions
+                    .should_write(extension::metadata::SIGNATURE)
+                    .and_then(|signature| {
+                        self.metadata()
+                            .filter(|metadata| !metadata.is_empty())
+                            .map(|metadata| metadata.write_to(write).map(|_| signature))
+                    })
+            },
             &|write| {
                 self.is_sparse()
                     .then(|| extension::sparse::write_to(write).map(|_| extension::sparse::SIGNATURE))
@@ -125,11 +283,20 @@ impl State {
 
         let mut offset_to_previous_ext = offset_to_extensions;
         let mut out = Vec::with_capacity(5);
-        for write_ext in extensions {
+        for write_ext in known_extensions {
             if let Some(signature) = write_ext(&mut write).transpose()? {
                 let offset_past_ext = write.count;
-                let ext_size = offset_past_ext - offset_to_previous_ext - (extension::MIN_SIZE as u32);
+                let ext_size = offset_past_ext - offset_to_previous_ext - extension::MIN_SIZE as u64;
                 offset_to_previous_ext = offset_past_ext;
+                out.push((signature, extension::size_u32(signature, ext_size)?));
+            }
+        }
+        for unknown in &self.unknown_extensions {
+            if let Some(signature) = extensions.should_write(unknown.signature) {
+                let ext_size = extension::size_u32(signature, unknown.data.len())?;
+                write.write_all(&signature)?;
+                write.write_all(&ext_size.to_be_bytes())?;
+                write.write_all(&unknown.data)?;
                 out.push((signature, ext_size));
             }
         }
@@ -138,23 +305,31 @@ impl State {
 }
 
 impl State {
-    fn detect_required_version(&self) -> Version {
-        self.entries
+    /// Return the `requested` version if it can represent all entries, or the lowest version that can if it's [`Version::Auto`].
+    fn version_to_write(&self, requested: Version) -> Result<crate::Version, Error> {
+        let required = self
+            .entries
             .iter()
-            .find_map(|e| e.flags.contains(entry::Flags::EXTENDED).then_some(Version::V3))
-            .unwrap_or(Version::V2)
+            .find_map(|e| e.flags.needs_extended_storage().then_some(crate::Version::V3))
+            .unwrap_or(crate::Version::V2);
+        match requested {
+            Version::Exactly(requested) if requested < required => Err(Error::VersionTooLow { requested, required }),
+            Version::Exactly(requested) => Ok(requested),
+            Version::Auto if self.version == crate::Version::V4 => Ok(crate::Version::V4),
+            Version::Auto => Ok(required),
+        }
     }
 }
 
 fn header<T: std::io::Write>(
     out: &mut CountBytes<T>,
-    version: Version,
+    version: crate::Version,
     num_entries: u32,
-) -> Result<u32, std::io::Error> {
+) -> Result<u64, std::io::Error> {
     let version = match version {
-        Version::V2 => 2_u32.to_be_bytes(),
-        Version::V3 => 3_u32.to_be_bytes(),
-        Version::V4 => 4_u32.to_be_bytes(),
+        crate::Version::V2 => 2_u32.to_be_bytes(),
+        crate::Version::V3 => 3_u32.to_be_bytes(),
+        crate::Version::V4 => 4_u32.to_be_bytes(),
     };
 
     out.write_all(crate::decode::header::SIGNATURE)?;
@@ -164,12 +339,62 @@ fn header<T: std::io::Write>(
     Ok(out.count)
 }
 
-fn entries<T: std::io::Write>(out: &mut CountBytes<T>, state: &State, header_size: u32) -> Result<u32, std::io::Error> {
-    for entry in state.entries() {
-        if entry.flags.contains(entry::Flags::REMOVE) {
-            continue;
+/// Return `entry` with its size set to 0 if it's racy and racy entries should be smudged, or the `entry` itself.
+fn smudged<'a>(entry: &'a Entry, state: &State, smudge_racy_entries: Option<entry::stat::Options>) -> Cow<'a, Entry> {
+    match smudge_racy_entries {
+        Some(options) if entry.is_racy(state.timestamp(), options) => {
+            let mut entry = entry.clone();
+            entry.stat.size = 0;
+            Cow::Owned(entry)
+        }
+        _ => Cow::Borrowed(entry),
+    }
+}
+
+/// A block of entries that may be listed in the index-entry-offset-table extension.
+struct Block {
+    /// The offset from the beginning of the file to the first entry of the block.
+    offset: u64,
+    num_entries: u32,
+}
+
+/// Record the start of a new block of entries in `blocks` if `entry_index` is the first entry of a block.
+/// Return `true` if a new block was started.
+fn maybe_start_block(
+    blocks: &mut Vec<Block>,
+    entries_per_block: Option<usize>,
+    entry_index: usize,
+    offset: u64,
+) -> bool {
+    let Some(entries_per_block) = entries_per_block else {
+        return false;
+    };
+    if entry_index % entries_per_block != 0 {
+        if let Some(block) = blocks.last_mut() {
+            block.num_entries += 1;
         }
-        entry.write_to(&mut *out, state)?;
+        return false;
+    }
+    blocks.push(Block { offset, num_entries: 1 });
+    true
+}
+
+fn entries<T: std::io::Write>(
+    out: &mut CountBytes<T>,
+    state: &State,
+    header_size: u64,
+    entries_per_block: Option<usize>,
+    smudge_racy_entries: Option<entry::stat::Options>,
+) -> Result<(u64, Vec<Block>), std::io::Error> {
+    let mut blocks = Vec::new();
+    for (idx, entry) in state
+        .entries()
+        .iter()
+        .filter(|e| !e.flags.contains(entry::Flags::REMOVE))
+        .enumerate()
+    {
+        maybe_start_block(&mut blocks, entries_per_block, idx, out.count);
+        smudged(entry, state, smudge_racy_entries).write_to(&mut *out, state)?;
         match (out.count - header_size) % 8 {
             0 => {}
             n => {
@@ -179,12 +404,41 @@ fn entries<T: std::io::Write>(out: &mut CountBytes<T>, state: &State, header_siz
         };
     }
 
-    Ok(out.count)
+    Ok((out.count, blocks))
+}
+
+/// Write entries with prefix-compressed paths and without padding.
+///
+/// The first entry of each block doesn't share a prefix with the previous entry so blocks can be decoded independently.
+fn entries_v4<T: std::io::Write>(
+    out: &mut CountBytes<T>,
+    state: &State,
+    entries_per_block: Option<usize>,
+    smudge_racy_entries: Option<entry::stat::Options>,
+) -> Result<(u64, Vec<Block>), std::io::Error> {
+    let mut blocks = Vec::new();
+    let mut previous_path: &bstr::BStr = "".into();
+    for (idx, entry) in state
+        .entries()
+        .iter()
+        .filter(|e| !e.flags.contains(entry::Flags::REMOVE))
+        .enumerate()
+    {
+        let smudged_entry = smudged(entry, state, smudge_racy_entries);
+        if maybe_start_block(&mut blocks, entries_per_block, idx, out.count) {
+            smudged_entry.write_to_v4_with_common_prefix(&mut *out, state, previous_path.len(), 0)?;
+        } else {
+            smudged_entry.write_to_v4(&mut *out, state, previous_path)?;
+        }
+        previous_path = entry.path(state);
+    }
+
+    Ok((out.count, blocks))
 }
 
 mod util {
     pub struct CountBytes<T> {
-        pub count: u32,
+        pub count: u64,
         pub inner: T,
     }
 
@@ -203,15 +457,7 @@ mod util {
     {
         fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
             let written = self.inner.write(buf)?;
-            self.count = self
-                .count
-                .checked_add(u32::try_from(written).expect("we don't write 4GB buffers"))
-                .ok_or_else(|| {
-                    std::io::Error::new(
-                        std::io::ErrorKind::Other,
-                        "Cannot write indices larger than 4 gigabytes",
-                    )
-                })?;
+            self.count += written as u64;
             Ok(written)
         }
 
@@ -0,0 +1,26 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+export GIT_INDEX_VERSION=2;
+git init -q
+git config index.threads 1
+
+mkdir -p d dir/sub unchanged
+touch a b d/a d/b dir/b dir/sub/a exe unchanged/a
+echo content > c
+git add .
+git commit -q -m "initial"
+git rev-parse @^{tree} > head.tree
+
+echo changed > a && git add a
+git rm -q b
+chmod +x exe && git add exe
+git rm -q -r d
+echo new > new && git add new
+echo new > dir/sub/new && git add dir/sub/new
+# a file becomes a directory
+git rm -q c && mkdir c && touch c/file && git add c/file
+# sorts before 'dir/' in the index
+touch dir-file && git add dir-file
+
+git diff-index --cached --no-abbrev HEAD > baseline.raw
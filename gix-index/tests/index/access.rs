@@ -125,6 +125,44 @@ fn ignorecase_clashes_and_order() {
     );
 }
 
+#[test]
+fn icase_collisions() {
+    use gix_index::icase::CollisionKind::{Directory, File};
+    let file = icase_fixture();
+    let icase = file.prepare_icase_backing();
+    let collisions = file.icase_collisions(&icase);
+    for collision in &collisions {
+        let (path, other) = (collision.entry.path(&file), collision.other.path(&file));
+        assert_ne!(path, other, "entries never collide with themselves");
+        match collision.kind {
+            File => assert!(path.eq_ignore_ascii_case(other)),
+            Directory => assert!(other.to_ascii_lowercase().starts_with(&path.to_ascii_lowercase())),
+        }
+    }
+    assert_eq!(
+        collisions
+            .iter()
+            .map(|c| (c.entry.path(&file).to_str().expect("valid"), c.kind))
+            .collect::<Vec<_>>(),
+        [
+            ("FILE_X", File),
+            ("FILE_x", File),
+            ("X", File),
+            ("d", Directory),
+            ("file_X", File),
+            ("file_x", File),
+            ("x", File),
+        ],
+        "`D/B` and `D/C` don't collide as `d` is the file that collides with their directory"
+    );
+
+    let file = Fixture::Generated("v2_more_files").open();
+    assert!(
+        file.icase_collisions(&file.prepare_icase_backing()).is_empty(),
+        "no collisions if there are no paths that only differ in case"
+    );
+}
+
 #[test]
 fn prefixed_entries_icase_with_name_clashes() {
     let file = icase_fixture();
@@ -214,6 +252,537 @@ fn remove_entries() {
     file.remove_entries(|_, _, _| unreachable!("should not be called"));
 }
 
+fn paths(state: &gix_index::State) -> Vec<String> {
+    state
+        .entries()
+        .iter()
+        .map(|e| {
+            let path = e.path(state).to_string();
+            match e.stage_raw() {
+                0 => path,
+                stage => format!("{path}:{stage}"),
+            }
+        })
+        .collect()
+}
+
+#[test]
+fn upsert_entry() -> crate::Result {
+    let mut file = Fixture::Generated("v4_more_files_IEOT").open();
+    let entry = file.entry(0).clone();
+    let upsert = |file: &mut gix_index::File, path: &str, stage: Stage| {
+        file.upsert_entry(
+            entry.stat,
+            entry.id,
+            gix_index::entry::Flags::from_stage(stage),
+            entry.mode,
+            path.into(),
+        )
+    };
+
+    let idx = upsert(&mut file, "ca", Stage::Unconflicted);
+    assert_eq!(file.entry(idx).path(&file), "ca");
+    assert_eq!(
+        paths(&file),
+        [
+            "a",
+            "b",
+            "c",
+            "ca",
+            "d/a",
+            "d/b",
+            "d/c",
+            "d/last/123",
+            "d/last/34",
+            "d/last/6",
+            "x"
+        ]
+    );
+    let tree = file.tree().expect("present");
+    assert_eq!(tree.num_entries, None, "the root tree is invalidated");
+    assert_eq!(tree.children[0].num_entries, Some(6), "other trees stay valid");
+
+    upsert(&mut file, "d/last", Stage::Unconflicted);
+    assert_eq!(
+        paths(&file),
+        ["a", "b", "c", "ca", "d/a", "d/b", "d/c", "d/last", "x"],
+        "a file replaces the directory at its path"
+    );
+    let tree = file.tree().expect("present");
+    assert_eq!(tree.children[0].num_entries, None);
+    assert!(tree.children[0].children.is_empty(), "the tree at 'd/last' was removed");
+
+    upsert(&mut file, "x/y", Stage::Unconflicted);
+    assert_eq!(
+        paths(&file),
+        ["a", "b", "c", "ca", "d/a", "d/b", "d/c", "d/last", "x/y"],
+        "a file at a leading directory is replaced"
+    );
+
+    upsert(&mut file, "a", Stage::Ours);
+    upsert(&mut file, "a", Stage::Theirs);
+    let idx = upsert(&mut file, "a", Stage::Ours);
+    assert_eq!(idx, 0, "existing entries are updated in place");
+    assert_eq!(
+        paths(&file)[..3],
+        ["a:2", "a:3", "b"],
+        "conflicting stages replace the unconflicted entry"
+    );
+    upsert(&mut file, "a", Stage::Unconflicted);
+    assert_eq!(
+        paths(&file)[..2],
+        ["a", "b"],
+        "an unconflicted entry replaces all conflicting stages"
+    );
+
+    file.verify_entries()?;
+    file.verify_extensions(false, gix_object::find::Never)?;
+    Ok(())
+}
+
+#[test]
+fn remove_entries_by_pathspec() -> crate::Result {
+    let mut file = Fixture::Generated("v4_more_files_IEOT").open();
+    let mut pathspec = gix_pathspec::Search::from_specs(
+        [gix_pathspec::parse(b"d/last", Default::default())?],
+        None,
+        std::path::Path::new(""),
+    )?;
+    let num_removed = file.remove_entries_by_pathspec(&mut pathspec, &mut |_, _, _, _| unreachable!("no attributes"));
+    assert_eq!(num_removed, 3);
+    assert_eq!(paths(&file), ["a", "b", "c", "d/a", "d/b", "d/c", "x"]);
+    let tree = file.tree().expect("present");
+    assert_eq!(tree.num_entries, None);
+    assert_eq!(tree.children[0].num_entries, None);
+    assert_eq!(
+        tree.children[0].children[0].num_entries, None,
+        "the tree at 'd/last' is invalidated as its entries were removed"
+    );
+
+    let mut pathspec = gix_pathspec::Search::from_specs(
+        [gix_pathspec::parse(b"d/last", Default::default())?],
+        None,
+        std::path::Path::new(""),
+    )?;
+    assert_eq!(
+        file.remove_entries_by_pathspec(&mut pathspec, &mut |_, _, _, _| unreachable!("no attributes")),
+        0,
+        "nothing matches anymore"
+    );
+    Ok(())
+}
+
+#[test]
+fn remove_entry() {
+    let mut file = Fixture::Generated("v4_more_files_IEOT").open();
+    assert!(!file.remove_entry("d".into()), "directories aren't entries");
+    assert!(file.remove_entry("d/last/34".into()));
+    assert_eq!(
+        paths(&file),
+        ["a", "b", "c", "d/a", "d/b", "d/c", "d/last/123", "d/last/6", "x"]
+    );
+    let tree = file.tree().expect("present");
+    assert_eq!(tree.num_entries, None);
+    assert_eq!(tree.children[0].children[0].num_entries, None);
+    assert!(!file.remove_entry("d/last/34".into()), "it's gone");
+
+    let mut file = Fixture::Loose("conflicting-file").open();
+    assert!(file.remove_entry("file".into()));
+    assert_eq!(file.entries().len(), 0, "all stages are removed");
+    assert_eq!(
+        file.resolve_undo().map(Vec::len),
+        Some(1),
+        "and the conflict is recorded as resolved"
+    );
+}
+
+#[test]
+fn set_assume_unchanged_and_skip_worktree_by_pathspec() -> crate::Result {
+    let tmp = gix_testtools::scripted_fixture_writable_standalone("make_index/v2_more_files.sh")?;
+    let mut file = gix_index::File::at(
+        tmp.path().join(".git").join("index"),
+        gix_hash::Kind::Sha1,
+        false,
+        Default::default(),
+    )?;
+    let ls_files = || -> crate::Result<String> {
+        let out = std::process::Command::new("git")
+            .args(["ls-files", "-v"])
+            .current_dir(tmp.path())
+            .output()?;
+        assert!(out.status.success(), "{out:?}");
+        Ok(out.stdout.to_str()?.to_owned())
+    };
+    let search = |spec: &str| -> crate::Result<gix_pathspec::Search> {
+        Ok(gix_pathspec::Search::from_specs(
+            [gix_pathspec::parse(spec.as_bytes(), Default::default())?],
+            None,
+            std::path::Path::new(""),
+        )?)
+    };
+    let no_attributes = &mut |_: &_, _, _, _: &mut _| -> bool { unreachable!("no attributes") };
+
+    assert_eq!(
+        file.set_assume_unchanged_by_pathspec(&mut search("a")?, no_attributes, true),
+        1
+    );
+    assert_eq!(
+        file.version(),
+        gix_index::Version::V2,
+        "assume-unchanged can be stored in V2"
+    );
+    assert_eq!(
+        file.set_skip_worktree_by_pathspec(&mut search("d")?, no_attributes, true),
+        3
+    );
+    assert_eq!(
+        file.version(),
+        gix_index::Version::V3,
+        "skip-worktree needs extended flags, so the index is upgraded"
+    );
+    assert!(file
+        .entries()
+        .iter()
+        .filter(|e| e.flags.contains(gix_index::entry::Flags::SKIP_WORKTREE))
+        .all(|e| e.flags.contains(gix_index::entry::Flags::EXTENDED)));
+    file.write(Default::default())?;
+    assert_eq!(
+        ls_files()?,
+        "h a\nH b\nH c\nS d/a\nS d/b\nS d/c\n",
+        "git sees the flags"
+    );
+
+    assert_eq!(
+        file.set_skip_worktree_by_pathspec(&mut search("d/*")?, no_attributes, false),
+        3
+    );
+    assert_eq!(
+        file.set_assume_unchanged_by_pathspec(&mut search("missing")?, no_attributes, true),
+        0
+    );
+    assert_eq!(
+        file.version(),
+        gix_index::Version::V2,
+        "without extended flags, the index is downgraded again"
+    );
+    assert!(file
+        .entries()
+        .iter()
+        .all(|e| !e.flags.contains(gix_index::entry::Flags::EXTENDED)));
+    file.write(Default::default())?;
+    assert_eq!(ls_files()?, "h a\nH b\nH c\nH d/a\nH d/b\nH d/c\n");
+    Ok(())
+}
+
+#[test]
+fn rename_entry() -> crate::Result {
+    let mut file = Fixture::Generated("v4_more_files_IEOT").open();
+    assert!(!file.rename_entry("missing".into(), "a".into()));
+
+    assert!(file.rename_entry("a".into(), "d/last/5".into()));
+    assert_eq!(
+        paths(&file),
+        [
+            "b",
+            "c",
+            "d/a",
+            "d/b",
+            "d/c",
+            "d/last/123",
+            "d/last/34",
+            "d/last/5",
+            "d/last/6",
+            "x"
+        ]
+    );
+    let tree = file.tree().expect("present");
+    assert_eq!(tree.num_entries, None);
+    assert_eq!(tree.children[0].num_entries, None);
+    assert_eq!(tree.children[0].children[0].num_entries, None);
+
+    assert!(file.rename_entry("b".into(), "c".into()));
+    assert_eq!(
+        paths(&file),
+        [
+            "c",
+            "d/a",
+            "d/b",
+            "d/c",
+            "d/last/123",
+            "d/last/34",
+            "d/last/5",
+            "d/last/6",
+            "x"
+        ],
+        "existing entries at the destination are replaced"
+    );
+    file.verify_entries()?;
+    Ok(())
+}
+
+#[test]
+fn resolved_conflicts_are_recorded_in_resolve_undo() -> crate::Result {
+    let mut file = Fixture::Loose("conflicting-file").open();
+    assert!(file.resolve_undo().is_none());
+    let stages: Vec<_> = file.entries().iter().map(|e| (e.mode.bits(), e.id)).collect();
+    let entry = file.entry(0).clone();
+    file.upsert_entry(
+        entry.stat,
+        entry.id,
+        gix_index::entry::Flags::empty(),
+        entry.mode,
+        "file".into(),
+    );
+    assert_eq!(paths(&file), ["file"]);
+
+    let resolve_undo = file.resolve_undo().expect("created when conflicts are resolved");
+    assert_eq!(resolve_undo.len(), 1);
+    assert_eq!(resolve_undo[0].name(), "file");
+    assert_eq!(
+        resolve_undo[0]
+            .stages()
+            .iter()
+            .map(|stage| stage.map(|stage| (stage.mode(), stage.id().to_owned())))
+            .collect::<Vec<_>>(),
+        stages.into_iter().map(Some).collect::<Vec<_>>(),
+        "all stages are recorded"
+    );
+
+    let mut file = Fixture::Loose("conflicting-file").open();
+    let mut pathspec = gix_pathspec::Search::from_specs(
+        [gix_pathspec::parse(b"file", Default::default())?],
+        None,
+        std::path::Path::new(""),
+    )?;
+    assert_eq!(
+        file.remove_entries_by_pathspec(&mut pathspec, &mut |_, _, _, _| unreachable!("no attributes")),
+        3
+    );
+    assert_eq!(
+        file.resolve_undo().map(Vec::len),
+        Some(1),
+        "removing conflicting stages also resolves the conflict"
+    );
+
+    let mut file = Fixture::Loose("REUC").open();
+    let entry = file.entry(0).clone();
+    file.upsert_entry(
+        entry.stat,
+        entry.id,
+        gix_index::entry::Flags::empty(),
+        entry.mode,
+        "fi/le".into(),
+    );
+    assert_eq!(
+        file.resolve_undo().map(Vec::len),
+        Some(1),
+        "unconflicted entries are not recorded"
+    );
+    Ok(())
+}
+
+#[test]
+fn untracked_cache_is_invalidated_for_changed_paths() {
+    let mut file = Fixture::Loose("UNTR").open();
+    let dir = |file: &gix_index::File, name: &str| {
+        file.untracked()
+            .expect("present")
+            .directories()
+            .iter()
+            .find(|d| d.name == name)
+            .expect("exists")
+            .clone()
+    };
+    assert!(dir(&file, "dtwo").stat.is_some());
+    assert_eq!(dir(&file, "dtwo").untracked_entries, ["two"]);
+
+    let entry = file.entry(0).clone();
+    file.upsert_entry(entry.stat, entry.id, entry.flags, entry.mode, "dtwo/two".into());
+    let dtwo = dir(&file, "dtwo");
+    assert!(
+        dtwo.stat.is_none() && dtwo.untracked_entries.is_empty(),
+        "the directory containing the path is invalidated"
+    );
+    assert!(
+        dir(&file, "").stat.is_some() && dir(&file, "dthree").stat.is_some(),
+        "as untracked directories aren't shown separately, other directories stay valid"
+    );
+
+    assert!(file.rename_entry("dtwo/two".into(), "three".into()));
+    assert!(
+        dir(&file, "").stat.is_none(),
+        "the root directory is invalidated as well now"
+    );
+    assert!(dir(&file, "dthree").stat.is_some());
+}
+
+#[test]
+fn fs_monitor_bitmap_follows_entries() -> crate::Result {
+    use gix_index::extension::FsMonitor;
+    fn dirty(file: &gix_index::File) -> Vec<usize> {
+        let mut out = Vec::new();
+        file.fs_monitor()
+            .expect("present")
+            .entry_dirty()
+            .for_each_set_bit(|idx| {
+                out.push(idx);
+                Some(())
+            });
+        out
+    }
+
+    let mut file = Fixture::Loose("FSMN").open();
+    assert_eq!(
+        paths(&file),
+        [
+            "dir1/modified",
+            "dir1/tracked",
+            "dir2/modified",
+            "dir2/tracked",
+            "modified",
+            "tracked"
+        ]
+    );
+    let token = file.fs_monitor().expect("present").token().clone();
+    file.set_fs_monitor(FsMonitor::new(token, gix_bitmap::ewah::Vec::from_set_bits([1, 4])));
+
+    let mut pathspec = gix_pathspec::Search::from_specs(
+        [gix_pathspec::parse(b"dir1/modified", Default::default())?],
+        None,
+        std::path::Path::new(""),
+    )?;
+    file.remove_entries_by_pathspec(&mut pathspec, &mut |_, _, _, _| unreachable!("no attributes"));
+    assert_eq!(dirty(&file), [0, 3], "bits move along with their entries");
+
+    let entry = file.entry(0).clone();
+    file.upsert_entry(entry.stat, entry.id, entry.flags, entry.mode, "dir1/new".into());
+    assert_eq!(dirty(&file), [0, 1, 4], "new entries aren't known to be valid");
+
+    assert!(file.rename_entry("tracked".into(), "tracked2".into()));
+    assert_eq!(dirty(&file), [0, 1, 4, 5], "neither are renamed ones");
+    assert!(
+        file.entries()
+            .iter()
+            .all(|e| !e.flags.contains(gix_index::entry::Flags::FSMONITOR_VALID)),
+        "flags are only used temporarily"
+    );
+    Ok(())
+}
+
+#[test]
+fn update_tree_only_writes_invalidated_trees_and_matches_git_write_tree() -> crate::Result {
+    struct CountingWrite {
+        inner: gix_odb::Handle,
+        num_writes: std::cell::Cell<usize>,
+    }
+    impl gix_object::Write for &CountingWrite {
+        fn write_stream(
+            &self,
+            kind: gix_object::Kind,
+            size: u64,
+            from: &mut dyn std::io::Read,
+        ) -> Result<gix_hash::ObjectId, gix_object::write::Error> {
+            self.num_writes.set(self.num_writes.get() + 1);
+            self.inner.write_stream(kind, size, from)
+        }
+    }
+    let git = |tmp: &std::path::Path, args: &[&str]| -> crate::Result<String> {
+        let out = std::process::Command::new("git").args(args).current_dir(tmp).output()?;
+        assert!(out.status.success(), "{out:?}");
+        Ok(out.stdout.to_str()?.trim().to_owned())
+    };
+
+    let tmp = gix_testtools::scripted_fixture_writable_standalone("make_index/v2_deeper_tree.sh")?;
+    let objects = CountingWrite {
+        inner: gix_odb::at(tmp.path().join(".git/objects"))?,
+        num_writes: Default::default(),
+    };
+    let mut file = gix_index::File::at(
+        tmp.path().join(".git/index"),
+        gix_hash::Kind::Sha1,
+        false,
+        Default::default(),
+    )?;
+    let head_tree = gix_hash::ObjectId::from_hex(git(tmp.path(), &["rev-parse", "@^{tree}"])?.as_bytes())?;
+
+    assert_eq!(file.update_tree(&objects)?, head_tree);
+    assert_eq!(objects.num_writes.get(), 0, "the tree cache is valid, nothing to write");
+
+    let valid_tree = file.remove_tree().expect("present");
+    assert_eq!(file.update_tree(&objects)?, head_tree);
+    assert_eq!(
+        objects.num_writes.get(),
+        8,
+        "the root, 'd', 'd/nested', 'sub' and its four subtrees are written"
+    );
+    assert_eq!(file.tree(), Some(&valid_tree), "the tree cache is fully recreated");
+
+    objects.num_writes.set(0);
+    let blob = gix_object::Write::write_buf(&&objects, gix_object::Kind::Blob, b"content")?;
+    let entry = file.entry_by_path("d/nested/1".into()).expect("present").clone();
+    file.upsert_entry(entry.stat, blob, entry.flags, entry.mode, "d/nested/1".into());
+    let new_tree = file.update_tree(&objects)?;
+    assert_eq!(
+        objects.num_writes.get(),
+        1 + 3,
+        "the blob and the trees leading to the changed entry are written"
+    );
+
+    let mut without_tree = file.clone();
+    without_tree.remove_tree();
+    without_tree.write(Default::default())?;
+    assert_eq!(
+        git(tmp.path(), &["write-tree"])?,
+        new_tree.to_string(),
+        "git computes the same tree without the help of a tree cache"
+    );
+
+    file.upsert_entry(
+        Default::default(),
+        gix_hash::ObjectId::empty_blob(gix_hash::Kind::Sha1),
+        gix_index::entry::Flags::INTENT_TO_ADD | gix_index::entry::Flags::EXTENDED,
+        gix_index::entry::Mode::FILE,
+        "d/new".into(),
+    );
+    assert_eq!(
+        file.update_tree(&objects)?,
+        new_tree,
+        "entries that are only intended to be added aren't part of the tree"
+    );
+    let tree = file.tree().expect("present");
+    assert_eq!(tree.num_entries, None, "but the tree cache remains invalid");
+    assert_eq!(tree.children[0].num_entries, None);
+    Ok(())
+}
+
+#[test]
+fn update_tree_excludes_entries_marked_for_removal_from_all_counts() -> crate::Result {
+    let tmp = gix_testtools::scripted_fixture_read_only_standalone("make_index/v2_deeper_tree.sh")?;
+    let objects = gix_odb::at(tmp.join(".git/objects"))?;
+    let mut file = gix_index::File::at(tmp.join(".git/index"), gix_hash::Kind::Sha1, false, Default::default())?;
+    file.remove_tree();
+
+    let mut expected = file.clone();
+    expected.remove_entries(|_, path, _| path == "sub/c/3");
+    let expected_tree = expected.update_tree(&objects)?;
+
+    file.entry_mut_by_path_and_stage("sub/c/3".into(), Stage::Unconflicted)
+        .expect("present")
+        .flags
+        .insert(gix_index::entry::Flags::REMOVE);
+    assert_eq!(file.update_tree(&objects)?, expected_tree);
+    assert_eq!(
+        file.tree(),
+        expected.tree(),
+        "the removed entry isn't counted in 'sub/c', nor in 'sub' or the root"
+    );
+    assert_eq!(
+        file.tree().and_then(|tree| tree.num_entries),
+        Some(file.entries().len() as u32 - 1)
+    );
+    Ok(())
+}
+
 #[test]
 fn sort_entries() {
     let mut file = Fixture::Generated("v4_more_files_IEOT").open();
@@ -311,3 +880,80 @@ fn check_prefix(index: &gix_index::State, prefix: &str, expected: &[&str]) {
         "{prefix:?}"
     );
 }
+
+#[test]
+fn convert_object_hash() -> crate::Result {
+    fn inverted(id: &gix_hash::oid) -> gix_hash::ObjectId {
+        gix_hash::ObjectId::from_bytes_or_panic(&id.as_bytes().iter().map(|b| !b).collect::<Vec<_>>())
+    }
+    fn tree_ids(tree: &gix_index::extension::Tree, out: &mut Vec<gix_hash::ObjectId>) {
+        out.push(tree.id);
+        for child in &tree.children {
+            tree_ids(child, out);
+        }
+    }
+    fn all_ids(state: &gix_index::State) -> Vec<gix_hash::ObjectId> {
+        let mut out: Vec<_> = state.entries().iter().map(|e| e.id).collect();
+        if let Some(tree) = state.tree() {
+            tree_ids(tree, &mut out);
+        }
+        out.extend(
+            state
+                .resolve_undo()
+                .into_iter()
+                .flatten()
+                .flat_map(|path| path.stages().iter().flatten().map(|stage| stage.id().to_owned())),
+        );
+        out
+    }
+
+    let mut file = Fixture::Loose("REUC").open();
+    assert!(file.tree().is_some() && file.resolve_undo().is_some());
+    let expected: Vec<_> = all_ids(&file).iter().map(|id| inverted(id)).collect();
+    file.convert_object_hash(gix_hash::Kind::Sha1, |id| {
+        Ok::<_, std::convert::Infallible>(inverted(id))
+    })?;
+    assert_eq!(
+        all_ids(&file),
+        expected,
+        "entries, the tree-cache and resolve-undo ids are all mapped"
+    );
+
+    let mut buf = Vec::new();
+    file.write_to(&mut buf, Default::default())?;
+    let (actual, _) = gix_index::State::from_bytes(
+        &buf,
+        filetime::FileTime::now(),
+        gix_hash::Kind::Sha1,
+        Default::default(),
+    )?;
+    assert_eq!(all_ids(&actual), expected, "the mapped ids are written");
+
+    let mut file = Fixture::Loose("UNTR").open();
+    assert!(file.untracked().is_some());
+    let before = all_ids(&file);
+    let err = file
+        .convert_object_hash(gix_hash::Kind::Sha1, |id| {
+            if id == before[1] {
+                Err(std::io::Error::new(std::io::ErrorKind::NotFound, "unknown"))
+            } else {
+                Ok(inverted(id))
+            }
+        })
+        .unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        format!("Could not map object id {} to its SHA1 counterpart", before[1])
+    );
+    assert_eq!(all_ids(&file), before, "nothing changes on error");
+    assert!(file.untracked().is_some());
+
+    file.convert_object_hash(gix_hash::Kind::Sha1, |id| {
+        Ok::<_, std::convert::Infallible>(inverted(id))
+    })?;
+    assert!(
+        file.untracked().is_none(),
+        "the untracked cache refers to files, not objects, so it's removed"
+    );
+    Ok(())
+}
@@ -0,0 +1,133 @@
+use bstr::ByteSlice;
+use filetime::FileTime;
+use gix_index::{
+    conflict::{resolve, Resolution, Side},
+    entry::{Mode, Stage},
+    State,
+};
+
+use crate::{hex_to_id, index::Fixture};
+
+fn side(hex: &str) -> Option<Side> {
+    Some(Side {
+        mode: Mode::FILE,
+        id: hex_to_id(hex),
+    })
+}
+
+fn conflicts(state: &State) -> Vec<(&str, [Option<gix_hash::ObjectId>; 3])> {
+    state
+        .conflicts()
+        .map(|c| {
+            (
+                c.path.to_str().expect("valid"),
+                [c.base, c.ours, c.theirs].map(|e| e.map(|e| e.id)),
+            )
+        })
+        .collect()
+}
+
+const BASE: &str = "1111111111111111111111111111111111111111";
+const OURS: &str = "2222222222222222222222222222222222222222";
+const THEIRS: &str = "3333333333333333333333333333333333333333";
+
+#[test]
+fn conflicts_of_index_written_by_git() {
+    let file = Fixture::Loose("conflicting-file").open();
+    let actual = conflicts(&file);
+    assert_eq!(actual.len(), 1, "all stages belong to a single path");
+    assert!(
+        actual[0].1.iter().all(Option::is_some),
+        "the file was changed on both sides"
+    );
+}
+
+#[test]
+fn set_and_resolve_conflict_with_resolve_undo_roundtrip() -> crate::Result {
+    let mut state = State::new(gix_hash::Kind::Sha1);
+    state.upsert_entry(
+        Default::default(),
+        hex_to_id(BASE),
+        gix_index::entry::Flags::empty(),
+        Mode::FILE,
+        "a".into(),
+    );
+    state.set_conflict("a".into(), side(BASE), side(OURS), side(THEIRS));
+    state.set_conflict("b/c".into(), None, side(OURS), None);
+    assert_eq!(
+        conflicts(&state),
+        [
+            (
+                "a",
+                [Some(hex_to_id(BASE)), Some(hex_to_id(OURS)), Some(hex_to_id(THEIRS))]
+            ),
+            ("b/c", [None, Some(hex_to_id(OURS)), None])
+        ],
+        "the unconflicted entry was replaced"
+    );
+
+    state.resolve_conflict("a".into(), Resolution::Take(Stage::Theirs))?;
+    state.resolve_conflict("b/c".into(), Resolution::Remove)?;
+    assert_eq!(conflicts(&state), [], "nothing is conflicted anymore");
+    assert_eq!(state.entries().len(), 1);
+    let entry = &state.entries()[0];
+    assert_eq!(
+        (entry.path(&state), entry.stage(), entry.id),
+        ("a".into(), Stage::Unconflicted, hex_to_id(THEIRS))
+    );
+
+    let mut buf = Vec::new();
+    gix_index::File::from_state(state, "index").write_to(&mut buf, Default::default())?;
+    let (state, _) = State::from_bytes(&buf, FileTime::now(), gix_hash::Kind::Sha1, Default::default())?;
+    let reuc = state.resolve_undo().expect("resolutions were recorded");
+    assert_eq!(
+        reuc.iter()
+            .map(|p| (
+                p.name().to_str().expect("valid"),
+                p.stages().map(|s| s.map(|s| (s.mode(), s.id().to_owned())))
+            ))
+            .collect::<Vec<_>>(),
+        [
+            (
+                "a",
+                [
+                    Some((0o100644, hex_to_id(BASE))),
+                    Some((0o100644, hex_to_id(OURS))),
+                    Some((0o100644, hex_to_id(THEIRS)))
+                ]
+            ),
+            ("b/c", [None, Some((0o100644, hex_to_id(OURS))), None])
+        ],
+        "the stages are written to and read from the REUC extension"
+    );
+
+    let mut state = state;
+    state.set_conflict("a".into(), side(BASE), side(OURS), side(THEIRS));
+    assert_eq!(
+        state.resolve_undo().map(Vec::len),
+        Some(1),
+        "putting a path back into conflict removes its record"
+    );
+    Ok(())
+}
+
+#[test]
+fn resolving_needs_a_conflict_and_the_chosen_stage() {
+    let mut state = State::new(gix_hash::Kind::Sha1);
+    assert!(matches!(
+        state.resolve_conflict("a".into(), Resolution::Remove),
+        Err(resolve::Error::NotConflicted { .. })
+    ));
+
+    state.set_conflict("a".into(), None, side(OURS), side(THEIRS));
+    assert!(matches!(
+        state.resolve_conflict("a".into(), Resolution::Take(Stage::Base)),
+        Err(resolve::Error::MissingStage { stage: Stage::Base, .. })
+    ));
+    assert!(matches!(
+        state.resolve_conflict("a".into(), Resolution::Take(Stage::Unconflicted)),
+        Err(resolve::Error::MissingStage { .. })
+    ));
+    assert_eq!(conflicts(&state).len(), 1, "failed resolutions don't change anything");
+    assert!(state.resolve_undo().is_none());
+}
@@ -0,0 +1,122 @@
+use bstr::ByteSlice;
+use gix_index::{
+    conflict::Side,
+    diff::Change,
+    entry::{Mode, Stage},
+    State,
+};
+use gix_testtools::scripted_fixture_read_only_standalone;
+
+use crate::hex_to_id;
+
+fn fixture() -> crate::Result<(gix_index::File, gix_odb::Handle, gix_hash::ObjectId, String)> {
+    let worktree_dir = scripted_fixture_read_only_standalone("make_index/v2_changes_against_tree.sh")?;
+    let git_dir = worktree_dir.join(".git");
+    let index = gix_index::File::at(git_dir.join("index"), gix_hash::Kind::Sha1, false, Default::default())?;
+    let odb = gix_odb::at(git_dir.join("objects"))?;
+    let head_tree = hex_to_id(std::fs::read_to_string(worktree_dir.join("head.tree"))?.trim());
+    let baseline = std::fs::read_to_string(worktree_dir.join("baseline.raw"))?;
+    Ok((index, odb, head_tree, baseline))
+}
+
+/// Format `changes` like `git diff-index --no-abbrev` does.
+fn to_raw(changes: &[Change]) -> String {
+    let null = gix_hash::Kind::Sha1.null();
+    changes
+        .iter()
+        .map(|change| {
+            let (previous_mode, previous_id, mode, id, status) = match change {
+                Change::Addition { entry_mode, id, .. } => (Mode::empty(), null, *entry_mode, *id, 'A'),
+                Change::Deletion { entry_mode, id, .. } => (*entry_mode, *id, Mode::empty(), null, 'D'),
+                Change::Modification {
+                    previous_entry_mode,
+                    previous_id,
+                    entry_mode,
+                    id,
+                    ..
+                } => (*previous_entry_mode, *previous_id, *entry_mode, *id, 'M'),
+                Change::Unmerged { .. } => (Mode::empty(), null, Mode::empty(), null, 'U'),
+            };
+            format!(
+                ":{:06o} {:06o} {previous_id} {id} {status}\t{}\n",
+                previous_mode.bits(),
+                mode.bits(),
+                change.location()
+            )
+        })
+        .collect()
+}
+
+#[test]
+fn changes_against_tree_match_git() -> crate::Result {
+    let (index, odb, head_tree, baseline) = fixture()?;
+    assert!(
+        index.tree().is_some(),
+        "the tree cache is partially valid, so unchanged directories are skipped"
+    );
+    let changes = index.changes_against_tree(&head_tree, &odb)?;
+    assert_eq!(to_raw(&changes), baseline);
+
+    let added = changes
+        .iter()
+        .find_map(|change| match change {
+            Change::Addition { location, index, .. } if location == "dir/sub/new" => Some(*index),
+            _ => None,
+        })
+        .expect("present");
+    assert_eq!(
+        index.entry(added).path(&index),
+        "dir/sub/new",
+        "the index entry can be accessed directly"
+    );
+    Ok(())
+}
+
+#[test]
+fn unchanged_trees_are_not_read_if_the_tree_cache_is_valid() -> crate::Result {
+    let (_index, odb, head_tree, _baseline) = fixture()?;
+    let index = State::from_tree(&head_tree, &odb, Default::default())?;
+    assert_eq!(
+        index.changes_against_tree(&head_tree, gix_object::find::Never)?,
+        Vec::new(),
+        "no object is accessed as the root tree is known to be unchanged"
+    );
+    Ok(())
+}
+
+#[test]
+fn conflicts_are_unmerged() -> crate::Result {
+    let (mut index, odb, head_tree, _baseline) = fixture()?;
+    let side = Some(Side {
+        mode: Mode::FILE,
+        id: hex_to_id("1111111111111111111111111111111111111111"),
+    });
+    index.set_conflict("a".into(), side, side, None);
+    index.set_conflict("z-new".into(), None, side, side);
+
+    let changes = index.changes_against_tree(&head_tree, &odb)?;
+    let unmerged: Vec<_> = changes
+        .iter()
+        .filter_map(|change| match change {
+            Change::Unmerged { location, index: idx } => Some((location.to_str().expect("valid"), *idx)),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(unmerged.len(), 2, "each conflicting path is listed once");
+    for (location, idx) in unmerged {
+        let entry = index.entry(idx);
+        assert_eq!(entry.path(&index), location);
+        assert_ne!(
+            entry.stage(),
+            Stage::Unconflicted,
+            "it points to the first conflicting stage"
+        );
+    }
+    assert!(
+        changes
+            .iter()
+            .all(|change| change.location() != "a" || matches!(change, Change::Unmerged { .. })),
+        "conflicting entries aren't compared to the tree"
+    );
+    Ok(())
+}
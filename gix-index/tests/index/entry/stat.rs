@@ -239,6 +239,28 @@ mod matches {
     }
 }
 
+#[test]
+fn matches_fs() -> crate::Result {
+    let tmp = gix_testtools::tempfile::TempDir::new()?;
+    let path = tmp.path().join("file");
+    std::fs::write(&path, b"content")?;
+    let stat = Stat::from_fs(&gix_index::fs::Metadata::from_path_no_follow(&path)?)?;
+    assert!(stat.matches_fs(
+        &gix_index::fs::Metadata::from_path_no_follow(&path)?,
+        Options::default()
+    ));
+
+    std::fs::write(&path, b"changed content")?;
+    assert!(
+        !stat.matches_fs(
+            &gix_index::fs::Metadata::from_path_no_follow(&path)?,
+            Options::default()
+        ),
+        "a change in size is always detected"
+    );
+    Ok(())
+}
+
 #[test]
 fn is_racy() {
     let stat1 = Stat {
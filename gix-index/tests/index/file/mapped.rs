@@ -0,0 +1,76 @@
+use bstr::ByteSlice;
+use gix_index::mapped;
+
+fn mapped_file(name: &str) -> Result<mapped::File, mapped::Error> {
+    mapped::File::at(
+        crate::fixture_index_path(name),
+        gix_hash::Kind::Sha1,
+        false,
+        Default::default(),
+    )
+}
+
+#[test]
+fn entries_are_the_same_as_in_the_decoded_file() -> crate::Result {
+    for name in ["v2_more_files", "v3_added_files", "v2_all_file_kinds"] {
+        let mapped = mapped_file(name)?;
+        let decoded = crate::index::file::read::file(name);
+        assert_eq!(mapped.len(), decoded.entries().len());
+        assert_eq!(mapped.version(), decoded.version());
+        assert_eq!(mapped.checksum(), decoded.checksum());
+        for (actual, expected) in mapped.entries().zip(decoded.entries()) {
+            assert_eq!(actual, mapped::EntryRef::from_entry(expected, &decoded));
+            assert_eq!(
+                mapped.entry_by_path(actual.path),
+                decoded
+                    .entry_by_path(actual.path)
+                    .map(|e| mapped::EntryRef::from_entry(e, &decoded)),
+                "lookups by path work the same"
+            );
+        }
+        assert_eq!(mapped.entry_by_path("does-not-exist".as_bytes().as_bstr()), None);
+    }
+    Ok(())
+}
+
+#[test]
+fn unsupported_files_are_decoded_by_index() -> crate::Result {
+    for name in ["v4_more_files_IEOT", "v2_split_index"] {
+        assert!(matches!(
+            mapped_file(name),
+            Err(mapped::Error::DeltaPaths | mapped::Error::SplitIndex)
+        ));
+        let index = mapped::Index::at(
+            crate::fixture_index_path(name),
+            gix_hash::Kind::Sha1,
+            false,
+            Default::default(),
+        )?;
+        assert!(!index.is_mapped());
+        assert_eq!(index.len(), crate::index::file::read::file(name).entries().len());
+    }
+    Ok(())
+}
+
+#[test]
+fn index_is_decoded_on_first_mutation() -> crate::Result {
+    let mut index = mapped::Index::at(
+        crate::fixture_index_path("v2_more_files"),
+        gix_hash::Kind::Sha1,
+        false,
+        Default::default(),
+    )?;
+    assert!(index.is_mapped());
+    let entries: Vec<_> = index.entries().map(|e| e.path.to_owned()).collect();
+
+    let file = index.to_mut()?;
+    file.remove_entries(|idx, _, _| idx == 0);
+    assert!(!index.is_mapped());
+    assert_eq!(index.len(), entries.len() - 1);
+    assert_eq!(
+        index.entries().map(|e| e.path.to_owned()).collect::<Vec<_>>(),
+        entries[1..],
+        "mutations are visible"
+    );
+    Ok(())
+}
@@ -1,4 +1,5 @@
 mod access;
 mod init;
+mod mapped;
 mod read;
 mod write;
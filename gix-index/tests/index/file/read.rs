@@ -1,6 +1,7 @@
 use std::path::{Path, PathBuf};
 
 use bstr::ByteSlice;
+use filetime::FileTime;
 use gix_index::{
     entry::{self, Flags, Mode},
     Version,
@@ -181,7 +182,16 @@ fn reuc_extension() {
     let file = loose_file("REUC");
     assert_eq!(file.version(), Version::V2);
 
-    assert!(file.resolve_undo().is_some());
+    let paths = file.resolve_undo().expect("present");
+    assert_eq!(paths.len(), 1);
+    let path = &paths[0];
+    assert_eq!(path.name(), "fi/le");
+    assert!(
+        path.stages()
+            .iter()
+            .all(|stage| stage.map_or(false, |stage| stage.mode() == 0o100644)),
+        "all three stages are present"
+    );
 }
 
 #[test]
@@ -205,7 +215,14 @@ fn fsmn_v1() {
     let file = loose_file("FSMN");
     assert_eq!(file.version(), Version::V2);
 
-    assert!(file.fs_monitor().is_some());
+    let fsmn = file.fs_monitor().expect("present");
+    assert_eq!(
+        fsmn.token(),
+        &gix_index::extension::fs_monitor::Token::V2 {
+            token: "1642331326943378000".into()
+        }
+    );
+    assert_eq!(fsmn.entry_dirty().num_bits(), 6, "all entries are marked dirty");
 }
 
 #[test]
@@ -254,6 +271,33 @@ fn v4_with_delta_paths_and_ieot_ext() {
     }
 }
 
+#[test]
+fn v4_with_corrupt_ieot_ext_is_read_sequentially() -> crate::Result {
+    let data = std::fs::read(crate::fixture_index_path("v4_more_files_IEOT"))?;
+    let options = gix_index::decode::Options {
+        thread_limit: Some(4),
+        min_extension_block_in_bytes_for_threading: 0,
+        expected_checksum: None,
+    };
+    let (expected, _) = gix_index::State::from_bytes(&data, FileTime::now(), gix_hash::Kind::Sha1, options)?;
+    assert_eq!(expected.entries().len(), 10);
+
+    let ieot = data.find(b"IEOT").expect("extension present");
+    let first_offset = ieot + 4 /* signature */ + 4 /* size */ + 4 /* version */;
+    for (field_offset, corrupt_value) in [(first_offset, u32::MAX), (first_offset + 4, 3), (first_offset, 0)] {
+        let mut data = data.clone();
+        data[field_offset..][..4].copy_from_slice(&corrupt_value.to_be_bytes());
+        let (actual, _) = gix_index::State::from_bytes(&data, FileTime::now(), gix_hash::Kind::Sha1, options)?;
+        assert_eq!(
+            actual.entries(),
+            expected.entries(),
+            "entries are read as if there was no offset table"
+        );
+        assert_eq!(actual.path_backing(), expected.path_backing());
+    }
+    Ok(())
+}
+
 #[test]
 fn sparse_checkout_non_sparse_index() {
     let file = file("v3_skip_worktree");
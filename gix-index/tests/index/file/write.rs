@@ -15,11 +15,34 @@ fn roundtrips() -> crate::Result {
             options_with(write::Extensions::Given {
                 tree_cache: true,
                 end_of_index_entry: true,
+                offset_table: false,
+                resolve_undo: false,
+                untracked_cache: false,
+                fs_monitor: false,
             }),
         ),
         (Generated("V2_empty"), only_tree_ext()),
         (Generated("v2_more_files"), only_tree_ext()),
         (Generated("v2_all_file_kinds"), only_tree_ext()),
+        (Generated("v4_more_files"), only_tree_ext()),
+        (Loose("UNTR"), tree_and_untracked_cache_ext()),
+        (Loose("UNTR-with-oids"), tree_and_untracked_cache_ext()),
+        (Loose("FSMN"), tree_and_fs_monitor_ext()),
+        (Loose("REUC"), tree_and_resolve_undo_ext()),
+        (
+            Generated("v4_more_files_IEOT"),
+            Options {
+                offset_table_blocks: 2,
+                ..options_with(write::Extensions::Given {
+                    tree_cache: true,
+                    end_of_index_entry: true,
+                    offset_table: true,
+                    resolve_undo: false,
+                    untracked_cache: false,
+                    fs_monitor: false,
+                })
+            },
+        ),
     ];
 
     for (fixture, options) in input {
@@ -46,8 +69,13 @@ fn skip_hash() -> crate::Result {
 
     expected.set_path(&path);
     expected.write(Options {
+        version: write::Version::Auto,
         extensions: Default::default(),
         skip_hash: false,
+        offset_table_blocks: 0,
+        fsync: Default::default(),
+        smudge_racy_entries: None,
+        verify: None,
     })?;
 
     let actual = gix_index::File::at(
@@ -63,8 +91,13 @@ fn skip_hash() -> crate::Result {
     );
 
     expected.write(Options {
+        version: write::Version::Auto,
         extensions: Default::default(),
         skip_hash: true,
+        offset_table_blocks: 0,
+        fsync: Default::default(),
+        smudge_racy_entries: None,
+        verify: None,
     })?;
 
     let actual = gix_index::File::at(
@@ -78,6 +111,228 @@ fn skip_hash() -> crate::Result {
     Ok(())
 }
 
+#[test]
+fn git_accepts_the_trailing_hash() -> crate::Result {
+    let tmp = gix_testtools::scripted_fixture_writable_standalone("make_index/v2_more_files.sh")?;
+    let path = tmp.path().join(".git").join("index");
+    let fsck = || gix_testtools::run_git(tmp.path(), &["fsck", "--no-progress", "--no-dangling"]);
+
+    for version in [Version::V2, Version::V3, Version::V4] {
+        let mut index = gix_index::File::at(&path, gix_hash::Kind::Sha1, false, Default::default())?;
+        index.write(Options {
+            version: write::Version::Exactly(version),
+            offset_table_blocks: 2,
+            ..Default::default()
+        })?;
+        let checksum = index.checksum().expect("written");
+        let bytes = std::fs::read(&path)?;
+        assert_eq!(
+            &bytes[bytes.len() - checksum.as_slice().len()..],
+            checksum.as_slice(),
+            "the digest of everything before it is appended"
+        );
+        assert!(
+            fsck()?.success(),
+            "`git fsck` verifies the trailing hash of {version:?}"
+        );
+    }
+
+    let mut bytes = std::fs::read(&path)?;
+    *bytes.last_mut().expect("non-empty") ^= 1;
+    std::fs::write(&path, bytes)?;
+    assert!(
+        !fsck()?.success(),
+        "a corrupt hash is detected, so the check above is meaningful"
+    );
+    Ok(())
+}
+
+#[test]
+fn write_is_atomic_and_respects_locks() -> crate::Result {
+    let tmp = gix_testtools::tempfile::TempDir::new()?;
+    let path = tmp.path().join("index");
+    let lock_path = tmp.path().join("index.lock");
+    let mut index = Loose("conflicting-file").open();
+    index.set_path(&path);
+    let options = Options {
+        fsync: gix_features::fs::Fsync::PerFile,
+        ..Default::default()
+    };
+    for fsync in [gix_features::fs::Fsync::WriteoutOnly, gix_features::fs::Fsync::Batch] {
+        index.write(Options {
+            fsync,
+            ..Default::default()
+        })?;
+    }
+    index.write(options)?;
+    assert!(!lock_path.exists(), "the lock is gone after the index was written");
+    let written = std::fs::read(&path)?;
+
+    std::fs::write(&lock_path, b"")?;
+    index.remove_entries(|_, _, _| true);
+    assert!(
+        matches!(index.write(options), Err(gix_index::file::write::Error::AcquireLock(_))),
+        "writing fails if another process holds the lock"
+    );
+    assert_eq!(std::fs::read(&path)?, written, "the index on disk remains unchanged");
+    assert!(lock_path.is_file(), "locks of other processes are left alone");
+    std::fs::remove_file(&lock_path)?;
+
+    index.write(options)?;
+    let actual = gix_index::File::at(&path, gix_hash::Kind::Sha1, false, Default::default())?;
+    assert_eq!(actual.entries().len(), 0, "the index was replaced");
+    assert!(!lock_path.exists());
+    Ok(())
+}
+
+#[test]
+fn split_index() -> crate::Result {
+    let base = gix_testtools::scripted_fixture_read_only_standalone(
+        std::path::Path::new("make_index").join("v2_split_vs_regular_index.sh"),
+    )?;
+    let tmp = gix_testtools::tempfile::TempDir::new()?;
+    for entry in std::fs::read_dir(base.join("split/.git"))? {
+        let entry = entry?;
+        let name = entry.file_name();
+        if name == "index" || name.to_string_lossy().starts_with("sharedindex.") {
+            std::fs::copy(entry.path(), tmp.path().join(name))?;
+        }
+    }
+    let path = tmp.path().join("index");
+    let open = || gix_index::File::at(&path, gix_hash::Kind::Sha1, false, Default::default());
+    let shared_indices = || -> std::io::Result<usize> {
+        Ok(std::fs::read_dir(tmp.path())?
+            .filter(|e| {
+                e.as_ref()
+                    .map_or(false, |e| e.file_name().to_string_lossy().starts_with("sharedindex."))
+            })
+            .count())
+    };
+    let entries = |index: &gix_index::File| {
+        index
+            .entries()
+            .iter()
+            .map(|e| (e.path(index).to_owned(), e.id, e.flags))
+            .collect::<Vec<_>>()
+    };
+
+    let mut index = open()?;
+    assert_eq!(shared_indices()?, 1);
+    let empty_blob = gix_hash::ObjectId::empty_blob(gix_hash::Kind::Sha1);
+    index.entries_mut()[0].id = empty_blob;
+    index.remove_entries(|idx, _, _| idx == 1);
+    index.dangerously_push_entry(
+        Default::default(),
+        empty_blob,
+        entry::Flags::empty(),
+        entry::Mode::FILE,
+        "f-new".into(),
+    );
+    index.sort_entries();
+    let expected = entries(&index);
+
+    index.write_split(Default::default(), 100, None)?;
+    let actual = open()?;
+    assert_eq!(
+        entries(&actual),
+        expected,
+        "replacements, deletions and additions are applied"
+    );
+    assert_eq!(shared_indices()?, 1, "the existing shared index was used");
+    assert_eq!(
+        entries(&index),
+        expected,
+        "the in-memory index still has all entries after writing"
+    );
+
+    let mut index = actual;
+    index.write_split(Default::default(), 0, None)?;
+    assert_eq!(shared_indices()?, 2, "a new shared index was written");
+    let actual = open()?;
+    assert_eq!(entries(&actual), expected);
+    let mut regular = Generated("v2_more_files").open();
+    let expected = entries(&regular);
+    regular.set_path(tmp.path().join("other-index"));
+    regular.write_split(Default::default(), 20, None)?;
+    assert_eq!(shared_indices()?, 3, "a shared index is created for regular indices");
+    let actual = gix_index::File::at(
+        tmp.path().join("other-index"),
+        gix_hash::Kind::Sha1,
+        false,
+        Default::default(),
+    )?;
+    assert_eq!(entries(&actual), expected);
+    assert!(actual.link().is_none(), "the link is dissolved when reading");
+    Ok(())
+}
+
+#[test]
+fn split_index_is_readable_by_git_and_expires_unused_shared_indices() -> crate::Result {
+    let tmp = gix_testtools::scripted_fixture_writable_standalone("make_index/v2_split_vs_regular_index.sh")?;
+    let worktree = tmp.path().join("split");
+    let git_dir = worktree.join(".git");
+    let shared_indices = || -> std::io::Result<Vec<std::ffi::OsString>> {
+        Ok(std::fs::read_dir(&git_dir)?
+            .filter_map(Result::ok)
+            .map(|e| e.file_name())
+            .filter(|name| name.to_string_lossy().starts_with("sharedindex."))
+            .collect())
+    };
+    let ls_files = || -> crate::Result<Vec<String>> {
+        let out = std::process::Command::new("git")
+            .args(["ls-files", "--stage"])
+            .current_dir(&worktree)
+            .output()?;
+        assert!(out.status.success(), "{out:?}");
+        Ok(String::from_utf8(out.stdout)?.lines().map(ToOwned::to_owned).collect())
+    };
+    let expected_ls_files = |index: &gix_index::File| {
+        index
+            .entries()
+            .iter()
+            .map(|e| format!("{:06o} {} 0\t{}", e.mode.bits(), e.id, e.path(index)))
+            .collect::<Vec<_>>()
+    };
+
+    let mut index = gix_index::File::at(git_dir.join("index"), gix_hash::Kind::Sha1, false, Default::default())?;
+    let previous_shared_indices = shared_indices()?;
+    assert_eq!(previous_shared_indices.len(), 1);
+    let empty_blob = gix_hash::ObjectId::empty_blob(gix_hash::Kind::Sha1);
+    index.entries_mut()[0].id = empty_blob;
+    index.remove_entries(|idx, _, _| idx == 1);
+    index.dangerously_push_entry(
+        Default::default(),
+        empty_blob,
+        entry::Flags::empty(),
+        entry::Mode::FILE,
+        "f-new".into(),
+    );
+    index.sort_entries();
+
+    index.write_split(Default::default(), 100, Some(std::time::SystemTime::now()))?;
+    assert_eq!(
+        shared_indices()?,
+        previous_shared_indices,
+        "the existing shared index is used, and it's never expired"
+    );
+    assert_eq!(
+        ls_files()?,
+        expected_ls_files(&index),
+        "git applies our changes to the shared index"
+    );
+
+    index.write_split(Default::default(), 0, None)?;
+    assert_eq!(shared_indices()?.len(), 2, "a new shared index was written");
+    assert_eq!(ls_files()?, expected_ls_files(&index));
+
+    index.write_split(Default::default(), 0, Some(std::time::SystemTime::now()))?;
+    let current = shared_indices()?;
+    assert_eq!(current.len(), 1, "all unused shared indices were expired");
+    assert!(!previous_shared_indices.contains(&current[0]));
+    assert_eq!(ls_files()?, expected_ls_files(&index), "git can still read the index");
+    Ok(())
+}
+
 #[test]
 fn roundtrips_sparse_index() -> crate::Result {
     // NOTE: I initially tried putting these fixtures into the main roundtrip test above,
@@ -133,9 +388,8 @@ fn state_comparisons_with_various_extension_configurations() {
         Generated("v2_more_files"),
         Generated("v2_all_file_kinds"),
         Generated("v2_split_index"),
-        // TODO: this fails because git allows to configure the index version while gitoxide doesn't
-        //       the fixture artificially sets the version to V4 and gitoxide writes it back out as the lowest required version, V2
-        // Generated("v4_more_files_IEOT"),
+        Generated("v4_more_files"),
+        Generated("v4_more_files_IEOT"),
         Generated("v3_skip_worktree"),
         Generated("v3_added_files"),
         Generated("v3_sparse_index_non_cone"),
@@ -149,10 +403,42 @@ fn state_comparisons_with_various_extension_configurations() {
             options_with(write::Extensions::Given {
                 tree_cache: true,
                 end_of_index_entry: false,
+                offset_table: false,
+                resolve_undo: false,
+                untracked_cache: false,
+                fs_monitor: false,
             }),
             options_with(write::Extensions::Given {
                 tree_cache: false,
                 end_of_index_entry: true,
+                offset_table: false,
+                resolve_undo: false,
+                untracked_cache: false,
+                fs_monitor: false,
+            }),
+            options_with(write::Extensions::Given {
+                tree_cache: false,
+                end_of_index_entry: false,
+                offset_table: false,
+                resolve_undo: false,
+                untracked_cache: true,
+                fs_monitor: false,
+            }),
+            options_with(write::Extensions::Given {
+                tree_cache: false,
+                end_of_index_entry: false,
+                offset_table: false,
+                resolve_undo: false,
+                untracked_cache: false,
+                fs_monitor: true,
+            }),
+            options_with(write::Extensions::Given {
+                tree_cache: false,
+                end_of_index_entry: false,
+                offset_table: false,
+                resolve_undo: true,
+                untracked_cache: false,
+                fs_monitor: false,
             }),
         ] {
             let expected = fixture.open();
@@ -181,6 +467,238 @@ fn extended_flags_automatically_upgrade_the_version_to_avoid_data_loss() -> crat
     Ok(())
 }
 
+#[test]
+fn explicit_versions_are_written_if_they_can_represent_all_entries() -> crate::Result {
+    let mut expected = Generated("v2").open();
+    for version in [Version::V3, Version::V4] {
+        let mut buf = Vec::new();
+        let (actual_version, _digest) = expected.write_to(
+            &mut buf,
+            Options {
+                version: write::Version::Exactly(version),
+                ..Default::default()
+            },
+        )?;
+        assert_eq!(actual_version, version, "higher versions can always be written");
+        let (actual, _) = State::from_bytes(&buf, FileTime::now(), gix_hash::Kind::Sha1, Default::default())?;
+        assert_eq!(actual.version(), version);
+        assert_eq!(actual.entries(), expected.entries());
+    }
+
+    expected.set_version(Version::V4);
+    let (actual_version, _digest) = expected.write_to(
+        &mut Vec::new(),
+        Options {
+            version: write::Version::Exactly(Version::V2),
+            ..Default::default()
+        },
+    )?;
+    assert_eq!(actual_version, Version::V2, "the version of the state is ignored");
+
+    expected.entries_mut()[0].flags.insert(entry::Flags::EXTENDED);
+    let err = expected
+        .write_to(
+            &mut Vec::new(),
+            Options {
+                version: write::Version::Exactly(Version::V2),
+                ..Default::default()
+            },
+        )
+        .unwrap_err();
+    assert!(
+        matches!(
+            err,
+            write::Error::VersionTooLow {
+                requested: Version::V2,
+                required: Version::V3
+            }
+        ),
+        "extended flags can't be represented in V2"
+    );
+    Ok(())
+}
+
+#[test]
+fn extended_flags_are_written_even_without_the_extended_bit() -> crate::Result {
+    let mut expected = Generated("v2").open();
+    let entry = &mut expected.entries_mut()[0];
+    entry.flags.insert(entry::Flags::SKIP_WORKTREE);
+    assert!(!entry.flags.contains(entry::Flags::EXTENDED));
+
+    let mut buf = Vec::new();
+    let (actual_version, _digest) = expected.write_to(&mut buf, Default::default())?;
+    assert_eq!(actual_version, Version::V3, "skip-worktree can only be stored in V3");
+
+    let (actual, _) = State::from_bytes(&buf, FileTime::now(), gix_hash::Kind::Sha1, Default::default())?;
+    assert_eq!(
+        actual.entries()[0].flags,
+        expected.entries()[0].flags | entry::Flags::EXTENDED,
+        "the flag survives the round-trip, and the entry is marked as extended"
+    );
+    Ok(())
+}
+
+#[test]
+fn v4_writes_prefix_compressed_paths() -> crate::Result {
+    let mut index = Generated("v2_more_files").open();
+    assert_eq!(index.version(), Version::V2);
+    let mut v2 = Vec::new();
+    index.write_to(&mut v2, Default::default())?;
+
+    index.set_version(Version::V4);
+    let mut v4 = Vec::new();
+    let (actual_version, _digest) = index.write_to(&mut v4, Default::default())?;
+    assert_eq!(actual_version, Version::V4, "V4 is kept if it was set");
+    assert!(
+        v4.len() < v2.len(),
+        "paths are compressed and entries aren't padded: {} < {}",
+        v4.len(),
+        v2.len()
+    );
+
+    let (actual, _) = State::from_bytes(&v4, FileTime::now(), gix_hash::Kind::Sha1, Default::default())?;
+    assert_eq!(actual.version(), Version::V4);
+    assert_eq!(actual.entries(), index.entries());
+    assert_eq!(actual.path_backing(), index.path_backing());
+    Ok(())
+}
+
+#[test]
+fn metadata_is_preserved() -> crate::Result {
+    let mut index = Generated("v2").open();
+    assert!(index.metadata().is_none());
+    let metadata = index.metadata_mut();
+    assert_eq!(metadata.set("build", "a", "hash-of-a"), None);
+    metadata.set("build", "b", Vec::new());
+    metadata.set("other-tool", "a", [1, 2, 3]);
+    assert_eq!(
+        metadata.set("build", "a", "new-hash-of-a").as_deref(),
+        Some(&b"hash-of-a"[..])
+    );
+    let expected = index.metadata().cloned();
+
+    for extensions in [
+        write::Extensions::All,
+        write::Extensions::Given {
+            tree_cache: false,
+            end_of_index_entry: false,
+            offset_table: false,
+            resolve_undo: false,
+            untracked_cache: false,
+            fs_monitor: false,
+        },
+    ] {
+        let mut buf = Vec::new();
+        index.write_to(&mut buf, options_with(extensions))?;
+        let (actual, _) = State::from_bytes(&buf, FileTime::now(), gix_hash::Kind::Sha1, Default::default())?;
+        assert_eq!(actual.metadata().cloned(), expected, "metadata is always written");
+        let metadata = actual.metadata().expect("present");
+        assert_eq!(metadata.namespaces().collect::<Vec<_>>(), ["build", "other-tool"]);
+        assert_eq!(metadata.get("build".into(), "a".into()), Some(&b"new-hash-of-a"[..]));
+        assert_eq!(metadata.get("build".into(), "b".into()), Some(&b""[..]));
+        assert_eq!(metadata.namespace("other-tool".into()).count(), 1);
+    }
+
+    let mut buf = Vec::new();
+    index.write_to(&mut buf, options_with(write::Extensions::None))?;
+    let (actual, _) = State::from_bytes(&buf, FileTime::now(), gix_hash::Kind::Sha1, Default::default())?;
+    assert!(actual.metadata().is_none());
+
+    let metadata = index.metadata_mut();
+    assert!(metadata.remove("other-tool".into(), "a".into()).is_some());
+    assert!(metadata.remove_namespace("build".into()));
+    assert!(metadata.is_empty());
+    let mut buf = Vec::new();
+    index.write_to(&mut buf, Default::default())?;
+    let (actual, _) = State::from_bytes(&buf, FileTime::now(), gix_hash::Kind::Sha1, Default::default())?;
+    assert!(actual.metadata().is_none(), "empty metadata isn't written");
+    Ok(())
+}
+
+#[test]
+fn new_untracked_cache_is_written_without_directories() -> crate::Result {
+    let mut index = Generated("v2").open();
+    let identifier = extension::untracked_cache::identifier("/path/to/worktree".into());
+    assert!(identifier.starts_with(b"Location /path/to/worktree, system "));
+    assert_eq!(identifier.last(), Some(&0), "git keeps the trailing null byte");
+    assert!(index
+        .set_untracked(extension::UntrackedCache::new(identifier.clone()))
+        .is_none());
+
+    let mut buf = Vec::new();
+    index.write_to(&mut buf, Default::default())?;
+    let (actual, _) = State::from_bytes(&buf, FileTime::now(), gix_hash::Kind::Sha1, Default::default())?;
+    let untracked = actual.untracked().expect("written and read back");
+    assert_eq!(untracked, index.untracked().expect("set"));
+    assert_eq!(untracked.identifier(), identifier);
+    assert_eq!(untracked.exclude_filename_per_dir(), ".gitignore");
+    assert!(
+        untracked.directories().is_empty(),
+        "git will fill in the directories the next time it runs"
+    );
+    Ok(())
+}
+
+#[test]
+fn unknown_extensions_are_preserved() -> crate::Result {
+    let index = Generated("v2").open();
+    let mut buf = Vec::new();
+    index.write_to(&mut buf, options_with(write::Extensions::None))?;
+    buf.truncate(buf.len() - gix_hash::Kind::Sha1.len_in_bytes());
+    for (signature, data) in [(b"ZZZZ", &b"newer git"[..]), (b"YYYY", b"")] {
+        buf.extend_from_slice(signature);
+        buf.extend_from_slice(&(data.len() as u32).to_be_bytes());
+        buf.extend_from_slice(data);
+    }
+    buf.extend_from_slice(gix_hash::Kind::Sha1.null().as_slice());
+
+    let (state, _) = State::from_bytes(&buf, FileTime::now(), gix_hash::Kind::Sha1, Default::default())?;
+    let expected = vec![
+        extension::Unknown {
+            signature: *b"ZZZZ",
+            data: b"newer git".to_vec(),
+        },
+        extension::Unknown {
+            signature: *b"YYYY",
+            data: Vec::new(),
+        },
+    ];
+    assert_eq!(state.unknown_extensions(), expected, "ordering is preserved");
+
+    let mut out = Vec::new();
+    state.write_to(&mut out, options_with(write::Extensions::All))?;
+    out.extend_from_slice(gix_hash::Kind::Sha1.null().as_slice());
+    let (actual, _) = State::from_bytes(&out, FileTime::now(), gix_hash::Kind::Sha1, Default::default())?;
+    assert_eq!(actual.unknown_extensions(), expected, "they survive a round-trip");
+    assert_eq!(actual.entries(), state.entries());
+
+    for extensions in [
+        write::Extensions::None,
+        write::Extensions::Given {
+            tree_cache: true,
+            end_of_index_entry: true,
+            offset_table: true,
+            resolve_undo: true,
+            untracked_cache: true,
+            fs_monitor: true,
+        },
+    ] {
+        let mut out = Vec::new();
+        state.write_to(&mut out, options_with(extensions))?;
+        out.extend_from_slice(gix_hash::Kind::Sha1.null().as_slice());
+        let (actual, _) = State::from_bytes(&out, FileTime::now(), gix_hash::Kind::Sha1, Default::default())?;
+        assert!(
+            actual.unknown_extensions().is_empty(),
+            "unknown extensions are only written if all extensions are"
+        );
+    }
+
+    let mut state = state;
+    assert_eq!(state.remove_unknown_extensions(), expected);
+    assert!(state.unknown_extensions().is_empty());
+    Ok(())
+}
+
 #[test]
 fn remove_flag_is_respected() -> crate::Result {
     let mut index = Generated("v4_more_files_IEOT").open();
@@ -210,6 +728,92 @@ fn remove_flag_is_respected() -> crate::Result {
     Ok(())
 }
 
+#[test]
+fn untracked_cache_stat_times_roundtrip() -> crate::Result {
+    let mut index = Loose("UNTR").open();
+    let stat = entry::Stat {
+        mtime: entry::stat::Time { secs: 1, nsecs: 2 },
+        ctime: entry::stat::Time { secs: 3, nsecs: 4 },
+        ..Default::default()
+    };
+    let dir = index
+        .untracked_mut()
+        .expect("present")
+        .directories_mut()
+        .iter_mut()
+        .find(|dir| dir.stat.is_some())
+        .expect("at least one directory with valid stat");
+    dir.stat = Some(stat);
+    let name = dir.name.clone();
+
+    let mut buf = Vec::new();
+    index.write_to(&mut buf, tree_and_untracked_cache_ext())?;
+    let (actual, _) = State::from_bytes(&buf, FileTime::now(), gix_hash::Kind::Sha1, Default::default())?;
+    let dir = actual
+        .untracked()
+        .expect("written")
+        .directories()
+        .iter()
+        .find(|dir| dir.name == name)
+        .expect("still present");
+    assert_eq!(
+        dir.stat,
+        Some(stat),
+        "ctime and mtime aren't swapped when reading them back"
+    );
+    Ok(())
+}
+
+#[test]
+fn racy_entries_can_be_smudged() -> crate::Result {
+    let mut index = Generated("v4_more_files_IEOT").open();
+    index.set_timestamp(FileTime::from_unix_time(1_000, 0));
+    for (idx, entry) in index.entries_mut().iter_mut().enumerate() {
+        entry.stat.mtime.secs = if idx < 3 { 1_000 } else { 999 };
+        entry.stat.size = 42;
+    }
+    let written_sizes = |index: &gix_index::File, options: Options| -> crate::Result<Vec<u32>> {
+        let mut buf = Vec::<u8>::new();
+        index.write_to(&mut buf, options)?;
+        let (state, _checksum) = State::from_bytes(&buf, FileTime::now(), gix_hash::Kind::Sha1, Default::default())?;
+        Ok(state.entries().iter().map(|e| e.stat.size).collect())
+    };
+    let smudge_racy_entries = Options {
+        smudge_racy_entries: Some(Default::default()),
+        ..Default::default()
+    };
+
+    assert!(
+        written_sizes(&index, Options::default())?
+            .iter()
+            .all(|size| *size == 42),
+        "by default, entries are written as they are"
+    );
+    assert_eq!(
+        written_sizes(&index, smudge_racy_entries)?,
+        [0, 0, 0, 42, 42, 42, 42, 42, 42, 42],
+        "racy entries are written with a size of 0 if requested"
+    );
+    assert!(
+        index.entries().iter().all(|e| e.stat.size == 42),
+        "the index itself isn't altered when writing"
+    );
+
+    let racy_paths: Vec<_> = index.entries()[..3].iter().map(|e| e.path(&index).to_owned()).collect();
+    let mut checked = Vec::new();
+    let num_smudged = index.smudge_racily_clean_entries(Default::default(), |_entry, rela_path| {
+        checked.push(rela_path.to_owned());
+        Ok::<_, std::convert::Infallible>(rela_path != racy_paths[1])
+    })?;
+    assert_eq!(checked, racy_paths, "only racy entries are checked for modifications");
+    assert_eq!(num_smudged, 2, "only modified entries are smudged");
+    assert_eq!(
+        index.entries().iter().map(|e| e.stat.size).collect::<Vec<_>>(),
+        [0, 42, 0, 42, 42, 42, 42, 42, 42, 42]
+    );
+    Ok(())
+}
+
 fn compare_states_against_baseline(
     actual: &State,
     actual_version: Version,
@@ -243,6 +847,30 @@ fn compare_states(actual: &State, actual_version: Version, expected: &State, opt
             .and_then(|_| expected.tree()),
         "tree extension mismatch, actual vs option in {fixture:?}"
     );
+    assert_eq!(
+        actual.resolve_undo(),
+        options
+            .extensions
+            .should_write(extension::resolve_undo::SIGNATURE)
+            .and_then(|_| expected.resolve_undo()),
+        "resolve-undo extension mismatch, actual vs option in {fixture:?}"
+    );
+    assert_eq!(
+        actual.untracked(),
+        options
+            .extensions
+            .should_write(extension::untracked_cache::SIGNATURE)
+            .and_then(|_| expected.untracked()),
+        "untracked cache extension mismatch, actual vs option in {fixture:?}"
+    );
+    assert_eq!(
+        actual.fs_monitor(),
+        options
+            .extensions
+            .should_write(extension::fs_monitor::SIGNATURE)
+            .and_then(|_| expected.fs_monitor()),
+        "fsmonitor extension mismatch, actual vs option in {fixture:?}"
+    );
 
     // As `write_to` does / should not mutate we can test those properties here.
     // Anything that can be configured has to be tested separately when comparing against baseline
@@ -290,17 +918,64 @@ fn compare_raw_bytes(generated: &[u8], expected: &[u8], fixture: &str) {
 
 fn only_tree_ext() -> Options {
     Options {
+        version: write::Version::Auto,
         extensions: write::Extensions::Given {
             end_of_index_entry: false,
+            offset_table: false,
+            resolve_undo: false,
+            untracked_cache: false,
+            fs_monitor: false,
             tree_cache: true,
         },
         skip_hash: false,
+        offset_table_blocks: 0,
+        fsync: Default::default(),
+        smudge_racy_entries: None,
+        verify: None,
     }
 }
 
+fn tree_and_untracked_cache_ext() -> Options {
+    options_with(write::Extensions::Given {
+        tree_cache: true,
+        end_of_index_entry: false,
+        offset_table: false,
+        resolve_undo: false,
+        untracked_cache: true,
+        fs_monitor: false,
+    })
+}
+
+fn tree_and_resolve_undo_ext() -> Options {
+    options_with(write::Extensions::Given {
+        tree_cache: true,
+        end_of_index_entry: false,
+        offset_table: false,
+        resolve_undo: true,
+        untracked_cache: false,
+        fs_monitor: false,
+    })
+}
+
+fn tree_and_fs_monitor_ext() -> Options {
+    options_with(write::Extensions::Given {
+        tree_cache: true,
+        end_of_index_entry: false,
+        offset_table: false,
+        resolve_undo: false,
+        untracked_cache: false,
+        fs_monitor: true,
+    })
+}
+
 fn options_with(extensions: write::Extensions) -> Options {
     Options {
+        version: write::Version::Auto,
         extensions,
         skip_hash: false,
+        offset_table_blocks: 0,
+        fsync: Default::default(),
+        smudge_racy_entries: None,
+        verify: None,
     }
 }
@@ -24,6 +24,12 @@ fn from_tree() -> crate::Result {
         let actual_state = State::from_tree(&tree_id, &odb, Default::default())?;
 
         compare_states(&actual_state, &expected_state, fixture);
+        actual_state.verify_extensions(true, &odb)?;
+        assert_eq!(
+            actual_state.tree(),
+            expected_state.tree(),
+            "the tree cache matches the one git produces in {fixture:?}"
+        );
     }
     Ok(())
 }
@@ -52,6 +58,48 @@ fn from_tree_validation() -> crate::Result {
     Ok(())
 }
 
+#[test]
+fn expand_sparse_index() -> crate::Result {
+    let worktree_dir = scripted_fixture_read_only_standalone("make_index/v3_sparse_index.sh")?;
+    let git_dir = worktree_dir.join(".git");
+    let mut index = gix_index::File::at(git_dir.join("index"), gix_hash::Kind::Sha1, false, Default::default())?;
+    assert!(index.is_sparse());
+    let num_sparse_dirs = index.entries().iter().filter(|e| e.mode.is_sparse()).count();
+    assert_eq!(num_sparse_dirs, 2, "c1/c3 and d are excluded");
+
+    let odb = gix_odb::at(git_dir.join("objects"))?;
+    index.expand(&odb, Default::default())?;
+    assert!(!index.is_sparse());
+    assert!(index.entries().iter().all(|e| !e.mode.is_sparse()));
+    index.verify_entries()?;
+
+    let head_tree = index.tree().expect("present").id;
+    let expected = State::from_tree(&head_tree, &odb, Default::default())?;
+    assert_eq!(
+        index
+            .entries()
+            .iter()
+            .map(|e| (e.path(&index), e.id))
+            .collect::<Vec<_>>(),
+        expected
+            .entries()
+            .iter()
+            .map(|e| (e.path(&expected), e.id))
+            .collect::<Vec<_>>(),
+        "all entries of the tree are present"
+    );
+    for entry in index.entries() {
+        let path = entry.path(&index);
+        let is_excluded = path.starts_with(b"c1/c3/") || path.starts_with(b"d/");
+        assert_eq!(
+            entry.flags.contains(gix_index::entry::Flags::SKIP_WORKTREE),
+            is_excluded,
+            "expanded entries are skipped like their directory: {path}"
+        );
+    }
+    Ok(())
+}
+
 #[test]
 fn new() {
     let state = State::new(gix_hash::Kind::Sha1);
@@ -1,8 +1,12 @@
 mod access;
+mod conflict;
+mod diff;
 mod entry;
 mod file;
 mod fs;
 mod init;
+mod serde;
+mod verify;
 
 use std::path::{Path, PathBuf};
 
@@ -0,0 +1,103 @@
+use bstr::{BStr, ByteSlice};
+use gix_index::{entry, State};
+
+use crate::index::Fixture;
+
+fn round_trip(state: &State) -> State {
+    let json = serde_json::to_string(state).expect("serializable");
+    let actual: State = serde_json::from_str(&json).expect("valid states deserialize");
+    assert_eq!(
+        serde_json::to_string(&actual).expect("serializable"),
+        json,
+        "nothing is lost in the round-trip"
+    );
+    actual
+}
+
+fn paths(state: &State) -> Vec<&BStr> {
+    state.entries().iter().map(|e| e.path(state)).collect()
+}
+
+fn written(state: &State) -> Vec<u8> {
+    let mut buf = Vec::new();
+    state
+        .write_to(&mut buf, Default::default())
+        .expect("in-memory writes work");
+    buf
+}
+
+#[test]
+fn round_trip_with_extensions() {
+    for fixture in [
+        Fixture::Generated("v2"),
+        Fixture::Generated("v4_more_files_IEOT"),
+        Fixture::Generated("v3_sparse_index"),
+        Fixture::Loose("REUC"),
+        Fixture::Loose("UNTR-with-oids"),
+        Fixture::Loose("FSMN"),
+        Fixture::Loose("conflicting-file"),
+        Fixture::Loose("very-long-path"),
+    ] {
+        let expected = fixture.open();
+        let actual = round_trip(&expected);
+        assert_eq!(paths(&actual), paths(&expected), "{}", fixture.to_name());
+        assert_eq!(actual.is_sparse(), expected.is_sparse(), "{}", fixture.to_name());
+        assert_eq!(written(&actual), written(&expected), "{}", fixture.to_name());
+    }
+}
+
+#[test]
+fn paths_are_strings_unless_they_are_not_valid_utf8() {
+    let mut state: State = Fixture::Generated("v2").open().into();
+    let json = serde_json::to_value(&state).expect("serializable");
+    assert_eq!(json["entries"][0]["path"], "a", "paths are readable");
+
+    let entry = state.entry(0).clone();
+    state.dangerously_push_entry(entry.stat, entry.id, entry.flags, entry.mode, b"b\xff".as_bstr());
+    state.sort_entries();
+    let json = serde_json::to_value(&state).expect("serializable");
+    assert_eq!(
+        json["entries"][1]["path"],
+        serde_json::json!([b'b', 0xff]),
+        "paths that aren't valid UTF-8 are bytes"
+    );
+
+    let actual = round_trip(&state);
+    assert_eq!(paths(&actual), paths(&state));
+}
+
+#[test]
+fn invalid_states_are_rejected() {
+    let state: State = Fixture::Generated("v4_more_files_IEOT").open().into();
+    let valid = serde_json::to_value(&state).expect("serializable");
+
+    let mut json = valid.clone();
+    json["entries"][0]["path"] = "a/../b".into();
+    let err = serde_json::from_value::<State>(json).unwrap_err();
+    assert!(
+        err.to_string().contains("must not contain '.' or '..' components"),
+        "{err}"
+    );
+
+    let mut json = valid.clone();
+    json["entries"][0]["path"] = ".git/config".into();
+    assert!(
+        serde_json::from_value::<State>(json).is_err(),
+        "paths are validated like when checking them out"
+    );
+
+    let mut json = valid;
+    let entries = json["entries"].as_array_mut().expect("array");
+    entries.swap(0, 1);
+    let err = serde_json::from_value::<State>(json).unwrap_err();
+    assert!(err.to_string().contains("should order after prior entry"), "{err}");
+}
+
+#[test]
+fn conflicting_entries_keep_their_stage() {
+    let state: State = Fixture::Loose("conflicting-file").open().into();
+    let actual = round_trip(&state);
+    let stages = |state: &State| state.entries().iter().map(gix_index::Entry::stage).collect::<Vec<_>>();
+    assert_eq!(stages(&actual), stages(&state));
+    assert!(stages(&actual).contains(&entry::Stage::Ours));
+}
@@ -0,0 +1,168 @@
+use gix_index::{entry::Stage, verify, write};
+
+use crate::index::Fixture;
+
+/// Add an entry at `path` and `stage` without removing conflicting entries, like `upsert_entry()` would.
+fn add(file: &mut gix_index::File, path: &str, stage: Stage) {
+    let entry = file.entry(0).clone();
+    file.dangerously_push_entry(
+        entry.stat,
+        entry.id,
+        gix_index::entry::Flags::from_stage(stage),
+        entry.mode,
+        path.into(),
+    );
+    file.sort_entries();
+}
+
+#[test]
+fn valid_indices_pass() {
+    for fixture in [
+        Fixture::Generated("v2"),
+        Fixture::Generated("v4_more_files_IEOT"),
+        Fixture::Loose("conflicting-file"),
+    ] {
+        fixture.open().verify(Default::default()).expect("valid");
+    }
+}
+
+#[test]
+fn merged_and_unmerged_entries_of_the_same_path_are_rejected() {
+    let mut file = Fixture::Generated("v4_more_files_IEOT").open();
+    add(&mut file, "a", Stage::Ours);
+    let err = file.verify(Default::default()).unwrap_err();
+    assert!(matches!(
+        err,
+        verify::Error::Entries(verify::entries::Error::MergedAndUnmerged { index: 1, .. })
+    ));
+}
+
+#[test]
+fn invalid_paths_are_rejected() {
+    for (path, is_relative) in [("d/../x", true), ("./x", true), (".git/config", false), ("a//b", false)] {
+        let mut file = Fixture::Generated("v4_more_files_IEOT").open();
+        add(&mut file, path, Stage::Unconflicted);
+        file.verify_entries().expect("the order is still valid");
+        let err = file.verify(Default::default()).unwrap_err();
+        match err {
+            verify::Error::Paths(verify::paths::Error::RelativeComponent { path: actual, .. }) => {
+                assert!(is_relative, "{path}");
+                assert_eq!(actual, path);
+            }
+            verify::Error::Paths(verify::paths::Error::InvalidComponent { path: actual, .. }) => {
+                assert!(!is_relative, "{path}");
+                assert_eq!(actual, path);
+            }
+            err => unreachable!("unexpected error for {path}: {err:?}"),
+        }
+    }
+}
+
+#[test]
+fn tree_cache_with_more_entries_than_the_index_is_rejected() {
+    let mut file = Fixture::Generated("v2").open();
+    assert!(file.tree().is_some(), "the fixture needs a tree cache");
+    file.remove_entries(|idx, _, _| idx == 0);
+    let err = file.verify(Default::default()).unwrap_err();
+    assert!(matches!(
+        err,
+        verify::Error::Extensions(verify::extensions::Error::TreeEntriesCount { .. })
+    ));
+}
+
+#[test]
+fn writing_can_refuse_to_persist_inconsistent_indices() {
+    let mut file = Fixture::Generated("v4_more_files_IEOT").open();
+    add(&mut file, ".git/config", Stage::Unconflicted);
+    let mut buf = Vec::new();
+    file.write_to(&mut buf, write::Options::default())
+        .expect("no verification by default");
+
+    buf.clear();
+    let err = file
+        .write_to(
+            &mut buf,
+            write::Options {
+                verify: Some(Default::default()),
+                ..Default::default()
+            },
+        )
+        .unwrap_err();
+    assert!(matches!(err, write::Error::Verify(verify::Error::Paths(_))));
+    assert!(buf.is_empty(), "nothing was written");
+}
+
+#[test]
+fn path_mirrors_git_verify_path() {
+    let all = gix_validate::path::component::Options::default();
+    let none = gix_validate::path::component::Options {
+        protect_windows: false,
+        protect_hfs: false,
+        protect_ntfs: false,
+    };
+    let file = gix_index::entry::Mode::FILE;
+    for path in ["a", "a/b", ".gitignore", "dir/.github/x", "git~2"] {
+        verify::path(path.into(), file, all).expect("valid");
+    }
+    for path in ["../x", "a/./b", ".git/hooks/x", ".GIT/x", "a/.git", "", "a/", "/a"] {
+        assert!(verify::path(path.into(), file, none).is_err(), "{path}: always invalid");
+    }
+    for path in [
+        ".git::$INDEX_ALLOCATION/hooks/x",
+        "git~1/hooks/x",
+        ".git. /x",
+        ".g\u{200c}it/hooks/x",
+    ] {
+        assert!(
+            verify::path(path.into(), file, all).is_err(),
+            "{path}: masks .git on NTFS or HFS+"
+        );
+        verify::path(path.into(), file, none).expect("fine without protections");
+    }
+    verify::path("dir/".into(), gix_index::entry::Mode::DIR, all).expect("sparse directories end with a slash");
+    assert!(matches!(
+        verify::path(".gitmodules".into(), gix_index::entry::Mode::SYMLINK, all).unwrap_err(),
+        verify::path::Error::InvalidComponent { .. }
+    ));
+}
+
+#[test]
+fn invalid_paths_can_be_rejected_on_insertion_or_removed() -> crate::Result {
+    let mut file = Fixture::Generated("v4_more_files_IEOT").open();
+    let entry = file.entry(0).clone();
+    let num_entries = file.entries().len();
+    for path in ["d/../x", ".git/config", "d/.git::$INDEX_ALLOCATION/x"] {
+        let err = file
+            .try_upsert_entry(
+                entry.stat,
+                entry.id,
+                entry.flags,
+                entry.mode,
+                path.into(),
+                Default::default(),
+            )
+            .unwrap_err();
+        assert!(err.to_string().contains(path), "{err}");
+    }
+    assert_eq!(file.entries().len(), num_entries, "nothing was inserted");
+    let idx = file.try_upsert_entry(
+        entry.stat,
+        entry.id,
+        entry.flags,
+        entry.mode,
+        "d/new".into(),
+        Default::default(),
+    )?;
+    assert_eq!(file.entry(idx).path(&file), "d/new");
+
+    assert_eq!(file.remove_entries_with_invalid_paths(Default::default()), 0);
+    add(&mut file, ".git/config", Stage::Unconflicted);
+    add(&mut file, "d/../x", Stage::Unconflicted);
+    assert_eq!(
+        file.remove_entries_with_invalid_paths(Default::default()),
+        2,
+        "the index is sanitized"
+    );
+    file.verify(Default::default())?;
+    Ok(())
+}
@@ -7,12 +7,18 @@ impl BuiltinDriver {
             BuiltinDriver::Text => "text",
             BuiltinDriver::Binary => "binary",
             BuiltinDriver::Union => "union",
+            BuiltinDriver::Ours => "ours",
         }
     }
 
     /// Get all available built-in drivers.
     pub fn all() -> &'static [Self] {
-        &[BuiltinDriver::Text, BuiltinDriver::Binary, BuiltinDriver::Union]
+        &[
+            BuiltinDriver::Text,
+            BuiltinDriver::Binary,
+            BuiltinDriver::Union,
+            BuiltinDriver::Ours,
+        ]
     }
 
     /// Try to match one of our variants to `name`, case-sensitive, and return its instance.
@@ -28,3 +34,4 @@ pub use binary::function::merge as binary;
 ///
 pub mod text;
 pub use text::function::merge as text;
+pub(crate) use text::function::merge_and_count_conflicts as text_and_count_conflicts;
@@ -21,6 +21,20 @@ use std::ops::Range;
 /// This idea is to save time if the input is known to be very similar.
 #[allow(clippy::too_many_arguments)]
 pub fn merge<'a>(
+    out: &mut Vec<u8>,
+    input: &mut imara_diff::intern::InternedInput<&'a [u8]>,
+    labels: Labels<'_>,
+    current: &'a [u8],
+    ancestor: &'a [u8],
+    other: &'a [u8],
+    opts: Options,
+) -> Resolution {
+    merge_and_count_conflicts(out, input, labels, current, ancestor, other, opts).0
+}
+
+/// Like [`merge()`], but also return the amount of conflicts that were marked in `out`.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn merge_and_count_conflicts<'a>(
     out: &mut Vec<u8>,
     input: &mut imara_diff::intern::InternedInput<&'a [u8]>,
     Labels {
@@ -35,7 +49,7 @@ pub fn merge<'a>(
         diff_algorithm,
         conflict,
     }: Options,
-) -> Resolution {
+) -> (Resolution, usize) {
     out.clear();
     input.update_before(tokens(ancestor));
     input.update_after(tokens(current));
@@ -63,7 +77,7 @@ pub fn merge<'a>(
 
     if hunks.is_empty() {
         write_ancestor(input, 0, input.before.len(), out);
-        return Resolution::Complete;
+        return (Resolution::Complete, 0);
     }
 
     hunks.sort_by(|a, b| a.before.start.cmp(&b.before.start));
@@ -71,6 +85,7 @@ pub fn merge<'a>(
     let mut intersecting = Vec::new();
     let mut ancestor_integrated_until = 0;
     let mut resolution = Resolution::Complete;
+    let mut num_conflicts = 0;
     let mut current_hunks = Vec::with_capacity(2);
     while take_intersecting(&mut hunks, &mut current_hunks, &mut intersecting).is_some() {
         if intersecting.is_empty() {
@@ -137,6 +152,7 @@ pub fn merge<'a>(
                         ConflictStyle::Merge => {
                             if contains_lines(our_hunks) || contains_lines(their_hunks) {
                                 resolution = Resolution::Conflict;
+                                num_conflicts += 1;
                                 write_conflict_marker(out, b'<', current_label, marker_size, nl);
                                 write_hunks(our_hunks, input, &current_tokens, out);
                                 write_conflict_marker(out, b'=', None, marker_size, nl);
@@ -148,6 +164,7 @@ pub fn merge<'a>(
                             if contains_lines(our_hunks) || contains_lines(their_hunks) {
                                 if hunks_differ_in_diff3(style, our_hunks, their_hunks, input, &current_tokens) {
                                     resolution = Resolution::Conflict;
+                                    num_conflicts += 1;
                                     write_conflict_marker(out, b'<', current_label, marker_size, nl);
                                     write_hunks(our_hunks, input, &current_tokens, out);
                                     let ancestor_hunk = Hunk {
@@ -229,7 +246,7 @@ pub fn merge<'a>(
     }
     write_ancestor(input, ancestor_integrated_until, input.before.len(), out);
 
-    resolution
+    (resolution, num_conflicts)
 }
 
 fn first_hunk<'a>(front: &'a [Hunk], ours: &'a [Hunk], theirs: &'a [Hunk], back: &'a [Hunk]) -> &'a Hunk {
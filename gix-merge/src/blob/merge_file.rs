@@ -0,0 +1,69 @@
+use crate::blob::builtin_driver::text;
+use crate::blob::Resolution;
+
+/// Options for [`merge_file()`](crate::blob::merge_file()).
+#[derive(Default, Copy, Clone, Debug, Eq, PartialEq)]
+pub struct Options<'a> {
+    /// The labels to annotate conflict markers with, typically the names of the files or revisions being merged.
+    pub labels: text::Labels<'a>,
+    /// Control how the text merge is performed and how conflicts are resolved or marked.
+    pub text: text::Options,
+    /// If `true`, default `false`, merge content that looks binary as if it was text instead of failing.
+    pub allow_binary: bool,
+}
+
+/// The result of [`merge_file()`](crate::blob::merge_file()).
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Outcome {
+    /// The merged content, possibly with conflict markers.
+    pub merged: Vec<u8>,
+    /// Whether the merge is complete or conflicted.
+    pub resolution: Resolution,
+    /// The amount of conflicts that were marked in [`merged`](Self::merged), which is `0` unless
+    /// [resolution](Self::resolution) is [`Resolution::Conflict`].
+    pub num_conflicts: usize,
+}
+
+/// The error returned by [`merge_file()`](crate::blob::merge_file()).
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("Cannot merge binary content of the {side} side")]
+    Binary { side: &'static str },
+}
+
+pub(super) mod function {
+    use crate::blob::builtin_driver;
+    use crate::blob::merge_file::{Error, Options, Outcome};
+
+    /// Merge `ours` and `theirs` with `base` as their common ancestor according to `options`, similar to `git merge-file`,
+    /// and return the merged content along with the conflicts it contains.
+    ///
+    /// This works on plain buffers and needs no repository, which is useful to merge files that aren't tracked in `git`.
+    /// Content that appears binary is rejected unless [`Options::allow_binary`] is set.
+    pub fn merge_file(base: &[u8], ours: &[u8], theirs: &[u8], options: Options<'_>) -> Result<Outcome, Error> {
+        if !options.allow_binary {
+            for (side, data) in [("base", base), ("ours", ours), ("theirs", theirs)] {
+                if gix_filter::binary::is_binary(data, None, gix_filter::binary::Heuristic::NullByte) {
+                    return Err(Error::Binary { side });
+                }
+            }
+        }
+        let mut merged = Vec::new();
+        let mut input = imara_diff::intern::InternedInput::default();
+        let (resolution, num_conflicts) = builtin_driver::text_and_count_conflicts(
+            &mut merged,
+            &mut input,
+            options.labels,
+            ours,
+            base,
+            theirs,
+            options.text,
+        );
+        Ok(Outcome {
+            merged,
+            resolution,
+            num_conflicts,
+        })
+    }
+}
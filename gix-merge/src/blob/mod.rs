@@ -8,6 +8,9 @@ use std::path::PathBuf;
 ///
 pub mod builtin_driver;
 ///
+pub mod merge_file;
+pub use merge_file::function::merge_file;
+///
 pub mod pipeline;
 ///
 pub mod platform;
@@ -62,6 +65,10 @@ pub enum BuiltinDriver {
     /// This can be useful for files that change a lot, but will remain usable merely by adding
     /// all changed lines.
     Union,
+    /// Keep the *current* (ours) version without performing any merge, and without considering this a conflict.
+    ///
+    /// This is useful for files that are maintained separately on each branch, like changelogs of release branches.
+    Ours,
 }
 
 /// Define a driver program that performs a three-way merge.
@@ -100,12 +107,13 @@ pub struct Driver {
     /// <driver-program> .merge_file_nR2Qs1 .merge_file_WYXCJe .merge_file_UWbzrm 7 file e2a2970 HEAD feature
     /// ```
     ///
-    /// The driver is expected to leave its version in the file at `%A`, by overwriting it.
+    /// The driver is expected to leave its version in the file at `%A`, by overwriting it, and to exit with a non-zero
+    /// status if conflicts remain, in which case the result will still be used but will be marked as conflicting.
     pub command: BString,
     /// If `true`, this is the `name` of the driver to use when a virtual-merge-base is created, as a merge of all
     /// available merge-bases if there are more than one.
     ///
-    /// This value can also be special built-in drivers named `text`, `binary` or `union`. Note that user-defined
+    /// This value can also be special built-in drivers named `text`, `binary`, `union` or `ours`. Note that user-defined
     /// drivers with the same name will be preferred over built-in ones, but only for files whose git attributes
     /// specified the driver by *name*.
     pub recursive: Option<BString>,
@@ -23,7 +23,7 @@ pub enum Error {
     PrepareExternalDriver(#[from] inner::prepare_external_driver::Error),
     #[error("Failed to launch external merge driver: {cmd}")]
     SpawnExternalDriver { cmd: String, source: std::io::Error },
-    #[error("External merge driver failed with non-zero exit status {status:?}: {cmd}")]
+    #[error("External merge driver was terminated with exit status {status:?}: {cmd}")]
     ExternalDriverFailure {
         status: std::process::ExitStatus,
         cmd: String,
@@ -313,7 +313,7 @@ pub(super) mod inner {
                 let base = self.ancestor.data.as_slice().unwrap_or_default();
                 let ours = self.current.data.as_slice().unwrap_or_default();
                 let theirs = self.other.data.as_slice().unwrap_or_default();
-                let driver = if driver != BuiltinDriver::Binary
+                let driver = if matches!(driver, BuiltinDriver::Text | BuiltinDriver::Union)
                     && (is_binary_buf(self.ancestor.data)
                         || is_binary_buf(self.other.data)
                         || is_binary_buf(self.current.data))
@@ -352,6 +352,7 @@ pub(super) mod inner {
                         );
                         (Pick::Buffer, resolution)
                     }
+                    BuiltinDriver::Ours => (Pick::Ours, Resolution::Complete),
                 }
             }
         }
@@ -359,10 +360,7 @@ pub(super) mod inner {
         fn is_binary_buf(data: resource::Data<'_>) -> bool {
             match data {
                 Data::Missing => false,
-                Data::Buffer(buf) => {
-                    let buf = &buf[..buf.len().min(8000)];
-                    buf.contains(&0)
-                }
+                Data::Buffer(buf) => gix_filter::binary::is_binary(buf, None, gix_filter::binary::Heuristic::NullByte),
                 Data::TooLarge { .. } => true,
             }
         }
@@ -395,7 +393,8 @@ impl<'parent> PlatformRef<'parent> {
                     cmd: format!("{:?}", cmd.cmd),
                     source: err,
                 })?;
-                if !status.success() {
+                // Like Git, a non-zero exit code signals conflicts, but being terminated by a signal is an error.
+                if status.code().is_none() {
                     return Err(Error::ExternalDriverFailure {
                         cmd: format!("{:?}", cmd.cmd),
                         status,
@@ -403,7 +402,12 @@ impl<'parent> PlatformRef<'parent> {
                 }
                 out.clear();
                 cmd.open_result_file()?.read_to_end(out)?;
-                Ok((inner::builtin_merge::Pick::Buffer, Resolution::Complete))
+                let resolution = if status.success() {
+                    Resolution::Complete
+                } else {
+                    Resolution::Conflict
+                };
+                Ok((inner::builtin_merge::Pick::Buffer, resolution))
             }
             Err(builtin) => {
                 let mut input = imara_diff::intern::InternedInput::new(&[][..], &[]);
@@ -0,0 +1,148 @@
+use bstr::ByteSlice;
+use gix_hash::ObjectId;
+
+use crate::blob::builtin_driver::text;
+use crate::blob::{builtin_driver, Resolution};
+
+/// The error returned by [`conflict_id()`].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("Line {line_number}: conflict marker '{marker}' wasn't expected here")]
+    UnexpectedMarker { line_number: usize, marker: char },
+    #[error("The conflict starting at line {line_number} wasn't terminated")]
+    UnterminatedConflict { line_number: usize },
+}
+
+/// Find all conflicts in `content`, as indicated by conflict markers of `marker_size`, and place a normalized version
+/// of `content` into `preimage`, which can be used to identify the same conflict independently of conflict labels,
+/// base sections, or which side is *ours* or *theirs*.
+/// Return the id of the conflict, computed with `object_hash`, or `None` if there was no conflict.
+///
+/// The id and the `preimage` are computed exactly like Git does, so that they are compatible with what Git
+/// stores in `.git/rr-cache/<id>/preimage`.
+///
+/// ### Deviation
+///
+/// Nested conflicts are not supported and cause an error.
+pub fn conflict_id(
+    content: &[u8],
+    marker_size: u8,
+    object_hash: gix_hash::Kind,
+    preimage: &mut Vec<u8>,
+) -> Result<Option<ObjectId>, Error> {
+    #[derive(Copy, Clone, PartialEq)]
+    enum State {
+        Outside,
+        InOurs,
+        InBase,
+        InTheirs,
+    }
+
+    preimage.clear();
+    let mut hasher = gix_features::hash::hasher(object_hash);
+    let mut num_conflicts = 0;
+    let mut state = State::Outside;
+    let mut conflict_start = 0;
+    let (mut ours, mut theirs) = (Vec::new(), Vec::new());
+    for (line_idx, line) in content.lines_with_terminator().enumerate() {
+        let line_number = line_idx + 1;
+        let marker = conflict_marker(line, marker_size);
+        state = match (state, marker) {
+            (State::Outside, Some(b'<')) => {
+                conflict_start = line_number;
+                ours.clear();
+                theirs.clear();
+                State::InOurs
+            }
+            (State::InOurs, Some(b'|')) => State::InBase,
+            (State::InOurs | State::InBase, Some(b'=')) => State::InTheirs,
+            (State::InTheirs, Some(b'>')) => {
+                if ours > theirs {
+                    std::mem::swap(&mut ours, &mut theirs);
+                }
+                hasher.update(&ours);
+                hasher.update(b"\0");
+                hasher.update(&theirs);
+                hasher.update(b"\0");
+                write_conflict(preimage, marker_size, &ours, &theirs);
+                num_conflicts += 1;
+                State::Outside
+            }
+            (State::Outside, _) => {
+                // Stray markers outside of conflicts, like `=======` in Markdown, are regular content.
+                preimage.extend_from_slice(line);
+                State::Outside
+            }
+            (_, Some(marker)) => {
+                return Err(Error::UnexpectedMarker {
+                    line_number,
+                    marker: marker as char,
+                })
+            }
+            (State::InOurs, None) => {
+                ours.extend_from_slice(line);
+                State::InOurs
+            }
+            (State::InBase, None) => State::InBase,
+            (State::InTheirs, None) => {
+                theirs.extend_from_slice(line);
+                State::InTheirs
+            }
+        };
+    }
+    if state != State::Outside {
+        return Err(Error::UnterminatedConflict {
+            line_number: conflict_start,
+        });
+    }
+    Ok((num_conflicts != 0).then(|| ObjectId::from(hasher.digest())))
+}
+
+/// Resolve the conflicts in `preimage`, the normalized version of the currently conflicted file as produced by [`conflict_id()`],
+/// by merging it with the `recorded_preimage` as base and the `recorded_postimage`, as previously recorded for the same conflict id.
+///
+/// Place the result into `out` and return `true` if the resolution could be applied cleanly, or `false` if `out` contains
+/// conflicts, in which case it shouldn't be used.
+pub fn resolve(preimage: &[u8], recorded_preimage: &[u8], recorded_postimage: &[u8], out: &mut Vec<u8>) -> bool {
+    let mut input = imara_diff::intern::InternedInput::new(&[][..], &[]);
+    let resolution = builtin_driver::text(
+        out,
+        &mut input,
+        Default::default(),
+        preimage,
+        recorded_preimage,
+        recorded_postimage,
+        text::Options::default(),
+    );
+    resolution == Resolution::Complete
+}
+
+/// Return the character of the conflict marker in `line` if it is one, considering `marker_size`.
+fn conflict_marker(line: &[u8], marker_size: u8) -> Option<u8> {
+    let marker_size = marker_size as usize;
+    let marker = *line.first()?;
+    if !matches!(marker, b'<' | b'|' | b'=' | b'>')
+        || line.len() < marker_size
+        || line[..marker_size].iter().any(|b| *b != marker)
+    {
+        return None;
+    }
+    match line.get(marker_size) {
+        None => (marker == b'=').then_some(marker),
+        Some(b) if b.is_ascii_whitespace() => Some(marker),
+        Some(_) => None,
+    }
+}
+
+fn write_conflict(out: &mut Vec<u8>, marker_size: u8, ours: &[u8], theirs: &[u8]) {
+    let marker = |out: &mut Vec<u8>, b: u8| {
+        out.extend(std::iter::repeat(b).take(marker_size as usize));
+        out.push(b'\n');
+    };
+    marker(out, b'<');
+    out.extend_from_slice(ours);
+    marker(out, b'=');
+    out.extend_from_slice(theirs);
+    marker(out, b'>');
+}
@@ -1,6 +1,7 @@
 use crate::tree::utils::{
-    apply_change, perform_blob_merge, possibly_rewritten_location, rewrite_location_with_renamed_directory,
-    to_components, track, unique_path_in_tree, ChangeList, ChangeListRef, PossibleConflict, TrackedChange, TreeNodes,
+    apply_change, detect_directory_renames, is_affected_by_directory_renames, perform_blob_merge,
+    possibly_rewritten_location, rewrite_location_with_renamed_directory, to_components, track, tracked,
+    unique_path_in_tree, ChangeList, ChangeListRef, PossibleConflict, TrackedChange, TreeNodes,
 };
 use crate::tree::ConflictMapping::{Original, Swapped};
 use crate::tree::{
@@ -44,6 +45,10 @@ use std::convert::Infallible;
 ///
 /// Our rename-tracking also produces copy information, but we discard it and simply treat it like an addition.
 ///
+/// Directory renames are inferred from file renames [if enabled](Options::detect_directory_renames), but only for
+/// directories that are gone on the renaming side and that the other side added files to. Unlike Merge-ORT, moving these
+/// files into the renamed directory is never considered a conflict.
+///
 /// Finally, our algorithm will consider reasonable solutions to merge-conflicts as conflicts that are resolved, leaving
 /// only content with conflict markers as unresolved ones.
 ///
@@ -78,49 +83,57 @@ where
 
     let mut our_changes = Vec::new();
     if ours_needs_diff {
-        let our_tree = objects.find_tree_iter(our_tree, &mut side_buf)?;
-        gix_diff::tree_with_rewrites(
-            ancestor_tree,
+        diff_side(
+            (base_tree, ancestor_tree),
             our_tree,
-            diff_resource_cache,
-            diff_state,
+            &mut side_buf,
             objects,
-            |change| -> Result<_, Infallible> {
-                track(change, &mut our_changes);
-                Ok(gix_diff::tree_with_rewrites::Action::Continue)
-            },
-            gix_diff::tree_with_rewrites::Options {
-                location: Some(Location::Path),
-                rewrites: options.rewrites,
-            },
+            diff_state,
+            diff_resource_cache,
+            &options,
+            &mut our_changes,
         )?;
     }
 
-    let mut our_tree = TreeNodes::new();
-    for (idx, change) in our_changes.iter().enumerate() {
-        our_tree.track_change(&change.inner, idx);
-    }
-
     let mut their_changes = Vec::new();
     if theirs_needs_diff {
-        let their_tree = objects.find_tree_iter(their_tree, &mut side_buf)?;
-        gix_diff::tree_with_rewrites(
-            ancestor_tree,
+        diff_side(
+            (base_tree, ancestor_tree),
             their_tree,
-            diff_resource_cache,
-            diff_state,
+            &mut side_buf,
             objects,
-            |change| -> Result<_, Infallible> {
-                track(change, &mut their_changes);
-                Ok(gix_diff::tree_with_rewrites::Action::Continue)
-            },
-            gix_diff::tree_with_rewrites::Options {
-                location: Some(Location::Path),
-                rewrites: options.rewrites,
-            },
+            diff_state,
+            diff_resource_cache,
+            &options,
+            &mut their_changes,
         )?;
     }
 
+    if options.detect_directory_renames {
+        // Only do the work if the other side has changes that could possibly be moved along.
+        let mut bufs = (Vec::new(), Vec::new());
+        let (ours_affected, theirs_affected) = (
+            is_affected_by_directory_renames(&our_changes),
+            is_affected_by_directory_renames(&their_changes),
+        );
+        if theirs_affected {
+            detect_directory_renames(&mut our_changes, objects, (base_tree, our_tree, their_tree), &mut bufs)?;
+        }
+        if ours_affected {
+            detect_directory_renames(
+                &mut their_changes,
+                objects,
+                (base_tree, their_tree, our_tree),
+                &mut bufs,
+            )?;
+        }
+    }
+
+    let mut our_tree = TreeNodes::new();
+    for (idx, change) in our_changes.iter().enumerate() {
+        our_tree.track_change(&change.inner, idx);
+    }
+
     let mut their_tree = TreeNodes::new();
     for (idx, change) in their_changes.iter().enumerate() {
         their_tree.track_change(&change.inner, idx);
@@ -1212,3 +1225,51 @@ fn index_entry_at_path(
         path_hint: Some(hint),
     })
 }
+
+/// Place the changes from `ancestor_tree` (with id `base_tree`) to `side_tree` into `changes`, taking them from the
+/// [changes cache](Options::changes_cache) if possible.
+#[allow(clippy::too_many_arguments)]
+fn diff_side(
+    (base_tree, ancestor_tree): (&gix_hash::oid, gix_object::TreeRefIter<'_>),
+    side_tree: &gix_hash::oid,
+    side_buf: &mut Vec<u8>,
+    objects: &impl gix_object::FindObjectOrHeader,
+    diff_state: &mut gix_diff::tree::State,
+    diff_resource_cache: &mut gix_diff::blob::Platform,
+    options: &Options,
+    changes: &mut ChangeList,
+) -> Result<(), Error> {
+    if let Some(cached) = options
+        .changes_cache
+        .as_ref()
+        .and_then(|cache| cache.get(base_tree, side_tree, options.rewrites))
+    {
+        changes.extend(cached.iter().cloned().map(tracked));
+        return Ok(());
+    }
+    let side_tree_iter = objects.find_tree_iter(side_tree, side_buf)?;
+    gix_diff::tree_with_rewrites(
+        ancestor_tree,
+        side_tree_iter,
+        diff_resource_cache,
+        diff_state,
+        objects,
+        |change| -> Result<_, Infallible> {
+            track(change, changes);
+            Ok(gix_diff::tree_with_rewrites::Action::Continue)
+        },
+        gix_diff::tree_with_rewrites::Options {
+            location: Some(Location::Path),
+            rewrites: options.rewrites,
+        },
+    )?;
+    if let Some(cache) = &options.changes_cache {
+        cache.insert(
+            base_tree,
+            side_tree,
+            options.rewrites,
+            changes.iter().map(|change| change.inner.clone()).collect(),
+        );
+    }
+    Ok(())
+}
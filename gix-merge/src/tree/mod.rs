@@ -10,6 +10,8 @@ pub enum Error {
     FindTree(#[from] gix_object::find::existing_object::Error),
     #[error("Could not find ancestor, our or their tree iterator to get started")]
     FindTreeIter(#[from] gix_object::find::existing_iter::Error),
+    #[error("Could not look up a directory to detect directory renames")]
+    FindDirectory(#[from] gix_object::find::Error),
     #[error("Failed to diff our side or their side")]
     DiffTree(#[from] gix_diff::tree_with_rewrites::Error),
     #[error("Could not apply merge result to base tree")]
@@ -361,6 +363,88 @@ pub struct Options {
     /// If `true`, instead of issuing a conflict with [`ResolutionFailure`], do nothing and keep the base/ancestor
     /// version. This is useful when one wants to avoid any kind of merge-conflict to have *some*, *lossy* resolution.
     pub allow_lossy_resolution: bool,
+    /// If `true`, and if [rewrites](Self::rewrites) are tracked, directories that were renamed as a whole on one side
+    /// are also inferred from the renames of the files within them. This way, files that were *added* to the old directory
+    /// on the other side are moved into the renamed directory, instead of being left behind.
+    ///
+    /// This is similar to `merge.directoryRenames` in Git, and such moves are recorded as
+    /// [`Resolution::SourceLocationAffectedByRename`].
+    pub detect_directory_renames: bool,
+    /// If `Some(cache)`, the changes between the ancestor tree and the tree of each side are looked up in `cache`
+    /// before performing a tree-diff with rename tracking, and are stored in it afterwards.
+    pub changes_cache: Option<ChangesCache>,
+}
+
+/// A cache for the changes between the ancestor tree and the tree of one side of a merge, including the rewrites that
+/// were found among them, similar to the way Merge-ORT caches renames.
+///
+/// Rename tracking is the most expensive part of merging trees with many moved files, and it's avoided entirely if
+/// a side was seen in a previous merge with the same ancestor, as happens when merging multiple branches into the
+/// same target or when checking if they would merge cleanly.
+///
+/// Clones share the same cache, which only grows until it is [cleared](Self::clear()).
+#[derive(Default, Clone)]
+pub struct ChangesCache {
+    #[allow(clippy::type_complexity)]
+    changes: std::sync::Arc<
+        std::sync::Mutex<
+            std::collections::HashMap<
+                (gix_hash::ObjectId, gix_hash::ObjectId),
+                (Option<Rewrites>, std::sync::Arc<[Change]>),
+            >,
+        >,
+    >,
+}
+
+impl std::fmt::Debug for ChangesCache {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ChangesCache").field("len", &self.len()).finish()
+    }
+}
+
+impl ChangesCache {
+    /// Return the amount of pairs of trees whose changes are cached.
+    pub fn len(&self) -> usize {
+        self.changes.lock().expect("no panic while locked").len()
+    }
+
+    /// Return `true` if there are no cached changes.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Remove all cached changes.
+    pub fn clear(&self) {
+        self.changes.lock().expect("no panic while locked").clear();
+    }
+
+    /// Return the changes from `base_tree` to `side_tree`, if they were obtained with the same `rewrites` configuration.
+    pub(super) fn get(
+        &self,
+        base_tree: &gix_hash::oid,
+        side_tree: &gix_hash::oid,
+        rewrites: Option<Rewrites>,
+    ) -> Option<std::sync::Arc<[Change]>> {
+        self.changes
+            .lock()
+            .expect("no panic while locked")
+            .get(&(base_tree.to_owned(), side_tree.to_owned()))
+            .filter(|(cached_rewrites, _)| *cached_rewrites == rewrites)
+            .map(|(_, changes)| changes.clone())
+    }
+
+    pub(super) fn insert(
+        &self,
+        base_tree: &gix_hash::oid,
+        side_tree: &gix_hash::oid,
+        rewrites: Option<Rewrites>,
+        changes: std::sync::Arc<[Change]>,
+    ) {
+        self.changes
+            .lock()
+            .expect("no panic while locked")
+            .insert((base_tree.to_owned(), side_tree.to_owned()), (rewrites, changes));
+    }
 }
 
 pub(super) mod function;
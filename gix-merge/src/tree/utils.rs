@@ -17,7 +17,8 @@ use gix_diff::tree_with_rewrites::{Change, ChangeRef};
 use gix_hash::ObjectId;
 use gix_object::tree;
 use gix_object::tree::{EntryKind, EntryMode};
-use std::collections::HashMap;
+use gix_object::FindExt;
+use std::collections::{BTreeMap, HashMap, HashSet};
 
 /// Assuming that `their_location` is the destination of *their* rewrite, check if *it* passes
 /// over a directory rewrite in *our* tree. If so, rewrite it so that we get the path
@@ -54,6 +55,138 @@ pub fn rewrite_location_with_renamed_directory(their_location: &BStr, passed_cha
     }
 }
 
+/// Return `true` if `changes` contain additions or renames which would be affected if the other side renamed
+/// the directory they are placed in.
+pub fn is_affected_by_directory_renames(changes: &ChangeListRef) -> bool {
+    changes.iter().any(|change| {
+        !change.inner.entry_mode().is_tree() && matches!(change.inner, Change::Addition { .. } | Change::Rewrite { .. })
+    })
+}
+
+/// Infer directories that were renamed as a whole from the file-renames in `changes`, and push a tree-rewrite for each
+/// of them onto `changes`, just like the ones the tree-diff produces for directories that were renamed without modification.
+/// That way, additions by the other side into the old directory will follow the rename.
+///
+/// `base_tree`, `side_tree` and `other_tree` are used to check that a directory was removed on the side of `changes`,
+/// but still exists on the other side as otherwise both sides renamed (or removed) it.
+/// A directory is renamed to where most of its files went, and if that's ambiguous, it's not considered renamed.
+/// `bufs` are used to look up trees with `objects`.
+pub fn detect_directory_renames(
+    changes: &mut ChangeList,
+    objects: &impl gix_object::Find,
+    (base_tree, side_tree, other_tree): (&gix_hash::oid, &gix_hash::oid, &gix_hash::oid),
+    (buf, lookup_buf): &mut (Vec<u8>, Vec<u8>),
+) -> Result<(), Error> {
+    let mut renamed_dirs = HashMap::<(&BStr, &BStr), usize>::new();
+    let mut changed_locations = HashSet::<&BStr>::new();
+    for change in changes.iter() {
+        let change = &change.inner;
+        // The source directory is expected to be deleted, and the destination may have been added, so these don't count.
+        if !(change.entry_mode().is_tree() && matches!(change, Change::Addition { .. } | Change::Deletion { .. })) {
+            changed_locations.insert(change.location());
+            changed_locations.insert(change.source_location());
+        }
+        if let Change::Rewrite {
+            source_location,
+            location,
+            entry_mode,
+            copy: false,
+            ..
+        } = change
+        {
+            if entry_mode.is_tree() {
+                continue;
+            }
+            if let Some(dirs) = renamed_directory(source_location.as_bstr(), location.as_bstr()) {
+                *renamed_dirs.entry(dirs).or_default() += 1;
+            }
+        }
+    }
+
+    // Maps the source directory to the destination directory that most files moved to, or `None` if it's ambiguous.
+    let mut best_destination = BTreeMap::<&BStr, (Option<&BStr>, usize)>::new();
+    for ((source_dir, destination_dir), count) in renamed_dirs {
+        let best = best_destination.entry(source_dir).or_insert((None, 0));
+        if count > best.1 {
+            *best = (Some(destination_dir), count);
+        } else if count == best.1 {
+            best.0 = None;
+        }
+    }
+
+    let mut lookup_tree = |tree_id: &gix_hash::oid, location: &BStr| -> Result<Option<tree::Entry>, Error> {
+        let tree = objects.find_tree_iter(tree_id, buf)?;
+        Ok(tree
+            .lookup_entry(objects, lookup_buf, location.split_str("/"))?
+            .filter(|entry| entry.mode.is_tree()))
+    };
+    let mut new_changes = Vec::new();
+    for (source_dir, destination_dir) in best_destination
+        .into_iter()
+        .filter_map(|(source, (destination, _count))| Some((source, destination?)))
+    {
+        // Renamed directories that are also changed otherwise are too complex to be handled here.
+        if changed_locations.contains(source_dir) || changed_locations.contains(destination_dir) {
+            continue;
+        }
+        let Some(source) = lookup_tree(base_tree, source_dir)? else {
+            continue;
+        };
+        if lookup_tree(side_tree, source_dir)?.is_some() || lookup_tree(other_tree, source_dir)?.is_none() {
+            continue;
+        }
+        let Some(destination) = lookup_tree(side_tree, destination_dir)? else {
+            continue;
+        };
+        new_changes.push(Change::Rewrite {
+            source_location: source_dir.to_owned(),
+            source_entry_mode: source.mode,
+            source_relation: None,
+            source_id: source.oid,
+            diff: None,
+            entry_mode: destination.mode,
+            id: destination.oid,
+            location: destination_dir.to_owned(),
+            relation: None,
+            copy: false,
+        });
+    }
+
+    // The directory rewrites replace the deletion of their source and the addition of their destination.
+    changes.retain(|change| {
+        let change = &change.inner;
+        !change.entry_mode().is_tree()
+            || !new_changes.iter().any(|rewrite| match change {
+                Change::Deletion { location, .. } => *location == rewrite.source_location(),
+                Change::Addition { location, .. } => *location == rewrite.location(),
+                _ => false,
+            })
+    });
+    changes.extend(new_changes.into_iter().map(|change| TrackedChange {
+        inner: change,
+        was_written: true,
+        needs_tree_insertion: None,
+        rewritten_location: None,
+    }));
+    Ok(())
+}
+
+/// Return the `(source_dir, destination_dir)` of the file rename from `source` to `destination`, after removing all
+/// trailing directory components they have in common, or `None` if the file didn't change its directory.
+fn renamed_directory<'a>(source: &'a BStr, destination: &'a BStr) -> Option<(&'a BStr, &'a BStr)> {
+    let (mut source_dir, _) = source.rsplit_once_str("/")?;
+    let (mut destination_dir, _) = destination.rsplit_once_str("/")?;
+    while let (Some((source_parent, source_name)), Some((destination_parent, destination_name))) =
+        (source_dir.rsplit_once_str("/"), destination_dir.rsplit_once_str("/"))
+    {
+        if source_name != destination_name {
+            break;
+        }
+        (source_dir, destination_dir) = (source_parent, destination_parent);
+    }
+    (source_dir != destination_dir).then(|| (source_dir.as_bstr(), destination_dir.as_bstr()))
+}
+
 /// Produce a unique path within the directory that contains the file at `file_path` like `a/b`, using `editor`
 /// and `tree` to assure unique names, to obtain the tree at `a/` and `side_name` to more clearly signal
 /// where the file is coming from.
@@ -217,9 +350,14 @@ pub fn track(change: ChangeRef<'_>, changes: &mut ChangeList) {
     if change.entry_mode().is_tree() && matches!(change, ChangeRef::Modification { .. }) {
         return;
     }
+    changes.push(tracked(change.into_owned()));
+}
+
+/// Turn `change`, as previously [tracked](track()), into a new tracked change.
+pub fn tracked(change: Change) -> TrackedChange {
     let is_tree = change.entry_mode().is_tree();
-    changes.push(TrackedChange {
-        inner: match change.into_owned() {
+    TrackedChange {
+        inner: match change {
             Change::Rewrite {
                 id,
                 entry_mode,
@@ -238,7 +376,7 @@ pub fn track(change: ChangeRef<'_>, changes: &mut ChangeList) {
         was_written: is_tree,
         needs_tree_insertion: None,
         rewritten_location: None,
-    });
+    }
 }
 
 /// Unconditionally apply `change` to `editor`.
@@ -0,0 +1,59 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+function write_lines () {
+	printf "%s\n" "$@"
+}
+
+function base () {
+  git init -q
+  git config merge.directoryRenames true
+
+  mkdir dir
+  write_lines 1 2 3 4 5 6 7 8 9 >dir/a
+  write_lines a b c d e f g h i >dir/b
+  write_lines A B C D E F G H I >dir/c
+  echo "unrelated" >top
+}
+
+function theirs () {
+  git checkout -q theirs
+  echo "new" >dir/new
+  mkdir dir/sub
+  echo "nested" >dir/sub/file
+  git add .
+  git commit -q -m "add files to dir"
+}
+
+(mkdir whole-directory && cd whole-directory
+  base
+  git add .
+  git commit -q -m "base"
+
+  git branch ours
+  git branch theirs
+
+  git checkout -q ours
+  git mv dir renamed
+  write_lines 1 2 3 4 5 6 7 8 9 10 >renamed/a
+  git commit -q -am "rename dir to renamed, and change a file in it so its tree differs"
+
+  theirs
+)
+
+(mkdir into-existing-directory && cd into-existing-directory
+  base
+  mkdir renamed
+  echo "existing" >renamed/existing
+  git add .
+  git commit -q -m "base"
+
+  git branch ours
+  git branch theirs
+
+  git checkout -q ours
+  git mv dir/* renamed/
+  git commit -q -m "move all files of dir into the existing directory renamed, so the directory itself isn't seen as renamed"
+
+  theirs
+)
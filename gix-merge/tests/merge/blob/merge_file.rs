@@ -0,0 +1,66 @@
+use gix_merge::blob::{builtin_driver::text, merge_file, Resolution};
+
+#[test]
+fn clean_merge() -> crate::Result {
+    let outcome = gix_merge::blob::merge_file(b"a\nb\nc\n", b"A\nb\nc\n", b"a\nb\nC\n", Default::default())?;
+    assert_eq!(outcome.merged, b"A\nb\nC\n");
+    assert_eq!(outcome.resolution, Resolution::Complete);
+    assert_eq!(outcome.num_conflicts, 0);
+    Ok(())
+}
+
+#[test]
+fn conflicts_are_marked_and_counted() -> crate::Result {
+    let options = merge_file::Options {
+        labels: text::Labels {
+            ancestor: None,
+            current: Some("ours.toml".into()),
+            other: Some("theirs.toml".into()),
+        },
+        ..Default::default()
+    };
+    let outcome =
+        gix_merge::blob::merge_file(b"a\nb\nc\nd\ne\n", b"1\nb\nc\nd\n5\n", b"one\nb\nc\nd\nfive\n", options)?;
+    assert_eq!(outcome.resolution, Resolution::Conflict);
+    assert_eq!(outcome.num_conflicts, 2);
+    assert_eq!(
+        outcome.merged,
+        "<<<<<<< ours.toml\n1\n=======\none\n>>>>>>> theirs.toml\nb\nc\nd\n<<<<<<< ours.toml\n5\n=======\nfive\n>>>>>>> theirs.toml\n"
+            .as_bytes()
+    );
+
+    let outcome = gix_merge::blob::merge_file(
+        b"a\n",
+        b"1\n",
+        b"one\n",
+        merge_file::Options {
+            text: text::Options {
+                conflict: text::Conflict::ResolveWithOurs,
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+    )?;
+    assert_eq!(outcome.merged, b"1\n");
+    assert_eq!(outcome.resolution, Resolution::CompleteWithAutoResolvedConflict);
+    assert_eq!(outcome.num_conflicts, 0, "conflicts that were resolved aren't counted");
+    Ok(())
+}
+
+#[test]
+fn binary_content_is_rejected_by_default() -> crate::Result {
+    let err = gix_merge::blob::merge_file(b"a", b"b\0", b"c", Default::default()).unwrap_err();
+    assert!(matches!(err, merge_file::Error::Binary { side: "ours" }));
+
+    let outcome = gix_merge::blob::merge_file(
+        b"a",
+        b"a",
+        b"c\0",
+        merge_file::Options {
+            allow_binary: true,
+            ..Default::default()
+        },
+    )?;
+    assert_eq!(outcome.merged, b"c\0");
+    Ok(())
+}
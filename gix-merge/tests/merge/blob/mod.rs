@@ -1,4 +1,5 @@
 mod builtin_driver;
+mod merge_file;
 mod pipeline;
 mod platform;
 
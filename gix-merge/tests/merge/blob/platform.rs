@@ -207,6 +207,47 @@ theirs
             );
         }
 
+        platform_ref.driver = DriverChoice::BuiltIn(BuiltinDriver::Ours);
+        let res = platform_ref.merge(&mut buf, default_labels(), &Default::default())?;
+        assert_eq!(
+            res,
+            (Pick::Ours, Resolution::Complete),
+            "the ours driver keeps our side without conflict"
+        );
+        assert_eq!(platform_ref.buffer_by_pick(res.0).unwrap().unwrap().as_bstr(), "ours");
+
+        Ok(())
+    }
+
+    #[test]
+    fn with_external_that_signals_conflict() -> crate::Result {
+        let mut platform = new_platform(
+            [gix_merge::blob::Driver {
+                name: "b".into(),
+                command: "echo merged-with-conflict > %A; exit 1".into(),
+                ..Default::default()
+            }],
+            pipeline::Mode::ToGit,
+        );
+        let mut db = ObjectDb::default();
+        for (content, kind) in [
+            ("base", ResourceKind::CommonAncestorOrBase),
+            ("ours", ResourceKind::CurrentOrOurs),
+            ("theirs", ResourceKind::OtherOrTheirs),
+        ] {
+            let id = db.insert(content);
+            platform.set_resource(id, EntryKind::Blob, "b".into(), kind, &db)?;
+        }
+
+        let platform_ref = platform.prepare_merge(&db, Default::default())?;
+        let mut buf = Vec::new();
+        let res = platform_ref.merge(&mut buf, default_labels(), &Default::default())?;
+        assert_eq!(
+            res,
+            (Pick::Buffer, Resolution::Conflict),
+            "a non-zero exit code means the result has conflicts"
+        );
+        assert_eq!(buf.as_bstr(), "merged-with-conflict\n", "the result is still read back");
         Ok(())
     }
 
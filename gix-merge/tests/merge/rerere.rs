@@ -0,0 +1,91 @@
+use bstr::ByteSlice;
+use gix_merge::rerere::{conflict_id, resolve, Error};
+
+use crate::hex_to_id;
+
+const MARKER_SIZE: u8 = 7;
+
+#[test]
+fn conflict_id_is_independent_of_labels_base_and_side_order() -> crate::Result {
+    let mut preimage = Vec::new();
+    let id = conflict_id(
+        b"before\n<<<<<<< ours\na\nb\n||||||| base\nx\n=======\nc\n>>>>>>> theirs\nafter\n",
+        MARKER_SIZE,
+        gix_hash::Kind::Sha1,
+        &mut preimage,
+    )?
+    .expect("a conflict was found");
+    assert_eq!(
+        id,
+        hex_to_id("69d49c5999a13c99444b4e8877d75fb18ab4040d"),
+        "the hash is computed over both sides like Git does"
+    );
+    assert_eq!(
+        preimage.as_bstr(),
+        "before\n<<<<<<<\na\nb\n=======\nc\n>>>>>>>\nafter\n",
+        "labels and the base are removed"
+    );
+
+    let mut swapped = Vec::new();
+    let swapped_id = conflict_id(
+        b"before\n<<<<<<< HEAD\nc\n=======\na\nb\n>>>>>>> feature\nafter\n",
+        MARKER_SIZE,
+        gix_hash::Kind::Sha1,
+        &mut swapped,
+    )?;
+    assert_eq!(swapped_id, Some(id), "sides are sorted, so the order doesn't matter");
+    assert_eq!(swapped, preimage);
+    Ok(())
+}
+
+#[test]
+fn conflict_id_without_conflict() -> crate::Result {
+    let mut preimage = Vec::new();
+    let id = conflict_id(
+        b"# Title\n=======\n>>>>>>>> not a marker\n",
+        MARKER_SIZE,
+        gix_hash::Kind::Sha1,
+        &mut preimage,
+    )?;
+    assert_eq!(id, None, "stray markers outside of conflicts are just content");
+    assert_eq!(preimage.as_bstr(), "# Title\n=======\n>>>>>>>> not a marker\n");
+    Ok(())
+}
+
+#[test]
+fn conflict_id_with_malformed_conflicts() {
+    let mut preimage = Vec::new();
+    let err = conflict_id(b"<<<<<<< ours\na\n", MARKER_SIZE, gix_hash::Kind::Sha1, &mut preimage).unwrap_err();
+    assert!(matches!(err, Error::UnterminatedConflict { line_number: 1 }));
+
+    let err = conflict_id(
+        b"<<<<<<< ours\na\n>>>>>>> theirs\n",
+        MARKER_SIZE,
+        gix_hash::Kind::Sha1,
+        &mut preimage,
+    )
+    .unwrap_err();
+    assert!(matches!(
+        err,
+        Error::UnexpectedMarker {
+            line_number: 3,
+            marker: '>'
+        }
+    ));
+}
+
+#[test]
+fn resolve_applies_the_recorded_resolution() {
+    let preimage = b"1\n<<<<<<<\na\n=======\nb\n>>>>>>>\n3\n";
+    let postimage = b"1\na and b\n3\n";
+    let mut out = Vec::new();
+    assert!(resolve(preimage, preimage, postimage, &mut out));
+    assert_eq!(out.as_bstr(), "1\na and b\n3\n");
+
+    let current = b"0\n1\n<<<<<<<\na\n=======\nb\n>>>>>>>\n3\n";
+    assert!(
+        resolve(current, preimage, postimage, &mut out),
+        "unrelated changes are merged"
+    );
+    assert_eq!(out.as_bstr(), "0\n1\na and b\n3\n");
+}
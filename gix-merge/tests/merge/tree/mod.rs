@@ -2,7 +2,7 @@ use crate::tree::baseline::Deviation;
 use gix_diff::Rewrites;
 use gix_merge::commit::Options;
 use gix_merge::tree::TreatAsUnresolved;
-use gix_object::Write;
+use gix_object::{FindExt, Write};
 use gix_worktree::stack::state::attributes;
 use std::path::Path;
 
@@ -157,6 +157,114 @@ fn run_baseline() -> crate::Result {
     Ok(())
 }
 
+#[test]
+fn directory_renames_move_additions_into_the_renamed_directory() -> crate::Result {
+    let root = gix_testtools::scripted_fixture_read_only("directory-renames.sh")?;
+    for (case, expected_with_detection, expected_without_detection) in [
+        (
+            "whole-directory",
+            &[
+                "renamed/a",
+                "renamed/b",
+                "renamed/c",
+                "renamed/new",
+                "renamed/sub/file",
+                "top",
+            ][..],
+            // The directory itself is seen as renamed by the tree-diff, which always moves additions along.
+            &[
+                "renamed/a",
+                "renamed/b",
+                "renamed/c",
+                "renamed/new",
+                "renamed/sub/file",
+                "top",
+            ][..],
+        ),
+        (
+            "into-existing-directory",
+            &[
+                "renamed/a",
+                "renamed/b",
+                "renamed/c",
+                "renamed/existing",
+                "renamed/new",
+                "renamed/sub/file",
+                "top",
+            ][..],
+            &[
+                "dir/new",
+                "dir/sub/file",
+                "renamed/a",
+                "renamed/b",
+                "renamed/c",
+                "renamed/existing",
+                "top",
+            ][..],
+        ),
+    ] {
+        let root = root.join(case);
+        let odb = gix_odb::memory::Proxy::new(gix_odb::at(root.join(".git/objects"))?, gix_hash::Kind::Sha1);
+        let mut buf = Vec::new();
+        let mut tree_and_parent_of = |branch: &str| -> crate::Result<_> {
+            let hex = std::fs::read_to_string(root.join(".git/refs/heads").join(branch))?;
+            let commit = odb.find_commit(&gix_hash::ObjectId::from_hex(hex.trim().as_bytes())?, &mut buf)?;
+            let parent = commit.parents().next().expect("single parent");
+            Ok((commit.tree(), parent))
+        };
+        let (our_tree, base) = tree_and_parent_of("ours")?;
+        let (their_tree, _) = tree_and_parent_of("theirs")?;
+        let base_tree = odb.find_commit(&base, &mut buf)?.tree();
+
+        // All merges after the first one reuse the changes of both sides.
+        let changes_cache = gix_merge::tree::ChangesCache::default();
+        for (detect_directory_renames, expected) in
+            [(true, expected_with_detection), (false, expected_without_detection)]
+        {
+            for (ours, theirs) in [(our_tree, their_tree), (their_tree, our_tree)] {
+                let mut options = basic_merge_options().tree_merge;
+                options.detect_directory_renames = detect_directory_renames;
+                options.changes_cache = Some(changes_cache.clone());
+                let mut actual = gix_merge::tree(
+                    &base_tree,
+                    &ours,
+                    &theirs,
+                    Default::default(),
+                    &odb,
+                    |content| odb.write_buf(gix_object::Kind::Blob, content),
+                    &mut Default::default(),
+                    &mut new_diff_resource_cache(&root),
+                    &mut new_blob_merge_platform(&root, None),
+                    options,
+                )?;
+                assert!(
+                    !actual.has_unresolved_conflicts(TreatAsUnresolved::Renames),
+                    "{case}: moving additions along with a renamed directory isn't a conflict"
+                );
+                assert_eq!(
+                    actual.conflicts.len(),
+                    if expected.contains(&"renamed/new") { 2 } else { 0 },
+                    "{case}: each moved addition is recorded"
+                );
+
+                let actual_id = actual.tree.write(|tree| odb.write(tree))?;
+                let index = gix_index::State::from_tree(&actual_id, &odb, Default::default())?;
+                let paths: Vec<_> = index.entries().iter().map(|e| e.path(&index).to_string()).collect();
+                assert_eq!(
+                    paths, expected,
+                    "{case}: detect_directory_renames = {detect_directory_renames}"
+                );
+            }
+        }
+        assert_eq!(
+            changes_cache.len(),
+            2,
+            "{case}: the changes of each side are cached once"
+        );
+    }
+    Ok(())
+}
+
 fn basic_merge_options() -> Options {
     gix_merge::commit::Options {
         allow_missing_merge_base: true,
@@ -173,6 +281,8 @@ fn basic_merge_options() -> Options {
             fail_on_conflict: None,
             marker_size_multiplier: 0,
             allow_lossy_resolution: false,
+            detect_directory_renames: false,
+            changes_cache: None,
         },
     }
 }
@@ -0,0 +1,404 @@
+use std::io;
+
+use bstr::{BStr, BString};
+
+use crate::{
+    commit::{
+        ref_iter::{CommitRefIterRaw, Token},
+        Builder,
+    },
+    encode,
+    encode::NL,
+    Commit, Kind,
+};
+
+/// A header of a commit after its `committer`, like `encoding` or `gpgsig`.
+#[derive(PartialEq, Eq, Debug, Hash, Clone)]
+pub struct Header {
+    name: BString,
+    value: BString,
+    /// The header exactly as it was parsed, including its name and trailing newline, if it wasn't changed.
+    raw: Option<BString>,
+}
+
+impl Header {
+    /// The name of the header, like `gpgsig`.
+    pub fn name(&self) -> &BStr {
+        self.name.as_ref()
+    }
+
+    /// The value of the header, with the leading space of continuation lines removed if it spans multiple lines.
+    pub fn value(&self) -> &BStr {
+        self.value.as_ref()
+    }
+}
+
+/// The error returned by [`Builder::header()`] and [`Builder::set_header()`].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("Header name {name:?} must not be empty or contain whitespace")]
+    InvalidHeaderName { name: BString },
+    #[error("Header {name:?} can't be an extra header as it's written by the builder itself")]
+    ReservedHeaderName { name: BString },
+    #[error("Header values must not be empty")]
+    EmptyValue,
+}
+
+/// Lifecycle
+impl Builder {
+    /// Create a new commit without parents for `tree`, with `author`, `committer` and `message`.
+    pub fn new(
+        tree: gix_hash::ObjectId,
+        author: gix_actor::Signature,
+        committer: gix_actor::Signature,
+        message: impl Into<BString>,
+    ) -> Self {
+        Builder {
+            tree,
+            parents: Default::default(),
+            author,
+            author_raw: None,
+            committer,
+            committer_raw: None,
+            headers: Vec::new(),
+            message: message.into(),
+        }
+    }
+
+    /// Parse the commit in `data` so that everything that isn't changed will be written exactly as it was.
+    pub fn from_bytes(data: &[u8]) -> Result<Self, crate::decode::Error> {
+        let mut tree = None;
+        let mut parents = smallvec::SmallVec::new();
+        let mut author = None;
+        let mut committer = None;
+        let mut headers = Vec::new();
+        let mut message = BString::default();
+        let tokens = CommitRefIterRaw {
+            data,
+            state: Default::default(),
+            offset: 0,
+        };
+        for token in tokens {
+            let token = token?;
+            let raw = || Some(BString::from(&data[token.token_range.clone()]));
+            match token.token {
+                Token::Tree { id } => tree = Some(id),
+                Token::Parent { id } => parents.push(id),
+                Token::Author { signature } => author = Some((signature.into(), raw())),
+                Token::Committer { signature } => committer = Some((signature.into(), raw())),
+                Token::Encoding(encoding) => headers.push(Header {
+                    name: "encoding".into(),
+                    value: encoding.into(),
+                    raw: raw(),
+                }),
+                Token::ExtraHeader((name, value)) => headers.push(Header {
+                    name: name.into(),
+                    value: value.into_owned(),
+                    raw: raw(),
+                }),
+                Token::Message(msg) => message = msg.into(),
+            }
+        }
+        let missing = crate::decode::empty_error;
+        let ((author, author_raw), (committer, committer_raw)) =
+            (author.ok_or_else(missing)?, committer.ok_or_else(missing)?);
+        Ok(Builder {
+            tree: tree.ok_or_else(missing)?,
+            parents,
+            author,
+            author_raw,
+            committer,
+            committer_raw,
+            headers,
+            message,
+        })
+    }
+
+    /// Turn this instance into a commit, which will write `encoding` before all other extra headers.
+    pub fn into_commit(self) -> Commit {
+        let mut encoding = None;
+        let mut extra_headers = Vec::new();
+        for header in self.headers {
+            if header.name == "encoding" && encoding.is_none() {
+                encoding = Some(header.value);
+            } else {
+                extra_headers.push((header.name, header.value));
+            }
+        }
+        Commit {
+            tree: self.tree,
+            parents: self.parents,
+            author: self.author,
+            committer: self.committer,
+            encoding,
+            message: self.message,
+            extra_headers,
+        }
+    }
+}
+
+impl From<Commit> for Builder {
+    fn from(commit: Commit) -> Self {
+        let Commit {
+            tree,
+            parents,
+            author,
+            committer,
+            encoding,
+            message,
+            extra_headers,
+        } = commit;
+        Builder {
+            tree,
+            parents,
+            author,
+            author_raw: None,
+            committer,
+            committer_raw: None,
+            headers: encoding
+                .map(|encoding| ("encoding".into(), encoding))
+                .into_iter()
+                .chain(extra_headers)
+                .map(|(name, value)| Header { name, value, raw: None })
+                .collect(),
+            message,
+        }
+    }
+}
+
+/// Access
+impl Builder {
+    /// The hash of the tree of the commit.
+    pub fn tree_id(&self) -> gix_hash::ObjectId {
+        self.tree
+    }
+
+    /// The hashes of all parent commits.
+    pub fn parent_ids(&self) -> &[gix_hash::ObjectId] {
+        &self.parents
+    }
+
+    /// The author of the commit.
+    pub fn author_signature(&self) -> &gix_actor::Signature {
+        &self.author
+    }
+
+    /// The committer of the commit.
+    pub fn committer_signature(&self) -> &gix_actor::Signature {
+        &self.committer
+    }
+
+    /// The commit message.
+    pub fn message_raw(&self) -> &BStr {
+        self.message.as_ref()
+    }
+
+    /// All headers after the committer in the order in which they will be written.
+    pub fn headers(&self) -> impl ExactSizeIterator<Item = &Header> + '_ {
+        self.headers.iter()
+    }
+
+    /// Return the value of the first header named `name`.
+    pub fn header_value(&self, name: &BStr) -> Option<&BStr> {
+        self.headers
+            .iter()
+            .find(|header| header.name == name)
+            .map(|header| header.value.as_ref())
+    }
+}
+
+/// Mutation
+impl Builder {
+    /// Set the hash of the tree of the commit to `id`.
+    pub fn tree(&mut self, id: gix_hash::ObjectId) -> &mut Self {
+        self.tree = id;
+        self
+    }
+
+    /// Add `id` as parent after all other parents.
+    pub fn parent(&mut self, id: gix_hash::ObjectId) -> &mut Self {
+        self.parents.push(id);
+        self
+    }
+
+    /// Replace all parents with `ids`.
+    pub fn parents(&mut self, ids: impl IntoIterator<Item = gix_hash::ObjectId>) -> &mut Self {
+        self.parents = ids.into_iter().collect();
+        self
+    }
+
+    /// Set the author to `signature`.
+    pub fn author(&mut self, signature: gix_actor::Signature) -> &mut Self {
+        self.author = signature;
+        self.author_raw = None;
+        self
+    }
+
+    /// Set the committer to `signature`.
+    pub fn committer(&mut self, signature: gix_actor::Signature) -> &mut Self {
+        self.committer = signature;
+        self.committer_raw = None;
+        self
+    }
+
+    /// Set the commit message to `message`.
+    pub fn message(&mut self, message: impl Into<BString>) -> &mut Self {
+        self.message = message.into();
+        self
+    }
+
+    /// Set the `encoding` header to `encoding` in place, or add it before all other headers if it doesn't exist yet,
+    /// or remove it if `encoding` is `None`.
+    pub fn encoding(&mut self, encoding: Option<BString>) -> Result<&mut Self, Error> {
+        let name: &BStr = "encoding".into();
+        match encoding {
+            Some(encoding) => {
+                if self.header_value(name).is_some() {
+                    self.set_header(name, encoding)
+                } else {
+                    let value = normalize_value(encoding)?;
+                    self.headers.insert(
+                        0,
+                        Header {
+                            name: name.into(),
+                            value,
+                            raw: None,
+                        },
+                    );
+                    Ok(self)
+                }
+            }
+            None => {
+                self.remove_header(name);
+                Ok(self)
+            }
+        }
+    }
+
+    /// Add a header with `name` and `value` after all other headers, even if a header with the same name exists.
+    ///
+    /// `value` may span multiple lines, like signatures do, in which case it's stored with a trailing newline,
+    /// just like it would be read back from the written commit.
+    pub fn header(&mut self, name: impl Into<BString>, value: impl Into<BString>) -> Result<&mut Self, Error> {
+        let name = name.into();
+        check_name(name.as_ref())?;
+        let value = normalize_value(value.into())?;
+        self.headers.push(Header { name, value, raw: None });
+        Ok(self)
+    }
+
+    /// Set the value of the first header with `name` to `value` and remove all other headers with the same name,
+    /// or add it after all other headers if there is none.
+    ///
+    /// `value` is stored like it is by [`header()`](Self::header()).
+    pub fn set_header(&mut self, name: impl Into<BString>, value: impl Into<BString>) -> Result<&mut Self, Error> {
+        let name = name.into();
+        check_name(name.as_ref())?;
+        let value = normalize_value(value.into())?;
+        match self.headers.iter().position(|header| header.name == name) {
+            Some(pos) => {
+                let header = &mut self.headers[pos];
+                if header.value != value {
+                    header.value = value;
+                    header.raw = None;
+                }
+                let mut index = 0;
+                self.headers.retain(|header| {
+                    let keep = index <= pos || header.name != name;
+                    index += 1;
+                    keep
+                });
+            }
+            None => self.headers.push(Header { name, value, raw: None }),
+        }
+        Ok(self)
+    }
+
+    /// Remove all headers with `name` and return the amount of removed headers.
+    pub fn remove_header(&mut self, name: &BStr) -> usize {
+        let len = self.headers.len();
+        self.headers.retain(|header| header.name != name);
+        len - self.headers.len()
+    }
+}
+
+fn check_name(name: &BStr) -> Result<(), Error> {
+    if name.is_empty() || name.iter().any(u8::is_ascii_whitespace) {
+        return Err(Error::InvalidHeaderName { name: name.into() });
+    }
+    if ["tree", "parent", "author", "committer"]
+        .iter()
+        .any(|reserved| name == *reserved)
+    {
+        return Err(Error::ReservedHeaderName { name: name.into() });
+    }
+    Ok(())
+}
+
+/// Assure `value` is stored the way it will be parsed from the written commit, which is with a trailing newline
+/// if it spans multiple lines, and without one otherwise.
+fn normalize_value(mut value: BString) -> Result<BString, Error> {
+    if value.last() == Some(&b'\n') {
+        value.pop();
+    }
+    if value.is_empty() {
+        return Err(Error::EmptyValue);
+    }
+    if value.contains(&b'\n') {
+        value.push(b'\n');
+    }
+    Ok(value)
+}
+
+/// Serialization
+impl crate::WriteTo for Builder {
+    /// Serializes this instance to `out` in the git serialization format.
+    fn write_to(&self, mut out: &mut dyn io::Write) -> io::Result<()> {
+        encode::trusted_header_id(b"tree", &self.tree, &mut out)?;
+        for parent in &self.parents {
+            encode::trusted_header_id(b"parent", parent, &mut out)?;
+        }
+        match &self.author_raw {
+            Some(raw) => out.write_all(raw)?,
+            None => encode::trusted_header_signature(b"author", &self.author.to_ref(), &mut out)?,
+        }
+        match &self.committer_raw {
+            Some(raw) => out.write_all(raw)?,
+            None => encode::trusted_header_signature(b"committer", &self.committer.to_ref(), &mut out)?,
+        }
+        for header in &self.headers {
+            match &header.raw {
+                Some(raw) => out.write_all(raw)?,
+                None => encode::header_field_multi_line(&header.name, &header.value, &mut out)?,
+            }
+        }
+        out.write_all(NL)?;
+        out.write_all(&self.message)
+    }
+
+    fn kind(&self) -> Kind {
+        Kind::Commit
+    }
+
+    fn size(&self) -> u64 {
+        let mut count = Counter::default();
+        self.write_to(&mut count).expect("counting never fails");
+        count.0
+    }
+}
+
+/// A writer that only counts the bytes written to it.
+#[derive(Default)]
+struct Counter(u64);
+
+impl io::Write for Counter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0 += buf.len() as u64;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
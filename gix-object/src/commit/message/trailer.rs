@@ -0,0 +1,315 @@
+use crate::bstr::{BStr, BString, ByteSlice, ByteVec};
+
+/// Where to place a trailer that is added, as configured with `trailer.where`.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Placement {
+    /// Place it after all other trailers.
+    #[default]
+    End,
+    /// Place it before all other trailers.
+    Start,
+    /// Place it after the last trailer with the same token, or after all other trailers.
+    After,
+    /// Place it before the first trailer with the same token, or before all other trailers.
+    Before,
+}
+
+impl Placement {
+    fn is_after_or_end(&self) -> bool {
+        matches!(self, Placement::After | Placement::End)
+    }
+}
+
+/// What to do when adding a trailer whose token is already present, as configured with `trailer.ifExists`.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum IfExists {
+    /// Add the trailer unless the trailer next to where it would be placed has the same token and value.
+    #[default]
+    AddIfDifferentNeighbor,
+    /// Add the trailer unless a trailer with the same token and value exists.
+    AddIfDifferent,
+    /// Add the trailer even if the same trailer exists.
+    Add,
+    /// Replace an existing trailer with the same token, the last one for [`Placement::End`] and [`Placement::After`],
+    /// or the first one otherwise.
+    Replace,
+    /// Don't add the trailer.
+    DoNothing,
+}
+
+/// What to do when adding a trailer whose token isn't present yet, as configured with `trailer.ifMissing`.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum IfMissing {
+    /// Add the trailer.
+    #[default]
+    Add,
+    /// Don't add the trailer.
+    DoNothing,
+}
+
+/// Options to control how trailers are parsed and added.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Options {
+    /// The characters that separate a token from its value, as configured with `trailer.separators`.
+    ///
+    /// The first of them is used when writing new trailers, and it defaults to `:`.
+    pub separators: BString,
+    /// Where to add new trailers.
+    pub placement: Placement,
+    /// What to do if a trailer with the same token exists.
+    pub if_exists: IfExists,
+    /// What to do if no trailer with the same token exists.
+    pub if_missing: IfMissing,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            separators: ":".into(),
+            placement: Default::default(),
+            if_exists: Default::default(),
+            if_missing: Default::default(),
+        }
+    }
+}
+
+/// A trailer with its token and value, like `Signed-off-by: Name <email>`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Trailer {
+    /// The name of the trailer, like `Signed-off-by`.
+    pub token: BString,
+    /// The value after the separator, with whitespace trimmed, and with lines of multi-line values joined by a single space.
+    pub value: BString,
+}
+
+impl Trailer {
+    /// Return `true` if `other` has the same token, compared case-insensitively, and the same value.
+    fn is_same(&self, other: &Trailer) -> bool {
+        self.token.eq_ignore_ascii_case(&other.token) && self.value == other.value
+    }
+}
+
+struct Item {
+    trailer: Trailer,
+    /// The lines as they were parsed from the message, including continuation lines, so they can be written unchanged.
+    raw: Option<BString>,
+}
+
+/// A commit message that is split into the part before its trailers and the trailers themselves, so that trailers
+/// can be added or removed in the same way as `git interpret-trailers` would.
+///
+/// Trailers are expected in the last paragraph of a message with at least two paragraphs, which is considered
+/// to be a trailer block only if all of its lines are trailers or continuation lines starting with whitespace.
+/// Trailers that are present in the message are written back unchanged, even if their separator or whitespace differs.
+///
+/// ### Deviation
+///
+/// Git is more lenient and allows a trailer block to contain other lines as long as some of the trailers are
+/// well-known, and it ignores comments and patch dividers at the end of a message.
+/// Per-token configuration, like `trailer.<token>.key`, isn't supported.
+pub struct Editor {
+    /// The message without trailers and trailing whitespace.
+    head: BString,
+    items: Vec<Item>,
+    options: Options,
+}
+
+/// Lifecycle
+impl Editor {
+    /// Split `message` into its trailers and the part before them, using `options` to parse and add trailers.
+    pub fn new(message: &BStr, options: Options) -> Self {
+        let (head, items) = match split_trailer_block(message) {
+            Some((head, block)) => match parse_block(block, options.separators.as_bstr()) {
+                Some(items) => (head, items),
+                None => (message, Vec::new()),
+            },
+            None => (message, Vec::new()),
+        };
+        Editor {
+            head: head.trim_end().into(),
+            items,
+            options,
+        }
+    }
+
+    /// Return the message with all trailers, each on its own line and separated from the rest of the message by an empty line.
+    pub fn into_message(self) -> BString {
+        let mut out = self.head;
+        if !out.is_empty() {
+            out.push(b'\n');
+        }
+        if self.items.is_empty() {
+            return out;
+        }
+        if !out.is_empty() {
+            out.push(b'\n');
+        }
+        let separator = self.options.separators.first().copied().unwrap_or(b':');
+        for item in self.items {
+            match item.raw {
+                Some(raw) => out.push_str(raw),
+                None => {
+                    out.push_str(&item.trailer.token);
+                    out.push(separator);
+                    if !item.trailer.value.is_empty() {
+                        out.push(b' ');
+                        out.push_str(&item.trailer.value);
+                    }
+                }
+            }
+            out.push(b'\n');
+        }
+        out
+    }
+}
+
+/// Access
+impl Editor {
+    /// Return all trailers in the order in which they will be written.
+    pub fn trailers(&self) -> impl ExactSizeIterator<Item = &Trailer> + '_ {
+        self.items.iter().map(|item| &item.trailer)
+    }
+
+    /// Return the value of all trailers whose token matches `token` case-insensitively.
+    pub fn values<'a>(&'a self, token: &'a BStr) -> impl Iterator<Item = &'a BStr> + 'a {
+        self.trailers()
+            .filter(move |trailer| trailer.token.eq_ignore_ascii_case(token))
+            .map(|trailer| trailer.value.as_bstr())
+    }
+}
+
+/// Mutation
+impl Editor {
+    /// Add a trailer with `token` and `value` according to the [placement](Options::placement) and the rules
+    /// for [existing](Options::if_exists) and [missing](Options::if_missing) trailers, and return `true` if it was added.
+    pub fn add(&mut self, token: impl Into<BString>, value: impl Into<BString>) -> bool {
+        let (token, value): (BString, BString) = (token.into(), value.into());
+        let new = Trailer {
+            token: token.trim().into(),
+            value: value.trim().into(),
+        };
+        let placement = self.options.placement;
+        let backwards = placement.is_after_or_end();
+        let same_token = |item: &Item| item.trailer.token.eq_ignore_ascii_case(&new.token);
+        let existing = if backwards {
+            self.items.iter().rposition(same_token)
+        } else {
+            self.items.iter().position(same_token)
+        };
+
+        let Some(existing) = existing else {
+            return match self.options.if_missing {
+                IfMissing::Add => {
+                    let pos = if backwards { self.items.len() } else { 0 };
+                    self.items.insert(
+                        pos,
+                        Item {
+                            trailer: new,
+                            raw: None,
+                        },
+                    );
+                    true
+                }
+                IfMissing::DoNothing => false,
+            };
+        };
+
+        // The trailer next to which the new one is placed.
+        let neighbor = match placement {
+            Placement::After | Placement::Before => existing,
+            Placement::End => self.items.len() - 1,
+            Placement::Start => 0,
+        };
+        let add = match self.options.if_exists {
+            IfExists::DoNothing => false,
+            IfExists::Add | IfExists::Replace => true,
+            IfExists::AddIfDifferent => !self.items.iter().any(|item| item.trailer.is_same(&new)),
+            IfExists::AddIfDifferentNeighbor => !self.items[neighbor].trailer.is_same(&new),
+        };
+        if !add {
+            return false;
+        }
+        let pos = if backwards { neighbor + 1 } else { neighbor };
+        self.items.insert(
+            pos,
+            Item {
+                trailer: new,
+                raw: None,
+            },
+        );
+        if self.options.if_exists == IfExists::Replace {
+            self.items.remove(if existing >= pos { existing + 1 } else { existing });
+        }
+        true
+    }
+
+    /// Remove all trailers whose token matches `token` case-insensitively, and return the amount of removed trailers.
+    pub fn remove(&mut self, token: &BStr) -> usize {
+        let len = self.items.len();
+        self.items
+            .retain(|item| !item.trailer.token.eq_ignore_ascii_case(token));
+        len - self.items.len()
+    }
+}
+
+/// Return the part of `message` before its last paragraph along with the last paragraph, if there are at least two.
+fn split_trailer_block(message: &BStr) -> Option<(&BStr, &BStr)> {
+    let message = message.trim_end();
+    let mut offset = 0;
+    let mut block_start = None;
+    let mut previous_was_empty = false;
+    for line in message.lines_with_terminator() {
+        let is_empty = line.trim().is_empty();
+        if previous_was_empty && !is_empty {
+            block_start = Some(offset);
+        }
+        previous_was_empty = is_empty;
+        offset += line.len();
+    }
+    block_start.map(|start| (message[..start].as_bstr(), message[start..].as_bstr()))
+}
+
+/// Parse all lines in `block` as trailers, or return `None` if one of them isn't a trailer.
+fn parse_block(block: &BStr, separators: &BStr) -> Option<Vec<Item>> {
+    let mut items = Vec::<Item>::new();
+    for line in block.lines() {
+        if line.first().map_or(false, |b| b.is_ascii_whitespace()) {
+            let item = items.last_mut()?;
+            let raw = item.raw.as_mut().expect("parsed items are raw");
+            raw.push(b'\n');
+            raw.push_str(line);
+            let value = &mut item.trailer.value;
+            if !value.is_empty() {
+                value.push(b' ');
+            }
+            value.push_str(line.trim());
+            continue;
+        }
+        let (token, value) = parse_line(line.as_bstr(), separators)?;
+        items.push(Item {
+            trailer: Trailer {
+                token: token.into(),
+                value: value.into(),
+            },
+            raw: Some(line.into()),
+        });
+    }
+    (!items.is_empty()).then_some(items)
+}
+
+/// Parse `line` into a token and its value if it's a trailer that uses one of the `separators`.
+///
+/// Tokens consist of alphanumeric characters and `-`, and may be followed by whitespace before the separator.
+fn parse_line<'a>(line: &'a BStr, separators: &BStr) -> Option<(&'a BStr, &'a BStr)> {
+    let pos = line.find_byteset(separators)?;
+    let token = line[..pos].trim_end();
+    if token.is_empty() || !token.iter().all(|b| b.is_ascii_alphanumeric() || *b == b'-') {
+        return None;
+    }
+    Some((token.as_bstr(), line[pos + 1..].trim().as_bstr()))
+}
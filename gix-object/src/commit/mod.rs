@@ -5,10 +5,37 @@ use winnow::prelude::*;
 
 use crate::{Commit, CommitRef, TagRef};
 
+///
+pub mod builder;
 mod decode;
 ///
 pub mod message;
 
+/// A way to create commits or to change existing ones, which keeps all headers in their original order and
+/// writes all parts that weren't changed exactly as they were parsed.
+///
+/// This includes extra headers like `gpgsig`, `mergetag` and `encoding`, along with unknown ones, so commits can be
+/// rewritten or re-signed without altering anything that wasn't meant to change.
+#[derive(PartialEq, Eq, Debug, Hash, Clone)]
+pub struct Builder {
+    /// The hash of recorded working tree state.
+    tree: gix_hash::ObjectId,
+    /// Hash of each parent commit.
+    parents: smallvec::SmallVec<[gix_hash::ObjectId; 1]>,
+    /// Who wrote this commit.
+    author: gix_actor::Signature,
+    /// The `author` line as it was parsed, if `author` wasn't changed.
+    author_raw: Option<BString>,
+    /// Who committed this commit.
+    committer: gix_actor::Signature,
+    /// The `committer` line as it was parsed, if `committer` wasn't changed.
+    committer_raw: Option<BString>,
+    /// All headers after the committer in order, including `encoding`.
+    headers: Vec<builder::Header>,
+    /// The commit message documenting the change.
+    message: BString,
+}
+
 /// A parsed commit message that assumes a title separated from the body by two consecutive newlines.
 ///
 /// Titles can have any amount of whitespace
@@ -274,10 +274,10 @@ impl<'a> Iterator for CommitRefIter<'a> {
 }
 
 /// A variation of [`CommitRefIter`] that return's [`RawToken`]s instead.
-struct CommitRefIterRaw<'a> {
-    data: &'a [u8],
-    state: State,
-    offset: usize,
+pub(crate) struct CommitRefIterRaw<'a> {
+    pub(crate) data: &'a [u8],
+    pub(crate) state: State,
+    pub(crate) offset: usize,
 }
 
 impl<'a> Iterator for CommitRefIterRaw<'a> {
@@ -309,10 +309,10 @@ impl<'a> Iterator for CommitRefIterRaw<'a> {
 }
 
 /// A combination of a parsed [`Token`] as well as the range of bytes that were consumed to parse it.
-struct RawToken<'a> {
+pub(crate) struct RawToken<'a> {
     /// The parsed token.
-    token: Token<'a>,
-    token_range: Range<usize>,
+    pub(crate) token: Token<'a>,
+    pub(crate) token_range: Range<usize>,
 }
 
 /// A token returned by the [commit iterator][CommitRefIter].
@@ -0,0 +1,616 @@
+//! Validate the raw data of objects as strictly as `git fsck` does, beyond what is needed to [decode](crate::Data::decode()) them.
+//!
+//! Each problem is identified by a [`MessageId`] which has the same name as in `git fsck`, like `zeroPaddedFilemode`,
+//! along with a [`Severity`] which can be adjusted just like with `fsck.<msg-id>` in `git config`.
+//!
+//! ### Deviation
+//!
+//! Only checks that need nothing but the object itself are performed, so `.gitmodules` contents and connectivity aren't checked.
+use std::collections::BTreeMap;
+
+use bstr::{BStr, ByteSlice};
+
+use crate::Kind;
+
+/// How severe a [problem](Problem) is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Severity {
+    /// The problem isn't reported.
+    Ignore,
+    /// The problem is reported, but doesn't make the object invalid.
+    Warn,
+    /// The problem makes the object invalid.
+    Error,
+}
+
+impl Severity {
+    /// Parse `name` as used as value of `fsck.<msg-id>`, i.e. `error`, `warn` or `ignore`, case-insensitively.
+    pub fn from_name(name: &BStr) -> Option<Self> {
+        Some(if name.eq_ignore_ascii_case(b"error") {
+            Severity::Error
+        } else if name.eq_ignore_ascii_case(b"warn") {
+            Severity::Warn
+        } else if name.eq_ignore_ascii_case(b"ignore") {
+            Severity::Ignore
+        } else {
+            return None;
+        })
+    }
+}
+
+/// Identifies a problem with the same name as `git fsck`, like `hasDotgit`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[allow(missing_docs)]
+pub enum MessageId {
+    BadDate,
+    BadDateOverflow,
+    BadEmail,
+    BadFilemode,
+    BadName,
+    BadObjectSha1,
+    BadParentSha1,
+    BadTagName,
+    BadTimezone,
+    BadTree,
+    BadTreeSha1,
+    BadType,
+    DuplicateEntries,
+    EmptyName,
+    FullPathname,
+    GitattributesSymlink,
+    GitignoreSymlink,
+    GitmodulesSymlink,
+    HasDot,
+    HasDotdot,
+    HasDotgit,
+    MailmapSymlink,
+    MissingAuthor,
+    MissingCommitter,
+    MissingEmail,
+    MissingNameBeforeEmail,
+    MissingObject,
+    MissingSpaceBeforeDate,
+    MissingSpaceBeforeEmail,
+    MissingTagEntry,
+    MissingTaggerEntry,
+    MissingTree,
+    MissingTypeEntry,
+    MultipleAuthors,
+    NulInCommit,
+    NulInHeader,
+    NullSha1,
+    TreeNotSorted,
+    UnterminatedHeader,
+    ZeroPaddedDate,
+    ZeroPaddedFilemode,
+}
+
+impl MessageId {
+    /// All message ids.
+    pub const ALL: &'static [MessageId] = &[
+        MessageId::BadDate,
+        MessageId::BadDateOverflow,
+        MessageId::BadEmail,
+        MessageId::BadFilemode,
+        MessageId::BadName,
+        MessageId::BadObjectSha1,
+        MessageId::BadParentSha1,
+        MessageId::BadTagName,
+        MessageId::BadTimezone,
+        MessageId::BadTree,
+        MessageId::BadTreeSha1,
+        MessageId::BadType,
+        MessageId::DuplicateEntries,
+        MessageId::EmptyName,
+        MessageId::FullPathname,
+        MessageId::GitattributesSymlink,
+        MessageId::GitignoreSymlink,
+        MessageId::GitmodulesSymlink,
+        MessageId::HasDot,
+        MessageId::HasDotdot,
+        MessageId::HasDotgit,
+        MessageId::MailmapSymlink,
+        MessageId::MissingAuthor,
+        MessageId::MissingCommitter,
+        MessageId::MissingEmail,
+        MessageId::MissingNameBeforeEmail,
+        MessageId::MissingObject,
+        MessageId::MissingSpaceBeforeDate,
+        MessageId::MissingSpaceBeforeEmail,
+        MessageId::MissingTagEntry,
+        MessageId::MissingTaggerEntry,
+        MessageId::MissingTree,
+        MessageId::MissingTypeEntry,
+        MessageId::MultipleAuthors,
+        MessageId::NulInCommit,
+        MessageId::NulInHeader,
+        MessageId::NullSha1,
+        MessageId::TreeNotSorted,
+        MessageId::UnterminatedHeader,
+        MessageId::ZeroPaddedDate,
+        MessageId::ZeroPaddedFilemode,
+    ];
+
+    /// Return the name of this id as used by `git fsck` and in `fsck.<msg-id>`, like `zeroPaddedFilemode`.
+    pub fn name(&self) -> &'static str {
+        use MessageId::*;
+        match self {
+            BadDate => "badDate",
+            BadDateOverflow => "badDateOverflow",
+            BadEmail => "badEmail",
+            BadFilemode => "badFilemode",
+            BadName => "badName",
+            BadObjectSha1 => "badObjectSha1",
+            BadParentSha1 => "badParentSha1",
+            BadTagName => "badTagName",
+            BadTimezone => "badTimezone",
+            BadTree => "badTree",
+            BadTreeSha1 => "badTreeSha1",
+            BadType => "badType",
+            DuplicateEntries => "duplicateEntries",
+            EmptyName => "emptyName",
+            FullPathname => "fullPathname",
+            GitattributesSymlink => "gitattributesSymlink",
+            GitignoreSymlink => "gitignoreSymlink",
+            GitmodulesSymlink => "gitmodulesSymlink",
+            HasDot => "hasDot",
+            HasDotdot => "hasDotdot",
+            HasDotgit => "hasDotgit",
+            MailmapSymlink => "mailmapSymlink",
+            MissingAuthor => "missingAuthor",
+            MissingCommitter => "missingCommitter",
+            MissingEmail => "missingEmail",
+            MissingNameBeforeEmail => "missingNameBeforeEmail",
+            MissingObject => "missingObject",
+            MissingSpaceBeforeDate => "missingSpaceBeforeDate",
+            MissingSpaceBeforeEmail => "missingSpaceBeforeEmail",
+            MissingTagEntry => "missingTagEntry",
+            MissingTaggerEntry => "missingTaggerEntry",
+            MissingTree => "missingTree",
+            MissingTypeEntry => "missingTypeEntry",
+            MultipleAuthors => "multipleAuthors",
+            NulInCommit => "nulInCommit",
+            NulInHeader => "nulInHeader",
+            NullSha1 => "nullSha1",
+            TreeNotSorted => "treeNotSorted",
+            UnterminatedHeader => "unterminatedHeader",
+            ZeroPaddedDate => "zeroPaddedDate",
+            ZeroPaddedFilemode => "zeroPaddedFilemode",
+        }
+    }
+
+    /// Find the id with the given `name`, compared case-insensitively like `git config` keys are.
+    pub fn from_name(name: &BStr) -> Option<Self> {
+        Self::ALL
+            .iter()
+            .find(|id| name.eq_ignore_ascii_case(id.name().as_bytes()))
+            .copied()
+    }
+
+    /// The severity `git fsck` uses by default for this id.
+    ///
+    /// Ids that `git` considers informational are warnings here, just as `git fsck` reports them.
+    pub fn default_severity(&self) -> Severity {
+        use MessageId::*;
+        match self {
+            BadFilemode | BadTagName | EmptyName | FullPathname | GitattributesSymlink | GitignoreSymlink | HasDot
+            | HasDotdot | HasDotgit | MailmapSymlink | MissingTaggerEntry | NulInCommit | NullSha1
+            | ZeroPaddedFilemode => Severity::Warn,
+            _ => Severity::Error,
+        }
+    }
+
+    /// A description of the problem.
+    pub fn description(&self) -> &'static str {
+        use MessageId::*;
+        match self {
+            BadDate => "invalid author/committer line - bad date",
+            BadDateOverflow => "invalid author/committer line - date causes integer overflow",
+            BadEmail => "invalid author/committer line - bad email",
+            BadFilemode => "contains bad file modes",
+            BadName => "invalid author/committer line - bad name",
+            BadObjectSha1 => "invalid 'object' line format - bad sha1",
+            BadParentSha1 => "invalid 'parent' line format - bad sha1",
+            BadTagName => "invalid 'tag' name",
+            BadTimezone => "invalid author/committer line - bad time zone",
+            BadTree => "cannot be parsed as a tree",
+            BadTreeSha1 => "invalid 'tree' line format - bad sha1",
+            BadType => "invalid 'type' value",
+            DuplicateEntries => "contains duplicate file entries",
+            EmptyName => "contains empty pathname",
+            FullPathname => "contains full pathnames",
+            GitattributesSymlink => ".gitattributes is a symlink",
+            GitignoreSymlink => ".gitignore is a symlink",
+            GitmodulesSymlink => ".gitmodules is a symbolic link",
+            HasDot => "contains '.'",
+            HasDotdot => "contains '..'",
+            HasDotgit => "contains '.git'",
+            MailmapSymlink => ".mailmap is a symlink",
+            MissingAuthor => "invalid format - expected 'author' line",
+            MissingCommitter => "invalid format - expected 'committer' line",
+            MissingEmail => "invalid author/committer line - missing email",
+            MissingNameBeforeEmail => "invalid author/committer line - missing space before email",
+            MissingObject => "invalid format - expected 'object' line",
+            MissingSpaceBeforeDate => "invalid author/committer line - missing space before date",
+            MissingSpaceBeforeEmail => "invalid author/committer line - missing space before email",
+            MissingTagEntry => "invalid format - expected 'tag' line",
+            MissingTaggerEntry => "invalid format - expected 'tagger' line",
+            MissingTree => "invalid format - expected 'tree' line",
+            MissingTypeEntry => "invalid format - expected 'type' line",
+            MultipleAuthors => "invalid format - multiple 'author' lines",
+            NulInCommit => "NUL byte in the commit object body",
+            NulInHeader => "unterminated header: NUL in header",
+            NullSha1 => "contains entries pointing to null sha1",
+            TreeNotSorted => "not properly sorted",
+            UnterminatedHeader => "unterminated header",
+            ZeroPaddedDate => "invalid author/committer line - zero-padded date",
+            ZeroPaddedFilemode => "contains zero-padded file modes",
+        }
+    }
+}
+
+/// A problem found in an object.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Problem {
+    /// What the problem is.
+    pub id: MessageId,
+    /// How severe it is, as configured, which is never [`Severity::Ignore`].
+    pub severity: Severity,
+}
+
+impl std::fmt::Display for Problem {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.id.name(), self.id.description())
+    }
+}
+
+/// Options for use in [`object()`] and the functions to validate specific kinds of objects.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Options {
+    /// The kind of hash that objects refer to other objects with.
+    pub object_hash: gix_hash::Kind,
+    /// Severities that override the [default severity](MessageId::default_severity()), like `fsck.<msg-id>` does.
+    pub severities: BTreeMap<MessageId, Severity>,
+    /// If `true`, like `git fsck --strict`, file modes like `100664` are considered [bad](MessageId::BadFilemode),
+    /// even though old versions of git used to write them.
+    pub strict: bool,
+    /// If `true`, backslashes in tree entry names are considered [path separators](MessageId::FullPathname), just like they
+    /// would be when checked out on Windows.
+    pub protect_windows: bool,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            object_hash: gix_hash::Kind::Sha1,
+            severities: Default::default(),
+            strict: false,
+            protect_windows: false,
+        }
+    }
+}
+
+impl Options {
+    /// Return the severity of `id` as configured.
+    pub fn severity(&self, id: MessageId) -> Severity {
+        self.severities
+            .get(&id)
+            .copied()
+            .unwrap_or_else(|| id.default_severity())
+    }
+
+    fn report(&self, out: &mut Vec<Problem>, id: MessageId) {
+        let severity = self.severity(id);
+        if severity != Severity::Ignore && !out.iter().any(|p| p.id == id) {
+            out.push(Problem { id, severity });
+        }
+    }
+}
+
+/// Validate `data` of an object of `kind` according to `options` and return all problems that weren't ignored.
+pub fn object(kind: Kind, data: &[u8], options: &Options) -> Vec<Problem> {
+    match kind {
+        Kind::Tree => tree(data, options),
+        Kind::Commit => commit(data, options),
+        Kind::Tag => tag(data, options),
+        Kind::Blob => Vec::new(),
+    }
+}
+
+/// Validate the `data` of a tree according to `options` and return all problems that weren't ignored.
+pub fn tree(mut data: &[u8], options: &Options) -> Vec<Problem> {
+    use MessageId::*;
+    let mut out = Vec::new();
+    let hash_len = options.object_hash.len_in_bytes();
+    let mut previous: Option<(&[u8], bool)> = None;
+    while !data.is_empty() {
+        let Some(space) = data.find_byte(b' ') else {
+            options.report(&mut out, BadTree);
+            break;
+        };
+        let raw_mode = &data[..space];
+        let Some(mode) = raw_mode
+            .iter()
+            .try_fold(0u32, |mode, b| {
+                (b'0'..=b'7')
+                    .contains(b)
+                    .then_some(mode)
+                    .and_then(|mode| mode.checked_mul(8))
+                    .map(|mode| mode + u32::from(b - b'0'))
+            })
+            .filter(|_| !raw_mode.is_empty())
+        else {
+            options.report(&mut out, BadTree);
+            break;
+        };
+        data = &data[space + 1..];
+        let Some(nul) = data.find_byte(0) else {
+            options.report(&mut out, BadTree);
+            break;
+        };
+        let name = &data[..nul];
+        data = &data[nul + 1..];
+        if data.len() < hash_len {
+            options.report(&mut out, BadTree);
+            break;
+        }
+        let (id, rest) = data.split_at(hash_len);
+        data = rest;
+
+        if raw_mode.len() > 1 && raw_mode[0] == b'0' {
+            options.report(&mut out, ZeroPaddedFilemode);
+        }
+        match mode {
+            0o100644 | 0o100755 | 0o120000 | 0o040000 | 0o160000 => {}
+            0o100664 if !options.strict => {}
+            _ => options.report(&mut out, BadFilemode),
+        }
+        if id.iter().all(|b| *b == 0) {
+            options.report(&mut out, NullSha1);
+        }
+        let is_tree = mode == 0o040000;
+        check_tree_entry_name(name.as_bstr(), mode, options, &mut out);
+
+        if let Some((previous_name, previous_is_tree)) = previous {
+            match tree_entry_order(previous_name, previous_is_tree, name, is_tree) {
+                std::cmp::Ordering::Less => {}
+                std::cmp::Ordering::Equal => options.report(&mut out, DuplicateEntries),
+                std::cmp::Ordering::Greater => options.report(&mut out, TreeNotSorted),
+            }
+        }
+        previous = Some((name, is_tree));
+    }
+    out
+}
+
+fn check_tree_entry_name(name: &BStr, mode: u32, options: &Options, out: &mut Vec<Problem>) {
+    use MessageId::*;
+    if name.is_empty() {
+        options.report(out, EmptyName);
+        return;
+    }
+    if name.contains(&b'/') || (options.protect_windows && name.contains(&b'\\')) {
+        options.report(out, FullPathname);
+    }
+    if name == "." {
+        options.report(out, HasDot);
+    } else if name == ".." {
+        options.report(out, HasDotdot);
+    }
+    let is_symlink = mode == 0o120000;
+    let protect = gix_validate::path::component::Options {
+        protect_windows: false,
+        protect_hfs: true,
+        protect_ntfs: true,
+    };
+    let validation = gix_validate::path::component(
+        name,
+        is_symlink.then_some(gix_validate::path::component::Mode::Symlink),
+        protect,
+    );
+    match validation {
+        Err(gix_validate::path::component::Error::DotGitDir) => options.report(out, HasDotgit),
+        Err(gix_validate::path::component::Error::SymlinkedGitModules) => options.report(out, GitmodulesSymlink),
+        _ => {}
+    }
+    if is_symlink {
+        use gix_validate::path::component::DotFile;
+        for (file, id) in [
+            (DotFile::GitAttributes, GitattributesSymlink),
+            (DotFile::GitIgnore, GitignoreSymlink),
+            (DotFile::Mailmap, MailmapSymlink),
+        ] {
+            if gix_validate::path::component_is_dot_file(name, file, protect) {
+                options.report(out, id);
+            }
+        }
+    }
+}
+
+/// Compare entries like git sorts them in trees, where trees are compared as if their name had a trailing slash.
+/// Entries with the same name are equal, no matter their mode.
+fn tree_entry_order(a: &[u8], a_is_tree: bool, b: &[u8], b_is_tree: bool) -> std::cmp::Ordering {
+    if a == b {
+        return std::cmp::Ordering::Equal;
+    }
+    let common = a.len().min(b.len());
+    a[..common].cmp(&b[..common]).then_with(|| {
+        let next = |name: &[u8], is_tree: bool| name.get(common).copied().or(is_tree.then_some(b'/'));
+        next(a, a_is_tree).cmp(&next(b, b_is_tree))
+    })
+}
+
+/// Validate the `data` of a commit according to `options` and return all problems that weren't ignored.
+pub fn commit(data: &[u8], options: &Options) -> Vec<Problem> {
+    use MessageId::*;
+    let mut out = Vec::new();
+    if !verify_headers(data, options, &mut out) {
+        return out;
+    }
+    let mut cursor = data;
+    'headers: {
+        let Some(tree) = header(&mut cursor, b"tree") else {
+            options.report(&mut out, MissingTree);
+            break 'headers;
+        };
+        if !is_hex_id(tree, options.object_hash) {
+            options.report(&mut out, BadTreeSha1);
+            break 'headers;
+        }
+        while let Some(parent) = header(&mut cursor, b"parent") {
+            if !is_hex_id(parent, options.object_hash) {
+                options.report(&mut out, BadParentSha1);
+                break 'headers;
+            }
+        }
+        let mut num_authors = 0;
+        while let Some(author) = header(&mut cursor, b"author") {
+            num_authors += 1;
+            if let Err(id) = ident(author) {
+                options.report(&mut out, id);
+                break 'headers;
+            }
+        }
+        match num_authors {
+            0 => {
+                options.report(&mut out, MissingAuthor);
+                break 'headers;
+            }
+            1 => {}
+            _ => {
+                options.report(&mut out, MultipleAuthors);
+                break 'headers;
+            }
+        }
+        let Some(committer) = header(&mut cursor, b"committer") else {
+            options.report(&mut out, MissingCommitter);
+            break 'headers;
+        };
+        if let Err(id) = ident(committer) {
+            options.report(&mut out, id);
+        }
+    }
+    if data.contains(&0) {
+        options.report(&mut out, NulInCommit);
+    }
+    out
+}
+
+/// Validate the `data` of an annotated tag according to `options` and return all problems that weren't ignored.
+pub fn tag(data: &[u8], options: &Options) -> Vec<Problem> {
+    use MessageId::*;
+    let mut out = Vec::new();
+    if !verify_headers(data, options, &mut out) {
+        return out;
+    }
+    let mut cursor = data;
+    let Some(object) = header(&mut cursor, b"object") else {
+        options.report(&mut out, MissingObject);
+        return out;
+    };
+    if !is_hex_id(object, options.object_hash) {
+        options.report(&mut out, BadObjectSha1);
+        return out;
+    }
+    let Some(kind) = header(&mut cursor, b"type") else {
+        options.report(&mut out, MissingTypeEntry);
+        return out;
+    };
+    if Kind::from_bytes(kind).is_err() {
+        options.report(&mut out, BadType);
+        return out;
+    }
+    let Some(name) = header(&mut cursor, b"tag") else {
+        options.report(&mut out, MissingTagEntry);
+        return out;
+    };
+    if gix_validate::tag::name(name.as_bstr()).is_err() {
+        options.report(&mut out, BadTagName);
+    }
+    match header(&mut cursor, b"tagger") {
+        Some(tagger) => {
+            if let Err(id) = ident(tagger) {
+                options.report(&mut out, id);
+            }
+        }
+        None => options.report(&mut out, MissingTaggerEntry),
+    }
+    out
+}
+
+/// Return `false` if the headers of `data` aren't terminated by an empty line or the end of `data`, or if they contain a null byte.
+fn verify_headers(data: &[u8], options: &Options, out: &mut Vec<Problem>) -> bool {
+    for (pos, byte) in data.iter().enumerate() {
+        match byte {
+            0 => {
+                options.report(out, MessageId::NulInHeader);
+                return false;
+            }
+            b'\n' if data.get(pos + 1) == Some(&b'\n') => return true,
+            _ => {}
+        }
+    }
+    if data.last() == Some(&b'\n') {
+        return true;
+    }
+    options.report(out, MessageId::UnterminatedHeader);
+    false
+}
+
+/// If `cursor` starts with a header named `name`, return its value without the trailing newline and advance past it.
+fn header<'a>(cursor: &mut &'a [u8], name: &[u8]) -> Option<&'a [u8]> {
+    let value = cursor.strip_prefix(name)?.strip_prefix(b" ")?;
+    let end = value.find_byte(b'\n').unwrap_or(value.len());
+    *cursor = value.get(end + 1..).unwrap_or_default();
+    Some(&value[..end])
+}
+
+fn is_hex_id(hex: &[u8], object_hash: gix_hash::Kind) -> bool {
+    hex.len() == object_hash.len_in_hex() && gix_hash::ObjectId::from_hex(hex).is_ok()
+}
+
+/// Validate an identity like `Name <email> 1234567890 +0100`, just like `git fsck` does.
+fn ident(value: &[u8]) -> Result<(), MessageId> {
+    use MessageId::*;
+    if value.first() == Some(&b'<') {
+        return Err(MissingNameBeforeEmail);
+    }
+    let pos = value.find_byteset(b"<>").unwrap_or(value.len());
+    match value.get(pos) {
+        Some(b'>') => return Err(BadName),
+        Some(b'<') => {}
+        _ => return Err(MissingEmail),
+    }
+    if value[pos - 1] != b' ' {
+        return Err(MissingSpaceBeforeEmail);
+    }
+    let rest = &value[pos + 1..];
+    let rest = match rest.find_byteset(b"<>") {
+        Some(end) if rest[end] == b'>' => &rest[end + 1..],
+        _ => return Err(BadEmail),
+    };
+    let rest = rest.strip_prefix(b" ").ok_or(MissingSpaceBeforeDate)?;
+    if rest.first() == Some(&b'0') && rest.get(1) != Some(&b' ') {
+        return Err(ZeroPaddedDate);
+    }
+    let num_digits = rest.iter().take_while(|b| b.is_ascii_digit()).count();
+    if num_digits == 0 {
+        return Err(BadDate);
+    }
+    if rest[..num_digits]
+        .to_str()
+        .ok()
+        .and_then(|seconds| seconds.parse::<gix_date::SecondsSinceUnixEpoch>().ok())
+        .is_none()
+    {
+        return Err(BadDateOverflow);
+    }
+    let timezone = rest[num_digits..].strip_prefix(b" ").ok_or(BadDate)?;
+    match timezone {
+        [b'+' | b'-', digits @ ..] if digits.len() == 4 && digits.iter().all(u8::is_ascii_digit) => Ok(()),
+        _ => Err(BadTimezone),
+    }
+}
@@ -43,6 +43,8 @@ mod traits;
 pub use traits::{Exists, Find, FindExt, FindObjectOrHeader, Header as FindHeader, HeaderExt, Write, WriteTo};
 
 pub mod encode;
+///
+pub mod fsck;
 pub(crate) mod parse;
 
 ///
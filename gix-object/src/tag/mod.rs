@@ -1,6 +1,6 @@
 use winnow::prelude::*;
 
-use crate::TagRef;
+use crate::{bstr::BString, Kind, Tag, TagRef};
 
 mod decode;
 
@@ -24,3 +24,50 @@ impl<'a> TagRef<'a> {
         gix_hash::ObjectId::from_hex(self.target).expect("prior validation")
     }
 }
+
+/// Lifecycle
+impl Tag {
+    /// Create a new tag named `name` pointing to `target` of `target_kind`, created by `tagger` and described by `message`.
+    ///
+    /// Just like `git tag`, a non-empty `message` is terminated with a newline if it isn't yet. Fails if `name` isn't valid.
+    pub fn new(
+        target: gix_hash::ObjectId,
+        target_kind: Kind,
+        name: impl Into<BString>,
+        tagger: Option<gix_actor::Signature>,
+        message: impl Into<BString>,
+    ) -> Result<Self, write::Error> {
+        let name = name.into();
+        write::validated_name(name.as_ref())?;
+        let mut message = message.into();
+        if !message.is_empty() && !message.ends_with(b"\n") {
+            message.push(b'\n');
+        }
+        Ok(Tag {
+            target,
+            target_kind,
+            name,
+            tagger,
+            message,
+            pgp_signature: None,
+        })
+    }
+}
+
+/// Access
+impl Tag {
+    /// Return the data that a signature of this tag has to be made over, which is everything that is written before the signature.
+    ///
+    /// The resulting signature can then be set as [`pgp_signature`](Tag::pgp_signature) so the tag is signed.
+    /// Fails only if the name of the tag is invalid.
+    pub fn signed_data(&self) -> std::io::Result<BString> {
+        use crate::WriteTo;
+        let unsigned = Tag {
+            pgp_signature: Some(BString::default()),
+            ..self.clone()
+        };
+        let mut buf = Vec::with_capacity(unsigned.size() as usize);
+        unsigned.write_to(&mut buf)?;
+        Ok(buf.into())
+    }
+}
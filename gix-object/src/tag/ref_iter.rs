@@ -53,6 +53,41 @@ impl<'a> TagRefIter<'a> {
     }
 }
 
+/// Access
+impl<'a> TagRefIter<'a> {
+    /// Parse `data` as tag and return its signature along with the data that was signed, which is everything before
+    /// the signature, or `None` if the tag isn't signed.
+    ///
+    /// Just like git, the signature starts at the last line that begins a PGP, SSH or X509 signature, and it
+    /// includes everything up to the end of `data`.
+    pub fn signature(data: &'a [u8]) -> Result<Option<(&'a BStr, &'a BStr)>, crate::decode::Error> {
+        let mut iter = TagRefIter::from_bytes(data);
+        for token in iter.by_ref() {
+            if let Token::Tagger(_) = token? {
+                break;
+            }
+        }
+        let body_start = data.len() - iter.data.len();
+        let mut offset = body_start;
+        let mut signature_start = None;
+        for line in data[body_start..].lines_with_terminator() {
+            if SIGNATURE_BEGIN.iter().any(|begin| line.starts_with(begin)) {
+                signature_start = Some(offset);
+            }
+            offset += line.len();
+        }
+        Ok(signature_start.map(|start| (data[start..].as_bstr(), data[..start].as_bstr())))
+    }
+}
+
+/// The first lines of all kinds of signatures git knows.
+const SIGNATURE_BEGIN: &[&[u8]] = &[
+    b"-----BEGIN PGP SIGNATURE-----",
+    b"-----BEGIN PGP MESSAGE-----",
+    b"-----BEGIN SSH SIGNATURE-----",
+    b"-----BEGIN SIGNED MESSAGE-----",
+];
+
 fn missing_field() -> crate::decode::Error {
     crate::decode::empty_error()
 }
@@ -94,7 +94,7 @@ impl crate::WriteTo for TagRef<'_> {
     }
 }
 
-fn validated_name(name: &BStr) -> Result<&BStr, Error> {
+pub(crate) fn validated_name(name: &BStr) -> Result<&BStr, Error> {
     gix_validate::tag::name(name)?;
     if name[0] == b'-' {
         return Err(Error::StartsWithDash);
@@ -11,6 +11,8 @@ pub mod editor;
 mod ref_iter;
 ///
 pub mod write;
+///
+pub mod writer;
 
 /// The state needed to apply edits instantly to in-memory trees.
 ///
@@ -36,6 +38,27 @@ pub struct Editor<'a> {
     tree_buf: Vec<u8>,
 }
 
+/// A serializer for trees that receives one entry at a time, in the order in which they are stored,
+/// and writes them right away to produce the tree data and its hash without keeping the entries themselves.
+///
+/// Each entry is checked to sort after the previous one the way git sorts trees, where the names of trees
+/// compare as if they had a trailing `/`, so that only valid trees can be produced.
+/// The buffer to write into is provided by the caller, which allows to reuse it across trees.
+#[derive(Debug)]
+pub struct Writer<'a> {
+    /// The tree data written so far.
+    out: &'a mut Vec<u8>,
+    /// The kind of hashes to produce.
+    object_hash: gix_hash::Kind,
+    /// The location of the filename of the last written entry in `out`, and whether it's a tree.
+    previous: Option<(std::ops::Range<usize>, bool)>,
+    /// The locations of the filenames of blobs in `out` which a tree written later could still have, each one a prefix of
+    /// the next, like `a` and `a.b`, as these sort before `a/` and thus before a tree named `a`.
+    blob_candidates: Vec<std::ops::Range<usize>>,
+    /// The amount of entries written so far.
+    num_entries: usize,
+}
+
 /// The mode of items storable in a tree, similar to the file mode on a unix file system.
 ///
 /// Used in [`mutable::Entry`][crate::tree::Entry] and [`EntryRef`].
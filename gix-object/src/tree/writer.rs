@@ -0,0 +1,131 @@
+use std::cmp::Ordering;
+
+use bstr::{BStr, BString, ByteSlice};
+
+use crate::{
+    encode::SPACE,
+    tree::{EntryMode, Writer},
+    Kind,
+};
+
+/// The error returned by [`Writer::push()`].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("Nullbytes are invalid in file paths as they are separators: {name:?}")]
+    NullbyteInFilename { name: BString },
+    #[error("Empty filenames are invalid in trees")]
+    EmptyFilename,
+    #[error("Entry {name:?} must not be written after {previous:?} as trees must be sorted")]
+    NotSorted { previous: BString, name: BString },
+    #[error("Entry {name:?} was already written")]
+    Duplicate { name: BString },
+}
+
+/// Lifecycle
+impl<'a> Writer<'a> {
+    /// Create a new writer that clears `out` and writes tree data into it, which will be hashed with `object_hash`.
+    pub fn new(out: &'a mut Vec<u8>, object_hash: gix_hash::Kind) -> Self {
+        out.clear();
+        Writer {
+            out,
+            object_hash,
+            previous: None,
+            blob_candidates: Vec::new(),
+            num_entries: 0,
+        }
+    }
+
+    /// Finish the tree and return its id along with its data, which is exactly what would be stored in the object database.
+    pub fn finish(self) -> (gix_hash::ObjectId, &'a [u8]) {
+        let out: &'a Vec<u8> = self.out;
+        (crate::compute_hash(self.object_hash, Kind::Tree, out), out)
+    }
+}
+
+/// Access
+impl Writer<'_> {
+    /// Return the amount of entries written so far.
+    pub fn num_entries(&self) -> usize {
+        self.num_entries
+    }
+
+    /// Return the tree data written so far.
+    pub fn data(&self) -> &[u8] {
+        self.out
+    }
+}
+
+/// Mutation
+impl Writer<'_> {
+    /// Write an entry with `mode` and `filename` that points to `oid`.
+    ///
+    /// It fails if it doesn't sort after the previously written entry, or if `filename` is already present,
+    /// no matter the mode of the entry that has it, leaving the written data unchanged.
+    pub fn push(&mut self, mode: EntryMode, filename: &BStr, oid: &gix_hash::oid) -> Result<&mut Self, Error> {
+        if filename.is_empty() {
+            return Err(Error::EmptyFilename);
+        }
+        if filename.find_byte(0).is_some() {
+            return Err(Error::NullbyteInFilename {
+                name: filename.to_owned(),
+            });
+        }
+        let is_tree = mode.is_tree();
+        // Blobs whose name isn't a prefix of this one can't have the name of this or any later, correctly sorted entry.
+        let num_candidates = self
+            .blob_candidates
+            .iter()
+            .take_while(|candidate| filename.starts_with(&self.out[(*candidate).clone()]))
+            .count();
+        if is_tree {
+            if let Some(candidate) = self.blob_candidates[..num_candidates].last() {
+                if self.out[candidate.clone()] == filename[..] {
+                    return Err(Error::Duplicate {
+                        name: filename.to_owned(),
+                    });
+                }
+            }
+        }
+        if let Some((previous, previous_is_tree)) = self.previous.clone() {
+            let previous = self.out[previous].as_bstr();
+            if previous == filename {
+                return Err(Error::Duplicate {
+                    name: filename.to_owned(),
+                });
+            }
+            if compare(previous, previous_is_tree, filename, is_tree) != Ordering::Less {
+                return Err(Error::NotSorted {
+                    previous: previous.to_owned(),
+                    name: filename.to_owned(),
+                });
+            }
+        }
+
+        self.blob_candidates.truncate(num_candidates);
+
+        let mut buf = Default::default();
+        self.out.extend_from_slice(mode.as_bytes(&mut buf));
+        self.out.extend_from_slice(SPACE);
+        let start = self.out.len();
+        self.out.extend_from_slice(filename);
+        if !is_tree {
+            self.blob_candidates.push(start..self.out.len());
+        }
+        self.previous = Some((start..self.out.len(), is_tree));
+        self.out.push(0);
+        self.out.extend_from_slice(oid.as_bytes());
+        self.num_entries += 1;
+        Ok(self)
+    }
+}
+
+/// Compare names like git does in trees, where the names of trees compare as if they had a trailing `/`.
+fn compare(a: &BStr, a_is_tree: bool, b: &BStr, b_is_tree: bool) -> Ordering {
+    let common = a.len().min(b.len());
+    a[..common].cmp(&b[..common]).then_with(|| {
+        let a = a.get(common).or_else(|| a_is_tree.then_some(&b'/'));
+        let b = b.get(common).or_else(|| b_is_tree.then_some(&b'/'));
+        a.cmp(&b)
+    })
+}
@@ -0,0 +1,148 @@
+use gix_object::{
+    bstr::{BStr, ByteSlice},
+    commit::{builder::Error, Builder},
+    CommitRef, WriteTo,
+};
+
+use crate::{fixture_name, hex_to_id, signature};
+
+fn write(builder: &Builder) -> Vec<u8> {
+    let mut buf = Vec::new();
+    builder.write_to(&mut buf).expect("writing to a vec works");
+    assert_eq!(builder.size(), buf.len() as u64, "size is correct");
+    buf
+}
+
+#[test]
+fn round_trips_all_fixtures_exactly() -> crate::Result {
+    for name in [
+        "merge.txt",
+        "mergetag.txt",
+        "message-with-footer.txt",
+        "signed-singleline.txt",
+        "signed-whitespace.txt",
+        "signed-with-encoding.txt",
+        "signed.txt",
+        "two-multiline-headers.txt",
+        "unsigned.txt",
+        "whitespace.txt",
+        "with-encoding.txt",
+    ] {
+        let input = fixture_name("commit", name);
+        let builder = Builder::from_bytes(&input)?;
+        assert_eq!(write(&builder).as_bstr(), input.as_bstr(), "{name}");
+    }
+    Ok(())
+}
+
+#[test]
+fn headers_keep_their_order_and_bytes_when_other_parts_change() -> crate::Result {
+    let input = fixture_name("commit", "signed-with-encoding.txt");
+    let mut builder = Builder::from_bytes(&input)?;
+    assert_eq!(
+        builder.headers().map(|h| h.name().to_owned()).collect::<Vec<_>>(),
+        ["encoding", "gpgsig"]
+    );
+    let original_signature = builder.header_value("gpgsig".into()).map(ToOwned::to_owned);
+
+    builder
+        .message("new message\n")
+        .tree(hex_to_id("4b825dc642cb6eb9a060e54bf8d69288fbfc1234"));
+    let actual = write(&builder);
+    let commit = CommitRef::from_bytes(&actual)?;
+    assert_eq!(commit.message, "new message\n");
+    assert_eq!(commit.encoding, Some(b"ISO-8859-1".as_bstr()));
+    assert_eq!(
+        commit.extra_headers().pgp_signature(),
+        original_signature.as_ref().map(|s| s.as_bstr())
+    );
+    let header_start = |data: &[u8]| data.find("encoding").expect("present");
+    let header_end = |data: &[u8]| data.find("\n\n").expect("present");
+    assert_eq!(
+        actual[header_start(&actual)..header_end(&actual)].as_bstr(),
+        input[header_start(&input)..header_end(&input)].as_bstr(),
+        "the headers are written exactly as they were"
+    );
+    Ok(())
+}
+
+#[test]
+fn re_signing() -> crate::Result {
+    let input = fixture_name("commit", "signed.txt");
+    let mut builder = Builder::from_bytes(&input)?;
+    assert_eq!(builder.remove_header("gpgsig".into()), 1);
+    let unsigned = write(&builder);
+    assert_eq!(
+        unsigned.as_bstr(),
+        gix_object::CommitRefIter::signature(&input)?
+            .expect("signed")
+            .1
+            .to_bstring(),
+        "removing the signature yields the data that was signed"
+    );
+
+    builder.set_header("gpgsig", "-----BEGIN SIGNATURE-----\nnew\n-----END SIGNATURE-----")?;
+    let actual = write(&builder);
+    let commit = CommitRef::from_bytes(&actual)?;
+    assert_eq!(
+        commit.extra_headers().pgp_signature(),
+        Some(b"-----BEGIN SIGNATURE-----\nnew\n-----END SIGNATURE-----\n".as_bstr()),
+        "multi-line values are read back with a trailing newline"
+    );
+    assert_eq!(
+        builder.header_value("gpgsig".into()),
+        commit.extra_headers().pgp_signature(),
+        "the builder stores values like they are read back"
+    );
+    assert_eq!(
+        write(&Builder::from_bytes(&actual)?),
+        actual,
+        "and the re-signed commit round-trips"
+    );
+    Ok(())
+}
+
+#[test]
+fn new_commits_and_header_edits() -> crate::Result {
+    let tree = hex_to_id("4b825dc642cb6eb9a060e54bf8d69288fbfc1234");
+    let mut builder = Builder::new(tree, signature(1).into(), signature(2).into(), "message");
+    builder
+        .parent(hex_to_id("09d8d3a12e161a7f6afb522dbe8900a9c09bce06"))
+        .header("x-custom", "one")?
+        .header("x-custom", "two")?
+        .encoding(Some("ISO-8859-1".into()))?;
+    assert_eq!(
+        builder
+            .headers()
+            .map(|h| format!("{}={}", h.name(), h.value()))
+            .collect::<Vec<_>>(),
+        ["encoding=ISO-8859-1", "x-custom=one", "x-custom=two"],
+        "encoding goes first, like git writes it"
+    );
+
+    builder.set_header("x-custom", "three")?;
+    assert_eq!(builder.header_value("x-custom".into()), Some("three".into()));
+    assert_eq!(builder.headers().len(), 2, "other headers of the same name are removed");
+
+    builder.encoding(None)?;
+    assert_eq!(builder.header_value("encoding".into()), None::<&BStr>);
+
+    let actual = write(&builder);
+    let commit = CommitRef::from_bytes(&actual)?;
+    assert_eq!(commit.parents().count(), 1);
+    assert_eq!(commit.extra_headers.len(), 1);
+    assert_eq!(commit.extra_headers[0].1.as_ref(), "three");
+    assert_eq!(builder.clone().into_commit().extra_headers.len(), 1);
+
+    assert!(matches!(builder.header("", "v"), Err(Error::InvalidHeaderName { .. })));
+    assert!(matches!(
+        builder.header("a b", "v"),
+        Err(Error::InvalidHeaderName { .. })
+    ));
+    assert!(matches!(
+        builder.header("parent", "v"),
+        Err(Error::ReservedHeaderName { .. })
+    ));
+    assert!(matches!(builder.header("x", ""), Err(Error::EmptyValue)));
+    Ok(())
+}
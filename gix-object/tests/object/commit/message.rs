@@ -220,6 +220,191 @@ mod body {
     }
 }
 
+mod trailer {
+    use gix_object::{
+        bstr::BString,
+        commit::message::trailer::{Editor, IfExists, IfMissing, Options, Placement, Trailer},
+    };
+
+    fn edit(message: &str, options: Options, add: &[(&str, &str)]) -> BString {
+        let mut editor = Editor::new(message.into(), options);
+        for (token, value) in add {
+            editor.add(*token, *value);
+        }
+        editor.into_message()
+    }
+
+    #[test]
+    fn parse_with_continuation_lines_and_custom_separators() {
+        let editor = Editor::new(
+            "title\n\nbody\n\nFixes #123\nSigned-off-by: A <a@example.com>\nNote: first\n  second\n".into(),
+            Options {
+                separators: ":#".into(),
+                ..Default::default()
+            },
+        );
+        assert_eq!(
+            editor.trailers().cloned().collect::<Vec<_>>(),
+            vec![
+                Trailer {
+                    token: "Fixes".into(),
+                    value: "123".into()
+                },
+                Trailer {
+                    token: "Signed-off-by".into(),
+                    value: "A <a@example.com>".into()
+                },
+                Trailer {
+                    token: "Note".into(),
+                    value: "first second".into()
+                },
+            ]
+        );
+        assert_eq!(
+            editor.values("signed-off-by".into()).collect::<Vec<_>>(),
+            ["A <a@example.com>"]
+        );
+    }
+
+    #[test]
+    fn paragraphs_with_other_lines_are_no_trailers() {
+        let editor = Editor::new("title\n\nbody\nSigned-off-by: A\n".into(), Options::default());
+        assert_eq!(editor.trailers().len(), 0);
+        let editor = Editor::new("Signed-off-by: A\n".into(), Options::default());
+        assert_eq!(editor.trailers().len(), 0, "the title is never a trailer");
+    }
+
+    #[test]
+    fn add_to_message_without_trailers() {
+        assert_eq!(
+            edit("title\n\nbody", Options::default(), &[("Signed-off-by", "A")]),
+            "title\n\nbody\n\nSigned-off-by: A\n"
+        );
+        assert_eq!(
+            edit("title\n", Options::default(), &[("Signed-off-by", "A")]),
+            "title\n\nSigned-off-by: A\n"
+        );
+        assert_eq!(
+            edit("", Options::default(), &[("Signed-off-by", "A")]),
+            "Signed-off-by: A\n"
+        );
+    }
+
+    #[test]
+    fn existing_trailers_are_kept_as_is() {
+        assert_eq!(
+            edit(
+                "title\n\nNote :  first\n  second\n\n",
+                Options::default(),
+                &[("Co-authored-by", "B")]
+            ),
+            "title\n\nNote :  first\n  second\nCo-authored-by: B\n"
+        );
+    }
+
+    #[test]
+    fn if_exists_add_if_different_neighbor_by_default() {
+        let message = "title\n\nSigned-off-by: A\nAcked-by: B\n";
+        assert_eq!(
+            edit(message, Options::default(), &[("signed-off-by", "A")]),
+            "title\n\nSigned-off-by: A\nAcked-by: B\nsigned-off-by: A\n",
+            "the last trailer is the neighbor, and it differs"
+        );
+        assert_eq!(
+            edit(message, Options::default(), &[("Acked-by", "B")]),
+            message,
+            "the same trailer is the neighbor"
+        );
+    }
+
+    #[test]
+    fn if_exists() {
+        let message = "title\n\nSigned-off-by: A\nAcked-by: B\n";
+        let opts = |if_exists| Options {
+            if_exists,
+            ..Default::default()
+        };
+        assert_eq!(
+            edit(message, opts(IfExists::AddIfDifferent), &[("Signed-off-by", "A")]),
+            message
+        );
+        assert_eq!(
+            edit(message, opts(IfExists::Add), &[("Acked-by", "B")]),
+            "title\n\nSigned-off-by: A\nAcked-by: B\nAcked-by: B\n"
+        );
+        assert_eq!(
+            edit(message, opts(IfExists::Replace), &[("Signed-off-by", "C")]),
+            "title\n\nAcked-by: B\nSigned-off-by: C\n"
+        );
+        assert_eq!(
+            edit(message, opts(IfExists::DoNothing), &[("Signed-off-by", "C")]),
+            message
+        );
+    }
+
+    #[test]
+    fn if_missing() {
+        let message = "title\n\nSigned-off-by: A\n";
+        assert_eq!(
+            edit(
+                message,
+                Options {
+                    if_missing: IfMissing::DoNothing,
+                    ..Default::default()
+                },
+                &[("Acked-by", "B")]
+            ),
+            message
+        );
+    }
+
+    #[test]
+    fn placement() {
+        let message = "title\n\nA: 1\nB: 2\nA: 3\nC: 4\n";
+        let opts = |placement| Options {
+            placement,
+            ..Default::default()
+        };
+        assert_eq!(
+            edit(message, opts(Placement::Start), &[("A", "5")]),
+            "title\n\nA: 5\nA: 1\nB: 2\nA: 3\nC: 4\n"
+        );
+        assert_eq!(
+            edit(message, opts(Placement::After), &[("A", "5")]),
+            "title\n\nA: 1\nB: 2\nA: 3\nA: 5\nC: 4\n"
+        );
+        assert_eq!(
+            edit(message, opts(Placement::Before), &[("A", "5"), ("D", "6")]),
+            "title\n\nD: 6\nA: 5\nA: 1\nB: 2\nA: 3\nC: 4\n"
+        );
+        assert_eq!(
+            edit(
+                message,
+                Options {
+                    placement: Placement::Before,
+                    if_exists: IfExists::Replace,
+                    ..Default::default()
+                },
+                &[("A", "5")]
+            ),
+            "title\n\nA: 5\nB: 2\nA: 3\nC: 4\n",
+            "the first trailer with the same token is replaced"
+        );
+        assert_eq!(
+            edit(
+                message,
+                Options {
+                    separators: "=".into(),
+                    ..Default::default()
+                },
+                &[("B", "5")]
+            ),
+            "title\n\nA: 1\nB: 2\nA: 3\nC: 4\n\nB= 5\n",
+            "trailers need one of the separators to be recognized"
+        );
+    }
+}
+
 mod summary {
     use std::borrow::Cow;
 
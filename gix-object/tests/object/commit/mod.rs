@@ -186,6 +186,7 @@ fn invalid() {
     );
 }
 
+mod builder;
 mod from_bytes;
 mod iter;
 mod message;
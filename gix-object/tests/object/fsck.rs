@@ -0,0 +1,286 @@
+use gix_object::{
+    bstr::ByteSlice,
+    fsck::{self, MessageId, Options, Severity},
+};
+
+fn tree_entry(mode: &str, name: &str) -> Vec<u8> {
+    tree_entry_with_id(mode, name, 1)
+}
+
+fn tree_entry_with_id(mode: &str, name: &str, id_byte: u8) -> Vec<u8> {
+    let mut out = format!("{mode} {name}\0").into_bytes();
+    out.extend_from_slice(&[id_byte; 20]);
+    out
+}
+
+fn ids(problems: Vec<fsck::Problem>) -> Vec<MessageId> {
+    problems.into_iter().map(|p| p.id).collect()
+}
+
+#[test]
+fn message_ids_round_trip_through_their_names() {
+    for id in MessageId::ALL {
+        assert_eq!(MessageId::from_name(id.name().as_bytes().as_bstr()), Some(*id));
+        assert_eq!(
+            MessageId::from_name(id.name().to_ascii_lowercase().as_bytes().as_bstr()),
+            Some(*id),
+            "names are case-insensitive"
+        );
+    }
+    assert_eq!(MessageId::from_name("doesNotExist".into()), None);
+    assert_eq!(Severity::from_name("WARN".into()), Some(Severity::Warn));
+    assert_eq!(Severity::from_name("info".into()), None);
+}
+
+#[test]
+fn valid_tree_has_no_problems() {
+    let tree: Vec<u8> = [
+        tree_entry("100644", "a"),
+        tree_entry("40000", "a.b"),
+        tree_entry("40000", "a0"),
+        tree_entry("120000", "link"),
+        tree_entry("160000", "sub"),
+    ]
+    .concat();
+    assert_eq!(fsck::tree(&tree, &Options::default()), vec![]);
+}
+
+#[test]
+fn tree_entries_must_be_sorted_and_unique() {
+    let tree: Vec<u8> = [tree_entry("100644", "b"), tree_entry("100644", "a")].concat();
+    assert_eq!(ids(fsck::tree(&tree, &Options::default())), [MessageId::TreeNotSorted]);
+
+    let tree: Vec<u8> = [tree_entry("40000", "a"), tree_entry("100644", "a.b")].concat();
+    assert_eq!(
+        ids(fsck::tree(&tree, &Options::default())),
+        [MessageId::TreeNotSorted],
+        "trees sort as if they had a trailing slash"
+    );
+
+    let tree: Vec<u8> = [tree_entry("100644", "a"), tree_entry("40000", "a")].concat();
+    assert_eq!(
+        ids(fsck::tree(&tree, &Options::default())),
+        [MessageId::DuplicateEntries]
+    );
+}
+
+#[test]
+fn tree_modes() {
+    let tree = tree_entry("040000", "a");
+    let problems = fsck::tree(&tree, &Options::default());
+    assert_eq!(ids(problems.clone()), [MessageId::ZeroPaddedFilemode]);
+    assert_eq!(problems[0].severity, Severity::Warn);
+
+    let tree = tree_entry("100664", "a");
+    assert_eq!(fsck::tree(&tree, &Options::default()), vec![], "tolerated by default");
+    assert_eq!(
+        ids(fsck::tree(
+            &tree,
+            &Options {
+                strict: true,
+                ..Default::default()
+            }
+        )),
+        [MessageId::BadFilemode]
+    );
+
+    let tree = tree_entry("100600", "a");
+    assert_eq!(ids(fsck::tree(&tree, &Options::default())), [MessageId::BadFilemode]);
+
+    let tree = tree_entry("10x644", "a");
+    assert_eq!(ids(fsck::tree(&tree, &Options::default())), [MessageId::BadTree]);
+}
+
+#[test]
+fn tree_entry_names() {
+    for (name, expected) in [
+        (".", MessageId::HasDot),
+        ("..", MessageId::HasDotdot),
+        (".git", MessageId::HasDotgit),
+        (".GIT", MessageId::HasDotgit),
+        ("git~1", MessageId::HasDotgit),
+        ("a/b", MessageId::FullPathname),
+    ] {
+        let tree = tree_entry("40000", name);
+        assert_eq!(ids(fsck::tree(&tree, &Options::default())), [expected], "{name}");
+    }
+
+    let tree = tree_entry("120000", ".gitmodules");
+    assert_eq!(
+        ids(fsck::tree(&tree, &Options::default())),
+        [MessageId::GitmodulesSymlink]
+    );
+
+    for (name, expected) in [
+        (".gitattributes", MessageId::GitattributesSymlink),
+        (".GITIGNORE", MessageId::GitignoreSymlink),
+        ("gi250a~1", MessageId::GitignoreSymlink),
+        (".mailmap\u{200c}", MessageId::MailmapSymlink),
+        (".mailmap::$DATA", MessageId::MailmapSymlink),
+    ] {
+        let tree = tree_entry("120000", name);
+        assert_eq!(ids(fsck::tree(&tree, &Options::default())), [expected], "{name}");
+        let tree = tree_entry("100644", name);
+        assert_eq!(
+            fsck::tree(&tree, &Options::default()),
+            vec![],
+            "only symlinks are a problem"
+        );
+    }
+
+    let tree = tree_entry("100644", "a\\b");
+    assert_eq!(fsck::tree(&tree, &Options::default()), vec![]);
+    assert_eq!(
+        ids(fsck::tree(
+            &tree,
+            &Options {
+                protect_windows: true,
+                ..Default::default()
+            }
+        )),
+        [MessageId::FullPathname]
+    );
+
+    let tree = tree_entry_with_id("100644", "a", 0);
+    assert_eq!(ids(fsck::tree(&tree, &Options::default())), [MessageId::NullSha1]);
+}
+
+#[test]
+fn severities_can_be_configured() {
+    let tree = tree_entry("040000", ".git");
+    let mut options = Options::default();
+    options
+        .severities
+        .insert(MessageId::ZeroPaddedFilemode, Severity::Ignore);
+    options.severities.insert(MessageId::HasDotgit, Severity::Error);
+    let problems = fsck::tree(&tree, &options);
+    assert_eq!(
+        problems,
+        [fsck::Problem {
+            id: MessageId::HasDotgit,
+            severity: Severity::Error
+        }]
+    );
+    assert_eq!(problems[0].to_string(), "hasDotgit: contains '.git'");
+}
+
+const TREE: &str = "tree 9bedf67800b2923982bdf60c89c57ce6f2a8ea04\n";
+const PARENT: &str = "parent 9bedf67800b2923982bdf60c89c57ce6f2a8ea04\n";
+
+fn commit(author: &str) -> Vec<u8> {
+    format!("{TREE}{PARENT}author {author}\ncommitter {author}\n\nmessage\n").into_bytes()
+}
+
+#[test]
+fn valid_commit_has_no_problems() {
+    assert_eq!(
+        fsck::commit(&commit("Name <email> 1234567890 +0100"), &Options::default()),
+        vec![]
+    );
+    assert_eq!(
+        fsck::object(
+            gix_object::Kind::Commit,
+            &commit("<email> 1234567890 +0100"),
+            &Options::default()
+        )
+        .len(),
+        1,
+        "object() dispatches by kind"
+    );
+}
+
+#[test]
+fn commit_identities() {
+    for (ident, expected) in [
+        ("<email> 1234567890 +0100", MessageId::MissingNameBeforeEmail),
+        ("Name> 1234567890 +0100", MessageId::BadName),
+        ("Name 1234567890 +0100", MessageId::MissingEmail),
+        ("Name<email> 1234567890 +0100", MessageId::MissingSpaceBeforeEmail),
+        ("Name <email 1234567890 +0100", MessageId::BadEmail),
+        ("Name <email>1234567890 +0100", MessageId::MissingSpaceBeforeDate),
+        ("Name <email> 01234567890 +0100", MessageId::ZeroPaddedDate),
+        ("Name <email> x +0100", MessageId::BadDate),
+        ("Name <email> 99999999999999999999999 +0100", MessageId::BadDateOverflow),
+        ("Name <email> 1234567890 0100", MessageId::BadTimezone),
+    ] {
+        assert_eq!(
+            ids(fsck::commit(&commit(ident), &Options::default())),
+            [expected],
+            "{ident}"
+        );
+    }
+}
+
+#[test]
+fn commit_headers() {
+    let author = "author Name <email> 1 +0000\n";
+    let committer = "committer Name <email> 1 +0000\n";
+    for (commit, expected) in [
+        (format!("{PARENT}{author}{committer}\n"), MessageId::MissingTree),
+        (format!("tree 9bedf678\n{author}{committer}\n"), MessageId::BadTreeSha1),
+        (
+            format!("{TREE}parent xyz\n{author}{committer}\n"),
+            MessageId::BadParentSha1,
+        ),
+        (format!("{TREE}{committer}\n"), MessageId::MissingAuthor),
+        (
+            format!("{TREE}{author}{author}{committer}\n"),
+            MessageId::MultipleAuthors,
+        ),
+        (format!("{TREE}{author}\n"), MessageId::MissingCommitter),
+        (format!("{TREE}{author}{committer}\0"), MessageId::NulInHeader),
+        (
+            format!("{TREE}{author}{committer}no newline"),
+            MessageId::UnterminatedHeader,
+        ),
+        (format!("{TREE}{author}{committer}\nbody\0"), MessageId::NulInCommit),
+    ] {
+        assert_eq!(
+            ids(fsck::commit(commit.as_bytes(), &Options::default())),
+            [expected],
+            "{commit:?}"
+        );
+    }
+}
+
+const OBJECT: &str = "object 9bedf67800b2923982bdf60c89c57ce6f2a8ea04\n";
+
+#[test]
+fn tag_headers() {
+    let tagger = "tagger Name <email> 1 +0000\n";
+    assert_eq!(
+        fsck::tag(
+            format!("{OBJECT}type commit\ntag v1.0\n{tagger}\nmessage\n").as_bytes(),
+            &Options::default()
+        ),
+        vec![]
+    );
+    for (tag, expected) in [
+        (format!("type commit\ntag v1.0\n{tagger}\n"), MessageId::MissingObject),
+        (
+            format!("object 123\ntype commit\ntag v1.0\n{tagger}\n"),
+            MessageId::BadObjectSha1,
+        ),
+        (format!("{OBJECT}tag v1.0\n{tagger}\n"), MessageId::MissingTypeEntry),
+        (format!("{OBJECT}type thing\ntag v1.0\n{tagger}\n"), MessageId::BadType),
+        (format!("{OBJECT}type commit\n{tagger}\n"), MessageId::MissingTagEntry),
+        (
+            format!("{OBJECT}type commit\ntag v1..0\n{tagger}\n"),
+            MessageId::BadTagName,
+        ),
+        (
+            format!("{OBJECT}type commit\ntag v1.0\n\n"),
+            MessageId::MissingTaggerEntry,
+        ),
+        (
+            format!("{OBJECT}type commit\ntag v1.0\ntagger Name\n\n"),
+            MessageId::MissingEmail,
+        ),
+    ] {
+        assert_eq!(
+            ids(fsck::tag(tag.as_bytes(), &Options::default())),
+            [expected],
+            "{tag:?}"
+        );
+    }
+}
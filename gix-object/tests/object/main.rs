@@ -4,6 +4,7 @@ use gix_hash::ObjectId;
 
 mod commit;
 mod encode;
+mod fsck;
 mod object_ref;
 mod tag;
 mod tree;
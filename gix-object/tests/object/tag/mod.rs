@@ -294,3 +294,84 @@ cjHJZXWmV4CcRfmLsXzU8s2cR9A0DBvOxhPD1TlKC2JhBFXigjuL9U4Rbq9tdegB
         }),
     }
 }
+
+mod create {
+    use gix_object::{bstr::ByteSlice, Kind, Tag, TagRef, TagRefIter, WriteTo};
+
+    use crate::{fixture_name, hex_to_id, signature};
+
+    fn new_tag(message: &str) -> Tag {
+        Tag::new(
+            hex_to_id("ffa700b4aca13b80cb6b98a078e7c96804f8e0ec"),
+            Kind::Commit,
+            "v1.0",
+            Some(signature(1).into()),
+            message,
+        )
+        .expect("valid name")
+    }
+
+    #[test]
+    fn messages_are_terminated_with_a_newline() {
+        assert_eq!(new_tag("message").message, "message\n");
+        assert_eq!(new_tag("message\n").message, "message\n");
+        assert_eq!(new_tag("").message, "", "empty messages stay empty");
+    }
+
+    #[test]
+    fn invalid_names_are_rejected() {
+        let id = hex_to_id("ffa700b4aca13b80cb6b98a078e7c96804f8e0ec");
+        for name in ["-v1", "v1..0", ""] {
+            assert!(Tag::new(id, Kind::Commit, name, None, "").is_err(), "{name:?}");
+        }
+    }
+
+    #[test]
+    fn sign_and_extract_signature() -> crate::Result {
+        let mut tag = new_tag("message");
+        assert_eq!(
+            TagRefIter::signature(&write(&tag))?,
+            None,
+            "unsigned tags have no signature"
+        );
+
+        let signed_data = tag.signed_data()?;
+        let signature = "-----BEGIN PGP SIGNATURE-----\nabc\n-----END PGP SIGNATURE-----\n";
+        tag.pgp_signature = Some(signature.into());
+        let data = write(&tag);
+        let (actual_signature, actual_signed_data) = TagRefIter::signature(&data)?.expect("signed");
+        assert_eq!(actual_signature, signature);
+        assert_eq!(
+            actual_signed_data, signed_data,
+            "the data to sign is the data before the signature"
+        );
+
+        let tag = TagRef::from_bytes(&data)?;
+        assert_eq!(tag.pgp_signature, Some(signature.as_bytes().as_bstr()));
+        assert_eq!(tag.message, "message\n");
+        Ok(())
+    }
+
+    #[test]
+    fn extract_signature_from_fixture_and_of_other_kinds() -> crate::Result {
+        let data = fixture_name("tag", "signed.txt");
+        let (signature, signed_data) = TagRefIter::signature(&data)?.expect("signed");
+        assert_eq!(Some(signature), TagRef::from_bytes(&data)?.pgp_signature);
+        assert_eq!(signed_data.len() + signature.len(), data.len());
+
+        let mut data = write(&new_tag("message"));
+        let signature = "-----BEGIN SSH SIGNATURE-----\nabc\n-----END SSH SIGNATURE-----\n";
+        data.extend_from_slice(signature.as_bytes());
+        assert_eq!(
+            TagRefIter::signature(&data)?.map(|(signature, _)| signature),
+            Some(signature.as_bytes().as_bstr())
+        );
+        Ok(())
+    }
+
+    fn write(tag: &Tag) -> Vec<u8> {
+        let mut buf = Vec::new();
+        tag.write_to(&mut buf).expect("valid tag");
+        buf
+    }
+}
@@ -0,0 +1,103 @@
+use gix_object::{
+    bstr::ByteSlice,
+    tree::{writer::Error, EntryKind, Writer},
+    TreeRef,
+};
+
+#[test]
+fn reproduces_existing_trees_exactly() -> crate::Result {
+    let root = gix_testtools::scripted_fixture_read_only("make_trees.sh")?;
+    let input = std::fs::read(root.join("tree.baseline"))?;
+    let tree = TreeRef::from_bytes(&input)?;
+
+    let mut buf = Vec::new();
+    let mut writer = Writer::new(&mut buf, gix_hash::Kind::Sha1);
+    for entry in &tree.entries {
+        writer.push(entry.mode, entry.filename, entry.oid)?;
+    }
+    assert_eq!(writer.num_entries(), tree.entries.len());
+    let (id, data) = writer.finish();
+    assert_eq!(data, input, "the data is the same");
+    assert_eq!(
+        id,
+        gix_object::compute_hash(gix_hash::Kind::Sha1, gix_object::Kind::Tree, &input)
+    );
+    Ok(())
+}
+
+#[test]
+fn empty_tree() {
+    let mut buf = b"garbage".to_vec();
+    let (id, data) = Writer::new(&mut buf, gix_hash::Kind::Sha1).finish();
+    assert!(data.is_empty(), "the buffer is cleared initially");
+    assert_eq!(id, gix_hash::ObjectId::empty_tree(gix_hash::Kind::Sha1));
+}
+
+#[test]
+fn entries_must_be_sorted_like_git_does() -> crate::Result {
+    let id = gix_hash::Kind::Sha1.null();
+    let mut buf = Vec::new();
+    let mut writer = Writer::new(&mut buf, gix_hash::Kind::Sha1);
+    writer
+        .push(EntryKind::Blob.into(), "a".into(), &id)?
+        .push(EntryKind::Blob.into(), "a.b".into(), &id)?
+        .push(EntryKind::Tree.into(), "a0".into(), &id)?;
+
+    let before = writer.data().to_owned();
+    assert!(matches!(
+        writer.push(EntryKind::Tree.into(), "a".into(), &id),
+        Err(Error::Duplicate { name }) if name == "a"
+    ));
+    assert!(matches!(
+        writer.push(EntryKind::Blob.into(), "a.c".into(), &id),
+        Err(Error::NotSorted { previous, name }) if previous == "a0" && name == "a.c"
+    ));
+    assert_eq!(writer.data(), before, "failed pushes don't change the data");
+
+    let mut writer = Writer::new(&mut buf, gix_hash::Kind::Sha1);
+    writer.push(EntryKind::Tree.into(), "a".into(), &id)?;
+    assert!(
+        matches!(
+            writer.push(EntryKind::Blob.into(), "a.b".into(), &id),
+            Err(Error::NotSorted { .. })
+        ),
+        "trees sort as if their name had a trailing slash, and '/' sorts after '.'"
+    );
+    writer.push(EntryKind::Blob.into(), "a0".into(), &id)?;
+    assert_eq!(writer.num_entries(), 2);
+
+    let mut writer = Writer::new(&mut buf, gix_hash::Kind::Sha1);
+    writer
+        .push(EntryKind::Blob.into(), "a".into(), &id)?
+        .push(EntryKind::Blob.into(), "a.b".into(), &id)?
+        .push(EntryKind::Blob.into(), "a.b.c".into(), &id)?;
+    assert!(
+        matches!(
+            writer.push(EntryKind::Tree.into(), "a.b".into(), &id),
+            Err(Error::Duplicate { name }) if name == "a.b"
+        ),
+        "blobs are remembered as long as trees of the same name could still follow"
+    );
+    writer.push(EntryKind::Tree.into(), "a.c".into(), &id)?;
+    assert!(matches!(
+        writer.push(EntryKind::Tree.into(), "a".into(), &id),
+        Err(Error::Duplicate { name }) if name == "a"
+    ));
+    Ok(())
+}
+
+#[test]
+fn invalid_entries() {
+    let id = gix_hash::Kind::Sha1.null();
+    let mut buf = Vec::new();
+    let mut writer = Writer::new(&mut buf, gix_hash::Kind::Sha1);
+    assert!(matches!(
+        writer.push(EntryKind::Blob.into(), "".into(), &id),
+        Err(Error::EmptyFilename)
+    ));
+    assert!(matches!(
+        writer.push(EntryKind::Blob.into(), b"a\0b".as_bstr(), &id),
+        Err(Error::NullbyteInFilename { .. })
+    ));
+    assert_eq!(writer.num_entries(), 0);
+}
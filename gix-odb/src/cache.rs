@@ -79,6 +79,13 @@ impl<S> Cache<S> {
     pub fn has_pack_cache(&self) -> bool {
         self.pack_cache.is_some()
     }
+    /// Return the statistics of the pack cache of this instance, or `None` if there is no pack cache or if it doesn't
+    /// keep statistics.
+    ///
+    /// Note that each instance has its own pack cache, so clones start with empty statistics.
+    pub fn pack_cache_statistics(&self) -> Option<gix_pack::cache::Statistics> {
+        self.pack_cache.as_ref()?.borrow().statistics()
+    }
     /// Remove the current pack cache as well as its constructor from this instance.
     pub fn unset_pack_cache(&mut self) {
         self.pack_cache = None;
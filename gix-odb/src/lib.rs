@@ -30,6 +30,10 @@ pub use store_impls::{dynamic as store, loose};
 
 pub mod alternate;
 
+///
+pub mod quarantine;
+pub use quarantine::Quarantine;
+
 /// A way to access objects along with pre-configured thread-local caches for packed base objects as well as objects themselves.
 ///
 /// By default, no cache will be used.
@@ -105,6 +109,8 @@ pub struct Store {
 
     /// The source directory from which all content is loaded, and the central write lock for use when a directory refresh is needed.
     pub(crate) path: PathBuf,
+    /// Object directories to use as alternates before the ones listed in `info/alternates`.
+    pub(crate) additional_alternates: Vec<PathBuf>,
 
     /// The current working directory at the time this store was instantiated. It becomes relevant when resolving alternate paths
     /// when re-reading the store configuration on updates when an object was missed.
@@ -130,10 +136,14 @@ pub struct Store {
 
     /// The amount of times we re-read the disk state to consolidate our in-memory representation.
     pub(crate) num_disk_state_consolidation: AtomicUsize,
+    /// Channels to inform about packs that appeared, disappeared or changed when consolidating with the disk state.
+    pub(crate) subscribers: parking_lot::Mutex<Vec<std::sync::mpsc::Sender<store::Event>>>,
     /// If true, we are allowed to use multi-pack indices and they must have the `object_hash` or be ignored.
     use_multi_pack_index: bool,
     /// The hash kind to use for some operations
     object_hash: gix_hash::Kind,
+    /// How to flush new loose objects to disk.
+    fsync: gix_features::fs::Fsync,
 }
 
 /// Create a new cached handle to the object store with support for additional options.
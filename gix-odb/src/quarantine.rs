@@ -0,0 +1,166 @@
+use std::path::{Path, PathBuf};
+
+/// A temporary object directory named `incoming-*` within an object database to receive objects into, keeping them
+/// isolated until they are known to be valid and can be [migrated](Quarantine::migrate()) into the object database.
+///
+/// It has the object database as alternate, so objects that are received can refer to objects that already exist.
+/// Everything in it is deleted when it is dropped without being migrated, leaving the object database unchanged.
+///
+/// This is the equivalent of the quarantine directory that `git receive-pack` uses.
+pub struct Quarantine {
+    dir: tempfile::TempDir,
+    objects_dir: PathBuf,
+}
+
+/// Lifecycle
+impl Quarantine {
+    /// Create a new quarantine directory within `objects_dir`, the directory of the object database that objects
+    /// will eventually be migrated into.
+    pub fn new(objects_dir: impl Into<PathBuf>) -> std::io::Result<Self> {
+        let objects_dir = objects_dir.into();
+        let dir = tempfile::Builder::new().prefix("incoming-").tempdir_in(&objects_dir)?;
+        std::fs::create_dir(dir.path().join("pack"))?;
+        let info_dir = dir.path().join("info");
+        std::fs::create_dir(&info_dir)?;
+        let alternate =
+            gix_path::realpath(&objects_dir).map_err(|err| std::io::Error::new(std::io::ErrorKind::Other, err))?;
+        let mut alternates = gix_path::into_bstr(alternate).into_owned();
+        alternates.push(b'\n');
+        std::fs::write(info_dir.join("alternates"), alternates)?;
+        Ok(Quarantine { dir, objects_dir })
+    }
+
+    /// Move all loose objects and packs into the object database, and delete the quarantine directory.
+    ///
+    /// Objects and packs that already exist in the object database are left as they are. Files that belong to
+    /// a pack are moved so that the pack index comes last, to never make a pack visible before its data is in place.
+    pub fn migrate(self) -> Result<migrate::Outcome, migrate::Error> {
+        let mut out = migrate::Outcome::default();
+        let read_dir = |dir: &Path| {
+            std::fs::read_dir(dir).map_err(|source| migrate::Error::ReadDir {
+                path: dir.to_owned(),
+                source,
+            })
+        };
+
+        let mut pack_files = Vec::new();
+        for entry in read_dir(self.dir.path())? {
+            let entry = entry.map_err(|source| migrate::Error::ReadDir {
+                path: self.dir.path().to_owned(),
+                source,
+            })?;
+            let name = entry.file_name();
+            let is_fan_out_dir = name.len() == 2
+                && name
+                    .to_str()
+                    .map_or(false, |name| name.bytes().all(|b| b.is_ascii_hexdigit()));
+            if name == "pack" {
+                for pack_entry in read_dir(&entry.path())? {
+                    let pack_entry = pack_entry.map_err(|source| migrate::Error::ReadDir {
+                        path: entry.path(),
+                        source,
+                    })?;
+                    pack_files.push(pack_entry.path());
+                }
+            } else if is_fan_out_dir {
+                let destination_dir = self.objects_dir.join(&name);
+                for object in read_dir(&entry.path())? {
+                    let object = object.map_err(|source| migrate::Error::ReadDir {
+                        path: entry.path(),
+                        source,
+                    })?;
+                    if move_file(&object.path(), &destination_dir.join(object.file_name()))? {
+                        out.num_loose_objects += 1;
+                    }
+                }
+            }
+        }
+
+        pack_files.sort_by(|a, b| pack_file_priority(a).cmp(&pack_file_priority(b)).then_with(|| a.cmp(b)));
+        let pack_dir = self.objects_dir.join("pack");
+        for source in pack_files {
+            let destination = pack_dir.join(source.file_name().expect("read from directory"));
+            if move_file(&source, &destination)? {
+                out.pack_files.push(destination);
+            }
+        }
+        Ok(out)
+    }
+}
+
+/// Access
+impl Quarantine {
+    /// The path to the quarantine object directory, which can be opened as object database.
+    pub fn path(&self) -> &Path {
+        self.dir.path()
+    }
+
+    /// The directory into which packs should be written.
+    pub fn pack_dir(&self) -> PathBuf {
+        self.dir.path().join("pack")
+    }
+
+    /// The directory of the object database that objects are migrated into.
+    pub fn objects_dir(&self) -> &Path {
+        &self.objects_dir
+    }
+}
+
+/// Move `source` to `destination` and return `true`, or return `false` if `destination` already exists.
+fn move_file(source: &Path, destination: &Path) -> Result<bool, migrate::Error> {
+    if destination.exists() {
+        return Ok(false);
+    }
+    let rename = || std::fs::rename(source, destination);
+    let res = match rename() {
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => destination.parent().map_or(Err(err), |parent| {
+            std::fs::create_dir_all(parent).and_then(|_| rename())
+        }),
+        res => res,
+    };
+    res.map_err(|source_err| migrate::Error::Move {
+        source_path: source.to_owned(),
+        destination: destination.to_owned(),
+        source: source_err,
+    })?;
+    Ok(true)
+}
+
+/// The order in which files in a pack directory are moved, which is the same as the one used by git.
+fn pack_file_priority(path: &Path) -> usize {
+    match path.extension().and_then(|ext| ext.to_str()) {
+        Some("keep") => 0,
+        Some("pack") => 1,
+        Some("rev") => 2,
+        Some("idx") => 3,
+        _ => 4,
+    }
+}
+
+///
+pub mod migrate {
+    use std::path::PathBuf;
+
+    /// The outcome of [`Quarantine::migrate()`](super::Quarantine::migrate()).
+    #[derive(Default, Debug, Clone, PartialEq, Eq)]
+    pub struct Outcome {
+        /// The amount of loose objects that were moved into the object database.
+        pub num_loose_objects: usize,
+        /// The paths of all pack files in the object database that were moved there, in the order they were moved.
+        pub pack_files: Vec<PathBuf>,
+    }
+
+    /// The error returned by [`Quarantine::migrate()`](super::Quarantine::migrate()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("Could not read quarantine directory at \"{}\"", path.display())]
+        ReadDir { path: PathBuf, source: std::io::Error },
+        #[error("Could not move \"{}\" to \"{}\"", source_path.display(), destination.display())]
+        Move {
+            source_path: PathBuf,
+            destination: PathBuf,
+            source: std::io::Error,
+        },
+    }
+}
@@ -7,6 +7,11 @@ impl Store {
         &self.path
     }
 
+    /// Object directories that are used as alternates in addition to the ones listed in `info/alternates`.
+    pub fn additional_alternates(&self) -> &[std::path::PathBuf] {
+        &self.additional_alternates
+    }
+
     /// The kind of object hash to assume when dealing with pack indices and pack data files.
     pub fn object_hash(&self) -> gix_hash::Kind {
         self.object_hash
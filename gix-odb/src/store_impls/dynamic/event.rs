@@ -0,0 +1,47 @@
+use std::path::PathBuf;
+
+/// A change to the pack indices known to a [`Store`](super::Store), as observed when it refreshes its view of the disk.
+///
+/// Note that no events are produced for the initial load of the pack directories.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Event {
+    /// A new pack index, or multi-pack index, appeared on disk.
+    Added {
+        /// The path to the new index file.
+        index_path: PathBuf,
+    },
+    /// A pack index, or multi-pack index, that was known before disappeared from disk.
+    Removed {
+        /// The path to the index file that was removed.
+        index_path: PathBuf,
+    },
+    /// A multi-pack index was rewritten.
+    Changed {
+        /// The path to the changed multi-pack index file.
+        index_path: PathBuf,
+    },
+}
+
+impl super::Store {
+    /// Return a receiver for [events](Event) about packs that appear, disappear or change on disk, which are sent whenever
+    /// this store refreshes its view of the pack directories.
+    ///
+    /// The store doesn't actively watch the disk, so refreshes happen as configured by the [`RefreshMode`](super::RefreshMode)
+    /// of handles when objects are missed.
+    /// Dropping the receiver unsubscribes.
+    pub fn subscribe(&self) -> std::sync::mpsc::Receiver<Event> {
+        let (tx, rx) = std::sync::mpsc::channel();
+        self.subscribers.lock().push(tx);
+        rx
+    }
+
+    /// Send all `events` to our subscribers, forgetting about the ones that went away.
+    pub(crate) fn emit(&self, events: Vec<Event>) {
+        if events.is_empty() {
+            return;
+        }
+        self.subscribers
+            .lock()
+            .retain(|tx| events.iter().all(|event| tx.send(event.clone()).is_ok()));
+    }
+}
@@ -352,6 +352,8 @@ impl TryFrom<&super::Store> for super::Store {
                 object_hash: Default::default(),
                 use_multi_pack_index: false,
                 current_dir: s.current_dir.clone().into(),
+                additional_alternates: s.additional_alternates.clone(),
+                fsync: s.fsync,
             },
         )
     }
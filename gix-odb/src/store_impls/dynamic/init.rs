@@ -1,4 +1,7 @@
-use std::{path::PathBuf, sync::Arc};
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+};
 
 use arc_swap::ArcSwap;
 
@@ -19,6 +22,13 @@ pub struct Options {
     /// The current directory of the process at the time of instantiation.
     /// If unset, it will be retrieved using `gix_fs::current_dir(false)`.
     pub current_dir: Option<std::path::PathBuf>,
+    /// Object directories to use as alternates in addition to the ones listed in `info/alternates`, and
+    /// which are searched before them, just like `GIT_ALTERNATE_OBJECT_DIRECTORIES` would be.
+    ///
+    /// Relative paths are resolved against `current_dir`, and alternates listed in these directories are followed as well.
+    pub additional_alternates: Vec<PathBuf>,
+    /// How to flush new loose objects to disk before moving them into place.
+    pub fsync: gix_features::fs::Fsync,
 }
 
 impl Default for Options {
@@ -28,6 +38,8 @@ impl Default for Options {
             object_hash: Default::default(),
             use_multi_pack_index: true,
             current_dir: None,
+            additional_alternates: Vec::new(),
+            fsync: Default::default(),
         }
     }
 }
@@ -77,6 +89,8 @@ impl Store {
             object_hash,
             use_multi_pack_index,
             current_dir,
+            additional_alternates,
+            fsync,
         }: Options,
     ) -> std::io::Result<Self> {
         let _span = gix_features::trace::detail!("gix_odb::Store::at()");
@@ -96,9 +110,8 @@ impl Store {
         let slot_count = match slots {
             Slots::Given(n) => n as usize,
             Slots::AsNeededByDiskState { multiplier, minimum } => {
-                let mut db_paths = crate::alternate::resolve(objects_dir.clone(), &current_dir)
+                let db_paths = object_db_paths(&objects_dir, &additional_alternates, &current_dir)
                     .map_err(|err| std::io::Error::new(std::io::ErrorKind::Other, err))?;
-                db_paths.insert(0, objects_dir.clone());
                 let num_slots = super::Store::collect_indices_and_mtime_sorted_by_size(db_paths, None, None)
                     .map_err(|err| std::io::Error::new(std::io::ErrorKind::Other, err))?
                     .len();
@@ -120,13 +133,41 @@ impl Store {
             write: Default::default(),
             replacements,
             path: objects_dir,
+            additional_alternates,
             files: Vec::from_iter(std::iter::repeat_with(MutableIndexAndPack::default).take(slot_count)),
             index: ArcSwap::new(Arc::new(SlotMapIndex::default())),
             use_multi_pack_index,
             object_hash,
+            fsync,
             num_handles_stable: Default::default(),
             num_handles_unstable: Default::default(),
             num_disk_state_consolidation: Default::default(),
+            subscribers: Default::default(),
         })
     }
 }
+
+/// Return `objects_dir` followed by all `additional_alternates` and all alternates of `objects_dir`, along with the
+/// alternates of `additional_alternates`, without duplicates.
+pub(crate) fn object_db_paths(
+    objects_dir: &Path,
+    additional_alternates: &[PathBuf],
+    current_dir: &Path,
+) -> Result<Vec<PathBuf>, crate::alternate::Error> {
+    let mut out = vec![objects_dir.to_owned()];
+    let mut push = |path: PathBuf| {
+        if !out.contains(&path) {
+            out.push(path);
+        }
+    };
+    for dir in additional_alternates {
+        let dir = current_dir.join(dir);
+        let alternates = crate::alternate::resolve(dir.clone(), current_dir)?;
+        push(dir);
+        alternates.into_iter().for_each(&mut push);
+    }
+    crate::alternate::resolve(objects_dir.to_owned(), current_dir)?
+        .into_iter()
+        .for_each(push);
+    Ok(out)
+}
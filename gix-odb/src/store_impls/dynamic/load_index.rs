@@ -10,7 +10,7 @@ use std::{
     time::SystemTime,
 };
 
-use crate::store::{handle, types, RefreshMode};
+use crate::store::{handle, types, Event, RefreshMode};
 
 pub(crate) struct Snapshot {
     /// Indices ready for object lookup or contains checks, ordered usually by modification data, recent ones first.
@@ -81,6 +81,17 @@ impl super::Store {
             return self.consolidate_with_disk_state(true /* needs_init */, false /*load one new index*/);
         }
 
+        if let RefreshMode::Always = refresh_mode {
+            if marker.generation == index.generation && marker.state_id == index.state_id() {
+                // Prefer new packs over the ones we know, even if not all of them are loaded yet.
+                if let Some(snapshot) =
+                    self.consolidate_with_disk_state(false /* needs init */, true /*load one new index*/)?
+                {
+                    return Ok(Some(snapshot));
+                }
+            }
+        }
+
         if marker.generation != index.generation || marker.state_id != index.state_id() {
             // We have a more recent state already, provide it.
             Ok(Some(self.collect_snapshot()))
@@ -92,7 +103,8 @@ impl super::Store {
             } else {
                 // …and if that didn't yield anything new consider refreshing our disk state.
                 match refresh_mode {
-                    RefreshMode::Never => Ok(None),
+                    // For `Always`, the disk state was just consolidated already.
+                    RefreshMode::Never | RefreshMode::Always => Ok(None),
                     RefreshMode::AfterAllIndicesLoaded => {
                         self.consolidate_with_disk_state(false /* needs init */, true /*load one new index*/)
                     }
@@ -214,9 +226,7 @@ impl super::Store {
         }
         self.num_disk_state_consolidation.fetch_add(1, Ordering::Relaxed);
 
-        let db_paths: Vec<_> = std::iter::once(objects_directory.to_owned())
-            .chain(crate::alternate::resolve(objects_directory.clone(), &self.current_dir)?)
-            .collect();
+        let db_paths = super::init::object_db_paths(objects_directory, &self.additional_alternates, &self.current_dir)?;
 
         // turn db paths into loose object databases. Reuse what's there, but only if it is in the right order.
         let loose_dbs = if was_uninitialized
@@ -229,7 +239,7 @@ impl super::Store {
             Arc::new(
                 db_paths
                     .iter()
-                    .map(|path| crate::loose::Store::at(path, self.object_hash))
+                    .map(|path| crate::loose::Store::at(path, self.object_hash).with_fsync(self.fsync))
                     .collect::<Vec<_>>(),
             )
         } else {
@@ -255,6 +265,8 @@ impl super::Store {
             .then(|| VecDeque::with_capacity(indices_by_modification_time.len()))
             .unwrap_or_default();
 
+        // Changes to report to subscribers, but only if we know a previous state to compare to.
+        let mut events = Vec::new();
         // Figure out this number based on what we see while handling the existing indices
         let mut num_loaded_indices = 0;
         for (index_info, mtime) in indices_by_modification_time.into_iter().map(|(a, b, _)| (a, b)) {
@@ -268,6 +280,9 @@ impl super::Store {
                         // we have a changed multi-pack index. We can't just change the existing slot as it may alter slot indices
                         // that are currently available. Instead we have to move what's there into a new slot, along with the changes,
                         // and later free the slot or dispose of the index in the slot (like we do for removed/missing files).
+                        events.push(Event::Changed {
+                            index_path: index_info.path().to_owned(),
+                        });
                         index_paths_to_add.push_back((index_info, mtime, Some(slot_idx)));
                         // If the current slot is loaded, the soon-to-be copied multi-index path will be loaded as well.
                         if files.index_is_loaded() {
@@ -283,9 +298,19 @@ impl super::Store {
                         new_slot_map_indices.push(slot_idx);
                     }
                 }
-                None => index_paths_to_add.push_back((index_info, mtime, None)),
+                None => {
+                    if !was_uninitialized {
+                        events.push(Event::Added {
+                            index_path: index_info.path().to_owned(),
+                        });
+                    }
+                    index_paths_to_add.push_back((index_info, mtime, None));
+                }
             }
         }
+        events.extend(idx_by_index_path.keys().map(|index_path| Event::Removed {
+            index_path: index_path.clone(),
+        }));
         let needs_stable_indices = self.maintain_stable_indices(&write);
 
         let mut next_possibly_free_index = index
@@ -416,6 +441,8 @@ impl super::Store {
             slot.files.store(files);
         }
 
+        self.emit(events);
+
         let new_index = self.index.load();
         Ok(if index.state_id() == new_index.state_id() {
             // there was no change, and nothing was loaded in the meantime, reflect that in the return value to not get into loops.
@@ -31,7 +31,7 @@ where
 }
 
 /// Decide what happens when all indices are loaded.
-#[derive(Default, Clone, Copy)]
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
 pub enum RefreshMode {
     /// Check for new or changed pack indices (and pack data files) when the last known index is loaded.
     /// During runtime we will keep pack indices stable by never reusing them, however, there is the option for
@@ -41,6 +41,11 @@ pub enum RefreshMode {
     /// Use this if you expect a lot of missing objects that shouldn't trigger refreshes even after all packs are loaded.
     /// This comes at the risk of not learning that the packs have changed in the mean time.
     Never,
+    /// Check for new or changed pack indices whenever an object is missed, even if not all known indices are loaded yet.
+    ///
+    /// This is useful for long-running processes that receive new packs and expect to find their objects right away,
+    /// at the cost of reading the pack directories on each miss.
+    Always,
 }
 
 impl RefreshMode {
@@ -48,6 +53,11 @@ impl RefreshMode {
     pub fn never(&mut self) {
         *self = RefreshMode::Never;
     }
+
+    /// Set this refresh mode to always refresh when an object is missed.
+    pub fn always(&mut self) {
+        *self = RefreshMode::Always;
+    }
 }
 
 ///
@@ -75,6 +85,9 @@ pub(crate) mod handle;
 ///
 pub mod load_index;
 
+mod event;
+pub use event::Event;
+
 ///
 pub mod verify;
 
@@ -86,3 +99,6 @@ mod access;
 
 ///
 pub mod structure;
+
+///
+pub mod statistics;
@@ -1,4 +1,7 @@
-use std::{collections::HashSet, ops::Deref};
+use std::{
+    collections::{hash_map::Entry, HashMap, HashSet},
+    ops::Deref,
+};
 
 use gix_object::Exists;
 
@@ -123,6 +126,68 @@ where
         Ok(Some(candidate.to_prefix()))
     }
 
+    /// Return the shortest prefix of each of `ids` that is at least `hex_len` characters long and that doesn't match
+    /// any other object in the entire object database, in the order of `ids`.
+    ///
+    /// This is the batch version of [`disambiguate_prefix()`](Self::disambiguate_prefix()) for use when displaying many ids,
+    /// and it is much faster as each id only needs a binary search per pack index or multi-pack index to find its neighbours,
+    /// while loose objects are listed only once per fanout directory.
+    /// Like `git`, ids that aren't contained in the object database are shortened as if they were, so they
+    /// don't match any object that exists.
+    ///
+    /// `hex_len` is clamped to the range of valid prefix lengths for the kind of hash.
+    /// Note that all indices will be loaded after calling this method.
+    pub fn disambiguate_prefixes(
+        &self,
+        ids: impl IntoIterator<Item = impl AsRef<gix_hash::oid>>,
+        hex_len: usize,
+    ) -> Result<Vec<gix_hash::Prefix>, disambiguate::Error> {
+        let _span = gix_features::trace::detail!("gix_odb::Handle::disambiguate_prefixes()");
+        {
+            let mut snapshot = self.snapshot.borrow_mut();
+            *snapshot = self.store.load_all_indices().map_err(lookup::Error::from)?;
+        }
+        let snapshot = self.snapshot.borrow();
+        let mut loose_by_first_byte = HashMap::<u8, Vec<gix_hash::ObjectId>>::new();
+        ids.into_iter()
+            .map(|id| {
+                let id = id.as_ref();
+                let max_hex_len = id.kind().len_in_hex();
+                let mut hex_len = hex_len.clamp(gix_hash::Prefix::MIN_HEX_LEN, max_hex_len);
+                let mut account_for = |other: &gix_hash::oid| {
+                    if other != id {
+                        hex_len = hex_len.max(common_hex_prefix_len(id, other) + 1);
+                    }
+                };
+                for index in &snapshot.indices {
+                    index_neighbours(index, id).for_each(&mut account_for);
+                }
+
+                let loose = match loose_by_first_byte.entry(id.first_byte()) {
+                    Entry::Occupied(entry) => entry.into_mut(),
+                    Entry::Vacant(entry) => {
+                        let mut ids = Vec::new();
+                        for lodb in snapshot.loose_dbs.iter() {
+                            for res in lodb.iter_fanout(id.first_byte()) {
+                                match res {
+                                    Ok(id) => ids.push(id),
+                                    Err(err) => match err.io_error() {
+                                        Some(err) if err.kind() == std::io::ErrorKind::NotFound => {}
+                                        None | Some(_) => return Err(lookup::Error::from(err).into()),
+                                    },
+                                }
+                            }
+                        }
+                        entry.insert(ids)
+                    }
+                };
+                loose.iter().for_each(|other| account_for(other));
+
+                Ok(gix_hash::Prefix::new(id, hex_len.min(max_hex_len)).expect("hex-len to always be in bounds"))
+            })
+            .collect()
+    }
+
     /// Find the only object matching `prefix` and return it as `Ok(Some(Ok(<ObjectId>)))`, or return `Ok(Some(Err(()))`
     /// if multiple different objects with the same prefix were found.
     ///
@@ -194,3 +259,41 @@ where
         }
     }
 }
+
+/// Return the ids that directly precede and follow `id` in the sorted `index`, without `id` itself.
+fn index_neighbours<'a>(
+    index: &'a super::handle::IndexLookup,
+    id: &gix_hash::oid,
+) -> impl Iterator<Item = &'a gix_hash::oid> {
+    let (mut lower_bound, mut upper_bound) = (0, index.num_objects());
+    let mut found = false;
+    while lower_bound < upper_bound {
+        let mid = (lower_bound + upper_bound) / 2;
+        match index.oid_at_index(mid).cmp(id) {
+            std::cmp::Ordering::Less => lower_bound = mid + 1,
+            std::cmp::Ordering::Greater => upper_bound = mid,
+            std::cmp::Ordering::Equal => {
+                lower_bound = mid;
+                found = true;
+                break;
+            }
+        }
+    }
+    let next = lower_bound + u32::from(found);
+    lower_bound
+        .checked_sub(1)
+        .into_iter()
+        .chain((next < index.num_objects()).then_some(next))
+        .map(move |entry_index| index.oid_at_index(entry_index))
+}
+
+/// Return the amount of hex characters `a` and `b` have in common at their beginning.
+fn common_hex_prefix_len(a: &gix_hash::oid, b: &gix_hash::oid) -> usize {
+    a.as_bytes()
+        .iter()
+        .zip(b.as_bytes())
+        .position(|(a, b)| a != b)
+        .map_or(a.kind().len_in_hex(), |pos| {
+            pos * 2 + usize::from(a.as_bytes()[pos] >> 4 == b.as_bytes()[pos] >> 4)
+        })
+}
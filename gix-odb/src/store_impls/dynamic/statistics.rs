@@ -0,0 +1,291 @@
+use std::path::{Path, PathBuf};
+
+use crate::Store;
+
+/// The error returned by [`Store::statistics()`].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("Could not read the directory or file at '{}'", path.display())]
+    Io { source: std::io::Error, path: PathBuf },
+    #[error(transparent)]
+    Index(#[from] gix_pack::index::init::Error),
+    #[error(transparent)]
+    MultiIndex(#[from] gix_pack::multi_index::init::Error),
+}
+
+/// Information about the files of an object database directory, similar to what `git count-objects -v` reports.
+///
+/// Alternate object databases are not included.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Statistics {
+    /// The objects directory that was inspected.
+    pub objects_directory: PathBuf,
+    /// Loose objects by fanout directory, i.e. the entry at index `0x1f` is for objects whose id starts with `1f`.
+    ///
+    /// It has an entry for all 256 fanout directories, even if they don't exist.
+    pub loose: Vec<Fanout>,
+    /// The amount of loose objects that are also contained in a pack, and which could be removed with `git prune-packed`.
+    pub num_prune_packable: usize,
+    /// All packs that have a data and an index file, sorted by the path of their index.
+    pub packs: Vec<Pack>,
+    /// The multi-pack index, if present.
+    pub multi_index: Option<MultiIndex>,
+    /// Files that don't belong into the object database, sorted by path.
+    pub garbage: Vec<Garbage>,
+}
+
+/// Information about the loose objects in a single fanout directory.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Fanout {
+    /// The amount of loose objects.
+    pub num_objects: usize,
+    /// The amount of bytes the loose objects occupy on disk.
+    pub size_on_disk: u64,
+}
+
+/// Information about a pack.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Pack {
+    /// The location of the pack index file.
+    pub index_path: PathBuf,
+    /// The amount of objects stored in the pack, as recorded in its index.
+    pub num_objects: u32,
+    /// The size of the pack data file and the pack index file in bytes.
+    pub size: u64,
+}
+
+/// Information about a multi-pack index.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MultiIndex {
+    /// The location of the multi-pack index file.
+    pub path: PathBuf,
+    /// The amount of unique objects in all packs of the multi-pack index.
+    pub num_objects: u32,
+    /// The name of each pack index of the multi-pack index, along with the amount of objects the multi-pack index
+    /// resolves to the respective pack.
+    pub num_objects_per_pack: Vec<(PathBuf, u32)>,
+}
+
+/// A file that doesn't belong into the object database.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Garbage {
+    /// The location of the file.
+    pub path: PathBuf,
+    /// The size of the file in bytes.
+    pub size: u64,
+    /// The reason the file is considered garbage.
+    pub kind: GarbageKind,
+}
+
+/// The reason a file is considered [garbage](Garbage).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum GarbageKind {
+    /// The file isn't known to be part of an object database.
+    Unknown,
+    /// The file belongs to a pack which lacks its data file or its index.
+    IncompletePack,
+}
+
+impl Statistics {
+    /// The amount of loose objects.
+    pub fn num_loose_objects(&self) -> usize {
+        self.loose.iter().map(|f| f.num_objects).sum()
+    }
+
+    /// The amount of bytes all loose objects occupy on disk.
+    pub fn loose_size_on_disk(&self) -> u64 {
+        self.loose.iter().map(|f| f.size_on_disk).sum()
+    }
+
+    /// The amount of objects in all packs, which counts objects multiple times if they are stored in multiple packs.
+    pub fn num_packed_objects(&self) -> u64 {
+        self.packs.iter().map(|p| u64::from(p.num_objects)).sum()
+    }
+
+    /// The size of all pack data and index files in bytes.
+    pub fn packs_size(&self) -> u64 {
+        self.packs.iter().map(|p| p.size).sum()
+    }
+
+    /// The size of all garbage files in bytes.
+    pub fn garbage_size(&self) -> u64 {
+        self.garbage.iter().map(|g| g.size).sum()
+    }
+}
+
+impl Store {
+    /// Gather information about loose objects, packs and garbage files in our objects directory the way
+    /// `git count-objects -v` does.
+    ///
+    /// Note that this reads the directory afresh and opens all pack indices independently of what's currently loaded,
+    /// which also makes it expensive. Files that change while the directory is read may or may not be accounted for.
+    pub fn statistics(&self) -> Result<Statistics, Error> {
+        let _span = gix_features::trace::detail!("gix_odb::Store::statistics()");
+        let objects_directory = self.path.clone();
+        let mut garbage = Vec::new();
+
+        let hex_len = self.object_hash.len_in_hex();
+        let mut loose = vec![Fanout::default(); 256];
+        let mut loose_ids = Vec::new();
+        let mut hex = Vec::with_capacity(hex_len);
+        for (fanout_byte, fanout) in loose.iter_mut().enumerate() {
+            let fanout_dir = objects_directory.join(format!("{fanout_byte:02x}"));
+            for (path, file_name) in read_dir(&fanout_dir)? {
+                hex.clear();
+                hex.extend_from_slice(format!("{fanout_byte:02x}").as_bytes());
+                hex.extend_from_slice(file_name.to_str().unwrap_or_default().as_bytes());
+                let id = (hex.len() == hex_len)
+                    .then(|| gix_hash::ObjectId::from_hex(&hex).ok())
+                    .flatten();
+                match (id, path.symlink_metadata()) {
+                    (Some(id), Ok(meta)) if meta.is_file() => {
+                        fanout.num_objects += 1;
+                        fanout.size_on_disk += size_on_disk(&meta);
+                        loose_ids.push(id);
+                    }
+                    _ => garbage.push(Garbage::new(path, GarbageKind::Unknown)),
+                }
+            }
+        }
+
+        let pack_dir = objects_directory.join("pack");
+        let mut pack_files = Vec::new();
+        let mut multi_index_path = None;
+        for (path, file_name) in read_dir(&pack_dir)? {
+            let file_name = file_name.to_string_lossy();
+            let belongs_to_multi_index = file_name == "multi-pack-index.d"
+                || (file_name.starts_with("multi-pack-index")
+                    && (file_name.ends_with(".bitmap") || file_name.ends_with(".rev")));
+            if file_name == "multi-pack-index" {
+                multi_index_path = Some(path);
+            } else if belongs_to_multi_index {
+                // Like `git`, ignore these as they aren't garbage, but also aren't needed here.
+            } else if [".idx", ".rev", ".pack", ".bitmap", ".keep", ".promisor", ".mtimes"]
+                .iter()
+                .any(|ext| file_name.ends_with(ext))
+            {
+                pack_files.push(path);
+            } else {
+                garbage.push(Garbage::new(path, GarbageKind::Unknown));
+            }
+        }
+
+        // Like `git`, group files by their stem and consider all of them garbage unless there is a pack and its index.
+        pack_files.sort();
+        let mut indices = Vec::new();
+        let mut packs = Vec::new();
+        let mut rest = pack_files.as_slice();
+        while let Some(first) = rest.first() {
+            let group_len = rest
+                .iter()
+                .take_while(|path| path.file_stem() == first.file_stem())
+                .count();
+            let (group, remaining) = rest.split_at(group_len);
+            rest = remaining;
+            let has_extension = |ext: &str| group.iter().any(|p| p.extension().map_or(false, |e| e == ext));
+            if has_extension("pack") && has_extension("idx") {
+                let index_path = group
+                    .iter()
+                    .find(|p| p.extension().map_or(false, |e| e == "idx"))
+                    .expect("present");
+                let data_path = index_path.with_extension("pack");
+                let index = gix_pack::index::File::at(index_path, self.object_hash)?;
+                packs.push(Pack {
+                    index_path: index_path.clone(),
+                    num_objects: index.num_objects(),
+                    size: file_len(index_path)? + file_len(&data_path)?,
+                });
+                indices.push(index);
+            } else {
+                garbage.extend(
+                    group
+                        .iter()
+                        .map(|path| Garbage::new(path.clone(), GarbageKind::IncompletePack)),
+                );
+            }
+        }
+
+        let multi_index = multi_index_path.map(gix_pack::multi_index::File::at).transpose()?;
+        let num_prune_packable = loose_ids
+            .iter()
+            .filter(|id| {
+                indices.iter().any(|index| index.lookup(id).is_some())
+                    || multi_index.as_ref().map_or(false, |index| index.lookup(id).is_some())
+            })
+            .count();
+        let multi_index = multi_index.map(|index| {
+            let mut num_objects_per_pack = vec![0; index.num_indices() as usize];
+            for entry in index.iter() {
+                num_objects_per_pack[entry.pack_index as usize] += 1;
+            }
+            MultiIndex {
+                path: index.path().to_owned(),
+                num_objects: index.num_objects(),
+                num_objects_per_pack: index.index_names().iter().cloned().zip(num_objects_per_pack).collect(),
+            }
+        });
+
+        garbage.sort_by(|a, b| a.path.cmp(&b.path));
+        Ok(Statistics {
+            objects_directory,
+            loose,
+            num_prune_packable,
+            packs,
+            multi_index,
+            garbage,
+        })
+    }
+}
+
+impl Garbage {
+    fn new(path: PathBuf, kind: GarbageKind) -> Self {
+        Garbage {
+            size: path.metadata().map_or(0, |meta| meta.len()),
+            path,
+            kind,
+        }
+    }
+}
+
+/// Return `(path, file_name)` of all entries in `dir`, or nothing if `dir` doesn't exist.
+fn read_dir(dir: &Path) -> Result<Vec<(PathBuf, std::ffi::OsString)>, Error> {
+    let io_err = |source| Error::Io {
+        source,
+        path: dir.to_owned(),
+    };
+    let entries = match std::fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(err) => return Err(io_err(err)),
+    };
+    entries
+        .map(|entry| entry.map(|entry| (entry.path(), entry.file_name())).map_err(io_err))
+        .collect()
+}
+
+fn file_len(path: &Path) -> Result<u64, Error> {
+    path.metadata().map(|meta| meta.len()).map_err(|source| Error::Io {
+        source,
+        path: path.to_owned(),
+    })
+}
+
+/// The amount of bytes actually used on disk, which can be more than the length of the file.
+fn size_on_disk(meta: &std::fs::Metadata) -> u64 {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::MetadataExt;
+        meta.blocks() * 512
+    }
+    #[cfg(not(unix))]
+    {
+        meta.len()
+    }
+}
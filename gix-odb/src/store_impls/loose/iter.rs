@@ -79,4 +79,20 @@ impl loose::Store {
             hash_hex_len: self.object_hash.len_in_hex(),
         }
     }
+
+    /// Return an iterator over all objects in the fanout directory for ids starting with `first_byte`.
+    pub(crate) fn iter_fanout(&self, first_byte: u8) -> loose::Iter {
+        loose::Iter {
+            inner: fs::walkdir_new(
+                &self.path.join(format!("{first_byte:02x}")),
+                fs::walkdir::Parallelism::Serial,
+                false,
+            )
+            .min_depth(1)
+            .max_depth(1)
+            .follow_links(false)
+            .into_iter(),
+            hash_hex_len: self.object_hash.len_in_hex(),
+        }
+    }
 }
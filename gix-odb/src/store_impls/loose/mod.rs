@@ -12,6 +12,8 @@ pub struct Store {
     pub(crate) path: PathBuf,
     /// The kind of hash we should assume during iteration and when writing new objects.
     pub(crate) object_hash: gix_hash::Kind,
+    /// How to flush new objects to disk before moving them into place.
+    pub(crate) fsync: fs::Fsync,
 }
 
 /// Initialization
@@ -26,9 +28,16 @@ impl Store {
         Store {
             path: objects_directory.into(),
             object_hash,
+            fsync: fs::Fsync::None,
         }
     }
 
+    /// Flush new objects to disk as configured by `fsync` before moving them into place.
+    pub fn with_fsync(mut self, fsync: fs::Fsync) -> Self {
+        self.fsync = fsync;
+        self
+    }
+
     /// Return the path to our `objects` directory.
     pub fn path(&self) -> &Path {
         &self.path
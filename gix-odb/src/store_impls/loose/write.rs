@@ -141,6 +141,11 @@ impl Store {
             }
         }
         let file = file.into_inner();
+        self.fsync.sync(file.as_file()).map_err(|err| Error::Io {
+            source: err,
+            message: "flush object to disk in",
+            path: self.path.to_owned(),
+        })?;
         let res = file.persist(&object_path);
         // On windows, we assume that such errors are due to its special filesystem semantics,
         // on any other platform that would be a legitimate error though.
@@ -19,6 +19,7 @@ pub mod alternate;
 pub mod find;
 pub mod header;
 pub mod memory;
+pub mod quarantine;
 pub mod regression;
 pub mod sink;
 pub mod store;
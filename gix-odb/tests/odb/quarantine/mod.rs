@@ -0,0 +1,45 @@
+use gix_object::{Exists, Write};
+
+fn incoming_dirs(objects_dir: &std::path::Path) -> std::io::Result<usize> {
+    Ok(std::fs::read_dir(objects_dir)?
+        .filter_map(Result::ok)
+        .filter(|entry| entry.file_name().to_string_lossy().starts_with("incoming-"))
+        .count())
+}
+
+#[test]
+fn objects_are_isolated_until_migrated() -> crate::Result {
+    let tmp = gix_testtools::tempfile::TempDir::new()?;
+    let objects_dir = tmp.path().join("objects");
+    std::fs::create_dir(&objects_dir)?;
+    let main = gix_odb::at(&objects_dir)?;
+    let existing = main.write_buf(gix_object::Kind::Blob, b"existing")?;
+
+    let quarantine = gix_odb::Quarantine::new(&objects_dir)?;
+    assert!(quarantine.path().starts_with(&objects_dir));
+    assert_eq!(incoming_dirs(&objects_dir)?, 1);
+    let incoming = gix_odb::at(quarantine.path())?;
+    assert!(incoming.exists(&existing), "the object database is an alternate");
+    let new = incoming.write_buf(gix_object::Kind::Blob, b"new")?;
+    assert!(!main.exists(&new), "new objects are isolated");
+
+    let outcome = quarantine.migrate()?;
+    assert_eq!(outcome.num_loose_objects, 1);
+    assert!(outcome.pack_files.is_empty());
+    assert!(main.exists(&new), "objects were moved into the object database");
+    assert_eq!(incoming_dirs(&objects_dir)?, 0, "the quarantine directory is removed");
+    Ok(())
+}
+
+#[test]
+fn dropping_discards_all_objects() -> crate::Result {
+    let tmp = gix_testtools::tempfile::TempDir::new()?;
+    let main = gix_odb::at(tmp.path())?;
+    let quarantine = gix_odb::Quarantine::new(tmp.path())?;
+    let new = gix_odb::at(quarantine.path())?.write_buf(gix_object::Kind::Blob, b"new")?;
+    drop(quarantine);
+
+    assert!(!main.exists(&new));
+    assert_eq!(incoming_dirs(tmp.path())?, 0);
+    Ok(())
+}
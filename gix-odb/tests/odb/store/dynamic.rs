@@ -209,6 +209,83 @@ fn multi_index_keep_open() -> crate::Result {
     Ok(())
 }
 
+#[test]
+fn statistics() -> crate::Result {
+    let dir = gix_testtools::scripted_fixture_writable_standalone("make_repo_multi_index.sh")?;
+    let objects_dir = dir.path().join(".git/objects");
+    let handle = gix_odb::at(&objects_dir)?;
+
+    let fanout_dir = objects_dir.join("00");
+    std::fs::create_dir_all(&fanout_dir)?;
+    std::fs::write(fanout_dir.join("garbage"), b"content")?;
+    std::fs::write(objects_dir.join("pack/pack-incomplete.pack"), b"no index")?;
+    std::fs::write(objects_dir.join("pack/unknown"), b"content")?;
+
+    let stats = handle.store_ref().statistics()?;
+    assert_eq!(stats.loose.len(), 256);
+    assert_eq!(stats.num_loose_objects(), 868);
+    assert_eq!(
+        stats.num_prune_packable, 864,
+        "`git repack` doesn't remove the objects it packed"
+    );
+    assert_eq!(stats.packs.len(), 15);
+    let multi_index = stats.multi_index.as_ref().expect("present");
+    assert_eq!(multi_index.num_objects_per_pack.len(), 15);
+    assert_eq!(
+        multi_index.num_objects_per_pack.iter().map(|(_, n)| n).sum::<u32>(),
+        multi_index.num_objects
+    );
+    assert_eq!(
+        stats
+            .garbage
+            .iter()
+            .map(|g| (g.path.strip_prefix(&objects_dir).expect("contained").to_owned(), g.kind))
+            .collect::<Vec<_>>(),
+        vec![
+            (
+                fanout_dir.strip_prefix(&objects_dir)?.join("garbage"),
+                store::statistics::GarbageKind::Unknown
+            ),
+            (
+                "pack/pack-incomplete.pack".into(),
+                store::statistics::GarbageKind::IncompletePack
+            ),
+            ("pack/unknown".into(), store::statistics::GarbageKind::Unknown),
+        ]
+    );
+
+    let out = Command::new("git")
+        .args(["count-objects", "-v"])
+        .current_dir(dir.path())
+        .output()?;
+    assert!(out.status.success(), "{out:?}");
+    let expected: Vec<_> = String::from_utf8(out.stdout)?
+        .lines()
+        .map(|line| {
+            let (name, value) = line.split_once(": ").expect("key-value");
+            (name.to_owned(), value.parse::<u64>().expect("number"))
+        })
+        .collect();
+    let actual: Vec<_> = [
+        ("count", stats.num_loose_objects() as u64),
+        ("size", stats.loose_size_on_disk() / 1024),
+        ("in-pack", stats.num_packed_objects()),
+        ("packs", stats.packs.len() as u64),
+        ("size-pack", stats.packs_size() / 1024),
+        ("prune-packable", stats.num_prune_packable as u64),
+        ("garbage", stats.garbage.len() as u64),
+        ("size-garbage", stats.garbage_size() / 1024),
+    ]
+    .into_iter()
+    .map(|(name, value)| (name.to_owned(), value))
+    .collect();
+    assert_eq!(
+        actual, expected,
+        "the same numbers are reported as by `git count-objects -v`"
+    );
+    Ok(())
+}
+
 #[test]
 fn write() -> crate::Result {
     let dir = gix_testtools::tempfile::tempdir()?;
@@ -241,6 +318,37 @@ fn alternate_dbs_query() -> crate::Result {
     Ok(())
 }
 
+#[test]
+fn additional_alternates() -> crate::Result {
+    let dir = gix_testtools::scripted_fixture_read_only_standalone("make_alternates_odb.sh")?;
+    let tmp = gix_testtools::tempfile::TempDir::new()?;
+    let additional = std::env::current_dir()?.join(dir).join(".git/objects");
+    let handle = gix_odb::at_opts(
+        tmp.path(),
+        None,
+        gix_odb::store::init::Options {
+            additional_alternates: vec![additional.clone()],
+            ..Default::default()
+        },
+    )?;
+    assert_eq!(handle.store_ref().additional_alternates(), [additional.clone()]);
+
+    let alternates = handle.store_ref().alternate_db_paths()?;
+    assert_eq!(
+        alternates.len(),
+        2,
+        "the additional alternate as well as its own alternate"
+    );
+    assert_eq!(alternates[0], additional, "additional alternates come first");
+
+    let empty_blob = hex_to_id("e69de29bb2d1d6434b8b29ae775ad8c2e48c5391");
+    assert!(
+        handle.exists(&empty_blob),
+        "objects are found in additional alternates even though the primary object database is empty"
+    );
+    Ok(())
+}
+
 #[test]
 fn object_replacement() -> crate::Result {
     let dir = gix_testtools::scripted_fixture_read_only_standalone("make_replaced_history.sh")?;
@@ -616,6 +724,36 @@ mod disambiguate_prefix {
             .is_none());
         assert_all_indices_loaded(&handle, 2, 2);
     }
+
+    #[test]
+    fn in_batch_is_the_same_as_one_by_one() -> crate::Result {
+        let (handle, _tmp) = db_with_all_object_sources()?;
+        let ids: Vec<_> = handle.iter()?.map(Result::unwrap).collect();
+        for hex_len in [0, 4, 7, 40, 50] {
+            let prefixes = handle.disambiguate_prefixes(&ids, hex_len)?;
+            assert_eq!(prefixes.len(), ids.len());
+            for (id, prefix) in ids.iter().zip(prefixes) {
+                let expected = handle
+                    .disambiguate_prefix(Candidate::new(*id, hex_len.clamp(4, 40))?)?
+                    .expect("object exists");
+                assert_eq!(prefix, expected, "{id}");
+            }
+        }
+        assert!(
+            ids.iter()
+                .any(|id| handle.disambiguate_prefixes(Some(id), 4).unwrap()[0].hex_len() > 4),
+            "some prefixes are ambiguous"
+        );
+
+        let null = gix_hash::ObjectId::null(gix_hash::Kind::Sha1);
+        let prefix = handle.disambiguate_prefixes(Some(null), 4)?[0];
+        assert_eq!(
+            handle.lookup_prefix(prefix, None)?,
+            None,
+            "ids that don't exist are shortened to match no existing object"
+        );
+        Ok(())
+    }
 }
 
 mod iter {
@@ -949,6 +1087,68 @@ fn auto_refresh_with_and_without_id_stability() -> crate::Result {
     Ok(())
 }
 
+#[test]
+fn refresh_always_and_pack_events() -> crate::Result {
+    let objects_dir = gix_testtools::tempfile::tempdir()?;
+    gix_testtools::copy_recursively_into_existing_dir(fixture_path_standalone("objects"), &objects_dir)?;
+    let pack_dir = objects_dir.path().join("pack");
+    let hidden = pack_dir.join("pack-11fdfa9e156ab73caae3b6da867192221f2089c2");
+    let set_hidden = |hide: bool| {
+        for ext in ["idx", "pack"] {
+            let (from, to) = (hidden.with_extension(ext), hidden.with_extension(format!("{ext}.bak")));
+            let (from, to) = if hide { (from, to) } else { (to, from) };
+            std::fs::rename(from, to).unwrap();
+        }
+    };
+    set_hidden(true);
+
+    let mut handle = gix_odb::at(objects_dir.path())?;
+    handle.refresh.always();
+    let events = handle.store_ref().subscribe();
+    let mut buf = Vec::new();
+    assert!(handle
+        .find(&hex_to_id("dd25c539efbb0ab018caa4cda2d133285634e9b5"), &mut buf)
+        .is_ok());
+    assert!(
+        events.try_recv().is_err(),
+        "the initial load of the pack directory doesn't produce events"
+    );
+
+    set_hidden(false);
+    assert!(
+        handle
+            .find(&hex_to_id("501b297447a8255d3533c6858bb692575cdefaa0"), &mut buf)
+            .is_ok(),
+        "the new pack is picked up right away"
+    );
+    assert_eq!(
+        events.try_recv()?,
+        store::Event::Added {
+            index_path: hidden.with_extension("idx")
+        }
+    );
+    assert!(events.try_recv().is_err(), "nothing else changed");
+
+    set_hidden(true);
+    assert!(!handle.exists(&hex_to_id("aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa")));
+    assert_eq!(
+        events.try_recv()?,
+        store::Event::Removed {
+            index_path: hidden.with_extension("idx")
+        }
+    );
+
+    drop(events);
+    set_hidden(false);
+    assert!(
+        handle
+            .find(&hex_to_id("501b297447a8255d3533c6858bb692575cdefaa0"), &mut buf)
+            .is_ok(),
+        "sending events to subscribers that went away is fine"
+    );
+    Ok(())
+}
+
 mod verify {
     use std::sync::atomic::AtomicBool;
 
@@ -13,7 +13,7 @@ use crate::data;
 
 mod error;
 pub use error::Error;
-use gix_features::progress::prodash::DynNestedProgress;
+use gix_features::progress::prodash::{BoxedDynNestedProgress, DynNestedProgress};
 
 mod types;
 use types::{LockWriter, PassThrough};
@@ -32,6 +32,8 @@ pub enum ProgressId {
     ///
     /// Underneath will be more progress information related to actually producing the index.
     IndexingSteps(PhantomData<crate::index::write::ProgressId>),
+    /// The amount of base objects that were looked up to resolve a thin pack.
+    ResolveThinPackBases,
 }
 
 impl From<ProgressId> for gix_features::progress::Id {
@@ -39,6 +41,7 @@ impl From<ProgressId> for gix_features::progress::Id {
         match v {
             ProgressId::ReadPackBytes => *b"BWRB",
             ProgressId::IndexingSteps(_) => *b"BWCI",
+            ProgressId::ResolveThinPackBases => *b"BWTB",
         }
     }
 }
@@ -51,8 +54,10 @@ impl crate::Bundle {
     /// * `progress` provides detailed progress information which can be discarded with [`gix_features::progress::Discard`].
     /// * `should_interrupt` is checked regularly and when true, the whole operation will stop.
     /// * `thin_pack_base_object_lookup` If set, we expect to see a thin-pack with objects that reference their base object by object id which is
-    ///    expected to exist in the object database the bundle is contained within.
-    ///    `options` further configure how the task is performed.
+    ///   expected to exist in the object database the bundle is contained within. These are looked up one at a time and inserted into the pack
+    ///   right before the first object that needs them. Use [`write_to_directory_with_parallel_lookup()`](Self::write_to_directory_with_parallel_lookup())
+    ///   to look them up in parallel instead.
+    ///   `options` further configure how the task is performed.
     ///
     /// # Note
     ///
@@ -68,6 +73,55 @@ impl crate::Bundle {
         options: Options,
     ) -> Result<Outcome, Error> {
         let _span = gix_features::trace::coarse!("gix_pack::Bundle::write_to_directory()");
+        Self::write_to_directory_inner::<_, OneAtATime>(
+            pack,
+            directory,
+            progress,
+            should_interrupt,
+            thin_pack_base_object_lookup,
+            options,
+        )
+    }
+
+    /// Like [`write_to_directory()`](Self::write_to_directory()), but looks up the base objects of a thin pack in parallel,
+    /// hence the `Clone + Send` bounds.
+    ///
+    /// Entries are read ahead by up to [`Options::thin_pack_lookahead_bytes`] to find the base objects they need, and these are
+    /// looked up using up to [`Options::thread_limit`] threads, so large fetches into large repositories don't have to wait for
+    /// each base object in turn.
+    /// The first entries are read ahead the least so they can be processed as soon as possible, see
+    /// [`PrefetchOptions`](data::input::PrefetchOptions) for details.
+    pub fn write_to_directory_with_parallel_lookup(
+        pack: &mut dyn io::BufRead,
+        directory: Option<&Path>,
+        progress: &mut dyn DynNestedProgress,
+        should_interrupt: &AtomicBool,
+        thin_pack_base_object_lookup: Option<impl gix_object::Find + Clone + Send>,
+        options: Options,
+    ) -> Result<Outcome, Error> {
+        let _span = gix_features::trace::coarse!("gix_pack::Bundle::write_to_directory_with_parallel_lookup()");
+        Self::write_to_directory_inner::<_, InParallel>(
+            pack,
+            directory,
+            progress,
+            should_interrupt,
+            thin_pack_base_object_lookup,
+            options,
+        )
+    }
+
+    fn write_to_directory_inner<Find, Lookup>(
+        pack: &mut dyn io::BufRead,
+        directory: Option<&Path>,
+        progress: &mut dyn DynNestedProgress,
+        should_interrupt: &AtomicBool,
+        thin_pack_base_object_lookup: Option<Find>,
+        options: Options,
+    ) -> Result<Outcome, Error>
+    where
+        Find: gix_object::Find,
+        Lookup: ThinPackLookup<Find>,
+    {
         let mut read_progress = progress.add_child_with_id("read pack".into(), ProgressId::ReadPackBytes.into());
         read_progress.init(None, progress::bytes());
         let pack = progress::Read {
@@ -83,6 +137,7 @@ impl crate::Bundle {
                 None => gix_tempfile::new(std::env::temp_dir(), ContainingDirectory::Exists, AutoRemove::Tempfile)?,
             },
         )));
+        let mut bases_progress = None;
         let (pack_entries_iter, pack_version): (
             Box<dyn Iterator<Item = Result<data::input::Entry, data::input::Error>>>,
             _,
@@ -93,7 +148,7 @@ impl crate::Bundle {
                     should_interrupt,
                 };
                 let buffered_pack = io::BufReader::new(pack);
-                let pack_entries_iter = data::input::LookupRefDeltaObjectsIter::new(
+                let (pack_entries_iter, thin_pack_progress) = resolve_thin_pack::<_, _, Lookup>(
                     data::input::BytesToEntriesIter::new_from_header(
                         buffered_pack,
                         options.iteration_mode,
@@ -101,7 +156,10 @@ impl crate::Bundle {
                         object_hash,
                     )?,
                     thin_pack_lookup,
+                    &options,
+                    progress,
                 );
+                bases_progress = Some(thin_pack_progress);
                 let pack_version = pack_entries_iter.inner.version();
                 let pack_entries_iter = data::input::EntriesToBytesIter::new(
                     pack_entries_iter,
@@ -150,6 +208,7 @@ impl crate::Bundle {
             should_interrupt,
             pack_version,
         )?;
+        drop(bases_progress);
 
         Ok(Outcome {
             index: outcome,
@@ -191,6 +250,7 @@ impl crate::Bundle {
         })));
         let object_hash = options.object_hash;
         let eight_pages = 4096 * 8;
+        let mut bases_progress = None;
         let (pack_entries_iter, pack_version): (
             Box<dyn Iterator<Item = Result<data::input::Entry, data::input::Error>> + Send + 'static>,
             _,
@@ -201,7 +261,7 @@ impl crate::Bundle {
                     should_interrupt,
                 };
                 let buffered_pack = io::BufReader::with_capacity(eight_pages, pack);
-                let pack_entries_iter = data::input::LookupRefDeltaObjectsIter::new(
+                let (pack_entries_iter, thin_pack_progress) = resolve_thin_pack::<_, _, OneAtATime>(
                     data::input::BytesToEntriesIter::new_from_header(
                         buffered_pack,
                         options.iteration_mode,
@@ -209,7 +269,10 @@ impl crate::Bundle {
                         object_hash,
                     )?,
                     thin_pack_lookup,
+                    &options,
+                    progress,
                 );
+                bases_progress = Some(thin_pack_progress);
                 let pack_kind = pack_entries_iter.inner.version();
                 (Box::new(pack_entries_iter), pack_kind)
             }
@@ -250,6 +313,7 @@ impl crate::Bundle {
             should_interrupt,
             pack_version,
         )?;
+        drop(bases_progress);
 
         Ok(Outcome {
             index: outcome,
@@ -269,6 +333,9 @@ impl crate::Bundle {
             iteration_mode: _,
             index_version: index_kind,
             object_hash,
+            sha1_flavor,
+            thin_pack_lookahead_bytes: _,
+            fsync,
         }: Options,
         data_file: SharedTempFile,
         mut pack_entries_iter: Box<dyn Iterator<Item = Result<data::input::Entry, data::input::Error>> + 'a>,
@@ -296,6 +363,7 @@ impl crate::Bundle {
                     &mut index_file,
                     should_interrupt,
                     object_hash,
+                    sha1_flavor,
                     pack_version,
                 )?;
                 drop(pack_entries_iter);
@@ -310,23 +378,23 @@ impl crate::Bundle {
                 } else {
                     let data_path = directory.join(format!("pack-{}.pack", outcome.data_hash.to_hex()));
                     let index_path = data_path.with_extension("idx");
-                    let keep_path = if data_path.is_file() {
+                    // Protect the pack from concurrent maintenance until refs point to it, even if it existed already.
+                    // A pre-existing `.keep` file isn't ours, and the pack stays protected by it.
+                    let keep = crate::keep::Guard::new(&data_path, b"")?;
+                    if !data_path.is_file() {
                         // avoid trying to overwrite existing files, we know they have the same content
                         // and this is likely to fail on Windows as negotiation opened the pack.
-                        None
-                    } else {
-                        let keep_path = data_path.with_extension("keep");
-
-                        std::fs::write(&keep_path, b"")?;
-                        Arc::try_unwrap(data_file)
+                        let mut data_file = Arc::try_unwrap(data_file)
                             .expect("only one handle left after pack was consumed")
                             .into_inner()
                             .into_inner()
-                            .map_err(|err| Error::from(err.into_error()))?
-                            .persist(&data_path)?;
-                        Some(keep_path)
-                    };
+                            .map_err(|err| Error::from(err.into_error()))?;
+                        data_file.with_mut(|file| fsync.sync(file.as_file()))??;
+                        data_file.persist(&data_path)?;
+                    }
+                    let keep_path = keep.persist();
                     if !index_path.is_file() {
+                        index_file.with_mut(|file| fsync.sync(file.as_file()))??;
                         index_file
                             .persist(&index_path)
                             .map_err(|err| {
@@ -352,6 +420,7 @@ impl crate::Bundle {
                     &mut io::sink(),
                     should_interrupt,
                     object_hash,
+                    sha1_flavor,
                     pack_version,
                 )?,
                 data_path: None,
@@ -362,6 +431,87 @@ impl crate::Bundle {
     }
 }
 
+/// Determines how the base objects of thin packs are looked up.
+trait ThinPackLookup<Find> {
+    /// Wrap `entries` so that the base objects of their ref-deltas are looked up in `lookup`, and count these in `progress`.
+    fn wrap<I>(
+        entries: I,
+        lookup: Find,
+        options: &Options,
+        progress: &dyn gix_features::progress::Count,
+    ) -> data::input::LookupRefDeltaObjectsIter<I, Find>
+    where
+        I: Iterator<Item = Result<data::input::Entry, data::input::Error>>;
+}
+
+/// Look up base objects one at a time, when they are first needed.
+enum OneAtATime {}
+
+impl<Find: gix_object::Find> ThinPackLookup<Find> for OneAtATime {
+    fn wrap<I>(
+        entries: I,
+        lookup: Find,
+        _options: &Options,
+        _progress: &dyn gix_features::progress::Count,
+    ) -> data::input::LookupRefDeltaObjectsIter<I, Find>
+    where
+        I: Iterator<Item = Result<data::input::Entry, data::input::Error>>,
+    {
+        data::input::LookupRefDeltaObjectsIter::new(entries, lookup)
+    }
+}
+
+/// Read entries ahead to look up their base objects in parallel, unless [`Options::thin_pack_lookahead_bytes`] is `0`.
+enum InParallel {}
+
+impl<Find: gix_object::Find + Clone + Send> ThinPackLookup<Find> for InParallel {
+    fn wrap<I>(
+        entries: I,
+        lookup: Find,
+        options: &Options,
+        progress: &dyn gix_features::progress::Count,
+    ) -> data::input::LookupRefDeltaObjectsIter<I, Find>
+    where
+        I: Iterator<Item = Result<data::input::Entry, data::input::Error>>,
+    {
+        if options.thin_pack_lookahead_bytes == 0 {
+            return data::input::LookupRefDeltaObjectsIter::new(entries, lookup);
+        }
+        data::input::LookupRefDeltaObjectsIter::new_with_prefetch(
+            entries,
+            lookup,
+            data::input::PrefetchOptions {
+                thread_limit: options.thread_limit,
+                max_buffered_bytes: options.thin_pack_lookahead_bytes,
+            },
+            progress,
+        )
+    }
+}
+
+/// Wrap `entries` of a thin pack so that the base objects of ref-deltas are looked up in `lookup` and inserted into the pack,
+/// as determined by `Lookup`.
+/// The returned progress counts the base objects that were looked up and should be kept alive until the iteration is done.
+fn resolve_thin_pack<I, Find, Lookup>(
+    entries: I,
+    lookup: Find,
+    options: &Options,
+    progress: &mut dyn DynNestedProgress,
+) -> (data::input::LookupRefDeltaObjectsIter<I, Find>, BoxedDynNestedProgress)
+where
+    I: Iterator<Item = Result<data::input::Entry, data::input::Error>>,
+    Find: gix_object::Find,
+    Lookup: ThinPackLookup<Find>,
+{
+    let mut bases_progress = progress.add_child_with_id(
+        "resolve thin pack bases".into(),
+        ProgressId::ResolveThinPackBases.into(),
+    );
+    bases_progress.init(None, progress::count("bases"));
+    let entries = Lookup::wrap(entries, lookup, options, &bases_progress);
+    (entries, bases_progress)
+}
+
 fn resolve_entry(range: data::EntryRange, mapped_file: &memmap2::Mmap) -> Option<&[u8]> {
     mapped_file.get(range.start as usize..range.end as usize)
 }
@@ -14,6 +14,20 @@ pub struct Options {
     pub index_version: crate::index::Version,
     /// The kind of hash to use when writing the bundle.
     pub object_hash: gix_hash::Kind,
+    /// The implementation to use when computing the hashes of objects in the pack.
+    ///
+    /// Set it to [`Sha1Flavor::CollisionDetecting`](gix_hash::hasher::Sha1Flavor::CollisionDetecting) to reject packs
+    /// from untrusted sources if they contain objects that are part of a SHA-1 collision attack.
+    pub sha1_flavor: gix_hash::hasher::Sha1Flavor,
+    /// The amount of bytes of pack entries to read ahead at most when receiving a thin pack with
+    /// [`write_to_directory_with_parallel_lookup()`](crate::Bundle::write_to_directory_with_parallel_lookup()), to find the base objects
+    /// of the ref-deltas among them and look these up in parallel using up to `thread_limit` threads.
+    ///
+    /// All entries within this window are kept in memory, along with their base objects.
+    /// If `0`, base objects are looked up one at a time when they are first needed.
+    pub thin_pack_lookahead_bytes: usize,
+    /// How to flush the pack and its index to disk before moving them into place.
+    pub fsync: gix_features::fs::Fsync,
 }
 
 impl Default for Options {
@@ -24,6 +38,9 @@ impl Default for Options {
             iteration_mode: crate::data::input::Mode::Verify,
             index_version: Default::default(),
             object_hash: Default::default(),
+            sha1_flavor: Default::default(),
+            thin_pack_lookahead_bytes: 16 * 1024 * 1024,
+            fsync: Default::default(),
         }
     }
 }
@@ -45,8 +62,9 @@ pub struct Outcome {
     /// The path to the pack data file.
     pub data_path: Option<PathBuf>,
     /// The path to the `.keep` file to prevent collection of the newly written pack until refs are pointing to it.
-    /// It might be `None` if the file at `data_path` already existed, indicating that we have received a pack that
-    /// was already present locally.
+    /// It is also created if the file at `data_path` already existed, indicating that we have received a pack that
+    /// was already present locally, as it may have been about to be deleted by concurrent maintenance.
+    /// It is `None` if a `.keep` file was already present, which is then left alone as it's not ours to remove.
     ///
     /// The file is created right before moving the pack data and index data into place (i.e. `data_path` and `index_path`)
     /// and is expected to be removed by the caller when ready.
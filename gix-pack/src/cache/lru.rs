@@ -3,7 +3,7 @@ use super::DecodeEntry;
 #[cfg(feature = "pack-cache-lru-dynamic")]
 mod memory {
     use super::DecodeEntry;
-    use crate::cache::set_vec_to_slice;
+    use crate::cache::{set_vec_to_slice, Statistics};
     use clru::WeightScale;
     use std::num::NonZeroUsize;
 
@@ -27,6 +27,7 @@ mod memory {
         inner: clru::CLruCache<Key, Entry, std::collections::hash_map::RandomState, CustomScale>,
         free_list: Vec<Vec<u8>>,
         debug: gix_features::cache::Debug,
+        stats: Statistics,
     }
 
     impl MemoryCappedHashmap {
@@ -39,6 +40,7 @@ mod memory {
                         .with_scale(CustomScale),
                 ),
                 free_list: Vec::new(),
+                stats: Statistics::default(),
                 debug: gix_features::cache::Debug::new(format!("MemoryCappedHashmap({memory_cap_in_bytes}B)")),
             }
         }
@@ -47,6 +49,7 @@ mod memory {
     impl DecodeEntry for MemoryCappedHashmap {
         fn put(&mut self, pack_id: u32, offset: u64, data: &[u8], kind: gix_object::Kind, compressed_size: usize) {
             self.debug.put();
+            self.stats.puts += 1;
             let Some(data) = set_vec_to_slice(self.free_list.pop().unwrap_or_default(), data) else {
                 return;
             };
@@ -72,11 +75,17 @@ mod memory {
             });
             if res.is_some() {
                 self.debug.hit();
+                self.stats.hits += 1;
             } else {
                 self.debug.miss();
+                self.stats.misses += 1;
             }
             res
         }
+
+        fn statistics(&self) -> Option<Statistics> {
+            Some(self.stats)
+        }
     }
 }
 
@@ -86,7 +95,7 @@ pub use memory::MemoryCappedHashmap;
 #[cfg(feature = "pack-cache-lru-static")]
 mod _static {
     use super::DecodeEntry;
-    use crate::cache::set_vec_to_slice;
+    use crate::cache::{set_vec_to_slice, Statistics};
     struct Entry {
         pack_id: u32,
         offset: u64,
@@ -102,6 +111,7 @@ mod _static {
         inner: uluru::LRUCache<Entry, SIZE>,
         last_evicted: Vec<u8>,
         debug: gix_features::cache::Debug,
+        stats: Statistics,
         /// the amount of bytes we are currently holding, taking into account the capacities of all Vecs we keep.
         mem_used: usize,
         /// The total amount of memory we should be able to hold with all entries combined.
@@ -114,6 +124,7 @@ mod _static {
             StaticLinkedList {
                 inner: Default::default(),
                 last_evicted: Vec::new(),
+                stats: Statistics::default(),
                 debug: gix_features::cache::Debug::new(format!("StaticLinkedList<{SIZE}>")),
                 mem_used: 0,
                 mem_limit: if mem_limit == 0 { usize::MAX } else { mem_limit },
@@ -148,6 +159,7 @@ mod _static {
                 }
             }
             self.debug.put();
+            self.stats.puts += 1;
             let mut v = std::mem::take(&mut self.last_evicted);
             self.mem_used -= v.capacity();
             if set_vec_to_slice(&mut v, data).is_none() {
@@ -177,11 +189,17 @@ mod _static {
             });
             if res.is_some() {
                 self.debug.hit();
+                self.stats.hits += 1;
             } else {
                 self.debug.miss();
+                self.stats.misses += 1;
             }
             res
         }
+
+        fn statistics(&self) -> Option<Statistics> {
+            Some(self.stats)
+        }
     }
 
     #[cfg(test)]
@@ -198,6 +216,26 @@ mod _static {
             );
         }
 
+        #[test]
+        fn statistics() {
+            let mut c = StaticLinkedList::<10>::new(0);
+            let mut buf = Vec::new();
+            assert!(c.get(0, 0, &mut buf).is_none());
+            c.put(0, 0, &[1], gix_object::Kind::Blob, 1);
+            assert!(c.get(0, 0, &mut buf).is_some());
+            assert!(c.get(0, 0, &mut buf).is_some());
+            let stats = c.statistics().expect("always kept");
+            assert_eq!(
+                stats,
+                crate::cache::Statistics {
+                    hits: 2,
+                    misses: 1,
+                    puts: 1
+                }
+            );
+            assert_eq!(stats.hit_ratio(), Some(2.0 / 3.0));
+        }
+
         #[test]
         fn journey() {
             let mut c = StaticLinkedList::<10>::new(100);
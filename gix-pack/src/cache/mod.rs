@@ -1,4 +1,4 @@
-use std::ops::DerefMut;
+use std::ops::{Deref, DerefMut};
 
 use gix_object::Kind;
 
@@ -13,6 +13,30 @@ pub trait DecodeEntry {
     /// Attempt to fetch the object at `offset` and store its decoded bytes in `out`, as previously stored with [`DecodeEntry::put()`], and return
     /// its (object `kind`, `decompressed_size`)
     fn get(&mut self, pack_id: u32, offset: u64, out: &mut Vec<u8>) -> Option<(gix_object::Kind, usize)>;
+    /// Return the hit/miss statistics collected so far, or `None` if this cache doesn't keep track of them.
+    fn statistics(&self) -> Option<Statistics> {
+        None
+    }
+}
+
+/// Statistics about the efficiency of a [`DecodeEntry`] or [`Object`] cache, useful to size it based on real workloads.
+#[derive(Default, Debug, Copy, Clone, PartialEq, Eq, Hash, Ord, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Statistics {
+    /// The amount of times an entry could be retrieved from the cache.
+    pub hits: usize,
+    /// The amount of times an entry wasn't found in the cache.
+    pub misses: usize,
+    /// The amount of times an entry was stored in the cache.
+    pub puts: usize,
+}
+
+impl Statistics {
+    /// Return the ratio of hits to all lookups in the range `0.0..=1.0`, or `None` if there was no lookup yet.
+    pub fn hit_ratio(&self) -> Option<f32> {
+        let lookups = self.hits + self.misses;
+        (lookups != 0).then(|| self.hits as f32 / lookups as f32)
+    }
 }
 
 /// A cache that stores nothing and retrieves nothing, thus it _never_ caches.
@@ -34,6 +58,10 @@ impl<T: DecodeEntry + ?Sized> DecodeEntry for Box<T> {
     fn get(&mut self, pack_id: u32, offset: u64, out: &mut Vec<u8>) -> Option<(Kind, usize)> {
         self.deref_mut().get(pack_id, offset, out)
     }
+
+    fn statistics(&self) -> Option<Statistics> {
+        self.deref().statistics()
+    }
 }
 
 /// A way of storing and retrieving entire objects to and from a cache.
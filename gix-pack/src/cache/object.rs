@@ -79,9 +79,94 @@ mod memory {
             res
         }
     }
+
+    /// A thread-safe LRU cache which is split into shards that are locked individually, each with its own share of the memory cap.
+    ///
+    /// Clones of this instance refer to the same cache, which makes it possible to share decoded objects among
+    /// all handles of an object database, and thus among threads.
+    #[derive(Clone)]
+    pub struct SharedMemoryCappedHashmap {
+        shards: std::sync::Arc<[std::sync::Mutex<Shard>]>,
+    }
+
+    struct Shard {
+        cache: MemoryCappedHashmap,
+        stats: cache::Statistics,
+    }
+
+    impl SharedMemoryCappedHashmap {
+        /// Return a new instance with `num_shards` shards which together evict least recently used items if they use more than
+        /// `memory_cap_in_bytes` of object data.
+        ///
+        /// More shards reduce lock contention if many threads access the cache at the same time, but make the eviction less precise
+        /// as each shard is capped individually.
+        pub fn new(memory_cap_in_bytes: usize, num_shards: NonZeroUsize) -> SharedMemoryCappedHashmap {
+            let num_shards = num_shards.get().min(256);
+            let cap_per_shard = (memory_cap_in_bytes / num_shards).max(1);
+            SharedMemoryCappedHashmap {
+                shards: (0..num_shards)
+                    .map(|_| {
+                        std::sync::Mutex::new(Shard {
+                            cache: MemoryCappedHashmap::new(cap_per_shard),
+                            stats: Default::default(),
+                        })
+                    })
+                    .collect(),
+            }
+        }
+
+        /// The amount of bytes we can hold in total, across all shards.
+        pub fn capacity(&self) -> usize {
+            self.shards.iter().map(|shard| lock(shard).cache.capacity()).sum()
+        }
+
+        /// The amount of shards the cache is split into.
+        pub fn num_shards(&self) -> usize {
+            self.shards.len()
+        }
+
+        /// Return the hit and miss statistics collected across all shards by all users of this cache so far.
+        pub fn statistics(&self) -> cache::Statistics {
+            self.shards.iter().fold(cache::Statistics::default(), |mut acc, shard| {
+                let stats = lock(shard).stats;
+                acc.hits += stats.hits;
+                acc.misses += stats.misses;
+                acc.puts += stats.puts;
+                acc
+            })
+        }
+
+        fn shard(&self, id: &gix_hash::oid) -> std::sync::MutexGuard<'_, Shard> {
+            // Object ids are evenly distributed, so their first byte is enough to pick a shard.
+            lock(&self.shards[id.first_byte() as usize % self.shards.len()])
+        }
+    }
+
+    fn lock(shard: &std::sync::Mutex<Shard>) -> std::sync::MutexGuard<'_, Shard> {
+        shard.lock().unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+
+    impl cache::Object for SharedMemoryCappedHashmap {
+        fn put(&mut self, id: gix_hash::ObjectId, kind: gix_object::Kind, data: &[u8]) {
+            let mut shard = self.shard(&id);
+            shard.stats.puts += 1;
+            cache::Object::put(&mut shard.cache, id, kind, data);
+        }
+
+        fn get(&mut self, id: &gix_hash::ObjectId, out: &mut Vec<u8>) -> Option<gix_object::Kind> {
+            let mut shard = self.shard(id);
+            let res = cache::Object::get(&mut shard.cache, id, out);
+            if res.is_some() {
+                shard.stats.hits += 1;
+            } else {
+                shard.stats.misses += 1;
+            }
+            res
+        }
+    }
 }
 #[cfg(feature = "object-cache-dynamic")]
-pub use memory::MemoryCappedHashmap;
+pub use memory::{MemoryCappedHashmap, SharedMemoryCappedHashmap};
 
 /// A cache implementation that doesn't do any caching.
 pub struct Never;
@@ -1,12 +1,50 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use gix_features::{parallel, progress::StepShared};
 use gix_hash::ObjectId;
 
 use crate::data::{entry::Header, input};
 
+/// Configuration for [`LookupRefDeltaObjectsIter::new_with_prefetch()`].
+#[derive(Debug, Clone, Copy)]
+pub struct PrefetchOptions {
+    /// The amount of threads to use at most when looking up base objects. If `None`, all logical cores are used.
+    pub thread_limit: Option<usize>,
+    /// The amount of bytes of pack entries to read ahead at most to find the base objects that ref-deltas refer to.
+    ///
+    /// Larger values allow more base objects to be looked up in parallel, but all entries within the window,
+    /// along with the base objects found for them, are kept in memory.
+    ///
+    /// As entries within the window can only be returned once all of its base objects were looked up, the first window
+    /// is a sixteenth of this size, and each following one doubles in size until it reaches this size. That way, the
+    /// entries at the beginning of the pack, which are needed first, don't wait for the lookup of bases needed much later.
+    pub max_buffered_bytes: usize,
+}
+
+/// A function to look up all base objects with the given ids in parallel.
+type LookupInParallelFn<Find> =
+    fn(&Find, Vec<ObjectId>, Option<usize>, &StepShared) -> Vec<(ObjectId, Result<input::Entry, input::Error>)>;
+
+struct Prefetch<Find> {
+    options: PrefetchOptions,
+    /// The amount of bytes to read ahead the next time, which grows up to `options.max_buffered_bytes`.
+    window_bytes: usize,
+    lookup_in_parallel: LookupInParallelFn<Find>,
+    /// Entries that were read ahead from the inner iterator, in order.
+    buffered: VecDeque<Result<input::Entry, input::Error>>,
+    /// Base objects that were looked up for ref-deltas in `buffered`, but that weren't inserted into the pack yet.
+    bases: HashMap<ObjectId, Result<input::Entry, input::Error>>,
+    /// Counts the base objects we looked up.
+    progress: StepShared,
+}
+
 /// An iterator to resolve thin packs on the fly.
 pub struct LookupRefDeltaObjectsIter<I, Find> {
     /// The inner iterator whose entries we will resolve.
     pub inner: I,
     lookup: Find,
+    /// If set, entries are read ahead to look up their base objects in parallel.
+    prefetch: Option<Prefetch<Find>>,
     /// The cached delta to provide next time we are called, it's the delta to go with the base we just resolved in its place.
     next_delta: Option<input::Entry>,
     /// Fuse to stop iteration after first missing object.
@@ -30,6 +68,7 @@ where
         LookupRefDeltaObjectsIter {
             inner: iter,
             lookup,
+            prefetch: None,
             error: false,
             inserted_entry_length_at_offset: Vec::new(),
             inserted_entries_length_in_bytes: 0,
@@ -38,6 +77,83 @@ where
         }
     }
 
+    /// Like [`new()`](Self::new()), but reads entries ahead from `iter` until up to `options.max_buffered_bytes` are buffered,
+    /// to then look up all base objects for the ref-deltas among them in parallel, incrementing `progress` for each one of them.
+    /// Base objects are looked up in the order in which they are needed, and windows grow over time so the entries
+    /// at the beginning of the pack are available quickly.
+    ///
+    /// This is useful to avoid waiting for each base object in turn when receiving thin packs into large repositories.
+    pub fn new_with_prefetch(
+        iter: I,
+        lookup: Find,
+        options: PrefetchOptions,
+        progress: &dyn gix_features::progress::Count,
+    ) -> Self
+    where
+        Find: Clone + Send,
+    {
+        LookupRefDeltaObjectsIter {
+            prefetch: Some(Prefetch {
+                options,
+                window_bytes: (options.max_buffered_bytes / 16).max(1),
+                lookup_in_parallel: lookup_in_parallel::<Find>,
+                buffered: VecDeque::new(),
+                bases: HashMap::new(),
+                progress: progress.counter(),
+            }),
+            ..Self::new(iter, lookup)
+        }
+    }
+
+    /// Return the next entry of the inner iterator, or of the entries we have read ahead.
+    fn next_entry(&mut self) -> Option<Result<input::Entry, input::Error>> {
+        let Some(prefetch) = self.prefetch.as_mut() else {
+            return self.inner.next();
+        };
+        if prefetch.buffered.is_empty() {
+            let mut base_ids = Vec::new();
+            let mut seen = HashSet::new();
+            let mut buffered_bytes = 0;
+            for item in self.inner.by_ref() {
+                let is_err = item.is_err();
+                if let Ok(entry) = &item {
+                    buffered_bytes += entry.bytes_in_pack() as usize;
+                    if let Header::RefDelta { base_id } = entry.header {
+                        if !prefetch.bases.contains_key(&base_id)
+                            && !self.inserted_entry_length_at_offset.iter().any(|c| c.oid == base_id)
+                            && seen.insert(base_id)
+                        {
+                            base_ids.push(base_id);
+                        }
+                    }
+                }
+                prefetch.buffered.push_back(item);
+                if is_err || buffered_bytes >= prefetch.window_bytes {
+                    break;
+                }
+            }
+            prefetch.window_bytes = prefetch
+                .window_bytes
+                .saturating_mul(2)
+                .min(prefetch.options.max_buffered_bytes.max(1));
+            prefetch.bases.extend((prefetch.lookup_in_parallel)(
+                &self.lookup,
+                base_ids,
+                prefetch.options.thread_limit,
+                &prefetch.progress,
+            ));
+        }
+        prefetch.buffered.pop_front()
+    }
+
+    /// Obtain the base object with `id` as entry, either from what was looked up in parallel, or from our lookup.
+    fn lookup_base(&mut self, id: ObjectId) -> Result<input::Entry, input::Error> {
+        if let Some(base) = self.prefetch.as_mut().and_then(|p| p.bases.remove(&id)) {
+            return base;
+        }
+        lookup_base(&self.lookup, id, &mut self.buf)
+    }
+
     fn shifted_pack_offset(&self, pack_offset: u64) -> u64 {
         let new_ofs = pack_offset as i64 + self.inserted_entries_length_in_bytes;
         new_ofs.try_into().expect("offset value is never becomes negative")
@@ -87,30 +203,26 @@ where
         if let Some(delta) = self.next_delta.take() {
             return Some(Ok(delta));
         }
-        match self.inner.next() {
+        match self.next_entry() {
             Some(Ok(mut entry)) => match entry.header {
                 Header::RefDelta { base_id } => {
                     match self.inserted_entry_length_at_offset.iter().rfind(|e| e.oid == base_id) {
                         None => {
-                            let base_entry = match self.lookup.try_find(&base_id, &mut self.buf).ok()? {
-                                Some(obj) => {
+                            let base_entry = match self.lookup_base(base_id) {
+                                Ok(mut base_entry) => {
                                     let current_pack_offset = entry.pack_offset;
-                                    let mut entry = match input::Entry::from_data_obj(&obj, 0) {
-                                        Ok(e) => e,
-                                        Err(err) => return Some(Err(err)),
-                                    };
-                                    entry.pack_offset = self.shifted_pack_offset(current_pack_offset);
+                                    base_entry.pack_offset = self.shifted_pack_offset(current_pack_offset);
                                     self.track_change(
-                                        entry.pack_offset,
+                                        base_entry.pack_offset,
                                         current_pack_offset,
-                                        entry.bytes_in_pack() as i64,
+                                        base_entry.bytes_in_pack() as i64,
                                         Some(base_id),
                                     );
-                                    entry
+                                    base_entry
                                 }
-                                None => {
+                                Err(err) => {
                                     self.error = true;
-                                    return Some(Err(input::Error::NotFound { object_id: base_id }));
+                                    return Some(Err(err));
                                 }
                             };
 
@@ -184,7 +296,9 @@ where
     }
 
     fn size_hint(&self) -> (usize, Option<usize>) {
+        let num_buffered = self.prefetch.as_ref().map_or(0, |p| p.buffered.len());
         let (min, max) = self.inner.size_hint();
+        let (min, max) = (min + num_buffered, max.map(|max| max + num_buffered));
         max.map_or_else(|| (min * 2, None), |max| (min, Some(max * 2)))
     }
 }
@@ -201,3 +315,68 @@ struct Change {
     /// The object id of the entry responsible for the change, or null if it's an entry just for tracking an insertion.
     oid: ObjectId,
 }
+
+/// Look up the object with `id` in `lookup` and turn it into an entry that can be inserted into the pack.
+fn lookup_base(lookup: &dyn gix_object::Find, id: ObjectId, buf: &mut Vec<u8>) -> Result<input::Entry, input::Error> {
+    match lookup.try_find(&id, buf) {
+        Ok(Some(obj)) => input::Entry::from_data_obj(&obj, 0),
+        Ok(None) | Err(_) => Err(input::Error::NotFound { object_id: id }),
+    }
+}
+
+fn lookup_in_parallel<Find>(
+    lookup: &Find,
+    ids: Vec<ObjectId>,
+    thread_limit: Option<usize>,
+    progress: &StepShared,
+) -> Vec<(ObjectId, Result<input::Entry, input::Error>)>
+where
+    Find: gix_object::Find + Clone + Send,
+{
+    if ids.is_empty() {
+        return Vec::new();
+    }
+    let (chunk_size, thread_limit, _) =
+        parallel::optimize_chunk_size_and_thread_limit(1, Some(ids.len()), thread_limit, None);
+    let chunks = ids.chunks(chunk_size).map(<[ObjectId]>::to_vec).collect::<Vec<_>>();
+    let num_chunks = chunks.len();
+    parallel::in_parallel_if(
+        || num_chunks > 1,
+        chunks.into_iter(),
+        thread_limit,
+        {
+            let lookup = lookup.clone();
+            move |_| (lookup.clone(), Vec::new())
+        },
+        |ids, (lookup, buf)| {
+            ids.into_iter()
+                .map(|id| {
+                    let base = lookup_base(&*lookup, id, buf);
+                    progress.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+                    (id, base)
+                })
+                .collect::<Vec<_>>()
+        },
+        Collect(Vec::new()),
+    )
+    .expect("infallible")
+}
+
+/// Collect the output of all threads in no particular order.
+struct Collect<T>(Vec<T>);
+
+impl<T> parallel::Reduce for Collect<T> {
+    type Input = Vec<T>;
+    type FeedProduce = ();
+    type Output = Vec<T>;
+    type Error = std::convert::Infallible;
+
+    fn feed(&mut self, item: Self::Input) -> Result<Self::FeedProduce, Self::Error> {
+        self.0.extend(item);
+        Ok(())
+    }
+
+    fn finalize(self) -> Result<Self::Output, Self::Error> {
+        Ok(self.0)
+    }
+}
@@ -39,3 +39,7 @@ pub mod entry;
 
 ///
 pub mod bytes;
+
+///
+#[cfg(feature = "async-output")]
+pub mod stream;
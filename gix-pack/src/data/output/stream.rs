@@ -0,0 +1,242 @@
+use std::collections::VecDeque;
+use std::pin::Pin;
+use std::sync::{Arc, Condvar, Mutex};
+use std::task::{Context, Poll, Waker};
+
+use crate::data::output;
+
+/// The error produced by [`Chunks`] as part of its stream of items.
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("Failed to generate pack data")]
+    Generate(#[source] Box<dyn std::error::Error + Send + Sync + 'static>),
+    #[error("The thread generating the pack panicked")]
+    Panic,
+}
+
+/// Options for use in [`Chunks::spawn()`].
+#[derive(Debug, Copy, Clone)]
+pub struct Options {
+    /// The maximum amount of bytes in each chunk.
+    pub chunk_size: usize,
+    /// The amount of chunks that may be buffered before the generating thread waits for the consumer to catch up.
+    ///
+    /// The maximum amount of memory used for buffering is thus `chunk_size * max_buffered_chunks`.
+    pub max_buffered_chunks: usize,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            chunk_size: 64 * 1024,
+            max_buffered_chunks: 4,
+        }
+    }
+}
+
+/// A [`Stream`](futures_lite::stream::Stream) of pack data, split into chunks of bounded size, for use by async servers.
+///
+/// Pack generation happens on a dedicated thread, so polling the stream never blocks the executor. The generating thread
+/// pauses as long as [`max_buffered_chunks`](Options::max_buffered_chunks) are waiting to be consumed, which applies
+/// backpressure all the way to the [entries](output::Entry) input, and it stops early if the stream is dropped.
+///
+/// The stream ends after the last chunk which contains the pack trailer, or after the first error.
+pub struct Chunks {
+    shared: Arc<Shared>,
+    digest: Option<gix_hash::ObjectId>,
+    is_done: bool,
+}
+
+struct State {
+    chunks: VecDeque<Vec<u8>>,
+    /// Set once the generating thread is done, with the pack digest on success.
+    outcome: Option<Result<gix_hash::ObjectId, Error>>,
+    receiver_dropped: bool,
+    waker: Option<Waker>,
+}
+
+struct Shared {
+    state: Mutex<State>,
+    /// Signalled when the consumer took a chunk or went away.
+    space_available: Condvar,
+    max_buffered_chunks: usize,
+}
+
+impl Shared {
+    fn lock(&self) -> std::sync::MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+
+    fn finish(&self, outcome: Result<gix_hash::ObjectId, Error>) {
+        let mut state = self.lock();
+        if state.outcome.is_none() {
+            state.outcome = Some(outcome);
+        }
+        if let Some(waker) = state.waker.take() {
+            waker.wake();
+        }
+    }
+}
+
+impl Chunks {
+    /// Spawn a thread that writes all [entries](output::Entry) obtained from `input` as pack of `version` with exactly `num_entries`
+    /// objects, using `object_hash` for the trailing checksum, and return a stream of the pack bytes.
+    ///
+    /// See [`FromEntriesIter::new()`](output::bytes::FromEntriesIter::new()) for details on the expected input.
+    pub fn spawn<I, E>(
+        input: I,
+        num_entries: u32,
+        version: crate::data::Version,
+        object_hash: gix_hash::Kind,
+        Options {
+            chunk_size,
+            max_buffered_chunks,
+        }: Options,
+    ) -> std::io::Result<Self>
+    where
+        I: Iterator<Item = Result<Vec<output::Entry>, E>> + Send + 'static,
+        E: std::error::Error + Send + Sync + 'static,
+    {
+        let shared = Arc::new(Shared {
+            state: Mutex::new(State {
+                chunks: VecDeque::new(),
+                outcome: None,
+                receiver_dropped: false,
+                waker: None,
+            }),
+            space_available: Condvar::new(),
+            max_buffered_chunks: max_buffered_chunks.max(1),
+        });
+        std::thread::Builder::new()
+            .name("gix-pack::data::output::stream".into())
+            .spawn({
+                let shared = Arc::clone(&shared);
+                move || {
+                    let _guard = PanicGuard(Arc::clone(&shared));
+                    let writer = ChunkWriter {
+                        shared: Arc::clone(&shared),
+                        buf: Vec::with_capacity(chunk_size.max(1)),
+                        chunk_size: chunk_size.max(1),
+                    };
+                    let mut iter =
+                        output::bytes::FromEntriesIter::new(input, writer, num_entries, version, object_hash);
+                    let outcome = iter.find_map(Result::err).map_or_else(
+                        || Ok(iter.digest().expect("digest is available when done")),
+                        |err| Err(Error::Generate(Box::new(err))),
+                    );
+                    shared.finish(outcome);
+                }
+            })?;
+        Ok(Chunks {
+            shared,
+            digest: None,
+            is_done: false,
+        })
+    }
+
+    /// Returns the trailing hash over all written entries once the stream is exhausted.
+    /// It's `None` if the stream isn't yet done, or if it failed.
+    pub fn digest(&self) -> Option<gix_hash::ObjectId> {
+        self.digest
+    }
+}
+
+impl futures_lite::stream::Stream for Chunks {
+    type Item = Result<Vec<u8>, Error>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        if this.is_done {
+            return Poll::Ready(None);
+        }
+        let mut state = this.shared.lock();
+        if let Some(chunk) = state.chunks.pop_front() {
+            this.shared.space_available.notify_one();
+            return Poll::Ready(Some(Ok(chunk)));
+        }
+        match state.outcome.take() {
+            Some(outcome) => {
+                this.is_done = true;
+                match outcome {
+                    Ok(digest) => {
+                        this.digest = Some(digest);
+                        Poll::Ready(None)
+                    }
+                    Err(err) => Poll::Ready(Some(Err(err))),
+                }
+            }
+            None => {
+                state.waker = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+}
+
+impl Drop for Chunks {
+    fn drop(&mut self) {
+        self.shared.lock().receiver_dropped = true;
+        self.shared.space_available.notify_one();
+    }
+}
+
+/// Report a panic of the generating thread to the consumer, which would otherwise wait forever.
+/// It has no effect if the outcome was already set.
+struct PanicGuard(Arc<Shared>);
+
+impl Drop for PanicGuard {
+    fn drop(&mut self) {
+        self.0.finish(Err(Error::Panic));
+    }
+}
+
+/// A writer that hands out full chunks to the consumer, waiting while too many chunks are buffered.
+struct ChunkWriter {
+    shared: Arc<Shared>,
+    buf: Vec<u8>,
+    chunk_size: usize,
+}
+
+impl ChunkWriter {
+    fn send(&mut self) -> std::io::Result<()> {
+        if self.buf.is_empty() {
+            return Ok(());
+        }
+        let mut state = self.shared.lock();
+        while state.chunks.len() >= self.shared.max_buffered_chunks && !state.receiver_dropped {
+            state = self
+                .shared
+                .space_available
+                .wait(state)
+                .unwrap_or_else(std::sync::PoisonError::into_inner);
+        }
+        if state.receiver_dropped {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::BrokenPipe,
+                "The pack stream was dropped",
+            ));
+        }
+        let chunk = std::mem::replace(&mut self.buf, Vec::with_capacity(self.chunk_size));
+        state.chunks.push_back(chunk);
+        if let Some(waker) = state.waker.take() {
+            waker.wake();
+        }
+        Ok(())
+    }
+}
+
+impl std::io::Write for ChunkWriter {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let n = buf.len().min(self.chunk_size - self.buf.len());
+        self.buf.extend_from_slice(&buf[..n]);
+        if self.buf.len() == self.chunk_size {
+            self.send()?;
+        }
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.send()
+    }
+}
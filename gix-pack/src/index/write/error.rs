@@ -19,6 +19,8 @@ pub enum Error {
     #[error("{pack_offset} is not a valid offset for pack offset {distance}")]
     IteratorInvariantBaseOffset { pack_offset: u64, distance: u64 },
     #[error(transparent)]
+    Hash(#[from] gix_hash::hasher::Error),
+    #[error(transparent)]
     Tree(#[from] crate::cache::delta::Error),
     #[error(transparent)]
     TreeTraversal(#[from] crate::cache::delta::traverse::Error),
@@ -69,10 +69,13 @@ impl crate::index::File {
     /// * `kind` is the version of pack index to produce, use [`crate::index::Version::default()`] if in doubt.
     /// * `tread_limit` is used for a parallel tree traversal for obtaining object hashes with optimal performance.
     /// * `root_progress` is the top-level progress to stay informed about the progress of this potentially long-running
-    ///    computation.
+    ///   computation.
     /// * `object_hash` defines what kind of object hash we write into the index file.
+    /// * `sha1_flavor` defines the implementation used to compute object hashes. Use
+    ///   [`Sha1Flavor::CollisionDetecting`](gix_hash::hasher::Sha1Flavor::CollisionDetecting) for packs from untrusted sources
+    ///   to fail if an object is part of a collision attack.
     /// * `pack_version` is the version of the underlying pack for which `entries` are read. It's used in case none of these objects are provided
-    ///    to compute a pack-hash.
+    ///   to compute a pack-hash.
     ///
     /// # Remarks
     ///
@@ -91,6 +94,7 @@ impl crate::index::File {
         out: &mut dyn io::Write,
         should_interrupt: &AtomicBool,
         object_hash: gix_hash::Kind,
+        sha1_flavor: gix_hash::hasher::Sha1Flavor,
         pack_version: crate::data::Version,
     ) -> Result<Outcome, Error>
     where
@@ -192,10 +196,7 @@ impl crate::index::File {
                      entry,
                      decompressed: bytes,
                      ..
-                 }| {
-                    modify_base(data, entry, bytes, version.hash());
-                    Ok::<_, Error>(())
-                },
+                 }| { modify_base(data, entry, bytes, version.hash(), sha1_flavor) },
                 traverse::Options {
                     object_progress: Box::new(
                         root_progress.add_child_with_id("Resolving".into(), ProgressId::ResolveObjects.into()),
@@ -253,8 +254,22 @@ impl crate::index::File {
     }
 }
 
-fn modify_base(entry: &mut TreeEntry, pack_entry: &crate::data::Entry, decompressed: &[u8], hash: gix_hash::Kind) {
+fn modify_base(
+    entry: &mut TreeEntry,
+    pack_entry: &crate::data::Entry,
+    decompressed: &[u8],
+    hash: gix_hash::Kind,
+    sha1_flavor: gix_hash::hasher::Sha1Flavor,
+) -> Result<(), Error> {
+    use gix_hash::Hasher;
+
     let object_kind = pack_entry.header.as_kind().expect("base object as source of iteration");
-    let id = gix_object::compute_hash(hash, object_kind, decompressed);
-    entry.id = id;
+    let mut hasher = gix_features::hash::hasher_with_flavor(hash, sha1_flavor);
+    hasher.update(&gix_object::encode::loose_header(
+        object_kind,
+        decompressed.len() as u64,
+    ));
+    hasher.update(decompressed);
+    entry.id = hasher.try_finalize()?;
+    Ok(())
 }
@@ -0,0 +1,75 @@
+use std::{
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+/// Return the path to the `.keep` file of the pack data file at `pack_path`, whose presence prevents maintenance
+/// operations like `git gc` or `git repack` from deleting the pack.
+pub fn path(pack_path: &Path) -> PathBuf {
+    pack_path.with_extension("keep")
+}
+
+/// Return `true` if the pack data file at `pack_path` has a `.keep` file, and thus must not be deleted
+/// or repacked into another pack.
+pub fn is_kept(pack_path: &Path) -> bool {
+    path(pack_path).is_file()
+}
+
+/// A `.keep` file which marks a pack as in use while an operation depends on it, removing it when dropped
+/// unless it is [persisted](Guard::persist()).
+///
+/// If the `.keep` file already existed, it is left untouched as it is owned by someone else, who
+/// might keep the pack permanently.
+#[derive(Debug)]
+pub struct Guard {
+    path: PathBuf,
+    created: bool,
+}
+
+/// Lifecycle
+impl Guard {
+    /// Mark the pack data file at `pack_path` as kept, writing `reason` into the `.keep` file to tell which operation
+    /// is relying on it, like `receive-pack 42 on hostname`.
+    ///
+    /// The `.keep` file is created atomically so there is no race with other processes doing the same.
+    pub fn new(pack_path: &Path, reason: &[u8]) -> std::io::Result<Self> {
+        let path = path(pack_path);
+        let guard = match std::fs::OpenOptions::new().write(true).create_new(true).open(&path) {
+            Ok(mut file) => {
+                let guard = Guard { path, created: true };
+                file.write_all(reason)?;
+                guard
+            }
+            Err(err) if err.kind() == std::io::ErrorKind::AlreadyExists => Guard { path, created: false },
+            Err(err) => return Err(err),
+        };
+        Ok(guard)
+    }
+
+    /// Leave the `.keep` file in place, and return its path if it was created by us so the caller can remove it
+    /// once the pack is safe, typically once references point to its objects.
+    pub fn persist(mut self) -> Option<PathBuf> {
+        std::mem::take(&mut self.created).then(|| std::mem::take(&mut self.path))
+    }
+}
+
+/// Access
+impl Guard {
+    /// The path to the `.keep` file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Return `true` if the `.keep` file was created by us and will be removed when dropped.
+    pub fn is_owned(&self) -> bool {
+        self.created
+    }
+}
+
+impl Drop for Guard {
+    fn drop(&mut self) {
+        if self.created {
+            std::fs::remove_file(&self.path).ok();
+        }
+    }
+}
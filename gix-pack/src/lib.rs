@@ -42,6 +42,8 @@ pub use find_traits::{Find, FindExt};
 ///
 pub mod index;
 ///
+pub mod keep;
+///
 pub mod multi_index;
 
 ///
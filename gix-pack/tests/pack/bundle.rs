@@ -154,6 +154,27 @@ mod write_to_directory {
         Ok(())
     }
 
+    #[test]
+    fn existing_packs_are_kept_without_touching_foreign_keep_files() -> Result<(), Box<dyn std::error::Error>> {
+        let dir = TempDir::new()?;
+        let first = write_pack(Some(&dir), SMALL_PACK)?;
+        let keep_path = first.keep_path.expect("created for the new pack");
+
+        let second = write_pack(Some(&dir), SMALL_PACK)?;
+        assert_eq!(second.keep_path, None, "the existing keep file isn't ours");
+        assert!(keep_path.is_file(), "and it's left alone");
+
+        fs::remove_file(&keep_path)?;
+        let third = write_pack(Some(&dir), SMALL_PACK)?;
+        assert_eq!(
+            third.keep_path.as_deref(),
+            Some(keep_path.as_path()),
+            "existing packs are protected as well until refs point to them"
+        );
+        assert!(keep_path.is_file());
+        Ok(())
+    }
+
     fn file_name(entry: &fs::DirEntry) -> String {
         entry.path().file_name().unwrap().to_str().unwrap().to_owned()
     }
@@ -176,6 +197,8 @@ mod write_to_directory {
                 iteration_mode: pack::data::input::Mode::Verify,
                 index_version: pack::index::Version::V2,
                 object_hash: gix_hash::Kind::Sha1,
+                sha1_flavor: Default::default(),
+                ..Default::default()
             },
         )
         .map_err(Into::into)
@@ -172,6 +172,67 @@ mod lookup_ref_delta_objects {
         validate_pack_offsets(&actual);
     }
 
+    #[test]
+    fn prefetched_bases_are_inserted_like_the_ones_looked_up_on_demand() -> crate::Result {
+        let first_id = hex_to_id("0000000000000000000000000000000000000001");
+        let second_id = hex_to_id("0000000000000000000000000000000000000002");
+        let third = entry(delta_ref(second_id), D_B);
+        let fourth = entry(delta_ofs(third.bytes_in_pack()), D_C);
+        let input = compute_offsets(vec![
+            entry(delta_ref(first_id), D_A),
+            entry(base(), D_B),
+            third,
+            fourth,
+            entry(delta_ref(first_id), D_C),
+        ]);
+
+        let calls = AtomicUsize::default();
+        let db = FindData::new(D_D, &calls);
+        let expected =
+            LookupRefDeltaObjectsIter::new(into_results_iter(input.clone()), &db).collect::<Result<Vec<_>, _>>()?;
+        assert_eq!(calls.swap(0, Ordering::Relaxed), 2, "each base is looked up once");
+
+        for max_buffered_bytes in [0, 1, expected[2].bytes_in_pack() as usize + 1, usize::MAX] {
+            let actual = LookupRefDeltaObjectsIter::new_with_prefetch(
+                into_results_iter(input.clone()),
+                &db,
+                input::PrefetchOptions {
+                    thread_limit: Some(2),
+                    max_buffered_bytes,
+                },
+                &gix_features::progress::Discard,
+            )
+            .collect::<Result<Vec<_>, _>>()?;
+            assert_eq!(
+                actual, expected,
+                "the result doesn't depend on how many entries are read ahead, for window of {max_buffered_bytes} bytes"
+            );
+            assert_eq!(
+                calls.swap(0, Ordering::Relaxed),
+                2,
+                "bases that were already looked up are never looked up again"
+            );
+            validate_pack_offsets(&actual);
+        }
+
+        let mut iter = LookupRefDeltaObjectsIter::new_with_prefetch(
+            into_results_iter(input.clone()),
+            &db,
+            input::PrefetchOptions {
+                thread_limit: Some(2),
+                max_buffered_bytes: 16 * input[0].bytes_in_pack() as usize,
+            },
+            &gix_features::progress::Discard,
+        );
+        assert_eq!(iter.next().transpose()?, Some(expected[0].clone()));
+        assert_eq!(
+            calls.load(Ordering::Relaxed),
+            1,
+            "the first window is the smallest, so only the base that is needed first is looked up"
+        );
+        Ok(())
+    }
+
     #[test]
     fn lookup_errors_trigger_a_fuse_and_stop_iteration() {
         let input = vec![entry(delta_ref(gix_hash::Kind::Sha1.null()), D_A), entry(base(), D_B)];
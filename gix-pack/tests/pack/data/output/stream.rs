@@ -0,0 +1,162 @@
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    mpsc, Arc,
+};
+
+use futures_lite::StreamExt;
+use gix_pack::data::output;
+
+/// Produce `num_batches` batches of a single blob entry each, with data that doesn't compress well so chunks fill up quickly.
+fn entries(num_batches: usize) -> Vec<Result<Vec<output::Entry>, std::io::Error>> {
+    let mut seed = 42u32;
+    (0..num_batches)
+        .map(|_| {
+            let data: Vec<u8> = (0..1024)
+                .map(|_| {
+                    seed = seed.wrapping_mul(1_103_515_245).wrapping_add(12345);
+                    (seed >> 16) as u8
+                })
+                .collect();
+            let id = gix_object::compute_hash(gix_hash::Kind::Sha1, gix_object::Kind::Blob, &data);
+            let entry = output::Entry::from_data(
+                &output::Count::from_data(id, None),
+                &gix_object::Data::new(gix_object::Kind::Blob, &data),
+            )
+            .expect("valid data");
+            Ok(vec![entry])
+        })
+        .collect()
+}
+
+/// An input iterator which counts how many batches were pulled from it, and reports when it's dropped.
+struct Observed {
+    inner: std::vec::IntoIter<Result<Vec<output::Entry>, std::io::Error>>,
+    pulled: Arc<AtomicUsize>,
+    dropped: mpsc::Sender<()>,
+}
+
+impl Iterator for Observed {
+    type Item = Result<Vec<output::Entry>, std::io::Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let item = self.inner.next()?;
+        self.pulled.fetch_add(1, Ordering::SeqCst);
+        Some(item)
+    }
+}
+
+impl Drop for Observed {
+    fn drop(&mut self) {
+        self.dropped.send(()).ok();
+    }
+}
+
+fn observed(num_batches: usize) -> (Observed, Arc<AtomicUsize>, mpsc::Receiver<()>) {
+    let pulled = Arc::new(AtomicUsize::default());
+    let (tx, rx) = mpsc::channel();
+    (
+        Observed {
+            inner: entries(num_batches).into_iter(),
+            pulled: pulled.clone(),
+            dropped: tx,
+        },
+        pulled,
+        rx,
+    )
+}
+
+#[test]
+fn chunks_are_bounded_and_match_the_blocking_writer() -> crate::Result {
+    let num_entries = 10;
+    let mut expected = Vec::new();
+    let mut writer = output::bytes::FromEntriesIter::new(
+        entries(num_entries).into_iter(),
+        &mut expected,
+        num_entries as u32,
+        gix_pack::data::Version::V2,
+        gix_hash::Kind::Sha1,
+    );
+    for res in &mut writer {
+        res?;
+    }
+    let expected_digest = writer.digest().expect("done");
+    assert!(expected.len() > num_entries * 1024, "the pack contains all entries");
+
+    let mut stream = output::stream::Chunks::spawn(
+        entries(num_entries).into_iter(),
+        num_entries as u32,
+        gix_pack::data::Version::V2,
+        gix_hash::Kind::Sha1,
+        output::stream::Options {
+            chunk_size: 500,
+            max_buffered_chunks: 1,
+        },
+    )?;
+    let mut actual = Vec::new();
+    let mut num_chunks = 0;
+    futures_lite::future::block_on(async {
+        while let Some(chunk) = stream.next().await {
+            let chunk = chunk?;
+            assert!(
+                !chunk.is_empty() && chunk.len() <= 500,
+                "chunks are never empty and bounded"
+            );
+            actual.extend_from_slice(&chunk);
+            num_chunks += 1;
+        }
+        Ok::<_, output::stream::Error>(())
+    })?;
+    assert!(num_chunks > 20, "the pack is split into many chunks");
+    assert_eq!(
+        actual, expected,
+        "the streamed pack is exactly what would be written to disk"
+    );
+    assert_eq!(stream.digest(), Some(expected_digest));
+    assert!(
+        futures_lite::future::block_on(stream.next()).is_none(),
+        "the stream stays exhausted"
+    );
+    Ok(())
+}
+
+#[test]
+fn generation_waits_for_the_consumer_and_stops_once_the_stream_is_dropped() -> crate::Result {
+    let num_entries = 100;
+    let (input, pulled, input_dropped) = observed(num_entries);
+    let mut stream = output::stream::Chunks::spawn(
+        input,
+        num_entries as u32,
+        gix_pack::data::Version::V2,
+        gix_hash::Kind::Sha1,
+        output::stream::Options {
+            chunk_size: 100,
+            max_buffered_chunks: 2,
+        },
+    )?;
+
+    // Give the generating thread the opportunity to run ahead, which it must not do.
+    std::thread::sleep(std::time::Duration::from_millis(50));
+    let pulled_before_consumption = pulled.load(Ordering::SeqCst);
+    assert!(
+        pulled_before_consumption <= 2,
+        "each entry is larger than all buffered chunks together, so generation blocks on the first entries, got {pulled_before_consumption}"
+    );
+
+    futures_lite::future::block_on(async {
+        for _ in 0..30 {
+            stream.next().await.expect("more chunks")?;
+        }
+        Ok::<_, output::stream::Error>(())
+    })?;
+    drop(stream);
+
+    input_dropped
+        .recv_timeout(std::time::Duration::from_secs(10))
+        .expect("the generating thread stops and drops its input once the stream is dropped");
+    let pulled = pulled.load(Ordering::SeqCst);
+    assert!(
+        pulled < num_entries / 2,
+        "generation stopped early instead of producing the whole pack, pulled {pulled} batches"
+    );
+    Ok(())
+}
@@ -157,6 +157,7 @@ mod version {
                     &mut actual,
                     &AtomicBool::new(false),
                     gix_hash::Kind::Sha1,
+                    gix_hash::hasher::Sha1Flavor::CollisionDetecting,
                     pack_version,
                 )?;
 
@@ -0,0 +1,41 @@
+use gix_pack::keep;
+use gix_testtools::tempfile::TempDir;
+
+#[test]
+fn guard_removes_only_keep_files_it_created() -> crate::Result {
+    let dir = TempDir::new()?;
+    let pack_path = dir.path().join("pack-abc.pack");
+    assert_eq!(keep::path(&pack_path), dir.path().join("pack-abc.keep"));
+    assert!(!keep::is_kept(&pack_path));
+
+    let guard = keep::Guard::new(&pack_path, b"receive-pack 42 on localhost\n")?;
+    assert!(guard.is_owned());
+    assert!(keep::is_kept(&pack_path));
+    assert_eq!(std::fs::read(guard.path())?, b"receive-pack 42 on localhost\n");
+
+    let other = keep::Guard::new(&pack_path, b"ignored")?;
+    assert!(!other.is_owned(), "the keep file belongs to the first guard");
+    drop(other);
+    assert!(keep::is_kept(&pack_path), "foreign keep files are left alone");
+
+    drop(guard);
+    assert!(!keep::is_kept(&pack_path), "our own keep file is removed when dropped");
+    Ok(())
+}
+
+#[test]
+fn persist_leaves_the_keep_file_in_place() -> crate::Result {
+    let dir = TempDir::new()?;
+    let pack_path = dir.path().join("pack-abc.pack");
+    let keep_path = keep::Guard::new(&pack_path, b"")?.persist();
+    assert_eq!(keep_path, Some(keep::path(&pack_path)));
+    assert!(keep::is_kept(&pack_path));
+
+    assert_eq!(
+        keep::Guard::new(&pack_path, b"")?.persist(),
+        None,
+        "persisting a foreign keep file doesn't make it ours"
+    );
+    assert!(keep::is_kept(&pack_path));
+    Ok(())
+}
@@ -43,6 +43,9 @@ pub use fetch_fn::{fetch, FetchConnection};
 mod remote_progress;
 pub use remote_progress::RemoteProgress;
 
+///
+pub mod serve;
+
 #[cfg(all(feature = "blocking-client", feature = "async-client"))]
 compile_error!("Cannot set both 'blocking-client' and 'async-client' features as they are mutually exclusive");
 
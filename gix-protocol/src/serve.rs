@@ -0,0 +1,178 @@
+use bstr::BString;
+
+/// The configuration of a server which determines the capabilities it advertises and which `want` lines it honors,
+/// similar to what `git upload-pack` reads from `uploadpack.*` configuration.
+///
+/// It's meant to be provided per repository by hosts that embed a server, so policies can be tuned without
+/// touching the protocol implementation.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Options {
+    /// The kind of hash the repository uses, advertised as `object-format`.
+    pub object_hash: gix_hash::Kind,
+    /// The value of the `agent` capability, like `git/gix-1.0`.
+    pub agent: BString,
+    /// If `true`, clients may ask for objects at the tips of references that weren't advertised, like hidden ones.
+    ///
+    /// It's the equivalent of `uploadpack.allowTipSHA1InWant`.
+    pub allow_tip_sha1_in_want: bool,
+    /// If `true`, clients may ask for any object that is reachable from any reference.
+    ///
+    /// It's the equivalent of `uploadpack.allowReachableSHA1InWant`.
+    pub allow_reachable_sha1_in_want: bool,
+    /// If `true`, clients may ask for any object, which implies the previous two.
+    ///
+    /// It's the equivalent of `uploadpack.allowAnySHA1InWant`.
+    pub allow_any_sha1_in_want: bool,
+    /// If `true`, clients may use `filter` to perform partial clones.
+    ///
+    /// It's the equivalent of `uploadpack.allowFilter`.
+    pub allow_filter: bool,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            object_hash: gix_hash::Kind::Sha1,
+            agent: crate::agent(concat!("gix-", env!("CARGO_PKG_VERSION"))).into(),
+            allow_tip_sha1_in_want: false,
+            allow_reachable_sha1_in_want: false,
+            allow_any_sha1_in_want: false,
+            allow_filter: false,
+        }
+    }
+}
+
+/// Which objects clients may ask for in `want` lines, as determined by [`Options::wants()`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Wants {
+    /// Only objects at the tips of advertised references.
+    Advertised,
+    /// Objects at the tips of all references, advertised or not.
+    Tips,
+    /// Objects that are reachable from any reference.
+    Reachable,
+    /// Any object.
+    Any,
+}
+
+impl Options {
+    /// Return the most permissive policy for `want` lines that was configured.
+    pub fn wants(&self) -> Wants {
+        if self.allow_any_sha1_in_want {
+            Wants::Any
+        } else if self.allow_reachable_sha1_in_want {
+            Wants::Reachable
+        } else if self.allow_tip_sha1_in_want {
+            Wants::Tips
+        } else {
+            Wants::Advertised
+        }
+    }
+
+    /// Return the capabilities to advertise along with the first reference in protocol V0 and V1, in the order `git`
+    /// advertises them.
+    pub fn v1_capabilities(&self) -> Vec<BString> {
+        let mut out: Vec<BString> = [
+            "multi_ack",
+            "thin-pack",
+            "side-band",
+            "side-band-64k",
+            "ofs-delta",
+            "shallow",
+            "deepen-since",
+            "deepen-not",
+            "deepen-relative",
+            "no-progress",
+            "include-tag",
+            "multi_ack_detailed",
+        ]
+        .into_iter()
+        .map(Into::into)
+        .collect();
+        if self.allow_tip_sha1_in_want || self.allow_any_sha1_in_want {
+            out.push("allow-tip-sha1-in-want".into());
+        }
+        if self.allow_reachable_sha1_in_want || self.allow_any_sha1_in_want {
+            out.push("allow-reachable-sha1-in-want".into());
+        }
+        out.push("no-done".into());
+        if self.allow_filter {
+            out.push("filter".into());
+        }
+        out.push(self.object_format_capability());
+        out.push(self.agent_capability());
+        out
+    }
+
+    /// Return the capability lines to advertise in protocol V2, one per line.
+    pub fn v2_capabilities(&self) -> Vec<BString> {
+        let mut fetch = String::from("fetch=shallow");
+        if self.allow_filter {
+            fetch.push_str(" filter");
+        }
+        vec![
+            self.agent_capability(),
+            "ls-refs=unborn".into(),
+            fetch.into(),
+            "server-option".into(),
+            self.object_format_capability(),
+        ]
+    }
+
+    fn object_format_capability(&self) -> BString {
+        format!("object-format={}", self.object_hash.to_string().to_ascii_lowercase()).into()
+    }
+
+    fn agent_capability(&self) -> BString {
+        let mut out = BString::from("agent=");
+        out.extend_from_slice(&self.agent);
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Options, Wants};
+
+    #[test]
+    fn capabilities_reflect_configuration() {
+        let opts = Options {
+            agent: "git/test".into(),
+            ..Default::default()
+        };
+        assert_eq!(opts.wants(), Wants::Advertised);
+        let caps = opts.v1_capabilities();
+        assert!(!caps.iter().any(|c| c.starts_with(b"allow-") || c == "filter"));
+        assert_eq!(caps[caps.len() - 2..], ["object-format=sha1", "agent=git/test"]);
+        assert_eq!(
+            opts.v2_capabilities(),
+            [
+                "agent=git/test",
+                "ls-refs=unborn",
+                "fetch=shallow",
+                "server-option",
+                "object-format=sha1"
+            ]
+        );
+
+        let opts = Options {
+            allow_any_sha1_in_want: true,
+            allow_filter: true,
+            ..opts
+        };
+        assert_eq!(opts.wants(), Wants::Any);
+        let caps = opts.v1_capabilities();
+        for expected in ["allow-tip-sha1-in-want", "allow-reachable-sha1-in-want", "filter"] {
+            assert!(caps.iter().any(|c| c == expected), "{expected} is implied");
+        }
+        assert_eq!(opts.v2_capabilities()[2], "fetch=shallow filter");
+
+        let opts = Options {
+            allow_tip_sha1_in_want: true,
+            allow_reachable_sha1_in_want: true,
+            ..Default::default()
+        };
+        assert_eq!(opts.wants(), Wants::Reachable, "the most permissive setting wins");
+    }
+}
@@ -14,6 +14,8 @@
 //!     * one reference maps to a file on disk
 //!   * **packed**
 //!     * references are stored in a single human-readable file, along with their targets if they are symbolic.
+//! * **[reftable]**
+//!   * references and their logs are stored in a stack of binary tables, with newer tables overriding older ones.
 //!
 //! ## Feature Flags
 #![cfg_attr(
@@ -29,7 +31,7 @@ use gix_object::bstr::{BStr, BString};
 
 #[path = "store/mod.rs"]
 mod store_impl;
-pub use store_impl::{file, packed};
+pub use store_impl::{file, packed, reftable};
 
 mod fullname;
 ///
@@ -70,20 +72,46 @@ pub mod store {
             /// to avoid side effects. This only needs to be `true` on Windows, but can be `true` on other platforms
             /// if they need to remain compatible with Windows.
             pub prohibit_windows_device_names: bool,
+            /// The equivalent of `core.ignoreCase`, to be set if the filesystem ignores the case of file names, so
+            /// references whose names only differ in case can't be stored as loose references side by side.
+            pub ignore_case: bool,
+            /// How to flush loose references and packed-refs to disk before moving them into place.
+            pub fsync: gix_features::fs::Fsync,
+            /// The format in which references are stored, the equivalent of `extensions.refStorage`.
+            pub format: super::Format,
         }
     }
     /// The way a file store handles the reflog
     #[derive(Default, Debug, PartialOrd, PartialEq, Ord, Eq, Hash, Clone, Copy)]
     pub enum WriteReflog {
         /// Always write the reflog for all references for ref edits, unconditionally.
+        ///
+        /// This is the equivalent of `core.logAllRefUpdates=always`.
         Always,
-        /// Write a ref log for ref edits according to the standard rules.
+        /// Write a ref log for ref edits according to the standard rules, i.e. create them for `HEAD` and references
+        /// in `refs/heads/`, `refs/remotes/` and `refs/notes/`, and append to existing ones.
+        ///
+        /// This is the equivalent of `core.logAllRefUpdates=true`, and the default in repositories with a worktree.
         #[default]
         Normal,
+        /// Only append to existing ref logs, and only create new ones if this is requested by the edit.
+        ///
+        /// This is the equivalent of `core.logAllRefUpdates=false`, and the default in bare repositories.
+        AppendToExisting,
         /// Never write a ref log.
         Disable,
     }
 
+    /// The format in which references are stored in a repository, as configured by `extensions.refStorage`.
+    #[derive(Default, Debug, PartialEq, Eq, Hash, Clone, Copy)]
+    pub enum Format {
+        /// Loose references in files along with a `packed-refs` file, the traditional format.
+        #[default]
+        Files,
+        /// All references and their logs are stored in reference tables.
+        Reftable,
+    }
+
     /// A thread-local handle for interacting with a [`Store`][crate::Store] to find and iterate references.
     #[derive(Clone)]
     #[allow(dead_code)]
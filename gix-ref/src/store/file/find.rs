@@ -165,6 +165,18 @@ impl file::Store {
         let full_name = precomposed_partial_name
             .unwrap_or(partial_name)
             .construct_full_name_ref(inbetween, path_buf, consider_pseudo_ref);
+        if let Some((dir, name_in_table)) = self.reftable_dir_and_name(full_name) {
+            let stack = self.reftable_snapshot(&dir)?;
+            return Ok(stack
+                .and_then(|stack| stack.try_find(name_in_table.as_ref()))
+                .map(|mut r| {
+                    r.name = full_name.to_owned();
+                    if let Some(namespace) = &self.namespace {
+                        r.strip_namespace(namespace);
+                    }
+                    r
+                }));
+        }
         let content_buf = self.ref_contents(full_name).map_err(|err| Error::ReadFileContents {
             source: err,
             path: self.reference_path(full_name),
@@ -388,7 +400,7 @@ pub mod existing {
 mod error {
     use std::{convert::Infallible, io, path::PathBuf};
 
-    use crate::{file, store_impl::packed};
+    use crate::{file, store_impl::packed, store_impl::reftable};
 
     /// The error returned by [file::Store::find()].
     #[derive(Debug, thiserror::Error)]
@@ -407,6 +419,8 @@ mod error {
         PackedRef(#[from] packed::find::Error),
         #[error("Could not open the packed refs buffer when trying to find references.")]
         PackedOpen(#[from] packed::buffer::open::Error),
+        #[error("Could not open the stack of reference tables when trying to find references.")]
+        ReftableOpen(#[from] reftable::open::Error),
     }
 
     impl From<Infallible> for Error {
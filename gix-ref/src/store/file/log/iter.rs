@@ -77,6 +77,82 @@ impl<'a> Iterator for Forward<'a> {
     }
 }
 
+/// Returns a forward iterator over the lines read from `log`, starting from the first line in the file and ending at the last.
+///
+/// Unlike [`forward()`], only one line is held in memory at a time, which is useful for huge reference logs that
+/// are only read.
+/// It will continue parsing even if individual log entries failed to parse, leaving it to the driver to decide whether to
+/// abort or continue.
+pub fn forward_streaming<R>(log: R) -> ForwardStreaming<R>
+where
+    R: std::io::BufRead,
+{
+    ForwardStreaming {
+        read: log,
+        buf: Vec::new(),
+        count: 0,
+    }
+}
+
+/// An iterator yielding parsed lines read from a file from start to end, oldest to newest.
+pub struct ForwardStreaming<R> {
+    read: R,
+    buf: Vec<u8>,
+    count: usize,
+}
+
+impl<R> Iterator for ForwardStreaming<R>
+where
+    R: std::io::BufRead,
+{
+    type Item = Result<crate::log::Line, reverse::Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.buf.clear();
+        match self.read.read_until(b'\n', &mut self.buf) {
+            Ok(0) => None,
+            Ok(_) => {
+                let line = self.buf.strip_suffix(b"\n").unwrap_or(&self.buf);
+                let line = line.strip_suffix(b"\r").unwrap_or(line);
+                let res = log::LineRef::from_bytes(line)
+                    .map_err(|err| reverse::Error::Decode(decode::Error::new(err, LineNumber::FromStart(self.count))))
+                    .map(Into::into);
+                self.count += 1;
+                Some(res)
+            }
+            Err(err) => Some(Err(err.into())),
+        }
+    }
+}
+
+/// The data read by the [reverse](reverse()) and [streaming](forward_streaming()) iterators obtained from a [store](file::Store),
+/// which is a file unless references are stored in tables.
+#[derive(Debug)]
+pub enum Source {
+    /// The file containing the log.
+    File(std::fs::File),
+    /// The log as read from reference tables, in the serialization format of files.
+    Buffer(std::io::Cursor<Vec<u8>>),
+}
+
+impl std::io::Read for Source {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        match self {
+            Source::File(file) => file.read(buf),
+            Source::Buffer(buffer) => buffer.read(buf),
+        }
+    }
+}
+
+impl std::io::Seek for Source {
+    fn seek(&mut self, pos: std::io::SeekFrom) -> std::io::Result<u64> {
+        match self {
+            Source::File(file) => file.seek(pos),
+            Source::Buffer(buffer) => buffer.seek(pos),
+        }
+    }
+}
+
 /// A platform to store a buffer to hold ref log lines for iteration.
 #[must_use = "Iterators should be obtained from this platform"]
 pub struct Platform<'a, 's> {
@@ -90,7 +166,7 @@ pub struct Platform<'a, 's> {
 
 impl Platform<'_, '_> {
     /// Return a forward iterator over all log-lines, most recent to oldest.
-    pub fn rev(&mut self) -> std::io::Result<Option<log::iter::Reverse<'_, std::fs::File>>> {
+    pub fn rev(&mut self) -> std::io::Result<Option<log::iter::Reverse<'_, Source>>> {
         self.buf.clear();
         self.buf.resize(512, 0);
         self.store
@@ -98,6 +174,13 @@ impl Platform<'_, '_> {
             .map_err(must_be_io_err)
     }
 
+    /// Return a forward iterator over all log-lines, oldest to most recent, which reads one line at a time.
+    pub fn all_streaming(
+        &self,
+    ) -> std::io::Result<Option<log::iter::ForwardStreaming<std::io::BufReader<Source>>>> {
+        self.store.reflog_iter_streaming(self.name).map_err(must_be_io_err)
+    }
+
     /// Return a forward iterator over all log-lines, oldest to most recent.
     pub fn all(&mut self) -> std::io::Result<Option<log::iter::Forward<'_>>> {
         self.buf.clear();
@@ -147,7 +230,7 @@ pub mod reverse {
 
     use super::decode;
 
-    /// The error returned by the [`Reverse`][super::Reverse] iterator
+    /// The error returned by the [`Reverse`][super::Reverse] and [`ForwardStreaming`][super::ForwardStreaming] iterators
     #[derive(Debug, thiserror::Error)]
     #[allow(missing_docs)]
     pub enum Error {
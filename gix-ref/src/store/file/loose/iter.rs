@@ -28,6 +28,15 @@ impl SortedLoosePaths {
             }),
         }
     }
+
+    /// Return an instance that doesn't yield any paths, for when references aren't stored in files.
+    pub fn empty(base: PathBuf) -> Self {
+        SortedLoosePaths {
+            base,
+            filename_prefix: None,
+            file_walk: None,
+        }
+    }
 }
 
 impl Iterator for SortedLoosePaths {
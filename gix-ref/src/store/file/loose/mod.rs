@@ -43,18 +43,26 @@ mod init {
                 object_hash,
                 precompose_unicode,
                 prohibit_windows_device_names,
+                ignore_case,
+                fsync,
+                format,
             }: crate::store::init::Options,
         ) -> Self {
             file::Store {
                 git_dir,
                 packed_buffer_mmap_threshold: packed_refs_mmap_threshold(),
+                format,
                 common_dir: None,
                 write_reflog,
                 namespace: None,
                 prohibit_windows_device_names,
                 packed: gix_fs::SharedFileSnapshotMut::new().into(),
+                reftable: gix_fs::SharedFileSnapshotMut::new().into(),
+                worktree_reftable: gix_fs::SharedFileSnapshotMut::new().into(),
                 object_hash,
                 precompose_unicode,
+                ignore_case,
+                fsync,
             }
         }
 
@@ -71,18 +79,26 @@ mod init {
                 object_hash,
                 precompose_unicode,
                 prohibit_windows_device_names,
+                ignore_case,
+                fsync,
+                format,
             }: crate::store::init::Options,
         ) -> Self {
             file::Store {
                 git_dir,
                 packed_buffer_mmap_threshold: packed_refs_mmap_threshold(),
+                format,
                 common_dir: Some(common_dir),
                 write_reflog,
                 namespace: None,
                 prohibit_windows_device_names,
                 packed: gix_fs::SharedFileSnapshotMut::new().into(),
+                reftable: gix_fs::SharedFileSnapshotMut::new().into(),
+                worktree_reftable: gix_fs::SharedFileSnapshotMut::new().into(),
                 object_hash,
                 precompose_unicode,
+                ignore_case,
+                fsync,
             }
         }
     }
@@ -1,7 +1,7 @@
 use std::{io::Read, path::PathBuf};
 
 use crate::{
-    store_impl::{file, file::log},
+    store_impl::{file, file::log, file::log::iter::Source},
     FullNameRef,
 };
 
@@ -16,7 +16,15 @@ impl file::Store {
         Name: TryInto<&'a FullNameRef, Error = E>,
         crate::name::Error: From<E>,
     {
-        Ok(self.reflog_path(name.try_into()?).is_file())
+        let name = name.try_into()?;
+        if let Some((dir, name_in_table)) = self.reftable_dir_and_name(name) {
+            return Ok(self
+                .reftable_snapshot(&dir)
+                .ok()
+                .flatten()
+                .map_or(false, |stack| stack.log_exists(name_in_table.as_ref())));
+        }
+        Ok(self.reflog_path(name).is_file())
     }
 
     /// Return a reflog reverse iterator for the given fully qualified `name`, reading chunks from the back into the fixed buffer `buf`.
@@ -27,19 +35,58 @@ impl file::Store {
         &self,
         name: Name,
         buf: &'b mut [u8],
-    ) -> Result<Option<log::iter::Reverse<'b, std::fs::File>>, Error>
+    ) -> Result<Option<log::iter::Reverse<'b, Source>>, Error>
+    where
+        Name: TryInto<&'a FullNameRef, Error = E>,
+        crate::name::Error: From<E>,
+    {
+        let name: &FullNameRef = name.try_into().map_err(|err| Error::RefnameValidation(err.into()))?;
+        if let Some(log) = self.reftable_log(name)? {
+            return log
+                .map(|log| log::iter::reverse(Source::Buffer(std::io::Cursor::new(log)), buf))
+                .transpose()
+                .map_err(Into::into);
+        }
+        let path = self.reflog_path(name);
+        if path.is_dir() {
+            return Ok(None);
+        }
+        match std::fs::File::open(&path) {
+            Ok(file) => Ok(Some(log::iter::reverse(Source::File(file), buf)?)),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    /// Return a reflog forward iterator for the given fully qualified `name` which reads the file line by line.
+    ///
+    /// The iterator will traverse log entries from oldest to newest.
+    /// Return `Ok(None)` if no reflog exists.
+    pub fn reflog_iter_streaming<'a, Name, E>(
+        &self,
+        name: Name,
+    ) -> Result<Option<log::iter::ForwardStreaming<std::io::BufReader<Source>>>, Error>
     where
         Name: TryInto<&'a FullNameRef, Error = E>,
         crate::name::Error: From<E>,
     {
         let name: &FullNameRef = name.try_into().map_err(|err| Error::RefnameValidation(err.into()))?;
+        if let Some(log) = self.reftable_log(name)? {
+            return Ok(log.map(|log| {
+                log::iter::forward_streaming(std::io::BufReader::new(Source::Buffer(std::io::Cursor::new(log))))
+            }));
+        }
         let path = self.reflog_path(name);
         if path.is_dir() {
             return Ok(None);
         }
         match std::fs::File::open(&path) {
-            Ok(file) => Ok(Some(log::iter::reverse(file, buf)?)),
+            Ok(file) => Ok(Some(log::iter::forward_streaming(std::io::BufReader::new(Source::File(
+                file,
+            ))))),
             Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            #[cfg(windows)]
+            Err(err) if err.kind() == std::io::ErrorKind::PermissionDenied => Ok(None),
             Err(err) => Err(err.into()),
         }
     }
@@ -58,6 +105,12 @@ impl file::Store {
         crate::name::Error: From<E>,
     {
         let name: &FullNameRef = name.try_into().map_err(|err| Error::RefnameValidation(err.into()))?;
+        if let Some(log) = self.reftable_log(name)? {
+            return Ok(log.map(|log| {
+                *buf = log;
+                log::iter::forward(buf)
+            }));
+        }
         let path = self.reflog_path(name);
         match std::fs::File::open(&path) {
             Ok(mut file) => {
@@ -81,6 +134,26 @@ impl file::Store {
         let (base, rela_path) = self.reflog_base_and_relative_path(name);
         base.join(rela_path)
     }
+
+    /// If the log of `name` is stored in reference tables, return `Some` with its serialization in the format of
+    /// log files, or `Some(None)` if there is no such log.
+    /// Return `None` if the log is stored in a file.
+    fn reftable_log(&self, name: &FullNameRef) -> Result<Option<Option<Vec<u8>>>, Error> {
+        let Some((dir, name_in_table)) = self.reftable_dir_and_name(name) else {
+            return Ok(None);
+        };
+        let Some(stack) = self.reftable_snapshot(&dir)? else {
+            return Ok(Some(None));
+        };
+        let Some(lines) = stack.log(name_in_table.as_ref()) else {
+            return Ok(Some(None));
+        };
+        let mut out = Vec::new();
+        for (_update_index, line) in lines {
+            line.write_to(&mut out)?;
+        }
+        Ok(Some(Some(out)))
+    }
 }
 
 ///
@@ -105,19 +178,22 @@ pub mod create_or_update {
             new: &oid,
             committer: Option<gix_actor::SignatureRef<'_>>,
             message: &BStr,
-            mut force_create_reflog: bool,
+            force_create_reflog: bool,
+            write_reflog: WriteReflog,
         ) -> Result<(), Error> {
             let (reflog_base, full_name) = self.reflog_base_and_relative_path(name);
-            match self.write_reflog {
-                WriteReflog::Normal | WriteReflog::Always => {
-                    if self.write_reflog == WriteReflog::Always {
-                        force_create_reflog = true;
-                    }
+            match write_reflog {
+                WriteReflog::Normal | WriteReflog::Always | WriteReflog::AppendToExisting => {
                     let mut options = std::fs::OpenOptions::new();
                     options.append(true).read(false);
                     let log_path = reflog_base.join(&full_name);
 
-                    if force_create_reflog || self.should_autocreate_reflog(&full_name) {
+                    let create = match write_reflog {
+                        WriteReflog::Always => true,
+                        WriteReflog::Normal => force_create_reflog || self.should_autocreate_reflog(&full_name),
+                        WriteReflog::AppendToExisting | WriteReflog::Disable => force_create_reflog,
+                    };
+                    if create {
                         let parent_dir = log_path.parent().expect("always with parent directory");
                         gix_tempfile::create_dir::all(parent_dir, Default::default()).map_err(|err| {
                             Error::CreateLeadingDirectories {
@@ -131,6 +207,8 @@ pub mod create_or_update {
                     let file_for_appending = match options.open(&log_path) {
                         Ok(f) => Some(f),
                         Err(err) if err.kind() == std::io::ErrorKind::NotFound => None,
+                        // A directory in place of the reflog means there is no reflog to append to.
+                        Err(_) if !create && log_path.is_dir() => None,
                         Err(err) => {
                             // TODO: when Kind::IsADirectory becomes stable, use that.
                             if log_path.is_dir() {
@@ -172,11 +250,10 @@ pub mod create_or_update {
             }
         }
 
-        fn should_autocreate_reflog(&self, full_name: &Path) -> bool {
+        pub(in crate::store_impl::file) fn should_autocreate_reflog(&self, full_name: &Path) -> bool {
             full_name.starts_with("refs/heads/")
                 || full_name.starts_with("refs/remotes/")
                 || full_name.starts_with("refs/notes/")
-                || full_name.starts_with("refs/worktree/") // NOTE: git does not write reflogs for worktree private refs
                 || full_name == Path::new("HEAD")
         }
 
@@ -239,6 +316,8 @@ mod error {
         RefnameValidation(#[from] crate::name::Error),
         #[error("The reflog file could not read")]
         Io(#[from] std::io::Error),
+        #[error("The stack of reference tables with the reflog could not be read")]
+        ReftableOpen(#[from] crate::reftable::open::Error),
     }
 }
 pub use error::Error;
@@ -33,7 +33,12 @@ fn reflog_lines(store: &file::Store, name: &str, buf: &mut Vec<u8>) -> Result<Ve
         .map_err(Into::into)
 }
 
-const WRITE_MODES: &[WriteReflog] = &[WriteReflog::Normal, WriteReflog::Disable, WriteReflog::Always];
+const WRITE_MODES: &[WriteReflog] = &[
+    WriteReflog::Normal,
+    WriteReflog::AppendToExisting,
+    WriteReflog::Disable,
+    WriteReflog::Always,
+];
 
 #[test]
 fn should_autocreate_is_unaffected_by_writemode() -> Result {
@@ -41,7 +46,13 @@ fn should_autocreate_is_unaffected_by_writemode() -> Result {
     for should_create_name in &["HEAD", "refs/heads/main", "refs/remotes/any", "refs/notes/any"] {
         assert!(store.should_autocreate_reflog(Path::new(should_create_name)));
     }
-    for should_not_create_name in &["FETCH_HEAD", "SOMETHING", "refs/special/this", "refs/tags/0.1.0"] {
+    for should_not_create_name in &[
+        "FETCH_HEAD",
+        "SOMETHING",
+        "refs/special/this",
+        "refs/tags/0.1.0",
+        "refs/worktree/private",
+    ] {
         assert!(!store.should_autocreate_reflog(Path::new(should_not_create_name)));
     }
     Ok(())
@@ -70,6 +81,7 @@ fn missing_reflog_creates_it_even_if_similarly_named_empty_dir_exists_and_append
             committer.to_ref().into(),
             b"the message".as_bstr(),
             false,
+            store.write_reflog,
         )?;
 
         let mut buf = Vec::new();
@@ -92,6 +104,7 @@ fn missing_reflog_creates_it_even_if_similarly_named_empty_dir_exists_and_append
                     committer.to_ref().into(),
                     b"next message".as_bstr(),
                     false,
+                    store.write_reflog,
                 )?;
 
                 let lines = reflog_lines(&store, full_name_str, &mut buf)?;
@@ -106,10 +119,10 @@ fn missing_reflog_creates_it_even_if_similarly_named_empty_dir_exists_and_append
                     }
                 );
             }
-            WriteReflog::Disable => {
+            WriteReflog::AppendToExisting | WriteReflog::Disable => {
                 assert!(
                     store.reflog_iter(full_name, &mut buf)?.is_none(),
-                    "there is no logs in disabled mode"
+                    "no log is created in these modes"
                 );
             }
         };
@@ -128,6 +141,7 @@ fn missing_reflog_creates_it_even_if_similarly_named_empty_dir_exists_and_append
             committer.to_ref().into(),
             b"more complicated reflog creation".as_bstr(),
             false,
+            store.write_reflog,
         )?;
 
         match mode {
@@ -142,7 +156,7 @@ fn missing_reflog_creates_it_even_if_similarly_named_empty_dir_exists_and_append
                     "the empty directory was replaced with the reflog file"
                 );
             }
-            WriteReflog::Disable => {
+            WriteReflog::AppendToExisting | WriteReflog::Disable => {
                 assert!(
                     store.reflog_iter(full_name_str, &mut buf)?.is_none(),
                     "reflog still doesn't exist"
@@ -157,3 +171,63 @@ fn missing_reflog_creates_it_even_if_similarly_named_empty_dir_exists_and_append
     }
     Ok(())
 }
+
+#[test]
+fn existing_reflogs_are_appended_to_and_creation_can_be_forced_unless_disabled() -> Result {
+    for mode in WRITE_MODES {
+        let (_keep, store) = empty_store(*mode)?;
+        let new = hex_to_id("28ce6a8b26aa170e1de65536fe8abe1832bd3242");
+        let committer = Signature {
+            name: "committer".into(),
+            email: "committer@example.com".into(),
+            time: Time {
+                seconds: 1234,
+                offset: 1800,
+                sign: Sign::Plus,
+            },
+        };
+        let mut buf = Vec::new();
+        let mut append = |name: &str, force_create_reflog: bool| -> Result<Option<usize>> {
+            let full_name: &FullNameRef = name.try_into()?;
+            store.reflog_create_or_append(
+                full_name,
+                None,
+                &new,
+                committer.to_ref().into(),
+                b"message".as_bstr(),
+                force_create_reflog,
+                store.write_reflog,
+            )?;
+            Ok(match store.reflog_iter(name, &mut buf)? {
+                Some(lines) => Some(lines.count()),
+                None => None,
+            })
+        };
+
+        let num_lines = append("refs/tags/not-autocreated", false)?;
+        assert_eq!(
+            num_lines,
+            (*mode == WriteReflog::Always).then_some(1),
+            "{mode:?}: only 'always' creates reflogs for all references"
+        );
+
+        let existing = "refs/tags/existing";
+        let reflog_path = store.reflog_path(existing.try_into()?);
+        std::fs::create_dir_all(reflog_path.parent().expect("parent"))?;
+        std::fs::write(&reflog_path, b"")?;
+        let num_lines = append(existing, false)?;
+        assert_eq!(
+            num_lines,
+            Some(if *mode == WriteReflog::Disable { 0 } else { 1 }),
+            "{mode:?}: existing reflogs are appended to unless reflogs are disabled"
+        );
+
+        let num_lines = append("refs/tags/forced", true)?;
+        assert_eq!(
+            num_lines,
+            (*mode != WriteReflog::Disable).then_some(1),
+            "{mode:?}: creation can be forced unless reflogs are disabled"
+        );
+    }
+    Ok(())
+}
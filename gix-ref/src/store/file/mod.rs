@@ -3,7 +3,11 @@ use std::{
     path::{Path, PathBuf},
 };
 
-use crate::{bstr::BStr, store::WriteReflog, Namespace};
+use crate::{
+    bstr::BStr,
+    store::{Format, WriteReflog},
+    Namespace,
+};
 
 /// A store for reference which uses plain files.
 ///
@@ -22,6 +26,8 @@ pub struct Store {
     object_hash: gix_hash::Kind,
     /// The amount of bytes needed for `mmap` to be used to open packed refs.
     packed_buffer_mmap_threshold: u64,
+    /// The format in which references are stored.
+    format: Format,
 
     /// The way to handle reflog edits
     pub write_reflog: WriteReflog,
@@ -37,10 +43,19 @@ pub struct Store {
     /// Iterated references will be returned verbatim, thus when sending them over the wire they have to be precomposed
     /// as needed.
     pub precompose_unicode: bool,
+    /// If set, the filesystem is assumed to ignore the case of file names, which is used to detect when a loose
+    /// reference would be written into the file of another reference whose name differs only in case.
+    pub ignore_case: bool,
+    /// How to flush loose references and packed-refs to disk before moving them into place.
+    pub fsync: gix_features::fs::Fsync,
     /// A packed buffer which can be mapped in one version and shared as such.
     /// It's updated only in one spot, which is prior to reading it based on file stamps.
     /// Doing it like this has the benefit of being able to hand snapshots out to people without blocking others from updating it.
     packed: packed::modifiable::MutableSharedBuffer,
+    /// The stack of reference tables in the common directory, handled like the `packed` buffer.
+    reftable: reftable::modifiable::MutableSharedStack,
+    /// The stack of reference tables with the private references of a linked work tree, handled like the `packed` buffer.
+    worktree_reftable: reftable::modifiable::MutableSharedStack,
 }
 
 mod access {
@@ -56,10 +71,15 @@ mod access {
         }
     }
 
-    use crate::file;
+    use crate::{file, store::Format};
 
     /// Access
     impl file::Store {
+        /// Return the format in which references are stored.
+        pub fn format(&self) -> Format {
+            self.format
+        }
+
         /// Return the `.git` directory at which all references are loaded.
         ///
         /// For worktrees, this is the linked work-tree private ref location,
@@ -90,6 +110,9 @@ pub struct Transaction<'s, 'p> {
     packed_transaction: Option<crate::store_impl::packed::Transaction>,
     updates: Option<Vec<transaction::Edit>>,
     packed_refs: transaction::PackedRefs<'p>,
+    /// The locked stacks of reference tables the edits are written to, if references are stored in tables.
+    reftables: Vec<transaction::reftable::LockedStack>,
+    write_reflog: WriteReflog,
 }
 
 pub(in crate::store_impl::file) fn path_to_name<'a>(path: impl Into<Cow<'a, Path>>) -> Cow<'a, BStr> {
@@ -123,5 +146,8 @@ pub mod transaction;
 ///
 pub mod packed;
 
+///
+pub mod reftable;
+
 mod raw_ext;
 pub use raw_ext::ReferenceExt;
@@ -8,6 +8,7 @@ use std::{
 
 use crate::{
     file::{loose, loose::iter::SortedLoosePaths, path_to_name},
+    store::Format,
     store_impl::{file, packed},
     BString, FullName, Namespace, Reference,
 };
@@ -24,6 +25,8 @@ pub struct LooseThenPacked<'p, 's> {
     iter_git_dir: Peekable<SortedLoosePaths>,
     #[allow(dead_code)]
     iter_common_dir: Option<Peekable<SortedLoosePaths>>,
+    /// All references to return if they are stored in tables, in which case the other iterators are empty.
+    iter_reftable: Option<std::vec::IntoIter<Reference>>,
     buf: Vec<u8>,
 }
 
@@ -116,6 +119,11 @@ impl Iterator for LooseThenPacked<'_, '_> {
     type Item = Result<Reference, Error>;
 
     fn next(&mut self) -> Option<Self::Item> {
+        if let Some(refs) = self.iter_reftable.as_mut() {
+            let r = refs.next()?;
+            return Some(Ok(self.strip_namespace(r)));
+        }
+
         fn advance_to_non_private(iter: &mut Peekable<SortedLoosePaths>) {
             while let Some(Ok((_path, name))) = iter.peek() {
                 if name.category().map_or(false, |cat| cat.is_worktree_private()) {
@@ -410,6 +418,9 @@ impl file::Store {
         common_dir_info: Option<IterInfo<'_>>,
         packed: Option<&'p packed::Buffer>,
     ) -> std::io::Result<LooseThenPacked<'p, 's>> {
+        if self.format == Format::Reftable {
+            return self.iter_reftable(git_dir_info.prefix());
+        }
         Ok(LooseThenPacked {
             git_dir: self.git_dir(),
             common_dir: self.common_dir(),
@@ -426,6 +437,37 @@ impl file::Store {
             },
             iter_git_dir: git_dir_info.into_iter(),
             iter_common_dir: common_dir_info.map(IterInfo::into_iter),
+            iter_reftable: None,
+            buf: Vec::new(),
+            namespace: self.namespace.as_ref(),
+        })
+    }
+
+    /// Iterate the references in tables whose names start with `prefix`, or all references in `refs/` if unset.
+    /// Just like with loose references, `refs/heads` is treated like `refs/heads/` if there are references in it.
+    fn iter_reftable<'s, 'p>(&'s self, prefix: Option<&Path>) -> std::io::Result<LooseThenPacked<'p, 's>> {
+        let mut prefix = prefix.map_or_else(|| "refs/".into(), |prefix| path_to_name(prefix).into_owned());
+        let refs = self
+            .reftable_refs()
+            .map_err(|err| std::io::Error::new(std::io::ErrorKind::Other, err))?;
+        if !prefix.ends_with(b"/") {
+            let mut dir_prefix = prefix.clone();
+            dir_prefix.push(b'/');
+            if refs.iter().any(|r| r.name.as_bstr().starts_with(&dir_prefix)) {
+                prefix = dir_prefix;
+            }
+        }
+        let refs: Vec<_> = refs
+            .into_iter()
+            .filter(|r| r.name.as_bstr().starts_with(&prefix))
+            .collect();
+        Ok(LooseThenPacked {
+            git_dir: self.git_dir(),
+            common_dir: self.common_dir(),
+            iter_packed: None,
+            iter_git_dir: SortedLoosePaths::empty(self.git_dir.clone()).peekable(),
+            iter_common_dir: None,
+            iter_reftable: Some(refs.into_iter()),
             buf: Vec::new(),
             namespace: self.namespace.as_ref(),
         })
@@ -0,0 +1,122 @@
+use std::path::{Path, PathBuf};
+
+use gix_features::threading::OwnShared;
+
+use crate::{
+    store::Format,
+    store_impl::{file, reftable},
+    FullName, FullNameRef,
+};
+
+/// An up-to-date snapshot of a stack of reference tables.
+pub type SharedStackSnapshot = gix_fs::SharedFileSnapshot<reftable::Stack>;
+
+impl file::Store {
+    /// Try to open the stack of reference tables in the common directory. It's not an error if it doesn't exist, but yields `Ok(None)`,
+    /// which is also the case if references are stored in files.
+    pub fn open_reftable(&self) -> Result<Option<reftable::Stack>, reftable::open::Error> {
+        reftable::Stack::at(self.common_dir_resolved().join("reftable"), self.object_hash)
+    }
+
+    /// Return a possibly cached stack of reference tables in the common directory with shared ownership. At retrieval it will
+    /// assure it's up to date, but after that it can be considered a snapshot as it cannot change anymore.
+    pub fn cached_reftable(&self) -> Result<Option<SharedStackSnapshot>, reftable::open::Error> {
+        self.reftable_snapshot(&self.common_dir_resolved().join("reftable"))
+    }
+
+    /// Return the `reftable` directory of the stack which stores `name` along with the name under which it is stored there,
+    /// or `None` if `name` is stored in a file, either because this store uses files or because `name` is a special reference.
+    ///
+    /// The name may differ due to the namespace and because references of other work trees are stored with their
+    /// plain name in the stack of their work tree, just like their logs would be.
+    pub(crate) fn reftable_dir_and_name(&self, name: &FullNameRef) -> Option<(PathBuf, FullName)> {
+        if self.format != Format::Reftable || is_special_ref(name) {
+            return None;
+        }
+        let is_reflog = true;
+        let (base, name) = self.to_base_dir_and_relative_name(name, is_reflog);
+        let name = match &self.namespace {
+            Some(namespace) => namespace.to_owned().into_namespaced_name(name),
+            None => name.to_owned(),
+        };
+        Some((base.join("reftable"), name))
+    }
+
+    /// Return a possibly cached snapshot of the stack in the `reftable` directory `dir`, or `None` if it doesn't exist.
+    pub(crate) fn reftable_snapshot(&self, dir: &Path) -> Result<Option<SharedStackSnapshot>, reftable::open::Error> {
+        match self.reftable_cache(dir) {
+            Some(cache) => cache.recent_snapshot(
+                || dir.join("tables.list").metadata().and_then(|m| m.modified()).ok(),
+                || reftable::Stack::at(dir.to_owned(), self.object_hash),
+            ),
+            None => Ok(reftable::Stack::at(dir.to_owned(), self.object_hash)?
+                .map(|stack| OwnShared::new(gix_fs::FileSnapshot::new(stack)))),
+        }
+    }
+
+    /// Return the references stored in the `reftable` directory of the common directory and, in linked work trees, the
+    /// work tree private references of the `git_dir`, sorted by name.
+    pub(crate) fn reftable_refs(&self) -> Result<Vec<crate::Reference>, reftable::open::Error> {
+        let common = self.cached_reftable()?;
+        let mut refs: Vec<_> = match self.common_dir() {
+            Some(_) => {
+                let is_private = |r: &crate::Reference| r.name.category().map_or(false, |c| c.is_worktree_private());
+                let private = self.reftable_snapshot(&self.git_dir.join("reftable"))?;
+                common
+                    .iter()
+                    .flat_map(|stack| stack.iter())
+                    .filter(|r| !is_private(r))
+                    .chain(private.iter().flat_map(|stack| stack.iter()).filter(is_private))
+                    .collect()
+            }
+            None => common.iter().flat_map(|stack| stack.iter()).collect(),
+        };
+        refs.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(refs)
+    }
+
+    fn reftable_cache(&self, dir: &Path) -> Option<&modifiable::MutableSharedStack> {
+        let base = dir.parent()?;
+        if base == self.common_dir_resolved() {
+            Some(&self.reftable)
+        } else if self.common_dir.is_some() && base == self.git_dir {
+            Some(&self.worktree_reftable)
+        } else {
+            None
+        }
+    }
+}
+
+/// Return `true` if `name` is stored in a file even if all other references are stored in tables.
+fn is_special_ref(name: &FullNameRef) -> bool {
+    let name = name.as_bstr();
+    name == "FETCH_HEAD" || name == "MERGE_HEAD"
+}
+
+pub(crate) mod modifiable {
+    use std::path::Path;
+
+    use gix_features::threading::OwnShared;
+
+    use crate::{file, reftable};
+
+    pub(crate) type MutableSharedStack = OwnShared<gix_fs::SharedFileSnapshotMut<reftable::Stack>>;
+
+    impl file::Store {
+        /// Forcefully reload the stack of reference tables in the `reftable` directory `dir` if it is cached.
+        ///
+        /// This method should be used if it's clear that the stack on disk has changed, to
+        /// make the latest changes visible before other operations are done on this instance.
+        pub(crate) fn force_refresh_reftable(&self, dir: &Path) -> Result<(), reftable::open::Error> {
+            let Some(cache) = self.reftable_cache(dir) else {
+                return Ok(());
+            };
+            cache.force_refresh(|| {
+                let Ok(modified) = dir.join("tables.list").metadata().and_then(|m| m.modified()) else {
+                    return Ok(None);
+                };
+                reftable::Stack::at(dir.to_owned(), self.object_hash).map(|stack| Some(modified).zip(stack))
+            })
+        }
+    }
+}
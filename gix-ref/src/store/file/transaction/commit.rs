@@ -1,4 +1,7 @@
+use gix_hash::{oid, ObjectId};
+
 use crate::{
+    store::Format,
     store_impl::file::{transaction::PackedRefs, Transaction},
     transaction::{Change, LogChange, RefEdit, RefLog},
     Target,
@@ -17,6 +20,7 @@ impl Transaction<'_, '_> {
     ///
     /// In this stage, we perform the following operations:
     ///
+    /// * delete reflogs of deleted references that are in the way of the reflogs of updated references
     /// * update the ref log
     /// * move updated refs into place
     /// * delete reflogs and empty parent directories
@@ -30,59 +34,52 @@ impl Transaction<'_, '_> {
     }
 
     fn commit_inner(self, committer: Option<gix_actor::SignatureRef<'_>>) -> Result<Vec<RefEdit>, Error> {
+        if self.store.format() == Format::Reftable {
+            return self.commit_reftable(committer);
+        }
         let mut updates = self.updates.expect("BUG: must call prepare before commit");
         let delete_loose_refs = matches!(
             self.packed_refs,
             PackedRefs::DeletionsAndNonSymbolicUpdatesRemoveLooseSourceReference(_)
         );
 
+        // Reflogs of deleted references which are in the way of the reflog of an updated reference, like `a` for `a/b`,
+        // have to be removed before the new reflog can be created.
+        let mut reflog_deleted = vec![false; updates.len()];
+        for (idx, change) in updates.iter().enumerate() {
+            if matches!(change.update.change, Change::Delete { .. })
+                && updates.iter().any(|other| {
+                    matches!(other.update.change, Change::Update { .. })
+                        && is_directory_file_conflict(change.update.name.as_bstr(), other.update.name.as_bstr())
+                })
+            {
+                self.store.delete_reflog_of(change)?;
+                reflog_deleted[idx] = true;
+            }
+        }
+
         // Perform updates first so live commits remain referenced
         for change in &mut updates {
             assert!(!change.update.deref, "Deref mode is turned into splits and turned off");
             match &change.update.change {
                 // reflog first, then reference
-                Change::Update { log, new, expected } => {
+                Change::Update { log, new, .. } => {
                     let lock = change.lock.take();
                     let (update_ref, update_reflog) = match log.mode {
                         RefLog::Only => (false, true),
                         RefLog::AndReference => (true, true),
                     };
                     if update_reflog {
-                        let log_update = match new {
-                            Target::Symbolic(_) => {
-                                // Special HACK: no reflog for symref changes as there is no OID involved which the reflog needs.
-                                // Unless, the ref is new and we can obtain a peeled id
-                                // identified by the expectation of what could be there, as is the case when cloning.
-                                match expected {
-                                    PreviousValue::ExistingMustMatch(Target::Object(oid)) => {
-                                        Some((Some(gix_hash::ObjectId::null(oid.kind())), oid))
-                                    }
-                                    _ => None,
-                                }
-                            }
-                            Target::Object(new_oid) => {
-                                let previous = match expected {
-                                    // Here, this means that the ref already existed, and that it will receive (even transitively)
-                                    // the given value
-                                    PreviousValue::MustExistAndMatch(Target::Object(oid)) => Some(oid.to_owned()),
-                                    _ => None,
-                                }
-                                .or(change.leaf_referent_previous_oid);
-                                Some((previous, new_oid))
-                            }
-                        };
-                        if let Some((previous, new_oid)) = log_update {
-                            let do_update = previous.as_ref().map_or(true, |previous| previous != new_oid);
-                            if do_update {
-                                self.store.reflog_create_or_append(
-                                    change.update.name.as_ref(),
-                                    previous,
-                                    new_oid,
-                                    committer,
-                                    log.message.as_ref(),
-                                    log.force_create_reflog,
-                                )?;
-                            }
+                        if let Some((previous, new_oid)) = reflog_entry(change) {
+                            self.store.reflog_create_or_append(
+                                change.update.name.as_ref(),
+                                previous,
+                                new_oid,
+                                committer,
+                                log.message.as_ref(),
+                                log.force_create_reflog,
+                                self.write_reflog,
+                            )?;
                         }
                     }
                     // Don't do anything else while keeping the lock after potentially updating the reflog.
@@ -117,29 +114,13 @@ impl Transaction<'_, '_> {
             }
         }
 
-        for change in &mut updates {
-            let (reflog_root, relative_name) = self.store.reflog_base_and_relative_path(change.update.name.as_ref());
+        for (change, reflog_deleted) in updates.iter().zip(reflog_deleted) {
             match &change.update.change {
                 Change::Update { .. } => {}
-                Change::Delete { .. } => {
-                    // Reflog deletion happens first in case it fails a ref without log is less terrible than
-                    // a log without a reference.
-                    let reflog_path = reflog_root.join(relative_name);
-                    if let Err(err) = std::fs::remove_file(&reflog_path) {
-                        if err.kind() != std::io::ErrorKind::NotFound {
-                            return Err(Error::DeleteReflog {
-                                source: err,
-                                full_name: change.name(),
-                            });
-                        }
-                    } else {
-                        gix_tempfile::remove_dir::empty_upward_until_boundary(
-                            reflog_path.parent().expect("never without parent"),
-                            &reflog_root,
-                        )
-                        .ok();
-                    }
-                }
+                // Reflog deletion happens first in case it fails a ref without log is less terrible than
+                // a log without a reference.
+                Change::Delete { .. } if !reflog_deleted => self.store.delete_reflog_of(change)?,
+                Change::Delete { .. } => {}
             }
         }
 
@@ -162,8 +143,9 @@ impl Transaction<'_, '_> {
             if take_lock_and_delete {
                 let lock = change.lock.take();
                 let reference_path = self.store.reference_path(change.update.name.as_ref());
-                if let Err(err) = std::fs::remove_file(reference_path) {
-                    if err.kind() != std::io::ErrorKind::NotFound {
+                if let Err(err) = std::fs::remove_file(&reference_path) {
+                    // The place of the deleted reference, or of its directory, may have been taken by a new one.
+                    if err.kind() != std::io::ErrorKind::NotFound && reference_path.is_file() {
                         return Err(Error::DeleteReference {
                             err,
                             full_name: change.name(),
@@ -176,6 +158,66 @@ impl Transaction<'_, '_> {
         Ok(updates.into_iter().map(|edit| edit.update).collect())
     }
 }
+
+impl crate::file::Store {
+    fn delete_reflog_of(&self, change: &super::Edit) -> Result<(), Error> {
+        let (reflog_root, relative_name) = self.reflog_base_and_relative_path(change.update.name.as_ref());
+        let reflog_path = reflog_root.join(relative_name);
+        if let Err(err) = std::fs::remove_file(&reflog_path) {
+            if err.kind() != std::io::ErrorKind::NotFound {
+                return Err(Error::DeleteReflog {
+                    source: err,
+                    full_name: change.name(),
+                });
+            }
+        } else {
+            gix_tempfile::remove_dir::empty_upward_until_boundary(
+                reflog_path.parent().expect("never without parent"),
+                &reflog_root,
+            )
+            .ok();
+        }
+        Ok(())
+    }
+}
+
+/// Return the previous and the new object id to record in the reflog for the update in `change`, or `None` if there
+/// is nothing to record.
+pub(super) fn reflog_entry(change: &super::Edit) -> Option<(Option<ObjectId>, &oid)> {
+    let Change::Update { new, expected, .. } = &change.update.change else {
+        return None;
+    };
+    let (previous, new_oid) = match new {
+        Target::Symbolic(_) => {
+            // Special HACK: no reflog for symref changes as there is no OID involved which the reflog needs.
+            // Unless, the ref is new and we can obtain a peeled id
+            // identified by the expectation of what could be there, as is the case when cloning.
+            match expected {
+                PreviousValue::ExistingMustMatch(Target::Object(oid)) => (Some(ObjectId::null(oid.kind())), oid),
+                _ => return None,
+            }
+        }
+        Target::Object(new_oid) => {
+            let previous = match expected {
+                // Here, this means that the ref already existed, and that it will receive (even transitively)
+                // the given value
+                PreviousValue::MustExistAndMatch(Target::Object(oid)) => Some(oid.to_owned()),
+                _ => None,
+            }
+            .or(change.leaf_referent_previous_oid);
+            (previous, new_oid)
+        }
+    };
+    let do_update = previous.as_ref().map_or(true, |previous| previous != new_oid);
+    do_update.then_some((previous, new_oid.as_ref()))
+}
+
+/// Return `true` if `a` is a directory of `b` or vice versa.
+fn is_directory_file_conflict(a: &gix_object::bstr::BStr, b: &gix_object::bstr::BStr) -> bool {
+    let (shorter, longer) = if a.len() < b.len() { (a, b) } else { (b, a) };
+    longer.starts_with(shorter) && longer.get(shorter.len()) == Some(&b'/')
+}
+
 mod error {
     use gix_object::bstr::BString;
 
@@ -197,6 +239,8 @@ mod error {
         DeleteReflog { full_name: BString, source: std::io::Error },
         #[error("The reflog could not be created or updated")]
         CreateOrUpdateRefLog(#[from] file::log::create_or_update::Error),
+        #[error("The table with all changes could not be added to the stack of reference tables")]
+        ReftableWrite(#[from] crate::reftable::write::Error),
     }
 }
 pub use error::Error;
@@ -4,6 +4,7 @@ use gix_hash::ObjectId;
 use gix_object::bstr::BString;
 
 use crate::{
+    store::WriteReflog,
     store_impl::{file, file::Transaction},
     transaction::RefEdit,
 };
@@ -66,13 +67,15 @@ impl file::Store {
     /// and will be provided as result of a successful transaction. Note that upon transaction failure, packed-refs
     /// will never have been altered.
     ///
-    /// The transaction inherits the parent namespace.
+    /// The transaction inherits the parent namespace, as well as the way reflogs are written.
     pub fn transaction(&self) -> Transaction<'_, '_> {
         Transaction {
             store: self,
             packed_transaction: None,
             updates: None,
             packed_refs: PackedRefs::default(),
+            reftables: Vec::new(),
+            write_reflog: self.write_reflog,
         }
     }
 }
@@ -83,6 +86,13 @@ impl<'p> Transaction<'_, 'p> {
         self.packed_refs = packed_refs;
         self
     }
+
+    /// Configure if and when reflogs are created or appended to during the transaction, overriding the
+    /// [setting of the store](file::Store::write_reflog).
+    pub fn write_reflog(mut self, write_reflog: WriteReflog) -> Self {
+        self.write_reflog = write_reflog;
+        self
+    }
 }
 
 impl std::fmt::Debug for Transaction<'_, '_> {
@@ -99,3 +109,5 @@ pub mod prepare;
 
 ///
 pub mod commit;
+
+pub(in crate::store_impl::file) mod reftable;
@@ -1,6 +1,9 @@
+use gix_object::bstr::{BStr, BString, ByteSlice};
+
 use crate::{
     packed,
     packed::transaction::buffer_into_transaction,
+    store::Format,
     store_impl::{
         file,
         file::{
@@ -14,7 +17,7 @@ use crate::{
 };
 
 impl Transaction<'_, '_> {
-    fn lock_ref_and_apply_change(
+    pub(super) fn lock_ref_and_apply_change(
         store: &file::Store,
         lock_fail_mode: gix_lock::acquire::Fail,
         packed: Option<&packed::Buffer>,
@@ -28,6 +31,15 @@ impl Transaction<'_, '_> {
             "locks can only be acquired once and it's all or nothing"
         );
 
+        if store.ignore_case {
+            if let Some(conflicting_name) = case_conflict(store, change.update.name.as_ref())? {
+                return Err(Error::CaseConflict {
+                    full_name: change.name(),
+                    conflicting_name,
+                });
+            }
+        }
+
         let existing_ref = store
             .ref_contents(change.update.name.as_ref())
             .map_err(Error::from)
@@ -63,44 +75,11 @@ impl Transaction<'_, '_> {
                         lock_fail_mode,
                         Some(base.clone().into_owned()),
                     )
-                    .map_err(|err| Error::LockAcquire {
-                        source: err,
-                        full_name: "borrowcheck won't allow change.name()".into(),
-                    })?
+                    .map_err(lock_error)?
                     .into()
                 };
 
-                match (&expected, &existing_ref) {
-                    (PreviousValue::MustNotExist, _) => {
-                        panic!("BUG: MustNotExist constraint makes no sense if references are to be deleted")
-                    }
-                    (PreviousValue::ExistingMustMatch(_) | PreviousValue::Any, None)
-                    | (PreviousValue::MustExist | PreviousValue::Any, Some(_)) => {}
-                    (PreviousValue::MustExist | PreviousValue::MustExistAndMatch(_), None) => {
-                        return Err(Error::DeleteReferenceMustExist {
-                            full_name: change.name(),
-                        })
-                    }
-                    (
-                        PreviousValue::MustExistAndMatch(previous) | PreviousValue::ExistingMustMatch(previous),
-                        Some(existing),
-                    ) => {
-                        let actual = existing.target.clone();
-                        if *previous != actual {
-                            let expected = previous.clone();
-                            return Err(Error::ReferenceOutOfDate {
-                                full_name: change.name(),
-                                expected,
-                                actual,
-                            });
-                        }
-                    }
-                }
-
-                // Keep the previous value for the caller and ourselves. Maybe they want to keep a log of sorts.
-                if let Some(existing) = existing_ref {
-                    *expected = PreviousValue::MustExistAndMatch(existing.target);
-                }
+                verify_deletion(&change.update.name, expected, existing_ref)?;
 
                 lock
             }
@@ -112,71 +91,12 @@ impl Transaction<'_, '_> {
                         lock_fail_mode,
                         Some(base.clone().into_owned()),
                     )
-                    .map_err(|err| Error::LockAcquire {
-                        source: err,
-                        full_name: "borrowcheck won't allow change.name() and this will be corrected by caller".into(),
-                    })
+                    .map_err(lock_error)
                 };
                 let mut lock = (!has_global_lock).then(obtain_lock).transpose()?;
 
-                match (&expected, &existing_ref) {
-                    (PreviousValue::Any, _)
-                    | (PreviousValue::MustExist, Some(_))
-                    | (PreviousValue::MustNotExist | PreviousValue::ExistingMustMatch(_), None) => {}
-                    (PreviousValue::MustExist, None) => {
-                        let expected = Target::Object(store.object_hash.null());
-                        let full_name = change.name();
-                        return Err(Error::MustExist { full_name, expected });
-                    }
-                    (PreviousValue::MustNotExist, Some(existing)) => {
-                        if existing.target != *new {
-                            let new = new.clone();
-                            return Err(Error::MustNotExist {
-                                full_name: change.name(),
-                                actual: existing.target.clone(),
-                                new,
-                            });
-                        }
-                    }
-                    (
-                        PreviousValue::MustExistAndMatch(previous) | PreviousValue::ExistingMustMatch(previous),
-                        Some(existing),
-                    ) => {
-                        if *previous != existing.target {
-                            let actual = existing.target.clone();
-                            let expected = previous.to_owned();
-                            let full_name = change.name();
-                            return Err(Error::ReferenceOutOfDate {
-                                full_name,
-                                actual,
-                                expected,
-                            });
-                        }
-                    }
-
-                    (PreviousValue::MustExistAndMatch(previous), None) => {
-                        let expected = previous.to_owned();
-                        let full_name = change.name();
-                        return Err(Error::MustExist { full_name, expected });
-                    }
-                };
-
-                fn new_would_change_existing(new: &Target, existing: &Target) -> (bool, bool) {
-                    match (new, existing) {
-                        (Target::Object(new), Target::Object(old)) => (old != new, false),
-                        (Target::Symbolic(new), Target::Symbolic(old)) => (old != new, true),
-                        (Target::Object(_), _) => (true, false),
-                        (Target::Symbolic(_), _) => (true, true),
-                    }
-                }
-
-                let (is_effective, is_symbolic) = if let Some(existing) = existing_ref {
-                    let (effective, is_symbolic) = new_would_change_existing(new, &existing.target);
-                    *expected = PreviousValue::MustExistAndMatch(existing.target);
-                    (effective, is_symbolic)
-                } else {
-                    (true, matches!(new, Target::Symbolic(_)))
-                };
+                let (is_effective, is_symbolic) =
+                    verify_update(store, &change.update.name, expected, new, existing_ref)?;
 
                 if (is_effective && !direct_to_packed_refs) || is_symbolic {
                     let mut lock = lock.take().map_or_else(obtain_lock, Ok)?;
@@ -185,6 +105,7 @@ impl Transaction<'_, '_> {
                         Target::Object(oid) => write!(file, "{oid}"),
                         Target::Symbolic(name) => writeln!(file, "ref: {}", name.0),
                     })?;
+                    lock.with_mut(|file| store.fsync.sync(file))?;
                     Some(lock.close()?)
                 } else {
                     None
@@ -249,6 +170,10 @@ impl Transaction<'_, '_> {
             )
             .map_err(Error::PreprocessingFailed)?;
 
+        if self.store.format() == Format::Reftable {
+            return self.prepare_reftable(updates, ref_files_lock_fail_mode);
+        }
+
         let mut maybe_updates_for_packed_refs = match self.packed_refs {
             PackedRefs::DeletionsAndNonSymbolicUpdates(_)
             | PackedRefs::DeletionsAndNonSymbolicUpdatesRemoveLooseSourceReference(_) => Some(0_usize),
@@ -332,6 +257,7 @@ impl Transaction<'_, '_> {
                                     packed_refs_lock_fail_mode,
                                     self.store.precompose_unicode,
                                     self.store.namespace.clone(),
+                                    self.store.fsync,
                                 )
                                 .map_err(Error::PackedTransactionAcquire)
                             })
@@ -350,6 +276,14 @@ impl Transaction<'_, '_> {
             }
         }
 
+        match self.packed_transaction.as_ref().and_then(packed::Transaction::buffer) {
+            Some(packed) => check_name_conflicts(self.store, Some(packed), &updates)?,
+            None => {
+                let packed = self.store.assure_packed_refs_uptodate()?;
+                check_name_conflicts(self.store, packed.as_deref().map(|packed| &**packed), &updates)?;
+            }
+        }
+
         for cid in 0..updates.len() {
             let change = &mut updates[cid];
             if let Err(err) = Self::lock_ref_and_apply_change(
@@ -363,43 +297,9 @@ impl Transaction<'_, '_> {
                     PackedRefs::DeletionsAndNonSymbolicUpdatesRemoveLooseSourceReference(_)
                 ),
             ) {
-                let err = match err {
-                    Error::LockAcquire {
-                        source,
-                        full_name: _bogus,
-                    } => Error::LockAcquire {
-                        source,
-                        full_name: {
-                            let mut cursor = change.parent_index;
-                            let mut ref_name = change.name();
-                            while let Some(parent_idx) = cursor {
-                                let parent = &updates[parent_idx];
-                                if parent.parent_index.is_none() {
-                                    ref_name = parent.name();
-                                } else {
-                                    cursor = parent.parent_index;
-                                }
-                            }
-                            ref_name
-                        },
-                    },
-                    other => other,
-                };
-                return Err(err);
+                return Err(with_root_name(err, &updates, cid));
             };
-
-            // traverse parent chain from leaf/peeled ref and set the leaf previous oid accordingly
-            // to help with their reflog entries
-            if let (Some(crate::TargetRef::Object(oid)), Some(parent_idx)) =
-                (change.update.change.previous_value(), change.parent_index)
-            {
-                let oid = oid.to_owned();
-                let mut parent_idx_cursor = Some(parent_idx);
-                while let Some(parent) = parent_idx_cursor.take().map(|idx| &mut updates[idx]) {
-                    parent_idx_cursor = parent.parent_index;
-                    parent.leaf_referent_previous_oid = Some(oid);
-                }
-            }
+            set_leaf_referent_previous_oid(&mut updates, cid);
         }
         self.updates = Some(updates);
         Ok(self)
@@ -420,6 +320,295 @@ impl Transaction<'_, '_> {
     }
 }
 
+/// Check that the `existing` value of the reference `full_name` is what's `expected` before deleting it, and keep its
+/// previous value in `expected` for the caller and ourselves. Maybe they want to keep a log of sorts.
+pub(super) fn verify_deletion(
+    full_name: &FullName,
+    expected: &mut PreviousValue,
+    existing: Option<Reference>,
+) -> Result<(), Error> {
+    match (&*expected, &existing) {
+        (PreviousValue::MustNotExist, _) => {
+            panic!("BUG: MustNotExist constraint makes no sense if references are to be deleted")
+        }
+        (PreviousValue::ExistingMustMatch(_) | PreviousValue::Any, None)
+        | (PreviousValue::MustExist | PreviousValue::Any, Some(_)) => {}
+        (PreviousValue::MustExist | PreviousValue::MustExistAndMatch(_), None) => {
+            return Err(Error::DeleteReferenceMustExist {
+                full_name: full_name.0.clone(),
+            })
+        }
+        (
+            PreviousValue::MustExistAndMatch(previous) | PreviousValue::ExistingMustMatch(previous),
+            Some(existing),
+        ) => {
+            let actual = existing.target.clone();
+            if *previous != actual {
+                let expected = previous.clone();
+                return Err(Error::ReferenceOutOfDate {
+                    full_name: full_name.0.clone(),
+                    expected,
+                    actual,
+                });
+            }
+        }
+    }
+
+    if let Some(existing) = existing {
+        *expected = PreviousValue::MustExistAndMatch(existing.target);
+    }
+    Ok(())
+}
+
+/// Check that the `existing` value of the reference `full_name` is what's `expected` before changing it to `new`, and keep its
+/// previous value in `expected`.
+/// Return `(is_effective, is_symbolic)` to indicate if the value would change and if the old or new value is symbolic.
+pub(super) fn verify_update(
+    store: &file::Store,
+    full_name: &FullName,
+    expected: &mut PreviousValue,
+    new: &Target,
+    existing: Option<Reference>,
+) -> Result<(bool, bool), Error> {
+    match (&*expected, &existing) {
+        (PreviousValue::Any, _)
+        | (PreviousValue::MustExist, Some(_))
+        | (PreviousValue::MustNotExist | PreviousValue::ExistingMustMatch(_), None) => {}
+        (PreviousValue::MustExist, None) => {
+            let expected = Target::Object(store.object_hash.null());
+            let full_name = full_name.0.clone();
+            return Err(Error::MustExist { full_name, expected });
+        }
+        (PreviousValue::MustNotExist, Some(existing)) => {
+            if existing.target != *new {
+                let new = new.clone();
+                return Err(Error::MustNotExist {
+                    full_name: full_name.0.clone(),
+                    actual: existing.target.clone(),
+                    new,
+                });
+            }
+        }
+        (
+            PreviousValue::MustExistAndMatch(previous) | PreviousValue::ExistingMustMatch(previous),
+            Some(existing),
+        ) => {
+            if *previous != existing.target {
+                let actual = existing.target.clone();
+                let expected = previous.to_owned();
+                let full_name = full_name.0.clone();
+                return Err(Error::ReferenceOutOfDate {
+                    full_name,
+                    actual,
+                    expected,
+                });
+            }
+        }
+
+        (PreviousValue::MustExistAndMatch(previous), None) => {
+            let expected = previous.to_owned();
+            let full_name = full_name.0.clone();
+            return Err(Error::MustExist { full_name, expected });
+        }
+    };
+
+    fn new_would_change_existing(new: &Target, existing: &Target) -> (bool, bool) {
+        match (new, existing) {
+            (Target::Object(new), Target::Object(old)) => (old != new, false),
+            (Target::Symbolic(new), Target::Symbolic(old)) => (old != new, true),
+            (Target::Object(_), _) => (true, false),
+            (Target::Symbolic(_), _) => (true, true),
+        }
+    }
+
+    Ok(if let Some(existing) = existing {
+        let (effective, is_symbolic) = new_would_change_existing(new, &existing.target);
+        *expected = PreviousValue::MustExistAndMatch(existing.target);
+        (effective, is_symbolic)
+    } else {
+        (true, matches!(new, Target::Symbolic(_)))
+    })
+}
+
+/// Return the name of the edit at `idx` or of the edit it was ultimately split off from.
+fn root_name(updates: &[Edit], idx: usize) -> BString {
+    let mut edit = &updates[idx];
+    while let Some(parent_idx) = edit.parent_index {
+        edit = &updates[parent_idx];
+    }
+    edit.name()
+}
+
+/// Traverse the parent chain from the leaf/peeled ref at `idx` and set the leaf previous oid accordingly
+/// to help with their reflog entries.
+pub(super) fn set_leaf_referent_previous_oid(updates: &mut [Edit], idx: usize) {
+    let change = &updates[idx];
+    if let (Some(crate::TargetRef::Object(oid)), Some(parent_idx)) =
+        (change.update.change.previous_value(), change.parent_index)
+    {
+        let oid = oid.to_owned();
+        let mut parent_idx_cursor = Some(parent_idx);
+        while let Some(parent) = parent_idx_cursor.take().map(|idx| &mut updates[idx]) {
+            parent_idx_cursor = parent.parent_index;
+            parent.leaf_referent_previous_oid = Some(oid);
+        }
+    }
+}
+
+/// Correct the name in lock errors of the edit at `idx`, to be the name of the edit it was ultimately split off from.
+pub(super) fn with_root_name(err: Error, updates: &[Edit], idx: usize) -> Error {
+    match err {
+        Error::LockAcquire {
+            source,
+            full_name: _bogus,
+        } => Error::LockAcquire {
+            source,
+            full_name: root_name(updates, idx),
+        },
+        Error::LockHeld {
+            source,
+            lock_path,
+            full_name: _bogus,
+        } => Error::LockHeld {
+            source,
+            lock_path,
+            full_name: root_name(updates, idx),
+        },
+        other => other,
+    }
+}
+
+/// Turn `err` into our error, with a name that is to be corrected by the caller.
+pub(super) fn lock_error(err: gix_lock::acquire::Error) -> Error {
+    let full_name = "borrowcheck won't allow change.name() and this will be corrected by caller".into();
+    match err {
+        gix_lock::acquire::Error::PermanentlyLocked { ref resource_path, .. } => {
+            let mut lock_path = resource_path.clone().into_os_string();
+            lock_path.push(".lock");
+            Error::LockHeld {
+                lock_path: lock_path.into(),
+                source: err,
+                full_name,
+            }
+        }
+        source => Error::LockAcquire { source, full_name },
+    }
+}
+
+/// Fail if one of the references to create or update in `updates` would have to be stored in a directory that is
+/// another reference, or in the place of a directory that contains other references, either in the loose reference
+/// directory, in the `packed` buffer, or within `updates` itself.
+/// References that are deleted in the same transaction don't cause conflicts, unless they are loose as their files
+/// would still be in the way.
+fn check_name_conflicts(store: &file::Store, packed: Option<&packed::Buffer>, updates: &[Edit]) -> Result<(), Error> {
+    let is_edited = |name: &BStr, deleted: bool| {
+        updates.iter().any(|edit| {
+            let mode = match edit.update.change {
+                Change::Update {
+                    log: LogChange { mode, .. },
+                    ..
+                } => mode,
+                Change::Delete { log, .. } => log,
+            };
+            mode != RefLog::Only
+                && matches!(edit.update.change, Change::Delete { .. }) == deleted
+                && edit.update.name.as_bstr() == name
+        })
+    };
+    for edit in updates {
+        match edit.update.change {
+            Change::Update {
+                log: LogChange { mode, .. },
+                ..
+            } if mode != RefLog::Only => {}
+            _ => continue,
+        }
+        let name = edit.update.name.as_bstr();
+        let conflict = |conflicting_name: &BStr| Error::NameConflict {
+            full_name: name.to_owned(),
+            conflicting_name: conflicting_name.to_owned(),
+        };
+
+        for pos in name.find_iter(b"/").skip(1) {
+            let prefix = name[..pos].as_bstr();
+            let prefix_name = FullNameRef::new_unchecked(prefix);
+            let is_loose = store.reference_path(prefix_name).is_file();
+            if is_edited(prefix, true) && !is_loose {
+                continue;
+            }
+            if is_edited(prefix, false)
+                || is_loose
+                || packed
+                    .map(|packed| packed.try_find(prefix_name))
+                    .transpose()?
+                    .flatten()
+                    .is_some()
+            {
+                return Err(conflict(prefix));
+            }
+        }
+
+        let mut dirs = vec![(store.reference_path(edit.update.name.as_ref()), name.to_owned())];
+        while let Some((dir, dir_name)) = dirs.pop() {
+            let entries = match std::fs::read_dir(&dir) {
+                Ok(entries) => entries,
+                Err(_) => continue,
+            };
+            for entry in entries {
+                let entry = entry?;
+                let mut entry_name = dir_name.clone();
+                entry_name.push(b'/');
+                entry_name.extend_from_slice(&gix_path::os_str_into_bstr(&entry.file_name()).unwrap_or_default());
+                if entry.file_type()?.is_dir() {
+                    dirs.push((entry.path(), entry_name));
+                } else if !entry_name.ends_with(b".lock") {
+                    return Err(conflict(entry_name.as_ref()));
+                }
+            }
+        }
+
+        if let Some(packed) = packed {
+            let mut prefix = name.to_owned();
+            prefix.push(b'/');
+            for packed_ref in packed.iter_prefixed(prefix)? {
+                let packed_ref = packed_ref?;
+                if !is_edited(packed_ref.name.as_bstr(), true) {
+                    return Err(conflict(packed_ref.name.as_bstr()));
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Return the name of the reference whose loose file would be used for `name` as it matches `name` only when
+/// ignoring case, assuming the filesystem ignores case, or `None` if there is no such reference.
+fn case_conflict(store: &file::Store, name: &FullNameRef) -> std::io::Result<Option<BString>> {
+    let path = store.reference_path(name);
+    let (Some(dir), Some(file_name)) = (path.parent(), path.file_name()) else {
+        return Ok(None);
+    };
+    if !path.is_file() {
+        return Ok(None);
+    }
+    let mut conflicting = None;
+    for entry in std::fs::read_dir(dir)? {
+        let entry_name = entry?.file_name();
+        if entry_name.as_os_str() == file_name {
+            return Ok(None);
+        }
+        if entry_name.eq_ignore_ascii_case(file_name) {
+            conflicting = Some(entry_name);
+        }
+    }
+    Ok(conflicting.map(|entry_name| {
+        let name = name.as_bstr();
+        let mut out: BString = name[..name.rfind_byte(b'/').map_or(0, |pos| pos + 1)].into();
+        out.extend_from_slice(&gix_path::os_string_into_bstring(entry_name).unwrap_or_default());
+        out
+    }))
+}
+
 fn possibly_adjust_name_for_prefixes(name: &FullNameRef) -> Option<FullName> {
     match name.category_and_short_name() {
         Some((c, sn)) => {
@@ -466,6 +655,24 @@ mod error {
             source: gix_lock::acquire::Error,
             full_name: BString,
         },
+        #[error("The lock for reference {full_name:?} is held by another process, as lock file at \"{}\" exists", lock_path.display())]
+        LockHeld {
+            source: gix_lock::acquire::Error,
+            full_name: BString,
+            lock_path: std::path::PathBuf,
+        },
+        #[error("Reference {full_name:?} cannot be written as reference {conflicting_name:?} exists and one is a directory of the other")]
+        NameConflict {
+            full_name: BString,
+            conflicting_name: BString,
+        },
+        #[error("Reference {full_name:?} cannot be written as the filesystem ignores case, and reference {conflicting_name:?} would be overwritten")]
+        CaseConflict {
+            full_name: BString,
+            conflicting_name: BString,
+        },
+        #[error("The packed refs could not be iterated to check for name conflicts")]
+        PackedIter(#[from] packed::iter::Error),
         #[error("An IO error occurred while applying an edit")]
         Io(#[from] std::io::Error),
         #[error("The reference {full_name:?} for deletion did not exist or could not be parsed")]
@@ -486,6 +693,8 @@ mod error {
         },
         #[error("Could not read reference")]
         ReferenceDecode(#[from] file::loose::reference::decode::Error),
+        #[error("The stack of reference tables could not be read")]
+        ReftableOpen(#[from] crate::reftable::open::Error),
     }
 }
 
@@ -0,0 +1,299 @@
+use std::path::PathBuf;
+
+use gix_object::bstr::{BStr, ByteSlice};
+
+use crate::{
+    log,
+    reftable::{LogRecord, RefRecord, Stack, Table, Value},
+    store::WriteReflog,
+    store_impl::file::{
+        log::create_or_update,
+        transaction::{
+            commit::{self, reflog_entry},
+            prepare::{self, lock_error, set_leaf_referent_previous_oid, verify_deletion, verify_update, with_root_name},
+            Edit,
+        },
+        Transaction,
+    },
+    transaction::{Change, LogChange, RefEdit, RefLog},
+    FullName, FullNameRef, Target,
+};
+
+/// A stack of reference tables as read while holding the lock on its `tables.list` file.
+pub(in crate::store_impl::file) struct LockedStack {
+    stack: Stack,
+    lock: gix_lock::File,
+}
+
+impl LockedStack {
+    fn acquire(
+        dir: PathBuf,
+        object_hash: gix_hash::Kind,
+        lock_fail_mode: gix_lock::acquire::Fail,
+    ) -> Result<Self, prepare::Error> {
+        // The stack of a linked work tree may not exist yet.
+        std::fs::create_dir_all(&dir)?;
+        let lock = gix_lock::File::acquire_to_update_resource(dir.join("tables.list"), lock_fail_mode, None)
+            .map_err(lock_error)?;
+        let stack = Stack::at(dir.clone(), object_hash)?.unwrap_or_else(|| Stack::empty(dir, object_hash));
+        Ok(LockedStack { stack, lock })
+    }
+}
+
+impl Transaction<'_, '_> {
+    /// Lock all stacks the `updates` are stored in and check the updates against them, while references which are stored in
+    /// files even if all others are stored in tables are locked as usual.
+    pub(super) fn prepare_reftable(
+        mut self,
+        mut updates: Vec<Edit>,
+        lock_fail_mode: gix_lock::acquire::Fail,
+    ) -> Result<Self, prepare::Error> {
+        let store = self.store;
+        let mut locations = Vec::with_capacity(updates.len());
+        for idx in 0..updates.len() {
+            let Some((dir, name)) = store.reftable_dir_and_name(updates[idx].update.name.as_ref()) else {
+                if let Err(err) = Self::lock_ref_and_apply_change(store, lock_fail_mode, None, &mut updates[idx], false, false)
+                {
+                    return Err(with_root_name(err, &updates, idx));
+                }
+                locations.push(None);
+                set_leaf_referent_previous_oid(&mut updates, idx);
+                continue;
+            };
+            let stack_idx = match self.reftables.iter().position(|locked| locked.stack.dir() == dir) {
+                Some(stack_idx) => stack_idx,
+                None => {
+                    let locked = LockedStack::acquire(dir, store.object_hash, lock_fail_mode)
+                        .map_err(|err| with_root_name(err, &updates, idx))?;
+                    self.reftables.push(locked);
+                    self.reftables.len() - 1
+                }
+            };
+
+            let existing = self.reftables[stack_idx].stack.try_find(name.as_ref());
+            let change = &mut updates[idx];
+            match &mut change.update.change {
+                Change::Delete { expected, .. } => verify_deletion(&change.update.name, expected, existing)?,
+                Change::Update { expected, new, .. } => {
+                    verify_update(store, &change.update.name, expected, new, existing)?;
+                }
+            }
+            locations.push(Some((stack_idx, name)));
+            set_leaf_referent_previous_oid(&mut updates, idx);
+        }
+        check_name_conflicts(&self.reftables, &updates, &locations)?;
+        self.updates = Some(updates);
+        Ok(self)
+    }
+
+    /// Write one table with all changes to each of the locked stacks, and apply changes to references stored in files.
+    pub(super) fn commit_reftable(
+        self,
+        committer: Option<gix_actor::SignatureRef<'_>>,
+    ) -> Result<Vec<RefEdit>, commit::Error> {
+        let store = self.store;
+        let mut updates = self.updates.expect("BUG: must call prepare before commit");
+        let mut tables: Vec<_> = self
+            .reftables
+            .iter()
+            .map(|locked| {
+                let update_index = locked.stack.max_update_index() + 1;
+                Table {
+                    min_update_index: update_index,
+                    max_update_index: update_index,
+                    ..Default::default()
+                }
+            })
+            .collect();
+
+        for change in &mut updates {
+            assert!(!change.update.deref, "Deref mode is turned into splits and turned off");
+            let Some((dir, name)) = store.reftable_dir_and_name(change.update.name.as_ref()) else {
+                commit_in_file(store, change)?;
+                continue;
+            };
+            let stack_idx = self
+                .reftables
+                .iter()
+                .position(|locked| locked.stack.dir() == dir)
+                .expect("the stacks of all edits are locked when preparing");
+            let stack = &self.reftables[stack_idx].stack;
+            let table = &mut tables[stack_idx];
+            let update_index = table.min_update_index;
+            match &change.update.change {
+                Change::Update { log, new, .. } => {
+                    if log.mode == RefLog::AndReference {
+                        table.refs.push(RefRecord {
+                            name: name.as_bstr().to_owned(),
+                            update_index,
+                            value: match new {
+                                Target::Object(id) => Value::Object(*id),
+                                Target::Symbolic(target) => Value::Symbolic(target.as_bstr().to_owned()),
+                            },
+                        });
+                    }
+                    if let Some((previous, new_oid)) = reflog_entry(change) {
+                        let append = || stack.log_exists(name.as_ref());
+                        let write_reflog = match self.write_reflog {
+                            WriteReflog::Always => true,
+                            WriteReflog::Normal => {
+                                log.force_create_reflog
+                                    || store.should_autocreate_reflog(&gix_path::from_bstr(name.as_bstr()))
+                                    || append()
+                            }
+                            WriteReflog::AppendToExisting => log.force_create_reflog || append(),
+                            WriteReflog::Disable => false,
+                        };
+                        if write_reflog {
+                            let committer = committer.ok_or(create_or_update::Error::MissingCommitter)?;
+                            if log.message.contains(&b'\n') {
+                                return Err(create_or_update::Error::MessageWithNewlines.into());
+                            }
+                            table.logs.push(LogRecord {
+                                name: name.as_bstr().to_owned(),
+                                update_index,
+                                line: Some(log::Line {
+                                    previous_oid: previous.unwrap_or_else(|| new_oid.kind().null()),
+                                    new_oid: new_oid.to_owned(),
+                                    signature: committer.to_owned(),
+                                    message: log.message.clone(),
+                                }),
+                            });
+                        }
+                    }
+                }
+                Change::Delete { log: mode, .. } => {
+                    if *mode == RefLog::AndReference {
+                        table.refs.push(RefRecord {
+                            name: name.as_bstr().to_owned(),
+                            update_index,
+                            value: Value::Deletion,
+                        });
+                    }
+                    // Just like with loose references, the log is deleted along with the reference.
+                    table.logs.extend(stack.log(name.as_ref()).into_iter().flatten().map(
+                        |(update_index, _line)| LogRecord {
+                            name: name.as_bstr().to_owned(),
+                            update_index,
+                            line: None,
+                        },
+                    ));
+                }
+            }
+        }
+
+        for (locked, table) in self.reftables.into_iter().zip(tables) {
+            if table.refs.is_empty() && table.logs.is_empty() {
+                // Dropping the lock leaves the stack untouched.
+                continue;
+            }
+            let LockedStack { stack, lock } = locked;
+            stack.add_table(&table, lock, store.fsync)?;
+            // Always refresh ourselves right away to avoid races. Errors are ignored as the stack will be read again
+            // when it's needed next.
+            store.force_refresh_reftable(stack.dir()).ok();
+        }
+        Ok(updates.into_iter().map(|edit| edit.update).collect())
+    }
+}
+
+/// Move the locked reference file of `change` into place or delete it, without writing a reflog, as only special references
+/// like `FETCH_HEAD` are stored in files if references are stored in tables.
+fn commit_in_file(store: &crate::file::Store, change: &mut Edit) -> Result<(), commit::Error> {
+    let lock = change.lock.take();
+    match &change.update.change {
+        Change::Update {
+            log: LogChange { mode, .. },
+            ..
+        } => {
+            if *mode == RefLog::AndReference {
+                if let Some(Err(err)) = lock.map(gix_lock::Marker::commit) {
+                    return Err(commit::Error::LockCommit {
+                        source: err.error,
+                        full_name: change.name(),
+                    });
+                }
+            }
+        }
+        Change::Delete { log: mode, .. } => {
+            if *mode == RefLog::AndReference {
+                if let Err(err) = std::fs::remove_file(store.reference_path(change.update.name.as_ref())) {
+                    if err.kind() != std::io::ErrorKind::NotFound {
+                        return Err(commit::Error::DeleteReference {
+                            err,
+                            full_name: change.name(),
+                        });
+                    }
+                }
+            }
+            drop(lock);
+        }
+    }
+    Ok(())
+}
+
+/// Fail if one of the references to create or update in `updates` would be the directory of another reference in its stack,
+/// or would have another reference as directory, as these couldn't be stored as loose references anymore.
+/// References that are deleted in the same transaction don't cause conflicts.
+///
+/// `locations` are the indices into `stacks` along with the names in the stack for each of the `updates`, if stored in tables.
+fn check_name_conflicts(
+    stacks: &[LockedStack],
+    updates: &[Edit],
+    locations: &[Option<(usize, FullName)>],
+) -> Result<(), prepare::Error> {
+    let is_edited = |stack_idx: usize, name: &BStr, deleted: bool| {
+        updates.iter().zip(locations).any(|(edit, location)| {
+            let mode = match edit.update.change {
+                Change::Update {
+                    log: LogChange { mode, .. },
+                    ..
+                } => mode,
+                Change::Delete { log, .. } => log,
+            };
+            mode != RefLog::Only
+                && matches!(edit.update.change, Change::Delete { .. }) == deleted
+                && location
+                    .as_ref()
+                    .map_or(false, |(idx, edit_name)| *idx == stack_idx && edit_name.as_bstr() == name)
+        })
+    };
+    for (edit, location) in updates.iter().zip(locations) {
+        let Some((stack_idx, name)) = location else {
+            continue;
+        };
+        match edit.update.change {
+            Change::Update {
+                log: LogChange { mode, .. },
+                ..
+            } if mode != RefLog::Only => {}
+            _ => continue,
+        }
+        let stack = &stacks[*stack_idx].stack;
+        let name = name.as_bstr();
+        let conflict = |conflicting_name: &BStr| prepare::Error::NameConflict {
+            full_name: edit.name(),
+            conflicting_name: conflicting_name.to_owned(),
+        };
+
+        for pos in name.find_iter(b"/").skip(1) {
+            let prefix = name[..pos].as_bstr();
+            if is_edited(*stack_idx, prefix, true) {
+                continue;
+            }
+            if is_edited(*stack_idx, prefix, false) || stack.try_find(FullNameRef::new_unchecked(prefix)).is_some() {
+                return Err(conflict(prefix));
+            }
+        }
+
+        let mut prefix = name.to_owned();
+        prefix.push(b'/');
+        let existing = stack
+            .iter_prefixed(prefix.as_ref())
+            .find(|existing| !is_edited(*stack_idx, existing.name.as_bstr(), true));
+        if let Some(existing) = existing {
+            return Err(conflict(existing.name.as_bstr()));
+        }
+    }
+    Ok(())
+}
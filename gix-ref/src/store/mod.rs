@@ -3,3 +3,5 @@ pub mod file;
 
 ///
 pub mod packed;
+
+pub mod reftable;
@@ -41,6 +41,8 @@ pub(crate) struct Transaction {
     precompose_unicode: bool,
     /// The namespace to use when preparing or writing refs
     namespace: Option<Namespace>,
+    /// How to flush the `packed-refs` file to disk before moving it into place.
+    fsync: gix_features::fs::Fsync,
 }
 
 /// A reference as parsed from the `packed-refs` file
@@ -0,0 +1,355 @@
+use std::borrow::Cow;
+
+use gix_actor::date::{time::Sign, Time};
+use gix_hash::ObjectId;
+
+use crate::{
+    log,
+    reftable::{
+        get_varint, header_size, hhmm_to_offset, LogRecord, RefRecord, Table, Value, BLOCK_TYPE_LOG, BLOCK_TYPE_REF,
+        FOOTER_SIZE_WITHOUT_HEADER, HEADER_SIZE_V1, MAGIC,
+    },
+};
+
+mod error {
+    /// The error returned by [`Table::from_bytes()`][crate::reftable::Table::from_bytes()].
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("The table doesn't start with a valid header")]
+        Header,
+        #[error("Table version {version} is not supported")]
+        UnsupportedVersion { version: u8 },
+        #[error("The hash with id {id:?} is not supported")]
+        UnsupportedHash { id: [u8; 4] },
+        #[error("The table uses object ids of kind {actual}, but {expected} was expected")]
+        ObjectHash {
+            expected: gix_hash::Kind,
+            actual: gix_hash::Kind,
+        },
+        #[error("The table is truncated")]
+        Truncated,
+        #[error("The footer doesn't match the header or its checksum is incorrect")]
+        Footer,
+        #[error("A block at offset {offset} is malformed")]
+        Block { offset: usize },
+        #[error("The log block at offset {offset} could not be decompressed")]
+        Inflate {
+            offset: usize,
+            source: gix_features::zlib::inflate::Error,
+        },
+        #[error("A record in the block at offset {offset} is malformed")]
+        Record { offset: usize },
+        #[error("A record in the block at offset {offset} has the unknown value type {value_type}")]
+        ValueType { offset: usize, value_type: u8 },
+    }
+}
+pub use error::Error;
+
+impl Table {
+    /// Decode a table from `data`, the complete contents of a table file, which is expected to contain object ids
+    /// of kind `object_hash`.
+    pub fn from_bytes(data: &[u8], object_hash: gix_hash::Kind) -> Result<Self, Error> {
+        if data.len() < HEADER_SIZE_V1 || &data[..4] != MAGIC {
+            return Err(Error::Header);
+        }
+        let version = data[4];
+        if !matches!(version, 1 | 2) {
+            return Err(Error::UnsupportedVersion { version });
+        }
+        let header_size = header_size(version);
+        let footer_size = header_size + FOOTER_SIZE_WITHOUT_HEADER;
+        if data.len() < header_size + footer_size {
+            return Err(Error::Truncated);
+        }
+        let kind = if version == 1 {
+            gix_hash::Kind::Sha1
+        } else {
+            match &data[24..28] {
+                b"sha1" => gix_hash::Kind::Sha1,
+                id => {
+                    return Err(Error::UnsupportedHash {
+                        id: id.try_into().expect("four bytes"),
+                    })
+                }
+            }
+        };
+        if kind != object_hash {
+            return Err(Error::ObjectHash {
+                expected: object_hash,
+                actual: kind,
+            });
+        }
+
+        let end = data.len() - footer_size;
+        let footer = &data[end..];
+        let checksum_pos = footer_size - 4;
+        if footer[..header_size] != data[..header_size]
+            || gix_features::hash::crc32(&footer[..checksum_pos]) != be32(&footer[checksum_pos..])
+        {
+            return Err(Error::Footer);
+        }
+        let log_position = usize::try_from(be64(&footer[header_size + 24..])).map_err(|_| Error::Footer)?;
+
+        let mut table = Table {
+            min_update_index: be64(&data[8..]),
+            max_update_index: be64(&data[16..]),
+            refs: Vec::new(),
+            logs: Vec::new(),
+        };
+        let block_size = be24(&data[5..]);
+        let hash_len = object_hash.len_in_bytes();
+        let first_block_type = (end > header_size).then(|| data[header_size]);
+
+        if first_block_type == Some(BLOCK_TYPE_REF) {
+            for block in Blocks::new(data, 0, header_size, block_size, end, BLOCK_TYPE_REF) {
+                let (offset, header_offset, block) = block?;
+                for_each_record(&block, header_offset, offset, |name, value_type, input| {
+                    let update_index = get_varint(input)
+                        .and_then(|delta| delta.checked_add(table.min_update_index))
+                        .ok_or(Error::Record { offset })?;
+                    let value = match value_type {
+                        0 => Value::Deletion,
+                        1 => Value::Object(take_id(input, hash_len, offset)?),
+                        2 => Value::Peeled {
+                            target: take_id(input, hash_len, offset)?,
+                            peeled: take_id(input, hash_len, offset)?,
+                        },
+                        3 => Value::Symbolic(take_string(input, offset)?.into()),
+                        _ => return Err(Error::ValueType { offset, value_type }),
+                    };
+                    table.refs.push(RefRecord {
+                        name: name.into(),
+                        update_index,
+                        value,
+                    });
+                    Ok(())
+                })?;
+            }
+        }
+
+        let log_start = if first_block_type == Some(BLOCK_TYPE_LOG) {
+            Some(0)
+        } else {
+            (log_position != 0).then_some(log_position)
+        };
+        if let Some(log_start) = log_start {
+            for block in Blocks::new(data, log_start, header_size, block_size, end, BLOCK_TYPE_LOG) {
+                let (offset, header_offset, block) = block?;
+                for_each_record(&block, header_offset, offset, |key, value_type, input| {
+                    let (name, update_index) = match key.len().checked_sub(9) {
+                        Some(name_len) if key[name_len] == 0 => (&key[..name_len], !be64(&key[name_len + 1..])),
+                        _ => return Err(Error::Record { offset }),
+                    };
+                    let line = match value_type {
+                        0 => None,
+                        1 => Some(decode_log_line(input, hash_len, offset)?),
+                        _ => return Err(Error::ValueType { offset, value_type }),
+                    };
+                    table.logs.push(LogRecord {
+                        name: name.into(),
+                        update_index,
+                        line,
+                    });
+                    Ok(())
+                })?;
+            }
+        }
+        Ok(table)
+    }
+}
+
+/// An iterator over consecutive blocks of the same type, yielding their offset, the offset of their block header
+/// and their decompressed content, which includes the file header for the first block.
+struct Blocks<'a> {
+    data: &'a [u8],
+    offset: usize,
+    file_header_size: usize,
+    block_size: usize,
+    end: usize,
+    block_type: u8,
+}
+
+impl<'a> Blocks<'a> {
+    fn new(
+        data: &'a [u8],
+        offset: usize,
+        file_header_size: usize,
+        block_size: usize,
+        end: usize,
+        block_type: u8,
+    ) -> Self {
+        Blocks {
+            data,
+            offset,
+            file_header_size,
+            block_size,
+            end,
+            block_type,
+        }
+    }
+
+    fn read_block(&self, header_offset: usize) -> Result<(Cow<'a, [u8]>, usize), Error> {
+        let (data, offset, end) = (self.data, self.offset, self.end);
+        let block_err = Error::Block { offset };
+        let records_start = offset + header_offset + 4;
+        if records_start > end {
+            return Err(Error::Truncated);
+        }
+        let block_len = be24(&data[offset + header_offset + 1..]);
+        if block_len < header_offset + 4 + 2 {
+            return Err(block_err);
+        }
+        if self.block_type == BLOCK_TYPE_LOG {
+            let mut block = Vec::with_capacity(block_len + 1);
+            block.extend_from_slice(&data[offset..records_start]);
+            // Leave space for one more byte so the end of the stream is seen once all expected bytes are produced.
+            block.resize(block_len + 1, 0);
+            let mut inflate = gix_features::zlib::Inflate::default();
+            let (status, consumed_in, consumed_out) = inflate
+                .once(&data[records_start..end], &mut block[header_offset + 4..])
+                .map_err(|source| Error::Inflate { offset, source })?;
+            if status != gix_features::zlib::Status::StreamEnd || consumed_out != block_len - header_offset - 4 {
+                return Err(block_err);
+            }
+            block.truncate(block_len);
+            Ok((block.into(), records_start + consumed_in))
+        } else {
+            let block = data.get(offset..offset + block_len).ok_or(Error::Truncated)?;
+            // Blocks are padded to the block size unless they are directly followed by the next block.
+            let is_unpadded = block_len < self.block_size && offset + block_len < end && data[offset + block_len] != 0;
+            let next_offset = offset
+                + if is_unpadded {
+                    block_len
+                } else {
+                    self.block_size.max(block_len)
+                };
+            Ok((block.into(), next_offset))
+        }
+    }
+}
+
+impl<'a> Iterator for Blocks<'a> {
+    type Item = Result<(usize, usize, Cow<'a, [u8]>), Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let header_offset = if self.offset == 0 { self.file_header_size } else { 0 };
+        if self
+            .data
+            .get(self.offset + header_offset)
+            .filter(|_| self.offset < self.end)
+            != Some(&self.block_type)
+        {
+            return None;
+        }
+        let offset = self.offset;
+        match self.read_block(header_offset) {
+            Ok((block, next_offset)) => {
+                self.offset = next_offset;
+                Some(Ok((offset, header_offset, block)))
+            }
+            Err(err) => {
+                self.offset = self.end;
+                Some(Err(err))
+            }
+        }
+    }
+}
+
+/// Call `decode_value` with the key and value type of each record in `block`, along with the input to decode the value from.
+fn for_each_record(
+    block: &[u8],
+    header_offset: usize,
+    offset: usize,
+    mut decode_value: impl FnMut(&[u8], u8, &mut &[u8]) -> Result<(), Error>,
+) -> Result<(), Error> {
+    let record_err = || Error::Record { offset };
+    let restart_count = be16(&block[block.len() - 2..]);
+    let records_end = (block.len() - 2)
+        .checked_sub(restart_count * 3)
+        .filter(|end| *end >= header_offset + 4)
+        .ok_or(Error::Block { offset })?;
+    let mut input = &block[header_offset + 4..records_end];
+    let mut key = Vec::new();
+    while !input.is_empty() {
+        let prefix_len = get_varint(&mut input).ok_or_else(record_err)? as usize;
+        let suffix_len_and_type = get_varint(&mut input).ok_or_else(record_err)?;
+        let suffix_len = (suffix_len_and_type >> 3) as usize;
+        if prefix_len > key.len() || suffix_len > input.len() {
+            return Err(record_err());
+        }
+        key.truncate(prefix_len);
+        key.extend_from_slice(&input[..suffix_len]);
+        input = &input[suffix_len..];
+        decode_value(&key, (suffix_len_and_type & 0x7) as u8, &mut input)?;
+    }
+    Ok(())
+}
+
+fn decode_log_line(input: &mut &[u8], hash_len: usize, offset: usize) -> Result<log::Line, Error> {
+    let previous_oid = take_id(input, hash_len, offset)?;
+    let new_oid = take_id(input, hash_len, offset)?;
+    let name = take_string(input, offset)?;
+    let email = take_string(input, offset)?;
+    let seconds = get_varint(input)
+        .and_then(|seconds| i64::try_from(seconds).ok())
+        .ok_or(Error::Record { offset })?;
+    let tz_offset_hhmm = take(input, 2, offset).map(|tz| i16::from_be_bytes([tz[0], tz[1]]))?;
+    let mut message = take_string(input, offset)?;
+    // Messages are stored with a trailing newline, which isn't part of it.
+    if let Some(without_newline) = message.strip_suffix(b"\n") {
+        message = without_newline;
+    }
+    Ok(log::Line {
+        previous_oid,
+        new_oid,
+        signature: gix_actor::Signature {
+            name: name.into(),
+            email: email.into(),
+            time: Time {
+                seconds,
+                offset: hhmm_to_offset(tz_offset_hhmm),
+                sign: if tz_offset_hhmm < 0 { Sign::Minus } else { Sign::Plus },
+            },
+        },
+        message: message.into(),
+    })
+}
+
+fn take<'a>(input: &mut &'a [u8], len: usize, offset: usize) -> Result<&'a [u8], Error> {
+    if input.len() < len {
+        return Err(Error::Record { offset });
+    }
+    let (taken, rest) = input.split_at(len);
+    *input = rest;
+    Ok(taken)
+}
+
+fn take_id(input: &mut &[u8], hash_len: usize, offset: usize) -> Result<ObjectId, Error> {
+    take(input, hash_len, offset).map(ObjectId::from_bytes_or_panic)
+}
+
+fn take_string<'a>(input: &mut &'a [u8], offset: usize) -> Result<&'a [u8], Error> {
+    let len = get_varint(input).ok_or(Error::Record { offset })?;
+    take(
+        input,
+        usize::try_from(len).map_err(|_| Error::Record { offset })?,
+        offset,
+    )
+}
+
+fn be16(data: &[u8]) -> usize {
+    usize::from(u16::from_be_bytes([data[0], data[1]]))
+}
+
+fn be24(data: &[u8]) -> usize {
+    usize::from(data[0]) << 16 | usize::from(data[1]) << 8 | usize::from(data[2])
+}
+
+fn be32(data: &[u8]) -> u32 {
+    u32::from_be_bytes(data[..4].try_into().expect("four bytes"))
+}
+
+fn be64(data: &[u8]) -> u64 {
+    u64::from_be_bytes(data[..8].try_into().expect("eight bytes"))
+}
@@ -0,0 +1,277 @@
+use std::io::{self, Write};
+
+use crate::reftable::{
+    offset_to_hhmm, put_varint, LogRecord, RefRecord, Table, Value, BLOCK_TYPE_LOG, BLOCK_TYPE_REF, MAGIC,
+    RESTART_INTERVAL,
+};
+
+/// The block size used unless a record needs more space.
+const DEFAULT_BLOCK_SIZE: usize = 4096;
+/// The largest block size that can be represented in the header.
+const MAX_BLOCK_SIZE: usize = 0xff_ffff;
+
+/// A record with its key, the type of its value and the encoded value.
+type Record = (Vec<u8>, u8, Vec<u8>);
+
+impl Table {
+    /// Serialize this table in version 1 of the format into `out`, with object ids of kind `object_hash`.
+    ///
+    /// Records may be in any order, but there must be only one record per reference name, and only one log record
+    /// per reference name and update index. The update indices of references must be within the bounds of the table,
+    /// while log records may also refer to entries of older tables, which is needed to delete them.
+    pub fn write_to(&self, out: &mut dyn Write, object_hash: gix_hash::Kind) -> io::Result<()> {
+        let hash_len = object_hash.len_in_bytes();
+        let mut refs = self
+            .refs
+            .iter()
+            .map(|record| self.encode_ref(record, hash_len))
+            .collect::<io::Result<Vec<_>>>()?;
+        let mut logs = self
+            .logs
+            .iter()
+            .map(|record| self.encode_log(record, hash_len))
+            .collect::<io::Result<Vec<_>>>()?;
+        for records in [&mut refs, &mut logs] {
+            records.sort_by(|a, b| a.0.cmp(&b.0));
+            if records.windows(2).any(|w| w[0].0 == w[1].0) {
+                return Err(invalid_input("records must not be duplicated"));
+            }
+        }
+
+        let largest_record = refs
+            .iter()
+            .chain(logs.iter())
+            .map(|(key, _, value)| key.len() + value.len() + 2 * 10)
+            .max()
+            .unwrap_or_default();
+        // Leave room for the file header, the block header and the restart table of a block with just this record.
+        let space_needed = largest_record + 24 + 4 + 3 + 2;
+        let mut block_size = DEFAULT_BLOCK_SIZE;
+        while log_block_capacity(block_size) < space_needed && block_size <= MAX_BLOCK_SIZE {
+            block_size += DEFAULT_BLOCK_SIZE;
+        }
+        if block_size > MAX_BLOCK_SIZE {
+            return Err(invalid_input("a record is too large to be stored in a block"));
+        }
+
+        let mut header = Vec::with_capacity(24);
+        header.extend_from_slice(MAGIC);
+        header.push(1);
+        header.extend_from_slice(&(block_size as u32).to_be_bytes()[1..]);
+        header.extend_from_slice(&self.min_update_index.to_be_bytes());
+        header.extend_from_slice(&self.max_update_index.to_be_bytes());
+
+        let mut buf = Vec::new();
+        write_blocks(&mut buf, &refs, BLOCK_TYPE_REF, &header, block_size)?;
+        let log_position = if logs.is_empty() { 0 } else { buf.len() };
+        write_blocks(&mut buf, &logs, BLOCK_TYPE_LOG, &header, block_size)?;
+        if buf.is_empty() {
+            buf.extend_from_slice(&header);
+        }
+
+        let footer_start = buf.len();
+        buf.extend_from_slice(&header);
+        for position in [0, 0, 0, log_position as u64, 0] {
+            buf.extend_from_slice(&position.to_be_bytes());
+        }
+        let checksum = gix_features::hash::crc32(&buf[footer_start..]);
+        buf.extend_from_slice(&checksum.to_be_bytes());
+        out.write_all(&buf)
+    }
+
+    fn encode_ref(&self, record: &RefRecord, hash_len: usize) -> io::Result<Record> {
+        let mut value = Vec::with_capacity(1 + 2 * hash_len);
+        put_varint(&mut value, self.update_index_delta(record.update_index)?);
+        let value_type = match &record.value {
+            Value::Deletion => 0,
+            Value::Object(id) => {
+                value.extend_from_slice(checked_id(id, hash_len)?);
+                1
+            }
+            Value::Peeled { target, peeled } => {
+                value.extend_from_slice(checked_id(target, hash_len)?);
+                value.extend_from_slice(checked_id(peeled, hash_len)?);
+                2
+            }
+            Value::Symbolic(target) => {
+                put_string(&mut value, target);
+                3
+            }
+        };
+        Ok((record.name.to_vec(), value_type, value))
+    }
+
+    fn encode_log(&self, record: &LogRecord, hash_len: usize) -> io::Result<Record> {
+        let mut key = Vec::with_capacity(record.name.len() + 9);
+        key.extend_from_slice(&record.name);
+        key.push(0);
+        // Newer entries sort first.
+        key.extend_from_slice(&(!record.update_index).to_be_bytes());
+
+        let Some(line) = &record.line else {
+            return Ok((key, 0, Vec::new()));
+        };
+        if line.message.contains(&b'\n') {
+            return Err(invalid_input("log messages must not contain newlines"));
+        }
+        let mut value = Vec::with_capacity(2 * hash_len + 64 + line.message.len());
+        value.extend_from_slice(checked_id(&line.previous_oid, hash_len)?);
+        value.extend_from_slice(checked_id(&line.new_oid, hash_len)?);
+        put_string(&mut value, &line.signature.name);
+        put_string(&mut value, &line.signature.email);
+        let time = line.signature.time;
+        put_varint(&mut value, time.seconds.max(0) as u64);
+        value.extend_from_slice(&offset_to_hhmm(time.offset).to_be_bytes());
+        let mut message = line.message.to_vec();
+        message.push(b'\n');
+        put_string(&mut value, &message);
+        Ok((key, 1, value))
+    }
+
+    fn update_index_delta(&self, update_index: u64) -> io::Result<u64> {
+        if !(self.min_update_index..=self.max_update_index).contains(&update_index) {
+            return Err(invalid_input(
+                "update indices of references must be within the bounds of the table",
+            ));
+        }
+        Ok(update_index - self.min_update_index)
+    }
+}
+
+/// A block under construction, with the file `header` at its beginning if it's the first block of the table.
+struct Block {
+    buf: Vec<u8>,
+    header_offset: usize,
+    restarts: Vec<u32>,
+    last_key: Vec<u8>,
+    num_records: usize,
+}
+
+impl Block {
+    fn new(block_type: u8, header: Option<&[u8]>) -> Self {
+        let mut buf = header.map(<[u8]>::to_vec).unwrap_or_default();
+        let header_offset = buf.len();
+        buf.extend_from_slice(&[block_type, 0, 0, 0]);
+        Block {
+            buf,
+            header_offset,
+            restarts: Vec::new(),
+            last_key: Vec::new(),
+            num_records: 0,
+        }
+    }
+
+    /// Add the record to this block if it fits into `capacity` bytes, and return `true` if it was added.
+    fn add(&mut self, (key, value_type, value): &Record, capacity: usize) -> bool {
+        let is_restart = self.num_records % RESTART_INTERVAL == 0;
+        if is_restart && self.restarts.len() == usize::from(u16::MAX) {
+            return false;
+        }
+        let prefix_len = if is_restart {
+            0
+        } else {
+            self.last_key.iter().zip(key).take_while(|(a, b)| a == b).count()
+        };
+        let mut record = Vec::with_capacity(key.len() - prefix_len + value.len() + 4);
+        put_varint(&mut record, prefix_len as u64);
+        put_varint(
+            &mut record,
+            ((key.len() - prefix_len) as u64) << 3 | u64::from(*value_type),
+        );
+        record.extend_from_slice(&key[prefix_len..]);
+        record.extend_from_slice(value);
+
+        let num_restarts = self.restarts.len() + usize::from(is_restart);
+        if self.num_records != 0 && self.buf.len() + record.len() + 3 * num_restarts + 2 > capacity {
+            return false;
+        }
+        if is_restart {
+            self.restarts.push(self.buf.len() as u32);
+        }
+        self.buf.extend_from_slice(&record);
+        self.last_key.clone_from(key);
+        self.num_records += 1;
+        true
+    }
+
+    /// Append the restart table and return the block along with the offset of its block header.
+    fn finish(mut self) -> (Vec<u8>, usize) {
+        for restart in &self.restarts {
+            self.buf.extend_from_slice(&restart.to_be_bytes()[1..]);
+        }
+        self.buf.extend_from_slice(&(self.restarts.len() as u16).to_be_bytes());
+        let block_len = (self.buf.len() as u32).to_be_bytes();
+        self.buf[self.header_offset + 1..self.header_offset + 4].copy_from_slice(&block_len[1..]);
+        (self.buf, self.header_offset)
+    }
+}
+
+/// Write all `records` into blocks of `block_type` to `out`, starting with the file `header` if `out` is still empty.
+fn write_blocks(
+    out: &mut Vec<u8>,
+    records: &[Record],
+    block_type: u8,
+    header: &[u8],
+    block_size: usize,
+) -> io::Result<()> {
+    let capacity = if block_type == BLOCK_TYPE_LOG {
+        log_block_capacity(block_size)
+    } else {
+        block_size
+    };
+    let mut block: Option<Block> = None;
+    for record in records {
+        loop {
+            let current = block.get_or_insert_with(|| Block::new(block_type, out.is_empty().then_some(header)));
+            if current.add(record, capacity) {
+                break;
+            }
+            if current.num_records == 0 {
+                return Err(invalid_input("a record is too large to be stored in a block"));
+            }
+            finish_block(out, block.take().expect("present"), block_type, block_size)?;
+        }
+    }
+    if let Some(block) = block {
+        finish_block(out, block, block_type, block_size)?;
+    }
+    Ok(())
+}
+
+fn finish_block(out: &mut Vec<u8>, block: Block, block_type: u8, block_size: usize) -> io::Result<()> {
+    let start = out.len();
+    let (block, header_offset) = block.finish();
+    if block_type == BLOCK_TYPE_LOG {
+        // Log blocks are compressed after their block header, and aren't padded.
+        out.extend_from_slice(&block[..header_offset + 4]);
+        let mut deflate = gix_features::zlib::stream::deflate::Write::new(&mut *out);
+        deflate.write_all(&block[header_offset + 4..])?;
+        deflate.flush()?;
+    } else {
+        out.extend_from_slice(&block);
+        out.resize(start + block_size, 0);
+    }
+    Ok(())
+}
+
+/// Return the amount of uncompressed bytes a log block can hold so that its compressed form fits into `block_size`,
+/// even if the data can't be compressed.
+fn log_block_capacity(block_size: usize) -> usize {
+    block_size - (block_size / 4096 + block_size / 16384 + 32)
+}
+
+fn put_string(out: &mut Vec<u8>, value: &[u8]) {
+    put_varint(out, value.len() as u64);
+    out.extend_from_slice(value);
+}
+
+fn checked_id(id: &gix_hash::oid, hash_len: usize) -> io::Result<&[u8]> {
+    if id.as_bytes().len() != hash_len {
+        return Err(invalid_input("object ids must be of the kind of the table"));
+    }
+    Ok(id.as_bytes())
+}
+
+fn invalid_input(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidInput, message)
+}
@@ -0,0 +1,267 @@
+//! Reading and writing references and their logs in the [reftable format](https://git-scm.com/docs/reftable),
+//! which is used if `extensions.refStorage` is set to `reftable`.
+//!
+//! All tables of a repository are listed in `reftable/tables.list`, oldest first, and each table records the changes
+//! of one or more transactions identified by their _update index_. Records in newer tables take precedence over those in
+//! older tables, so that the [`Stack`] of all tables is merged into a single view of all references and their logs.
+//!
+//! ### Limitations
+//!
+//! * Tables are read in full and the merged view is kept in memory, which is why indices in tables are ignored when reading
+//!   and not written either.
+//! * Tables are never compacted automatically, so each transaction adds a table to the stack.
+use std::{collections::BTreeMap, path::PathBuf};
+
+use gix_hash::ObjectId;
+
+use crate::{bstr::BString, log, FullName};
+
+/// The value of a reference as recorded in a [`Table`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Value {
+    /// The reference was deleted, which hides it in older tables.
+    Deletion,
+    /// The reference points to an object.
+    Object(ObjectId),
+    /// The reference points to an annotated tag, which peels to another object.
+    Peeled {
+        /// The object the reference points to, an annotated tag.
+        target: ObjectId,
+        /// The object the annotated `target` tag ultimately points to.
+        peeled: ObjectId,
+    },
+    /// The reference points to another reference by its full name.
+    Symbolic(BString),
+}
+
+/// A reference as recorded in a [`Table`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RefRecord {
+    /// The full name of the reference.
+    pub name: BString,
+    /// The update index of the transaction that recorded this value.
+    pub update_index: u64,
+    /// The value the reference had after the transaction.
+    pub value: Value,
+}
+
+/// An entry of a reference log as recorded in a [`Table`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LogRecord {
+    /// The full name of the reference the log belongs to.
+    pub name: BString,
+    /// The update index of the transaction that recorded this entry, which orders entries of the same log.
+    pub update_index: u64,
+    /// The entry itself, or `None` if the entry with the same name and update index in older tables is deleted.
+    pub line: Option<log::Line>,
+}
+
+/// A single table of references and their logs, with records of all transactions between and including
+/// `min_update_index` and `max_update_index`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Table {
+    /// The update index of the first transaction recorded in this table.
+    pub min_update_index: u64,
+    /// The update index of the last transaction recorded in this table.
+    pub max_update_index: u64,
+    /// The references in this table, in any order.
+    pub refs: Vec<RefRecord>,
+    /// The entries of reference logs in this table, in any order.
+    pub logs: Vec<LogRecord>,
+}
+
+/// All tables listed in `tables.list` of a `reftable` directory, merged into a single view of the references and their logs.
+#[derive(Debug, Clone)]
+pub struct Stack {
+    /// The `reftable` directory containing `tables.list` and all tables.
+    dir: PathBuf,
+    object_hash: gix_hash::Kind,
+    /// The file names of all tables, oldest first.
+    tables: Vec<String>,
+    max_update_index: u64,
+    /// All references that exist, which is why [`Value::Deletion`] is never stored.
+    refs: BTreeMap<FullName, Value>,
+    /// All reference logs with their entries keyed by update index.
+    logs: BTreeMap<FullName, BTreeMap<u64, log::Line>>,
+}
+
+const MAGIC: &[u8; 4] = b"REFT";
+/// The size of the file header in version 1, which is also the first part of the footer.
+const HEADER_SIZE_V1: usize = 24;
+/// The size of the file header in version 2, which adds the hash id.
+const HEADER_SIZE_V2: usize = 28;
+/// The size of the footer without the header it starts with.
+const FOOTER_SIZE_WITHOUT_HEADER: usize = 44;
+const BLOCK_TYPE_REF: u8 = b'r';
+const BLOCK_TYPE_LOG: u8 = b'g';
+/// Every how many records the key is stored in full to allow searching.
+const RESTART_INTERVAL: usize = 16;
+
+fn header_size(version: u8) -> usize {
+    if version == 1 {
+        HEADER_SIZE_V1
+    } else {
+        HEADER_SIZE_V2
+    }
+}
+
+/// Append `value` to `out` in the variable-length encoding used for offsets in packs, most significant bits first.
+fn put_varint(out: &mut Vec<u8>, mut value: u64) {
+    let mut buf = [0u8; 10];
+    let mut pos = buf.len() - 1;
+    buf[pos] = (value & 0x7f) as u8;
+    value >>= 7;
+    while value != 0 {
+        value -= 1;
+        pos -= 1;
+        buf[pos] = 0x80 | (value & 0x7f) as u8;
+        value >>= 7;
+    }
+    out.extend_from_slice(&buf[pos..]);
+}
+
+/// Decode a value written by [`put_varint()`] from the beginning of `input` and advance it, or return `None` if it's
+/// truncated or too large.
+fn get_varint(input: &mut &[u8]) -> Option<u64> {
+    let (&first, rest) = input.split_first()?;
+    *input = rest;
+    let mut value = u64::from(first & 0x7f);
+    let mut byte = first;
+    while byte & 0x80 != 0 {
+        let (&next, rest) = input.split_first()?;
+        *input = rest;
+        if value >= u64::MAX >> 7 {
+            return None;
+        }
+        value = ((value + 1) << 7) | u64::from(next & 0x7f);
+        byte = next;
+    }
+    Some(value)
+}
+
+/// Convert the time zone `offset` in seconds into the signed decimal `hhmm` form in which it is stored in log records,
+/// so `+0530` becomes `530`, just like it's written in the text of a signature.
+fn offset_to_hhmm(offset: i32) -> i16 {
+    let minutes = offset / 60;
+    (minutes / 60 * 100 + minutes % 60) as i16
+}
+
+/// The inverse of [`offset_to_hhmm()`], returning the time zone offset in seconds.
+fn hhmm_to_offset(hhmm: i16) -> i32 {
+    let hhmm = i32::from(hhmm);
+    (hhmm / 100 * 60 + hhmm % 100) * 60
+}
+
+///
+pub mod decode;
+mod encode;
+///
+pub mod open;
+///
+pub mod write;
+
+mod access {
+    use std::path::{Path, PathBuf};
+
+    use crate::{
+        bstr::BStr,
+        log,
+        reftable::{Stack, Value},
+        FullName, FullNameRef, Reference, Target,
+    };
+
+    impl Stack {
+        /// Return the `reftable` directory from which the tables were read.
+        pub fn dir(&self) -> &Path {
+            &self.dir
+        }
+
+        /// Return the path to the `tables.list` file, which is also the resource to lock when adding tables.
+        pub fn list_path(&self) -> PathBuf {
+            self.dir.join("tables.list")
+        }
+
+        /// The file names of all tables in the stack, oldest first.
+        pub fn tables(&self) -> &[String] {
+            &self.tables
+        }
+
+        /// The update index of the most recent transaction, or `0` if there are no tables.
+        pub fn max_update_index(&self) -> u64 {
+            self.max_update_index
+        }
+
+        /// Return the reference with the fully qualified `name`, or `None` if it doesn't exist.
+        pub fn try_find(&self, name: &FullNameRef) -> Option<Reference> {
+            self.refs
+                .get_key_value(name)
+                .map(|(name, value)| to_reference(name, value))
+        }
+
+        /// Return all references, sorted by name.
+        pub fn iter(&self) -> impl Iterator<Item = Reference> + '_ {
+            self.refs.iter().map(|(name, value)| to_reference(name, value))
+        }
+
+        /// Return all references whose name starts with `prefix`, sorted by name.
+        pub fn iter_prefixed<'a>(&'a self, prefix: &'a BStr) -> impl Iterator<Item = Reference> + 'a {
+            self.refs
+                .range::<FullNameRef, _>((
+                    std::ops::Bound::Included(FullNameRef::new_unchecked(prefix)),
+                    std::ops::Bound::Unbounded,
+                ))
+                .take_while(move |(name, _)| name.as_bstr().starts_with(prefix))
+                .map(|(name, value)| to_reference(name, value))
+        }
+
+        /// Return `true` if the reference log of `name` has at least one entry.
+        pub fn log_exists(&self, name: &FullNameRef) -> bool {
+            self.logs.contains_key(name)
+        }
+
+        /// Return the entries of the reference log of `name` along with their update index, oldest first, or `None` if
+        /// there is no such log.
+        pub fn log(&self, name: &FullNameRef) -> Option<impl DoubleEndedIterator<Item = (u64, &log::Line)> + '_> {
+            self.logs
+                .get(name)
+                .map(|entries| entries.iter().map(|(update_index, line)| (*update_index, line)))
+        }
+
+        /// Return the names of all references which have a log, sorted by name.
+        pub fn log_names(&self) -> impl Iterator<Item = &FullNameRef> + '_ {
+            self.logs.keys().map(AsRef::as_ref)
+        }
+    }
+
+    fn to_reference(name: &FullName, value: &Value) -> Reference {
+        let (target, peeled) = match value {
+            Value::Object(id) => (Target::Object(*id), None),
+            Value::Peeled { target, peeled } => (Target::Object(*target), Some(*peeled)),
+            Value::Symbolic(target) => (Target::Symbolic(FullName(target.clone())), None),
+            Value::Deletion => unreachable!("BUG: deletions are never stored"),
+        };
+        Reference {
+            name: name.clone(),
+            target,
+            peeled,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{hhmm_to_offset, offset_to_hhmm};
+
+    #[test]
+    fn time_zones_are_stored_as_hhmm() {
+        for (offset, hhmm) in [
+            (0, 0),
+            (5 * 3600 + 30 * 60, 530),
+            (-8 * 3600, -800),
+            (-(3600 + 45 * 60), -145),
+        ] {
+            assert_eq!(offset_to_hhmm(offset), hhmm, "{offset}");
+            assert_eq!(hhmm_to_offset(hhmm), offset, "{hhmm}");
+        }
+    }
+}
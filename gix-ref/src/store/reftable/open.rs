@@ -0,0 +1,161 @@
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    bstr::ByteSlice,
+    reftable::{Stack, Table, Value},
+    FullName,
+};
+
+mod error {
+    use std::path::PathBuf;
+
+    use crate::bstr::BString;
+
+    /// The error returned by [`Stack::at()`][crate::reftable::Stack::at()].
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("Could not read {path:?}")]
+        Io { source: std::io::Error, path: PathBuf },
+        #[error("The table name {name:?} in tables.list is invalid")]
+        TableName { name: BString },
+        #[error("The table at {path:?} could not be decoded")]
+        Decode {
+            source: crate::reftable::decode::Error,
+            path: PathBuf,
+        },
+        #[error("The table at {path:?} contains the invalid reference name {name:?}")]
+        RefName {
+            source: gix_validate::reference::name::Error,
+            name: BString,
+            path: PathBuf,
+        },
+    }
+}
+pub use error::Error;
+
+impl Stack {
+    /// Read all tables listed in `dir/tables.list`, where `dir` is the `reftable` directory of a repository, and merge them
+    /// into a single view, expecting object ids of kind `object_hash`.
+    ///
+    /// Return `Ok(None)` if there is no `tables.list` file.
+    pub fn at(dir: PathBuf, object_hash: gix_hash::Kind) -> Result<Option<Self>, Error> {
+        let list_path = dir.join("tables.list");
+        // Tables may be compacted concurrently, which removes the tables we just read about, so retry a few times.
+        let mut attempts_left = 3;
+        loop {
+            let list = match std::fs::read(&list_path) {
+                Ok(list) => list,
+                Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+                Err(source) => {
+                    return Err(Error::Io {
+                        source,
+                        path: list_path,
+                    })
+                }
+            };
+            let names = list
+                .lines()
+                .filter(|name| !name.is_empty())
+                .map(|name| {
+                    name.to_str()
+                        .ok()
+                        .filter(|name| !name.contains(['/', '\\']) && !name.starts_with('.'))
+                        .map(ToOwned::to_owned)
+                        .ok_or_else(|| Error::TableName { name: name.into() })
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            let mut tables = Vec::with_capacity(names.len());
+            for name in &names {
+                let path = dir.join(name);
+                match std::fs::read(&path) {
+                    Ok(data) => tables.push((
+                        Table::from_bytes(&data, object_hash).map_err(|source| Error::Decode {
+                            source,
+                            path: path.clone(),
+                        })?,
+                        path,
+                    )),
+                    Err(err) if err.kind() == std::io::ErrorKind::NotFound && attempts_left > 1 => break,
+                    Err(source) => return Err(Error::Io { source, path }),
+                }
+            }
+            if tables.len() != names.len() {
+                attempts_left -= 1;
+                continue;
+            }
+            return Stack::from_tables(dir, object_hash, names, tables).map(Some);
+        }
+    }
+
+    /// Return a stack without any tables that would be stored in `dir`, as if its `tables.list` file was empty,
+    /// to be able to [add the first table](Self::add_table()).
+    pub fn empty(dir: PathBuf, object_hash: gix_hash::Kind) -> Self {
+        Stack {
+            dir,
+            object_hash,
+            tables: Vec::new(),
+            max_update_index: 0,
+            refs: BTreeMap::new(),
+            logs: BTreeMap::new(),
+        }
+    }
+
+    fn from_tables(
+        dir: PathBuf,
+        object_hash: gix_hash::Kind,
+        names: Vec<String>,
+        tables: Vec<(Table, PathBuf)>,
+    ) -> Result<Self, Error> {
+        let mut stack = Stack {
+            tables: names,
+            ..Stack::empty(dir, object_hash)
+        };
+        for (table, path) in tables {
+            for record in table.refs {
+                let name = full_name(record.name, &path)?;
+                match record.value {
+                    Value::Deletion => {
+                        stack.refs.remove(&name);
+                    }
+                    Value::Symbolic(target) => {
+                        let target = full_name(target, &path)?;
+                        stack.refs.insert(name, Value::Symbolic(target.into()));
+                    }
+                    value => {
+                        stack.refs.insert(name, value);
+                    }
+                }
+            }
+            for record in table.logs {
+                let name = full_name(record.name, &path)?;
+                match record.line {
+                    Some(line) => {
+                        stack.logs.entry(name).or_default().insert(record.update_index, line);
+                    }
+                    None => {
+                        if let Some(entries) = stack.logs.get_mut(&name) {
+                            entries.remove(&record.update_index);
+                            if entries.is_empty() {
+                                stack.logs.remove(&name);
+                            }
+                        }
+                    }
+                }
+            }
+            stack.max_update_index = table.max_update_index;
+        }
+        Ok(stack)
+    }
+}
+
+fn full_name(name: crate::bstr::BString, path: &Path) -> Result<FullName, Error> {
+    FullName::try_from(name.as_bstr()).map_err(|source| Error::RefName {
+        source,
+        name,
+        path: path.to_owned(),
+    })
+}
@@ -0,0 +1,85 @@
+use std::io::Write;
+
+use crate::reftable::{Stack, Table};
+
+mod error {
+    /// The error returned by [`Stack::add_table()`][crate::reftable::Stack::add_table()].
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("The update indices {min_update_index}..={max_update_index} of the new table must follow {max_update_index_of_stack}")]
+        UpdateIndex {
+            min_update_index: u64,
+            max_update_index: u64,
+            max_update_index_of_stack: u64,
+        },
+        #[error("The new table could not be created")]
+        Lock(#[from] gix_lock::acquire::Error),
+        #[error("The new table could not be written")]
+        Io(#[from] std::io::Error),
+        #[error("The new table could not be moved into place")]
+        Commit(#[from] gix_lock::commit::Error<gix_lock::File>),
+    }
+}
+pub use error::Error;
+
+impl Stack {
+    /// Write `table` into a new file in the directory of this stack and add it to the top of the stack by committing `lock`,
+    /// which must be the lock for [`list_path()`](Self::list_path()) that was held while reading this stack.
+    /// The new table is flushed to disk according to `fsync`.
+    ///
+    /// The update indices of `table` must be greater than the ones of all tables in the stack.
+    /// Return the file name of the new table.
+    ///
+    /// Note that this instance isn't updated, [open](Self::at()) the stack again to see the new table.
+    pub fn add_table(
+        &self,
+        table: &Table,
+        mut lock: gix_lock::File,
+        fsync: gix_features::fs::Fsync,
+    ) -> Result<String, Error> {
+        if table.min_update_index <= self.max_update_index || table.max_update_index < table.min_update_index {
+            return Err(Error::UpdateIndex {
+                min_update_index: table.min_update_index,
+                max_update_index: table.max_update_index,
+                max_update_index_of_stack: self.max_update_index,
+            });
+        }
+        let name = format!(
+            "0x{:012x}-0x{:012x}-{:08x}.ref",
+            table.min_update_index,
+            table.max_update_index,
+            random_suffix()
+        );
+        let mut table_file = gix_lock::File::acquire_to_update_resource(
+            self.dir.join(&name),
+            gix_lock::acquire::Fail::Immediately,
+            None,
+        )?;
+        table_file.with_mut(|file| {
+            table.write_to(file, self.object_hash)?;
+            fsync.sync(file)
+        })?;
+        table_file.commit()?;
+
+        lock.with_mut(|file| {
+            for existing in self.tables.iter().chain(Some(&name)) {
+                writeln!(file, "{existing}")?;
+            }
+            fsync.sync(file)
+        })?;
+        lock.commit()?;
+        Ok(name)
+    }
+}
+
+/// Return a random number to make table names unique even if they are written by multiple processes at once.
+fn random_suffix() -> u32 {
+    use std::hash::{BuildHasher, Hasher};
+    let mut hasher = std::collections::hash_map::RandomState::new().build_hasher();
+    hasher.write_u32(std::process::id());
+    if let Ok(elapsed) = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH) {
+        hasher.write_u128(elapsed.as_nanos());
+    }
+    hasher.finish() as u32
+}
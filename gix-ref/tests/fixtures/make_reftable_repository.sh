@@ -0,0 +1,12 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+git init -q --ref-format=reftable repo
+cd repo
+
+git checkout -q -b main
+GIT_COMMITTER_DATE="1700000000 +0530" git commit -q --allow-empty -m c1
+GIT_COMMITTER_DATE="1700003600 -0800" git commit -q --allow-empty -m c2
+git branch feature HEAD~1
+git tag light
+git tag -m "annotated" annotated
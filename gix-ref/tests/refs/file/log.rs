@@ -191,4 +191,34 @@ mod iter {
             assert!(iter.next().is_none(), "iterator exhausted");
         }
     }
+    mod forward_streaming {
+        use crate::file::log::iter::reflog;
+
+        #[test]
+        fn yields_the_same_as_forward() -> crate::Result {
+            let log = reflog("HEAD")?;
+            let expected: Vec<gix_ref::log::Line> = gix_ref::file::log::iter::forward(&log)
+                .map(|line| line.map(Into::into))
+                .collect::<Result<_, _>>()?;
+            let actual = gix_ref::file::log::iter::forward_streaming(log.as_slice()).collect::<Result<Vec<_>, _>>()?;
+            assert_eq!(actual, expected);
+            Ok(())
+        }
+
+        #[test]
+        fn a_single_failure_does_not_abort_iteration() {
+            let log_first_broken = "0000000000000000000000000000000000000000 134385fbroken7062102c6a483440bfda2a03 committer <committer@example.com> 946771200 +0000	commit
+0000000000000000000000000000000000000000 134385f6d781b7e97062102c6a483440bfda2a03 committer <committer@example.com> 946771200 +0000	commit (initial): c1\r\n";
+
+            let mut iter = gix_ref::file::log::iter::forward_streaming(log_first_broken.as_bytes());
+            let err = iter.next().expect("error is not none").expect_err("the line is broken");
+            assert!(err.to_string().starts_with("Could not decode log line"));
+            assert_eq!(
+                iter.next().expect("a second line").expect("parses ok").message,
+                "commit (initial): c1",
+                "without the windows line ending"
+            );
+            assert!(iter.next().is_none(), "iterator exhausted");
+        }
+    }
 }
@@ -0,0 +1,243 @@
+use gix_lock::acquire::Fail;
+use gix_ref::{
+    reftable::{LogRecord, RefRecord, Stack, Table, Value},
+    store::{Format, WriteReflog},
+    transaction::{Change, LogChange, PreviousValue, RefEdit, RefLog},
+    Target,
+};
+
+use crate::{
+    file::transaction::prepare_and_commit::{committer, create_at},
+    hex_to_id,
+};
+
+fn store(dir: &std::path::Path) -> gix_ref::file::Store {
+    gix_ref::file::Store::at(
+        dir.into(),
+        gix_ref::store::init::Options {
+            write_reflog: WriteReflog::Normal,
+            format: Format::Reftable,
+            ..Default::default()
+        },
+    )
+}
+
+#[test]
+fn read() -> crate::Result {
+    let tmp = gix_testtools::tempfile::TempDir::new()?;
+    let dir = tmp.path().join("reftable");
+    std::fs::create_dir(&dir)?;
+    let id = hex_to_id("134385f6d781b7e97062102c6a483440bfda2a03");
+    let record = |name: &str, value: Value| RefRecord {
+        name: name.into(),
+        update_index: 1,
+        value,
+    };
+    let table = Table {
+        min_update_index: 1,
+        max_update_index: 1,
+        refs: vec![
+            record("HEAD", Value::Symbolic("refs/heads/main".into())),
+            record("refs/heads/main", Value::Object(id)),
+            record("refs/remotes/origin/main", Value::Object(id)),
+        ],
+        logs: vec![LogRecord {
+            name: "refs/heads/main".into(),
+            update_index: 1,
+            line: Some(gix_ref::log::Line {
+                previous_oid: gix_hash::Kind::Sha1.null(),
+                new_oid: id,
+                signature: committer(),
+                message: "created".into(),
+            }),
+        }],
+    };
+    let stack = Stack::empty(dir, gix_hash::Kind::Sha1);
+    let lock =
+        gix_lock::File::acquire_to_update_resource(stack.list_path(), gix_lock::acquire::Fail::Immediately, None)?;
+    stack.add_table(&table, lock, Default::default())?;
+
+    let store = store(tmp.path());
+    assert_eq!(store.format(), Format::Reftable);
+    assert_eq!(store.find("main")?.target, Target::Object(id));
+    assert_eq!(
+        store.find("HEAD")?.target,
+        Target::Symbolic("refs/heads/main".try_into()?),
+        "HEAD is in the table as well"
+    );
+    assert!(store.try_find("feature")?.is_none());
+    assert_eq!(
+        store
+            .iter()?
+            .all()?
+            .map(|r| r.map(|r| r.name.as_bstr().to_owned()))
+            .collect::<Result<Vec<_>, _>>()?,
+        ["refs/heads/main", "refs/remotes/origin/main"],
+        "iteration only yields references in `refs/`"
+    );
+    assert_eq!(
+        store
+            .iter()?
+            .prefixed("refs/remotes/".as_ref())?
+            .map(|r| r.map(|r| r.name.as_bstr().to_owned()))
+            .collect::<Result<Vec<_>, _>>()?,
+        ["refs/remotes/origin/main"]
+    );
+
+    assert!(store.reflog_exists("refs/heads/main")?);
+    assert!(!store.reflog_exists("refs/remotes/origin/main")?);
+    let mut buf = Vec::new();
+    let log: Vec<_> = store
+        .reflog_iter("refs/heads/main", &mut buf)?
+        .expect("present")
+        .map(|line| line.map(|line| line.to_owned()))
+        .collect::<Result<_, _>>()?;
+    assert_eq!(log.len(), 1);
+    assert_eq!(log[0].message, "created");
+    assert_eq!(log[0].signature, committer(), "the time zone survives as well");
+    assert_eq!(
+        store
+            .reflog_iter_rev("refs/heads/main", &mut [0u8; 256])?
+            .map(Iterator::count),
+        Some(1),
+        "logs can be read in reverse as well"
+    );
+    Ok(())
+}
+
+#[test]
+fn read_tables_written_by_git() -> crate::Result {
+    if gix_testtools::should_skip_as_git_version_is_smaller_than(2, 45, 0) {
+        return Ok(());
+    }
+    let dir = gix_testtools::scripted_fixture_read_only_standalone("make_reftable_repository.sh")?;
+    let store = store(&dir.join("repo/.git"));
+    let main = store.find("main")?;
+    assert_eq!(store.find("light")?.target, main.target);
+    assert_eq!(
+        store
+            .reflog_iter_rev("HEAD", &mut [0u8; 512])?
+            .expect("HEAD has a log")
+            .count(),
+        2
+    );
+    Ok(())
+}
+
+fn table_count(dir: &std::path::Path) -> std::io::Result<usize> {
+    Ok(std::fs::read_to_string(dir.join("reftable").join("tables.list"))?
+        .lines()
+        .count())
+}
+
+#[test]
+fn journey() -> crate::Result {
+    let tmp = gix_testtools::tempfile::TempDir::new()?;
+    let store = store(tmp.path());
+    assert_eq!(store.format(), Format::Reftable);
+    assert!(store.open_reftable()?.is_none(), "the stack is created on first write");
+
+    store
+        .transaction()
+        .prepare(
+            [
+                create_at("refs/heads/main"),
+                RefEdit {
+                    change: Change::Update {
+                        log: LogChange::default(),
+                        expected: PreviousValue::MustNotExist,
+                        new: Target::Symbolic("refs/heads/main".try_into()?),
+                    },
+                    name: "HEAD".try_into()?,
+                    deref: false,
+                },
+            ],
+            Fail::Immediately,
+            Fail::Immediately,
+        )?
+        .commit(committer().to_ref())?;
+    assert_eq!(table_count(tmp.path())?, 1, "one table per transaction");
+    assert!(
+        !tmp.path().join("refs").exists() && !tmp.path().join("HEAD").exists(),
+        "nothing is written into files"
+    );
+
+    let main = store.find("main")?;
+    assert_eq!(main.name.as_bstr(), "refs/heads/main");
+    assert_eq!(
+        main.target.to_ref(),
+        create_at("refs/heads/main").change.new_value().expect("update")
+    );
+    assert_eq!(
+        store.find("HEAD")?.target,
+        Target::Symbolic("refs/heads/main".try_into()?)
+    );
+    assert_eq!(
+        store
+            .iter()?
+            .all()?
+            .map(|r| r.map(|r| r.name.as_bstr().to_owned()))
+            .collect::<Result<Vec<_>, _>>()?,
+        ["refs/heads/main"],
+        "iteration only yields references in `refs/`"
+    );
+
+    let mut buf = Vec::new();
+    let log: Vec<_> = store
+        .reflog_iter("refs/heads/main", &mut buf)?
+        .expect("log was forcefully created")
+        .map(|line| line.map(|line| line.to_owned()))
+        .collect::<Result<_, _>>()?;
+    assert_eq!(log.len(), 1);
+    assert_eq!(log[0].message, "log peeled");
+    assert!(log[0].previous_oid.is_null());
+    assert_eq!(
+        store
+            .reflog_iter_rev("refs/heads/main", &mut [0u8; 256])?
+            .map(Iterator::count),
+        Some(1),
+        "logs can be read in reverse as well"
+    );
+
+    let err = store
+        .transaction()
+        .prepare(
+            Some(create_at("refs/heads/main/sub")),
+            Fail::Immediately,
+            Fail::Immediately,
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Reference \"refs/heads/main/sub\" cannot be written as reference \"refs/heads/main\" exists and one is a directory of the other",
+        "names are checked for conflicts just like with files"
+    );
+
+    store
+        .transaction()
+        .prepare(
+            [
+                create_at("FETCH_HEAD"),
+                RefEdit {
+                    change: Change::Delete {
+                        expected: PreviousValue::MustExist,
+                        log: RefLog::AndReference,
+                    },
+                    name: "refs/heads/main".try_into()?,
+                    deref: false,
+                },
+            ],
+            Fail::Immediately,
+            Fail::Immediately,
+        )?
+        .commit(committer().to_ref())?;
+    assert_eq!(table_count(tmp.path())?, 2);
+    assert!(store.try_find("main")?.is_none(), "deletions shadow older tables");
+    assert!(!store.reflog_exists("refs/heads/main")?, "logs are deleted as well");
+    assert!(
+        tmp.path().join("FETCH_HEAD").is_file(),
+        "special references are always stored in files"
+    );
+    assert!(store.find("FETCH_HEAD").is_ok(), "…and can be found there");
+    Ok(())
+}
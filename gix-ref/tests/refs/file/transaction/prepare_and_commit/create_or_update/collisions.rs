@@ -32,14 +32,178 @@ fn conflicting_creation_without_packed_refs() -> crate::Result {
         Ok(_) if case_sensitive => {}
         Ok(_) if !case_sensitive => panic!("should fail as 'a' and 'A' clash"),
         Err(err) if case_sensitive => panic!("should work as case sensitivity allows 'a' and 'A' to coexist: {err:?}"),
-        Err(err) if !case_sensitive => {
-            assert_eq!(err.to_string(), "A lock could not be obtained for reference \"refs/A\"");
-        }
+        Err(err) if !case_sensitive => match err {
+            gix_ref::file::transaction::prepare::Error::LockHeld {
+                full_name, lock_path, ..
+            } => {
+                assert_eq!(full_name, "refs/A");
+                assert!(
+                    lock_path.ends_with("A.lock"),
+                    "the lock path is the one of the reference that failed"
+                );
+            }
+            _ => unreachable!("lock files clash on case-insensitive filesystems: {err:?}"),
+        },
         _ => unreachable!("actually everything is covered"),
     }
     Ok(())
 }
 
+#[test]
+fn name_conflicts_with_loose_and_packed_refs_and_within_transaction() -> crate::Result {
+    use gix_ref::file::transaction::prepare::Error;
+
+    let (_dir, store) = empty_store()?;
+    store
+        .transaction()
+        .prepare(
+            [create_at("refs/heads/loose"), create_at("refs/heads/dir/loose")],
+            Fail::Immediately,
+            Fail::Immediately,
+        )?
+        .commit(committer().to_ref())?;
+    store
+        .transaction()
+        .packed_refs(PackedRefs::DeletionsAndNonSymbolicUpdatesRemoveLooseSourceReference(
+            Box::new(EmptyCommit),
+        ))
+        .prepare(
+            [
+                create_at("refs/heads/packed"),
+                create_at("refs/heads/packed-dir/packed"),
+            ],
+            Fail::Immediately,
+            Fail::Immediately,
+        )?
+        .commit(committer().to_ref())?;
+
+    for (edits, expected_name, expected_conflicting_name) in [
+        (
+            vec![create_at("refs/heads/loose/sub")],
+            "refs/heads/loose/sub",
+            "refs/heads/loose",
+        ),
+        (
+            vec![create_at("refs/heads/dir")],
+            "refs/heads/dir",
+            "refs/heads/dir/loose",
+        ),
+        (
+            vec![create_at("refs/heads/packed/sub")],
+            "refs/heads/packed/sub",
+            "refs/heads/packed",
+        ),
+        (
+            vec![create_at("refs/heads/packed-dir")],
+            "refs/heads/packed-dir",
+            "refs/heads/packed-dir/packed",
+        ),
+        (
+            vec![create_at("refs/heads/new"), create_at("refs/heads/new/sub")],
+            "refs/heads/new/sub",
+            "refs/heads/new",
+        ),
+    ] {
+        match store.transaction().prepare(edits, Fail::Immediately, Fail::Immediately) {
+            Err(Error::NameConflict {
+                full_name,
+                conflicting_name,
+            }) => {
+                assert_eq!(full_name, expected_name);
+                assert_eq!(conflicting_name, expected_conflicting_name);
+            }
+            res => unreachable!("expected a name conflict, got {:?}", res.map(|_| ())),
+        }
+    }
+
+    store
+        .transaction()
+        .prepare(
+            [delete_at("refs/heads/packed"), create_at("refs/heads/packed/sub")],
+            Fail::Immediately,
+            Fail::Immediately,
+        )?
+        .commit(committer().to_ref())?;
+    assert!(
+        store.try_find_loose("refs/heads/packed/sub")?.is_some(),
+        "references that are deleted in the same transaction don't conflict"
+    );
+    assert!(
+        store.reflog_exists("refs/heads/packed/sub")?,
+        "the reflog of the deleted reference was removed to make room for the new one"
+    );
+
+    store
+        .transaction()
+        .prepare(
+            [delete_at("refs/heads/packed-dir/packed"), create_at("refs/heads/packed-dir")],
+            Fail::Immediately,
+            Fail::Immediately,
+        )?
+        .commit(committer().to_ref())?;
+    assert!(store.try_find_loose("refs/heads/packed-dir")?.is_some());
+    assert!(
+        store.reflog_exists("refs/heads/packed-dir")?,
+        "the directory of reflogs of deleted references is removed as well"
+    );
+
+    for (edits, expected_name, expected_conflicting_name) in [
+        (
+            vec![delete_at("refs/heads/loose"), create_at("refs/heads/loose/sub")],
+            "refs/heads/loose/sub",
+            "refs/heads/loose",
+        ),
+        (
+            vec![delete_at("refs/heads/dir/loose"), create_at("refs/heads/dir")],
+            "refs/heads/dir",
+            "refs/heads/dir/loose",
+        ),
+    ] {
+        match store.transaction().prepare(edits, Fail::Immediately, Fail::Immediately) {
+            Err(Error::NameConflict {
+                full_name,
+                conflicting_name,
+            }) => {
+                assert_eq!(full_name, expected_name);
+                assert_eq!(
+                    conflicting_name, expected_conflicting_name,
+                    "loose references are in the way even if they are deleted in the same transaction"
+                );
+            }
+            res => unreachable!("expected a name conflict, got {:?}", res.map(|_| ())),
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn case_conflicts_are_detected_if_the_filesystem_ignores_case() -> crate::Result {
+    let (dir, mut store) = empty_store()?;
+    if case_sensitive(dir.path()) {
+        return Ok(());
+    }
+    store.ignore_case = true;
+    store
+        .transaction()
+        .prepare([create_at("refs/heads/main")], Fail::Immediately, Fail::Immediately)?
+        .commit(committer().to_ref())?;
+
+    match store
+        .transaction()
+        .prepare([create_at("refs/heads/Main")], Fail::Immediately, Fail::Immediately)
+    {
+        Err(gix_ref::file::transaction::prepare::Error::CaseConflict {
+            full_name,
+            conflicting_name,
+        }) => {
+            assert_eq!(full_name, "refs/heads/Main");
+            assert_eq!(conflicting_name, "refs/heads/main");
+        }
+        res => unreachable!("expected a case conflict, got {:?}", res.map(|_| ())),
+    }
+    Ok(())
+}
+
 #[test]
 fn non_conflicting_creation_without_packed_refs_work() -> crate::Result {
     let (_dir, store) = empty_store()?;
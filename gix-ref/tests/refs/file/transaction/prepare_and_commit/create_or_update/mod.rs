@@ -65,35 +65,36 @@ fn reference_with_equally_named_empty_or_non_empty_directory_already_in_place_ca
             std::fs::write(head_dir.join("file.ext"), "".as_bytes())?;
         }
 
-        let edits = store
-            .transaction()
-            .prepare(
-                Some(RefEdit {
-                    change: Change::Update {
-                        log: LogChange::default(),
-                        expected: PreviousValue::MustNotExist,
-                        new: Target::Symbolic("refs/heads/main".try_into().unwrap()),
-                    },
-                    name: "HEAD".try_into()?,
-                    deref: false,
-                }),
-                Fail::Immediately,
-                Fail::Immediately,
-            )?
-            .commit(committer().to_ref());
+        let res = store.transaction().prepare(
+            Some(RefEdit {
+                change: Change::Update {
+                    log: LogChange::default(),
+                    expected: PreviousValue::MustNotExist,
+                    new: Target::Symbolic("refs/heads/main".try_into().unwrap()),
+                },
+                name: "HEAD".try_into()?,
+                deref: false,
+            }),
+            Fail::Immediately,
+            Fail::Immediately,
+        );
         if *is_empty {
-            let edits = edits?;
+            let edits = res?.commit(committer().to_ref())?;
             assert!(
                 store.try_find_loose(edits[0].name.as_ref())?.is_some(),
                 "HEAD was created despite a directory being in the way"
             );
         } else {
-            match edits {
-                #[cfg_attr(target_os = "windows", allow(unused_variables))]
-                Err(transaction::commit::Error::LockCommit { source, full_name }) => {
+            match res {
+                Err(transaction::prepare::Error::NameConflict {
+                    full_name,
+                    conflicting_name,
+                }) => {
                     assert_eq!(full_name, "HEAD");
-                    #[cfg(not(windows))]
-                    assert_eq!(source.to_string(), "Directory not empty");
+                    assert_eq!(
+                        conflicting_name, "HEAD/file.ext",
+                        "the file in the directory is seen as reference that is in the way"
+                    );
                 }
                 _ => unreachable!("other errors shouldn't happen here"),
             };
@@ -490,7 +491,12 @@ fn windows_device_name_is_illegal_with_enabled_windows_protections() -> crate::R
 
 #[test]
 fn symbolic_head_missing_referent_then_update_referent() -> crate::Result {
-    for reflog_writemode in &[WriteReflog::Normal, WriteReflog::Disable, WriteReflog::Always] {
+    for reflog_writemode in &[
+        WriteReflog::Normal,
+        WriteReflog::AppendToExisting,
+        WriteReflog::Disable,
+        WriteReflog::Always,
+    ] {
         let (_keep, mut store) = empty_store()?;
         store.write_reflog = *reflog_writemode;
         let referent = "refs/heads/alt-main";
@@ -628,10 +634,10 @@ fn symbolic_head_missing_referent_then_update_referent() -> crate::Result {
                     let expected_line = log_line(gix_hash::Kind::Sha1.null(), new_oid, "an actual change");
                     assert_eq!(reflog_lines(&store, ref_name)?, vec![expected_line]);
                 }
-                WriteReflog::Disable => {
+                WriteReflog::AppendToExisting | WriteReflog::Disable => {
                     assert!(
                         store.reflog_iter(*ref_name, &mut buf)?.is_none(),
-                        "nothing is ever written if its disabled"
+                        "nothing is written if there is no log to append to, or if it's disabled"
                     );
                 }
             }
@@ -640,6 +646,56 @@ fn symbolic_head_missing_referent_then_update_referent() -> crate::Result {
     Ok(())
 }
 
+#[test]
+fn reflog_writemode_can_be_overridden_per_transaction() -> crate::Result {
+    let (_keep, store) = empty_store()?;
+    assert_eq!(store.write_reflog, WriteReflog::Normal);
+    let edit = |name: &str| RefEdit {
+        change: Change::Update {
+            log: LogChange {
+                mode: RefLog::AndReference,
+                force_create_reflog: false,
+                message: "the message".into(),
+            },
+            expected: PreviousValue::MustNotExist,
+            new: Target::Object(hex_to_id("28ce6a8b26aa170e1de65536fe8abe1832bd3242")),
+        },
+        name: name.try_into().expect("valid"),
+        deref: false,
+    };
+
+    let mut buf = Vec::new();
+    store
+        .transaction()
+        .write_reflog(WriteReflog::Always)
+        .prepare(Some(edit("refs/tags/a")), Fail::Immediately, Fail::Immediately)?
+        .commit(committer().to_ref())?;
+    assert!(
+        store.reflog_iter("refs/tags/a", &mut buf)?.is_some(),
+        "tags get a reflog only if it is written for all references"
+    );
+
+    store
+        .transaction()
+        .write_reflog(WriteReflog::Disable)
+        .prepare(Some(edit("refs/heads/b")), Fail::Immediately, Fail::Immediately)?
+        .commit(committer().to_ref())?;
+    assert!(
+        store.reflog_iter("refs/heads/b", &mut buf)?.is_none(),
+        "branches don't get a reflog if it's disabled in the transaction"
+    );
+
+    store
+        .transaction()
+        .prepare(Some(edit("refs/heads/c")), Fail::Immediately, Fail::Immediately)?
+        .commit(committer().to_ref())?;
+    assert!(
+        store.reflog_iter("refs/heads/c", &mut buf)?.is_some(),
+        "the setting of the store is used by default"
+    );
+    Ok(())
+}
+
 #[test]
 /// Writing a peeled ref to which head points to doesn't update HEAD on the fly even though that might be what's would
 /// be needed to keep the reflog consistent
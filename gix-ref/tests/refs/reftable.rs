@@ -0,0 +1,257 @@
+use gix_date::{time::Sign, Time};
+use gix_ref::{
+    log,
+    reftable::{LogRecord, RefRecord, Stack, Table, Value},
+};
+
+use crate::hex_to_id;
+
+fn line(new: &str, seconds: i64, offset: i32, message: &str) -> log::Line {
+    log::Line {
+        previous_oid: gix_hash::Kind::Sha1.null(),
+        new_oid: hex_to_id(new),
+        signature: gix_actor::Signature {
+            name: "committer".into(),
+            email: "committer@example.com".into(),
+            time: Time {
+                seconds,
+                offset,
+                sign: if offset < 0 { Sign::Minus } else { Sign::Plus },
+            },
+        },
+        message: message.into(),
+    }
+}
+
+fn sorted(mut table: Table) -> Table {
+    table.refs.sort_by(|a, b| a.name.cmp(&b.name));
+    table
+        .logs
+        .sort_by(|a, b| (&a.name, a.update_index).cmp(&(&b.name, b.update_index)));
+    table
+}
+
+fn sample_table() -> Table {
+    let id = "134385f6d781b7e97062102c6a483440bfda2a03";
+    Table {
+        min_update_index: 2,
+        max_update_index: 3,
+        refs: vec![
+            RefRecord {
+                name: "refs/heads/main".into(),
+                update_index: 2,
+                value: Value::Object(hex_to_id(id)),
+            },
+            RefRecord {
+                name: "HEAD".into(),
+                update_index: 2,
+                value: Value::Symbolic("refs/heads/main".into()),
+            },
+            RefRecord {
+                name: "refs/tags/v1".into(),
+                update_index: 3,
+                value: Value::Peeled {
+                    target: hex_to_id("4c3f4cce493d7beb45012e478021b5f65295e5a3"),
+                    peeled: hex_to_id(id),
+                },
+            },
+            RefRecord {
+                name: "refs/heads/gone".into(),
+                update_index: 3,
+                value: Value::Deletion,
+            },
+        ],
+        logs: vec![
+            LogRecord {
+                name: "refs/heads/main".into(),
+                update_index: 2,
+                line: Some(line(id, 1_700_000_000, 5 * 3600 + 30 * 60, "commit: east")),
+            },
+            LogRecord {
+                name: "refs/heads/main".into(),
+                update_index: 3,
+                line: Some(line(id, 1_700_003_600, -8 * 3600, "commit: west")),
+            },
+            LogRecord {
+                name: "refs/heads/gone".into(),
+                update_index: 1,
+                line: None,
+            },
+        ],
+    }
+}
+
+#[test]
+fn table_roundtrip() -> crate::Result {
+    let table = sample_table();
+    let mut buf = Vec::new();
+    table.write_to(&mut buf, gix_hash::Kind::Sha1)?;
+    assert_eq!(&buf[..4], b"REFT");
+    let actual = Table::from_bytes(&buf, gix_hash::Kind::Sha1)?;
+    assert_eq!(sorted(actual), sorted(table));
+    Ok(())
+}
+
+#[test]
+fn stacked_tables_override_older_ones() -> crate::Result {
+    let tmp = gix_testtools::tempfile::TempDir::new()?;
+    let dir = tmp.path().join("reftable");
+    std::fs::create_dir(&dir)?;
+    let lock = |stack: &Stack| {
+        gix_lock::File::acquire_to_update_resource(stack.list_path(), gix_lock::acquire::Fail::Immediately, None)
+    };
+
+    let stack = Stack::empty(dir.clone(), gix_hash::Kind::Sha1);
+    let first = Table {
+        min_update_index: 1,
+        max_update_index: 1,
+        refs: vec![RefRecord {
+            name: "refs/heads/gone".into(),
+            update_index: 1,
+            value: Value::Object(hex_to_id("4c3f4cce493d7beb45012e478021b5f65295e5a3")),
+        }],
+        logs: vec![LogRecord {
+            name: "refs/heads/gone".into(),
+            update_index: 1,
+            line: Some(line("4c3f4cce493d7beb45012e478021b5f65295e5a3", 1, 0, "created")),
+        }],
+    };
+    stack.add_table(&first, lock(&stack)?, Default::default())?;
+    let stack = Stack::at(dir.clone(), gix_hash::Kind::Sha1)?.expect("tables.list was written");
+    assert!(stack.log_exists("refs/heads/gone".try_into()?));
+
+    let err = stack.add_table(&first, lock(&stack)?, Default::default()).unwrap_err();
+    assert!(
+        err.to_string().starts_with("The update indices 1..=1"),
+        "update indices must grow"
+    );
+
+    stack.add_table(&sample_table(), lock(&stack)?, Default::default())?;
+    let stack = Stack::at(dir, gix_hash::Kind::Sha1)?.expect("still present");
+    assert_eq!(stack.tables().len(), 2);
+    assert_eq!(stack.max_update_index(), 3);
+    assert_eq!(
+        stack.iter().map(|r| r.name.as_bstr().to_owned()).collect::<Vec<_>>(),
+        ["HEAD", "refs/heads/main", "refs/tags/v1"],
+        "the deletion shadows the reference of the older table"
+    );
+    assert!(
+        !stack.log_exists("refs/heads/gone".try_into()?),
+        "log entries can be deleted as well"
+    );
+    let offsets: Vec<_> = stack
+        .log("refs/heads/main".try_into()?)
+        .expect("present")
+        .map(|(_, line)| line.signature.time.offset)
+        .collect();
+    assert_eq!(offsets, [5 * 3600 + 30 * 60, -8 * 3600]);
+    Ok(())
+}
+
+mod written_by_git {
+    use std::process::Command;
+
+    use gix_ref::{
+        reftable::{LogRecord, RefRecord, Stack, Table, Value},
+        FullNameRef, Target,
+    };
+
+    fn git_supports_reftable() -> bool {
+        !gix_testtools::should_skip_as_git_version_is_smaller_than(2, 45, 0)
+    }
+
+    fn target(stack: &Stack, name: &str) -> crate::Result<Target> {
+        let name: &FullNameRef = name.try_into()?;
+        Ok(stack.try_find(name).expect("present").target)
+    }
+
+    #[test]
+    fn read() -> crate::Result {
+        if !git_supports_reftable() {
+            return Ok(());
+        }
+        let dir = gix_testtools::scripted_fixture_read_only_standalone("make_reftable_repository.sh")?;
+        let stack = Stack::at(dir.join("repo/.git/reftable"), gix_hash::Kind::Sha1)?.expect("written by git");
+
+        assert_eq!(target(&stack, "HEAD")?, Target::Symbolic("refs/heads/main".try_into()?));
+        let main = target(&stack, "refs/heads/main")?;
+        assert_eq!(target(&stack, "refs/tags/light")?, main);
+        let annotated = stack.try_find("refs/tags/annotated".try_into()?).expect("present");
+        assert_eq!(
+            annotated.peeled.map(Target::Object),
+            Some(main.clone()),
+            "git stores peeled tags"
+        );
+
+        let log: Vec<_> = stack
+            .log("refs/heads/main".try_into()?)
+            .expect("present")
+            .map(|(_, line)| line.clone())
+            .collect();
+        assert_eq!(log.len(), 2);
+        assert_eq!(Target::Object(log[1].new_oid), main);
+        assert_eq!(log[1].previous_oid, log[0].new_oid);
+        assert_eq!(log[0].message, "commit (initial): c1");
+        assert_eq!(
+            log.iter().map(|line| line.signature.time.offset).collect::<Vec<_>>(),
+            [5 * 3600 + 30 * 60, -8 * 3600],
+            "time zones are read from their hhmm form"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn write() -> crate::Result {
+        if !git_supports_reftable() {
+            return Ok(());
+        }
+        let dir = gix_testtools::scripted_fixture_writable_standalone("make_reftable_repository.sh")?;
+        let repo = dir.path().join("repo");
+        let stack = Stack::at(repo.join(".git/reftable"), gix_hash::Kind::Sha1)?.expect("written by git");
+        let Target::Object(main) = target(&stack, "refs/heads/main")? else {
+            unreachable!("a branch")
+        };
+
+        let update_index = stack.max_update_index() + 1;
+        let mut line = super::line(&main.to_string(), 1_700_007_200, 5 * 3600 + 45 * 60, "gix: moved");
+        line.previous_oid = match target(&stack, "refs/heads/feature")? {
+            Target::Object(id) => id,
+            Target::Symbolic(_) => unreachable!("a branch"),
+        };
+        let table = Table {
+            min_update_index: update_index,
+            max_update_index: update_index,
+            refs: vec![RefRecord {
+                name: "refs/heads/feature".into(),
+                update_index,
+                value: Value::Object(main),
+            }],
+            logs: vec![LogRecord {
+                name: "refs/heads/feature".into(),
+                update_index,
+                line: Some(line),
+            }],
+        };
+        let lock =
+            gix_lock::File::acquire_to_update_resource(stack.list_path(), gix_lock::acquire::Fail::Immediately, None)?;
+        stack.add_table(&table, lock, Default::default())?;
+
+        let git = |args: &[&str]| -> crate::Result<String> {
+            let out = Command::new("git").args(args).current_dir(&repo).output()?;
+            assert!(out.status.success(), "{out:?}");
+            Ok(String::from_utf8(out.stdout)?)
+        };
+        assert_eq!(
+            git(&["rev-parse", "feature"])?.trim(),
+            main.to_string(),
+            "git sees the table gix added"
+        );
+        let reflog = git(&["reflog", "show", "--date=iso", "--format=%gd %gs", "feature"])?;
+        assert_eq!(
+            reflog.lines().next(),
+            Some("feature@{2023-11-15 05:58:20 +0545} gix: moved"),
+            "the time zone is written in the hhmm form git expects"
+        );
+        Ok(())
+    }
+}
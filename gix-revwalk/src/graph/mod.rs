@@ -137,7 +137,10 @@ impl<'cache, T> Graph<'_, 'cache, T> {
         first_parent: bool,
     ) -> Result<(), insert_parents::Error> {
         let commit = self.lookup(id)?;
-        let parents: SmallVec<[_; 2]> = commit.iter_parents().collect();
+        let mut parents: SmallVec<[_; 2]> = commit.iter_parents().collect();
+        if let Some(overridden) = self.overridden_parents(id) {
+            parents = overridden.map(Ok).collect();
+        }
         for parent_id in parents {
             let parent_id = parent_id?;
             match self.map.entry(parent_id) {
@@ -178,7 +181,10 @@ impl<'cache, T> Graph<'_, 'cache, T> {
             + From<commit::iter_parents::Error>,
     {
         let commit = self.lookup(id).map_err(E::from)?;
-        let parents: SmallVec<[_; 2]> = commit.iter_parents().collect();
+        let mut parents: SmallVec<[_; 2]> = commit.iter_parents().collect();
+        if let Some(overridden) = self.overridden_parents(id) {
+            parents = overridden.map(Ok).collect();
+        }
         for parent_id in parents {
             let parent_id = parent_id.map_err(E::from)?;
             let parent = match try_lookup(&parent_id, &*self.find, self.cache, &mut self.parent_buf).map_err(E::from)? {
@@ -221,8 +227,26 @@ impl<'find, 'cache, T> Graph<'find, 'cache, T> {
             map: gix_hashtable::HashMap::default(),
             buf: Vec::new(),
             parent_buf: Vec::new(),
+            parents: None,
         }
     }
+
+    /// Use `parents` to override the parents recorded in commits, typically to apply grafts and the shallow boundary.
+    ///
+    /// Note that it affects all methods that provide parents, but not [`LazyCommit::iter_parents()`] which always
+    /// returns the parents as recorded in the commit.
+    pub fn with_parents(mut self, parents: impl crate::Parents + 'find) -> Self {
+        self.parents = Some(Box::new(parents));
+        self
+    }
+
+    /// Return the parents of `id` as provided by our parents provider, or `None` if the recorded parents should be used.
+    fn overridden_parents(&self, id: &gix_hash::oid) -> Option<impl Iterator<Item = gix_hash::ObjectId> + '_> {
+        self.parents
+            .as_ref()
+            .and_then(|p| p.parents_of(id))
+            .map(|ids| ids.iter().copied())
+    }
 }
 
 /// Commit based methods
@@ -246,6 +270,9 @@ impl<T> Graph<'_, '_, Commit<T>> {
                     Some(commit) => commit,
                 };
                 let mut commit = commit.to_owned(new_data)?;
+                if let Some(parents) = self.parents.as_ref().and_then(|p| p.parents_of(&id)) {
+                    commit.parents = parents.iter().copied().collect();
+                }
                 update_data(&mut commit.data);
                 entry.insert(commit);
             }
@@ -297,6 +324,9 @@ impl<T: Default> Graph<'_, '_, Commit<T>> {
                     Some(commit) => commit,
                 };
                 let mut commit = commit.to_owned(T::default)?;
+                if let Some(parents) = self.parents.as_ref().and_then(|p| p.parents_of(&id)) {
+                    commit.parents = parents.iter().copied().collect();
+                }
                 update_commit(&mut commit);
                 entry.insert(commit);
             }
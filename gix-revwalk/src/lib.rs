@@ -15,6 +15,11 @@
 /// implemented by the `find` function that returns objects. Also we assume that the commitgraph as been written with replacements
 /// active to provide a consistent view.
 ///
+/// ### About grafts and shallow commits
+///
+/// Use [`Graph::with_parents()`] to have a [`Parents`] implementation like [`parents::Grafts`] override the parents of commits,
+/// which is how grafts and the shallow boundary are taken into account.
+///
 /// ### Odb or `find` configuration
 ///
 /// The `find` handle should be setup to *quickly determine if an object exists or not* to assure quick operation *on shallow repositories*.
@@ -36,8 +41,42 @@ pub struct Graph<'find, 'cache, T> {
     buf: Vec<u8>,
     /// Another buffer we typically use to store parents.
     parent_buf: Vec<u8>,
+    /// If set, the provider of parents to use instead of the ones recorded in commits.
+    parents: Option<Box<dyn Parents + 'find>>,
+}
+
+/// A provider of commit parents that take precedence over the ones recorded in the commits themselves.
+///
+/// This is how grafts and the shallow boundary are applied uniformly across all algorithms that traverse the commit graph,
+/// while object replacements are expected to be handled by the object database itself.
+pub trait Parents {
+    /// Return the parents that commit `id` is supposed to have, or `None` if the parents recorded in the commit should be used.
+    ///
+    /// An empty slice makes the commit appear to have no parents at all, which is the case for commits on the shallow boundary.
+    fn parents_of(&self, id: &gix_hash::oid) -> Option<&[gix_hash::ObjectId]>;
+}
+
+impl<T: Parents + ?Sized> Parents for &T {
+    fn parents_of(&self, id: &gix_hash::oid) -> Option<&[gix_hash::ObjectId]> {
+        (*self).parents_of(id)
+    }
 }
 
+impl<T: Parents + ?Sized> Parents for Box<T> {
+    fn parents_of(&self, id: &gix_hash::oid) -> Option<&[gix_hash::ObjectId]> {
+        (**self).parents_of(id)
+    }
+}
+
+impl<T: Parents + ?Sized> Parents for std::sync::Arc<T> {
+    fn parents_of(&self, id: &gix_hash::oid) -> Option<&[gix_hash::ObjectId]> {
+        (**self).parents_of(id)
+    }
+}
+
+///
+pub mod parents;
+
 ///
 pub mod graph;
 
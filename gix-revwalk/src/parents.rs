@@ -0,0 +1,100 @@
+use gix_hash::{oid, ObjectId};
+use smallvec::SmallVec;
+
+use crate::Parents;
+
+/// The parents a commit should have instead of the ones recorded in it.
+pub type Graft = SmallVec<[ObjectId; 1]>;
+
+/// A set of parent overrides keyed by commit, built from the `info/grafts` file and the shallow boundary of a repository.
+///
+/// It implements [`Parents`] and thus can be passed to all traversals to make them see the same history as `git` would.
+#[derive(Default, Debug, Clone, PartialEq, Eq)]
+pub struct Grafts {
+    map: gix_hashtable::HashMap<ObjectId, Graft>,
+}
+
+///
+pub mod decode {
+    /// The error returned by [`Grafts::from_bytes()`](super::Grafts::from_bytes()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("Could not decode object id on line {line_number} of the grafts file")]
+        DecodeHash {
+            line_number: usize,
+            source: gix_hash::decode::Error,
+        },
+    }
+}
+
+/// Initialization
+impl Grafts {
+    /// Parse the contents of an `info/grafts` file from `data`, where each line is a commit id followed by zero or more
+    /// parent ids, all separated by a single space.
+    ///
+    /// Empty lines and lines starting with `#` are ignored, just like `git` does.
+    pub fn from_bytes(data: &[u8]) -> Result<Self, decode::Error> {
+        let mut out = Grafts::default();
+        for (idx, line) in data.split(|b| *b == b'\n').enumerate() {
+            let line = line.strip_suffix(b"\r").unwrap_or(line);
+            if line.is_empty() || line.starts_with(b"#") {
+                continue;
+            }
+            let mut ids = line.split(|b| *b == b' ').filter(|token| !token.is_empty()).map(|hex| {
+                ObjectId::from_hex(hex).map_err(|source| decode::Error::DecodeHash {
+                    line_number: idx + 1,
+                    source,
+                })
+            });
+            let Some(id) = ids.next().transpose()? else {
+                continue;
+            };
+            let parents = ids.collect::<Result<Graft, _>>()?;
+            out.map.insert(id, parents);
+        }
+        Ok(out)
+    }
+}
+
+/// Mutation
+impl Grafts {
+    /// Let the commit `id` have `parents` instead of the ones recorded in it, returning the previous graft of `id` if there was one.
+    pub fn insert(&mut self, id: ObjectId, parents: impl IntoIterator<Item = ObjectId>) -> Option<Graft> {
+        self.map.insert(id, parents.into_iter().collect())
+    }
+
+    /// Declare all `commits` as part of the shallow boundary, which makes them appear to have no parents.
+    ///
+    /// As this overrides previous grafts of the same commits, it should be called after all grafts were added
+    /// to have the shallow boundary take precedence, which is what `git` does.
+    pub fn insert_shallow(&mut self, commits: impl IntoIterator<Item = ObjectId>) {
+        for id in commits {
+            self.map.insert(id, Graft::new());
+        }
+    }
+}
+
+/// Access
+impl Grafts {
+    /// Return the amount of commits whose parents are overridden.
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    /// Return `true` if no commit has its parents overridden.
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    /// Return an iterator over all commits along with the parents they are supposed to have, in no particular order.
+    pub fn iter(&self) -> impl Iterator<Item = (&oid, &[ObjectId])> {
+        self.map.iter().map(|(id, parents)| (id.as_ref(), parents.as_slice()))
+    }
+}
+
+impl Parents for Grafts {
+    fn parents_of(&self, id: &oid) -> Option<&[ObjectId]> {
+        self.map.get(id).map(SmallVec::as_slice)
+    }
+}
@@ -13,3 +13,51 @@ mod graph {
         }
     }
 }
+
+mod parents {
+    mod grafts {
+        use gix_hash::ObjectId;
+        use gix_revwalk::{parents::Grafts, Parents};
+
+        fn id(hex: &str) -> ObjectId {
+            ObjectId::from_hex(hex.as_bytes()).expect("valid hex")
+        }
+
+        #[test]
+        fn from_bytes_and_shallow_precedence() -> Result<(), Box<dyn std::error::Error>> {
+            let a = "aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa";
+            let b = "bbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb";
+            let c = "cccccccccccccccccccccccccccccccccccccccc";
+            let mut grafts = Grafts::from_bytes(format!("# comment\n\n{a} {b} {c}\r\n{b}\n").as_bytes())?;
+            assert_eq!(grafts.len(), 2);
+            assert_eq!(grafts.parents_of(&id(a)), Some([id(b), id(c)].as_slice()));
+            assert_eq!(
+                grafts.parents_of(&id(b)),
+                Some([].as_slice()),
+                "grafts can remove all parents"
+            );
+            assert_eq!(
+                grafts.parents_of(&id(c)),
+                None,
+                "commits without graft use their own parents"
+            );
+
+            grafts.insert_shallow(Some(id(a)));
+            assert_eq!(
+                grafts.parents_of(&id(a)),
+                Some([].as_slice()),
+                "the shallow boundary overrides grafts"
+            );
+            Ok(())
+        }
+
+        #[test]
+        fn from_bytes_fails_on_invalid_hex() {
+            let err = Grafts::from_bytes(b"\nnot-a-hash\n").unwrap_err();
+            assert_eq!(
+                err.to_string(),
+                "Could not decode object id on line 2 of the grafts file"
+            );
+        }
+    }
+}
@@ -10,18 +10,49 @@ pub struct Account {
     pub password: String,
 }
 
+/// Describes who owns a path in relation to the user executing the current process.
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Ownership {
+    /// The path is owned by the user executing the current process.
+    ///
+    /// On Windows, this is also the case for the user's home directory, which is de-facto owned by them.
+    CurrentUser,
+    /// The path is owned by the user who invoked the current process through `sudo`, as indicated by `SUDO_UID`.
+    SudoUser,
+    /// The path is owned by the administrators group, and the user executing the current process is a member of it.
+    Administrators,
+    /// The path is owned by someone else.
+    Other,
+}
+
+impl Ownership {
+    /// Return `true` if the path is considered owned by the user executing the current process,
+    /// which is the case for all ownerships but [`Other`][Ownership::Other].
+    pub fn is_current_user(&self) -> bool {
+        !matches!(self, Ownership::Other)
+    }
+}
+
 /// Returns true if the given `path` is owned by the user who is executing the current process.
 ///
 /// Note that this method is very specific to avoid having to deal with any operating system types.
 pub fn is_path_owned_by_current_user(path: &Path) -> std::io::Result<bool> {
-    impl_::is_path_owned_by_current_user(path)
+    Ok(path_ownership(path)?.is_current_user())
+}
+
+/// Determine the [ownership][Ownership] of `path` in relation to the user executing the current process.
+///
+/// Symlinks are not followed, and the owner of the symlink itself is checked.
+pub fn path_ownership(path: &Path) -> std::io::Result<Ownership> {
+    impl_::path_ownership(path)
 }
 
 // Wasi doesn't have a concept of a user, so this is implicitly true.
 #[cfg(target_os = "wasi")]
 mod impl_ {
-    pub fn is_path_owned_by_current_user(_path: &std::path::Path) -> std::io::Result<bool> {
-        Ok(true)
+    pub fn path_ownership(_path: &std::path::Path) -> std::io::Result<super::Ownership> {
+        Ok(super::Ownership::CurrentUser)
     }
 }
 
@@ -29,7 +60,9 @@ mod impl_ {
 mod impl_ {
     use std::path::Path;
 
-    pub fn is_path_owned_by_current_user(path: &Path) -> std::io::Result<bool> {
+    use super::Ownership;
+
+    pub fn path_ownership(path: &Path) -> std::io::Result<Ownership> {
         fn owner_from_path(path: &Path) -> std::io::Result<u32> {
             use std::os::unix::fs::MetadataExt;
             let meta = std::fs::symlink_metadata(path)?;
@@ -47,13 +80,14 @@ mod impl_ {
         let owner_of_path = owner_from_path(path)?;
         let owner_of_process = owner_of_current_process()?;
         if owner_of_path == owner_of_process {
-            Ok(true)
-        } else if let Some(sudo_uid) =
-            std::env::var_os("SUDO_UID").and_then(|val| val.to_str().and_then(|val_str| u32::from_str(val_str).ok()))
+            Ok(Ownership::CurrentUser)
+        } else if std::env::var_os("SUDO_UID")
+            .and_then(|val| val.to_str().and_then(|val_str| u32::from_str(val_str).ok()))
+            .map_or(false, |sudo_uid| owner_of_path == sudo_uid)
         {
-            Ok(owner_of_path == sudo_uid)
+            Ok(Ownership::SudoUser)
         } else {
-            Ok(false)
+            Ok(Ownership::Other)
         }
     }
 }
@@ -68,6 +102,8 @@ mod impl_ {
         ptr,
     };
 
+    use super::Ownership;
+
     macro_rules! error {
         ($msg:expr) => {{
             let inner = io::Error::last_os_error();
@@ -78,7 +114,7 @@ mod impl_ {
         }};
     }
 
-    pub fn is_path_owned_by_current_user(path: &Path) -> io::Result<bool> {
+    pub fn path_ownership(path: &Path) -> io::Result<Ownership> {
         use windows_sys::Win32::{
             Foundation::{GetLastError, LocalFree, ERROR_INSUFFICIENT_BUFFER, ERROR_SUCCESS},
             Security::{
@@ -101,7 +137,7 @@ mod impl_ {
         // but it can be considered de-facto owned by the user
         // Ignore errors here and just do the regular checks below
         if gix_path::realpath(path).ok() == gix_path::env::home_dir() {
-            return Ok(true);
+            return Ok(Ownership::CurrentUser);
         }
 
         #[allow(unsafe_code)]
@@ -191,12 +227,12 @@ mod impl_ {
             // If the current user is the owner of the parent folder then they also
             // own this file
             if EqualSid(folder_owner, token_owner) != 0 {
-                return Ok(true);
+                return Ok(Ownership::CurrentUser);
             }
 
             // Admin-group owned folders are considered owned by the current user, if they are in the admin group
             if IsWellKnownSid(token_owner, WinBuiltinAdministratorsSid) == 0 {
-                return Ok(false);
+                return Ok(Ownership::Other);
             }
 
             let mut is_member = 0;
@@ -204,7 +240,11 @@ mod impl_ {
                 error!("Couldn't check if user is an administrator");
             }
 
-            Ok(is_member != 0)
+            Ok(if is_member != 0 {
+                Ownership::Administrators
+            } else {
+                Ownership::Other
+            })
         }
     }
 
@@ -3,11 +3,16 @@ use crate::Trust;
 impl Trust {
     /// Derive `Full` trust if `path` is owned by the user executing the current process, or `Reduced` trust otherwise.
     pub fn from_path_ownership(path: &std::path::Path) -> std::io::Result<Self> {
-        Ok(if crate::identity::is_path_owned_by_current_user(path)? {
+        Ok(Self::from_ownership(crate::identity::path_ownership(path)?))
+    }
+
+    /// Derive `Full` trust if `ownership` indicates that the current user owns a path, or `Reduced` trust otherwise.
+    pub fn from_ownership(ownership: crate::identity::Ownership) -> Self {
+        if ownership.is_current_user() {
             Trust::Full
         } else {
             Trust::Reduced
-        })
+        }
     }
 }
 
@@ -8,6 +8,22 @@ fn is_path_owned_by_current_user() -> crate::Result {
     Ok(())
 }
 
+#[test]
+fn path_ownership() -> crate::Result {
+    use gix_sec::identity::Ownership;
+    let dir = tempfile::tempdir()?;
+    let ownership = gix_sec::identity::path_ownership(dir.path())?;
+    assert_eq!(ownership, Ownership::CurrentUser);
+    assert!(ownership.is_current_user());
+    assert!(!Ownership::Other.is_current_user());
+    assert_eq!(gix_sec::Trust::from_ownership(ownership), gix_sec::Trust::Full);
+    assert_eq!(
+        gix_sec::Trust::from_ownership(Ownership::Other),
+        gix_sec::Trust::Reduced
+    );
+    Ok(())
+}
+
 #[test]
 #[cfg(windows)]
 fn windows_home() -> crate::Result {
@@ -116,6 +116,7 @@ where
                 State {
                     buf: Vec::new(),
                     buf2: Vec::new(),
+                    reader: Default::default(),
                     attr_stack: stack,
                     path_stack: SymlinkCheck::new(worktree.into()),
                     timestamp,
@@ -217,6 +218,8 @@ where
 struct State<'a, 'b> {
     buf: Vec<u8>,
     buf2: Vec<u8>,
+    /// Reads large worktree files by memory-mapping them.
+    reader: crate::worktree_file::Reader,
     timestamp: FileTime,
     /// This is the cheap stack that only assure that we don't go through symlinks.
     /// It's always used to get the path to perform an lstat on.
@@ -433,6 +436,7 @@ impl<'index> State<'_, 'index> {
         };
         let fetch_data = ReadDataImpl {
             buf: &mut self.buf,
+            reader: &mut self.reader,
             path: worktree_path,
             rela_path,
             entry,
@@ -501,6 +505,7 @@ where
     Find: gix_object::Find,
 {
     buf: &'a mut Vec<u8>,
+    reader: &'a mut crate::worktree_file::Reader,
     path: &'a Path,
     rela_path: &'a BStr,
     file_len: u64,
@@ -552,18 +557,31 @@ where
             let platform = self
                 .attr_stack
                 .at_entry(self.rela_path, Some(self.entry.mode), &self.objects)?;
-            let file = std::fs::File::open(self.path)?;
-            let out = self
-                .filter
-                .convert_to_git(
-                    file,
-                    self.path,
-                    &mut |_path, attrs| {
-                        platform.matching_attributes(attrs);
-                    },
-                    &mut |buf| Ok(self.objects.find_blob(self.id, buf).map(|_| Some(()))?),
-                )
-                .map_err(|err| io::Error::new(io::ErrorKind::Other, err))?;
+            let mut file = std::fs::File::open(self.path)?;
+            let objects = &self.objects;
+            let id = self.id;
+            let mut attributes = |_path: &BStr, attrs: &mut gix_filter::attributes::search::Outcome| {
+                platform.matching_attributes(attrs);
+            };
+            let mut index_object = |buf: &mut Vec<u8>| -> Result<Option<()>, gix_object::find::Error> {
+                Ok(objects.find_blob(id, buf).map(|_| Some(()))?)
+            };
+            let out = if self.reader.is_mapped(self.file_len) {
+                let data = self.reader.read(&mut file, self.file_len)?;
+                match self
+                    .filter
+                    .convert_to_git(data, self.path, &mut attributes, &mut index_object)
+                    .map_err(|err| io::Error::new(io::ErrorKind::Other, err))?
+                {
+                    ToGitOutcome::Unchanged(data) => ToGitOutcome::Buffer(data),
+                    ToGitOutcome::Process(stream) => ToGitOutcome::Process(stream),
+                    ToGitOutcome::Buffer(data) => ToGitOutcome::Buffer(data),
+                }
+            } else {
+                self.filter
+                    .convert_to_git(file, self.path, &mut attributes, &mut index_object)
+                    .map_err(|err| io::Error::new(io::ErrorKind::Other, err))?
+            };
             let len = match out {
                 ToGitOutcome::Unchanged(_) => Some(self.file_len),
                 ToGitOutcome::Process(_) | ToGitOutcome::Buffer(_) => None,
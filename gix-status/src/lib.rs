@@ -29,6 +29,8 @@ pub mod index_as_worktree_with_renames;
 #[cfg(feature = "worktree-rewrites")]
 pub use index_as_worktree_with_renames::function::index_as_worktree_with_renames;
 
+pub mod worktree_file;
+
 /// A stack that validates we are not going through a symlink in a way that is read-only.
 ///
 /// It can efficiently validate paths when these are queried in sort-order, which leads to each component
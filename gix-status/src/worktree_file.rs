@@ -0,0 +1,55 @@
+//! Read worktree files to hash them, the same way for all operations that need to know their object ids.
+use std::io::Read;
+
+/// Files at least this many bytes large are memory-mapped instead of being read into memory by default.
+pub const DEFAULT_MMAP_THRESHOLD: u64 = 1024 * 1024;
+
+/// A reader for worktree files which reuses its buffer, and memory-maps large files instead of reading them.
+///
+/// It's used when [comparing worktree files to index entries](crate::index_as_worktree()), and to hash worktree files
+/// for adding them to the index.
+pub struct Reader {
+    buf: Vec<u8>,
+    mapped: Option<memmap2::Mmap>,
+    mmap_threshold: u64,
+}
+
+impl Default for Reader {
+    fn default() -> Self {
+        Reader::new(DEFAULT_MMAP_THRESHOLD)
+    }
+}
+
+impl Reader {
+    /// Create a new instance which memory-maps all files that are at least `mmap_threshold` bytes large.
+    pub fn new(mmap_threshold: u64) -> Self {
+        Reader {
+            buf: Vec::new(),
+            mapped: None,
+            mmap_threshold,
+        }
+    }
+
+    /// Return `true` if a file of `len` bytes would be memory-mapped.
+    pub fn is_mapped(&self, len: u64) -> bool {
+        len >= self.mmap_threshold
+    }
+
+    /// Read all data of `file`, which is `len` bytes large, and return it.
+    ///
+    /// The data remains valid until the next call.
+    pub fn read(&mut self, file: &mut std::fs::File, len: u64) -> std::io::Result<&[u8]> {
+        self.mapped = None;
+        if self.is_mapped(len) {
+            // SAFETY: the file could be changed while it's mapped, which at worst produces a hash that doesn't match
+            //         any version of it, just like it would if it changed while being read.
+            #[allow(unsafe_code)]
+            let mapped = unsafe { memmap2::Mmap::map(&*file)? };
+            Ok(self.mapped.insert(mapped))
+        } else {
+            self.buf.clear();
+            file.read_to_end(&mut self.buf)?;
+            Ok(&self.buf)
+        }
+    }
+}
@@ -0,0 +1,13 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+git init -q
+
+# Large enough to be memory-mapped when reading them.
+head -c 2000000 /dev/zero > unchanged
+head -c 2000000 /dev/zero > modified
+
+git add -A
+git commit -q -m "Commit"
+
+{ head -c 1999999 /dev/zero; echo -n "x"; } > modified
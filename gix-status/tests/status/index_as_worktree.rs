@@ -486,6 +486,36 @@ fn refresh() {
     );
 }
 
+#[test]
+fn large_files_are_compared_by_content() {
+    let expected_outcome = Outcome {
+        entries_to_process: 2,
+        entries_processed: 2,
+        symlink_metadata_calls: 2,
+        ..Default::default()
+    };
+    assert_eq!(
+        fixture_with_index(
+            "status_large_files",
+            |index| index
+                .entries_mut()
+                .iter_mut()
+                .for_each(|e| e.stat.mtime = Default::default()),
+            &[(
+                BStr::new(b"modified"),
+                0,
+                Change::Modification {
+                    executable_bit_changed: false,
+                    content_change: Some(()),
+                    set_entry_stat_size_zero: false
+                }
+                .into(),
+            )],
+        ),
+        expected_outcome,
+    );
+}
+
 #[test]
 fn modified() {
     let expected_outcome = Outcome {
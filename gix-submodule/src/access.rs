@@ -145,7 +145,36 @@ impl File {
     }
 
     /// Retrieve the `url` field of the submodule named `name`. It's an error if it doesn't exist or is empty.
+    ///
+    /// Like `git fsck`, URLs that look like command-line options are rejected, along with URLs that could be used to
+    /// inject newlines into the communication with credential helpers or to escape their host once resolved.
+    /// Note that relative URLs, i.e. those starting with `./` or `../`, are returned as is, use
+    /// [`resolved_url()`](Self::resolved_url()) to resolve them.
     pub fn url(&self, name: &BStr) -> Result<gix_url::Url, config::url::Error> {
+        let url = self.validated_url(name)?;
+        Self::parse_url(name, url.as_ref())
+    }
+
+    /// Like [`url()`](Self::url()), but if the URL is relative, i.e. starts with `./` or `../`, resolve it against `base`,
+    /// the URL of the superproject's default remote, just like `git submodule init` does.
+    ///
+    /// If the superproject has no remote, `git` uses the path to its worktree as `base` instead.
+    /// Note that if `base` is a relative path, the resolved URL is relative as well.
+    pub fn resolved_url(&self, name: &BStr, base: &BStr) -> Result<gix_url::Url, config::url::Error> {
+        let url = self.validated_url(name)?;
+        if !crate::url::is_relative(url.as_ref()) {
+            return Self::parse_url(name, url.as_ref());
+        }
+        let resolved =
+            crate::url::resolve_relative(url.as_ref(), base).ok_or_else(|| config::url::Error::Relative {
+                submodule: name.to_owned(),
+                actual: url.clone().into_owned(),
+                base: base.to_owned(),
+            })?;
+        Self::parse_url(name, resolved.as_ref())
+    }
+
+    fn validated_url(&self, name: &BStr) -> Result<Cow<'_, BStr>, config::url::Error> {
         let url = self
             .config
             .string(format!("submodule.{name}.url"))
@@ -158,7 +187,21 @@ impl File {
                 submodule: name.to_owned(),
             });
         }
-        gix_url::Url::from_bytes(url.as_ref()).map_err(|err| config::url::Error::Parse {
+        match crate::url::validate(url.as_ref()) {
+            Ok(()) => Ok(url),
+            Err(crate::url::Unsafe::CommandLineOption) => Err(config::url::Error::CommandLineOption {
+                submodule: name.to_owned(),
+                actual: url.into_owned(),
+            }),
+            Err(crate::url::Unsafe::Other) => Err(config::url::Error::Unsafe {
+                submodule: name.to_owned(),
+                actual: url.into_owned(),
+            }),
+        }
+    }
+
+    fn parse_url(name: &BStr, url: &BStr) -> Result<gix_url::Url, config::url::Error> {
+        gix_url::Url::from_bytes(url).map_err(|err| config::url::Error::Parse {
             submodule: name.to_owned(),
             source: err,
         })
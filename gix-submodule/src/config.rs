@@ -182,7 +182,7 @@ pub mod update {
 pub mod url {
     use bstr::BString;
 
-    /// The error returned by [File::url()](crate::File::url).
+    /// The error returned by [File::url()](crate::File::url) and [File::resolved_url()](crate::File::resolved_url).
     #[derive(Debug, thiserror::Error)]
     #[allow(missing_docs)]
     pub enum Error {
@@ -193,6 +193,18 @@ pub mod url {
         },
         #[error("The submodule '{submodule}' was missing its 'url' field or it was empty")]
         Missing { submodule: BString },
+        #[error("The url '{actual}' of submodule '{submodule}' would be interpreted as command-line option")]
+        CommandLineOption { submodule: BString, actual: BString },
+        #[error(
+            "The url '{actual}' of submodule '{submodule}' is unsafe as it contains a newline or could escape its host"
+        )]
+        Unsafe { submodule: BString, actual: BString },
+        #[error("The relative url '{actual}' of submodule '{submodule}' has too many leading '../' to be resolved against '{base}'")]
+        Relative {
+            submodule: BString,
+            actual: BString,
+            base: BString,
+        },
     }
 }
 
@@ -17,6 +17,7 @@ pub struct File {
 }
 
 mod access;
+mod url;
 
 ///
 pub mod config;
@@ -0,0 +1,156 @@
+use bstr::{BStr, BString, ByteSlice, ByteVec};
+
+/// Return `true` if `url` starts with `./` or `../`, which makes it relative to the URL of the superproject's remote.
+pub(crate) fn is_relative(url: &BStr) -> bool {
+    strip_dot_slash(url).is_some() || strip_dot_dot_slash(url).is_some()
+}
+
+/// The reasons a submodule `url` isn't safe to use.
+pub(crate) enum Unsafe {
+    CommandLineOption,
+    Other,
+}
+
+/// Perform the checks of `git fsck` on `url` to assure it can't be used to inject command-line options, or to
+/// manipulate the credentials or host used for the submodule when it's resolved or passed to a transport.
+pub(crate) fn validate(url: &BStr) -> Result<(), Unsafe> {
+    if url.starts_with(b"-") {
+        return Err(Unsafe::CommandLineOption);
+    }
+    if is_relative(url) || url.starts_with(b"git://") {
+        // Relative URLs may be appended to an HTTP URL and be percent-decoded there.
+        if contains_newline(url) {
+            return Err(Unsafe::Other);
+        }
+        // URLs that escape their root via `../` can overwrite the host or the scheme, like in `https:///example.com`.
+        let (num_dotdots, rest) = count_leading_dotdots(url);
+        if num_dotdots > 0 && (rest.starts_with(b":") || rest.starts_with(b"/")) {
+            return Err(Unsafe::Other);
+        }
+    } else if let Some(url) = ["http::", "https::", "ftp::", "ftps::"]
+        .iter()
+        .find_map(|helper_prefix| url.strip_prefix(helper_prefix.as_bytes()))
+        .or_else(|| {
+            ["http://", "https://", "ftp://", "ftps://"]
+                .iter()
+                .any(|scheme| url.starts_with(scheme.as_bytes()))
+                .then_some(url.as_bytes())
+        })
+    {
+        // These URLs are used to query credential helpers, which must neither see newlines nor an empty host.
+        let Some(scheme_end) = url.find(b"://") else {
+            return Err(Unsafe::Other);
+        };
+        let authority = &url[scheme_end + 3..];
+        let authority = authority.find_byte(b'/').map_or(authority, |end| &authority[..end]);
+        let host = authority.rfind_byte(b'@').map_or(authority, |at| &authority[at + 1..]);
+        if host.is_empty() || contains_newline(url.as_bstr()) {
+            return Err(Unsafe::Other);
+        }
+    }
+    Ok(())
+}
+
+/// Resolve the relative submodule `url` against `base`, the URL of the superproject's default remote, like `git` does.
+///
+/// Return `None` if `url` has more leading `../` than `base` has components.
+pub(crate) fn resolve_relative(mut url: &BStr, base: &BStr) -> Option<BString> {
+    let mut base = base.to_owned();
+    if base.last().map_or(false, |b| is_dir_sep(*b)) {
+        base.pop();
+    }
+    let base_is_relative = is_local_not_ssh(base.as_ref()) && !gix_path::from_bstr(base.as_bstr()).is_absolute();
+    if base_is_relative && strip_dot_slash(base.as_ref()).is_none() && strip_dot_dot_slash(base.as_ref()).is_none() {
+        base.insert_str(0, "./");
+    }
+
+    let mut separate_with_colon = false;
+    loop {
+        if let Some(rest) = strip_dot_dot_slash(url) {
+            url = rest;
+            separate_with_colon |= chop_last_component(&mut base, base_is_relative)?;
+        } else if let Some(rest) = strip_dot_slash(url) {
+            url = rest;
+        } else {
+            break;
+        }
+    }
+
+    base.push(if separate_with_colon { b':' } else { b'/' });
+    base.push_str(url);
+    if url.ends_with(b"/") {
+        base.pop();
+    }
+    Some(match strip_dot_slash(base.as_ref()) {
+        Some(rest) => rest.to_owned(),
+        None => base,
+    })
+}
+
+/// Remove the last path component of `base`, or the path of an scp-like URL in which case `true` is returned.
+fn chop_last_component(base: &mut BString, base_is_relative: bool) -> Option<bool> {
+    if let Some(pos) = base.rfind_byteset(dir_separators()) {
+        base.truncate(pos);
+        return Some(false);
+    }
+    if let Some(pos) = base.rfind_byte(b':') {
+        base.truncate(pos);
+        return Some(true);
+    }
+    if base_is_relative || base == "." {
+        return None;
+    }
+    *base = ".".into();
+    Some(false)
+}
+
+/// Return `true` if `url` is a local path, and not an scp-like URL like `host:path`.
+fn is_local_not_ssh(url: &BStr) -> bool {
+    match url.find_byte(b':') {
+        None => true,
+        Some(colon) => {
+            url.find_byte(b'/').map_or(false, |slash| slash < colon)
+                || (cfg!(windows) && colon == 1 && url[0].is_ascii_alphabetic())
+        }
+    }
+}
+
+fn count_leading_dotdots(mut url: &BStr) -> (usize, &BStr) {
+    let mut count = 0;
+    loop {
+        if let Some(rest) = strip_dot_dot_slash(url) {
+            count += 1;
+            url = rest;
+        } else if let Some(rest) = strip_dot_slash(url) {
+            url = rest;
+        } else {
+            return (count, url);
+        }
+    }
+}
+
+fn contains_newline(url: &BStr) -> bool {
+    url.contains(&b'\n') || url.find("%0a").is_some() || url.find("%0A").is_some()
+}
+
+fn strip_dot_slash(url: &BStr) -> Option<&BStr> {
+    url.strip_prefix(b".")
+        .filter(|rest| rest.first().map_or(false, |b| is_dir_sep(*b)))
+        .map(|rest| rest[1..].as_bstr())
+}
+
+fn strip_dot_dot_slash(url: &BStr) -> Option<&BStr> {
+    url.strip_prefix(b".").and_then(|rest| strip_dot_slash(rest.as_bstr()))
+}
+
+fn dir_separators() -> &'static [u8] {
+    if cfg!(windows) {
+        b"/\\"
+    } else {
+        b"/"
+    }
+}
+
+fn is_dir_sep(b: u8) -> bool {
+    dir_separators().contains(&b)
+}
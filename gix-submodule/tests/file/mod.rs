@@ -236,6 +236,90 @@ mod url {
 
         assert!(matches!(submodule_url("file://"), Error::Parse { .. }));
     }
+
+    #[test]
+    fn unsafe_urls_are_rejected_like_git_fsck_does() {
+        for url in ["-upload-pack=evil", "--config=x"] {
+            assert!(
+                matches!(submodule_url(url), Error::CommandLineOption { .. }),
+                "{url}: would be passed as option"
+            );
+        }
+        for url in [
+            "./%0ahost=example.com",
+            "../%0Ahost=example.com",
+            "git://example.com:1234/repo%0a.git",
+            "https://one.example.com%0ahost=two.example.com/",
+            "https:///example.com/",
+            "https::example.com/",
+            "http::https:///example.com/",
+            "../../:localhost:8080/repo",
+            ".././../:localhost:8080/repo",
+            "../..//localhost/repo",
+        ] {
+            assert!(
+                matches!(submodule_url(url), Error::Unsafe { .. }),
+                "{url}: newlines could be injected, or the host could be changed"
+            );
+        }
+        for url in [
+            "https://example.com/repo",
+            "../sub",
+            "./sub/../other",
+            "file:///path/to/repo",
+            "git@example.com:repo",
+        ] {
+            let module = submodule(&format!("[submodule.a]\nurl = {url}"));
+            assert!(module.url("a".into()).is_ok(), "{url}: is safe to use");
+        }
+    }
+
+    #[test]
+    fn resolved_url_matches_git_submodule_init() -> crate::Result {
+        for (base, url, expected) in [
+            ("https://example.com/super", "./sub", "https://example.com/super/sub"),
+            ("https://example.com/super", "../sub", "https://example.com/sub"),
+            ("https://example.com/super/", "../sub/", "https://example.com/sub"),
+            ("https://example.com/super", "./../sub/x", "https://example.com/sub/x"),
+            ("https://example.com/super", "../../sub", "https://sub/"),
+            ("git@host:super", "./sub", "git@host:super/sub"),
+            ("git@host:super", "../sub", "git@host:sub"),
+            ("host:", "./sub", "host:/sub"),
+            ("host:", "../sub", "host:sub"),
+            ("/abs/super", "../sub", "/abs/sub"),
+            ("/abs/super", "../../sub", "/sub"),
+            ("./super", "./sub", "super/sub"),
+            ("super", "../sub", "sub"),
+            ("../super", "../sub/x", "../sub/x"),
+            (".", "./sub", "./sub"),
+            (".", "../sub", "sub"),
+            (
+                "/abs/super",
+                "https://example.com/absolute",
+                "https://example.com/absolute",
+            ),
+            ("/abs/super", "not-relative", "not-relative"),
+        ] {
+            let module = submodule(&format!("[submodule.a]\nurl = {url}"));
+            assert_eq!(
+                module.resolved_url("a".into(), base.into())?.to_bstring(),
+                expected,
+                "{url} resolved against {base}"
+            );
+        }
+
+        for (base, url) in [("super", "../../sub"), ("../super", "../../sub"), (".", "../../sub")] {
+            let module = submodule(&format!("[submodule.a]\nurl = {url}"));
+            assert!(
+                matches!(
+                    module.resolved_url("a".into(), base.into()).unwrap_err(),
+                    Error::Relative { .. }
+                ),
+                "{url} can't strip more components than {base} has"
+            );
+        }
+        Ok(())
+    }
 }
 
 mod update {
@@ -9,6 +9,7 @@ pub(crate) mod function {
     /// [local repositories][crate::client::file::connect()],
     /// [repositories over ssh][crate::client::ssh::connect()],
     /// [git daemons][crate::client::git::connect()],
+    /// [remote helpers][crate::client::helper::connect()] for all other schemes,
     /// and if compiled in connections to [git repositories over https][crate::client::http::connect()].
     ///
     /// Use `options` to further control specifics of the transport resulting from the connection.
@@ -19,7 +20,10 @@ pub(crate) mod function {
     {
         let mut url = url.try_into().map_err(gix_url::parse::Error::from)?;
         Ok(match url.scheme {
-            gix_url::Scheme::Ext(_) => return Err(Error::UnsupportedScheme(url.scheme)),
+            gix_url::Scheme::Ext(_) => Box::new(
+                crate::client::blocking_io::helper::connect(url, options.version, options.trace)
+                    .map_err(|e| Box::new(e) as Box<dyn std::error::Error + Send + Sync>)?,
+            ),
             gix_url::Scheme::File => {
                 if url.user().is_some() || url.password().is_some() || url.host().is_some() || url.port.is_some() {
                     return Err(Error::UnsupportedUrlTokens {
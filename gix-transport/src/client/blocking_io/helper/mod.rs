@@ -0,0 +1,871 @@
+use std::{
+    any::Any,
+    borrow::Cow,
+    ffi::OsString,
+    io::{BufRead, Read, Write},
+    path::{Path, PathBuf},
+    process::{self, Stdio},
+};
+
+use bstr::{BStr, BString, ByteSlice};
+
+use crate::{
+    client::{self, git, MessageKind, RequestWriter, SetServiceResponse, WriteMode},
+    Protocol, Service,
+};
+
+mod native;
+
+/// The error used when talking to a remote helper.
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("Failed to invoke remote helper {command:?}")]
+    InvokeProgram { source: std::io::Error, command: OsString },
+    #[error("An IO error occurred when communicating with the remote helper")]
+    Io(#[from] std::io::Error),
+    #[error("The remote helper does not support the {capability:?} capability which is needed for {command:?}")]
+    MissingCapability {
+        capability: &'static str,
+        command: &'static str,
+    },
+    #[error("The remote helper requires the {0:?} capability which isn't supported")]
+    UnsupportedMandatoryCapability(BString),
+    #[error("The remote helper can neither connect to the {service} service nor provide it with other commands")]
+    UnsupportedService { service: &'static str },
+    #[error("'git {command}' failed with {status} while talking to the remote helper")]
+    GitFailed {
+        command: &'static str,
+        status: process::ExitStatus,
+    },
+    #[error("Could not parse line {line:?} emitted by the remote helper in response to {command:?}")]
+    UnexpectedLine { line: BString, command: &'static str },
+    #[error("The remote helper exited before finishing its response to {command:?}")]
+    UnexpectedEof { command: &'static str },
+}
+
+/// The capabilities advertised by a remote helper in response to the `capabilities` command.
+#[derive(Default, Debug, Clone, PartialEq, Eq)]
+pub struct Capabilities {
+    /// The helper can connect to a `git` service, turning itself into a bidirectional pipe.
+    pub connect: bool,
+    /// The helper can connect to a `git` service for protocol V2, with each request being stateless.
+    pub stateless_connect: bool,
+    /// The helper can fetch objects using the `fetch` command.
+    pub fetch: bool,
+    /// The helper can push objects using the `push` command.
+    pub push: bool,
+    /// The helper can produce a `fast-import` stream with the `import` command.
+    pub import: bool,
+    /// The helper can consume a `fast-export` stream with the `export` command.
+    pub export: bool,
+    /// The helper supports the `option` command.
+    pub option: bool,
+    /// The helper can report connectivity of a fetched pack, allowing to skip the connectivity check.
+    pub check_connectivity: bool,
+    /// The helper can take a `--signed` option when pushing.
+    pub signed_tags: bool,
+    /// The refspecs to use when importing, mapping the refs of the remote into a private namespace.
+    pub refspecs: Vec<BString>,
+    /// The file to load marks from before an import or export, if set.
+    pub import_marks: Option<BString>,
+    /// The file to save marks to after an import or export, if set.
+    pub export_marks: Option<BString>,
+    /// Capabilities that are not known to us, but optional, with their value if present.
+    pub other: Vec<BString>,
+}
+
+impl Capabilities {
+    /// Parse all `lines` as sent by a remote helper in response to the `capabilities` command, without the terminating
+    /// empty line.
+    ///
+    /// Fail if the helper requires a capability that we don't know.
+    pub fn from_lines<'a>(lines: impl IntoIterator<Item = &'a BStr>) -> Result<Self, Error> {
+        let mut out = Capabilities::default();
+        for line in lines {
+            let (mandatory, line) = match line.strip_prefix(b"*") {
+                Some(line) => (true, line.as_bstr()),
+                None => (false, line),
+            };
+            let (name, value) = match line.find_byte(b' ') {
+                Some(pos) => (line[..pos].as_bytes(), Some(line[pos + 1..].as_bstr())),
+                None => (line.as_bytes(), None),
+            };
+            match (name, value) {
+                (b"connect", None) => out.connect = true,
+                (b"stateless-connect", None) => out.stateless_connect = true,
+                (b"fetch", None) => out.fetch = true,
+                (b"push", None) => out.push = true,
+                (b"import", None) => out.import = true,
+                (b"export", None) => out.export = true,
+                (b"option", None) => out.option = true,
+                (b"check-connectivity", None) => out.check_connectivity = true,
+                (b"signed-tags", None) => out.signed_tags = true,
+                (b"refspec", Some(spec)) => out.refspecs.push(spec.to_owned()),
+                (b"import-marks", Some(path)) => out.import_marks = Some(path.to_owned()),
+                (b"export-marks", Some(path)) => out.export_marks = Some(path.to_owned()),
+                _ if mandatory => return Err(Error::UnsupportedMandatoryCapability(line.to_owned())),
+                _ => out.other.push(line.to_owned()),
+            }
+        }
+        Ok(out)
+    }
+}
+
+/// A reference as listed by a remote helper in response to the `list` command.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Ref {
+    /// A reference pointing to an object.
+    Direct {
+        /// The full name of the reference, like `refs/heads/main`.
+        full_ref_name: BString,
+        /// The hexadecimal object id the reference points to.
+        object: BString,
+    },
+    /// A symbolic reference pointing to another reference, typically `HEAD`.
+    Symbolic {
+        /// The full name of the reference, like `HEAD`.
+        full_ref_name: BString,
+        /// The name of the reference it points to, like `refs/heads/main`.
+        target: BString,
+    },
+    /// A reference whose value the helper doesn't know, typical for helpers that `import`.
+    Unknown {
+        /// The full name of the reference.
+        full_ref_name: BString,
+    },
+}
+
+impl Ref {
+    /// Parse a single line of the `list` command output, or return `None` if the line is a keyword like `:object-format`
+    /// which doesn't name a reference.
+    /// Attributes like `unchanged` which may follow the reference name are ignored.
+    pub fn from_line(line: &BStr) -> Result<Option<Self>, Error> {
+        if line.starts_with(b":") {
+            return Ok(None);
+        }
+        let mut tokens = line.splitn_str(3, b" ");
+        let (value, full_ref_name) = match (tokens.next(), tokens.next()) {
+            (Some(value), Some(name)) if !value.is_empty() && !name.is_empty() => (value, name.as_bstr().to_owned()),
+            _ => {
+                return Err(Error::UnexpectedLine {
+                    line: line.to_owned(),
+                    command: "list",
+                })
+            }
+        };
+        Ok(Some(match value {
+            b"?" => Ref::Unknown { full_ref_name },
+            _ => match value.strip_prefix(b"@") {
+                Some(target) => Ref::Symbolic {
+                    full_ref_name,
+                    target: target.into(),
+                },
+                None => Ref::Direct {
+                    full_ref_name,
+                    object: value.into(),
+                },
+            },
+        }))
+    }
+}
+
+/// The answer of a remote helper to the `option` command.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OptionResponse {
+    /// The option was set.
+    Ok,
+    /// The option is not known to the helper.
+    Unsupported,
+    /// The option is known, but its value was rejected for the given reason.
+    Error(BString),
+}
+
+/// Options for the [transport][SpawnHelperOnDemand] of remote helpers, passed via
+/// [configure()][client::TransportWithoutIO::configure()].
+#[derive(Default, Debug, Clone)]
+pub struct Options {
+    /// The repository to fetch objects into and to push them from. Helpers without the `connect` capability need it
+    /// to transfer objects, and it's made available to them as `GIT_DIR`.
+    pub git_dir: Option<PathBuf>,
+    /// The name of the remote, passed as first argument to the helper. If unset, the url is used in its place.
+    pub remote_name: Option<BString>,
+}
+
+/// The status of a single reference after a `push` or `export`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PushStatus {
+    /// The remote reference of the given name was updated.
+    Ok {
+        /// The full name of the updated reference.
+        full_ref_name: BString,
+    },
+    /// The remote reference of the given name could not be updated.
+    Error {
+        /// The full name of the reference that failed to update.
+        full_ref_name: BString,
+        /// The reason for the failure, which may be empty.
+        message: BString,
+    },
+}
+
+impl PushStatus {
+    /// Return the full name of the reference this status is about.
+    pub fn full_ref_name(&self) -> &BStr {
+        match self {
+            PushStatus::Ok { full_ref_name } | PushStatus::Error { full_ref_name, .. } => full_ref_name.as_ref(),
+        }
+    }
+}
+
+/// A running remote helper program, like `git-remote-hg`, along with the means to communicate with it.
+///
+/// It implements the low-level commands of the remote helper protocol, see
+/// [gitremote-helpers](https://git-scm.com/docs/gitremote-helpers) for details.
+pub struct Process {
+    child: Option<process::Child>,
+    stdin: Option<process::ChildStdin>,
+    stdout: Option<std::io::BufReader<process::ChildStdout>>,
+    capabilities: Option<Capabilities>,
+    buf: Vec<u8>,
+}
+
+/// Lifecycle
+impl Process {
+    /// Spawn the remote helper for the scheme of `url`, that is `git-remote-<scheme>`, passing `remote_name` and `url`
+    /// as arguments. If `remote_name` is `None`, the `url` is used in its place just like `git` does.
+    ///
+    /// If `git_dir` is set, it will be made available to the helper as `GIT_DIR` as some helpers need it to store state.
+    /// `envs` are additional environment variables for the helper, like `GIT_PROTOCOL`.
+    pub fn spawn(
+        url: &gix_url::Url,
+        remote_name: Option<&BStr>,
+        git_dir: Option<PathBuf>,
+        envs: impl IntoIterator<Item = (&'static str, String)>,
+    ) -> Result<Self, Error> {
+        let url_str = url.to_bstring();
+        let program = format!("git-remote-{}", url.scheme.as_str());
+        let mut cmd = gix_command::prepare(program.as_str())
+            .arg(remote_name.unwrap_or(url_str.as_ref()).to_os_str_lossy().into_owned())
+            .arg(url_str.to_os_str_lossy().into_owned())
+            .stderr(Stdio::inherit());
+        cmd.stdin = Stdio::piped();
+        cmd.stdout = Stdio::piped();
+
+        let mut cmd = std::process::Command::from(cmd);
+        if let Some(git_dir) = git_dir {
+            cmd.env("GIT_DIR", git_dir);
+        }
+        cmd.envs(envs);
+        gix_features::trace::debug!(command = ?cmd, "gix_transport::helper::Process");
+        let mut child = cmd.spawn().map_err(|err| Error::InvokeProgram {
+            source: err,
+            command: program.into(),
+        })?;
+        Ok(Process {
+            stdin: child.stdin.take(),
+            stdout: Some(std::io::BufReader::new(child.stdout.take().expect("stdout configured"))),
+            child: Some(child),
+            capabilities: None,
+            buf: Vec::new(),
+        })
+    }
+
+    /// Turn this instance into a bidirectional pipe to `service` on the remote, whose output is the service output and whose
+    /// input is the service input, after which it behaves like a `git` process spawned locally.
+    ///
+    /// Return `None` if the helper asked to fall back to its other commands, which leaves this instance usable.
+    /// This requires the `connect` capability.
+    #[allow(clippy::type_complexity)]
+    pub fn connect(
+        &mut self,
+        service: Service,
+    ) -> Result<
+        Option<(
+            std::io::BufReader<process::ChildStdout>,
+            process::ChildStdin,
+            process::Child,
+        )>,
+        Error,
+    > {
+        const COMMAND: &str = "connect";
+        self.require(|c| c.connect, COMMAND, COMMAND)?;
+        writeln!(self.stdin(), "connect {}", service.as_str())?;
+        let line = self.read_line(COMMAND)?;
+        if line == "fallback" {
+            return Ok(None);
+        } else if !line.is_empty() {
+            return Err(Error::UnexpectedLine {
+                line: line.to_owned(),
+                command: COMMAND,
+            });
+        }
+        Ok(Some((
+            self.stdout.take().expect("present until dropped"),
+            self.stdin.take().expect("present until dropped"),
+            self.child.take().expect("present until dropped"),
+        )))
+    }
+}
+
+/// Commands
+impl Process {
+    /// Obtain the capabilities of the helper, which are queried on first call.
+    pub fn capabilities(&mut self) -> Result<&Capabilities, Error> {
+        if self.capabilities.is_none() {
+            writeln!(self.stdin(), "capabilities")?;
+            let lines = self.read_lines_until_empty("capabilities")?;
+            self.capabilities = Some(Capabilities::from_lines(lines.iter().map(AsRef::as_ref))?);
+        }
+        Ok(self.capabilities.as_ref().expect("just set"))
+    }
+
+    /// Set the option `name` to `value`, like `verbosity` or `depth`.
+    pub fn option(&mut self, name: &str, value: &BStr) -> Result<OptionResponse, Error> {
+        const COMMAND: &str = "option";
+        self.require(|c| c.option, COMMAND, COMMAND)?;
+        let mut line = BString::from(format!("option {name} "));
+        line.extend_from_slice(value);
+        line.push(b'\n');
+        self.stdin().write_all(&line)?;
+        let line = self.read_line(COMMAND)?;
+        Ok(match line.as_bytes() {
+            b"ok" => OptionResponse::Ok,
+            b"unsupported" => OptionResponse::Unsupported,
+            _ => match line.strip_prefix(b"error ") {
+                Some(message) => OptionResponse::Error(message.into()),
+                None => {
+                    return Err(Error::UnexpectedLine {
+                        line: line.to_owned(),
+                        command: COMMAND,
+                    })
+                }
+            },
+        })
+    }
+
+    /// List all references of the remote, or only those that may be pushed to if `for_push` is `true`.
+    pub fn list(&mut self, for_push: bool) -> Result<Vec<Ref>, Error> {
+        self.capabilities()?;
+        if for_push {
+            writeln!(self.stdin(), "list for-push")?;
+        } else {
+            writeln!(self.stdin(), "list")?;
+        }
+        self.read_lines_until_empty("list")?
+            .iter()
+            .filter_map(|line| Ref::from_line(line.as_ref()).transpose())
+            .collect()
+    }
+
+    /// Fetch the given `refs`, each being a pair of the hexadecimal object id and the full reference name as obtained by [`list()`][Self::list()].
+    /// The objects will be written into the object database of the repository directly.
+    ///
+    /// Return the lines the helper responded with, like `lock <file>` or `connectivity-ok`.
+    pub fn fetch<'a>(&mut self, refs: impl IntoIterator<Item = (&'a BStr, &'a BStr)>) -> Result<Vec<BString>, Error> {
+        const COMMAND: &str = "fetch";
+        self.require(|c| c.fetch, COMMAND, COMMAND)?;
+        let mut batch = Vec::new();
+        for (object, name) in refs {
+            batch.extend_from_slice(b"fetch ");
+            batch.extend_from_slice(object);
+            batch.push(b' ');
+            batch.extend_from_slice(name);
+            batch.push(b'\n');
+        }
+        batch.push(b'\n');
+        self.stdin().write_all(&batch)?;
+        self.read_lines_until_empty(COMMAND)
+    }
+
+    /// Push all `refspecs`, like `refs/heads/main:refs/heads/main` or `+refs/heads/feature:refs/heads/feature`, with
+    /// the objects they need taken from the object database of the repository directly.
+    pub fn push<'a>(&mut self, refspecs: impl IntoIterator<Item = &'a BStr>) -> Result<Vec<PushStatus>, Error> {
+        const COMMAND: &str = "push";
+        self.require(|c| c.push, COMMAND, COMMAND)?;
+        let mut batch = Vec::new();
+        for spec in refspecs {
+            batch.extend_from_slice(b"push ");
+            batch.extend_from_slice(spec);
+            batch.push(b'\n');
+        }
+        batch.push(b'\n');
+        self.stdin().write_all(&batch)?;
+        self.read_push_status(COMMAND)
+    }
+
+    /// Import the given references, with `read_stream` receiving the `fast-import` stream produced by the helper.
+    /// It must consume the stream up to and including the `done` command.
+    pub fn import<'a>(
+        &mut self,
+        full_ref_names: impl IntoIterator<Item = &'a BStr>,
+        read_stream: impl FnOnce(&mut dyn BufRead) -> std::io::Result<()>,
+    ) -> Result<(), Error> {
+        const COMMAND: &str = "import";
+        self.require(|c| c.import, COMMAND, COMMAND)?;
+        let mut batch = Vec::new();
+        for name in full_ref_names {
+            batch.extend_from_slice(b"import ");
+            batch.extend_from_slice(name);
+            batch.push(b'\n');
+        }
+        batch.push(b'\n');
+        self.stdin().write_all(&batch)?;
+        read_stream(self.stdout.as_mut().expect("present until dropped"))?;
+        Ok(())
+    }
+
+    /// Export references to the remote, with `write_stream` producing the `fast-export` stream to send to the helper.
+    ///
+    /// Return the status of each reference that the helper reported on.
+    pub fn export(
+        &mut self,
+        write_stream: impl FnOnce(&mut dyn Write) -> std::io::Result<()>,
+    ) -> Result<Vec<PushStatus>, Error> {
+        const COMMAND: &str = "export";
+        self.require(|c| c.export, COMMAND, COMMAND)?;
+        writeln!(self.stdin(), "export")?;
+        write_stream(self.stdin())?;
+        self.stdin().flush()?;
+        self.read_push_status(COMMAND)
+    }
+
+    /// Import the given references into the repository at `git_dir`, or the one `git` finds in the current working directory,
+    /// by passing the stream produced by the helper to `git fast-import`.
+    ///
+    /// The references end up in the private namespace the helper configured with its `refspec` capability.
+    pub fn import_into<'a>(
+        &mut self,
+        full_ref_names: impl IntoIterator<Item = &'a BStr>,
+        git_dir: Option<&Path>,
+    ) -> Result<(), Error> {
+        let mut fast_import = git(git_dir, ["fast-import", "--quiet"])
+            .stdin(Stdio::piped())
+            .spawn()
+            .map_err(|err| Error::InvokeProgram {
+                source: err,
+                command: "git".into(),
+            })?;
+        let mut stdin = fast_import.stdin.take().expect("configured");
+        self.import(full_ref_names, |stream| copy_fast_import_stream(stream, &mut stdin))?;
+        drop(stdin);
+        let status = fast_import.wait()?;
+        if !status.success() {
+            return Err(Error::GitFailed {
+                command: "fast-import",
+                status,
+            });
+        }
+        Ok(())
+    }
+
+    /// Export `refs`, pairs of a reference in the repository at `git_dir` and the full name of the reference on the remote to
+    /// update with it, by passing the stream produced by `git fast-export` to the helper.
+    ///
+    /// Return the status of each reference that the helper reported on.
+    pub fn export_from<'a>(
+        &mut self,
+        refs: impl IntoIterator<Item = (&'a BStr, &'a BStr)>,
+        git_dir: Option<&Path>,
+    ) -> Result<Vec<PushStatus>, Error> {
+        let caps = self.capabilities()?.clone();
+        let mut args = vec![
+            OsString::from("fast-export"),
+            "--use-done-feature".into(),
+            if caps.signed_tags {
+                "--signed-tags=verbatim"
+            } else {
+                "--signed-tags=warn-strip"
+            }
+            .into(),
+        ];
+        if let Some(marks) = caps.import_marks.as_ref() {
+            let mut arg = OsString::from("--import-marks-if-exists=");
+            arg.push(marks.to_os_str_lossy());
+            args.push(arg);
+        }
+        if let Some(marks) = caps.export_marks.as_ref() {
+            let mut arg = OsString::from("--export-marks=");
+            arg.push(marks.to_os_str_lossy());
+            args.push(arg);
+        }
+        let mut local_names = Vec::new();
+        for (local, remote) in refs {
+            let mut arg = OsString::from("--refspec=");
+            arg.push(local.to_os_str_lossy());
+            arg.push(":");
+            arg.push(remote.to_os_str_lossy());
+            args.push(arg);
+            local_names.push(local.to_os_str_lossy().into_owned());
+        }
+        args.extend(local_names);
+
+        let mut fast_export = git(git_dir, args)
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .spawn()
+            .map_err(|err| Error::InvokeProgram {
+                source: err,
+                command: "git".into(),
+            })?;
+        let mut stdout = fast_export.stdout.take().expect("configured");
+        let statuses = self.export(|out| std::io::copy(&mut stdout, out).map(|_| ()))?;
+        let status = fast_export.wait()?;
+        if !status.success() {
+            return Err(Error::GitFailed {
+                command: "fast-export",
+                status,
+            });
+        }
+        Ok(statuses)
+    }
+}
+
+/// Utilities
+impl Process {
+    fn stdin(&mut self) -> &mut process::ChildStdin {
+        self.stdin.as_mut().expect("present until dropped")
+    }
+
+    fn require(
+        &mut self,
+        has: impl FnOnce(&Capabilities) -> bool,
+        capability: &'static str,
+        command: &'static str,
+    ) -> Result<(), Error> {
+        if has(self.capabilities()?) {
+            Ok(())
+        } else {
+            Err(Error::MissingCapability { capability, command })
+        }
+    }
+
+    fn read_line(&mut self, command: &'static str) -> Result<&BStr, Error> {
+        self.buf.clear();
+        let stdout = self.stdout.as_mut().expect("present until dropped");
+        if stdout.read_until(b'\n', &mut self.buf)? == 0 {
+            return Err(Error::UnexpectedEof { command });
+        }
+        Ok(self.buf.trim_end_with(|c| c == '\n' || c == '\r').as_bstr())
+    }
+
+    fn read_lines_until_empty(&mut self, command: &'static str) -> Result<Vec<BString>, Error> {
+        let mut out = Vec::new();
+        loop {
+            let line = self.read_line(command)?;
+            if line.is_empty() {
+                break Ok(out);
+            }
+            out.push(line.to_owned());
+        }
+    }
+
+    fn read_push_status(&mut self, command: &'static str) -> Result<Vec<PushStatus>, Error> {
+        self.read_lines_until_empty(command)?
+            .into_iter()
+            .map(|line| {
+                if let Some(name) = line.strip_prefix(b"ok ") {
+                    Ok(PushStatus::Ok {
+                        full_ref_name: name.into(),
+                    })
+                } else if let Some(rest) = line.strip_prefix(b"error ") {
+                    let (name, message) = rest.split_once_str(b" ").unwrap_or((rest, &[][..]));
+                    Ok(PushStatus::Error {
+                        full_ref_name: name.into(),
+                        message: message.into(),
+                    })
+                } else {
+                    Err(Error::UnexpectedLine { line, command })
+                }
+            })
+            .collect()
+    }
+}
+
+/// Prepare a `git` invocation with `args` in `git_dir`, or in the current working directory if unset.
+fn git(git_dir: Option<&Path>, args: impl IntoIterator<Item = impl Into<OsString>>) -> process::Command {
+    let mut cmd = process::Command::from(gix_command::prepare("git").args(args).stderr(Stdio::inherit()));
+    if let Some(git_dir) = git_dir {
+        cmd.env("GIT_DIR", git_dir);
+    }
+    cmd
+}
+
+/// Copy the `fast-import` stream in `read` to `out` up to and including the `done` command, leaving everything after it.
+fn copy_fast_import_stream(read: &mut dyn BufRead, out: &mut dyn Write) -> std::io::Result<()> {
+    let unexpected_eof = || {
+        std::io::Error::new(
+            std::io::ErrorKind::UnexpectedEof,
+            "the fast-import stream ended before the 'done' command",
+        )
+    };
+    let mut line = Vec::new();
+    loop {
+        line.clear();
+        if read.read_until(b'\n', &mut line)? == 0 {
+            return Err(unexpected_eof());
+        }
+        out.write_all(&line)?;
+        let command = line.strip_suffix(b"\n").unwrap_or(&line);
+        if command == b"done" {
+            return out.flush();
+        }
+        let Some(size) = command.strip_prefix(b"data ") else {
+            continue;
+        };
+        match size.strip_prefix(b"<<") {
+            Some(delimiter) => {
+                let delimiter = delimiter.to_owned();
+                loop {
+                    line.clear();
+                    if read.read_until(b'\n', &mut line)? == 0 {
+                        return Err(unexpected_eof());
+                    }
+                    out.write_all(&line)?;
+                    if line.strip_suffix(b"\n").unwrap_or(&line) == delimiter.as_slice() {
+                        break;
+                    }
+                }
+            }
+            None => {
+                let size: u64 = size.to_str().ok().and_then(|size| size.parse().ok()).ok_or_else(|| {
+                    std::io::Error::new(std::io::ErrorKind::InvalidData, "invalid size of 'data' command")
+                })?;
+                if std::io::copy(&mut read.take(size), out)? != size {
+                    return Err(unexpected_eof());
+                }
+            }
+        }
+    }
+}
+
+impl Drop for Process {
+    fn drop(&mut self) {
+        // Closing stdin tells the helper that we are done, after which it is expected to exit.
+        drop(self.stdin.take());
+        if let Some(mut child) = self.child.take() {
+            child.wait().ok();
+        }
+    }
+}
+
+/// A transport which uses a remote helper `git-remote-<scheme>` to talk to the remote,
+/// which is how transports that are unknown to us can be used with `fetch` and `push` unchanged.
+///
+/// Helpers with the `connect` capability are used as pipe to the remote service. Otherwise, the service is emulated
+/// with their `fetch` or `import` commands when fetching, and with `push` or `export` when pushing, which transfer objects
+/// through the repository set in the [`Options`] passed to [configure()][client::TransportWithoutIO::configure()].
+///
+/// It can only be instantiated using the [`connect()`] function.
+pub struct SpawnHelperOnDemand {
+    desired_version: Protocol,
+    url: gix_url::Url,
+    options: Options,
+    connection: Option<git::Connection<Box<dyn Read + Send>, Box<dyn Write + Send>>>,
+    child: Option<process::Child>,
+    emulation: Option<std::thread::JoinHandle<()>>,
+    trace: bool,
+}
+
+impl client::TransportWithoutIO for SpawnHelperOnDemand {
+    fn request(
+        &mut self,
+        write_mode: WriteMode,
+        on_into_read: MessageKind,
+        trace: bool,
+    ) -> Result<RequestWriter<'_>, client::Error> {
+        self.connection
+            .as_mut()
+            .expect("handshake() to have been called first")
+            .request(write_mode, on_into_read, trace)
+    }
+
+    fn to_url(&self) -> Cow<'_, BStr> {
+        Cow::Owned(self.url.to_bstring())
+    }
+
+    fn connection_persists_across_multiple_requests(&self) -> bool {
+        true
+    }
+
+    fn configure(&mut self, config: &dyn Any) -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>> {
+        if let Some(options) = config.downcast_ref::<Options>() {
+            self.options = options.clone();
+        }
+        Ok(())
+    }
+}
+
+impl client::Transport for SpawnHelperOnDemand {
+    fn handshake<'a>(
+        &mut self,
+        service: Service,
+        extra_parameters: &'a [(&'a str, Option<&'a str>)],
+    ) -> Result<SetServiceResponse<'_>, client::Error> {
+        let envs = (self.desired_version != Protocol::V1)
+            .then(|| ("GIT_PROTOCOL", format!("version={}", self.desired_version as usize)));
+        let mut helper = Process::spawn(
+            &self.url,
+            self.options.remote_name.as_ref().map(AsRef::as_ref),
+            self.options.git_dir.clone(),
+            envs,
+        )
+        .map_err(client::Error::RemoteHelper)?;
+        let connected = if helper.capabilities().map_err(client::Error::RemoteHelper)?.connect {
+            helper.connect(service).map_err(client::Error::RemoteHelper)?
+        } else {
+            None
+        };
+        let (stdout, stdin, desired_version): (Box<dyn Read + Send>, Box<dyn Write + Send>, _) = match connected {
+            Some((stdout, stdin, child)) => {
+                self.child = Some(child);
+                (Box::new(stdout), Box::new(stdin), self.desired_version)
+            }
+            None => {
+                let (stdout, stdin, emulation) = native::spawn(helper, service, self.options.git_dir.clone());
+                self.emulation = Some(emulation);
+                (Box::new(stdout), Box::new(stdin), Protocol::V1)
+            }
+        };
+        self.connection = Some(git::Connection::new_for_spawned_process(
+            stdout,
+            stdin,
+            desired_version,
+            self.url.path.clone(),
+            self.trace,
+        ));
+        self.connection
+            .as_mut()
+            .expect("connection to be there right after setting it")
+            .handshake(service, extra_parameters)
+    }
+}
+
+impl Drop for SpawnHelperOnDemand {
+    fn drop(&mut self) {
+        // Close our end of the pipes first so the helper can see the end of input.
+        drop(self.connection.take());
+        if let Some(mut child) = self.child.take() {
+            child.wait().ok();
+        }
+        if let Some(emulation) = self.emulation.take() {
+            emulation.join().ok();
+        }
+    }
+}
+
+/// Connect to the remote at `url` through the remote helper `git-remote-<scheme>` using the given `desired_version`,
+/// which is spawned on [handshake][client::Transport::handshake()].
+/// If `trace` is `true`, all packetlines received or sent will be passed to the facilities of the `gix-trace` crate.
+///
+/// Use [`Process`] to talk to helpers directly.
+pub fn connect(
+    url: gix_url::Url,
+    desired_version: Protocol,
+    trace: bool,
+) -> Result<SpawnHelperOnDemand, std::convert::Infallible> {
+    Ok(SpawnHelperOnDemand {
+        desired_version,
+        url,
+        options: Options::default(),
+        connection: None,
+        child: None,
+        emulation: None,
+        trace,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use bstr::ByteSlice;
+
+    use super::{copy_fast_import_stream, native::private_ref_name, Capabilities, Error, Ref};
+
+    #[test]
+    fn capabilities_from_lines() {
+        let caps = Capabilities::from_lines(
+            [
+                "import",
+                "*connect",
+                "refspec refs/heads/*:refs/hg/origin/heads/*",
+                "*export-marks .git/hg/marks",
+                "no-private-update",
+            ]
+            .iter()
+            .map(|l| l.as_bytes().as_bstr()),
+        )
+        .expect("valid");
+        assert!(caps.import && caps.connect);
+        assert!(!caps.fetch && !caps.push);
+        assert_eq!(caps.refspecs, ["refs/heads/*:refs/hg/origin/heads/*"]);
+        assert_eq!(caps.export_marks, Some(".git/hg/marks".into()));
+        assert_eq!(
+            caps.other,
+            ["no-private-update"],
+            "unknown optional capabilities are kept"
+        );
+
+        let err = Capabilities::from_lines(Some("*unknown".into())).unwrap_err();
+        assert!(matches!(err, Error::UnsupportedMandatoryCapability(name) if name == "unknown"));
+    }
+
+    #[test]
+    fn ref_from_line() {
+        assert_eq!(
+            Ref::from_line("@refs/heads/main HEAD".into()).expect("valid"),
+            Some(Ref::Symbolic {
+                full_ref_name: "HEAD".into(),
+                target: "refs/heads/main".into()
+            })
+        );
+        assert_eq!(
+            Ref::from_line("e69de29bb2d1d6434b8b29ae775ad8c2e48c5391 refs/heads/main unchanged".into()).expect("valid"),
+            Some(Ref::Direct {
+                full_ref_name: "refs/heads/main".into(),
+                object: "e69de29bb2d1d6434b8b29ae775ad8c2e48c5391".into()
+            }),
+            "attributes are ignored"
+        );
+        assert_eq!(
+            Ref::from_line("? refs/heads/feature".into()).expect("valid"),
+            Some(Ref::Unknown {
+                full_ref_name: "refs/heads/feature".into()
+            })
+        );
+        assert_eq!(Ref::from_line(":object-format sha1".into()).expect("valid"), None);
+        assert!(Ref::from_line("no-name".into()).is_err());
+    }
+
+    #[test]
+    fn fast_import_stream_is_copied_up_to_done() {
+        let stream =
+            "blob\nmark :1\ndata 5\ndone\n\ncommit refs/heads/main\ndata <<EOM\ndone\nEOM\ndone\nnext-command\n";
+        let mut out = Vec::new();
+        copy_fast_import_stream(&mut stream.as_bytes(), &mut out).expect("valid");
+        assert_eq!(
+            out.as_bstr(),
+            stream.strip_suffix("next-command\n").expect("present"),
+            "data looking like `done` doesn't end the stream, and nothing after the end is consumed"
+        );
+
+        let err = copy_fast_import_stream(&mut "blob\n".as_bytes(), &mut Vec::new()).unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::UnexpectedEof);
+    }
+
+    #[test]
+    fn private_ref_names() {
+        let specs = [
+            "refs/heads/*:refs/hg/origin/heads/*".into(),
+            "+HEAD:refs/hg/origin/HEAD".into(),
+        ];
+        assert_eq!(
+            private_ref_name(&specs, "refs/heads/main".into()),
+            Some("refs/hg/origin/heads/main".into())
+        );
+        assert_eq!(
+            private_ref_name(&specs, "HEAD".into()),
+            Some("refs/hg/origin/HEAD".into())
+        );
+        assert_eq!(private_ref_name(&specs, "refs/tags/v1".into()), None);
+    }
+}
@@ -0,0 +1,473 @@
+//! Speak the `upload-pack` and `receive-pack` services in protocol V1 on behalf of a remote helper that can't `connect`,
+//! translating them into its `fetch`, `import`, `push` and `export` commands.
+//!
+//! As these commands transfer objects through the repository directly, the objects never pass through the emulated
+//! services: clients receive an empty pack, and packs sent by clients are skipped.
+use std::{
+    io::{self, BufRead, Read, Write},
+    path::{Path, PathBuf},
+};
+
+use bstr::{BStr, BString, ByteSlice, ByteVec};
+use gix_features::io::pipe;
+use gix_packetline::{decode::PacketLineOrWantedSize, PacketLineRef};
+
+use super::{Capabilities, Error, Process, PushStatus, Ref};
+use crate::Service;
+
+/// Run `service` for `helper` in a thread, and return the ends of the pipes to read its output from and to write its
+/// input to, along with the handle of the thread.
+///
+/// `git_dir` is the repository that objects are fetched into and pushed from.
+pub(super) fn spawn(
+    mut helper: Process,
+    service: Service,
+    git_dir: Option<PathBuf>,
+) -> (pipe::Reader, pipe::Writer, std::thread::JoinHandle<()>) {
+    let (mut to_client, client_reads) = pipe::unidirectional(16);
+    let (client_writes, mut from_client) = pipe::unidirectional(16);
+    let handle = std::thread::Builder::new()
+        .name("gix-transport.remote-helper".into())
+        .spawn(move || {
+            let git_dir = git_dir.as_deref();
+            let res = match service {
+                Service::UploadPack => upload_pack(&mut helper, git_dir, &mut to_client, &mut from_client),
+                Service::ReceivePack => receive_pack(&mut helper, git_dir, &mut to_client, &mut from_client),
+            };
+            if let Err(err) = res {
+                to_client
+                    .channel
+                    .send(Err(io::Error::new(io::ErrorKind::Other, err)))
+                    .ok();
+            }
+        })
+        .expect("named threads with small stack work on all platforms");
+    (client_reads, client_writes, handle)
+}
+
+fn upload_pack(
+    helper: &mut Process,
+    git_dir: Option<&Path>,
+    out: &mut impl Write,
+    input: &mut impl Read,
+) -> Result<(), Error> {
+    let caps = helper.capabilities()?.clone();
+    if !(caps.fetch || caps.import) {
+        return Err(Error::UnsupportedService {
+            service: Service::UploadPack.as_str(),
+        });
+    }
+    let listed = helper.list(false)?;
+    // Helpers that import don't know the objects the refs point to until they were imported, just like `git` we prefer `fetch`.
+    let refs = if caps.fetch {
+        direct_refs(&listed)
+    } else {
+        let names: Vec<_> = listed.iter().filter_map(unknown_or_direct_name).collect();
+        helper.import_into(names.iter().map(AsRef::as_ref), git_dir)?;
+        resolve_private_refs(&names, &caps, git_dir, "import")?
+    };
+    let hex_len = advertise(
+        &listed,
+        &refs,
+        &["multi_ack_detailed", "side-band-64k", "include-tag"],
+        out,
+    )?;
+
+    let mut buf = Vec::new();
+    let mut wants = Vec::new();
+    let mut include_tags = false;
+    loop {
+        match read_line(input, &mut buf)? {
+            None => return Ok(()),
+            Some(None) => break,
+            Some(Some(line)) => {
+                if let Some(want) = line.strip_prefix(b"want ") {
+                    let mut tokens = want.split_str(" ");
+                    wants.push(tokens.next().unwrap_or_default().as_bstr().to_owned());
+                    include_tags |= tokens.any(|capability| capability == b"include-tag");
+                }
+            }
+        }
+    }
+    if wants.is_empty() {
+        return Ok(());
+    }
+    loop {
+        match read_line(input, &mut buf)? {
+            None => return Ok(()),
+            Some(None) => text_line("NAK", out)?,
+            Some(Some(line)) if line == "done" => break,
+            Some(Some(_have)) => {}
+        }
+    }
+
+    if caps.fetch {
+        let to_fetch: Vec<_> = refs
+            .iter()
+            // We can't know which tags point to the wanted objects, so all of them are included.
+            .filter(|(object, name)| wants.contains(object) || (include_tags && name.starts_with(b"refs/tags/")))
+            .map(|(object, name)| (object.as_bstr(), name.as_bstr()))
+            .collect();
+        for line in helper.fetch(to_fetch)? {
+            // We don't update references before the client does, so there is no need to keep the pack locked.
+            if let Some(lock_file) = line.strip_prefix(b"lock ") {
+                if let Ok(lock_file) = lock_file.to_path() {
+                    std::fs::remove_file(lock_file).ok();
+                }
+            }
+        }
+    }
+    text_line("NAK", out)?;
+    gix_packetline::encode::band_to_write(gix_packetline::Channel::Data, &empty_pack(hex_len), &mut *out)?;
+    PacketLineRef::Flush.write_to(&mut *out)?;
+    out.flush()?;
+    Ok(())
+}
+
+fn receive_pack(
+    helper: &mut Process,
+    git_dir: Option<&Path>,
+    out: &mut impl Write,
+    input: &mut impl BufRead,
+) -> Result<(), Error> {
+    let caps = helper.capabilities()?.clone();
+    if !(caps.push || caps.export) {
+        return Err(Error::UnsupportedService {
+            service: Service::ReceivePack.as_str(),
+        });
+    }
+    let listed = helper.list(true)?;
+    let refs = direct_refs(&listed);
+    let hex_len = advertise(&listed, &refs, &["report-status", "delete-refs", "ofs-delta"], out)?;
+
+    let mut buf = Vec::new();
+    let mut updates = Vec::new();
+    loop {
+        match read_line(input, &mut buf)? {
+            None => return Ok(()),
+            Some(None) => break,
+            Some(Some(line)) => {
+                let line = line.split_str(b"\0").next().unwrap_or_default();
+                let mut tokens = line.splitn_str(3, b" ");
+                match (tokens.next(), tokens.next(), tokens.next()) {
+                    (Some(_previous), Some(new), Some(name)) => {
+                        updates.push((new.as_bstr().to_owned(), name.as_bstr().to_owned()));
+                    }
+                    _ => {
+                        return Err(Error::UnexpectedLine {
+                            line: line.as_bstr().to_owned(),
+                            command: "receive-pack",
+                        })
+                    }
+                }
+            }
+        }
+    }
+    if updates.is_empty() {
+        return Ok(());
+    }
+    let is_deletion = |new: &BString| new.iter().all(|b| *b == b'0');
+    if !updates.iter().all(|(new, _)| is_deletion(new)) {
+        skip_pack(input, hex_len / 2)?;
+    }
+
+    let statuses = if caps.push {
+        // The client already checked the previous values, so each update is forced just like the client asked for it.
+        let specs: Vec<BString> = updates
+            .iter()
+            .map(|(new, name)| {
+                let mut spec = BString::default();
+                if !is_deletion(new) {
+                    spec.push(b'+');
+                    spec.push_str(new);
+                }
+                spec.push(b':');
+                spec.push_str(name);
+                spec
+            })
+            .collect();
+        helper.push(specs.iter().map(AsRef::as_ref))?
+    } else {
+        let mut statuses = Vec::new();
+        let mut exports = Vec::new();
+        for (new, name) in &updates {
+            if is_deletion(new) {
+                statuses.push(PushStatus::Error {
+                    full_ref_name: name.clone(),
+                    message: "remote helpers can't delete references with 'export'".into(),
+                });
+                continue;
+            }
+            let private = private_ref_name(&caps.refspecs, name.as_ref()).ok_or(Error::MissingCapability {
+                capability: "refspec",
+                command: "export",
+            })?;
+            run_git(
+                git_dir,
+                ["update-ref".into(), private.clone(), new.clone()],
+                "update-ref",
+            )?;
+            exports.push((private, name.clone()));
+        }
+        if !exports.is_empty() {
+            statuses.extend(
+                helper.export_from(
+                    exports
+                        .iter()
+                        .map(|(private, name)| (private.as_bstr(), name.as_bstr())),
+                    git_dir,
+                )?,
+            );
+        }
+        statuses
+    };
+
+    text_line("unpack ok", out)?;
+    for (_, name) in &updates {
+        let mut line = BString::default();
+        match statuses.iter().find(|status| status.full_ref_name() == name) {
+            Some(PushStatus::Ok { .. }) => {
+                line.push_str("ok ");
+                line.push_str(name);
+            }
+            Some(PushStatus::Error { message, .. }) => {
+                line.push_str("ng ");
+                line.push_str(name);
+                line.push(b' ');
+                line.push_str(if message.is_empty() {
+                    "failed".as_bytes()
+                } else {
+                    message.as_slice()
+                });
+            }
+            None => {
+                line.push_str("ng ");
+                line.push_str(name);
+                line.push_str(" the remote helper didn't report a status");
+            }
+        }
+        text_line(line.as_bstr(), out)?;
+    }
+    PacketLineRef::Flush.write_to(&mut *out)?;
+    out.flush()?;
+    Ok(())
+}
+
+/// Write the V1 advertisement of `refs`, with the symbolic references in `listed` pointing to one of them, and `capabilities`.
+/// Return the length of hexadecimal object ids.
+fn advertise(
+    listed: &[Ref],
+    refs: &[(BString, BString)],
+    capabilities: &[&str],
+    out: &mut impl Write,
+) -> io::Result<usize> {
+    let mut advertised = Vec::new();
+    let mut capabilities = capabilities.iter().map(ToString::to_string).collect::<Vec<_>>();
+    for r in listed {
+        if let Ref::Symbolic { full_ref_name, target } = r {
+            if let Some((object, _)) = refs.iter().find(|(_, name)| name == target) {
+                advertised.push((object.as_bstr(), full_ref_name.as_bstr()));
+                capabilities.push(format!("symref={full_ref_name}:{target}"));
+            }
+        }
+    }
+    advertised.extend(refs.iter().map(|(object, name)| (object.as_bstr(), name.as_bstr())));
+    let hex_len = advertised.first().map_or(40, |(object, _)| object.len());
+    let null = "0".repeat(hex_len);
+    if advertised.is_empty() {
+        advertised.push((null.as_bytes().as_bstr(), "capabilities^{}".into()));
+    }
+
+    let capabilities = capabilities.join(" ");
+    for (idx, (object, name)) in advertised.into_iter().enumerate() {
+        let mut line = BString::from(object);
+        line.push(b' ');
+        line.push_str(name);
+        if idx == 0 {
+            line.push(b'\0');
+            line.push_str(&capabilities);
+        }
+        text_line(line.as_bstr(), out)?;
+    }
+    PacketLineRef::Flush.write_to(&mut *out)?;
+    out.flush()?;
+    Ok(hex_len)
+}
+
+/// Read a packet line into `buf`, and return `None` if the client is done, `Some(None)` on flush, and the data without
+/// trailing newline otherwise.
+fn read_line<'a>(input: &mut impl Read, buf: &'a mut Vec<u8>) -> io::Result<Option<Option<&'a BStr>>> {
+    let mut hex = [0u8; 4];
+    match input.read_exact(&mut hex) {
+        Ok(()) => {}
+        Err(err) if err.kind() == io::ErrorKind::UnexpectedEof => return Ok(None),
+        Err(err) => return Err(err),
+    }
+    let len = match gix_packetline::decode::hex_prefix(&hex)
+        .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?
+    {
+        PacketLineOrWantedSize::Line(_) => return Ok(Some(None)),
+        PacketLineOrWantedSize::Wanted(len) => len as usize,
+    };
+    buf.resize(len, 0);
+    input.read_exact(buf)?;
+    Ok(Some(Some(buf.trim_end_with(|c| c == '\n').as_bstr())))
+}
+
+fn text_line(line: impl AsRef<[u8]>, out: &mut impl Write) -> io::Result<()> {
+    gix_packetline::encode::text_to_write(line.as_ref(), out).map(|_| ())
+}
+
+/// A pack without objects, with a checksum matching the hash whose hexadecimal form has `hex_len` characters.
+fn empty_pack(hex_len: usize) -> Vec<u8> {
+    let mut pack = b"PACK\0\0\0\x02\0\0\0\0".to_vec();
+    let checksum = if hex_len == 64 {
+        "7ed890d8a45760f3eecf73045b1d1047085af4776dc683d78eac82203df1993f"
+    } else {
+        "029d08823bd8a8eab510ad6ac75c823cfd3ed31e"
+    };
+    pack.extend(
+        checksum
+            .as_bytes()
+            .chunks(2)
+            .map(|hex| u8::from_str_radix(std::str::from_utf8(hex).expect("ascii"), 16).expect("valid hex")),
+    );
+    pack
+}
+
+/// Read the pack in `input` to its end without keeping it, with object ids being `hash_len` bytes long.
+fn skip_pack(input: &mut impl BufRead, hash_len: usize) -> io::Result<()> {
+    fn read_byte(input: &mut impl Read) -> io::Result<u8> {
+        let mut byte = [0u8; 1];
+        input.read_exact(&mut byte)?;
+        Ok(byte[0])
+    }
+    let invalid = |msg: &str| io::Error::new(io::ErrorKind::InvalidData, msg.to_owned());
+
+    let mut header = [0u8; 12];
+    input.read_exact(&mut header)?;
+    if &header[..4] != b"PACK" {
+        return Err(invalid("expected a pack after the commands"));
+    }
+    let num_objects = u32::from_be_bytes(header[8..].try_into().expect("four bytes"));
+    let mut inflate = gix_features::zlib::Inflate::default();
+    let mut out = vec![0; 8192];
+    let mut id = vec![0; hash_len];
+    for _ in 0..num_objects {
+        let mut byte = read_byte(input)?;
+        let kind = (byte >> 4) & 0b111;
+        while byte & 0x80 != 0 {
+            byte = read_byte(input)?;
+        }
+        match kind {
+            // offset delta, followed by a variable length offset
+            6 => while read_byte(input)? & 0x80 != 0 {},
+            // reference delta, followed by the id of its base
+            7 => input.read_exact(&mut id)?,
+            _ => {}
+        }
+        inflate.reset();
+        loop {
+            let buf = input.fill_buf()?;
+            if buf.is_empty() {
+                return Err(io::ErrorKind::UnexpectedEof.into());
+            }
+            let (status, consumed, produced) = inflate
+                .once(buf, &mut out)
+                .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+            input.consume(consumed);
+            if status == gix_features::zlib::Status::StreamEnd {
+                break;
+            }
+            if consumed == 0 && produced == 0 {
+                return Err(invalid("the zlib stream of a pack entry didn't make progress"));
+            }
+        }
+    }
+    input.read_exact(&mut id)
+}
+
+fn direct_refs(listed: &[Ref]) -> Vec<(BString, BString)> {
+    listed
+        .iter()
+        .filter_map(|r| match r {
+            Ref::Direct { full_ref_name, object } => Some((object.clone(), full_ref_name.clone())),
+            Ref::Symbolic { .. } | Ref::Unknown { .. } => None,
+        })
+        .collect()
+}
+
+fn unknown_or_direct_name(r: &Ref) -> Option<BString> {
+    match r {
+        Ref::Direct { full_ref_name, .. } | Ref::Unknown { full_ref_name } => Some(full_ref_name.clone()),
+        Ref::Symbolic { .. } => None,
+    }
+}
+
+/// Map `name` through the `refspecs` of the helper into the private namespace it imports into and exports from.
+pub(super) fn private_ref_name(refspecs: &[BString], name: &BStr) -> Option<BString> {
+    refspecs.iter().find_map(|spec| {
+        let spec = spec.strip_prefix(b"+").unwrap_or(spec);
+        let (src, dst) = spec.split_once_str(b":")?;
+        match (src.strip_suffix(b"*"), dst.strip_suffix(b"*")) {
+            (Some(src_prefix), Some(dst_prefix)) => name.strip_prefix(src_prefix).map(|rest| {
+                let mut out = BString::from(dst_prefix);
+                out.push_str(rest);
+                out
+            }),
+            (None, None) => (src == name.as_bytes()).then(|| dst.into()),
+            _ => None,
+        }
+    })
+}
+
+/// Find the objects that the imported `names` point to after the import, in the private namespace if the helper has one.
+fn resolve_private_refs(
+    names: &[BString],
+    caps: &Capabilities,
+    git_dir: Option<&Path>,
+    command: &'static str,
+) -> Result<Vec<(BString, BString)>, Error> {
+    let private: Vec<_> = names
+        .iter()
+        .map(|name| private_ref_name(&caps.refspecs, name.as_ref()).unwrap_or_else(|| name.clone()))
+        .collect();
+    let mut args = vec![
+        BString::from("for-each-ref"),
+        "--format=%(objectname) %(refname)".into(),
+    ];
+    args.extend(private.iter().cloned());
+    let output = run_git(git_dir, args, "for-each-ref")?;
+    let mut refs = Vec::new();
+    for line in ByteSlice::lines(output.as_slice()) {
+        let (object, private_name) = line.split_once_str(b" ").ok_or_else(|| Error::UnexpectedLine {
+            line: line.as_bstr().to_owned(),
+            command,
+        })?;
+        if let Some(idx) = private.iter().position(|name| name == private_name) {
+            refs.push((object.as_bstr().to_owned(), names[idx].clone()));
+        }
+    }
+    Ok(refs)
+}
+
+/// Run `git` with `args` in `git_dir` and return its output.
+pub(super) fn run_git(
+    git_dir: Option<&Path>,
+    args: impl IntoIterator<Item = BString>,
+    command: &'static str,
+) -> Result<BString, Error> {
+    let mut cmd = super::git(git_dir, args.into_iter().map(|arg| arg.to_os_str_lossy().into_owned()));
+    cmd.stdin(std::process::Stdio::null());
+    let output = cmd.output().map_err(|err| Error::InvokeProgram {
+        source: err,
+        command: "git".into(),
+    })?;
+    if !output.status.success() {
+        return Err(Error::GitFailed {
+            command,
+            status: output.status,
+        });
+    }
+    Ok(output.stdout.into())
+}
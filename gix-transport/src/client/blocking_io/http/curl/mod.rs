@@ -1,5 +1,8 @@
 use std::{
-    sync::mpsc::{Receiver, SyncSender},
+    sync::{
+        mpsc::{sync_channel, Receiver, SyncSender},
+        Mutex,
+    },
     thread,
 };
 
@@ -56,12 +59,29 @@ pub(crate) fn curl_is_spurious(err: &curl::Error) -> bool {
         || err.is_partial_file()
 }
 
+type Worker = (
+    thread::JoinHandle<Result<(), Error>>,
+    SyncSender<remote::Request>,
+    Receiver<remote::Response>,
+);
+
+/// Workers whose curl handles keep connections alive, for use by the next transport to the same host.
+static POOL: Mutex<http::pool::Pool<Worker>> = Mutex::new(http::pool::Pool::new());
+
+pub(crate) fn clear_pool() {
+    if let Ok(mut pool) = POOL.lock() {
+        pool.clear();
+    }
+}
+
 /// A utility to abstract interactions with curl handles.
 pub struct Curl {
     req: SyncSender<remote::Request>,
     res: Receiver<remote::Response>,
     handle: Option<thread::JoinHandle<Result<(), Error>>>,
     config: http::Options,
+    /// The key under which our worker is returned to the pool when dropped, set if connections are pooled.
+    pool_key: Option<String>,
 }
 
 impl Curl {
@@ -91,6 +111,16 @@ impl Curl {
         for header in headers {
             list.append(header.as_ref())?;
         }
+        let reset = self.config.pool_connections && self.pool_key.is_none();
+        if reset {
+            let key = http::pool::key(base_url).to_owned();
+            if let Some((handle, req, res)) = POOL.lock().ok().and_then(|mut pool| pool.take(&key)) {
+                self.handle = Some(handle);
+                self.req = req;
+                self.res = res;
+            }
+            self.pool_key = Some(key);
+        }
         if self
             .req
             .send(remote::Request {
@@ -99,6 +129,7 @@ impl Curl {
                 headers: list,
                 upload_body_kind,
                 config: self.config.clone(),
+                reset,
             })
             .is_err()
         {
@@ -128,6 +159,23 @@ impl Default for Curl {
             req,
             res,
             config: http::Options::default(),
+            pool_key: None,
+        }
+    }
+}
+
+impl Drop for Curl {
+    fn drop(&mut self) {
+        let (Some(key), Some(handle)) = (self.pool_key.take(), self.handle.take()) else {
+            return;
+        };
+        if handle.is_finished() {
+            return;
+        }
+        let req = std::mem::replace(&mut self.req, sync_channel(0).0);
+        let res = std::mem::replace(&mut self.res, sync_channel(0).1);
+        if let Ok(mut pool) = POOL.lock() {
+            pool.put(key, (handle, req, res));
         }
     }
 }
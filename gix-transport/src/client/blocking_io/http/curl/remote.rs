@@ -113,6 +113,8 @@ pub struct Request {
     pub headers: curl::easy::List,
     pub upload_body_kind: Option<PostBodyDataKind>,
     pub config: http::Options,
+    /// If `true`, this is the first request of a new transport, so all state of prior requests must be forgotten.
+    pub reset: bool,
 }
 
 pub struct Response {
@@ -130,9 +132,7 @@ pub fn new() -> (
     let (res_send, res_recv) = sync_channel(0);
     let handle = std::thread::spawn(move || -> Result<(), Error> {
         let mut handle = Easy2::new(Handler::default());
-        // We don't wait for the possibility for pipelining to become clear, and curl tries to reuse connections by default anyway.
-        handle.pipewait(false)?;
-        handle.tcp_keepalive(true)?;
+        configure_defaults(&mut handle)?;
 
         let mut follow = None;
         let mut redirected_base_url = None::<String>;
@@ -159,10 +159,19 @@ pub fn new() -> (
                     ssl_version,
                     ssl_verify,
                     http_version,
+                    pool_connections,
                     backend,
                 },
+            reset,
         } in req_recv
         {
+            if reset {
+                // Options are reset to not leak them into the next transport, but connections and caches are kept.
+                handle.reset();
+                configure_defaults(&mut handle)?;
+                follow = None;
+                redirected_base_url = None;
+            }
             let effective_url = redirect::swap_tails(redirected_base_url.as_deref(), &base_url, url.clone());
             handle.url(&effective_url)?;
 
@@ -208,6 +217,10 @@ pub fn new() -> (
                 // Furthermore, `git` itself doesn't actually check for errors when configuring curl at all,
                 // treating all or most flags as non-critical.
                 handle.http_version(version).ok();
+            } else if pool_connections {
+                // Prefer HTTP/2 where the server supports it, as a single multiplexed connection then serves all requests,
+                // and it's kept alive along with its TLS session for the next transport.
+                handle.http_version(curl::easy::HttpVersion::V2TLS).ok();
             }
 
             let mut proxy_auth_action = None;
@@ -365,6 +378,13 @@ pub fn new() -> (
     (handle, req_send, res_recv)
 }
 
+fn configure_defaults(handle: &mut Easy2<Handler>) -> Result<(), curl::Error> {
+    // We don't wait for the possibility for pipelining to become clear, and curl tries to reuse connections by default anyway.
+    handle.pipewait(false)?;
+    handle.tcp_keepalive(true)?;
+    Ok(())
+}
+
 fn to_curl_ssl_version(vers: SslVersion) -> curl::easy::SslVersion {
     use curl::easy::SslVersion::*;
     match vers {
@@ -36,6 +36,8 @@ pub mod curl;
 #[cfg(feature = "http-client-reqwest")]
 pub mod reqwest;
 
+#[cfg(any(feature = "http-client-curl", feature = "http-client-reqwest"))]
+mod pool;
 mod traits;
 
 ///
@@ -185,6 +187,16 @@ pub struct Options {
     pub ssl_verify: bool,
     /// The HTTP version to enforce. If unset, it is implementation defined.
     pub http_version: Option<HttpVersion>,
+    /// If `true`, the connections of a transport are kept alive when it's dropped so the next transport to the same
+    /// host within this process can reuse them, along with HTTP/2 sessions and TLS state.
+    ///
+    /// This avoids the cost of connecting for tools that talk to the same remotes repeatedly.
+    /// Unless [`http_version`](Self::http_version) is set, HTTP/2 is preferred for `https://` URLs if the server supports it.
+    /// Note that idle connections are handed to one transport at a time, so transports that run concurrently
+    /// each use connections of their own.
+    /// Call [`clear_connection_pool()`] to close all idle connections.
+    /// Refers to `gitoxide.http.poolConnections`.
+    pub pool_connections: bool,
     /// Backend specific options, if available.
     pub backend: Option<Arc<Mutex<dyn Any + Send + Sync + 'static>>>,
 }
@@ -207,11 +219,23 @@ impl Default for Options {
             ssl_version: None,
             ssl_verify: true,
             http_version: None,
+            pool_connections: false,
             backend: None,
         }
     }
 }
 
+/// Close all connections that were kept alive for reuse as [`Options::pool_connections`] was set.
+///
+/// Connections that are currently used by a transport are not affected.
+#[cfg(any(feature = "http-client-curl", feature = "http-client-reqwest"))]
+pub fn clear_connection_pool() {
+    #[cfg(feature = "http-client-curl")]
+    curl::clear_pool();
+    #[cfg(feature = "http-client-reqwest")]
+    reqwest::clear_pool();
+}
+
 /// The actual http client implementation, using curl
 #[cfg(feature = "http-client-curl")]
 pub type Impl = curl::Curl;
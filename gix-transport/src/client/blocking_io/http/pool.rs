@@ -0,0 +1,67 @@
+/// The maximum amount of idle workers to keep per backend, after which the least recently returned ones are dropped.
+const MAX_IDLE: usize = 64;
+
+/// Idle request workers along with the connections they keep alive, keyed by the scheme and authority they last talked to.
+pub(crate) struct Pool<T> {
+    idle: Vec<(String, T)>,
+}
+
+impl<T> Pool<T> {
+    pub(crate) const fn new() -> Self {
+        Pool { idle: Vec::new() }
+    }
+
+    /// Remove and return the most recently returned worker for `key`, if there is one.
+    pub(crate) fn take(&mut self, key: &str) -> Option<T> {
+        let pos = self.idle.iter().rposition(|(idle_key, _)| idle_key == key)?;
+        Some(self.idle.remove(pos).1)
+    }
+
+    /// Keep `worker` for reuse by the next transport connecting to `key`.
+    pub(crate) fn put(&mut self, key: String, worker: T) {
+        if self.idle.len() >= MAX_IDLE {
+            self.idle.remove(0);
+        }
+        self.idle.push((key, worker));
+    }
+
+    pub(crate) fn clear(&mut self) {
+        self.idle.clear();
+    }
+}
+
+/// Return the portion of `url` that identifies the connection to use, i.e. `scheme://authority`.
+pub(crate) fn key(url: &str) -> &str {
+    let start = url.find("://").map_or(0, |pos| pos + 3);
+    let end = url[start..].find('/').map_or(url.len(), |pos| start + pos);
+    &url[..end]
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn key() {
+        assert_eq!(super::key("https://example.com/repo.git"), "https://example.com");
+        assert_eq!(
+            super::key("http://user@example.com:8080/a/b?service=git-upload-pack"),
+            "http://user@example.com:8080"
+        );
+        assert_eq!(super::key("https://example.com"), "https://example.com");
+    }
+
+    #[test]
+    fn take_prefers_most_recent_and_put_evicts_oldest() {
+        let mut pool = super::Pool::new();
+        pool.put("a".into(), 1);
+        pool.put("b".into(), 2);
+        pool.put("a".into(), 3);
+        assert_eq!(pool.take("a"), Some(3));
+        assert_eq!(pool.take("a"), Some(1));
+        assert_eq!(pool.take("a"), None);
+
+        for n in 0..super::MAX_IDLE {
+            pool.put("c".into(), n);
+        }
+        assert_eq!(pool.take("b"), None, "the oldest entry was dropped to make room");
+    }
+}
@@ -8,6 +8,8 @@ pub struct Remote {
     response: std::sync::mpsc::Receiver<remote::Response>,
     /// A mechanism for configuring the remote.
     config: crate::client::http::Options,
+    /// The key under which our worker is returned to the pool when dropped, set if connections are pooled.
+    pool_key: Option<String>,
 }
 
 /// A function to configure a single request prior to sending it, support most complex configuration beyond what's possible with
@@ -26,3 +28,4 @@ pub struct Options {
 
 ///
 pub mod remote;
+pub(crate) use remote::clear_pool;
@@ -2,7 +2,7 @@ use std::{
     any::Any,
     io::{Read, Write},
     str::FromStr,
-    sync::{atomic, Arc},
+    sync::{atomic, mpsc, Arc, Mutex},
 };
 
 use gix_features::io::pipe;
@@ -34,6 +34,21 @@ impl crate::IsSpuriousError for Error {
     }
 }
 
+type Worker = (
+    std::thread::JoinHandle<Result<(), Error>>,
+    mpsc::SyncSender<Request>,
+    mpsc::Receiver<Response>,
+);
+
+/// Workers whose clients keep connections alive, for use by the next transport to the same host.
+static POOL: Mutex<http::pool::Pool<Worker>> = Mutex::new(http::pool::Pool::new());
+
+pub(crate) fn clear_pool() {
+    if let Ok(mut pool) = POOL.lock() {
+        pool.clear();
+    }
+}
+
 impl Default for Remote {
     fn default() -> Self {
         let (req_send, req_recv) = std::sync::mpsc::sync_channel(0);
@@ -83,8 +98,13 @@ impl Default for Remote {
                 headers,
                 upload_body_kind,
                 config,
+                reset,
             } in req_recv
             {
+                if reset {
+                    follow = None;
+                    redirected_base_url = None;
+                }
                 let effective_url = redirect::swap_tails(redirected_base_url.as_deref(), &base_url, url.clone());
                 let mut req_builder = if upload_body_kind.is_some() {
                     client.post(&effective_url)
@@ -198,6 +218,23 @@ impl Default for Remote {
             request: req_send,
             response: res_recv,
             config: http::Options::default(),
+            pool_key: None,
+        }
+    }
+}
+
+impl Drop for Remote {
+    fn drop(&mut self) {
+        let (Some(key), Some(handle)) = (self.pool_key.take(), self.handle.take()) else {
+            return;
+        };
+        if handle.is_finished() {
+            return;
+        }
+        let request = std::mem::replace(&mut self.request, mpsc::sync_channel(0).0);
+        let response = std::mem::replace(&mut self.response, mpsc::sync_channel(0).1);
+        if let Ok(mut pool) = POOL.lock() {
+            pool.put(key, (handle, request, response));
         }
     }
 }
@@ -242,6 +279,16 @@ impl Remote {
                 None => continue,
             };
         }
+        let reset = self.config.pool_connections && self.pool_key.is_none();
+        if reset {
+            let key = http::pool::key(base_url).to_owned();
+            if let Some((handle, request, response)) = POOL.lock().ok().and_then(|mut pool| pool.take(&key)) {
+                self.handle = Some(handle);
+                self.request = request;
+                self.response = response;
+            }
+            self.pool_key = Some(key);
+        }
         if self
             .request
             .send(Request {
@@ -250,6 +297,7 @@ impl Remote {
                 headers: header_map,
                 upload_body_kind,
                 config: self.config.clone(),
+                reset,
             })
             .is_err()
         {
@@ -313,6 +361,8 @@ pub(crate) struct Request {
     pub headers: reqwest::header::HeaderMap,
     pub upload_body_kind: Option<PostBodyDataKind>,
     pub config: http::Options,
+    /// If `true`, this is the first request of a new transport, so all state of prior requests must be forgotten.
+    pub reset: bool,
 }
 
 /// A link to a thread who provides data for the contained readers.
@@ -4,6 +4,8 @@ pub mod connect;
 ///
 pub mod file;
 ///
+pub mod helper;
+///
 #[cfg(feature = "http-client")]
 pub mod http;
 
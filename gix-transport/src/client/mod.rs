@@ -15,8 +15,8 @@ mod blocking_io;
 pub use blocking_io::http;
 #[cfg(feature = "blocking-client")]
 pub use blocking_io::{
-    connect, file, ssh, ExtendedBufRead, HandleProgress, ReadlineBufRead, RequestWriter, SetServiceResponse, Transport,
-    TransportV2Ext,
+    connect, file, helper, ssh, ExtendedBufRead, HandleProgress, ReadlineBufRead, RequestWriter, SetServiceResponse,
+    Transport, TransportV2Ext,
 };
 #[cfg(feature = "blocking-client")]
 #[doc(inline)]
@@ -97,16 +97,20 @@ mod error {
     #[cfg(feature = "http-client")]
     use crate::client::http;
     #[cfg(feature = "blocking-client")]
-    use crate::client::ssh;
+    use crate::client::{helper, ssh};
 
     #[cfg(feature = "http-client")]
     type HttpError = http::Error;
     #[cfg(feature = "blocking-client")]
     type SshInvocationError = ssh::invocation::Error;
+    #[cfg(feature = "blocking-client")]
+    type RemoteHelperError = helper::Error;
     #[cfg(not(feature = "http-client"))]
     type HttpError = std::convert::Infallible;
     #[cfg(not(feature = "blocking-client"))]
     type SshInvocationError = std::convert::Infallible;
+    #[cfg(not(feature = "blocking-client"))]
+    type RemoteHelperError = std::convert::Infallible;
 
     /// The error used in most methods of the [`client`][crate::client] module
     #[derive(thiserror::Error, Debug)]
@@ -140,6 +144,8 @@ mod error {
         Http(#[from] HttpError),
         #[error(transparent)]
         SshInvocation(SshInvocationError),
+        #[error(transparent)]
+        RemoteHelper(RemoteHelperError),
         #[error("The repository path '{path}' could be mistaken for a command-line argument")]
         AmbiguousPath { path: BString },
     }
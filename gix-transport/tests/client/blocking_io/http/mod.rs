@@ -145,6 +145,105 @@ fn http_will_use_pipelining() {
     thread.join().unwrap();
 }
 
+/// Serve `401 Unauthorized` to every request on connections that are kept alive, and return the address to connect to
+/// along with the amount of accepted connections and the request lines received so far.
+fn serve_unauthorized_with_keep_alive() -> (
+    std::net::SocketAddr,
+    std::sync::Arc<std::sync::atomic::AtomicUsize>,
+    std::sync::Arc<std::sync::Mutex<Vec<String>>>,
+) {
+    let server = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = server.local_addr().unwrap();
+    let connections = std::sync::Arc::new(std::sync::atomic::AtomicUsize::default());
+    let request_lines = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+    std::thread::spawn({
+        let connections = connections.clone();
+        let request_lines = request_lines.clone();
+        move || {
+            for stream in server.incoming() {
+                connections.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+                let request_lines = request_lines.clone();
+                std::thread::spawn(move || {
+                    let mut conn = std::io::BufReader::new(stream.unwrap());
+                    loop {
+                        let mut lines = (&mut conn)
+                            .lines()
+                            .map_while(Result::ok)
+                            .take_while(|line| !line.trim().is_empty());
+                        let Some(request_line) = lines.next() else {
+                            break;
+                        };
+                        lines.for_each(drop);
+                        request_lines.lock().unwrap().push(request_line.trim().to_owned());
+                        if conn
+                            .get_mut()
+                            .write_all(b"HTTP/1.1 401 Unauthorized\r\nContent-Length: 0\r\n\r\n")
+                            .is_err()
+                        {
+                            break;
+                        }
+                    }
+                });
+            }
+        }
+    });
+    (addr, connections, request_lines)
+}
+
+fn handshake_with_pooled_connection(addr: std::net::SocketAddr, proxy: Option<String>) -> crate::Result {
+    let url = format!("http://{addr}/reponame");
+    let mut client = http::connect(url.try_into().expect("valid url"), Protocol::V2, false);
+    client
+        .configure(&http::Options {
+            pool_connections: true,
+            proxy,
+            ..Default::default()
+        })
+        .expect("http options are accepted");
+    match client.handshake(Service::UploadPack, &[]) {
+        Ok(_) => unreachable!("expecting permission denied to be detected"),
+        Err(client::Error::Io(err)) if err.kind() == std::io::ErrorKind::PermissionDenied => {}
+        Err(err) => unreachable!("{err:?}"),
+    }
+    Ok(())
+}
+
+#[test]
+fn pooled_connections_are_reused_by_the_next_transport_to_the_same_host() -> crate::Result {
+    let (addr, connections, request_lines) = serve_unauthorized_with_keep_alive();
+
+    handshake_with_pooled_connection(addr, None)?;
+    handshake_with_pooled_connection(addr, None)?;
+    assert_eq!(
+        request_lines.lock().unwrap().len(),
+        2,
+        "each transport performed its request"
+    );
+    assert_eq!(
+        connections.load(std::sync::atomic::Ordering::SeqCst),
+        1,
+        "the second transport used the connection the first one left in the pool"
+    );
+    Ok(())
+}
+
+#[test]
+fn pooled_connections_do_not_pass_options_on_to_the_next_transport() -> crate::Result {
+    let (addr, _connections, request_lines) = serve_unauthorized_with_keep_alive();
+
+    handshake_with_pooled_connection(addr, Some(format!("http://{addr}")))?;
+    handshake_with_pooled_connection(addr, None)?;
+    assert_eq!(
+        *request_lines.lock().unwrap(),
+        [
+            format!("GET http://{addr}/reponame/info/refs?service=git-upload-pack HTTP/1.1"),
+            "GET /reponame/info/refs?service=git-upload-pack HTTP/1.1".into()
+        ],
+        "the first request went through the proxy, but the next transport didn't inherit it"
+    );
+    Ok(())
+}
+
 #[test]
 fn http_authentication_error_can_be_differentiated_and_identity_is_transmitted() -> crate::Result {
     let (server, mut client) = assert_error_status(401, std::io::ErrorKind::PermissionDenied)?;
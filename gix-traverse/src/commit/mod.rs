@@ -14,6 +14,7 @@ pub struct Simple<Find, Predicate> {
     predicate: Predicate,
     state: simple::State,
     parents: Parents,
+    parents_provider: Option<Box<dyn gix_revwalk::Parents + Send + Sync>>,
     sorting: simple::Sorting,
 }
 
@@ -34,6 +35,7 @@ pub struct Topo<Find, Predicate> {
     indegree_queue: PriorityQueue<topo::iter::GenAndCommitTime, ObjectId>,
     topo_queue: topo::iter::Queue,
     parents: Parents,
+    parents_provider: Option<Box<dyn gix_revwalk::Parents + Send + Sync>>,
     min_gen: u32,
     buf: Vec<u8>,
 }
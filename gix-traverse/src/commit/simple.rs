@@ -176,6 +176,13 @@ mod init {
             self
         }
 
+        /// Set or unset the `provider` of parents that take precedence over the ones recorded in commits,
+        /// which is how grafts and the shallow boundary are applied.
+        pub fn parents_provider(mut self, provider: Option<Box<dyn gix_revwalk::Parents + Send + Sync>>) -> Self {
+            self.parents_provider = provider;
+            self
+        }
+
         /// Set the commitgraph as `cache` to greatly accelerate any traversal.
         ///
         /// The cache will be used if possible, but we will fall-back without error to using the object
@@ -252,6 +259,7 @@ mod init {
                 predicate,
                 state,
                 parents: Default::default(),
+                parents_provider: None,
                 sorting: Default::default(),
             }
         }
@@ -317,7 +325,29 @@ mod init {
                 (Newest(t) | Oldest(Reverse(t)), o) => (t, o),
             };
             let mut parents: ParentIds = Default::default();
+            let overridden_parents = self.parents_provider.as_ref().and_then(|p| p.parents_of(&oid));
             match super::super::find(self.cache.as_ref(), &self.objects, &oid, &mut state.buf) {
+                Ok(_) if overridden_parents.is_some() => {
+                    for &id in overridden_parents.expect("checked") {
+                        parents.push(id);
+                        let was_inserted = state.seen.insert(id);
+                        if !(was_inserted && (self.predicate)(&id)) {
+                            continue;
+                        }
+
+                        let parent_commit_time = self
+                            .objects
+                            .find_commit_iter(id.as_ref(), &mut state.parents_buf)
+                            .ok()
+                            .and_then(|parent| parent.committer().ok().map(|committer| committer.time.seconds))
+                            .unwrap_or_default();
+                        let key = to_queue_key(parent_commit_time, order);
+                        match cutoff {
+                            Some(cutoff_older_than) if parent_commit_time < cutoff_older_than => continue,
+                            Some(_) | None => state.queue.insert(key, id),
+                        }
+                    }
+                }
                 Ok(Either::CachedCommit(commit)) => {
                     if !collect_parents(&mut state.parent_ids, self.cache.as_ref(), commit.iter_parents()) {
                         // drop corrupt caches and try again with ODB
@@ -385,7 +415,20 @@ mod init {
             let state = &mut self.state;
             let oid = state.next.pop_front()?;
             let mut parents: ParentIds = Default::default();
+            let overridden_parents = self.parents_provider.as_ref().and_then(|p| p.parents_of(&oid));
             match super::super::find(self.cache.as_ref(), &self.objects, &oid, &mut state.buf) {
+                Ok(_) if overridden_parents.is_some() => {
+                    for &id in overridden_parents.expect("checked") {
+                        parents.push(id);
+                        let was_inserted = state.seen.insert(id);
+                        if was_inserted && (self.predicate)(&id) {
+                            state.next.push_back(id);
+                        }
+                        if matches!(self.parents, Parents::First) {
+                            break;
+                        }
+                    }
+                }
                 Ok(Either::CachedCommit(commit)) => {
                     if !collect_parents(&mut state.parent_ids, self.cache.as_ref(), commit.iter_parents()) {
                         // drop corrupt caches and try again with ODB
@@ -12,6 +12,7 @@ pub struct Builder<Find, Predicate> {
     predicate: Predicate,
     sorting: Sorting,
     parents: Parents,
+    parents_provider: Option<Box<dyn gix_revwalk::Parents + Send + Sync>>,
     tips: Vec<ObjectId>,
     ends: Vec<ObjectId>,
 }
@@ -38,6 +39,7 @@ where
             find,
             sorting: Default::default(),
             parents: Default::default(),
+            parents_provider: None,
             tips,
             ends,
             predicate: |_| true,
@@ -57,6 +59,7 @@ where
             find: self.find,
             sorting: self.sorting,
             parents: self.parents,
+            parents_provider: self.parents_provider,
             tips: self.tips,
             ends: self.ends,
             predicate,
@@ -81,6 +84,13 @@ where
         self
     }
 
+    /// Set or unset the `provider` of parents that take precedence over the ones recorded in commits,
+    /// which is how grafts and the shallow boundary are applied.
+    pub fn with_parents_provider(mut self, provider: Option<Box<dyn gix_revwalk::Parents + Send + Sync>>) -> Self {
+        self.parents_provider = provider;
+        self
+    }
+
     /// Set or unset the `commit_graph` to use for the iteration.
     pub fn with_commit_graph(mut self, commit_graph: Option<gix_commitgraph::Graph>) -> Self {
         self.commit_graph = commit_graph;
@@ -101,6 +111,7 @@ where
             indegree_queue: PriorityQueue::new(),
             topo_queue: super::iter::Queue::new(self.sorting),
             parents: self.parents,
+            parents_provider: self.parents_provider,
             min_gen: gix_commitgraph::GENERATION_NUMBER_INFINITY,
             buf: vec![],
         };
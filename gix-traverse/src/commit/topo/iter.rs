@@ -182,6 +182,7 @@ where
         collect_parents(
             &mut self.commit_graph,
             &self.find,
+            self.parents_provider.as_deref(),
             id,
             matches!(self.parents, Parents::First),
             &mut self.buf,
@@ -193,7 +194,14 @@ where
         &mut self,
         id: &oid,
     ) -> Result<SmallVec<[(ObjectId, GenAndCommitTime); 1]>, Error> {
-        collect_parents(&mut self.commit_graph, &self.find, id, false, &mut self.buf)
+        collect_parents(
+            &mut self.commit_graph,
+            &self.find,
+            self.parents_provider.as_deref(),
+            id,
+            false,
+            &mut self.buf,
+        )
     }
 
     fn pop_commit(&mut self) -> Option<Result<Info, Error>> {
@@ -238,6 +246,7 @@ where
 fn collect_parents<Find>(
     cache: &mut Option<gix_commitgraph::Graph>,
     f: Find,
+    provider: Option<&(dyn gix_revwalk::Parents + Send + Sync)>,
     id: &oid,
     first_only: bool,
     buf: &mut Vec<u8>,
@@ -246,6 +255,14 @@ where
     Find: gix_object::Find,
 {
     let mut parents = SmallVec::<[(ObjectId, GenAndCommitTime); 1]>::new();
+    if let Some(overridden) = provider.and_then(|p| p.parents_of(id)) {
+        let take = if first_only { 1 } else { overridden.len() };
+        for id in overridden.iter().take(take) {
+            let commit = find(cache.as_ref(), &f, id, buf)?;
+            parents.push((*id, gen_and_commit_time(commit)?));
+        }
+        return Ok(parents);
+    }
     match find(cache.as_ref(), &f, id, buf)? {
         Either::CommitRefIter(c) => {
             for token in c {
@@ -274,7 +291,7 @@ where
                 let Ok(pos) = pos else {
                     // drop corrupt cache and use ODB from now on.
                     *cache = None;
-                    return collect_parents(cache, f, id, first_only, buf);
+                    return collect_parents(cache, f, provider, id, first_only, buf);
                 };
                 let parent_commit = cache
                     .as_ref()
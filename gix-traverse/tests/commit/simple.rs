@@ -10,6 +10,7 @@ struct TraversalAssertion<'a> {
     expected: &'a [&'a str],
     mode: commit::Parents,
     sorting: commit::simple::Sorting,
+    grafts: Option<gix_revwalk::parents::Grafts>,
 }
 
 impl<'a> TraversalAssertion<'a> {
@@ -25,6 +26,7 @@ impl<'a> TraversalAssertion<'a> {
             expected,
             mode: Default::default(),
             sorting: Default::default(),
+            grafts: None,
         }
     }
 
@@ -37,6 +39,11 @@ impl<'a> TraversalAssertion<'a> {
         self.sorting = sorting;
         self
     }
+
+    fn with_grafts(&mut self, grafts: gix_revwalk::parents::Grafts) -> &mut Self {
+        self.grafts = Some(grafts);
+        self
+    }
 }
 
 impl TraversalAssertion<'_> {
@@ -66,6 +73,7 @@ impl TraversalAssertion<'_> {
             let oids = commit::Simple::filtered(tips.clone(), &store, predicate.clone())
                 .sorting(self.sorting)?
                 .parents(self.mode)
+                .parents_provider(self.grafts.clone().map(|grafts| Box::new(grafts) as _))
                 .commit_graph(self.setup_commitgraph(store.store_ref(), use_commitgraph))
                 .map(|res| res.map(|info| info.id))
                 .collect::<Result<Vec<_>, _>>()?;
@@ -82,6 +90,7 @@ impl TraversalAssertion<'_> {
             let oids = commit::Simple::new(tips.clone(), &store)
                 .sorting(self.sorting)?
                 .parents(self.mode)
+                .parents_provider(self.grafts.clone().map(|grafts| Box::new(grafts) as _))
                 .commit_graph(self.setup_commitgraph(store.store_ref(), use_commitgraph))
                 .map(|res| res.map(|info| info.id))
                 .collect::<Result<Vec<_>, _>>()?;
@@ -539,3 +548,55 @@ mod adjusted_dates {
         [CommitTimeOrder::NewestFirst, CommitTimeOrder::OldestFirst]
     }
 }
+
+mod grafts {
+    use gix_traverse::commit::simple::{CommitTimeOrder, Sorting};
+
+    use crate::{commit::simple::TraversalAssertion, hex_to_id};
+
+    #[test]
+    fn grafted_parents_replace_the_recorded_ones() -> crate::Result {
+        let mut grafts = gix_revwalk::parents::Grafts::default();
+        grafts.insert(
+            hex_to_id("01ec18a3ebf2855708ad3c9d244306bc1fae3e9b"), /* m1b1 */
+            Some(hex_to_id("9556057aee5abb06912922e9f26c46386a816822")), /* c4 */
+        );
+        for sorting in [
+            Sorting::BreadthFirst,
+            Sorting::ByCommitTime(CommitTimeOrder::NewestFirst),
+            Sorting::ByCommitTime(CommitTimeOrder::OldestFirst),
+        ] {
+            TraversalAssertion::new(
+                "make_traversal_repo_for_commits_same_date.sh",
+                &["01ec18a3ebf2855708ad3c9d244306bc1fae3e9b"], /* m1b1 */
+                &[
+                    "9556057aee5abb06912922e9f26c46386a816822", /* c4 */
+                    "17d78c64cef6c33a10a604573fd2c429e477fd63", /* c3 */
+                    "9902e3c3e8f0c569b4ab295ddf473e6de763e1e7", /* c2 */
+                    "134385f6d781b7e97062102c6a483440bfda2a03", /* c1 */
+                ],
+            )
+            .with_sorting(sorting)
+            .with_grafts(grafts.clone())
+            .check()?;
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn shallow_commits_have_no_parents() -> crate::Result {
+        let mut grafts = gix_revwalk::parents::Grafts::default();
+        grafts.insert_shallow(Some(hex_to_id("9902e3c3e8f0c569b4ab295ddf473e6de763e1e7"))); /* c2 */
+        TraversalAssertion::new(
+            "make_traversal_repo_for_commits_same_date.sh",
+            &["9556057aee5abb06912922e9f26c46386a816822"], /* c4 */
+            &[
+                "17d78c64cef6c33a10a604573fd2c429e477fd63", /* c3 */
+                "9902e3c3e8f0c569b4ab295ddf473e6de763e1e7", /* c2 */
+            ],
+        )
+        .with_sorting(Sorting::BreadthFirst)
+        .with_grafts(grafts)
+        .check()
+    }
+}
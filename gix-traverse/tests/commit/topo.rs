@@ -14,6 +14,7 @@ struct TraversalAssertion<'a> {
     expected: &'a [&'a str],
     mode: Parents,
     sorting: topo::Sorting,
+    grafts: Option<gix_revwalk::parents::Grafts>,
 }
 
 /// API
@@ -38,6 +39,7 @@ impl<'a> TraversalAssertion<'a> {
             expected,
             mode: Default::default(),
             sorting: Default::default(),
+            grafts: None,
         }
     }
 
@@ -51,6 +53,11 @@ impl<'a> TraversalAssertion<'a> {
         self
     }
 
+    fn with_grafts(&mut self, grafts: gix_revwalk::parents::Grafts) -> &mut Self {
+        self.grafts = Some(grafts);
+        self
+    }
+
     fn check_with_predicate(&mut self, predicate: impl FnMut(&oid) -> bool + Clone) -> crate::Result<()> {
         let (store, tips, ends, expected) = self.setup()?;
 
@@ -59,6 +66,7 @@ impl<'a> TraversalAssertion<'a> {
                 .sorting(self.sorting)
                 .with_commit_graph(self.setup_commitgraph(store.store_ref(), use_commitgraph))
                 .parents(self.mode)
+                .with_parents_provider(self.grafts.clone().map(|grafts| Box::new(grafts) as _))
                 .with_predicate(predicate.clone())
                 .build()?
                 .map(|res| res.map(|info| info.id))
@@ -87,6 +95,7 @@ impl<'a> TraversalAssertion<'a> {
                 .sorting(self.sorting)
                 .with_commit_graph(self.setup_commitgraph(store.store_ref(), use_commitgraph))
                 .parents(self.mode)
+                .with_parents_provider(self.grafts.clone().map(|grafts| Box::new(grafts) as _))
                 .build()?
                 .map(|res| res.map(|info| info.id))
                 .collect::<Result<Vec<_>, _>>()?;
@@ -371,3 +380,39 @@ mod date_order {
         Ok(())
     }
 }
+
+mod grafts {
+    use gix_traverse::commit::{topo, Parents};
+
+    use super::TraversalAssertion;
+    use crate::hex_to_id;
+
+    #[test]
+    fn grafted_parents_replace_the_recorded_ones() -> crate::Result {
+        let mut grafts = gix_revwalk::parents::Grafts::default();
+        grafts.insert(
+            hex_to_id("62ed296d9986f50477e9f7b7e81cd0258939a43d"),
+            Some(hex_to_id("f1cce1b5c7efcdfa106e95caa6c45a2cae48a481")),
+        );
+        for mode in [Parents::All, Parents::First] {
+            TraversalAssertion::new(
+                &["62ed296d9986f50477e9f7b7e81cd0258939a43d"],
+                &[],
+                &[
+                    "62ed296d9986f50477e9f7b7e81cd0258939a43d",
+                    "f1cce1b5c7efcdfa106e95caa6c45a2cae48a481",
+                    "945d8a360915631ad545e0cf04630d86d3d4eaa1",
+                    "a863c02247a6c5ba32dff5224459f52aa7f77f7b",
+                    "2f291881edfb0597493a52d26ea09dd7340ce507",
+                    "9c46b8765703273feb10a2ebd810e70b8e2ca44a",
+                    "fb3e21cf45b04b617011d2b30973f3e5ce60d0cd",
+                ],
+            )
+            .with_sorting(topo::Sorting::TopoOrder)
+            .with_parents(mode)
+            .with_grafts(grafts.clone())
+            .check()?;
+        }
+        Ok(())
+    }
+}
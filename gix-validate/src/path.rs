@@ -53,6 +53,20 @@ pub mod component {
         }
     }
 
+    /// A file which `git` reads from the worktree, and which thus is dangerous to have as symbolic link as it
+    /// could point anywhere.
+    #[derive(Debug, Copy, Clone, PartialEq, Eq)]
+    pub enum DotFile {
+        /// The `.gitmodules` file.
+        GitModules,
+        /// The `.gitattributes` file.
+        GitAttributes,
+        /// The `.gitignore` file.
+        GitIgnore,
+        /// The `.mailmap` file.
+        Mailmap,
+    }
+
     /// The mode of the component, if it's the leaf of a path.
     #[derive(Debug, Copy, Clone, PartialEq, Eq)]
     pub enum Mode {
@@ -123,6 +137,26 @@ pub fn component(
     Ok(input)
 }
 
+/// Return `true` if the path component at `input` would refer to the special `file` once it is written to disk,
+/// taking into account the name-folding of HFS+ and the short-names and stream-names of NTFS if these are protected
+/// against as configured in `options`.
+///
+/// This is useful to detect dot-files that are symbolic links, as these might lead to files outside the worktree being read.
+pub fn component_is_dot_file(input: &BStr, file: component::DotFile, options: component::Options) -> bool {
+    use component::DotFile::*;
+    let (name, ntfs_shortname_prefix) = match file {
+        GitModules => ("gitmodules", "gi7eba"),
+        GitAttributes => ("gitattributes", "gi7d29"),
+        GitIgnore => ("gitignore", "gi250a"),
+        Mailmap => ("mailmap", "maba30"),
+    };
+    (options.protect_hfs && is_dot_hfs(input, name))
+        || (options.protect_ntfs && is_dot_ntfs(input, name, ntfs_shortname_prefix))
+        || input
+            .strip_prefix(b".")
+            .map_or(false, |input| input.eq_ignore_ascii_case(name.as_bytes()))
+}
+
 /// Return `true` if the path component at `input` looks like a Windows device, like `CON`
 /// or `LPT1` (case-insensitively).
 ///
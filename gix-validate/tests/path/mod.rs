@@ -8,6 +8,50 @@ fn component_is_windows_device() {
     }
 }
 
+#[test]
+fn component_is_dot_file() {
+    use gix_validate::path::component::{DotFile, Options};
+    let none = Options {
+        protect_windows: false,
+        protect_hfs: false,
+        protect_ntfs: false,
+    };
+    for (name, file) in [
+        (".gitattributes", DotFile::GitAttributes),
+        (".GitIgnore", DotFile::GitIgnore),
+        (".mailmap", DotFile::Mailmap),
+        (".gitmodules", DotFile::GitModules),
+    ] {
+        assert!(gix_validate::path::component_is_dot_file(name.into(), file, none));
+    }
+    for (name, file) in [
+        (".gitattributes.", DotFile::GitAttributes),
+        ("gitatt~1", DotFile::GitAttributes),
+        ("gi7d29~9", DotFile::GitAttributes),
+        ("gi250a~1", DotFile::GitIgnore),
+        (".gitignore:stream", DotFile::GitIgnore),
+        ("mailma~2", DotFile::Mailmap),
+        ("maba30~1", DotFile::Mailmap),
+        ("gi7eba~1", DotFile::GitModules),
+    ] {
+        assert!(
+            gix_validate::path::component_is_dot_file(name.into(), file, Options::default()),
+            "{name}: NTFS shortnames and streams"
+        );
+        assert!(!gix_validate::path::component_is_dot_file(name.into(), file, none));
+    }
+    assert!(gix_validate::path::component_is_dot_file(
+        ".git\u{200c}ignore".into(),
+        DotFile::GitIgnore,
+        Options::default()
+    ));
+    assert!(!gix_validate::path::component_is_dot_file(
+        ".gitignore".into(),
+        DotFile::Mailmap,
+        Options::default()
+    ));
+}
+
 mod component {
     use gix_validate::path::component;
 
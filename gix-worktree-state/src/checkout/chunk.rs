@@ -308,6 +308,19 @@ where
         },
         *options,
     );
+    handle_result(res, entry_path, errors, collisions, files, bytes, options.keep_going)
+}
+
+/// Count `res` if it was successful, or record it as collision or error if `keep_going` is `true`.
+pub fn handle_result<'entry>(
+    res: Result<entry::Outcome<'entry>, checkout::Error>,
+    entry_path: &BStr,
+    errors: &mut Vec<checkout::ErrorRecord>,
+    collisions: &mut Vec<checkout::Collision>,
+    files: &AtomicUsize,
+    bytes: &AtomicUsize,
+    keep_going: bool,
+) -> Result<entry::Outcome<'entry>, checkout::Error> {
     match res {
         Ok(out) => {
             if let Some(num) = out.as_bytes() {
@@ -319,8 +332,9 @@ where
         Err(checkout::Error::Io(err)) if is_collision(&err, entry_path, collisions, files) => {
             Ok(entry::Outcome::Written { bytes: 0 })
         }
-        Err(err) => handle_error(err, entry_path, files, errors, options.keep_going)
-            .map(|()| entry::Outcome::Written { bytes: 0 }),
+        Err(err) => {
+            handle_error(err, entry_path, files, errors, keep_going).map(|()| entry::Outcome::Written { bytes: 0 })
+        }
     }
 }
 
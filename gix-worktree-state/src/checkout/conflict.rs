@@ -0,0 +1,246 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use bstr::BStr;
+use gix_filter::driver::apply::Delay;
+#[cfg(feature = "merge")]
+use gix_index::entry::Mode;
+use gix_index::{entry::Stage, Entry};
+
+use crate::checkout::{self, chunk, entry, Conflicts};
+
+/// Write all conflicted paths in `entries` according to `conflicts`, with all stages of a path being adjacent to each other.
+///
+/// Note that `entries` are copies of the index entries, so the index itself isn't altered.
+pub fn process<Find>(
+    mut entries: &[(Entry, &BStr)],
+    conflicts: &Conflicts,
+    out: &mut chunk::Outcome<'_>,
+    files: &AtomicUsize,
+    bytes: &AtomicUsize,
+    ctx: &mut chunk::Context<Find>,
+) -> Result<(), checkout::Error>
+where
+    Find: gix_object::Find + Clone,
+{
+    // Conflicts are written last, so there is no opportunity to process delayed files anymore.
+    ctx.options.filter_process_delay = Delay::Forbid;
+    #[cfg(feature = "merge")]
+    let mut platform = None;
+    while let Some((_, rela_path)) = entries.first() {
+        let rela_path = *rela_path;
+        let num_stages = entries.iter().take_while(|(_, path)| *path == rela_path).count();
+        let (stages, rest) = entries.split_at(num_stages);
+        entries = rest;
+
+        if matches!(conflicts, Conflicts::Skip) {
+            files.fetch_add(num_stages, Ordering::Relaxed);
+            continue;
+        }
+        // Only one file is written for all stages.
+        files.fetch_add(num_stages - 1, Ordering::Relaxed);
+        let res = match conflicts {
+            Conflicts::Skip => unreachable!("handled above"),
+            Conflicts::Ours => checkout_stage(stages, rela_path, Stage::Ours, ctx),
+            Conflicts::Theirs => checkout_stage(stages, rela_path, Stage::Theirs, ctx),
+            #[cfg(feature = "merge")]
+            Conflicts::Merge(merge_options) => merge(stages, rela_path, merge_options, &mut platform, ctx),
+        };
+        let outcome = chunk::handle_result(
+            res,
+            rela_path,
+            &mut out.errors,
+            &mut out.collisions,
+            files,
+            bytes,
+            ctx.options.keep_going,
+        )?;
+        out.bytes_written += outcome.as_bytes().expect("delays are forbidden") as u64;
+        out.files += 1;
+    }
+    Ok(())
+}
+
+fn entry_at<'a>(stages: &'a [(Entry, &BStr)], stage: Stage) -> Option<&'a Entry> {
+    stages
+        .iter()
+        .find_map(|(entry, _)| (entry.stage() == stage).then_some(entry))
+}
+
+/// Write the entry at `stage` as if it wasn't conflicting.
+fn checkout_stage<Find>(
+    stages: &[(Entry, &BStr)],
+    rela_path: &BStr,
+    stage: Stage,
+    ctx: &mut chunk::Context<Find>,
+) -> Result<entry::Outcome<'static>, checkout::Error>
+where
+    Find: gix_object::Find + Clone,
+{
+    let entry = entry_at(stages, stage).ok_or_else(|| checkout::Error::MissingStage {
+        path: rela_path.to_owned(),
+        stage,
+    })?;
+    let bytes = entry::checkout(
+        &mut entry.clone(),
+        rela_path,
+        entry::Context {
+            objects: &mut ctx.objects,
+            path_cache: &mut ctx.path_cache,
+            filters: &mut ctx.filters,
+            buf: &mut ctx.buf,
+        },
+        ctx.options,
+    )?
+    .as_bytes()
+    .expect("delays are forbidden");
+    Ok(entry::Outcome::Written { bytes })
+}
+
+#[cfg(feature = "merge")]
+fn is_file(mode: Mode) -> bool {
+    matches!(mode, Mode::FILE | Mode::FILE_EXECUTABLE)
+}
+
+/// Merge our and their version of `rela_path` according to its `merge` attribute and write the result, with conflict
+/// markers, using the mode of our entry.
+#[cfg(feature = "merge")]
+fn merge<Find>(
+    stages: &[(Entry, &BStr)],
+    rela_path: &BStr,
+    merge: &checkout::Merge,
+    platform: &mut Option<gix_merge::blob::Platform>,
+    chunk::Context {
+        objects,
+        path_cache,
+        filters,
+        buf,
+        options: chunk_options,
+    }: &mut chunk::Context<Find>,
+) -> Result<entry::Outcome<'static>, checkout::Error>
+where
+    Find: gix_object::Find + Clone,
+{
+    use gix_filter::{driver::apply::MaybeDelayed, pipeline::convert::ToWorktreeOutcome};
+    use gix_merge::blob::{builtin_driver, pipeline, ResourceKind};
+    use std::io::Write;
+
+    let side = |stage| {
+        entry_at(stages, stage).ok_or_else(|| checkout::Error::MissingStage {
+            path: rela_path.to_owned(),
+            stage,
+        })
+    };
+    let (ours, theirs) = (side(Stage::Ours)?, side(Stage::Theirs)?);
+    if !is_file(ours.mode) || !is_file(theirs.mode) {
+        return Err(checkout::Error::MergeNonFile {
+            path: rela_path.to_owned(),
+        });
+    }
+
+    // The platform reads attributes through its own copy of the stack, which is set up once for all conflicts.
+    let platform = platform.get_or_insert_with(|| {
+        gix_merge::blob::Platform::new(
+            gix_merge::blob::Pipeline::new(Default::default(), filters.clone(), Default::default()),
+            pipeline::Mode::ToGit,
+            path_cache.clone(),
+            merge.drivers.clone(),
+            gix_merge::blob::platform::Options {
+                default_driver: merge.default_driver.clone(),
+            },
+        )
+    });
+    let odb = WithHeaders(&*objects);
+    let base = entry_at(stages, Stage::Base).filter(|base| is_file(base.mode));
+    for (entry, kind) in [
+        (Some(ours), ResourceKind::CurrentOrOurs),
+        (base, ResourceKind::CommonAncestorOrBase),
+        (Some(theirs), ResourceKind::OtherOrTheirs),
+    ] {
+        // A missing base is set as null object, which is treated as empty.
+        let (id, mode) = entry.map_or((gix_hash::ObjectId::null(ours.id.kind()), Mode::FILE), |entry| {
+            (entry.id, entry.mode)
+        });
+        platform.set_resource(id, entry_kind(mode), rela_path, kind, &odb)?;
+    }
+    let merge_ref = platform.prepare_merge(&odb, merge.options)?;
+    let (pick, _resolution) = merge_ref.merge(
+        buf,
+        builtin_driver::text::Labels {
+            ancestor: Some("base".into()),
+            current: Some("ours".into()),
+            other: Some("theirs".into()),
+        },
+        &merge.command_context,
+    )?;
+    // Resources are never too large as there is no size limit, and binary merges pick one of the sides.
+    let merged = merge_ref.buffer_by_pick(pick).ok().flatten().unwrap_or(buf.as_slice());
+
+    let dest_relative = gix_path::try_from_bstr(rela_path).map_err(|_| checkout::Error::IllformedUtf8 {
+        path: rela_path.to_owned(),
+    })?;
+    let path_cache = path_cache.at_path(dest_relative, Some(ours.mode), &*objects)?;
+    let dest = path_cache.path();
+    let filtered = filters.convert_to_worktree(
+        merged,
+        rela_path,
+        &mut |_, attrs| {
+            path_cache.matching_attributes(attrs);
+        },
+        Delay::Forbid,
+    )?;
+    let (mut file, set_executable_after_creation) = entry::open_file(
+        dest,
+        chunk_options.destination_is_initially_empty,
+        chunk_options.overwrite_existing,
+        chunk_options.fs.executable_bit,
+        ours.mode,
+    )?;
+    let num_bytes = match filtered {
+        ToWorktreeOutcome::Unchanged(buf) | ToWorktreeOutcome::Buffer(buf) => {
+            file.write_all(buf)?;
+            buf.len()
+        }
+        ToWorktreeOutcome::Process(MaybeDelayed::Immediate(mut filtered)) => {
+            std::io::copy(&mut filtered, &mut file)? as usize
+        }
+        ToWorktreeOutcome::Process(MaybeDelayed::Delayed(_)) => unreachable!("delays are forbidden"),
+    };
+    // The stat information is discarded as conflicting entries are never updated.
+    entry::finalize_entry(&mut ours.clone(), file, set_executable_after_creation.then_some(dest))?;
+    Ok(entry::Outcome::Written { bytes: num_bytes })
+}
+
+#[cfg(feature = "merge")]
+fn entry_kind(mode: Mode) -> gix_object::tree::EntryKind {
+    if mode == Mode::FILE_EXECUTABLE {
+        gix_object::tree::EntryKind::BlobExecutable
+    } else {
+        gix_object::tree::EntryKind::Blob
+    }
+}
+
+/// Provide object headers by reading the whole object, as merging needs them but checkouts only have access to objects.
+#[cfg(feature = "merge")]
+struct WithHeaders<'a, Find>(&'a Find);
+
+#[cfg(feature = "merge")]
+impl<Find: gix_object::Find> gix_object::Find for WithHeaders<'_, Find> {
+    fn try_find<'a>(
+        &self,
+        id: &gix_hash::oid,
+        buffer: &'a mut Vec<u8>,
+    ) -> Result<Option<gix_object::Data<'a>>, gix_object::find::Error> {
+        self.0.try_find(id, buffer)
+    }
+}
+
+#[cfg(feature = "merge")]
+impl<Find: gix_object::Find> gix_object::FindHeader for WithHeaders<'_, Find> {
+    fn try_header(&self, id: &gix_hash::oid) -> Result<Option<gix_object::Header>, gix_object::find::Error> {
+        let mut buf = Vec::new();
+        Ok(self.0.try_find(id, &mut buf)?.map(|obj| gix_object::Header {
+            kind: obj.kind,
+            size: obj.data.len() as u64,
+        }))
+    }
+}
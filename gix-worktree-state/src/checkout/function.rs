@@ -3,7 +3,7 @@ use std::sync::atomic::AtomicBool;
 use gix_features::{interrupt, parallel::in_parallel_with_finalize};
 use gix_worktree::{stack, Stack};
 
-use crate::checkout::chunk;
+use crate::checkout::{chunk, conflict};
 
 /// Checkout the entire `index` into `dir`, and resolve objects found in index entries with `objects` to write their content to their
 /// respective path in `dir`.
@@ -58,6 +58,15 @@ where
         None,
     );
 
+    // Conflicting entries are written separately as each of their stages may end up in a different chunk.
+    let conflicted: Vec<_> = index
+        .entries()
+        .iter()
+        .filter(|entry| entry.stage_raw() != 0)
+        .map(|entry| (entry.clone(), entry.path_in(paths)))
+        .collect();
+    let conflicts = std::mem::take(&mut options.conflicts);
+
     let mut ctx = chunk::Context {
         buf: Vec::new(),
         options: (&options).into(),
@@ -85,7 +94,12 @@ where
         delayed_paths_unknown,
         delayed_paths_unprocessed,
     } = if num_threads == 1 {
-        let entries_with_paths = interrupt::Iter::new(index.entries_mut_with_paths_in(paths), should_interrupt);
+        let entries_with_paths = interrupt::Iter::new(
+            index
+                .entries_mut_with_paths_in(paths)
+                .filter(|(entry, _)| entry.stage_raw() == 0),
+            should_interrupt,
+        );
         let mut delayed_filter_results = Vec::new();
         let mut out = chunk::process(
             entries_with_paths,
@@ -97,7 +111,12 @@ where
         chunk::process_delayed_filter_results(delayed_filter_results, &num_files, &num_bytes, &mut out, &mut ctx)?;
         out
     } else {
-        let entries_with_paths = interrupt::Iter::new(index.entries_mut_with_paths_in(paths), should_interrupt);
+        let entries_with_paths = interrupt::Iter::new(
+            index
+                .entries_mut_with_paths_in(paths)
+                .filter(|(entry, _)| entry.stage_raw() == 0),
+            should_interrupt,
+        );
         in_parallel_with_finalize(
             gix_features::iter::Chunks {
                 inner: entries_with_paths,
@@ -143,11 +162,25 @@ where
             as u64;
     }
 
+    let mut out = chunk::Outcome {
+        errors,
+        collisions,
+        ..Default::default()
+    };
+    conflict::process(&conflicted, &conflicts, &mut out, &num_files, &num_bytes, &mut ctx)?;
+    let chunk::Outcome {
+        errors,
+        collisions,
+        bytes_written: conflict_bytes_written,
+        files: conflict_files_updated,
+        ..
+    } = out;
+
     Ok(crate::checkout::Outcome {
-        files_updated,
+        files_updated: files_updated + conflict_files_updated,
         collisions,
         errors,
-        bytes_written,
+        bytes_written: bytes_written + conflict_bytes_written,
         delayed_paths_unknown,
         delayed_paths_unprocessed,
     })
@@ -36,6 +36,47 @@ pub struct Outcome {
     pub delayed_paths_unprocessed: Vec<BString>,
 }
 
+/// Determine how to handle conflicted paths, i.e. paths with entries in the base, our or their stage.
+///
+/// No matter the choice, the conflicting entries remain as they are in the index and won't have their stat information updated,
+/// so checking out conflicts never resolves them.
+#[derive(Debug, Default, Clone)]
+pub enum Conflicts {
+    /// Don't write anything to the worktree for conflicted paths, similar to `git checkout-index`.
+    #[default]
+    Skip,
+    /// Write the content of [our](gix_index::entry::Stage::Ours) entry, similar to `git checkout --ours`.
+    ///
+    /// It's an error if there is no such entry, for instance because we deleted the path.
+    Ours,
+    /// Write the content of [their](gix_index::entry::Stage::Theirs) entry, similar to `git checkout --theirs`.
+    ///
+    /// It's an error if there is no such entry, for instance because they deleted the path.
+    Theirs,
+    /// Merge our and their content with the [base](gix_index::entry::Stage::Base) as ancestor and write the result
+    /// with conflict markers, similar to `git checkout --merge`.
+    ///
+    /// The merge driver is chosen by the `merge` attribute of the path, and conflicts are labelled `ours`, `base` and `theirs`.
+    /// Binary files are resolved by writing our version.
+    /// It's an error if our or their entry is missing, or if one of them isn't a file.
+    #[cfg(feature = "merge")]
+    Merge(Merge),
+}
+
+/// Configure how conflicted paths are merged with [`Conflicts::Merge`].
+#[cfg(feature = "merge")]
+#[derive(Debug, Default, Clone)]
+pub struct Merge {
+    /// Options for the merge, like those of the builtin text driver.
+    pub options: gix_merge::blob::platform::merge::Options,
+    /// The merge drivers that can be referred to by name with the `merge` attribute.
+    pub drivers: Vec<gix_merge::blob::Driver>,
+    /// The name of the driver to use if the `merge` attribute is unspecified, like `merge.default`.
+    pub default_driver: Option<BString>,
+    /// The context to use when running merge drivers.
+    pub command_context: gix_command::Context,
+}
+
 /// Options to further configure the checkout operation.
 #[derive(Clone, Default)]
 pub struct Options {
@@ -68,6 +109,8 @@ pub struct Options {
     pub filters: gix_filter::Pipeline,
     /// Control how long-running processes may use the 'delay' capability.
     pub filter_process_delay: gix_filter::driver::apply::Delay,
+    /// Control how conflicted paths are written to the worktree.
+    pub conflicts: Conflicts,
 }
 
 /// The error returned by the [checkout()][crate::checkout()] function.
@@ -94,10 +137,27 @@ pub enum Error {
     FilterFetchDelayed(#[from] gix_filter::driver::delayed::fetch::Error),
     #[error("The entry at path '{rela_path}' was listed as delayed by the filter process, but we never passed it")]
     FilterPathUnknown { rela_path: BString },
+    #[error("The conflicted path '{path}' has no entry in stage {stage:?} to checkout")]
+    MissingStage {
+        path: BString,
+        stage: gix_index::entry::Stage,
+    },
+    #[error("The conflicted path '{path}' can only be merged if our and their entries are files")]
+    MergeNonFile { path: BString },
+    #[cfg(feature = "merge")]
+    #[error(transparent)]
+    MergeSetResource(#[from] gix_merge::blob::platform::set_resource::Error),
+    #[cfg(feature = "merge")]
+    #[error(transparent)]
+    MergePrepare(#[from] gix_merge::blob::platform::prepare_merge::Error),
+    #[cfg(feature = "merge")]
+    #[error(transparent)]
+    Merge(#[from] gix_merge::blob::platform::merge::Error),
     #[error("The following paths were delayed and apparently forgotten to be processed by the filter driver: ")]
     FilterPathsUnprocessed { rela_paths: Vec<BString> },
 }
 
 mod chunk;
+mod conflict;
 mod entry;
 pub(crate) mod function;
@@ -0,0 +1,26 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+git init -q
+
+echo "clean" > clean
+printf 'one\ntwo\nthree\n' > both-modified
+echo "base" > deleted-by-them
+git add -A
+git commit -q -m "base"
+
+git checkout -q -b theirs
+printf 'one\ntwo by them\nthree\n' > both-modified
+git rm -q deleted-by-them
+echo "added by them" > added-by-both
+git add -A
+git commit -q -m "theirs"
+
+git checkout -q -b ours HEAD~1
+printf 'one\ntwo by us\nthree\n' > both-modified
+echo "modified by us" > deleted-by-them
+echo "added by us" > added-by-both
+git add -A
+git commit -q -m "ours"
+
+git merge -q theirs >/dev/null || true
@@ -0,0 +1,32 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+git init -q
+
+cat <<EOF_ATTRS >.gitattributes
+union merge=union
+unset -merge
+custom merge=custom
+EOF_ATTRS
+for file in text union unset custom; do
+  echo "one" > $file
+done
+printf 'one\0\n' > binary
+git add -A
+git commit -q -m "base"
+
+git checkout -q -b theirs
+for file in text union unset custom; do
+  printf 'one\nby them\n' > $file
+done
+printf 'one\0by them\n' > binary
+git commit -q -am "theirs"
+
+git checkout -q -b ours HEAD~1
+for file in text union unset custom; do
+  printf 'one\nby us\n' > $file
+done
+printf 'one\0by us\n' > binary
+git commit -q -am "ours"
+
+git merge -q theirs >/dev/null || true
@@ -10,7 +10,7 @@ use std::{
 use gix_features::progress;
 use gix_object::{bstr::ByteSlice, Data};
 use gix_testtools::tempfile::TempDir;
-use gix_worktree_state::checkout::Collision;
+use gix_worktree_state::checkout::{Collision, Conflicts};
 use once_cell::sync::Lazy;
 
 use crate::fixture_path;
@@ -396,6 +396,140 @@ fn keep_going_collects_results() {
     assert!(outcome.collisions.is_empty());
 }
 
+#[test]
+fn conflicts_are_skipped_by_default() -> crate::Result {
+    let (_source_tree, destination, index, outcome) =
+        checkout_index_in_tmp_dir(opts_from_probe(), "make_conflicts", None)?;
+    assert_eq!(
+        stripped_prefix(&destination, &dir_structure(&destination)),
+        paths(["clean"]),
+        "only unconflicted entries are written"
+    );
+    assert_eq!(outcome.files_updated, 1);
+    assert!(outcome.errors.is_empty());
+    assert_conflicts_are_untouched(&index);
+    Ok(())
+}
+
+#[test]
+fn conflicts_can_be_written_from_our_or_their_stage() -> crate::Result {
+    for (conflicts, expected_both_modified, expected_added_by_both, missing_path) in [
+        (Conflicts::Ours, "one\ntwo by us\nthree\n", "added by us\n", None),
+        (
+            Conflicts::Theirs,
+            "one\ntwo by them\nthree\n",
+            "added by them\n",
+            Some("deleted-by-them"),
+        ),
+    ] {
+        let mut opts = opts_from_probe();
+        opts.conflicts = conflicts;
+        opts.keep_going = true;
+        let (_source_tree, destination, index, outcome) = checkout_index_in_tmp_dir(opts, "make_conflicts", None)?;
+
+        assert_eq!(
+            std::fs::read_to_string(destination.path().join("both-modified"))?,
+            expected_both_modified
+        );
+        assert_eq!(
+            std::fs::read_to_string(destination.path().join("added-by-both"))?,
+            expected_added_by_both
+        );
+        assert_eq!(
+            destination.path().join("deleted-by-them").is_file(),
+            missing_path.is_none(),
+            "a path can't be written if there is no entry for the chosen side"
+        );
+        assert_eq!(
+            outcome.errors.iter().map(|r| r.path.to_string()).collect::<Vec<_>>(),
+            missing_path.into_iter().collect::<Vec<_>>()
+        );
+        assert_eq!(outcome.files_updated, 4, "one file per path, even if it failed");
+        assert_conflicts_are_untouched(&index);
+    }
+
+    let mut opts = opts_from_probe();
+    opts.conflicts = Conflicts::Theirs;
+    let err = checkout_index_in_tmp_dir(opts, "make_conflicts", None).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "The conflicted path 'deleted-by-them' has no entry in stage Theirs to checkout",
+        "without keep-going, missing stages are an error"
+    );
+    Ok(())
+}
+
+#[test]
+fn conflicts_can_be_merged_with_conflict_markers() -> crate::Result {
+    let mut opts = opts_from_probe();
+    opts.conflicts = Conflicts::Merge(Default::default());
+    opts.keep_going = true;
+    let (_source_tree, destination, index, outcome) = checkout_index_in_tmp_dir(opts, "make_conflicts", None)?;
+
+    assert_eq!(
+        std::fs::read_to_string(destination.path().join("both-modified"))?,
+        "one\n<<<<<<< ours\ntwo by us\n=======\ntwo by them\n>>>>>>> theirs\nthree\n"
+    );
+    assert_eq!(
+        std::fs::read_to_string(destination.path().join("added-by-both"))?,
+        "<<<<<<< ours\nadded by us\n=======\nadded by them\n>>>>>>> theirs\n",
+        "a missing base is treated as empty"
+    );
+    assert!(
+        !destination.path().join("deleted-by-them").exists(),
+        "like `git checkout --merge`, we need both sides"
+    );
+    assert_eq!(
+        outcome.errors.iter().map(|r| r.path.to_string()).collect::<Vec<_>>(),
+        ["deleted-by-them"]
+    );
+    assert_conflicts_are_untouched(&index);
+    Ok(())
+}
+
+#[test]
+fn conflicts_are_merged_according_to_their_merge_attribute() -> crate::Result {
+    let mut opts = opts_from_probe();
+    opts.conflicts = Conflicts::Merge(gix_worktree_state::checkout::Merge {
+        drivers: vec![gix_merge::blob::Driver {
+            name: "custom".into(),
+            command: "echo 'by driver' >%A".into(),
+            ..Default::default()
+        }],
+        ..Default::default()
+    });
+    let (_source_tree, destination, _index, outcome) =
+        checkout_index_in_tmp_dir(opts, "make_conflicts_with_merge_attributes", None)?;
+    assert!(outcome.errors.is_empty());
+
+    let read = |path: &str| std::fs::read(destination.path().join(path));
+    assert_eq!(
+        read("text")?.as_bstr(),
+        "one\n<<<<<<< ours\nby us\n=======\nby them\n>>>>>>> theirs\n"
+    );
+    assert_eq!(read("union")?.as_bstr(), "one\nby us\nby them\n");
+    assert_eq!(read("unset")?.as_bstr(), "one\nby us\n", "`-merge` picks our side");
+    assert_eq!(
+        read("binary")?.as_bstr(),
+        "one\0by us\n",
+        "binary files can't be merged, so our side is picked"
+    );
+    assert_eq!(read("custom")?.as_bstr(), "by driver\n", "drivers are run by name");
+    Ok(())
+}
+
+fn assert_conflicts_are_untouched(index: &gix_index::File) {
+    let conflicts: Vec<_> = index.entries().iter().filter(|e| e.stage_raw() != 0).collect();
+    assert_eq!(conflicts.len(), 7, "all stages are still present");
+    for entry in conflicts {
+        assert_eq!(
+            entry.stat,
+            Default::default(),
+            "conflicting entries don't receive stat information as they don't correspond to a file"
+        );
+    }
+}
+
 #[test]
 fn no_case_related_collisions_on_case_sensitive_filesystem() {
     let opts = opts_from_probe();
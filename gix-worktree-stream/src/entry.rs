@@ -17,6 +17,8 @@ pub enum Error {
     Find(#[from] gix_object::find::existing::Error),
     #[error("Could not find a tree to traverse")]
     FindTree(#[from] gix_object::find::existing_iter::Error),
+    #[error("Could not find the commit to obtain the tree from")]
+    FindCommit(#[source] gix_object::find::existing_object::Error),
     #[error("Could not query attributes for path \"{path}\"")]
     Attributes {
         path: BString,
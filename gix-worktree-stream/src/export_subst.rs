@@ -0,0 +1,128 @@
+//! Expansion of `$Format:…$` placeholders in files that have the `export-subst` attribute set.
+use gix_object::bstr::{ByteSlice, ByteVec};
+use gix_object::date::time::format;
+
+const START: &[u8] = b"$Format:";
+
+/// Expand all `$Format:<format>$` placeholders in `input` with information from `commit` whose id is `id`,
+/// and write the result into `out`, which is cleared beforehand. Return `true` if at least one placeholder was expanded.
+///
+/// `<format>` supports a subset of the placeholders known to `git log --pretty=format:`:
+///
+/// * `%H`, `%h` - the commit hash in full and abbreviated
+/// * `%T`, `%t` - the tree hash in full and abbreviated
+/// * `%P`, `%p` - the parent hashes in full and abbreviated, separated by space
+/// * `%an`, `%ae`, `%ad`, `%at`, `%ai`, `%aI` - author name, email, and date in default, unix, ISO-like and strict ISO format
+/// * `%cn`, `%ce`, `%cd`, `%ct`, `%ci`, `%cI` - the same for the committer
+/// * `%s`, `%b`, `%B` - the message summary, body and raw message
+/// * `%n`, `%%` - a newline and a literal `%`
+///
+/// Unknown placeholders are written verbatim, just like `git` does.
+///
+/// ### Deviation
+///
+/// Abbreviated hashes always have 7 hex characters as the object database isn't consulted to assure uniqueness.
+pub fn expand(id: &gix_hash::oid, commit: &gix_object::CommitRef<'_>, input: &[u8], out: &mut Vec<u8>) -> bool {
+    out.clear();
+    let mut expanded = false;
+    let mut rest = input;
+    while let Some(start) = rest.find(START) {
+        let format_start = start + START.len();
+        let Some(format_len) = rest[format_start..].find_byte(b'$') else {
+            break;
+        };
+        out.extend_from_slice(&rest[..start]);
+        expand_format(id, commit, &rest[format_start..][..format_len], out);
+        expanded = true;
+        rest = &rest[format_start + format_len + 1..];
+    }
+    out.extend_from_slice(rest);
+    expanded
+}
+
+fn expand_format(id: &gix_hash::oid, commit: &gix_object::CommitRef<'_>, mut format: &[u8], out: &mut Vec<u8>) {
+    const SHORT_HEX_LEN: usize = 7;
+    while let Some(pos) = format.find_byte(b'%') {
+        out.extend_from_slice(&format[..pos]);
+        format = &format[pos + 1..];
+        let consumed = match format {
+            [b'H', ..] => {
+                out.push_str(id.to_hex().to_string());
+                1
+            }
+            [b'h', ..] => {
+                out.push_str(id.to_hex_with_len(SHORT_HEX_LEN).to_string());
+                1
+            }
+            [b'T', ..] => {
+                out.push_str(commit.tree().to_hex().to_string());
+                1
+            }
+            [b't', ..] => {
+                out.push_str(commit.tree().to_hex_with_len(SHORT_HEX_LEN).to_string());
+                1
+            }
+            [b'P' | b'p', ..] => {
+                for (idx, parent) in commit.parents().enumerate() {
+                    if idx != 0 {
+                        out.push(b' ');
+                    }
+                    let hex = if format[0] == b'P' {
+                        parent.to_hex().to_string()
+                    } else {
+                        parent.to_hex_with_len(SHORT_HEX_LEN).to_string()
+                    };
+                    out.push_str(hex);
+                }
+                1
+            }
+            [role @ (b'a' | b'c'), field @ (b'n' | b'e' | b'd' | b't' | b'i' | b'I'), ..] => {
+                let signature = if *role == b'a' {
+                    commit.author()
+                } else {
+                    commit.committer()
+                };
+                let time = signature.time;
+                match field {
+                    b'n' => out.extend_from_slice(signature.name),
+                    b'e' => out.extend_from_slice(signature.email),
+                    b'd' => out.push_str(time.format(format::DEFAULT)),
+                    b't' => out.push_str(time.seconds.to_string()),
+                    b'i' => out.push_str(time.format(format::ISO8601)),
+                    b'I' => out.push_str(time.format(format::ISO8601_STRICT)),
+                    _ => unreachable!("BUG: matched above"),
+                }
+                2
+            }
+            [b's', ..] => {
+                let summary = commit.message().summary();
+                out.extend_from_slice(&summary);
+                1
+            }
+            [b'b', ..] => {
+                if let Some(body) = commit.message().body {
+                    out.extend_from_slice(body);
+                }
+                1
+            }
+            [b'B', ..] => {
+                out.extend_from_slice(commit.message);
+                1
+            }
+            [b'n', ..] => {
+                out.push(b'\n');
+                1
+            }
+            [b'%', ..] => {
+                out.push(b'%');
+                1
+            }
+            _ => {
+                out.push(b'%');
+                0
+            }
+        };
+        format = &format[consumed..];
+    }
+    out.extend_from_slice(format);
+}
@@ -31,7 +31,7 @@ use crate::{entry, entry::Error, protocol, AdditionalEntry, SharedErrorSlot, Str
 ///
 /// ### Limitations
 ///
-/// * `export-subst` is not support, as it requires the entire formatting engine of `git log`.
+/// * `export-subst` is ignored as there is no commit to take the substitutions from. Use [`from_commit()`] to support it.
 pub fn from_tree<Find, E>(
     tree: gix_hash::ObjectId,
     objects: Find,
@@ -40,6 +40,47 @@ pub fn from_tree<Find, E>(
         + Send
         + 'static,
 ) -> Stream
+where
+    Find: gix_object::Find + Clone + Send + 'static,
+    E: std::error::Error + Send + Sync + 'static,
+{
+    spawn(Root::Tree(tree), objects, pipeline, attributes)
+}
+
+/// Like [`from_tree()`], but traverse the tree of `commit` and additionally expand `$Format:…$` placeholders
+/// in all files that have the `export-subst` attribute set, using information from `commit`.
+///
+/// See [`export_subst::expand()`](crate::export_subst::expand()) for the supported placeholders.
+/// Note that the expansion happens after the conversion to the worktree representation, just like in `git archive`.
+pub fn from_commit<Find, E>(
+    commit: gix_hash::ObjectId,
+    objects: Find,
+    pipeline: gix_filter::Pipeline,
+    attributes: impl FnMut(&BStr, gix_object::tree::EntryMode, &mut gix_attributes::search::Outcome) -> Result<(), E>
+        + Send
+        + 'static,
+) -> Stream
+where
+    Find: gix_object::Find + Clone + Send + 'static,
+    E: std::error::Error + Send + Sync + 'static,
+{
+    spawn(Root::Commit(commit), objects, pipeline, attributes)
+}
+
+/// The object to start the traversal from.
+enum Root {
+    Tree(gix_hash::ObjectId),
+    Commit(gix_hash::ObjectId),
+}
+
+fn spawn<Find, E>(
+    root: Root,
+    objects: Find,
+    pipeline: gix_filter::Pipeline,
+    attributes: impl FnMut(&BStr, gix_object::tree::EntryMode, &mut gix_attributes::search::Outcome) -> Result<(), E>
+        + Send
+        + 'static,
+) -> Stream
 where
     Find: gix_object::Find + Clone + Send + 'static,
     E: std::error::Error + Send + Sync + 'static,
@@ -49,7 +90,7 @@ where
         let slot = stream.err.clone();
         move || {
             if let Err(err) = run(
-                tree,
+                root,
                 objects,
                 pipeline,
                 attributes,
@@ -76,7 +117,7 @@ where
 }
 
 fn run<Find, E>(
-    tree: gix_hash::ObjectId,
+    root: Root,
     objects: Find,
     mut pipeline: gix_filter::Pipeline,
     mut attributes: impl FnMut(&BStr, gix_object::tree::EntryMode, &mut gix_attributes::search::Outcome) -> Result<(), E>
@@ -91,13 +132,20 @@ where
     E: std::error::Error + Send + Sync + 'static,
 {
     let mut buf = Vec::new();
+    let (tree, commit) = match root {
+        Root::Tree(tree) => (tree, None),
+        Root::Commit(id) => {
+            let tree = objects.find_commit(&id, &mut buf).map_err(Error::FindCommit)?.tree();
+            (tree, Some((id, buf.clone())))
+        }
+    };
     let tree_iter = objects.find_tree_iter(tree.as_ref(), &mut buf)?;
     if pipeline.driver_context_mut().treeish.is_none() {
         pipeline.driver_context_mut().treeish = Some(tree);
     }
 
     let mut attrs = gix_attributes::search::Outcome::default();
-    attrs.initialize_with_selection(&Default::default(), Some("export-ignore"));
+    attrs.initialize_with_selection(&Default::default(), ["export-ignore", "export-subst"]);
     let mut dlg = traverse::Delegate {
         out,
         err,
@@ -113,6 +161,8 @@ where
         path_deque: Default::default(),
         path: Default::default(),
         buf: Vec::with_capacity(1024),
+        commit,
+        subst_buf: Vec::new(),
     };
     gix_traverse::tree::breadthfirst(
         tree_iter,
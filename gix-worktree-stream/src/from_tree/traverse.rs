@@ -1,4 +1,7 @@
-use std::{collections::VecDeque, io::Write};
+use std::{
+    collections::VecDeque,
+    io::{Read, Write},
+};
 
 use gix_filter::{driver::apply::MaybeDelayed, pipeline::convert::ToWorktreeOutcome};
 use gix_object::{
@@ -22,6 +25,10 @@ where
     pub(crate) fetch_attributes: AttributesFn,
     pub(crate) objects: Find,
     pub(crate) buf: Vec<u8>,
+    /// The id and data of the commit whose tree is traversed, if known, to expand placeholders in `export-subst` files.
+    pub(crate) commit: Option<(gix_hash::ObjectId, Vec<u8>)>,
+    /// A buffer to hold the result of the `export-subst` expansion.
+    pub(crate) subst_buf: Vec<u8>,
 }
 
 impl<AttributesFn, Find> Delegate<'_, AttributesFn, Find>
@@ -49,7 +56,17 @@ where
         self.attrs
             .iter_selected()
             .next()
-            .expect("initialized with one attr")
+            .expect("initialized with two attrs")
+            .assignment
+            .state
+    }
+
+    /// Return the state of the `export-subst` attribute.
+    fn subst_state(&self) -> gix_attributes::StateRef<'_> {
+        self.attrs
+            .iter_selected()
+            .nth(1)
+            .expect("initialized with two attrs")
             .assignment
             .state
     }
@@ -62,6 +79,7 @@ where
         if self.ignore_state().is_set() {
             return Ok(Action::Continue);
         }
+        let substitute = self.commit.is_some() && self.subst_state().is_set();
         self.objects.find(entry.oid, &mut self.buf)?;
 
         self.pipeline.driver_context_mut().blob = Some(entry.oid.into());
@@ -74,6 +92,33 @@ where
             gix_filter::driver::apply::Delay::Forbid,
         )?;
 
+        if let Some((id, data)) = self.commit.as_ref().filter(|_| substitute) {
+            let mut processed = Vec::new();
+            let input: &[u8] = match converted {
+                ToWorktreeOutcome::Unchanged(buf) | ToWorktreeOutcome::Buffer(buf) => buf,
+                ToWorktreeOutcome::Process(MaybeDelayed::Immediate(mut read)) => {
+                    read.read_to_end(&mut processed)?;
+                    &processed
+                }
+                ToWorktreeOutcome::Process(MaybeDelayed::Delayed(_)) => {
+                    unreachable!("we forbade it")
+                }
+            };
+            let commit = gix_object::CommitRef::from_bytes(data).expect("BUG: commit was decoded before");
+            crate::export_subst::expand(id, &commit, input, &mut self.subst_buf);
+            protocol::write_entry_header_and_path(
+                self.path.as_ref(),
+                entry.oid,
+                entry.mode,
+                Some(self.subst_buf.len()),
+                self.out,
+            )?;
+            // Our pipe writer always writes the whole amount.
+            #[allow(clippy::unused_io_amount)]
+            self.out.write(&self.subst_buf)?;
+            return Ok(Action::Continue);
+        }
+
         // Our pipe writer always writes the whole amount.
         #[allow(clippy::unused_io_amount)]
         match converted {
@@ -315,3 +315,60 @@ mod from_tree {
         }
     }
 }
+
+mod export_subst {
+    use gix_object::bstr::ByteSlice;
+    use gix_worktree_stream::export_subst::expand;
+
+    const COMMIT: &[u8] = b"tree 4b825dc642cb6eb9a060e54bf8d69288fbee4904
+parent 0000000000000000000000000000000000000001
+parent 0000000000000000000000000000000000000002
+author Jane Doe <jane@example.com> 1700000000 +0100
+committer John Doe <john@example.com> 1700000100 +0000
+
+the summary
+
+the body
+";
+
+    fn expanded(input: &str) -> (bool, String) {
+        let commit = gix_object::CommitRef::from_bytes(COMMIT).expect("valid commit");
+        let id = crate::hex_to_id("1111111111111111111111111111111111111111");
+        let mut out = Vec::new();
+        let res = expand(&id, &commit, input.as_bytes(), &mut out);
+        (res, out.to_str().expect("valid UTF-8").to_owned())
+    }
+
+    #[test]
+    fn hashes() {
+        assert_eq!(
+            expanded("id: $Format:%H %h$, tree: $Format:%t$, parents: $Format:%p$"),
+            (
+                true,
+                "id: 1111111111111111111111111111111111111111 1111111, tree: 4b825dc, parents: 0000000 0000000".into()
+            )
+        );
+        assert_eq!(
+            expanded("$Format:%T$").1,
+            "4b825dc642cb6eb9a060e54bf8d69288fbee4904",
+            "full hashes are supported as well"
+        );
+    }
+
+    #[test]
+    fn signatures_and_message() {
+        assert_eq!(
+            expanded("$Format:%an <%ae> %at|%cn <%ce> %ct$").1,
+            "Jane Doe <jane@example.com> 1700000000|John Doe <john@example.com> 1700000100"
+        );
+        assert_eq!(expanded("$Format:%aI$").1, "2023-11-14T23:13:20+01:00");
+        assert_eq!(expanded("$Format:%s%n%b$").1, "the summary\nthe body\n");
+    }
+
+    #[test]
+    fn unknown_placeholders_and_unterminated_formats_are_kept_verbatim() {
+        assert_eq!(expanded("$Format:%x %% %$").1, "%x % %");
+        assert_eq!(expanded("$Format:%H"), (false, "$Format:%H".into()));
+        assert_eq!(expanded("no placeholder"), (false, "no placeholder".into()));
+    }
+}
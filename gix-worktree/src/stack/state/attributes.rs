@@ -5,7 +5,7 @@ use gix_glob::pattern::Case;
 use gix_object::FindExt;
 
 use crate::{
-    stack::state::{AttributeMatchGroup, Attributes},
+    stack::state::{AttributeMatchGroup, Attributes, PatternCache},
     PathIdMapping, Stack,
 };
 
@@ -19,6 +19,9 @@ pub struct Statistics {
     pub pattern_files: usize,
     /// Amount of pattern files we tried to find on disk.
     pub tried_pattern_files: usize,
+    /// Amount of pattern files on disk whose patterns were obtained from the [cache](PatternCache) as they didn't change,
+    /// which are also counted in [`pattern_files`](Self::pattern_files).
+    pub cached_pattern_files: usize,
 }
 
 /// Decide where to read `.gitattributes` files from.
@@ -78,8 +81,16 @@ impl Attributes {
             info_attributes,
             source,
             collection,
+            pattern_cache: None,
         }
     }
+
+    /// Obtain the patterns of `.gitattributes` files in the worktree from `cache` if they didn't change, and keep newly
+    /// read ones there.
+    pub fn with_pattern_cache(mut self, cache: PatternCache) -> Self {
+        self.pattern_cache = Some(cache);
+        self
+    }
 }
 
 impl Attributes {
@@ -123,26 +134,26 @@ impl Attributes {
                     stats.patterns_buffers += 1;
                 }
                 if !added && matches!(self.source, Source::IdMappingThenWorktree) {
-                    added = self.stack.add_patterns_file(
-                        dir.join(".gitattributes"),
-                        no_follow_symlinks,
-                        Some(root),
+                    added = self.add_patterns_file_from_worktree(
+                        root,
+                        dir,
                         buf,
-                        &mut self.collection,
+                        no_follow_symlinks,
                         read_macros_as_dir_is_root,
+                        stats,
                     )?;
                     stats.pattern_files += usize::from(added);
                     stats.tried_pattern_files += 1;
                 }
             }
             Source::WorktreeThenIdMapping => {
-                added = self.stack.add_patterns_file(
-                    dir.join(".gitattributes"),
-                    no_follow_symlinks,
-                    Some(root),
+                added = self.add_patterns_file_from_worktree(
+                    root,
+                    dir,
                     buf,
-                    &mut self.collection,
+                    no_follow_symlinks,
                     read_macros_as_dir_is_root,
+                    stats,
                 )?;
                 stats.pattern_files += usize::from(added);
                 stats.tried_pattern_files += 1;
@@ -188,6 +199,39 @@ impl Attributes {
         Ok(())
     }
 
+    /// Add the `.gitattributes` file in `dir` if it exists, possibly obtaining its patterns from our cache.
+    fn add_patterns_file_from_worktree(
+        &mut self,
+        root: &Path,
+        dir: &Path,
+        buf: &mut Vec<u8>,
+        follow_symlinks: bool,
+        allow_macros: bool,
+        stats: &mut Statistics,
+    ) -> std::io::Result<bool> {
+        let source = dir.join(".gitattributes");
+        match &self.pattern_cache {
+            Some(cache) => Ok(
+                match cache.attribute_patterns(source, follow_symlinks, Some(root), buf)? {
+                    Some((list, is_cached)) => {
+                        self.stack.add_patterns_list(list, &mut self.collection, allow_macros);
+                        stats.cached_pattern_files += usize::from(is_cached);
+                        true
+                    }
+                    None => false,
+                },
+            ),
+            None => self.stack.add_patterns_file(
+                source,
+                follow_symlinks,
+                Some(root),
+                buf,
+                &mut self.collection,
+                allow_macros,
+            ),
+        }
+    }
+
     pub(crate) fn matching_attributes(
         &self,
         relative_path: &BStr,
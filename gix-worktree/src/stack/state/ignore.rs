@@ -5,7 +5,7 @@ use gix_glob::pattern::Case;
 use gix_object::FindExt;
 
 use crate::{
-    stack::state::{Ignore, IgnoreMatchGroup},
+    stack::state::{Ignore, IgnoreMatchGroup, PatternCache},
     PathIdMapping,
 };
 
@@ -47,6 +47,9 @@ pub struct Statistics {
     pub pattern_files: usize,
     /// Amount of pattern files we tried to find on disk.
     pub tried_pattern_files: usize,
+    /// Amount of pattern files on disk whose patterns were obtained from the [cache](PatternCache) as they didn't change,
+    /// which are also counted in [`pattern_files`](Self::pattern_files).
+    pub cached_pattern_files: usize,
 }
 
 impl Ignore {
@@ -69,8 +72,15 @@ impl Ignore {
             exclude_file_name_for_directories: exclude_file_name_for_directories
                 .map_or_else(|| ".gitignore".into(), ToOwned::to_owned),
             source,
+            pattern_cache: None,
         }
     }
+
+    /// Obtain the patterns of ignore files in the worktree from `cache` if they didn't change, and keep newly read ones there.
+    pub fn with_pattern_cache(mut self, cache: PatternCache) -> Self {
+        self.pattern_cache = Some(cache);
+        self
+    }
 }
 
 impl Ignore {
@@ -194,13 +204,25 @@ impl Ignore {
             }
             Source::WorktreeThenIdMappingIfNotSkipped => {
                 let follow_symlinks = ignore_file_in_index.is_err();
-                let added = gix_glob::search::add_patterns_file(
-                    &mut self.stack.patterns,
-                    dir.join(".gitignore"),
-                    follow_symlinks,
-                    Some(root),
-                    buf,
-                )?;
+                let added = match &self.pattern_cache {
+                    Some(cache) => {
+                        match cache.ignore_patterns(dir.join(".gitignore"), follow_symlinks, Some(root), buf)? {
+                            Some((list, is_cached)) => {
+                                self.stack.patterns.push(list);
+                                stats.cached_pattern_files += usize::from(is_cached);
+                                true
+                            }
+                            None => false,
+                        }
+                    }
+                    None => gix_glob::search::add_patterns_file(
+                        &mut self.stack.patterns,
+                        dir.join(".gitignore"),
+                        follow_symlinks,
+                        Some(root),
+                        buf,
+                    )?,
+                };
                 stats.pattern_files += usize::from(added);
                 stats.tried_pattern_files += 1;
                 if !added {
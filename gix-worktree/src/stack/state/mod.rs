@@ -25,6 +25,8 @@ pub struct Attributes {
     collection: gix_attributes::search::MetadataCollection,
     /// Where to read `.gitattributes` data from.
     source: attributes::Source,
+    /// If set, the patterns of `.gitattributes` files read from the worktree are obtained from here if they didn't change.
+    pattern_cache: Option<PatternCache>,
 }
 
 /// State related to the exclusion of files, supporting static overrides and globals, along with a stack of dynamically read
@@ -47,6 +49,24 @@ pub struct Ignore {
     pub(crate) exclude_file_name_for_directories: BString,
     /// Where to read ignore files from
     source: ignore::Source,
+    /// If set, the patterns of ignore files read from the worktree are obtained from here if they didn't change.
+    pattern_cache: Option<PatternCache>,
+}
+
+/// A cache for the patterns of `.gitignore` and `.gitattributes` files read from the worktree, which can be shared by all
+/// [`Ignore`] and [`Attributes`] instances of the same worktree so each file is only parsed again once it changed.
+///
+/// This is most useful in long-running processes which repeatedly create new stacks, for instance to obtain the status
+/// of the worktree, as otherwise each of them has to read and parse the same files again.
+/// Files are considered unchanged if their size and modification time didn't change, so modifications within the granularity
+/// of the file system's timestamps which also keep the size of the file may go unnoticed.
+///
+/// Clones share the same underlying cache, which is thread-safe so stacks using it can be sent to other threads.
+#[derive(Default, Clone)]
+pub struct PatternCache {
+    ignore: std::sync::Arc<std::sync::Mutex<pattern_cache::Lists<gix_ignore::search::Ignore>>>,
+    #[cfg(feature = "attributes")]
+    attributes: std::sync::Arc<std::sync::Mutex<pattern_cache::Lists<gix_attributes::search::Attributes>>>,
 }
 
 ///
@@ -54,6 +74,7 @@ pub struct Ignore {
 pub mod attributes;
 ///
 pub mod ignore;
+mod pattern_cache;
 
 /// Initialization
 impl State {
@@ -0,0 +1,89 @@
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    sync::{Mutex, PoisonError},
+    time::SystemTime,
+};
+
+use gix_glob::search::{pattern::List, Pattern};
+
+use crate::stack::state::PatternCache;
+
+/// The cached pattern lists by the path of the file they were read from, along with the stamp of the file at that time.
+pub(crate) type Lists<T> = HashMap<PathBuf, (Stamp, List<T>)>;
+
+/// Information to detect if a file changed since it was read.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Stamp {
+    len: u64,
+    modified: SystemTime,
+}
+
+impl PatternCache {
+    /// Return the patterns of the ignore file at `source` like [`List::from_file()`], along with `true` if they were
+    /// obtained from the cache.
+    pub(crate) fn ignore_patterns(
+        &self,
+        source: PathBuf,
+        follow_symlinks: bool,
+        root: Option<&Path>,
+        buf: &mut Vec<u8>,
+    ) -> std::io::Result<Option<(List<gix_ignore::search::Ignore>, bool)>> {
+        patterns(&self.ignore, source, follow_symlinks, root, buf)
+    }
+
+    /// Return the patterns of the attributes file at `source` like [`List::from_file()`], along with `true` if they were
+    /// obtained from the cache.
+    #[cfg(feature = "attributes")]
+    pub(crate) fn attribute_patterns(
+        &self,
+        source: PathBuf,
+        follow_symlinks: bool,
+        root: Option<&Path>,
+        buf: &mut Vec<u8>,
+    ) -> std::io::Result<Option<(List<gix_attributes::search::Attributes>, bool)>> {
+        patterns(&self.attributes, source, follow_symlinks, root, buf)
+    }
+}
+
+fn patterns<T: Pattern>(
+    lists: &Mutex<Lists<T>>,
+    source: PathBuf,
+    follow_symlinks: bool,
+    root: Option<&Path>,
+    buf: &mut Vec<u8>,
+) -> std::io::Result<Option<(List<T>, bool)>>
+where
+    List<T>: Clone,
+{
+    let meta = if follow_symlinks {
+        std::fs::metadata(&source)
+    } else {
+        std::fs::symlink_metadata(&source)
+    };
+    // Everything that isn't a plain file with a known modification time is read as usual, including the handling of errors.
+    let stamp = meta.ok().filter(std::fs::Metadata::is_file).and_then(|meta| {
+        Some(Stamp {
+            len: meta.len(),
+            modified: meta.modified().ok()?,
+        })
+    });
+    if let Some(stamp) = stamp {
+        let lists = lists.lock().unwrap_or_else(PoisonError::into_inner);
+        if let Some((_, list)) = lists.get(&source).filter(|(cached, _)| *cached == stamp) {
+            return Ok(Some((list.clone(), true)));
+        }
+    }
+
+    let list = List::<T>::from_file(source.clone(), root, follow_symlinks, buf)?;
+    let mut lists = lists.lock().unwrap_or_else(PoisonError::into_inner);
+    match (&list, stamp) {
+        (Some(list), Some(stamp)) => {
+            lists.insert(source, (stamp, list.clone()));
+        }
+        _ => {
+            lists.remove(&source);
+        }
+    }
+    Ok(list.map(|list| (list, false)))
+}
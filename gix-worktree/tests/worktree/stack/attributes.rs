@@ -73,6 +73,54 @@ fn baseline() -> crate::Result {
     Ok(())
 }
 
+#[test]
+fn pattern_cache_is_shared_by_stacks_with_different_collections() -> crate::Result {
+    let dir = gix_testtools::tempfile::TempDir::new()?;
+    std::fs::write(dir.path().join(".gitattributes"), "*.txt text diff\n")?;
+    let pattern_cache = state::PatternCache::default();
+    let attributes = |global_patterns: &[u8]| -> crate::Result<(Vec<String>, state::attributes::Statistics)> {
+        let mut collection = gix_attributes::search::MetadataCollection::default();
+        let mut globals = gix_attributes::Search::default();
+        globals.add_patterns_buffer(global_patterns, "globals".into(), None, &mut collection, true);
+        let state = gix_worktree::stack::State::AttributesStack(
+            state::Attributes::new(
+                globals,
+                None,
+                state::attributes::Source::WorktreeThenIdMapping,
+                collection,
+            )
+            .with_pattern_cache(pattern_cache.clone()),
+        );
+        let mut stack = gix_worktree::Stack::new(
+            dir.path(),
+            state,
+            gix_glob::pattern::Case::Sensitive,
+            Vec::new(),
+            Vec::new(),
+        );
+        let mut out = stack.attribute_matches();
+        stack
+            .at_entry("file.txt", None, &gix_object::find::Never)?
+            .matching_attributes(&mut out);
+        let mut names: Vec<_> = out.iter().map(|m| m.assignment.name.as_str().to_owned()).collect();
+        names.sort();
+        Ok((names, stack.take_statistics().attributes))
+    };
+
+    let (names, stats) = attributes(b"")?;
+    assert_eq!(names, ["diff", "text"]);
+    assert_eq!(stats.cached_pattern_files, 0, "the cache starts out empty");
+
+    let (names, stats) = attributes(b"* other\n")?;
+    assert_eq!(
+        names,
+        ["diff", "other", "text"],
+        "cached patterns work with collections that assign different ids to attributes"
+    );
+    assert_eq!(stats.cached_pattern_files, 1);
+    Ok(())
+}
+
 fn assert_references(out: &Outcome) {
     for m in out.iter() {
         if let Some(source) = m.kind.source_id() {
@@ -176,3 +176,56 @@ fn check_against_baseline() -> crate::Result {
     }
     Ok(())
 }
+
+#[test]
+fn pattern_cache_reuses_unchanged_ignore_files() -> crate::Result {
+    use gix_worktree::stack::state::{Ignore, PatternCache};
+
+    let dir = gix_testtools::tempfile::TempDir::new()?;
+    std::fs::write(dir.path().join(".gitignore"), "*.a\n")?;
+    let pattern_cache = PatternCache::default();
+    let is_excluded = |path: &str| -> crate::Result<(bool, gix_worktree::stack::state::ignore::Statistics)> {
+        let state = gix_worktree::stack::State::IgnoreStack(
+            Ignore::new(
+                Default::default(),
+                Default::default(),
+                None,
+                Source::WorktreeThenIdMappingIfNotSkipped,
+            )
+            .with_pattern_cache(pattern_cache.clone()),
+        );
+        let mut stack = Stack::new(
+            dir.path(),
+            state,
+            gix_glob::pattern::Case::Sensitive,
+            Vec::new(),
+            Default::default(),
+        );
+        let is_excluded = stack
+            .at_entry(path, Some(Mode::FILE), &gix_object::find::Never)?
+            .is_excluded();
+        Ok((is_excluded, stack.take_statistics().ignore))
+    };
+
+    let (excluded, stats) = is_excluded("file.a")?;
+    assert!(excluded);
+    assert_eq!(stats.pattern_files, 1);
+    assert_eq!(stats.cached_pattern_files, 0, "the cache starts out empty");
+
+    let (excluded, stats) = is_excluded("file.a")?;
+    assert!(excluded);
+    assert_eq!(stats.pattern_files, 1);
+    assert_eq!(stats.cached_pattern_files, 1, "the unchanged file isn't parsed again");
+
+    std::fs::write(dir.path().join(".gitignore"), "*.bb\n")?;
+    let (excluded, stats) = is_excluded("file.a")?;
+    assert!(!excluded, "the changed file is read again");
+    assert_eq!(stats.cached_pattern_files, 0);
+    assert!(is_excluded("file.bb")?.0);
+
+    std::fs::remove_file(dir.path().join(".gitignore"))?;
+    let (excluded, stats) = is_excluded("file.bb")?;
+    assert!(!excluded, "removed files aren't taken from the cache");
+    assert_eq!(stats.pattern_files, 0);
+    Ok(())
+}
@@ -35,6 +35,28 @@ impl PrepareFetch {
         self
     }
 
+    /// If `true`, default `false`, only fetch the branch that will be checked out, which is the one named by
+    /// [`with_ref_name()`](Self::with_ref_name()) or the one the remote `HEAD` points to, and configure the remote
+    /// to only fetch this branch in future, just like `git clone --single-branch` would.
+    ///
+    /// Tags are only fetched if they point into the history of the fetched branch, unless the remote is
+    /// [configured](Self::configure_remote()) otherwise.
+    /// Note that this requires one additional connection to the remote to learn which branch to fetch.
+    pub fn with_single_branch(mut self, toggle: bool) -> Self {
+        self.single_branch = toggle;
+        self
+    }
+
+    /// Make this a partial clone which omits all objects that don't match the filter `spec`, like `blob:none` or `tree:0`,
+    /// just like `git clone --filter=<spec>` would.
+    ///
+    /// The remote is configured as promisor with `spec` as its `partialCloneFilter`, so future fetches from it use the same filter.
+    /// Note that objects that were filtered aren't fetched on demand later, so a clone filtering blobs can't be checked out.
+    pub fn with_filter(mut self, spec: impl Into<BString>) -> Self {
+        self.filter = Some(spec.into());
+        self
+    }
+
     /// Apply the given configuration `values` right before readying the actual fetch from the remote.
     /// The configuration is marked with [source API](gix_config::Source::Api), and will not be written back, it's
     /// retained only in memory.
@@ -4,6 +4,8 @@ use crate::{clone::PrepareCheckout, Repository};
 pub mod main_worktree {
     use std::{path::PathBuf, sync::atomic::AtomicBool};
 
+    use gix_features::progress::Count;
+
     use crate::{clone::PrepareCheckout, Progress, Repository};
 
     /// The error returned by [`PrepareCheckout::main_worktree()`].
@@ -35,6 +37,16 @@ pub mod main_worktree {
         FindHead(#[from] crate::reference::find::existing::Error),
         #[error("The HEAD reference could not be located")]
         PeelHeadToId(#[from] crate::head::peel::Error),
+        #[error("Could not read the submodules to initialize")]
+        Modules(#[from] crate::submodule::modules::Error),
+        #[error("Could not find the remote to resolve relative submodule urls against")]
+        FindRemote(#[from] crate::remote::find::existing::Error),
+        #[error("Could not set the url or active state of a submodule")]
+        SetSubmoduleConfig(#[from] gix_config::file::set_raw_value::Error),
+        #[error("Could not write the submodule configuration to the local configuration file")]
+        WriteSubmoduleConfig(#[source] std::io::Error),
+        #[error("The checkout was interrupted and can be resumed by calling main_worktree() again")]
+        Interrupted,
     }
 
     /// The progress ids used in [`PrepareCheckout::main_worktree()`].
@@ -65,6 +77,10 @@ pub mod main_worktree {
         /// Note that this is a no-op if the remote was empty, leaving this repository empty as well. This can be validated by checking
         /// if the `head()` of the returned repository is *not* unborn.
         ///
+        /// If `should_interrupt` is set during the checkout, [`Error::Interrupted`] is returned. Calling this method again
+        /// resumes the checkout, writing only the files that weren't written yet, and `progress` starts at the amount of
+        /// files that were already checked out.
+        ///
         /// # Panics
         ///
         /// If called after it was successful. The reason here is that it auto-deletes the contained repository,
@@ -111,22 +127,39 @@ pub mod main_worktree {
                 }
             };
 
-            let index = gix_index::State::from_tree(&root_tree, &repo.objects, repo.config.protect_options()?)
-                .map_err(|err| Error::IndexFromTree {
-                    id: root_tree,
-                    source: err,
-                })?;
-            let mut index = gix_index::File::from_state(index, repo.index_path());
-
             let mut opts = repo
                 .config
                 .checkout_options(repo, gix_worktree::stack::state::attributes::Source::IdMapping)?;
-            opts.destination_is_initially_empty = true;
+            let (mut index, num_checked_out) = match self.interrupted_index.take() {
+                Some(mut index) => {
+                    // Skip all files that were written before the interruption, which are the ones with stat information.
+                    let mut num_checked_out = 0;
+                    for entry in index.entries_mut() {
+                        if entry.stat != gix_index::entry::Stat::default() {
+                            entry.flags.insert(gix_index::entry::Flags::SKIP_WORKTREE);
+                            num_checked_out += 1;
+                        }
+                    }
+                    opts.destination_is_initially_empty = false;
+                    opts.overwrite_existing = true;
+                    (index, num_checked_out)
+                }
+                None => {
+                    let index = gix_index::State::from_tree(&root_tree, &repo.objects, repo.config.protect_options()?)
+                        .map_err(|err| Error::IndexFromTree {
+                            id: root_tree,
+                            source: err,
+                        })?;
+                    opts.destination_is_initially_empty = true;
+                    (gix_index::File::from_state(index, repo.index_path()), 0)
+                }
+            };
 
             let mut files = progress.add_child_with_id("checkout".to_string(), ProgressId::CheckoutFiles.into());
             let mut bytes = progress.add_child_with_id("writing".to_string(), ProgressId::BytesWritten.into());
 
             files.init(Some(index.entries().len()), crate::progress::count("files"));
+            files.set(num_checked_out);
             bytes.init(None, crate::progress::bytes());
 
             let start = std::time::Instant::now();
@@ -141,11 +174,130 @@ pub mod main_worktree {
             )?;
             files.show_throughput(start);
             bytes.show_throughput(start);
+            if num_checked_out != 0 {
+                for entry in index.entries_mut() {
+                    entry.flags.remove(gix_index::entry::Flags::SKIP_WORKTREE);
+                }
+            }
+            if should_interrupt.load(std::sync::atomic::Ordering::Relaxed) {
+                self.interrupted_index = Some(index);
+                return Err(Error::Interrupted);
+            }
 
-            index.write(Default::default())?;
+            index.write(gix_index::write::Options {
+                fsync: repo.config.fsync.index,
+                ..Default::default()
+            })?;
+            if self.init_submodules {
+                init_submodules(self.repo.as_mut().expect("still present"))?;
+            }
             Ok((self.repo.take().expect("still present").clone(), outcome))
         }
     }
+
+    /// Write the `url` of all submodules listed in `.gitmodules` to the local configuration of `repo` if they aren't
+    /// configured yet, and mark them as active unless `submodule.active` is set, just like `git submodule init`.
+    fn init_submodules(repo: &mut Repository) -> Result<(), Error> {
+        use std::io::Write;
+
+        use crate::bstr::{BStr, ByteSlice};
+
+        let Some(modules) = repo.modules()? else {
+            return Ok(());
+        };
+        let base_url = match repo.find_default_remote(crate::remote::Direction::Fetch).transpose()? {
+            Some(remote) => remote
+                .url(crate::remote::Direction::Fetch)
+                .map(gix_url::Url::to_bstring),
+            None => None,
+        }
+        .or_else(|| repo.work_dir().map(|dir| gix_path::into_bstr(dir).into_owned()))
+        .unwrap_or_default();
+        let has_active_pathspecs = repo.config.resolved.string("submodule.active").is_some();
+
+        let mut config = gix_config::File::new(repo.config.resolved.meta().clone());
+        for name in modules.names() {
+            if repo.config.resolved.string_by("submodule", Some(name), "url").is_some() {
+                continue;
+            }
+            let Some(url) = modules.config().string_by("submodule", Some(name), "url") else {
+                continue;
+            };
+            let url = resolve_relative_url(url.as_ref(), base_url.as_bstr());
+            config.set_raw_value_by("submodule", Some(name), "url", url.as_bstr())?;
+            if !has_active_pathspecs {
+                config.set_raw_value_by("submodule", Some(name), "active", BStr::new("true"))?;
+            }
+        }
+        if config.is_void() {
+            return Ok(());
+        }
+
+        let path = config
+            .meta()
+            .path
+            .clone()
+            .expect("local configuration of a repository has a path");
+        let mut local_config = std::fs::OpenOptions::new()
+            .append(true)
+            .open(path)
+            .map_err(Error::WriteSubmoduleConfig)?;
+        local_config
+            .write_all(config.detect_newline_style())
+            .and_then(|_| config.write_to(&mut local_config))
+            .map_err(Error::WriteSubmoduleConfig)?;
+        gix_features::threading::OwnShared::make_mut(&mut repo.config.resolved).append(config);
+        Ok(())
+    }
+
+    /// Resolve `url` against `base` if it starts with `./` or `../`, and return it unchanged otherwise.
+    ///
+    /// Each `../` removes one path component from `base`, which may also be separated by `:` as in `host:path` urls.
+    pub(super) fn resolve_relative_url(url: &crate::bstr::BStr, base: &crate::bstr::BStr) -> crate::bstr::BString {
+        use crate::bstr::ByteSlice;
+
+        if !(url.starts_with(b"./") || url.starts_with(b"../")) {
+            return url.to_owned();
+        }
+        let mut url = url.as_bytes();
+        let mut base = base.as_bytes();
+        while base.ends_with(b"/") {
+            base = &base[..base.len() - 1];
+        }
+        let mut separator = b'/';
+        loop {
+            if let Some(rest) = url.strip_prefix(b"./") {
+                url = rest;
+            } else if let Some(rest) = url.strip_prefix(b"../") {
+                url = rest;
+                match base.rfind_byteset(b"/:") {
+                    Some(pos) => {
+                        separator = base[pos];
+                        base = &base[..pos];
+                    }
+                    None => base = b".",
+                }
+            } else {
+                break;
+            }
+        }
+        let mut out = crate::bstr::BString::from(base);
+        out.push(separator);
+        out.extend_from_slice(url);
+        out
+    }
+}
+
+/// Builder
+impl PrepareCheckout {
+    /// If `true`, default `false`, register all submodules listed in `.gitmodules` in the local configuration after the checkout,
+    /// similar to `git submodule init`, so that they are ready to be cloned.
+    ///
+    /// Relative submodule urls are resolved against the url of the remote the repository was cloned from.
+    pub fn with_submodule_init(mut self, toggle: bool) -> Self {
+        self.init_submodules = toggle;
+        self
+    }
 }
 
 /// Access
@@ -183,3 +335,39 @@ impl From<PrepareCheckout> for Repository {
         prep.persist()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::main_worktree::resolve_relative_url;
+
+    #[test]
+    fn relative_urls_are_resolved_against_the_base() {
+        for (url, base, expected) in [
+            (
+                "https://example.com/a.git",
+                "https://example.com/b.git",
+                "https://example.com/a.git",
+            ),
+            (
+                "../a.git",
+                "https://example.com/org/b.git",
+                "https://example.com/org/a.git",
+            ),
+            (
+                "../../other/a.git",
+                "https://example.com/org/b.git/",
+                "https://example.com/other/a.git",
+            ),
+            ("./a", "/path/to/repo", "/path/to/repo/a"),
+            ("../a.git", "host:org/b.git", "host:org/a.git"),
+            ("../a.git", "host:b.git", "host:a.git"),
+            ("../a", "repo", "./a"),
+        ] {
+            assert_eq!(
+                resolve_relative_url(url.into(), base.into()),
+                expected,
+                "{url} against {base}"
+            );
+        }
+    }
+}
@@ -10,6 +10,8 @@ pub enum Error {
     Connect(#[from] crate::remote::connect::Error),
     #[error(transparent)]
     PrepareFetch(#[from] crate::remote::fetch::prepare::Error),
+    #[error("Could not list the references of the remote to determine the single branch to fetch")]
+    RefMap(#[from] crate::remote::ref_map::Error),
     #[error(transparent)]
     Fetch(#[from] crate::remote::fetch::Error),
     #[error(transparent)]
@@ -28,6 +30,8 @@ pub enum Error {
     LoadConfig(#[from] gix_config::file::init::from_paths::Error),
     #[error("Failed to store configured remote in memory")]
     SaveConfig(#[from] crate::remote::save::AsError),
+    #[error("Failed to configure the remote as promisor of a partial clone")]
+    PartialCloneConfig(#[from] gix_config::file::set_raw_value::Error),
     #[error("Failed to write repository configuration to disk")]
     SaveConfigIo(#[from] std::io::Error),
     #[error("The remote HEAD points to a reference named {head_ref_name:?} which is invalid.")]
@@ -118,10 +122,39 @@ impl PrepareFetch {
         if let Some(f) = self.configure_remote.as_mut() {
             remote = f(remote).map_err(Error::RemoteConfiguration)?;
         } else {
-            clone_fetch_tags = remote::fetch::Tags::All.into();
+            clone_fetch_tags = if self.single_branch {
+                remote::fetch::Tags::Included
+            } else {
+                remote::fetch::Tags::All
+            }
+            .into();
+        }
+
+        if self.single_branch {
+            let ref_map = {
+                let mut connection = remote.connect(remote::Direction::Fetch).await?;
+                if let Some(f) = self.configure_connection.as_mut() {
+                    f(&mut connection).map_err(Error::RemoteConnection)?;
+                }
+                let mut opts = self.fetch_options.clone();
+                // Have all refs listed, including `HEAD`, to learn what it points to.
+                opts.prefix_from_spec_as_filter_on_remote = false;
+                if let Some(ref_name) = &self.ref_name {
+                    opts.extra_refspecs.push(
+                        gix_refspec::parse(ref_name.as_ref().as_bstr(), gix_refspec::parse::Operation::Fetch)
+                            .expect("partial names are valid refspecs")
+                            .to_owned(),
+                    );
+                }
+                connection.ref_map(&mut *progress, opts).await?
+            };
+            let spec = util::single_branch_refspec(&ref_map, self.ref_name.as_ref(), remote_name.as_ref())?;
+            remote
+                .replace_refspecs(spec, remote::Direction::Fetch)
+                .expect("valid generated refspec");
         }
 
-        let config = util::write_remote_to_local_config_file(&mut remote, remote_name.clone())?;
+        let config = util::write_remote_to_local_config_file(&mut remote, remote_name.clone(), self.filter.as_ref())?;
 
         // Now we are free to apply remote configuration we don't want to be written to disk.
         if let Some(fetch_tags) = clone_fetch_tags {
@@ -198,6 +231,10 @@ impl PrepareFetch {
             b.insert_str(0, "clone: from ");
             b
         };
+        let pending_pack = match &self.filter {
+            Some(spec) => pending_pack.with_filter(spec.clone()),
+            None => pending_pack,
+        };
         let outcome = pending_pack
             .with_write_packed_refs_only(true)
             .with_reflog_message(RefLogMessage::Override {
@@ -235,6 +272,8 @@ impl PrepareFetch {
             crate::clone::PrepareCheckout {
                 repo: repo.into(),
                 ref_name: self.ref_name.clone(),
+                init_submodules: false,
+                interrupted_index: None,
             },
             fetch_outcome,
         ))
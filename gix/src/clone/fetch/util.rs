@@ -8,6 +8,7 @@ use gix_ref::{
 use super::Error;
 use crate::{
     bstr::{BStr, BString, ByteSlice},
+    config::tree::{Core, Extensions, Key, Remote},
     Repository,
 };
 
@@ -20,9 +21,28 @@ enum WriteMode {
 pub fn write_remote_to_local_config_file(
     remote: &mut crate::Remote<'_>,
     remote_name: BString,
+    filter: Option<&BString>,
 ) -> Result<gix_config::File<'static>, Error> {
     let mut config = gix_config::File::new(local_config_meta(remote.repo));
-    remote.save_as_to(remote_name, &mut config)?;
+    remote.save_as_to(remote_name.clone(), &mut config)?;
+    if let Some(filter) = filter {
+        // Like `git`, register the remote as promisor of all objects that were filtered.
+        let subsection = Some(remote_name.as_bstr());
+        config.set_raw_value_by(
+            Remote::PROMISOR.section().name(),
+            subsection,
+            Remote::PROMISOR.name(),
+            "true",
+        )?;
+        config.set_raw_value_by(
+            Remote::PARTIAL_CLONE_FILTER.section().name(),
+            subsection,
+            Remote::PARTIAL_CLONE_FILTER.name(),
+            filter.as_bstr(),
+        )?;
+        config.set_raw_value(&Core::REPOSITORY_FORMAT_VERSION, "1")?;
+        config.set_raw_value(&Extensions::PARTIAL_CLONE, remote_name.as_bstr())?;
+    }
 
     write_to_local_config(&config, WriteMode::Append)?;
     Ok(config)
@@ -183,6 +203,45 @@ pub fn update_head(
     Ok(())
 }
 
+/// Return the refspec to use for fetching only the branch named `ref_name`, or the branch `HEAD` points to if `None`,
+/// with `ref_map` being the unfiltered listing of all references on the remote.
+/// Return `None` if there is no branch or tag to follow, for instance if the remote `HEAD` is detached.
+pub(super) fn single_branch_refspec(
+    ref_map: &crate::remote::fetch::RefMap,
+    ref_name: Option<&PartialName>,
+    remote_name: &BStr,
+) -> Result<Option<BString>, Error> {
+    let target = match ref_name {
+        Some(ref_name) => find_custom_refname(ref_map, ref_name)?.1,
+        None => ref_map.remote_refs.iter().find_map(|r| match r {
+            gix_protocol::handshake::Ref::Symbolic {
+                full_ref_name, target, ..
+            }
+            | gix_protocol::handshake::Ref::Unborn { full_ref_name, target }
+                if full_ref_name == "HEAD" =>
+            {
+                Some(target.as_bstr())
+            }
+            _ => None,
+        }),
+    };
+    Ok(target.and_then(|name| {
+        if let Some(branch) = name.strip_prefix(b"refs/heads/") {
+            Some(
+                format!(
+                    "+refs/heads/{branch}:refs/remotes/{remote_name}/{branch}",
+                    branch = branch.as_bstr()
+                )
+                .into(),
+            )
+        } else if name.starts_with(b"refs/tags/") {
+            Some(format!("+{name}:{name}").into())
+        } else {
+            None
+        }
+    }))
+}
+
 pub(super) fn find_custom_refname<'a>(
     ref_map: &'a crate::remote::fetch::RefMap,
     ref_name: &PartialName,
@@ -37,6 +37,12 @@ pub struct PrepareFetch {
     /// The name of the reference to fetch. If `None`, the reference pointed to by `HEAD` will be checked out.
     #[cfg_attr(not(feature = "blocking-network-client"), allow(dead_code))]
     ref_name: Option<gix_ref::PartialName>,
+    /// If `true`, only fetch the branch that is checked out, and configure the remote accordingly.
+    #[cfg_attr(not(feature = "blocking-network-client"), allow(dead_code))]
+    single_branch: bool,
+    /// The filter to use for a partial clone, like `blob:none`.
+    #[cfg_attr(not(feature = "blocking-network-client"), allow(dead_code))]
+    filter: Option<BString>,
 }
 
 /// The error returned by [`PrepareFetch::new()`].
@@ -126,6 +132,8 @@ impl PrepareFetch {
             configure_connection: None,
             shallow: remote::fetch::Shallow::NoChange,
             ref_name: None,
+            single_branch: false,
+            filter: None,
         })
     }
 }
@@ -140,6 +148,10 @@ pub struct PrepareCheckout {
     pub(self) repo: Option<crate::Repository>,
     /// The name of the reference to check out. If `None`, the reference pointed to by `HEAD` will be checked out.
     pub(self) ref_name: Option<gix_ref::PartialName>,
+    /// If `true`, register all submodules in the local configuration after the checkout, like `git submodule init` would.
+    pub(self) init_submodules: bool,
+    /// The index of a checkout that was interrupted, with the stat information of all files that were written already.
+    pub(self) interrupted_index: Option<gix_index::File>,
 }
 
 // This module encapsulates functionality that works with both feature toggles. Can be combined with `fetch`
@@ -20,6 +20,15 @@ pub enum Error {
     WriteObject(#[from] crate::object::write::Error),
     #[error(transparent)]
     ReferenceEdit(#[from] crate::reference::edit::Error),
+    #[cfg(feature = "command")]
+    #[error(transparent)]
+    ConfigBoolean(#[from] crate::config::boolean::Error),
+    #[cfg(feature = "command")]
+    #[error(transparent)]
+    Signer(#[from] crate::repository::keyring::Error),
+    #[cfg(feature = "command")]
+    #[error(transparent)]
+    Sign(#[from] crate::keyring::sign::Error),
 }
 
 ///
@@ -77,6 +86,8 @@ pub mod describe {
         OpenCache(#[from] crate::repository::commit_graph_if_enabled::Error),
         #[error(transparent)]
         Describe(#[from] gix_revision::describe::Error),
+        #[error(transparent)]
+        Grafts(#[from] crate::shallow::grafts::Error),
         #[error("Could not produce an unambiguous shortened id for formatting.")]
         ShortId(#[from] crate::id::shorten::Error),
         #[error(transparent)]
@@ -224,7 +235,7 @@ pub mod describe {
             &self,
             cache: Option<&'_ gix_commitgraph::Graph>,
         ) -> Result<Option<Resolution<'repo>>, Error> {
-            let mut graph = self.repo.revision_graph(cache);
+            let mut graph = self.repo.revision_graph(cache)?;
             let outcome = gix_revision::describe(
                 &self.id,
                 &mut graph,
@@ -259,3 +270,53 @@ pub mod describe {
         }
     }
 }
+
+///
+#[cfg(feature = "blob-diff")]
+pub mod diff_to_parents {
+    use gix_hash::ObjectId;
+
+    use crate::object::tree::diff::ChangeDetached;
+
+    /// The error returned by [Commit::diff_to_parents()](crate::Commit::diff_to_parents()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        FindCommit(#[from] crate::object::find::existing::Error),
+        #[error(transparent)]
+        PeelToTree(#[from] crate::object::peel::to_kind::Error),
+        #[error(transparent)]
+        Tree(#[from] crate::object::commit::Error),
+        #[error(transparent)]
+        DiffTreeToTree(#[from] crate::repository::diff_tree_to_tree::Error),
+    }
+
+    /// Determine how merge commits, i.e. commits with more than one parent, are diffed, similar to the `--diff-merges`
+    /// option of `git log` and `git show`.
+    ///
+    /// Commits with a single parent are always diffed against it, and root commits are diffed against the empty tree.
+    #[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+    pub enum Merges {
+        /// Diff against each parent, but only keep the changes to paths that differ from all parents,
+        /// similar to `git show --cc`.
+        #[default]
+        Combined,
+        /// Diff against the first parent only, similar to `git show --first-parent`.
+        FirstParent,
+        /// Diff against each parent separately, keeping all changes, similar to `git show -m`.
+        Separate,
+        /// Don't diff merge commits at all, similar to `git show --no-diff-merges`.
+        Off,
+    }
+
+    /// The changes of a commit against one of its parents.
+    #[derive(Debug, Clone)]
+    pub struct Changes {
+        /// The id of the parent the commit was diffed against, or `None` if it was diffed against the empty tree
+        /// as it's a root commit.
+        pub parent_id: Option<ObjectId>,
+        /// The changes that turn the tree of the parent into the tree of the commit.
+        pub changes: Vec<ChangeDetached>,
+    }
+}
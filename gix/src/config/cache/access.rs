@@ -188,6 +188,35 @@ impl Cache {
         ("agent", Some(gix_protocol::agent(agent).into()))
     }
 
+    /// Returns the options for serving this repository, as configured in the `uploadpack` section.
+    #[cfg(any(feature = "async-network-client", feature = "blocking-network-client"))]
+    pub(crate) fn serve_options(&self) -> Result<gix_protocol::serve::Options, boolean::Error> {
+        use config::tree::Uploadpack;
+        Ok(gix_protocol::serve::Options {
+            object_hash: self.object_hash,
+            agent: self.user_agent_tuple().1.expect("always set").into_owned().into(),
+            allow_tip_sha1_in_want: boolean(
+                self,
+                "uploadpack.allowTipSHA1InWant",
+                &Uploadpack::ALLOW_TIP_SHA1_IN_WANT,
+                false,
+            )?,
+            allow_reachable_sha1_in_want: boolean(
+                self,
+                "uploadpack.allowReachableSHA1InWant",
+                &Uploadpack::ALLOW_REACHABLE_SHA1_IN_WANT,
+                false,
+            )?,
+            allow_any_sha1_in_want: boolean(
+                self,
+                "uploadpack.allowAnySHA1InWant",
+                &Uploadpack::ALLOW_ANY_SHA1_IN_WANT,
+                false,
+            )?,
+            allow_filter: boolean(self, "uploadpack.allowFilter", &Uploadpack::ALLOW_FILTER, false)?,
+        })
+    }
+
     /// Return `true` if packet-tracing is enabled. Lenient and defaults to `false`.
     #[cfg(any(feature = "async-network-client", feature = "blocking-network-client"))]
     pub(crate) fn trace_packet(&self) -> bool {
@@ -214,6 +243,18 @@ impl Cache {
             .get_or_try_init(|| remote::url::SchemePermission::from_config(&self.resolved, self.filter_config_section))
     }
 
+    /// Returns `true` if the ancestry of forcibly updated refs should be checked when fetching to tell forced updates
+    /// from fast-forwards.
+    #[cfg(any(feature = "blocking-network-client", feature = "async-network-client"))]
+    pub(crate) fn fetch_show_forced_updates(&self) -> Result<bool, config::boolean::Error> {
+        boolean(
+            self,
+            "fetch.showForcedUpdates",
+            &config::tree::Fetch::SHOW_FORCED_UPDATES,
+            true,
+        )
+    }
+
     pub(crate) fn may_use_commit_graph(&self) -> Result<bool, config::boolean::Error> {
         const DEFAULT: bool = true;
         self.resolved.boolean("core.commitGraph").map_or(Ok(DEFAULT), |res| {
@@ -223,6 +264,15 @@ impl Cache {
         })
     }
 
+    pub(crate) fn untracked_cache_mode(
+        &self,
+    ) -> Result<config::untracked_cache::Mode, config::key::GenericErrorWithValue> {
+        use crate::config::cache::util::ApplyLeniencyDefault;
+        Core::UNTRACKED_CACHE
+            .try_into_untracked_cache(self.resolved.boolean(Core::UNTRACKED_CACHE))
+            .with_lenient_default(self.lenient_config)
+    }
+
     /// Returns (file-timeout, pack-refs timeout)
     pub(crate) fn lock_timeout(
         &self,
@@ -375,6 +425,7 @@ impl Cache {
             destination_is_initially_empty: false,
             overwrite_existing: false,
             keep_going: false,
+            conflicts: Default::default(),
             stat_options: self.stat_options().map_err(|err| match err {
                 config::stat_options::Error::ConfigCheckStat(err) => {
                     config::checkout_options::Error::ConfigCheckStat(err)
@@ -401,7 +452,8 @@ impl Cache {
             gix_ignore::Search::from_git_dir(git_dir, excludes_file, buf)?,
             None,
             source,
-        ))
+        )
+        .with_pattern_cache(self.pattern_cache.clone()))
     }
     // TODO: at least one test, maybe related to core.attributesFile configuration.
     #[cfg(feature = "attributes")]
@@ -439,7 +491,8 @@ impl Cache {
             Some(info_attributes_path),
             source,
             collection,
-        );
+        )
+        .with_pattern_cache(self.pattern_cache.clone());
         Ok((state, buf))
     }
 
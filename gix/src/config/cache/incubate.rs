@@ -14,8 +14,10 @@ pub(crate) struct StageOne {
     pub lossy: Option<bool>,
     pub object_hash: gix_hash::Kind,
     pub reflog: Option<gix_ref::store::WriteReflog>,
+    pub ref_format: gix_ref::store::Format,
     pub precompose_unicode: bool,
     pub protect_windows: bool,
+    pub ignore_case: bool,
 }
 
 /// Initialization
@@ -54,6 +56,15 @@ impl StageOne {
             })
             .transpose()?
             .unwrap_or(gix_hash::Kind::Sha1);
+        let ref_format = (repo_format_version == 1)
+            .then(|| {
+                config
+                    .string(Extensions::REF_STORAGE)
+                    .map(|format| Extensions::REF_STORAGE.try_into_ref_format(format))
+            })
+            .flatten()
+            .transpose()?
+            .unwrap_or_default();
 
         let extension_worktree = util::config_bool(
             &config,
@@ -90,6 +101,8 @@ impl StageOne {
             )
             .with_lenient_default_value(lenient, IS_WINDOWS)?;
 
+        let ignore_case = util::config_bool(&config, &Core::IGNORE_CASE, "core.ignoreCase", false, lenient)?;
+
         let reflog = util::query_refupdates(&config, lenient)?;
         Ok(StageOne {
             git_dir_config: config,
@@ -98,8 +111,10 @@ impl StageOne {
             lossy,
             object_hash,
             reflog,
+            ref_format,
             precompose_unicode,
             protect_windows,
+            ignore_case,
         })
     }
 }
@@ -27,8 +27,10 @@ impl Cache {
             is_bare,
             object_hash,
             reflog: _,
+            ref_format: _,
             precompose_unicode: _,
             protect_windows: _,
+            ignore_case: _,
         }: StageOne,
         git_dir: &std::path::Path,
         branch_name: Option<&gix_ref::FullNameRef>,
@@ -56,6 +58,7 @@ impl Cache {
         lenient_config: bool,
         api_config_overrides: &[BString],
         cli_config_overrides: &[BString],
+        environment_overrides: Option<&open::EnvironmentOverrides>,
     ) -> Result<Self, Error> {
         let options = gix_config::file::init::Options {
             includes: if use_includes {
@@ -136,7 +139,14 @@ impl Cache {
                         source: gix_config::Source::Api,
                     })?;
             }
-            apply_environment_overrides(&mut globals, *git_prefix, http_transport, identity, objects)?;
+            apply_environment_overrides(
+                &mut globals,
+                *git_prefix,
+                http_transport,
+                identity,
+                objects,
+                environment_overrides,
+            )?;
             globals.set_meta(local_meta);
             globals
         };
@@ -146,6 +156,7 @@ impl Cache {
         use util::config_bool;
         let reflog = util::query_refupdates(&config, lenient_config)?;
         let refs_namespace = util::query_refs_namespace(&config, lenient_config)?;
+        let fsync = util::query_fsync(&config, lenient_config)?;
         let ignore_case = config_bool(&config, &Core::IGNORE_CASE, "core.ignoreCase", false, lenient_config)?;
         let use_multi_pack_index = config_bool(
             &config,
@@ -170,6 +181,7 @@ impl Cache {
             object_cache_bytes,
             reflog,
             refs_namespace,
+            fsync,
             is_bare,
             ignore_case,
             hex_len,
@@ -186,6 +198,8 @@ impl Cache {
             url_scheme: Default::default(),
             #[cfg(feature = "blob-diff")]
             diff_algorithm: Default::default(),
+            #[cfg(feature = "excludes")]
+            pattern_cache: Default::default(),
         })
     }
 
@@ -227,11 +241,13 @@ impl Cache {
         }
         let reflog = util::query_refupdates(config, self.lenient_config)?;
         let refs_namespace = util::query_refs_namespace(config, self.lenient_config)?;
+        let fsync = util::query_fsync(config, self.lenient_config)?;
 
         self.hex_len = hex_len;
         self.ignore_case = ignore_case;
         self.reflog = reflog;
         self.refs_namespace = refs_namespace;
+        self.fsync = fsync;
 
         self.user_agent = Default::default();
         self.personas = Default::default();
@@ -303,8 +319,14 @@ impl crate::Repository {
     }
 
     fn apply_changed_values(&mut self) {
-        self.refs.write_reflog = util::reflog_or_default(self.config.reflog, self.work_dir().is_some());
+        self.refs.write_reflog = if self.options.read_only {
+            gix_ref::store::WriteReflog::Disable
+        } else {
+            util::reflog_or_default(self.config.reflog, self.work_dir().is_some())
+        };
         self.refs.namespace.clone_from(&self.config.refs_namespace);
+        self.refs.ignore_case = self.config.ignore_case;
+        self.refs.fsync = self.config.fsync.references;
     }
 }
 
@@ -314,15 +336,19 @@ fn apply_environment_overrides(
     http_transport: Permission,
     identity: Permission,
     objects: Permission,
+    overrides: Option<&open::EnvironmentOverrides>,
 ) -> Result<(), Error> {
     fn env(key: &'static dyn config::tree::Key) -> &'static str {
         key.the_environment_override()
     }
-    fn var_as_bstring(var: &str, perm: Permission) -> Option<BString> {
-        perm.check_opt(var)
-            .and_then(std::env::var_os)
-            .and_then(|val| gix_path::os_string_into_bstring(val).ok())
-    }
+    // Overrides that were set programmatically don't need permission to be used.
+    let var_as_bstring = |var: &str, perm: Permission| -> Option<BString> {
+        overrides.and_then(|o| o.config_value(var)).or_else(|| {
+            perm.check_opt(var)
+                .and_then(std::env::var_os)
+                .and_then(|val| gix_path::os_string_into_bstring(val).ok())
+        })
+    };
 
     let mut env_override = gix_config::File::new(gix_config::file::Metadata::from(gix_config::Source::EnvOverride));
     for (section_name, subsection_name, permission, data) in [
@@ -55,6 +55,41 @@ pub(crate) fn query_refupdates(
         .map_err(Into::into)
 }
 
+pub(crate) fn query_fsync(config: &gix_config::File<'static>, lenient_config: bool) -> Result<config::Fsync, Error> {
+    use gix_features::fs::Fsync;
+    let mut components = config
+        .string("core.fsync")
+        .map(|value| Core::FSYNC.try_into_components(value))
+        .transpose()
+        .with_leniency(lenient_config)?
+        .unwrap_or_default();
+    if config_bool(
+        config,
+        &Core::FSYNC_OBJECT_FILES,
+        "core.fsyncObjectFiles",
+        false,
+        lenient_config,
+    )? {
+        components.loose_objects = true;
+    }
+    let method = config
+        .string("core.fsyncMethod")
+        .map(|value| Core::FSYNC_METHOD.try_into_fsync_method(value))
+        .transpose()
+        .with_leniency(lenient_config)?
+        .unwrap_or(Fsync::PerFile);
+    // Like in `git`, batching only applies to loose objects, everything else is flushed file by file.
+    let per_file = if method == Fsync::Batch { Fsync::PerFile } else { method };
+    let method_if = |enabled: bool, method: Fsync| if enabled { method } else { Fsync::None };
+    Ok(config::Fsync {
+        loose_objects: method_if(components.loose_objects, method),
+        packs: method_if(components.packs || components.pack_metadata, per_file),
+        index: method_if(components.index, per_file),
+        references: method_if(components.references, per_file),
+        commit_graph: method_if(components.commit_graph, per_file),
+    })
+}
+
 pub(crate) fn query_refs_namespace(
     config: &gix_config::File<'static>,
     lenient_config: bool,
@@ -74,7 +109,7 @@ pub(crate) fn reflog_or_default(
     config_reflog.unwrap_or(if has_worktree {
         gix_ref::store::WriteReflog::Normal
     } else {
-        gix_ref::store::WriteReflog::Disable
+        gix_ref::store::WriteReflog::AppendToExisting
     })
 }
 
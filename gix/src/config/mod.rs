@@ -182,6 +182,23 @@ pub mod diff {
     }
 }
 
+///
+pub mod fsck_options {
+    use crate::bstr::BString;
+
+    /// The error produced when obtaining [options to validate objects](crate::Repository::fsck_options()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("'fsck.{name}' doesn't refer to a known message id")]
+        UnknownMessageId { name: BString },
+        #[error("'fsck.{name}' must be one of 'error', 'warn' or 'ignore', got '{value}'")]
+        InvalidSeverity { name: BString, value: BString },
+        #[error(transparent)]
+        ConfigBoolean(#[from] super::boolean::Error),
+    }
+}
+
 ///
 pub mod stat_options {
     /// The error produced when collecting stat information, and returned by [Repository::stat_options()](crate::Repository::stat_options()).
@@ -433,6 +450,45 @@ pub mod abbrev {
     }
 }
 
+///
+pub mod untracked_cache {
+    /// How to treat the untracked cache, as configured with `core.untrackedCache`.
+    #[derive(Default, Debug, Copy, Clone, PartialEq, Eq)]
+    pub enum Mode {
+        /// Keep the untracked cache if the index has one, but don't add it otherwise.
+        #[default]
+        Keep,
+        /// Use the untracked cache, and add it to the index if it's not present, trusting that the file system supports it.
+        Enabled,
+        /// Don't use the untracked cache, and remove it from the index.
+        Disabled,
+    }
+
+    ///
+    pub mod probe {
+        /// The error returned by [`Repository::probe_untracked_cache()`](crate::Repository::probe_untracked_cache()).
+        #[derive(Debug, thiserror::Error)]
+        #[allow(missing_docs)]
+        pub enum Error {
+            #[error("Could not probe the modification times of directories in the worktree")]
+            Probe(#[source] std::io::Error),
+            #[error("Could not write '{}'", path.display())]
+            Io {
+                path: std::path::PathBuf,
+                source: std::io::Error,
+            },
+            #[error(transparent)]
+            LoadConfig(#[from] gix_config::file::init::from_paths::Error),
+            #[error(transparent)]
+            SetConfig(#[from] gix_config::file::set_raw_value::Error),
+            #[error(transparent)]
+            AcquireLock(#[from] gix_lock::acquire::Error),
+            #[error(transparent)]
+            CommitLock(#[from] gix_lock::commit::Error<gix_lock::File>),
+        }
+    }
+}
+
 ///
 pub mod remote {
     ///
@@ -490,6 +546,130 @@ pub mod refspec {
     pub type Error = super::key::Error<gix_refspec::parse::Error, 'r', 'p'>;
 }
 
+///
+pub mod fsync {
+    use crate::bstr::BStr;
+
+    /// The parts of a repository that are flushed to disk when written, as configured by `core.fsync`.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub struct Components {
+        /// Loose objects, `loose-object`.
+        pub loose_objects: bool,
+        /// Pack files, `pack`.
+        pub packs: bool,
+        /// Pack indices and other files related to packs, `pack-metadata`.
+        pub pack_metadata: bool,
+        /// The commit-graph, `commit-graph`.
+        pub commit_graph: bool,
+        /// The index, `index`.
+        pub index: bool,
+        /// Loose references and packed-refs, `reference`.
+        pub references: bool,
+    }
+
+    impl Default for Components {
+        /// Flush packs, their metadata and the commit-graph, just like `git` does by default.
+        fn default() -> Self {
+            Components {
+                packs: true,
+                pack_metadata: true,
+                commit_graph: true,
+                ..Components::NONE
+            }
+        }
+    }
+
+    impl Components {
+        /// No component is flushed to disk.
+        pub const NONE: Components = Components {
+            loose_objects: false,
+            packs: false,
+            pack_metadata: false,
+            commit_graph: false,
+            index: false,
+            references: false,
+        };
+
+        /// Return the components designated by the component or aggregate `name`, or `None` if it's unknown.
+        pub fn from_name(name: &BStr) -> Option<Self> {
+            let objects = Components {
+                loose_objects: true,
+                packs: true,
+                ..Components::NONE
+            };
+            let derived_metadata = Components {
+                pack_metadata: true,
+                commit_graph: true,
+                ..Components::NONE
+            };
+            let committed = Components {
+                references: true,
+                ..objects
+            };
+            let added = Components {
+                index: true,
+                ..committed
+            };
+            Some(match &**name {
+                b"loose-object" => Components {
+                    loose_objects: true,
+                    ..Components::NONE
+                },
+                b"pack" => Components {
+                    packs: true,
+                    ..Components::NONE
+                },
+                b"pack-metadata" => Components {
+                    pack_metadata: true,
+                    ..Components::NONE
+                },
+                b"commit-graph" => Components {
+                    commit_graph: true,
+                    ..Components::NONE
+                },
+                b"index" => Components {
+                    index: true,
+                    ..Components::NONE
+                },
+                b"reference" => Components {
+                    references: true,
+                    ..Components::NONE
+                },
+                b"objects" => objects,
+                b"derived-metadata" => derived_metadata,
+                b"committed" => committed,
+                b"added" => added,
+                b"all" => added.union(derived_metadata),
+                _ => return None,
+            })
+        }
+
+        /// Return the components that are in `self` or in `other`.
+        pub fn union(self, other: Self) -> Self {
+            Components {
+                loose_objects: self.loose_objects || other.loose_objects,
+                packs: self.packs || other.packs,
+                pack_metadata: self.pack_metadata || other.pack_metadata,
+                commit_graph: self.commit_graph || other.commit_graph,
+                index: self.index || other.index,
+                references: self.references || other.references,
+            }
+        }
+
+        /// Return the components that are in `self`, but not in `other`.
+        pub fn without(self, other: Self) -> Self {
+            Components {
+                loose_objects: self.loose_objects && !other.loose_objects,
+                packs: self.packs && !other.packs,
+                pack_metadata: self.pack_metadata && !other.pack_metadata,
+                commit_graph: self.commit_graph && !other.commit_graph,
+                index: self.index && !other.index,
+                references: self.references && !other.references,
+            }
+        }
+    }
+}
+
 ///
 pub mod refs_namespace {
     /// The error produced when failing to parse a refspec from the configuration.
@@ -594,6 +774,8 @@ pub(crate) struct Cache {
     pub reflog: Option<gix_ref::store::WriteReflog>,
     /// The representation of `gitoxide.core.refsNamespace`, or `None` if the variable wasn't set.
     pub refs_namespace: Option<gix_ref::Namespace>,
+    /// How to flush files to disk, as derived from `core.fsync` and `core.fsyncMethod`.
+    pub fsync: Fsync,
     /// The configured user agent for presentation to servers.
     pub(crate) user_agent: OnceCell<String>,
     /// identities for later use, lazy initialization.
@@ -609,6 +791,9 @@ pub(crate) struct Cache {
     /// The algorithm to use when diffing blobs
     #[cfg(feature = "blob-diff")]
     pub(crate) diff_algorithm: OnceCell<gix_diff::blob::Algorithm>,
+    /// The patterns of ignore and attribute files in the worktree, shared by all stacks so unchanged files aren't parsed again.
+    #[cfg(feature = "excludes")]
+    pub(crate) pattern_cache: gix_worktree::stack::state::PatternCache,
     /// The amount of bytes to use for a memory backed delta pack cache. If `Some(0)`, no cache is used, if `None`
     /// a standard cache is used which costs near to nothing and always pays for itself.
     pub(crate) pack_cache_bytes: Option<usize>,
@@ -617,7 +802,7 @@ pub(crate) struct Cache {
     /// The amount of bytes we can hold in our static LRU cache. Otherwise, go with the defaults.
     pub(crate) static_pack_cache_limit_bytes: Option<usize>,
     /// The config section filter from the options used to initialize this instance. Keep these in sync!
-    filter_config_section: fn(&gix_config::file::Metadata) -> bool,
+    pub(crate) filter_config_section: fn(&gix_config::file::Metadata) -> bool,
     /// The object kind to pick if a prefix is ambiguous.
     #[cfg(feature = "revision")]
     pub object_kind_hint: Option<crate::revision::spec::parse::ObjectKindHint>,
@@ -632,6 +817,21 @@ pub(crate) struct Cache {
     // TODO: make core.precomposeUnicode available as well.
 }
 
+/// How to flush the files of each part of the repository to disk after writing them.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Fsync {
+    /// Used for loose objects.
+    pub loose_objects: gix_features::fs::Fsync,
+    /// Used for packs and their indices.
+    pub packs: gix_features::fs::Fsync,
+    /// Used for the index.
+    pub index: gix_features::fs::Fsync,
+    /// Used for loose references and packed-refs.
+    pub references: gix_features::fs::Fsync,
+    /// Used for the commit-graph.
+    pub commit_graph: gix_features::fs::Fsync,
+}
+
 /// Utilities shared privately across the crate, for lack of a better place.
 pub(crate) mod shared {
     use crate::{
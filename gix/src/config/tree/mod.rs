@@ -24,6 +24,8 @@ pub(crate) mod root {
         pub const CHECKOUT: sections::Checkout = sections::Checkout;
         /// The `clone` section.
         pub const CLONE: sections::Clone = sections::Clone;
+        /// The `commit` section.
+        pub const COMMIT: sections::Commit = sections::Commit;
         /// The `committer` section.
         pub const COMMITTER: sections::Committer = sections::Committer;
         /// The `core` section.
@@ -39,6 +41,8 @@ pub(crate) mod root {
         pub const FETCH: sections::Fetch = sections::Fetch;
         /// The `gitoxide` section.
         pub const GITOXIDE: sections::Gitoxide = sections::Gitoxide;
+        /// The `gpg` section.
+        pub const GPG: sections::Gpg = sections::Gpg;
         /// The `http` section.
         pub const HTTP: sections::Http = sections::Http;
         /// The `index` section.
@@ -57,6 +61,8 @@ pub(crate) mod root {
         pub const PUSH: sections::Push = sections::Push;
         /// The `remote` section.
         pub const REMOTE: sections::Remote = sections::Remote;
+        /// The `rerere` section.
+        pub const RERERE: sections::Rerere = sections::Rerere;
         /// The `safe` section.
         pub const SAFE: sections::Safe = sections::Safe;
         /// The `ssh` section.
@@ -64,10 +70,18 @@ pub(crate) mod root {
         /// The `status` section.
         #[cfg(feature = "status")]
         pub const STATUS: sections::Status = sections::Status;
+        /// The `tag` section.
+        pub const TAG: sections::Tag = sections::Tag;
+        /// The `trailer` section.
+        pub const TRAILER: sections::Trailer = sections::Trailer;
+        /// The `uploadpack` section.
+        pub const UPLOADPACK: sections::Uploadpack = sections::Uploadpack;
         /// The `user` section.
         pub const USER: sections::User = sections::User;
         /// The `url` section.
         pub const URL: sections::Url = sections::Url;
+        /// The `versionsort` section.
+        pub const VERSION_SORT: sections::VersionSort = sections::VersionSort;
 
         /// List all available sections.
         pub fn sections(&self) -> &[&dyn Section] {
@@ -76,6 +90,7 @@ pub(crate) mod root {
                 &Self::BRANCH,
                 &Self::CHECKOUT,
                 &Self::CLONE,
+                &Self::COMMIT,
                 &Self::COMMITTER,
                 &Self::CORE,
                 &Self::CREDENTIAL,
@@ -84,6 +99,7 @@ pub(crate) mod root {
                 &Self::EXTENSIONS,
                 &Self::FETCH,
                 &Self::GITOXIDE,
+                &Self::GPG,
                 &Self::HTTP,
                 &Self::INDEX,
                 &Self::INIT,
@@ -93,12 +109,17 @@ pub(crate) mod root {
                 &Self::PROTOCOL,
                 &Self::PUSH,
                 &Self::REMOTE,
+                &Self::RERERE,
                 &Self::SAFE,
                 &Self::SSH,
                 #[cfg(feature = "status")]
                 &Self::STATUS,
+                &Self::TAG,
+                &Self::TRAILER,
+                &Self::UPLOADPACK,
                 &Self::USER,
                 &Self::URL,
+                &Self::VERSION_SORT,
             ]
         }
     }
@@ -106,9 +127,10 @@ pub(crate) mod root {
 
 mod sections;
 pub use sections::{
-    branch, checkout, core, credential, extensions, fetch, gitoxide, http, index, protocol, push, remote, ssh, Author,
-    Branch, Checkout, Clone, Committer, Core, Credential, Extensions, Fetch, Gitoxide, Http, Index, Init, Mailmap,
-    Merge, Pack, Protocol, Push, Remote, Safe, Ssh, Url, User,
+    branch, checkout, core, credential, extensions, fetch, gitoxide, gpg, http, index, protocol, push, remote, ssh,
+    trailer, Author, Branch, Checkout, Clone, Commit, Committer, Core, Credential, Extensions, Fetch, Gitoxide, Gpg,
+    Http, Index, Init, Mailmap, Merge, Pack, Protocol, Push, Remote, Rerere, Safe, Ssh, Tag, Trailer, Uploadpack, Url,
+    User, VersionSort,
 };
 #[cfg(feature = "blob-diff")]
 pub use sections::{diff, Diff};
@@ -0,0 +1,20 @@
+use crate::config::{
+    tree::{keys, Commit, Key, Section},
+    Tree,
+};
+
+impl Commit {
+    /// The `commit.gpgSign` key.
+    pub const GPG_SIGN: keys::Boolean = keys::Boolean::new_boolean("gpgSign", &Tree::COMMIT)
+        .with_note("Commits are signed with the signer configured in `gpg.format` and `user.signingKey`");
+}
+
+impl Section for Commit {
+    fn name(&self) -> &str {
+        "commit"
+    }
+
+    fn keys(&self) -> &[&dyn Key] {
+        &[&Self::GPG_SIGN]
+    }
+}
@@ -26,6 +26,16 @@ impl Core {
     pub const EDITOR: keys::Program = keys::Program::new_program("editor", &config::Tree::CORE);
     /// The `core.fileMode` key.
     pub const FILE_MODE: keys::Boolean = keys::Boolean::new_boolean("fileMode", &config::Tree::CORE);
+    /// The `core.fsync` key.
+    pub const FSYNC: Fsync = Fsync::new_with_validate("fsync", &config::Tree::CORE, validate::Fsync);
+    /// The `core.fsyncMethod` key.
+    pub const FSYNC_METHOD: FsyncMethod =
+        FsyncMethod::new_with_validate("fsyncMethod", &config::Tree::CORE, validate::FsyncMethod).with_deviation(
+            "'writeout-only' only starts writeback on Linux and flushes file contents elsewhere. 'batch' doesn't flush once more after the last file",
+        );
+    /// The `core.fsyncObjectFiles` key.
+    pub const FSYNC_OBJECT_FILES: keys::Boolean = keys::Boolean::new_boolean("fsyncObjectFiles", &config::Tree::CORE)
+        .with_note("deprecated in favor of adding 'loose-object' to 'core.fsync'");
     /// The `core.ignoreCase` key.
     pub const IGNORE_CASE: keys::Boolean = keys::Boolean::new_boolean("ignoreCase", &config::Tree::CORE);
     /// The `core.filesRefLockTimeout` key.
@@ -55,6 +65,9 @@ impl Core {
     pub const SYMLINKS: keys::Boolean = keys::Boolean::new_boolean("symlinks", &config::Tree::CORE);
     /// The `core.trustCTime` key.
     pub const TRUST_C_TIME: keys::Boolean = keys::Boolean::new_boolean("trustCTime", &config::Tree::CORE);
+    /// The `core.untrackedCache` key.
+    pub const UNTRACKED_CACHE: UntrackedCache =
+        UntrackedCache::new_with_validate("untrackedCache", &config::Tree::CORE, validate::UntrackedCache);
     /// The `core.worktree` key.
     pub const WORKTREE: keys::Any = keys::Any::new("worktree", &config::Tree::CORE)
         .with_environment_override("GIT_WORK_TREE")
@@ -77,6 +90,11 @@ impl Core {
         .with_environment_override("GIT_NO_REPLACE_OBJECTS");
     /// The `core.commitGraph` key.
     pub const COMMIT_GRAPH: keys::Boolean = keys::Boolean::new_boolean("commitGraph", &config::Tree::CORE);
+    /// The `core.sparseCheckout` key.
+    pub const SPARSE_CHECKOUT: keys::Boolean = keys::Boolean::new_boolean("sparseCheckout", &config::Tree::CORE);
+    /// The `core.sparseCheckoutCone` key.
+    pub const SPARSE_CHECKOUT_CONE: keys::Boolean =
+        keys::Boolean::new_boolean("sparseCheckoutCone", &config::Tree::CORE);
     /// The `core.safecrlf` key.
     #[cfg(feature = "attributes")]
     pub const SAFE_CRLF: SafeCrlf = SafeCrlf::new_with_validate("safecrlf", &config::Tree::CORE, validate::SafeCrlf);
@@ -110,6 +128,9 @@ impl Section for Core {
             &Self::DISAMBIGUATE,
             &Self::EDITOR,
             &Self::FILE_MODE,
+            &Self::FSYNC,
+            &Self::FSYNC_METHOD,
+            &Self::FSYNC_OBJECT_FILES,
             &Self::IGNORE_CASE,
             &Self::FILES_REF_LOCK_TIMEOUT,
             &Self::PACKED_REFS_TIMEOUT,
@@ -119,6 +140,7 @@ impl Section for Core {
             &Self::REPOSITORY_FORMAT_VERSION,
             &Self::SYMLINKS,
             &Self::TRUST_C_TIME,
+            &Self::UNTRACKED_CACHE,
             &Self::WORKTREE,
             &Self::PROTECT_HFS,
             &Self::PROTECT_NTFS,
@@ -128,6 +150,8 @@ impl Section for Core {
             &Self::SSH_COMMAND,
             &Self::USE_REPLACE_REFS,
             &Self::COMMIT_GRAPH,
+            &Self::SPARSE_CHECKOUT,
+            &Self::SPARSE_CHECKOUT_CONE,
             #[cfg(feature = "attributes")]
             &Self::SAFE_CRLF,
             #[cfg(feature = "attributes")]
@@ -143,6 +167,12 @@ impl Section for Core {
 /// The `core.checkStat` key.
 pub type CheckStat = keys::Any<validate::CheckStat>;
 
+/// The `core.fsync` key.
+pub type Fsync = keys::Any<validate::Fsync>;
+
+/// The `core.fsyncMethod` key.
+pub type FsyncMethod = keys::Any<validate::FsyncMethod>;
+
 /// The `core.abbrev` key.
 pub type Abbrev = keys::Any<validate::Abbrev>;
 
@@ -152,6 +182,9 @@ pub type LogAllRefUpdates = keys::Any<validate::LogAllRefUpdates>;
 /// The `core.disambiguate` key.
 pub type Disambiguate = keys::Any<validate::Disambiguate>;
 
+/// The `core.untrackedCache` key.
+pub type UntrackedCache = keys::Any<validate::UntrackedCache>;
+
 #[cfg(feature = "attributes")]
 mod filter {
     use super::validate;
@@ -345,7 +378,7 @@ mod log_all_ref_updates {
                 Some(Ok(bool)) => Ok(Some(if bool {
                     gix_ref::store::WriteReflog::Normal
                 } else {
-                    gix_ref::store::WriteReflog::Disable
+                    gix_ref::store::WriteReflog::AppendToExisting
                 })),
                 Some(Err(err)) => match err.input {
                     val if val.eq_ignore_ascii_case(b"always") => Ok(Some(gix_ref::store::WriteReflog::Always)),
@@ -357,6 +390,29 @@ mod log_all_ref_updates {
     }
 }
 
+mod untracked_cache {
+    use crate::{config, config::tree::core::UntrackedCache};
+
+    impl UntrackedCache {
+        /// Returns the mode in which to use the untracked cache as parsed from `value`. If `value` is not a boolean, we try
+        /// to interpret the string value instead, which is the default if `None`.
+        pub fn try_into_untracked_cache(
+            &'static self,
+            value: Option<Result<bool, gix_config::value::Error>>,
+        ) -> Result<config::untracked_cache::Mode, config::key::GenericErrorWithValue> {
+            use config::untracked_cache::Mode;
+            match value {
+                Some(Ok(bool)) => Ok(if bool { Mode::Enabled } else { Mode::Disabled }),
+                Some(Err(err)) => match err.input {
+                    val if val.eq_ignore_ascii_case(b"keep") => Ok(Mode::Keep),
+                    val => Err(config::key::GenericErrorWithValue::from_value(self, val)),
+                },
+                None => Ok(Mode::default()),
+            }
+        }
+    }
+}
+
 mod check_stat {
     use std::borrow::Cow;
 
@@ -383,6 +439,69 @@ mod check_stat {
     }
 }
 
+mod fsync {
+    use std::borrow::Cow;
+
+    use crate::{
+        bstr::{BStr, ByteSlice},
+        config,
+        config::{
+            fsync::Components,
+            tree::core::{Fsync, FsyncMethod},
+        },
+    };
+
+    impl Fsync {
+        /// Convert the comma-separated list of components in `value` into the set of components to flush to disk,
+        /// with components prefixed with `-` being removed from the default set, and `none` removing all of them.
+        pub fn try_into_components(
+            &'static self,
+            value: Cow<'_, BStr>,
+        ) -> Result<Components, config::key::GenericErrorWithValue> {
+            let mut current = Components::default();
+            let (mut positive, mut negative) = (Components::NONE, Components::NONE);
+            for name in value
+                .split_str(",")
+                .map(|name| name.trim())
+                .filter(|name| !name.is_empty())
+            {
+                if name == b"none" {
+                    current = Components::NONE;
+                    continue;
+                }
+                let (name, negated) = match name.strip_prefix(b"-") {
+                    Some(name) => (name, true),
+                    None => (name, false),
+                };
+                let components = Components::from_name(name.as_bstr())
+                    .ok_or_else(|| config::key::GenericErrorWithValue::from_value(self, value.clone().into_owned()))?;
+                if negated {
+                    negative = negative.union(components);
+                } else {
+                    positive = positive.union(components);
+                }
+            }
+            Ok(current.without(negative).union(positive))
+        }
+    }
+
+    impl FsyncMethod {
+        /// Convert `value` into the way files should be flushed to disk.
+        pub fn try_into_fsync_method(
+            &'static self,
+            value: Cow<'_, BStr>,
+        ) -> Result<gix_features::fs::Fsync, config::key::GenericErrorWithValue> {
+            use gix_features::fs::Fsync;
+            Ok(match value.as_ref().as_bytes() {
+                b"fsync" => Fsync::PerFile,
+                b"writeout-only" => Fsync::WriteoutOnly,
+                b"batch" => Fsync::Batch,
+                _ => return Err(config::key::GenericErrorWithValue::from_value(self, value.into_owned())),
+            })
+        }
+    }
+}
+
 mod abbrev {
     use std::borrow::Cow;
 
@@ -472,6 +591,31 @@ mod validate {
         }
     }
 
+    pub struct UntrackedCache;
+    impl keys::Validate for UntrackedCache {
+        fn validate(&self, value: &BStr) -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>> {
+            super::Core::UNTRACKED_CACHE
+                .try_into_untracked_cache(Some(gix_config::Boolean::try_from(value).map(|b| b.0)))?;
+            Ok(())
+        }
+    }
+
+    pub struct Fsync;
+    impl keys::Validate for Fsync {
+        fn validate(&self, value: &BStr) -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>> {
+            super::Core::FSYNC.try_into_components(value.into())?;
+            Ok(())
+        }
+    }
+
+    pub struct FsyncMethod;
+    impl keys::Validate for FsyncMethod {
+        fn validate(&self, value: &BStr) -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>> {
+            super::Core::FSYNC_METHOD.try_into_fsync_method(value.into())?;
+            Ok(())
+        }
+    }
+
     pub struct CheckStat;
     impl keys::Validate for CheckStat {
         fn validate(&self, value: &BStr) -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>> {
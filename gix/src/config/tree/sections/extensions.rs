@@ -11,11 +11,21 @@ impl Extensions {
         ObjectFormat::new_with_validate("objectFormat", &config::Tree::EXTENSIONS, validate::ObjectFormat).with_note(
             "Support for SHA256 is prepared but not fully implemented yet. For now we abort when encountered",
         );
+    /// The `extensions.partialClone` key, naming the remote that promises to provide objects that are missing locally.
+    pub const PARTIAL_CLONE: keys::RemoteName =
+        keys::RemoteName::new_remote_name("partialClone", &config::Tree::EXTENSIONS);
+    /// The `extensions.refStorage` key.
+    pub const REF_STORAGE: RefStorage =
+        RefStorage::new_with_validate("refStorage", &config::Tree::EXTENSIONS, validate::RefStorage)
+            .with_note("The reftable format is recognized, but we abort when encountered as it can't be read yet");
 }
 
-/// The `core.checkStat` key.
+/// The `extensions.objectFormat` key.
 pub type ObjectFormat = keys::Any<validate::ObjectFormat>;
 
+/// The `extensions.refStorage` key.
+pub type RefStorage = keys::Any<validate::RefStorage>;
+
 mod object_format {
     use std::borrow::Cow;
 
@@ -35,13 +45,39 @@ mod object_format {
     }
 }
 
+mod ref_storage {
+    use std::borrow::Cow;
+
+    use crate::{bstr::BStr, config, config::tree::sections::extensions::RefStorage};
+
+    impl RefStorage {
+        pub fn try_into_ref_format(
+            &'static self,
+            value: Cow<'_, BStr>,
+        ) -> Result<gix_ref::store::Format, config::key::GenericErrorWithValue> {
+            if value.as_ref() == "files" {
+                Ok(gix_ref::store::Format::Files)
+            } else if value.as_ref() == "reftable" {
+                Ok(gix_ref::store::Format::Reftable)
+            } else {
+                Err(config::key::GenericErrorWithValue::from_value(self, value.into_owned()))
+            }
+        }
+    }
+}
+
 impl Section for Extensions {
     fn name(&self) -> &str {
         "extensions"
     }
 
     fn keys(&self) -> &[&dyn Key] {
-        &[&Self::OBJECT_FORMAT, &Self::WORKTREE_CONFIG]
+        &[
+            &Self::OBJECT_FORMAT,
+            &Self::PARTIAL_CLONE,
+            &Self::REF_STORAGE,
+            &Self::WORKTREE_CONFIG,
+        ]
     }
 }
 
@@ -56,4 +92,13 @@ mod validate {
             Ok(())
         }
     }
+
+    pub struct RefStorage;
+
+    impl keys::Validate for RefStorage {
+        fn validate(&self, value: &BStr) -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>> {
+            super::Extensions::REF_STORAGE.try_into_ref_format(value.into())?;
+            Ok(())
+        }
+    }
 }
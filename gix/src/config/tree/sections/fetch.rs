@@ -10,6 +10,9 @@ impl Fetch {
         &config::Tree::FETCH,
         validate::NegotiationAlgorithm,
     );
+    /// The `fetch.showForcedUpdates` key.
+    pub const SHOW_FORCED_UPDATES: keys::Boolean =
+        keys::Boolean::new_boolean("showForcedUpdates", &config::Tree::FETCH);
     /// The `fetch.recurseSubmodules` key.
     #[cfg(feature = "attributes")]
     pub const RECURSE_SUBMODULES: RecurseSubmodules =
@@ -24,6 +27,7 @@ impl Section for Fetch {
     fn keys(&self) -> &[&dyn Key] {
         &[
             &Self::NEGOTIATION_ALGORITHM,
+            &Self::SHOW_FORCED_UPDATES,
             #[cfg(feature = "attributes")]
             &Self::RECURSE_SUBMODULES,
         ]
@@ -129,6 +129,14 @@ mod subsections {
             keys::Boolean::new_boolean("externalCommandStderr", &Gitoxide::CORE)
                 .with_environment_override("GIX_EXTERNAL_COMMAND_STDERR");
 
+        /// The `gitoxide.core.sha1CollisionDetection` key (default `true`).
+        ///
+        /// If `true`, the default, objects in packs received from a remote are hashed with SHA-1 collision detection,
+        /// just like `git` does with `sha1dc`, and a fetch fails if a collision attack is detected.
+        /// If `false`, a faster implementation without these checks is used.
+        pub const SHA1_COLLISION_DETECTION: keys::Boolean =
+            keys::Boolean::new_boolean("sha1CollisionDetection", &Gitoxide::CORE);
+
         /// The `gitoxide.core.refsNamespace` key.
         pub const REFS_NAMESPACE: RefsNamespace =
             keys::Any::new_with_validate("refsNamespace", &Gitoxide::CORE, super::validate::RefsNamespace)
@@ -149,6 +157,7 @@ mod subsections {
                 &Self::PROTECT_WINDOWS,
                 &Self::FILTER_PROCESS_DELAY,
                 &Self::EXTERNAL_COMMAND_STDERR,
+                &Self::SHA1_COLLISION_DETECTION,
                 &Self::REFS_NAMESPACE,
             ]
         }
@@ -207,6 +216,12 @@ mod subsections {
         pub const PROXY_AUTH_METHOD: http::ProxyAuthMethod =
             http::ProxyAuthMethod::new_proxy_auth_method("proxyAuthMethod", &Gitoxide::HTTP)
                 .with_environment_override("GIT_HTTP_PROXY_AUTHMETHOD");
+        /// The `gitoxide.http.poolConnections` key.
+        ///
+        /// If set, connections are kept alive after use to be reused by later fetches and pushes to the same host
+        /// within the same process.
+        pub const POOL_CONNECTIONS: keys::Boolean = keys::Boolean::new_boolean("poolConnections", &Gitoxide::HTTP)
+            .with_note("entirely new, useful for long-running processes that talk to the same hosts repeatedly");
     }
 
     impl Section for Http {
@@ -225,6 +240,7 @@ mod subsections {
                 &Self::SSL_VERSION_MAX,
                 &Self::SSL_NO_VERIFY,
                 &Self::PROXY_AUTH_METHOD,
+                &Self::POOL_CONNECTIONS,
             ]
         }
 
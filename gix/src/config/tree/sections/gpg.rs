@@ -0,0 +1,125 @@
+use crate::config::{
+    tree::{keys, Gpg, Key, Section},
+    Tree,
+};
+
+impl Gpg {
+    /// The `gpg.program` key.
+    pub const PROGRAM: keys::Executable = keys::Executable::new_executable("program", &Tree::GPG)
+        .with_note("Used for `openpgp` signatures if `gpg.openpgp.program` is unset");
+    /// The `gpg.minTrustLevel` key.
+    pub const MIN_TRUST_LEVEL: keys::String = keys::String::new_string("minTrustLevel", &Tree::GPG);
+    /// The `gpg.format` key.
+    pub const FORMAT: keys::String =
+        keys::String::new_string("format", &Tree::GPG).with_note("One of 'openpgp' (the default), 'x509' or 'ssh'");
+
+    /// The `gpg.openpgp` section.
+    pub const OPENPGP: OpenPgp = OpenPgp;
+    /// The `gpg.x509` section.
+    pub const X509: X509 = X509;
+    /// The `gpg.ssh` section.
+    pub const SSH: Ssh = Ssh;
+}
+
+impl Section for Gpg {
+    fn name(&self) -> &str {
+        "gpg"
+    }
+
+    fn keys(&self) -> &[&dyn Key] {
+        &[&Self::PROGRAM, &Self::MIN_TRUST_LEVEL, &Self::FORMAT]
+    }
+
+    fn sub_sections(&self) -> &[&dyn Section] {
+        &[&Self::OPENPGP, &Self::X509, &Self::SSH]
+    }
+}
+
+mod subsections {
+    use crate::config::{
+        tree::{keys, Gpg, Key, Section},
+        Tree,
+    };
+
+    /// The `openpgp` sub-section.
+    #[derive(Copy, Clone, Default)]
+    pub struct OpenPgp;
+
+    impl OpenPgp {
+        /// The `gpg.openpgp.program` key.
+        pub const PROGRAM: keys::Executable = keys::Executable::new_executable("program", &Gpg::OPENPGP);
+    }
+
+    impl Section for OpenPgp {
+        fn name(&self) -> &str {
+            "openpgp"
+        }
+
+        fn keys(&self) -> &[&dyn Key] {
+            &[&Self::PROGRAM]
+        }
+
+        fn parent(&self) -> Option<&dyn Section> {
+            Some(&Tree::GPG)
+        }
+    }
+
+    /// The `x509` sub-section.
+    #[derive(Copy, Clone, Default)]
+    pub struct X509;
+
+    impl X509 {
+        /// The `gpg.x509.program` key.
+        pub const PROGRAM: keys::Executable = keys::Executable::new_executable("program", &Gpg::X509);
+    }
+
+    impl Section for X509 {
+        fn name(&self) -> &str {
+            "x509"
+        }
+
+        fn keys(&self) -> &[&dyn Key] {
+            &[&Self::PROGRAM]
+        }
+
+        fn parent(&self) -> Option<&dyn Section> {
+            Some(&Tree::GPG)
+        }
+    }
+
+    /// The `ssh` sub-section.
+    #[derive(Copy, Clone, Default)]
+    pub struct Ssh;
+
+    impl Ssh {
+        /// The `gpg.ssh.program` key.
+        pub const PROGRAM: keys::Executable = keys::Executable::new_executable("program", &Gpg::SSH);
+        /// The `gpg.ssh.allowedSignersFile` key.
+        pub const ALLOWED_SIGNERS_FILE: keys::Path = keys::Path::new_path("allowedSignersFile", &Gpg::SSH);
+        /// The `gpg.ssh.revocationFile` key.
+        pub const REVOCATION_FILE: keys::Path = keys::Path::new_path("revocationFile", &Gpg::SSH);
+        /// The `gpg.ssh.defaultKeyCommand` key.
+        pub const DEFAULT_KEY_COMMAND: keys::Program = keys::Program::new_program("defaultKeyCommand", &Gpg::SSH)
+            .with_note("Used to obtain a literal key if `user.signingKey` is unset");
+    }
+
+    impl Section for Ssh {
+        fn name(&self) -> &str {
+            "ssh"
+        }
+
+        fn keys(&self) -> &[&dyn Key] {
+            &[
+                &Self::PROGRAM,
+                &Self::ALLOWED_SIGNERS_FILE,
+                &Self::REVOCATION_FILE,
+                &Self::DEFAULT_KEY_COMMAND,
+            ]
+        }
+
+        fn parent(&self) -> Option<&dyn Section> {
+            Some(&Tree::GPG)
+        }
+    }
+}
+pub use subsections::{OpenPgp, Ssh, X509};
@@ -34,6 +34,11 @@ impl Merge {
     #[cfg(feature = "merge")]
     pub const CONFLICT_STYLE: ConflictStyle =
         ConflictStyle::new_with_validate("conflictStyle", &config::Tree::MERGE, validate::ConflictStyle);
+    /// The `merge.directoryRenames` key.
+    #[cfg(feature = "merge")]
+    pub const DIRECTORY_RENAMES: DirectoryRenames =
+        DirectoryRenames::new_with_validate("directoryRenames", &config::Tree::MERGE, validate::DirectoryRenames)
+            .with_note("'conflict' is treated like 'true' as moved files aren't considered conflicting");
 }
 
 impl Section for Merge {
@@ -53,6 +58,8 @@ impl Section for Merge {
             &Self::DRIVER_RECURSIVE,
             #[cfg(feature = "merge")]
             &Self::CONFLICT_STYLE,
+            #[cfg(feature = "merge")]
+            &Self::DIRECTORY_RENAMES,
         ]
     }
 }
@@ -61,6 +68,32 @@ impl Section for Merge {
 #[cfg(feature = "merge")]
 pub type ConflictStyle = keys::Any<validate::ConflictStyle>;
 
+/// The `merge.directoryRenames` key.
+#[cfg(feature = "merge")]
+pub type DirectoryRenames = keys::Any<validate::DirectoryRenames>;
+
+#[cfg(feature = "merge")]
+mod directory_renames {
+    use crate::{bstr::BStr, config, config::tree::sections::merge::DirectoryRenames};
+    use std::borrow::Cow;
+
+    impl DirectoryRenames {
+        /// Return `true` if directory renames should be detected, which is the case for booleans that are `true`
+        /// and for `conflict`.
+        pub fn try_into_directory_renames(
+            &'static self,
+            value: Cow<'_, BStr>,
+        ) -> Result<bool, config::key::GenericErrorWithValue> {
+            if value.as_ref() == "conflict" {
+                return Ok(true);
+            }
+            gix_config::Boolean::try_from(value.as_ref())
+                .map(|b| b.0)
+                .map_err(|_| config::key::GenericErrorWithValue::from_value(self, value.into_owned()))
+        }
+    }
+}
+
 #[cfg(feature = "merge")]
 mod conflict_style {
     use crate::{bstr::BStr, config, config::tree::sections::merge::ConflictStyle};
@@ -101,4 +134,12 @@ mod validate {
             Ok(())
         }
     }
+
+    pub struct DirectoryRenames;
+    impl keys::Validate for DirectoryRenames {
+        fn validate(&self, value: &BStr) -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>> {
+            Merge::DIRECTORY_RENAMES.try_into_directory_renames(value.into())?;
+            Ok(())
+        }
+    }
 }
@@ -20,6 +20,11 @@ pub mod checkout;
 pub struct Clone;
 mod clone;
 
+/// The `commit` top-level section.
+#[derive(Copy, Clone, Default)]
+pub struct Commit;
+mod commit;
+
 /// The `committer` top-level section.
 #[derive(Copy, Clone, Default)]
 pub struct Committer;
@@ -57,6 +62,11 @@ pub mod fetch;
 pub struct Gitoxide;
 pub mod gitoxide;
 
+/// The `gpg` top-level section.
+#[derive(Copy, Clone, Default)]
+pub struct Gpg;
+pub mod gpg;
+
 /// The `http` top-level section.
 #[derive(Copy, Clone, Default)]
 pub struct Http;
@@ -95,6 +105,11 @@ pub mod protocol;
 pub struct Push;
 pub mod push;
 
+/// The `rerere` top-level section.
+#[derive(Copy, Clone, Default)]
+pub struct Rerere;
+mod rerere;
+
 /// The `remote` top-level section.
 #[derive(Copy, Clone, Default)]
 pub struct Remote;
@@ -117,6 +132,21 @@ pub struct Status;
 #[cfg(feature = "status")]
 pub mod status;
 
+/// The `tag` top-level section.
+#[derive(Copy, Clone, Default)]
+pub struct Tag;
+mod tag;
+
+/// The `trailer` top-level section.
+#[derive(Copy, Clone, Default)]
+pub struct Trailer;
+pub mod trailer;
+
+/// The `uploadpack` top-level section.
+#[derive(Copy, Clone, Default)]
+pub struct Uploadpack;
+mod uploadpack;
+
 /// The `user` top-level section.
 #[derive(Copy, Clone, Default)]
 pub struct User;
@@ -126,3 +156,8 @@ mod user;
 #[derive(Copy, Clone, Default)]
 pub struct Url;
 mod url;
+
+/// The `versionsort` top-level section.
+#[derive(Copy, Clone, Default)]
+pub struct VersionSort;
+mod version_sort;
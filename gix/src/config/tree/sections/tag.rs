@@ -0,0 +1,20 @@
+use crate::config::{
+    tree::{keys, Key, Section, Tag},
+    Tree,
+};
+
+impl Tag {
+    /// The `tag.gpgSign` key.
+    pub const GPG_SIGN: keys::Boolean = keys::Boolean::new_boolean("gpgSign", &Tree::TAG)
+        .with_note("Annotated tags are signed with the signer configured in `gpg.format` and `user.signingKey`");
+}
+
+impl Section for Tag {
+    fn name(&self) -> &str {
+        "tag"
+    }
+
+    fn keys(&self) -> &[&dyn Key] {
+        &[&Self::GPG_SIGN]
+    }
+}
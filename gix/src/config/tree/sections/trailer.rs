@@ -0,0 +1,136 @@
+use crate::config;
+use crate::config::tree::sections::Trailer;
+use crate::config::tree::{keys, Key, Section};
+
+impl Trailer {
+    /// The `trailer.separators` key.
+    pub const SEPARATORS: keys::String = keys::String::new_string("separators", &config::Tree::TRAILER);
+    /// The `trailer.where` key.
+    pub const WHERE: Where = Where::new_with_validate("where", &config::Tree::TRAILER, validate::Where);
+    /// The `trailer.ifExists` key.
+    pub const IF_EXISTS: IfExists = IfExists::new_with_validate("ifExists", &config::Tree::TRAILER, validate::IfExists);
+    /// The `trailer.ifMissing` key.
+    pub const IF_MISSING: IfMissing =
+        IfMissing::new_with_validate("ifMissing", &config::Tree::TRAILER, validate::IfMissing);
+}
+
+/// The `trailer.where` key.
+pub type Where = keys::Any<validate::Where>;
+
+/// The `trailer.ifExists` key.
+pub type IfExists = keys::Any<validate::IfExists>;
+
+/// The `trailer.ifMissing` key.
+pub type IfMissing = keys::Any<validate::IfMissing>;
+
+mod where_ {
+    use std::borrow::Cow;
+
+    use gix_object::commit::message::trailer::Placement;
+
+    use crate::{bstr::BStr, config, config::tree::trailer::Where};
+
+    impl Where {
+        /// Convert `value` into the placement of new trailers.
+        pub fn try_into_placement(
+            &'static self,
+            value: Cow<'_, BStr>,
+        ) -> Result<Placement, config::key::GenericErrorWithValue> {
+            use crate::bstr::ByteSlice;
+            Ok(match value.as_ref().to_ascii_lowercase().as_bytes() {
+                b"end" => Placement::End,
+                b"start" => Placement::Start,
+                b"after" => Placement::After,
+                b"before" => Placement::Before,
+                _ => return Err(config::key::GenericErrorWithValue::from_value(self, value.into_owned())),
+            })
+        }
+    }
+}
+
+mod if_exists {
+    use std::borrow::Cow;
+
+    use crate::{bstr::BStr, config, config::tree::trailer::IfExists};
+
+    impl IfExists {
+        /// Convert `value` into what to do with trailers whose token already exists.
+        pub fn try_into_if_exists(
+            &'static self,
+            value: Cow<'_, BStr>,
+        ) -> Result<gix_object::commit::message::trailer::IfExists, config::key::GenericErrorWithValue> {
+            use gix_object::commit::message::trailer::IfExists;
+
+            use crate::bstr::ByteSlice;
+            Ok(match value.as_ref().to_ascii_lowercase().as_bytes() {
+                b"addifdifferentneighbor" => IfExists::AddIfDifferentNeighbor,
+                b"addifdifferent" => IfExists::AddIfDifferent,
+                b"add" => IfExists::Add,
+                b"replace" => IfExists::Replace,
+                b"donothing" => IfExists::DoNothing,
+                _ => return Err(config::key::GenericErrorWithValue::from_value(self, value.into_owned())),
+            })
+        }
+    }
+}
+
+mod if_missing {
+    use std::borrow::Cow;
+
+    use crate::{bstr::BStr, config, config::tree::trailer::IfMissing};
+
+    impl IfMissing {
+        /// Convert `value` into what to do with trailers whose token doesn't exist yet.
+        pub fn try_into_if_missing(
+            &'static self,
+            value: Cow<'_, BStr>,
+        ) -> Result<gix_object::commit::message::trailer::IfMissing, config::key::GenericErrorWithValue> {
+            use gix_object::commit::message::trailer::IfMissing;
+
+            use crate::bstr::ByteSlice;
+            Ok(match value.as_ref().to_ascii_lowercase().as_bytes() {
+                b"add" => IfMissing::Add,
+                b"donothing" => IfMissing::DoNothing,
+                _ => return Err(config::key::GenericErrorWithValue::from_value(self, value.into_owned())),
+            })
+        }
+    }
+}
+
+impl Section for Trailer {
+    fn name(&self) -> &str {
+        "trailer"
+    }
+
+    fn keys(&self) -> &[&dyn Key] {
+        &[&Self::SEPARATORS, &Self::WHERE, &Self::IF_EXISTS, &Self::IF_MISSING]
+    }
+}
+
+mod validate {
+    use crate::{bstr::BStr, config::tree::keys};
+
+    pub struct Where;
+    impl keys::Validate for Where {
+        fn validate(&self, value: &BStr) -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>> {
+            super::Trailer::WHERE.try_into_placement(value.into())?;
+            Ok(())
+        }
+    }
+
+    pub struct IfExists;
+    impl keys::Validate for IfExists {
+        fn validate(&self, value: &BStr) -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>> {
+            super::Trailer::IF_EXISTS.try_into_if_exists(value.into())?;
+            Ok(())
+        }
+    }
+
+    pub struct IfMissing;
+    impl keys::Validate for IfMissing {
+        fn validate(&self, value: &BStr) -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>> {
+            super::Trailer::IF_MISSING.try_into_if_missing(value.into())?;
+            Ok(())
+        }
+    }
+}
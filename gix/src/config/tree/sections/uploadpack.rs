@@ -0,0 +1,33 @@
+use crate::config::{
+    tree::{keys, Key, Section, Uploadpack},
+    Tree,
+};
+
+impl Uploadpack {
+    /// The `uploadpack.allowTipSHA1InWant` key.
+    pub const ALLOW_TIP_SHA1_IN_WANT: keys::Boolean =
+        keys::Boolean::new_boolean("allowTipSHA1InWant", &Tree::UPLOADPACK);
+    /// The `uploadpack.allowReachableSHA1InWant` key.
+    pub const ALLOW_REACHABLE_SHA1_IN_WANT: keys::Boolean =
+        keys::Boolean::new_boolean("allowReachableSHA1InWant", &Tree::UPLOADPACK);
+    /// The `uploadpack.allowAnySHA1InWant` key.
+    pub const ALLOW_ANY_SHA1_IN_WANT: keys::Boolean =
+        keys::Boolean::new_boolean("allowAnySHA1InWant", &Tree::UPLOADPACK);
+    /// The `uploadpack.allowFilter` key.
+    pub const ALLOW_FILTER: keys::Boolean = keys::Boolean::new_boolean("allowFilter", &Tree::UPLOADPACK);
+}
+
+impl Section for Uploadpack {
+    fn name(&self) -> &str {
+        "uploadpack"
+    }
+
+    fn keys(&self) -> &[&dyn Key] {
+        &[
+            &Self::ALLOW_TIP_SHA1_IN_WANT,
+            &Self::ALLOW_REACHABLE_SHA1_IN_WANT,
+            &Self::ALLOW_ANY_SHA1_IN_WANT,
+            &Self::ALLOW_FILTER,
+        ]
+    }
+}
@@ -0,0 +1,20 @@
+use crate::config::{
+    tree::{keys, Key, Section, VersionSort},
+    Tree,
+};
+
+impl VersionSort {
+    /// The `versionsort.suffix` key.
+    pub const SUFFIX: keys::String = keys::String::new_string("suffix", &Tree::VERSION_SORT)
+        .with_note("Can be specified multiple times, and the order of suffixes determines the order of pre-releases");
+}
+
+impl Section for VersionSort {
+    fn name(&self) -> &str {
+        "versionsort"
+    }
+
+    fn keys(&self) -> &[&dyn Key] {
+        &[&Self::SUFFIX]
+    }
+}
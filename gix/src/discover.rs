@@ -3,7 +3,7 @@ use std::path::Path;
 
 pub use gix_discover::*;
 
-use crate::{bstr::BString, ThreadSafeRepository};
+use crate::ThreadSafeRepository;
 
 /// The error returned by [`crate::discover()`].
 #[derive(Debug, thiserror::Error)]
@@ -60,6 +60,8 @@ impl ThreadSafeRepository {
     /// - `GIT_DISCOVERY_ACROSS_FILESYSTEM`
     /// - `GIT_CEILING_DIRECTORIES`
     ///
+    /// The locations of repository resources are overridden as described in [`EnvironmentOverrides`][crate::open::EnvironmentOverrides].
+    ///
     /// Finally, use the `trust_map` to determine which of our own repository options to use
     /// based on the trust level of the effective repository directory.
     ///
@@ -70,27 +72,42 @@ impl ThreadSafeRepository {
     /// compatibility with Git.
     pub fn discover_with_environment_overrides_opts(
         directory: impl AsRef<Path>,
-        mut options: upwards::Options<'_>,
+        options: upwards::Options<'_>,
         trust_map: gix_sec::trust::Mapping<crate::open::Options>,
     ) -> Result<Self, Error> {
-        fn apply_additional_environment(mut opts: upwards::Options<'_>) -> upwards::Options<'_> {
-            use crate::bstr::ByteVec;
+        Self::discover_with_custom_environment_overrides_opts(
+            directory,
+            crate::open::EnvironmentOverrides::from_env(),
+            options,
+            trust_map,
+        )
+    }
 
-            if let Some(cross_fs) = std::env::var_os("GIT_DISCOVERY_ACROSS_FILESYSTEM")
-                .and_then(|v| Vec::from_os_string(v).ok().map(BString::from))
-            {
-                if let Ok(b) = gix_config::Boolean::try_from(cross_fs.as_ref()) {
-                    opts.cross_fs = b.into();
-                }
-            }
-            opts
+    /// Like [`discover_with_environment_overrides_opts()`][Self::discover_with_environment_overrides_opts()], but use the
+    /// given `overrides` instead of reading them from the environment of the current process.
+    ///
+    /// This allows to emulate a process with a different environment, for instance to run many of them within the same process.
+    pub fn discover_with_custom_environment_overrides_opts(
+        directory: impl AsRef<Path>,
+        overrides: crate::open::EnvironmentOverrides,
+        mut options: upwards::Options<'_>,
+        trust_map: gix_sec::trust::Mapping<crate::open::Options>,
+    ) -> Result<Self, Error> {
+        if overrides.git_dir.is_some() {
+            return Self::open_with_custom_environment_overrides(directory.as_ref(), overrides, trust_map)
+                .map_err(Error::Open);
         }
 
-        if std::env::var_os("GIT_DIR").is_some() {
-            return Self::open_with_environment_overrides(directory.as_ref(), trust_map).map_err(Error::Open);
+        if !overrides.ceiling_dirs.is_empty() {
+            options.ceiling_dirs.clone_from(&overrides.ceiling_dirs);
         }
-
-        options = apply_additional_environment(options.apply_environment());
+        if let Some(cross_fs) = overrides.discovery_across_filesystem {
+            options.cross_fs = cross_fs;
+        }
+        let trust_map = gix_sec::trust::Mapping {
+            full: trust_map.full.environment_overrides(overrides.clone()),
+            reduced: trust_map.reduced.environment_overrides(overrides),
+        };
         Self::discover_opts(directory, options, trust_map)
     }
 }
@@ -7,6 +7,10 @@ pub type AncestorsIter<Find> = gix_traverse::commit::Simple<Find, fn(&gix_hash::
 /// An extension trait to add functionality to [`ObjectId`]s.
 pub trait ObjectIdExt: Sealed {
     /// Create an iterator over the ancestry of the commits reachable from this id, which must be a commit.
+    ///
+    /// Note that the parents are the ones recorded in each commit. Set a [parents provider](AncestorsIter::parents_provider())
+    /// obtained with [`Repository::grafts()`](crate::Repository::grafts()) to see grafts and the shallow boundary as well,
+    /// or use [`Id::ancestors()`](crate::Id::ancestors()) which does so automatically.
     fn ancestors<Find>(self, find: Find) -> AncestorsIter<Find>
     where
         Find: gix_object::Find;
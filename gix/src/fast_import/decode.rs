@@ -0,0 +1,345 @@
+use std::io::{BufRead, Read};
+
+use gix_hash::ObjectId;
+use gix_object::bstr::{BString, ByteSlice};
+
+use super::{Blob, Command, Commit, DataRef, FileChange, Mark, ObjectRef, Tag};
+
+/// The error returned when iterating a [`Parser`].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error("Line {line_number}: unknown command \"{line}\"")]
+    UnknownCommand { line_number: usize, line: BString },
+    #[error("Line {line_number}: expected {expected}, got \"{line}\"")]
+    Unexpected {
+        line_number: usize,
+        expected: &'static str,
+        line: BString,
+    },
+    #[error("The stream ended unexpectedly while expecting {expected}")]
+    UnexpectedEof { expected: &'static str },
+}
+
+/// An iterator over the [commands](Command) in a stream as understood by `git fast-import`.
+///
+/// Comments and `option` commands are skipped.
+pub struct Parser<R> {
+    read: R,
+    line: Vec<u8>,
+    /// If `true`, `line` was read but not consumed yet.
+    is_pending: bool,
+    line_number: usize,
+}
+
+impl<R> Parser<R>
+where
+    R: BufRead,
+{
+    /// Create a new instance to parse commands from `read`.
+    pub fn new(read: R) -> Self {
+        Parser {
+            read,
+            line: Vec::new(),
+            is_pending: false,
+            line_number: 0,
+        }
+    }
+}
+
+impl<R> Iterator for Parser<R>
+where
+    R: BufRead,
+{
+    type Item = Result<Command, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            match self.next_line() {
+                Ok(true) => {}
+                Ok(false) => return None,
+                Err(err) => return Some(Err(err.into())),
+            }
+            if self.line.is_empty() || self.line.starts_with(b"option ") {
+                continue;
+            }
+            return Some(self.parse_command());
+        }
+    }
+}
+
+impl<R> Parser<R>
+where
+    R: BufRead,
+{
+    fn parse_command(&mut self) -> Result<Command, Error> {
+        let line = std::mem::take(&mut self.line);
+        let res = if line == b"blob" {
+            Command::Blob(Blob {
+                mark: self.mark()?,
+                original_oid: self.optional(b"original-oid ")?,
+                data: self.data()?,
+            })
+        } else if let Some(name) = line.strip_prefix(b"commit ") {
+            self.commit(name.into())?
+        } else if let Some(name) = line.strip_prefix(b"tag ") {
+            let mark = self.mark()?;
+            let original_oid = self.optional(b"original-oid ")?;
+            let from = object_ref(&self.required(b"from ", "'from' in tag")?);
+            let tagger = self
+                .optional(b"tagger ")?
+                .map(|sig| self.signature(&sig, "tagger signature"))
+                .transpose()?;
+            Command::Tag(Tag {
+                name: name.into(),
+                mark,
+                original_oid,
+                from,
+                tagger,
+                message: self.data()?,
+            })
+        } else if let Some(name) = line.strip_prefix(b"reset ") {
+            Command::Reset {
+                name: name.into(),
+                from: self.optional(b"from ")?.map(|from| object_ref(&from)),
+            }
+        } else if let Some(message) = line.strip_prefix(b"progress ") {
+            Command::Progress(message.into())
+        } else if let Some(feature) = line.strip_prefix(b"feature ") {
+            Command::Feature(feature.into())
+        } else if line == b"checkpoint" {
+            Command::Checkpoint
+        } else if line == b"done" {
+            Command::Done
+        } else {
+            return Err(Error::UnknownCommand {
+                line_number: self.line_number,
+                line: line.into(),
+            });
+        };
+        Ok(res)
+    }
+
+    fn commit(&mut self, name: BString) -> Result<Command, Error> {
+        let mark = self.mark()?;
+        let original_oid = self.optional(b"original-oid ")?;
+        let author = self
+            .optional(b"author ")?
+            .map(|sig| self.signature(&sig, "author signature"))
+            .transpose()?;
+        let committer = self.required(b"committer ", "'committer' in commit")?;
+        let committer = self.signature(&committer, "committer signature")?;
+        let encoding = self.optional(b"encoding ")?;
+        let message = self.data()?;
+        let from = self.optional(b"from ")?.map(|from| object_ref(&from));
+        let mut merges = Vec::new();
+        while let Some(merge) = self.optional(b"merge ")? {
+            merges.push(object_ref(&merge));
+        }
+
+        let mut changes = Vec::new();
+        while self.next_line()? {
+            let change = if let Some(rest) = self.line.strip_prefix(b"M ") {
+                // Inline data needs to read more lines, so we can't borrow the current one.
+                let rest = rest.to_owned();
+                let mut tokens = rest.splitn(3, |b| *b == b' ');
+                let (Some(mode), Some(data), Some(path)) = (tokens.next(), tokens.next(), tokens.next()) else {
+                    return Err(self.unexpected("'M <mode> <dataref> <path>'"));
+                };
+                let mode = parse_mode(mode).ok_or_else(|| self.unexpected("a valid file mode"))?;
+                let path = unquote(path).ok_or_else(|| self.unexpected("a valid path"))?;
+                let data = match data {
+                    b"inline" => DataRef::Inline(self.data()?),
+                    _ => match object_ref(data) {
+                        ObjectRef::Mark(mark) => DataRef::Mark(mark),
+                        ObjectRef::Id(id) => DataRef::Id(id),
+                        ObjectRef::Name(_) => return Err(self.unexpected("a mark or object id")),
+                    },
+                };
+                FileChange::Modify { mode, data, path }
+            } else if let Some(path) = self.line.strip_prefix(b"D ") {
+                FileChange::Delete {
+                    path: unquote(path).ok_or_else(|| self.unexpected("a valid path"))?,
+                }
+            } else if let Some(paths) = self.line.strip_prefix(b"C ") {
+                let (source, destination) = split_paths(paths).ok_or_else(|| self.unexpected("'C <source> <dest>'"))?;
+                FileChange::Copy { source, destination }
+            } else if let Some(paths) = self.line.strip_prefix(b"R ") {
+                let (source, destination) = split_paths(paths).ok_or_else(|| self.unexpected("'R <source> <dest>'"))?;
+                FileChange::Rename { source, destination }
+            } else if self.line == b"deleteall" {
+                FileChange::DeleteAll
+            } else {
+                self.is_pending = true;
+                break;
+            };
+            changes.push(change);
+        }
+
+        Ok(Command::Commit(Commit {
+            name,
+            mark,
+            original_oid,
+            author,
+            committer,
+            encoding,
+            message,
+            from,
+            merges,
+            changes,
+        }))
+    }
+
+    /// Read the next line that isn't a comment, without its trailing newline, and return `false` if there is none.
+    fn next_line(&mut self) -> std::io::Result<bool> {
+        if self.is_pending {
+            self.is_pending = false;
+            return Ok(true);
+        }
+        loop {
+            self.line.clear();
+            if self.read.read_until(b'\n', &mut self.line)? == 0 {
+                return Ok(false);
+            }
+            self.line_number += 1;
+            if self.line.last() == Some(&b'\n') {
+                self.line.pop();
+            }
+            if !self.line.starts_with(b"#") {
+                return Ok(true);
+            }
+        }
+    }
+
+    /// Return the remainder of the next line if it starts with `prefix`, or leave it for the next call.
+    fn optional(&mut self, prefix: &[u8]) -> std::io::Result<Option<BString>> {
+        if !self.next_line()? {
+            return Ok(None);
+        }
+        Ok(match self.line.strip_prefix(prefix) {
+            Some(rest) => Some(rest.into()),
+            None => {
+                self.is_pending = true;
+                None
+            }
+        })
+    }
+
+    fn required(&mut self, prefix: &[u8], expected: &'static str) -> Result<BString, Error> {
+        if !self.next_line()? {
+            return Err(Error::UnexpectedEof { expected });
+        }
+        self.line
+            .strip_prefix(prefix)
+            .map(Into::into)
+            .ok_or_else(|| self.unexpected(expected))
+    }
+
+    fn mark(&mut self) -> Result<Option<Mark>, Error> {
+        self.optional(b"mark ")?
+            .map(|mark| parse_mark(&mark).ok_or_else(|| self.unexpected("'mark :<idnum>'")))
+            .transpose()
+    }
+
+    fn signature(&self, input: &[u8], expected: &'static str) -> Result<gix_actor::Signature, Error> {
+        gix_actor::SignatureRef::from_bytes::<()>(input)
+            .map(Into::into)
+            .map_err(|_| self.unexpected(expected))
+    }
+
+    /// Read the `data` command in the next line along with the data it introduces.
+    fn data(&mut self) -> Result<BString, Error> {
+        let header = self.required(b"data ", "'data'")?;
+        let mut data = Vec::new();
+        if let Some(delimiter) = header.strip_prefix(b"<<") {
+            let delimiter = delimiter.to_owned();
+            loop {
+                if !self.next_line()? {
+                    return Err(Error::UnexpectedEof {
+                        expected: "the delimiter of 'data'",
+                    });
+                }
+                if self.line == delimiter {
+                    break;
+                }
+                data.extend_from_slice(&self.line);
+                data.push(b'\n');
+            }
+        } else {
+            let len: usize = header
+                .to_str()
+                .ok()
+                .and_then(|len| len.parse().ok())
+                .ok_or_else(|| self.unexpected("'data <count>'"))?;
+            // The length is untrusted, so let the buffer grow with the data that is actually there.
+            (&mut self.read).take(len as u64).read_to_end(&mut data)?;
+            if data.len() != len {
+                return Err(Error::UnexpectedEof {
+                    expected: "the announced amount of bytes of 'data'",
+                });
+            }
+            self.line_number += data.iter().filter(|b| **b == b'\n').count();
+            // The data may be followed by an optional newline.
+            if self.read.fill_buf()?.first() == Some(&b'\n') {
+                self.read.consume(1);
+                self.line_number += 1;
+            }
+        }
+        Ok(data.into())
+    }
+
+    fn unexpected(&self, expected: &'static str) -> Error {
+        Error::Unexpected {
+            line_number: self.line_number,
+            expected,
+            line: self.line.as_bstr().to_owned(),
+        }
+    }
+}
+
+fn parse_mark(input: &[u8]) -> Option<Mark> {
+    input.strip_prefix(b":")?.to_str().ok()?.parse().ok()
+}
+
+fn object_ref(input: &[u8]) -> ObjectRef {
+    if let Some(mark) = parse_mark(input) {
+        return ObjectRef::Mark(mark);
+    }
+    match ObjectId::from_hex(input) {
+        Ok(id) => ObjectRef::Id(id),
+        Err(_) => ObjectRef::Name(input.into()),
+    }
+}
+
+fn parse_mode(input: &[u8]) -> Option<gix_object::tree::EntryKind> {
+    use gix_object::tree::EntryKind;
+    Some(match input {
+        b"100644" | b"644" => EntryKind::Blob,
+        b"100755" | b"755" => EntryKind::BlobExecutable,
+        b"120000" => EntryKind::Link,
+        b"160000" => EntryKind::Commit,
+        b"040000" | b"40000" => EntryKind::Tree,
+        _ => return None,
+    })
+}
+
+/// Split `input` into a source and destination path, where the source must be quoted if it contains spaces.
+fn split_paths(input: &[u8]) -> Option<(BString, BString)> {
+    let source_end = if input.starts_with(b"\"") {
+        gix_quote::ansi_c::undo(input.as_bstr()).ok()?.1
+    } else {
+        input.find_byte(b' ')?
+    };
+    let (source, destination) = input.split_at(source_end);
+    Some((unquote(source)?, unquote(destination.strip_prefix(b" ")?)?))
+}
+
+/// Remove C-style quotes from `input` if present, or return it unchanged. Return `None` if the quoting is invalid.
+fn unquote(input: &[u8]) -> Option<BString> {
+    match gix_quote::ansi_c::undo(input.as_bstr()).ok()? {
+        (unquoted, consumed) if consumed == input.len() => Some(unquoted.into_owned()),
+        _ => None,
+    }
+}
@@ -0,0 +1,179 @@
+use std::io::Write;
+
+use gix_object::bstr::ByteSlice;
+
+use super::{Command, DataRef, FileChange, ObjectRef};
+
+impl Command {
+    /// Serialize this command to `out` in a format understood by `git fast-import`.
+    pub fn write_to(&self, out: &mut dyn Write) -> std::io::Result<()> {
+        match self {
+            Command::Blob(blob) => {
+                out.write_all(b"blob\n")?;
+                write_mark(blob.mark, out)?;
+                write_original_oid(blob.original_oid.as_ref().map(AsRef::as_ref), out)?;
+                write_data(&blob.data, out)?;
+            }
+            Command::Commit(commit) => {
+                out.write_all(b"commit ")?;
+                out.write_all(&commit.name)?;
+                out.write_all(b"\n")?;
+                write_mark(commit.mark, out)?;
+                write_original_oid(commit.original_oid.as_ref().map(AsRef::as_ref), out)?;
+                if let Some(author) = &commit.author {
+                    out.write_all(b"author ")?;
+                    author.write_to(out)?;
+                    out.write_all(b"\n")?;
+                }
+                out.write_all(b"committer ")?;
+                commit.committer.write_to(out)?;
+                out.write_all(b"\n")?;
+                if let Some(encoding) = &commit.encoding {
+                    out.write_all(b"encoding ")?;
+                    out.write_all(encoding)?;
+                    out.write_all(b"\n")?;
+                }
+                write_data(&commit.message, out)?;
+                if let Some(from) = &commit.from {
+                    write_object_ref(b"from ", from, out)?;
+                }
+                for merge in &commit.merges {
+                    write_object_ref(b"merge ", merge, out)?;
+                }
+                for change in &commit.changes {
+                    change.write_to(out)?;
+                }
+            }
+            Command::Tag(tag) => {
+                out.write_all(b"tag ")?;
+                out.write_all(&tag.name)?;
+                out.write_all(b"\n")?;
+                write_mark(tag.mark, out)?;
+                write_original_oid(tag.original_oid.as_ref().map(AsRef::as_ref), out)?;
+                write_object_ref(b"from ", &tag.from, out)?;
+                if let Some(tagger) = &tag.tagger {
+                    out.write_all(b"tagger ")?;
+                    tagger.write_to(out)?;
+                    out.write_all(b"\n")?;
+                }
+                write_data(&tag.message, out)?;
+            }
+            Command::Reset { name, from } => {
+                out.write_all(b"reset ")?;
+                out.write_all(name)?;
+                out.write_all(b"\n")?;
+                if let Some(from) = from {
+                    write_object_ref(b"from ", from, out)?;
+                }
+            }
+            Command::Progress(message) => {
+                out.write_all(b"progress ")?;
+                out.write_all(message)?;
+                out.write_all(b"\n")?;
+            }
+            Command::Checkpoint => out.write_all(b"checkpoint\n")?,
+            Command::Feature(feature) => {
+                out.write_all(b"feature ")?;
+                out.write_all(feature)?;
+                out.write_all(b"\n")?;
+            }
+            Command::Done => out.write_all(b"done\n")?,
+        }
+        // Terminate each command with an empty line for readability, which is optional.
+        out.write_all(b"\n")
+    }
+}
+
+impl FileChange {
+    /// Serialize this file change to `out` in a format understood by `git fast-import`.
+    pub fn write_to(&self, out: &mut dyn Write) -> std::io::Result<()> {
+        match self {
+            FileChange::Modify { mode, data, path } => {
+                out.write_all(b"M ")?;
+                out.write_all(mode.as_octal_str())?;
+                out.write_all(b" ")?;
+                match data {
+                    DataRef::Mark(mark) => write!(out, ":{mark}")?,
+                    DataRef::Id(id) => write!(out, "{id}")?,
+                    DataRef::Inline(_) => out.write_all(b"inline")?,
+                }
+                out.write_all(b" ")?;
+                write_path(path, false, out)?;
+                out.write_all(b"\n")?;
+                if let DataRef::Inline(data) = data {
+                    write_data(data, out)?;
+                }
+            }
+            FileChange::Delete { path } => {
+                out.write_all(b"D ")?;
+                write_path(path, false, out)?;
+                out.write_all(b"\n")?;
+            }
+            FileChange::Copy { source, destination } | FileChange::Rename { source, destination } => {
+                out.write_all(if matches!(self, FileChange::Copy { .. }) {
+                    b"C "
+                } else {
+                    b"R "
+                })?;
+                write_path(source, true, out)?;
+                out.write_all(b" ")?;
+                write_path(destination, false, out)?;
+                out.write_all(b"\n")?;
+            }
+            FileChange::DeleteAll => out.write_all(b"deleteall\n")?,
+        }
+        Ok(())
+    }
+}
+
+fn write_mark(mark: Option<super::Mark>, out: &mut dyn Write) -> std::io::Result<()> {
+    match mark {
+        Some(mark) => writeln!(out, "mark :{mark}"),
+        None => Ok(()),
+    }
+}
+
+fn write_original_oid(id: Option<&gix_object::bstr::BStr>, out: &mut dyn Write) -> std::io::Result<()> {
+    if let Some(id) = id {
+        out.write_all(b"original-oid ")?;
+        out.write_all(id)?;
+        out.write_all(b"\n")?;
+    }
+    Ok(())
+}
+
+fn write_object_ref(prefix: &[u8], object: &ObjectRef, out: &mut dyn Write) -> std::io::Result<()> {
+    out.write_all(prefix)?;
+    match object {
+        ObjectRef::Mark(mark) => write!(out, ":{mark}")?,
+        ObjectRef::Id(id) => write!(out, "{id}")?,
+        ObjectRef::Name(name) => out.write_all(name)?,
+    }
+    out.write_all(b"\n")
+}
+
+fn write_data(data: &[u8], out: &mut dyn Write) -> std::io::Result<()> {
+    writeln!(out, "data {}", data.len())?;
+    out.write_all(data)?;
+    out.write_all(b"\n")
+}
+
+/// Write `path`, quoting it C-style if needed, or if it contains spaces and `quote_spaces` is `true`.
+fn write_path(path: &[u8], quote_spaces: bool, out: &mut dyn Write) -> std::io::Result<()> {
+    let needs_quotes = path.starts_with(b"\"")
+        || path.find_byteset(b"\n\\").is_some()
+        || (quote_spaces && path.find_byte(b' ').is_some());
+    if !needs_quotes {
+        return out.write_all(path);
+    }
+    out.write_all(b"\"")?;
+    for b in path {
+        match b {
+            b'"' => out.write_all(b"\\\"")?,
+            b'\\' => out.write_all(b"\\\\")?,
+            b'\n' => out.write_all(b"\\n")?,
+            b => out.write_all(&[*b])?,
+        }
+    }
+    out.write_all(b"\"")
+}
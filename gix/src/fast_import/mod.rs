@@ -0,0 +1,181 @@
+use gix_hash::ObjectId;
+use gix_object::bstr::BString;
+
+///
+pub mod decode;
+pub use decode::Parser;
+///
+pub mod encode;
+
+///
+pub mod import {
+    use gix_hash::ObjectId;
+
+    /// The outcome of [`Repository::fast_import()`](crate::Repository::fast_import()).
+    #[derive(Debug, Clone, Default)]
+    pub struct Outcome {
+        /// A mapping of all marks in the stream to the objects they refer to.
+        pub marks: std::collections::BTreeMap<super::Mark, ObjectId>,
+        /// All references that were created or updated, along with the object they point to now.
+        pub refs: Vec<(gix_ref::FullName, ObjectId)>,
+        /// The amount of blobs that were written.
+        pub blobs: usize,
+        /// The amount of commits that were written.
+        pub commits: usize,
+        /// The amount of tags that were written.
+        pub tags: usize,
+    }
+}
+
+///
+pub mod export {
+    /// The outcome of [`Repository::fast_export()`](crate::Repository::fast_export()).
+    #[derive(Debug, Clone, Copy, Default)]
+    pub struct Outcome {
+        /// The amount of blobs that were written.
+        pub blobs: usize,
+        /// The amount of commits that were written.
+        pub commits: usize,
+        /// The amount of tags that were written.
+        pub tags: usize,
+    }
+}
+
+/// A mark as used in `:<idnum>` to refer to objects created earlier in the same stream.
+pub type Mark = u32;
+
+/// A way to refer to a commit or an object in a stream.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ObjectRef {
+    /// An object previously marked in the stream, like `:1`.
+    Mark(Mark),
+    /// An object that is expected to exist in the object database already.
+    Id(ObjectId),
+    /// The name of a branch, which is either known in the stream or resolved in the repository.
+    Name(BString),
+}
+
+/// The source of the data of a file that is added or modified as part of a [`Commit`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DataRef {
+    /// A blob previously marked in the stream, like `:1`.
+    Mark(Mark),
+    /// A blob or commit (for submodules) that is expected to exist in the object database already.
+    Id(ObjectId),
+    /// The data of the blob, which directly follows the file change as `data` command.
+    Inline(BString),
+}
+
+/// A change to the tree of a [`Commit`], relative to the tree of its first parent.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FileChange {
+    /// Add or modify the file at `path` with `data`, using `mode`.
+    Modify {
+        /// The kind of the entry.
+        mode: gix_object::tree::EntryKind,
+        /// The content of the entry.
+        data: DataRef,
+        /// The slash-separated path of the entry relative to the root of the tree.
+        path: BString,
+    },
+    /// Remove the file or directory at `path`.
+    Delete {
+        /// The slash-separated path of the entry relative to the root of the tree.
+        path: BString,
+    },
+    /// Copy the file or directory at `source` to `destination`.
+    Copy {
+        /// The path to copy from.
+        source: BString,
+        /// The path to copy to, which is replaced if it exists.
+        destination: BString,
+    },
+    /// Move the file or directory at `source` to `destination`.
+    Rename {
+        /// The path to move from.
+        source: BString,
+        /// The path to move to, which is replaced if it exists.
+        destination: BString,
+    },
+    /// Remove all entries, which is used to start a tree from scratch.
+    DeleteAll,
+}
+
+/// A `blob` command to create a blob.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Blob {
+    /// The mark to refer to the blob by later.
+    pub mark: Option<Mark>,
+    /// The hexadecimal object id the blob had in the original repository, if known.
+    pub original_oid: Option<BString>,
+    /// The content of the blob.
+    pub data: BString,
+}
+
+/// A `commit` command to create a commit and to update a branch to point to it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Commit {
+    /// The full name of the reference to update, like `refs/heads/main`.
+    pub name: BString,
+    /// The mark to refer to the commit by later.
+    pub mark: Option<Mark>,
+    /// The hexadecimal object id the commit had in the original repository, if known.
+    pub original_oid: Option<BString>,
+    /// The author of the commit, or `None` to use the committer.
+    pub author: Option<gix_actor::Signature>,
+    /// The committer of the commit.
+    pub committer: gix_actor::Signature,
+    /// The encoding of the message, if it's not UTF-8.
+    pub encoding: Option<BString>,
+    /// The commit message.
+    pub message: BString,
+    /// The first parent of the commit, or `None` to use the current commit of the branch if there is one.
+    pub from: Option<ObjectRef>,
+    /// All additional parents.
+    pub merges: Vec<ObjectRef>,
+    /// The changes to apply to the tree of the first parent, in order.
+    pub changes: Vec<FileChange>,
+}
+
+/// A `tag` command to create an annotated tag object, along with a reference to it in `refs/tags/`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Tag {
+    /// The name of the tag, without the `refs/tags/` prefix.
+    pub name: BString,
+    /// The mark to refer to the tag by later.
+    pub mark: Option<Mark>,
+    /// The hexadecimal object id the tag had in the original repository, if known.
+    pub original_oid: Option<BString>,
+    /// The object the tag points to.
+    pub from: ObjectRef,
+    /// The creator of the tag, if known.
+    pub tagger: Option<gix_actor::Signature>,
+    /// The tag message.
+    pub message: BString,
+}
+
+/// A single command in a stream as understood by `git fast-import`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Command {
+    /// Create a blob.
+    Blob(Blob),
+    /// Create a commit and update a branch.
+    Commit(Commit),
+    /// Create an annotated tag.
+    Tag(Tag),
+    /// Reset the branch `name` to `from`, or start it from scratch if `None`.
+    Reset {
+        /// The full name of the reference to reset.
+        name: BString,
+        /// The commit to point to.
+        from: Option<ObjectRef>,
+    },
+    /// Write the given progress message.
+    Progress(BString),
+    /// Persist all data written so far.
+    Checkpoint,
+    /// Require the given feature, like `done` or `force`.
+    Feature(BString),
+    /// Signal the end of the stream, which is required if the `done` feature was requested.
+    Done,
+}
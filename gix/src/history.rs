@@ -0,0 +1,106 @@
+///
+pub mod rewrite {
+    use gix_hash::{oid, ObjectId};
+    use gix_object::bstr::{BStr, BString};
+
+    /// The error type returned by [`Filter`] implementations.
+    pub type Error = Box<dyn std::error::Error + Send + Sync + 'static>;
+
+    /// A way to change the contents of commits while [rewriting history](crate::Repository::rewrite_history()).
+    ///
+    /// All methods leave their input unchanged by default, so only the interesting ones need to be implemented.
+    pub trait Filter: Sync {
+        /// Return the id of the tree to use instead of `tree`, which is typically written by using `repo`
+        /// to remove large files or extract a sub-directory.
+        ///
+        /// It's called only once for each unique tree, in parallel from multiple threads, each with its own `repo` instance.
+        fn tree(&self, repo: &crate::Repository, tree: ObjectId) -> Result<ObjectId, Error> {
+            _ = repo;
+            Ok(tree)
+        }
+
+        /// Return the message to use instead of `message` of the commit with id `commit`, or `None` to keep it.
+        fn message(&self, commit: &oid, message: &BStr) -> Result<Option<BString>, Error> {
+            _ = (commit, message);
+            Ok(None)
+        }
+
+        /// Return the signature to use instead of `author` of the commit with id `commit`, or `None` to keep it.
+        fn author(
+            &self,
+            commit: &oid,
+            author: gix_actor::SignatureRef<'_>,
+        ) -> Result<Option<gix_actor::Signature>, Error> {
+            _ = (commit, author);
+            Ok(None)
+        }
+
+        /// Return the signature to use instead of `committer` of the commit with id `commit`, or `None` to keep it.
+        fn committer(
+            &self,
+            commit: &oid,
+            committer: gix_actor::SignatureRef<'_>,
+        ) -> Result<Option<gix_actor::Signature>, Error> {
+            _ = (commit, committer);
+            Ok(None)
+        }
+    }
+
+    /// The no-op filter which keeps all commits as they are.
+    impl Filter for () {}
+
+    /// Options for use in [`Repository::rewrite_history()`](crate::Repository::rewrite_history()).
+    #[derive(Debug, Clone)]
+    pub struct Options {
+        /// Commits which, along with all of their ancestors, are not rewritten, similar to `^<rev>` on the command-line.
+        pub hidden: Vec<ObjectId>,
+        /// If `true`, commits with at most one parent are dropped if their rewritten tree is the same as the one of their
+        /// rewritten parent, or the empty tree if they have no parent.
+        pub prune_empty: bool,
+        /// If `true`, update all references to point to their rewritten commit. Otherwise, only objects are written
+        /// and the caller can use the returned [map](Outcome::map) to update references on its own.
+        pub update_refs: bool,
+        /// The amount of threads to use for rewriting trees, or `None` to use as many as there are logical cores.
+        pub thread_limit: Option<usize>,
+    }
+
+    impl Default for Options {
+        fn default() -> Self {
+            Options {
+                hidden: Vec::new(),
+                prune_empty: false,
+                update_refs: true,
+                thread_limit: None,
+            }
+        }
+    }
+
+    /// A reference that was changed to point to the rewritten version of the commit it was pointing to.
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct RefUpdate {
+        /// The full name of the reference, which is never symbolic.
+        pub name: gix_ref::FullName,
+        /// The commit the reference pointed to before the rewrite.
+        pub previous_id: ObjectId,
+        /// The commit the reference points to now.
+        pub new_id: ObjectId,
+    }
+
+    /// The outcome of [`Repository::rewrite_history()`](crate::Repository::rewrite_history()).
+    #[derive(Debug, Clone, Default)]
+    pub struct Outcome {
+        /// A mapping from the ids of all traversed commits to their rewritten counterpart, which may be the same id
+        /// if nothing changed.
+        ///
+        /// If the commit was pruned, the id of the commit that replaces it is used instead, which is `None` if there is none.
+        pub map: gix_hashtable::HashMap<ObjectId, Option<ObjectId>>,
+        /// All references that were changed, in the order in which they were passed.
+        ///
+        /// It's empty if [`Options::update_refs`] was `false`.
+        pub refs: Vec<RefUpdate>,
+        /// The amount of commits that were written as they changed.
+        pub commits_rewritten: usize,
+        /// The amount of commits that were dropped as they became empty.
+        pub commits_pruned: usize,
+    }
+}
@@ -42,11 +42,15 @@ impl<'repo> Id<'repo> {
 
     /// Turn this object id into a shortened id with a length in hex as configured by `core.abbrev`.
     pub fn shorten(&self) -> Result<gix_hash::Prefix, shorten::Error> {
-        let hex_len = self.repo.config.hex_len.map_or_else(
-            || self.repo.objects.packed_object_count().map(calculate_auto_hex_len),
-            Ok,
-        )?;
+        self.shorten_to(configured_hex_len(self.repo)?)
+    }
 
+    /// Turn this object id into a shortened id that is at least `hex_len` characters long, or longer if that's needed
+    /// to make it unambiguous, similar to `git rev-parse --short=<hex_len>`.
+    ///
+    /// `hex_len` is clamped to the range of valid prefix lengths for the kind of hash.
+    pub fn shorten_to(&self, hex_len: usize) -> Result<gix_hash::Prefix, shorten::Error> {
+        let hex_len = hex_len.clamp(gix_hash::Prefix::MIN_HEX_LEN, self.inner.kind().len_in_hex());
         let prefix = gix_odb::store::prefix::disambiguate::Candidate::new(self.inner, hex_len)
             .expect("BUG: internal hex-len must always be valid");
         self.repo
@@ -62,6 +66,13 @@ impl<'repo> Id<'repo> {
     }
 }
 
+/// Return the length of shortened ids as configured by `core.abbrev`, or as computed from the amount of objects if it's `auto`.
+pub(crate) fn configured_hex_len(repo: &crate::Repository) -> Result<usize, gix_odb::store::load_index::Error> {
+    repo.config
+        .hex_len
+        .map_or_else(|| repo.objects.packed_object_count().map(calculate_auto_hex_len), Ok)
+}
+
 fn calculate_auto_hex_len(num_packed_objects: u64) -> usize {
     let mut len = 64 - num_packed_objects.leading_zeros();
     len = (len + 1) / 2;
@@ -70,7 +81,7 @@ fn calculate_auto_hex_len(num_packed_objects: u64) -> usize {
 
 ///
 pub mod shorten {
-    /// Returned by [`Id::prefix()`][super::Id::shorten()].
+    /// Returned by [`Id::shorten()`][super::Id::shorten()] and [`Repository::shorten_ids()`][crate::Repository::shorten_ids()].
     #[derive(Debug, thiserror::Error)]
     #[allow(missing_docs)]
     pub enum Error {
@@ -0,0 +1,272 @@
+//! Snapshot `HEAD`, the index and worktree files before compound operations to be able to roll them back.
+use std::path::{Path, PathBuf};
+
+use gix_hash::ObjectId;
+use gix_ref::transaction::{Change, LogChange, PreviousValue, RefEdit, RefLog};
+use gix_ref::{FullName, Target};
+
+use crate::bstr::{BStr, BString, ByteSlice};
+use crate::Repository;
+
+/// A guard holding the state of `HEAD`, the index and a set of worktree files as they were when it was
+/// [created](Repository::journal()), to allow restoring them if a compound operation like a merge, rebase
+/// or pull fails or is aborted.
+///
+/// Call [`commit()`](Self::commit()) to keep all changes once the operation succeeded, or [`rollback()`](Self::rollback())
+/// to restore the recorded state.
+/// If dropped without either, the recorded state is restored as well, but errors are only logged.
+///
+/// Worktree files must be [tracked](Self::track()) *before* they are changed, as only their state at the time
+/// they are tracked can be restored.
+///
+/// ### Limitations
+///
+/// * The journal is kept in memory only, so it can't help if the process is terminated.
+/// * Only files and symlinks are recorded, directories that are tracked or that were created in the meantime are left as is.
+#[must_use = "the journal rolls back all changes when dropped, call `commit()` to keep them"]
+pub struct Journal<'repo> {
+    pub(crate) repo: &'repo Repository,
+    pub(crate) state: Option<State>,
+}
+
+pub(crate) struct State {
+    pub(crate) head: Target,
+    /// The branch `HEAD` pointed to along with the object it pointed to, which is `None` if it was unborn.
+    pub(crate) referent: Option<(FullName, Option<ObjectId>)>,
+    /// The content of the index file, or `None` if it didn't exist.
+    pub(crate) index: Option<Vec<u8>>,
+    /// Worktree-relative paths along with their recorded state.
+    pub(crate) files: Vec<(BString, FileState)>,
+}
+
+pub(crate) enum FileState {
+    Missing,
+    File {
+        content: Vec<u8>,
+        permissions: std::fs::Permissions,
+    },
+    Symlink {
+        target: PathBuf,
+    },
+}
+
+/// The error returned by [`Journal::track()`].
+pub mod track {
+    use crate::bstr::BString;
+
+    /// The error returned by [`Journal::track()`](super::Journal::track()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("Worktree files can't be tracked in a bare repository")]
+        MissingWorkTree,
+        #[error("The path '{rela_path}' is a directory, only files and symlinks can be tracked")]
+        Directory { rela_path: BString },
+        #[error("Could not read the state of '{rela_path}'")]
+        Io { rela_path: BString, source: std::io::Error },
+    }
+}
+
+/// The error returned by [`Journal::rollback()`].
+pub mod rollback {
+    use std::path::PathBuf;
+
+    /// The error returned by [`Journal::rollback()`](super::Journal::rollback()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        FindReference(#[from] crate::reference::find::Error),
+        #[error(transparent)]
+        EditReferences(#[from] crate::reference::edit::Error),
+        #[error("Could not restore '{path}'")]
+        Io { path: PathBuf, source: std::io::Error },
+        #[error("Could not acquire a lock to restore the index")]
+        AcquireIndexLock(#[from] gix_lock::acquire::Error),
+        #[error("Could not commit the lock to restore the index")]
+        CommitIndexLock(#[from] gix_lock::commit::Error<gix_lock::File>),
+    }
+}
+
+impl Journal<'_> {
+    /// Record the current state of the worktree file at `rela_path` so it can be restored later.
+    /// Paths that are already tracked are ignored so their initial state is retained.
+    pub fn track(&mut self, rela_path: impl Into<BString>) -> Result<&mut Self, track::Error> {
+        let rela_path = rela_path.into();
+        let state = self.state.as_mut().expect("present until consumed");
+        if state.files.iter().any(|(path, _)| *path == rela_path) {
+            return Ok(self);
+        }
+        let work_dir = self.repo.work_dir().ok_or(track::Error::MissingWorkTree)?;
+        let path = work_dir.join(gix_path::from_bstr(rela_path.as_bstr()));
+        let io_err = |err| track::Error::Io {
+            rela_path: rela_path.clone(),
+            source: err,
+        };
+        let file_state = match std::fs::symlink_metadata(&path) {
+            Ok(meta) if meta.is_dir() => {
+                return Err(track::Error::Directory {
+                    rela_path: rela_path.clone(),
+                })
+            }
+            Ok(meta) if meta.is_symlink() => FileState::Symlink {
+                target: std::fs::read_link(&path).map_err(io_err)?,
+            },
+            Ok(meta) => FileState::File {
+                content: std::fs::read(&path).map_err(io_err)?,
+                permissions: meta.permissions(),
+            },
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => FileState::Missing,
+            Err(err) => return Err(io_err(err)),
+        };
+        state.files.push((rela_path, file_state));
+        Ok(self)
+    }
+
+    /// Return the worktree-relative paths of all tracked files.
+    pub fn tracked_paths(&self) -> impl Iterator<Item = &BStr> + '_ {
+        self.state
+            .iter()
+            .flat_map(|state| state.files.iter().map(|(path, _)| path.as_bstr()))
+    }
+
+    /// Keep all changes that were made since this instance was created, and forget the recorded state.
+    pub fn commit(mut self) {
+        self.state.take();
+    }
+
+    /// Restore `HEAD` along with the branch it pointed to, the index and all tracked worktree files to the state they
+    /// had when they were recorded.
+    ///
+    /// References are restored first, then the index and finally the worktree files, stopping at the first error.
+    pub fn rollback(mut self) -> Result<(), rollback::Error> {
+        let state = self.state.take().expect("present until consumed");
+        restore(self.repo, state)
+    }
+}
+
+impl Drop for Journal<'_> {
+    fn drop(&mut self) {
+        if let Some(state) = self.state.take() {
+            if let Err(_err) = restore(self.repo, state) {
+                gix_trace::error!("Failed to roll back journal on drop: {_err}");
+            }
+        }
+    }
+}
+
+fn restore(repo: &Repository, state: State) -> Result<(), rollback::Error> {
+    let log = LogChange {
+        mode: RefLog::AndReference,
+        force_create_reflog: false,
+        message: "rollback".into(),
+    };
+    let mut edits = Vec::with_capacity(2);
+    if let Some((name, id)) = state.referent {
+        match id {
+            Some(id) => edits.push(RefEdit {
+                change: Change::Update {
+                    log: log.clone(),
+                    expected: PreviousValue::Any,
+                    new: Target::Object(id),
+                },
+                name,
+                deref: false,
+            }),
+            None => {
+                if repo.try_find_reference(&name)?.is_some() {
+                    edits.push(RefEdit {
+                        change: Change::Delete {
+                            expected: PreviousValue::Any,
+                            log: RefLog::AndReference,
+                        },
+                        name,
+                        deref: false,
+                    });
+                }
+            }
+        }
+    }
+    edits.push(RefEdit {
+        change: Change::Update {
+            log,
+            expected: PreviousValue::Any,
+            new: state.head,
+        },
+        name: "HEAD".try_into().expect("valid"),
+        deref: false,
+    });
+    repo.edit_references(edits)?;
+
+    let index_path = repo.index_path();
+    match state.index {
+        Some(index) => {
+            // Like any index write, go through a lock so readers never see a partially restored index.
+            let mut lock =
+                gix_lock::File::acquire_to_update_resource(&index_path, gix_lock::acquire::Fail::Immediately, None)?;
+            std::io::Write::write_all(&mut lock, &index).map_err(|err| rollback::Error::Io {
+                path: index_path,
+                source: err,
+            })?;
+            lock.commit()?;
+        }
+        None => write_or_remove(&index_path, None, None)?,
+    }
+
+    if let Some(work_dir) = repo.work_dir() {
+        for (rela_path, file_state) in state.files {
+            let path = work_dir.join(gix_path::from_bstr(rela_path.as_bstr()));
+            match file_state {
+                FileState::Missing => write_or_remove(&path, None, None)?,
+                FileState::File { content, permissions } => {
+                    write_or_remove(&path, Some(&content), Some(permissions))?;
+                }
+                FileState::Symlink { target } => {
+                    write_or_remove(&path, None, None)?;
+                    path.parent()
+                        .map_or(Ok(()), std::fs::create_dir_all)
+                        .and_then(|_| gix_fs::symlink::create(&target, &path))
+                        .map_err(|err| rollback::Error::Io { path, source: err })?;
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Write `content` to `path` with `permissions`, or remove the file at `path` if `content` is `None`.
+/// Symlinks at `path` are always removed first so they aren't followed.
+fn write_or_remove(
+    path: &Path,
+    content: Option<&[u8]>,
+    permissions: Option<std::fs::Permissions>,
+) -> Result<(), rollback::Error> {
+    let io_err = |err| rollback::Error::Io {
+        path: path.to_owned(),
+        source: err,
+    };
+    match std::fs::symlink_metadata(path) {
+        Ok(meta) if meta.is_symlink() || content.is_none() => std::fs::remove_file(path).map_err(io_err)?,
+        Ok(_) => {}
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => {}
+        Err(err) => return Err(io_err(err)),
+    }
+    if let Some(content) = content {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).map_err(io_err)?;
+        }
+        std::fs::write(path, content).map_err(io_err)?;
+        if let Some(permissions) = permissions {
+            std::fs::set_permissions(path, permissions).map_err(io_err)?;
+        }
+    }
+    Ok(())
+}
+
+pub(crate) fn read_if_exists(path: &Path) -> std::io::Result<Option<Vec<u8>>> {
+    match std::fs::read(path) {
+        Ok(content) => Ok(Some(content)),
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(err) => Err(err),
+    }
+}
@@ -0,0 +1,260 @@
+//! Verify signatures of commits and tags with a pluggable [`Keyring`], and create them with a pluggable [`Signer`].
+//!
+//! A keyring is anything that can check a signature over a payload and tell who made it, and how much the signer is trusted.
+//! With the `command` feature, [`Programs`] provides keyrings backed by the same programs that `git` uses, i.e. `gpg`,
+//! `gpgsm` and `ssh-keygen` with an allowed-signers file, configured like `git` would via
+//! [`Repository::keyring()`](crate::Repository::keyring()).
+//! Similarly, [`SigningProgram`] signs with these programs as configured via [`Repository::signer()`](crate::Repository::signer()),
+//! which for SSH signatures doesn't require `gpg` at all.
+//! Native implementations, for instance one based on `sequoia-openpgp`, can be used by implementing [`Keyring`] or [`Signer`] for them.
+use crate::bstr::{BStr, BString, ByteSlice};
+
+///
+#[cfg(feature = "command")]
+pub mod program;
+#[cfg(feature = "command")]
+pub use program::{Gpg, GpgSigner, Programs, SigningProgram, SshAllowedSigners, SshSigner, SshSigningKey};
+
+/// The error returned by [`Keyring::verify()`] and [`Signer::sign()`] implementations.
+pub type Error = Box<dyn std::error::Error + Send + Sync + 'static>;
+
+/// The kind of signature, as identified by its armor.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Format {
+    /// An OpenPGP signature, typically checked with `gpg`.
+    OpenPgp,
+    /// An X.509 (S/MIME) signature, typically checked with `gpgsm`.
+    X509,
+    /// A signature made with an SSH key, typically checked with `ssh-keygen`.
+    Ssh,
+}
+
+impl Format {
+    /// Identify the format of `signature` by the first line of its armor, or return `None` if it's unknown.
+    pub fn from_signature(signature: &[u8]) -> Option<Self> {
+        let first_line = signature.lines().next()?.trim();
+        Some(match first_line {
+            b"-----BEGIN PGP SIGNATURE-----" | b"-----BEGIN PGP MESSAGE-----" => Format::OpenPgp,
+            b"-----BEGIN SIGNED MESSAGE-----" => Format::X509,
+            b"-----BEGIN SSH SIGNATURE-----" => Format::Ssh,
+            _ => return None,
+        })
+    }
+
+    /// Parse `name` as used in `gpg.format`, or return `None` if it isn't a known format.
+    pub fn from_bytes(name: &[u8]) -> Option<Self> {
+        Some(match name {
+            b"openpgp" => Format::OpenPgp,
+            b"x509" => Format::X509,
+            b"ssh" => Format::Ssh,
+            _ => return None,
+        })
+    }
+
+    /// Return the name of the format as used in `gpg.format`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Format::OpenPgp => "openpgp",
+            Format::X509 => "x509",
+            Format::Ssh => "ssh",
+        }
+    }
+}
+
+/// The level of trust in the key that made a signature, ordered from least to most trusted, like in `gpg.minTrustLevel`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Trust {
+    /// Nothing is known about the key, or the trust could not be determined.
+    Undefined,
+    /// The key is explicitly not trusted.
+    Never,
+    /// The key is marginally trusted.
+    Marginal,
+    /// The key is fully trusted.
+    Fully,
+    /// The key is ultimately trusted, typically because it's one of our own.
+    Ultimate,
+}
+
+impl Trust {
+    /// Parse `name` as used in `gpg.minTrustLevel`, or return `None` if it isn't a known trust level.
+    pub fn from_bytes(name: &[u8]) -> Option<Self> {
+        Some(match name {
+            b"undefined" => Trust::Undefined,
+            b"never" => Trust::Never,
+            b"marginal" => Trust::Marginal,
+            b"fully" => Trust::Fully,
+            b"ultimate" => Trust::Ultimate,
+            _ => return None,
+        })
+    }
+}
+
+/// The result of checking a signature, similar to what `git log --format=%G?` shows.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Status {
+    /// The signature is valid.
+    Good,
+    /// The signature does not match the payload.
+    Bad,
+    /// The signature is valid, but has expired.
+    ExpiredSignature,
+    /// The signature is valid, but was made by a key that has expired.
+    ExpiredKey,
+    /// The signature was made by a key that was revoked.
+    RevokedKey,
+    /// The signature could not be checked, usually because the key is unknown to the keyring.
+    Unverifiable,
+}
+
+/// What a [`Keyring`] learned about a signature.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Outcome {
+    /// The result of the check.
+    pub status: Status,
+    /// The identity of the signer, like the primary user id of an OpenPGP key or the principal of an SSH key, if known.
+    pub signer: Option<BString>,
+    /// The identifier of the key that made the signature, like its fingerprint, if known.
+    pub key: Option<BString>,
+    /// How much the key that made the signature is trusted.
+    pub trust: Trust,
+    /// The human-readable output of the verification program, if any, for display and auditing.
+    pub output: BString,
+}
+
+/// A keyring knows keys and their trust, and uses them to verify signatures.
+pub trait Keyring {
+    /// Verify that `signature` of the given `format` was made over `payload`, and return what's known about the signer.
+    ///
+    /// An `Err` is only returned if the check couldn't be performed at all, while bad signatures are indicated
+    /// by the [status](Outcome::status).
+    fn verify(&self, format: Format, signature: &BStr, payload: &BStr) -> Result<Outcome, Error>;
+}
+
+impl<T: Keyring + ?Sized> Keyring for &T {
+    fn verify(&self, format: Format, signature: &BStr, payload: &BStr) -> Result<Outcome, Error> {
+        (*self).verify(format, signature, payload)
+    }
+}
+
+impl<T: Keyring + ?Sized> Keyring for Box<T> {
+    fn verify(&self, format: Format, signature: &BStr, payload: &BStr) -> Result<Outcome, Error> {
+        (**self).verify(format, signature, payload)
+    }
+}
+
+/// A signer creates signatures with a key it has access to.
+pub trait Signer {
+    /// Sign `payload` and return the armored signature, like `-----BEGIN SSH SIGNATURE-----…`, including its trailing newline.
+    fn sign(&self, payload: &BStr) -> Result<BString, Error>;
+}
+
+impl<T: Signer + ?Sized> Signer for &T {
+    fn sign(&self, payload: &BStr) -> Result<BString, Error> {
+        (*self).sign(payload)
+    }
+}
+
+impl<T: Signer + ?Sized> Signer for Box<T> {
+    fn sign(&self, payload: &BStr) -> Result<BString, Error> {
+        (**self).sign(payload)
+    }
+}
+
+/// The verification of a signed commit or tag.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Verification {
+    /// The format of the signature.
+    pub format: Format,
+    /// The hash of the signed payload, which is the object without its signature, to allow recording exactly what was verified.
+    ///
+    /// It's computed as if the payload was a blob, so it can be reproduced with `git hash-object`.
+    pub payload_digest: gix_hash::ObjectId,
+    /// What the keyring learned about the signature.
+    pub outcome: Outcome,
+}
+
+impl Verification {
+    /// Return `true` if the signature is [good](Status::Good) and was made by a key that is trusted at least as much as
+    /// `min_trust`.
+    pub fn is_trusted(&self, min_trust: Trust) -> bool {
+        self.outcome.status == Status::Good && self.outcome.trust >= min_trust
+    }
+}
+
+///
+pub mod verify {
+    /// The error returned by [`Commit::verify_signature()`](crate::Commit::verify_signature()) and
+    /// [`Tag::verify_signature()`](crate::Tag::verify_signature()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        Decode(#[from] gix_object::decode::Error),
+        #[error("The signature format could not be identified from its armor")]
+        UnknownFormat,
+        #[error("The keyring failed to verify the signature")]
+        Keyring(#[source] super::Error),
+    }
+}
+
+///
+pub mod sign {
+    /// The error returned by [`sign_commit()`](super::sign_commit()) and [`sign_tag()`](super::sign_tag()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("Could not serialize the object to obtain the payload to sign")]
+        Payload(#[from] std::io::Error),
+        #[error("The signer failed to sign the payload")]
+        Signer(#[source] super::Error),
+    }
+}
+
+/// Sign `commit` with `signer` and store the signature in its `gpgsig` header, replacing a previous signature,
+/// just like `git commit -S` would.
+///
+/// The commit can then be written with [`Repository::write_object()`](crate::Repository::write_object()).
+pub fn sign_commit(commit: &mut gix_object::Commit, signer: &dyn Signer) -> Result<(), sign::Error> {
+    use gix_object::WriteTo;
+
+    let header_name = match commit.tree.kind() {
+        gix_hash::Kind::Sha1 => "gpgsig",
+    };
+    commit.extra_headers.retain(|(name, _)| name != header_name);
+    let mut payload = Vec::new();
+    commit.write_to(&mut payload)?;
+    let signature = signer.sign(payload.as_bstr()).map_err(sign::Error::Signer)?;
+    commit.extra_headers.push((header_name.into(), signature));
+    Ok(())
+}
+
+/// Sign `tag` with `signer` and store the signature after its message, replacing a previous signature,
+/// just like `git tag -s` would.
+///
+/// The tag can then be written with [`Repository::create_tag()`](crate::Repository::create_tag()).
+pub fn sign_tag(tag: &mut gix_object::Tag, signer: &dyn Signer) -> Result<(), sign::Error> {
+    let payload = tag.signed_data()?;
+    let signature = signer.sign(payload.as_bstr()).map_err(sign::Error::Signer)?;
+    tag.pgp_signature = Some(signature);
+    Ok(())
+}
+
+/// Verify `signature` over `payload` with `keyring`, assuming the payload is hashed with `hash_kind`.
+pub(crate) fn verify(
+    keyring: &dyn Keyring,
+    signature: &BStr,
+    payload: &BStr,
+    hash_kind: gix_hash::Kind,
+) -> Result<Verification, verify::Error> {
+    let format = Format::from_signature(signature).ok_or(verify::Error::UnknownFormat)?;
+    let payload_digest = gix_object::compute_hash(hash_kind, gix_object::Kind::Blob, payload);
+    let outcome = keyring
+        .verify(format, signature, payload)
+        .map_err(verify::Error::Keyring)?;
+    Ok(Verification {
+        format,
+        payload_digest,
+        outcome,
+    })
+}
@@ -0,0 +1,530 @@
+//! Keyrings and signers that are backed by the programs `git` uses for signature verification and creation.
+use std::{
+    ffi::OsString,
+    io::Write,
+    path::{Path, PathBuf},
+    process::Stdio,
+};
+
+use super::{Format, Keyring, Outcome, Signer, Status, Trust};
+use crate::bstr::{BStr, BString, ByteSlice};
+
+/// The error returned by the keyrings and signers in this module.
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("Could not write the signature, payload or key to a temporary file")]
+    WriteTempFile(#[source] std::io::Error),
+    #[error("Could not run the verification program {program:?}")]
+    Run {
+        program: OsString,
+        #[source]
+        source: std::io::Error,
+    },
+    #[error("SSH signatures can only be verified if `gpg.ssh.allowedSignersFile` is configured")]
+    MissingAllowedSignersFile,
+    #[error("{program:?} failed to sign the data: {output}")]
+    Sign { program: OsString, output: BString },
+    #[error("`ssh-keygen -Y sign` is needed for SSH signing, which is available in OpenSSH 8.2p1 and later")]
+    SshSignUnsupported,
+    #[error("Could not read the signature written by {program:?}")]
+    ReadSignature {
+        program: OsString,
+        #[source]
+        source: std::io::Error,
+    },
+}
+
+/// A keyring that uses `gpg` for OpenPGP signatures, or `gpgsm` for X.509 signatures, along with the keys and trust
+/// database of the current user.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Gpg {
+    /// The program to run, like `gpg`.
+    pub program: OsString,
+}
+
+/// A keyring that uses `ssh-keygen` to verify SSH signatures against a file listing the keys of all allowed signers,
+/// see `ALLOWED SIGNERS` in `man ssh-keygen`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SshAllowedSigners {
+    /// The program to run, like `ssh-keygen`.
+    pub program: OsString,
+    /// The file that maps principals to their keys, as configured in `gpg.ssh.allowedSignersFile`.
+    pub allowed_signers: PathBuf,
+    /// A file with revoked keys, as configured in `gpg.ssh.revocationFile`.
+    pub revocation_file: Option<PathBuf>,
+}
+
+/// A keyring which dispatches to the program that handles the [format](Format) of the signature, the way `git` does.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Programs {
+    /// The keyring for [OpenPGP](Format::OpenPgp) signatures.
+    pub openpgp: Gpg,
+    /// The keyring for [X.509](Format::X509) signatures.
+    pub x509: Gpg,
+    /// The keyring for [SSH](Format::Ssh) signatures, or `None` if no allowed-signers file is configured, which makes
+    /// them unverifiable.
+    pub ssh: Option<SshAllowedSigners>,
+}
+
+/// A signer that uses `gpg` for OpenPGP signatures, or `gpgsm` for X.509 signatures, with a key of the current user.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GpgSigner {
+    /// The program to run, like `gpg`.
+    pub program: OsString,
+    /// The key to sign with, as configured in `user.signingKey`, or the name and email of the committer like `Name <email>`.
+    pub key: BString,
+}
+
+/// The key an [`SshSigner`] signs with, as configured in `user.signingKey`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SshSigningKey {
+    /// A public key like `ssh-ed25519 AAAA…`, whose private key is expected to be held by `ssh-agent`.
+    Literal(BString),
+    /// The path to a private key, or to a public key whose private key is held by `ssh-agent`.
+    Path(PathBuf),
+}
+
+impl SshSigningKey {
+    /// Return the literal key in `value` if it's prefixed with `key::`, or if it starts with `ssh-` which `git` still supports
+    /// for backwards compatibility, or `None` if `value` is a path.
+    pub fn from_literal(value: &BStr) -> Option<Self> {
+        value
+            .strip_prefix(b"key::")
+            .or_else(|| value.starts_with(b"ssh-").then_some(value.as_bytes()))
+            .map(|key| SshSigningKey::Literal(key.into()))
+    }
+}
+
+/// A signer that uses `ssh-keygen` to create SSH signatures, see `man ssh-keygen`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SshSigner {
+    /// The program to run, like `ssh-keygen`.
+    pub program: OsString,
+    /// The key to sign with.
+    pub key: SshSigningKey,
+}
+
+/// A signer which uses the program that handles the [format](Format) configured in `gpg.format`, the way `git` does.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SigningProgram {
+    /// Create [OpenPGP](Format::OpenPgp) or [X.509](Format::X509) signatures.
+    Gpg(GpgSigner),
+    /// Create [SSH](Format::Ssh) signatures.
+    Ssh(SshSigner),
+}
+
+impl Default for Programs {
+    fn default() -> Self {
+        Programs {
+            openpgp: Gpg { program: "gpg".into() },
+            x509: Gpg {
+                program: "gpgsm".into(),
+            },
+            ssh: None,
+        }
+    }
+}
+
+impl Keyring for Programs {
+    fn verify(&self, format: Format, signature: &BStr, payload: &BStr) -> Result<Outcome, super::Error> {
+        match format {
+            Format::OpenPgp => self.openpgp.verify(format, signature, payload),
+            Format::X509 => self.x509.verify(format, signature, payload),
+            Format::Ssh => self
+                .ssh
+                .as_ref()
+                .ok_or_else(|| Box::new(Error::MissingAllowedSignersFile) as super::Error)?
+                .verify(format, signature, payload),
+        }
+    }
+}
+
+impl Signer for SigningProgram {
+    fn sign(&self, payload: &BStr) -> Result<BString, super::Error> {
+        match self {
+            SigningProgram::Gpg(signer) => signer.sign(payload),
+            SigningProgram::Ssh(signer) => signer.sign(payload),
+        }
+    }
+}
+
+impl Signer for GpgSigner {
+    fn sign(&self, payload: &BStr) -> Result<BString, super::Error> {
+        let (success, signature, status) = run(
+            gix_command::prepare(&self.program)
+                .args(["--status-fd=2", "-bsau"])
+                .arg(gix_path::from_bstr(self.key.as_bstr()).into_owned()),
+            &self.program,
+            payload,
+        )?;
+        if !success || !status.lines().any(|line| line.starts_with(b"[GNUPG:] SIG_CREATED ")) {
+            return Err(Error::Sign {
+                program: self.program.clone(),
+                output: status.into(),
+            }
+            .into());
+        }
+        Ok(remove_cr(signature))
+    }
+}
+
+impl Signer for SshSigner {
+    fn sign(&self, payload: &BStr) -> Result<BString, super::Error> {
+        let literal_key_file;
+        let (key_file, use_agent) = match &self.key {
+            SshSigningKey::Literal(key) => {
+                literal_key_file = TempFile::new(key.as_bstr())?;
+                (literal_key_file.path(), true)
+            }
+            SshSigningKey::Path(path) => (path.as_path(), false),
+        };
+        let payload_file = TempFile::new(payload)?;
+        let mut cmd = gix_command::prepare(&self.program)
+            .args(["-Y", "sign", "-n", "git", "-f"])
+            .arg(key_file);
+        if use_agent {
+            cmd = cmd.arg("-U");
+        }
+        let (success, stdout, stderr) = run(cmd.arg(payload_file.path()), &self.program, "".into())?;
+
+        // `ssh-keygen` writes the signature next to the signed file.
+        let mut signature_file = payload_file.path().as_os_str().to_owned();
+        signature_file.push(".sig");
+        let signature = success.then(|| std::fs::read(&signature_file));
+        std::fs::remove_file(&signature_file).ok();
+        match signature {
+            Some(signature) => Ok(remove_cr(signature.map_err(|source| Error::ReadSignature {
+                program: self.program.clone(),
+                source,
+            })?)),
+            None if stderr.contains_str("usage:") => Err(Error::SshSignUnsupported.into()),
+            None => {
+                let mut output: BString = stdout.into();
+                output.extend_from_slice(&stderr);
+                Err(Error::Sign {
+                    program: self.program.clone(),
+                    output,
+                }
+                .into())
+            }
+        }
+    }
+}
+
+/// Remove carriage returns from line endings, in case the signing program produces them on Windows.
+fn remove_cr(signature: Vec<u8>) -> BString {
+    signature.replace(b"\r\n", b"\n").into()
+}
+
+impl Keyring for Gpg {
+    fn verify(&self, format: Format, signature: &BStr, payload: &BStr) -> Result<Outcome, super::Error> {
+        let signature_file = TempFile::new(signature)?;
+        let mut cmd = gix_command::prepare(&self.program);
+        if format == Format::OpenPgp {
+            cmd = cmd.arg("--keyid-format=long");
+        }
+        let cmd = cmd
+            .args(["--status-fd=1", "--verify"])
+            .arg(signature_file.path())
+            .arg("-");
+        let (_success, stdout, stderr) = run(cmd, &self.program, payload)?;
+        Ok(parse_gpg_status(stdout.as_bstr(), stderr.into()))
+    }
+}
+
+/// Turn the machine-readable status lines of `gpg --status-fd` in `status` into an outcome.
+fn parse_gpg_status(status: &BStr, output: BString) -> Outcome {
+    let mut out = Outcome {
+        status: Status::Unverifiable,
+        signer: None,
+        key: None,
+        trust: Trust::Undefined,
+        output,
+    };
+    for line in status.lines().filter_map(|line| line.strip_prefix(b"[GNUPG:] ")) {
+        let mut tokens = line.splitn_str(2, " ");
+        let keyword = tokens.next().unwrap_or_default();
+        let rest = tokens.next().unwrap_or_default();
+        let signature_status = match keyword {
+            b"GOODSIG" => Some(Status::Good),
+            b"BADSIG" => Some(Status::Bad),
+            b"EXPSIG" => Some(Status::ExpiredSignature),
+            b"EXPKEYSIG" => Some(Status::ExpiredKey),
+            b"REVKEYSIG" => Some(Status::RevokedKey),
+            b"ERRSIG" => Some(Status::Unverifiable),
+            b"VALIDSIG" => {
+                // The primary key fingerprint is the last field, falling back to the fingerprint of the signing (sub-)key.
+                let fields: Vec<_> = rest.split_str(" ").collect();
+                out.key = fields
+                    .get(9)
+                    .or_else(|| fields.first())
+                    .map(|fingerprint| fingerprint.as_bstr().to_owned());
+                None
+            }
+            b"TRUST_UNDEFINED" => {
+                out.trust = Trust::Undefined;
+                None
+            }
+            b"TRUST_NEVER" => {
+                out.trust = Trust::Never;
+                None
+            }
+            b"TRUST_MARGINAL" => {
+                out.trust = Trust::Marginal;
+                None
+            }
+            b"TRUST_FULLY" => {
+                out.trust = Trust::Fully;
+                None
+            }
+            b"TRUST_ULTIMATE" => {
+                out.trust = Trust::Ultimate;
+                None
+            }
+            _ => None,
+        };
+        if let Some(status) = signature_status {
+            out.status = status;
+            let mut tokens = rest.splitn_str(2, " ");
+            if out.key.is_none() {
+                out.key = tokens.next().map(|key_id| key_id.as_bstr().to_owned());
+            } else {
+                tokens.next();
+            }
+            if status != Status::Unverifiable {
+                out.signer = tokens.next().map(|uid| uid.as_bstr().to_owned());
+            }
+        }
+    }
+    out
+}
+
+impl Keyring for SshAllowedSigners {
+    fn verify(&self, _format: Format, signature: &BStr, payload: &BStr) -> Result<Outcome, super::Error> {
+        let signature_file = TempFile::new(signature)?;
+        let (success, principals, _stderr) = run(
+            gix_command::prepare(&self.program)
+                .args(["-Y", "find-principals", "-f"])
+                .arg(&self.allowed_signers)
+                .arg("-s")
+                .arg(signature_file.path()),
+            &self.program,
+            "".into(),
+        )?;
+
+        let principals: Vec<_> = if success {
+            principals
+                .lines()
+                .map(|line| line.trim())
+                .filter(|line| !line.is_empty())
+                .collect()
+        } else {
+            Vec::new()
+        };
+        if principals.is_empty() {
+            // An unknown key, so the signature can only be checked for consistency, without any trust.
+            let (success, stdout, stderr) = run(
+                gix_command::prepare(&self.program)
+                    .args(["-Y", "check-novalidate", "-n", "git", "-s"])
+                    .arg(signature_file.path()),
+                &self.program,
+                payload,
+            )?;
+            return Ok(ssh_outcome(success, None, Trust::Undefined, stdout, stderr));
+        }
+
+        let mut output = BString::default();
+        for principal in principals {
+            let mut cmd = gix_command::prepare(&self.program)
+                .args(["-Y", "verify", "-n", "git", "-f"])
+                .arg(&self.allowed_signers)
+                .arg("-I")
+                .arg(gix_path::from_bstr(principal.as_bstr()).into_owned())
+                .arg("-s")
+                .arg(signature_file.path());
+            if let Some(revocation_file) = &self.revocation_file {
+                cmd = cmd.arg("-r").arg(revocation_file);
+            }
+            let (success, stdout, stderr) = run(cmd, &self.program, payload)?;
+            if success {
+                return Ok(ssh_outcome(
+                    true,
+                    Some(principal.as_bstr().to_owned()),
+                    Trust::Fully,
+                    stdout,
+                    stderr,
+                ));
+            }
+            output.extend_from_slice(&stdout);
+            output.extend_from_slice(&stderr);
+        }
+        Ok(ssh_outcome(false, None, Trust::Undefined, output.into(), Vec::new()))
+    }
+}
+
+/// Produce an outcome from the output of `ssh-keygen`, which reads like `Good "git" signature for <principal> with <type> key <fingerprint>`.
+fn ssh_outcome(success: bool, signer: Option<BString>, trust: Trust, stdout: Vec<u8>, stderr: Vec<u8>) -> Outcome {
+    let mut output: BString = stdout.into();
+    output.extend_from_slice(&stderr);
+    let key = output
+        .lines()
+        .find(|line| line.starts_with(b"Good \""))
+        .and_then(|line| line.rfind(" key ").map(|pos| line[pos + " key ".len()..].trim()))
+        .filter(|fingerprint| !fingerprint.is_empty())
+        .map(|fingerprint| fingerprint.as_bstr().to_owned());
+    Outcome {
+        status: if success { Status::Good } else { Status::Bad },
+        signer,
+        key,
+        trust,
+        output,
+    }
+}
+
+/// A temporary file holding a signature, payload or key, as the programs need some of their inputs as files.
+struct TempFile {
+    _handle: gix_tempfile::Handle<gix_tempfile::handle::Writable>,
+    path: PathBuf,
+}
+
+impl TempFile {
+    fn new(content: &BStr) -> Result<Self, Error> {
+        let mut handle = gix_tempfile::new(
+            std::env::temp_dir(),
+            gix_tempfile::ContainingDirectory::Exists,
+            gix_tempfile::AutoRemove::Tempfile,
+        )
+        .map_err(Error::WriteTempFile)?;
+        let path = handle
+            .with_mut(|file| -> std::io::Result<_> {
+                file.write_all(content)?;
+                file.flush()?;
+                Ok(file.path().to_owned())
+            })
+            .and_then(std::convert::identity)
+            .map_err(Error::WriteTempFile)?;
+        Ok(TempFile { _handle: handle, path })
+    }
+
+    fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+/// Run `cmd` while passing `stdin` to it, and return whether it succeeded along with its output.
+fn run(cmd: gix_command::Prepare, program: &OsString, stdin: &BStr) -> Result<(bool, Vec<u8>, Vec<u8>), Error> {
+    let to_err = |source| Error::Run {
+        program: program.clone(),
+        source,
+    };
+    let mut cmd: std::process::Command = cmd
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .into();
+    gix_trace::debug!("About to execute {cmd:?}");
+    let mut child = cmd.spawn().map_err(to_err)?;
+    let mut child_stdin = child.stdin.take().expect("configured");
+    let output = std::thread::scope(|scope| {
+        // Write concurrently to avoid deadlocks if the program produces a lot of output before consuming its input.
+        let writer = scope.spawn(move || child_stdin.write_all(stdin));
+        let output = child.wait_with_output();
+        // Programs may exit without reading all of their input, which is fine.
+        writer.join().ok();
+        output
+    })
+    .map_err(to_err)?;
+    Ok((output.status.success(), output.stdout, output.stderr))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn gpg_status_good_signature() {
+        let status = "[GNUPG:] NEWSIG\n\
+                      [GNUPG:] KEY_CONSIDERED 1234ABCD 0\n\
+                      [GNUPG:] GOODSIG 0123456789ABCDEF Jane Doe <jane@example.com>\n\
+                      [GNUPG:] VALIDSIG SUBKEYFPR 2024-01-01 1704067200 0 4 0 22 8 00 PRIMARYFPR\n\
+                      [GNUPG:] TRUST_ULTIMATE 0 pgp\n";
+        let out = parse_gpg_status(status.into(), "output".into());
+        assert_eq!(out.status, Status::Good);
+        assert_eq!(
+            out.signer.as_ref().map(|v| v.as_bstr()),
+            Some(b"Jane Doe <jane@example.com>".as_bstr())
+        );
+        assert_eq!(
+            out.key.as_ref().map(|v| v.as_bstr()),
+            Some(b"PRIMARYFPR".as_bstr()),
+            "the primary fingerprint is preferred"
+        );
+        assert_eq!(out.trust, Trust::Ultimate);
+        assert_eq!(out.output, "output");
+    }
+
+    #[test]
+    fn gpg_status_bad_and_missing_key() {
+        let out = parse_gpg_status(
+            "[GNUPG:] BADSIG 0123456789ABCDEF Jane Doe <jane@example.com>\n".into(),
+            BString::default(),
+        );
+        assert_eq!(out.status, Status::Bad);
+        assert_eq!(
+            out.key.as_ref().map(|v| v.as_bstr()),
+            Some(b"0123456789ABCDEF".as_bstr())
+        );
+        assert_eq!(out.trust, Trust::Undefined);
+
+        let out = parse_gpg_status(
+            "[GNUPG:] ERRSIG 0123456789ABCDEF 22 8 00 1704067200 9 -\n[GNUPG:] NO_PUBKEY 0123456789ABCDEF\n".into(),
+            BString::default(),
+        );
+        assert_eq!(out.status, Status::Unverifiable);
+        assert_eq!(
+            out.key.as_ref().map(|v| v.as_bstr()),
+            Some(b"0123456789ABCDEF".as_bstr())
+        );
+        assert_eq!(out.signer, None);
+
+        let out = parse_gpg_status("".into(), BString::default());
+        assert_eq!(
+            out.status,
+            Status::Unverifiable,
+            "no status means the signature wasn't checked"
+        );
+    }
+
+    #[test]
+    fn ssh_signing_key_from_literal() {
+        assert_eq!(
+            SshSigningKey::from_literal("key::ssh-ed25519 AAAA".into()),
+            Some(SshSigningKey::Literal("ssh-ed25519 AAAA".into()))
+        );
+        assert_eq!(
+            SshSigningKey::from_literal("key::ecdsa-sha2-nistp256 AAAA".into()),
+            Some(SshSigningKey::Literal("ecdsa-sha2-nistp256 AAAA".into())),
+            "the prefix allows any kind of key"
+        );
+        assert_eq!(
+            SshSigningKey::from_literal("ssh-rsa AAAA".into()),
+            Some(SshSigningKey::Literal("ssh-rsa AAAA".into())),
+            "deprecated, but still supported"
+        );
+        assert_eq!(SshSigningKey::from_literal("~/.ssh/id_ed25519".into()), None);
+    }
+
+    #[test]
+    fn ssh_output() {
+        let out = ssh_outcome(
+            true,
+            Some("jane@example.com".into()),
+            Trust::Fully,
+            b"Good \"git\" signature for jane@example.com with ED25519 key SHA256:abcdef\n".to_vec(),
+            Vec::new(),
+        );
+        assert_eq!(out.status, Status::Good);
+        assert_eq!(out.key.as_ref().map(|v| v.as_bstr()), Some(b"SHA256:abcdef".as_bstr()));
+    }
+}
@@ -95,6 +95,8 @@
 pub use gix_actor as actor;
 #[cfg(feature = "attributes")]
 pub use gix_attributes as attrs;
+#[cfg(feature = "blame")]
+pub use gix_blame as blame;
 #[cfg(feature = "command")]
 pub use gix_command as command;
 pub use gix_commitgraph as commitgraph;
@@ -181,8 +183,14 @@ pub mod commit;
 ///
 #[cfg(feature = "dirwalk")]
 pub mod dirwalk;
+///
+pub mod fast_import;
 pub mod head;
+///
+pub mod history;
 pub mod id;
+pub mod journal;
+pub mod keyring;
 pub mod object;
 #[cfg(feature = "attributes")]
 pub mod pathspec;
@@ -332,6 +340,10 @@ pub mod status;
 ///
 pub mod shallow;
 
+///
+#[cfg(feature = "worktree-mutation")]
+pub mod sparse_checkout;
+
 ///
 pub mod discover;
 
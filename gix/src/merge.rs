@@ -47,7 +47,7 @@ pub mod commit {
     #[derive(Default, Debug, Clone)]
     pub struct Options {
         allow_missing_merge_base: bool,
-        tree_merge: crate::merge::tree::Options,
+        pub(crate) tree_merge: crate::merge::tree::Options,
         use_first_merge_base: bool,
     }
 
@@ -107,7 +107,7 @@ pub mod commit {
 ///
 pub mod tree {
     use gix_merge::blob::builtin_driver;
-    pub use gix_merge::tree::{Conflict, ContentMerge, Resolution, ResolutionFailure, TreatAsUnresolved};
+    pub use gix_merge::tree::{ChangesCache, Conflict, ContentMerge, Resolution, ResolutionFailure, TreatAsUnresolved};
 
     /// The outcome produced by [`Repository::merge_trees()`](crate::Repository::merge_trees()).
     #[derive(Clone)]
@@ -153,6 +153,51 @@ pub mod tree {
         ) -> bool {
             gix_merge::tree::apply_index_entries(&self.conflicts, how, index)
         }
+
+        /// Return the entries of all conflicts that are unresolved according to `how` at their respective stages,
+        /// sorted by path and stage, like `git merge-tree --write-tree` prints them as *conflicted file info*.
+        /// `tree_id` is the id of [`Self::tree`] after it was written.
+        ///
+        /// Neither the index nor the worktree of the repository are touched, which makes this suitable for determining
+        /// if and how a merge would conflict in bare repositories.
+        pub fn conflict_entries(
+            &self,
+            tree_id: &gix_hash::oid,
+            how: TreatAsUnresolved,
+        ) -> Result<Vec<ConflictEntry>, crate::repository::index_from_tree::Error> {
+            if !self.has_unresolved_conflicts(how) {
+                return Ok(Vec::new());
+            }
+            let mut index = self.tree.repo.index_from_tree(tree_id)?;
+            self.index_changed_after_applying_conflicts(&mut index, how);
+            Ok(index
+                .entries()
+                .iter()
+                .filter(|entry| {
+                    entry.stage() != gix_index::entry::Stage::Unconflicted
+                        && !entry.flags.contains(gix_index::entry::Flags::REMOVE)
+                })
+                .map(|entry| ConflictEntry {
+                    mode: entry.mode,
+                    id: entry.id,
+                    stage: entry.stage(),
+                    path: entry.path(&index).to_owned(),
+                })
+                .collect())
+        }
+    }
+
+    /// An entry of a conflicting path at one of its stages, as it would be placed into the index.
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct ConflictEntry {
+        /// The mode of the entry.
+        pub mode: gix_index::entry::Mode,
+        /// The id of the object at this stage.
+        pub id: gix_hash::ObjectId,
+        /// The stage, which is never [`Unconflicted`](gix_index::entry::Stage::Unconflicted).
+        pub stage: gix_index::entry::Stage,
+        /// The path relative to the root of the merged tree.
+        pub path: crate::bstr::BString,
     }
 
     /// A way to configure [`Repository::merge_trees()`](crate::Repository::merge_trees()).
@@ -160,6 +205,7 @@ pub mod tree {
     pub struct Options {
         inner: gix_merge::tree::Options,
         file_favor: Option<FileFavor>,
+        pub(crate) rerere: bool,
     }
 
     impl From<gix_merge::tree::Options> for Options {
@@ -167,6 +213,7 @@ pub mod tree {
             Options {
                 inner: opts,
                 file_favor: None,
+                rerere: false,
             }
         }
     }
@@ -238,5 +285,168 @@ pub mod tree {
             self.file_favor = file_favor;
             self
         }
+
+        /// Set the `style` of conflict markers to write into conflicting files, overriding `merge.conflictStyle`.
+        ///
+        /// Note that this has no effect if conflicts are resolved automatically, for instance with a [file favor](Self::with_file_favor()).
+        pub fn with_conflict_style(mut self, style: builtin_driver::text::ConflictStyle) -> Self {
+            if let builtin_driver::text::Conflict::Keep { style: current, .. } =
+                &mut self.inner.blob_merge.text.conflict
+            {
+                *current = style;
+            }
+            self
+        }
+
+        /// Set the amount of characters used for each conflict marker to `marker_size`, instead of
+        /// [the default](builtin_driver::text::Conflict::DEFAULT_MARKER_SIZE).
+        ///
+        /// Note that the `conflict-marker-size` attribute takes precedence for the paths it applies to.
+        pub fn with_marker_size(mut self, marker_size: std::num::NonZeroU8) -> Self {
+            if let builtin_driver::text::Conflict::Keep {
+                marker_size: current, ..
+            } = &mut self.inner.blob_merge.text.conflict
+            {
+                *current = marker_size;
+            }
+            self
+        }
+
+        /// If `true`, directories that were renamed on one side are inferred from the renames of the files within them,
+        /// so files that were added to the old directory on the other side are moved into the renamed one.
+        pub fn with_detect_directory_renames(mut self, detect_directory_renames: bool) -> Self {
+            self.inner.detect_directory_renames = detect_directory_renames;
+            self
+        }
+
+        /// Use `cache` to look up the changes of each side before diffing it against the merge-base, and to store them
+        /// afterwards, which avoids repeating rename tracking when merging multiple trees with the same merge-base.
+        ///
+        /// Pass a clone of the same [`ChangesCache`] to all merges that should share it.
+        pub fn with_changes_cache(mut self, cache: Option<ChangesCache>) -> Self {
+            self.inner.changes_cache = cache;
+            self
+        }
+
+        /// If `true`, content conflicts are resolved with the resolutions that were previously recorded for them
+        /// by [`Repository::rerere()`](crate::Repository::rerere()), if there are any.
+        pub fn with_rerere(mut self, rerere: bool) -> Self {
+            self.rerere = rerere;
+            self
+        }
+    }
+}
+
+///
+pub mod rerere {
+    use crate::bstr::BString;
+    pub use gix_merge::rerere::{conflict_id, resolve};
+
+    /// Configuration for [`Repository::rerere()`](crate::Repository::rerere()), typically obtained
+    /// with [`Repository::rerere_options()`](crate::Repository::rerere_options()).
+    #[derive(Default, Debug, Copy, Clone, PartialEq, Eq)]
+    pub struct Options {
+        /// If `true`, conflicts and their resolutions will be recorded, and recorded resolutions will be applied.
+        /// If `false`, nothing happens.
+        pub enabled: bool,
+        /// If `true`, paths that were resolved with a recorded resolution will also be added to the index, marking
+        /// them as resolved.
+        pub auto_update: bool,
+    }
+
+    /// The outcome of [`Repository::rerere()`](crate::Repository::rerere()).
+    #[derive(Default, Debug, Clone, PartialEq, Eq)]
+    pub struct Outcome {
+        /// The paths whose conflicts were recorded for the first time, to be able to record their resolution later.
+        pub recorded_preimages: Vec<BString>,
+        /// The paths whose conflicts were resolved by the user and whose resolutions were recorded.
+        pub recorded_resolutions: Vec<BString>,
+        /// The paths whose conflicts were resolved with a previously recorded resolution.
+        pub resolved: Vec<BString>,
+        /// The paths whose conflicts were resolved and that were added to the index as well,
+        /// if [auto-update](Options::auto_update) was enabled.
+        pub staged: Vec<BString>,
+    }
+}
+
+///
+pub mod replay {
+    use gix_hash::ObjectId;
+
+    use crate::merge::tree::{ConflictEntry, TreatAsUnresolved};
+
+    /// A way to configure [`Repository::replay()`](crate::Repository::replay()).
+    #[derive(Debug, Clone)]
+    pub struct Options {
+        pub(crate) tree_merge: crate::merge::tree::Options,
+        pub(crate) treat_as_unresolved: TreatAsUnresolved,
+    }
+
+    impl Default for Options {
+        fn default() -> Self {
+            crate::merge::tree::Options::default().into()
+        }
+    }
+
+    impl From<crate::merge::tree::Options> for Options {
+        fn from(value: crate::merge::tree::Options) -> Self {
+            Options {
+                tree_merge: value,
+                treat_as_unresolved: TreatAsUnresolved::Renames,
+            }
+        }
+    }
+
+    impl From<gix_merge::tree::Options> for Options {
+        fn from(value: gix_merge::tree::Options) -> Self {
+            crate::merge::tree::Options::from(value).into()
+        }
+    }
+
+    /// Builder
+    impl Options {
+        /// Use `how` to determine which conflicts of a replayed commit are unresolved and thus stop the replay,
+        /// instead of [`TreatAsUnresolved::Renames`].
+        pub fn with_treat_as_unresolved(mut self, how: TreatAsUnresolved) -> Self {
+            self.treat_as_unresolved = how;
+            self
+        }
+    }
+
+    /// A commit that was replayed onto the new base.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub struct Replayed {
+        /// The id of the commit that was replayed.
+        pub original: ObjectId,
+        /// The id of the newly written commit, or `None` if the commit was dropped as its changes are already
+        /// present in the new base, which would have made it empty.
+        pub new: Option<ObjectId>,
+    }
+
+    /// A commit whose changes couldn't be applied without conflicts.
+    #[derive(Debug, Clone)]
+    pub struct Conflict {
+        /// The id of the commit that couldn't be replayed.
+        pub commit: ObjectId,
+        /// All conflicts that were encountered when merging the commit, including the ones that could be resolved.
+        pub conflicts: Vec<crate::merge::tree::Conflict>,
+        /// The unresolved paths at all of their stages, as they would be placed into the index.
+        pub entries: Vec<ConflictEntry>,
+    }
+
+    /// The outcome of [`Repository::replay()`](crate::Repository::replay()).
+    #[derive(Debug, Clone)]
+    pub struct Outcome {
+        /// All commits that were replayed, in the order they were applied, parents before their children.
+        pub commits: Vec<Replayed>,
+        /// The id of the last replayed commit, or of the new base if no commit was replayed.
+        ///
+        /// This is what the branch should point to afterwards, unless there was a [conflict](Self::conflict).
+        pub tip: ObjectId,
+        /// The commit that stopped the replay, or `None` if all commits were replayed.
+        ///
+        /// If set, [`commits`](Self::commits) contains only the commits before the conflicting one, and [`tip`](Self::tip)
+        /// points to the last of them.
+        pub conflict: Option<Conflict>,
     }
 }
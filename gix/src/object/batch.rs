@@ -0,0 +1,296 @@
+use std::io::{BufRead, Write};
+
+use gix_hash::ObjectId;
+
+use crate::bstr::{BStr, BString, ByteSlice};
+
+/// A placeholder in a [`Format`], written like `%(objectname)`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Atom {
+    /// `%(objectname)`, the full hex-encoded id of the object.
+    ObjectName,
+    /// `%(objecttype)`, the kind of the object.
+    ObjectType,
+    /// `%(objectsize)`, the size of the object's data in bytes.
+    ObjectSize,
+    /// `%(objectsize:disk)`, the amount of bytes the object takes on disk, either as loose object file or as pack entry.
+    ObjectSizeDisk,
+    /// `%(rest)`, the text after the first whitespace of the input line.
+    Rest,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Item {
+    Literal(BString),
+    Atom(Atom),
+}
+
+/// The format of each line of output of [`Repository::object_batch()`](crate::Repository::object_batch()),
+/// like `%(objectname) %(objecttype) %(objectsize)`, which is also the default.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Format {
+    items: Vec<Item>,
+}
+
+impl Default for Format {
+    fn default() -> Self {
+        Format {
+            items: vec![
+                Item::Atom(Atom::ObjectName),
+                Item::Literal(" ".into()),
+                Item::Atom(Atom::ObjectType),
+                Item::Literal(" ".into()),
+                Item::Atom(Atom::ObjectSize),
+            ],
+        }
+    }
+}
+
+///
+pub mod format {
+    use crate::bstr::BString;
+
+    /// The error returned by [`Format::parse()`](super::Format::parse()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("Format {format:?} has an unterminated placeholder")]
+        Unterminated { format: BString },
+        #[error("Unknown format element: {atom:?}")]
+        UnknownAtom { atom: BString },
+    }
+}
+
+impl Format {
+    /// Parse `input` like `%(objectname) %(objecttype)`, where `%%` is a literal `%`, and like in `git`, a `%` that isn't
+    /// followed by `(` or `%` is kept as is.
+    ///
+    /// ### Deviation
+    ///
+    /// `%(deltabase)` isn't supported.
+    pub fn parse(input: &BStr) -> Result<Self, format::Error> {
+        let mut items = Vec::new();
+        let mut literal = BString::default();
+        let mut cursor = input.as_bytes();
+        while let Some(pos) = cursor.find_byte(b'%') {
+            literal.extend_from_slice(&cursor[..pos]);
+            cursor = &cursor[pos + 1..];
+            if let Some(rest) = cursor.strip_prefix(b"(") {
+                let end = rest.find_byte(b')').ok_or_else(|| format::Error::Unterminated {
+                    format: input.to_owned(),
+                })?;
+                let atom = match &rest[..end] {
+                    b"objectname" => Atom::ObjectName,
+                    b"objecttype" => Atom::ObjectType,
+                    b"objectsize" => Atom::ObjectSize,
+                    b"objectsize:disk" => Atom::ObjectSizeDisk,
+                    b"rest" => Atom::Rest,
+                    unknown => {
+                        return Err(format::Error::UnknownAtom {
+                            atom: unknown.as_bstr().to_owned(),
+                        })
+                    }
+                };
+                if !literal.is_empty() {
+                    items.push(Item::Literal(std::mem::take(&mut literal)));
+                }
+                items.push(Item::Atom(atom));
+                cursor = &rest[end + 1..];
+            } else if let Some(rest) = cursor.strip_prefix(b"%") {
+                literal.push(b'%');
+                cursor = rest;
+            } else {
+                literal.push(b'%');
+            }
+        }
+        literal.extend_from_slice(cursor);
+        if !literal.is_empty() {
+            items.push(Item::Literal(literal));
+        }
+        Ok(Format { items })
+    }
+
+    /// Return `true` if `atom` is used in this format.
+    pub fn uses(&self, atom: Atom) -> bool {
+        self.items.contains(&Item::Atom(atom))
+    }
+}
+
+/// What to output for each object.
+#[derive(Default, Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Mode {
+    /// Output only a line with information as given by the format, like `git cat-file --batch-check`.
+    #[default]
+    Check,
+    /// Output the line with information followed by the data of the object and a newline, like `git cat-file --batch`.
+    Contents,
+}
+
+/// Options for use in [`Repository::object_batch()`](crate::Repository::object_batch()).
+#[derive(Default, Debug, Clone)]
+pub struct Options {
+    /// The format of each line of information.
+    pub format: Format,
+    /// What to output for each object.
+    pub mode: Mode,
+    /// If `true`, the output isn't flushed after each object, which is faster if nobody waits for the output of
+    /// individual objects, like `git cat-file --buffer`.
+    pub buffer: bool,
+}
+
+/// The outcome of [`Repository::object_batch()`](crate::Repository::object_batch()).
+#[derive(Default, Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Outcome {
+    /// The amount of objects that were found and printed.
+    pub found: usize,
+    /// The amount of input lines that didn't name an object.
+    pub missing: usize,
+    /// The amount of input lines with a short object id or name that matched more than one object.
+    pub ambiguous: usize,
+}
+
+/// The error returned by [`Repository::object_batch()`](crate::Repository::object_batch()).
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error(transparent)]
+    FindHeader(#[from] crate::object::find::Error),
+    #[error(transparent)]
+    FindObject(#[from] crate::object::find::existing::Error),
+    #[error(transparent)]
+    AlternateDbPaths(#[from] gix_odb::store::load_index::Error),
+}
+
+impl crate::Repository {
+    /// Read one object name per line from `input`, and write information about it to `out` according to `options`,
+    /// speaking the same protocol as `git cat-file --batch` and `git cat-file --batch-check`.
+    ///
+    /// Object names can be anything that [`rev_parse_single()`](Self::rev_parse_single()) accepts. If the format uses
+    /// `%(rest)`, the name ends at the first whitespace, and what follows it is available as `%(rest)`.
+    /// For names that don't resolve to an object, `<name> missing` is written instead, or `<name> ambiguous` if they
+    /// could refer to more than one object.
+    pub fn object_batch(&self, input: impl BufRead, mut out: impl Write, options: &Options) -> Result<Outcome, Error> {
+        let split_rest = options.format.uses(Atom::Rest);
+        let mut outcome = Outcome::default();
+        let mut buf = Vec::new();
+        let mut pack_location_handle = None;
+        for line in input.split(b'\n') {
+            let line = line?;
+            let (name, rest) = if split_rest {
+                match line.find_byteset(b" \t") {
+                    Some(pos) => (&line[..pos], line[pos + 1..].trim_start()),
+                    None => (line.as_slice(), &[][..]),
+                }
+            } else {
+                (line.as_slice(), &[][..])
+            };
+
+            let found = match self.rev_parse_single(name.as_bstr()) {
+                Ok(id) => self
+                    .try_find_header(id)?
+                    .map(|header| (id.detach(), header))
+                    .ok_or(false),
+                Err(err) => Err(is_ambiguous(&err)),
+            };
+            let (id, header) = match found {
+                Ok(found) => found,
+                Err(ambiguous) => {
+                    out.write_all(name)?;
+                    if ambiguous {
+                        out.write_all(b" ambiguous\n")?;
+                        outcome.ambiguous += 1;
+                    } else {
+                        out.write_all(b" missing\n")?;
+                        outcome.missing += 1;
+                    }
+                    if !options.buffer {
+                        out.flush()?;
+                    }
+                    continue;
+                }
+            };
+
+            for item in &options.format.items {
+                match item {
+                    Item::Literal(literal) => out.write_all(literal)?,
+                    Item::Atom(Atom::ObjectName) => write!(out, "{id}")?,
+                    Item::Atom(Atom::ObjectType) => write!(out, "{}", header.kind())?,
+                    Item::Atom(Atom::ObjectSize) => write!(out, "{}", header.size())?,
+                    Item::Atom(Atom::ObjectSizeDisk) => {
+                        write!(
+                            out,
+                            "{}",
+                            self.object_disk_size(&id, &mut pack_location_handle, &mut buf)?
+                                .unwrap_or_default()
+                        )?;
+                    }
+                    Item::Atom(Atom::Rest) => out.write_all(rest)?,
+                }
+            }
+            out.write_all(b"\n")?;
+            if options.mode == Mode::Contents {
+                let object = self.find_object(id)?;
+                out.write_all(&object.data)?;
+                out.write_all(b"\n")?;
+            }
+            outcome.found += 1;
+            if !options.buffer {
+                out.flush()?;
+            }
+        }
+        out.flush()?;
+        Ok(outcome)
+    }
+
+    /// Return the amount of bytes `id` takes on disk, as size of its pack entry or of its loose object file,
+    /// or `None` if it's neither, for instance because it only exists in memory.
+    fn object_disk_size(
+        &self,
+        id: &ObjectId,
+        pack_location_handle: &mut Option<gix_odb::store::Handle<gix_features::threading::OwnShared<gix_odb::Store>>>,
+        buf: &mut Vec<u8>,
+    ) -> Result<Option<u64>, Error> {
+        let handle = pack_location_handle.get_or_insert_with(|| {
+            let mut handle = (**self.objects).clone();
+            handle.prevent_pack_unload();
+            handle
+        });
+        if let Some(location) = gix_pack::Find::location_by_oid(&*handle, id, buf) {
+            return Ok(Some(location.entry_size as u64));
+        }
+        let hex = id.to_hex().to_string();
+        let store = self.objects.store_ref();
+        for objects_dir in std::iter::once(store.path().to_owned()).chain(store.alternate_db_paths()?) {
+            match std::fs::metadata(objects_dir.join(&hex[..2]).join(&hex[2..])) {
+                Ok(meta) => return Ok(Some(meta.len())),
+                Err(err) if err.kind() == std::io::ErrorKind::NotFound => {}
+                Err(err) => return Err(err.into()),
+            }
+        }
+        Ok(None)
+    }
+}
+
+/// Return `true` if `err` is due to a short object id or name that matches more than one object.
+fn is_ambiguous(err: &crate::revision::spec::parse::single::Error) -> bool {
+    use crate::revision::spec::parse::{single, Error};
+    fn is_ambiguous_inner(err: &Error) -> bool {
+        match err {
+            Error::AmbiguousPrefix { .. } | Error::AmbiguousRefAndObject { .. } => true,
+            Error::Multi { current, next } => {
+                current.downcast_ref::<Error>().map_or(false, is_ambiguous_inner)
+                    || next
+                        .as_ref()
+                        .and_then(|next| next.downcast_ref::<Error>())
+                        .map_or(false, is_ambiguous_inner)
+            }
+            _ => false,
+        }
+    }
+    match err {
+        single::Error::Parse(err) => is_ambiguous_inner(err),
+        single::Error::RangedRev { .. } => false,
+    }
+}
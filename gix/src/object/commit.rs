@@ -158,13 +158,86 @@ impl<'repo> Commit<'repo> {
     }
 
     /// Extracts the PGP signature and the data that was used to create the signature, or `None` if it wasn't signed.
-    // TODO: make it possible to verify the signature, probably by wrapping `SignedData`. It's quite some work to do it properly.
     pub fn signature(
         &self,
     ) -> Result<Option<(std::borrow::Cow<'_, BStr>, gix_object::commit::SignedData<'_>)>, gix_object::decode::Error>
     {
         gix_object::CommitRefIter::signature(&self.data)
     }
+
+    /// Verify the signature of this commit with `keyring`, or return `None` if it isn't signed.
+    ///
+    /// Note that the returned [verification](crate::keyring::Verification) must be inspected to learn whether the signature
+    /// is good and trusted enough.
+    pub fn verify_signature(
+        &self,
+        keyring: &dyn crate::keyring::Keyring,
+    ) -> Result<Option<crate::keyring::Verification>, crate::keyring::verify::Error> {
+        let Some((signature, signed_data)) = self.signature()? else {
+            return Ok(None);
+        };
+        let payload = signed_data.to_bstring();
+        crate::keyring::verify(keyring, &signature, BStr::new(&payload), self.id.kind()).map(Some)
+    }
+
+    /// Diff the tree of this commit against the trees of its parents, handling merge commits according to `merges`,
+    /// and return one set of changes per parent that was diffed against.
+    ///
+    /// Root commits are diffed against the empty tree. Diff options are obtained from the git configuration,
+    /// like with [`Repository::diff_tree_to_tree()`](crate::Repository::diff_tree_to_tree()).
+    #[cfg(feature = "blob-diff")]
+    pub fn diff_to_parents(
+        &self,
+        merges: crate::commit::diff_to_parents::Merges,
+    ) -> Result<Vec<crate::commit::diff_to_parents::Changes>, crate::commit::diff_to_parents::Error> {
+        use crate::bstr::{BString, ByteSlice};
+        use crate::commit::diff_to_parents::{Changes, Merges};
+
+        let tree = self.tree()?;
+        let parent_ids: Vec<_> = self.parent_ids().map(crate::Id::detach).collect();
+        let parent_ids = match (parent_ids.len(), merges) {
+            (0, _) => {
+                return Ok(vec![Changes {
+                    parent_id: None,
+                    changes: self.repo.diff_tree_to_tree(None, &tree, None)?,
+                }]);
+            }
+            (1, _) | (_, Merges::Combined | Merges::Separate) => &parent_ids[..],
+            (_, Merges::FirstParent) => &parent_ids[..1],
+            (_, Merges::Off) => return Ok(Vec::new()),
+        };
+
+        let mut out = Vec::with_capacity(parent_ids.len());
+        for parent_id in parent_ids {
+            let parent_tree = self.repo.find_object(*parent_id)?.peel_to_tree()?;
+            out.push(Changes {
+                parent_id: Some(*parent_id),
+                changes: self.repo.diff_tree_to_tree(&parent_tree, &tree, None)?,
+            });
+        }
+
+        if merges == Merges::Combined && out.len() > 1 {
+            let changed_against_all: std::collections::BTreeSet<BString> = out[0]
+                .changes
+                .iter()
+                .map(|change| change.location().to_owned())
+                .filter(|location| {
+                    out[1..].iter().all(|other| {
+                        other
+                            .changes
+                            .iter()
+                            .any(|change| change.location() == location.as_bstr())
+                    })
+                })
+                .collect();
+            for parent in &mut out {
+                parent
+                    .changes
+                    .retain(|change| changed_against_all.contains(change.location()));
+            }
+        }
+        Ok(out)
+    }
 }
 
 impl std::fmt::Debug for Commit<'_> {
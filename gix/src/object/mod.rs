@@ -11,6 +11,9 @@ pub(crate) mod cache {
 }
 pub use errors::{conversion, find, write};
 ///
+#[cfg(feature = "revision")]
+pub mod batch;
+///
 pub mod blob;
 ///
 pub mod commit;
@@ -1,4 +1,4 @@
-use crate::{ext::ObjectIdExt, ObjectDetached, Tag};
+use crate::{bstr::BStr, ext::ObjectIdExt, ObjectDetached, Tag};
 
 impl<'repo> Tag<'repo> {
     /// Decode the entire tag object and return it for accessing all tag information.
@@ -23,6 +23,25 @@ impl<'repo> Tag<'repo> {
     pub fn tagger(&self) -> Result<Option<gix_actor::SignatureRef<'_>>, gix_object::decode::Error> {
         gix_object::TagRefIter::from_bytes(&self.data).tagger()
     }
+
+    /// Extracts the signature and the data that was used to create the signature, or `None` if it wasn't signed.
+    pub fn signature(&self) -> Result<Option<(&BStr, &BStr)>, gix_object::decode::Error> {
+        gix_object::TagRefIter::signature(&self.data)
+    }
+
+    /// Verify the signature of this tag with `keyring`, or return `None` if it isn't signed.
+    ///
+    /// Note that the returned [verification](crate::keyring::Verification) must be inspected to learn whether the signature
+    /// is good and trusted enough.
+    pub fn verify_signature(
+        &self,
+        keyring: &dyn crate::keyring::Keyring,
+    ) -> Result<Option<crate::keyring::Verification>, crate::keyring::verify::Error> {
+        let Some((signature, payload)) = self.signature()? else {
+            return Ok(None);
+        };
+        crate::keyring::verify(keyring, signature, payload, self.id.kind()).map(Some)
+    }
 }
 
 /// Remove Lifetime
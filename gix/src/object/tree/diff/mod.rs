@@ -169,6 +169,17 @@ pub struct Stats {
     pub files_changed: u64,
 }
 
+/// A summary of the files changed in a diff, along with the amount of lines changed in each of them, typically
+/// for display similar to `git diff --stat`, `git diff --numstat` or `git diff --shortstat`.
+#[derive(Default, Debug, Clone, PartialEq)]
+pub struct Summary {
+    /// All changed files, sorted by their location.
+    pub files: Vec<summary::File>,
+}
+
+///
+pub mod summary;
+
 ///
 pub mod stats {
     /// The error returned by [`stats()`](super::Platform::stats()).
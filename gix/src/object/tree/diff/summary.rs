@@ -0,0 +1,426 @@
+use std::io::Write;
+
+use gix_diff::tree_with_rewrites::Change as ChangeDetached;
+
+use super::{Stats, Summary};
+use crate::{
+    bstr::{BStr, BString, ByteSlice},
+    Repository,
+};
+
+/// The error returned by [`Summary::from_changes()`].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+#[allow(clippy::large_enum_variant)]
+pub enum Error {
+    #[error(transparent)]
+    SetResource(#[from] crate::object::blob::diff::init::Error),
+    #[error(transparent)]
+    PrepareDiff(#[from] gix_diff::blob::platform::prepare_diff::Error),
+    #[error(transparent)]
+    FindHeader(#[from] crate::object::find::existing::Error),
+    #[error(transparent)]
+    DiffAlgorithm(#[from] crate::config::diff::algorithm::Error),
+}
+
+/// The summary of a single file that was added, removed, modified, renamed or copied.
+#[derive(Debug, Clone, PartialEq)]
+pub struct File {
+    /// The location of the file after the change, or the location of a deleted file.
+    pub location: BString,
+    /// Information about the source of the file if it was renamed or copied.
+    pub rewrite: Option<Rewrite>,
+    /// The mode of the file before the change, or `None` if it was added.
+    pub previous_mode: Option<gix_object::tree::EntryMode>,
+    /// The mode of the file after the change, or `None` if it was deleted.
+    pub mode: Option<gix_object::tree::EntryMode>,
+    /// How the content of the file changed.
+    pub content: Content,
+}
+
+/// Describe how the content of a [`File`] changed.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum Content {
+    /// The file is text, and lines were added and removed.
+    Lines(Lines),
+    /// The file is considered binary on either side, so only its size in bytes is known, with `0` for a side
+    /// that didn't exist.
+    Binary {
+        /// The size of the file before the change.
+        before: u64,
+        /// The size of the file after the change.
+        after: u64,
+    },
+}
+
+impl Content {
+    /// Return the changed lines, or `None` if the file is binary.
+    pub fn lines(&self) -> Option<Lines> {
+        match self {
+            Content::Lines(lines) => Some(*lines),
+            Content::Binary { .. } => None,
+        }
+    }
+}
+
+/// Describe where a renamed or copied [`File`] came from.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Rewrite {
+    /// The location of the source of the rename or copy.
+    pub source_location: BString,
+    /// How similar the file is to its source, from `0.0` to `1.0` for identical files.
+    /// Use [`similarity_percent()`](Self::similarity_percent()) to display it like `git` does.
+    pub similarity: f32,
+    /// If `true`, the source was copied, otherwise it was renamed.
+    pub copy: bool,
+}
+
+impl Rewrite {
+    /// Return the [similarity](Self::similarity) as percentage, rounded down like `git` does.
+    pub fn similarity_percent(&self) -> u8 {
+        (self.similarity.clamp(0.0, 1.0) * 100.0) as u8
+    }
+}
+
+/// The amount of lines that were added and removed in a single [`File`].
+#[derive(Default, Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
+pub struct Lines {
+    /// The amount of added lines.
+    pub added: u32,
+    /// The amount of removed lines.
+    pub removed: u32,
+}
+
+/// Initialization
+impl Summary {
+    /// Summarize `changes`, typically obtained with [`Repository::diff_tree_to_tree()`], by counting the lines changed in
+    /// each file with `resource_cache`, obtained with [`Repository::diff_resource_cache()`] for example.
+    ///
+    /// Changes to trees are skipped as only the files they contain are summarized, and a file that replaced a tree or was
+    /// replaced by one is summarized as addition or deletion. Like `git`, submodules are summarized as if they were a file
+    /// containing `Subproject commit <id>`. `repo` is used to access the objects of both sides of the diff.
+    /// Files are sorted by their location, like `git` does.
+    pub fn from_changes(
+        repo: &Repository,
+        changes: &[ChangeDetached],
+        resource_cache: &mut gix_diff::blob::Platform,
+    ) -> Result<Self, Error> {
+        let mut files = Vec::with_capacity(changes.len());
+        for change in changes {
+            let (previous, current) = match change {
+                ChangeDetached::Addition { entry_mode, id, .. } => (None, Some((*entry_mode, *id))),
+                ChangeDetached::Deletion { entry_mode, id, .. } => (Some((*entry_mode, *id)), None),
+                ChangeDetached::Modification {
+                    previous_entry_mode,
+                    previous_id,
+                    entry_mode,
+                    id,
+                    ..
+                } => (Some((*previous_entry_mode, *previous_id)), Some((*entry_mode, *id))),
+                ChangeDetached::Rewrite {
+                    source_entry_mode,
+                    source_id,
+                    entry_mode,
+                    id,
+                    ..
+                } => (Some((*source_entry_mode, *source_id)), Some((*entry_mode, *id))),
+            };
+            let previous = previous.filter(|(mode, _)| !mode.is_tree());
+            let current = current.filter(|(mode, _)| !mode.is_tree());
+            if previous.is_none() && current.is_none() {
+                continue;
+            }
+
+            let (location, rewrite) = match change {
+                ChangeDetached::Addition { location, .. }
+                | ChangeDetached::Deletion { location, .. }
+                | ChangeDetached::Modification { location, .. } => (location.clone(), None),
+                ChangeDetached::Rewrite {
+                    source_location,
+                    location,
+                    diff,
+                    copy,
+                    ..
+                } => (
+                    location.clone(),
+                    Some(Rewrite {
+                        source_location: source_location.clone(),
+                        similarity: diff.map_or(1.0, |diff| diff.similarity),
+                        copy: *copy,
+                    }),
+                ),
+            };
+            let source_location = rewrite
+                .as_ref()
+                .map_or(location.as_ref(), |r| r.source_location.as_ref());
+            let content = if previous.map_or(false, |(mode, _)| mode.is_commit())
+                || current.map_or(false, |(mode, _)| mode.is_commit())
+            {
+                submodule_line_counts(repo, previous, current)?
+            } else {
+                let kind = previous
+                    .or(current)
+                    .map(|(mode, _)| mode.kind())
+                    .expect("one side is set");
+                for (side, location, resource_kind) in [
+                    (previous, source_location, gix_diff::blob::ResourceKind::OldOrSource),
+                    (
+                        current,
+                        location.as_ref(),
+                        gix_diff::blob::ResourceKind::NewOrDestination,
+                    ),
+                ] {
+                    let (kind, id) = side.map_or((kind, repo.object_hash().null()), |(mode, id)| (mode.kind(), id));
+                    resource_cache.set_resource(id, kind, location, resource_kind, &repo.objects)?;
+                }
+                let counts = crate::object::blob::diff::Platform {
+                    resource_cache: &mut *resource_cache,
+                }
+                .line_counts()?;
+                resource_cache.clear_resource_cache_keep_allocation();
+                match counts {
+                    Some(counts) => Content::Lines(Lines {
+                        added: counts.insertions,
+                        removed: counts.removals,
+                    }),
+                    None => {
+                        let size =
+                            |side: Option<(gix_object::tree::EntryMode, gix_hash::ObjectId)>| -> Result<u64, Error> {
+                                side.map_or(Ok(0), |(_, id)| Ok(repo.find_header(id)?.size()))
+                            };
+                        Content::Binary {
+                            before: size(previous)?,
+                            after: size(current)?,
+                        }
+                    }
+                }
+            };
+
+            files.push(File {
+                location,
+                rewrite,
+                previous_mode: previous.map(|(mode, _)| mode),
+                mode: current.map(|(mode, _)| mode),
+                content,
+            });
+        }
+        files.sort_by(|a, b| a.location.cmp(&b.location));
+        Ok(Summary { files })
+    }
+}
+
+/// Count the lines changed between `previous` and `current` of which at least one is a submodule, which is represented as
+/// a file containing `Subproject commit <id>` just like `git` does it, and return them.
+fn submodule_line_counts(
+    repo: &Repository,
+    previous: Option<(gix_object::tree::EntryMode, gix_hash::ObjectId)>,
+    current: Option<(gix_object::tree::EntryMode, gix_hash::ObjectId)>,
+) -> Result<Content, Error> {
+    let text = |side: Option<(gix_object::tree::EntryMode, gix_hash::ObjectId)>| -> Result<Vec<u8>, Error> {
+        Ok(match side {
+            None => Vec::new(),
+            Some((mode, id)) if mode.is_commit() => format!("Subproject commit {id}\n").into_bytes(),
+            Some((_, id)) => repo.find_object(id)?.detach().data,
+        })
+    };
+    let (before, after) = (text(previous)?, text(current)?);
+    let input = gix_diff::blob::intern::InternedInput::new(
+        gix_diff::blob::sources::byte_lines_with_terminator(&before),
+        gix_diff::blob::sources::byte_lines_with_terminator(&after),
+    );
+    let counts = gix_diff::blob::diff(repo.diff_algorithm()?, &input, gix_diff::blob::sink::Counter::default());
+    Ok(Content::Lines(Lines {
+        added: counts.insertions,
+        removed: counts.removals,
+    }))
+}
+
+/// Access
+impl Summary {
+    /// Return the totals of all files, with binary files counting as changed files without any changed lines.
+    pub fn totals(&self) -> Stats {
+        self.files.iter().fold(Stats::default(), |mut acc, file| {
+            let lines = file.content.lines().unwrap_or_default();
+            acc.files_changed += 1;
+            acc.lines_added += u64::from(lines.added);
+            acc.lines_removed += u64::from(lines.removed);
+            acc
+        })
+    }
+}
+
+/// Formatting
+impl Summary {
+    /// Write one line per file with the amount of added and removed lines, separated by tabs, like `git diff --numstat`.
+    ///
+    /// Binary files are shown with `-` instead of line counts.
+    pub fn write_numstat(&self, mut out: impl Write) -> std::io::Result<()> {
+        for file in &self.files {
+            match file.content {
+                Content::Lines(Lines { added, removed }) => write!(out, "{added}\t{removed}\t")?,
+                Content::Binary { .. } => write!(out, "-\t-\t")?,
+            }
+            writeln!(out, "{}", file.display_location())?;
+        }
+        Ok(())
+    }
+
+    /// Write one line per file with the amount of changed lines and a graph of `+` and `-` characters, followed by
+    /// the [totals](Self::write_shortstat()), like `git diff --stat`.
+    ///
+    /// The graph is scaled so that lines don't exceed `width` characters, if possible.
+    pub fn write_stat(&self, mut out: impl Write, width: usize) -> std::io::Result<()> {
+        let names: Vec<_> = self.files.iter().map(File::display_location).collect();
+        let name_width = names.iter().map(|name| name.chars().count()).max().unwrap_or_default();
+        let max_change = self
+            .files
+            .iter()
+            .filter_map(|file| {
+                file.content
+                    .lines()
+                    .map(|lines| u64::from(lines.added) + u64::from(lines.removed))
+            })
+            .max()
+            .unwrap_or_default();
+        let has_binary_files = self.files.iter().any(|file| file.content.lines().is_none());
+        let count_width = max_change
+            .to_string()
+            .len()
+            .max(if has_binary_files { "Bin".len() } else { 0 });
+        // Leave room for ` `, ` | ` and the space before the graph.
+        let graph_width = width.saturating_sub(name_width + count_width + 5).max(10) as u64;
+
+        let scale = |value: u64| {
+            if max_change <= graph_width || value == 0 {
+                value
+            } else {
+                1 + value * (graph_width - 1) / max_change
+            }
+        };
+        for (file, name) in self.files.iter().zip(names) {
+            let padding = name_width - name.chars().count();
+            write!(out, " {name}{:padding$} | ", "")?;
+            match file.content {
+                Content::Lines(Lines { added, removed }) => {
+                    let (added, removed) = (u64::from(added), u64::from(removed));
+                    // Like `git`, show both kinds of changes if there are any, and scale the smaller one
+                    // so the larger one absorbs rounding errors.
+                    let mut graph = scale(added + removed);
+                    if graph < 2 && added > 0 && removed > 0 {
+                        graph = 2;
+                    }
+                    let (added_width, removed_width) = if added < removed {
+                        let added = scale(added);
+                        (added, graph - added)
+                    } else {
+                        let removed = scale(removed);
+                        (graph - removed, removed)
+                    };
+                    let (added_width, removed_width) = (added_width as usize, removed_width as usize);
+                    write!(out, "{:>count_width$}", added + removed)?;
+                    if graph > 0 {
+                        write!(out, " {:+<added_width$}{:-<removed_width$}", "", "")?;
+                    }
+                    writeln!(out)?;
+                }
+                Content::Binary { before, after } => writeln!(out, "Bin {before} -> {after} bytes")?,
+            }
+        }
+        self.write_shortstat(out)
+    }
+
+    /// Write one line for each file that was created, deleted, renamed or copied, or whose mode changed, like `git diff --summary`.
+    ///
+    /// Renames and copies are shown along with their similarity in percent.
+    pub fn write_summary(&self, mut out: impl Write) -> std::io::Result<()> {
+        for file in &self.files {
+            let mode_change = match (file.previous_mode, file.mode) {
+                (Some(previous), Some(current)) if previous != current => Some((previous, current)),
+                _ => None,
+            };
+            match (&file.rewrite, file.previous_mode, file.mode) {
+                (Some(rewrite), _, _) => {
+                    writeln!(
+                        out,
+                        " {} {} ({}%)",
+                        if rewrite.copy { "copy" } else { "rename" },
+                        file.display_location(),
+                        rewrite.similarity_percent()
+                    )?;
+                    if let Some((previous, current)) = mode_change {
+                        writeln!(out, " mode change {:06o} => {:06o}", previous.0, current.0)?;
+                    }
+                }
+                (None, None, Some(mode)) => writeln!(out, " create mode {:06o} {}", mode.0, file.location)?,
+                (None, Some(mode), None) => writeln!(out, " delete mode {:06o} {}", mode.0, file.location)?,
+                (None, _, _) => {
+                    if let Some((previous, current)) = mode_change {
+                        writeln!(
+                            out,
+                            " mode change {:06o} => {:06o} {}",
+                            previous.0, current.0, file.location
+                        )?;
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Write a single line with the amount of changed files, added and removed lines, like `git diff --shortstat`.
+    pub fn write_shortstat(&self, mut out: impl Write) -> std::io::Result<()> {
+        let Stats {
+            lines_added,
+            lines_removed,
+            files_changed,
+        } = self.totals();
+        let plural = |count: u64| if count == 1 { "" } else { "s" };
+        write!(out, " {files_changed} file{} changed", plural(files_changed))?;
+        if lines_added > 0 || lines_removed == 0 {
+            write!(out, ", {lines_added} insertion{}(+)", plural(lines_added))?;
+        }
+        if lines_removed > 0 || lines_added == 0 {
+            write!(out, ", {lines_removed} deletion{}(-)", plural(lines_removed))?;
+        }
+        writeln!(out)
+    }
+}
+
+impl File {
+    /// Return the location of this file for display, with renames and copies shown as `dir/{old => new}` similar to `git`,
+    /// where the common leading and trailing path components of both locations are only shown once.
+    pub fn display_location(&self) -> BString {
+        let Some(rewrite) = &self.rewrite else {
+            return self.location.clone();
+        };
+        let (source, dest): (&BStr, &BStr) = (rewrite.source_location.as_ref(), self.location.as_ref());
+        let prefix_len = source
+            .iter()
+            .zip(dest.iter())
+            .take_while(|(a, b)| a == b)
+            .enumerate()
+            .filter_map(|(idx, (byte, _))| (*byte == b'/').then_some(idx + 1))
+            .last()
+            .unwrap_or_default();
+        let suffix_len = source[prefix_len..]
+            .iter()
+            .rev()
+            .zip(dest[prefix_len..].iter().rev())
+            .take_while(|(a, b)| a == b)
+            .enumerate()
+            .filter_map(|(idx, (byte, _))| (*byte == b'/').then_some(idx + 1))
+            .last()
+            .unwrap_or_default();
+        if prefix_len == 0 && suffix_len == 0 {
+            return format!("{source} => {dest}").into();
+        }
+        let mut out = BString::from(&source[..prefix_len]);
+        out.extend_from_slice(b"{");
+        out.extend_from_slice(&source[prefix_len..source.len() - suffix_len]);
+        out.extend_from_slice(b" => ");
+        out.extend_from_slice(&dest[prefix_len..dest.len() - suffix_len]);
+        out.extend_from_slice(b"}");
+        out.extend_from_slice(&source[source.len() - suffix_len..]);
+        out
+    }
+}
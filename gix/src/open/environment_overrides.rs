@@ -0,0 +1,115 @@
+use std::{ffi::OsString, path::Path};
+
+use super::EnvironmentOverrides;
+use crate::{
+    bstr::BString,
+    config::tree::{gitoxide, Core, Key},
+};
+
+/// Lifecycle
+impl EnvironmentOverrides {
+    /// Obtain all overrides from the environment of the current process.
+    pub fn from_env() -> Self {
+        Self::from_vars(|name| std::env::var_os(name))
+    }
+
+    /// Obtain all overrides by calling `var` with the name of an environment variable like `GIT_DIR`,
+    /// which returns its value if it is set.
+    ///
+    /// Empty values are treated like unset ones.
+    pub fn from_vars(mut var: impl FnMut(&str) -> Option<OsString>) -> Self {
+        let mut var = |name: &str| var(name).filter(|value| !value.is_empty());
+        let mut path = |name: &str| var(name).map(Into::into);
+        let git_dir = path("GIT_DIR");
+        let worktree_dir = path(Core::WORKTREE.the_environment_override());
+        let common_dir = path("GIT_COMMON_DIR");
+        let index_file = path("GIT_INDEX_FILE");
+        let object_dir = path("GIT_OBJECT_DIRECTORY");
+        let shallow_file = path(gitoxide::Core::SHALLOW_FILE.the_environment_override());
+        let graft_file = path("GIT_GRAFT_FILE");
+        let alternate_object_dirs = var("GIT_ALTERNATE_OBJECT_DIRECTORIES")
+            .map(|dirs| {
+                std::env::split_paths(&dirs)
+                    .filter(|dir| !dir.as_os_str().is_empty())
+                    .collect()
+            })
+            .unwrap_or_default();
+        let mut string = |name: &str| var(name).and_then(|value| gix_path::os_string_into_bstring(value).ok());
+        let namespace = string(gitoxide::Core::REFS_NAMESPACE.the_environment_override());
+        let replace_ref_base = string(gitoxide::Objects::REPLACE_REF_BASE.the_environment_override());
+        let discovery_across_filesystem = string("GIT_DISCOVERY_ACROSS_FILESYSTEM")
+            .and_then(|value| gix_config::Boolean::try_from(value.as_ref()).ok())
+            .map(Into::into);
+        let no_replace_objects = var(Core::USE_REPLACE_REFS.the_environment_override()).is_some();
+        let ceiling_dirs = var("GIT_CEILING_DIRECTORIES")
+            .map(|dirs| gix_discover::upwards::parse_ceiling_dirs(&dirs))
+            .unwrap_or_default();
+        EnvironmentOverrides {
+            git_dir,
+            worktree_dir,
+            common_dir,
+            index_file,
+            object_dir,
+            alternate_object_dirs,
+            shallow_file,
+            graft_file,
+            namespace,
+            replace_ref_base,
+            no_replace_objects,
+            ceiling_dirs,
+            discovery_across_filesystem,
+        }
+    }
+}
+
+/// Access
+impl EnvironmentOverrides {
+    /// Return `true` if no override is set.
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+impl EnvironmentOverrides {
+    /// Turn all relative paths into absolute ones by joining them with `current_dir`.
+    pub(crate) fn make_absolute(&mut self, current_dir: &Path) {
+        for path in [
+            &mut self.git_dir,
+            &mut self.worktree_dir,
+            &mut self.common_dir,
+            &mut self.index_file,
+            &mut self.object_dir,
+            &mut self.shallow_file,
+            &mut self.graft_file,
+        ]
+        .into_iter()
+        .flatten()
+        .chain(self.alternate_object_dirs.iter_mut())
+        {
+            if path.is_relative() {
+                *path = current_dir.join(&*path);
+            }
+        }
+    }
+}
+
+impl EnvironmentOverrides {
+    /// Return the value of the environment variable `name` as it should be written into the configuration, if it is overridden.
+    ///
+    /// This is only implemented for the variables that are otherwise mapped to configuration values.
+    pub(crate) fn config_value(&self, name: &str) -> Option<BString> {
+        if name == gitoxide::Core::SHALLOW_FILE.the_environment_override() {
+            self.shallow_file
+                .as_deref()
+                .map(|path| gix_path::into_bstr(path).into_owned())
+        } else if name == gitoxide::Core::REFS_NAMESPACE.the_environment_override() {
+            self.namespace.clone()
+        } else if name == gitoxide::Objects::REPLACE_REF_BASE.the_environment_override() {
+            self.replace_ref_base.clone()
+        } else if name == Core::USE_REPLACE_REFS.the_environment_override() {
+            self.no_replace_objects.then(|| "true".into())
+        } else {
+            None
+        }
+    }
+}
@@ -20,7 +20,8 @@ pub struct Permissions {
 /// The environment variables `GIT_REPLACE_REF_BASE` and `GIT_NO_REPLACE_OBJECTS` are mapped to `gitoxide.objects.replaceRefBase`
 /// and `gitoxide.objects.noReplace` respectively and then interpreted exactly as their environment variable counterparts.
 ///
-/// Use [Permissions] to control which environment variables can be read, and config-overrides to control these values programmatically.
+/// Use [Permissions] to control which environment variables can be read, and config-overrides or
+/// [environment overrides][EnvironmentOverrides] to control these values programmatically.
 #[derive(Clone)]
 pub struct Options {
     pub(crate) object_store_slots: gix_odb::store::init::Slots,
@@ -35,8 +36,89 @@ pub struct Options {
     pub(crate) api_config_overrides: Vec<BString>,
     pub(crate) cli_config_overrides: Vec<BString>,
     pub(crate) open_path_as_is: bool,
+    pub(crate) read_only: bool,
     /// Internal to pass an already obtained CWD on to where it may also be used. This avoids the CWD being queried more than once per repo.
     pub(crate) current_dir: Option<PathBuf>,
+    /// Locations of repository resources that override the ones that would otherwise be used, boxed to keep `Options` small.
+    pub(crate) environment_overrides: Option<Box<EnvironmentOverrides>>,
+}
+
+/// Locations of repository resources and other repository settings as they are typically set by `GIT_*` environment variables,
+/// to be used instead of the values that would otherwise be derived from the `.git` directory or its configuration.
+///
+/// Use [`EnvironmentOverrides::from_env()`] to obtain them from the environment of the current process, or
+/// [`EnvironmentOverrides::from_vars()`] to obtain them from any other source, for instance to emulate many processes
+/// with different environments within the same process.
+///
+/// The supported environment variables are:
+///
+/// - `GIT_DIR`
+/// - `GIT_WORK_TREE`
+/// - `GIT_COMMON_DIR`
+/// - `GIT_INDEX_FILE`
+/// - `GIT_OBJECT_DIRECTORY`
+/// - `GIT_ALTERNATE_OBJECT_DIRECTORIES`
+/// - `GIT_SHALLOW_FILE`
+/// - `GIT_GRAFT_FILE`
+/// - `GIT_NAMESPACE`
+/// - `GIT_REPLACE_REF_BASE`
+/// - `GIT_NO_REPLACE_OBJECTS`
+/// - `GIT_CEILING_DIRECTORIES`
+/// - `GIT_DISCOVERY_ACROSS_FILESYSTEM`
+///
+/// Relative paths are interpreted relative to the current working directory when opening a repository.
+///
+/// Note that all other environment variables understood by `gitoxide` are mapped to configuration values,
+/// and can thus be controlled with [config overrides][Options::config_overrides()] and [permissions][Permissions::env].
+#[derive(Default, Debug, Clone, PartialEq, Eq)]
+pub struct EnvironmentOverrides {
+    /// The `.git` directory to open instead of the one that was passed or discovered, from `GIT_DIR`.
+    ///
+    /// It's only used by [`ThreadSafeRepository::open_with_environment_overrides()`][crate::ThreadSafeRepository::open_with_environment_overrides()]
+    /// and related methods.
+    pub git_dir: Option<PathBuf>,
+    /// The worktree directory to use if no worktree was found otherwise, from `GIT_WORK_TREE`.
+    pub worktree_dir: Option<PathBuf>,
+    /// The directory containing objects, references and configuration shared among all worktrees, from `GIT_COMMON_DIR`.
+    ///
+    /// If set, the `commondir` file in the `.git` directory is ignored.
+    pub common_dir: Option<PathBuf>,
+    /// The index file to use instead of `$GIT_DIR/index`, from `GIT_INDEX_FILE`.
+    pub index_file: Option<PathBuf>,
+    /// The directory to read and write objects from instead of `$GIT_COMMON_DIR/objects`, from `GIT_OBJECT_DIRECTORY`.
+    pub object_dir: Option<PathBuf>,
+    /// Object directories to use as alternates before the ones configured in the object directory,
+    /// from `GIT_ALTERNATE_OBJECT_DIRECTORIES`.
+    pub alternate_object_dirs: Vec<PathBuf>,
+    /// The file listing the commits whose parents are missing in a shallow repository instead of `$GIT_COMMON_DIR/shallow`,
+    /// from `GIT_SHALLOW_FILE`.
+    ///
+    /// It takes precedence over `gitoxide.core.shallowFile`.
+    pub shallow_file: Option<PathBuf>,
+    /// The file listing graft points instead of `$GIT_COMMON_DIR/info/grafts`, from `GIT_GRAFT_FILE`.
+    pub graft_file: Option<PathBuf>,
+    /// The namespace to put all references into, like `foo` or `foo/bar`, from `GIT_NAMESPACE`.
+    ///
+    /// It takes precedence over `gitoxide.core.refsNamespace`.
+    pub namespace: Option<BString>,
+    /// The prefix of references that point to replacement objects instead of `refs/replace/`, from `GIT_REPLACE_REF_BASE`.
+    ///
+    /// It takes precedence over `gitoxide.objects.replaceRefBase`.
+    pub replace_ref_base: Option<BString>,
+    /// If `true`, don't use replacement objects at all, from `GIT_NO_REPLACE_OBJECTS`.
+    ///
+    /// It takes precedence over `core.useReplaceRefs`.
+    pub no_replace_objects: bool,
+    /// Directories to stop at when discovering a repository, from `GIT_CEILING_DIRECTORIES`.
+    ///
+    /// It's only used by [`ThreadSafeRepository::discover_with_environment_overrides()`][crate::ThreadSafeRepository::discover_with_environment_overrides()]
+    /// and related methods, which set [`ceiling_dirs`](gix_discover::upwards::Options::ceiling_dirs) if it's not empty.
+    pub ceiling_dirs: Vec<PathBuf>,
+    /// Whether or not discovery may cross filesystem boundaries, from `GIT_DISCOVERY_ACROSS_FILESYSTEM`.
+    ///
+    /// It's only used by [`ThreadSafeRepository::discover_with_environment_overrides()`][crate::ThreadSafeRepository::discover_with_environment_overrides()]
+    /// and related methods, which set [`cross_fs`](gix_discover::upwards::Options::cross_fs) if it's set.
+    pub discovery_across_filesystem: Option<bool>,
 }
 
 /// The error returned by [`crate::open()`].
@@ -58,6 +140,7 @@ pub enum Error {
     EnvironmentAccessDenied(#[from] gix_sec::permission::Error<std::path::PathBuf>),
 }
 
+mod environment_overrides;
 mod options;
 pub mod permissions;
 mod repository;
@@ -1,6 +1,6 @@
 use std::path::PathBuf;
 
-use super::{Error, Options};
+use super::{EnvironmentOverrides, Error, Options};
 use crate::{bstr::BString, config, open::Permissions, ThreadSafeRepository};
 
 impl Default for Options {
@@ -14,9 +14,11 @@ impl Default for Options {
             lenient_config: true,
             bail_if_untrusted: false,
             open_path_as_is: false,
+            read_only: false,
             api_config_overrides: Vec::new(),
             cli_config_overrides: Vec::new(),
             current_dir: None,
+            environment_overrides: None,
         }
     }
 }
@@ -70,6 +72,17 @@ impl Options {
         self
     }
 
+    /// Use `overrides` for the locations of the index, the object database, the common directory and the worktree,
+    /// instead of the ones derived from the `.git` directory, just like `git` does when the respective `GIT_*`
+    /// environment variables are set.
+    ///
+    /// This allows to programmatically emulate environments that differ from the one of the current process.
+    /// Empty `overrides` are the same as not setting any.
+    pub fn environment_overrides(mut self, overrides: EnvironmentOverrides) -> Self {
+        self.environment_overrides = (!overrides.is_empty()).then(|| Box::new(overrides));
+        self
+    }
+
     /// If `true`, default `false`, we will not modify the incoming path to open to assure it is a `.git` directory.
     ///
     /// If `false`, we will try to open the input directory as is, even though it doesn't appear to be a `git` repository
@@ -79,6 +92,19 @@ impl Options {
         self
     }
 
+    /// If `true`, default `false`, the repository will be opened in read-only mode which guarantees that `gitoxide` doesn't
+    /// write to it.
+    ///
+    /// This is useful for backup or scanning tools that read repositories which are in use by others at the same time.
+    /// All operations that would write objects, references, reference logs, the index or any other file in the repository
+    /// fail with a [read-only error](crate::repository::read_only::Error) instead, and reference logs are disabled
+    /// as well. Note that direct access to the [object database](crate::Repository::objects) or the
+    /// [reference store](crate::Repository::refs) isn't affected.
+    pub fn read_only(mut self, toggle: bool) -> Self {
+        self.read_only = toggle;
+        self
+    }
+
     /// Set the trust level of the `.git` directory we are about to open.
     ///
     /// This can be set manually to force trust even though otherwise it might
@@ -167,9 +193,11 @@ impl gix_sec::trust::DefaultForLevel for Options {
                 bail_if_untrusted: false,
                 lenient_config: true,
                 open_path_as_is: false,
+                read_only: false,
                 api_config_overrides: Vec::new(),
                 cli_config_overrides: Vec::new(),
                 current_dir: None,
+                environment_overrides: None,
             },
             gix_sec::Trust::Reduced => Options {
                 object_store_slots: gix_odb::store::init::Slots::Given(32), // limit resource usage
@@ -179,10 +207,12 @@ impl gix_sec::trust::DefaultForLevel for Options {
                 bail_if_untrusted: false,
                 lenient_config: true,
                 open_path_as_is: false,
+                read_only: false,
                 lossy_config: None,
                 api_config_overrides: Vec::new(),
                 cli_config_overrides: Vec::new(),
                 current_dir: None,
+                environment_overrides: None,
             },
         }
     }
@@ -3,7 +3,7 @@ use gix_features::threading::OwnShared;
 use std::ffi::OsStr;
 use std::{borrow::Cow, path::PathBuf};
 
-use super::{Error, Options};
+use super::{EnvironmentOverrides, Error, Options};
 use crate::{
     config,
     config::{
@@ -14,32 +14,6 @@ use crate::{
     ThreadSafeRepository,
 };
 
-#[derive(Default, Clone)]
-pub(crate) struct EnvironmentOverrides {
-    /// An override of the worktree typically from the environment, and overrides even worktree dirs set as parameter.
-    ///
-    /// This emulates the way git handles this override.
-    worktree_dir: Option<PathBuf>,
-    /// An override for the .git directory, typically from the environment.
-    ///
-    /// If set, the passed in `git_dir` parameter will be ignored in favor of this one.
-    git_dir: Option<PathBuf>,
-}
-
-impl EnvironmentOverrides {
-    fn from_env() -> Result<Self, gix_sec::permission::Error<std::path::PathBuf>> {
-        let mut worktree_dir = None;
-        if let Some(path) = std::env::var_os(Core::WORKTREE.the_environment_override()) {
-            worktree_dir = PathBuf::from(path).into();
-        }
-        let mut git_dir = None;
-        if let Some(path) = std::env::var_os("GIT_DIR") {
-            git_dir = PathBuf::from(path).into();
-        }
-        Ok(EnvironmentOverrides { worktree_dir, git_dir })
-    }
-}
-
 impl ThreadSafeRepository {
     /// Open a git repository at the given `path`, possibly expanding it to `path/.git` if `path` is a work tree dir.
     pub fn open(path: impl Into<PathBuf>) -> Result<Self, Error> {
@@ -114,10 +88,22 @@ impl ThreadSafeRepository {
     pub fn open_with_environment_overrides(
         fallback_directory: impl Into<PathBuf>,
         trust_map: gix_sec::trust::Mapping<Options>,
+    ) -> Result<Self, Error> {
+        Self::open_with_custom_environment_overrides(fallback_directory, EnvironmentOverrides::from_env(), trust_map)
+    }
+
+    /// Like [`open_with_environment_overrides()`][Self::open_with_environment_overrides()], but use the given `overrides`
+    /// instead of reading them from the environment of the current process.
+    ///
+    /// This allows to emulate a process with a different environment, for instance to run many of them within the same process.
+    /// Note that `overrides` replace the [environment overrides][Options::environment_overrides()] of the options in `trust_map`.
+    pub fn open_with_custom_environment_overrides(
+        fallback_directory: impl Into<PathBuf>,
+        overrides: EnvironmentOverrides,
+        trust_map: gix_sec::trust::Mapping<Options>,
     ) -> Result<Self, Error> {
         let _span = gix_trace::coarse!("ThreadSafeRepository::open_with_environment_overrides()");
-        let overrides = EnvironmentOverrides::from_env()?;
-        let (path, path_kind): (PathBuf, _) = match overrides.git_dir {
+        let (path, path_kind): (PathBuf, _) = match overrides.git_dir.clone() {
             Some(git_dir) => gix_discover::is_git(&git_dir)
                 .map_err(|err| Error::NotARepository {
                     source: err,
@@ -140,10 +126,14 @@ impl ThreadSafeRepository {
         let (git_dir, worktree_dir) = gix_discover::repository::Path::from_dot_git_dir(path, path_kind, &cwd)
             .expect("we have sanitized path with is_git()")
             .into_repository_and_work_tree_directories();
-        let worktree_dir = worktree_dir.or(overrides.worktree_dir);
 
         let git_dir_trust = gix_sec::Trust::from_path_ownership(&git_dir)?;
-        let mut options = trust_map.into_value_by_level(git_dir_trust);
+        let mut options = trust_map
+            .into_value_by_level(git_dir_trust)
+            .environment_overrides(overrides);
+        if options.git_dir_trust.is_none() {
+            options.git_dir_trust = git_dir_trust.into();
+        }
         options.current_dir = Some(cwd);
         ThreadSafeRepository::open_from_paths(git_dir, worktree_dir, options)
     }
@@ -154,6 +144,14 @@ impl ThreadSafeRepository {
         mut options: Options,
     ) -> Result<Self, Error> {
         let _span = gix_trace::detail!("open_from_paths()");
+        if let Some(overrides) = options.environment_overrides.as_deref_mut() {
+            overrides.make_absolute(
+                options
+                    .current_dir
+                    .as_deref()
+                    .expect("BUG: current_dir must be set by caller"),
+            );
+        }
         let Options {
             git_dir_trust,
             object_store_slots,
@@ -162,6 +160,7 @@ impl ThreadSafeRepository {
             lenient_config,
             bail_if_untrusted,
             open_path_as_is: _,
+            read_only,
             permissions:
                 Permissions {
                     ref env,
@@ -171,12 +170,20 @@ impl ThreadSafeRepository {
             ref api_config_overrides,
             ref cli_config_overrides,
             ref mut current_dir,
+            ref environment_overrides,
         } = options;
+        let environment_overrides = environment_overrides.as_deref();
         let git_dir_trust = git_dir_trust.expect("trust must be determined by now");
 
-        let mut common_dir = gix_discover::path::from_plain_file(git_dir.join("commondir").as_ref())
-            .transpose()?
-            .map(|cd| git_dir.join(cd));
+        let mut common_dir = match environment_overrides.and_then(|o| o.common_dir.clone()) {
+            Some(common_dir) => Some(common_dir),
+            None => gix_discover::path::from_plain_file(git_dir.join("commondir").as_ref())
+                .transpose()?
+                .map(|cd| git_dir.join(cd)),
+        };
+        if worktree_dir.is_none() {
+            worktree_dir = environment_overrides.and_then(|o| o.worktree_dir.clone());
+        }
         let repo_config = config::cache::StageOne::new(
             common_dir.as_deref().unwrap_or(&git_dir),
             git_dir.as_ref(),
@@ -218,6 +225,10 @@ impl ThreadSafeRepository {
                 object_hash,
                 precompose_unicode: repo_config.precompose_unicode,
                 prohibit_windows_device_names: repo_config.protect_windows,
+                ignore_case: repo_config.ignore_case,
+                // set once the full configuration is known.
+                fsync: Default::default(),
+                format: repo_config.ref_format,
             };
             match &common_dir {
                 Some(common_dir) => {
@@ -244,6 +255,7 @@ impl ThreadSafeRepository {
             lenient_config,
             api_config_overrides,
             cli_config_overrides,
+            environment_overrides,
         )?;
 
         if bail_if_untrusted && git_dir_trust != gix_sec::Trust::Full {
@@ -332,8 +344,13 @@ impl ThreadSafeRepository {
             }
         }
 
-        refs.write_reflog = config::cache::util::reflog_or_default(config.reflog, worktree_dir.is_some());
+        refs.write_reflog = if read_only {
+            gix_ref::store::WriteReflog::Disable
+        } else {
+            config::cache::util::reflog_or_default(config.reflog, worktree_dir.is_some())
+        };
         refs.namespace.clone_from(&config.refs_namespace);
+        refs.fsync = config.fsync.references;
         let replacements = replacement_objects_refs_prefix(&config.resolved, lenient_config, filter_config_section)?
             .and_then(|prefix| {
                 let _span = gix_trace::detail!("find replacement objects");
@@ -355,13 +372,19 @@ impl ThreadSafeRepository {
 
         Ok(ThreadSafeRepository {
             objects: OwnShared::new(gix_odb::Store::at_opts(
-                common_dir_ref.join("objects"),
+                environment_overrides
+                    .and_then(|o| o.object_dir.clone())
+                    .unwrap_or_else(|| common_dir_ref.join("objects")),
                 &mut replacements.into_iter(),
                 gix_odb::store::init::Options {
                     slots: object_store_slots,
                     object_hash: config.object_hash,
                     use_multi_pack_index: config.use_multi_pack_index,
                     current_dir: current_dir.to_owned().into(),
+                    additional_alternates: environment_overrides
+                        .map(|o| o.alternate_object_dirs.clone())
+                        .unwrap_or_default(),
+                    fsync: config.fsync.loose_objects,
                 },
             )?),
             common_dir,
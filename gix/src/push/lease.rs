@@ -0,0 +1,86 @@
+use crate::bstr::{BStr, BString, ByteSlice};
+
+/// What the reference on the remote is expected to be for an update to proceed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Expect {
+    /// The reference must have the value of its remote-tracking reference, or must not exist if there is none.
+    ///
+    /// This is what `--force-with-lease` without `<expect>` does.
+    RemoteTracking,
+    /// The reference must point to the given object, or must not exist if it's `None`.
+    Value(Option<gix_hash::ObjectId>),
+}
+
+/// A parsed `--force-with-lease[=<refname>[:<expect>]]` argument.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Spec {
+    /// The name of the reference on the remote to protect, or `None` to protect all updated references.
+    pub name: Option<BString>,
+    /// The revision that the reference on the remote is expected to point to, or `None` to expect the value of its
+    /// remote-tracking reference.
+    ///
+    /// If it's empty, the reference is expected to not exist.
+    pub expect: Option<BString>,
+}
+
+/// Initialization
+impl Spec {
+    /// Parse `value` as it is passed to `--force-with-lease=`, with an empty value being the same as passing no value.
+    pub fn from_bytes(value: &BStr) -> Self {
+        if value.is_empty() {
+            return Spec {
+                name: None,
+                expect: None,
+            };
+        }
+        match value.find_byte(b':') {
+            Some(pos) => Spec {
+                name: Some(value[..pos].into()),
+                expect: Some(value[pos + 1..].into()),
+            },
+            None => Spec {
+                name: Some(value.into()),
+                expect: None,
+            },
+        }
+    }
+}
+
+/// Access
+impl Spec {
+    /// Return `true` if this lease applies to `remote_ref`, the full name of a reference on the remote.
+    ///
+    /// Names are matched like `git` does, so `main` matches `refs/heads/main`.
+    pub fn matches(&self, remote_ref: &gix_ref::FullNameRef) -> bool {
+        let Some(name) = self.name.as_ref() else {
+            return true;
+        };
+        let full_name = remote_ref.as_bstr();
+        ["", "refs/", "refs/tags/", "refs/heads/", "refs/remotes/"]
+            .iter()
+            .any(|prefix| {
+                full_name
+                    .strip_prefix(prefix.as_bytes())
+                    .map_or(false, |rest| rest == name.as_bytes())
+            })
+            || full_name
+                .strip_prefix(b"refs/remotes/")
+                .and_then(|rest| rest.strip_suffix(b"/HEAD"))
+                .map_or(false, |rest| rest == name.as_bytes())
+    }
+}
+
+///
+#[cfg(feature = "revision")]
+pub mod expect {
+    /// The error returned by [Repository::push_lease_expect()](crate::Repository::push_lease_expect()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("Could not resolve the expected value '{spec}' of a lease")]
+        RevParse {
+            spec: crate::bstr::BString,
+            source: crate::revision::spec::parse::single::Error,
+        },
+    }
+}
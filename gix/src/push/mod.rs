@@ -0,0 +1,97 @@
+//! Types for preparing pushes, like the checks performed before a pack is sent.
+
+/// All possible values of `push.default`.
+#[derive(Default, Copy, Clone, PartialOrd, PartialEq, Ord, Eq, Hash, Debug)]
+pub enum Default {
+    /// Do not push anything unless a refspec is provided explicitly.
+    ///
+    /// This is for safety.
+    Nothing,
+    /// Push the current branch to update a remote branch with the same name.
+    Current,
+    /// Push the current branch to the branch it would fetch from and merge with,
+    /// i.e. what is configured in `branch.<name>.merge`, retrievable with
+    /// the `@{upstream}` refspec.
+    Upstream,
+    /// Push the current branch with the same name to the remote.
+    /// This is the same as [`Current`](Default::Current), but fails if
+    /// `branch.<name>.merge` is set to a branch that is named differently.
+    #[default]
+    Simple,
+    /// Push *all* branches to their similarly named counterpart on the remote.
+    Matching,
+}
+
+///
+pub mod lease;
+
+///
+#[cfg(any(feature = "blocking-network-client", feature = "async-network-client"))]
+pub mod check {
+    /// The error returned by [Repository::push_check()](crate::Repository::push_check()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("Could not determine the remote-tracking reference of '{name}'")]
+        RemoteTrackingRefName {
+            name: gix_ref::FullName,
+            source: gix_validate::reference::name::Error,
+        },
+        #[error(transparent)]
+        FindReference(#[from] crate::reference::find::Error),
+        #[error(transparent)]
+        CommitGraph(#[from] crate::repository::commit_graph_if_enabled::Error),
+        #[error(transparent)]
+        Grafts(#[from] crate::shallow::grafts::Error),
+        #[error(transparent)]
+        FindObject(#[from] crate::object::find::existing::Error),
+        #[error(transparent)]
+        FindCommit(#[from] gix_revwalk::graph::get_or_insert_default::Error),
+    }
+}
+
+/// A single update of a reference on the remote, as it is requested by a push.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RefUpdate {
+    /// The full name of the reference on the remote, like `refs/heads/main`.
+    pub remote_ref: gix_ref::FullName,
+    /// The object to set the reference to, or `None` to delete it.
+    pub new: Option<gix_hash::ObjectId>,
+    /// If `true`, the update may lose history on the remote, like with a `+` prefixed refspec or `--force`.
+    pub force: bool,
+    /// If set, the update is only performed if the reference on the remote matches the expectation, like with `--force-with-lease`.
+    ///
+    /// A lease that holds implies `force`.
+    pub lease: Option<lease::Expect>,
+}
+
+/// The outcome of checking a [`RefUpdate`] against the references advertised by the remote, before any pack is sent.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Status {
+    /// The update can be performed.
+    Ok,
+    /// The reference on the remote already has the desired value, so there is nothing to do.
+    UpToDate,
+    /// The lease was broken as the reference on the remote doesn't have the `expected` value, but the `actual` one.
+    ///
+    /// `None` means the reference doesn't exist. This is what `git` reports as `stale info`.
+    RejectedStale {
+        /// The value the reference was expected to have.
+        expected: Option<gix_hash::ObjectId>,
+        /// The value the reference has on the remote.
+        actual: Option<gix_hash::ObjectId>,
+    },
+    /// The reference on the remote points to an object we don't have, so it must be fetched first to know if history would be lost.
+    RejectedFetchFirst,
+    /// The update isn't forced, but would lose history on the remote as it isn't a fast-forward.
+    RejectedNonFastForward,
+    /// The update isn't forced, but would change a tag that already exists on the remote.
+    RejectedAlreadyExists,
+}
+
+impl Status {
+    /// Return `true` if the update was rejected.
+    pub fn is_rejected(&self) -> bool {
+        !matches!(self, Status::Ok | Status::UpToDate)
+    }
+}
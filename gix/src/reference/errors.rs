@@ -17,6 +17,8 @@ pub mod edit {
         LockTimeoutConfiguration(#[from] config::lock_timeout::Error),
         #[error(transparent)]
         ParseCommitterTime(#[from] crate::config::time::Error),
+        #[error(transparent)]
+        ReadOnly(#[from] crate::repository::read_only::Error),
     }
 }
 
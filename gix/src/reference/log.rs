@@ -20,6 +20,141 @@ impl Reference<'_> {
     }
 }
 
+/// The order in which to return reference log entries.
+#[derive(Default, Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Order {
+    /// Start with the first entry that was written.
+    OldestFirst,
+    /// Start with the most recent entry.
+    #[default]
+    NewestFirst,
+}
+
+/// A filter for reference log entries, with all set criteria needing to match for an entry to pass.
+#[derive(Default, Debug, Clone)]
+pub struct Filter {
+    /// If set, the entry must have been written at or after this time.
+    pub since: Option<gix_date::SecondsSinceUnixEpoch>,
+    /// If set, the entry must have been written at or before this time.
+    pub until: Option<gix_date::SecondsSinceUnixEpoch>,
+    /// If set, the message of the entry must match this regular expression.
+    ///
+    /// Without the `revparse-regex` feature, the message must contain it instead.
+    pub message: Option<BString>,
+    /// If set, the entry must have changed the reference from this id.
+    pub previous_oid: Option<gix_hash::ObjectId>,
+    /// If set, the entry must have changed the reference to this id.
+    pub new_oid: Option<gix_hash::ObjectId>,
+}
+
+/// An iterator over reference log entries that pass a [`Filter`].
+pub struct Query<'p> {
+    iter: query::Iter<'p>,
+    matcher: query::Matcher,
+}
+
+impl<'p> Query<'p> {
+    /// Return an iterator over all entries of the reference log accessible through `platform` that pass `filter`,
+    /// in the given `order`, or `None` if there is no reference log.
+    ///
+    /// `platform` is obtained with [`Reference::log_iter()`] or [`Head::log_iter()`](crate::Head::log_iter()), and its buffer
+    /// is used as sliding window when iterating from the most recent entry.
+    /// Either way, the log is streamed and never loaded into memory as a whole, making this suitable for huge logs.
+    pub fn new(
+        platform: &'p mut gix_ref::file::log::iter::Platform<'_, '_>,
+        filter: &Filter,
+        order: Order,
+    ) -> Result<Option<Self>, query::Error> {
+        let matcher = query::Matcher::new(filter)?;
+        let iter = match order {
+            Order::OldestFirst => platform.all_streaming()?.map(query::Iter::Forward),
+            Order::NewestFirst => platform.rev()?.map(query::Iter::Reverse),
+        };
+        Ok(iter.map(|iter| Query { iter, matcher }))
+    }
+}
+
+impl Iterator for Query<'_> {
+    type Item = Result<gix_ref::log::Line, gix_ref::file::log::iter::reverse::Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let line = match &mut self.iter {
+                query::Iter::Forward(iter) => iter.next()?,
+                query::Iter::Reverse(iter) => iter.next()?,
+            };
+            match line {
+                Ok(line) if !self.matcher.matches(&line) => {}
+                res => return Some(res),
+            }
+        }
+    }
+}
+
+///
+pub mod query {
+    use crate::bstr::ByteSlice;
+
+    /// The error returned by [`Query::new()`](super::Query::new()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("Could not open the reference log")]
+        Open(#[from] std::io::Error),
+        #[cfg(feature = "revparse-regex")]
+        #[error(transparent)]
+        InvalidRegex(#[from] regex::Error),
+    }
+
+    pub(super) enum Iter<'p> {
+        Forward(gix_ref::file::log::iter::ForwardStreaming<std::io::BufReader<gix_ref::file::log::iter::Source>>),
+        Reverse(gix_ref::file::log::iter::Reverse<'p, gix_ref::file::log::iter::Source>),
+    }
+
+    pub(super) struct Matcher {
+        filter: super::Filter,
+        #[cfg(feature = "revparse-regex")]
+        regex: Option<regex::bytes::Regex>,
+    }
+
+    impl Matcher {
+        pub(super) fn new(filter: &super::Filter) -> Result<Self, Error> {
+            Ok(Matcher {
+                #[cfg(feature = "revparse-regex")]
+                regex: filter
+                    .message
+                    .as_ref()
+                    .map(|message| regex::bytes::Regex::new(message.to_str_lossy().as_ref()))
+                    .transpose()?,
+                filter: filter.clone(),
+            })
+        }
+
+        pub(super) fn matches(&self, line: &gix_ref::log::Line) -> bool {
+            let filter = &self.filter;
+            let seconds = line.signature.time.seconds;
+            filter.since.map_or(true, |since| seconds >= since)
+                && filter.until.map_or(true, |until| seconds <= until)
+                && filter.previous_oid.map_or(true, |id| id == line.previous_oid)
+                && filter.new_oid.map_or(true, |id| id == line.new_oid)
+                && self.matches_message(line.message.as_bstr())
+        }
+
+        #[cfg(feature = "revparse-regex")]
+        fn matches_message(&self, message: &crate::bstr::BStr) -> bool {
+            self.regex.as_ref().map_or(true, |regex| regex.is_match(message))
+        }
+
+        #[cfg(not(feature = "revparse-regex"))]
+        fn matches_message(&self, message: &crate::bstr::BStr) -> bool {
+            self.filter
+                .message
+                .as_ref()
+                .map_or(true, |needle| message.contains_str(needle))
+        }
+    }
+}
+
 /// Generate a message typical for git commit logs based on the given `operation`, commit `message` and `num_parents` of the commit.
 pub fn message(operation: &str, message: &BStr, num_parents: usize) -> BString {
     let mut out = BString::from(operation);
@@ -1,6 +1,9 @@
 use super::Error;
 use crate::{
-    config::{cache::util::ApplyLeniency, tree::Pack},
+    config::{
+        cache::util::{ApplyLeniency, ApplyLeniencyDefault},
+        tree::{gitoxide, Pack},
+    },
     Repository,
 };
 
@@ -24,3 +27,19 @@ pub fn pack_index_version(repo: &Repository) -> Result<gix_pack::index::Version,
         .with_leniency(repo.options.lenient_config)?
         .unwrap_or(gix_pack::index::Version::V2))
 }
+
+pub fn sha1_flavor(repo: &Repository) -> Result<gix_hash::hasher::Sha1Flavor, Error> {
+    let key = &gitoxide::Core::SHA1_COLLISION_DETECTION;
+    let detect_collisions = repo
+        .config
+        .resolved
+        .boolean(key)
+        .map(|res| key.enrich_error(res).with_lenient_default(repo.options.lenient_config))
+        .transpose()?
+        .unwrap_or(true);
+    Ok(if detect_collisions {
+        gix_hash::hasher::Sha1Flavor::CollisionDetecting
+    } else {
+        gix_hash::hasher::Sha1Flavor::Fast
+    })
+}
@@ -30,6 +30,8 @@ pub enum Error {
     },
     #[error(transparent)]
     ShallowOpen(#[from] crate::shallow::open::Error),
+    #[error(transparent)]
+    Grafts(#[from] crate::shallow::grafts::Error),
     #[error("Server lack feature {feature:?}: {description}")]
     MissingServerFeature {
         feature: &'static str,
@@ -39,12 +41,27 @@ pub enum Error {
     WriteShallowFile(#[from] crate::shallow::write::Error),
     #[error("'shallow' file could not be locked in preparation for writing changes")]
     LockShallowFile(#[from] gix_lock::acquire::Error),
-    #[error("Could not obtain configuration to learn if shallow remotes should be rejected")]
+    #[error(transparent)]
     RejectShallowRemoteConfig(#[from] config::boolean::Error),
     #[error("Receiving objects from shallow remotes is prohibited due to the value of `clone.rejectShallow`")]
     RejectShallowRemote,
     #[error(transparent)]
     NegotiationAlgorithmConfig(#[from] config::key::GenericErrorWithValue),
+    #[error("Could not create or open the quarantine directory for received objects")]
+    CreateQuarantine(#[source] std::io::Error),
+    #[error("Could not make the object database available to threads looking up base objects of the received pack")]
+    ShareObjectDatabase(#[source] std::io::Error),
+    #[error("The received objects are incomplete as object {id} is missing")]
+    MissingObject { id: gix_hash::ObjectId },
+    #[error("Could not read received object {id} to check connectivity")]
+    ConnectivityCheck {
+        id: gix_hash::ObjectId,
+        source: Box<dyn std::error::Error + Send + Sync + 'static>,
+    },
+    #[error(transparent)]
+    MigrateQuarantine(#[from] gix_odb::quarantine::migrate::Error),
+    #[error(transparent)]
+    ReadOnly(#[from] crate::repository::read_only::Error),
     #[error("Failed to read remaining bytes in stream")]
     ReadRemainingBytes(#[source] std::io::Error),
     #[error("None of the refspec(s) {} matched any of the {num_remote_refs} refs on the remote", refspecs.iter().map(|r| r.to_ref().instruction().to_bstring().to_string()).collect::<Vec<_>>().join(", "))]
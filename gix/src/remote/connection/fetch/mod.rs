@@ -193,6 +193,8 @@ where
             reflog_message: None,
             write_packed_refs: WritePackedRefs::Never,
             shallow: Default::default(),
+            quarantine: false,
+            filter: None,
         })
     }
 }
@@ -224,6 +226,8 @@ where
     reflog_message: Option<RefLogMessage>,
     write_packed_refs: WritePackedRefs,
     shallow: remote::fetch::Shallow,
+    quarantine: bool,
+    filter: Option<crate::bstr::BString>,
 }
 
 /// Builder
@@ -265,6 +269,27 @@ where
         self.shallow = shallow;
         self
     }
+
+    /// If enabled, receive the pack into a quarantine directory within the object database, and only move it into the
+    /// object database once all objects reachable from the received remote refs are known to be present.
+    ///
+    /// This is how `git receive-pack` protects the object database from incomplete or unwanted objects.
+    pub fn with_quarantine(mut self, enabled: bool) -> Self {
+        self.quarantine = enabled;
+        self
+    }
+
+    /// Ask the remote to omit the objects that don't match the filter `spec`, like `blob:none` or `tree:0`,
+    /// to perform a partial fetch like `git fetch --filter=<spec>` would.
+    ///
+    /// If unset, the filter is read from `remote.<name>.partialCloneFilter`, and no filter is used if there is none.
+    /// If the remote doesn't support filters, it's ignored and all objects are fetched, just like `git` does.
+    ///
+    /// Note that objects that were filtered aren't fetched on demand later, so operations that need them will fail.
+    pub fn with_filter(mut self, spec: impl Into<crate::bstr::BString>) -> Self {
+        self.filter = Some(spec.into());
+        self
+    }
 }
 
 impl<T> Drop for Prepare<'_, '_, T>
@@ -10,9 +10,10 @@ use gix_protocol::{
 };
 
 use crate::{
+    bstr::{BString, ByteSlice},
     config::{
         cache::util::ApplyLeniency,
-        tree::{Clone, Fetch, Key},
+        tree::{Clone, Extensions, Fetch, Key, Remote},
     },
     remote,
     remote::{
@@ -106,6 +107,9 @@ where
 
         let fetch = gix_protocol::Command::Fetch;
         let repo = con.remote.repo;
+        if matches!(self.dry_run, fetch::DryRun::No) {
+            repo.ensure_writable("fetch into the repository")?;
+        }
         let fetch_features = {
             let mut f = fetch.default_features(protocol_version, &handshake.capabilities);
             f.push(repo.config.user_agent_tuple());
@@ -127,6 +131,17 @@ where
             arguments.use_include_tag();
         }
         let (shallow_commits, mut shallow_lock) = add_shallow_args(&mut arguments, &self.shallow, repo)?;
+        let filter = self
+            .filter
+            .take()
+            .or_else(|| partial_clone_filter(repo, con.remote.name()));
+        if let Some(spec) = filter.as_ref() {
+            if arguments.can_use_filter() {
+                arguments.filter(spec.to_str_lossy().as_ref());
+            } else {
+                progress.info("filtering not recognized by server, ignoring".into());
+            }
+        }
 
         if self.ref_map.object_hash != repo.object_hash() {
             return Err(Error::IncompatibleObjectHash {
@@ -157,7 +172,7 @@ where
             r
         };
         let cache = graph_repo.commit_graph_if_enabled().ok().flatten();
-        let mut graph = graph_repo.revision_graph(cache.as_ref());
+        let mut graph = graph_repo.revision_graph(cache.as_ref())?;
         let action = negotiate::mark_complete_and_common_ref(
             &graph_repo,
             negotiator.deref_mut(),
@@ -276,45 +291,82 @@ where
                     index_version: config::pack_index_version(repo)?,
                     iteration_mode: gix_pack::data::input::Mode::Verify,
                     object_hash: con.remote.repo.object_hash(),
+                    sha1_flavor: config::sha1_flavor(repo)?,
+                    fsync: repo.config.fsync.packs,
+                    ..Default::default()
                 };
 
-                let write_pack_bundle = if matches!(self.dry_run, fetch::DryRun::No) {
-                    #[cfg(not(feature = "async-network-client"))]
-                    let mut rd = reader;
-                    #[cfg(feature = "async-network-client")]
-                    let mut rd = gix_protocol::futures_lite::io::BlockOn::new(reader);
-                    let res = gix_pack::Bundle::write_to_directory(
-                        &mut rd,
-                        Some(&repo.objects.store_ref().path().join("pack")),
-                        progress,
-                        should_interrupt,
-                        Some(Box::new({
-                            let repo = repo.clone();
-                            repo.objects
-                        })),
-                        options,
-                    )?;
-                    // Assure the final flush packet is consumed.
-                    #[cfg(feature = "async-network-client")]
-                    let has_read_to_end = { rd.get_ref().stopped_at().is_some() };
-                    #[cfg(not(feature = "async-network-client"))]
-                    let has_read_to_end = { rd.stopped_at().is_some() };
-                    if !has_read_to_end {
-                        std::io::copy(&mut rd, &mut std::io::sink()).map_err(Error::ReadRemainingBytes)?;
-                    }
-                    #[cfg(feature = "async-network-client")]
-                    {
-                        reader = rd.into_inner();
-                    }
+                let write_pack_bundle =
+                    if matches!(self.dry_run, fetch::DryRun::No) {
+                        #[cfg(not(feature = "async-network-client"))]
+                        let mut rd = reader;
+                        #[cfg(feature = "async-network-client")]
+                        let mut rd = gix_protocol::futures_lite::io::BlockOn::new(reader);
+                        let quarantine = self
+                            .quarantine
+                            .then(|| gix_odb::Quarantine::new(repo.objects.store_ref().path()))
+                            .transpose()
+                            .map_err(Error::CreateQuarantine)?;
+                        let mut res = gix_pack::Bundle::write_to_directory_with_parallel_lookup(
+                            &mut rd,
+                            Some(&quarantine.as_ref().map_or_else(
+                                || repo.objects.store_ref().path().join("pack"),
+                                gix_odb::Quarantine::pack_dir,
+                            )),
+                            progress,
+                            should_interrupt,
+                            Some(repo.objects.clone().into_arc().map_err(Error::ShareObjectDatabase)?),
+                            options,
+                        )?;
+                        // Assure the final flush packet is consumed.
+                        #[cfg(feature = "async-network-client")]
+                        let has_read_to_end = { rd.get_ref().stopped_at().is_some() };
+                        #[cfg(not(feature = "async-network-client"))]
+                        let has_read_to_end = { rd.stopped_at().is_some() };
+                        if !has_read_to_end {
+                            std::io::copy(&mut rd, &mut std::io::sink()).map_err(Error::ReadRemainingBytes)?;
+                        }
+                        #[cfg(feature = "async-network-client")]
+                        {
+                            reader = rd.into_inner();
+                        }
 
-                    #[cfg(not(feature = "async-network-client"))]
-                    {
-                        reader = rd;
-                    }
-                    Some(res)
-                } else {
-                    None
-                };
+                        #[cfg(not(feature = "async-network-client"))]
+                        {
+                            reader = rd;
+                        }
+                        if let Some(quarantine) = quarantine {
+                            let shallow_boundary =
+                                shallow_commits
+                                    .iter()
+                                    .flat_map(|commits| commits.iter().copied())
+                                    .chain(previous_response.shallow_updates().iter().filter_map(
+                                        |update| match update {
+                                            gix_protocol::fetch::response::ShallowUpdate::Shallow(id) => Some(*id),
+                                            gix_protocol::fetch::response::ShallowUpdate::Unshallow(_) => None,
+                                        },
+                                    ))
+                                    .collect();
+                            verify_connectivity(
+                                repo,
+                                &quarantine,
+                                &self.ref_map,
+                                &shallow_boundary,
+                                filter.is_some() || is_promisor_remote(repo, con.remote.name()),
+                            )?;
+                            quarantine.migrate()?;
+                            let pack_dir = repo.objects.store_ref().path().join("pack");
+                            for path in [&mut res.index_path, &mut res.data_path, &mut res.keep_path]
+                                .into_iter()
+                                .flatten()
+                            {
+                                *path = pack_dir.join(path.file_name().expect("pack files have a name"));
+                            }
+                        }
+                        Some(res)
+                    } else {
+                        None
+                    };
                 drop(reader);
 
                 if matches!(protocol_version, gix_protocol::transport::Protocol::V2) {
@@ -372,6 +424,113 @@ where
     }
 }
 
+/// Assure that all objects reachable from the remote ref targets of `ref_map` that are present in `quarantine` are
+/// present as well, stopping at objects that the object database of `repo` already has, and at `shallow_boundary` commits.
+///
+/// Targets that aren't present at all are ignored here as the ref update will reject them.
+/// If the remote is a `promisor`, objects that are missing entirely were filtered and are promised to be provided later,
+/// so they are skipped like git does.
+fn verify_connectivity(
+    repo: &Repository,
+    quarantine: &gix_odb::Quarantine,
+    ref_map: &fetch::RefMap,
+    shallow_boundary: &gix_hashtable::HashSet,
+    promisor: bool,
+) -> Result<(), Error> {
+    use gix_object::{Exists, Find};
+
+    let incoming = gix_odb::at(quarantine.path()).map_err(Error::CreateQuarantine)?;
+    let empty_tree = gix_hash::ObjectId::empty_tree(repo.object_hash());
+    let mut seen = gix_hashtable::HashSet::default();
+    let mut queue: Vec<_> = ref_map
+        .mappings
+        .iter()
+        .filter_map(|mapping| mapping.remote.as_id())
+        .filter(|id| incoming.exists(id))
+        .map(ToOwned::to_owned)
+        .collect();
+    let mut buf = Vec::new();
+    while let Some(id) = queue.pop() {
+        if id == empty_tree || !seen.insert(id) || repo.objects.exists(&id) {
+            continue;
+        }
+        let Some(data) = incoming
+            .try_find(&id, &mut buf)
+            .map_err(|err| Error::ConnectivityCheck { id, source: err })?
+        else {
+            if promisor {
+                continue;
+            }
+            return Err(Error::MissingObject { id });
+        };
+        match data
+            .decode()
+            .map_err(|err| Error::ConnectivityCheck { id, source: err.into() })?
+        {
+            gix_object::ObjectRef::Commit(commit) => {
+                queue.push(commit.tree());
+                if !shallow_boundary.contains(&id) {
+                    queue.extend(commit.parents());
+                }
+            }
+            gix_object::ObjectRef::Tree(tree) => queue.extend(
+                tree.entries
+                    .iter()
+                    .filter(|entry| !entry.mode.is_commit())
+                    .map(|entry| entry.oid.to_owned()),
+            ),
+            gix_object::ObjectRef::Tag(tag) => queue.push(tag.target()),
+            gix_object::ObjectRef::Blob(_) => {}
+        }
+    }
+    Ok(())
+}
+
+/// Return `true` if the remote with `name` promises to provide objects that are missing locally, which is the case
+/// for partial clones.
+fn is_promisor_remote(repo: &Repository, name: Option<&remote::Name<'_>>) -> bool {
+    let Some(name) = name.and_then(remote::Name::as_symbol) else {
+        return false;
+    };
+    let config = &repo.config.resolved;
+    let mut filter = repo.filter_config_section();
+    let subsection = Some(name.into());
+    config
+        .string_filter(Extensions::PARTIAL_CLONE.logical_name(), &mut filter)
+        .map_or(false, |partial_clone_remote| partial_clone_remote.as_ref() == name)
+        || config
+            .boolean_filter_by(
+                Remote::PROMISOR.section().name(),
+                subsection,
+                Remote::PROMISOR.name(),
+                &mut filter,
+            )
+            .and_then(Result::ok)
+            .unwrap_or(false)
+        || config
+            .string_filter_by(
+                Remote::PARTIAL_CLONE_FILTER.section().name(),
+                subsection,
+                Remote::PARTIAL_CLONE_FILTER.name(),
+                &mut filter,
+            )
+            .is_some()
+}
+
+/// Return the value of `remote.<name>.partialCloneFilter` for the remote with `name`, if set.
+fn partial_clone_filter(repo: &Repository, name: Option<&remote::Name<'_>>) -> Option<BString> {
+    let name = name.and_then(remote::Name::as_symbol)?;
+    repo.config
+        .resolved
+        .string_filter_by(
+            Remote::PARTIAL_CLONE_FILTER.section().name(),
+            Some(name.into()),
+            Remote::PARTIAL_CLONE_FILTER.name(),
+            &mut repo.filter_config_section(),
+        )
+        .map(std::borrow::Cow::into_owned)
+}
+
 fn acquire_shallow_lock(repo: &Repository) -> Result<gix_lock::File, Error> {
     gix_lock::File::acquire_to_update_resource(repo.shallow_file(), gix_lock::acquire::Fail::Immediately, None)
         .map_err(Into::into)
@@ -58,6 +58,9 @@ impl From<Mode> for Update {
 /// * …the local ref is currently checked out
 /// * …existing refs would not become 'unborn', i.e. point to a reference that doesn't exist and won't be created due to ref-specs
 ///
+/// To tell forced updates from fast-forwards, the ancestry of the old and new commits is checked, unless `fetch.showForcedUpdates`
+/// is `false` and the ref-spec allows forced updates anyway. This saves time in large repositories.
+///
 /// With these safeguards in place, one can handle each naturally and implement mirrors or bare repos easily.
 #[allow(clippy::too_many_arguments)]
 pub(crate) fn update(
@@ -74,6 +77,7 @@ pub(crate) fn update(
     let mut edits = Vec::new();
     let mut updates = Vec::new();
     let mut edit_indices_to_validate = Vec::new();
+    let show_forced_updates = repo.config.fetch_show_forced_updates()?;
 
     let implicit_tag_refspec = fetch_tags
         .to_refspec()
@@ -149,6 +153,7 @@ pub(crate) fn update(
                                 } else {
                                     let mut force = spec.allow_non_fast_forward();
                                     let is_fast_forward = match dry_run {
+                                        fetch::DryRun::No if force && !show_forced_updates => false,
                                         fetch::DryRun::No => {
                                             let ancestors = repo
                                                 .find_object(local_id)?
@@ -156,9 +161,13 @@ pub(crate) fn update(
                                                 .map_err(|_| ())
                                                 .and_then(|c| c.committer().map(|a| a.time.seconds).map_err(|_| ()))
                                                 .and_then(|local_commit_time| {
+                                                    let grafts = repo.grafts().map_err(|_| ())?;
                                                     remote_id
                                                         .to_owned()
                                                         .ancestors(&repo.objects)
+                                                        .parents_provider(
+                                                            (!grafts.is_empty()).then(|| Box::new(grafts) as _),
+                                                        )
                                                         .sorting(
                                                             gix_traverse::commit::simple::Sorting::ByCommitTimeCutoff {
                                                                 order: Default::default(),
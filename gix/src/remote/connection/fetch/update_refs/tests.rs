@@ -190,6 +190,59 @@ mod update {
         }
     }
 
+    #[test]
+    fn forced_updates_are_only_detected_if_enabled() -> Result {
+        for (spec, show_forced_updates, expected_mode, reflog_message) in [
+            (
+                "+refs/heads/main:refs/remotes/origin/g",
+                "true",
+                fetch::refs::update::Mode::FastForward,
+                "fast-forward",
+            ),
+            (
+                "+refs/heads/main:refs/remotes/origin/g",
+                "false",
+                fetch::refs::update::Mode::Forced,
+                "forced-update",
+            ),
+            (
+                "refs/heads/main:refs/remotes/origin/g",
+                "false",
+                fetch::refs::update::Mode::FastForward,
+                "fast-forward",
+            ),
+        ] {
+            let (mut repo, _tmp) = repo_rw("two-origins");
+            repo.config_snapshot_mut()
+                .set_value(&gix::config::tree::Fetch::SHOW_FORCED_UPDATES, show_forced_updates)?;
+            let (mappings, specs) = mapping_from_spec(spec, &repo);
+            let out = fetch::refs::update(
+                &repo,
+                prefixed("action"),
+                &mappings,
+                &specs,
+                &[],
+                fetch::Tags::None,
+                fetch::DryRun::No,
+                fetch::WritePackedRefs::Never,
+            )?;
+            assert_eq!(
+                out.updates,
+                vec![fetch::refs::Update {
+                    mode: expected_mode,
+                    type_change: None,
+                    edit_index: Some(0),
+                }],
+                "{spec}: without the ancestry check, only forced updates can be reported for specs that allow them"
+            );
+            match &out.edits[0].change {
+                Change::Update { log, .. } => assert_eq!(log.message, format!("action: {reflog_message}")),
+                _ => unreachable!("only updates"),
+            }
+        }
+        Ok(())
+    }
+
     #[test]
     fn checked_out_branches_in_worktrees_are_rejected_with_additional_information() -> Result {
         let root = gix_path::realpath(gix_testtools::scripted_fixture_read_only_with_args_single_archive(
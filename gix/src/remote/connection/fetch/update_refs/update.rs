@@ -23,6 +23,8 @@ mod error {
         PeelToId(#[from] crate::reference::peel::Error),
         #[error("Failed to follow a symbolic reference to assure worktree isn't affected")]
         FollowSymref(#[from] gix_ref::file::find::existing::Error),
+        #[error(transparent)]
+        ShowForcedUpdates(#[from] crate::config::boolean::Error),
     }
 }
 
@@ -51,6 +53,9 @@ pub enum Mode {
     /// The old ref's commit was an ancestor of the new one, allowing for a fast-forward without a merge.
     FastForward,
     /// The ref was set to point to the new commit from the remote without taking into consideration its ancestry.
+    ///
+    /// Typically the old ref's commit wasn't an ancestor of the new one, but if `fetch.showForcedUpdates` is `false`,
+    /// the ancestry isn't checked for ref-specs that allow forced updates, so all of their updates are reported as forced.
     Forced,
     /// A new ref has been created as there was none before.
     New,
@@ -0,0 +1,135 @@
+use gix_features::progress::Progress;
+use gix_protocol::transport::client::Transport;
+
+use crate::{
+    bstr::{BStr, BString, ByteSlice, ByteVec},
+    remote::{
+        connection::{ref_map, HandshakeWithRefs},
+        Connection,
+    },
+};
+
+/// For use in [`Connection::list_refs()`].
+#[derive(Default, Debug, Clone)]
+pub struct Options {
+    /// Only list branches, i.e. references below `refs/heads/`, like `git ls-remote --branches` does.
+    ///
+    /// If [tags](Self::tags) are also listed, both kinds of references are listed.
+    pub branches: bool,
+    /// Only list tags, i.e. references below `refs/tags/`, like `git ls-remote --tags` does.
+    pub tags: bool,
+    /// Only list references whose full name ends with a match of one of these patterns, like `git ls-remote <remote> <patterns>…`.
+    ///
+    /// A pattern matches if it matches everything after any `/` of the name or the whole name, so `main` matches
+    /// `refs/heads/main` and `refs/remotes/origin/main`, `heads/*` matches all branches, and `HEAD` matches `HEAD`.
+    /// Note that wildcards also match `/`.
+    /// If empty, references aren't filtered by name.
+    pub patterns: Vec<BString>,
+    /// Parameters in the form of `(name, optional value)` to add to the handshake.
+    ///
+    /// This is useful in case of custom servers.
+    pub handshake_parameters: Vec<(String, Option<String>)>,
+}
+
+/// The outcome of [`Connection::list_refs()`].
+#[derive(Debug, Clone)]
+pub struct Outcome {
+    /// All references of the remote that passed the filters in the order the server sent them, along with the target of
+    /// symbolic references like `HEAD` and the object annotated tags point to.
+    pub refs: Vec<gix_protocol::handshake::Ref>,
+    /// Information about the handshake with the remote.
+    pub handshake: gix_protocol::handshake::Outcome,
+}
+
+impl<T> Connection<'_, '_, T>
+where
+    T: Transport,
+{
+    /// List the references on the remote like `git ls-remote` does, filtered according to `options`, and ignoring
+    /// the [`refspecs`][crate::Remote::refspecs()] of our remote.
+    ///
+    /// If only [branches](Options::branches) or [tags](Options::tags) are requested, the server is asked to only send these
+    /// if it supports it, whereas [patterns](Options::patterns) are always matched locally.
+    /// Just like with [`ref_map()`](Self::ref_map()), the URL of the remote is rewritten according to the configuration,
+    /// and credentials are obtained from configured helpers unless the connection was configured with
+    /// [`with_credentials()`](Self::with_credentials()).
+    ///
+    /// # Consumption
+    ///
+    /// Due to management of the transport, it's cleanest to only use it for a single interaction. Thus, it's consumed
+    /// along with the connection.
+    #[allow(clippy::result_large_err)]
+    #[gix_protocol::maybe_async::maybe_async]
+    pub async fn list_refs(mut self, progress: impl Progress, options: Options) -> Result<Outcome, ref_map::Error> {
+        let res = self.list_refs_inner(progress, options).await;
+        gix_protocol::indicate_end_of_interaction(&mut self.transport, self.trace)
+            .await
+            .ok();
+        res
+    }
+
+    #[allow(clippy::result_large_err)]
+    #[gix_protocol::maybe_async::maybe_async]
+    async fn list_refs_inner(
+        &mut self,
+        progress: impl Progress,
+        Options {
+            branches,
+            tags,
+            patterns,
+            handshake_parameters,
+        }: Options,
+    ) -> Result<Outcome, ref_map::Error> {
+        let _span = gix_trace::coarse!("remote::Connection::list_refs()");
+        let mut prefixes = Vec::new();
+        if branches {
+            prefixes.push("refs/heads/");
+        }
+        if tags {
+            prefixes.push("refs/tags/");
+        }
+        // Let the server filter by prefix, which is done by deriving them from these refspecs.
+        let specs: Vec<_> = prefixes
+            .iter()
+            .map(|prefix| {
+                gix_refspec::parse(
+                    format!("{prefix}*:{prefix}*").as_str().into(),
+                    gix_refspec::parse::Operation::Fetch,
+                )
+                .expect("valid")
+                .to_owned()
+            })
+            .collect();
+        let HandshakeWithRefs { outcome, mut refs } = self
+            .fetch_refs(!specs.is_empty(), handshake_parameters, &specs, progress)
+            .await?;
+
+        // Like `git`, prefix names with a slash so patterns without slashes can match top-level names as well.
+        let patterns: Vec<BString> = patterns
+            .into_iter()
+            .map(|mut pattern| {
+                pattern.insert_str(0, "*/");
+                pattern
+            })
+            .collect();
+        let mut name_buf = BString::default();
+        refs.retain(|r| {
+            let name = r.unpack().0;
+            let kind_matches = prefixes.is_empty() || prefixes.iter().any(|prefix| name.starts_with_str(prefix));
+            kind_matches && (patterns.is_empty() || tail_matches(&patterns, name, &mut name_buf))
+        });
+        Ok(Outcome {
+            refs,
+            handshake: outcome,
+        })
+    }
+}
+
+fn tail_matches(patterns: &[BString], name: &BStr, buf: &mut BString) -> bool {
+    buf.clear();
+    buf.push(b'/');
+    buf.push_str(name);
+    patterns
+        .iter()
+        .any(|pattern| gix_glob::wildmatch(pattern.as_bstr(), buf.as_bstr(), gix_glob::wildmatch::Mode::empty()))
+}
@@ -25,5 +25,8 @@ mod access;
 ///
 pub mod ref_map;
 
+///
+pub mod list_refs;
+
 ///
 pub mod fetch;
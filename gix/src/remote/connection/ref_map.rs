@@ -181,7 +181,7 @@ where
 
     #[allow(clippy::result_large_err)]
     #[gix_protocol::maybe_async::maybe_async]
-    async fn fetch_refs(
+    pub(super) async fn fetch_refs(
         &mut self,
         filter_by_prefix: bool,
         extra_parameters: Vec<(String, Option<String>)>,
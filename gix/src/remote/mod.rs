@@ -56,7 +56,7 @@ pub mod connect;
 #[cfg(any(feature = "async-network-client", feature = "blocking-network-client"))]
 mod connection;
 #[cfg(any(feature = "async-network-client", feature = "blocking-network-client"))]
-pub use connection::{ref_map, AuthenticateFn, Connection};
+pub use connection::{list_refs, ref_map, AuthenticateFn, Connection};
 
 ///
 pub mod save;
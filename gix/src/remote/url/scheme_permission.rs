@@ -43,8 +43,8 @@ impl<'a> TryFrom<Cow<'a, BStr>> for Allow {
 
 #[derive(Debug, Clone)]
 pub(crate) struct SchemePermission {
-    /// `None`, env-var is unset or wasn't queried, otherwise true if `GIT_PROTOCOL_FROM_USER` is `1`.
-    user_allowed: Option<bool>,
+    /// `true` if `GIT_PROTOCOL_FROM_USER` is unset or `1`.
+    user_allowed: bool,
     /// The general allow value from `protocol.allow`.
     allow: Option<Allow>,
     /// Per scheme allow information
@@ -63,7 +63,6 @@ impl SchemePermission {
             .map(|value| Protocol::ALLOW.try_into_allow(value, None))
             .transpose()?;
 
-        let mut saw_user = allow.map_or(false, |allow| allow == Allow::User);
         let allow_per_scheme = match config.sections_by_name_and_filter("protocol", &mut filter) {
             Some(it) => {
                 let mut map = BTreeMap::default();
@@ -80,7 +79,6 @@ impl SchemePermission {
                         .map(|value| Protocol::ALLOW.try_into_allow(value, Some(scheme.as_str())))
                         .transpose()?
                     {
-                        saw_user |= value == Allow::User;
                         map.insert(scheme, value);
                     }
                 }
@@ -89,11 +87,9 @@ impl SchemePermission {
             None => Default::default(),
         };
 
-        let user_allowed = saw_user.then(|| {
-            config
-                .string_filter(gitoxide::Allow::PROTOCOL_FROM_USER.logical_name().as_str(), &mut filter)
-                .map_or(true, |val| val.as_ref() == "1")
-        });
+        let user_allowed = config
+            .string_filter(gitoxide::Allow::PROTOCOL_FROM_USER.logical_name().as_str(), &mut filter)
+            .map_or(true, |val| val.as_ref() == "1");
         Ok(SchemePermission {
             allow,
             allow_per_scheme,
@@ -110,11 +106,13 @@ impl SchemePermission {
                 use gix_url::Scheme::*;
                 match scheme {
                     File | Git | Ssh | Http | Https => true,
-                    Ext(_) => false,
-                    // TODO: figure out what 'ext' really entails, and what 'other' protocols are which aren't representable for us yet
+                    // `ext::` runs arbitrary commands, which is never allowed by default.
+                    Ext(name) if name == "ext" => false,
+                    // Like `git`, allow remote helpers unless `GIT_PROTOCOL_FROM_USER` forbids it.
+                    Ext(_) => self.user_allowed,
                 }
             },
-            |allow| allow.to_bool(self.user_allowed),
+            |allow| allow.to_bool(Some(self.user_allowed)),
         )
     }
 }
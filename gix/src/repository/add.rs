@@ -0,0 +1,176 @@
+use std::sync::atomic::AtomicBool;
+
+use gix_index::entry::{Flags, Mode, Stage};
+
+use crate::bstr::{BStr, BString, ByteSlice};
+use crate::repository::hash_worktree_files;
+use crate::Repository;
+
+/// The error returned by [Repository::add_to_index()](crate::Repository::add_to_index()).
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("Files can only be added in repositories with a worktree")]
+    MissingWorkDir,
+    #[error(transparent)]
+    ConfigBoolean(#[from] crate::config::boolean::Error),
+    #[error(transparent)]
+    StatOptions(#[from] crate::config::stat_options::Error),
+    #[error(transparent)]
+    Pathspec(#[from] crate::pathspec::init::Error),
+    #[error(transparent)]
+    Dirwalk(#[from] crate::dirwalk::Error),
+    #[error(transparent)]
+    HashFiles(#[from] hash_worktree_files::Error),
+    #[error("Could not obtain metadata for '{}'", path.display())]
+    Io {
+        path: std::path::PathBuf,
+        source: std::io::Error,
+    },
+}
+
+/// The outcome of [Repository::add_to_index()](crate::Repository::add_to_index()).
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Outcome {
+    /// The paths of untracked files that were added, in sort order.
+    pub added: Vec<BString>,
+    /// The paths of tracked files whose entries were updated as their content or mode changed, or as they were conflicted,
+    /// in sort order.
+    pub updated: Vec<BString>,
+    /// The paths of tracked files whose entries were removed as they are not present in the worktree anymore, in sort order.
+    pub removed: Vec<BString>,
+    /// The repository-relative paths of nested repositories, which were not added as they would need a commit to point to.
+    pub skipped_repositories: Vec<BString>,
+}
+
+impl Repository {
+    /// Add all files matching `patterns` to `index`, similar to `git add --all <pathspec>`, hashing them with up to
+    /// `thread_limit` threads. Empty `patterns` match all files in the worktree.
+    ///
+    /// Untracked files are added unless they are excluded, and tracked files are hashed and updated unless their stat
+    /// information shows that they didn't change. Conflicts are resolved with the version in the worktree, and entries
+    /// of files that don't exist anymore are removed.
+    /// Submodules, nested repositories and entries that are marked to skip the worktree are left untouched.
+    ///
+    /// All files are hashed with [`hash_worktree_files()`](Self::hash_worktree_files()), which applies the filter pipeline
+    /// and writes them into the object database.
+    /// `index` is not written, and `should_interrupt` is polled to abort the operation with an error.
+    pub fn add_to_index(
+        &self,
+        index: &mut gix_index::State,
+        patterns: impl IntoIterator<Item = impl AsRef<BStr>>,
+        thread_limit: Option<usize>,
+        should_interrupt: &AtomicBool,
+    ) -> Result<Outcome, Error> {
+        let _span = gix_trace::coarse!("gix::add_to_index");
+        let workdir = self.work_dir().ok_or(Error::MissingWorkDir)?;
+        let patterns: Vec<BString> = patterns.into_iter().map(|p| p.as_ref().to_owned()).collect();
+        let fs = self.filesystem_options()?;
+        let stat_options = self.stat_options()?;
+
+        let mut out = Outcome::default();
+        let mut candidates = Vec::new();
+        let mut previous = Vec::new();
+        {
+            let mut pathspec = self.pathspec(
+                false, /* empty patterns match prefix */
+                &patterns,
+                true, /* inherit ignore case */
+                index,
+                crate::worktree::stack::state::attributes::Source::WorktreeThenIdMapping,
+            )?;
+            let mut previous_path = None;
+            for entry in index.entries() {
+                let rela_path = entry.path(index);
+                if previous_path == Some(rela_path) {
+                    continue;
+                }
+                previous_path = Some(rela_path);
+                if entry.mode.is_submodule()
+                    || entry.mode.is_sparse()
+                    || entry.flags.contains(Flags::SKIP_WORKTREE)
+                    || !pathspec.is_included(rela_path, Some(false))
+                {
+                    continue;
+                }
+                let path = workdir.join(gix_path::from_bstr(rela_path));
+                match gix_index::fs::Metadata::from_path_no_follow(&path) {
+                    // Files inside a directory that replaced the file are untracked, and added below.
+                    Ok(metadata) if metadata.is_dir() => out.removed.push(rela_path.to_owned()),
+                    Ok(metadata) => {
+                        let is_unchanged = entry.stage() == Stage::Unconflicted
+                            && entry
+                                .mode
+                                .change_to_match_fs(&metadata, fs.symlink, fs.executable_bit)
+                                .is_none()
+                            && entry.stat.matches_fs(&metadata, stat_options)
+                            && !entry.stat.is_racy(index.timestamp(), stat_options);
+                        if !is_unchanged {
+                            candidates.push(rela_path.to_owned());
+                            previous.push((entry.mode, entry.id, entry.stage() != Stage::Unconflicted));
+                        }
+                    }
+                    Err(err)
+                        if err.kind() == std::io::ErrorKind::NotFound
+                            || path.parent().map_or(false, |dir| !dir.is_dir()) =>
+                    {
+                        out.removed.push(rela_path.to_owned());
+                    }
+                    Err(source) => return Err(Error::Io { path, source }),
+                }
+            }
+        }
+
+        let options = self
+            .dirwalk_options()?
+            .empty_patterns_match_prefix(false)
+            .emit_untracked(gix_dir::walk::EmissionMode::Matching)
+            .emit_ignored(None)
+            .emit_empty_directories(false)
+            .recurse_repositories(false);
+        let mut collect = gix_dir::walk::delegate::Collect::default();
+        self.dirwalk(index, &patterns, should_interrupt, options, &mut collect)?;
+        for (entry, _dir_status) in collect.into_entries_by_path() {
+            if entry.status != gix_dir::entry::Status::Untracked {
+                continue;
+            }
+            match entry.disk_kind {
+                Some(gix_dir::entry::Kind::File | gix_dir::entry::Kind::Symlink) => out.added.push(entry.rela_path),
+                Some(gix_dir::entry::Kind::Repository) => out.skipped_repositories.push(entry.rela_path),
+                Some(gix_dir::entry::Kind::Directory) | None => {}
+            }
+        }
+
+        let entries = self.hash_worktree_files(
+            candidates.into_iter().chain(out.added.iter().cloned()),
+            hash_worktree_files::Options {
+                thread_limit,
+                write_objects: true,
+                ..Default::default()
+            },
+            should_interrupt,
+        )?;
+        for rela_path in &out.removed {
+            index.remove_entry(rela_path.as_bstr());
+        }
+        let previous = previous.into_iter().map(Some).chain(std::iter::repeat(None));
+        for (entry, previous) in entries.into_iter().zip(previous) {
+            // Like `git`, keep the executable bit of the entry if the file system can't represent it.
+            let mode = match previous {
+                Some((Mode::FILE_EXECUTABLE, _, _)) if !fs.executable_bit && entry.mode == Mode::FILE => {
+                    Mode::FILE_EXECUTABLE
+                }
+                _ => entry.mode,
+            };
+            // Entries that merely looked changed are still updated to refresh their stat information.
+            if previous.map_or(false, |(previous_mode, previous_id, is_conflicted)| {
+                is_conflicted || previous_mode != mode || previous_id != entry.id
+            }) {
+                out.updated.push(entry.rela_path.clone());
+            }
+            index.upsert_entry(entry.stat, entry.id, Flags::empty(), mode, entry.rela_path.as_bstr());
+        }
+        out.added.sort();
+        Ok(out)
+    }
+}
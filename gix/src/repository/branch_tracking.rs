@@ -0,0 +1,228 @@
+use gix_hash::ObjectId;
+use gix_ref::FullName;
+
+use crate::{remote, Repository};
+
+/// The error returned by [Repository::branch_tracking()](crate::Repository::branch_tracking()).
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error(transparent)]
+    OpenPackedRefs(#[from] crate::reference::iter::Error),
+    #[error(transparent)]
+    IterInit(#[from] crate::reference::iter::init::Error),
+    #[error(transparent)]
+    Grafts(#[from] crate::shallow::grafts::Error),
+    #[error("Could not read a local branch")]
+    Iter(#[source] Box<dyn std::error::Error + Send + Sync + 'static>),
+    #[error(transparent)]
+    Peel(#[from] crate::reference::peel::Error),
+    #[error(transparent)]
+    TrackingRefName(#[from] crate::repository::branch_remote_tracking_ref_name::Error),
+    #[error(transparent)]
+    FindReference(#[from] crate::reference::find::Error),
+    #[error(transparent)]
+    CommitGraph(#[from] crate::repository::commit_graph_if_enabled::Error),
+    #[error(transparent)]
+    FindCommit(#[from] gix_revwalk::graph::get_or_insert_default::Error),
+}
+
+/// A local branch along with information about its upstream, as returned by
+/// [Repository::branch_tracking()](crate::Repository::branch_tracking()).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Branch {
+    /// The full name of the branch, like `refs/heads/main`.
+    pub name: FullName,
+    /// The object the branch points to after peeling, typically a commit.
+    pub id: ObjectId,
+    /// The upstream of the branch, or `None` if none is configured.
+    pub upstream: Option<Upstream>,
+}
+
+/// The upstream of a [`Branch`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Upstream {
+    /// The full name of the local tracking branch of the upstream, like `refs/remotes/origin/main`.
+    pub name: FullName,
+    /// What we know about the upstream.
+    pub status: Status,
+}
+
+/// The status of an [`Upstream`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Status {
+    /// The upstream is configured, but its tracking branch doesn't exist, which typically happens if it was deleted on the remote
+    /// and pruned locally.
+    Gone,
+    /// The tracking branch of the upstream exists.
+    Present {
+        /// The object the tracking branch points to after peeling.
+        id: ObjectId,
+        /// The amount of commits reachable from the branch, but not from the upstream.
+        ahead: usize,
+        /// The amount of commits reachable from the upstream, but not from the branch.
+        behind: usize,
+    },
+}
+
+impl Repository {
+    /// Return all local branches in `refs/heads` along with their upstream, if configured, and the amount of commits they are
+    /// ahead of and behind their upstream, similar to what `git branch -vv` displays.
+    ///
+    /// The upstream is the tracking branch that corresponds to `branch.<name>.merge` on `branch.<name>.remote`,
+    /// see [`branch_remote_tracking_ref_name()`](Self::branch_remote_tracking_ref_name()) for details.
+    /// If the upstream is configured but its tracking branch doesn't exist, it's reported as [gone](Status::Gone).
+    ///
+    /// Commits are counted with the help of the commit-graph if it's available, and traversals stop as soon as all remaining
+    /// commits are reachable from both the branch and its upstream.
+    pub fn branch_tracking(&self) -> Result<Vec<Branch>, Error> {
+        let _span = gix_trace::coarse!("gix::branch_tracking()");
+        let cache = self.commit_graph_if_enabled()?;
+        let mut graph = self.revision_graph(cache.as_ref())?;
+
+        let mut out = Vec::new();
+        for branch in self.references()?.local_branches()? {
+            let mut branch = branch.map_err(Error::Iter)?;
+            let id = branch.peel_to_id_in_place()?.detach();
+            let upstream = match self.branch_remote_tracking_ref_name(branch.name(), remote::Direction::Fetch) {
+                None => None,
+                Some(name) => {
+                    let name = name?.into_owned();
+                    let status = match self.try_find_reference(name.as_ref())? {
+                        None => Status::Gone,
+                        Some(mut upstream) => {
+                            let upstream_id = upstream.peel_to_id_in_place()?.detach();
+                            let (ahead, behind) = ahead_behind(&mut graph, id, upstream_id)?;
+                            Status::Present {
+                                id: upstream_id,
+                                ahead,
+                                behind,
+                            }
+                        }
+                    };
+                    Some(Upstream { name, status })
+                }
+            };
+            out.push(Branch {
+                name: branch.inner.name,
+                id,
+                upstream,
+            });
+        }
+        Ok(out)
+    }
+}
+
+/// The flags used to paint commits when counting them.
+mod flags {
+    /// The commit is reachable from the branch.
+    pub const BRANCH: u8 = 1 << 0;
+    /// The commit is reachable from the upstream.
+    pub const UPSTREAM: u8 = 1 << 1;
+    /// The commit is reachable from both the branch and the upstream.
+    pub const BOTH: u8 = BRANCH | UPSTREAM;
+    /// The commit was counted as being ahead.
+    pub const AHEAD: u8 = 1 << 2;
+    /// The commit was counted as being behind.
+    pub const BEHIND: u8 = 1 << 3;
+    /// The commit is in the queue.
+    pub const QUEUED: u8 = 1 << 4;
+
+    /// Return `true` if the commit with `flags` still has to be visited, as it's not yet known to be reachable from both tips,
+    /// or because it was counted.
+    pub fn is_interesting(flags: u8) -> bool {
+        flags & BOTH != BOTH || flags & (AHEAD | BEHIND) != 0
+    }
+}
+
+/// Count the commits only reachable from `branch` and those only reachable from `upstream`, in that order.
+///
+/// Commits are visited in order of generation and commit time, with those that sort the same being visited in the order
+/// they were queued. Commits are visited again if they turn out to be reachable from more tips than previously known,
+/// which corrects their count. The traversal stops once no queued commit [is interesting](flags::is_interesting()) anymore,
+/// so counts may still be off if commit times are skewed and no commit-graph is available.
+fn ahead_behind(
+    graph: &mut gix_revwalk::Graph<'_, '_, gix_revwalk::graph::Commit<u8>>,
+    branch: ObjectId,
+    upstream: ObjectId,
+) -> Result<(usize, usize), Error> {
+    graph.clear_commit_data(|flags| *flags = 0);
+    let mut queue = Queue {
+        inner: gix_revwalk::PriorityQueue::new(),
+        num_queued: 0,
+        num_interesting: 0,
+    };
+    for (id, flag) in [(branch, flags::BRANCH), (upstream, flags::UPSTREAM)] {
+        graph.get_or_insert_full_commit(id, |commit| queue.paint(commit, id, flag))?;
+    }
+
+    let (mut ahead, mut behind) = (0, 0);
+    while queue.num_interesting != 0 {
+        let (_key, id) = queue.inner.pop().expect("interesting commits are in the queue");
+        let commit = graph.get_mut(&id).expect("queued commits are in the graph");
+        if flags::is_interesting(commit.data) {
+            queue.num_interesting -= 1;
+        }
+        if commit.data & flags::AHEAD != 0 {
+            ahead -= 1;
+        }
+        if commit.data & flags::BEHIND != 0 {
+            behind -= 1;
+        }
+        let reachable_from = commit.data & flags::BOTH;
+        commit.data = reachable_from;
+        match reachable_from {
+            flags::BRANCH => {
+                ahead += 1;
+                commit.data |= flags::AHEAD;
+            }
+            flags::UPSTREAM => {
+                behind += 1;
+                commit.data |= flags::BEHIND;
+            }
+            _ => {}
+        }
+        for parent_id in commit.parents.clone() {
+            graph.get_or_insert_full_commit(parent_id, |parent| queue.paint(parent, parent_id, reachable_from))?;
+        }
+    }
+    Ok((ahead, behind))
+}
+
+/// The queue of commits to visit in [`ahead_behind()`], which contains each commit at most once.
+struct Queue {
+    inner: gix_revwalk::PriorityQueue<(u32, gix_date::SecondsSinceUnixEpoch, std::cmp::Reverse<usize>), ObjectId>,
+    num_queued: usize,
+    /// The amount of queued commits that [are interesting](flags::is_interesting()), which avoids having to check them all.
+    num_interesting: usize,
+}
+
+impl Queue {
+    /// Add `reachable_from` to the flags of `commit` with `id`, and queue it unless it's already queued.
+    ///
+    /// Queued commits use their latest flags once they are popped, and can only become uninteresting while waiting.
+    fn paint(&mut self, commit: &mut gix_revwalk::graph::Commit<u8>, id: ObjectId, reachable_from: u8) {
+        if commit.data & reachable_from == reachable_from {
+            return;
+        }
+        let was_interesting = flags::is_interesting(commit.data);
+        commit.data |= reachable_from;
+        if commit.data & flags::QUEUED == 0 {
+            commit.data |= flags::QUEUED;
+            self.num_queued += 1;
+            self.inner.insert(
+                (
+                    commit.generation.unwrap_or(gix_commitgraph::GENERATION_NUMBER_INFINITY),
+                    commit.commit_time,
+                    std::cmp::Reverse(self.num_queued),
+                ),
+                id,
+            );
+            if flags::is_interesting(commit.data) {
+                self.num_interesting += 1;
+            }
+        } else if was_interesting && !flags::is_interesting(commit.data) {
+            self.num_interesting -= 1;
+        }
+    }
+}
@@ -19,6 +19,38 @@ impl crate::Repository {
         }
     }
 
+    /// Like [`object_cache_size()`](Self::object_cache_size()), but use a single cache of at most `bytes` that is shared by this
+    /// instance and all of its clones, even if these are used in other threads. Set `bytes` to `None` to deactivate it entirely.
+    ///
+    /// The cache is split into `num_shards` parts that are locked individually to reduce contention, with each part
+    /// receiving an equal share of `bytes`.
+    /// This is useful if many threads are likely to access the same objects, as these only need to be decoded once.
+    ///
+    /// Use [`set_shared_object_cache()`](Self::set_shared_object_cache()) to share a cache with separately opened repositories.
+    pub fn shared_object_cache_size(&mut self, bytes: impl Into<Option<usize>>, num_shards: std::num::NonZeroUsize) {
+        let cache = match bytes.into() {
+            Some(0) | None => None,
+            Some(bytes) => Some(gix_pack::cache::object::SharedMemoryCappedHashmap::new(
+                bytes, num_shards,
+            )),
+        };
+        self.set_shared_object_cache(cache);
+    }
+
+    /// Use the given shared object `cache` for this instance and all of its clones, or deactivate the object cache if it is `None`.
+    ///
+    /// As clones of `cache` refer to the same cache, it can be passed to any amount of repositories, even if they were opened
+    /// separately, so that objects decoded by one of them are available to all of them.
+    pub fn set_shared_object_cache(
+        &mut self,
+        cache: impl Into<Option<gix_pack::cache::object::SharedMemoryCappedHashmap>>,
+    ) {
+        match cache.into() {
+            Some(cache) => self.objects.set_object_cache(move || Box::new(cache.clone())),
+            None => self.objects.unset_object_cache(),
+        }
+    }
+
     /// Set an object cache of size `bytes` if none is set.
     ///
     /// Use this method to avoid overwriting any existing value while assuring better performance in case no value is set.
@@ -40,6 +72,33 @@ impl crate::Repository {
     }
 }
 
+/// Configure the cache for delta-base objects used when decoding packed objects
+impl crate::Repository {
+    /// Sets the amount of space used at most for caching delta-base objects when decoding objects from packs to `Some(bytes)`,
+    /// or `None` to deactivate it entirely. This overrides `core.deltaBaseCacheLimit` for this instance and its future clones.
+    ///
+    /// To use a different cache policy, provide a custom cache implementation
+    /// with [`objects.set_pack_cache()`](gix_odb::Cache::set_pack_cache()).
+    #[cfg(feature = "pack-cache-lru-dynamic")]
+    pub fn pack_cache_size(&mut self, bytes: impl Into<Option<usize>>) {
+        match bytes.into() {
+            Some(0) | None => self.objects.unset_pack_cache(),
+            Some(bytes) => self
+                .objects
+                .set_pack_cache(move || Box::new(gix_pack::cache::lru::MemoryCappedHashmap::new(bytes))),
+        }
+    }
+
+    /// Return the hit and miss statistics of the delta-base cache of this instance, or `None` if there is no such cache or
+    /// if it doesn't keep statistics.
+    ///
+    /// Use these to size the cache with [`pack_cache_size()`](Self::pack_cache_size()) based on real workloads.
+    /// Note that each clone of this instance, like the ones used in other threads, has its own cache and thus its own statistics.
+    pub fn pack_cache_statistics(&self) -> Option<gix_pack::cache::Statistics> {
+        self.objects.pack_cache_statistics()
+    }
+}
+
 /// Handling of InMemory object writing
 impl crate::Repository {
     /// When writing objects, keep them in memory instead of writing them to disk.
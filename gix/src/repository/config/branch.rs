@@ -183,7 +183,7 @@ impl crate::Repository {
     }
 }
 
-fn matching_remote<'a>(
+pub(crate) fn matching_remote<'a>(
     lhs: &FullNameRef,
     specs: impl IntoIterator<Item = &'a gix_refspec::RefSpec>,
     object_hash: gix_hash::Kind,
@@ -37,6 +37,156 @@ impl crate::Repository {
         self.config.stat_options()
     }
 
+    /// Return how the untracked cache should be treated, as configured by `core.untrackedCache`.
+    pub fn untracked_cache_mode(&self) -> Result<config::untracked_cache::Mode, config::key::GenericErrorWithValue> {
+        self.config.untracked_cache_mode()
+    }
+
+    /// Return `true` if the untracked cache can be used in the worktree as the modification time of its directories
+    /// [changes reliably](gix_fs::Capabilities::probe_untracked_cache_with_delay()) when entries are added or removed,
+    /// waiting for `delay` before each change, or `false` if this is a bare repository.
+    ///
+    /// This is what `git update-index --test-untracked-cache` does, with `git` waiting a second before each change.
+    /// As it takes a while, it's only performed on explicit request. Its result is stored as `core.untrackedCache`
+    /// in the local configuration file and in our in-memory configuration, so the next [status](Self::status())
+    /// adds the untracked cache extension to the index or removes it without probing again.
+    /// Nothing is stored in bare repositories.
+    pub fn probe_untracked_cache(
+        &mut self,
+        delay: std::time::Duration,
+    ) -> Result<bool, config::untracked_cache::probe::Error> {
+        use config::untracked_cache::probe::Error;
+
+        let Some(workdir) = self.work_dir() else {
+            return Ok(false);
+        };
+        let supported = gix_fs::Capabilities::probe_untracked_cache_with_delay(workdir, delay).map_err(Error::Probe)?;
+
+        let path = self.common_dir().join("config");
+        let mut lock = gix_lock::File::acquire_to_update_resource(&path, gix_lock::acquire::Fail::Immediately, None)?;
+        let mut local = gix_config::File::from_path_no_includes(path.clone(), gix_config::Source::Local)?;
+        let value = if supported { "true" } else { "false" };
+        local.set_raw_value(&config::tree::Core::UNTRACKED_CACHE, value)?;
+        gix_features::threading::OwnShared::make_mut(&mut self.config.resolved)
+            .set_raw_value(&config::tree::Core::UNTRACKED_CACHE, value)?;
+        local.write_to(&mut lock).map_err(|source| Error::Io { path, source })?;
+        lock.commit()?;
+        Ok(supported)
+    }
+
+    /// Return `true` if the untracked cache extension of `index` should be kept up to date, or `false` if
+    /// it should be ignored and removed from the index when it's written next.
+    ///
+    /// With `core.untrackedCache=keep`, the default, the extension is used only if `index` has it already.
+    /// If `true`, it's always used as the configuration is trusted to only be set if
+    /// [it's supported](Self::probe_untracked_cache()), which is also where it may come from.
+    #[cfg(feature = "index")]
+    pub fn use_untracked_cache(&self, index: &gix_index::State) -> Result<bool, config::key::GenericErrorWithValue> {
+        use config::untracked_cache::Mode;
+        Ok(match self.untracked_cache_mode()? {
+            Mode::Keep => index.untracked().is_some(),
+            Mode::Enabled => true,
+            Mode::Disabled => false,
+        })
+    }
+
+    /// Return the options to [edit commit message trailers](gix_object::commit::message::trailer::Editor) with,
+    /// as configured in the `trailer` section.
+    pub fn trailer_options(
+        &self,
+    ) -> Result<gix_object::commit::message::trailer::Options, config::key::GenericErrorWithValue> {
+        use crate::config::{cache::util::ApplyLeniencyDefault, tree::Trailer};
+
+        let config = &self.config.resolved;
+        let lenient = self.config.lenient_config;
+        let mut opts = gix_object::commit::message::trailer::Options::default();
+        if let Some(separators) = config.string(Trailer::SEPARATORS).filter(|s| !s.is_empty()) {
+            opts.separators = separators.into_owned();
+        }
+        if let Some(value) = config.string(Trailer::WHERE) {
+            opts.placement = Trailer::WHERE.try_into_placement(value).with_lenient_default(lenient)?;
+        }
+        if let Some(value) = config.string(Trailer::IF_EXISTS) {
+            opts.if_exists = Trailer::IF_EXISTS
+                .try_into_if_exists(value)
+                .with_lenient_default(lenient)?;
+        }
+        if let Some(value) = config.string(Trailer::IF_MISSING) {
+            opts.if_missing = Trailer::IF_MISSING
+                .try_into_if_missing(value)
+                .with_lenient_default(lenient)?;
+        }
+        Ok(opts)
+    }
+
+    /// Return the options to [validate objects](gix_object::fsck) with, with the severity of each message id
+    /// as configured with `fsck.<msg-id>`, like `fsck.zeroPaddedFilemode = ignore`.
+    ///
+    /// Unknown message ids and invalid severities are errors unless the configuration is lenient, in which case they are ignored.
+    /// `fsck.skipList` isn't supported and ignored.
+    pub fn fsck_options(&self) -> Result<gix_object::fsck::Options, config::fsck_options::Error> {
+        use crate::config::{cache::util::ApplyLeniencyDefaultValue, tree::gitoxide};
+        use gix_object::fsck::{MessageId, Severity};
+
+        let lenient = self.config.lenient_config;
+        let mut opts = gix_object::fsck::Options {
+            object_hash: self.object_hash(),
+            protect_windows: gitoxide::Core::PROTECT_WINDOWS
+                .enrich_error(
+                    self.config
+                        .resolved
+                        .boolean(gitoxide::Core::PROTECT_WINDOWS)
+                        .unwrap_or(Ok(cfg!(windows))),
+                )
+                .with_lenient_default_value(lenient, cfg!(windows))?,
+            ..Default::default()
+        };
+        for section in self
+            .config
+            .resolved
+            .sections_by_name("fsck")
+            .into_iter()
+            .flatten()
+            .filter(|s| (self.filter_config_section())(s.meta()))
+        {
+            for name in section.value_names() {
+                let name: &str = name.as_ref();
+                if name.eq_ignore_ascii_case("skipList") {
+                    continue;
+                }
+                let Some(id) = MessageId::from_name(name.into()) else {
+                    if lenient {
+                        continue;
+                    }
+                    return Err(config::fsck_options::Error::UnknownMessageId { name: name.into() });
+                };
+                let value = section.value(name).unwrap_or_default();
+                match Severity::from_name(value.as_ref()) {
+                    Some(severity) => {
+                        opts.severities.insert(id, severity);
+                    }
+                    None if lenient => {}
+                    None => {
+                        return Err(config::fsck_options::Error::InvalidSeverity {
+                            name: name.into(),
+                            value: value.into_owned(),
+                        })
+                    }
+                }
+            }
+        }
+        Ok(opts)
+    }
+
+    /// Return the options for serving this repository to clients, as configured in the `uploadpack` section.
+    ///
+    /// Hosts which embed a server can adjust these per repository to tune which capabilities are advertised
+    /// and which objects clients may ask for.
+    #[cfg(any(feature = "blocking-network-client", feature = "async-network-client"))]
+    pub fn serve_options(&self) -> Result<gix_protocol::serve::Options, config::boolean::Error> {
+        self.config.serve_options()
+    }
+
     /// The options used to open the repository.
     pub fn open_options(&self) -> &crate::open::Options {
         &self.options
@@ -130,7 +280,7 @@ impl crate::Repository {
     }
 }
 
-mod branch;
+pub(super) mod branch;
 mod remote;
 #[cfg(any(feature = "blocking-network-client", feature = "async-network-client"))]
 mod transport;
@@ -11,6 +11,8 @@ impl crate::Repository {
     ///
     /// Note that the caller may cast the instance themselves to modify it before passing it on.
     ///
+    /// Remote helpers receive the repository to transfer objects with, which they need if they can't `connect`.
+    ///
     /// For transports that support proxy authentication, the
     /// [default authentication method](crate::config::Snapshot::credential_helpers()) will be used with the url of the proxy
     /// if it contains a user name.
@@ -421,6 +423,17 @@ impl crate::Repository {
                         }
                     }
 
+                    {
+                        let key = "gitoxide.http.poolConnections";
+                        opts.pool_connections = config
+                            .boolean_filter(key, &mut trusted_only)
+                            .map(|value| config::tree::gitoxide::Http::POOL_CONNECTIONS.enrich_error(value))
+                            .transpose()
+                            .with_leniency(lenient)
+                            .map_err(config::transport::http::Error::from)?
+                            .unwrap_or_default();
+                    }
+
                     #[cfg(feature = "blocking-http-transport-curl")]
                     {
                         let key = "http.schannelCheckRevoke";
@@ -438,7 +451,14 @@ impl crate::Repository {
                     Ok(Some(Box::new(opts)))
                 }
             }
-            File | Git | Ssh | Ext(_) => Ok(None),
+            #[cfg(feature = "blocking-network-client")]
+            Ext(_) => Ok(Some(Box::new(gix_protocol::transport::client::helper::Options {
+                git_dir: Some(self.git_dir().to_owned()),
+                remote_name: remote_name.map(ToOwned::to_owned),
+            }))),
+            #[cfg(not(feature = "blocking-network-client"))]
+            Ext(_) => Ok(None),
+            File | Git | Ssh => Ok(None),
         }
     }
 }
@@ -0,0 +1,419 @@
+use gix_hash::ObjectId;
+use gix_object::bstr::BString;
+#[cfg(feature = "tree-editor")]
+use gix_object::bstr::ByteSlice;
+use gix_ref::FullName;
+
+use crate::fast_import::{self, Command, DataRef, FileChange, Mark, ObjectRef};
+
+/// The error returned by [`Repository::fast_import()`](crate::Repository::fast_import()).
+#[cfg(feature = "tree-editor")]
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error(transparent)]
+    Parse(#[from] crate::fast_import::decode::Error),
+    #[error("Mark :{mark} was used before it was defined")]
+    UnknownMark { mark: crate::fast_import::Mark },
+    #[error("Cannot copy or rename '{path}' as it doesn't exist")]
+    MissingSource { path: BString },
+    #[error("The feature '{feature}' isn't supported")]
+    UnsupportedFeature { feature: BString },
+    #[error("The stream requested the 'done' feature, but ended without 'done' command")]
+    MissingDone,
+    #[error(transparent)]
+    FindReference(#[from] crate::reference::find::existing::Error),
+    #[error(transparent)]
+    PeelReference(#[from] crate::reference::peel::Error),
+    #[error(transparent)]
+    FindObject(#[from] crate::object::find::existing::Error),
+    #[error(transparent)]
+    FindCommit(#[from] crate::object::find::existing::with_conversion::Error),
+    #[error(transparent)]
+    DecodeCommit(#[from] gix_object::decode::Error),
+    #[error(transparent)]
+    EditTree(#[from] crate::repository::edit_tree::Error),
+    #[error(transparent)]
+    InitEditor(#[from] crate::object::tree::editor::init::Error),
+    #[error(transparent)]
+    EditorEdit(#[from] gix_object::tree::editor::Error),
+    #[error(transparent)]
+    WriteTree(#[from] crate::object::tree::editor::write::Error),
+    #[error(transparent)]
+    WriteObject(#[from] crate::object::write::Error),
+    #[error(transparent)]
+    ValidateRefName(#[from] gix_validate::reference::name::Error),
+    #[error(transparent)]
+    EditReferences(#[from] crate::reference::edit::Error),
+}
+
+impl crate::Repository {
+    /// Read all commands in the `git fast-import` stream from `input`, write the objects they describe, and update all
+    /// branches and tags mentioned in the stream to point to their final commit or tag once the stream was fully read.
+    ///
+    /// Objects are written as they are encountered, which is why `checkpoint` commands have no effect.
+    ///
+    /// ### Deviation
+    ///
+    /// * References are updated unconditionally, as if `--force` was given.
+    /// * Marks can't be imported or exported from or to files, and only the `raw` date format is supported.
+    #[cfg(feature = "tree-editor")]
+    pub fn fast_import(
+        &self,
+        input: impl std::io::BufRead,
+    ) -> Result<fast_import::import::Outcome, crate::repository::fast_import::Error> {
+        use gix_ref::{
+            transaction::{Change, LogChange, PreviousValue, RefEdit, RefLog},
+            Target,
+        };
+
+        let _span = gix_trace::coarse!("gix::fast_import()");
+        let mut outcome = fast_import::import::Outcome::default();
+        // The state of each reference known to the stream, with `None` if it was reset to start from scratch.
+        let mut refs = std::collections::BTreeMap::<BString, Option<ObjectId>>::new();
+        let mut needs_done = false;
+        let mut saw_done = false;
+        for command in fast_import::Parser::new(input) {
+            match command? {
+                Command::Blob(blob) => {
+                    let id = self.write_blob(&blob.data)?.detach();
+                    set_mark(&mut outcome.marks, blob.mark, id);
+                    outcome.blobs += 1;
+                }
+                Command::Commit(commit) => {
+                    let first_parent = match &commit.from {
+                        Some(from) => Some(self.resolve_fast_import_ref(from, &outcome.marks, &refs)?),
+                        None => refs.get(&commit.name).copied().flatten(),
+                    };
+                    let base_tree = match first_parent {
+                        Some(parent) => self.find_commit(parent)?.tree_id()?.detach(),
+                        None => ObjectId::empty_tree(self.object_hash()),
+                    };
+                    let mut editor = self.edit_tree(base_tree)?;
+                    for change in commit.changes {
+                        match change {
+                            FileChange::Modify { mode, data, path } => {
+                                let id = match data {
+                                    DataRef::Mark(mark) => {
+                                        *outcome.marks.get(&mark).ok_or(Error::UnknownMark { mark })?
+                                    }
+                                    DataRef::Id(id) => id,
+                                    DataRef::Inline(data) => {
+                                        outcome.blobs += 1;
+                                        self.write_blob(&data)?.detach()
+                                    }
+                                };
+                                editor.upsert(&path, mode, id)?;
+                            }
+                            FileChange::Delete { path } => {
+                                editor.remove(&path)?;
+                            }
+                            FileChange::Copy { source, destination } => {
+                                copy_entry(self, &mut editor, &source, &destination)?;
+                            }
+                            FileChange::Rename { source, destination } => {
+                                if source != destination {
+                                    copy_entry(self, &mut editor, &source, &destination)?;
+                                    editor.remove(&source)?;
+                                }
+                            }
+                            FileChange::DeleteAll => {
+                                editor.set_root(&self.empty_tree())?;
+                            }
+                        }
+                    }
+                    let tree = editor.write()?.detach();
+
+                    let mut parents: smallvec::SmallVec<[ObjectId; 1]> = first_parent.into_iter().collect();
+                    for merge in &commit.merges {
+                        parents.push(self.resolve_fast_import_ref(merge, &outcome.marks, &refs)?);
+                    }
+                    let id = self
+                        .write_object(&gix_object::Commit {
+                            tree,
+                            parents,
+                            author: commit.author.unwrap_or_else(|| commit.committer.clone()),
+                            committer: commit.committer,
+                            encoding: commit.encoding,
+                            message: commit.message,
+                            extra_headers: Vec::new(),
+                        })?
+                        .detach();
+                    set_mark(&mut outcome.marks, commit.mark, id);
+                    refs.insert(commit.name, Some(id));
+                    outcome.commits += 1;
+                }
+                Command::Tag(tag) => {
+                    let target = self.resolve_fast_import_ref(&tag.from, &outcome.marks, &refs)?;
+                    let id = self
+                        .write_object(&gix_object::Tag {
+                            target,
+                            target_kind: self.find_header(target)?.kind(),
+                            name: tag.name.clone(),
+                            tagger: tag.tagger,
+                            message: tag.message,
+                            pgp_signature: None,
+                        })?
+                        .detach();
+                    set_mark(&mut outcome.marks, tag.mark, id);
+                    let mut name = BString::from("refs/tags/");
+                    name.extend_from_slice(&tag.name);
+                    refs.insert(name, Some(id));
+                    outcome.tags += 1;
+                }
+                Command::Reset { name, from } => {
+                    let target = from
+                        .map(|from| self.resolve_fast_import_ref(&from, &outcome.marks, &refs))
+                        .transpose()?;
+                    refs.insert(name, target);
+                }
+                Command::Feature(feature) => match feature.as_slice() {
+                    b"done" => needs_done = true,
+                    b"force" | b"date-format=raw" => {}
+                    _ => return Err(Error::UnsupportedFeature { feature }),
+                },
+                Command::Progress(_) | Command::Checkpoint => {}
+                Command::Done => {
+                    saw_done = true;
+                    break;
+                }
+            }
+        }
+        if needs_done && !saw_done {
+            return Err(Error::MissingDone);
+        }
+
+        let mut edits = Vec::new();
+        for (name, id) in refs {
+            let Some(id) = id else { continue };
+            let name = FullName::try_from(name)?;
+            edits.push(RefEdit {
+                change: Change::Update {
+                    log: LogChange {
+                        mode: RefLog::AndReference,
+                        force_create_reflog: false,
+                        message: "fast-import".into(),
+                    },
+                    expected: PreviousValue::Any,
+                    new: Target::Object(id),
+                },
+                name: name.clone(),
+                deref: false,
+            });
+            outcome.refs.push((name, id));
+        }
+        self.edit_references(edits)?;
+        Ok(outcome)
+    }
+
+    /// Write a stream understood by `git fast-import` to `out` which recreates all commits reachable from the references
+    /// with the given full `names`, along with the references themselves.
+    ///
+    /// References pointing to annotated tags are exported as `tag` commands, while all other references are set
+    /// with `reset` commands once all commits were written.
+    ///
+    /// ### Deviation
+    ///
+    /// * Extra headers of commits, like signatures, aren't exported.
+    /// * Copies and renames aren't detected, and are exported as deletions and modifications instead.
+    /// * All commits are written to the branch of the first reference, which is then reset along with all others.
+    pub fn fast_export(
+        &self,
+        names: impl IntoIterator<Item = FullName>,
+        out: &mut dyn std::io::Write,
+    ) -> Result<fast_import::export::Outcome, crate::repository::fast_export::Error> {
+        let _span = gix_trace::coarse!("gix::fast_export()");
+        let mut tips = Vec::new();
+        let mut refs = Vec::<(FullName, ObjectId)>::new();
+        for name in names {
+            let mut reference = self.find_reference(&name)?;
+            while let Some(next) = reference.follow() {
+                reference = next?;
+            }
+            let Some(id) = reference.target().try_id().map(ToOwned::to_owned) else {
+                continue;
+            };
+            tips.push(self.find_object(id)?.peel_to_commit()?.id);
+            refs.push((reference.name().to_owned(), id));
+        }
+
+        let commits = super::history::topological_order(
+            self.rev_walk(tips)
+                .all()?
+                .map(|info| info.map(|info| (info.id, info.parent_ids.into_iter().collect::<Vec<_>>())))
+                .collect::<Result<_, _>>()?,
+        );
+        let branch: BString = refs
+            .first()
+            .map_or_else(|| "refs/heads/main".into(), |(name, _)| name.as_bstr().to_owned());
+
+        let mut outcome = fast_import::export::Outcome::default();
+        let mut marks = gix_hashtable::HashMap::<ObjectId, Mark>::default();
+        let mut next_mark: Mark = 1;
+        let mut state = gix_diff::tree::State::default();
+        let empty_tree = self.empty_tree();
+        for id in commits {
+            let commit = self.find_commit(id)?;
+            let commit_ref = commit.decode()?;
+            let parents: Vec<_> = commit_ref.parents().collect();
+            let parent_tree = match parents.first() {
+                Some(parent) => Some(self.find_tree(self.find_commit(*parent)?.tree_id()?)?),
+                None => None,
+            };
+            let tree = self.find_tree(commit_ref.tree())?;
+            let mut recorder = gix_diff::tree::Recorder::default();
+            gix_diff::tree(
+                gix_object::TreeRefIter::from_bytes(&parent_tree.as_ref().unwrap_or(&empty_tree).data),
+                gix_object::TreeRefIter::from_bytes(&tree.data),
+                &mut state,
+                &self.objects,
+                &mut recorder,
+            )?;
+
+            let mut deletions = Vec::new();
+            let mut modifications = Vec::new();
+            for change in recorder.records {
+                use gix_diff::tree::recorder::Change::*;
+                match change {
+                    Deletion { entry_mode, path, .. } => {
+                        if !entry_mode.is_tree() {
+                            deletions.push(FileChange::Delete { path });
+                        }
+                    }
+                    Addition {
+                        entry_mode, oid, path, ..
+                    }
+                    | Modification {
+                        entry_mode, oid, path, ..
+                    } => {
+                        if entry_mode.is_tree() {
+                            continue;
+                        }
+                        // Submodules refer to commits which aren't part of the stream.
+                        let data = if entry_mode.is_commit() {
+                            DataRef::Id(oid)
+                        } else if let Some(mark) = marks.get(&oid) {
+                            DataRef::Mark(*mark)
+                        } else {
+                            let mark = next_mark;
+                            next_mark += 1;
+                            Command::Blob(fast_import::Blob {
+                                mark: Some(mark),
+                                original_oid: Some(oid.to_string().into()),
+                                data: self.find_blob(oid)?.take_data().into(),
+                            })
+                            .write_to(out)?;
+                            marks.insert(oid, mark);
+                            outcome.blobs += 1;
+                            DataRef::Mark(mark)
+                        };
+                        modifications.push(FileChange::Modify {
+                            mode: entry_mode.kind(),
+                            data,
+                            path,
+                        });
+                    }
+                }
+            }
+
+            if parents.is_empty() {
+                Command::Reset {
+                    name: branch.clone(),
+                    from: None,
+                }
+                .write_to(out)?;
+            }
+            let mut parents = parents.into_iter().map(|id| object_ref(&marks, id));
+            let mark = next_mark;
+            next_mark += 1;
+            Command::Commit(fast_import::Commit {
+                name: branch.clone(),
+                mark: Some(mark),
+                original_oid: Some(id.to_string().into()),
+                author: Some(commit_ref.author.into()),
+                committer: commit_ref.committer.into(),
+                encoding: commit_ref.encoding.map(ToOwned::to_owned),
+                message: commit_ref.message.to_owned(),
+                from: parents.next(),
+                merges: parents.collect(),
+                changes: deletions.into_iter().chain(modifications).collect(),
+            })
+            .write_to(out)?;
+            marks.insert(id, mark);
+            outcome.commits += 1;
+        }
+
+        for (name, id) in refs {
+            let object = self.find_object(id)?;
+            if object.kind == gix_object::Kind::Tag {
+                let tag = object.into_tag();
+                let tag = tag.decode()?;
+                Command::Tag(fast_import::Tag {
+                    name: tag.name.to_owned(),
+                    mark: None,
+                    original_oid: Some(id.to_string().into()),
+                    from: object_ref(&marks, tag.target()),
+                    tagger: tag.tagger.map(Into::into),
+                    message: tag.message.to_owned(),
+                })
+                .write_to(out)?;
+                outcome.tags += 1;
+            } else {
+                Command::Reset {
+                    name: name.as_bstr().to_owned(),
+                    from: Some(object_ref(&marks, id)),
+                }
+                .write_to(out)?;
+            }
+        }
+        Ok(outcome)
+    }
+
+    #[cfg(feature = "tree-editor")]
+    fn resolve_fast_import_ref(
+        &self,
+        object: &ObjectRef,
+        marks: &std::collections::BTreeMap<Mark, ObjectId>,
+        refs: &std::collections::BTreeMap<BString, Option<ObjectId>>,
+    ) -> Result<ObjectId, crate::repository::fast_import::Error> {
+        Ok(match object {
+            ObjectRef::Mark(mark) => *marks.get(mark).ok_or(Error::UnknownMark { mark: *mark })?,
+            ObjectRef::Id(id) => *id,
+            ObjectRef::Name(name) => match refs.get(name) {
+                Some(Some(id)) => *id,
+                _ => self.find_reference(name.as_bstr())?.peel_to_id_in_place()?.detach(),
+            },
+        })
+    }
+}
+
+/// Refer to `id` by its mark if it was part of the stream, or by its id otherwise.
+fn object_ref(marks: &gix_hashtable::HashMap<ObjectId, Mark>, id: ObjectId) -> ObjectRef {
+    match marks.get(&id) {
+        Some(mark) => ObjectRef::Mark(*mark),
+        None => ObjectRef::Id(id),
+    }
+}
+
+#[cfg(feature = "tree-editor")]
+fn set_mark(marks: &mut std::collections::BTreeMap<Mark, ObjectId>, mark: Option<Mark>, id: ObjectId) {
+    if let Some(mark) = mark {
+        marks.insert(mark, id);
+    }
+}
+
+/// Place the entry at `source` at `destination` as well, as seen in the current state of `editor`.
+#[cfg(feature = "tree-editor")]
+fn copy_entry(
+    repo: &crate::Repository,
+    editor: &mut crate::object::tree::Editor<'_>,
+    source: &BString,
+    destination: &BString,
+) -> Result<(), crate::repository::fast_import::Error> {
+    let tree = repo.find_tree(editor.write()?)?;
+    let entry = tree
+        .lookup_entry(source.split_str("/"))?
+        .ok_or_else(|| crate::repository::fast_import::Error::MissingSource { path: source.clone() })?;
+    editor.upsert(destination, entry.mode().kind(), entry.object_id())?;
+    Ok(())
+}
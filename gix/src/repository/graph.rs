@@ -8,6 +8,8 @@ impl crate::Repository {
     /// Note that a commitgraph is only allowed to be used if `core.commitGraph` is true (the default), and that configuration errors are
     /// ignored as well.
     ///
+    /// Grafts and the shallow boundary are applied as obtained by [`grafts()`](crate::Repository::grafts()).
+    ///
     /// ### Performance
     ///
     /// Note that the [Graph][gix_revwalk::Graph] can be sensitive to various object database settings that may affect the performance
@@ -15,8 +17,14 @@ impl crate::Repository {
     pub fn revision_graph<'cache, T>(
         &self,
         cache: Option<&'cache gix_commitgraph::Graph>,
-    ) -> gix_revwalk::Graph<'_, 'cache, T> {
-        gix_revwalk::Graph::new(&self.objects, cache)
+    ) -> Result<gix_revwalk::Graph<'_, 'cache, T>, crate::shallow::grafts::Error> {
+        let graph = gix_revwalk::Graph::new(&self.objects, cache);
+        let grafts = self.grafts()?;
+        Ok(if grafts.is_empty() {
+            graph
+        } else {
+            graph.with_parents(grafts)
+        })
     }
 
     /// Return a cache for commits and their graph structure, as managed by `git commit-graph`, for accelerating commit walks on
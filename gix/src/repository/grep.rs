@@ -0,0 +1,343 @@
+use std::borrow::Cow;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use gix_hash::ObjectId;
+use gix_index::entry::{Flags, Mode, Stage};
+
+use crate::bstr::{BStr, BString, ByteSlice};
+use crate::Repository;
+
+/// The error returned by [Repository::grep()](crate::Repository::grep()).
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("The worktree can only be searched in repositories with a worktree")]
+    MissingWorkDir,
+    #[error("The operation was interrupted")]
+    Interrupted,
+    #[error(transparent)]
+    OpenIndex(#[from] crate::worktree::open_index::Error),
+    #[error(transparent)]
+    IndexFromTree(#[from] crate::repository::index_from_tree::Error),
+    #[error(transparent)]
+    Pathspec(#[from] crate::pathspec::init::Error),
+    #[error(transparent)]
+    AttributeStack(#[from] crate::config::attribute_stack::Error),
+    #[error("Could not obtain attributes for '{path}'")]
+    Attributes { path: BString, source: std::io::Error },
+    #[error(transparent)]
+    FindBlob(#[from] crate::object::find::existing::with_conversion::Error),
+    #[error("Could not read '{}'", path.display())]
+    Io { path: PathBuf, source: std::io::Error },
+    #[cfg(feature = "revparse-regex")]
+    #[error("The regular expression {pattern:?} isn't valid UTF-8")]
+    RegexNotUtf8 { pattern: BString },
+    #[cfg(feature = "revparse-regex")]
+    #[error(transparent)]
+    InvalidRegex(#[from] regex::Error),
+}
+
+/// The pattern to search for with [Repository::grep()](crate::Repository::grep()).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Pattern {
+    /// Find the given bytes verbatim, like `git grep -F` does.
+    Fixed(BString),
+    /// Find matches of a regular expression, which is similar to `git grep -E`.
+    ///
+    /// It matches bytes, so classes like `\w` only match ASCII characters, but it may contain any character.
+    #[cfg(feature = "revparse-regex")]
+    Regex(BString),
+}
+
+/// The place to search for content in with [Repository::grep()](crate::Repository::grep()).
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Source {
+    /// Search the blobs of the tree with the given id, like `git grep <pattern> <tree>` does.
+    Tree(ObjectId),
+    /// Search the blobs of the index, like `git grep --cached` does.
+    ///
+    /// Conflicting entries and entries that are only intended to be added are skipped.
+    Index,
+    /// Search the files in the worktree that are tracked by the index, like `git grep` does.
+    ///
+    /// Entries that are marked to be skipped in the worktree or to be assumed unchanged are searched in the index instead,
+    /// and files that are missing in the worktree are skipped.
+    Worktree,
+}
+
+/// Determine how to handle binary files in [Repository::grep()](crate::Repository::grep()).
+#[derive(Default, Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Binary {
+    /// Report binary files that match, but without the lines that do, like `git grep` does.
+    #[default]
+    Match,
+    /// Skip binary files entirely, like `git grep -I` does.
+    Skip,
+    /// Treat binary files like text, like `git grep --text` does.
+    Text,
+}
+
+/// Options for use in [Repository::grep()](crate::Repository::grep()).
+#[derive(Default, Debug, Copy, Clone)]
+pub struct Options {
+    /// The amount of threads to use at most, or `None` to use all logical cores.
+    pub thread_limit: Option<usize>,
+    /// If `true`, the pattern matches regardless of the case of ASCII characters, like `git grep -i`.
+    pub ignore_case: bool,
+    /// How to handle binary files.
+    pub binary: Binary,
+}
+
+/// A file with at least one matching line, as returned by [Repository::grep()](crate::Repository::grep()).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct File {
+    /// The path to the file, relative to the root of the repository.
+    pub rela_path: BString,
+    /// If `true`, the file was considered binary and [`matches`](Self::matches) is empty, as binary files aren't line-based.
+    pub is_binary: bool,
+    /// All matching lines in the order they appear in the file.
+    pub matches: Vec<Match>,
+}
+
+/// A line that matches the pattern, as part of a [`File`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Match {
+    /// The number of the line, starting at 1.
+    pub line_number: usize,
+    /// The byte offset of the first match within the line, starting at 1, like `git grep --column` reports it.
+    pub column: usize,
+    /// The matching line without its line terminator.
+    pub line: BString,
+}
+
+impl Repository {
+    /// Search all files in `source` for lines matching `pattern` in parallel, and return all files with matching lines
+    /// sorted by path.
+    ///
+    /// Only files matching the `pathspecs` are searched, with all files being searched if there are none.
+    /// Only regular files are searched, whereas symbolic links and submodules are skipped like `git` does.
+    ///
+    /// Like `git`, a file is considered binary if the `diff` attribute is unset for it, and text if it is set.
+    /// Otherwise it is binary if there is a null-byte at its beginning. Attributes are read from the worktree and the index,
+    /// or only from the index in bare repositories.
+    /// `should_interrupt` is polled to abort the operation with an error.
+    ///
+    /// Note that file content is searched as stored in `git` or in the worktree, without applying filters or text conversions.
+    pub fn grep(
+        &self,
+        source: Source,
+        pattern: &Pattern,
+        pathspecs: impl IntoIterator<Item = impl AsRef<BStr>>,
+        options: Options,
+        should_interrupt: &AtomicBool,
+    ) -> Result<Vec<File>, Error> {
+        let _span = gix_trace::coarse!("gix::grep");
+        let matcher = Matcher::new(pattern, options.ignore_case)?;
+        let workdir = match source {
+            Source::Worktree => Some(self.work_dir().ok_or(Error::MissingWorkDir)?),
+            Source::Tree(_) | Source::Index => None,
+        };
+        let index: crate::worktree::IndexPersistedOrInMemory = match source {
+            Source::Tree(id) => self.index_from_tree(&id)?.into(),
+            Source::Index | Source::Worktree => self.index_or_empty()?.into(),
+        };
+
+        let attributes_source = if self.work_dir().is_some() {
+            gix_worktree::stack::state::attributes::Source::WorktreeThenIdMapping
+        } else {
+            gix_worktree::stack::state::attributes::Source::IdMapping
+        };
+        let mut attributes = self.attributes_only(&index, attributes_source)?;
+        let mut outcome = attributes.selected_attribute_matches(["diff"]);
+        let mut pathspec = self.pathspec(true, pathspecs, workdir.is_some(), &index, attributes_source)?;
+
+        let mut files = Vec::new();
+        let mut previous_path = None::<&BStr>;
+        for (rela_path, entry) in pathspec.index_entries_with_paths(&index).into_iter().flatten() {
+            if previous_path == Some(rela_path) || !matches!(entry.mode, Mode::FILE | Mode::FILE_EXECUTABLE) {
+                continue;
+            }
+            previous_path = Some(rela_path);
+            let use_blob = workdir.is_none() || entry.flags.intersects(Flags::SKIP_WORKTREE | Flags::ASSUME_VALID);
+            if use_blob && (entry.stage() != Stage::Unconflicted || entry.flags.contains(Flags::INTENT_TO_ADD)) {
+                continue;
+            }
+
+            attributes
+                .at_entry(rela_path, Some(entry.mode))
+                .map_err(|source| Error::Attributes {
+                    path: rela_path.to_owned(),
+                    source,
+                })?
+                .matching_attributes(&mut outcome);
+            let forced_binary = outcome
+                .iter_selected()
+                .next()
+                .and_then(|m| gix_filter::binary::forced_by_attribute(m.assignment.state));
+            files.push(Item {
+                rela_path: rela_path.to_owned(),
+                blob: use_blob.then_some(entry.id),
+                forced_binary,
+            });
+        }
+
+        let repo = self.clone().into_sync();
+        let mut files = gix_features::parallel::in_parallel(
+            gix_features::iter::Chunks {
+                inner: files.into_iter(),
+                size: 16,
+            },
+            options.thread_limit,
+            move |_| repo.to_thread_local(),
+            |items, repo| -> Result<Vec<_>, Error> {
+                let repo: &Repository = repo;
+                let mut out = Vec::new();
+                for item in items {
+                    if should_interrupt.load(Ordering::Relaxed) {
+                        return Err(Error::Interrupted);
+                    }
+                    let data = match item.blob {
+                        Some(id) => repo.find_blob(id)?.take_data(),
+                        None => {
+                            let path = workdir
+                                .expect("set if blobs aren't used")
+                                .join(gix_path::from_bstr(item.rela_path.as_bstr()));
+                            match gix_index::fs::Metadata::from_path_no_follow(&path) {
+                                Ok(metadata) if metadata.is_file() => {}
+                                Ok(_) => continue,
+                                Err(err) if err.kind() == std::io::ErrorKind::NotFound => continue,
+                                Err(source) => return Err(Error::Io { path, source }),
+                            }
+                            std::fs::read(&path).map_err(|source| Error::Io { path, source })?
+                        }
+                    };
+                    out.extend(search(&data, item, &matcher, options.binary));
+                }
+                Ok(out)
+            },
+            CollectFiles::default(),
+        )?;
+        files.sort_by(|a, b| a.rela_path.cmp(&b.rela_path));
+        Ok(files)
+    }
+}
+
+/// A file to search, along with everything needed to do so.
+struct Item {
+    rela_path: BString,
+    /// The blob to search, or `None` to search the file in the worktree.
+    blob: Option<ObjectId>,
+    forced_binary: Option<bool>,
+}
+
+/// Search `data` of `item` with `matcher` and return a file if something matched.
+fn search(data: &[u8], item: Item, matcher: &Matcher, binary: Binary) -> Option<File> {
+    let is_binary = binary != Binary::Text
+        && gix_filter::binary::is_binary(data, item.forced_binary, gix_filter::binary::Heuristic::NullByte);
+    if is_binary && binary == Binary::Skip {
+        return None;
+    }
+
+    let haystack = matcher.prepare(data);
+    let mut matches = Vec::new();
+    let mut binary_matches = false;
+    let mut offset = 0;
+    for (line_idx, line) in data.lines_with_terminator().enumerate() {
+        let range = offset..offset + line.len();
+        offset = range.end;
+        let line = line.strip_suffix(b"\n").unwrap_or(line);
+        let Some(pos) = matcher.find(&haystack[range][..line.len()]) else {
+            continue;
+        };
+        if is_binary {
+            binary_matches = true;
+            break;
+        }
+        matches.push(Match {
+            line_number: line_idx + 1,
+            column: pos + 1,
+            line: line.into(),
+        });
+    }
+
+    (binary_matches || !matches.is_empty()).then_some(File {
+        rela_path: item.rela_path,
+        is_binary,
+        matches,
+    })
+}
+
+enum Matcher {
+    Fixed {
+        needle: BString,
+        ignore_case: bool,
+    },
+    #[cfg(feature = "revparse-regex")]
+    Regex(regex::bytes::Regex),
+}
+
+impl Matcher {
+    fn new(pattern: &Pattern, ignore_case: bool) -> Result<Self, Error> {
+        Ok(match pattern {
+            Pattern::Fixed(needle) => Matcher::Fixed {
+                needle: if ignore_case {
+                    needle.to_ascii_lowercase().into()
+                } else {
+                    needle.clone()
+                },
+                ignore_case,
+            },
+            #[cfg(feature = "revparse-regex")]
+            Pattern::Regex(pattern) => {
+                let pattern = pattern.to_str().map_err(|_| Error::RegexNotUtf8 {
+                    pattern: pattern.clone(),
+                })?;
+                Matcher::Regex(
+                    regex::bytes::RegexBuilder::new(pattern)
+                        .case_insensitive(ignore_case)
+                        .unicode(false)
+                        .build()?,
+                )
+            }
+        })
+    }
+
+    /// Return `data` in the form that [`find()`](Self::find()) expects, with the same length.
+    fn prepare<'a>(&self, data: &'a [u8]) -> Cow<'a, [u8]> {
+        match self {
+            Matcher::Fixed { ignore_case: true, .. } => Cow::Owned(data.to_ascii_lowercase()),
+            Matcher::Fixed { ignore_case: false, .. } => Cow::Borrowed(data),
+            #[cfg(feature = "revparse-regex")]
+            Matcher::Regex(_) => Cow::Borrowed(data),
+        }
+    }
+
+    /// Return the byte offset of the first match in `line`, if there is one.
+    fn find(&self, line: &[u8]) -> Option<usize> {
+        match self {
+            Matcher::Fixed { needle, .. } => line.find(needle),
+            #[cfg(feature = "revparse-regex")]
+            Matcher::Regex(regex) => regex.find(line).map(|m| m.start()),
+        }
+    }
+}
+
+#[derive(Default)]
+struct CollectFiles(Vec<File>);
+
+impl gix_features::parallel::Reduce for CollectFiles {
+    type Input = Result<Vec<File>, Error>;
+    type FeedProduce = ();
+    type Output = Vec<File>;
+    type Error = Error;
+
+    fn feed(&mut self, item: Self::Input) -> Result<Self::FeedProduce, Self::Error> {
+        self.0.extend(item?);
+        Ok(())
+    }
+
+    fn finalize(self) -> Result<Self::Output, Self::Error> {
+        Ok(self.0)
+    }
+}
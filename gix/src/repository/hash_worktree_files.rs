@@ -0,0 +1,229 @@
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use gix_hash::ObjectId;
+use gix_index::entry::{Mode, Stat};
+
+use crate::bstr::{BStr, BString, ByteSlice};
+use crate::Repository;
+
+/// The error returned by [Repository::hash_worktree_files()](crate::Repository::hash_worktree_files()).
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("Files can only be hashed in repositories with a worktree")]
+    MissingWorkDir,
+    #[error("The operation was interrupted")]
+    Interrupted,
+    #[error(transparent)]
+    ConfigBoolean(#[from] crate::config::boolean::Error),
+    #[error(transparent)]
+    FilterPipeline(#[from] crate::repository::filter::pipeline::Error),
+    #[error(transparent)]
+    ConvertToGit(#[from] crate::filter::pipeline::convert_to_git::Error),
+    #[error("Could not read '{}'", path.display())]
+    Io { path: PathBuf, source: std::io::Error },
+    #[error("'{}' is neither a file nor a symlink", path.display())]
+    UnsupportedKind { path: PathBuf },
+    #[error(transparent)]
+    WriteObject(#[from] crate::object::write::Error),
+}
+
+/// Options for use in [Repository::hash_worktree_files()](crate::Repository::hash_worktree_files()).
+#[derive(Debug, Copy, Clone)]
+pub struct Options {
+    /// The amount of threads to use at most, or `None` to use all logical cores.
+    pub thread_limit: Option<usize>,
+    /// If `true`, write all hashed blobs into the object database, like `git add` does.
+    /// Otherwise only their ids are computed, which is all that's needed to see if content changed.
+    pub write_objects: bool,
+    /// Files at least this many bytes large are memory-mapped instead of being read into memory.
+    pub mmap_threshold: u64,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            thread_limit: None,
+            write_objects: false,
+            mmap_threshold: gix_status::worktree_file::DEFAULT_MMAP_THRESHOLD,
+        }
+    }
+}
+
+/// A file in the worktree along with its hash, as returned by [Repository::hash_worktree_files()](crate::Repository::hash_worktree_files()).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Entry {
+    /// The path to the file, relative to the root of the worktree.
+    pub rela_path: BString,
+    /// The stat information of the file as it was hashed.
+    pub stat: Stat,
+    /// The id of the file content after it was converted with the filter pipeline.
+    pub id: ObjectId,
+    /// The mode of the file, suitable for an index entry.
+    pub mode: Mode,
+}
+
+impl Repository {
+    /// Hash the files at all `rela_paths`, relative to the root of the worktree, in parallel and return them in the
+    /// same order.
+    ///
+    /// Files are passed through the filter pipeline as configured in `.gitattributes` and the git configuration,
+    /// and written into the object database if [`write_objects`](Options::write_objects) is set.
+    /// They are processed from the largest to the smallest so threads stay busy until the end, and large files are
+    /// memory-mapped instead of being read.
+    /// `should_interrupt` is polled to abort the operation with an error.
+    ///
+    /// This is the service to use for all operations that need to know the object ids of worktree files, like adding them
+    /// to the index or checking whether they changed, whereas [`dirwalk()`](Self::dirwalk()) can be used to find them
+    /// while respecting pathspecs.
+    pub fn hash_worktree_files(
+        &self,
+        rela_paths: impl IntoIterator<Item = BString>,
+        options: Options,
+        should_interrupt: &AtomicBool,
+    ) -> Result<Vec<Entry>, Error> {
+        let _span = gix_trace::coarse!("gix::hash_worktree_files");
+        let workdir = self.work_dir().ok_or(Error::MissingWorkDir)?;
+        let mut files = rela_paths
+            .into_iter()
+            .enumerate()
+            .map(|(idx, rela_path)| {
+                let path = workdir.join(gix_path::from_bstr(rela_path.as_bstr()));
+                let metadata = gix_index::fs::Metadata::from_path_no_follow(&path).map_err(|source| Error::Io {
+                    path: path.clone(),
+                    source,
+                })?;
+                if !(metadata.is_file() || metadata.is_symlink()) {
+                    return Err(Error::UnsupportedKind { path });
+                }
+                Ok((idx, rela_path, metadata))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        files.sort_by_key(|(_, _, metadata)| std::cmp::Reverse(metadata.len()));
+
+        let executable_bit = self.filesystem_options()?.executable_bit;
+        let repo = self.clone().into_sync();
+        let mut entries = gix_features::parallel::in_parallel(
+            gix_features::iter::Chunks {
+                inner: files.into_iter(),
+                size: 16,
+            },
+            options.thread_limit,
+            move |_| repo.to_thread_local(),
+            |files, repo| -> Result<Vec<_>, Error> {
+                let repo: &Repository = repo;
+                let (mut pipeline, index) = repo.filter_pipeline(None)?;
+                let mut reader = gix_status::worktree_file::Reader::new(options.mmap_threshold);
+                let mut out = Vec::new();
+                files
+                    .into_iter()
+                    .map(|(idx, rela_path, metadata)| {
+                        if should_interrupt.load(Ordering::Relaxed) {
+                            return Err(Error::Interrupted);
+                        }
+                        let id = hash_file(
+                            repo,
+                            &mut pipeline,
+                            &index,
+                            workdir,
+                            rela_path.as_bstr(),
+                            &metadata,
+                            options,
+                            &mut reader,
+                            &mut out,
+                        )?;
+                        let mode = if metadata.is_symlink() {
+                            Mode::SYMLINK
+                        } else if executable_bit && metadata.is_executable() {
+                            Mode::FILE_EXECUTABLE
+                        } else {
+                            Mode::FILE
+                        };
+                        // Times before the unix epoch can't be represented, and as with `git` they merely make the entry look modified.
+                        let stat = Stat::from_fs(&metadata).unwrap_or_default();
+                        Ok((
+                            idx,
+                            Entry {
+                                rela_path,
+                                stat,
+                                id,
+                                mode,
+                            },
+                        ))
+                    })
+                    .collect()
+            },
+            CollectEntries::default(),
+        )?;
+        entries.sort_by_key(|(idx, _)| *idx);
+        Ok(entries.into_iter().map(|(_, entry)| entry).collect())
+    }
+}
+
+/// Hash the file at `rela_path` with `metadata`, using `reader` to obtain its content and `out` to hold the output of filter processes.
+#[allow(clippy::too_many_arguments)]
+fn hash_file(
+    repo: &Repository,
+    pipeline: &mut crate::filter::Pipeline<'_>,
+    index: &gix_index::State,
+    workdir: &Path,
+    rela_path: &BStr,
+    metadata: &gix_index::fs::Metadata,
+    options: Options,
+    reader: &mut gix_status::worktree_file::Reader,
+    out: &mut Vec<u8>,
+) -> Result<ObjectId, Error> {
+    let rela_fs_path = gix_path::from_bstr(rela_path);
+    let path = workdir.join(&rela_fs_path);
+    let io_err = |source| Error::Io {
+        path: path.clone(),
+        source,
+    };
+    let store = |data: &[u8]| -> Result<ObjectId, Error> {
+        Ok(if options.write_objects {
+            repo.write_blob(data)?.detach()
+        } else {
+            gix_object::compute_hash(repo.object_hash(), gix_object::Kind::Blob, data)
+        })
+    };
+    if metadata.is_symlink() {
+        let target = std::fs::read_link(&path).map_err(io_err)?;
+        let target = gix_path::to_unix_separators_on_windows(gix_path::into_bstr(target));
+        return store(target.as_bytes());
+    }
+
+    let mut file = std::fs::File::open(&path).map_err(io_err)?;
+    let data = reader.read(&mut file, metadata.len()).map_err(io_err)?;
+    match pipeline.convert_to_git(data, &rela_fs_path, index)? {
+        gix_filter::pipeline::convert::ToGitOutcome::Unchanged(data) => store(data),
+        gix_filter::pipeline::convert::ToGitOutcome::Buffer(data) => store(data),
+        gix_filter::pipeline::convert::ToGitOutcome::Process(mut stream) => {
+            out.clear();
+            stream.read_to_end(out).map_err(io_err)?;
+            store(out)
+        }
+    }
+}
+
+type IndexedEntry = (usize, Entry);
+
+#[derive(Default)]
+struct CollectEntries(Vec<IndexedEntry>);
+
+impl gix_features::parallel::Reduce for CollectEntries {
+    type Input = Result<Vec<IndexedEntry>, Error>;
+    type FeedProduce = ();
+    type Output = Vec<IndexedEntry>;
+    type Error = Error;
+
+    fn feed(&mut self, item: Self::Input) -> Result<Self::FeedProduce, Self::Error> {
+        self.0.extend(item?);
+        Ok(())
+    }
+
+    fn finalize(self) -> Result<Self::Output, Self::Error> {
+        Ok(self.0)
+    }
+}
@@ -0,0 +1,229 @@
+use gix_hash::ObjectId;
+use gix_ref::{
+    transaction::{Change, LogChange, PreviousValue, RefEdit, RefLog},
+    FullName, Target,
+};
+
+use crate::{
+    history::rewrite::{Filter, Options, Outcome, RefUpdate},
+    repository::rewrite_history::Error,
+};
+
+impl crate::Repository {
+    /// Rewrite all commits reachable from the references with the given full `names`, using `filter` to alter their trees,
+    /// messages and signatures, and rewrite their parents so that they point to the rewritten commits.
+    /// Use `options` to further configure the operation.
+    ///
+    /// This is the foundation for operations like purging large files from history or extracting a sub-directory into its
+    /// own history, similar to what `git filter-repo` does.
+    ///
+    /// Trees are rewritten in parallel, while commits are written one at a time, parents before their children.
+    /// Commits that end up unchanged keep their id, and merely have their id mapped to itself in the [map](Outcome::map).
+    /// Note that changed commits lose their signatures, if present, as these wouldn't be valid anymore.
+    ///
+    /// ### Deviation
+    ///
+    /// References pointing to annotated tags are traversed but not updated, as tags aren't rewritten.
+    pub fn rewrite_history(
+        &self,
+        names: impl IntoIterator<Item = FullName>,
+        filter: &impl Filter,
+        options: Options,
+    ) -> Result<Outcome, Error> {
+        let _span = gix_trace::coarse!("gix::rewrite_history()");
+        let mut tips = Vec::new();
+        let mut refs_to_update = Vec::<(FullName, ObjectId)>::new();
+        for name in names {
+            let mut reference = self.find_reference(&name)?;
+            while let Some(next) = reference.follow() {
+                reference = next?;
+            }
+            let Some(id) = reference.target().try_id().map(ToOwned::to_owned) else {
+                continue;
+            };
+            let commit_id = self.find_object(id)?.peel_to_commit()?.id;
+            tips.push(commit_id);
+            if commit_id == id && !refs_to_update.iter().any(|(name, _)| name.as_ref() == reference.name()) {
+                refs_to_update.push((reference.name().to_owned(), id));
+            }
+        }
+
+        let commits = topological_order(
+            self.rev_walk(tips)
+                .with_pruned(options.hidden.iter().copied())
+                .all()?
+                .map(|info| info.map(|info| (info.id, info.parent_ids.into_iter().collect::<Vec<_>>())))
+                .collect::<Result<_, _>>()?,
+        );
+
+        let mut trees = gix_hashtable::HashSet::<ObjectId>::default();
+        for id in &commits {
+            trees.insert(self.find_commit(*id)?.tree_id()?.detach());
+        }
+        let trees = rewrite_trees(self, trees.into_iter(), filter, options.thread_limit)?;
+
+        let mut outcome = Outcome::default();
+        let empty_tree = ObjectId::empty_tree(self.object_hash());
+        for id in commits {
+            let commit = self.find_commit(id)?;
+            let commit_ref = commit.decode()?;
+            let tree = trees[&commit_ref.tree()];
+            let mut parents = smallvec::SmallVec::<[ObjectId; 1]>::new();
+            for parent in commit_ref.parents() {
+                let new_parent = match outcome.map.get(&parent) {
+                    Some(rewritten) => *rewritten,
+                    None => Some(parent),
+                };
+                if let Some(new_parent) = new_parent.filter(|p| !parents.contains(p)) {
+                    parents.push(new_parent);
+                }
+            }
+
+            if options.prune_empty && commit_ref.parents.len() <= 1 {
+                let parent_tree = match parents.first() {
+                    Some(parent) => self.find_commit(*parent)?.tree_id()?.detach(),
+                    None => empty_tree,
+                };
+                if parent_tree == tree {
+                    outcome.map.insert(id, parents.first().copied());
+                    outcome.commits_pruned += 1;
+                    continue;
+                }
+            }
+
+            let message = filter.message(&id, commit_ref.message).map_err(Error::Filter)?;
+            let author = filter.author(&id, commit_ref.author).map_err(Error::Filter)?;
+            let committer = filter.committer(&id, commit_ref.committer).map_err(Error::Filter)?;
+            let is_unchanged = message.is_none()
+                && author.is_none()
+                && committer.is_none()
+                && tree == commit_ref.tree()
+                && parents.iter().copied().eq(commit_ref.parents());
+            if is_unchanged {
+                outcome.map.insert(id, Some(id));
+                continue;
+            }
+
+            let mut new_commit = gix_object::Commit::from(commit_ref);
+            new_commit.tree = tree;
+            new_commit.parents = parents;
+            new_commit
+                .extra_headers
+                .retain(|(name, _)| !matches!(name.as_slice(), b"gpgsig" | b"gpgsig-sha256"));
+            if let Some(message) = message {
+                new_commit.message = message;
+            }
+            if let Some(author) = author {
+                new_commit.author = author;
+            }
+            if let Some(committer) = committer {
+                new_commit.committer = committer;
+            }
+            let new_id = self.write_object(&new_commit)?.detach();
+            outcome.map.insert(id, Some(new_id));
+            outcome.commits_rewritten += 1;
+        }
+
+        if options.update_refs {
+            let mut edits = Vec::new();
+            for (name, previous_id) in refs_to_update {
+                let Some(Some(new_id)) = outcome.map.get(&previous_id).copied() else {
+                    continue;
+                };
+                if new_id == previous_id {
+                    continue;
+                }
+                edits.push(RefEdit {
+                    change: Change::Update {
+                        log: LogChange {
+                            mode: RefLog::AndReference,
+                            force_create_reflog: false,
+                            message: "rewrite history".into(),
+                        },
+                        expected: PreviousValue::MustExistAndMatch(Target::Object(previous_id)),
+                        new: Target::Object(new_id),
+                    },
+                    name: name.clone(),
+                    deref: false,
+                });
+                outcome.refs.push(RefUpdate {
+                    name,
+                    previous_id,
+                    new_id,
+                });
+            }
+            self.edit_references(edits)?;
+        }
+        Ok(outcome)
+    }
+}
+
+/// Return the ids of all `commits` such that parents always come before their children.
+pub(crate) fn topological_order(commits: gix_hashtable::HashMap<ObjectId, Vec<ObjectId>>) -> Vec<ObjectId> {
+    let mut out = Vec::with_capacity(commits.len());
+    let mut seen = gix_hashtable::HashSet::default();
+    let mut stack = Vec::new();
+    for tip in commits.keys() {
+        if !seen.insert(*tip) {
+            continue;
+        }
+        stack.push((*tip, 0));
+        while let Some((id, parent_idx)) = stack.last_mut() {
+            let parents = &commits[&*id];
+            match parents.get(*parent_idx) {
+                Some(parent) => {
+                    *parent_idx += 1;
+                    if commits.contains_key(parent) && seen.insert(*parent) {
+                        stack.push((*parent, 0));
+                    }
+                }
+                None => {
+                    out.push(*id);
+                    stack.pop();
+                }
+            }
+        }
+    }
+    out
+}
+
+/// Map all `trees` through `filter` in parallel and return a mapping from the original tree to the rewritten one.
+fn rewrite_trees(
+    repo: &crate::Repository,
+    trees: impl Iterator<Item = ObjectId> + Send,
+    filter: &impl Filter,
+    thread_limit: Option<usize>,
+) -> Result<gix_hashtable::HashMap<ObjectId, ObjectId>, Error> {
+    let repo = repo.clone().into_sync();
+    gix_features::parallel::in_parallel(
+        gix_features::iter::Chunks { inner: trees, size: 16 },
+        thread_limit,
+        move |_| repo.to_thread_local(),
+        |trees, repo| {
+            trees
+                .into_iter()
+                .map(|tree| filter.tree(repo, tree).map(|new_tree| (tree, new_tree)))
+                .collect::<Result<Vec<_>, _>>()
+        },
+        CollectTrees::default(),
+    )
+}
+
+#[derive(Default)]
+struct CollectTrees(gix_hashtable::HashMap<ObjectId, ObjectId>);
+
+impl gix_features::parallel::Reduce for CollectTrees {
+    type Input = Result<Vec<(ObjectId, ObjectId)>, crate::history::rewrite::Error>;
+    type FeedProduce = ();
+    type Output = gix_hashtable::HashMap<ObjectId, ObjectId>;
+    type Error = Error;
+
+    fn feed(&mut self, item: Self::Input) -> Result<Self::FeedProduce, Self::Error> {
+        self.0.extend(item.map_err(Error::Filter)?);
+        Ok(())
+    }
+
+    fn finalize(self) -> Result<Self::Output, Self::Error> {
+        Ok(self.0)
+    }
+}
@@ -105,6 +105,7 @@ impl gix_object::Write for crate::Repository {
     }
 
     fn write_buf(&self, object: gix_object::Kind, from: &[u8]) -> Result<gix_hash::ObjectId, gix_object::write::Error> {
+        self.ensure_writable("write objects")?;
         let oid = gix_object::compute_hash(self.object_hash(), object, from);
         if self.objects.exists(&oid) {
             return Ok(oid);
@@ -146,7 +146,7 @@ impl crate::Repository {
                     source: err,
                 }
             })?,
-            self.git_dir().join("index"),
+            self.index_path(),
         ))
     }
 }
@@ -0,0 +1,122 @@
+use std::sync::atomic::AtomicBool;
+
+use gix_hash::ObjectId;
+use gix_index::entry::Flags;
+
+use crate::bstr::{BString, ByteSlice};
+use crate::Repository;
+
+/// The error returned by [Repository::index_from_worktree()](crate::Repository::index_from_worktree()).
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("An index can only be created in repositories with a worktree")]
+    MissingWorkDir,
+    #[error(transparent)]
+    ConfigBoolean(#[from] crate::config::boolean::Error),
+    #[error(transparent)]
+    Dirwalk(#[from] crate::dirwalk::Error),
+    #[error(transparent)]
+    HashFiles(#[from] crate::repository::hash_worktree_files::Error),
+    #[error(transparent)]
+    WriteObject(#[from] crate::object::write::Error),
+    #[error(transparent)]
+    EditTree(#[from] gix_object::tree::editor::Error),
+}
+
+/// The outcome of [Repository::index_from_worktree()](crate::Repository::index_from_worktree()).
+#[derive(Debug, Clone)]
+pub struct Outcome {
+    /// A new index with an entry for each file in the worktree, located where the index of the repository would be.
+    ///
+    /// It wasn't written yet.
+    pub index: gix_index::File,
+    /// The id of the tree with the same content as `index`, which was written to the object database along with all of its subtrees.
+    pub tree_id: ObjectId,
+    /// The repository-relative paths of nested repositories, which were not added as they would need a commit to point to.
+    pub skipped_repositories: Vec<BString>,
+}
+
+impl Repository {
+    /// Scan the worktree while honoring excludes like `.gitignore` files, hash all files into the object database using up
+    /// to `thread_limit` threads, and produce a new index and tree containing all of them. This is like `git add -A` into
+    /// an empty index, followed by `git write-tree`.
+    ///
+    /// Files are passed through the filter pipeline as configured in `.gitattributes` and the git configuration before
+    /// they are stored. The current index is disregarded and neither it nor references are changed, so the returned
+    /// [index](Outcome::index) must be written to become the index of this repository.
+    /// `should_interrupt` is polled to abort the operation with an error.
+    pub fn index_from_worktree(
+        &self,
+        thread_limit: Option<usize>,
+        should_interrupt: &AtomicBool,
+    ) -> Result<Outcome, Error> {
+        let _span = gix_trace::coarse!("gix::index_from_worktree");
+        if self.work_dir().is_none() {
+            return Err(Error::MissingWorkDir);
+        }
+        let options = self
+            .dirwalk_options()?
+            .empty_patterns_match_prefix(false)
+            .emit_untracked(gix_dir::walk::EmissionMode::Matching)
+            .emit_ignored(None)
+            .emit_empty_directories(false)
+            .recurse_repositories(false);
+        let mut collect = gix_dir::walk::delegate::Collect::default();
+        self.dirwalk(
+            &gix_index::State::new(self.object_hash()),
+            None::<&crate::bstr::BStr>,
+            should_interrupt,
+            options,
+            &mut collect,
+        )?;
+
+        let mut files = Vec::new();
+        let mut skipped_repositories = Vec::new();
+        for (entry, _dir_status) in collect.into_entries_by_path() {
+            if entry.status != gix_dir::entry::Status::Untracked {
+                continue;
+            }
+            match entry.disk_kind {
+                Some(gix_dir::entry::Kind::File | gix_dir::entry::Kind::Symlink) => files.push(entry.rela_path),
+                Some(gix_dir::entry::Kind::Repository) => skipped_repositories.push(entry.rela_path),
+                Some(gix_dir::entry::Kind::Directory) | None => {}
+            }
+        }
+
+        let entries = self.hash_worktree_files(
+            files,
+            crate::repository::hash_worktree_files::Options {
+                thread_limit,
+                write_objects: true,
+                ..Default::default()
+            },
+            should_interrupt,
+        )?;
+
+        let mut state = gix_index::State::new(self.object_hash());
+        let mut editor = gix_object::tree::Editor::new(gix_object::Tree::empty(), &self.objects, self.object_hash());
+        for crate::repository::hash_worktree_files::Entry {
+            rela_path,
+            stat,
+            id,
+            mode,
+        } in entries
+        {
+            let kind = mode
+                .to_tree_entry_mode()
+                .expect("only files and symlinks are added")
+                .into();
+            editor.upsert(rela_path.split(|b| *b == b'/'), kind, id)?;
+            state.dangerously_push_entry(stat, id, Flags::empty(), mode, rela_path.as_bstr());
+        }
+        state.sort_entries();
+        let tree_id = editor.write(|tree| self.write_object(tree).map(crate::Id::detach))?;
+
+        Ok(Outcome {
+            index: gix_index::File::from_state(state, self.index_path()),
+            tree_id,
+            skipped_repositories,
+        })
+    }
+}
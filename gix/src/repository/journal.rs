@@ -0,0 +1,56 @@
+use std::path::PathBuf;
+
+use gix_ref::Target;
+
+use crate::bstr::BString;
+use crate::journal::{read_if_exists, Journal, State};
+
+/// The error returned by [Repository::journal()](crate::Repository::journal()).
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error(transparent)]
+    FindHead(#[from] crate::reference::find::existing::Error),
+    #[error("Could not read the index file at '{path}'")]
+    ReadIndex { path: PathBuf, source: std::io::Error },
+    #[error(transparent)]
+    Track(#[from] crate::journal::track::Error),
+    #[error(transparent)]
+    ReadOnly(#[from] crate::repository::read_only::Error),
+}
+
+impl crate::Repository {
+    /// Create a [journal](Journal) which records the state of `HEAD`, the branch it points to, the index,
+    /// and the worktree files at `rela_paths` to be able to restore them if a compound operation fails.
+    ///
+    /// More worktree files can be [tracked](Journal::track()) as the operation progresses.
+    pub fn journal(&self, rela_paths: impl IntoIterator<Item = impl Into<BString>>) -> Result<Journal<'_>, Error> {
+        self.ensure_writable("create a journal")?;
+        let (head, referent) = match self.head()?.kind {
+            crate::head::Kind::Symbolic(reference) => {
+                let id = reference.target.try_id().map(ToOwned::to_owned);
+                (Target::Symbolic(reference.name.clone()), Some((reference.name, id)))
+            }
+            crate::head::Kind::Unborn(name) => (Target::Symbolic(name.clone()), Some((name, None))),
+            crate::head::Kind::Detached { target, .. } => (Target::Object(target), None),
+        };
+        let index_path = self.index_path();
+        let index = read_if_exists(&index_path).map_err(|err| Error::ReadIndex {
+            path: index_path,
+            source: err,
+        })?;
+        let mut journal = Journal {
+            repo: self,
+            state: Some(State {
+                head,
+                referent,
+                index,
+                files: Vec::new(),
+            }),
+        };
+        for rela_path in rela_paths {
+            journal.track(rela_path)?;
+        }
+        Ok(journal)
+    }
+}
@@ -0,0 +1,220 @@
+use std::ffi::OsString;
+
+use crate::{
+    bstr::{BString, ByteSlice},
+    config::{
+        cache::util::{ApplyLeniency, ApplyLeniencyDefault},
+        tree::{gpg, Gpg, User},
+    },
+    keyring::{
+        self,
+        program::{GpgSigner, Programs, SigningProgram, SshSigner, SshSigningKey},
+        Format,
+    },
+};
+
+/// The error returned by [Repository::keyring()](crate::Repository::keyring()),
+/// [Repository::keyring_min_trust_level()](crate::Repository::keyring_min_trust_level()) and
+/// [Repository::signer()](crate::Repository::signer()).
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error(transparent)]
+    PathInterpolation(#[from] gix_config::path::interpolate::Error),
+    #[error("gpg.minTrustLevel must be one of 'undefined', 'never', 'marginal', 'fully' or 'ultimate', got {value:?}")]
+    MinTrustLevel { value: BString },
+    #[error("gpg.format must be one of 'openpgp', 'x509' or 'ssh', got {value:?}")]
+    Format { value: BString },
+    #[error("user.signingKey needs to be set for {} signing", format.as_str())]
+    MissingSigningKey { format: Format },
+    #[error(transparent)]
+    CommitterTime(#[from] crate::config::time::Error),
+    #[error("Could not run gpg.ssh.defaultKeyCommand {command:?}")]
+    DefaultKeyCommand { command: OsString, source: std::io::Error },
+    #[error("gpg.ssh.defaultKeyCommand {command:?} failed: {stderr}")]
+    DefaultKeyCommandFailed { command: OsString, stderr: BString },
+    #[error("gpg.ssh.defaultKeyCommand {command:?} succeeded but returned no keys: {stdout} {stderr}")]
+    DefaultKeyCommandNoKey {
+        command: OsString,
+        stdout: BString,
+        stderr: BString,
+    },
+}
+
+/// Signature verification
+impl crate::Repository {
+    /// Return a keyring that verifies signatures with the programs configured in `gpg.program`, `gpg.openpgp.program`,
+    /// `gpg.x509.program` and `gpg.ssh.program`, using `gpg.ssh.allowedSignersFile` and `gpg.ssh.revocationFile`
+    /// for SSH signatures, just like `git verify-commit` and `git verify-tag` would.
+    ///
+    /// Note that the keyring is only able to verify SSH signatures if `gpg.ssh.allowedSignersFile` is set.
+    pub fn keyring(&self) -> Result<Programs, Error> {
+        let path = |key: &'static crate::config::tree::keys::Path| {
+            self.config
+                .trusted_file_path(key)
+                .transpose()
+                .with_leniency(self.config.lenient_config)
+                .map(|path| path.map(std::borrow::Cow::into_owned))
+        };
+
+        Ok(Programs {
+            openpgp: keyring::Gpg {
+                program: self.signature_program(Format::OpenPgp),
+            },
+            x509: keyring::Gpg {
+                program: self.signature_program(Format::X509),
+            },
+            ssh: path(&gpg::Ssh::ALLOWED_SIGNERS_FILE)?
+                .map(|allowed_signers| -> Result<_, Error> {
+                    Ok(keyring::program::SshAllowedSigners {
+                        program: self.signature_program(Format::Ssh),
+                        allowed_signers,
+                        revocation_file: path(&gpg::Ssh::REVOCATION_FILE)?,
+                    })
+                })
+                .transpose()?,
+        })
+    }
+
+    /// Return a signer for the format configured in `gpg.format`, defaulting to OpenPGP, which signs with the key in
+    /// `user.signingKey` using the programs configured in `gpg.program`, `gpg.openpgp.program`, `gpg.x509.program`
+    /// and `gpg.ssh.program`, just like `git commit -S` and `git tag -s` would.
+    ///
+    /// For SSH signatures, `user.signingKey` is either a literal public key prefixed with `key::`, whose private key is
+    /// held by `ssh-agent`, or the path to a key, so neither `gpg` nor its configuration are needed.
+    /// If it's unset, the first line printed by `gpg.ssh.defaultKeyCommand` is used as literal key.
+    /// For OpenPGP and X.509 signatures, the name and email of the committer are used to find the key if `user.signingKey` is unset.
+    ///
+    /// Use it with [`keyring::sign_commit()`] or [`keyring::sign_tag()`].
+    pub fn signer(&self) -> Result<SigningProgram, Error> {
+        let format = self
+            .config
+            .resolved
+            .string(&Gpg::FORMAT)
+            .map(|value| {
+                Format::from_bytes(value.trim()).ok_or_else(|| Error::Format {
+                    value: value.into_owned(),
+                })
+            })
+            .transpose()
+            .with_leniency(self.config.lenient_config)?
+            .unwrap_or(Format::OpenPgp);
+        let key = self
+            .config
+            .resolved
+            .string_filter(&User::SIGNING_KEY, &mut self.config.filter_config_section.clone())
+            .filter(|key| !key.is_empty());
+        let program = self.signature_program(format);
+
+        Ok(match format {
+            Format::OpenPgp | Format::X509 => {
+                let key = match key {
+                    Some(key) => key.into_owned(),
+                    None => {
+                        let committer = self.committer().ok_or(Error::MissingSigningKey { format })??;
+                        format!("{} <{}>", committer.name, committer.email).into()
+                    }
+                };
+                SigningProgram::Gpg(GpgSigner { program, key })
+            }
+            Format::Ssh => {
+                let key = match key {
+                    Some(key) => match SshSigningKey::from_literal(key.as_ref()) {
+                        Some(key) => key,
+                        None => SshSigningKey::Path(
+                            self.config
+                                .trusted_file_path(&User::SIGNING_KEY)
+                                .ok_or(Error::MissingSigningKey { format })??
+                                .into_owned(),
+                        ),
+                    },
+                    None => self.ssh_default_key()?.ok_or(Error::MissingSigningKey { format })?,
+                };
+                SigningProgram::Ssh(SshSigner { program, key })
+            }
+        })
+    }
+
+    /// Run `gpg.ssh.defaultKeyCommand` to obtain the literal key on the first line of its output, like `ssh-add -L` would,
+    /// or return `None` if it isn't set.
+    fn ssh_default_key(&self) -> Result<Option<SshSigningKey>, Error> {
+        let Some(command) = self.config.resolved.string_filter(
+            &gpg::Ssh::DEFAULT_KEY_COMMAND,
+            &mut self.config.filter_config_section.clone(),
+        ) else {
+            return Ok(None);
+        };
+        let command = gix_path::from_bstr(command).into_owned().into_os_string();
+        let output = std::process::Command::from(gix_command::prepare(&command).with_shell())
+            .stdin(std::process::Stdio::null())
+            .output()
+            .map_err(|source| Error::DefaultKeyCommand {
+                command: command.clone(),
+                source,
+            })?;
+        if !output.status.success() {
+            return Err(Error::DefaultKeyCommandFailed {
+                command,
+                stderr: output.stderr.into(),
+            });
+        }
+        let key = output
+            .stdout
+            .lines()
+            .next()
+            .and_then(|line| SshSigningKey::from_literal(line.trim().as_bstr()));
+        match key {
+            Some(key) => Ok(Some(key)),
+            None => Err(Error::DefaultKeyCommandNoKey {
+                command,
+                stdout: output.stdout.into(),
+                stderr: output.stderr.into(),
+            }),
+        }
+    }
+
+    /// Return `true` if objects should be signed as configured in the boolean `key`, like `commit.gpgSign`.
+    pub(crate) fn sign_by_default(
+        &self,
+        key: &'static crate::config::tree::keys::Boolean,
+    ) -> Result<bool, crate::config::boolean::Error> {
+        Ok(self
+            .config
+            .resolved
+            .boolean(key)
+            .map(|res| key.enrich_error(res).with_lenient_default(self.config.lenient_config))
+            .transpose()?
+            .unwrap_or_default())
+    }
+
+    /// Return the program to create and verify signatures of `format` with, as configured or the default one.
+    fn signature_program(&self, format: Format) -> OsString {
+        let program = |key: &'static crate::config::tree::keys::Executable| {
+            self.config
+                .resolved
+                .string_filter(key, &mut self.config.filter_config_section.clone())
+                .map(|value| gix_path::from_bstr(value).into_owned().into_os_string())
+        };
+        match format {
+            Format::OpenPgp => program(&gpg::OpenPgp::PROGRAM)
+                .or_else(|| program(&Gpg::PROGRAM))
+                .unwrap_or_else(|| "gpg".into()),
+            Format::X509 => program(&gpg::X509::PROGRAM).unwrap_or_else(|| "gpgsm".into()),
+            Format::Ssh => program(&gpg::Ssh::PROGRAM).unwrap_or_else(|| "ssh-keygen".into()),
+        }
+    }
+
+    /// Return the minimal level of trust that signatures must have to be considered valid, as configured in
+    /// `gpg.minTrustLevel`, or `None` if it isn't set.
+    pub fn keyring_min_trust_level(&self) -> Result<Option<keyring::Trust>, Error> {
+        let Some(value) = self.config.resolved.string(&Gpg::MIN_TRUST_LEVEL) else {
+            return Ok(None);
+        };
+        keyring::Trust::from_bytes(value.trim())
+            .map(Some)
+            .ok_or_else(|| Error::MinTrustLevel {
+                value: value.into_owned(),
+            })
+            .with_leniency(self.config.lenient_config)
+    }
+}
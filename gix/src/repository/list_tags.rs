@@ -0,0 +1,358 @@
+use std::cmp::Ordering;
+
+use gix_hash::ObjectId;
+
+use crate::bstr::{BString, ByteSlice};
+use crate::config::tree::VersionSort;
+use crate::Repository;
+
+/// The error returned by [Repository::list_tags()](crate::Repository::list_tags()).
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error(transparent)]
+    OpenPackedRefs(#[from] crate::reference::iter::Error),
+    #[error(transparent)]
+    IterInit(#[from] crate::reference::iter::init::Error),
+    #[error(transparent)]
+    Grafts(#[from] crate::shallow::grafts::Error),
+    #[error("Could not read a tag reference")]
+    Iter(#[source] Box<dyn std::error::Error + Send + Sync + 'static>),
+    #[error(transparent)]
+    FollowToObject(#[from] crate::reference::follow::to_object::Error),
+    #[error(transparent)]
+    Peel(#[from] crate::reference::peel::Error),
+    #[error(transparent)]
+    FindTag(#[from] crate::object::find::existing::Error),
+    #[error(transparent)]
+    DecodeTag(#[from] gix_object::decode::Error),
+    #[error(transparent)]
+    CommitGraph(#[from] crate::repository::commit_graph_if_enabled::Error),
+    #[error(transparent)]
+    FindCommit(#[from] gix_revwalk::graph::get_or_insert_default::Error),
+}
+
+/// The order in which [tags are listed](crate::Repository::list_tags()).
+#[derive(Default, Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Sort {
+    /// Sort tags by their name, byte by byte, which is what `git tag` does by default.
+    #[default]
+    Name,
+    /// Sort tags by the version numbers in their name so that `v1.10` comes after `v1.9`, like `git tag --sort=version:refname`.
+    ///
+    /// Pre-releases can be sorted before their release by configuring [suffixes](Options::version_sort_suffixes).
+    Version,
+}
+
+/// Options for use in [Repository::list_tags()](crate::Repository::list_tags()).
+#[derive(Default, Debug, Clone)]
+pub struct Options {
+    /// The order in which to return tags.
+    pub sort: Sort,
+    /// Suffixes like `-rc` that mark pre-releases when [sorting by version](Sort::Version), in the order in which
+    /// they should be sorted, similar to `versionsort.suffix`.
+    ///
+    /// Tags with one of these suffixes are sorted before those without one, so that `v1.0-rc1` comes before `v1.0`.
+    pub version_sort_suffixes: Vec<BString>,
+    /// If not empty, only list tags whose commit can reach one of these commits, like `git tag --contains`.
+    pub contains: Vec<ObjectId>,
+    /// If not empty, only list tags whose commit can't reach any of these commits, like `git tag --no-contains`.
+    pub no_contains: Vec<ObjectId>,
+    /// If not empty, only list tags that point at one of these objects directly or through a single tag object,
+    /// like `git tag --points-at`.
+    pub points_at: Vec<ObjectId>,
+}
+
+/// A tag as returned by [Repository::list_tags()](crate::Repository::list_tags()).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Tag {
+    /// The full name of the tag reference, like `refs/tags/v1.0`.
+    pub name: gix_ref::FullName,
+    /// The object the reference points to, which is a tag object for annotated tags.
+    pub id: ObjectId,
+    /// The object the tag points to after peeling all tag objects, typically a commit.
+    pub peeled: ObjectId,
+}
+
+impl Repository {
+    /// Obtain options for [`list_tags()`](Self::list_tags()) with [`version_sort_suffixes`](Options::version_sort_suffixes)
+    /// set from `versionsort.suffix`.
+    pub fn list_tags_options(&self) -> Options {
+        Options {
+            version_sort_suffixes: self
+                .config
+                .resolved
+                .strings(&VersionSort::SUFFIX)
+                .unwrap_or_default()
+                .into_iter()
+                .map(std::borrow::Cow::into_owned)
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    /// List all tags in `refs/tags` that match the filters in `options`, similar to `git tag --list`, sorted as configured.
+    ///
+    /// Filtering by [`contains`](Options::contains) or [`no_contains`](Options::no_contains) skips all tags that don't
+    /// point to a commit. The reachability of commits is determined with the help of the commit-graph if it's available,
+    /// which allows to stop traversals early.
+    pub fn list_tags(&self, options: Options) -> Result<Vec<Tag>, Error> {
+        let _span = gix_trace::coarse!("gix::list_tags()");
+        let cache = self.commit_graph_if_enabled()?;
+        let mut contains = Reachability::new(self, cache.as_ref(), &options.contains)?;
+        let mut no_contains = Reachability::new(self, cache.as_ref(), &options.no_contains)?;
+
+        let mut out = Vec::new();
+        for reference in self.references()?.tags()? {
+            let mut reference = reference.map_err(Error::Iter)?;
+            let id = reference.follow_to_object()?.detach();
+            let peeled = reference.peel_to_id_in_place()?.detach();
+            if !options.points_at.is_empty() && !self.tag_points_at(id, &options.points_at)? {
+                continue;
+            }
+            if contains.is_some() || no_contains.is_some() {
+                let is_commit = self.find_header(peeled)?.kind() == gix_object::Kind::Commit;
+                if !is_commit {
+                    continue;
+                }
+                if let Some(contains) = contains.as_mut() {
+                    if !contains.can_reach_targets(peeled)? {
+                        continue;
+                    }
+                }
+                if let Some(no_contains) = no_contains.as_mut() {
+                    if no_contains.can_reach_targets(peeled)? {
+                        continue;
+                    }
+                }
+            }
+            out.push(Tag {
+                name: reference.inner.name,
+                id,
+                peeled,
+            });
+        }
+
+        match options.sort {
+            Sort::Name => out.sort_by(|a, b| a.name.cmp(&b.name)),
+            Sort::Version => out.sort_by(|a, b| {
+                version_cmp(
+                    a.name.as_bstr().as_bytes(),
+                    b.name.as_bstr().as_bytes(),
+                    &options.version_sort_suffixes,
+                )
+                .then_with(|| a.name.cmp(&b.name))
+            }),
+        }
+        Ok(out)
+    }
+
+    /// Return `true` if `id` or the object of the tag object at `id` is contained in `points_at`.
+    fn tag_points_at(&self, id: ObjectId, points_at: &[ObjectId]) -> Result<bool, Error> {
+        if points_at.contains(&id) {
+            return Ok(true);
+        }
+        let object = self.find_object(id)?;
+        Ok(object.kind == gix_object::Kind::Tag
+            && points_at.contains(&gix_object::TagRef::from_bytes(&object.data)?.target()))
+    }
+}
+
+/// A way to learn if commits can reach one of the target commits, remembering the answer for all commits on the way.
+struct Reachability<'repo, 'cache> {
+    graph: gix_revwalk::Graph<'repo, 'cache, gix_revwalk::graph::Commit<()>>,
+    targets: gix_hashtable::HashSet<ObjectId>,
+    /// Commits with a lower generation than this can't reach any target.
+    min_generation: u32,
+    can_reach: gix_hashtable::HashMap<ObjectId, bool>,
+}
+
+impl<'repo, 'cache> Reachability<'repo, 'cache> {
+    fn new(
+        repo: &'repo Repository,
+        cache: Option<&'cache gix_commitgraph::Graph>,
+        targets: &[ObjectId],
+    ) -> Result<Option<Self>, Error> {
+        if targets.is_empty() {
+            return Ok(None);
+        }
+        let mut graph = repo.revision_graph(cache)?;
+        let mut min_generation = u32::MAX;
+        for target in targets {
+            let generation = graph
+                .get_or_insert_commit(*target, |_| {})?
+                .and_then(|commit| commit.generation);
+            min_generation = min_generation.min(generation.unwrap_or(0));
+        }
+        Ok(Some(Reachability {
+            graph,
+            targets: targets.iter().copied().collect(),
+            min_generation,
+            can_reach: Default::default(),
+        }))
+    }
+
+    /// Return `true` if `tip` is one of our targets or has one of them as ancestor.
+    fn can_reach_targets(&mut self, tip: ObjectId) -> Result<bool, Error> {
+        let mut stack = vec![tip];
+        while let Some(id) = stack.last().copied() {
+            if self.can_reach.contains_key(&id) {
+                stack.pop();
+                continue;
+            }
+            if self.targets.contains(&id) {
+                self.can_reach.insert(id, true);
+                stack.pop();
+                continue;
+            }
+            let commit = match self.graph.get_or_insert_commit(id, |_| {})? {
+                Some(commit) if commit.generation.map_or(true, |g| g >= self.min_generation) => commit,
+                _ => {
+                    self.can_reach.insert(id, false);
+                    stack.pop();
+                    continue;
+                }
+            };
+
+            let mut has_unknown_parents = false;
+            let mut reaches_target = false;
+            for parent in &commit.parents {
+                match self.can_reach.get(parent) {
+                    Some(true) => {
+                        reaches_target = true;
+                        break;
+                    }
+                    Some(false) => {}
+                    None => {
+                        has_unknown_parents = true;
+                        stack.push(*parent);
+                    }
+                }
+            }
+            if reaches_target || !has_unknown_parents {
+                self.can_reach.insert(id, reaches_target);
+                stack.truncate(stack.iter().rposition(|c| *c == id).expect("still on the stack"));
+            }
+        }
+        Ok(self.can_reach[&tip])
+    }
+}
+
+/// Compare `a` and `b` like `git` does when sorting tags by version, with `suffixes` marking pre-releases.
+fn version_cmp(a: &[u8], b: &[u8], suffixes: &[BString]) -> Ordering {
+    const S_N: usize = 0x0;
+    const S_I: usize = 0x3;
+    const S_F: usize = 0x6;
+    const S_Z: usize = 0x9;
+    const CMP: i8 = 2;
+    const LEN: i8 = 3;
+
+    #[rustfmt::skip]
+    const NEXT_STATE: [usize; 12] = [
+        /* state    x    d    0  */
+        /* S_N */  S_N, S_I, S_Z,
+        /* S_I */  S_N, S_I, S_I,
+        /* S_F */  S_N, S_F, S_F,
+        /* S_Z */  S_N, S_F, S_Z,
+    ];
+    #[rustfmt::skip]
+    const RESULT_TYPE: [i8; 36] = [
+        /* state   x/x  x/d  x/0  d/x  d/d  d/0  0/x  0/d  0/0  */
+        /* S_N */  CMP, CMP, CMP, CMP, LEN, CMP, CMP, CMP, CMP,
+        /* S_I */  CMP, -1,  -1,  1,   LEN, LEN, 1,   LEN, LEN,
+        /* S_F */  CMP, CMP, CMP, CMP, CMP, CMP, CMP, CMP, CMP,
+        /* S_Z */  CMP, 1,   1,   -1,  CMP, CMP, -1,  CMP, CMP,
+    ];
+
+    // Like C strings, bytes past the end are NUL.
+    let at = |s: &[u8], pos: usize| s.get(pos).copied().unwrap_or(0);
+    let class = |c: u8| usize::from(c == b'0') + usize::from(c.is_ascii_digit());
+
+    let mut pos = 0;
+    let (mut c1, mut c2) = (at(a, pos), at(b, pos));
+    let mut state = S_N + class(c1);
+    while c1 == c2 {
+        if c1 == 0 {
+            return Ordering::Equal;
+        }
+        pos += 1;
+        state = NEXT_STATE[state];
+        (c1, c2) = (at(a, pos), at(b, pos));
+        state += class(c1);
+    }
+    let diff = c1.cmp(&c2);
+
+    if let Some(ordering) = compare_prerelease_suffixes(a, b, pos, suffixes) {
+        return ordering;
+    }
+
+    match RESULT_TYPE[state * 3 + class(c2)] {
+        CMP => diff,
+        LEN => {
+            let mut pos = pos + 1;
+            while at(a, pos).is_ascii_digit() {
+                if !at(b, pos).is_ascii_digit() {
+                    return Ordering::Greater;
+                }
+                pos += 1;
+            }
+            if at(b, pos).is_ascii_digit() {
+                Ordering::Less
+            } else {
+                diff
+            }
+        }
+        result => result.cmp(&0),
+    }
+}
+
+/// If `a` or `b` contain one of the pre-release `suffixes` around the first differing byte at `pos`,
+/// return their order with the suffix taking precedence.
+///
+/// If both have a suffix, the order of the suffixes in `suffixes` decides. If a name contains multiple suffixes,
+/// the one starting first is used, or the longest one of those starting at the same position.
+fn compare_prerelease_suffixes(a: &[u8], b: &[u8], pos: usize, suffixes: &[BString]) -> Option<Ordering> {
+    struct Match {
+        suffix_idx: Option<usize>,
+        start: usize,
+        len: usize,
+    }
+    fn find_better_match(name: &[u8], suffix: &[u8], start: usize, suffix_idx: usize, m: &mut Match) {
+        let end = if m.suffix_idx.is_none() || m.len < suffix.len() {
+            Some(m.start)
+        } else {
+            m.start.checked_sub(1)
+        };
+        let Some(end) = end else { return };
+        if let Some(found) = (start..=end).find(|pos| name.get(*pos..).map_or(false, |name| name.starts_with(suffix))) {
+            *m = Match {
+                suffix_idx: Some(suffix_idx),
+                start: found,
+                len: suffix.len(),
+            };
+        }
+    }
+
+    let mut match_a = Match {
+        suffix_idx: None,
+        start: pos,
+        len: 0,
+    };
+    let mut match_b = Match {
+        suffix_idx: None,
+        start: pos,
+        len: 0,
+    };
+    for (suffix_idx, suffix) in suffixes.iter().enumerate() {
+        let start = pos.saturating_sub(suffix.len());
+        find_better_match(a, suffix, start, suffix_idx, &mut match_a);
+        find_better_match(b, suffix, start, suffix_idx, &mut match_b);
+    }
+    match (match_a.suffix_idx, match_b.suffix_idx) {
+        (None, None) => None,
+        (a, b) if a == b => None,
+        (Some(a), Some(b)) => Some(a.cmp(&b)),
+        (Some(_), None) => Some(Ordering::Less),
+        (None, Some(_)) => Some(Ordering::Greater),
+    }
+}
@@ -32,8 +32,37 @@ impl crate::Repository {
     }
 
     /// Return the path to the worktree index file, which may or may not exist.
+    ///
+    /// It's `$GIT_DIR/index` unless it was [overridden][crate::open::EnvironmentOverrides::index_file].
     pub fn index_path(&self) -> PathBuf {
-        self.git_dir().join("index")
+        self.options
+            .environment_overrides
+            .as_ref()
+            .and_then(|o| o.index_file.clone())
+            .unwrap_or_else(|| self.git_dir().join("index"))
+    }
+
+    /// Return the path to `rela_path` within the repository, like `git rev-parse --git-path <rela_path>` would.
+    ///
+    /// Paths which are shared among all worktrees, like `config`, `objects/` or `refs/heads/`, are placed into the
+    /// [common directory](Self::common_dir()), while worktree-specific ones like `HEAD`, `logs/HEAD` or `refs/bisect/`
+    /// are placed into the [git directory](Self::git_dir()).
+    /// The `index` file respects [overrides](Self::index_path()) as well.
+    pub fn git_path(&self, rela_path: impl AsRef<Path>) -> PathBuf {
+        let rela_path = rela_path.as_ref();
+        if rela_path == Path::new("index") {
+            return self.index_path();
+        }
+        let is_common = COMMON_PATHS
+            .iter()
+            .filter(|(prefix, _)| rela_path.starts_with(prefix))
+            .max_by_key(|(prefix, _)| prefix.len())
+            .map_or(false, |(_, is_common)| *is_common);
+        if is_common {
+            self.common_dir().join(rela_path)
+        } else {
+            self.git_dir().join(rela_path)
+        }
     }
 
     /// The path to the `.gitmodules` file in the worktree, if a worktree is available.
@@ -74,6 +103,18 @@ impl crate::Repository {
         Ok(current_dir.strip_prefix(&root).ok())
     }
 
+    /// Return `true` if the current working directory is inside the work tree, but not inside the `.git` directory,
+    /// just like `git rev-parse --is-inside-work-tree`.
+    ///
+    /// Note that the CWD is obtained once upon instantiation of the repository.
+    pub fn is_inside_work_tree(&self) -> Result<bool, gix_path::realpath::Error> {
+        if self.prefix()?.is_none() {
+            return Ok(false);
+        }
+        let git_dir = gix_path::realpath_opts(self.git_dir(), self.current_dir(), MAX_SYMLINKS)?;
+        Ok(!self.current_dir().starts_with(git_dir))
+    }
+
     /// Return the kind of repository, either bare or one with a work tree.
     pub fn kind(&self) -> crate::repository::Kind {
         match self.worktree() {
@@ -90,3 +131,32 @@ impl crate::Repository {
         }
     }
 }
+
+/// Paths within the git directory along with a flag that is `true` if they are shared among all worktrees,
+/// with the longest matching path taking precedence, just like in `git`.
+const COMMON_PATHS: &[(&str, bool)] = &[
+    ("branches", true),
+    ("common", true),
+    ("hooks", true),
+    ("info", true),
+    ("info/sparse-checkout", false),
+    ("logs", true),
+    ("logs/HEAD", false),
+    ("logs/refs/bisect", false),
+    ("logs/refs/rewritten", false),
+    ("logs/refs/worktree", false),
+    ("lost-found", true),
+    ("objects", true),
+    ("refs", true),
+    ("refs/bisect", false),
+    ("refs/rewritten", false),
+    ("refs/worktree", false),
+    ("remotes", true),
+    ("worktrees", true),
+    ("rr-cache", true),
+    ("svn", true),
+    ("config", true),
+    ("gc.pid", true),
+    ("packed-refs", true),
+    ("shallow", true),
+];
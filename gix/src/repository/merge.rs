@@ -88,7 +88,7 @@ impl Repository {
 
     /// Read all relevant configuration options to instantiate options for use in [`merge_trees()`](Self::merge_trees).
     pub fn tree_merge_options(&self) -> Result<crate::merge::tree::Options, tree_merge_options::Error> {
-        Ok(gix_merge::tree::Options {
+        let options: crate::merge::tree::Options = gix_merge::tree::Options {
             rewrites: Some(
                 crate::diff::utils::new_rewrites_inner(
                     &self.config.resolved,
@@ -109,8 +109,21 @@ impl Repository {
             marker_size_multiplier: 0,
             symlink_conflicts: None,
             allow_lossy_resolution: false,
+            detect_directory_renames: self
+                .config
+                .resolved
+                .string(&tree::Merge::DIRECTORY_RENAMES)
+                .map(|value| {
+                    tree::Merge::DIRECTORY_RENAMES
+                        .try_into_directory_renames(value)
+                        .with_lenient_default(self.config.lenient_config)
+                })
+                .transpose()?
+                .unwrap_or(true),
+            changes_cache: None,
         }
-        .into())
+        .into();
+        Ok(options.with_rerere(self.rerere_options()?.enabled))
     }
 
     /// Merge `our_tree` and `their_tree` together, assuming they have the same `ancestor_tree`, to yield a new tree
@@ -137,11 +150,12 @@ impl Repository {
         labels: gix_merge::blob::builtin_driver::text::Labels<'_>,
         options: crate::merge::tree::Options,
     ) -> Result<crate::merge::tree::Outcome<'_>, merge_trees::Error> {
+        let rerere = options.rerere;
         let mut diff_cache = self.diff_resource_cache_for_tree_diff()?;
         let mut blob_merge = self.merge_resource_cache(Default::default())?;
         let gix_merge::tree::Outcome {
-            tree,
-            conflicts,
+            mut tree,
+            mut conflicts,
             failed_on_first_unresolved_conflict,
         } = gix_merge::tree(
             ancestor_tree.as_ref(),
@@ -155,6 +169,9 @@ impl Repository {
             &mut blob_merge,
             options.into(),
         )?;
+        if rerere {
+            self.replay_recorded_resolutions(&mut tree, &mut conflicts)?;
+        }
 
         let validate = self.config.protect_options()?;
         Ok(crate::merge::tree::Outcome {
@@ -191,15 +208,16 @@ impl Repository {
         labels: gix_merge::blob::builtin_driver::text::Labels<'_>,
         options: crate::merge::commit::Options,
     ) -> Result<crate::merge::commit::Outcome<'_>, merge_commits::Error> {
+        let rerere = options.tree_merge.rerere;
         let mut diff_cache = self.diff_resource_cache_for_tree_diff()?;
         let mut blob_merge = self.merge_resource_cache(Default::default())?;
         let commit_graph = self.commit_graph_if_enabled()?;
-        let mut graph = self.revision_graph(commit_graph.as_ref());
+        let mut graph = self.revision_graph(commit_graph.as_ref())?;
         let gix_merge::commit::Outcome {
             tree_merge:
                 gix_merge::tree::Outcome {
-                    tree,
-                    conflicts,
+                    mut tree,
+                    mut conflicts,
                     failed_on_first_unresolved_conflict,
                 },
             merge_base_tree_id,
@@ -216,6 +234,9 @@ impl Repository {
             &mut |id| id.to_owned().attach(self).shorten_or_id().to_string(),
             options.into(),
         )?;
+        if rerere {
+            self.replay_recorded_resolutions(&mut tree, &mut conflicts)?;
+        }
 
         let validate = self.config.protect_options()?;
         let tree_merge = crate::merge::tree::Outcome {
@@ -250,7 +271,7 @@ impl Repository {
         options: crate::merge::tree::Options,
     ) -> Result<crate::merge::virtual_merge_base::Outcome<'_>, virtual_merge_base::Error> {
         let commit_graph = self.commit_graph_if_enabled()?;
-        let mut graph = self.revision_graph(commit_graph.as_ref());
+        let mut graph = self.revision_graph(commit_graph.as_ref())?;
         Ok(self.virtual_merge_base_with_graph(merge_bases, &mut graph, options)?)
     }
 
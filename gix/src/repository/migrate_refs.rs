@@ -0,0 +1,441 @@
+use std::{
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+use gix_hash::ObjectId;
+use gix_ref::{
+    log::Line,
+    reftable::{self, Value},
+    store::Format,
+    FullName, Target,
+};
+
+use crate::{
+    bstr::{BString, ByteSlice},
+    config::tree::{Core, Extensions},
+    Repository,
+};
+
+/// The error returned by [Repository::migrate_refs()](crate::Repository::migrate_refs()).
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("References are already stored as {format:?}")]
+    SameFormat { format: Format },
+    #[error("References can't be migrated in repositories with linked worktrees")]
+    Worktrees,
+    #[error("Could not read or write '{}'", path.display())]
+    Io { path: PathBuf, source: std::io::Error },
+    #[error(transparent)]
+    AcquireLock(#[from] gix_lock::acquire::Error),
+    #[error(transparent)]
+    CommitLock(#[from] gix_lock::commit::Error<gix_lock::File>),
+    #[error(transparent)]
+    FindReference(#[from] gix_ref::file::find::Error),
+    #[error(transparent)]
+    InitRefsIterator(#[from] gix_ref::packed::buffer::open::Error),
+    #[error(transparent)]
+    IterRefs(#[from] gix_ref::file::iter::loose_then_packed::Error),
+    #[error(transparent)]
+    ReadReflog(#[from] gix_ref::file::log::Error),
+    #[error(transparent)]
+    DecodeReflog(#[from] gix_ref::file::log::iter::decode::Error),
+    #[error(transparent)]
+    OpenReftable(#[from] reftable::open::Error),
+    #[error(transparent)]
+    WriteReftable(#[from] reftable::write::Error),
+    #[error(transparent)]
+    FindObject(#[from] crate::object::find::existing::Error),
+    #[error(transparent)]
+    LoadConfig(#[from] gix_config::file::init::from_paths::Error),
+    #[error(transparent)]
+    SetConfig(#[from] gix_config::file::set_raw_value::Error),
+    #[error(transparent)]
+    ReadOnly(#[from] crate::repository::read_only::Error),
+}
+
+/// Options for use in [Repository::migrate_refs()](crate::Repository::migrate_refs()).
+#[derive(Debug, Default, Copy, Clone)]
+pub struct Options {
+    /// If `true`, only read all references and their logs without writing anything.
+    pub dry_run: bool,
+}
+
+/// The outcome of [Repository::migrate_refs()](crate::Repository::migrate_refs()).
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Outcome {
+    /// The amount of migrated references, including `HEAD` and other references outside of `refs/`.
+    pub refs: usize,
+    /// The amount of migrated entries of all reference logs.
+    pub reflog_entries: usize,
+}
+
+/// The content of placeholder files which keep older versions of git from misreading repositories using reference tables.
+const REFTABLE_HEAD: &[u8] = b"ref: refs/heads/.invalid\n";
+const REFTABLE_REFS_HEADS: &[u8] = b"this repository uses the reftable format\n";
+
+/// All references sorted by name, and the logs of all references with their entries, oldest first.
+type Refs = (Vec<(FullName, Value)>, Vec<(FullName, Vec<Line>)>);
+
+impl Repository {
+    /// Convert all references and their logs to be stored in `format`, and configure the repository to use it,
+    /// similar to `git refs migrate --ref-format`.
+    ///
+    /// The lock files of both formats, `packed-refs.lock` and `reftable/tables.list.lock`, are held throughout
+    /// to keep out other writers which honor them.
+    /// The new format is written in full before `extensions.refStorage` is changed in the local configuration file,
+    /// and only then the references in the previous format are removed.
+    ///
+    /// ### Deviation
+    ///
+    /// Only the reference logs of existing references are migrated, and special references like `FETCH_HEAD` and `MERGE_HEAD`
+    /// are left as they are as they are stored in files in either format.
+    pub fn migrate_refs(&mut self, format: Format, options: Options) -> Result<Outcome, Error> {
+        let _span = gix_trace::coarse!("gix::migrate_refs()", format = ?format);
+        self.ensure_writable("migrate references")?;
+        let previous_format = self.refs.format();
+        if previous_format == format {
+            return Err(Error::SameFormat { format });
+        }
+        let worktrees = self.common_dir().join("worktrees");
+        if self.common_dir() != self.git_dir()
+            || !self
+                .worktrees()
+                .map_err(|source| Error::Io {
+                    path: worktrees,
+                    source,
+                })?
+                .is_empty()
+        {
+            return Err(Error::Worktrees);
+        }
+
+        let git_dir = self.git_dir().to_owned();
+        let reftable_dir = git_dir.join("reftable");
+        let locks = if options.dry_run {
+            None
+        } else {
+            let packed_lock = gix_lock::File::acquire_to_update_resource(
+                git_dir.join("packed-refs"),
+                gix_lock::acquire::Fail::Immediately,
+                None,
+            )?;
+            create_dir_all(&reftable_dir)?;
+            let tables_lock = gix_lock::File::acquire_to_update_resource(
+                reftable_dir.join("tables.list"),
+                gix_lock::acquire::Fail::Immediately,
+                None,
+            )?;
+            Some((packed_lock, tables_lock))
+        };
+
+        let (refs, logs) = match previous_format {
+            Format::Files => self.refs_in_files(&git_dir)?,
+            Format::Reftable => self.refs_in_reftable()?,
+        };
+        let outcome = Outcome {
+            refs: refs.len(),
+            reflog_entries: logs.iter().map(|(_, lines)| lines.len()).sum(),
+        };
+        let Some((packed_lock, tables_lock)) = locks else {
+            return Ok(outcome);
+        };
+
+        match format {
+            Format::Reftable => {
+                let max_update_index = logs
+                    .iter()
+                    .map(|(_, lines)| lines.len() as u64)
+                    .max()
+                    .unwrap_or(0)
+                    .max(1);
+                let table = reftable::Table {
+                    min_update_index: 1,
+                    max_update_index,
+                    refs: refs
+                        .into_iter()
+                        .map(|(name, value)| reftable::RefRecord {
+                            name: name.into_inner(),
+                            update_index: max_update_index,
+                            value,
+                        })
+                        .collect(),
+                    logs: logs
+                        .into_iter()
+                        .flat_map(|(name, lines)| {
+                            lines
+                                .into_iter()
+                                .zip(1..)
+                                .map(move |(line, update_index)| reftable::LogRecord {
+                                    name: name.as_bstr().to_owned(),
+                                    update_index,
+                                    line: Some(line),
+                                })
+                        })
+                        .collect(),
+                };
+                reftable::Stack::empty(reftable_dir, self.object_hash()).add_table(
+                    &table,
+                    tables_lock,
+                    self.refs.fsync,
+                )?;
+                self.write_ref_format_config(format)?;
+
+                remove_file(&git_dir.join("packed-refs"))?;
+                drop(packed_lock);
+                for dir in ["refs", "logs"] {
+                    remove_dir_all(&git_dir.join(dir))?;
+                }
+                for name in root_ref_names(&git_dir)? {
+                    remove_file(&git_dir.join(gix_path::from_bstr(name.as_bstr())))?;
+                }
+                write_file(&git_dir.join("HEAD"), REFTABLE_HEAD)?;
+                write_file(&git_dir.join("refs").join("heads"), REFTABLE_REFS_HEADS)?;
+            }
+            Format::Files => {
+                remove_file(&git_dir.join("refs").join("heads"))?;
+                for dir in ["heads", "tags"] {
+                    create_dir_all(&git_dir.join("refs").join(dir))?;
+                }
+                let mut packed_lock = packed_lock;
+                let path = git_dir.join("packed-refs");
+                let io_err = |source| Error::Io {
+                    path: path.clone(),
+                    source,
+                };
+                packed_lock
+                    .write_all(b"# pack-refs with: peeled fully-peeled sorted \n")
+                    .map_err(io_err)?;
+                for (name, value) in &refs {
+                    let ref_path = git_dir.join(gix_path::from_bstr(name.as_bstr()));
+                    match value {
+                        Value::Object(id) | Value::Peeled { target: id, .. }
+                            if name.as_bstr().starts_with(b"refs/") =>
+                        {
+                            let peeled = match value {
+                                Value::Peeled { peeled, .. } => Some(*peeled),
+                                _ => self.peeled(*id)?,
+                            };
+                            writeln!(packed_lock, "{id} {name}", name = name.as_bstr()).map_err(io_err)?;
+                            if let Some(peeled) = peeled {
+                                writeln!(packed_lock, "^{peeled}").map_err(io_err)?;
+                            }
+                        }
+                        Value::Object(id) | Value::Peeled { target: id, .. } => {
+                            write_file(&ref_path, format!("{id}\n").as_bytes())?;
+                        }
+                        Value::Symbolic(target) => {
+                            let mut content = BString::from("ref: ");
+                            content.extend_from_slice(target);
+                            content.push(b'\n');
+                            write_file(&ref_path, &content)?;
+                        }
+                        Value::Deletion => unreachable!("deleted references aren't returned"),
+                    }
+                }
+                packed_lock
+                    .with_mut(|file| self.refs.fsync.sync(file))
+                    .map_err(io_err)?;
+                packed_lock.commit()?;
+                for (name, lines) in &logs {
+                    let mut content = Vec::new();
+                    for line in lines {
+                        line.write_to(&mut content).expect("writing to memory doesn't fail");
+                    }
+                    write_file(
+                        &git_dir.join("logs").join(gix_path::from_bstr(name.as_bstr())),
+                        &content,
+                    )?;
+                }
+                self.write_ref_format_config(format)?;
+
+                drop(tables_lock);
+                remove_dir_all(&reftable_dir)?;
+            }
+        }
+
+        let mut refs = crate::RefStore::at(
+            git_dir,
+            gix_ref::store::init::Options {
+                write_reflog: self.refs.write_reflog,
+                object_hash: self.object_hash(),
+                precompose_unicode: self.refs.precompose_unicode,
+                prohibit_windows_device_names: self.refs.prohibit_windows_device_names,
+                ignore_case: self.refs.ignore_case,
+                fsync: self.refs.fsync,
+                format,
+            },
+        );
+        refs.namespace.clone_from(&self.refs.namespace);
+        self.refs = refs;
+        Ok(outcome)
+    }
+}
+
+impl Repository {
+    /// Read `HEAD` and other references outside of `refs/` along with all references in `refs/`, loose or packed, and their logs,
+    /// ignoring the namespace.
+    fn refs_in_files(&self, git_dir: &Path) -> Result<Refs, Error> {
+        let mut store = self.refs.clone();
+        store.namespace = None;
+        let mut refs = Vec::new();
+        for name in root_ref_names(git_dir)? {
+            if let Some(r) = store.try_find_loose(name.as_bstr())? {
+                refs.push(gix_ref::Reference::from(r));
+            }
+        }
+        for r in store.iter()?.all().map_err(|source| Error::Io {
+            path: git_dir.join("refs"),
+            source,
+        })? {
+            refs.push(r?);
+        }
+
+        let mut buf = Vec::new();
+        let mut logs = Vec::new();
+        for r in &refs {
+            if let Some(lines) = store.reflog_iter(r.name.as_ref(), &mut buf)? {
+                let lines = lines
+                    .map(|line| line.map(|line| line.to_owned()))
+                    .collect::<Result<Vec<_>, _>>()?;
+                logs.push((r.name.clone(), lines));
+            }
+        }
+        let refs = refs
+            .into_iter()
+            .map(|r| {
+                let value = match r.target {
+                    Target::Symbolic(target) => Value::Symbolic(target.into_inner()),
+                    Target::Object(id) => match r.peeled.map_or_else(|| self.peeled(id), |peeled| Ok(Some(peeled)))? {
+                        Some(peeled) => Value::Peeled { target: id, peeled },
+                        None => Value::Object(id),
+                    },
+                };
+                Ok((r.name, value))
+            })
+            .collect::<Result<_, Error>>()?;
+        Ok((refs, logs))
+    }
+
+    /// Read all references and their logs from the stack of reference tables.
+    fn refs_in_reftable(&self) -> Result<Refs, Error> {
+        let Some(stack) = self.refs.open_reftable()? else {
+            return Ok(Default::default());
+        };
+        let refs = stack
+            .iter()
+            .map(|r| {
+                let value = match (r.target, r.peeled) {
+                    (Target::Symbolic(target), _) => Value::Symbolic(target.into_inner()),
+                    (Target::Object(target), Some(peeled)) => Value::Peeled { target, peeled },
+                    (Target::Object(id), None) => Value::Object(id),
+                };
+                (r.name, value)
+            })
+            .collect();
+        let logs = stack
+            .log_names()
+            .map(|name| {
+                let lines = stack
+                    .log(name)
+                    .into_iter()
+                    .flatten()
+                    .map(|(_update_index, line)| line.clone())
+                    .collect();
+                (name.to_owned(), lines)
+            })
+            .collect();
+        Ok((refs, logs))
+    }
+
+    /// Return the object that the annotated tag `id` ultimately points to, or `None` if `id` isn't an annotated tag.
+    fn peeled(&self, id: ObjectId) -> Result<Option<ObjectId>, Error> {
+        if self.find_header(id)?.kind() != gix_object::Kind::Tag {
+            return Ok(None);
+        }
+        Ok(Some(self.find_object(id)?.peel_tags_to_end()?.id))
+    }
+
+    /// Persist `format` in the local configuration file, and apply it to our in-memory configuration as well.
+    fn write_ref_format_config(&mut self, format: Format) -> Result<(), Error> {
+        let path = self.common_dir().join("config");
+        let mut lock = gix_lock::File::acquire_to_update_resource(&path, gix_lock::acquire::Fail::Immediately, None)?;
+        let mut local = gix_config::File::from_path_no_includes(path.clone(), gix_config::Source::Local)?;
+
+        let resolved = gix_features::threading::OwnShared::make_mut(&mut self.config.resolved);
+        let format = match format {
+            Format::Files => "files",
+            Format::Reftable => "reftable",
+        };
+        local.set_raw_value(&Core::REPOSITORY_FORMAT_VERSION, "1")?;
+        local.set_raw_value(&Extensions::REF_STORAGE, format)?;
+        resolved.set_raw_value(&Core::REPOSITORY_FORMAT_VERSION, "1")?;
+        resolved.set_raw_value(&Extensions::REF_STORAGE, format)?;
+
+        local.write_to(&mut lock).map_err(|source| Error::Io { path, source })?;
+        lock.commit()?;
+        Ok(())
+    }
+}
+
+/// Return the names of `HEAD` and other references stored directly in `git_dir`, like `ORIG_HEAD`, sorted by name.
+/// Special references like `FETCH_HEAD` are stored in files in either format, and aren't returned.
+fn root_ref_names(git_dir: &Path) -> Result<Vec<BString>, Error> {
+    let entries = std::fs::read_dir(git_dir).map_err(|source| Error::Io {
+        path: git_dir.to_owned(),
+        source,
+    })?;
+    let mut names = Vec::new();
+    for entry in entries {
+        let entry = entry.map_err(|source| Error::Io {
+            path: git_dir.to_owned(),
+            source,
+        })?;
+        let Some(name) = entry.file_name().to_str().map(ToOwned::to_owned) else {
+            continue;
+        };
+        let is_root_ref =
+            name == "HEAD" || (name.ends_with("_HEAD") && name.bytes().all(|b| b.is_ascii_uppercase() || b == b'_'));
+        if is_root_ref && name != "FETCH_HEAD" && name != "MERGE_HEAD" && entry.path().is_file() {
+            names.push(name.into());
+        }
+    }
+    names.sort();
+    Ok(names)
+}
+
+fn create_dir_all(path: &Path) -> Result<(), Error> {
+    std::fs::create_dir_all(path).map_err(|source| Error::Io {
+        path: path.to_owned(),
+        source,
+    })
+}
+
+fn write_file(path: &Path, content: &[u8]) -> Result<(), Error> {
+    create_dir_all(path.parent().expect("files are in a directory"))?;
+    std::fs::write(path, content).map_err(|source| Error::Io {
+        path: path.to_owned(),
+        source,
+    })
+}
+
+fn remove_file(path: &Path) -> Result<(), Error> {
+    match std::fs::remove_file(path) {
+        Err(err) if err.kind() != std::io::ErrorKind::NotFound => Err(Error::Io {
+            path: path.to_owned(),
+            source: err,
+        }),
+        _ => Ok(()),
+    }
+}
+
+fn remove_dir_all(path: &Path) -> Result<(), Error> {
+    match std::fs::remove_dir_all(path) {
+        Err(err) if err.kind() != std::io::ErrorKind::NotFound => Err(Error::Io {
+            path: path.to_owned(),
+            source: err,
+        }),
+        _ => Ok(()),
+    }
+}
@@ -17,8 +17,13 @@ pub enum Kind {
     },
 }
 
+///
+#[cfg(feature = "status")]
+pub mod add;
 #[cfg(any(feature = "attributes", feature = "excludes"))]
 pub mod attributes;
+///
+pub mod branch_tracking;
 mod cache;
 mod config;
 ///
@@ -28,35 +33,73 @@ mod diff;
 #[cfg(feature = "dirwalk")]
 mod dirwalk;
 ///
+pub mod fast_import;
+///
 #[cfg(feature = "attributes")]
 pub mod filter;
 ///
 pub mod freelist;
 mod graph;
+///
+#[cfg(feature = "attributes")]
+pub mod grep;
+///
+#[cfg(feature = "status")]
+pub mod hash_worktree_files;
+mod history;
 pub(crate) mod identity;
 mod impls;
 #[cfg(feature = "index")]
 mod index;
+///
+#[cfg(feature = "status")]
+pub mod index_from_worktree;
 pub(crate) mod init;
+///
+pub mod journal;
+///
+#[cfg(feature = "command")]
+pub mod keyring;
 mod kind;
+///
+pub mod list_tags;
 mod location;
 #[cfg(feature = "mailmap")]
 mod mailmap;
 ///
 #[cfg(feature = "merge")]
 mod merge;
+///
+pub mod migrate_refs;
 mod object;
 #[cfg(feature = "attributes")]
 mod pathspec;
+mod push;
+///
+pub mod read_only;
 mod reference;
 mod remote;
+///
+#[cfg(feature = "merge")]
+pub mod replay;
+///
+#[cfg(feature = "merge")]
+pub mod rerere;
 mod revision;
 mod shallow;
+///
+#[cfg(feature = "mailmap")]
+pub mod shortlog;
+///
+#[cfg(feature = "worktree-mutation")]
+pub mod sparse_checkout;
 mod state;
 #[cfg(feature = "attributes")]
 mod submodule;
 mod thread_safe;
 mod worktree;
+///
+pub mod write_commit_graph;
 
 ///
 #[cfg(feature = "blob-diff")]
@@ -127,6 +170,8 @@ pub mod merge_trees {
         TreeMerge(#[from] gix_merge::tree::Error),
         #[error(transparent)]
         ValidationOptions(#[from] crate::config::boolean::Error),
+        #[error(transparent)]
+        Rerere(#[from] super::rerere::Error),
     }
 }
 
@@ -142,11 +187,15 @@ pub mod merge_commits {
         #[error(transparent)]
         MergeResourceCache(#[from] super::merge_resource_cache::Error),
         #[error(transparent)]
+        Grafts(#[from] crate::shallow::grafts::Error),
+        #[error(transparent)]
         DiffResourceCache(#[from] super::diff_resource_cache::Error),
         #[error(transparent)]
         CommitMerge(#[from] gix_merge::commit::Error),
         #[error(transparent)]
         ValidationOptions(#[from] crate::config::boolean::Error),
+        #[error(transparent)]
+        Rerere(#[from] super::rerere::Error),
     }
 }
 
@@ -161,6 +210,8 @@ pub mod virtual_merge_base {
         OpenCommitGraph(#[from] super::commit_graph_if_enabled::Error),
         #[error(transparent)]
         VirtualMergeBase(#[from] super::virtual_merge_base_with_graph::Error),
+        #[error(transparent)]
+        Grafts(#[from] crate::shallow::grafts::Error),
     }
 }
 
@@ -213,6 +264,8 @@ pub mod merge_base_octopus {
         OpenCache(#[from] crate::repository::commit_graph_if_enabled::Error),
         #[error(transparent)]
         MergeBaseOctopus(#[from] super::merge_base_octopus_with_graph::Error),
+        #[error(transparent)]
+        Grafts(#[from] crate::shallow::grafts::Error),
     }
 }
 
@@ -229,6 +282,10 @@ pub mod tree_merge_options {
         RewritesConfig(#[from] crate::diff::new_rewrites::Error),
         #[error(transparent)]
         CommandContext(#[from] crate::config::command_context::Error),
+        #[error(transparent)]
+        DirectoryRenames(#[from] crate::config::key::GenericErrorWithValue),
+        #[error(transparent)]
+        RerereOptions(#[from] crate::config::boolean::Error),
     }
 }
 
@@ -273,6 +330,8 @@ pub mod merge_base {
         OpenCache(#[from] crate::repository::commit_graph_if_enabled::Error),
         #[error(transparent)]
         FindMergeBase(#[from] gix_revision::merge_base::Error),
+        #[error(transparent)]
+        Grafts(#[from] crate::shallow::grafts::Error),
         #[error("Could not find a merge-base between commits {first} and {second}")]
         NotFound {
             first: gix_hash::ObjectId,
@@ -328,6 +387,109 @@ pub mod index_from_tree {
     }
 }
 
+///
+#[cfg(feature = "worktree-mutation")]
+pub mod abort_in_progress {
+    /// The error returned by [Repository::abort_in_progress()](crate::Repository::abort_in_progress()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("HEAD was moved to {actual} since the operation stopped at {expected}, refusing to rewind it")]
+        HeadMoved {
+            expected: gix_hash::ObjectId,
+            actual: gix_hash::ObjectId,
+        },
+        #[error("Local changes to '{path}' would be overwritten")]
+        LocalChanges { path: crate::bstr::BString },
+        #[error("Operations can only be aborted in repositories with a worktree")]
+        MissingWorkTree,
+        #[error("Could not read or write '{}'", path.display())]
+        Io {
+            path: std::path::PathBuf,
+            source: std::io::Error,
+        },
+        #[error(transparent)]
+        HeadId(#[from] crate::reference::head_id::Error),
+        #[error(transparent)]
+        FindCommit(#[from] crate::object::find::existing::with_conversion::Error),
+        #[error(transparent)]
+        DecodeCommit(#[from] gix_object::decode::Error),
+        #[error(transparent)]
+        IndexFromTree(#[from] super::index_from_tree::Error),
+        #[error(transparent)]
+        OpenIndex(#[from] crate::worktree::open_index::Error),
+        #[error(transparent)]
+        WriteIndex(#[from] gix_index::file::write::Error),
+        #[error(transparent)]
+        CheckoutOptions(#[from] crate::config::checkout_options::Error),
+        #[error(transparent)]
+        Checkout(#[from] gix_worktree_state::checkout::Error),
+        #[error("Failed to reopen object database as Arc (only if thread-safety wasn't compiled in)")]
+        OpenArcOdb(#[from] std::io::Error),
+        #[error(transparent)]
+        EditHead(#[from] crate::reference::edit::Error),
+        #[error("The branch to return to is invalid")]
+        InvalidBranchName(#[from] gix_validate::reference::name::Error),
+        #[error(transparent)]
+        FindBranch(#[from] crate::reference::find::existing::Error),
+        #[error(transparent)]
+        TryFindBranch(#[from] crate::reference::find::Error),
+        #[error(transparent)]
+        PeelBranch(#[from] crate::reference::peel::Error),
+        #[error(transparent)]
+        OpenRefsIterator(#[from] crate::reference::iter::Error),
+        #[error(transparent)]
+        InitRefsIterator(#[from] crate::reference::iter::init::Error),
+        #[error(transparent)]
+        FilterPipeline(#[from] super::filter::pipeline::Error),
+        #[error(transparent)]
+        ConvertToGit(#[from] crate::filter::pipeline::convert_to_git::Error),
+        #[error("Could not read a bisection reference")]
+        IterReferences(#[source] Box<dyn std::error::Error + Send + Sync + 'static>),
+        #[error(transparent)]
+        ReadOnly(#[from] super::read_only::Error),
+    }
+}
+
+///
+#[cfg(all(feature = "index", feature = "tree-editor"))]
+pub mod continue_in_progress {
+    /// The error returned by [Repository::continue_in_progress()](crate::Repository::continue_in_progress()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("Continuing an operation of type {in_progress:?} isn't implemented")]
+        Unsupported { in_progress: crate::state::InProgress },
+        #[error("The conflict at '{path}' must be resolved first")]
+        Conflict { path: crate::bstr::BString },
+        #[error("The index entry at '{path}' can't be stored in a tree")]
+        UnsupportedEntryMode { path: crate::bstr::BString },
+        #[error("Could not read or write '{}'", path.display())]
+        Io {
+            path: std::path::PathBuf,
+            source: std::io::Error,
+        },
+        #[error(transparent)]
+        HeadId(#[from] crate::reference::head_id::Error),
+        #[error(transparent)]
+        FindCommit(#[from] crate::object::find::existing::with_conversion::Error),
+        #[error(transparent)]
+        DecodeCommit(#[from] gix_object::decode::Error),
+        #[error(transparent)]
+        OpenIndex(#[from] crate::worktree::open_index::Error),
+        #[error(transparent)]
+        EditTree(#[from] super::edit_tree::Error),
+        #[error(transparent)]
+        AddToTree(#[from] gix_object::tree::editor::Error),
+        #[error(transparent)]
+        WriteTree(#[from] crate::object::tree::editor::write::Error),
+        #[error(transparent)]
+        Commit(#[from] crate::commit::Error),
+        #[error(transparent)]
+        ReadOnly(#[from] super::read_only::Error),
+    }
+}
+
 ///
 pub mod branch_remote_ref_name {
 
@@ -414,6 +576,66 @@ pub mod index_or_load_from_head_or_empty {
     }
 }
 
+///
+pub mod rewrite_history {
+    /// The error returned by [`Repository::rewrite_history()`](crate::Repository::rewrite_history()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        FindReference(#[from] crate::reference::find::existing::Error),
+        #[error(transparent)]
+        FollowReference(#[from] gix_ref::file::find::existing::Error),
+        #[error(transparent)]
+        FindObject(#[from] crate::object::find::existing::Error),
+        #[error(transparent)]
+        FindCommit(#[from] crate::object::find::existing::with_conversion::Error),
+        #[error(transparent)]
+        PeelToCommit(#[from] crate::object::peel::to_kind::Error),
+        #[error(transparent)]
+        DecodeCommit(#[from] gix_object::decode::Error),
+        #[error(transparent)]
+        Walk(#[from] crate::revision::walk::Error),
+        #[error(transparent)]
+        WalkIter(#[from] crate::revision::walk::iter::Error),
+        #[error("A history filter failed")]
+        Filter(#[source] crate::history::rewrite::Error),
+        #[error(transparent)]
+        WriteObject(#[from] crate::object::write::Error),
+        #[error(transparent)]
+        EditReferences(#[from] crate::reference::edit::Error),
+    }
+}
+
+///
+pub mod fast_export {
+    /// The error returned by [`Repository::fast_export()`](crate::Repository::fast_export()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        FindReference(#[from] crate::reference::find::existing::Error),
+        #[error(transparent)]
+        FollowReference(#[from] gix_ref::file::find::existing::Error),
+        #[error(transparent)]
+        FindObject(#[from] crate::object::find::existing::Error),
+        #[error(transparent)]
+        FindCommit(#[from] crate::object::find::existing::with_conversion::Error),
+        #[error(transparent)]
+        PeelToCommit(#[from] crate::object::peel::to_kind::Error),
+        #[error(transparent)]
+        Decode(#[from] gix_object::decode::Error),
+        #[error(transparent)]
+        Walk(#[from] crate::revision::walk::Error),
+        #[error(transparent)]
+        WalkIter(#[from] crate::revision::walk::iter::Error),
+        #[error(transparent)]
+        Diff(#[from] gix_diff::tree::Error),
+        #[error(transparent)]
+        Io(#[from] std::io::Error),
+    }
+}
+
 ///
 #[cfg(feature = "worktree-stream")]
 pub mod worktree_stream {
@@ -431,7 +653,9 @@ pub mod worktree_stream {
         FilterPipeline(#[from] crate::filter::pipeline::options::Error),
         #[error(transparent)]
         CommandContext(#[from] crate::config::command_context::Error),
-        #[error("Needed {id} to be a tree to turn into a workspace stream, got {actual}")]
+        #[error(transparent)]
+        DecodeCommit(#[from] gix_object::decode::Error),
+        #[error("Needed {id} to be a tree or commit to turn into a workspace stream, got {actual}")]
         NotATree {
             id: gix_hash::ObjectId,
             actual: gix_object::Kind,
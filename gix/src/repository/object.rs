@@ -156,6 +156,24 @@ impl crate::Repository {
     }
 }
 
+/// Shorten object ids
+impl crate::Repository {
+    /// Turn all `ids` into shortened ids with a length in hex as configured by `core.abbrev`, or longer if that's needed
+    /// to make them unambiguous, and return them in the same order.
+    ///
+    /// This is the same as calling [`Id::shorten()`] on each of them, but much faster when displaying many ids at once,
+    /// like in logs, as the object database is only prepared once and loose objects are only listed once per fanout directory.
+    /// Note that unlike [`Id::shorten()`], ids of objects that don't exist are shortened as well, so that they don't match
+    /// any object that exists.
+    pub fn shorten_ids(
+        &self,
+        ids: impl IntoIterator<Item = impl AsRef<gix_hash::oid>>,
+    ) -> Result<Vec<gix_hash::Prefix>, crate::id::shorten::Error> {
+        let hex_len = crate::id::configured_hex_len(self)?;
+        Ok(self.objects.disambiguate_prefixes(ids, hex_len)?)
+    }
+}
+
 /// Write objects of any type.
 impl crate::Repository {
     /// Write the given object into the object database and return its object id.
@@ -172,6 +190,8 @@ impl crate::Repository {
     }
 
     fn write_object_inner(&self, buf: &[u8], kind: gix_object::Kind) -> Result<Id<'_>, object::write::Error> {
+        self.ensure_writable("write objects")
+            .map_err(|err| object::write::Error(Box::new(err)))?;
         let oid = gix_object::compute_hash(self.object_hash(), kind, buf);
         if self.objects.exists(&oid) {
             return Ok(oid.attach(self));
@@ -188,6 +208,8 @@ impl crate::Repository {
     /// We avoid writing duplicate objects to slow disks that will eventually have to be garbage collected by
     /// pre-hashing the data, and checking if the object is already present.
     pub fn write_blob(&self, bytes: impl AsRef<[u8]>) -> Result<Id<'_>, object::write::Error> {
+        self.ensure_writable("write objects")
+            .map_err(|err| object::write::Error(Box::new(err)))?;
         let bytes = bytes.as_ref();
         let oid = gix_object::compute_hash(self.object_hash(), gix_object::Kind::Blob, bytes);
         if self.objects.exists(&oid) {
@@ -216,6 +238,8 @@ impl crate::Repository {
     }
 
     fn write_blob_stream_inner(&self, buf: &[u8]) -> Result<Id<'_>, object::write::Error> {
+        self.ensure_writable("write objects")
+            .map_err(|err| object::write::Error(Box::new(err)))?;
         let oid = gix_object::compute_hash(self.object_hash(), gix_object::Kind::Blob, buf);
         if self.objects.exists(&oid) {
             return Ok(oid.attach(self));
@@ -232,6 +256,7 @@ impl crate::Repository {
 impl crate::Repository {
     /// Create a tag reference named `name` (without `refs/tags/` prefix) pointing to a newly created tag object
     /// which in turn points to `target` and return the newly created reference.
+    /// With the `command` feature, the tag object is signed with the configured signer if `tag.gpgSign` is enabled.
     ///
     /// It will be created with `constraint` which is most commonly to [only create it][PreviousValue::MustNotExist]
     /// or to [force overwriting a possibly existing tag](PreviousValue::Any).
@@ -252,10 +277,41 @@ impl crate::Repository {
             message: message.as_ref().into(),
             pgp_signature: None,
         };
+        #[cfg(feature = "command")]
+        let tag = {
+            let mut tag = tag;
+            if self.sign_by_default(&crate::config::tree::Tag::GPG_SIGN)? {
+                crate::keyring::sign_tag(&mut tag, &self.signer()?)?;
+            }
+            tag
+        };
         let tag_id = self.write_object(&tag)?;
         self.tag_reference(name, tag_id, constraint).map_err(Into::into)
     }
 
+    /// Write the annotated `tag` as object and create a tag reference with the tag's name pointing to it, in a single
+    /// reference transaction with `constraint`, and return the newly created reference.
+    ///
+    /// Unlike [`tag()`](Self::tag()), this verifies that the target of `tag` exists and is of the kind the tag claims it to be,
+    /// and it retains everything about `tag`, like its signature. Use [`gix_object::Tag::new()`] to create it with a
+    /// validated name and a message formatted like `git tag` does.
+    pub fn create_tag(&self, tag: &gix_object::Tag, constraint: PreviousValue) -> Result<Reference<'_>, tag::Error> {
+        let actual = self.find_header(tag.target)?.kind();
+        if actual != tag.target_kind {
+            return Err(tag::Error::TargetKindMismatch {
+                id: tag.target,
+                expected: tag.target_kind,
+                actual,
+            });
+        }
+        let mut name = crate::bstr::BString::from("refs/tags/");
+        name.extend_from_slice(&tag.name);
+        let name = FullName::try_from(name)?;
+        let tag_id = self.write_object(tag)?;
+        self.tag_reference_inner(name, tag_id.detach(), constraint)
+            .map_err(Into::into)
+    }
+
     /// Similar to [`commit(…)`][crate::Repository::commit()], but allows to create the commit with `committer` and `author` specified.
     ///
     /// This forces setting the commit time and author time by hand. Note that typically, committer and author are the same.
@@ -307,6 +363,14 @@ impl crate::Repository {
             parents,
             extra_headers: Default::default(),
         };
+        #[cfg(feature = "command")]
+        let commit = {
+            let mut commit = commit;
+            if self.sign_by_default(&crate::config::tree::Commit::GPG_SIGN)? {
+                crate::keyring::sign_commit(&mut commit, &self.signer()?)?;
+            }
+            commit
+        };
 
         let commit_id = self.write_object(&commit)?;
         self.edit_reference(RefEdit {
@@ -346,6 +410,8 @@ impl crate::Repository {
     /// The first parent id in `parents` is expected to be the current target of `reference` and the operation will fail if it is not.
     /// If there is no parent, the `reference` is expected to not exist yet.
     ///
+    /// With the `command` feature, the commit is signed with the [configured signer](Self::signer()) if `commit.gpgSign` is enabled.
+    ///
     /// The method fails immediately if a `reference` lock can't be acquired.
     pub fn commit<Name, E>(
         &self,
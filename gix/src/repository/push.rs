@@ -0,0 +1,171 @@
+#[cfg(any(feature = "blocking-network-client", feature = "async-network-client"))]
+use crate::push::{check, RefUpdate, Status};
+
+/// Preparing pushes
+impl crate::Repository {
+    /// Resolve the expected value of the lease `spec` into an [`Expect`](crate::push::lease::Expect) for use in a
+    /// [`RefUpdate`](crate::push::RefUpdate).
+    #[cfg(feature = "revision")]
+    pub fn push_lease_expect(
+        &self,
+        spec: &crate::push::lease::Spec,
+    ) -> Result<crate::push::lease::Expect, crate::push::lease::expect::Error> {
+        use crate::{bstr::ByteSlice, push::lease};
+        Ok(match spec.expect.as_ref() {
+            None => lease::Expect::RemoteTracking,
+            Some(rev) if rev.is_empty() => lease::Expect::Value(None),
+            Some(rev) => lease::Expect::Value(Some(
+                self.rev_parse_single(rev.as_bstr())
+                    .map_err(|source| lease::expect::Error::RevParse {
+                        spec: rev.clone(),
+                        source,
+                    })?
+                    .detach(),
+            )),
+        })
+    }
+
+    /// Check all `updates` of references on `remote` against `remote_refs`, the references it advertised when connecting,
+    /// and return the status of each update in order.
+    ///
+    /// Leases are checked first, and [`RemoteTracking`](crate::push::lease::Expect::RemoteTracking) uses the remote-tracking references as configured by the fetch
+    /// refspecs of `remote`, so updates are rejected as stale if the remote changed since we last fetched from it.
+    /// Updates that aren't forced are rejected if they would lose history or move existing tags.
+    /// Like `git`, tags are peeled to the commit they point to, and updates from or to objects that aren't commits are never
+    /// considered fast-forwards.
+    ///
+    /// All updates share the same commit graph, so commits are only decoded once, and the commit-graph is used to stop
+    /// traversals early if it is available.
+    ///
+    /// This is meant to be done before any pack is sent, so that rejected updates can be removed from the push.
+    #[cfg(any(feature = "blocking-network-client", feature = "async-network-client"))]
+    pub fn push_check(
+        &self,
+        remote: &crate::Remote<'_>,
+        updates: &[RefUpdate],
+        remote_refs: &[gix_protocol::handshake::Ref],
+    ) -> Result<Vec<Status>, check::Error> {
+        use crate::push::lease;
+        let cache = self.commit_graph_if_enabled()?;
+        let mut graph = self.revision_graph(cache.as_ref())?;
+        updates
+            .iter()
+            .map(|update| {
+                let actual = remote_refs
+                    .iter()
+                    .map(gix_protocol::handshake::Ref::unpack)
+                    .find(|(name, _, _)| *name == update.remote_ref.as_bstr())
+                    .and_then(|(_, target, _)| target.map(ToOwned::to_owned));
+                if let Some(lease) = update.lease {
+                    let expected = match lease {
+                        lease::Expect::Value(value) => value,
+                        lease::Expect::RemoteTracking => self.remote_tracking_id(remote, update.remote_ref.as_ref())?,
+                    };
+                    if expected != actual {
+                        return Ok(Status::RejectedStale { expected, actual });
+                    }
+                }
+                if update.new == actual {
+                    return Ok(Status::UpToDate);
+                }
+                let (Some(actual), Some(new)) = (actual, update.new) else {
+                    return Ok(Status::Ok);
+                };
+                if update.force || update.lease.is_some() {
+                    return Ok(Status::Ok);
+                }
+                if update.remote_ref.as_bstr().starts_with(b"refs/tags/") {
+                    return Ok(Status::RejectedAlreadyExists);
+                }
+                if !self.has_object(actual) {
+                    return Ok(Status::RejectedFetchFirst);
+                }
+                let (Some(actual), Some(new)) = (self.peel_to_commit_id(actual)?, self.peel_to_commit_id(new)?) else {
+                    return Ok(Status::RejectedNonFastForward);
+                };
+                Ok(if is_ancestor(&mut graph, actual, new)? {
+                    Status::Ok
+                } else {
+                    Status::RejectedNonFastForward
+                })
+            })
+            .collect()
+    }
+
+    /// Peel `id` to the commit it points to if it's a tag, and return it, or `None` if it's not a commit.
+    #[cfg(any(feature = "blocking-network-client", feature = "async-network-client"))]
+    fn peel_to_commit_id(&self, id: gix_hash::ObjectId) -> Result<Option<gix_hash::ObjectId>, check::Error> {
+        let object = self.find_object(id)?.peel_tags_to_end()?;
+        Ok((object.kind == gix_object::Kind::Commit).then_some(object.id))
+    }
+
+    /// Return the value of the remote-tracking reference of `remote_ref` on `remote`, or `None` if there is none.
+    #[cfg(any(feature = "blocking-network-client", feature = "async-network-client"))]
+    fn remote_tracking_id(
+        &self,
+        remote: &crate::Remote<'_>,
+        remote_ref: &gix_ref::FullNameRef,
+    ) -> Result<Option<gix_hash::ObjectId>, check::Error> {
+        let tracking_ref = match super::config::branch::matching_remote(
+            remote_ref,
+            remote.refspecs(crate::remote::Direction::Fetch),
+            self.object_hash(),
+        ) {
+            None => return Ok(None),
+            Some(res) => res.map_err(|source| check::Error::RemoteTrackingRefName {
+                name: remote_ref.to_owned(),
+                source,
+            })?,
+        };
+        Ok(self
+            .try_find_reference(tracking_ref.as_ref())?
+            .and_then(|r| r.target().try_id().map(ToOwned::to_owned)))
+    }
+}
+
+/// Return `true` if `ancestor` is reachable from `tip`.
+///
+/// Commits are visited in order of generation and commit time, and with generation numbers, commits that are
+/// older than `ancestor` aren't traversed any further as `ancestor` can't be reachable from them.
+#[cfg(any(feature = "blocking-network-client", feature = "async-network-client"))]
+fn is_ancestor(
+    graph: &mut gix_revwalk::Graph<'_, '_, gix_revwalk::graph::Commit<bool>>,
+    ancestor: gix_hash::ObjectId,
+    tip: gix_hash::ObjectId,
+) -> Result<bool, gix_revwalk::graph::get_or_insert_default::Error> {
+    fn key(commit: &gix_revwalk::graph::Commit<bool>) -> (u32, gix_date::SecondsSinceUnixEpoch) {
+        (
+            commit.generation.unwrap_or(gix_commitgraph::GENERATION_NUMBER_INFINITY),
+            commit.commit_time,
+        )
+    }
+    graph.clear_commit_data(|seen| *seen = false);
+    let Some(min_generation) = graph.get_or_insert_full_commit(ancestor, |_| {})?.map(|c| c.generation) else {
+        return Ok(false);
+    };
+    let mut queue = gix_revwalk::PriorityQueue::new();
+    graph.get_or_insert_full_commit(tip, |commit| {
+        commit.data = true;
+        queue.insert(key(commit), tip);
+    })?;
+    while let Some((_key, id)) = queue.pop() {
+        if id == ancestor {
+            return Ok(true);
+        }
+        let commit = graph.get(&id).expect("queued commits are in the graph");
+        if let (Some(generation), Some(min_generation)) = (commit.generation, min_generation) {
+            if generation <= min_generation {
+                continue;
+            }
+        }
+        for parent_id in commit.parents.clone() {
+            graph.get_or_insert_full_commit(parent_id, |parent| {
+                if !parent.data {
+                    parent.data = true;
+                    queue.insert(key(parent), parent_id);
+                }
+            })?;
+        }
+    }
+    Ok(false)
+}
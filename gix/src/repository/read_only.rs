@@ -0,0 +1,27 @@
+use crate::Repository;
+
+/// The error returned by operations that would write to a repository which was opened
+/// [read-only](crate::open::Options::read_only()).
+#[derive(Debug, thiserror::Error)]
+#[error("Refusing to {operation} as the repository was opened read-only")]
+pub struct Error {
+    /// A short description of the write operation that was prevented.
+    pub operation: &'static str,
+}
+
+impl Repository {
+    /// Return `true` if this repository was opened [read-only](crate::open::Options::read_only()),
+    /// which causes all operations that would write to it to fail.
+    pub fn is_read_only(&self) -> bool {
+        self.options.read_only
+    }
+
+    /// Fail with an error naming `operation` if this repository is read-only.
+    pub(crate) fn ensure_writable(&self, operation: &'static str) -> Result<(), Error> {
+        if self.options.read_only {
+            Err(Error { operation })
+        } else {
+            Ok(())
+        }
+    }
+}
@@ -18,14 +18,26 @@ impl crate::Repository {
         target: impl Into<ObjectId>,
         constraint: PreviousValue,
     ) -> Result<Reference<'_>, reference::edit::Error> {
-        let id = target.into();
+        self.tag_reference_inner(
+            format!("refs/tags/{}", name.as_ref()).try_into()?,
+            target.into(),
+            constraint,
+        )
+    }
+
+    pub(crate) fn tag_reference_inner(
+        &self,
+        name: FullName,
+        id: ObjectId,
+        constraint: PreviousValue,
+    ) -> Result<Reference<'_>, reference::edit::Error> {
         let mut edits = self.edit_reference(RefEdit {
             change: Change::Update {
                 log: Default::default(),
                 expected: constraint,
                 new: Target::Object(id),
             },
-            name: format!("refs/tags/{}", name.as_ref()).try_into()?,
+            name,
             deref: false,
         })?;
         assert_eq!(edits.len(), 1, "reference splits should ever happen");
@@ -143,6 +155,7 @@ impl crate::Repository {
         &self,
         edits: impl IntoIterator<Item = RefEdit>,
     ) -> Result<Vec<RefEdit>, reference::edit::Error> {
+        self.ensure_writable("edit references")?;
         let (file_lock_fail, packed_refs_lock_fail) = self.config.lock_timeout()?;
         self.refs
             .transaction()
@@ -0,0 +1,154 @@
+use gix_hash::ObjectId;
+
+use crate::{
+    bstr::{BString, ByteSlice},
+    merge::replay::{Conflict, Options, Outcome, Replayed},
+    Repository,
+};
+
+/// The error returned by [Repository::replay()](crate::Repository::replay()).
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("Commit {id} is a merge commit, which can't be replayed")]
+    MergeCommit { id: ObjectId },
+    #[error(transparent)]
+    CommitterMissing(#[from] crate::commit::Error),
+    #[error(transparent)]
+    CommitterTime(#[from] crate::config::time::Error),
+    #[error(transparent)]
+    OpenCommitGraph(#[from] super::commit_graph_if_enabled::Error),
+    #[error(transparent)]
+    Grafts(#[from] crate::shallow::grafts::Error),
+    #[error(transparent)]
+    FindMergeBase(#[from] gix_revision::merge_base::Error),
+    #[error(transparent)]
+    FindCommit(#[from] crate::object::find::existing::with_conversion::Error),
+    #[error(transparent)]
+    DecodeCommit(#[from] gix_object::decode::Error),
+    #[error(transparent)]
+    MergeTrees(#[from] super::merge_trees::Error),
+    #[error(transparent)]
+    WriteTree(#[from] crate::object::tree::editor::write::Error),
+    #[error(transparent)]
+    ConflictEntries(#[from] super::index_from_tree::Error),
+    #[error(transparent)]
+    WriteObject(#[from] crate::object::write::Error),
+}
+
+impl Repository {
+    /// Replay all commits reachable from `tip` but not from `upstream` onto the commit `onto`, similar to
+    /// `git replay --onto <onto> <upstream>..<tip>` or `git rebase --onto <onto> <upstream> <tip>`.
+    /// Use `options` to configure how each commit is merged, typically obtained from
+    /// [`tree_merge_options()`](Self::tree_merge_options()).
+    ///
+    /// Each commit is cherry-picked onto the previously replayed one by merging its changes relative to its parent into the
+    /// tree of the new parent. The new commits keep the author and message of the original commits, but use the configured
+    /// committer, which must be set.
+    /// Commits whose changes are already present in the new parent are dropped, unless they were empty to begin with.
+    ///
+    /// Neither the worktree, the index nor any reference is touched, which makes this suitable for bare repositories
+    /// on servers. The caller is expected to update the branch to point to the new [tip](Outcome::tip) on success.
+    ///
+    /// The replay stops at the first commit with unresolved conflicts, which are then described in
+    /// [`Outcome::conflict`], while the commits replayed so far remain in the object database.
+    /// If these objects should not be observable outside of this instance, consider
+    /// [enabling object memory](Self::with_object_memory).
+    ///
+    /// ### Deviation
+    ///
+    /// Merge commits in the range to replay are not supported and cause an error.
+    pub fn replay(
+        &self,
+        upstream: impl Into<ObjectId>,
+        tip: impl Into<ObjectId>,
+        onto: impl Into<ObjectId>,
+        options: Options,
+    ) -> Result<Outcome, Error> {
+        let _span = gix_trace::coarse!("gix::replay()");
+        let (upstream, tip, onto) = (upstream.into(), tip.into(), onto.into());
+        let committer: gix_actor::Signature = self.committer().ok_or(crate::commit::Error::CommitterMissing)??.into();
+
+        let commit_graph = self.commit_graph_if_enabled()?;
+        let mut graph = self.revision_graph(commit_graph.as_ref())?;
+        let merge_base = gix_revision::merge_base(upstream, &[tip], &mut graph)?.map(|bases| bases[0]);
+
+        // Without merge commits, everything between `tip` and the merge-base is a single line of commits.
+        let mut commits = Vec::new();
+        let mut next = Some(tip);
+        while let Some(id) = next.filter(|id| Some(*id) != merge_base) {
+            let commit = self.find_commit(id)?;
+            let mut parents = commit.parent_ids();
+            next = parents.next().map(crate::Id::detach);
+            if parents.next().is_some() {
+                return Err(Error::MergeCommit { id });
+            }
+            commits.push(id);
+        }
+
+        let mut outcome = Outcome {
+            commits: Vec::with_capacity(commits.len()),
+            tip: onto,
+            conflict: None,
+        };
+        let empty_tree = ObjectId::empty_tree(self.object_hash());
+        for id in commits.into_iter().rev() {
+            let commit = self.find_commit(id)?;
+            let commit_ref = commit.decode()?;
+            let base_tree = match commit_ref.parents().next() {
+                Some(parent) => self.find_commit(parent)?.tree_id()?.detach(),
+                None => empty_tree,
+            };
+            let our_tree = self.find_commit(outcome.tip)?.tree_id()?.detach();
+
+            let short_id = id.to_hex_with_len(7).to_string();
+            let ancestor_label = BString::from(format!("parent of {short_id}"));
+            let their_label = BString::from(format!("{short_id} ({})", commit_ref.message_summary()));
+            let labels = gix_merge::blob::builtin_driver::text::Labels {
+                ancestor: Some(ancestor_label.as_bstr()),
+                current: Some("HEAD".into()),
+                other: Some(their_label.as_bstr()),
+            };
+            let mut merge = self.merge_trees(
+                base_tree,
+                our_tree,
+                commit_ref.tree(),
+                labels,
+                options.tree_merge.clone(),
+            )?;
+            let tree = merge.tree.write()?.detach();
+            if merge.has_unresolved_conflicts(options.treat_as_unresolved) {
+                let entries = merge.conflict_entries(&tree, options.treat_as_unresolved)?;
+                outcome.conflict = Some(Conflict {
+                    commit: id,
+                    conflicts: merge.conflicts,
+                    entries,
+                });
+                break;
+            }
+
+            if tree == our_tree && commit_ref.tree() != base_tree {
+                outcome.commits.push(Replayed {
+                    original: id,
+                    new: None,
+                });
+                continue;
+            }
+
+            let mut new_commit = gix_object::Commit::from(commit_ref);
+            new_commit.tree = tree;
+            new_commit.parents = std::iter::once(outcome.tip).collect();
+            new_commit.committer = committer.clone();
+            new_commit
+                .extra_headers
+                .retain(|(name, _)| !matches!(name.as_slice(), b"gpgsig" | b"gpgsig-sha256"));
+            let new_id = self.write_object(&new_commit)?.detach();
+            outcome.commits.push(Replayed {
+                original: id,
+                new: Some(new_id),
+            });
+            outcome.tip = new_id;
+        }
+        Ok(outcome)
+    }
+}
@@ -0,0 +1,320 @@
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use gix_index::entry::Stage;
+
+use crate::bstr::{BStr, BString, ByteSlice};
+use crate::config::cache::util::ApplyLeniencyDefault;
+use crate::config::tree::Rerere;
+use crate::merge::rerere::{conflict_id, resolve, Options, Outcome};
+
+/// The error returned by [Repository::rerere()](crate::Repository::rerere()).
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("Conflicts can only be resolved in repositories with a worktree")]
+    MissingWorkTree,
+    #[error("Could not read or write '{}'", path.display())]
+    Io {
+        path: std::path::PathBuf,
+        source: std::io::Error,
+    },
+    #[error("Could not identify the conflicts in '{rela_path}'")]
+    ConflictId {
+        rela_path: BString,
+        source: gix_merge::rerere::Error,
+    },
+    #[error(transparent)]
+    AcquireLock(#[from] gix_lock::acquire::Error),
+    #[error(transparent)]
+    CommitLock(#[from] gix_lock::commit::Error<gix_lock::File>),
+    #[error(transparent)]
+    WriteBlob(#[from] crate::object::write::Error),
+    #[error("Could not obtain the modification time of a resolved file")]
+    Stat(#[from] std::time::SystemTimeError),
+    #[error(transparent)]
+    ReadOnly(#[from] crate::repository::read_only::Error),
+    #[error(transparent)]
+    FindBlob(#[from] crate::object::find::existing::with_conversion::Error),
+    #[error(transparent)]
+    EditTree(#[from] gix_object::tree::editor::Error),
+}
+
+const MARKER_SIZE: u8 = gix_merge::blob::builtin_driver::text::Conflict::DEFAULT_MARKER_SIZE;
+
+/// Reuse recorded resolutions
+impl crate::Repository {
+    /// Obtain the options for [`rerere()`](Self::rerere()) from `rerere.enabled` and `rerere.autoUpdate`.
+    ///
+    /// Just like in Git, if `rerere.enabled` isn't set, it's enabled if the `rr-cache` directory exists.
+    pub fn rerere_options(&self) -> Result<Options, crate::config::boolean::Error> {
+        let boolean = |key: &'static crate::config::tree::keys::Boolean| {
+            self.config
+                .resolved
+                .boolean(key)
+                .map(|res| key.enrich_error(res).with_lenient_default(self.config.lenient_config))
+                .transpose()
+        };
+        Ok(Options {
+            enabled: match boolean(&Rerere::ENABLED)? {
+                Some(enabled) => enabled,
+                None => self.rr_cache_dir().is_dir(),
+            },
+            auto_update: boolean(&Rerere::AUTO_UPDATE)?.unwrap_or_default(),
+        })
+    }
+
+    /// Reuse recorded resolutions of conflicts, similar to `git rerere`, for all conflicted files in `index`
+    /// as seen in the worktree, and record resolutions for all conflicts that were previously seen and have been resolved since.
+    /// Use `options` to control if anything happens at all, and if resolutions should be [staged](Options::auto_update) in `index`.
+    ///
+    /// This is meant to be called after a merge produced conflicts in the worktree, to resolve all conflicts
+    /// that were already resolved once, and once more after the user resolved the remaining conflicts,
+    /// to record their resolutions for the next time.
+    ///
+    /// Conflicts and resolutions are stored in `rr-cache` in the same format that Git uses, and the conflicts
+    /// that await their resolution are tracked in the `MERGE_RR` file.
+    ///
+    /// Note that `index` is only changed in memory, so it's up to the caller to write it if [`Outcome::staged`] isn't empty.
+    ///
+    /// ### Deviation
+    ///
+    /// * Only the default conflict-marker size is supported, and nested conflicts can't be recorded.
+    /// * Only a single resolution is stored per conflict, whereas Git can store multiple variants.
+    /// * Staged files are not converted with `git` filters first.
+    pub fn rerere(&self, index: &mut gix_index::State, options: Options) -> Result<Outcome, Error> {
+        let mut outcome = Outcome::default();
+        if !options.enabled {
+            return Ok(outcome);
+        }
+        self.ensure_writable("record conflict resolutions")?;
+        let workdir = self.work_dir().ok_or(Error::MissingWorkTree)?;
+        let rr_cache = self.rr_cache_dir();
+        let mut lock = gix_lock::File::acquire_to_update_resource(
+            self.git_dir().join("MERGE_RR"),
+            gix_lock::acquire::Fail::Immediately,
+            None,
+        )?;
+        let mut merge_rr = read_merge_rr(lock.resource_path().as_ref())?;
+
+        let mut conflicted = Vec::<BString>::new();
+        for entry in index.entries() {
+            if entry.stage() == Stage::Unconflicted {
+                continue;
+            }
+            let rela_path = entry.path(index);
+            if conflicted.last().map_or(true, |last| last != rela_path) {
+                conflicted.push(rela_path.to_owned());
+            }
+        }
+
+        let mut preimage = Vec::new();
+        let mut buf = Vec::new();
+        for rela_path in conflicted {
+            if merge_rr.iter().any(|(_, path)| *path == rela_path) {
+                continue;
+            }
+            let path = workdir.join(gix_path::from_bstr(rela_path.as_bstr()));
+            let Some(content) = read_if_exists(&path)? else {
+                continue;
+            };
+            let Some(id) = conflict_id(&content, MARKER_SIZE, self.object_hash(), &mut preimage).map_err(|err| {
+                Error::ConflictId {
+                    rela_path: rela_path.clone(),
+                    source: err,
+                }
+            })?
+            else {
+                continue;
+            };
+            let id = id.to_hex().to_string();
+            let conflict_dir = rr_cache.join(&id);
+            let postimage_path = conflict_dir.join("postimage");
+            if let Some(postimage) = read_if_exists(&postimage_path)? {
+                let recorded_preimage = read_if_exists(&conflict_dir.join("preimage"))?.unwrap_or_default();
+                if resolve(&preimage, &recorded_preimage, &postimage, &mut buf) {
+                    write(&path, &buf)?;
+                    if options.auto_update {
+                        self.stage_resolution(index, rela_path.as_ref(), &path, &buf)?;
+                        outcome.staged.push(rela_path.clone());
+                    }
+                    outcome.resolved.push(rela_path);
+                    continue;
+                }
+                // The recorded resolution doesn't apply, so replace it with the upcoming one.
+                std::fs::remove_file(&postimage_path).map_err(|err| Error::Io {
+                    path: postimage_path.clone(),
+                    source: err,
+                })?;
+            }
+            std::fs::create_dir_all(&conflict_dir).map_err(|err| Error::Io {
+                path: conflict_dir.clone(),
+                source: err,
+            })?;
+            write(&conflict_dir.join("preimage"), &preimage)?;
+            merge_rr.push((id, rela_path.clone()));
+            outcome.recorded_preimages.push(rela_path);
+        }
+
+        let mut remaining = Vec::with_capacity(merge_rr.len());
+        for (id, rela_path) in merge_rr {
+            let path = workdir.join(gix_path::from_bstr(rela_path.as_bstr()));
+            let Some(content) = read_if_exists(&path)? else {
+                continue;
+            };
+            let is_resolved = matches!(
+                conflict_id(&content, MARKER_SIZE, self.object_hash(), &mut preimage),
+                Ok(None)
+            );
+            if is_resolved {
+                write(&rr_cache.join(&id).join("postimage"), &content)?;
+                outcome.recorded_resolutions.push(rela_path);
+            } else {
+                remaining.push((id, rela_path));
+            }
+        }
+
+        if remaining.is_empty() {
+            if let Err(err) = std::fs::remove_file(lock.resource_path()) {
+                if err.kind() != std::io::ErrorKind::NotFound {
+                    return Err(Error::Io {
+                        path: lock.resource_path(),
+                        source: err,
+                    });
+                }
+            }
+            drop(lock);
+        } else {
+            for (id, rela_path) in &remaining {
+                lock.write_all(id.as_bytes())
+                    .and_then(|_| lock.write_all(b"\t"))
+                    .and_then(|_| lock.write_all(rela_path))
+                    .and_then(|_| lock.write_all(b"\0"))
+                    .map_err(|err| Error::Io {
+                        path: lock.resource_path(),
+                        source: err,
+                    })?;
+            }
+            lock.commit()?;
+        }
+        Ok(outcome)
+    }
+
+    /// Resolve all content conflicts in `conflicts` with the resolutions recorded for them in `rr-cache`, and place
+    /// the resolved blobs into `tree`, which is the result of the merge that produced `conflicts`.
+    /// Resolved conflicts remain in `conflicts`, but their content merge is marked as complete.
+    ///
+    /// This is done as part of [`merge_trees()`](Self::merge_trees()) and [`merge_commits()`](Self::merge_commits())
+    /// if [enabled](crate::merge::tree::Options::with_rerere()), whereas conflicts and their resolutions are recorded
+    /// with [`rerere()`](Self::rerere()) once they are in the worktree.
+    pub(crate) fn replay_recorded_resolutions(
+        &self,
+        tree: &mut gix_object::tree::Editor<'_>,
+        conflicts: &mut [crate::merge::tree::Conflict],
+    ) -> Result<(), Error> {
+        use crate::merge::tree::Resolution;
+
+        let rr_cache = self.rr_cache_dir();
+        let (mut preimage, mut buf) = (Vec::new(), Vec::new());
+        for conflict in conflicts {
+            let location = conflict.ours.location().to_owned();
+            let mode = {
+                let (ours, theirs) = (conflict.ours.entry_mode(), conflict.theirs.entry_mode());
+                let base = conflict.entries()[0].map(|entry| entry.mode);
+                // Like the merge, keep the mode that changed.
+                if base == Some(ours) {
+                    theirs
+                } else {
+                    ours
+                }
+            };
+            let Ok(Resolution::OursModifiedTheirsModifiedThenBlobContentMerge { merged_blob }) =
+                &mut conflict.resolution
+            else {
+                continue;
+            };
+            if merged_blob.resolution != gix_merge::blob::Resolution::Conflict {
+                continue;
+            }
+            let content = self.find_blob(merged_blob.merged_blob_id)?;
+            let Ok(Some(id)) = conflict_id(&content.data, MARKER_SIZE, self.object_hash(), &mut preimage) else {
+                continue;
+            };
+            let conflict_dir = rr_cache.join(id.to_hex().to_string());
+            let Some(postimage) = read_if_exists(&conflict_dir.join("postimage"))? else {
+                continue;
+            };
+            let recorded_preimage = read_if_exists(&conflict_dir.join("preimage"))?.unwrap_or_default();
+            if !resolve(&preimage, &recorded_preimage, &postimage, &mut buf) {
+                continue;
+            }
+            let id = self.write_blob(&buf)?.detach();
+            tree.upsert(location.split(|b| *b == b'/'), mode.kind(), id)?;
+            merged_blob.merged_blob_id = id;
+            merged_blob.resolution = gix_merge::blob::Resolution::Complete;
+        }
+        Ok(())
+    }
+
+    /// The directory in which conflicts and their resolutions are recorded.
+    fn rr_cache_dir(&self) -> PathBuf {
+        self.common_dir().join("rr-cache")
+    }
+
+    /// Replace all conflicting entries at `rela_path` in `index` with `content`, which is also stored at `path`.
+    fn stage_resolution(
+        &self,
+        index: &mut gix_index::State,
+        rela_path: &BStr,
+        path: &Path,
+        content: &[u8],
+    ) -> Result<(), Error> {
+        let id = self.write_blob(content)?.detach();
+        let metadata = gix_index::fs::Metadata::from_path_no_follow(path).map_err(|err| Error::Io {
+            path: path.to_owned(),
+            source: err,
+        })?;
+        let stat = gix_index::entry::Stat::from_fs(&metadata)?;
+        let mode = index
+            .entries()
+            .iter()
+            .find(|entry| entry.stage() == Stage::Ours && entry.path(index) == rela_path)
+            .map_or(gix_index::entry::Mode::FILE, |entry| entry.mode);
+        index.remove_entries(|_, path, _| path == rela_path);
+        index.dangerously_push_entry(stat, id, gix_index::entry::Flags::empty(), mode, rela_path);
+        index.sort_entries();
+        Ok(())
+    }
+}
+
+/// Read the `MERGE_RR` file at `path` with lines of `<id>\t<path>\0`, or return an empty list if it doesn't exist.
+fn read_merge_rr(path: &Path) -> Result<Vec<(String, BString)>, Error> {
+    let Some(content) = read_if_exists(path)? else {
+        return Ok(Vec::new());
+    };
+    Ok(content
+        .split(|b| *b == 0)
+        .filter_map(|record| {
+            let (id, rela_path) = record.split_once_str(b"\t")?;
+            Some((id.to_str().ok()?.to_owned(), rela_path.into()))
+        })
+        .collect())
+}
+
+fn read_if_exists(path: &Path) -> Result<Option<Vec<u8>>, Error> {
+    match std::fs::read(path) {
+        Ok(content) => Ok(Some(content)),
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(err) => Err(Error::Io {
+            path: path.to_owned(),
+            source: err,
+        }),
+    }
+}
+
+fn write(path: &Path, content: &[u8]) -> Result<(), Error> {
+    std::fs::write(path, content).map_err(|err| Error::Io {
+        path: path.to_owned(),
+        source: err,
+    })
+}
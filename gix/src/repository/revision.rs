@@ -51,7 +51,7 @@ impl crate::Repository {
         let one = one.into();
         let two = two.into();
         let cache = self.commit_graph_if_enabled()?;
-        let mut graph = self.revision_graph(cache.as_ref());
+        let mut graph = self.revision_graph(cache.as_ref())?;
         let bases = gix_revision::merge_base(one, &[two], &mut graph)?.ok_or(super::merge_base::Error::NotFound {
             first: one,
             second: two,
@@ -134,7 +134,7 @@ impl crate::Repository {
         commits: impl IntoIterator<Item = impl Into<gix_hash::ObjectId>>,
     ) -> Result<Id<'_>, crate::repository::merge_base_octopus::Error> {
         let cache = self.commit_graph_if_enabled()?;
-        let mut graph = self.revision_graph(cache.as_ref());
+        let mut graph = self.revision_graph(cache.as_ref())?;
         Ok(self.merge_base_octopus_with_graph(commits, &mut graph)?)
     }
 
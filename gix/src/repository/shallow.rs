@@ -47,6 +47,36 @@ impl Repository {
         )
     }
 
+    /// Return the parent overrides of commits as defined by the [grafts file](Self::grafts_file()) and the
+    /// [shallow boundary](Self::shallow_commits()), with the latter taking precedence.
+    ///
+    /// Pass it to traversals to have them see the same history as `git` would, which is done automatically by
+    /// [`revision_graph()`](Self::revision_graph()) and [`rev_walk()`](Self::rev_walk()).
+    /// Object replacements aren't part of it as they are applied by the object database.
+    pub fn grafts(&self) -> Result<gix_revwalk::parents::Grafts, crate::shallow::grafts::Error> {
+        let mut grafts = match std::fs::read(self.grafts_file()) {
+            Ok(buf) => gix_revwalk::parents::Grafts::from_bytes(&buf)?,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Default::default(),
+            Err(err) => return Err(err.into()),
+        };
+        if let Some(commits) = self.shallow_commits()? {
+            grafts.insert_shallow(commits.iter().copied());
+        }
+        Ok(grafts)
+    }
+
+    /// Return the path to the `info/grafts` file which lists commits along with the parents they are supposed to have.
+    ///
+    /// Note that it typically doesn't exist as grafts are deprecated in favor of replace objects.
+    /// It's `$GIT_COMMON_DIR/info/grafts` unless it was [overridden][crate::open::EnvironmentOverrides::graft_file].
+    pub fn grafts_file(&self) -> PathBuf {
+        self.options
+            .environment_overrides
+            .as_ref()
+            .and_then(|o| o.graft_file.clone())
+            .unwrap_or_else(|| self.common_dir().join("info").join("grafts"))
+    }
+
     /// Return the path to the `shallow` file which contains hashes, one per line, that describe commits that don't have their
     /// parents within this repository.
     ///
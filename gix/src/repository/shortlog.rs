@@ -0,0 +1,134 @@
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use gix_hash::ObjectId;
+
+use crate::bstr::BString;
+use crate::Repository;
+
+/// The error returned by [Repository::shortlog()](crate::Repository::shortlog()).
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error(transparent)]
+    Walk(#[from] crate::revision::walk::iter::Error),
+    #[error(transparent)]
+    FindCommit(#[from] crate::object::find::existing::Error),
+    #[error("Could not decode commit {id}")]
+    DecodeCommit {
+        id: ObjectId,
+        source: gix_object::decode::Error,
+    },
+    #[error("The operation was interrupted")]
+    Interrupted,
+}
+
+/// The signature of a commit to group contributions by.
+#[derive(Default, Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum GroupBy {
+    /// Group by the author of each commit, like `git shortlog` does by default.
+    #[default]
+    Author,
+    /// Group by the committer of each commit, like `git shortlog --committer`.
+    Committer,
+}
+
+/// Options for use in [Repository::shortlog()](crate::Repository::shortlog()).
+#[derive(Default, Debug, Copy, Clone)]
+pub struct Options {
+    /// The signature to group contributions by.
+    pub group_by: GroupBy,
+    /// If `true`, contributors with the same name but different email addresses are kept apart, like `git shortlog -e` does.
+    pub email: bool,
+    /// If `true`, collect the summary of each commit, otherwise only the commits are counted like `git shortlog -s` does.
+    pub summaries: bool,
+}
+
+/// A contributor and their contributions, as returned by [Repository::shortlog()](crate::Repository::shortlog()).
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Contributor {
+    /// The name of the contributor, after applying the mailmap.
+    pub name: BString,
+    /// The email of the contributor after applying the mailmap, if [`Options::email`] was set.
+    pub email: Option<BString>,
+    /// The amount of commits the contributor made.
+    pub count: usize,
+    /// The summaries of all commits from oldest to newest, if [`Options::summaries`] was set.
+    pub summaries: Vec<BString>,
+}
+
+impl Repository {
+    /// Group all commits produced by `walk` by their mailmap-resolved author as configured in `options`, similar to
+    /// `git shortlog`, and return all contributors sorted by name.
+    ///
+    /// Commits are decoded and grouped in a thread of its own while `walk` is driven on the current one.
+    /// `should_interrupt` is polled to abort the operation with an error.
+    /// To obtain a walk over a range like `v1.0..v2.0`, use [`rev_walk()`](Self::rev_walk()) with hidden tips.
+    pub fn shortlog(
+        &self,
+        walk: crate::revision::Walk<'_>,
+        options: Options,
+        should_interrupt: &AtomicBool,
+    ) -> Result<Vec<Contributor>, Error> {
+        let _span = gix_trace::coarse!("gix::shortlog");
+        let mailmap = self.open_mailmap();
+        std::thread::scope(|scope| -> Result<_, Error> {
+            let (tx, rx) = std::sync::mpsc::sync_channel::<(ObjectId, Vec<u8>)>(256);
+            let group = scope.spawn(move || -> Result<_, Error> {
+                let mut by_contributor = BTreeMap::<(BString, Option<BString>), (usize, Vec<BString>)>::new();
+                for (id, data) in rx {
+                    let commit = gix_object::CommitRef::from_bytes(&data)
+                        .map_err(|source| Error::DecodeCommit { id, source })?;
+                    let signature = match options.group_by {
+                        GroupBy::Author => commit.author(),
+                        GroupBy::Committer => commit.committer(),
+                    };
+                    let signature = mailmap.resolve_cow(signature.trim());
+                    let key = (
+                        signature.name.into_owned(),
+                        options.email.then(|| signature.email.into_owned()),
+                    );
+                    let (count, summaries) = by_contributor.entry(key).or_default();
+                    *count += 1;
+                    if options.summaries {
+                        summaries.push(commit.message_summary().into_owned());
+                    }
+                }
+                Ok(by_contributor)
+            });
+
+            let mut walk_result = Ok(());
+            for info in walk {
+                if should_interrupt.load(Ordering::Relaxed) {
+                    walk_result = Err(Error::Interrupted);
+                    break;
+                }
+                let commit = match info.map_err(Error::from).and_then(|info| Ok(info.object()?)) {
+                    Ok(commit) => commit,
+                    Err(err) => {
+                        walk_result = Err(err);
+                        break;
+                    }
+                };
+                if tx.send((commit.id, commit.detach().data)).is_err() {
+                    break;
+                }
+            }
+            drop(tx);
+            let by_contributor = group.join().expect("no panic")?;
+            walk_result?;
+            Ok(by_contributor
+                .into_iter()
+                .map(|((name, email), (count, mut summaries))| {
+                    summaries.reverse();
+                    Contributor {
+                        name,
+                        email,
+                        count,
+                        summaries,
+                    }
+                })
+                .collect())
+        })
+    }
+}
@@ -0,0 +1,353 @@
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::AtomicBool;
+
+use gix_index::entry::{Flags, Stage};
+
+use gix_filter::pipeline::convert::ToGitOutcome;
+
+use crate::bstr::{BStr, BString};
+use crate::config::cache::util::ApplyLeniencyDefault;
+use crate::config::tree::Core;
+use crate::sparse_checkout::{Mode, Outcome, Patterns};
+
+/// The error returned by [Repository::sparse_checkout_set()](crate::Repository::sparse_checkout_set()) and related methods.
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("Sparse checkouts can only be applied in repositories with a worktree")]
+    MissingWorkTree,
+    #[error("Sparse checkouts must be enabled with `core.sparseCheckout` to add patterns")]
+    NotEnabled,
+    #[error("Could not read or write '{}'", path.display())]
+    Io {
+        path: std::path::PathBuf,
+        source: std::io::Error,
+    },
+    #[error(transparent)]
+    Patterns(#[from] crate::sparse_checkout::Error),
+    #[error("Couldn't obtain configuration for core.sparseCheckout")]
+    ConfigBoolean(#[from] crate::config::boolean::Error),
+    #[error(transparent)]
+    LoadConfig(#[from] gix_config::file::init::from_paths::Error),
+    #[error(transparent)]
+    SetConfig(#[from] gix_config::file::set_raw_value::Error),
+    #[error(transparent)]
+    AcquireLock(#[from] gix_lock::acquire::Error),
+    #[error(transparent)]
+    CommitLock(#[from] gix_lock::commit::Error<gix_lock::File>),
+    #[error(transparent)]
+    OpenIndex(#[from] crate::worktree::open_index::Error),
+    #[error(transparent)]
+    WriteIndex(#[from] gix_index::file::write::Error),
+    #[error(transparent)]
+    CheckoutOptions(#[from] crate::config::checkout_options::Error),
+    #[error(transparent)]
+    Checkout(#[from] gix_worktree_state::checkout::Error),
+    #[error("Failed to reopen object database as Arc (only if thread-safety wasn't compiled in)")]
+    OpenArcOdb(#[from] std::io::Error),
+    #[error(transparent)]
+    ReadOnly(#[from] crate::repository::read_only::Error),
+    #[error(transparent)]
+    FilterPipeline(#[from] crate::repository::filter::pipeline::Error),
+    #[error(transparent)]
+    ConvertToGit(#[from] crate::filter::pipeline::convert_to_git::Error),
+}
+
+/// Sparse checkouts
+impl crate::Repository {
+    /// Return the path to the file storing the sparse-checkout patterns of this worktree, `$GIT_DIR/info/sparse-checkout`.
+    pub fn sparse_checkout_path(&self) -> PathBuf {
+        self.git_dir().join("info").join("sparse-checkout")
+    }
+
+    /// Return the sparse-checkout patterns that are in effect, or `None` if `core.sparseCheckout` is disabled or if there
+    /// is no sparse-checkout file.
+    ///
+    /// The patterns are interpreted in cone-mode if `core.sparseCheckoutCone` is enabled.
+    pub fn sparse_checkout_patterns(&self) -> Result<Option<Patterns>, Error> {
+        if !self
+            .sparse_checkout_boolean(&Core::SPARSE_CHECKOUT)?
+            .unwrap_or_default()
+        {
+            return Ok(None);
+        }
+        let mode = if self
+            .sparse_checkout_boolean(&Core::SPARSE_CHECKOUT_CONE)?
+            .unwrap_or_default()
+        {
+            Mode::Cone
+        } else {
+            Mode::Patterns
+        };
+        let path = self.sparse_checkout_path();
+        let data = match std::fs::read(&path) {
+            Ok(data) => data,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(err) => return Err(Error::Io { source: err, path }),
+        };
+        Ok(Some(Patterns::from_bytes(&data, mode)?))
+    }
+
+    /// Write `patterns` to the sparse-checkout file, enable `core.sparseCheckout` and set `core.sparseCheckoutCone` according
+    /// to the [mode](Patterns::mode()) of `patterns` in the local configuration, and apply them to the index and the worktree
+    /// like [`sparse_checkout_reapply()`](Self::sparse_checkout_reapply()) does.
+    ///
+    /// This is the equivalent of `git sparse-checkout set`.
+    pub fn sparse_checkout_set(
+        &mut self,
+        patterns: &Patterns,
+        should_interrupt: &AtomicBool,
+    ) -> Result<Outcome, Error> {
+        self.ensure_writable("set sparse-checkout patterns")?;
+        let path = self.sparse_checkout_path();
+        if let Some(info_dir) = path.parent() {
+            std::fs::create_dir_all(info_dir).map_err(|err| Error::Io {
+                source: err,
+                path: info_dir.to_owned(),
+            })?;
+        }
+        let mut lock = gix_lock::File::acquire_to_update_resource(&path, gix_lock::acquire::Fail::Immediately, None)?;
+        std::io::Write::write_all(&mut lock, &patterns.to_bytes()).map_err(|err| Error::Io {
+            source: err,
+            path: path.clone(),
+        })?;
+        lock.commit()?;
+
+        self.write_sparse_checkout_config(true, Some(patterns.mode() == Mode::Cone))?;
+        self.apply_sparse_checkout_patterns(Some(patterns), should_interrupt)
+    }
+
+    /// Add `input`, which are directories in cone-mode or patterns otherwise, to the sparse-checkout patterns that are
+    /// currently in effect and apply them like [`sparse_checkout_set()`](Self::sparse_checkout_set()) does.
+    ///
+    /// This is the equivalent of `git sparse-checkout add`, and fails if sparse checkouts are not enabled.
+    pub fn sparse_checkout_add(
+        &mut self,
+        input: impl IntoIterator<Item = impl Into<BString>>,
+        should_interrupt: &AtomicBool,
+    ) -> Result<Outcome, Error> {
+        let mut patterns = self.sparse_checkout_patterns()?.ok_or(Error::NotEnabled)?;
+        patterns.add(input)?;
+        self.sparse_checkout_set(&patterns, should_interrupt)
+    }
+
+    /// Apply the sparse-checkout patterns that are in effect to the index and the worktree by setting the skip-worktree bit
+    /// on all files that aren't included, and removing them from the worktree unless they were modified.
+    /// Previously skipped files that are included now will be checked out.
+    /// If sparse checkouts are disabled, all skipped files will be checked out.
+    ///
+    /// This is the equivalent of `git sparse-checkout reapply`.
+    pub fn sparse_checkout_reapply(&self, should_interrupt: &AtomicBool) -> Result<Outcome, Error> {
+        self.ensure_writable("apply sparse-checkout patterns")?;
+        let patterns = self.sparse_checkout_patterns()?;
+        self.apply_sparse_checkout_patterns(patterns.as_ref(), should_interrupt)
+    }
+
+    /// Check out all files that were excluded from the sparse checkout and disable `core.sparseCheckout` in the local
+    /// configuration, while keeping the sparse-checkout file for later use.
+    ///
+    /// This is the equivalent of `git sparse-checkout disable`.
+    pub fn sparse_checkout_disable(&mut self, should_interrupt: &AtomicBool) -> Result<Outcome, Error> {
+        self.ensure_writable("disable sparse checkouts")?;
+        let outcome = self.apply_sparse_checkout_patterns(None, should_interrupt)?;
+        self.write_sparse_checkout_config(false, None)?;
+        Ok(outcome)
+    }
+}
+
+impl crate::Repository {
+    fn sparse_checkout_boolean(&self, key: &'static crate::config::tree::keys::Boolean) -> Result<Option<bool>, Error> {
+        Ok(self
+            .config
+            .resolved
+            .boolean(key)
+            .map(|res| key.enrich_error(res).with_lenient_default(self.config.lenient_config))
+            .transpose()?)
+    }
+
+    /// Persist the given values in the local configuration file, and apply them to our in-memory configuration as well.
+    fn write_sparse_checkout_config(&mut self, enabled: bool, cone: Option<bool>) -> Result<(), Error> {
+        let path = self.common_dir().join("config");
+        let mut lock = gix_lock::File::acquire_to_update_resource(&path, gix_lock::acquire::Fail::Immediately, None)?;
+        let mut local = gix_config::File::from_path_no_includes(path.clone(), gix_config::Source::Local)?;
+
+        let resolved = gix_features::threading::OwnShared::make_mut(&mut self.config.resolved);
+        let as_str = |value: bool| if value { "true" } else { "false" };
+        for (key, value) in Some((&Core::SPARSE_CHECKOUT, enabled))
+            .into_iter()
+            .chain(cone.map(|cone| (&Core::SPARSE_CHECKOUT_CONE, cone)))
+        {
+            local.set_raw_value(key, as_str(value))?;
+            resolved.set_raw_value(key, as_str(value))?;
+        }
+
+        local.write_to(&mut lock).map_err(|err| Error::Io {
+            source: err,
+            path: path.clone(),
+        })?;
+        lock.commit()?;
+        Ok(())
+    }
+
+    /// Set or clear the skip-worktree bit on all unconflicted index entries according to `patterns`, with `None` including
+    /// all entries, and update the worktree accordingly.
+    fn apply_sparse_checkout_patterns(
+        &self,
+        patterns: Option<&Patterns>,
+        should_interrupt: &AtomicBool,
+    ) -> Result<Outcome, Error> {
+        let workdir = self.work_dir().ok_or(Error::MissingWorkTree)?;
+        let case = if self.config.ignore_case {
+            gix_glob::pattern::Case::Fold
+        } else {
+            gix_glob::pattern::Case::Sensitive
+        };
+        let mut index = self.open_index()?;
+        let (mut pipeline, pipeline_index) = self.filter_pipeline(None)?;
+        let mut outcome = Outcome::default();
+        let mut to_checkout = Vec::new();
+        let (entries, path_backing) = index.entries_mut_and_pathbacking();
+        for (idx, entry) in entries.iter_mut().enumerate() {
+            if entry.stage() != Stage::Unconflicted {
+                continue;
+            }
+            let rela_path = entry.path_in(path_backing);
+            let is_included = patterns.map_or(true, |patterns| patterns.is_included(rela_path, case));
+            let is_skipped = entry.flags.contains(Flags::SKIP_WORKTREE);
+            match (is_included, is_skipped) {
+                (true, true) => {
+                    entry.flags.remove(Flags::SKIP_WORKTREE);
+                    to_checkout.push(idx);
+                    outcome.added.push(rela_path.to_owned());
+                }
+                (false, false) => {
+                    let path = workdir.join(gix_path::from_bstr(rela_path));
+                    let io_err = |err| Error::Io {
+                        source: err,
+                        path: path.clone(),
+                    };
+                    if self.is_modified_in_worktree(
+                        &mut pipeline,
+                        &pipeline_index,
+                        rela_path,
+                        &path,
+                        entry.id,
+                        io_err,
+                    )? {
+                        outcome.kept_modified.push(rela_path.to_owned());
+                        continue;
+                    }
+                    remove_file_and_empty_parents(&path, workdir).map_err(io_err)?;
+                    entry.flags.insert(Flags::SKIP_WORKTREE);
+                    outcome.removed.push(rela_path.to_owned());
+                }
+                (true, false) | (false, true) => {}
+            }
+        }
+
+        if !to_checkout.is_empty() {
+            // Only check out the entries that became part of the checkout by temporarily skipping all others.
+            let mut previously_skipped = Vec::new();
+            let mut to_checkout = to_checkout.into_iter().peekable();
+            for (idx, entry) in index.entries_mut().iter_mut().enumerate() {
+                if to_checkout.peek() == Some(&idx) {
+                    to_checkout.next();
+                } else if !entry.flags.contains(Flags::SKIP_WORKTREE) {
+                    entry.flags.insert(Flags::SKIP_WORKTREE);
+                    previously_skipped.push(idx);
+                }
+            }
+            let opts = self
+                .config
+                .checkout_options(self, gix_worktree::stack::state::attributes::Source::IdMapping)?;
+            let res = gix_worktree_state::checkout(
+                &mut index,
+                workdir,
+                self.objects.clone().into_arc()?,
+                &gix_features::progress::Discard,
+                &gix_features::progress::Discard,
+                should_interrupt,
+                opts,
+            );
+            let entries = index.entries_mut();
+            for idx in previously_skipped {
+                entries[idx].flags.remove(Flags::SKIP_WORKTREE);
+            }
+            res?;
+        }
+
+        if !(outcome.added.is_empty() && outcome.removed.is_empty()) {
+            index.write(crate::index::write::Options {
+                fsync: self.config.fsync.index,
+                ..Default::default()
+            })?;
+        }
+        Ok(outcome)
+    }
+
+    /// Return `true` if the file at `path` doesn't have the content of the blob with `id` once it was converted with
+    /// `pipeline` as if it was added to `index` at `rela_path`.
+    /// Files that don't exist are considered unmodified, and anything that isn't a file or symlink is considered modified.
+    ///
+    /// I/O errors are turned into `E` with `io_err`.
+    pub(crate) fn is_modified_in_worktree<E>(
+        &self,
+        pipeline: &mut crate::filter::Pipeline<'_>,
+        index: &gix_index::State,
+        rela_path: &BStr,
+        path: &Path,
+        id: gix_hash::ObjectId,
+        io_err: impl Fn(std::io::Error) -> E,
+    ) -> Result<bool, E>
+    where
+        E: From<crate::filter::pipeline::convert_to_git::Error>,
+    {
+        let meta = match std::fs::symlink_metadata(path) {
+            Ok(meta) => meta,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(false),
+            Err(err) => return Err(io_err(err)),
+        };
+        let mut buf = Vec::new();
+        let data = if meta.is_symlink() {
+            let target = std::fs::read_link(path).map_err(&io_err)?;
+            buf = gix_path::to_unix_separators_on_windows(gix_path::into_bstr(target))
+                .into_owned()
+                .into();
+            &buf
+        } else if meta.is_file() {
+            let file = std::fs::File::open(path).map_err(&io_err)?;
+            match pipeline.convert_to_git(file, gix_path::from_bstr(rela_path).as_ref(), index)? {
+                ToGitOutcome::Buffer(data) => data,
+                ToGitOutcome::Unchanged(mut stream) => {
+                    stream.read_to_end(&mut buf).map_err(&io_err)?;
+                    &buf
+                }
+                ToGitOutcome::Process(mut stream) => {
+                    stream.read_to_end(&mut buf).map_err(&io_err)?;
+                    &buf
+                }
+            }
+        } else {
+            return Ok(true);
+        };
+        Ok(gix_object::compute_hash(self.object_hash(), gix_object::Kind::Blob, data) != id)
+    }
+}
+
+/// Remove the file at `path`, if it exists, along with all of its parent directories within `workdir` that became empty.
+pub(crate) fn remove_file_and_empty_parents(path: &Path, workdir: &Path) -> std::io::Result<()> {
+    match std::fs::remove_file(path) {
+        Ok(()) => {}
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+        Err(err) => return Err(err),
+    }
+    let mut dir = path.parent();
+    while let Some(parent) = dir.filter(|dir| *dir != workdir && dir.starts_with(workdir)) {
+        if std::fs::remove_dir(parent).is_err() {
+            break;
+        }
+        dir = parent.parent();
+    }
+    Ok(())
+}
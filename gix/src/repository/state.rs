@@ -5,6 +5,10 @@ impl crate::Repository {
     /// if no operation is currently in progress.
     ///
     /// Note to be confused with the repositories 'status'.
+    ///
+    /// All operations can be [aborted](Self::abort_in_progress()), but only merges, cherry-picks and reverts that
+    /// aren't part of a sequence can be [continued](Self::continue_in_progress()) once their conflicts are resolved,
+    /// as there are no implementations of rebases or mailbox applications that operate on the worktree yet.
     pub fn state(&self) -> Option<state::InProgress> {
         let git_dir = self.path();
 
@@ -42,3 +46,499 @@ impl crate::Repository {
         }
     }
 }
+
+/// The files in the git directory which describe a merge, cherry-pick or revert in progress.
+#[cfg(any(feature = "worktree-mutation", all(feature = "index", feature = "tree-editor")))]
+const STATE_FILES: &[&str] = &[
+    "MERGE_HEAD",
+    "MERGE_MSG",
+    "MERGE_MODE",
+    "AUTO_MERGE",
+    "CHERRY_PICK_HEAD",
+    "REVERT_HEAD",
+];
+
+/// Read the object ids stored one per line in the file at `path`.
+#[cfg(any(feature = "worktree-mutation", all(feature = "index", feature = "tree-editor")))]
+fn read_ids(path: &std::path::Path) -> std::io::Result<Vec<gix_hash::ObjectId>> {
+    use crate::bstr::ByteSlice;
+    std::fs::read(path)?
+        .lines()
+        .map(|hex| {
+            gix_hash::ObjectId::from_hex(hex).map_err(|err| std::io::Error::new(std::io::ErrorKind::InvalidData, err))
+        })
+        .collect()
+}
+
+/// Read the single object id stored in the file at `path`.
+#[cfg(any(feature = "worktree-mutation", all(feature = "index", feature = "tree-editor")))]
+fn read_id(path: &std::path::Path) -> std::io::Result<gix_hash::ObjectId> {
+    read_ids(path)?
+        .into_iter()
+        .next()
+        .ok_or_else(|| std::io::Error::new(std::io::ErrorKind::InvalidData, "the file doesn't contain an object id"))
+}
+
+/// Remove the files in the git directory which describe the operation in progress, if they exist.
+#[cfg(any(feature = "worktree-mutation", all(feature = "index", feature = "tree-editor")))]
+fn remove_state_files<'a>(
+    git_dir: &std::path::Path,
+    names: impl IntoIterator<Item = &'a str>,
+) -> Result<(), (std::path::PathBuf, std::io::Error)> {
+    for name in names {
+        let path = git_dir.join(name);
+        match std::fs::remove_file(&path) {
+            Err(err) if err.kind() != std::io::ErrorKind::NotFound => return Err((path, err)),
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+#[cfg(feature = "worktree-mutation")]
+mod abort {
+    use std::sync::atomic::AtomicBool;
+
+    use gix_index::entry::{Flags, Stage};
+    use gix_ref::{
+        transaction::{Change, LogChange, PreviousValue, RefEdit, RefLog},
+        FullName, Target,
+    };
+
+    use super::{read_id, remove_state_files, STATE_FILES};
+    use crate::{
+        bstr::{BString, ByteSlice},
+        repository::{abort_in_progress::Error, sparse_checkout::remove_file_and_empty_parents},
+        state::InProgress,
+    };
+
+    impl crate::Repository {
+        /// Abort the operation that is currently [in progress](Self::state()) and return it,
+        /// or return `None` if there is no operation in progress.
+        ///
+        /// The index and worktree are reset to the commit that was checked out before the operation started, while
+        /// keeping local changes to files that the operation didn't touch, and the files describing the operation are removed.
+        /// If a sequence of cherry-picks or reverts, or a mailbox application is aborted, `HEAD` is also moved back to where
+        /// it started, while rebases and bisections check out the branch or commit they started from.
+        /// This is the equivalent of `git merge --abort`, `git cherry-pick --abort`, `git revert --abort`,
+        /// `git rebase --abort`, `git am --abort` and `git bisect reset`.
+        ///
+        /// The operation fails without making any changes if files with local changes, or untracked files, would have to be
+        /// overwritten.
+        pub fn abort_in_progress(&self, should_interrupt: &AtomicBool) -> Result<Option<InProgress>, Error> {
+            let Some(in_progress) = self.state() else {
+                return Ok(None);
+            };
+            self.ensure_writable("abort the operation in progress")?;
+            let workdir = self.work_dir().ok_or(Error::MissingWorkTree)?;
+            let git_dir = self.git_dir();
+            let head_id = self.head_id()?.detach();
+            let (target, new_head) = match in_progress {
+                InProgress::Merge => (read_id_at(git_dir.join("ORIG_HEAD"))?, None),
+                InProgress::CherryPick | InProgress::Revert => (head_id, None),
+                InProgress::CherryPickSequence | InProgress::RevertSequence => {
+                    check_abort_safety(git_dir.join("sequencer").join("abort-safety"), head_id)?;
+                    (read_id_at(git_dir.join("sequencer").join("head"))?, None)
+                }
+                InProgress::ApplyMailbox => {
+                    check_abort_safety(git_dir.join("rebase-apply").join("abort-safety"), head_id)?;
+                    (read_id_at(git_dir.join("ORIG_HEAD"))?, None)
+                }
+                InProgress::Rebase | InProgress::RebaseInteractive | InProgress::ApplyMailboxRebase => {
+                    let state_dir = rebase_state_dir(git_dir);
+                    let orig_head = read_id_at(state_dir.join("orig-head"))?;
+                    let head_name = read_line_at(state_dir.join("head-name"))?;
+                    let head = if head_name == "detached HEAD" {
+                        Target::Object(orig_head)
+                    } else {
+                        Target::Symbolic(head_name.try_into()?)
+                    };
+                    (orig_head, Some(head))
+                }
+                InProgress::Bisect => {
+                    let start = read_line_at(git_dir.join("BISECT_START"))?;
+                    match gix_hash::ObjectId::from_hex(&start) {
+                        Ok(id) => (id, Some(Target::Object(id))),
+                        Err(_) => {
+                            let name: FullName = format!("refs/heads/{start}").try_into()?;
+                            let id = self.find_reference(name.as_ref())?.peel_to_id_in_place()?.detach();
+                            (id, Some(Target::Symbolic(name)))
+                        }
+                    }
+                }
+            };
+
+            let index = self.open_index()?;
+            let (mut pipeline, _) = self.filter_pipeline(None)?;
+            let tree_id = self.find_commit(target)?.tree_id()?;
+            let mut new_index = self.index_from_tree(&tree_id)?;
+
+            // Learn what to change before touching anything, so local changes can be protected.
+            let mut tracked = vec![false; new_index.entries().len()];
+            let mut unchanged = vec![false; new_index.entries().len()];
+            let mut to_remove = Vec::new();
+            for entry in index.entries() {
+                let rela_path = entry.path(&index);
+                let is_conflicted = entry.stage() != Stage::Unconflicted;
+                let new_idx = new_index.entry_index_by_path_and_stage(rela_path, Stage::Unconflicted);
+                if let Some(new_idx) = new_idx {
+                    tracked[new_idx] = true;
+                    let new_entry = &mut new_index.entries_mut()[new_idx];
+                    if !is_conflicted && new_entry.id == entry.id && new_entry.mode == entry.mode {
+                        new_entry.stat = entry.stat;
+                        new_entry.flags.insert(entry.flags & Flags::SKIP_WORKTREE);
+                        unchanged[new_idx] = true;
+                        continue;
+                    }
+                }
+                let path = workdir.join(gix_path::from_bstr(rela_path));
+                if !is_conflicted
+                    && !entry.flags.contains(Flags::SKIP_WORKTREE)
+                    && self.is_modified_in_worktree(&mut pipeline, &index, rela_path, &path, entry.id, |err| {
+                        Error::Io {
+                            source: err,
+                            path: path.clone(),
+                        }
+                    })?
+                {
+                    return Err(Error::LocalChanges {
+                        path: rela_path.to_owned(),
+                    });
+                }
+                if new_idx.is_none() {
+                    to_remove.push(path);
+                }
+            }
+            for (entry, _) in new_index
+                .entries()
+                .iter()
+                .zip(&tracked)
+                .filter(|(_, tracked)| !**tracked)
+            {
+                let rela_path = entry.path(&new_index);
+                let path = workdir.join(gix_path::from_bstr(rela_path));
+                if self.is_modified_in_worktree(&mut pipeline, &index, rela_path, &path, entry.id, |err| Error::Io {
+                    source: err,
+                    path: path.clone(),
+                })? {
+                    return Err(Error::LocalChanges {
+                        path: rela_path.to_owned(),
+                    });
+                }
+            }
+
+            for path in to_remove {
+                remove_file_and_empty_parents(&path, workdir).map_err(|err| Error::Io { source: err, path })?;
+            }
+
+            // Only check out the entries that changed by temporarily skipping all others.
+            let mut temporarily_skipped = Vec::new();
+            for (idx, entry) in new_index.entries_mut().iter_mut().enumerate() {
+                if unchanged[idx] && !entry.flags.contains(Flags::SKIP_WORKTREE) {
+                    entry.flags.insert(Flags::SKIP_WORKTREE);
+                    temporarily_skipped.push(idx);
+                }
+            }
+            let mut opts = self
+                .config
+                .checkout_options(self, gix_worktree::stack::state::attributes::Source::IdMapping)?;
+            opts.overwrite_existing = true;
+            let res = gix_worktree_state::checkout(
+                &mut new_index,
+                workdir,
+                self.objects.clone().into_arc()?,
+                &gix_features::progress::Discard,
+                &gix_features::progress::Discard,
+                should_interrupt,
+                opts,
+            );
+            let entries = new_index.entries_mut();
+            for idx in temporarily_skipped {
+                entries[idx].flags.remove(Flags::SKIP_WORKTREE);
+            }
+            res?;
+            new_index.write(crate::index::write::Options {
+                fsync: self.config.fsync.index,
+                ..Default::default()
+            })?;
+
+            match new_head {
+                None => {
+                    if target != head_id {
+                        self.edit_reference(RefEdit {
+                            change: Change::Update {
+                                log: LogChange {
+                                    mode: RefLog::AndReference,
+                                    force_create_reflog: false,
+                                    message: format!("reset: moving to {target}").into(),
+                                },
+                                expected: PreviousValue::MustExistAndMatch(Target::Object(head_id)),
+                                new: Target::Object(target),
+                            },
+                            name: "HEAD".try_into().expect("valid"),
+                            deref: true,
+                        })?;
+                    }
+                }
+                Some(new_head) => {
+                    let action = if in_progress == InProgress::Bisect {
+                        "bisect reset"
+                    } else {
+                        "rebase (abort)"
+                    };
+                    let message = match &new_head {
+                        Target::Symbolic(name) => format!("{action}: returning to {}", name.as_bstr()),
+                        Target::Object(id) => format!("{action}: returning to {id}"),
+                    };
+                    let mut edits = Vec::new();
+                    if let Target::Symbolic(name) = &new_head {
+                        let branch_id = self
+                            .try_find_reference(name.as_ref())?
+                            .map(|mut branch| branch.peel_to_id_in_place().map(crate::Id::detach))
+                            .transpose()?;
+                        if branch_id != Some(target) {
+                            edits.push(RefEdit {
+                                change: Change::Update {
+                                    log: LogChange {
+                                        mode: RefLog::AndReference,
+                                        force_create_reflog: false,
+                                        message: message.clone().into(),
+                                    },
+                                    expected: PreviousValue::Any,
+                                    new: Target::Object(target),
+                                },
+                                name: name.clone(),
+                                deref: false,
+                            });
+                        }
+                    }
+                    edits.push(RefEdit {
+                        change: Change::Update {
+                            log: LogChange {
+                                mode: RefLog::AndReference,
+                                force_create_reflog: false,
+                                message: message.into(),
+                            },
+                            expected: PreviousValue::Any,
+                            new: new_head,
+                        },
+                        name: "HEAD".try_into().expect("valid"),
+                        deref: false,
+                    });
+                    if in_progress == InProgress::Bisect {
+                        for reference in self.references()?.prefixed("refs/bisect/")? {
+                            let reference = reference.map_err(Error::IterReferences)?;
+                            edits.push(RefEdit {
+                                change: Change::Delete {
+                                    expected: PreviousValue::Any,
+                                    log: RefLog::AndReference,
+                                },
+                                name: reference.inner.name,
+                                deref: false,
+                            });
+                        }
+                    }
+                    self.edit_references(edits)?;
+                }
+            }
+
+            remove_state_files(git_dir, STATE_FILES.iter().copied().chain(Some("MERGE_RR")))
+                .map_err(|(path, err)| Error::Io { source: err, path })?;
+            let state_dir = match in_progress {
+                InProgress::CherryPickSequence | InProgress::RevertSequence => Some(git_dir.join("sequencer")),
+                InProgress::ApplyMailbox => Some(git_dir.join("rebase-apply")),
+                InProgress::Rebase | InProgress::RebaseInteractive | InProgress::ApplyMailboxRebase => {
+                    Some(rebase_state_dir(git_dir))
+                }
+                InProgress::Bisect => {
+                    remove_state_files(git_dir, BISECT_STATE_FILES.iter().copied())
+                        .map_err(|(path, err)| Error::Io { source: err, path })?;
+                    None
+                }
+                InProgress::Merge | InProgress::CherryPick | InProgress::Revert => None,
+            };
+            if let Some(state_dir) = state_dir {
+                std::fs::remove_dir_all(&state_dir).map_err(|err| Error::Io {
+                    source: err,
+                    path: state_dir,
+                })?;
+            }
+            Ok(Some(in_progress))
+        }
+    }
+
+    /// The files in the git directory which describe a bisection in progress, as removed by `git bisect reset`.
+    const BISECT_STATE_FILES: &[&str] = &[
+        "BISECT_EXPECTED_REV",
+        "BISECT_ANCESTORS_OK",
+        "BISECT_LOG",
+        "BISECT_TERMS",
+        "BISECT_NAMES",
+        "BISECT_RUN",
+        "BISECT_START",
+        "BISECT_FIRST_PARENT",
+        "BISECT_HEAD",
+    ];
+
+    /// Return the directory describing the rebase in progress, which depends on the backend that performs it.
+    fn rebase_state_dir(git_dir: &std::path::Path) -> std::path::PathBuf {
+        let merge_dir = git_dir.join("rebase-merge");
+        if merge_dir.is_dir() {
+            merge_dir
+        } else {
+            git_dir.join("rebase-apply")
+        }
+    }
+
+    /// Fail if `HEAD` isn't at the commit recorded in the `abort_safety` file, if it exists, as it was moved
+    /// after the operation stopped and rewinding it would lose commits.
+    fn check_abort_safety(abort_safety: std::path::PathBuf, head_id: gix_hash::ObjectId) -> Result<(), Error> {
+        if abort_safety.is_file() {
+            let expected = read_id_at(abort_safety)?;
+            if expected != head_id {
+                return Err(Error::HeadMoved {
+                    expected,
+                    actual: head_id,
+                });
+            }
+        }
+        Ok(())
+    }
+
+    fn read_line_at(path: std::path::PathBuf) -> Result<BString, Error> {
+        match std::fs::read(&path) {
+            Ok(content) => Ok(content.lines().next().unwrap_or_default().trim_end().into()),
+            Err(err) => Err(Error::Io { source: err, path }),
+        }
+    }
+
+    fn read_id_at(path: std::path::PathBuf) -> Result<gix_hash::ObjectId, Error> {
+        read_id(&path).map_err(|err| Error::Io { source: err, path })
+    }
+}
+
+#[cfg(all(feature = "index", feature = "tree-editor"))]
+mod resume {
+    use gix_hash::ObjectId;
+    use gix_index::entry::{Flags, Stage};
+
+    use super::{read_id, read_ids, remove_state_files, STATE_FILES};
+    use crate::{
+        bstr::{BStr, BString, ByteSlice},
+        repository::continue_in_progress::Error,
+        state::InProgress,
+    };
+
+    impl crate::Repository {
+        /// Conclude the merge, cherry-pick or revert that is currently [in progress](Self::state()) by committing the index
+        /// onto `HEAD` with the prepared message, and return the id of the new commit, or return `None` if there is
+        /// no operation in progress.
+        ///
+        /// All conflicts must have been resolved in the index beforehand.
+        /// Merge commits receive the commits that were merged as additional parents, and cherry-picked commits keep
+        /// the author of the original commit. Finally, the files describing the operation are removed.
+        /// This is the equivalent of `git merge --continue`, `git cherry-pick --continue` and `git revert --continue`,
+        /// without editing the message.
+        ///
+        /// ### Deviation
+        ///
+        /// Sequences of cherry-picks and reverts, rebases and mailbox applications can't be continued as there is
+        /// no implementation that applies the remaining commits or patches to the worktree yet.
+        /// Bisections have nothing to continue, use [`abort_in_progress()`](Self::abort_in_progress()) to end them.
+        pub fn continue_in_progress(&self) -> Result<Option<crate::Id<'_>>, Error> {
+            let Some(in_progress) = self.state() else {
+                return Ok(None);
+            };
+            self.ensure_writable("continue the operation in progress")?;
+            let git_dir = self.git_dir();
+            let mut parents = vec![self.head_id()?.detach()];
+            let mut picked_commit = None;
+            match in_progress {
+                InProgress::Merge => {
+                    let path = git_dir.join("MERGE_HEAD");
+                    parents.extend(read_ids(&path).map_err(|err| Error::Io { source: err, path })?);
+                }
+                InProgress::CherryPick => {
+                    let path = git_dir.join("CHERRY_PICK_HEAD");
+                    let id = read_id(&path).map_err(|err| Error::Io { source: err, path })?;
+                    picked_commit = Some(self.find_commit(id)?);
+                }
+                InProgress::Revert => {}
+                InProgress::ApplyMailbox
+                | InProgress::ApplyMailboxRebase
+                | InProgress::Bisect
+                | InProgress::CherryPickSequence
+                | InProgress::Rebase
+                | InProgress::RebaseInteractive
+                | InProgress::RevertSequence => return Err(Error::Unsupported { in_progress }),
+            }
+
+            let index = self.open_index()?;
+            let mut editor = self.edit_tree(ObjectId::empty_tree(self.object_hash()))?;
+            for entry in index.entries() {
+                let rela_path = entry.path(&index);
+                if entry.stage() != Stage::Unconflicted {
+                    return Err(Error::Conflict {
+                        path: rela_path.to_owned(),
+                    });
+                }
+                if entry.flags.contains(Flags::INTENT_TO_ADD) {
+                    continue;
+                }
+                let mode = entry
+                    .mode
+                    .to_tree_entry_mode()
+                    .ok_or_else(|| Error::UnsupportedEntryMode {
+                        path: rela_path.to_owned(),
+                    })?;
+                editor.upsert(rela_path, mode.kind(), entry.id)?;
+            }
+            let tree = editor.write()?;
+
+            let message_path = git_dir.join("MERGE_MSG");
+            let message = std::fs::read(&message_path).map_err(|err| Error::Io {
+                source: err,
+                path: message_path,
+            })?;
+            let message = strip_comments_and_whitespace(message.as_bstr());
+
+            let committer = self
+                .committer()
+                .ok_or(crate::commit::Error::CommitterMissing)?
+                .map_err(crate::commit::Error::from)?;
+            let author = match &picked_commit {
+                Some(commit) => commit.author()?,
+                None => self
+                    .author()
+                    .ok_or(crate::commit::Error::AuthorMissing)?
+                    .map_err(crate::commit::Error::from)?,
+            };
+            let id = self.commit_as(committer, author, "HEAD", message.to_str_lossy(), tree, parents)?;
+
+            remove_state_files(git_dir, STATE_FILES.iter().copied())
+                .map_err(|(path, err)| Error::Io { source: err, path })?;
+            Ok(Some(id))
+        }
+    }
+
+    /// Remove comment lines and trailing whitespace from each line of `message`, along with leading and trailing
+    /// empty lines, and collapse consecutive empty lines into one, like git does for messages that were edited.
+    fn strip_comments_and_whitespace(message: &BStr) -> BString {
+        let mut out = BString::default();
+        let mut pending_empty_line = false;
+        for line in message.lines().filter(|line| !line.starts_with(b"#")) {
+            let line = line.trim_end();
+            if line.is_empty() {
+                pending_empty_line = !out.is_empty();
+                continue;
+            }
+            if pending_empty_line {
+                out.push(b'\n');
+                pending_empty_line = false;
+            }
+            out.extend_from_slice(line);
+            out.push(b'\n');
+        }
+        out
+    }
+}
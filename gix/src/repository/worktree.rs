@@ -51,11 +51,13 @@ impl crate::Repository {
         self.config.is_bare && self.work_dir().is_none()
     }
 
-    /// If `id` points to a tree, produce a stream that yields one worktree entry after the other. The index of the tree at `id`
+    /// If `id` points to a tree or commit, produce a stream that yields one worktree entry after the other. The index of the tree at `id`
     /// is returned as well as it is an intermediate byproduct that might be useful to callers.
     ///
     /// The entries will look exactly like they would if one would check them out, with filters applied.
     /// The `export-ignore` attribute is used to skip blobs or directories to which it applies.
+    /// If `id` points to a commit, the `export-subst` attribute is used to expand `$Format:…$` placeholders
+    /// with information from the commit.
     #[cfg(feature = "worktree-stream")]
     pub fn worktree_stream(
         &self,
@@ -64,32 +66,37 @@ impl crate::Repository {
         use gix_odb::HeaderExt;
         let id = id.into();
         let header = self.objects.header(id)?;
-        if !header.kind().is_tree() {
-            return Err(crate::repository::worktree_stream::Error::NotATree {
-                id,
-                actual: header.kind(),
-            });
-        }
+        let tree = match header.kind() {
+            gix_object::Kind::Tree => id,
+            gix_object::Kind::Commit => self.find_object(id)?.into_commit().tree_id()?.detach(),
+            actual => return Err(crate::repository::worktree_stream::Error::NotATree { id, actual }),
+        };
 
         // TODO(perf): potential performance improvements could be to use the index at `HEAD` if possible (`index_from_head_tree…()`)
         // TODO(perf): when loading a non-HEAD tree, we effectively traverse the tree twice. This is usually fast though, and sharing
         //             an object cache between the copies of the ODB handles isn't trivial and needs a lock.
-        let index = self.index_from_tree(&id)?;
+        let index = self.index_from_tree(&tree)?;
         let mut cache = self
             .attributes_only(&index, gix_worktree::stack::state::attributes::Source::IdMapping)?
             .detach();
         let pipeline = gix_filter::Pipeline::new(self.command_context()?, crate::filter::Pipeline::options(self)?);
         let objects = self.objects.clone().into_arc().expect("TBD error handling");
-        let stream = gix_worktree_stream::from_tree(
-            id,
-            objects.clone(),
-            pipeline,
-            move |path, mode, attrs| -> std::io::Result<()> {
+        let attributes = {
+            let objects = objects.clone();
+            move |path: &crate::bstr::BStr,
+                  mode: gix_object::tree::EntryMode,
+                  attrs: &mut gix_attributes::search::Outcome|
+                  -> std::io::Result<()> {
                 let entry = cache.at_entry(path, Some(mode.into()), &objects)?;
                 entry.matching_attributes(attrs);
                 Ok(())
-            },
-        );
+            }
+        };
+        let stream = if tree == id {
+            gix_worktree_stream::from_tree(id, objects, pipeline, attributes)
+        } else {
+            gix_worktree_stream::from_commit(id, objects, pipeline, attributes)
+        };
         Ok((stream, index))
     }
 
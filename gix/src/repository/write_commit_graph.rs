@@ -0,0 +1,211 @@
+use gix_commitgraph::{bloom, file::write};
+use gix_hash::ObjectId;
+
+use crate::{bstr::ByteSlice, Repository};
+
+/// The error returned by [Repository::write_commit_graph()](crate::Repository::write_commit_graph()).
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error(transparent)]
+    InitRefsIterator(#[from] crate::reference::iter::init::Error),
+    #[error(transparent)]
+    InitRefsIteratorPlatform(#[from] crate::reference::iter::Error),
+    #[error(transparent)]
+    ObtainRefDuringIteration(#[from] Box<dyn std::error::Error + Send + Sync + 'static>),
+    #[error(transparent)]
+    FindObject(#[from] crate::object::find::existing::Error),
+    #[error(transparent)]
+    FindCommit(#[from] crate::object::find::existing::with_conversion::Error),
+    #[error(transparent)]
+    Commit(#[from] crate::object::commit::Error),
+    #[error(transparent)]
+    DecodeCommit(#[from] gix_object::decode::Error),
+    #[error(transparent)]
+    Walk(#[from] crate::revision::walk::Error),
+    #[error(transparent)]
+    WalkIter(#[from] crate::revision::walk::iter::Error),
+    #[error(transparent)]
+    Diff(#[from] gix_diff::tree::Error),
+    #[error(transparent)]
+    Write(#[from] write::Error),
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error("Could not acquire lock for commit-graph file")]
+    AcquireLock(#[from] gix_lock::acquire::Error),
+    #[error("Could not commit lock for commit-graph file")]
+    CommitLock(#[from] gix_lock::commit::Error<gix_lock::File>),
+    #[error(transparent)]
+    ReadOnly(#[from] super::read_only::Error),
+}
+
+/// Options for use in [Repository::write_commit_graph()](crate::Repository::write_commit_graph()).
+#[derive(Debug, Default, Copy, Clone)]
+pub struct Options {
+    /// If set, compute a changed-path Bloom filter for each commit with the given settings and store it in the commit-graph,
+    /// like `git commit-graph write --changed-paths` does.
+    ///
+    /// This makes writing considerably more expensive as each commit has to be diffed against its first parent,
+    /// but accelerates history traversals that are limited to a path.
+    pub changed_paths: Option<bloom::Settings>,
+    /// The amount of threads to use at most when computing changed paths, or `None` to use all logical cores.
+    pub thread_limit: Option<usize>,
+}
+
+/// The outcome of [Repository::write_commit_graph()](crate::Repository::write_commit_graph()).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Outcome {
+    /// The checksum of the newly written commit-graph file.
+    pub checksum: ObjectId,
+    /// The amount of commits in the commit-graph.
+    pub num_commits: u32,
+    /// The amount of commits that changed so many paths that their Bloom filter matches every path.
+    pub num_large_filters: usize,
+}
+
+impl Repository {
+    /// Write a commit-graph file containing all commits reachable from all references to `objects/info/commit-graph`,
+    /// configured by `options`, similar to `git commit-graph write --reachable`.
+    ///
+    /// Topological levels are used as generation numbers, as if `commitGraph.generationVersion` was `1`, and changed-path
+    /// Bloom filters are stored in the same way as `git` does, so the file can be used by both implementations.
+    /// The file is written to a lock file first which is moved into place once it is complete, and it is
+    /// [flushed to disk](crate::config::fsync::Components::commit_graph) if configured.
+    ///
+    /// Return `None` without writing anything if the repository [is shallow](Self::is_shallow()) or has
+    /// [grafts](Self::grafts_file()), as the parents seen by traversals then differ from the ones recorded in commits,
+    /// which is when `git` doesn't write commit-graphs either.
+    ///
+    /// ### Deviation
+    ///
+    /// Existing commit-graph chains in `objects/info/commit-graphs` are left untouched, even though `git` would remove them.
+    pub fn write_commit_graph(&self, options: Options) -> Result<Option<Outcome>, Error> {
+        let _span = gix_trace::coarse!("gix::write_commit_graph()");
+        self.ensure_writable("write a commit-graph")?;
+        if self.is_shallow() || self.grafts_file().is_file() {
+            return Ok(None);
+        }
+        let mut tips = Vec::new();
+        for reference in self.references()?.all()?.peeled()? {
+            let id = reference?.id().detach();
+            if self.find_header(id)?.kind() == gix_object::Kind::Commit {
+                tips.push(id);
+            }
+        }
+        let ids = self
+            .rev_walk(tips)
+            .all()?
+            .map(|info| info.map(|info| info.id))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let repo = self.clone().into_sync();
+        let commits = gix_features::parallel::in_parallel(
+            gix_features::iter::Chunks {
+                inner: ids.into_iter(),
+                size: 16,
+            },
+            options.thread_limit,
+            move |_| repo.to_thread_local(),
+            |ids, repo| {
+                let mut state = gix_diff::tree::State::default();
+                ids.into_iter()
+                    .map(|id| commit_to_write(repo, id, options.changed_paths, &mut state))
+                    .collect::<Result<Vec<_>, _>>()
+            },
+            CollectCommits::default(),
+        )?;
+        let num_large_filters = commits
+            .iter()
+            .filter(|commit| commit.changed_paths_filter.as_deref() == Some(&[0xff]))
+            .count();
+
+        let info_dir = self.objects.store_ref().path().join("info");
+        std::fs::create_dir_all(&info_dir)?;
+        let mut lock = std::io::BufWriter::new(gix_lock::File::acquire_to_update_resource(
+            info_dir.join("commit-graph"),
+            gix_lock::acquire::Fail::Immediately,
+            None,
+        )?);
+        let outcome = gix_commitgraph::File::write_to(
+            commits,
+            &mut lock,
+            write::Options {
+                object_hash: self.object_hash(),
+                changed_paths: options.changed_paths,
+            },
+        )?;
+        let mut lock = lock.into_inner().map_err(std::io::IntoInnerError::into_error)?;
+        lock.with_mut(|file| self.config.fsync.commit_graph.sync(file))?;
+        lock.commit()?;
+        Ok(Some(Outcome {
+            checksum: outcome.checksum,
+            num_commits: outcome.num_commits,
+            num_large_filters,
+        }))
+    }
+}
+
+/// Read the commit with `id` and compute its changed-path Bloom filter if `changed_paths` is set.
+fn commit_to_write(
+    repo: &Repository,
+    id: ObjectId,
+    changed_paths: Option<bloom::Settings>,
+    state: &mut gix_diff::tree::State,
+) -> Result<write::Commit, Error> {
+    let commit = repo.find_commit(id)?;
+    let commit_ref = commit.decode()?;
+    let parent_ids: Vec<_> = commit_ref.parents().collect();
+    let changed_paths_filter = match changed_paths {
+        Some(settings) => {
+            let parent_tree = match parent_ids.first() {
+                Some(parent) => repo.find_commit(*parent)?.tree()?,
+                None => repo.empty_tree(),
+            };
+            let tree = repo.find_tree(commit_ref.tree())?;
+            let mut recorder = gix_diff::tree::Recorder::default();
+            gix_diff::tree(
+                gix_object::TreeRefIter::from_bytes(&parent_tree.data),
+                gix_object::TreeRefIter::from_bytes(&tree.data),
+                state,
+                &repo.objects,
+                &mut recorder,
+            )?;
+            // Only files are recorded, as their leading directories are added by the filter itself.
+            Some(settings.filter(recorder.records.iter().filter_map(|change| {
+                use gix_diff::tree::recorder::Change::*;
+                match change {
+                    Addition { entry_mode, path, .. }
+                    | Deletion { entry_mode, path, .. }
+                    | Modification { entry_mode, path, .. } => (!entry_mode.is_tree()).then(|| path.as_bstr()),
+                }
+            })))
+        }
+        None => None,
+    };
+    Ok(write::Commit {
+        id,
+        tree_id: commit_ref.tree(),
+        parent_ids,
+        commit_time: u64::try_from(commit_ref.committer.time.seconds).unwrap_or_default(),
+        changed_paths_filter,
+    })
+}
+
+#[derive(Default)]
+struct CollectCommits(Vec<write::Commit>);
+
+impl gix_features::parallel::Reduce for CollectCommits {
+    type Input = Result<Vec<write::Commit>, Error>;
+    type FeedProduce = ();
+    type Output = Vec<write::Commit>;
+    type Error = Error;
+
+    fn feed(&mut self, item: Self::Input) -> Result<Self::FeedProduce, Self::Error> {
+        self.0.extend(item?);
+        Ok(())
+    }
+
+    fn finalize(self) -> Result<Self::Output, Self::Error> {
+        Ok(self.0)
+    }
+}
@@ -6,6 +6,7 @@ use gix_revision::spec::parse::{
     delegate,
     delegate::{PeelTo, Traversal},
 };
+use gix_revwalk::Parents;
 
 use crate::{
     bstr::{BStr, ByteSlice},
@@ -23,6 +24,13 @@ impl delegate::Navigate for Delegate<'_> {
         self.unset_disambiguate_call();
         self.follow_refs_to_objects_if_needed()?;
 
+        let grafts = match self.repo.grafts() {
+            Ok(grafts) => grafts,
+            Err(err) => {
+                self.err.push(err.into());
+                return None;
+            }
+        };
         let mut replacements = Replacements::default();
         let mut errors = Vec::new();
         let objs = self.objs[self.idx].as_mut()?;
@@ -41,43 +49,52 @@ impl delegate::Navigate for Delegate<'_> {
                             }
                         })
                     }) {
-                        Ok(commit) => match commit.parent_ids().nth(num.saturating_sub(1)) {
-                            Some(id) => replacements.push((commit.id, id.detach())),
-                            None => errors.push((
-                                commit.id,
-                                Error::ParentOutOfRange {
-                                    oid: commit.id().shorten_or_id(),
-                                    desired: num,
-                                    available: commit.parent_ids().count(),
-                                },
-                            )),
-                        },
+                        Ok(commit) => {
+                            let parent_ids: Vec<_> = match grafts.parents_of(&commit.id) {
+                                Some(ids) => ids.to_vec(),
+                                None => commit.parent_ids().map(crate::Id::detach).collect(),
+                            };
+                            match parent_ids.get(num.saturating_sub(1)) {
+                                Some(id) => replacements.push((commit.id, *id)),
+                                None => errors.push((
+                                    commit.id,
+                                    Error::ParentOutOfRange {
+                                        oid: commit.id().shorten_or_id(),
+                                        desired: num,
+                                        available: parent_ids.len(),
+                                    },
+                                )),
+                            }
+                        }
                         Err(err) => errors.push((*obj, err)),
                     }
                 }
                 Traversal::NthAncestor(num) => {
                     let id = obj.attach(repo);
-                    match id
-                        .ancestors()
-                        .first_parent_only()
-                        .all()
-                        .expect("cannot fail without sorting")
-                        .skip(num)
-                        .find_map(Result::ok)
-                    {
-                        Some(commit) => replacements.push((*obj, commit.id)),
+                    let ancestors = match id.ancestors().first_parent_only().all() {
+                        Ok(ancestors) => ancestors,
+                        Err(err) => {
+                            errors.push((*obj, err.into()));
+                            continue;
+                        }
+                    };
+                    let mut available = 0;
+                    let mut ancestor = None;
+                    for (idx, info) in ancestors.filter_map(Result::ok).enumerate() {
+                        if idx == num {
+                            ancestor = Some(info.id);
+                            break;
+                        }
+                        available = idx;
+                    }
+                    match ancestor {
+                        Some(ancestor) => replacements.push((*obj, ancestor)),
                         None => errors.push((
                             *obj,
                             Error::AncestorOutOfRange {
                                 oid: id.shorten_or_id(),
                                 desired: num,
-                                available: id
-                                    .ancestors()
-                                    .first_parent_only()
-                                    .all()
-                                    .expect("cannot fail without sorting")
-                                    .skip(1)
-                                    .count(),
+                                available,
                             },
                         )),
                     }
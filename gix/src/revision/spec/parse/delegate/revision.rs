@@ -105,60 +105,99 @@ impl delegate::Revision for Delegate<'_> {
 
     fn reflog(&mut self, query: ReflogLookup) -> Option<()> {
         self.unset_disambiguate_call();
+        let r = match &mut self.refs[self.idx] {
+            Some(r) => r.clone().attach(self.repo),
+            val @ None => match self.repo.head().map(crate::Head::try_into_referent) {
+                Ok(Some(r)) => {
+                    *val = Some(r.clone().detach());
+                    r
+                }
+                Ok(None) => {
+                    self.err.push(Error::UnbornHeadsHaveNoRefLog);
+                    return None;
+                }
+                Err(err) => {
+                    self.err.push(err.into());
+                    return None;
+                }
+            },
+        };
+        let mut platform = r.log_iter();
         match query {
-            ReflogLookup::Date(_date) => {
-                // TODO: actually do this - this should be possible now despite incomplete date parsing
-                self.err.push(Error::Planned {
-                    dependency: "remote handling and ref-specs are fleshed out more",
-                });
-                None
-            }
-            ReflogLookup::Entry(no) => {
-                let r = match &mut self.refs[self.idx] {
-                    Some(r) => r.clone().attach(self.repo),
-                    val @ None => match self.repo.head().map(crate::Head::try_into_referent) {
-                        Ok(Some(r)) => {
-                            *val = Some(r.clone().detach());
-                            r
-                        }
-                        Ok(None) => {
-                            self.err.push(Error::UnbornHeadsHaveNoRefLog);
-                            return None;
-                        }
-                        Err(err) => {
-                            self.err.push(err.into());
-                            return None;
-                        }
-                    },
-                };
-                let mut platform = r.log_iter();
-                match platform.rev().ok().flatten() {
-                    Some(mut it) => match it.nth(no).and_then(Result::ok) {
-                        Some(line) => {
+            ReflogLookup::Date(date) => match platform.rev().ok().flatten() {
+                Some(it) => {
+                    // Like git, pick the most recent entry at or before `date`, assuming entries are ordered by time.
+                    // If all entries are newer, the reference was at the previous id of the oldest entry.
+                    let mut oldest = None;
+                    for line in it.filter_map(Result::ok) {
+                        if line.signature.time.seconds <= date.seconds {
                             self.objs[self.idx]
                                 .get_or_insert_with(HashSet::default)
                                 .insert(line.new_oid);
+                            return Some(());
+                        }
+                        oldest = Some(line);
+                    }
+                    match oldest {
+                        Some(line) => {
+                            let id = if line.previous_oid.is_null() {
+                                line.new_oid
+                            } else {
+                                line.previous_oid
+                            };
+                            self.objs[self.idx].get_or_insert_with(HashSet::default).insert(id);
                             Some(())
                         }
                         None => {
-                            let available = platform.rev().ok().flatten().map_or(0, Iterator::count);
-                            self.err.push(Error::RefLogEntryOutOfRange {
+                            self.err.push(Error::EmptyRefLog {
                                 reference: r.detach(),
-                                desired: no,
-                                available,
+                                action: "lookup entry by date",
                             });
                             None
                         }
-                    },
+                    }
+                }
+                None => {
+                    self.err.push(Error::MissingRefLog {
+                        reference: r.name().as_bstr().into(),
+                        action: "lookup entry by date",
+                    });
+                    None
+                }
+            },
+            ReflogLookup::Entry(no) => match platform.rev().ok().flatten() {
+                Some(mut it) => match it.nth(no).and_then(Result::ok) {
+                    Some(line) => {
+                        self.objs[self.idx]
+                            .get_or_insert_with(HashSet::default)
+                            .insert(line.new_oid);
+                        Some(())
+                    }
                     None => {
-                        self.err.push(Error::MissingRefLog {
-                            reference: r.name().as_bstr().into(),
-                            action: "lookup entry",
+                        let available = platform.rev().ok().flatten().map_or(0, Iterator::count);
+                        self.err.push(if available == 0 {
+                            Error::EmptyRefLog {
+                                reference: r.detach(),
+                                action: "lookup entry",
+                            }
+                        } else {
+                            Error::RefLogEntryOutOfRange {
+                                reference: r.detach(),
+                                desired: no,
+                                available,
+                            }
                         });
                         None
                     }
+                },
+                None => {
+                    self.err.push(Error::MissingRefLog {
+                        reference: r.name().as_bstr().into(),
+                        action: "lookup entry",
+                    });
+                    None
                 }
-            }
+            },
         }
     }
 
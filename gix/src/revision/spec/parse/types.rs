@@ -89,6 +89,11 @@ pub enum Error {
         desired: usize,
         available: usize,
     },
+    #[error("Reference {:?} has an empty reference log, cannot {action}", reference.name.as_bstr())]
+    EmptyRefLog {
+        reference: gix_ref::Reference,
+        action: &'static str,
+    },
     #[error(
         "Commit {oid} has {available} ancestors along the first parent and ancestor number {desired} is out of range"
     )]
@@ -192,6 +197,8 @@ pub enum Error {
     Traverse(#[from] crate::revision::walk::iter::Error),
     #[error(transparent)]
     Walk(#[from] crate::revision::walk::Error),
+    #[error(transparent)]
+    Grafts(#[from] crate::shallow::grafts::Error),
     #[error("Spec does not contain a single object id")]
     SingleNotFound,
 }
@@ -1,5 +1,4 @@
 use gix_hash::ObjectId;
-use gix_object::FindExt;
 use gix_traverse::commit::simple::CommitTimeOrder;
 
 use crate::{ext::ObjectIdExt, revision, Repository};
@@ -11,7 +10,7 @@ pub enum Error {
     #[error(transparent)]
     SimpleTraversal(#[from] gix_traverse::commit::simple::Error),
     #[error(transparent)]
-    ShallowCommits(#[from] crate::shallow::open::Error),
+    Grafts(#[from] crate::shallow::grafts::Error),
     #[error(transparent)]
     ConfigBoolean(#[from] crate::config::boolean::Error),
 }
@@ -262,43 +261,16 @@ impl<'repo> Platform<'repo> {
             mut prune,
         } = self;
         prune.sort();
+        let grafts = repo.grafts()?;
         Ok(revision::Walk {
             repo,
             inner: Box::new(
-                gix_traverse::commit::Simple::filtered(tips, &repo.objects, {
-                    // Note that specific shallow handling for commit-graphs isn't needed as these contain
-                    // all information there is, and exclude shallow parents to be structurally consistent.
-                    let shallow_commits = repo.shallow_commits()?;
-                    let mut grafted_parents_to_skip = Vec::new();
-                    let mut buf = Vec::new();
-                    move |id| {
-                        if !filter(id) {
-                            return false;
-                        }
-                        let id = id.to_owned();
-                        if prune.binary_search(&id).is_ok() {
-                            return false;
-                        }
-                        match shallow_commits.as_ref() {
-                            Some(commits) => {
-                                if let Ok(idx) = grafted_parents_to_skip.binary_search(&id) {
-                                    grafted_parents_to_skip.remove(idx);
-                                    return false;
-                                };
-                                if commits.binary_search(&id).is_ok() {
-                                    if let Ok(commit) = repo.objects.find_commit_iter(&id, &mut buf) {
-                                        grafted_parents_to_skip.extend(commit.parent_ids());
-                                        grafted_parents_to_skip.sort();
-                                    }
-                                };
-                                true
-                            }
-                            None => true,
-                        }
-                    }
+                gix_traverse::commit::Simple::filtered(tips, &repo.objects, move |id| {
+                    filter(id) && prune.binary_search(&id.to_owned()).is_err()
                 })
                 .sorting(sorting.into_simple().expect("for now there is nothing else"))?
                 .parents(parents)
+                .parents_provider((!grafts.is_empty()).then(|| Box::new(grafts) as _))
                 .commit_graph(
                     commit_graph.or(use_commit_graph
                         .map_or_else(|| self.repo.config.may_use_commit_graph(), Ok)?
@@ -90,3 +90,18 @@ pub mod open {
         DecodeHash(#[from] gix_hash::decode::Error),
     }
 }
+
+///
+pub mod grafts {
+    /// The error returned by [`Repository::grafts()`][crate::Repository::grafts()].
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("Could not open grafts file for reading")]
+        Io(#[from] std::io::Error),
+        #[error(transparent)]
+        Decode(#[from] gix_revwalk::parents::decode::Error),
+        #[error(transparent)]
+        ShallowCommits(#[from] super::open::Error),
+    }
+}
@@ -0,0 +1,269 @@
+//! Manage the patterns of sparse checkouts, which restrict the files present in the worktree to a subset of the index.
+use std::collections::BTreeSet;
+
+use crate::bstr::{BStr, BString, ByteSlice, ByteVec};
+
+/// The error returned when parsing or validating sparse-checkout patterns.
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("Line {line:?} is not a cone-mode pattern, and cone-mode can't be used with it")]
+    NonConePattern { line: BString },
+    #[error("{directory:?} is not a valid directory in cone-mode as it must not be empty, contain wildcards or '.' and '..' components")]
+    InvalidConeDirectory { directory: BString },
+}
+
+/// The way in which the sparse-checkout file is interpreted, as controlled by `core.sparseCheckoutCone`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Mode {
+    /// Only directories are listed, and all files within them are included recursively, along with all files in
+    /// the root of the worktree and all files directly within parent directories of listed directories.
+    Cone,
+    /// Patterns like in `.gitignore` files determine which files are included, with negative patterns excluding them again.
+    Patterns,
+}
+
+/// The patterns as stored in `$GIT_DIR/info/sparse-checkout`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Patterns {
+    kind: Kind,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Kind {
+    /// All recursively included directories, without leading or trailing slash.
+    Cone(BTreeSet<BString>),
+    Patterns {
+        lines: Vec<BString>,
+        search: gix_ignore::Search,
+    },
+}
+
+/// Instantiation
+impl Patterns {
+    /// Create cone-mode patterns which include all of `directories` recursively, like `git sparse-checkout set --cone`
+    /// would. A leading or trailing slash is ignored.
+    pub fn from_directories(directories: impl IntoIterator<Item = impl Into<BString>>) -> Result<Self, Error> {
+        let mut out = Patterns {
+            kind: Kind::Cone(Default::default()),
+        };
+        out.add(directories)?;
+        Ok(out)
+    }
+
+    /// Create patterns that are interpreted like `.gitignore` patterns from `lines`, with each line being a pattern.
+    pub fn from_lines(lines: impl IntoIterator<Item = impl Into<BString>>) -> Self {
+        let mut out = Patterns {
+            kind: Kind::Patterns {
+                lines: Vec::new(),
+                search: Default::default(),
+            },
+        };
+        out.add(lines).expect("non-cone patterns never fail");
+        out
+    }
+
+    /// Parse the content of a sparse-checkout file in `data`, interpreting it according to `mode`.
+    ///
+    /// In cone mode, all lines must have been written in cone mode, or an error is returned.
+    pub fn from_bytes(data: &[u8], mode: Mode) -> Result<Self, Error> {
+        let lines = data
+            .lines()
+            .map(ByteSlice::trim_end)
+            .filter(|line| !line.is_empty() && !line.starts_with(b"#"));
+        Ok(match mode {
+            Mode::Patterns => Self::from_lines(lines.map(ByteSlice::as_bstr)),
+            Mode::Cone => {
+                let mut recursive = BTreeSet::new();
+                let mut parents = BTreeSet::new();
+                for line in lines {
+                    if line == b"/*" || line == b"!/*/" {
+                        continue;
+                    }
+                    let invalid = || Error::NonConePattern {
+                        line: line.as_bstr().to_owned(),
+                    };
+                    if let Some(dir) = line.strip_prefix(b"!/").and_then(|l| l.strip_suffix(b"/*/")) {
+                        parents.insert(unescape(dir));
+                    } else if let Some(dir) = line.strip_prefix(b"/").and_then(|l| l.strip_suffix(b"/")) {
+                        let dir = unescape(dir);
+                        validate_directory(dir.as_bstr()).map_err(|_| invalid())?;
+                        recursive.insert(dir);
+                    } else {
+                        return Err(invalid());
+                    }
+                }
+                // Directories that are followed by their negation are parents, not recursively included.
+                recursive.retain(|dir| !parents.contains(dir));
+                Patterns {
+                    kind: Kind::Cone(recursive),
+                }
+            }
+        })
+    }
+}
+
+/// Access and Mutation
+impl Patterns {
+    /// Return the mode in which these patterns are interpreted.
+    pub fn mode(&self) -> Mode {
+        match self.kind {
+            Kind::Cone(_) => Mode::Cone,
+            Kind::Patterns { .. } => Mode::Patterns,
+        }
+    }
+
+    /// Return the directories that are included recursively in cone-mode, or the patterns otherwise.
+    pub fn lines(&self) -> Vec<&BStr> {
+        match &self.kind {
+            Kind::Cone(dirs) => dirs.iter().map(|dir| dir.as_bstr()).collect(),
+            Kind::Patterns { lines, .. } => lines.iter().map(|line| line.as_bstr()).collect(),
+        }
+    }
+
+    /// Add `input`, which are directories in cone-mode or patterns otherwise, to the existing ones.
+    pub fn add(&mut self, input: impl IntoIterator<Item = impl Into<BString>>) -> Result<(), Error> {
+        match &mut self.kind {
+            Kind::Cone(dirs) => {
+                for dir in input {
+                    let dir: BString = dir.into();
+                    let trimmed = dir.trim_start_with(|c| c == '/').trim_end_with(|c| c == '/');
+                    validate_directory(trimmed.as_bstr())?;
+                    dirs.insert(trimmed.as_bstr().to_owned());
+                }
+            }
+            Kind::Patterns { lines, search } => {
+                let start = lines.len();
+                lines.extend(input.into_iter().map(Into::into));
+                let mut buf = BString::default();
+                for line in &lines[start..] {
+                    buf.push_str(line);
+                    buf.push(b'\n');
+                }
+                search.add_patterns_buffer(&buf, "sparse-checkout", None);
+            }
+        }
+        Ok(())
+    }
+
+    /// Return `true` if the file at `rela_path`, relative to the root of the worktree, is part of the sparse checkout.
+    pub fn is_included(&self, rela_path: &BStr, case: gix_glob::pattern::Case) -> bool {
+        match &self.kind {
+            Kind::Cone(dirs) => {
+                let Some(parent) = rela_path.rfind_byte(b'/').map(|pos| rela_path[..pos].as_bstr()) else {
+                    return true;
+                };
+                let eq = |a: &BStr, b: &BStr| match case {
+                    gix_glob::pattern::Case::Sensitive => a == b,
+                    gix_glob::pattern::Case::Fold => a.eq_ignore_ascii_case(b),
+                };
+                let is_ancestor_or_self = |dir: &BStr| {
+                    parent.len() >= dir.len()
+                        && eq(parent[..dir.len()].as_bstr(), dir)
+                        && (parent.len() == dir.len() || parent[dir.len()] == b'/')
+                };
+                let is_parent_of_cone = |dir: &BStr| {
+                    dir.len() > parent.len() && eq(dir[..parent.len()].as_bstr(), parent) && dir[parent.len()] == b'/'
+                };
+                dirs.iter()
+                    .any(|dir| is_ancestor_or_self(dir.as_bstr()) || is_parent_of_cone(dir.as_bstr()))
+            }
+            Kind::Patterns { search, .. } => {
+                let mut path = rela_path;
+                let mut is_dir = false;
+                loop {
+                    if let Some(m) = search.pattern_matching_relative_path(path, Some(is_dir), case) {
+                        break !m.pattern.is_negative();
+                    }
+                    match path.rfind_byte(b'/') {
+                        Some(pos) => {
+                            path = path[..pos].as_bstr();
+                            is_dir = true;
+                        }
+                        None => break false,
+                    }
+                }
+            }
+        }
+    }
+
+    /// Serialize these patterns in the format used by the sparse-checkout file.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out = Vec::new();
+        match &self.kind {
+            Kind::Cone(dirs) => {
+                out.extend_from_slice(b"/*\n!/*/\n");
+                let mut parents_written = BTreeSet::<&[u8]>::new();
+                for dir in dirs {
+                    // Skip directories that are already included by one of their parents.
+                    if dirs
+                        .iter()
+                        .any(|other| other.len() < dir.len() && dir.starts_with(other) && dir[other.len()] == b'/')
+                    {
+                        continue;
+                    }
+                    for pos in dir.find_iter(b"/") {
+                        let parent = &dir[..pos];
+                        if parents_written.insert(parent) {
+                            out.push(b'/');
+                            out.extend_from_slice(parent);
+                            out.extend_from_slice(b"/\n!/");
+                            out.extend_from_slice(parent);
+                            out.extend_from_slice(b"/*/\n");
+                        }
+                    }
+                    out.push(b'/');
+                    out.extend_from_slice(dir);
+                    out.extend_from_slice(b"/\n");
+                }
+            }
+            Kind::Patterns { lines, .. } => {
+                for line in lines {
+                    out.extend_from_slice(line);
+                    out.push(b'\n');
+                }
+            }
+        }
+        out
+    }
+}
+
+fn validate_directory(dir: &BStr) -> Result<(), Error> {
+    let is_invalid = dir.is_empty()
+        || dir.iter().any(|b| matches!(b, b'*' | b'?' | b'[' | b'\\' | b'\n'))
+        || dir.split_str("/").any(|c| c.is_empty() || c == b"." || c == b"..");
+    if is_invalid {
+        Err(Error::InvalidConeDirectory {
+            directory: dir.to_owned(),
+        })
+    } else {
+        Ok(())
+    }
+}
+
+fn unescape(dir: &[u8]) -> BString {
+    let mut out = BString::default();
+    let mut bytes = dir.iter();
+    while let Some(b) = bytes.next() {
+        if *b == b'\\' {
+            if let Some(escaped) = bytes.next() {
+                out.push(*escaped);
+            }
+        } else {
+            out.push(*b);
+        }
+    }
+    out
+}
+
+/// The outcome of applying sparse-checkout patterns to the index and the worktree.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Outcome {
+    /// The paths of all files that were written to the worktree as they became part of the sparse checkout.
+    pub added: Vec<BString>,
+    /// The paths of all files that were removed from the worktree as they are no longer part of the sparse checkout.
+    pub removed: Vec<BString>,
+    /// The paths of files that should have been removed, but were kept as they were modified in the worktree.
+    /// These remain part of the checkout until the modification is undone.
+    pub kept_modified: Vec<BString>,
+}
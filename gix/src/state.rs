@@ -1,5 +1,5 @@
 /// Tell what operation is currently in progress.
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum InProgress {
     /// A mailbox is being applied.
     ApplyMailbox,
@@ -320,6 +320,16 @@ pub mod iter {
         NewStat(crate::index::entry::Stat),
     }
 
+    /// What to do with the untracked cache extension of the index when writing it back.
+    pub(super) enum UntrackedCacheChange {
+        /// Leave it as is, which means it's kept if present.
+        Keep,
+        /// Remove it as it shouldn't be used.
+        Remove,
+        /// Add it or replace it with an empty one as it's missing or was created for another location or system.
+        Replace(gix_index::extension::UntrackedCache),
+    }
+
     /// The data the thread sends over to the receiving iterator.
     pub struct Outcome {
         /// The outcome of the index-to-worktree comparison operation.
@@ -327,7 +337,14 @@ pub mod iter {
         /// The index that was used for the operation.
         pub index: crate::worktree::IndexPersistedOrInMemory,
         skip_hash: bool,
+        fsync: gix_features::fs::Fsync,
         changes: Option<Vec<(usize, iter::ApplyChange)>>,
+        /// What to do with the untracked cache extension, as configured with `core.untrackedCache`.
+        untracked_cache: UntrackedCacheChange,
+        /// If `true`, the repository was opened read-only and changes must not be written.
+        read_only: bool,
+        /// The time just before the operation started, which is the timestamp of the index with the changes applied.
+        started_at: filetime::FileTime,
     }
 
     impl Outcome {
@@ -335,19 +352,73 @@ pub mod iter {
         ///
         /// If they are not written back, subsequent `status` operations will take longer to complete, whereas the
         /// additional work can be prevented by writing the changes back to the index.
+        ///
+        /// This is also the case if the untracked cache extension of the index should be removed or added
+        /// according to [`core.untrackedCache`](crate::Repository::untracked_cache_mode()).
         pub fn has_changes(&self) -> bool {
-            self.changes.as_ref().map_or(false, |changes| !changes.is_empty())
+            !matches!(self.untracked_cache, UntrackedCacheChange::Keep)
+                || self.changes.as_ref().map_or(false, |changes| !changes.is_empty())
         }
 
         /// Write the changes if there are any back to the index file.
         /// This can only be done once as the changes are consumed in the process, if there were any.
+        ///
+        /// With `core.untrackedCache=false`, the untracked cache extension is removed, and with `core.untrackedCache=true`,
+        /// an empty one is added if there is none for this worktree yet, so `git` can fill it in when it runs next.
+        /// Otherwise, it's written back unchanged.
+        ///
+        /// If the repository was opened [read-only](crate::open::Options::read_only()), an error of kind
+        /// [`PermissionDenied`](std::io::ErrorKind::PermissionDenied) wrapping a [read-only error](crate::repository::read_only::Error)
+        /// is returned instead if there are changes.
         pub fn write_changes(&mut self) -> Option<Result<(), gix_index::file::write::Error>> {
             let _span = gix_features::trace::coarse!("gix::status::index_worktree::iter::Outcome::write_changes()");
-            let changes = self.changes.take()?;
+            if !self.has_changes() {
+                return None;
+            }
+            if self.read_only {
+                return Some(Err(std::io::Error::new(
+                    std::io::ErrorKind::PermissionDenied,
+                    crate::repository::read_only::Error {
+                        operation: "write the index",
+                    },
+                )
+                .into()));
+            }
+            let mut index = self.changed_index().expect("there are changes");
+            Some(index.write(crate::index::write::Options {
+                extensions: Default::default(),
+                skip_hash: self.skip_hash,
+                fsync: self.fsync,
+                ..Default::default()
+            }))
+        }
+
+        /// Return a copy of the index that was used for the operation with all changes applied if there are any,
+        /// just like [`write_changes()`](Self::write_changes()) would write it, but without writing it.
+        /// This can only be done once as the changes are consumed in the process.
+        ///
+        /// Passing it to the next `status` operation as [in-memory index](crate::status::Platform::index()) avoids
+        /// redoing the work that led to the changes without having to write the index, which also works in
+        /// read-only repositories.
+        pub fn changed_index(&mut self) -> Option<crate::index::File> {
+            if !self.has_changes() {
+                return None;
+            }
+            let untracked_cache = std::mem::replace(&mut self.untracked_cache, UntrackedCacheChange::Keep);
+            let changes = self.changes.take().unwrap_or_default();
             let mut index = match &self.index {
                 IndexPersistedOrInMemory::Persisted(persisted) => (***persisted).clone(),
                 IndexPersistedOrInMemory::InMemory(index) => index.clone(),
             };
+            match untracked_cache {
+                UntrackedCacheChange::Keep => {}
+                UntrackedCacheChange::Remove => {
+                    index.remove_untracked();
+                }
+                UntrackedCacheChange::Replace(cache) => {
+                    index.set_untracked(cache);
+                }
+            }
 
             let entries = index.entries_mut();
             for (entry_index, change) in changes {
@@ -361,11 +432,9 @@ pub mod iter {
                     }
                 }
             }
-
-            Some(index.write(crate::index::write::Options {
-                extensions: Default::default(),
-                skip_hash: self.skip_hash,
-            }))
+            // Entries were refreshed with what was seen after the operation started, just like a written index would see it.
+            index.set_timestamp(self.started_at);
+            Some(index)
         }
     }
 
@@ -607,6 +676,33 @@ pub mod iter {
         ConfigSkipHash(#[from] crate::config::boolean::Error),
         #[error(transparent)]
         PrepareSubmodules(#[from] crate::submodule::modules::Error),
+        #[error(transparent)]
+        ConfigUntrackedCache(#[from] crate::config::key::GenericErrorWithValue),
+        #[error("Could not resolve the worktree path to identify its untracked cache")]
+        UntrackedCacheIdentifier(#[from] gix_path::realpath::Error),
+    }
+
+    fn untracked_cache_change(
+        repo: &crate::Repository,
+        index: &gix_index::State,
+    ) -> Result<UntrackedCacheChange, Error> {
+        if !repo.use_untracked_cache(index)? {
+            return Ok(if index.untracked().is_some() {
+                UntrackedCacheChange::Remove
+            } else {
+                UntrackedCacheChange::Keep
+            });
+        }
+        let Some(workdir) = repo.work_dir() else {
+            return Ok(UntrackedCacheChange::Keep);
+        };
+        // Like `git`, only reuse an existing cache if it was made for this worktree, as it may have been moved.
+        let workdir = gix_path::realpath_opts(workdir, repo.current_dir(), gix_path::realpath::MAX_SYMLINKS)?;
+        let identifier = gix_index::extension::untracked_cache::identifier(gix_path::into_bstr(workdir).as_ref());
+        Ok(match index.untracked() {
+            Some(cache) if cache.identifier() == identifier => UntrackedCacheChange::Keep,
+            _ => UntrackedCacheChange::Replace(gix_index::extension::UntrackedCache::new(identifier)),
+        })
     }
 
     /// Lifecycle
@@ -637,6 +733,10 @@ pub mod iter {
                 .transpose()
                 .with_lenient_default(self.repo.config.lenient_config)?
                 .unwrap_or_default();
+            let fsync = self.repo.config.fsync.index;
+            let untracked_cache = untracked_cache_change(self.repo, &index)?;
+            let read_only = self.repo.is_read_only();
+            let started_at = filetime::FileTime::now();
             let should_interrupt = self.should_interrupt.clone().unwrap_or_default();
             let submodule = BuiltinSubmoduleStatus::new(self.repo.clone().into_sync(), self.submodules)?;
             #[cfg(feature = "parallel")]
@@ -668,6 +768,10 @@ pub mod iter {
                                 index,
                                 changes: None,
                                 skip_hash,
+                                fsync,
+                                untracked_cache,
+                                read_only,
+                                started_at,
                             })
                         }
                     })
@@ -703,6 +807,10 @@ pub mod iter {
                     index,
                     changes: None,
                     skip_hash,
+                    fsync,
+                    untracked_cache,
+                    read_only,
+                    started_at,
                 };
                 let mut iter = super::Iter {
                     items: Vec::new().into_iter(),
@@ -102,6 +102,34 @@ impl Submodule<'_> {
         self.state.modules.url(self.name())
     }
 
+    /// Like [`url()`](Self::url()), but resolve relative urls like `../sibling` against the url of the default remote of the
+    /// superproject, just like `git submodule init` does.
+    ///
+    /// The default remote is the one configured for the currently checked-out branch, or `origin`. If it isn't configured,
+    /// the superproject is considered its own upstream and relative urls are resolved against the path to its worktree.
+    pub fn resolved_url(&self) -> Result<gix_url::Url, config::url::Error> {
+        let repo = self.state.repo;
+        let remote_name = repo
+            .head_name()
+            .ok()
+            .flatten()
+            .and_then(|name| repo.branch_remote_name(name.shorten(), crate::remote::Direction::Fetch));
+        let base = match remote_name {
+            Some(crate::remote::Name::Url(url)) => Some(url),
+            Some(crate::remote::Name::Symbol(name)) => {
+                repo.config
+                    .resolved
+                    .string_by("remote", Some(name.as_ref().into()), "url")
+            }
+            None => repo.config.resolved.string_by("remote", Some("origin".into()), "url"),
+        };
+        let base = base.unwrap_or_else(|| {
+            let worktree = repo.current_dir().join(repo.work_dir().unwrap_or(repo.git_dir()));
+            gix_path::into_bstr(worktree).into_owned().into()
+        });
+        self.state.modules.resolved_url(self.name(), base.as_ref())
+    }
+
     /// Return the `update` field from this submodule's configuration, if present, or `None`.
     ///
     /// This method takes into consideration submodule configuration overrides.
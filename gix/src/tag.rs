@@ -2,16 +2,33 @@
 #![allow(clippy::empty_docs)]
 mod error {
 
-    /// The error returned by [`tag(…)`][crate::Repository::tag()].
+    /// The error returned by [`tag(…)`][crate::Repository::tag()] and [`create_tag(…)`][crate::Repository::create_tag()].
     #[derive(Debug, thiserror::Error)]
     #[allow(missing_docs)]
     pub enum Error {
+        #[error("Could not find the target of the tag")]
+        FindTarget(#[from] crate::object::find::existing::Error),
+        #[error("The tag target {id} is a {actual}, but the tag says it's a {expected}")]
+        TargetKindMismatch {
+            id: gix_hash::ObjectId,
+            expected: gix_object::Kind,
+            actual: gix_object::Kind,
+        },
         #[error(transparent)]
         ReferenceNameValidation(#[from] gix_ref::name::Error),
         #[error(transparent)]
         WriteObject(#[from] crate::object::write::Error),
         #[error(transparent)]
         ReferenceEdit(#[from] crate::reference::edit::Error),
+        #[cfg(feature = "command")]
+        #[error(transparent)]
+        ConfigBoolean(#[from] crate::config::boolean::Error),
+        #[cfg(feature = "command")]
+        #[error(transparent)]
+        Signer(#[from] crate::repository::keyring::Error),
+        #[cfg(feature = "command")]
+        #[error(transparent)]
+        Sign(#[from] crate::keyring::sign::Error),
     }
 }
 pub use error::Error;
@@ -111,6 +111,16 @@ pub(crate) fn id(git_dir: &std::path::Path, has_common_dir: bool) -> Option<&BSt
 ///
 pub mod proxy;
 
+///
+pub mod prune;
+
+///
+pub mod repair;
+
+///
+#[cfg(feature = "status")]
+pub mod verify;
+
 ///
 #[cfg(feature = "index")]
 pub mod open_index {
@@ -0,0 +1,157 @@
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use crate::bstr::BString;
+use crate::Repository;
+
+/// The error returned by [Repository::prune_worktrees()](crate::Repository::prune_worktrees()).
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("Could not read the directory with worktree administrative files at '{}'", path.display())]
+    ReadDir { path: PathBuf, source: std::io::Error },
+    #[error("Could not remove the administrative files at '{}'", path.display())]
+    Remove { path: PathBuf, source: std::io::Error },
+    #[error(transparent)]
+    ReadOnly(#[from] crate::repository::read_only::Error),
+}
+
+/// Options for use in [Repository::prune_worktrees()](crate::Repository::prune_worktrees()).
+#[derive(Default, Debug, Copy, Clone)]
+pub struct Options {
+    /// If set, worktrees whose checkout is missing are only pruned if their `gitdir` file wasn't modified after this time,
+    /// like `git worktree prune --expire <time>`.
+    ///
+    /// If `None`, they are pruned no matter their age, which is what `git worktree prune` does by default.
+    pub expire: Option<SystemTime>,
+    /// If `true`, only report what would be pruned without removing anything, like `git worktree prune --dry-run`.
+    pub dry_run: bool,
+}
+
+/// The reason for the administrative files of a worktree to be pruned.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Reason {
+    /// The administrative files aren't contained in a directory.
+    NotADirectory,
+    /// The `gitdir` file pointing to the checkout doesn't exist.
+    MissingGitdirFile,
+    /// The `gitdir` file pointing to the checkout couldn't be read.
+    UnreadableGitdirFile,
+    /// The `gitdir` file pointing to the checkout is empty.
+    EmptyGitdirFile,
+    /// The `.git` file of the checkout at `location` doesn't exist anymore.
+    MissingCheckout {
+        /// The location of the `.git` file as recorded in the `gitdir` file.
+        location: PathBuf,
+    },
+}
+
+impl std::fmt::Display for Reason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Reason::NotADirectory => "not a valid directory",
+            Reason::MissingGitdirFile => "gitdir file does not exist",
+            Reason::UnreadableGitdirFile => "unable to read gitdir file",
+            Reason::EmptyGitdirFile => "invalid gitdir file",
+            Reason::MissingCheckout { .. } => "gitdir file points to non-existent location",
+        })
+    }
+}
+
+/// A worktree whose administrative files were pruned, as returned by [Repository::prune_worktrees()](crate::Repository::prune_worktrees()).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Pruned {
+    /// The id of the worktree, the name of the directory containing its administrative files.
+    pub id: BString,
+    /// The path to the administrative files that were removed, typically in `.git/worktrees/<id>`.
+    pub git_dir: PathBuf,
+    /// The reason for the worktree to be pruned.
+    pub reason: Reason,
+}
+
+impl Repository {
+    /// Remove the administrative files of linked worktrees whose checkout went missing, like `git worktree prune`, and
+    /// return information about each pruned worktree sorted by id.
+    ///
+    /// Locked worktrees are never pruned, and the `worktrees` directory itself is removed once it's empty.
+    pub fn prune_worktrees(&self, options: Options) -> Result<Vec<Pruned>, Error> {
+        if !options.dry_run {
+            self.ensure_writable("prune worktrees")?;
+        }
+        let worktrees_dir = self.common_dir().join("worktrees");
+        let iter = match std::fs::read_dir(&worktrees_dir) {
+            Ok(iter) => iter,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(source) => {
+                return Err(Error::ReadDir {
+                    path: worktrees_dir,
+                    source,
+                })
+            }
+        };
+        let mut out = Vec::new();
+        for entry in iter {
+            let entry = entry.map_err(|source| Error::ReadDir {
+                path: worktrees_dir.clone(),
+                source,
+            })?;
+            let git_dir = entry.path();
+            let Some(reason) = prune_reason(&git_dir, options.expire) else {
+                continue;
+            };
+            if !options.dry_run {
+                let res = if reason == Reason::NotADirectory {
+                    std::fs::remove_file(&git_dir)
+                } else {
+                    std::fs::remove_dir_all(&git_dir)
+                };
+                res.map_err(|source| Error::Remove {
+                    path: git_dir.clone(),
+                    source,
+                })?;
+            }
+            out.push(Pruned {
+                id: gix_path::into_bstr(PathBuf::from(entry.file_name())).into_owned(),
+                git_dir,
+                reason,
+            });
+        }
+        if !options.dry_run {
+            // It's fine if this fails as there are worktrees left.
+            std::fs::remove_dir(&worktrees_dir).ok();
+        }
+        out.sort_by(|a, b| a.id.cmp(&b.id));
+        Ok(out)
+    }
+}
+
+fn prune_reason(git_dir: &Path, expire: Option<SystemTime>) -> Option<Reason> {
+    if !git_dir.is_dir() {
+        return Some(Reason::NotADirectory);
+    }
+    if git_dir.join("locked").exists() {
+        return None;
+    }
+    let gitdir_file = git_dir.join("gitdir");
+    let mtime = match std::fs::metadata(&gitdir_file) {
+        Ok(meta) => meta.modified().ok(),
+        Err(_) => return Some(Reason::MissingGitdirFile),
+    };
+    let location = match gix_discover::path::from_plain_file(&gitdir_file) {
+        Some(Ok(location)) => location,
+        _ => return Some(Reason::UnreadableGitdirFile),
+    };
+    if location.as_os_str().is_empty() {
+        return Some(Reason::EmptyGitdirFile);
+    }
+    let location = git_dir.join(location);
+    if location.exists() {
+        return None;
+    }
+    let is_expired = match (expire, mtime) {
+        (None, _) => true,
+        (Some(expire), Some(mtime)) => mtime <= expire,
+        (Some(_), None) => false,
+    };
+    is_expired.then_some(Reason::MissingCheckout { location })
+}
@@ -0,0 +1,155 @@
+use std::path::{Path, PathBuf};
+
+use crate::Repository;
+
+/// The error returned by [Repository::repair_worktrees()](crate::Repository::repair_worktrees()).
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("Could not list linked worktrees")]
+    ListWorktrees(#[source] std::io::Error),
+    #[error("'{}' is not a file", path.display())]
+    DotGitNotAFile { path: PathBuf },
+    #[error("Unable to locate repository as '{}' does not reference one", path.display())]
+    NoRepository { path: PathBuf },
+    #[error("Could not write '{}'", path.display())]
+    Write { path: PathBuf, source: std::io::Error },
+    #[error(transparent)]
+    ReadOnly(#[from] crate::repository::read_only::Error),
+}
+
+/// The kind of repair performed by [Repository::repair_worktrees()](crate::Repository::repair_worktrees()).
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Kind {
+    /// The `.git` file in the checkout couldn't be read and was rewritten to point to the administrative files.
+    DotGitFileBroken,
+    /// The `.git` file in the checkout pointed to another location and was rewritten to point to the administrative files.
+    DotGitFileIncorrect,
+    /// The `gitdir` file of the administrative files couldn't be read and was rewritten to point to the checkout.
+    GitdirFileUnreadable,
+    /// The `gitdir` file of the administrative files pointed to another location, typically because the checkout was moved,
+    /// and was rewritten to point to the checkout.
+    GitdirFileIncorrect,
+}
+
+impl std::fmt::Display for Kind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Kind::DotGitFileBroken => ".git file broken",
+            Kind::DotGitFileIncorrect => ".git file incorrect",
+            Kind::GitdirFileUnreadable => "gitdir unreadable",
+            Kind::GitdirFileIncorrect => "gitdir incorrect",
+        })
+    }
+}
+
+/// A repair performed by [Repository::repair_worktrees()](crate::Repository::repair_worktrees()).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Repaired {
+    /// The path to the `.git` file in the checkout of the worktree.
+    pub dot_git: PathBuf,
+    /// The path to the administrative files of the worktree, typically in `.git/worktrees/<id>`.
+    pub git_dir: PathBuf,
+    /// What was repaired.
+    pub kind: Kind,
+}
+
+impl Repository {
+    /// Repair the links between linked worktrees and their administrative files, like `git worktree repair [<path>…]`,
+    /// and return all repairs that were performed.
+    ///
+    /// First, the `.git` files of all linked worktrees whose checkout can still be found are made to point to their
+    /// administrative files again. Then, for each path in `moved_worktrees`, the administrative files that its `.git` file
+    /// refers to are made to point to the checkout at its new location.
+    /// This is needed if a checkout was moved, as it can't be found from the administrative files anymore.
+    pub fn repair_worktrees(
+        &self,
+        moved_worktrees: impl IntoIterator<Item = impl AsRef<Path>>,
+    ) -> Result<Vec<Repaired>, Error> {
+        self.ensure_writable("repair worktrees")?;
+        let mut out = Vec::new();
+        for proxy in self.worktrees().map_err(Error::ListWorktrees)? {
+            let Ok(base) = proxy.base() else {
+                continue;
+            };
+            if !base.is_dir() {
+                continue;
+            }
+            out.extend(repair_dot_git_file(
+                &base.join(gix_discover::DOT_GIT_DIR),
+                proxy.git_dir(),
+            )?);
+        }
+        for worktree in moved_worktrees {
+            out.extend(self.repair_gitdir_file(worktree.as_ref())?);
+        }
+        Ok(out)
+    }
+
+    fn repair_gitdir_file(&self, worktree: &Path) -> Result<Option<Repaired>, Error> {
+        let dot_git = worktree.join(gix_discover::DOT_GIT_DIR);
+        if dot_git.is_dir() {
+            return Err(Error::DotGitNotAFile { path: dot_git });
+        }
+        let dot_git = gix_path::realpath(&dot_git).map_err(|_| Error::NoRepository { path: dot_git.clone() })?;
+        let git_dir = gix_discover::path::from_gitdir_file(&dot_git)
+            .ok()
+            .and_then(|git_dir| {
+                if git_dir.join("gitdir").is_file() {
+                    Some(git_dir)
+                } else {
+                    // The repository itself might have been moved, but the worktree id can still be used to find it.
+                    let git_dir = self.common_dir().join("worktrees").join(git_dir.file_name()?);
+                    git_dir.join("gitdir").is_file().then_some(git_dir)
+                }
+            })
+            .ok_or_else(|| Error::NoRepository { path: dot_git.clone() })?;
+
+        let gitdir_file = git_dir.join("gitdir");
+        let kind = match gix_discover::path::from_plain_file(&gitdir_file) {
+            Some(Ok(previous)) if is_same_path(&git_dir.join(&previous), &dot_git) => return Ok(None),
+            Some(Ok(_)) => Kind::GitdirFileIncorrect,
+            _ => Kind::GitdirFileUnreadable,
+        };
+        let mut content = gix_path::into_bstr(dot_git.as_path()).into_owned();
+        content.push(b'\n');
+        std::fs::write(&gitdir_file, content).map_err(|source| Error::Write {
+            path: gitdir_file,
+            source,
+        })?;
+        Ok(Some(Repaired { dot_git, git_dir, kind }))
+    }
+}
+
+fn repair_dot_git_file(dot_git: &Path, git_dir: &Path) -> Result<Option<Repaired>, Error> {
+    if dot_git.is_dir() {
+        return Err(Error::DotGitNotAFile {
+            path: dot_git.to_owned(),
+        });
+    }
+    let kind = match gix_discover::path::from_gitdir_file(dot_git) {
+        Ok(previous) if is_same_path(&previous, git_dir) => return Ok(None),
+        Ok(_) => Kind::DotGitFileIncorrect,
+        Err(_) => Kind::DotGitFileBroken,
+    };
+    let git_dir = gix_path::realpath(git_dir).unwrap_or_else(|_| git_dir.to_owned());
+    let mut content = crate::bstr::BString::from("gitdir: ");
+    content.extend_from_slice(&gix_path::into_bstr(git_dir.as_path()));
+    content.push(b'\n');
+    std::fs::write(dot_git, content).map_err(|source| Error::Write {
+        path: dot_git.to_owned(),
+        source,
+    })?;
+    Ok(Some(Repaired {
+        dot_git: dot_git.to_owned(),
+        git_dir,
+        kind,
+    }))
+}
+
+fn is_same_path(a: &Path, b: &Path) -> bool {
+    match (gix_path::realpath(a), gix_path::realpath(b)) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => false,
+    }
+}
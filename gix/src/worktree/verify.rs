@@ -0,0 +1,191 @@
+use std::cmp::Ordering;
+
+use crate::bstr::BString;
+use crate::status::index_worktree::iter::Summary;
+use crate::Worktree;
+
+/// The error returned by [`Worktree::verify()`].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error(transparent)]
+    OpenIndex(#[from] crate::worktree::open_index::Error),
+    #[error(transparent)]
+    FindHead(#[from] crate::reference::find::existing::Error),
+    #[error(transparent)]
+    HeadTreeId(#[from] crate::reference::head_tree_id::Error),
+    #[error(transparent)]
+    IndexFromTree(#[from] crate::repository::index_from_tree::Error),
+    #[error(transparent)]
+    Status(#[from] crate::status::Error),
+    #[error(transparent)]
+    IntoStatusIter(#[from] crate::status::index_worktree::iter::Error),
+    #[error(transparent)]
+    StatusIter(#[from] crate::status::index_worktree::Error),
+}
+
+/// A difference between the index, the tree of `HEAD` and the worktree, as found by [`Worktree::verify()`].
+#[derive(Debug)]
+pub enum Divergence {
+    /// The worktree file at `rela_path` doesn't match its index entry, with the kind of difference in `summary`.
+    Worktree {
+        /// The repository-relative path of the entry.
+        rela_path: BString,
+        /// The kind of difference.
+        summary: Summary,
+    },
+    /// The index has conflicting entries at `rela_path`, which are neither compared to the tree of `HEAD`
+    /// nor to the worktree.
+    Conflict {
+        /// The repository-relative path of the entries.
+        rela_path: BString,
+        /// The kind of conflict, as derived from the stages present in the index.
+        conflict: gix_status::index_as_worktree::Conflict,
+    },
+    /// The index entry at `rela_path` doesn't match the entry in the tree of `HEAD`.
+    Head {
+        /// The repository-relative path of the entry.
+        rela_path: BString,
+        /// The id and mode of the entry in the index, or `None` if it's not present there.
+        index: Option<(gix_hash::ObjectId, gix_index::entry::Mode)>,
+        /// The id and mode of the entry in the tree of `HEAD`, or `None` if it's not present there.
+        head: Option<(gix_hash::ObjectId, gix_index::entry::Mode)>,
+    },
+    /// The root of the `TREE` extension doesn't match the tree of `HEAD`.
+    TreeExtensionRoot {
+        /// The tree id recorded in the extension.
+        extension: gix_hash::ObjectId,
+        /// The id of the tree of `HEAD`.
+        head: gix_hash::ObjectId,
+    },
+    /// The `TREE` extension isn't consistent with the trees it refers to.
+    TreeExtension(gix_index::verify::extensions::Error),
+}
+
+/// The outcome of [`Worktree::verify()`].
+#[derive(Debug)]
+pub struct Outcome {
+    /// The amount of entries in the index.
+    pub num_entries: usize,
+    /// All differences that were found, in the order in which they were checked: `HEAD`, the `TREE` extension,
+    /// and finally conflicts and the worktree.
+    pub divergences: Vec<Divergence>,
+}
+
+impl Outcome {
+    /// Return `true` if the index, the tree of `HEAD` and the worktree all match.
+    pub fn is_consistent(&self) -> bool {
+        self.divergences.is_empty()
+    }
+}
+
+impl Worktree<'_> {
+    /// Cross-check that every index entry matches the entry of the same path in the tree of `HEAD`, that the
+    /// `TREE` extension of the index matches the tree of `HEAD` as well, and that all index entries match their files
+    /// in the worktree by stat and content, returning all divergences.
+    ///
+    /// Conflicting index entries are reported as [conflicts](Divergence::Conflict) only, and untracked files are ignored.
+    /// This is useful to validate checkouts, and it's expected to find no divergence right after one.
+    pub fn verify(&self) -> Result<Outcome, Error> {
+        let repo = self.parent;
+        let index = self.index()?;
+        let mut divergences = Vec::new();
+
+        let head_tree_id = if repo.head()?.is_unborn() {
+            None
+        } else {
+            Some(repo.head_tree_id()?.detach())
+        };
+        let head_index = head_tree_id.map(|id| repo.index_from_tree(&id)).transpose()?;
+        let empty = gix_index::State::new(repo.object_hash());
+        let head_state: &gix_index::State = head_index.as_deref().unwrap_or(&empty);
+        let mut head_entries = head_state.entries().iter().peekable();
+        let mut index_entries = index.entries().iter().peekable();
+        loop {
+            if let Some(conflicted) = index_entries.next_if(|e| e.stage_raw() != 0) {
+                let rela_path = conflicted.path(&index);
+                while index_entries.next_if(|e| e.path(&index) == rela_path).is_some() {}
+                head_entries.next_if(|e| e.path(head_state) == rela_path);
+                continue;
+            }
+            let ordering = match (index_entries.peek(), head_entries.peek()) {
+                (None, None) => break,
+                (Some(_), None) => Ordering::Less,
+                (None, Some(_)) => Ordering::Greater,
+                (Some(lhs), Some(rhs)) => lhs.path(&index).cmp(rhs.path(head_state)),
+            };
+            match ordering {
+                Ordering::Less => {
+                    let entry = index_entries.next().expect("peeked");
+                    divergences.push(Divergence::Head {
+                        rela_path: entry.path(&index).to_owned(),
+                        index: Some((entry.id, entry.mode)),
+                        head: None,
+                    });
+                }
+                Ordering::Greater => {
+                    let entry = head_entries.next().expect("peeked");
+                    divergences.push(Divergence::Head {
+                        rela_path: entry.path(head_state).to_owned(),
+                        index: None,
+                        head: Some((entry.id, entry.mode)),
+                    });
+                }
+                Ordering::Equal => {
+                    let (lhs, rhs) = (
+                        index_entries.next().expect("peeked"),
+                        head_entries.next().expect("peeked"),
+                    );
+                    if lhs.id != rhs.id || lhs.mode != rhs.mode {
+                        divergences.push(Divergence::Head {
+                            rela_path: lhs.path(&index).to_owned(),
+                            index: Some((lhs.id, lhs.mode)),
+                            head: Some((rhs.id, rhs.mode)),
+                        });
+                    }
+                }
+            }
+        }
+
+        if let Some(tree) = index.tree() {
+            if let Some(head) = head_tree_id.filter(|id| tree.num_entries.is_some() && tree.id != *id) {
+                divergences.push(Divergence::TreeExtensionRoot {
+                    extension: tree.id,
+                    head,
+                });
+            }
+            if let Err(err) = index.verify_extensions(true, &repo.objects) {
+                divergences.push(Divergence::TreeExtension(err));
+            }
+        }
+
+        let num_entries = index.entries().len();
+        let status = repo
+            .status(gix_features::progress::Discard)?
+            .untracked_files(crate::status::UntrackedFiles::None)
+            .index_worktree_rewrites(None)
+            .index(index.into())
+            .into_index_worktree_iter(Vec::new())?;
+        for item in status {
+            let item = item?;
+            if let crate::status::index_worktree::iter::Item::Modification {
+                rela_path,
+                status: gix_status::index_as_worktree::EntryStatus::Conflict(conflict),
+                ..
+            } = item
+            {
+                divergences.push(Divergence::Conflict { rela_path, conflict });
+            } else if let Some(summary) = item.summary() {
+                divergences.push(Divergence::Worktree {
+                    rela_path: item.rela_path().to_owned(),
+                    summary,
+                });
+            }
+        }
+
+        Ok(Outcome {
+            num_entries,
+            divergences,
+        })
+    }
+}
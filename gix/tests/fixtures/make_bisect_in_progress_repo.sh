@@ -0,0 +1,13 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+git init -q
+
+for name in f1 f2 f3; do
+  echo $name > $name
+  git add $name
+  git commit -m $name
+done
+
+# Mark the tip as bad and the first commit as good, which checks out the commit in the middle
+git bisect start main HEAD~2
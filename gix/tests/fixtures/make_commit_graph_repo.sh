@@ -0,0 +1,49 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+git init -q
+git checkout -q -b main
+
+mkdir -p dir/sub
+echo a >a
+echo b >dir/b
+echo c >dir/sub/c
+git add .
+git commit -q -m root
+
+git checkout -q -b side
+echo changed >dir/sub/c
+echo new >"dir/ümlaut"
+git add .
+git commit -q -m side
+
+git checkout -q -b other main
+mkdir e
+echo f >e/f
+git add .
+git commit -q -m other
+
+git checkout -q main
+git rm -q a
+echo changed >dir/b
+git commit -q -am main
+
+git checkout -q -b octopus main
+git merge -q -m octopus --no-ff side other >/dev/null
+
+git checkout -q -b empty octopus
+git commit -q --allow-empty -m empty
+
+git checkout -q -b many empty
+mkdir many
+for i in $(seq 600); do
+  echo $i >many/$i
+done
+git add .
+git commit -q -m many
+
+git tag -a -m "annotated" annotated side
+git tag tree-tag "main^{tree}"
+
+git -c commitGraph.generationVersion=1 commit-graph write --no-progress --reachable --changed-paths
+mv .git/objects/info/commit-graph .git/commit-graph-by-git
@@ -0,0 +1,11 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+git init -q
+
+seq 1000 > file
+git add file && git commit -q -m c1
+echo 1001 >> file
+git commit -q -am c2
+
+git repack -adq
@@ -0,0 +1,20 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+git init -q
+
+mkdir dir
+printf 'hello world\nfoo\nsay Hello\n' >a.txt
+printf 'nothing\n  hello there\n' >dir/b.txt
+printf 'hello\0binary\n' >binary.dat
+printf 'hello text\n' >forced-binary.txt
+printf 'hello\0text\n' >forced-text.dat
+printf 'forced-binary.txt -diff\nforced-text.dat diff\n' >.gitattributes
+ln -s hello link
+
+git add .
+git commit -q -m "initial"
+
+printf 'hello staged\n' >staged.txt
+git add staged.txt
+echo 'hello worktree' >>a.txt
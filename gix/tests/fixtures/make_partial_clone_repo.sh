@@ -0,0 +1,23 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+git init -q server
+(cd server
+  git config uploadpack.allowFilter true
+  echo a > a
+  echo b > b
+  git add . && git commit -q -m "initial"
+)
+
+git clone -q --no-checkout --filter=blob:none "file://$PWD/server" partial
+git clone -q --no-checkout --filter=blob:none "file://$PWD/server" partial-without-promisor
+(cd partial-without-promisor
+  git config --unset extensions.partialClone || :
+  git config --unset remote.origin.promisor
+  git config --unset remote.origin.partialCloneFilter
+)
+
+(cd server
+  echo c > c
+  git add c && git commit -q -m "add c"
+)
@@ -0,0 +1,20 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+git init -q
+
+git checkout -q -b main
+git commit -q --allow-empty -m c1
+git tag c1
+git commit -q --allow-empty -m c2
+git tag -a -m "annotated" annotated-c2
+
+git checkout -q -b other c1
+git commit -q --allow-empty -m c3
+
+git checkout -q main
+echo content | git hash-object -w --stdin >/dev/null
+
+git remote add origin .
+git update-ref refs/remotes/origin/main c1
+git commit-graph write --no-progress --reachable
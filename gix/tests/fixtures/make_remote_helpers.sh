@@ -0,0 +1,94 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+git init -q src
+(cd src
+  git commit -q --allow-empty -m c1
+  git tag v1
+  git commit -q --allow-empty -m c2
+  git branch feature
+)
+
+mkdir bin
+
+# A helper which fetches and pushes objects into and from the repository in `GIT_DIR` itself.
+cat <<'HELPER' > bin/git-remote-testfetch
+#!/bin/sh
+src=${2#*://}
+while read -r cmd arg; do
+  case "$cmd" in
+    capabilities)
+      printf 'fetch\npush\n\n'
+      ;;
+    list)
+      git --git-dir="$src/.git" for-each-ref --format='%(objectname) %(refname)' refs/heads refs/tags
+      echo "@$(git --git-dir="$src/.git" symbolic-ref HEAD) HEAD"
+      echo
+      ;;
+    fetch)
+      while read -r line && test -n "$line"; do :; done
+      echo | git --git-dir="$src/.git" pack-objects --revs --all --stdout 2>/dev/null | git index-pack --stdin >/dev/null
+      echo
+      ;;
+    push)
+      specs=$arg
+      while read -r line spec && test -n "$line"; do specs="$specs $spec"; done
+      for spec in $specs; do
+        git push -q "$src" "$spec" >&2 && echo "ok ${spec#*:}" || echo "error ${spec#*:} push failed"
+      done
+      echo
+      ;;
+    *)
+      exit 0
+      ;;
+  esac
+done
+HELPER
+
+# A helper which produces a fast-import stream, importing into a private namespace.
+cat <<'HELPER' > bin/git-remote-testimport
+#!/bin/sh
+src=${2#*://}
+while read -r cmd arg; do
+  case "$cmd" in
+    capabilities)
+      printf 'import\nrefspec refs/heads/*:refs/testimport/heads/*\n\n'
+      ;;
+    list)
+      git --git-dir="$src/.git" for-each-ref --format='? %(refname)' refs/heads
+      echo "@$(git --git-dir="$src/.git" symbolic-ref HEAD) HEAD"
+      echo
+      ;;
+    import)
+      names=$arg
+      while read -r line name && test -n "$line"; do names="$names $name"; done
+      git --git-dir="$src/.git" fast-export --use-done-feature --refspec 'refs/heads/*:refs/testimport/heads/*' $names
+      ;;
+    *)
+      exit 0
+      ;;
+  esac
+done
+HELPER
+
+# A helper which connects to `git` services directly.
+cat <<'HELPER' > bin/git-remote-testconnect
+#!/bin/sh
+src=${2#*://}
+while read -r cmd arg; do
+  case "$cmd" in
+    capabilities)
+      printf 'connect\n\n'
+      ;;
+    connect)
+      echo
+      exec git "${arg#git-}" "$src"
+      ;;
+    *)
+      exit 0
+      ;;
+  esac
+done
+HELPER
+
+chmod +x bin/*
@@ -0,0 +1,34 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+git init -q
+git checkout -b main
+
+printf '1\n2\n3\n' > file
+git add file && git commit -q -m base
+
+git checkout -q -b feature
+printf 'one\n2\n3\n' > file
+git commit -q -am "change first line"
+echo new > new
+git add new && git commit -q -m "add new file"
+git commit -q --allow-empty -m "empty"
+
+git checkout -q -b conflicting main
+printf '1\n2\nthree\n' > file
+git commit -q -am "change last line differently"
+
+git checkout -q -b merge main
+git merge -q --no-ff -m "merge feature" feature
+
+git checkout -q main
+printf '1\n2\nTHREE\n' > file
+git commit -q -am "change last line"
+
+git checkout -q -b applied main~1
+printf '1\n2\nTHREE\n' > file
+git commit -q -am "change last line as well"
+echo other > other
+git add other && git commit -q -m "add other file"
+
+git checkout -q main
@@ -0,0 +1,16 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+git init -q
+
+mkdir -p a/b/c d/e x
+echo root >root-file
+echo a >a/file
+echo b >a/b/file
+echo c >a/b/c/file
+echo d >d/file
+echo e >d/e/file
+echo x >x/file
+
+git add .
+git commit -q -m "initial"
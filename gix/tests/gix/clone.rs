@@ -504,6 +504,82 @@ mod blocking_io {
         assure_index_entries_on_disk(&index, repo.work_dir().expect("non-bare"));
         Ok(())
     }
+    #[test]
+    fn fetch_and_checkout_resumes_after_interruption() -> crate::Result {
+        let tmp = gix_testtools::tempfile::TempDir::new()?;
+        let mut prepare = gix::clone::PrepareFetch::new(
+            remote::repo("base").path(),
+            tmp.path(),
+            gix::create::Kind::WithWorktree,
+            Default::default(),
+            restricted(),
+        )?;
+        let (mut checkout, _out) = prepare.fetch_then_checkout(gix::progress::Discard, &AtomicBool::default())?;
+        let err = checkout
+            .main_worktree(gix::progress::Discard, &AtomicBool::new(true))
+            .unwrap_err();
+        assert!(
+            matches!(err, gix::clone::checkout::main_worktree::Error::Interrupted),
+            "{err:?}"
+        );
+        assert!(
+            tmp.path().join(".git").is_dir(),
+            "the repository is retained to be able to resume"
+        );
+
+        let (repo, _) = checkout.main_worktree(gix::progress::Discard, &AtomicBool::default())?;
+        let index = repo.index()?;
+        assert_eq!(index.entries().len(), 1, "All entries are known as per HEAD tree");
+        assert!(
+            index
+                .entries()
+                .iter()
+                .all(|e| !e.flags.contains(gix::index::entry::Flags::SKIP_WORKTREE)),
+            "entries skipped while resuming are restored"
+        );
+        assure_index_entries_on_disk(&index, repo.work_dir().expect("non-bare"));
+        Ok(())
+    }
+
+    #[test]
+    fn fetch_only_with_filter() -> crate::Result {
+        let fixture = gix_testtools::scripted_fixture_read_only("make_partial_clone_repo.sh")?;
+        let tmp = gix_testtools::tempfile::TempDir::new()?;
+        let mut prepare = gix::clone::PrepareFetch::new(
+            fixture.join("server"),
+            tmp.path(),
+            gix::create::Kind::Bare,
+            Default::default(),
+            restricted(),
+        )?
+        .with_filter("blob:none");
+        let (repo, _out) = prepare.fetch_only(gix::progress::Discard, &AtomicBool::default())?;
+
+        let tree = repo.head_commit()?.tree()?;
+        assert_eq!(tree.iter().count(), 3, "trees are received");
+        for entry in tree.iter() {
+            assert!(!repo.has_object(entry?.oid()), "blobs were filtered");
+        }
+
+        let repo = gix::open_opts(repo.path(), restricted())?;
+        let config = repo.config_snapshot();
+        assert_eq!(config.boolean("remote.origin.promisor"), Some(true));
+        assert_eq!(
+            config.string("remote.origin.partialCloneFilter").expect("set").as_ref(),
+            "blob:none"
+        );
+        assert_eq!(
+            config.string("extensions.partialClone").expect("set").as_ref(),
+            "origin"
+        );
+        assert_eq!(
+            config.integer("core.repositoryFormatVersion"),
+            Some(1),
+            "extensions are enabled"
+        );
+        Ok(())
+    }
+
     #[test]
     fn fetch_and_checkout_specific_ref() -> crate::Result {
         let tmp = gix_testtools::tempfile::TempDir::new()?;
@@ -552,6 +628,110 @@ mod blocking_io {
         Ok(())
     }
 
+    #[test]
+    fn fetch_and_checkout_single_branch() -> crate::Result {
+        for (ref_name, expected_branch) in [(None, "main"), (Some("a"), "a")] {
+            let tmp = gix_testtools::tempfile::TempDir::new()?;
+            let mut prepare = gix::clone::PrepareFetch::new(
+                remote::repo("base").path(),
+                tmp.path(),
+                gix::create::Kind::WithWorktree,
+                Default::default(),
+                restricted(),
+            )?
+            .with_ref_name(ref_name)?
+            .with_single_branch(true);
+            let (mut checkout, _out) =
+                prepare.fetch_then_checkout(gix::progress::Discard, &std::sync::atomic::AtomicBool::default())?;
+            let (repo, _) =
+                checkout.main_worktree(gix::progress::Discard, &std::sync::atomic::AtomicBool::default())?;
+
+            let remote = repo.find_remote("origin")?;
+            let specs: Vec<_> = remote
+                .refspecs(Direction::Fetch)
+                .iter()
+                .map(|spec| spec.to_ref().to_bstring())
+                .collect();
+            assert_eq!(
+                specs,
+                [format!(
+                    "+refs/heads/{expected_branch}:refs/remotes/origin/{expected_branch}"
+                )],
+                "the remote is configured to only fetch the chosen branch"
+            );
+
+            let remote_branches: Vec<_> = repo
+                .references()?
+                .remote_branches()?
+                .map(|r| r.expect("valid").name().as_bstr().to_owned())
+                .filter(|name| name != "refs/remotes/origin/HEAD")
+                .collect();
+            assert_eq!(
+                remote_branches,
+                [format!("refs/remotes/origin/{expected_branch}")],
+                "only the chosen branch was fetched"
+            );
+            assert_eq!(
+                repo.head_ref()?.expect("head points to ref").name().as_bstr(),
+                format!("refs/heads/{expected_branch}")
+            );
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn fetch_and_checkout_with_submodule_init() -> crate::Result {
+        let tmp = gix_testtools::tempfile::TempDir::new()?;
+        let fixture = gix_testtools::scripted_fixture_read_only("make_submodules.sh")?;
+        let mut prepare = gix::clone::PrepareFetch::new(
+            fixture.join("with-submodules"),
+            tmp.path(),
+            gix::create::Kind::WithWorktree,
+            Default::default(),
+            restricted(),
+        )?;
+        let (checkout, _out) =
+            prepare.fetch_then_checkout(gix::progress::Discard, &std::sync::atomic::AtomicBool::default())?;
+        let (repo, _) = checkout
+            .with_submodule_init(true)
+            .main_worktree(gix::progress::Discard, &std::sync::atomic::AtomicBool::default())?;
+
+        let local_config =
+            gix::config::File::from_path_no_includes(repo.git_dir().join("config"), gix::config::Source::Local)?;
+        let remote_url = repo
+            .find_remote("origin")?
+            .url(Direction::Fetch)
+            .expect("set")
+            .to_bstring();
+        let names: Vec<_> = repo
+            .submodules()?
+            .expect("present")
+            .map(|sm| sm.name().to_owned())
+            .collect();
+        assert!(!names.is_empty(), "the fixture has submodules");
+        for name in names {
+            let url = local_config
+                .string_by("submodule", Some(name.as_ref()), "url")
+                .expect("url was written to the local configuration");
+            assert_eq!(
+                url.as_ref(),
+                remote_url
+                    .rsplit_once_str("/")
+                    .map(|(base, _)| format!("{}/module1", base.as_bstr()))
+                    .expect("remote url has a parent"),
+                "relative urls are resolved against the url of the remote"
+            );
+            assert_eq!(
+                local_config
+                    .boolean_by("submodule", Some(name.as_ref()), "active")
+                    .transpose()?,
+                Some(true),
+                "submodules are marked active as `submodule.active` isn't set"
+            );
+        }
+        Ok(())
+    }
+
     #[test]
     fn fetch_and_checkout_specific_non_existing() -> crate::Result {
         let tmp = gix_testtools::tempfile::TempDir::new()?;
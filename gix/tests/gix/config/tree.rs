@@ -203,6 +203,57 @@ mod push {
     }
 }
 
+mod trailer {
+    use crate::config::tree::bcow;
+    use gix::config::tree::{Key, Trailer};
+    use gix::objs::commit::message::trailer::{IfExists, IfMissing, Placement};
+
+    #[test]
+    fn where_() -> crate::Result {
+        for (actual, expected) in [
+            ("end", Placement::End),
+            ("start", Placement::Start),
+            ("after", Placement::After),
+            ("Before", Placement::Before),
+        ] {
+            assert_eq!(Trailer::WHERE.try_into_placement(bcow(actual))?, expected);
+        }
+        assert_eq!(
+            Trailer::WHERE
+                .try_into_placement(bcow("middle"))
+                .unwrap_err()
+                .to_string(),
+            "The key \"trailer.where=middle\" was invalid"
+        );
+        assert!(Trailer::WHERE.validate("middle".into()).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn if_exists() -> crate::Result {
+        for (actual, expected) in [
+            ("addIfDifferentNeighbor", IfExists::AddIfDifferentNeighbor),
+            ("addIfDifferent", IfExists::AddIfDifferent),
+            ("add", IfExists::Add),
+            ("replace", IfExists::Replace),
+            ("doNothing", IfExists::DoNothing),
+        ] {
+            assert_eq!(Trailer::IF_EXISTS.try_into_if_exists(bcow(actual))?, expected);
+        }
+        assert!(Trailer::IF_EXISTS.validate("remove".into()).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn if_missing() -> crate::Result {
+        for (actual, expected) in [("add", IfMissing::Add), ("doNothing", IfMissing::DoNothing)] {
+            assert_eq!(Trailer::IF_MISSING.try_into_if_missing(bcow(actual))?, expected);
+        }
+        assert!(Trailer::IF_MISSING.validate("replace".into()).is_err());
+        Ok(())
+    }
+}
+
 mod fetch {
 
     #[test]
@@ -390,6 +441,25 @@ mod merge {
         );
         Ok(())
     }
+
+    #[test]
+    fn directory_renames() -> crate::Result {
+        for (actual, expected) in [("true", true), ("conflict", true), ("false", false), ("no", false)] {
+            assert_eq!(
+                Merge::DIRECTORY_RENAMES.try_into_directory_renames(bcow(actual))?,
+                expected
+            );
+            assert!(Merge::DIRECTORY_RENAMES.validate(actual.into()).is_ok());
+        }
+        assert_eq!(
+            Merge::DIRECTORY_RENAMES
+                .try_into_directory_renames(bcow("foo"))
+                .unwrap_err()
+                .to_string(),
+            "The key \"merge.directoryRenames=foo\" was invalid"
+        );
+        Ok(())
+    }
 }
 
 mod core {
@@ -460,6 +530,58 @@ mod core {
         Ok(())
     }
 
+    #[test]
+    fn fsync() -> crate::Result {
+        use gix::config::fsync::Components;
+        assert_eq!(Core::FSYNC.try_into_components(bcow(""))?, Components::default());
+        assert_eq!(Core::FSYNC.try_into_components(bcow("none"))?, Components::NONE);
+        assert_eq!(
+            Core::FSYNC.try_into_components(bcow("-pack, index"))?,
+            Components {
+                packs: false,
+                index: true,
+                ..Components::default()
+            }
+        );
+        assert_eq!(
+            Core::FSYNC.try_into_components(bcow("none,reference,loose-object"))?,
+            Components {
+                references: true,
+                loose_objects: true,
+                ..Components::NONE
+            }
+        );
+        assert_eq!(
+            Core::FSYNC.try_into_components(bcow("-derived-metadata,loose-object"))?,
+            Components {
+                loose_objects: true,
+                packs: true,
+                ..Components::NONE
+            }
+        );
+        assert!(Core::FSYNC.validate("committed".into()).is_ok());
+        assert_eq!(
+            Core::FSYNC
+                .try_into_components(bcow("index,bogus"))
+                .unwrap_err()
+                .to_string(),
+            "The key \"core.fsync=index,bogus\" was invalid"
+        );
+        assert!(Core::FSYNC.validate("bogus".into()).is_err());
+
+        use gix::features::fs::Fsync;
+        for (value, expected) in [
+            ("fsync", Fsync::PerFile),
+            ("writeout-only", Fsync::WriteoutOnly),
+            ("batch", Fsync::Batch),
+        ] {
+            assert_eq!(Core::FSYNC_METHOD.try_into_fsync_method(bcow(value))?, expected);
+            assert!(Core::FSYNC_METHOD.validate(value.into()).is_ok());
+        }
+        assert!(Core::FSYNC_METHOD.validate("bogus".into()).is_err());
+        Ok(())
+    }
+
     #[test]
     fn log_all_ref_updates() -> crate::Result {
         assert_eq!(
@@ -469,7 +591,8 @@ mod core {
         assert!(Core::LOG_ALL_REF_UPDATES.validate("true".into()).is_ok());
         assert_eq!(
             Core::LOG_ALL_REF_UPDATES.try_into_ref_updates(Some(Ok(false)),)?,
-            Some(gix_ref::store::WriteReflog::Disable)
+            Some(gix_ref::store::WriteReflog::AppendToExisting),
+            "like `git`, existing reflogs are still written to"
         );
         assert!(Core::LOG_ALL_REF_UPDATES.validate("0".into()).is_ok());
         let boolean = |value| {
@@ -493,6 +616,40 @@ mod core {
         Ok(())
     }
 
+    #[test]
+    fn untracked_cache() -> crate::Result {
+        use gix::config::untracked_cache::Mode;
+        assert_eq!(Core::UNTRACKED_CACHE.try_into_untracked_cache(None)?, Mode::Keep);
+        assert_eq!(
+            Core::UNTRACKED_CACHE.try_into_untracked_cache(Some(Ok(true)))?,
+            Mode::Enabled
+        );
+        assert_eq!(
+            Core::UNTRACKED_CACHE.try_into_untracked_cache(Some(Ok(false)))?,
+            Mode::Disabled
+        );
+        assert!(Core::UNTRACKED_CACHE.validate("false".into()).is_ok());
+        let boolean = |value| {
+            gix_config::Boolean::try_from(bcow(value))
+                .map(|b| Some(b.0))
+                .transpose()
+        };
+        assert_eq!(
+            Core::UNTRACKED_CACHE.try_into_untracked_cache(boolean("keep"))?,
+            Mode::Keep
+        );
+        assert!(Core::UNTRACKED_CACHE.validate("keep".into()).is_ok());
+        assert_eq!(
+            Core::UNTRACKED_CACHE
+                .try_into_untracked_cache(boolean("invalid"))
+                .unwrap_err()
+                .to_string(),
+            "The key \"core.untrackedCache=invalid\" was invalid"
+        );
+        assert!(Core::UNTRACKED_CACHE.validate("invalid".into()).is_err());
+        Ok(())
+    }
+
     #[test]
     fn abbrev() -> crate::Result {
         let object_hash = gix_hash::Kind::Sha1;
@@ -705,6 +862,28 @@ mod extensions {
         assert!(Extensions::OBJECT_FORMAT.validate("invalid".into()).is_err());
         Ok(())
     }
+
+    #[test]
+    fn ref_storage() -> crate::Result {
+        assert_eq!(
+            Extensions::REF_STORAGE.try_into_ref_format(bcow("files"))?,
+            gix_ref::store::Format::Files
+        );
+        assert_eq!(
+            Extensions::REF_STORAGE.try_into_ref_format(bcow("reftable"))?,
+            gix_ref::store::Format::Reftable
+        );
+        assert_eq!(
+            Extensions::REF_STORAGE
+                .try_into_ref_format(bcow("Files"))
+                .unwrap_err()
+                .to_string(),
+            "The key \"extensions.refStorage=Files\" was invalid"
+        );
+        assert!(Extensions::REF_STORAGE.validate("reftable".into()).is_ok());
+        assert!(Extensions::REF_STORAGE.validate("invalid".into()).is_err());
+        Ok(())
+    }
 }
 
 mod checkout {
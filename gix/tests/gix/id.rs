@@ -20,6 +20,14 @@ fn prefix() -> crate::Result {
     assert_eq!(prefix.cmp_oid(&id), Ordering::Equal);
     assert_eq!(prefix.hex_len(), 7, "preconfigured via core.abbrev default value");
 
+    assert_eq!(id.shorten_to(10)?.hex_len(), 10, "the desired length is used as is");
+    assert_eq!(
+        id.shorten_to(1)?.hex_len(),
+        gix_hash::Prefix::MIN_HEX_LEN,
+        "lengths are clamped to what's valid"
+    );
+    assert_eq!(id.shorten_to(100)?.hex_len(), 40);
+
     let repo = gix::open_opts(
         work_dir,
         gix::open::Options::isolated().config_overrides(Core::ABBREV.validated_assignment("5".into())),
@@ -55,6 +63,39 @@ fn prefix() -> crate::Result {
     Ok(())
 }
 
+#[test]
+fn shorten_ids() -> crate::Result {
+    let repo = crate::repo("make_repo_with_fork_and_dates.sh")?.to_thread_local();
+    let ids = repo
+        .head_id()?
+        .ancestors()
+        .all()?
+        .map(|info| info.map(|info| info.id))
+        .collect::<Result<Vec<_>, _>>()?;
+    let null = gix_hash::ObjectId::null(repo.object_hash());
+
+    let prefixes = repo.shorten_ids(ids.iter().chain(Some(&null)))?;
+    assert_eq!(prefixes.len(), ids.len() + 1);
+    for (id, prefix) in ids.iter().zip(&prefixes) {
+        assert_eq!(*prefix, id.attach(&repo).shorten()?, "the same as one by one");
+    }
+    assert_eq!(
+        prefixes.last().map(ToString::to_string),
+        Some("0000000".into()),
+        "ids that don't exist are shortened as well"
+    );
+
+    let repo = gix::open_opts(
+        repo.work_dir().expect("non-bare"),
+        gix::open::Options::isolated().config_overrides(Core::ABBREV.validated_assignment("5".into())),
+    )?;
+    assert!(
+        repo.shorten_ids(&ids)?.iter().all(|prefix| prefix.hex_len() == 5),
+        "preconfigured via core.abbrev"
+    );
+    Ok(())
+}
+
 #[test]
 fn display_and_debug() -> crate::Result {
     let repo = crate::basic_repo()?;
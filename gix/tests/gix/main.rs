@@ -11,6 +11,7 @@ mod head;
 mod id;
 mod init;
 mod object;
+mod push;
 mod reference;
 mod remote;
 mod repository;
@@ -0,0 +1,105 @@
+use gix::bstr::ByteSlice;
+use gix::object::batch::{Format, Mode, Options, Outcome};
+
+use crate::util::basic_repo;
+
+#[test]
+fn format_parse() -> crate::Result {
+    assert_eq!(
+        Format::parse("%(objectname) %(objecttype) %(objectsize)".into())?,
+        Format::default()
+    );
+    assert!(Format::parse("%(objectsize:disk)%%%00".into())?.uses(gix::object::batch::Atom::ObjectSizeDisk));
+    assert_eq!(
+        Format::parse("%%%00%".into())?,
+        Format::parse("%%%%00%%".into())?,
+        "like in `git`, only `%%` is an escape, and other `%` are kept"
+    );
+    assert!(matches!(
+        Format::parse("%(objectname".into()),
+        Err(gix::object::batch::format::Error::Unterminated { .. })
+    ));
+    assert!(matches!(
+        Format::parse("%(deltabase)".into()),
+        Err(gix::object::batch::format::Error::UnknownAtom { .. })
+    ));
+    Ok(())
+}
+
+#[test]
+fn check_and_contents() -> crate::Result {
+    let repo = basic_repo()?;
+    let blob = repo.rev_parse_single("HEAD:this")?;
+    let input = b"HEAD:this first rest\nmissing-object\n";
+
+    let mut out = Vec::new();
+    let outcome = repo.object_batch(
+        &input[..],
+        &mut out,
+        &Options {
+            format: Format::parse("%(objecttype) %(objectsize) %(rest)".into())?,
+            mode: Mode::Check,
+            buffer: true,
+        },
+    )?;
+    assert_eq!(
+        outcome,
+        Outcome {
+            found: 1,
+            missing: 1,
+            ambiguous: 0
+        }
+    );
+    assert_eq!(out, b"blob 6 first rest\nmissing-object missing\n");
+
+    let mut out = Vec::new();
+    repo.object_batch(
+        &b"HEAD:this"[..],
+        &mut out,
+        &Options {
+            mode: Mode::Contents,
+            ..Default::default()
+        },
+    )?;
+    assert_eq!(out, format!("{blob} blob 6\nhello\n\n").as_bytes());
+
+    let mut out = Vec::new();
+    repo.object_batch(
+        &b"HEAD:this"[..],
+        &mut out,
+        &Options {
+            format: Format::parse("%(objectsize:disk)".into())?,
+            ..Default::default()
+        },
+    )?;
+    let disk_size: u64 = std::str::from_utf8(&out)?.trim_end().parse()?;
+    assert!(disk_size > 0, "the loose object file size is used");
+    Ok(())
+}
+
+#[test]
+fn ambiguous_names_are_reported_as_such() -> crate::Result {
+    let repo = gix::open(
+        gix_testtools::scripted_fixture_read_only("make_rev_spec_parse_repos.sh")?.join("ambiguous_blob_tree_commit"),
+    )?;
+    let mut out = Vec::new();
+    let outcome = repo.object_batch(
+        &b"0000000000\n0000000000e4f\n0000000000z\n"[..],
+        &mut out,
+        &Default::default(),
+    )?;
+    assert_eq!(
+        outcome,
+        Outcome {
+            found: 1,
+            missing: 1,
+            ambiguous: 1
+        }
+    );
+    assert_eq!(
+        out.as_bstr(),
+        "0000000000 ambiguous\n0000000000e4f9fbd19cf1e932319e5ad0d1d00b commit 173\n0000000000z missing\n",
+        "just like `git cat-file --batch-check`"
+    );
+    Ok(())
+}
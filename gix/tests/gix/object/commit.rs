@@ -43,3 +43,85 @@ fn decode() -> crate::Result {
     assert_eq!(commit.decode()?.message, "c2\n");
     Ok(())
 }
+
+#[test]
+fn diff_to_parents() -> crate::Result {
+    use gix::commit::diff_to_parents::Merges;
+    use gix::object::tree::EntryKind;
+
+    let tmp = gix_testtools::tempfile::tempdir()?;
+    let repo = gix::ThreadSafeRepository::init_opts(
+        &tmp,
+        gix::create::Kind::Bare,
+        Default::default(),
+        crate::util::restricted_and_git(),
+    )?
+    .to_thread_local();
+
+    let tree_with = |files: &[(&str, &str)]| -> crate::Result<gix::ObjectId> {
+        let mut editor = repo.edit_tree(gix::ObjectId::empty_tree(repo.object_hash()))?;
+        for (path, content) in files {
+            editor.upsert(*path, EntryKind::Blob, repo.write_blob(content)?)?;
+        }
+        Ok(editor.write()?.detach())
+    };
+    let base_tree = tree_with(&[("a", "a"), ("b", "b")])?;
+    let ours_tree = tree_with(&[("a", "ours"), ("b", "b")])?;
+    let theirs_tree = tree_with(&[("a", "theirs"), ("b", "theirs")])?;
+    let merged_tree = tree_with(&[("a", "merged"), ("b", "theirs"), ("c", "new")])?;
+
+    let base = repo.commit("refs/heads/base", "base", base_tree, gix::commit::NO_PARENT_IDS)?;
+    let ours = repo.commit("refs/heads/ours", "ours", ours_tree, Some(base))?;
+    let theirs = repo.commit("refs/heads/theirs", "theirs", theirs_tree, Some(base))?;
+    let merge = repo.commit("refs/heads/merge", "merge", merged_tree, [ours, theirs])?;
+
+    let locations = |changes: &[gix::commit::diff_to_parents::Changes]| -> Vec<(Option<gix::ObjectId>, Vec<String>)> {
+        changes
+            .iter()
+            .map(|changes| {
+                (
+                    changes.parent_id,
+                    changes
+                        .changes
+                        .iter()
+                        .map(|change| change.location().to_string())
+                        .collect(),
+                )
+            })
+            .collect()
+    };
+
+    let root = base.object()?.into_commit();
+    assert_eq!(
+        locations(&root.diff_to_parents(Merges::Off)?),
+        [(None, vec!["a".to_string(), "b".into()])],
+        "root commits are diffed against the empty tree, whatever the merge mode"
+    );
+    assert_eq!(
+        locations(&ours.object()?.into_commit().diff_to_parents(Merges::Combined)?),
+        [(Some(base.detach()), vec!["a".to_string()])]
+    );
+
+    let merge = merge.object()?.into_commit();
+    assert_eq!(
+        locations(&merge.diff_to_parents(Merges::Separate)?),
+        [
+            (Some(ours.detach()), vec!["a".to_string(), "b".into(), "c".into()]),
+            (Some(theirs.detach()), vec!["a".to_string(), "c".into()]),
+        ]
+    );
+    assert_eq!(
+        locations(&merge.diff_to_parents(Merges::Combined)?),
+        [
+            (Some(ours.detach()), vec!["a".to_string(), "c".into()]),
+            (Some(theirs.detach()), vec!["a".to_string(), "c".into()]),
+        ],
+        "only paths that differ from all parents are kept"
+    );
+    assert_eq!(
+        locations(&merge.diff_to_parents(Merges::FirstParent)?),
+        [(Some(ours.detach()), vec!["a".to_string(), "b".into(), "c".into()])]
+    );
+    assert!(merge.diff_to_parents(Merges::Off)?.is_empty());
+    Ok(())
+}
@@ -114,6 +114,135 @@ fn changes_against_tree_modified() -> crate::Result {
     Ok(())
 }
 
+#[test]
+fn summary_of_modification_and_modified_rename() -> crate::Result {
+    let repo = named_repo("make_diff_repo.sh")?;
+    let from = tree_named(&repo, "@^{/r3-simple}~1");
+    let to = tree_named(&repo, ":/r3-simple");
+    let changes = repo.diff_tree_to_tree(
+        &from,
+        &to,
+        gix::diff::Options::default()
+            .with_rewrites(Some(Default::default()))
+            .track_path()
+            .to_owned(),
+    )?;
+    let mut cache = repo.diff_resource_cache_for_tree_diff()?;
+    let summary = gix::object::tree::diff::Summary::from_changes(&repo, &changes, &mut cache)?;
+
+    assert_eq!(summary.files.len(), 2, "the modified directory isn't listed");
+    let rewrite = summary.files[1].rewrite.as_ref().expect("a rename");
+    assert_eq!(rewrite.source_location, "dir/c");
+    assert!(!rewrite.copy);
+    assert_eq!(
+        summary.totals(),
+        gix::object::tree::diff::Stats {
+            lines_added: 2,
+            lines_removed: 0,
+            files_changed: 2,
+        }
+    );
+
+    let mut out = Vec::new();
+    summary.write_numstat(&mut out)?;
+    assert_eq!(out.as_bstr(), "1\t0\tb\n1\t0\tdir/{c => c-moved}\n");
+
+    out.clear();
+    summary.write_stat(&mut out, 80)?;
+    assert_eq!(
+        out.as_bstr(),
+        " b                  | 1 +\n dir/{c => c-moved} | 1 +\n 2 files changed, 2 insertions(+)\n",
+        "this is exactly what `git diff --stat` prints"
+    );
+
+    out.clear();
+    summary.write_shortstat(&mut out)?;
+    assert_eq!(out.as_bstr(), " 2 files changed, 2 insertions(+)\n");
+    Ok(())
+}
+
+#[test]
+fn summary_of_submodules_type_changes_and_modes() -> crate::Result {
+    let repo = named_repo("make_diff_repo.sh")?.with_object_memory();
+    let hi = repo.write_blob("hi\n")?.detach();
+    let x = repo.write_blob("x\n")?.detach();
+    let y = repo.write_blob("y\n")?.detach();
+    let old_commit = repo.rev_parse_single(":/c1")?.detach();
+    let new_commit = repo.head_id()?.detach();
+
+    let empty_tree = gix::ObjectId::empty_tree(repo.object_hash());
+    let mut editor = repo.edit_tree(empty_tree)?;
+    editor
+        .upsert("exe", EntryKind::Blob, hi)?
+        .upsert("g", EntryKind::Blob, x)?
+        .upsert("removed-sub", EntryKind::Commit, old_commit)?
+        .upsert("sub", EntryKind::Commit, old_commit)?;
+    let from = editor.write()?.object()?.into_tree();
+    editor
+        .upsert("exe", EntryKind::BlobExecutable, hi)?
+        .upsert("g/h", EntryKind::Blob, y)?
+        .upsert("new", EntryKind::Blob, hi)?
+        .remove("removed-sub")?
+        .upsert("sub", EntryKind::Commit, new_commit)?;
+    let to = editor.write()?.object()?.into_tree();
+
+    let changes = repo.diff_tree_to_tree(&from, &to, gix::diff::Options::default().with_rewrites(None))?;
+    let mut cache = repo.diff_resource_cache_for_tree_diff()?;
+    let summary = gix::object::tree::diff::Summary::from_changes(&repo, &changes, &mut cache)?;
+
+    let mut out = Vec::new();
+    summary.write_numstat(&mut out)?;
+    assert_eq!(
+        out.as_bstr(),
+        "0\t0\texe\n0\t1\tg\n1\t0\tg/h\n1\t0\tnew\n0\t1\tremoved-sub\n1\t1\tsub\n",
+        "submodules count as one line, and files replaced by trees are deleted, just like `git diff --numstat` shows it"
+    );
+
+    out.clear();
+    summary.write_summary(&mut out)?;
+    assert_eq!(
+        out.as_bstr(),
+        " mode change 100644 => 100755 exe\n delete mode 100644 g\n create mode 100644 g/h\n create mode 100644 new\n delete mode 160000 removed-sub\n",
+        "this is exactly what `git diff --summary` prints"
+    );
+    Ok(())
+}
+
+#[test]
+fn summary_shows_similarity_of_renames_and_copies() -> crate::Result {
+    let repo = named_repo("make_diff_repo.sh")?;
+    let from = tree_named(&repo, "@^{/r3-simple}~1");
+    let to = tree_named(&repo, ":/r3-simple");
+    let changes = repo.diff_tree_to_tree(
+        &from,
+        &to,
+        gix::diff::Options::default()
+            .with_rewrites(Some(Default::default()))
+            .track_path()
+            .to_owned(),
+    )?;
+    let mut cache = repo.diff_resource_cache_for_tree_diff()?;
+    let summary = gix::object::tree::diff::Summary::from_changes(&repo, &changes, &mut cache)?;
+
+    let rewrite = summary.files[1].rewrite.as_ref().expect("a rename");
+    assert_eq!(rewrite.similarity_percent(), 65);
+    let mut out = Vec::new();
+    summary.write_summary(&mut out)?;
+    assert_eq!(
+        out.as_bstr(),
+        " rename dir/{c => c-moved} (65%)\n",
+        "modifications aren't listed, and this is exactly what `git diff --summary` prints"
+    );
+
+    let identical = gix::object::tree::diff::summary::Rewrite {
+        source_location: "a".into(),
+        similarity: 1.0,
+        copy: true,
+    };
+    assert_eq!(identical.similarity_percent(), 100);
+    Ok(())
+}
+
 mod track_rewrites {
     use std::collections::HashMap;
     use std::convert::Infallible;
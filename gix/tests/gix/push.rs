@@ -0,0 +1,188 @@
+mod lease {
+    use gix::push::lease::Spec;
+
+    fn name(name: &str) -> gix_ref::FullName {
+        name.try_into().expect("valid")
+    }
+
+    #[test]
+    fn from_bytes() {
+        assert_eq!(
+            Spec::from_bytes("".into()),
+            Spec {
+                name: None,
+                expect: None
+            }
+        );
+        assert_eq!(
+            Spec::from_bytes("main".into()),
+            Spec {
+                name: Some("main".into()),
+                expect: None
+            }
+        );
+        assert_eq!(
+            Spec::from_bytes("main:".into()),
+            Spec {
+                name: Some("main".into()),
+                expect: Some("".into())
+            },
+            "an empty expectation means the reference must not exist"
+        );
+        assert_eq!(
+            Spec::from_bytes("main:HEAD~1".into()),
+            Spec {
+                name: Some("main".into()),
+                expect: Some("HEAD~1".into())
+            }
+        );
+    }
+
+    #[test]
+    fn matches() {
+        let spec = Spec::from_bytes("main".into());
+        for matching in ["refs/heads/main", "refs/tags/main", "refs/remotes/main/HEAD"] {
+            assert!(spec.matches(name(matching).as_ref()), "{matching}");
+        }
+        for non_matching in ["refs/heads/main2", "refs/heads/feature/main"] {
+            assert!(!spec.matches(name(non_matching).as_ref()), "{non_matching}");
+        }
+        assert!(
+            Spec::from_bytes("".into()).matches(name("refs/heads/anything").as_ref()),
+            "without name, all references match"
+        );
+    }
+}
+
+#[cfg(feature = "blocking-network-client")]
+mod check {
+    use gix::push::{lease, RefUpdate, Status};
+    use gix_protocol::handshake::Ref;
+
+    fn id(repo: &gix::Repository, name: &str) -> crate::Result<gix_hash::ObjectId> {
+        Ok(repo.find_reference(name)?.target().id().to_owned())
+    }
+
+    fn update(name: &str, new: Option<gix_hash::ObjectId>) -> RefUpdate {
+        RefUpdate {
+            remote_ref: name.try_into().expect("valid"),
+            new,
+            force: false,
+            lease: None,
+        }
+    }
+
+    fn advertised(name: &str, object: gix_hash::ObjectId) -> Ref {
+        Ref::Direct {
+            full_ref_name: name.into(),
+            object,
+        }
+    }
+
+    #[test]
+    fn fast_forwards_are_allowed_unless_history_would_be_lost() -> crate::Result {
+        for use_commit_graph in [false, true] {
+            let repo = gix::open_opts(
+                gix_testtools::scripted_fixture_read_only("make_push_repo.sh")?,
+                crate::restricted().config_overrides([format!("core.commitGraph={use_commit_graph}")]),
+            )?;
+            fast_forwards(&repo)?;
+        }
+        Ok(())
+    }
+
+    fn fast_forwards(repo: &gix::Repository) -> crate::Result {
+        let remote = repo.find_remote("origin")?;
+        let (c1, c2, c3) = (id(repo, "c1")?, id(repo, "main")?, id(repo, "other")?);
+        let annotated_tag = id(repo, "annotated-c2")?;
+        let blob = gix::hash::ObjectId::from_hex(b"d95f3ad14dee633a758d2e331151e950dd13e4ed")?;
+        let unknown = gix::hash::ObjectId::from_hex(b"aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa")?;
+
+        let remote_refs = [
+            advertised("refs/heads/behind", c1),
+            advertised("refs/heads/ahead", c2),
+            advertised("refs/heads/diverged", c2),
+            advertised("refs/heads/unknown", unknown),
+            advertised("refs/tags/c1", c1),
+        ];
+        let updates = [
+            update("refs/heads/behind", Some(c2)),
+            update("refs/heads/behind", Some(annotated_tag)),
+            update("refs/heads/behind", Some(blob)),
+            update("refs/heads/ahead", Some(c1)),
+            update("refs/heads/ahead", Some(c2)),
+            RefUpdate {
+                force: true,
+                ..update("refs/heads/ahead", Some(c1))
+            },
+            update("refs/heads/diverged", Some(c3)),
+            update("refs/heads/diverged", None),
+            update("refs/heads/unknown", Some(c2)),
+            update("refs/heads/new", Some(c3)),
+            update("refs/tags/c1", Some(c2)),
+        ];
+        let statuses = repo.push_check(&remote, &updates, &remote_refs)?;
+        assert_eq!(
+            statuses,
+            [
+                Status::Ok,
+                Status::Ok,
+                Status::RejectedNonFastForward,
+                Status::RejectedNonFastForward,
+                Status::UpToDate,
+                Status::Ok,
+                Status::RejectedNonFastForward,
+                Status::Ok,
+                Status::RejectedFetchFirst,
+                Status::Ok,
+                Status::RejectedAlreadyExists,
+            ],
+            "annotated tags are peeled, but other objects are never fast-forwards"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn leases_are_checked_against_remote_tracking_references_or_values() -> crate::Result {
+        let repo = crate::named_repo("make_push_repo.sh")?;
+        let remote = repo.find_remote("origin")?;
+        let (c1, c2, c3) = (id(&repo, "c1")?, id(&repo, "main")?, id(&repo, "other")?);
+
+        let with_lease = |name: &str, new, lease| RefUpdate {
+            lease: Some(lease),
+            ..update(name, new)
+        };
+        let updates = [
+            with_lease("refs/heads/main", Some(c3), lease::Expect::RemoteTracking),
+            with_lease("refs/heads/other", Some(c3), lease::Expect::RemoteTracking),
+            with_lease("refs/heads/other", Some(c3), lease::Expect::Value(Some(c1))),
+            with_lease("refs/heads/other", Some(c3), lease::Expect::Value(None)),
+        ];
+
+        let statuses = repo.push_check(&remote, &updates, &[advertised("refs/heads/main", c1)])?;
+        assert_eq!(
+            statuses,
+            [
+                Status::Ok,
+                Status::Ok,
+                Status::RejectedStale {
+                    expected: Some(c1),
+                    actual: None
+                },
+                Status::Ok,
+            ],
+            "a lease that holds allows non-fast-forwards, and a missing remote-tracking branch expects the reference not to exist"
+        );
+
+        let statuses = repo.push_check(&remote, &updates[..1], &[advertised("refs/heads/main", c2)])?;
+        assert_eq!(
+            statuses,
+            [Status::RejectedStale {
+                expected: Some(c1),
+                actual: Some(c2)
+            }],
+            "the remote changed since we last fetched"
+        );
+        Ok(())
+    }
+}
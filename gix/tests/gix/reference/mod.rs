@@ -18,6 +18,73 @@ mod log {
             "rebase (merge): the subject"
         );
     }
+
+    #[test]
+    fn query() -> crate::Result {
+        use gix::reference::log::{Filter, Order, Query};
+
+        let (repo, _tmp) = crate::util::basic_rw_repo()?;
+        let main = repo.find_reference("main")?;
+        let mut platform = main.log_iter();
+        let messages = |platform: &mut gix::refs::file::log::iter::Platform<'_, '_>,
+                        filter: &Filter,
+                        order: Order|
+         -> crate::Result<Vec<String>> {
+            Ok(Query::new(platform, filter, order)?
+                .expect("log exists")
+                .map(|line| line.map(|line| line.message.to_string()))
+                .collect::<Result<_, _>>()?)
+        };
+
+        let all = Filter::default();
+        assert_eq!(
+            messages(&mut platform, &all, Order::NewestFirst)?,
+            ["commit: c2", "commit (initial): c1"]
+        );
+        assert_eq!(
+            messages(&mut platform, &all, Order::OldestFirst)?,
+            ["commit (initial): c1", "commit: c2"]
+        );
+
+        let c2 = main.id().detach();
+        for order in [Order::OldestFirst, Order::NewestFirst] {
+            assert_eq!(
+                messages(
+                    &mut platform,
+                    &Filter {
+                        new_oid: Some(c2),
+                        ..Default::default()
+                    },
+                    order
+                )?,
+                ["commit: c2"]
+            );
+            assert_eq!(
+                messages(
+                    &mut platform,
+                    &Filter {
+                        message: Some("initial".into()),
+                        ..Default::default()
+                    },
+                    order
+                )?,
+                ["commit (initial): c1"]
+            );
+            assert_eq!(
+                messages(
+                    &mut platform,
+                    &Filter {
+                        until: Some(0),
+                        ..Default::default()
+                    },
+                    order
+                )?,
+                Vec::<String>::new(),
+                "all entries are more recent"
+            );
+        }
+        Ok(())
+    }
 }
 
 #[test]
@@ -468,6 +468,126 @@ mod blocking_and_async_io {
         Ok(())
     }
 
+    #[maybe_async::test(
+        feature = "blocking-network-client",
+        async(feature = "async-network-client-async-std", async_std::test)
+    )]
+    async fn fetch_pack_with_quarantine() -> crate::Result {
+        let daemon = spawn_git_daemon_if_async(repo_path("clone-as-base-with-changes"))?;
+        let (repo, _tmp) = repo_rw("two-origins");
+        let remote = into_daemon_remote_if_async(
+            repo.find_remote("changes-on-top-of-origin")?
+                .with_fetch_tags(gix::remote::fetch::Tags::None),
+            daemon.as_ref(),
+            None,
+        );
+
+        let res: gix::remote::fetch::Outcome = remote
+            .connect(Fetch)
+            .await?
+            .prepare_fetch(gix::progress::Discard, Default::default())
+            .await?
+            .with_quarantine(true)
+            .receive(gix::progress::Discard, &AtomicBool::default())
+            .await?;
+
+        let objects_dir = repo.objects.store_ref().path();
+        match res.status {
+            gix::remote::fetch::Status::Change {
+                write_pack_bundle,
+                update_refs,
+                ..
+            } => {
+                for path in [write_pack_bundle.data_path, write_pack_bundle.index_path] {
+                    let path = path.expect("a pack was written");
+                    assert!(path.is_file());
+                    assert_eq!(
+                        path.parent(),
+                        Some(objects_dir.join("pack").as_path()),
+                        "the pack was migrated"
+                    );
+                }
+                assert_eq!(update_refs.edits.len(), 2);
+                for edit in &update_refs.edits {
+                    let id = repo.find_reference(edit.name.as_ref())?.into_fully_peeled_id()?;
+                    assert!(id.object().is_ok(), "the object database contains the received objects");
+                }
+            }
+            _ => unreachable!("there are changes to fetch"),
+        }
+        assert!(
+            !std::fs::read_dir(objects_dir)?
+                .filter_map(Result::ok)
+                .any(|entry| entry.file_name().to_string_lossy().starts_with("incoming-")),
+            "the quarantine directory was removed"
+        );
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "blocking-network-client")]
+    fn fetch_pack_with_configured_sha1_collision_detection() -> crate::Result {
+        for value in ["true", "false", "not-a-boolean"] {
+            let (repo, _tmp) = repo_rw("two-origins");
+            let mut repo = gix::open_opts(repo.path(), crate::restricted().strict_config(true))?;
+            repo.config_snapshot_mut().append_config(
+                [format!("gitoxide.core.sha1CollisionDetection={value}").as_str()],
+                gix::config::Source::Api,
+            )?;
+            let res = repo
+                .find_remote("changes-on-top-of-origin")?
+                .with_fetch_tags(gix::remote::fetch::Tags::None)
+                .connect(Fetch)?
+                .prepare_fetch(gix::progress::Discard, Default::default())?
+                .receive(gix::progress::Discard, &AtomicBool::default());
+            match res {
+                Ok(res) => {
+                    assert_ne!(value, "not-a-boolean");
+                    assert!(
+                        matches!(res.status, Status::Change { .. }),
+                        "packs are received with either hasher"
+                    );
+                }
+                Err(err) => {
+                    assert_eq!(value, "not-a-boolean", "{err}");
+                    assert!(
+                        err.to_string().contains("gitoxide.core.sha1CollisionDetection"),
+                        "the configuration is used: {err}"
+                    );
+                }
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "blocking-network-client")]
+    fn fetch_pack_with_quarantine_into_partial_clone() -> crate::Result {
+        let tmp = gix_testtools::scripted_fixture_writable("make_partial_clone_repo.sh")?;
+        for (name, expect_success) in [("partial", true), ("partial-without-promisor", false)] {
+            let repo = gix::open_opts(tmp.path().join(name), crate::restricted())?;
+            let res = repo
+                .find_remote("origin")?
+                .connect(Fetch)?
+                .prepare_fetch(gix::progress::Discard, Default::default())?
+                .with_quarantine(true)
+                .receive(gix::progress::Discard, &AtomicBool::default());
+            if expect_success {
+                let outcome = res?;
+                assert!(
+                    matches!(outcome.status, Status::Change { .. }),
+                    "blobs that were filtered when cloning are promised by the remote, and not considered missing"
+                );
+            } else {
+                assert!(
+                    matches!(res, Err(fetch::Error::MissingObject { .. })),
+                    "without a promisor remote, objects that aren't present anywhere are missing"
+                );
+            }
+        }
+        Ok(())
+    }
+
     #[maybe_async::test(
         feature = "blocking-network-client",
         async(feature = "async-network-client-async-std", async_std::test)
@@ -652,3 +772,186 @@ mod blocking_and_async_io {
         Ok(())
     }
 }
+
+#[cfg(feature = "blocking-network-client")]
+mod remote_helper {
+    use std::{
+        io::{BufRead, Write},
+        path::Path,
+        sync::atomic::AtomicBool,
+    };
+
+    use gix::protocol::transport::{
+        client::{helper, MessageKind, Transport, TransportWithoutIO, WriteMode},
+        Protocol, Service,
+    };
+    use gix_testtools::{tempfile::TempDir, Env};
+    use serial_test::serial;
+
+    /// Return a writable copy of the fixture with the helpers in it made available in `PATH`, for as long as `Env` lives.
+    fn fixture() -> crate::Result<(TempDir, Env<'static>)> {
+        let dir = gix_testtools::scripted_fixture_writable("make_remote_helpers.sh")?;
+        let path = std::env::join_paths(
+            Some(dir.path().join("bin"))
+                .into_iter()
+                .chain(std::env::split_paths(&std::env::var_os("PATH").expect("PATH is set"))),
+        )?;
+        let env = Env::new().set("PATH", path.to_str().expect("valid UTF-8"));
+        Ok((dir, env))
+    }
+
+    fn url(scheme: &str, dir: &Path) -> crate::Result<String> {
+        Ok(format!(
+            "{scheme}://{}",
+            gix::path::realpath(dir.join("src"))?.display()
+        ))
+    }
+
+    fn clone_through(scheme: &str, dir: &Path) -> crate::Result<(gix::Repository, gix::Repository)> {
+        let (repo, _outcome) = gix::prepare_clone_bare(url(scheme, dir)?, dir.join("clone"))?
+            .fetch_only(gix::progress::Discard, &AtomicBool::default())?;
+        let src = gix::open_opts(dir.join("src"), crate::restricted())?;
+        Ok((repo, src))
+    }
+
+    fn assert_branches_match(repo: &gix::Repository, src: &gix::Repository) -> crate::Result {
+        for name in ["main", "feature"] {
+            assert_eq!(
+                repo.find_reference(format!("refs/remotes/origin/{name}").as_str())?
+                    .id(),
+                src.find_reference(name)?.id(),
+                "{name} was cloned"
+            );
+        }
+        assert_eq!(
+            repo.head_name()?.expect("not detached").as_bstr(),
+            "refs/heads/main",
+            "the symbolic HEAD is advertised"
+        );
+        Ok(())
+    }
+
+    #[test]
+    #[serial]
+    fn clone_with_fetch() -> crate::Result {
+        let (dir, _env) = fixture()?;
+        let (repo, src) = clone_through("testfetch", dir.path())?;
+        assert_branches_match(&repo, &src)?;
+        assert_eq!(repo.find_reference("v1")?.id(), src.find_reference("v1")?.id());
+        assert_eq!(
+            repo.head_commit()?.message()?.summary().as_ref(),
+            "c2",
+            "the objects the helper fetched are present"
+        );
+
+        let status = std::process::Command::new("git")
+            .args([
+                "-c",
+                "user.name=c",
+                "-c",
+                "user.email=c@example.com",
+                "commit",
+                "-q",
+                "--allow-empty",
+                "-m",
+                "c3",
+            ])
+            .current_dir(dir.path().join("src"))
+            .status()?;
+        assert!(status.success());
+        let outcome = repo
+            .find_remote("origin")?
+            .connect(gix::remote::Direction::Fetch)?
+            .prepare_fetch(gix::progress::Discard, Default::default())?
+            .receive(gix::progress::Discard, &AtomicBool::default())?;
+        assert!(
+            matches!(outcome.status, gix::remote::fetch::Status::Change { .. }),
+            "fetching again negotiates with the objects that are already present"
+        );
+        let main = repo
+            .find_reference("refs/remotes/origin/main")?
+            .into_fully_peeled_id()?;
+        assert_eq!(main.object()?.into_commit().message()?.summary().as_ref(), "c3");
+        Ok(())
+    }
+
+    #[test]
+    #[serial]
+    fn clone_with_import() -> crate::Result {
+        let (dir, _env) = fixture()?;
+        let (repo, src) = clone_through("testimport", dir.path())?;
+        assert_branches_match(&repo, &src)?;
+        assert_eq!(
+            repo.find_reference("refs/testimport/heads/main")?.id(),
+            src.find_reference("main")?.id(),
+            "objects and references are imported into the private namespace of the helper"
+        );
+        Ok(())
+    }
+
+    #[test]
+    #[serial]
+    fn clone_with_connect() -> crate::Result {
+        let (dir, _env) = fixture()?;
+        let (repo, src) = clone_through("testconnect", dir.path())?;
+        assert_branches_match(&repo, &src)
+    }
+
+    #[test]
+    #[serial]
+    fn push_with_push() -> crate::Result {
+        let (dir, _env) = fixture()?;
+        let (repo, src) = clone_through("testfetch", dir.path())?;
+        let old = src.find_reference("feature")?.id().detach();
+        let new = repo.head_id()?.detach();
+        let pack = std::process::Command::new("git")
+            .args(["pack-objects", "--stdout", "--revs", "-q"])
+            .current_dir(repo.git_dir())
+            .stdin(std::process::Stdio::piped())
+            .stdout(std::process::Stdio::piped())
+            .spawn()
+            .and_then(|mut child| {
+                writeln!(child.stdin.take().expect("piped"), "{new}\n^{old}")?;
+                child.wait_with_output()
+            })?
+            .stdout;
+
+        let mut transport = helper::connect(
+            gix::url::parse(url("testfetch", dir.path())?.as_str().into())?,
+            Protocol::V1,
+            false,
+        )?;
+        transport.configure(&helper::Options {
+            git_dir: Some(repo.git_dir().to_owned()),
+            remote_name: None,
+        })?;
+        {
+            let response = transport.handshake(Service::ReceivePack, &[])?;
+            assert_eq!(response.actual_protocol, Protocol::V1);
+            assert!(response.capabilities.contains("report-status"));
+            let advertised = response
+                .refs
+                .expect("V1 advertises refs")
+                .lines()
+                .collect::<Result<Vec<_>, _>>()?;
+            assert!(advertised.contains(&format!("{old} refs/heads/feature")));
+        }
+
+        let mut request = transport.request(WriteMode::OneLfTerminatedLinePerWriteCall, MessageKind::Flush, false)?;
+        request.write_all(format!("{old} {new} refs/heads/feature\0report-status").as_bytes())?;
+        request.write_message(MessageKind::Flush)?;
+        let (mut pack_writer, reader) = request.into_parts();
+        pack_writer.write_all(&pack)?;
+        drop(pack_writer);
+        assert_eq!(
+            reader.lines().collect::<Result<Vec<_>, _>>()?,
+            ["unpack ok", "ok refs/heads/feature"],
+            "the pack is skipped and the helper reports on the update"
+        );
+        drop(transport);
+
+        let src = gix::open_opts(dir.path().join("src"), crate::restricted())?;
+        assert_eq!(src.find_reference("feature")?.id(), new, "the helper pushed the update");
+        Ok(())
+    }
+}
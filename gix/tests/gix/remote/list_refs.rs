@@ -0,0 +1,125 @@
+#[cfg(any(feature = "blocking-network-client", feature = "async-network-client-async-std"))]
+mod blocking_and_async_io {
+    use gix::{
+        bstr::BString,
+        config::tree::Protocol,
+        remote::{list_refs::Options, Direction::Fetch},
+    };
+    use gix_features::progress;
+    use gix_protocol::{handshake, maybe_async};
+
+    use crate::{
+        remote,
+        remote::{into_daemon_remote_if_async, spawn_git_daemon_if_async},
+    };
+
+    #[maybe_async::test(
+        feature = "blocking-network-client",
+        async(feature = "async-network-client-async-std", async_std::test)
+    )]
+    async fn filters_match_git_ls_remote() -> crate::Result {
+        let daemon = spawn_git_daemon_if_async(remote::repo_path("base"))?;
+        for version in [
+            gix::protocol::transport::Protocol::V1,
+            gix::protocol::transport::Protocol::V2,
+        ] {
+            for (options, expected) in [
+                (Options::default(), 18),
+                (
+                    Options {
+                        branches: true,
+                        ..Default::default()
+                    },
+                    11,
+                ),
+                (
+                    Options {
+                        tags: true,
+                        ..Default::default()
+                    },
+                    6,
+                ),
+                (
+                    Options {
+                        branches: true,
+                        tags: true,
+                        ..Default::default()
+                    },
+                    17,
+                ),
+                (
+                    Options {
+                        patterns: vec!["HEAD".into(), "main".into()],
+                        ..Default::default()
+                    },
+                    2,
+                ),
+                (
+                    Options {
+                        tags: true,
+                        patterns: vec!["heads/*".into()],
+                        ..Default::default()
+                    },
+                    0,
+                ),
+            ] {
+                let mut repo = remote::repo("clone");
+                repo.config_snapshot_mut()
+                    .set_raw_value(&Protocol::VERSION, (version as u8).to_string().as_str())?;
+                let remote = into_daemon_remote_if_async(repo.find_remote("origin")?, daemon.as_ref(), None);
+                let outcome = remote
+                    .connect(Fetch)
+                    .await?
+                    .list_refs(progress::Discard, options.clone())
+                    .await?;
+                let names: Vec<BString> = outcome.refs.iter().map(|r| r.unpack().0.to_owned()).collect();
+                assert_eq!(
+                    names,
+                    baseline(&repo, &options),
+                    "{version:?} {options:?}: the same refs are listed as `git ls-remote` does"
+                );
+                assert_eq!(names.len(), expected, "{version:?} {options:?}");
+                assert_eq!(outcome.handshake.server_protocol_version, version);
+
+                if let Some(tag) = outcome
+                    .refs
+                    .iter()
+                    .find(|r| r.unpack().0 == "refs/tags/annotated-detached-tag")
+                {
+                    assert!(
+                        matches!(tag, handshake::Ref::Peeled { .. }),
+                        "{version:?}: annotated tags are peeled"
+                    );
+                }
+                if let Some(head) = outcome.refs.iter().find(|r| r.unpack().0 == "HEAD") {
+                    assert!(
+                        matches!(head, handshake::Ref::Symbolic { target, .. } if target == "refs/heads/main"),
+                        "{version:?}: the target of HEAD is known"
+                    );
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn baseline(repo: &gix::Repository, options: &Options) -> Vec<BString> {
+        let mut cmd = std::process::Command::new(gix::path::env::exe_invocation());
+        cmd.current_dir(repo.work_dir().expect("non-bare")).arg("ls-remote");
+        if options.branches {
+            cmd.arg("--heads");
+        }
+        if options.tags {
+            cmd.arg("--tags");
+        }
+        cmd.arg("origin").args(options.patterns.iter().map(ToString::to_string));
+        let out = cmd.output().expect("git is installed");
+        assert!(out.status.success() || out.status.code() == Some(2), "{out:?}");
+        String::from_utf8(out.stdout)
+            .expect("valid")
+            .lines()
+            .filter_map(|line| line.split_once('\t').map(|(_, name)| name))
+            .filter(|name| !name.ends_with("^{}"))
+            .map(Into::into)
+            .collect()
+    }
+}
@@ -0,0 +1,86 @@
+use gix::refs::transaction::PreviousValue;
+use gix::repository::branch_tracking::{Status, Upstream};
+use gix_testtools::tempfile;
+
+use crate::util::restricted_and_git;
+
+#[test]
+fn ahead_behind_and_gone_upstreams() -> crate::Result {
+    let tmp = tempfile::tempdir()?;
+    let mut repo =
+        gix::ThreadSafeRepository::init_opts(&tmp, gix::create::Kind::Bare, Default::default(), restricted_and_git())?
+            .to_thread_local();
+
+    let tree = repo.empty_tree().id;
+    let base = repo
+        .commit("refs/heads/main", "base", tree, gix::commit::NO_PARENT_IDS)?
+        .detach();
+    let remote = repo
+        .commit("refs/remotes/origin/main", "remote 1", tree, Some(base))?
+        .detach();
+    let remote = repo
+        .commit("refs/remotes/origin/main", "remote 2", tree, Some(remote))?
+        .detach();
+    let local = repo.commit("refs/heads/main", "local", tree, Some(base))?.detach();
+    repo.reference("refs/heads/gone", base, PreviousValue::MustNotExist, "create")?;
+    repo.reference("refs/heads/untracked", remote, PreviousValue::MustNotExist, "create")?;
+    repo.reference("refs/heads/up-to-date", remote, PreviousValue::MustNotExist, "create")?;
+
+    repo.config_snapshot_mut().append_config(
+        [
+            "remote.origin.url=https://example.com/repo",
+            "remote.origin.fetch=+refs/heads/*:refs/remotes/origin/*",
+            "branch.main.remote=origin",
+            "branch.main.merge=refs/heads/main",
+            "branch.gone.remote=origin",
+            "branch.gone.merge=refs/heads/gone",
+            "branch.up-to-date.remote=origin",
+            "branch.up-to-date.merge=refs/heads/main",
+        ],
+        gix::config::Source::Api,
+    )?;
+
+    let branches = repo.branch_tracking()?;
+    let actual: Vec<_> = branches
+        .iter()
+        .map(|branch| (branch.name.shorten().to_string(), branch.upstream.clone()))
+        .collect();
+    let origin_main = || -> gix::refs::FullName { "refs/remotes/origin/main".try_into().expect("valid") };
+    assert_eq!(
+        actual,
+        [
+            (
+                "gone".to_string(),
+                Some(Upstream {
+                    name: "refs/remotes/origin/gone".try_into()?,
+                    status: Status::Gone,
+                })
+            ),
+            (
+                "main".into(),
+                Some(Upstream {
+                    name: origin_main(),
+                    status: Status::Present {
+                        id: remote,
+                        ahead: 1,
+                        behind: 2,
+                    },
+                })
+            ),
+            ("untracked".into(), None),
+            (
+                "up-to-date".into(),
+                Some(Upstream {
+                    name: origin_main(),
+                    status: Status::Present {
+                        id: remote,
+                        ahead: 0,
+                        behind: 0,
+                    },
+                })
+            ),
+        ]
+    );
+    assert_eq!(branches[1].id, local);
+    Ok(())
+}
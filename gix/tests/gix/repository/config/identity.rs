@@ -164,11 +164,7 @@ fn author_from_different_config_sections() -> crate::Result {
         Some(gix_actor::SignatureRef {
             name: "global name".into(),
             email: "local@example.com".into(),
-            time: gix_date::Time {
-                seconds: 42,
-                offset: 1800,
-                sign: gix_date::time::Sign::Plus
-            }
+            time: gix_date::parse("1979-02-26 18:30:00", None)?,
         }),
         "author name comes from global config, \
          but email comes from repository-local config",
@@ -2,6 +2,134 @@ mod config_snapshot;
 mod identity;
 mod remote;
 
+#[test]
+#[cfg(feature = "index")]
+fn use_untracked_cache() -> crate::Result {
+    use gix::config::{tree::Core, untracked_cache::Mode};
+
+    let (mut repo, _tmp) = crate::util::basic_rw_repo()?;
+    let index = repo.index_or_empty()?;
+    assert_eq!(repo.untracked_cache_mode()?, Mode::Keep, "the default");
+    assert!(
+        !repo.use_untracked_cache(&index)?,
+        "the index doesn't have an untracked cache to keep"
+    );
+
+    repo.config_snapshot_mut().set_value(&Core::UNTRACKED_CACHE, "true")?;
+    assert_eq!(repo.untracked_cache_mode()?, Mode::Enabled);
+    assert!(
+        repo.use_untracked_cache(&index)?,
+        "the configuration is trusted, the probe is only performed on request"
+    );
+
+    repo.config_snapshot_mut().set_value(&Core::UNTRACKED_CACHE, "false")?;
+    assert_eq!(repo.untracked_cache_mode()?, Mode::Disabled);
+    assert!(!repo.use_untracked_cache(&index)?);
+    Ok(())
+}
+
+#[test]
+fn trailer_options() -> crate::Result {
+    use gix::{
+        config::tree::Trailer,
+        objs::commit::message::trailer::{Editor, IfExists, Options, Placement},
+    };
+
+    let (mut repo, _tmp) = crate::util::basic_rw_repo()?;
+    assert_eq!(repo.trailer_options()?, Options::default());
+
+    {
+        let mut config = repo.config_snapshot_mut();
+        config.set_value(&Trailer::SEPARATORS, "#:")?;
+        config.set_value(&Trailer::WHERE, "start")?;
+        config.set_value(&Trailer::IF_EXISTS, "replace")?;
+    }
+    let opts = repo.trailer_options()?;
+    assert_eq!(opts.separators, "#:");
+    assert_eq!(opts.placement, Placement::Start);
+    assert_eq!(opts.if_exists, IfExists::Replace);
+
+    let mut editor = Editor::new("title\n\nIssue: 1\n".into(), opts);
+    assert!(editor.add("Fixes", "2"));
+    assert_eq!(editor.into_message(), "title\n\nFixes# 2\nIssue: 1\n");
+    Ok(())
+}
+
+#[test]
+fn fsck_options() -> crate::Result {
+    use gix::objs::fsck::{MessageId, Severity};
+
+    let repo = crate::named_repo("make_basic_repo.sh")?;
+    let opts = repo.fsck_options()?;
+    assert!(opts.severities.is_empty());
+    assert_eq!(opts.severity(MessageId::ZeroPaddedFilemode), Severity::Warn);
+
+    let open = |overrides: &[&str], strict: bool| {
+        gix::open_opts(
+            repo.path(),
+            crate::restricted()
+                .strict_config(strict)
+                .config_overrides(overrides.iter().copied()),
+        )
+    };
+    let repo = open(
+        &[
+            "fsck.zeroPaddedFilemode=ignore",
+            "fsck.BADDATE=warn",
+            "fsck.skipList=ids",
+        ],
+        true,
+    )?;
+    let opts = repo.fsck_options()?;
+    assert_eq!(opts.severity(MessageId::ZeroPaddedFilemode), Severity::Ignore);
+    assert_eq!(
+        opts.severity(MessageId::BadDate),
+        Severity::Warn,
+        "ids are case-insensitive"
+    );
+    assert_eq!(opts.severities.len(), 2, "the skip-list is ignored");
+
+    let zero_padded_tree = [b"040000 a\0".as_slice(), &[1; 20]].concat();
+    assert_eq!(
+        gix::objs::fsck::tree(&zero_padded_tree, &opts),
+        Vec::new(),
+        "configured severities are used during validation"
+    );
+
+    for invalid in ["fsck.unknownId=warn", "fsck.badDate=fatal"] {
+        let repo = open(&[invalid], true)?;
+        assert!(repo.fsck_options().is_err(), "{invalid} is rejected in strict mode");
+        let repo = open(&[invalid], false)?;
+        assert!(
+            repo.fsck_options()?.severities.is_empty(),
+            "{invalid} is ignored when lenient"
+        );
+    }
+    Ok(())
+}
+
+#[test]
+#[cfg(any(feature = "blocking-network-client", feature = "async-network-client"))]
+fn serve_options() -> crate::Result {
+    use gix::{config::tree::Uploadpack, protocol::serve::Wants};
+
+    let (mut repo, _tmp) = crate::util::basic_rw_repo()?;
+    let opts = repo.serve_options()?;
+    assert_eq!(opts.wants(), Wants::Advertised, "nothing is allowed by default");
+    assert!(!opts.allow_filter);
+    assert!(opts.agent.starts_with(b"git/"));
+
+    {
+        let mut config = repo.config_snapshot_mut();
+        config.set_value(&Uploadpack::ALLOW_REACHABLE_SHA1_IN_WANT, "true")?;
+        config.set_value(&Uploadpack::ALLOW_FILTER, "true")?;
+    }
+    let opts = repo.serve_options()?;
+    assert_eq!(opts.wants(), Wants::Reachable);
+    assert!(opts.allow_filter);
+    Ok(())
+}
+
 #[cfg(feature = "blocking-network-client")]
 mod ssh_options {
     use std::ffi::OsStr;
@@ -57,6 +57,7 @@ mod http {
             ssl_version,
             ssl_verify,
             http_version,
+            pool_connections,
             backend,
         } = http_options(&repo, None, "https://example.com/does/not/matter");
         assert_eq!(
@@ -77,6 +78,7 @@ mod http {
         assert_eq!(connect_timeout, Some(std::time::Duration::from_millis(60 * 1024)));
         assert_eq!(no_proxy, None);
         assert!(!verbose, "verbose is disabled by default");
+        assert!(!pool_connections, "connections aren't pooled by default");
         assert_eq!(ssl_ca_info.as_deref(), Some(std::path::Path::new("./CA.pem")));
         #[cfg(feature = "blocking-http-transport-reqwest")]
         {
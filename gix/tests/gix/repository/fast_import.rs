@@ -0,0 +1,214 @@
+use gix::bstr::ByteSlice;
+use gix::fast_import::{Command, DataRef, FileChange, ObjectRef, Parser};
+
+use crate::util::repo_rw;
+
+const STREAM: &str = "\
+feature done
+# a comment
+blob
+mark :1
+data 6
+hello
+
+commit refs/heads/main
+mark :2
+author A U Thor <author@example.com> 1112911993 -0700
+committer C O Mitter <committer@example.com> 1112912053 -0700
+data <<EOF
+initial
+EOF
+M 100644 :1 a
+M 100755 inline dir/run.sh
+data 8
+echo hi
+
+commit refs/heads/main
+mark :3
+committer C O Mitter <committer@example.com> 1112912113 -0700
+data 6
+second
+from :2
+R a \"b c\"
+D dir/run.sh
+
+tag v1.0
+from :3
+tagger C O Mitter <committer@example.com> 1112912173 -0700
+data 4
+v1.0
+reset refs/heads/other
+from :2
+
+done
+";
+
+#[test]
+fn parse_and_encode_round_trip() -> crate::Result {
+    let commands = Parser::new(STREAM.as_bytes()).collect::<Result<Vec<_>, _>>()?;
+    assert_eq!(commands.len(), 7, "comments are skipped");
+    let Command::Commit(commit) = &commands[2] else {
+        unreachable!("the first commit follows the blob")
+    };
+    assert_eq!(commit.mark, Some(2));
+    assert_eq!(commit.message, "initial\n", "delimited data keeps its trailing newline");
+    assert_eq!(commit.changes.len(), 2);
+    assert_eq!(
+        commit.changes[1],
+        FileChange::Modify {
+            mode: gix::object::tree::EntryKind::BlobExecutable,
+            data: DataRef::Inline("echo hi\n".into()),
+            path: "dir/run.sh".into()
+        }
+    );
+    let Command::Commit(commit) = &commands[3] else {
+        unreachable!("second commit")
+    };
+    assert_eq!(commit.author, None, "it's optional");
+    assert_eq!(commit.from, Some(ObjectRef::Mark(2)));
+    assert_eq!(
+        commit.changes[0],
+        FileChange::Rename {
+            source: "a".into(),
+            destination: "b c".into()
+        },
+        "quoted paths are unquoted"
+    );
+
+    let mut out = Vec::new();
+    for command in &commands {
+        command.write_to(&mut out)?;
+    }
+    let reparsed = Parser::new(out.as_slice()).collect::<Result<Vec<_>, _>>()?;
+    assert_eq!(
+        reparsed, commands,
+        "the encoded stream parses back to the same commands"
+    );
+    Ok(())
+}
+
+#[test]
+fn parse_errors_carry_the_line_number() {
+    let err = Parser::new("blob\nmark :1\nnot-data\n".as_bytes())
+        .next()
+        .expect("one command")
+        .unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Line 3: expected 'data', got \"not-data\"",
+        "the offending line is reported"
+    );
+}
+
+#[test]
+fn quoted_paths_use_c_style_escapes() -> crate::Result {
+    let stream = "commit refs/heads/main\ncommitter C O Mitter <committer@example.com> 1112912053 -0700\ndata 0\nC \"a \\\"q\\\" b\" \"\\303\\244\\tc\"\nD \"invalid\\q\"\n";
+    let err = Parser::new(stream.as_bytes()).next().expect("one command").unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        r#"Line 5: expected a valid path, got "D "invalid\q"""#,
+        "unknown escapes are rejected"
+    );
+
+    let stream = stream.rsplit_once("D ").expect("present").0;
+    let commands = Parser::new(stream.as_bytes()).collect::<Result<Vec<_>, _>>()?;
+    let Command::Commit(commit) = &commands[0] else {
+        unreachable!("only a commit")
+    };
+    assert_eq!(
+        commit.changes,
+        [FileChange::Copy {
+            source: "a \"q\" b".into(),
+            destination: "ä\tc".into()
+        }]
+    );
+    Ok(())
+}
+
+#[test]
+fn data_with_a_length_beyond_the_stream_is_an_error() {
+    let err = Parser::new(format!("blob\ndata {}\nhello\n", usize::MAX).as_bytes())
+        .next()
+        .expect("one command")
+        .unwrap_err();
+    assert!(
+        matches!(err, gix::fast_import::decode::Error::UnexpectedEof { .. }),
+        "the announced length isn't trusted and also doesn't cause a huge allocation: {err:?}"
+    );
+}
+
+#[test]
+#[cfg(feature = "tree-editor")]
+fn import_writes_objects_and_updates_refs() -> crate::Result {
+    let tmp = gix_testtools::tempfile::TempDir::new()?;
+    let repo = gix::init_bare(tmp.path())?;
+    let outcome = repo.fast_import(STREAM.as_bytes())?;
+    assert_eq!(outcome.blobs, 2, "one blob command and one inline blob");
+    assert_eq!(outcome.commits, 2);
+    assert_eq!(outcome.tags, 1);
+    assert_eq!(outcome.marks.len(), 3);
+    assert_eq!(outcome.refs.len(), 3, "main, other and the tag");
+
+    let main = repo.find_reference("refs/heads/main")?.peel_to_commit()?;
+    assert_eq!(main.id, outcome.marks[&3]);
+    assert_eq!(
+        main.author()?.name,
+        "C O Mitter",
+        "the committer is used if there is no author"
+    );
+    let tree = main.tree()?;
+    let entries: Vec<_> = tree
+        .iter()
+        .map(|entry| entry.map(|entry| entry.filename().to_owned()))
+        .collect::<Result<_, _>>()?;
+    assert_eq!(entries, ["b c"], "the rename and deletion were applied");
+    let blob = tree.lookup_entry_by_path("b c")?.expect("present").object()?;
+    assert_eq!(blob.data.as_bstr(), "hello\n");
+
+    let other = repo.find_reference("refs/heads/other")?.peel_to_commit()?;
+    assert_eq!(other.id, outcome.marks[&2]);
+    assert_eq!(other.tree()?.iter().count(), 2, "'a' and 'dir'");
+
+    let tag = repo
+        .find_reference("refs/tags/v1.0")?
+        .peel_to_kind(gix::object::Kind::Tag)?;
+    assert_eq!(tag.into_tag().target_id()?, main.id);
+    Ok(())
+}
+
+#[test]
+#[cfg(feature = "tree-editor")]
+fn import_requires_done_if_requested() -> crate::Result {
+    let tmp = gix_testtools::tempfile::TempDir::new()?;
+    let repo = gix::init_bare(tmp.path())?;
+    let err = repo.fast_import("feature done\nprogress hi\n".as_bytes()).unwrap_err();
+    assert!(matches!(err, gix::repository::fast_import::Error::MissingDone));
+    Ok(())
+}
+
+#[test]
+#[cfg(feature = "tree-editor")]
+fn export_then_import_reproduces_the_same_commits() -> crate::Result {
+    let (repo, _tmp) = repo_rw("make_repo_with_fork_and_dates.sh")?;
+    let names: Vec<gix::refs::FullName> = ["refs/heads/main", "refs/heads/branch1"]
+        .into_iter()
+        .map(|name| name.try_into().expect("valid"))
+        .collect();
+    let mut stream = Vec::new();
+    let outcome = repo.fast_export(names, &mut stream)?;
+    assert_eq!(outcome.commits, 4);
+    assert_eq!(outcome.blobs, 0, "all commits are empty");
+
+    let tmp = gix_testtools::tempfile::TempDir::new()?;
+    let new_repo = gix::init_bare(tmp.path())?;
+    let import = new_repo.fast_import(stream.as_slice())?;
+    assert_eq!(import.commits, 4);
+    for name in ["refs/heads/main", "refs/heads/branch1"] {
+        assert_eq!(
+            new_repo.find_reference(name)?.id().detach(),
+            repo.find_reference(name)?.id().detach(),
+            "commits are recreated exactly"
+        );
+    }
+    Ok(())
+}
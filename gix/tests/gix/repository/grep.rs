@@ -0,0 +1,207 @@
+use std::sync::atomic::AtomicBool;
+
+use gix::repository::grep::{Binary, Options, Pattern, Source};
+
+use crate::util::named_repo;
+
+/// Render `files` like `git grep -n --column` does.
+fn render(files: &[gix::repository::grep::File]) -> Vec<String> {
+    let mut out = Vec::new();
+    for file in files {
+        if file.is_binary {
+            out.push(format!("Binary file {} matches", file.rela_path));
+        }
+        for m in &file.matches {
+            out.push(format!("{}:{}:{}:{}", file.rela_path, m.line_number, m.column, m.line));
+        }
+    }
+    out
+}
+
+fn grep(
+    repo: &gix::Repository,
+    source: Source,
+    pattern: &str,
+    pathspecs: &[&str],
+    options: Options,
+) -> crate::Result<Vec<String>> {
+    grep_pattern(repo, source, Pattern::Fixed(pattern.into()), pathspecs, options)
+}
+
+fn grep_pattern(
+    repo: &gix::Repository,
+    source: Source,
+    pattern: Pattern,
+    pathspecs: &[&str],
+    options: Options,
+) -> crate::Result<Vec<String>> {
+    Ok(render(&repo.grep(
+        source,
+        &pattern,
+        pathspecs,
+        options,
+        &AtomicBool::default(),
+    )?))
+}
+
+#[test]
+fn worktree_index_and_tree_match_git() -> crate::Result {
+    let repo = named_repo("make_grep_repo.sh")?;
+    let tree = repo.head_tree_id()?.detach();
+
+    assert_eq!(
+        grep(&repo, Source::Tree(tree), "hello", &[], Options::default())?,
+        [
+            "a.txt:1:1:hello world",
+            "Binary file binary.dat matches",
+            "dir/b.txt:2:3:  hello there",
+            "Binary file forced-binary.txt matches",
+            "forced-text.dat:1:1:hello\0text",
+        ],
+        "unlike `git`, attributes also apply when searching trees, and symlinks are never searched"
+    );
+    assert_eq!(
+        grep(&repo, Source::Index, "hello", &[], Options::default())?,
+        [
+            "a.txt:1:1:hello world",
+            "Binary file binary.dat matches",
+            "dir/b.txt:2:3:  hello there",
+            "Binary file forced-binary.txt matches",
+            "forced-text.dat:1:1:hello\0text",
+            "staged.txt:1:1:hello staged",
+        ]
+    );
+
+    let options = Options {
+        ignore_case: true,
+        ..Default::default()
+    };
+    assert_eq!(
+        grep(&repo, Source::Worktree, "HELLO", &[], options)?,
+        [
+            "a.txt:1:1:hello world",
+            "a.txt:3:5:say Hello",
+            "a.txt:4:1:hello worktree",
+            "Binary file binary.dat matches",
+            "dir/b.txt:2:3:  hello there",
+            "Binary file forced-binary.txt matches",
+            "forced-text.dat:1:1:hello\0text",
+            "staged.txt:1:1:hello staged",
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+fn binary_files_can_be_skipped_or_searched_as_text() -> crate::Result {
+    let repo = named_repo("make_grep_repo.sh")?;
+    let options = |binary| Options {
+        binary,
+        ..Default::default()
+    };
+    assert_eq!(
+        grep(
+            &repo,
+            Source::Index,
+            "hello",
+            &["*.dat", "*binary*"],
+            options(Binary::Skip)
+        )?,
+        ["forced-text.dat:1:1:hello\0text"]
+    );
+    assert_eq!(
+        grep(
+            &repo,
+            Source::Index,
+            "hello",
+            &["*.dat", "*binary*"],
+            options(Binary::Text)
+        )?,
+        [
+            "binary.dat:1:1:hello\0binary",
+            "forced-binary.txt:1:1:hello text",
+            "forced-text.dat:1:1:hello\0text",
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+fn pathspecs_limit_the_searched_files() -> crate::Result {
+    let repo = named_repo("make_grep_repo.sh")?;
+    assert_eq!(
+        grep(&repo, Source::Worktree, "hello", &["dir"], Options::default())?,
+        ["dir/b.txt:2:3:  hello there"]
+    );
+    assert_eq!(
+        grep(
+            &repo,
+            Source::Worktree,
+            "hello",
+            &[":!*.dat", ":!*.txt"],
+            Options::default()
+        )?,
+        Vec::<String>::new()
+    );
+    Ok(())
+}
+
+#[test]
+fn fixed_patterns_are_found_verbatim() -> crate::Result {
+    let repo = named_repo("make_grep_repo.sh")?;
+    assert_eq!(
+        grep(&repo, Source::Index, "l+o", &[], Options::default())?,
+        Vec::<String>::new(),
+        "special characters have no meaning"
+    );
+    assert_eq!(
+        grep_pattern(
+            &repo,
+            Source::Index,
+            Pattern::Fixed(b"hello\0".as_slice().into()),
+            &["*.dat"],
+            Options {
+                binary: Binary::Text,
+                ..Default::default()
+            }
+        )?,
+        ["binary.dat:1:1:hello\0binary", "forced-text.dat:1:1:hello\0text"],
+        "any bytes can be searched for"
+    );
+    Ok(())
+}
+
+#[test]
+#[cfg(feature = "revparse-regex")]
+fn patterns_can_be_regular_expressions() -> crate::Result {
+    let repo = named_repo("make_grep_repo.sh")?;
+    let regex = |pattern: &str| Pattern::Regex(pattern.into());
+    assert_eq!(
+        grep_pattern(&repo, Source::Index, regex("l+o [st]"), &[], Options::default())?,
+        [
+            "dir/b.txt:2:5:  hello there",
+            "Binary file forced-binary.txt matches",
+            "staged.txt:1:3:hello staged",
+        ]
+    );
+    assert_eq!(
+        grep_pattern(&repo, Source::Index, regex("w.rld|wörld"), &[], Options::default())?,
+        ["a.txt:1:7:hello world"],
+        "patterns match bytes, but may contain any character"
+    );
+
+    let err = repo
+        .grep(
+            Source::Index,
+            &Pattern::Regex(b"hello\xff".as_slice().into()),
+            None::<&str>,
+            Options::default(),
+            &AtomicBool::default(),
+        )
+        .unwrap_err();
+    assert!(
+        matches!(err, gix::repository::grep::Error::RegexNotUtf8 { .. }),
+        "regular expressions that aren't valid UTF-8 are rejected instead of being altered"
+    );
+    Ok(())
+}
@@ -0,0 +1,86 @@
+use gix::bstr::{BStr, BString, ByteSlice};
+use gix::history::rewrite::{Filter, Options};
+
+use crate::util::repo_rw;
+
+struct UppercaseMessages;
+
+impl Filter for UppercaseMessages {
+    fn message(&self, _commit: &gix::oid, message: &BStr) -> Result<Option<BString>, gix::history::rewrite::Error> {
+        Ok(Some(message.to_ascii_uppercase().into()))
+    }
+}
+
+fn branches() -> Vec<gix::refs::FullName> {
+    ["refs/heads/main", "refs/heads/branch1"]
+        .into_iter()
+        .map(|name| name.try_into().expect("valid"))
+        .collect()
+}
+
+#[test]
+fn noop_filter_keeps_all_commits_and_refs() -> crate::Result {
+    let (repo, _tmp) = repo_rw("make_repo_with_fork_and_dates.sh")?;
+    let main = repo.find_reference("main")?.id().detach();
+    let outcome = repo.rewrite_history(branches(), &(), Options::default())?;
+    assert_eq!(outcome.map.len(), 4, "all commits were traversed");
+    assert!(
+        outcome.map.iter().all(|(old, new)| Some(*old) == *new),
+        "nothing changed, so the ids remain the same"
+    );
+    assert_eq!(outcome.commits_rewritten, 0);
+    assert!(outcome.refs.is_empty(), "there was nothing to update");
+    assert_eq!(repo.find_reference("main")?.id(), main);
+    Ok(())
+}
+
+#[test]
+fn message_filter_rewrites_all_commits_and_updates_refs() -> crate::Result {
+    let (repo, _tmp) = repo_rw("make_repo_with_fork_and_dates.sh")?;
+    let previous_main = repo.find_reference("main")?.id().detach();
+    let outcome = repo.rewrite_history(branches(), &UppercaseMessages, Options::default())?;
+    assert_eq!(outcome.commits_rewritten, 4);
+    assert_eq!(outcome.refs.len(), 2, "both branches were updated");
+
+    let main = repo.find_reference("main")?.id();
+    assert_ne!(main, previous_main);
+    assert_eq!(outcome.map[&previous_main], Some(main.detach()));
+
+    let merge = main.object()?.into_commit();
+    assert_eq!(merge.message_raw()?.trim().as_bstr(), "M1B1");
+    for parent in merge.parent_ids() {
+        let parent = parent.object()?.into_commit();
+        assert!(
+            parent.message_raw()?.trim().iter().all(|b| !b.is_ascii_lowercase()),
+            "parents were rewritten as well"
+        );
+        assert!(outcome.map.values().any(|new| *new == Some(parent.id)));
+    }
+    Ok(())
+}
+
+#[test]
+fn hidden_commits_are_kept_as_parents() -> crate::Result {
+    let (repo, _tmp) = repo_rw("make_repo_with_fork_and_dates.sh")?;
+    let branch1 = repo.find_reference("branch1")?.id().detach();
+    let outcome = repo.rewrite_history(
+        branches(),
+        &UppercaseMessages,
+        Options {
+            hidden: vec![branch1],
+            ..Default::default()
+        },
+    )?;
+    assert!(!outcome.map.contains_key(&branch1), "hidden commits aren't traversed");
+    assert_eq!(
+        repo.find_reference("branch1")?.id(),
+        branch1,
+        "references pointing to hidden commits stay unchanged"
+    );
+    let merge = repo.find_reference("main")?.id().object()?.into_commit();
+    assert!(
+        merge.parent_ids().any(|id| id == branch1),
+        "the merge still points to the hidden commit"
+    );
+    Ok(())
+}
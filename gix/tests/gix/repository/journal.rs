@@ -0,0 +1,85 @@
+use gix::refs::transaction::PreviousValue;
+
+use crate::util::basic_rw_repo;
+
+fn move_main_to_parent(repo: &gix::Repository) -> crate::Result<gix::ObjectId> {
+    let parent = repo
+        .head_commit()?
+        .parent_ids()
+        .next()
+        .expect("c2 has a parent")
+        .detach();
+    repo.reference("refs/heads/main", parent, PreviousValue::Any, "move back")?;
+    Ok(parent)
+}
+
+#[test]
+fn rollback_restores_head_index_and_tracked_files() -> crate::Result {
+    let (repo, tmp) = basic_rw_repo()?;
+    let head_id = repo.head_id()?.detach();
+    let index = std::fs::read(repo.index_path())?;
+
+    let journal = repo.journal(["this", "new"])?;
+    assert_eq!(journal.tracked_paths().count(), 2);
+
+    std::fs::write(tmp.path().join("this"), "changed")?;
+    std::fs::write(tmp.path().join("new"), "created")?;
+    std::fs::remove_file(repo.index_path())?;
+    move_main_to_parent(&repo)?;
+
+    journal.rollback()?;
+    assert_eq!(repo.head_id()?, head_id, "the branch HEAD points to is restored");
+    assert_eq!(repo.head_name()?.expect("not detached").as_bstr(), "refs/heads/main");
+    assert_eq!(std::fs::read(repo.index_path())?, index);
+    assert_eq!(std::fs::read(tmp.path().join("this"))?, b"hello\n");
+    assert!(!tmp.path().join("new").exists(), "files that didn't exist are removed");
+    Ok(())
+}
+
+#[test]
+fn drop_rolls_back_and_commit_keeps_changes() -> crate::Result {
+    let (repo, tmp) = basic_rw_repo()?;
+    let head_id = repo.head_id()?.detach();
+    {
+        let _journal = repo.journal(Some("this"))?;
+        std::fs::write(tmp.path().join("this"), "changed")?;
+        move_main_to_parent(&repo)?;
+    }
+    assert_eq!(repo.head_id()?, head_id, "dropping the journal rolls back");
+    assert_eq!(std::fs::read(tmp.path().join("this"))?, b"hello\n");
+
+    let journal = repo.journal(Some("this"))?;
+    std::fs::write(tmp.path().join("this"), "changed")?;
+    let parent = move_main_to_parent(&repo)?;
+    journal.commit();
+    assert_eq!(repo.head_id()?, parent, "committed changes are kept");
+    assert_eq!(std::fs::read(tmp.path().join("this"))?, b"changed");
+    Ok(())
+}
+
+#[test]
+fn rollback_writes_the_index_through_its_lock() -> crate::Result {
+    let (repo, _tmp) = basic_rw_repo()?;
+    let journal = repo.journal(None::<&str>)?;
+    std::fs::write(repo.index_path(), "changed")?;
+
+    let lock_path = repo.index_path().with_extension("lock");
+    std::fs::write(&lock_path, "held by another process")?;
+    assert!(matches!(
+        journal.rollback(),
+        Err(gix::journal::rollback::Error::AcquireIndexLock(_))
+    ));
+    assert_eq!(
+        std::fs::read(repo.index_path())?,
+        b"changed",
+        "the index isn't touched while someone else holds its lock"
+    );
+
+    std::fs::remove_file(&lock_path)?;
+    let journal = repo.journal(None::<&str>)?;
+    std::fs::write(repo.index_path(), "changed again")?;
+    journal.rollback()?;
+    assert_eq!(std::fs::read(repo.index_path())?, b"changed");
+    assert!(!lock_path.exists(), "the lock is released once the index is restored");
+    Ok(())
+}
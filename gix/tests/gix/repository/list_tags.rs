@@ -0,0 +1,121 @@
+use gix::refs::transaction::PreviousValue;
+use gix::repository::list_tags::{Options, Sort};
+use gix_testtools::tempfile;
+
+use crate::util::restricted_and_git;
+
+fn tag_names(tags: &[gix::repository::list_tags::Tag]) -> Vec<String> {
+    tags.iter().map(|tag| tag.name.shorten().to_string()).collect()
+}
+
+#[test]
+fn sorting_and_filtering() -> crate::Result {
+    let tmp = tempfile::tempdir()?;
+    let mut repo =
+        gix::ThreadSafeRepository::init_opts(&tmp, gix::create::Kind::Bare, Default::default(), restricted_and_git())?
+            .to_thread_local();
+
+    let tree = repo.empty_tree().id;
+    let c1 = repo
+        .commit("refs/heads/main", "c1", tree, gix::commit::NO_PARENT_IDS)?
+        .detach();
+    let c2 = repo.commit("refs/heads/main", "c2", tree, Some(c1))?.detach();
+    let c3 = repo.commit("refs/heads/main", "c3", tree, Some(c2))?.detach();
+    let b1 = repo.commit("refs/heads/branch", "b1", tree, Some(c1))?.detach();
+    let blob = repo.write_blob("content")?.detach();
+
+    for (name, target) in [
+        ("v1.0", c1),
+        ("v1.0-rc1", c1),
+        ("v1.10", c2),
+        ("v1.9", b1),
+        ("blob", blob),
+    ] {
+        repo.tag_reference(name, target, PreviousValue::MustNotExist)?;
+    }
+    let annotated = repo
+        .tag(
+            "v2.0",
+            c3,
+            gix::object::Kind::Commit,
+            None,
+            "release",
+            PreviousValue::MustNotExist,
+        )?
+        .id()
+        .detach();
+
+    let tags = repo.list_tags(Options::default())?;
+    assert_eq!(
+        tag_names(&tags),
+        ["blob", "v1.0", "v1.0-rc1", "v1.10", "v1.9", "v2.0"],
+        "by default, tags are sorted by name"
+    );
+    let v2 = tags.last().expect("present");
+    assert_eq!(v2.id, annotated, "the id is the one of the tag object");
+    assert_eq!(v2.peeled, c3, "peeling leads to the commit");
+
+    let mut options = Options {
+        sort: Sort::Version,
+        ..Default::default()
+    };
+    assert_eq!(
+        tag_names(&repo.list_tags(options.clone())?),
+        ["blob", "v1.0", "v1.0-rc1", "v1.9", "v1.10", "v2.0"],
+        "numbers are compared by value"
+    );
+
+    options.version_sort_suffixes = vec!["-rc".into()];
+    assert_eq!(
+        tag_names(&repo.list_tags(options.clone())?),
+        ["blob", "v1.0-rc1", "v1.0", "v1.9", "v1.10", "v2.0"],
+        "pre-releases are sorted before their release"
+    );
+
+    repo.config_snapshot_mut()
+        .append_config(["versionsort.suffix=-rc"], gix::config::Source::Api)?;
+    assert_eq!(
+        repo.list_tags_options().version_sort_suffixes,
+        options.version_sort_suffixes,
+        "suffixes can be configured"
+    );
+
+    let tags = repo.list_tags(Options {
+        contains: vec![c2],
+        sort: Sort::Version,
+        ..Default::default()
+    })?;
+    assert_eq!(tag_names(&tags), ["v1.10", "v2.0"]);
+
+    let tags = repo.list_tags(Options {
+        contains: vec![c1],
+        no_contains: vec![c2],
+        sort: Sort::Version,
+        ..Default::default()
+    })?;
+    assert_eq!(
+        tag_names(&tags),
+        ["v1.0", "v1.0-rc1", "v1.9"],
+        "tags that don't point to commits are never matched by reachability filters"
+    );
+
+    let tags = repo.list_tags(Options {
+        points_at: vec![c1, c3],
+        ..Default::default()
+    })?;
+    assert_eq!(
+        tag_names(&tags),
+        ["v1.0", "v1.0-rc1", "v2.0"],
+        "annotated tags are peeled once to see what they point at"
+    );
+    let tags = repo.list_tags(Options {
+        points_at: vec![annotated],
+        ..Default::default()
+    })?;
+    assert_eq!(
+        tag_names(&tags),
+        ["v2.0"],
+        "the tag object itself can also be pointed at"
+    );
+    Ok(())
+}
@@ -11,3 +11,236 @@ fn tree_merge_options() -> crate::Result {
     );
     Ok(())
 }
+
+#[test]
+fn tree_merge_options_conflict_style_and_marker_size() -> crate::Result {
+    use gix::merge::blob::builtin_driver::text::{Conflict, ConflictStyle};
+    let repo = named_repo("make_basic_repo.sh")?;
+    let opts: gix::merge::plumbing::tree::Options = repo
+        .tree_merge_options()?
+        .with_conflict_style(ConflictStyle::ZealousDiff3)
+        .with_marker_size(10u8.try_into()?)
+        .into();
+    assert_eq!(
+        opts.blob_merge.text.conflict,
+        Conflict::Keep {
+            style: ConflictStyle::ZealousDiff3,
+            marker_size: 10u8.try_into()?
+        }
+    );
+
+    let opts: gix::merge::plumbing::tree::Options = repo
+        .tree_merge_options()?
+        .with_file_favor(Some(gix::merge::tree::FileFavor::Ours))
+        .with_conflict_style(ConflictStyle::Diff3)
+        .into();
+    assert_eq!(
+        opts.blob_merge.text.conflict,
+        Conflict::ResolveWithOurs,
+        "the file favor always wins as it resolves conflicts"
+    );
+    Ok(())
+}
+
+#[test]
+fn conflict_entries_of_tree_merge() -> crate::Result {
+    use gix::index::entry::{Mode, Stage};
+    use gix::merge::tree::TreatAsUnresolved;
+    use gix::object::tree::EntryKind;
+
+    let tmp = gix_testtools::tempfile::TempDir::new()?;
+    let repo = gix::init_bare(tmp.path())?;
+    let tree_with = |file: &str, other: &str| -> crate::Result<gix::ObjectId> {
+        let mut editor = repo.empty_tree().edit()?;
+        editor.upsert("file", EntryKind::Blob, repo.write_blob(file)?)?;
+        editor.upsert("other", EntryKind::Blob, repo.write_blob(other)?)?;
+        Ok(editor.write()?.detach())
+    };
+    let base = tree_with("base\n", "base\n")?;
+    let ours = tree_with("ours\n", "base\n")?;
+    let theirs = tree_with("theirs\n", "theirs\n")?;
+
+    let labels = gix::merge::blob::builtin_driver::text::Labels {
+        ancestor: Some("base".into()),
+        current: Some("ours".into()),
+        other: Some("theirs".into()),
+    };
+    let mut res = repo.merge_trees(base, ours, theirs, labels, repo.tree_merge_options()?)?;
+    assert!(res.has_unresolved_conflicts(TreatAsUnresolved::Renames));
+    let tree_id = res.tree.write()?;
+
+    let entries = res.conflict_entries(&tree_id, TreatAsUnresolved::Renames)?;
+    assert_eq!(
+        entries.iter().map(|e| (e.path.as_slice(), e.stage)).collect::<Vec<_>>(),
+        [
+            (&b"file"[..], Stage::Base),
+            (b"file", Stage::Ours),
+            (b"file", Stage::Theirs)
+        ],
+        "only the conflicting file is listed, at all of its stages"
+    );
+    assert!(entries.iter().all(|e| e.mode == Mode::FILE));
+    assert_eq!(
+        repo.find_object(entries[1].id)?.data,
+        b"ours\n",
+        "the stages are the original blobs"
+    );
+
+    let unconflicted = repo.merge_trees(base, ours, ours, Default::default(), repo.tree_merge_options()?)?;
+    assert!(
+        unconflicted
+            .conflict_entries(&ours, TreatAsUnresolved::Renames)?
+            .is_empty(),
+        "without conflicts there are no entries"
+    );
+    Ok(())
+}
+
+mod rerere {
+    use gix::bstr::ByteSlice;
+    use gix::index::entry::{Flags, Mode, Stage, Stat};
+    use gix::merge::rerere::Options;
+
+    use crate::util::basic_rw_repo;
+
+    const CONFLICT: &str = "1\n<<<<<<< ours\na\n=======\nb\n>>>>>>> theirs\n3\n";
+    const SWAPPED_CONFLICT: &str = "1\n<<<<<<< HEAD\nb\n=======\na\n>>>>>>> other\n3\n";
+
+    fn conflicted_index(repo: &gix::Repository) -> gix::index::State {
+        let mut index = gix::index::State::new(repo.object_hash());
+        for stage in [Flags::from_stage(Stage::Ours), Flags::from_stage(Stage::Theirs)] {
+            index.dangerously_push_entry(
+                Stat::default(),
+                repo.object_hash().null(),
+                stage,
+                Mode::FILE,
+                "file".into(),
+            );
+        }
+        index.sort_entries();
+        index
+    }
+
+    #[test]
+    fn options_default_to_the_presence_of_rr_cache() -> crate::Result {
+        let (repo, _tmp) = basic_rw_repo()?;
+        let opts = repo.rerere_options()?;
+        assert!(!opts.enabled, "there is no rr-cache yet");
+        assert!(!opts.auto_update);
+
+        std::fs::create_dir(repo.git_dir().join("rr-cache"))?;
+        assert!(repo.rerere_options()?.enabled, "now it exists");
+        Ok(())
+    }
+
+    #[test]
+    fn record_and_reuse_resolution() -> crate::Result {
+        let (repo, _tmp) = basic_rw_repo()?;
+        let options = Options {
+            enabled: true,
+            auto_update: true,
+        };
+        let path = repo.work_dir().expect("non-bare").join("file");
+        let merge_rr = repo.git_dir().join("MERGE_RR");
+
+        std::fs::write(&path, CONFLICT)?;
+        let mut index = conflicted_index(&repo);
+        let outcome = repo.rerere(&mut index, options)?;
+        assert_eq!(outcome.recorded_preimages, ["file"]);
+        assert!(outcome.resolved.is_empty());
+        assert!(merge_rr.is_file(), "the conflict awaits its resolution");
+
+        std::fs::write(&path, "1\na and b\n3\n")?;
+        let outcome = repo.rerere(&mut index, options)?;
+        assert_eq!(outcome.recorded_resolutions, ["file"]);
+        assert!(outcome.recorded_preimages.is_empty(), "the conflict is already known");
+        assert!(!merge_rr.exists(), "nothing is left to resolve");
+
+        std::fs::write(&path, SWAPPED_CONFLICT)?;
+        let outcome = repo.rerere(&mut index, options)?;
+        assert_eq!(
+            outcome.resolved,
+            ["file"],
+            "the same conflict is detected even if sides are swapped"
+        );
+        assert_eq!(outcome.staged, ["file"]);
+        assert_eq!(std::fs::read(&path)?.as_bstr(), "1\na and b\n3\n");
+        assert_eq!(index.entries().len(), 1, "the conflict was replaced");
+        let entry = &index.entries()[0];
+        assert_eq!(entry.stage(), Stage::Unconflicted);
+        assert_eq!(repo.find_object(entry.id)?.data.as_bstr(), "1\na and b\n3\n");
+        Ok(())
+    }
+
+    #[test]
+    fn recorded_resolutions_are_replayed_during_merges() -> crate::Result {
+        use gix::merge::tree::TreatAsUnresolved;
+        use gix::object::tree::EntryKind;
+
+        let (repo, _tmp) = basic_rw_repo()?;
+        let tree_with = |content: &str| -> crate::Result<gix::ObjectId> {
+            let mut editor = repo.empty_tree().edit()?;
+            editor.upsert("file", EntryKind::Blob, repo.write_blob(content)?)?;
+            Ok(editor.write()?.detach())
+        };
+        let (base, ours, theirs) = (
+            tree_with("1\nx\n3\n")?,
+            tree_with("1\na\n3\n")?,
+            tree_with("1\nb\n3\n")?,
+        );
+        let labels = gix::merge::blob::builtin_driver::text::Labels {
+            ancestor: None,
+            current: Some("ours".into()),
+            other: Some("theirs".into()),
+        };
+
+        let res = repo.merge_trees(base, ours, theirs, labels, repo.tree_merge_options()?)?;
+        assert!(
+            res.has_unresolved_conflicts(TreatAsUnresolved::Renames),
+            "nothing was recorded yet"
+        );
+
+        let options = Options {
+            enabled: true,
+            auto_update: false,
+        };
+        let path = repo.work_dir().expect("non-bare").join("file");
+        std::fs::write(&path, CONFLICT)?;
+        let mut index = conflicted_index(&repo);
+        repo.rerere(&mut index, options)?;
+        std::fs::write(&path, "1\na and b\n3\n")?;
+        assert_eq!(repo.rerere(&mut index, options)?.recorded_resolutions, ["file"]);
+
+        let repo = gix::open(repo.git_dir())?;
+        let mut res = repo.merge_trees(base, ours, theirs, labels, repo.tree_merge_options()?)?;
+        assert!(
+            !res.has_unresolved_conflicts(TreatAsUnresolved::Renames),
+            "the recorded resolution is used as rr-cache exists, which enables rerere"
+        );
+        assert_eq!(res.conflicts.len(), 1, "the resolved conflict is still listed");
+        let tree = repo.find_tree(res.tree.write()?)?;
+        let entry = tree.find_entry("file").expect("present");
+        assert_eq!(repo.find_object(entry.oid())?.data.as_bstr(), "1\na and b\n3\n");
+
+        let res = repo.merge_trees(
+            base,
+            ours,
+            theirs,
+            labels,
+            repo.tree_merge_options()?.with_rerere(false),
+        )?;
+        assert!(res.has_unresolved_conflicts(TreatAsUnresolved::Renames));
+        Ok(())
+    }
+
+    #[test]
+    fn disabled_does_nothing() -> crate::Result {
+        let (repo, _tmp) = basic_rw_repo()?;
+        std::fs::write(repo.work_dir().expect("non-bare").join("file"), CONFLICT)?;
+        let mut index = conflicted_index(&repo);
+        let outcome = repo.rerere(&mut index, Options::default())?;
+        assert!(outcome.recorded_preimages.is_empty());
+        assert!(!repo.git_dir().join("rr-cache").exists());
+        Ok(())
+    }
+}
@@ -0,0 +1,88 @@
+use gix::{bstr::ByteSlice, refs::store::Format};
+
+use crate::util::{repo_rw, restricted};
+
+/// The name, target and peeled id of a reference.
+type Ref = (String, gix::refs::Target, gix::ObjectId);
+
+/// All references with their targets and the ids they peel to, along with the amount of entries in the log of `HEAD`.
+fn refs_and_head_log(repo: &gix::Repository) -> crate::Result<(Vec<Ref>, usize)> {
+    let mut references = vec![repo.find_reference("HEAD")?];
+    let platform = repo.references()?;
+    for r in platform.all()? {
+        references.push(r?);
+    }
+    let mut refs = Vec::new();
+    for mut r in references {
+        let target = r.target().into_owned();
+        refs.push((
+            r.name().as_bstr().to_string(),
+            target,
+            r.peel_to_id_in_place()?.detach(),
+        ));
+    }
+    let head_log = repo.find_reference("HEAD")?.log_iter().all()?.expect("present").count();
+    Ok((refs, head_log))
+}
+
+#[test]
+fn files_to_reftable_and_back() -> crate::Result {
+    let (mut repo, _tmp) = repo_rw("make_commit_describe_multiple_tags.sh")?;
+    assert_eq!(repo.refs.format(), Format::Files);
+    let expected = refs_and_head_log(&repo)?;
+
+    let outcome = repo.migrate_refs(
+        Format::Reftable,
+        gix::repository::migrate_refs::Options { dry_run: true },
+    )?;
+    assert_eq!(outcome.refs, expected.0.len());
+    assert!(
+        !repo.git_dir().join("reftable").exists(),
+        "dry runs don't write anything"
+    );
+
+    let outcome = repo.migrate_refs(Format::Reftable, Default::default())?;
+    assert_eq!(outcome.refs, expected.0.len(), "HEAD, main and all tags");
+    assert_eq!(
+        refs_and_head_log(&repo)?,
+        expected,
+        "the instance uses the new format right away"
+    );
+    let git_dir = repo.git_dir().to_owned();
+    assert_eq!(std::fs::read(git_dir.join("HEAD"))?, b"ref: refs/heads/.invalid\n");
+    assert!(git_dir.join("refs").join("heads").is_file());
+    assert!(!git_dir.join("logs").exists() && !git_dir.join("packed-refs").exists());
+    if !gix_testtools::should_skip_as_git_version_is_smaller_than(2, 45, 0) {
+        let out = std::process::Command::new(gix::path::env::exe_invocation())
+            .args(["for-each-ref", "--format=%(refname)"])
+            .current_dir(&git_dir)
+            .output()?;
+        assert!(out.status.success(), "{out:?}");
+        assert_eq!(
+            out.stdout.lines().count(),
+            expected.0.len() - 1,
+            "git can read the tables, which list all references but HEAD"
+        );
+    }
+
+    let mut repo = gix::open_opts(&git_dir, restricted())?;
+    assert_eq!(
+        repo.refs.format(),
+        Format::Reftable,
+        "`extensions.refStorage` is detected on open"
+    );
+    assert_eq!(refs_and_head_log(&repo)?, expected);
+    assert!(matches!(
+        repo.migrate_refs(Format::Reftable, Default::default()),
+        Err(gix::repository::migrate_refs::Error::SameFormat { .. })
+    ));
+
+    let outcome = repo.migrate_refs(Format::Files, Default::default())?;
+    assert_eq!(outcome.refs, expected.0.len());
+    assert!(!git_dir.join("reftable").exists());
+
+    let repo = gix::open_opts(&git_dir, restricted())?;
+    assert_eq!(repo.refs.format(), Format::Files);
+    assert_eq!(refs_and_head_log(&repo)?, expected);
+    Ok(())
+}
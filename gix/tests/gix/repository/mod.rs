@@ -1,23 +1,38 @@
 use gix::Repository;
 
+mod branch_tracking;
 mod config;
 #[cfg(feature = "excludes")]
 mod excludes;
+mod fast_import;
 #[cfg(feature = "attributes")]
 mod filter;
+#[cfg(feature = "attributes")]
+mod grep;
+mod history;
+mod journal;
+mod list_tags;
 #[cfg(feature = "merge")]
 mod merge;
+mod migrate_refs;
 mod object;
 mod open;
 #[cfg(feature = "attributes")]
 mod pathspec;
 mod reference;
 mod remote;
+#[cfg(feature = "merge")]
+mod replay;
 mod shallow;
+#[cfg(feature = "mailmap")]
+mod shortlog;
+#[cfg(feature = "worktree-mutation")]
+mod sparse_checkout;
 mod state;
 #[cfg(feature = "attributes")]
 mod submodule;
 mod worktree;
+mod write_commit_graph;
 
 #[cfg(feature = "index")]
 mod index {
@@ -97,6 +112,220 @@ mod dirwalk {
     }
 }
 
+#[cfg(feature = "status")]
+mod index_from_worktree {
+    use std::sync::atomic::AtomicBool;
+
+    #[test]
+    fn files_are_added_and_excludes_are_honored() -> crate::Result {
+        let tmp = gix_testtools::tempfile::TempDir::new()?;
+        gix::init(tmp.path())?;
+        std::fs::write(tmp.path().join(".gitignore"), "ignored\n")?;
+        std::fs::write(tmp.path().join("a"), "a\n")?;
+        std::fs::write(tmp.path().join("ignored"), "ignored\n")?;
+        std::fs::create_dir_all(tmp.path().join("dir/empty"))?;
+        std::fs::write(tmp.path().join("dir/b"), "b\n")?;
+        gix::init(tmp.path().join("nested"))?;
+        let repo = gix::open_opts(tmp.path(), crate::restricted())?;
+
+        let outcome = repo.index_from_worktree(None, &AtomicBool::default())?;
+        assert_eq!(outcome.skipped_repositories, ["nested"]);
+        assert_eq!(outcome.index.path(), repo.index_path());
+        assert_eq!(
+            outcome
+                .index
+                .entries()
+                .iter()
+                .map(|e| e.path(&outcome.index).to_string())
+                .collect::<Vec<_>>(),
+            [".gitignore", "a", "dir/b"],
+            "ignored files, empty directories and nested repositories aren't added"
+        );
+        for entry in outcome.index.entries() {
+            let path = entry.path(&outcome.index).to_string();
+            assert_eq!(
+                repo.find_blob(entry.id)?.data,
+                std::fs::read(tmp.path().join(&path))?,
+                "all files are written to the object database"
+            );
+        }
+
+        let index_from_tree = repo.index_from_tree(&outcome.tree_id)?;
+        assert_eq!(
+            index_from_tree
+                .entries()
+                .iter()
+                .map(|e| (e.path(&index_from_tree).to_owned(), e.id, e.mode))
+                .collect::<Vec<_>>(),
+            outcome
+                .index
+                .entries()
+                .iter()
+                .map(|e| (e.path(&outcome.index).to_owned(), e.id, e.mode))
+                .collect::<Vec<_>>(),
+            "the tree has the same content as the index"
+        );
+        Ok(())
+    }
+}
+
+#[cfg(feature = "status")]
+mod add_to_index {
+    use std::sync::atomic::AtomicBool;
+
+    use gix::bstr::BString;
+
+    fn paths(index: &gix::index::State) -> Vec<String> {
+        index.entries().iter().map(|e| e.path(index).to_string()).collect()
+    }
+
+    #[test]
+    fn files_matching_pathspecs_are_added_updated_and_removed() -> crate::Result {
+        let tmp = gix_testtools::tempfile::TempDir::new()?;
+        gix::init(tmp.path())?;
+        std::fs::write(tmp.path().join(".gitignore"), "ignored\n")?;
+        std::fs::write(tmp.path().join("a"), "a\n")?;
+        std::fs::write(tmp.path().join("b"), "b\n")?;
+        std::fs::write(tmp.path().join("ignored"), "ignored\n")?;
+        std::fs::create_dir(tmp.path().join("dir"))?;
+        std::fs::write(tmp.path().join("dir/c"), "c\n")?;
+        gix::init(tmp.path().join("nested"))?;
+        let mut repo = gix::open_opts(tmp.path(), crate::restricted())?;
+        // Files written in the same second as the index was created aren't racy this way.
+        repo.config_snapshot_mut()
+            .set_value(&gix::config::tree::gitoxide::Core::USE_NSEC, "true")?;
+
+        let mut index = gix::index::State::new(repo.object_hash());
+        let none = AtomicBool::default();
+        let out = repo.add_to_index(&mut index, None::<&str>, None, &none)?;
+        assert_eq!(out.added, [".gitignore", "a", "b", "dir/c"]);
+        assert_eq!(out.skipped_repositories, ["nested"]);
+        assert!(out.updated.is_empty() && out.removed.is_empty());
+        assert_eq!(paths(&index), [".gitignore", "a", "b", "dir/c"]);
+        for entry in index.entries() {
+            assert_eq!(
+                repo.find_blob(entry.id)?.data,
+                std::fs::read(tmp.path().join(entry.path(&index).to_string()))?,
+                "all files are written to the object database"
+            );
+        }
+
+        std::fs::write(tmp.path().join("a"), "changed\n")?;
+        std::fs::remove_file(tmp.path().join("b"))?;
+        std::fs::write(tmp.path().join("dir/d"), "d\n")?;
+        std::fs::write(tmp.path().join("e"), "e\n")?;
+        let out = repo.add_to_index(&mut index, ["a", "b", "dir"], None, &none)?;
+        assert_eq!(out.added, ["dir/d"], "only matching untracked files are added");
+        assert_eq!(out.updated, ["a"], "unchanged files aren't hashed again");
+        assert_eq!(out.removed, ["b"], "deleted files are removed");
+        assert_eq!(paths(&index), [".gitignore", "a", "dir/c", "dir/d"]);
+        let a = index.entry_by_path("a".into()).expect("present");
+        assert_eq!(
+            a.id,
+            gix::objs::compute_hash(repo.object_hash(), gix::object::Kind::Blob, b"changed\n")
+        );
+
+        let out = repo.add_to_index(&mut index, [BString::from("*")], None, &none)?;
+        assert_eq!(out.added, ["e"], "globs match untracked files as well");
+        assert!(out.removed.is_empty());
+        assert_eq!(paths(&index), [".gitignore", "a", "dir/c", "dir/d", "e"]);
+        Ok(())
+    }
+
+    #[test]
+    fn conflicts_are_resolved_with_the_worktree_version() -> crate::Result {
+        use gix::index::entry::{Flags, Mode, Stage};
+
+        let tmp = gix_testtools::tempfile::TempDir::new()?;
+        gix::init(tmp.path())?;
+        std::fs::write(tmp.path().join("a"), "resolved\n")?;
+        let repo = gix::open_opts(tmp.path(), crate::restricted())?;
+
+        let mut index = gix::index::State::new(repo.object_hash());
+        let base = repo.write_blob("base\n")?.detach();
+        for stage in [Stage::Base, Stage::Ours, Stage::Theirs] {
+            index.dangerously_push_entry(
+                Default::default(),
+                base,
+                Flags::from_stage(stage),
+                Mode::FILE,
+                "a".into(),
+            );
+        }
+        let out = repo.add_to_index(&mut index, ["a"], None, &AtomicBool::default())?;
+        assert_eq!(out.updated, ["a"], "conflicts are always updated");
+        assert_eq!(paths(&index), ["a"], "all stages were replaced by the worktree version");
+        assert_eq!(index.entry(0).stage(), Stage::Unconflicted);
+        assert_eq!(
+            index.resolve_undo().map(Vec::len),
+            Some(1),
+            "the conflict can be restored"
+        );
+        Ok(())
+    }
+}
+
+#[cfg(feature = "status")]
+mod hash_worktree_files {
+    use std::sync::atomic::AtomicBool;
+
+    use gix::repository::hash_worktree_files::Options;
+
+    #[test]
+    fn ids_are_returned_in_input_order_and_objects_are_written_on_request() -> crate::Result {
+        let tmp = gix_testtools::tempfile::TempDir::new()?;
+        gix::init(tmp.path())?;
+        std::fs::write(tmp.path().join("small"), "a\n")?;
+        std::fs::write(tmp.path().join("large"), "b\n".repeat(1000))?;
+        std::fs::write(tmp.path().join(".gitattributes"), "crlf text eol=lf\n")?;
+        std::fs::write(tmp.path().join("crlf"), "c\r\n")?;
+        let repo = gix::open_opts(tmp.path(), crate::restricted())?;
+
+        let paths = ["small", "large", "crlf"];
+        let options = Options {
+            mmap_threshold: 100,
+            ..Default::default()
+        };
+        let entries = repo.hash_worktree_files(paths.map(Into::into), options, &AtomicBool::default())?;
+        assert_eq!(
+            entries.iter().map(|e| e.rela_path.to_string()).collect::<Vec<_>>(),
+            paths,
+            "order is retained even though larger files are processed first"
+        );
+        let hash = |data: &[u8]| gix::objs::compute_hash(repo.object_hash(), gix::object::Kind::Blob, data);
+        assert_eq!(entries[0].id, hash(b"a\n"));
+        assert_eq!(
+            entries[1].id,
+            hash("b\n".repeat(1000).as_bytes()),
+            "large files are mapped"
+        );
+        assert_eq!(entries[2].id, hash(b"c\n"), "the filter pipeline is applied");
+        assert!(
+            !repo.has_object(entries[0].id),
+            "by default, objects are only hashed but not written"
+        );
+
+        let entries = repo.hash_worktree_files(
+            paths.map(Into::into),
+            Options {
+                write_objects: true,
+                ..options
+            },
+            &AtomicBool::default(),
+        )?;
+        assert!(entries.iter().all(|e| repo.has_object(e.id)));
+
+        let err = repo
+            .hash_worktree_files(Some(".git".into()), options, &AtomicBool::default())
+            .unwrap_err();
+        assert!(
+            matches!(err, gix::repository::hash_worktree_files::Error::UnsupportedKind { .. }),
+            "directories can't be hashed"
+        );
+        Ok(())
+    }
+}
+
 #[test]
 fn size_in_memory() {
     let actual_size = std::mem::size_of::<Repository>();
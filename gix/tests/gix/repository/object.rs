@@ -398,6 +398,44 @@ mod find {
 
     use crate::basic_repo;
 
+    #[test]
+    #[cfg(feature = "pack-cache-lru-dynamic")]
+    fn pack_cache_size_and_statistics() -> crate::Result {
+        let mut repo = crate::named_repo("make_delta_repo.sh")?;
+        repo.pack_cache_size(None);
+        assert_eq!(repo.pack_cache_statistics(), None, "there is no cache to ask");
+
+        repo.pack_cache_size(64 * 1024);
+        assert_eq!(
+            repo.pack_cache_statistics(),
+            Some(gix::odb::pack::cache::Statistics::default()),
+            "a new cache starts without any statistics"
+        );
+        let first_version = repo.rev_parse_single("@~1:file")?.detach();
+        for _ in 0..2 {
+            assert_eq!(
+                repo.find_object(first_version)?.data.len(),
+                3893,
+                "it's a delta against the second version"
+            );
+        }
+        assert_eq!(
+            repo.pack_cache_statistics(),
+            Some(gix::odb::pack::cache::Statistics {
+                hits: 1,
+                misses: 1,
+                puts: 1
+            }),
+            "the delta is resolved once and then retrieved from the cache"
+        );
+        assert_eq!(
+            repo.clone().pack_cache_statistics(),
+            Some(Default::default()),
+            "clones have their own cache"
+        );
+        Ok(())
+    }
+
     #[test]
     fn find_and_try_find_with_and_without_object_cache() -> crate::Result {
         let mut repo = basic_repo()?;
@@ -407,17 +445,22 @@ mod find {
             0,
             "it's OK to query linked worktrees in a repo without worktrees"
         );
-        for round in 1..=2 {
+        let shared_cache = gix::odb::pack::cache::object::SharedMemoryCappedHashmap::new(
+            128 * 1024,
+            std::num::NonZeroUsize::new(4).expect("non-zero"),
+        );
+        for round in 1..=3 {
             match round {
                 1 => repo.object_cache_size(None),
                 2 => repo.object_cache_size(128 * 1024),
+                3 => repo.set_shared_object_cache(shared_cache.clone()),
                 _ => unreachable!("BUG"),
             }
             for commit_id in repo.head()?.into_peeled_id()?.ancestors().all()? {
                 let commit = commit_id?;
                 assert_eq!(commit.id().object()?.kind, gix_object::Kind::Commit);
                 assert_eq!(commit.id().header()?.kind(), gix_object::Kind::Commit);
-                if round == 2 {
+                if round >= 2 {
                     assert_eq!(
                         commit.id().object()?.kind,
                         gix_object::Kind::Commit,
@@ -434,6 +477,26 @@ mod find {
                 );
             }
         }
+
+        let stats_of_first_handle = shared_cache.statistics();
+        assert!(stats_of_first_handle.puts > 0, "the first handle filled the cache");
+
+        let mut separately_opened = basic_repo()?;
+        separately_opened.set_shared_object_cache(shared_cache.clone());
+        let mut num_commits = 0;
+        for commit_id in separately_opened.head()?.into_peeled_id()?.ancestors().all()? {
+            assert_eq!(commit_id?.id().object()?.kind, gix_object::Kind::Commit);
+            num_commits += 1;
+        }
+        let stats = shared_cache.statistics();
+        assert_eq!(
+            stats.misses, stats_of_first_handle.misses,
+            "all objects were decoded by the first handle, so the second one never misses"
+        );
+        assert!(
+            stats.hits >= stats_of_first_handle.hits + num_commits,
+            "the second handle gets its objects from what the first handle put into the shared cache"
+        );
         Ok(())
     }
 
@@ -505,6 +568,363 @@ mod tag {
         assert_eq!(tag.message, message);
         Ok(())
     }
+
+    #[test]
+    fn create_tag_verifies_target_and_keeps_signature() -> crate::Result {
+        let (repo, _keep) = crate::repo_rw("make_basic_repo.sh")?;
+        let current_head_id = repo.head_id()?.detach();
+        let mut tag = gix_object::Tag::new(
+            current_head_id,
+            gix_object::Kind::Commit,
+            "v1.0.0",
+            Some(repo.committer().expect("present")?.into()),
+            "message",
+        )?;
+        let signature = "-----BEGIN PGP SIGNATURE-----\nsig\n-----END PGP SIGNATURE-----\n";
+        let signed_data = tag.signed_data()?;
+        tag.pgp_signature = Some(signature.into());
+
+        let tag_ref = repo.create_tag(&tag, gix_ref::transaction::PreviousValue::MustNotExist)?;
+        assert_eq!(tag_ref.name().as_bstr(), "refs/tags/v1.0.0");
+        let tag_object = tag_ref.id().object()?.into_tag();
+        assert_eq!(tag_object.decode()?.message, "message\n");
+        let (actual_signature, actual_signed_data) = tag_object.signature()?.expect("signed");
+        assert_eq!(actual_signature, signature);
+        assert_eq!(actual_signed_data, signed_data);
+
+        tag.message = "other message".into();
+        assert!(
+            matches!(
+                repo.create_tag(&tag, gix_ref::transaction::PreviousValue::MustNotExist),
+                Err(gix::tag::Error::ReferenceEdit(_))
+            ),
+            "the constraint is respected"
+        );
+
+        tag.name = "v2".into();
+        tag.target_kind = gix_object::Kind::Tree;
+        assert!(matches!(
+            repo.create_tag(&tag, gix_ref::transaction::PreviousValue::MustNotExist),
+            Err(gix::tag::Error::TargetKindMismatch {
+                actual: gix_object::Kind::Commit,
+                ..
+            })
+        ));
+
+        tag.target = repo.object_hash().null();
+        assert!(matches!(
+            repo.create_tag(&tag, gix_ref::transaction::PreviousValue::MustNotExist),
+            Err(gix::tag::Error::FindTarget(_))
+        ));
+        assert!(
+            repo.try_find_reference("refs/tags/v2")?.is_none(),
+            "nothing is written on error"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn verify_signature_with_keyring() -> crate::Result {
+        use gix::keyring::{Format, Keyring, Outcome, Status, Trust};
+
+        struct Fake;
+        impl Keyring for Fake {
+            fn verify(
+                &self,
+                format: Format,
+                signature: &gix::bstr::BStr,
+                payload: &gix::bstr::BStr,
+            ) -> Result<Outcome, gix::keyring::Error> {
+                assert_eq!(format, Format::OpenPgp);
+                assert_eq!(
+                    signature,
+                    "-----BEGIN PGP SIGNATURE-----\nsig\n-----END PGP SIGNATURE-----\n"
+                );
+                Ok(Outcome {
+                    status: Status::Good,
+                    signer: Some("signer".into()),
+                    key: Some(format!("{} bytes", payload.len()).into()),
+                    trust: Trust::Marginal,
+                    output: Default::default(),
+                })
+            }
+        }
+
+        let (repo, _keep) = crate::repo_rw("make_basic_repo.sh")?;
+        let head = repo.head_commit()?;
+        assert!(
+            head.verify_signature(&Fake)?.is_none(),
+            "unsigned objects aren't verified"
+        );
+
+        let mut tag = gix_object::Tag::new(
+            head.id,
+            gix_object::Kind::Commit,
+            "signed",
+            Some(repo.committer().expect("present")?.into()),
+            "message",
+        )?;
+        let signed_data = tag.signed_data()?;
+        tag.pgp_signature = Some("-----BEGIN PGP SIGNATURE-----\nsig\n-----END PGP SIGNATURE-----\n".into());
+        let tag = repo.create_tag(&tag, gix_ref::transaction::PreviousValue::MustNotExist)?;
+        let verification = tag.id().object()?.into_tag().verify_signature(&Fake)?.expect("signed");
+        assert_eq!(verification.format, Format::OpenPgp);
+        assert_eq!(
+            verification.payload_digest,
+            gix_object::compute_hash(repo.object_hash(), gix_object::Kind::Blob, &signed_data),
+            "the digest is the blob-id of the signed data"
+        );
+        assert_eq!(
+            verification.outcome.key,
+            Some(format!("{} bytes", signed_data.len()).into())
+        );
+        assert!(verification.is_trusted(Trust::Marginal));
+        assert!(!verification.is_trusted(Trust::Fully));
+        Ok(())
+    }
+}
+
+#[cfg(feature = "command")]
+mod sign {
+    use gix::{
+        config::tree::{gpg, Gpg, User},
+        keyring::{Format, SigningProgram, SshSigningKey, Status, Trust},
+    };
+
+    #[test]
+    fn signer_from_configuration() -> crate::Result {
+        let (mut repo, _keep) =
+            crate::util::repo_rw_opts("make_basic_repo.sh", crate::restricted().strict_config(true))?;
+        let SigningProgram::Gpg(signer) = repo.signer()? else {
+            panic!("OpenPGP is the default format")
+        };
+        assert_eq!(signer.program, "gpg");
+        let committer = repo.committer().expect("set")?;
+        assert_eq!(
+            signer.key,
+            format!("{} <{}>", committer.name, committer.email),
+            "without signing key, the committer identity is used"
+        );
+
+        repo.config_snapshot_mut().set_value(&Gpg::FORMAT, "ssh")?;
+        assert!(
+            matches!(
+                repo.signer(),
+                Err(gix::repository::keyring::Error::MissingSigningKey { format: Format::Ssh })
+            ),
+            "SSH signatures always need a key"
+        );
+
+        let mut config = repo.config_snapshot_mut();
+        config.set_value(&User::SIGNING_KEY, "key::ssh-ed25519 AAAAkey")?;
+        config.set_value(&gpg::Ssh::PROGRAM, "custom-ssh-keygen")?;
+        config.commit()?;
+        let SigningProgram::Ssh(signer) = repo.signer()? else {
+            panic!("configured format")
+        };
+        assert_eq!(signer.program, "custom-ssh-keygen", "`gpg.ssh.program` is respected");
+        assert_eq!(signer.key, SshSigningKey::Literal("ssh-ed25519 AAAAkey".into()));
+
+        repo.config_snapshot_mut()
+            .set_value(&User::SIGNING_KEY, "keys/id_ed25519.pub")?;
+        let SigningProgram::Ssh(signer) = repo.signer()? else {
+            panic!("configured format")
+        };
+        assert_eq!(
+            signer.key,
+            SshSigningKey::Path("keys/id_ed25519.pub".into()),
+            "everything else is a path"
+        );
+
+        repo.config_snapshot_mut().set_value(&Gpg::FORMAT, "unknown")?;
+        assert!(matches!(
+            repo.signer(),
+            Err(gix::repository::keyring::Error::Format { .. })
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn signer_with_ssh_default_key_command() -> crate::Result {
+        let (mut repo, _keep) =
+            crate::util::repo_rw_opts("make_basic_repo.sh", crate::restricted().strict_config(true))?;
+        let mut config = repo.config_snapshot_mut();
+        config.set_value(&Gpg::FORMAT, "ssh")?;
+        config.set_value(
+            &gpg::Ssh::DEFAULT_KEY_COMMAND,
+            "printf 'ssh-ed25519 AAAAfirst\nssh-ed25519 AAAAsecond\n'",
+        )?;
+        config.commit()?;
+        let SigningProgram::Ssh(signer) = repo.signer()? else {
+            panic!("configured format")
+        };
+        assert_eq!(
+            signer.key,
+            SshSigningKey::Literal("ssh-ed25519 AAAAfirst".into()),
+            "the first key is used if `user.signingKey` is unset"
+        );
+
+        repo.config_snapshot_mut()
+            .set_value(&User::SIGNING_KEY, "key::ssh-ed25519 AAAAkey")?;
+        let SigningProgram::Ssh(signer) = repo.signer()? else {
+            panic!("configured format")
+        };
+        assert_eq!(
+            signer.key,
+            SshSigningKey::Literal("ssh-ed25519 AAAAkey".into()),
+            "the signing key has precedence"
+        );
+
+        let mut config = repo.config_snapshot_mut();
+        config.set_value(&User::SIGNING_KEY, "")?;
+        config.set_value(&gpg::Ssh::DEFAULT_KEY_COMMAND, "echo not-a-key")?;
+        config.commit()?;
+        assert!(matches!(
+            repo.signer(),
+            Err(gix::repository::keyring::Error::DefaultKeyCommandNoKey { .. })
+        ));
+
+        repo.config_snapshot_mut()
+            .set_value(&gpg::Ssh::DEFAULT_KEY_COMMAND, "exit 1")?;
+        assert!(matches!(
+            repo.signer(),
+            Err(gix::repository::keyring::Error::DefaultKeyCommandFailed { .. })
+        ));
+        Ok(())
+    }
+
+    /// Return a repository configured to sign with a new SSH key, and to verify signatures made with it.
+    fn repo_with_ssh_signing_key() -> crate::Result<(gix::Repository, gix_testtools::tempfile::TempDir)> {
+        let (mut repo, tmp) = crate::repo_rw("make_basic_repo.sh")?;
+        let key = tmp.path().join("key");
+        let status = std::process::Command::new("ssh-keygen")
+            .args(["-q", "-t", "ed25519", "-N", "", "-C", "", "-f"])
+            .arg(&key)
+            .status()?;
+        assert!(status.success(), "ssh-keygen can create keys");
+        let allowed_signers = tmp.path().join("allowed_signers");
+        let public_key = std::fs::read_to_string(key.with_extension("pub"))?;
+        std::fs::write(&allowed_signers, format!("signer@example.com {public_key}"))?;
+
+        let mut config = repo.config_snapshot_mut();
+        config.set_value(&Gpg::FORMAT, "ssh")?;
+        config.set_value(&User::SIGNING_KEY, gix::path::into_bstr(key.as_path()).as_ref())?;
+        config.set_value(
+            &gpg::Ssh::ALLOWED_SIGNERS_FILE,
+            gix::path::into_bstr(allowed_signers.as_path()).as_ref(),
+        )?;
+        config.commit()?;
+        Ok((repo, tmp))
+    }
+
+    #[test]
+    fn sign_with_ssh_key_and_verify_with_allowed_signers() -> crate::Result {
+        let (repo, _tmp) = repo_with_ssh_signing_key()?;
+        let signer = repo.signer()?;
+        let keyring = repo.keyring()?;
+
+        let head = repo.head_commit()?;
+        let mut commit = gix_object::Commit::from(head.decode()?);
+        commit.message = "signed".into();
+        gix::keyring::sign_commit(&mut commit, &signer)?;
+        let signed = repo.write_object(&commit)?.object()?.into_commit();
+        let verification = signed.verify_signature(&keyring)?.expect("signed");
+        assert_eq!(verification.format, Format::Ssh);
+        assert_eq!(verification.outcome.status, Status::Good);
+        assert_eq!(verification.outcome.signer, Some("signer@example.com".into()));
+        assert!(verification.is_trusted(Trust::Fully));
+
+        gix::keyring::sign_commit(&mut commit, &signer)?;
+        assert_eq!(
+            commit
+                .extra_headers
+                .iter()
+                .filter(|(name, _)| *name == "gpgsig")
+                .count(),
+            1,
+            "signing again replaces the previous signature"
+        );
+
+        let mut tag = gix_object::Tag::new(
+            head.id,
+            gix_object::Kind::Commit,
+            "signed",
+            Some(repo.committer().expect("present")?.into()),
+            "message",
+        )?;
+        gix::keyring::sign_tag(&mut tag, &signer)?;
+        let tag = repo.create_tag(&tag, gix_ref::transaction::PreviousValue::MustNotExist)?;
+        let verification = tag
+            .id()
+            .object()?
+            .into_tag()
+            .verify_signature(&keyring)?
+            .expect("signed");
+        assert_eq!(verification.outcome.status, Status::Good);
+
+        commit.message = "tampered".into();
+        let tampered = repo.write_object(&commit)?.object()?.into_commit();
+        assert_eq!(
+            tampered.verify_signature(&keyring)?.expect("signed").outcome.status,
+            Status::Bad
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn commits_and_tags_are_signed_if_configured() -> crate::Result {
+        let (mut repo, _tmp) = repo_with_ssh_signing_key()?;
+        let keyring = repo.keyring()?;
+
+        let head = repo.head_commit()?;
+        let (head_id, head_tree) = (head.id, head.tree_id()?.detach());
+        drop(head);
+        let id = repo.commit("HEAD", "unsigned", head_tree, Some(head_id))?.detach();
+        assert_eq!(
+            repo.find_commit(id)?.verify_signature(&keyring)?,
+            None,
+            "commits aren't signed by default"
+        );
+        let tag = repo.tag(
+            "unsigned",
+            id,
+            gix_object::Kind::Commit,
+            None,
+            "message",
+            gix_ref::transaction::PreviousValue::MustNotExist,
+        )?;
+        assert_eq!(tag.id().object()?.into_tag().verify_signature(&keyring)?, None);
+
+        drop(tag);
+        let mut config = repo.config_snapshot_mut();
+        config.set_value(&gix::config::tree::Commit::GPG_SIGN, "true")?;
+        config.set_value(&gix::config::tree::Tag::GPG_SIGN, "true")?;
+        config.commit()?;
+        let id = repo
+            .commit("HEAD", "signed by configuration", head_tree, Some(id))?
+            .detach();
+        let verification = repo
+            .find_commit(id)?
+            .verify_signature(&keyring)?
+            .expect("`commit.gpgSign` signs commits");
+        assert_eq!(verification.outcome.status, Status::Good);
+        let tag = repo.tag(
+            "signed-by-configuration",
+            id,
+            gix_object::Kind::Commit,
+            None,
+            "message",
+            gix_ref::transaction::PreviousValue::MustNotExist,
+        )?;
+        let verification = tag
+            .id()
+            .object()?
+            .into_tag()
+            .verify_signature(&keyring)?
+            .expect("`tag.gpgSign` signs annotated tags");
+        assert_eq!(verification.outcome.status, Status::Good);
+        Ok(())
+    }
 }
 
 mod commit_as {
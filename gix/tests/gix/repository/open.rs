@@ -51,6 +51,56 @@ fn on_root_with_decomposed_unicode() -> crate::Result {
     Ok(())
 }
 
+#[test]
+fn reftable_ref_storage() -> crate::Result {
+    let tmp = gix_testtools::tempfile::TempDir::new()?;
+    let git_dir = gix::init(tmp.path())?.git_dir().to_owned();
+    let mut config = std::fs::read(git_dir.join("config"))?;
+    config.extend_from_slice(b"[core]\n\trepositoryFormatVersion = 1\n[extensions]\n\trefStorage = reftable\n");
+    config.extend_from_slice(b"[user]\n\tname = committer\n\temail = committer@example.com\n");
+    std::fs::write(git_dir.join("config"), config)?;
+    // Lay out the directory like git does, to keep older versions of git away.
+    std::fs::write(git_dir.join("HEAD"), "ref: refs/heads/.invalid\n")?;
+    std::fs::remove_dir(git_dir.join("refs").join("heads"))?;
+    std::fs::write(
+        git_dir.join("refs").join("heads"),
+        "this repository uses the reftable format\n",
+    )?;
+    std::fs::create_dir(git_dir.join("reftable"))?;
+    std::fs::write(git_dir.join("reftable").join("tables.list"), "")?;
+
+    let repo = gix::discover(tmp.path())?;
+    assert_eq!(
+        repo.refs.format(),
+        gix_ref::store::Format::Reftable,
+        "the placeholder HEAD doesn't prevent discovery"
+    );
+    assert!(repo.try_find_reference("HEAD")?.is_none(), "there is no HEAD yet");
+
+    repo.edit_reference(gix_ref::transaction::RefEdit {
+        change: gix_ref::transaction::Change::Update {
+            log: Default::default(),
+            expected: gix_ref::transaction::PreviousValue::Any,
+            new: gix_ref::Target::Symbolic("refs/heads/main".try_into()?),
+        },
+        name: "HEAD".try_into()?,
+        deref: false,
+    })?;
+    let id = repo.commit("HEAD", "initial", repo.empty_tree().id, gix::commit::NO_PARENT_IDS)?;
+    assert_eq!(repo.head_name()?.expect("set").as_bstr(), "refs/heads/main");
+    assert_eq!(repo.head_id()?, id);
+    assert_eq!(
+        repo.find_reference("main")?.log_iter().all()?.expect("created").count(),
+        1
+    );
+    assert_eq!(
+        std::fs::read(git_dir.join("HEAD"))?,
+        b"ref: refs/heads/.invalid\n",
+        "the placeholder isn't touched"
+    );
+    Ok(())
+}
+
 #[test]
 fn bare_repo_with_index() -> crate::Result {
     let repo = named_subrepo_opts(
@@ -270,6 +320,48 @@ mod open_path_as_is {
     }
 }
 
+mod read_only {
+    use gix::refs::transaction::PreviousValue;
+
+    use crate::util::{repo_rw_opts, restricted};
+
+    #[test]
+    fn writes_fail_with_typed_errors() -> crate::Result {
+        let (repo, _tmp) = repo_rw_opts("make_basic_repo.sh", restricted().read_only(true))?;
+        assert!(repo.is_read_only());
+        assert_eq!(
+            repo.refs.write_reflog,
+            gix::refs::store::WriteReflog::Disable,
+            "reflogs are never written"
+        );
+
+        let err = repo.write_blob("hello").unwrap_err();
+        assert!(
+            err.0.downcast_ref::<gix::repository::read_only::Error>().is_some(),
+            "object writes are rejected even before checking if the object exists"
+        );
+
+        let head_id = repo.head_id()?;
+        let err = repo
+            .reference("refs/heads/new", head_id, PreviousValue::MustNotExist, "not written")
+            .unwrap_err();
+        assert!(matches!(err, gix::reference::edit::Error::ReadOnly(_)));
+        assert!(
+            repo.try_find_reference("refs/heads/new")?.is_none(),
+            "nothing was written"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn is_off_by_default() -> crate::Result {
+        let (repo, _tmp) = repo_rw_opts("make_basic_repo.sh", restricted())?;
+        assert!(!repo.is_read_only());
+        repo.write_blob("hello")?;
+        Ok(())
+    }
+}
+
 mod submodules {
     use std::path::Path;
 
@@ -444,3 +536,198 @@ mod worktree {
         Ok(())
     }
 }
+
+mod environment_overrides {
+    use std::ffi::OsString;
+
+    use gix::open::EnvironmentOverrides;
+
+    #[test]
+    fn from_vars() {
+        let alternates = std::env::join_paths(["a", "", "b"]).expect("valid paths");
+        let overrides = EnvironmentOverrides::from_vars(|name| match name {
+            "GIT_DIR" => Some("git-dir".into()),
+            "GIT_WORK_TREE" => Some("".into()),
+            "GIT_INDEX_FILE" => Some("custom-index".into()),
+            "GIT_ALTERNATE_OBJECT_DIRECTORIES" => Some(alternates.clone()),
+            "GIT_SHALLOW_FILE" => Some("custom-shallow".into()),
+            "GIT_GRAFT_FILE" => Some("custom-grafts".into()),
+            "GIT_NAMESPACE" => Some("ns".into()),
+            "GIT_REPLACE_REF_BASE" => Some("refs/custom-replace/".into()),
+            "GIT_NO_REPLACE_OBJECTS" => Some("".into()),
+            "GIT_CEILING_DIRECTORIES" => Some("relative-dirs-are-ignored".into()),
+            "GIT_DISCOVERY_ACROSS_FILESYSTEM" => Some("yes".into()),
+            _ => None::<OsString>,
+        });
+        assert_eq!(
+            overrides,
+            EnvironmentOverrides {
+                git_dir: Some("git-dir".into()),
+                worktree_dir: None,
+                common_dir: None,
+                index_file: Some("custom-index".into()),
+                object_dir: None,
+                alternate_object_dirs: vec!["a".into(), "b".into()],
+                shallow_file: Some("custom-shallow".into()),
+                graft_file: Some("custom-grafts".into()),
+                namespace: Some("ns".into()),
+                replace_ref_base: Some("refs/custom-replace/".into()),
+                no_replace_objects: false,
+                ceiling_dirs: Vec::new(),
+                discovery_across_filesystem: Some(true),
+            },
+            "empty values and paths are ignored"
+        );
+        assert!(
+            EnvironmentOverrides::from_vars(|name| (name == "GIT_NO_REPLACE_OBJECTS").then(|| "1".into()))
+                .no_replace_objects,
+            "any value disables replace objects, like in git"
+        );
+        assert!(!overrides.is_empty());
+        assert!(EnvironmentOverrides::from_vars(|_| None).is_empty());
+    }
+
+    #[test]
+    fn index_and_object_directory() -> crate::Result {
+        let source = gix::open_opts(
+            gix_testtools::scripted_fixture_read_only("make_basic_repo.sh")?,
+            gix::open::Options::isolated(),
+        )?;
+        let head_id = source.head_id()?.detach();
+        let source_objects = std::env::current_dir()?.join(source.objects.store_ref().path());
+
+        let tmp = gix_testtools::tempfile::TempDir::new()?;
+        let git_dir = gix::init_bare(tmp.path().join("empty.git"))?.git_dir().to_owned();
+        assert!(
+            !gix::open_opts(&git_dir, gix::open::Options::isolated())?.has_object(head_id),
+            "the repository is empty"
+        );
+
+        let index_file = tmp.path().join("custom-index");
+        let repo = gix::open_opts(
+            &git_dir,
+            gix::open::Options::isolated().environment_overrides(EnvironmentOverrides {
+                index_file: Some(index_file.clone()),
+                object_dir: Some(source_objects.clone()),
+                ..Default::default()
+            }),
+        )?;
+        assert_eq!(repo.index_path(), index_file);
+        assert_eq!(repo.objects.store_ref().path(), source_objects);
+        assert!(
+            repo.has_object(head_id),
+            "objects are read from the overridden directory"
+        );
+
+        let repo = gix::open_opts(
+            &git_dir,
+            gix::open::Options::isolated().environment_overrides(EnvironmentOverrides {
+                alternate_object_dirs: vec![source_objects],
+                ..Default::default()
+            }),
+        )?;
+        assert_eq!(repo.index_path(), git_dir.join("index"));
+        assert!(
+            repo.has_object(head_id),
+            "objects are read from the additional alternate"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn shallow_and_graft_files_namespace_and_replace_ref_base() -> crate::Result {
+        let tmp = gix_testtools::tempfile::TempDir::new()?;
+        let git_dir = gix::init_bare(tmp.path().join("empty.git"))?.git_dir().to_owned();
+        let shallow_file = tmp.path().join("custom-shallow");
+        let graft_file = tmp.path().join("custom-grafts");
+        let repo = gix::open_opts(
+            &git_dir,
+            gix::open::Options::isolated().environment_overrides(EnvironmentOverrides {
+                shallow_file: Some(shallow_file.clone()),
+                graft_file: Some(graft_file.clone()),
+                namespace: Some("foo/bar".into()),
+                replace_ref_base: Some("refs/custom-replace/".into()),
+                no_replace_objects: true,
+                ..Default::default()
+            }),
+        )?;
+        assert_eq!(repo.shallow_file(), shallow_file);
+        assert_eq!(repo.grafts_file(), graft_file);
+        assert_eq!(
+            repo.namespace().expect("set").as_bstr(),
+            "refs/namespaces/foo/refs/namespaces/bar/"
+        );
+        let config = repo.config_snapshot();
+        assert_eq!(
+            config.string("gitoxide.objects.replaceRefBase").expect("set").as_ref(),
+            "refs/custom-replace/"
+        );
+        assert_eq!(config.boolean("core.useReplaceRefs"), Some(true));
+
+        let repo = gix::open_opts(&git_dir, gix::open::Options::isolated())?;
+        assert_eq!(repo.shallow_file(), git_dir.join("shallow"));
+        assert_eq!(repo.grafts_file(), git_dir.join("info").join("grafts"));
+        assert_eq!(repo.namespace(), None);
+        Ok(())
+    }
+
+    #[test]
+    fn ceiling_dirs_limit_discovery() -> crate::Result {
+        let tmp = gix_testtools::tempfile::TempDir::new()?;
+        let worktree = gix::init(tmp.path().join("repo"))?
+            .work_dir()
+            .expect("non-bare")
+            .canonicalize()?;
+        let dir = worktree.join("a").join("b");
+        std::fs::create_dir_all(&dir)?;
+        let trust_map = || gix_sec::trust::Mapping {
+            full: gix::open::Options::isolated(),
+            reduced: gix::open::Options::isolated(),
+        };
+
+        let repo = gix::ThreadSafeRepository::discover_with_custom_environment_overrides_opts(
+            &dir,
+            EnvironmentOverrides::default(),
+            Default::default(),
+            trust_map(),
+        )?;
+        assert_eq!(repo.work_dir(), Some(worktree.as_path()));
+
+        let err = gix::ThreadSafeRepository::discover_with_custom_environment_overrides_opts(
+            &dir,
+            EnvironmentOverrides {
+                ceiling_dirs: vec![worktree.join("a")],
+                ..Default::default()
+            },
+            Default::default(),
+            trust_map(),
+        )
+        .unwrap_err();
+        assert!(
+            matches!(
+                err,
+                gix::discover::Error::Discover(gix::discover::upwards::Error::NoGitRepositoryWithinCeiling { .. })
+            ),
+            "{err:?}"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn git_dir_takes_precedence_over_fallback_directory() -> crate::Result {
+        let git_dir = gix_testtools::scripted_fixture_read_only("make_basic_repo.sh")?.join(".git");
+        let repo = gix::ThreadSafeRepository::open_with_custom_environment_overrides(
+            "does-not-exist",
+            EnvironmentOverrides {
+                git_dir: Some(git_dir.clone()),
+                ..Default::default()
+            },
+            gix_sec::trust::Mapping {
+                full: gix::open::Options::isolated(),
+                reduced: gix::open::Options::isolated(),
+            },
+        )?;
+        assert_eq!(repo.git_dir().canonicalize()?, git_dir.canonicalize()?);
+        Ok(())
+    }
+}
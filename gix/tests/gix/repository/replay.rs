@@ -0,0 +1,115 @@
+use gix::bstr::ByteSlice;
+use gix::merge::replay::Options;
+
+use crate::util::repo_rw;
+
+fn id(repo: &gix::Repository, spec: &str) -> crate::Result<gix::ObjectId> {
+    Ok(repo.rev_parse_single(spec)?.detach())
+}
+
+fn blob_at(repo: &gix::Repository, commit: gix::ObjectId, path: &str) -> crate::Result<Vec<u8>> {
+    let entry = repo
+        .find_commit(commit)?
+        .tree()?
+        .lookup_entry_by_path(path)?
+        .expect("path exists");
+    Ok(repo.find_object(entry.object_id())?.detach().data)
+}
+
+#[test]
+fn linear_history_is_replayed_onto_new_base_without_touching_refs() -> crate::Result {
+    let (repo, _tmp) = repo_rw("make_replay_repo.sh")?;
+    let (upstream, tip, onto) = (id(&repo, "main~1")?, id(&repo, "feature")?, id(&repo, "main")?);
+    let outcome = repo.replay(upstream, tip, onto, repo.tree_merge_options()?.into())?;
+    assert!(outcome.conflict.is_none());
+    assert_eq!(outcome.commits.len(), 3, "the originally empty commit is kept");
+    assert_eq!(
+        outcome.commits.iter().map(|c| c.original).collect::<Vec<_>>(),
+        [id(&repo, "feature~2")?, id(&repo, "feature~1")?, tip],
+        "parents come before their children"
+    );
+    assert_eq!(outcome.commits.last().and_then(|c| c.new), Some(outcome.tip));
+
+    let mut expected_parent = onto;
+    for replayed in &outcome.commits {
+        let new = repo.find_commit(replayed.new.expect("nothing was dropped"))?;
+        let original = repo.find_commit(replayed.original)?;
+        assert_eq!(
+            new.parent_ids().map(gix::Id::detach).collect::<Vec<_>>(),
+            [expected_parent]
+        );
+        assert_eq!(new.message_raw()?, original.message_raw()?);
+        assert_eq!(new.author()?, original.author()?);
+        assert_eq!(new.committer()?.name, "gitoxide", "the committer is the configured one");
+        expected_parent = new.id;
+    }
+
+    assert_eq!(
+        blob_at(&repo, outcome.tip, "file")?,
+        b"one\n2\nTHREE\n",
+        "both changes are present"
+    );
+    assert_eq!(blob_at(&repo, outcome.tip, "new")?, b"new\n");
+    assert_eq!(id(&repo, "feature")?, tip, "references aren't changed");
+    Ok(())
+}
+
+#[test]
+fn commits_whose_changes_are_already_present_are_dropped() -> crate::Result {
+    let (repo, _tmp) = repo_rw("make_replay_repo.sh")?;
+    let (upstream, tip, onto) = (id(&repo, "main~1")?, id(&repo, "applied")?, id(&repo, "main")?);
+    let outcome = repo.replay(upstream, tip, onto, Options::default())?;
+    assert!(outcome.conflict.is_none());
+    assert_eq!(outcome.commits.len(), 2);
+    assert_eq!(outcome.commits[0].new, None, "the change is already upstream");
+    assert_eq!(
+        repo.find_commit(outcome.tip)?
+            .parent_ids()
+            .map(gix::Id::detach)
+            .collect::<Vec<_>>(),
+        [onto],
+        "the remaining commit is placed right on top of the new base"
+    );
+    assert_eq!(blob_at(&repo, outcome.tip, "other")?, b"other\n");
+    Ok(())
+}
+
+#[test]
+fn conflicts_stop_the_replay_with_a_report() -> crate::Result {
+    use gix::index::entry::Stage;
+
+    let (repo, _tmp) = repo_rw("make_replay_repo.sh")?;
+    let (upstream, tip, onto) = (id(&repo, "main~1")?, id(&repo, "conflicting")?, id(&repo, "main")?);
+    let outcome = repo.replay(upstream, tip, onto, Options::default())?;
+    assert!(outcome.commits.is_empty());
+    assert_eq!(outcome.tip, onto, "nothing was replayed");
+
+    let conflict = outcome.conflict.expect("the last line was changed on both sides");
+    assert_eq!(conflict.commit, tip);
+    assert_eq!(conflict.conflicts.len(), 1);
+    assert_eq!(
+        conflict
+            .entries
+            .iter()
+            .map(|e| (e.path.as_bstr(), e.stage))
+            .collect::<Vec<_>>(),
+        [
+            ("file".into(), Stage::Base),
+            ("file".into(), Stage::Ours),
+            ("file".into(), Stage::Theirs)
+        ]
+    );
+    assert_eq!(repo.find_object(conflict.entries[2].id)?.data, b"1\n2\nthree\n");
+    Ok(())
+}
+
+#[test]
+fn merge_commits_cannot_be_replayed() -> crate::Result {
+    let (repo, _tmp) = repo_rw("make_replay_repo.sh")?;
+    let (upstream, tip, onto) = (id(&repo, "main~1")?, id(&repo, "merge")?, id(&repo, "main")?);
+    let err = repo
+        .replay(upstream, tip, onto, Options::default())
+        .expect_err("merges are unsupported");
+    assert!(matches!(err, gix::repository::replay::Error::MergeCommit { id } if id == tip));
+    Ok(())
+}
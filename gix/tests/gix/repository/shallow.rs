@@ -39,6 +39,13 @@ fn yes() -> crate::Result {
             repo.shallow_commits()?.expect("present").as_slice(),
             [hex_to_id("30887839de28edf7ab66c860e5c58b4d445f6b12")]
         );
+        let grafts = repo.grafts()?;
+        assert_eq!(grafts.len(), 1, "there is no grafts file, just the shallow boundary");
+        assert_eq!(
+            gix::revwalk::Parents::parents_of(&grafts, &hex_to_id("30887839de28edf7ab66c860e5c58b4d445f6b12")),
+            Some([].as_slice()),
+            "shallow commits appear to have no parents"
+        );
     }
     Ok(())
 }
@@ -132,3 +139,67 @@ mod traverse {
         Ok(())
     }
 }
+
+mod grafts {
+    use serial_test::parallel;
+
+    use crate::util::hex_to_id;
+
+    fn grafted_repo(grafts: &str) -> crate::Result<(gix::Repository, gix_testtools::tempfile::TempDir)> {
+        let tmp = gix_testtools::scripted_fixture_writable("make_shallow_repo.sh")?;
+        let repo = gix::open_opts(tmp.path().join("base"), crate::restricted())?;
+        std::fs::create_dir_all(repo.grafts_file().parent().expect("in info/"))?;
+        std::fs::write(repo.grafts_file(), grafts)?;
+        Ok((repo, tmp))
+    }
+
+    #[test]
+    #[parallel]
+    fn grafted_parents_are_seen_by_traversals_and_rev_parse() -> crate::Result {
+        let (repo, _tmp) =
+            grafted_repo("30887839de28edf7ab66c860e5c58b4d445f6b12 05dc291f5376cde200316cb0b74b00cfebc79ea4\n")?;
+        let commits: Vec<_> = repo
+            .head_id()?
+            .ancestors()
+            .all()?
+            .map(|c| c.map(|c| c.id))
+            .collect::<Result<_, _>>()?;
+        assert_eq!(
+            commits,
+            [
+                hex_to_id("30887839de28edf7ab66c860e5c58b4d445f6b12"),
+                hex_to_id("05dc291f5376cde200316cb0b74b00cfebc79ea4"),
+            ],
+            "the commit in the middle is skipped"
+        );
+
+        let root = hex_to_id("05dc291f5376cde200316cb0b74b00cfebc79ea4");
+        assert_eq!(repo.rev_parse_single("HEAD^")?, root);
+        assert_eq!(repo.rev_parse_single("HEAD~1")?, root);
+        assert_eq!(
+            repo.rev_parse_single("HEAD~2").unwrap_err().to_string(),
+            "Commit 3088783 has 1 ancestors along the first parent and ancestor number 2 is out of range"
+        );
+
+        let mut graph = repo.revision_graph::<()>(None)?;
+        let mut parents = Vec::new();
+        graph.insert_parents(
+            &hex_to_id("30887839de28edf7ab66c860e5c58b4d445f6b12"),
+            &mut |id, _| parents.push(id),
+            &mut |_, _| {},
+            false,
+        )?;
+        assert_eq!(parents, [root], "the revision graph sees the graft as well");
+        Ok(())
+    }
+
+    #[test]
+    #[parallel]
+    fn invalid_grafts_are_an_error() -> crate::Result {
+        let (repo, _tmp) = grafted_repo("not-a-hash\n")?;
+        assert!(repo.revision_graph::<()>(None).is_err());
+        assert!(repo.head_id()?.ancestors().all().is_err());
+        assert!(repo.rev_parse_single("HEAD^").is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,105 @@
+use std::sync::atomic::AtomicBool;
+
+use gix::repository::shortlog::{Contributor, GroupBy, Options};
+use gix_testtools::tempfile;
+
+use crate::util::restricted_and_git;
+
+fn signature(name: &str, email: &str, seconds: gix::date::SecondsSinceUnixEpoch) -> gix::actor::Signature {
+    gix::actor::Signature {
+        name: name.into(),
+        email: email.into(),
+        time: gix::date::Time::new(seconds, 0),
+    }
+}
+
+#[test]
+fn contributors_are_grouped_by_mailmap_resolved_signature() -> crate::Result {
+    let tmp = tempfile::tempdir()?;
+    let repo = gix::ThreadSafeRepository::init_opts(
+        &tmp,
+        gix::create::Kind::WithWorktree,
+        Default::default(),
+        restricted_and_git(),
+    )?
+    .to_thread_local();
+    std::fs::write(tmp.path().join(".mailmap"), "A <a@example.com> <old@example.com>\n")?;
+
+    let tree = repo.empty_tree().id;
+    let mut parents = Vec::new();
+    for (seconds, (author, message)) in [
+        (signature("A", "a@example.com", 1), "first\n\nbody"),
+        (signature("old name", "old@example.com", 2), "second"),
+        (signature("B", "b@example.com", 3), "third"),
+        (signature("A", "other@example.com", 4), "fourth"),
+    ]
+    .into_iter()
+    .enumerate()
+    {
+        let committer = signature("c", "c@example.com", seconds as gix::date::SecondsSinceUnixEpoch);
+        let id = repo.commit_as(&committer, &author, "HEAD", message, tree, parents.drain(..))?;
+        parents.push(id.detach());
+    }
+
+    let walk = || -> crate::Result<_> { Ok(repo.rev_walk(Some(repo.head_id()?)).all()?) };
+    let contributors = repo.shortlog(
+        walk()?,
+        Options {
+            summaries: true,
+            ..Default::default()
+        },
+        &AtomicBool::default(),
+    )?;
+    assert_eq!(
+        contributors,
+        [
+            Contributor {
+                name: "A".into(),
+                email: None,
+                count: 3,
+                summaries: vec!["first".into(), "second".into(), "fourth".into()],
+            },
+            Contributor {
+                name: "B".into(),
+                email: None,
+                count: 1,
+                summaries: vec!["third".into()],
+            }
+        ],
+        "the mailmap unifies identities and summaries are ordered from oldest to newest"
+    );
+
+    let contributors = repo.shortlog(
+        walk()?,
+        Options {
+            email: true,
+            ..Default::default()
+        },
+        &AtomicBool::default(),
+    )?;
+    assert_eq!(
+        contributors
+            .iter()
+            .map(|c| (c.name.to_string(), c.email.clone().expect("set").to_string(), c.count))
+            .collect::<Vec<_>>(),
+        [
+            ("A".into(), "a@example.com".into(), 2),
+            ("A".into(), "other@example.com".into(), 1),
+            ("B".into(), "b@example.com".into(), 1)
+        ],
+        "emails keep contributors apart, and summaries are optional"
+    );
+    assert!(contributors.iter().all(|c| c.summaries.is_empty()));
+
+    let contributors = repo.shortlog(
+        walk()?,
+        Options {
+            group_by: GroupBy::Committer,
+            ..Default::default()
+        },
+        &AtomicBool::default(),
+    )?;
+    assert_eq!(contributors.len(), 1, "there is only one committer");
+    assert_eq!(contributors[0].count, 4);
+    Ok(())
+}
@@ -0,0 +1,141 @@
+use std::sync::atomic::AtomicBool;
+
+use gix::sparse_checkout::{Mode, Patterns};
+
+use crate::util::repo_rw;
+
+mod patterns {
+    use gix::{bstr::BStr, glob::pattern::Case, sparse_checkout::Mode};
+
+    use super::Patterns;
+
+    fn included(patterns: &Patterns, paths: &[&str]) -> Vec<bool> {
+        paths
+            .iter()
+            .map(|path| patterns.is_included(BStr::new(path), Case::Sensitive))
+            .collect()
+    }
+
+    #[test]
+    fn cone_includes_root_files_parents_and_recursive_directories() -> crate::Result {
+        let patterns = Patterns::from_directories(["a/b/"])?;
+        assert_eq!(patterns.mode(), Mode::Cone);
+        assert_eq!(
+            included(
+                &patterns,
+                &["root-file", "a/file", "a/b/file", "a/b/c/file", "d/file", "ab/file"]
+            ),
+            [true, true, true, true, false, false]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn cone_roundtrip() -> crate::Result {
+        let patterns = Patterns::from_directories(["a/b", "a/b/c", "d"])?;
+        let bytes = patterns.to_bytes();
+        assert_eq!(
+            bytes, b"/*\n!/*/\n/a/\n!/a/*/\n/a/b/\n/d/\n",
+            "nested directories are already covered by their parent"
+        );
+        let parsed = Patterns::from_bytes(&bytes, Mode::Cone)?;
+        assert_eq!(parsed.lines(), ["a/b", "d"]);
+        Ok(())
+    }
+
+    #[test]
+    fn cone_validation() {
+        for invalid in ["", "a/*", "a/../b", "./a", "a//b"] {
+            assert!(
+                Patterns::from_directories([invalid]).is_err(),
+                "{invalid:?} is not a valid cone directory"
+            );
+        }
+        assert!(
+            Patterns::from_bytes(b"*.txt\n", Mode::Cone).is_err(),
+            "non-cone patterns are rejected in cone mode"
+        );
+    }
+
+    #[test]
+    fn non_cone_patterns_with_negation() {
+        let patterns = Patterns::from_lines(["/*", "!/d/", "*.md"]);
+        assert_eq!(patterns.mode(), Mode::Patterns);
+        assert_eq!(
+            included(&patterns, &["root-file", "a/file", "d/file", "d/README.md"]),
+            [true, true, false, true]
+        );
+    }
+}
+
+#[test]
+fn set_add_and_disable() -> crate::Result {
+    let (mut repo, _tmp) = repo_rw("make_sparse_checkout_repo.sh")?;
+    let workdir = repo.work_dir().expect("non-bare").to_owned();
+    let should_interrupt = AtomicBool::new(false);
+    assert!(repo.sparse_checkout_patterns()?.is_none(), "disabled by default");
+
+    let outcome = repo.sparse_checkout_set(&Patterns::from_directories(["a/b"])?, &should_interrupt)?;
+    assert_eq!(outcome.removed, ["d/e/file", "d/file", "x/file"]);
+    assert!(outcome.added.is_empty());
+    assert!(!workdir.join("d").exists(), "empty directories are removed as well");
+    assert!(workdir.join("a/b/c/file").is_file());
+
+    let patterns = repo.sparse_checkout_patterns()?.expect("enabled");
+    assert_eq!(patterns.mode(), Mode::Cone);
+    assert_eq!(patterns.lines(), ["a/b"]);
+    let index = repo.open_index()?;
+    let skipped: Vec<_> = index
+        .entries()
+        .iter()
+        .filter(|e| e.flags.contains(gix::index::entry::Flags::SKIP_WORKTREE))
+        .map(|e| e.path(&index).to_owned())
+        .collect();
+    assert_eq!(skipped, ["d/e/file", "d/file", "x/file"]);
+
+    std::fs::write(workdir.join("a/b/file"), "modified")?;
+    let outcome = repo.sparse_checkout_set(&Patterns::from_directories(["d/e"])?, &should_interrupt)?;
+    assert_eq!(
+        outcome.added,
+        ["d/e/file", "d/file"],
+        "parent directories of cones are included"
+    );
+    assert_eq!(outcome.removed, ["a/b/c/file", "a/file"]);
+    assert_eq!(outcome.kept_modified, ["a/b/file"], "modifications are never lost");
+
+    let outcome = repo.sparse_checkout_add(["a/b/c"], &should_interrupt)?;
+    assert_eq!(outcome.added, ["a/b/c/file", "a/file"]);
+    assert_eq!(
+        repo.sparse_checkout_patterns()?.expect("enabled").lines(),
+        ["a/b/c", "d/e"]
+    );
+
+    let outcome = repo.sparse_checkout_disable(&should_interrupt)?;
+    assert_eq!(outcome.added, ["x/file"]);
+    assert!(repo.sparse_checkout_patterns()?.is_none());
+    assert_eq!(std::fs::read(workdir.join("x/file"))?, b"x\n");
+    assert!(
+        repo.sparse_checkout_path().is_file(),
+        "the patterns are kept for re-enabling them later"
+    );
+    Ok(())
+}
+
+#[test]
+fn files_are_compared_after_conversion_to_git() -> crate::Result {
+    let (mut repo, _tmp) = repo_rw("make_sparse_checkout_repo.sh")?;
+    repo.config_snapshot_mut()
+        .set_value(&gix::config::tree::Core::AUTO_CRLF, "true")?;
+    let workdir = repo.work_dir().expect("non-bare").to_owned();
+    std::fs::write(workdir.join("x/file"), "x\r\n")?;
+    std::fs::write(workdir.join("d/file"), "d\r\nmodified\r\n")?;
+
+    let outcome = repo.sparse_checkout_set(&Patterns::from_directories(["a"])?, &AtomicBool::new(false))?;
+    assert_eq!(
+        outcome.removed,
+        ["d/e/file", "x/file"],
+        "line endings as written by a checkout with autocrlf aren't modifications"
+    );
+    assert_eq!(outcome.kept_modified, ["d/file"]);
+    Ok(())
+}
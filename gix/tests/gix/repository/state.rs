@@ -77,3 +77,309 @@ fn revert_sequence() -> Result {
 
     Ok(())
 }
+
+#[cfg(feature = "worktree-mutation")]
+mod abort {
+    use std::sync::atomic::AtomicBool;
+
+    use gix::state::InProgress;
+
+    use crate::util::repo_rw;
+
+    fn read_worktree_file(repo: &gix::Repository, rela_path: &str) -> std::io::Result<String> {
+        std::fs::read_to_string(repo.work_dir().expect("non-bare").join(rela_path))
+    }
+
+    fn assert_index_matches_head(repo: &gix::Repository) -> crate::Result {
+        let index = repo.open_index()?;
+        let expected = repo.index_from_tree(&repo.head_tree_id()?)?;
+        let entries = |index: &gix::index::File| {
+            index
+                .entries()
+                .iter()
+                .map(|e| (e.path(index).to_owned(), e.stage(), e.id))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(entries(&index), entries(&expected), "the index is reset to HEAD");
+        Ok(())
+    }
+
+    #[test]
+    fn merge() -> crate::Result {
+        let (repo, _tmp) = repo_rw("make_merge_repo.sh")?;
+        let head_id = repo.head_id()?;
+
+        assert_eq!(repo.abort_in_progress(&AtomicBool::default())?, Some(InProgress::Merge));
+        assert_eq!(repo.state(), None);
+        assert_eq!(repo.head_id()?, head_id, "HEAD didn't move during the merge");
+        assert_eq!(read_worktree_file(&repo, "file")?, "file.main changed\n");
+        assert_index_matches_head(&repo)?;
+        for name in ["MERGE_HEAD", "MERGE_MSG", "MERGE_MODE"] {
+            assert!(!repo.git_dir().join(name).exists(), "{name} was removed");
+        }
+        assert!(
+            repo.git_dir().join("ORIG_HEAD").is_file(),
+            "just like git, ORIG_HEAD is kept"
+        );
+
+        assert_eq!(
+            repo.abort_in_progress(&AtomicBool::default())?,
+            None,
+            "there is nothing left to abort"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn cherry_pick() -> crate::Result {
+        let (repo, _tmp) = repo_rw("make_cherry_pick_repo.sh")?;
+
+        assert_eq!(
+            repo.abort_in_progress(&AtomicBool::default())?,
+            Some(InProgress::CherryPick)
+        );
+        assert_eq!(repo.state(), None);
+        assert_eq!(read_worktree_file(&repo, "file")?, "main\n");
+        assert_index_matches_head(&repo)?;
+        assert!(!repo.git_dir().join("CHERRY_PICK_HEAD").exists());
+        Ok(())
+    }
+
+    #[test]
+    fn cherry_pick_sequence_keeps_unrelated_local_changes() -> crate::Result {
+        let (repo, _tmp) = repo_rw("make_cherry_pick_sequence_repo.sh")?;
+        let head_id = repo.head_id()?;
+        std::fs::write(repo.work_dir().expect("non-bare").join("f1"), "local\n")?;
+
+        assert_eq!(
+            repo.abort_in_progress(&AtomicBool::default())?,
+            Some(InProgress::CherryPickSequence)
+        );
+        assert_eq!(repo.state(), None);
+        assert_eq!(repo.head_id()?, head_id, "the sequence stopped at its first commit");
+        assert_eq!(read_worktree_file(&repo, "f2")?, "f2.main\n");
+        assert_eq!(
+            read_worktree_file(&repo, "f1")?,
+            "local\n",
+            "files the operation didn't touch keep their changes"
+        );
+        assert_index_matches_head(&repo)?;
+        assert!(!repo.git_dir().join("sequencer").exists());
+        Ok(())
+    }
+
+    #[test]
+    fn revert_restores_deleted_files() -> crate::Result {
+        let (repo, _tmp) = repo_rw("make_revert_repo.sh")?;
+        assert!(read_worktree_file(&repo, "f2").is_err(), "the revert deleted the file");
+
+        assert_eq!(
+            repo.abort_in_progress(&AtomicBool::default())?,
+            Some(InProgress::Revert)
+        );
+        assert_eq!(repo.state(), None);
+        assert_eq!(read_worktree_file(&repo, "f2")?, "");
+        assert_index_matches_head(&repo)?;
+        assert!(!repo.git_dir().join("REVERT_HEAD").exists());
+        Ok(())
+    }
+
+    #[test]
+    fn untracked_files_are_not_overwritten() -> crate::Result {
+        let (repo, _tmp) = repo_rw("make_revert_repo.sh")?;
+        std::fs::write(repo.work_dir().expect("non-bare").join("f2"), "untracked\n")?;
+
+        let err = repo
+            .abort_in_progress(&AtomicBool::default())
+            .expect_err("the file would be overwritten");
+        assert_eq!(err.to_string(), "Local changes to 'f2' would be overwritten");
+        assert_eq!(repo.state(), Some(InProgress::Revert), "nothing was changed");
+        assert_eq!(read_worktree_file(&repo, "f2")?, "untracked\n");
+        Ok(())
+    }
+
+    #[test]
+    fn rebase_returns_to_the_original_branch() -> crate::Result {
+        let (repo, _tmp) = repo_rw("make_rebase_i_repo.sh")?;
+        let orig_head =
+            gix_hash::ObjectId::from_hex(&std::fs::read(repo.git_dir().join("rebase-merge/orig-head"))?[..40])?;
+        assert!(repo.head()?.is_detached(), "the rebase stopped to edit a commit");
+
+        assert_eq!(
+            repo.abort_in_progress(&AtomicBool::default())?,
+            Some(InProgress::RebaseInteractive)
+        );
+        assert_eq!(repo.state(), None);
+        assert_eq!(
+            repo.head_name()?.expect("not detached").as_bstr(),
+            "refs/heads/main",
+            "the branch is checked out again"
+        );
+        assert_eq!(repo.head_id()?, orig_head);
+        assert_eq!(
+            read_worktree_file(&repo, "3")?,
+            "",
+            "the file of the last commit is back"
+        );
+        assert_index_matches_head(&repo)?;
+        assert!(!repo.git_dir().join("rebase-merge").exists());
+        Ok(())
+    }
+
+    #[test]
+    fn apply_mailbox() -> crate::Result {
+        let (repo, _tmp) = repo_rw("make_am_repo.sh")?;
+        let head_id = repo.head_id()?;
+
+        assert_eq!(
+            repo.abort_in_progress(&AtomicBool::default())?,
+            Some(InProgress::ApplyMailbox)
+        );
+        assert_eq!(repo.state(), None);
+        assert_eq!(repo.head_id()?, head_id, "no patch was applied");
+        assert_eq!(read_worktree_file(&repo, "file")?, "file.main.update\n");
+        assert_index_matches_head(&repo)?;
+        assert!(!repo.git_dir().join("rebase-apply").exists());
+        Ok(())
+    }
+
+    #[test]
+    fn bisect_returns_to_the_original_branch() -> crate::Result {
+        let (repo, _tmp) = repo_rw("make_bisect_in_progress_repo.sh")?;
+        assert!(repo.head()?.is_detached(), "bisect checked out a commit to test");
+        assert!(read_worktree_file(&repo, "f3").is_err());
+        assert!(repo.references()?.prefixed("refs/bisect/")?.count() > 0);
+
+        assert_eq!(
+            repo.abort_in_progress(&AtomicBool::default())?,
+            Some(InProgress::Bisect)
+        );
+        assert_eq!(repo.state(), None);
+        assert_eq!(repo.head_name()?.expect("not detached").as_bstr(), "refs/heads/main");
+        assert_eq!(repo.head_id()?, repo.rev_parse_single("main")?);
+        assert_eq!(read_worktree_file(&repo, "f3")?, "f3\n");
+        assert_index_matches_head(&repo)?;
+        assert_eq!(
+            repo.references()?.prefixed("refs/bisect/")?.count(),
+            0,
+            "the good and bad markers are removed"
+        );
+        for name in ["BISECT_LOG", "BISECT_START", "BISECT_NAMES", "BISECT_EXPECTED_REV"] {
+            assert!(!repo.git_dir().join(name).exists(), "{name} was removed");
+        }
+        Ok(())
+    }
+}
+
+#[cfg(all(feature = "index", feature = "tree-editor"))]
+mod continue_in_progress {
+    use gix::state::InProgress;
+    use gix_index::entry::{Flags, Mode};
+
+    use crate::util::repo_rw;
+
+    /// Resolve the conflict at `rela_path` in the index by staging `content`, like `git add` would.
+    fn resolve(repo: &gix::Repository, rela_path: &str, content: &str) -> crate::Result<gix_hash::ObjectId> {
+        let id = repo.write_blob(content)?.detach();
+        let mut index = repo.open_index()?;
+        index.remove_entries(|_, path, _| path == rela_path);
+        index.dangerously_push_entry(Default::default(), id, Flags::empty(), Mode::FILE, rela_path.into());
+        index.sort_entries();
+        index.write(Default::default())?;
+        Ok(id)
+    }
+
+    #[test]
+    fn merge_requires_resolved_conflicts() -> crate::Result {
+        let (repo, _tmp) = repo_rw("make_merge_repo.sh")?;
+        let head_id = repo.head_id()?.detach();
+        let merged_id = repo.rev_parse_single("other-branch")?.detach();
+
+        let err = repo
+            .continue_in_progress()
+            .expect_err("conflicts must be resolved first");
+        assert_eq!(err.to_string(), "The conflict at 'file' must be resolved first");
+        assert_eq!(repo.state(), Some(InProgress::Merge));
+
+        let resolved_id = resolve(&repo, "file", "resolved\n")?;
+        let id = repo.continue_in_progress()?.expect("merge in progress");
+        assert_eq!(repo.head_id()?, id, "HEAD points to the merge commit");
+        assert_eq!(repo.state(), None);
+        assert!(!repo.git_dir().join("MERGE_HEAD").exists());
+        assert!(!repo.git_dir().join("MERGE_MSG").exists());
+
+        let commit = id.object()?.into_commit();
+        assert_eq!(commit.parent_ids().collect::<Vec<_>>(), [head_id, merged_id]);
+        assert_eq!(
+            commit.message_raw()?,
+            "Merge branch 'other-branch'\n",
+            "the conflict listing in comments is removed"
+        );
+        let tree = commit.tree()?;
+        let entry = tree.find_entry("file").expect("present");
+        assert_eq!(entry.object_id(), resolved_id);
+        Ok(())
+    }
+
+    #[test]
+    fn cherry_pick_keeps_the_original_author() -> crate::Result {
+        let (repo, _tmp) = repo_rw("make_cherry_pick_repo.sh")?;
+        let head_id = repo.head_id()?.detach();
+        let picked = repo.rev_parse_single("other-branch")?.object()?.into_commit();
+
+        resolve(&repo, "file", "resolved\n")?;
+        let id = repo.continue_in_progress()?.expect("cherry-pick in progress");
+        assert_eq!(repo.state(), None);
+        assert!(!repo.git_dir().join("CHERRY_PICK_HEAD").exists());
+
+        let commit = id.object()?.into_commit();
+        assert_eq!(commit.parent_ids().collect::<Vec<_>>(), [head_id]);
+        assert_eq!(commit.message_raw()?, picked.message_raw()?);
+        assert_eq!(commit.author()?.name, picked.author()?.name);
+        assert_eq!(commit.author()?.time, picked.author()?.time);
+        assert_eq!(commit.committer()?.name, "gitoxide", "the committer is configured");
+        Ok(())
+    }
+
+    #[test]
+    fn revert() -> crate::Result {
+        let (repo, _tmp) = repo_rw("make_revert_repo.sh")?;
+        let head_id = repo.head_id()?.detach();
+
+        let id = repo.continue_in_progress()?.expect("revert in progress");
+        assert_eq!(repo.state(), None);
+        assert!(!repo.git_dir().join("REVERT_HEAD").exists());
+
+        let commit = id.object()?.into_commit();
+        assert_eq!(commit.parent_ids().collect::<Vec<_>>(), [head_id]);
+        assert!(commit.message_raw()?.starts_with(b"Revert \"f2\"\n"));
+        let tree = commit.tree()?;
+        assert!(tree.find_entry("f2").is_none(), "the reverted file was removed");
+        assert!(tree.find_entry("f3").is_some());
+        Ok(())
+    }
+
+    #[test]
+    fn sequences_are_unsupported() -> crate::Result {
+        let (repo, _tmp) = repo_rw("make_revert_sequence_repo.sh")?;
+        let err = repo.continue_in_progress().expect_err("sequences can't be continued");
+        assert_eq!(
+            err.to_string(),
+            "Continuing an operation of type RevertSequence isn't implemented"
+        );
+        assert_eq!(repo.state(), Some(InProgress::RevertSequence));
+        Ok(())
+    }
+
+    #[test]
+    fn rebases_are_unsupported() -> crate::Result {
+        let (repo, _tmp) = repo_rw("make_rebase_i_repo.sh")?;
+        let err = repo.continue_in_progress().expect_err("rebases can't be continued");
+        assert_eq!(
+            err.to_string(),
+            "Continuing an operation of type RebaseInteractive isn't implemented"
+        );
+        assert_eq!(repo.state(), Some(InProgress::RebaseInteractive));
+        Ok(())
+    }
+}
@@ -36,6 +36,125 @@ fn archive() -> crate::Result {
     Ok(())
 }
 
+#[test]
+#[cfg(feature = "status")]
+fn verify() -> crate::Result {
+    use gix::worktree::verify::Divergence;
+
+    let (repo, tmp) = crate::util::basic_rw_repo()?;
+    let outcome = repo.worktree().expect("non-bare").verify()?;
+    assert!(outcome.is_consistent(), "{:?}", outcome.divergences);
+    assert_eq!(outcome.num_entries, 1);
+
+    std::fs::write(tmp.path().join("this"), "changed")?;
+    let parent = repo
+        .head_commit()?
+        .parent_ids()
+        .next()
+        .expect("c2 has a parent")
+        .detach();
+    repo.reference(
+        "refs/heads/main",
+        parent,
+        gix::refs::transaction::PreviousValue::Any,
+        "move back",
+    )?;
+
+    let outcome = repo.worktree().expect("non-bare").verify()?;
+    assert!(outcome.divergences.iter().any(|d| matches!(
+        d,
+        Divergence::Head { rela_path, index: Some(_), head: Some(_) } if rela_path == "this"
+    )));
+    assert!(outcome.divergences.iter().any(|d| matches!(
+        d,
+        Divergence::Worktree { rela_path, summary: gix::status::index_worktree::iter::Summary::Modified } if rela_path == "this"
+    )));
+    Ok(())
+}
+
+#[test]
+fn verify_reports_conflicts_as_such() -> crate::Result {
+    use gix::{status::plumbing::index_as_worktree::Conflict, worktree::verify::Divergence};
+
+    let repo = crate::named_repo("make_merge_repo.sh")?;
+    let outcome = repo.worktree().expect("non-bare").verify()?;
+    assert_eq!(outcome.num_entries, 3, "all stages of the conflicting file");
+    assert!(
+        matches!(
+            outcome.divergences.as_slice(),
+            [Divergence::Conflict {
+                rela_path,
+                conflict: Conflict::BothModified
+            }] if rela_path == "file"
+        ),
+        "conflicting entries aren't compared to HEAD or the worktree: {:?}",
+        outcome.divergences
+    );
+    Ok(())
+}
+
+#[test]
+fn prune_and_repair() -> crate::Result {
+    use gix::worktree::{prune, repair};
+
+    // Worktrees refer to each other with absolute paths, so they must be created in place.
+    let tmp = gix_testtools::scripted_fixture_writable_with_args(
+        "make_worktree_repo.sh",
+        None::<String>,
+        gix_testtools::Creation::ExecuteScript,
+    )?;
+    let repo = gix::open_opts(tmp.path().join("repo"), crate::restricted())?;
+    let num_worktrees = repo.worktrees()?.len();
+
+    let pruned = repo.prune_worktrees(prune::Options {
+        dry_run: true,
+        ..Default::default()
+    })?;
+    assert_eq!(
+        pruned.len(),
+        1,
+        "only the deleted worktree is pruned, locked ones are kept"
+    );
+    assert_eq!(pruned[0].id, "wt-deleted");
+    assert!(matches!(pruned[0].reason, prune::Reason::MissingCheckout { .. }));
+    assert!(pruned[0].git_dir.is_dir(), "nothing is removed in a dry-run");
+
+    let pruned_with_expiry = repo.prune_worktrees(prune::Options {
+        expire: Some(std::time::UNIX_EPOCH),
+        ..Default::default()
+    })?;
+    assert!(pruned_with_expiry.is_empty(), "the gitdir file is too recent");
+
+    let pruned = repo.prune_worktrees(Default::default())?;
+    assert_eq!(pruned.len(), 1);
+    assert!(!pruned[0].git_dir.exists());
+    assert_eq!(repo.worktrees()?.len(), num_worktrees - 1);
+
+    assert!(
+        repo.repair_worktrees(None::<&std::path::Path>)?.is_empty(),
+        "nothing to do"
+    );
+
+    let moved = tmp.path().join("wt-b-moved");
+    std::fs::rename(tmp.path().join("wt-b"), &moved)?;
+    std::fs::write(tmp.path().join("wt-a").join(".git"), "garbage")?;
+    let repaired = repo.repair_worktrees(Some(&moved))?;
+    assert_eq!(
+        repaired.iter().map(|r| r.kind).collect::<Vec<_>>(),
+        [repair::Kind::DotGitFileBroken, repair::Kind::GitdirFileIncorrect]
+    );
+
+    let worktrees = repo.worktrees()?;
+    let wt_b = worktrees.iter().find(|wt| wt.id() == "wt-b").expect("present");
+    assert_eq!(gix_path::realpath(wt_b.base()?)?, gix_path::realpath(&moved)?);
+    gix::open_opts(tmp.path().join("wt-a"), crate::restricted())?;
+    assert!(
+        repo.repair_worktrees(Some(&moved))?.is_empty(),
+        "everything is repaired"
+    );
+    Ok(())
+}
+
 mod with_core_worktree_config {
     use std::io::BufRead;
 
@@ -252,6 +371,44 @@ fn from_nonbare_parent_repo() {
     run_assertions(repo, false /* bare */);
 }
 
+#[test]
+fn git_path_in_linked_worktree() -> crate::Result {
+    if gix_testtools::should_skip_as_git_version_is_smaller_than(2, 31, 0) {
+        return Ok(());
+    }
+    let dir = gix_testtools::scripted_fixture_read_only("make_worktree_repo.sh")?;
+    let main_repo = gix::open(dir.join("repo"))?;
+    let repo = main_repo
+        .worktrees()?
+        .into_iter()
+        .find(|wt| wt.base().map_or(false, |base| base.is_dir()))
+        .expect("at least one linked worktree that exists")
+        .into_repo()?;
+    assert_ne!(repo.git_dir(), repo.common_dir());
+
+    for common in [
+        "config",
+        "objects/info/packs",
+        "refs/heads/main",
+        "logs/refs/heads/main",
+        "info/exclude",
+    ] {
+        assert_eq!(repo.git_path(common), repo.common_dir().join(common), "{common}");
+    }
+    for private in [
+        "HEAD",
+        "logs/HEAD",
+        "refs/bisect/bad",
+        "info/sparse-checkout",
+        "MERGE_HEAD",
+    ] {
+        assert_eq!(repo.git_path(private), repo.git_dir().join(private), "{private}");
+    }
+    assert_eq!(repo.git_path("index"), repo.index_path());
+    assert_eq!(main_repo.git_path("HEAD"), main_repo.git_dir().join("HEAD"));
+    Ok(())
+}
+
 fn run_assertions(main_repo: gix::Repository, should_be_bare: bool) {
     assert_eq!(main_repo.is_bare(), should_be_bare);
     let mut baseline = Baseline::collect(
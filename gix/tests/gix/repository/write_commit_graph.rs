@@ -0,0 +1,70 @@
+use gix::repository::write_commit_graph::Options;
+
+use crate::util::{repo_rw, repo_rw_opts, restricted};
+
+#[test]
+fn changed_paths_are_written_exactly_like_git_does() -> crate::Result {
+    let (repo, _tmp) = repo_rw("make_commit_graph_repo.sh")?;
+    let outcome = repo
+        .write_commit_graph(Options {
+            changed_paths: Some(Default::default()),
+            thread_limit: None,
+        })?
+        .expect("written");
+    assert_eq!(
+        outcome.num_commits, 7,
+        "all commits reachable from references, ignoring tags of trees"
+    );
+    assert_eq!(outcome.num_large_filters, 1, "one commit adds too many files");
+
+    let info_dir = repo.objects.store_ref().path().join("info");
+    assert_eq!(
+        std::fs::read(info_dir.join("commit-graph"))?,
+        std::fs::read(repo.path().join("commit-graph-by-git"))?,
+        "git and gitoxide can use each others commit-graphs"
+    );
+    assert!(
+        !info_dir.join("commit-graph.lock").exists(),
+        "the lock file was moved into place"
+    );
+    Ok(())
+}
+
+#[test]
+fn without_changed_paths() -> crate::Result {
+    let (repo, _tmp) = repo_rw("make_commit_graph_repo.sh")?;
+    let outcome = repo.write_commit_graph(Options::default())?.expect("written");
+    assert_eq!(outcome.num_large_filters, 0);
+
+    let graph = repo.commit_graph()?;
+    assert_eq!(graph.num_commits(), outcome.num_commits);
+    let head = repo.head_commit()?;
+    let commit = graph.commit_by_id(head.id).expect("present");
+    assert_eq!(commit.changed_paths_filter(), None);
+    assert_eq!(commit.root_tree_id(), head.tree_id()?.detach());
+    assert_eq!(commit.generation(), 5, "root, main, octopus, empty and many");
+    Ok(())
+}
+
+#[test]
+fn read_only_repositories_are_rejected() -> crate::Result {
+    let (repo, _tmp) = repo_rw_opts("make_commit_graph_repo.sh", restricted().read_only(true))?;
+    let err = repo.write_commit_graph(Options::default()).unwrap_err();
+    assert!(matches!(err, gix::repository::write_commit_graph::Error::ReadOnly(_)));
+    assert!(!repo.objects.store_ref().path().join("info/commit-graph").exists());
+    Ok(())
+}
+
+#[test]
+fn shallow_repositories_are_skipped() -> crate::Result {
+    let tmp = gix_testtools::scripted_fixture_writable("make_shallow_repo.sh")?;
+    let repo = gix::open_opts(tmp.path().join("shallow"), restricted())?;
+    assert!(repo.is_shallow());
+    assert_eq!(
+        repo.write_commit_graph(Options::default())?,
+        None,
+        "the parents of the shallow boundary are missing, and git doesn't write it either"
+    );
+    assert!(!repo.objects.store_ref().path().join("info/commit-graph").exists());
+    Ok(())
+}
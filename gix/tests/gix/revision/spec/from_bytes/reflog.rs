@@ -1,7 +1,4 @@
-use gix::{
-    prelude::ObjectIdExt,
-    revision::{spec::parse::Error, Spec},
-};
+use gix::{prelude::ObjectIdExt, revision::Spec};
 
 use crate::{
     revision::spec::from_bytes::{parse_spec, parse_spec_no_baseline, repo},
@@ -78,10 +75,16 @@ fn by_index() {
 }
 
 #[test]
-fn by_date_is_planned_until_git_date_crate_is_implements_parsing() {
+fn by_date() {
     let repo = repo("complex_graph").unwrap();
-    assert!(matches!(
-        parse_spec_no_baseline("main@{1979-02-26 18:30:00}", &repo).unwrap_err(),
-        Error::Planned { .. }
-    ));
+    assert_eq!(
+        parse_spec_no_baseline("main@{2100-01-01 00:00:00}", &repo).unwrap(),
+        parse_spec_no_baseline("main@{0}", &repo).unwrap(),
+        "dates after the most recent entry yield the current value"
+    );
+    assert_eq!(
+        parse_spec_no_baseline("main@{1979-02-26 18:30:00}", &repo).unwrap(),
+        parse_spec_no_baseline("main@{3}", &repo).unwrap(),
+        "dates before the oldest entry yield its value as it was created then"
+    );
 }
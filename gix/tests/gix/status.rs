@@ -114,6 +114,159 @@ mod index_worktree {
             Ok(())
         }
 
+        #[test]
+        fn untracked_cache_is_added_and_removed_as_configured() -> crate::Result {
+            use gix::config::tree::Core;
+
+            let tmp = gix_testtools::scripted_fixture_writable("make_status_repos.sh")?;
+            let mut repo = gix::open_opts(tmp.path().join("untracked-only"), gix::open::Options::isolated())?;
+            assert!(repo.index()?.untracked().is_none());
+
+            let run_status = |repo: &gix::Repository| -> crate::Result<bool> {
+                let mut status = repo
+                    .status(gix::progress::Discard)?
+                    .into_index_worktree_iter(Vec::new())?;
+                for item in status.by_ref() {
+                    item?;
+                }
+                let out = status.outcome_mut().expect("iteration done");
+                let has_changes = out.has_changes();
+                if let Some(res) = out.write_changes() {
+                    res?;
+                }
+                Ok(has_changes)
+            };
+
+            run_status(&repo)?;
+            assert!(
+                repo.index()?.untracked().is_none(),
+                "by default, the untracked cache isn't added"
+            );
+
+            repo.config_snapshot_mut().set_value(&Core::UNTRACKED_CACHE, "true")?;
+            assert!(run_status(&repo)?, "the untracked cache is added…");
+            let untracked = repo.index()?.untracked().cloned().expect("written");
+            let workdir = gix::path::realpath(repo.work_dir().expect("non-bare"))?;
+            assert_eq!(
+                untracked.identifier(),
+                gix_index::extension::untracked_cache::identifier(gix::path::into_bstr(workdir).as_ref()),
+                "…for this worktree"
+            );
+
+            let out = std::process::Command::new(gix::path::env::exe_invocation())
+                .args(["-c", "core.untrackedCache=true", "status", "--porcelain"])
+                .current_dir(repo.work_dir().expect("non-bare"))
+                .output()?;
+            assert!(out.status.success(), "git accepts the untracked cache we wrote");
+            assert_eq!(out.stderr.as_slice(), b"", "and doesn't warn about it");
+            assert!(
+                repo.index()?.untracked().expect("kept").identifier() == untracked.identifier(),
+                "git keeps it as it's valid for this location and system"
+            );
+
+            repo.config_snapshot_mut().set_value(&Core::UNTRACKED_CACHE, "keep")?;
+            run_status(&repo)?;
+            assert!(repo.index()?.untracked().is_some(), "an existing cache is kept");
+
+            repo.config_snapshot_mut().set_value(&Core::UNTRACKED_CACHE, "false")?;
+            assert!(run_status(&repo)?, "the untracked cache is removed…");
+            assert!(repo.index()?.untracked().is_none(), "…if it's disabled");
+            Ok(())
+        }
+
+        #[test]
+        fn probed_untracked_cache_support_is_stored_and_caches_of_moved_worktrees_are_replaced() -> crate::Result {
+            use gix::config::{tree::Core, untracked_cache::Mode};
+
+            let tmp = gix_testtools::scripted_fixture_writable("make_status_repos.sh")?;
+            let mut repo = gix::open_opts(tmp.path().join("untracked-only"), gix::open::Options::isolated())?;
+            let supported = repo.probe_untracked_cache(std::time::Duration::from_millis(10))?;
+            if !supported {
+                return Ok(());
+            }
+            assert_eq!(repo.untracked_cache_mode()?, Mode::Enabled, "the result is applied…");
+            let repo = gix::open_opts(repo.work_dir().expect("non-bare"), gix::open::Options::isolated())?;
+            assert_eq!(
+                repo.untracked_cache_mode()?,
+                Mode::Enabled,
+                "…and stored in the local configuration"
+            );
+
+            let mut status = repo
+                .status(gix::progress::Discard)?
+                .into_index_worktree_iter(Vec::new())?;
+            for item in status.by_ref() {
+                item?;
+            }
+            status
+                .outcome_mut()
+                .expect("iteration done")
+                .write_changes()
+                .expect("changed")?;
+            let identifier = repo.index()?.untracked().expect("added").identifier().to_owned();
+
+            let moved = tmp.path().join("moved");
+            std::fs::rename(repo.work_dir().expect("non-bare"), &moved)?;
+            let mut repo = gix::open_opts(&moved, gix::open::Options::isolated())?;
+            repo.config_snapshot_mut().set_value(&Core::UNTRACKED_CACHE, "keep")?;
+            let mut status = repo
+                .status(gix::progress::Discard)?
+                .into_index_worktree_iter(Vec::new())?;
+            for item in status.by_ref() {
+                item?;
+            }
+            status
+                .outcome_mut()
+                .expect("iteration done")
+                .write_changes()
+                .expect("the cache of the previous location is replaced")?;
+            let untracked = repo.index()?.untracked().cloned().expect("kept");
+            assert_ne!(untracked.identifier(), identifier);
+            assert_eq!(
+                untracked.identifier(),
+                gix_index::extension::untracked_cache::identifier(
+                    gix::path::into_bstr(gix::path::realpath(&moved)?).as_ref()
+                ),
+            );
+            Ok(())
+        }
+
+        #[test]
+        fn changed_index_can_be_used_for_the_next_status_without_writing() -> crate::Result {
+            use gix::config::tree::Core;
+
+            let tmp = gix_testtools::scripted_fixture_writable("make_status_repos.sh")?;
+            let mut repo = gix::open_opts(tmp.path().join("untracked-only"), gix::open::Options::isolated())?;
+            {
+                let mut config = repo.config_snapshot_mut();
+                config.set_value(&Core::UNTRACKED_CACHE, "true")?;
+                // The fixture was just created, so without nanoseconds its files would look racy-clean forever.
+                config.set_value(&gix::config::tree::gitoxide::Core::USE_NSEC, "true")?;
+            }
+
+            let run_status = |index: Option<gix::index::File>| -> crate::Result<Option<gix::index::File>> {
+                let mut status = repo.status(gix::progress::Discard)?;
+                if let Some(index) = index {
+                    status = status.index(index.into());
+                }
+                let mut status = status.into_index_worktree_iter(Vec::new())?;
+                for item in status.by_ref() {
+                    item?;
+                }
+                Ok(status.outcome_mut().expect("iteration done").changed_index())
+            };
+
+            let index = run_status(None)?.expect("the untracked cache is added");
+            assert!(index.untracked().is_some(), "the change was applied in memory…");
+            assert!(repo.index()?.untracked().is_none(), "…but not written");
+
+            assert!(
+                run_status(Some(index))?.is_none(),
+                "there is nothing left to change when using the changed index"
+            );
+            Ok(())
+        }
+
         #[test]
         fn early_drop_for_is_dirty_emulation() -> crate::Result {
             let repo = submodule_repo("modified-untracked-and-submodule-head-changed-and-modified")?;
@@ -341,4 +341,32 @@ mod open {
         }
         Ok(())
     }
+
+    #[test]
+    fn resolved_url() -> crate::Result {
+        let mut repo = repo("with-submodules-after-clone")?;
+        {
+            let mut config = repo.config_snapshot_mut();
+            config.remove_section("submodule", Some("m1".into()));
+            config.set_raw_value(&"remote.origin.url", "https://example.com/org/super")?;
+        }
+        let sm = repo.submodules()?.into_iter().flatten().next().expect("one submodule");
+        assert_eq!(sm.url()?.to_bstring(), "../module1", "it's relative in .gitmodules");
+        assert_eq!(
+            sm.resolved_url()?.to_bstring(),
+            "https://example.com/org/module1",
+            "relative urls are resolved against the url of the default remote"
+        );
+
+        repo.config_snapshot_mut()
+            .remove_section("remote", Some("origin".into()));
+        let sm = repo.submodules()?.into_iter().flatten().next().expect("one submodule");
+        let worktree = gix::path::realpath(repo.work_dir().expect("non-bare"))?;
+        assert_eq!(
+            gix::path::from_bstring(sm.resolved_url()?.to_bstring()),
+            worktree.parent().expect("parent").join("module1"),
+            "without remote, the superproject is its own upstream"
+        );
+        Ok(())
+    }
 }
@@ -17,8 +17,8 @@ use gix::bstr::{io::BufReadExt, BString};
 use crate::plumbing::options::merge;
 use crate::plumbing::{
     options::{
-        attributes, commit, commitgraph, config, credential, exclude, free, fsck, index, mailmap, odb, revision, tree,
-        Args, Subcommands,
+        attributes, commit, commitgraph, config, count_objects, credential, exclude, free, fsck, index, mailmap, odb,
+        reflog, refs, revision, tag, tree, Args, Subcommands,
     },
     show_progress,
 };
@@ -149,6 +149,9 @@ pub fn main() -> Result<()> {
         Subcommands::Merge(merge::Platform { cmd }) => match cmd {
             merge::SubCommands::File {
                 resolve_with,
+                style,
+                marker_size,
+                labels,
                 ours,
                 base,
                 theirs,
@@ -164,7 +167,12 @@ pub fn main() -> Result<()> {
                         repository(Mode::Lenient)?,
                         out,
                         format,
-                        resolve_with.map(Into::into),
+                        core::repository::merge::file::Options {
+                            conflict: resolve_with.map(Into::into),
+                            style: style.map(Into::into),
+                            marker_size,
+                            labels,
+                        },
                         base,
                         ours,
                         theirs,
@@ -174,6 +182,7 @@ pub fn main() -> Result<()> {
             merge::SubCommands::Tree {
                 in_memory,
                 file_favor,
+                style,
                 debug,
                 ours,
                 base,
@@ -196,6 +205,7 @@ pub fn main() -> Result<()> {
                         core::repository::merge::tree::Options {
                             format,
                             file_favor: file_favor.map(Into::into),
+                            conflict_style: style.map(Into::into),
                             in_memory,
                             debug,
                         },
@@ -205,6 +215,7 @@ pub fn main() -> Result<()> {
             merge::SubCommands::Commit {
                 in_memory,
                 file_favor,
+                style,
                 debug,
                 ours,
                 theirs,
@@ -225,6 +236,7 @@ pub fn main() -> Result<()> {
                         core::repository::merge::tree::Options {
                             format,
                             file_favor: file_favor.map(Into::into),
+                            conflict_style: style.map(Into::into),
                             in_memory,
                             debug,
                         },
@@ -243,10 +255,146 @@ pub fn main() -> Result<()> {
                 core::repository::merge_base(repository(Mode::Lenient)?, first, others, out, format)
             },
         ),
+        Subcommands::RevParse(crate::plumbing::options::rev_parse::Command {
+            verify,
+            short,
+            symbolic_full_name,
+            abbrev_ref,
+            show_toplevel,
+            is_inside_work_tree,
+            git_path,
+            specs,
+        }) => prepare_and_run(
+            "rev-parse",
+            trace,
+            verbose,
+            progress,
+            progress_keep_open,
+            None,
+            move |_progress, out, err| {
+                use core::repository::rev_parse::Names;
+                core::repository::rev_parse(
+                    repository(Mode::Lenient)?,
+                    specs,
+                    out,
+                    err,
+                    core::repository::rev_parse::Options {
+                        format,
+                        verify,
+                        names: match short {
+                            Some(0) => Names::Short { hex_len: None },
+                            Some(hex_len) => Names::Short { hex_len: Some(hex_len) },
+                            None if symbolic_full_name => Names::SymbolicFullName,
+                            None if abbrev_ref => Names::AbbrevRef,
+                            None => Names::Full,
+                        },
+                        show_toplevel,
+                        is_inside_work_tree,
+                        git_paths: git_path,
+                    },
+                )
+            },
+        ),
+        Subcommands::Show(crate::plumbing::options::show::Command {
+            oneline,
+            first_parent,
+            separate,
+            no_diff_merges,
+            rev_spec,
+        }) => prepare_and_run(
+            "show",
+            trace,
+            verbose,
+            progress,
+            progress_keep_open,
+            None,
+            move |_progress, out, _err| {
+                use gix::commit::diff_to_parents::Merges;
+                core::repository::show(
+                    repository(Mode::Lenient)?,
+                    rev_spec,
+                    out,
+                    core::repository::show::Options {
+                        format: if oneline {
+                            core::repository::show::Format::Oneline
+                        } else {
+                            core::repository::show::Format::Medium
+                        },
+                        merges: if no_diff_merges {
+                            Merges::Off
+                        } else if first_parent {
+                            Merges::FirstParent
+                        } else if separate {
+                            Merges::Separate
+                        } else {
+                            Merges::Combined
+                        },
+                    },
+                )
+            },
+        ),
+        Subcommands::Blame(crate::plumbing::options::blame::Command {
+            statistics,
+            porcelain,
+            incremental,
+            rev,
+            file,
+        }) => prepare_and_run(
+            "blame",
+            trace,
+            verbose,
+            progress,
+            progress_keep_open,
+            None,
+            move |_progress, out, err| {
+                core::repository::blame_file(
+                    repository(Mode::Lenient)?,
+                    &file,
+                    core::repository::blame::Options {
+                        format: if incremental {
+                            core::repository::blame::Format::Incremental
+                        } else if porcelain {
+                            core::repository::blame::Format::Porcelain
+                        } else {
+                            core::repository::blame::Format::Human
+                        },
+                        rev,
+                    },
+                    out,
+                    statistics.then_some(err),
+                    format,
+                )
+            },
+        ),
+        Subcommands::Stats(crate::plumbing::options::stats::Command { top }) => prepare_and_run(
+            "stats",
+            trace,
+            auto_verbose,
+            progress,
+            progress_keep_open,
+            core::repository::stats::PROGRESS_RANGE,
+            move |progress, out, _err| {
+                core::repository::stats(
+                    repository(Mode::Lenient)?,
+                    progress,
+                    out,
+                    core::repository::stats::Options {
+                        format,
+                        thread_limit,
+                        top,
+                    },
+                )
+                .map(|_| ())
+            },
+        ),
         Subcommands::Diff(crate::plumbing::options::diff::Platform { cmd }) => match cmd {
             crate::plumbing::options::diff::SubCommands::Tree {
                 old_treeish,
                 new_treeish,
+                stat,
+                numstat,
+                shortstat,
+                summary: files_summary,
             } => prepare_and_run(
                 "diff-tree",
                 trace,
@@ -255,7 +403,24 @@ pub fn main() -> Result<()> {
                 progress_keep_open,
                 None,
                 move |_progress, out, _err| {
-                    core::repository::diff::tree(repository(Mode::Lenient)?, out, old_treeish, new_treeish)
+                    use core::repository::diff::Summary;
+                    let summary = if stat {
+                        Some(Summary::Stat { width: 80 })
+                    } else if numstat {
+                        Some(Summary::Numstat)
+                    } else if shortstat {
+                        Some(Summary::Shortstat)
+                    } else {
+                        None
+                    };
+                    core::repository::diff::tree(
+                        repository(Mode::Lenient)?,
+                        out,
+                        old_treeish,
+                        new_treeish,
+                        summary,
+                        files_summary,
+                    )
                 },
             ),
         },
@@ -269,6 +434,15 @@ pub fn main() -> Result<()> {
                 None,
                 move |_progress, out, _err| core::repository::worktree::list(repository(Mode::Lenient)?, out, format),
             ),
+            crate::plumbing::options::worktree::SubCommands::Verify => prepare_and_run(
+                "worktree-verify",
+                trace,
+                verbose,
+                progress,
+                progress_keep_open,
+                None,
+                move |_progress, out, _err| core::repository::worktree::verify(repository(Mode::Lenient)?, out, format),
+            ),
         },
         Subcommands::IsClean | Subcommands::IsChanged => {
             let mode = if matches!(cmd, Subcommands::IsClean) {
@@ -421,7 +595,7 @@ pub fn main() -> Result<()> {
             progress,
             progress_keep_open,
             None,
-            move |progress, _out, _err| {
+            move |progress, out, _err| {
                 if add_virtual_file.len() % 2 != 0 {
                     anyhow::bail!(
                         "Virtual files must be specified in pairs of two: slash/separated/path content, got {}",
@@ -430,7 +604,7 @@ pub fn main() -> Result<()> {
                 }
                 core::repository::archive::stream(
                     repository(Mode::Lenient)?,
-                    &output_file,
+                    output_file.as_deref(),
                     treeish.as_deref(),
                     progress,
                     core::repository::archive::Options {
@@ -453,6 +627,7 @@ pub fn main() -> Result<()> {
                             }
                         }),
                     },
+                    out,
                 )
             },
         ),
@@ -502,7 +677,31 @@ pub fn main() -> Result<()> {
                 },
             )
             .map(|_| ()),
-            commitgraph::Subcommands::Verify { statistics } => prepare_and_run(
+            commitgraph::Subcommands::Write {
+                changed_paths,
+                max_changed_paths,
+            } => prepare_and_run(
+                "commitgraph-write",
+                trace,
+                auto_verbose,
+                progress,
+                progress_keep_open,
+                None,
+                move |_progress, out, _err| {
+                    core::repository::commitgraph::write(
+                        repository(Mode::Lenient)?,
+                        out,
+                        core::repository::commitgraph::write::Options {
+                            format,
+                            thread_limit,
+                            changed_paths,
+                            max_changed_paths,
+                        },
+                    )
+                },
+            )
+            .map(|_| ()),
+            commitgraph::Subcommands::Verify { statistics, repair } => prepare_and_run(
                 "commitgraph-verify",
                 trace,
                 auto_verbose,
@@ -517,6 +716,7 @@ pub fn main() -> Result<()> {
                             err,
                             out,
                             output_statistics,
+                            repair,
                         },
                     )
                 },
@@ -528,6 +728,9 @@ pub fn main() -> Result<()> {
             handshake_info,
             bare,
             no_tags,
+            single_branch,
+            filter,
+            init_submodules,
             ref_name,
             remote,
             shallow,
@@ -538,6 +741,9 @@ pub fn main() -> Result<()> {
                 bare,
                 handshake_info,
                 no_tags,
+                single_branch,
+                filter,
+                init_submodules,
                 ref_name,
                 shallow: shallow.into(),
             };
@@ -600,9 +806,17 @@ pub fn main() -> Result<()> {
         }) => {
             use crate::plumbing::options::remote;
             match cmd {
-                remote::Subcommands::Refs | remote::Subcommands::RefMap { .. } => {
+                remote::Subcommands::Refs { .. } | remote::Subcommands::RefMap { .. } => {
                     let kind = match cmd {
-                        remote::Subcommands::Refs => core::repository::remote::refs::Kind::Remote,
+                        remote::Subcommands::Refs {
+                            branches,
+                            tags,
+                            patterns,
+                        } => core::repository::remote::refs::Kind::Remote {
+                            branches,
+                            tags,
+                            patterns,
+                        },
                         remote::Subcommands::RefMap {
                             ref_spec,
                             show_unmapped_remote_refs,
@@ -1187,15 +1401,61 @@ pub fn main() -> Result<()> {
                 },
             ),
         },
-        Subcommands::Cat { revspec } => prepare_and_run(
+        Subcommands::Cat {
+            batch,
+            batch_check,
+            buffer,
+            filters,
+            textconv,
+            path,
+            revspec,
+        } => prepare_and_run(
             "cat",
             trace,
             verbose,
             progress,
             progress_keep_open,
             None,
-            move |_progress, out, _err| core::repository::cat(repository(Mode::Lenient)?, &revspec, out),
+            move |_progress, out, _err| {
+                let repo = repository(Mode::Lenient)?;
+                match (batch, batch_check) {
+                    (Some(format), _) => core::repository::cat_batch(
+                        repo,
+                        gix::object::batch::Mode::Contents,
+                        Some(&format),
+                        buffer,
+                        out,
+                    ),
+                    (None, Some(format)) => {
+                        core::repository::cat_batch(repo, gix::object::batch::Mode::Check, Some(&format), buffer, out)
+                    }
+                    (None, None) => core::repository::cat(
+                        repo,
+                        &revspec.expect("required by clap"),
+                        if filters {
+                            core::repository::revision::resolve::BlobFormat::Worktree
+                        } else if textconv {
+                            core::repository::revision::resolve::BlobFormat::DiffOrGit
+                        } else {
+                            core::repository::revision::resolve::BlobFormat::Git
+                        },
+                        path.as_deref().map(Into::into),
+                        out,
+                    ),
+                }
+            },
         ),
+        Subcommands::Tag(cmd) => match cmd {
+            tag::Subcommands::Verify { rev_spec } => prepare_and_run(
+                "tag-verify",
+                trace,
+                auto_verbose,
+                progress,
+                progress_keep_open,
+                None,
+                move |_progress, out, _err| core::repository::tag::verify(repository(Mode::Lenient)?, &rev_spec, out),
+            ),
+        },
         Subcommands::Commit(cmd) => match cmd {
             commit::Subcommands::Verify { rev_spec } => prepare_and_run(
                 "commit-verify",
@@ -1204,8 +1464,8 @@ pub fn main() -> Result<()> {
                 progress,
                 progress_keep_open,
                 None,
-                move |_progress, _out, _err| {
-                    core::repository::commit::verify(repository(Mode::Lenient)?, rev_spec.as_deref())
+                move |_progress, out, _err| {
+                    core::repository::commit::verify(repository(Mode::Lenient)?, rev_spec.as_deref(), out)
                 },
             ),
             commit::Subcommands::Describe {
@@ -1337,6 +1597,29 @@ pub fn main() -> Result<()> {
             None,
             move |_progress, out, _err| core::repository::fsck(repository(Mode::Strict)?, spec, out),
         ),
+        Subcommands::CountObjects(count_objects::Platform {
+            verbose: count_verbose,
+            human_readable,
+        }) => prepare_and_run(
+            "count-objects",
+            trace,
+            verbose,
+            progress,
+            progress_keep_open,
+            None,
+            move |_progress, out, err| {
+                core::repository::odb::count_objects(
+                    repository(Mode::Strict)?,
+                    out,
+                    err,
+                    core::repository::odb::count_objects::Options {
+                        format,
+                        verbose: count_verbose,
+                        human_readable,
+                    },
+                )
+            },
+        ),
         Subcommands::Mailmap(cmd) => match cmd {
             mailmap::Subcommands::Entries => prepare_and_run(
                 "mailmap-entries",
@@ -1361,6 +1644,57 @@ pub fn main() -> Result<()> {
                 },
             ),
         },
+        Subcommands::Reflog(cmd) => match cmd {
+            reflog::Subcommands::List {
+                reverse,
+                since,
+                until,
+                message,
+                reference,
+            } => prepare_and_run(
+                "reflog-list",
+                trace,
+                verbose,
+                progress,
+                progress_keep_open,
+                None,
+                move |_progress, out, _err| {
+                    core::repository::reflog::list(
+                        repository(Mode::Lenient)?,
+                        out,
+                        core::repository::reflog::Options {
+                            format,
+                            reference,
+                            oldest_first: reverse,
+                            since,
+                            until,
+                            message,
+                        },
+                    )
+                },
+            ),
+        },
+        Subcommands::Refs(cmd) => match cmd {
+            refs::Subcommands::Migrate { ref_format, dry_run } => prepare_and_run(
+                "refs-migrate",
+                trace,
+                verbose,
+                progress,
+                progress_keep_open,
+                None,
+                move |_progress, out, _err| {
+                    core::repository::refs::migrate(
+                        repository(Mode::Lenient)?,
+                        out,
+                        core::repository::refs::Options {
+                            format,
+                            ref_format: ref_format.into(),
+                            dry_run,
+                        },
+                    )
+                },
+            ),
+        },
         Subcommands::Attributes(cmd) => match cmd {
             attributes::Subcommands::Query { statistics, pathspec } => prepare_and_run(
                 "attributes-query",
@@ -1513,6 +1847,87 @@ pub fn main() -> Result<()> {
                     )
                 },
             ),
+            index::Subcommands::FromWorktree {
+                force,
+                index_output_path,
+                skip_hash,
+            } => prepare_and_run(
+                "index-from-worktree",
+                trace,
+                verbose,
+                progress,
+                progress_keep_open,
+                None,
+                move |_progress, out, _err| {
+                    core::repository::index::from_worktree(
+                        repository(Mode::Strict)?,
+                        index_output_path,
+                        force,
+                        skip_hash,
+                        thread_limit,
+                        &gix::interrupt::IS_INTERRUPTED,
+                        out,
+                    )
+                },
+            ),
+            index::Subcommands::Add { pathspec } => prepare_and_run(
+                "index-add",
+                trace,
+                verbose,
+                progress,
+                progress_keep_open,
+                None,
+                move |_progress, out, _err| {
+                    core::repository::index::add(
+                        repository(Mode::Strict)?,
+                        pathspec,
+                        thread_limit,
+                        &gix::interrupt::IS_INTERRUPTED,
+                        out,
+                    )
+                },
+            ),
+            #[cfg(all(unix, feature = "gitoxide-core-tools-index-server"))]
+            index::Subcommands::Serve {
+                allow_write,
+                timeout,
+                socket,
+            } => prepare_and_run(
+                "index-serve",
+                trace,
+                verbose,
+                progress,
+                progress_keep_open,
+                None,
+                move |_progress, _out, err| {
+                    core::repository::index::serve::serve(
+                        repository(Mode::Lenient)?,
+                        socket,
+                        err,
+                        core::repository::index::serve::Options {
+                            thread_limit,
+                            allow_write,
+                            connection_timeout: (timeout != 0).then(|| std::time::Duration::from_secs(timeout)),
+                        },
+                    )
+                },
+            ),
+            #[cfg(all(unix, feature = "gitoxide-core-tools-index-server"))]
+            index::Subcommands::Query { socket, request } => prepare_and_run(
+                "index-query",
+                trace,
+                verbose,
+                progress,
+                progress_keep_open,
+                None,
+                move |_progress, out, _err| {
+                    core::repository::index::serve::query(
+                        &socket,
+                        gix::bstr::BStr::new(&gix::bstr::join(" ", request)),
+                        out,
+                    )
+                },
+            ),
         },
         Subcommands::Completions { shell, out_dir } => {
             let mut app = Args::command();
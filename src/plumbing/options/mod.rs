@@ -91,12 +91,17 @@ pub enum Subcommands {
     Odb(odb::Subcommands),
     /// Check for missing objects.
     Fsck(fsck::Platform),
+    /// Count loose objects and, optionally, packs and garbage files in the object database like `git count-objects`.
+    CountObjects(count_objects::Platform),
     /// Interact with tree objects.
     #[clap(subcommand)]
     Tree(tree::Subcommands),
     /// Interact with commit objects.
     #[clap(subcommand)]
     Commit(commit::Subcommands),
+    /// Interact with tag objects.
+    #[clap(subcommand)]
+    Tag(tag::Subcommands),
     /// Verify the integrity of the entire repository
     Verify {
         #[clap(flatten)]
@@ -116,6 +121,12 @@ pub enum Subcommands {
     /// Interact with the mailmap.
     #[clap(subcommand)]
     Mailmap(mailmap::Subcommands),
+    /// Interact with reference logs.
+    #[clap(subcommand)]
+    Reflog(reflog::Subcommands),
+    /// Interact with references.
+    #[clap(subcommand)]
+    Refs(refs::Subcommands),
     /// Interact with the remote hosts.
     #[cfg(any(feature = "gitoxide-core-async-client", feature = "gitoxide-core-blocking-client"))]
     Remote(remote::Platform),
@@ -130,10 +141,30 @@ pub enum Subcommands {
     /// Interact with submodules.
     #[clap(alias = "submodules")]
     Submodule(submodule::Platform),
-    /// Show whatever object is at the given spec.
+    /// Show whatever object is at the given spec, or read object names from stdin like `git cat-file --batch`.
     Cat {
+        /// Print information and contents of each object named on stdin, optionally using the given format.
+        #[clap(long, num_args = 0..=1, default_missing_value = "", value_name = "FORMAT", conflicts_with_all = ["batch_check", "revspec"])]
+        batch: Option<String>,
+        /// Print information of each object named on stdin, optionally using the given format.
+        #[clap(long, num_args = 0..=1, default_missing_value = "", value_name = "FORMAT", conflicts_with = "revspec")]
+        batch_check: Option<String>,
+        /// Don't flush the output after each object in batch mode.
+        #[clap(long)]
+        buffer: bool,
+        /// Show blobs as they would be checked out into the worktree, with end-of-line conversions and filters applied.
+        #[clap(long, conflicts_with_all = ["textconv", "batch", "batch_check"])]
+        filters: bool,
+        /// Show blobs as converted by their textconv driver, or as stored in git if there is none.
+        #[clap(long, conflicts_with_all = ["batch", "batch_check"])]
+        textconv: bool,
+        /// The path of the blob to determine how to convert it with `--filters` and `--textconv`,
+        /// if the object isn't named like `<rev>:<path>`.
+        #[clap(long, value_name = "PATH")]
+        path: Option<String>,
         /// The object to print to stdout.
-        revspec: String,
+        #[clap(required_unless_present_any = ["batch", "batch_check"])]
+        revspec: Option<String>,
     },
     IsClean,
     IsChanged,
@@ -144,6 +175,12 @@ pub enum Subcommands {
     #[cfg(feature = "gitoxide-core-tools-corpus")]
     Corpus(corpus::Platform),
     MergeBase(merge_base::Command),
+    RevParse(rev_parse::Command),
+    /// Show a commit along with the patch of the changes it introduced, like `git show`.
+    Show(show::Command),
+    Blame(blame::Command),
+    /// Show statistics about objects, references and indices to judge the health of the repository.
+    Stats(stats::Command),
     Merge(merge::Platform),
     Diff(diff::Platform),
     Worktree(worktree::Platform),
@@ -175,6 +212,7 @@ pub mod archive {
         /// Use the `.tar` file format, uncompressed.
         Tar,
         /// Use the `.tar.gz` file format, compressed with `gzip`.
+        #[clap(alias = "tar.gz", alias = "tgz")]
         TarGz,
         /// Use the `.zip` container format.
         Zip,
@@ -182,7 +220,7 @@ pub mod archive {
 
     #[derive(Debug, clap::Parser)]
     pub struct Platform {
-        /// Explicitly set the format. Otherwise derived from the suffix of the output file.
+        /// Explicitly set the format. Otherwise derived from the suffix of the output file, or `tar` when writing to stdout.
         #[clap(long, short = 'f', value_enum)]
         pub format: Option<Format>,
         /// Apply the prefix verbatim to any path we add to the archive. Use a trailing `/` if prefix is a directory.
@@ -197,14 +235,16 @@ pub mod archive {
         /// Add the new file from a slash-separated path, which must happen in pairs of two, first the path, then the content.
         #[clap(long, short = 'v')]
         pub add_virtual_file: Vec<String>,
-        /// The file to write the archive to.
+        /// The file to write the archive to, or stdout if unset.
         ///
         /// It's extension determines the archive format, unless `--format` is set.
-        pub output_file: PathBuf,
+        #[clap(long, short = 'o')]
+        pub output_file: Option<PathBuf>,
 
-        /// The revspec of the commit or tree to traverse, or the tree at `HEAD` if unspecified.
+        /// The revspec of the commit or tree to traverse, or the commit at `HEAD` if unspecified.
         ///
-        /// If commit, the commit timestamp will be used as timestamp for each file in the archive.
+        /// If commit, the commit timestamp will be used as timestamp for each file in the archive,
+        /// and `$Format:…$` placeholders in files with the `export-subst` attribute will be expanded.
         pub treeish: Option<String>,
     }
 }
@@ -287,6 +327,94 @@ pub mod merge_base {
     }
 }
 
+pub mod rev_parse {
+    use std::{ffi::OsString, path::PathBuf};
+
+    #[derive(Debug, clap::Parser)]
+    #[command(about = "Resolve revisions and query repository locations like `git rev-parse`")]
+    pub struct Command {
+        /// Require exactly one revision that resolves to a single object.
+        #[clap(long)]
+        pub verify: bool,
+        /// Abbreviate object ids to the given length or as configured by `core.abbrev`. Implies `--verify`.
+        #[clap(long, num_args = 0..=1, require_equals = true, default_missing_value = "0", value_name = "LENGTH")]
+        pub short: Option<usize>,
+        /// Print the full names of the references that are named by the revisions.
+        #[clap(long, conflicts_with_all = ["short", "abbrev_ref"])]
+        pub symbolic_full_name: bool,
+        /// Print the shortest unambiguous names of the references that are named by the revisions.
+        #[clap(long, conflicts_with = "short")]
+        pub abbrev_ref: bool,
+        /// Print the absolute path to the top-level directory of the work tree.
+        #[clap(long)]
+        pub show_toplevel: bool,
+        /// Print `true` if the current working directory is inside the work tree, or `false` otherwise.
+        #[clap(long)]
+        pub is_inside_work_tree: bool,
+        /// Print the path to the given path within the repository, adjusted for linked worktrees.
+        #[clap(long, value_name = "PATH")]
+        pub git_path: Vec<PathBuf>,
+        /// Revision specifications like `@`, `@~1` or `main..feature`.
+        pub specs: Vec<OsString>,
+    }
+}
+
+pub mod show {
+    use gix::bstr::BString;
+
+    #[derive(Debug, clap::Parser)]
+    #[command(about = "Show a commit along with the patch of the changes it introduced, like `git show`")]
+    pub struct Command {
+        /// Show the abbreviated commit id and the title of its message on a single line.
+        #[clap(long)]
+        pub oneline: bool,
+        /// Diff merge commits only against their first parent.
+        #[clap(long, conflicts_with_all = ["separate", "no_diff_merges"])]
+        pub first_parent: bool,
+        /// Diff merge commits against each of their parents, showing all changes.
+        #[clap(short = 'm', conflicts_with = "no_diff_merges")]
+        pub separate: bool,
+        /// Don't diff merge commits. By default, they are shown with a combined diff of the hunks that differ from all parents, like with `--cc`.
+        #[clap(long)]
+        pub no_diff_merges: bool,
+        /// The revision specification of the commit to show.
+        #[clap(default_value = "HEAD", value_parser = crate::shared::AsBString)]
+        pub rev_spec: BString,
+    }
+}
+
+pub mod blame {
+    use std::ffi::OsString;
+
+    #[derive(Debug, clap::Parser)]
+    #[command(about = "Annotate each line of a file with the commit that last changed it")]
+    pub struct Command {
+        /// Print additional statistics to help understanding performance.
+        #[clap(long, short = 's')]
+        pub statistics: bool,
+        /// Print the output in a machine-readable format designed for machine consumption.
+        #[clap(long, conflicts_with = "incremental")]
+        pub porcelain: bool,
+        /// Print entries in a machine-readable format as soon as they are found.
+        #[clap(long)]
+        pub incremental: bool,
+        /// The revision to start the blame at, defaulting to `HEAD`.
+        #[clap(long, short = 'r')]
+        pub rev: Option<String>,
+        /// The file to create the blame information for.
+        pub file: OsString,
+    }
+}
+
+pub mod stats {
+    #[derive(Debug, clap::Parser)]
+    pub struct Command {
+        /// The amount of biggest blobs and trees to show.
+        #[clap(long, default_value_t = 10)]
+        pub top: usize,
+    }
+}
+
 pub mod worktree {
     #[derive(Debug, clap::Parser)]
     #[command(about = "Commands for handling worktrees")]
@@ -299,6 +427,8 @@ pub mod worktree {
     pub enum SubCommands {
         /// List all worktrees, along with some accompanying information
         List,
+        /// Check that the index matches the tree of `HEAD`, and that the worktree matches the index, listing all differences
+        Verify,
     }
 }
 
@@ -367,6 +497,26 @@ pub mod merge {
         }
     }
 
+    #[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, clap::ValueEnum)]
+    pub enum ConflictStyle {
+        /// Show only our and their side of the conflict.
+        Merge,
+        /// Also show the base of the conflict.
+        Diff3,
+        /// Like `diff3`, but lines that both sides have in common are moved outside of the conflict.
+        Zdiff3,
+    }
+
+    impl From<ConflictStyle> for gix::merge::blob::builtin_driver::text::ConflictStyle {
+        fn from(value: ConflictStyle) -> Self {
+            match value {
+                ConflictStyle::Merge => gix::merge::blob::builtin_driver::text::ConflictStyle::Merge,
+                ConflictStyle::Diff3 => gix::merge::blob::builtin_driver::text::ConflictStyle::Diff3,
+                ConflictStyle::Zdiff3 => gix::merge::blob::builtin_driver::text::ConflictStyle::ZealousDiff3,
+            }
+        }
+    }
+
     #[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, clap::ValueEnum)]
     pub enum FileFavor {
         /// Use only ours in case of conflict.
@@ -398,6 +548,15 @@ pub mod merge {
             /// Decide how to resolve conflicts. If unset, write conflict markers and fail.
             #[clap(long, short = 'c')]
             resolve_with: Option<ResolveWith>,
+            /// The style of conflict markers, overriding `merge.conflictStyle`.
+            #[clap(long, short = 's')]
+            style: Option<ConflictStyle>,
+            /// The amount of characters to use for each conflict marker.
+            #[clap(long)]
+            marker_size: Option<std::num::NonZeroU8>,
+            /// The labels to use for ours, base and theirs in conflict markers, in that order, instead of their names.
+            #[clap(long = "label", short = 'L', value_name = "LABEL", value_parser = crate::shared::AsBString)]
+            labels: Vec<BString>,
 
             /// A path or revspec to our file.
             #[clap(value_name = "OURS", value_parser = crate::shared::AsBString)]
@@ -420,6 +579,9 @@ pub mod merge {
             /// Decide how to resolve content conflicts in files. If unset, write conflict markers and fail.
             #[clap(long, short = 'f')]
             file_favor: Option<FileFavor>,
+            /// The style of conflict markers, overriding `merge.conflictStyle`.
+            #[clap(long, short = 's')]
+            style: Option<ConflictStyle>,
             /// Print additional information about conflicts for debugging.
             #[clap(long, short = 'd')]
             debug: bool,
@@ -444,6 +606,9 @@ pub mod merge {
             /// Decide how to resolve content conflicts in files. If unset, write conflict markers and fail.
             #[clap(long, short = 'f')]
             file_favor: Option<FileFavor>,
+            /// The style of conflict markers, overriding `merge.conflictStyle`.
+            #[clap(long, short = 's')]
+            style: Option<ConflictStyle>,
             /// Print additional information about conflicts for debugging.
             #[clap(long, short = 'd')]
             debug: bool,
@@ -478,6 +643,20 @@ pub mod diff {
             /// A rev-spec representing the 'after' or new tree.
             #[clap(value_parser = crate::shared::AsBString)]
             new_treeish: BString,
+            /// Instead of listing changes, show the amount of changed lines per file along with a graph and the totals.
+            #[clap(long, conflicts_with_all = ["numstat", "shortstat"])]
+            stat: bool,
+            /// Instead of listing changes, show the amount of added and removed lines per file in a machine-readable format.
+            #[clap(long, conflicts_with = "shortstat")]
+            numstat: bool,
+            /// Instead of listing changes, only show the total amount of changed files and lines.
+            #[clap(long)]
+            shortstat: bool,
+            /// Instead of listing changes, show created, deleted, renamed and copied files along with mode changes.
+            ///
+            /// Can be combined with the other summaries, and renames and copies show their similarity.
+            #[clap(long)]
+            summary: bool,
         },
     }
 }
@@ -601,6 +780,20 @@ pub mod clone {
         #[clap(long)]
         pub no_tags: bool,
 
+        /// Only clone the branch to check out, and configure the remote to only fetch this branch in future.
+        #[clap(long)]
+        pub single_branch: bool,
+
+        /// Perform a partial clone which omits all objects that don't match the filter, like `blob:none` or `tree:0`.
+        ///
+        /// Note that omitted objects aren't fetched on demand, so the checkout fails if it needs them.
+        #[clap(long, value_name = "FILTER_SPEC")]
+        pub filter: Option<String>,
+
+        /// Register all submodules in the local configuration after the checkout, like `git submodule init` would.
+        #[clap(long, conflicts_with = "bare")]
+        pub init_submodules: bool,
+
         #[clap(flatten)]
         pub shallow: ShallowOptions,
 
@@ -670,8 +863,18 @@ pub mod remote {
     #[derive(Debug, clap::Subcommand)]
     #[clap(visible_alias = "remotes")]
     pub enum Subcommands {
-        /// Print all references available on the remote.
-        Refs,
+        /// Print all references available on the remote, like `git ls-remote`.
+        Refs {
+            /// Only print branches, i.e. references below `refs/heads/`.
+            #[clap(long, visible_alias = "heads")]
+            branches: bool,
+            /// Only print tags, i.e. references below `refs/tags/`.
+            #[clap(long)]
+            tags: bool,
+            /// Only print references whose full name ends with a match of one of the given patterns, like `main` or `tags/v*`.
+            #[clap(value_parser = crate::shared::AsBString)]
+            patterns: Vec<gix::bstr::BString>,
+        },
         /// Print all references available on the remote as filtered through ref-specs.
         RefMap {
             /// Also display remote references that were sent by the server, but filtered by the refspec locally.
@@ -699,6 +902,62 @@ pub mod mailmap {
     }
 }
 
+pub mod reflog {
+    use gix::bstr::BString;
+
+    #[derive(Debug, clap::Subcommand)]
+    pub enum Subcommands {
+        /// List the entries of a reference log, most recent first, without loading it into memory as a whole.
+        List {
+            /// List entries from oldest to most recent.
+            #[clap(long, short = 'r')]
+            reverse: bool,
+            /// Only list entries written at or after this date.
+            #[clap(long)]
+            since: Option<String>,
+            /// Only list entries written at or before this date.
+            #[clap(long)]
+            until: Option<String>,
+            /// Only list entries whose message matches this regular expression.
+            #[clap(long, short = 'm', value_parser = crate::shared::AsBString)]
+            message: Option<BString>,
+            /// The name of the reference whose log to list, defaulting to `HEAD`.
+            #[clap(value_parser = crate::shared::AsBString)]
+            reference: Option<BString>,
+        },
+    }
+}
+
+pub mod refs {
+    #[derive(Debug, Copy, Clone, PartialEq, Eq, clap::ValueEnum)]
+    pub enum Format {
+        Files,
+        Reftable,
+    }
+
+    impl From<Format> for gix::refs::store::Format {
+        fn from(value: Format) -> Self {
+            match value {
+                Format::Files => gix::refs::store::Format::Files,
+                Format::Reftable => gix::refs::store::Format::Reftable,
+            }
+        }
+    }
+
+    #[derive(Debug, clap::Subcommand)]
+    pub enum Subcommands {
+        /// Convert all references and their logs to another storage format, and configure the repository to use it.
+        Migrate {
+            /// The format to store references in.
+            #[clap(long, value_enum)]
+            ref_format: Format,
+            /// Only read all references and their logs, without changing the repository.
+            #[clap(long, short = 'n')]
+            dry_run: bool,
+        },
+    }
+}
+
 #[cfg(feature = "gitoxide-core-tools-clean")]
 pub mod clean {
     use crate::shared::CheckPathSpec;
@@ -789,6 +1048,18 @@ pub mod fsck {
     }
 }
 
+pub mod count_objects {
+    #[derive(Debug, clap::Parser)]
+    pub struct Platform {
+        /// Also print the amount and size of packed objects, of loose objects that are also packed, and of garbage files.
+        #[clap(long, short = 'v')]
+        pub verbose: bool,
+        /// Print sizes in human-readable units instead of KiB.
+        #[clap(long, short = 'H')]
+        pub human_readable: bool,
+    }
+}
+
 pub mod tree {
     #[derive(Debug, clap::Subcommand)]
     pub enum Subcommands {
@@ -816,6 +1087,17 @@ pub mod tree {
     }
 }
 
+pub mod tag {
+    #[derive(Debug, clap::Subcommand)]
+    pub enum Subcommands {
+        /// Verify the signature of an annotated tag.
+        Verify {
+            /// A specification of the annotated tag to verify.
+            rev_spec: String,
+        },
+    }
+}
+
 pub mod commit {
     #[derive(Debug, clap::Subcommand)]
     pub enum Subcommands {
@@ -888,6 +1170,9 @@ pub mod commitgraph {
             /// output statistical information about the graph.
             #[clap(long, short = 's')]
             statistics: bool,
+            /// drop corrupt layers of a split commit-graph and rewrite its chain before verifying it.
+            #[clap(long)]
+            repair: bool,
         },
         /// List all entries in the commit-graph file as reachable by starting from `HEAD`.
         List {
@@ -895,6 +1180,15 @@ pub mod commitgraph {
             #[clap(default_value = "@")]
             spec: std::ffi::OsString,
         },
+        /// Write a commit-graph file with all commits reachable from all references.
+        Write {
+            /// Compute changed-path Bloom filters to accelerate history traversals limited to a path.
+            #[clap(long)]
+            changed_paths: bool,
+            /// Commits that changed more paths than this get a filter that matches every path.
+            #[clap(long, requires = "changed_paths")]
+            max_changed_paths: Option<u32>,
+        },
     }
 }
 
@@ -1089,6 +1383,54 @@ pub mod index {
             /// A revspec that points to the to generate the index from.
             spec: std::ffi::OsString,
         },
+        /// Create an index and tree from all files in the worktree, honoring excludes, and print the tree id.
+        ///
+        /// This is like `git add -A` into an empty index, followed by `git write-tree`.
+        FromWorktree {
+            /// Overwrite the specified index file if it already exists.
+            #[clap(long, short = 'f')]
+            force: bool,
+            /// Path to the index file to be written.
+            /// If none is given it will be kept in memory only, while the tree and all objects are still written.
+            #[clap(long, short = 'i')]
+            index_output_path: Option<PathBuf>,
+            /// Don't write the trailing hash for a performance gain.
+            #[clap(long, short = 's')]
+            skip_hash: bool,
+        },
+        /// Add files matching the given pathspecs to the index and write it, like `git add --all`.
+        ///
+        /// Untracked files are added, modified and conflicting files are updated, and deleted files are removed.
+        Add {
+            /// The git path specifications of the files to add. If none are given, all files are added.
+            #[clap(value_parser = CheckPathSpec)]
+            pathspec: Vec<BString>,
+        },
+        /// Keep the index and attribute stacks in memory and answer queries about the worktree over a unix domain socket
+        /// until a `shutdown` request is received.
+        ///
+        /// Use `gix index query` to send requests, which is much faster than loading everything for each invocation in large repositories.
+        #[cfg(all(unix, feature = "gitoxide-core-tools-index-server"))]
+        Serve {
+            /// Write the index back if its stat information was refreshed while answering a `status` request.
+            #[clap(long, short = 'w')]
+            allow_write: bool,
+            /// The amount of seconds after which a connection that doesn't send a request is closed,
+            /// so other clients can be served. 0 keeps connections open indefinitely.
+            #[clap(long, short = 't', default_value_t = 10)]
+            timeout: u64,
+            /// The path at which to create the socket to listen at.
+            socket: PathBuf,
+        },
+        /// Send a request to a server started with `gix index serve` and print its response.
+        #[cfg(all(unix, feature = "gitoxide-core-tools-index-server"))]
+        Query {
+            /// The path of the socket the server is listening at.
+            socket: PathBuf,
+            /// The request to send, like `status [<pathspec>…]`, `entry <path>`, `attributes <path>`, `excluded <path>` or `shutdown`.
+            #[clap(required = true)]
+            request: Vec<BString>,
+        },
     }
 }
 
@@ -183,14 +183,6 @@ static GIT_CONFIG: &[Record] = &[
         usage: NotPlanned("No plan to implement 'format-patch' or 'request-pull' summary")
 
     },
-    Record {
-        config: "core.fsync",
-        usage: Planned("More safety for disk write operations is a good thing, definitely on the server")
-    },
-    Record {
-        config: "core.fsyncMethod",
-        usage: Planned("Needed to support 'core.fsync'")
-    },
     Record {
         config: "core.sharedRepository",
         usage: NotPlanned("On demand")
@@ -249,10 +241,6 @@ static GIT_CONFIG: &[Record] = &[
         config: "fetch.parallel",
         usage: Planned("")
     },
-    Record {
-        config: "fetch.showForcedUpdates",
-        usage: NotApplicable("We don't support advice")
-    },
     Record {
         config: "fetch.output",
         usage: NotPlanned("'gix' might support it, but there is no intention on copying the 'git' CLI")
@@ -402,14 +390,6 @@ static GIT_CONFIG: &[Record] = &[
         config: "sparse.expectFilesOutsideOfPatterns",
         usage: NotPlanned("TODO")
     },
-    Record {
-        config: "remote.<name>.promisor",
-        usage: Planned("Required for big monorepos, and typically used in conjunction with sparse indices")
-    },
-    Record {
-        config: "remote.<name>.partialCloneFilter",
-        usage: Planned("Required for big monorepos, and typically used in conjunction with sparse indices")
-    },
     Record {
         config: "merge.directoryRenames",
         usage: NotPlanned("On demand")
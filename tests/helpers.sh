@@ -59,6 +59,49 @@ function small-repo-in-sandbox() {
   } &>/dev/null
 }
 
+function repo-with-history-to-show-in-sandbox() {
+  sandbox
+  {
+    git init -b main
+    git config commit.gpgsign false
+    printf 'fn first() {\n\t1\n\t2\n\t3\n\t4\n\t5\n\t6\n\t7\n}\n\nfn second() {\n\t1\n\t2\n}\n' > code.rs
+    printf 'one\ntwo\nthree\n' > both
+    seq 20 > renamed-from
+    printf 'no newline' > eof
+    echo script > run.sh
+    printf '\0binary' > bin
+    git add . && git commit -m "initial"
+
+    sed -i.bak 's/\t2/\tzwei/; s/^}$/} \/\/ end/' code.rs && rm code.rs.bak
+    printf 'no newline\nnow' > eof
+    chmod +x run.sh
+    git mv renamed-from renamed-to
+    git rm bin
+    touch empty
+    printf 'new\nfile\n' > added
+    git add . && git commit -F - <<MSG
+
+subject that is
+long
+
+	body with tab
+and	inner tab
+
+MSG
+    git checkout -b side
+    printf 'one\nTWO (side)\nthree\n' > both
+    echo side > side-only
+    git add . && git commit -m side
+    git checkout main
+    printf 'one\nTWO (main)\nthree\n' > both
+    echo main > main-only
+    git add . && git commit -m main
+    git merge side || true
+    printf 'one\nTWO (resolved)\nthree\nfour\n' > both
+    git add . && git commit -m "merge side"
+  } &>/dev/null
+}
+
 function launch-git-daemon() {
     git -c uploadpack.allowrefinwant daemon --verbose --base-path=. --export-all --user-path &>/dev/null &
     daemon_pid=$!
@@ -192,6 +192,221 @@ title "gix commit-graph"
         fi
       )
     )
+    (small-repo-in-sandbox
+      (with "a split commit-graph whose newest layer lists a commit missing from the object database"
+        {
+          git commit-graph write --no-progress --reachable --split
+          git checkout -b doomed
+          git commit --allow-empty -m "doomed"
+          git commit-graph write --no-progress --reachable --split=no-merge
+          git checkout main
+          git branch -D doomed
+          git reflog expire --expire=now --all
+          git prune --expire=now
+        } &>/dev/null
+        it "fails verification" && {
+          WITH_SNAPSHOT="$snapshot/odb-mismatch-failure" \
+          expect_run $WITH_FAILURE "$exe_plumbing" --no-verbose commit-graph verify
+        }
+        it "drops the layer with the missing commit when repairing" && {
+          WITH_SNAPSHOT="$snapshot/odb-mismatch-repair-success" \
+          expect_run $SUCCESSFULLY "$exe_plumbing" --no-verbose commit-graph verify --repair
+        }
+      )
+    )
+  )
+)
+
+title "gix show"
+(when "running 'show'"
+  (repo-with-history-to-show-in-sandbox
+    for args in "HEAD~2" "--oneline HEAD~2" "HEAD" "--oneline HEAD" "-m HEAD" "--first-parent HEAD" "--no-diff-merges HEAD"; do
+      (with "the arguments '$args'"
+        expected="$(mktemp)"
+        echo -n "$(git show $args)" > "$expected"
+        it "generates the same output as 'git show'" && {
+          WITH_SNAPSHOT="$expected" \
+          expect_run $SUCCESSFULLY "$exe_plumbing" --no-verbose show $args
+        }
+      )
+    done
+  )
+)
+
+title "gix cat"
+(when "running 'cat'"
+  (small-repo-in-sandbox
+    {
+      printf 'text*\ttext eol=crlf\n*.conv diff=upper\n' > .gitattributes
+      printf 'one\ntwo\n' > text-file
+      printf 'lower case\n' > file.conv
+      git config diff.upper.textconv "tr a-z A-Z <"
+      git add . && git commit -m "attributes"
+    } &>/dev/null
+    blob="$(git rev-parse HEAD:text-file)"
+    for args in "--filters HEAD:text-file" "--filters --path=text-file $blob" "--textconv HEAD:file.conv" "--textconv HEAD:text-file" "-p HEAD:file.conv"; do
+      (with "the arguments '$args'"
+        expected="$(mktemp)"
+        echo -n "$(git cat-file $args)" > "$expected"
+        it "generates the same output as 'git cat-file'" && {
+          WITH_SNAPSHOT="$expected" \
+          expect_run $SUCCESSFULLY "$exe_plumbing" --no-verbose cat ${args#-p }
+        }
+      )
+    done
+    (with "'--filters' and no path"
+      it "fails as it doesn't know how to convert the blob" && {
+        expect_run $WITH_FAILURE "$exe_plumbing" --no-verbose cat --filters "$blob"
+      }
+    )
+  )
+)
+
+title "gix blame"
+(when "running 'blame'"
+  (repo-with-history-to-show-in-sandbox
+    for args in "--porcelain both" "--incremental both" "--porcelain code.rs" "--incremental eof" "--porcelain added"; do
+      (with "the arguments '$args'"
+        expected="$(mktemp)"
+        echo -n "$(git blame $args)" > "$expected"
+        it "generates the same output as 'git blame', including boundary and previous commits" && {
+          WITH_SNAPSHOT="$expected" \
+          expect_run $SUCCESSFULLY "$exe_plumbing" --no-verbose blame $args
+        }
+      )
+    done
+  )
+)
+
+title "gix rev-parse"
+(when "running 'rev-parse'"
+  snapshot="$snapshot/rev-parse"
+  (small-repo-in-sandbox
+    {
+      git tag dev
+      git remote add origin .
+      git config branch.main.remote origin
+      git config branch.main.merge refs/heads/main
+      git update-ref refs/remotes/origin/main HEAD~1
+      mkdir sub
+    } &>/dev/null
+    for args in "--abbrev-ref HEAD" "--abbrev-ref heads/dev main" "--abbrev-ref main@{upstream}" "--symbolic-full-name HEAD" "--symbolic-full-name tags/dev main@{u}" "--is-inside-work-tree" "--show-toplevel"; do
+      (with "the arguments '$args'"
+        expected="$(mktemp)"
+        echo -n "$(git rev-parse $args)" > "$expected"
+        it "generates the same output as 'git rev-parse'" && {
+          WITH_SNAPSHOT="$expected" \
+          expect_run $SUCCESSFULLY "$exe_plumbing" --no-verbose rev-parse $args
+        }
+      )
+    done
+    (with "an ambiguous reference name"
+      it "skips it with an error, like 'git rev-parse'" && {
+        WITH_SNAPSHOT="$snapshot/abbrev-ref-ambiguous-success" \
+        expect_run $SUCCESSFULLY "$exe_plumbing" --no-verbose rev-parse --abbrev-ref dev main
+      }
+    )
+    for dir in sub .git/refs; do
+      (with "the argument '--is-inside-work-tree' in '$dir'"
+        cd "$dir"
+        expected="$(mktemp)"
+        echo -n "$(git rev-parse --is-inside-work-tree)" > "$expected"
+        it "generates the same output as 'git rev-parse'" && {
+          WITH_SNAPSHOT="$expected" \
+          expect_run $SUCCESSFULLY "$exe_plumbing" --no-verbose rev-parse --is-inside-work-tree
+        }
+      )
+    done
+    (with "the argument '--show-toplevel' in 'sub'"
+      cd sub
+      expected="$(mktemp)"
+      echo -n "$(git rev-parse --show-toplevel)" > "$expected"
+      it "generates the same output as 'git rev-parse'" && {
+        WITH_SNAPSHOT="$expected" \
+        expect_run $SUCCESSFULLY "$exe_plumbing" --no-verbose rev-parse --show-toplevel
+      }
+    )
+  )
+)
+
+title "gix stats"
+(when "running 'stats'"
+  snapshot="$snapshot/stats"
+  (small-repo-in-sandbox
+    git repack -a &>/dev/null
+    it "counts objects that are both loose and packed only once" && {
+      WITH_SNAPSHOT="$snapshot/loose-and-packed-success" \
+      expect_run $SUCCESSFULLY "$exe_plumbing" --no-verbose stats
+    }
+  )
+)
+
+title "gix index serve"
+(when "running 'index serve'"
+  snapshot="$snapshot/index/serve"
+  (small-repo-in-sandbox
+    {
+      echo "*.ignored" > .gitignore
+      echo "a -diff" > .gitattributes
+      git add .gitignore .gitattributes
+      git commit -m "ignores and attributes"
+      echo "changed" > a
+      touch untracked c.ignored
+    } &>/dev/null
+    socket="$PWD/.git/index.sock"
+    "$exe_plumbing" --no-verbose index serve --timeout 1 "$socket" 2>/dev/null &
+    server=$!
+    trap 'kill $server 2>/dev/null' EXIT
+    while ! test -S "$socket"; do sleep 0.1; done
+
+    (with "a 'status' request"
+      it "lists the changes between the index and the worktree" && {
+        WITH_SNAPSHOT="$snapshot/status-success" \
+        expect_run $SUCCESSFULLY "$exe_plumbing" --no-verbose index query "$socket" status
+      }
+      it "does so again with the index kept in memory" && {
+        WITH_SNAPSHOT="$snapshot/status-success" \
+        expect_run $SUCCESSFULLY "$exe_plumbing" --no-verbose index query "$socket" status
+      }
+      it "limits the changes to the given pathspecs" && {
+        WITH_SNAPSHOT="$snapshot/status-pathspec-success" \
+        expect_run $SUCCESSFULLY "$exe_plumbing" --no-verbose index query "$socket" status a b
+      }
+    )
+    (with "an 'entry' request"
+      it "lists the entry at the path" && {
+        WITH_SNAPSHOT="$snapshot/entry-success" \
+        expect_run $SUCCESSFULLY "$exe_plumbing" --no-verbose index query "$socket" entry a
+      }
+    )
+    (with "an 'attributes' request"
+      it "lists the attributes of the path" && {
+        WITH_SNAPSHOT="$snapshot/attributes-success" \
+        expect_run $SUCCESSFULLY "$exe_plumbing" --no-verbose index query "$socket" attributes a
+      }
+    )
+    (with "an 'excluded' request"
+      it "shows the pattern that excludes the path" && {
+        WITH_SNAPSHOT="$snapshot/excluded-success" \
+        expect_run $SUCCESSFULLY "$exe_plumbing" --no-verbose index query "$socket" excluded c.ignored
+      }
+    )
+    (with "an unknown request"
+      it "fails with an error" && {
+        WITH_SNAPSHOT="$snapshot/unknown-failure" \
+        expect_run $WITH_FAILURE "$exe_plumbing" --no-verbose index query "$socket" frobnicate
+      }
+    )
+    (with "a 'shutdown' request"
+      it "succeeds" && {
+        WITH_SNAPSHOT="$snapshot/shutdown-success" \
+        expect_run $SUCCESSFULLY "$exe_plumbing" --no-verbose index query "$socket" shutdown
+      }
+      it "stops the server and removes the socket" && {
+        for _ in $(seq 50); do test -e "$socket" || break; sleep 0.1; done
+        expect_run $WITH_FAILURE test -e "$socket"
+      }
+    )
   )
 )
 